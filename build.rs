@@ -58,14 +58,38 @@ const DIAGNOSTIC_MESSAGES_PATH: &str =
     "./typescript-go/_submodules/TypeScript/src/compiler/diagnosticMessages.json";
 
 const DIAGNOSTIC_MESSAGES_OUTPUT_PATH: &str = "src/compiler/diagnostics/generated.rs";
+
+// The derived core properties and general categories of the Unicode Character Database,
+// which `make unicode-data` downloads at the pinned version. The identifier tables are only
+// regenerated when they have been downloaded.
+const UNICODE_PROPERTIES_PATH: &str = "./unicode/DerivedCoreProperties.txt";
+
+const UNICODE_CATEGORIES_PATH: &str = "./unicode/DerivedGeneralCategory.txt";
+
+const UNICODE_TABLES_OUTPUT_PATH: &str = "src/compiler/scanner/unicode.rs";
+
 fn main() -> io::Result<()> {
     // Tell Cargo to re-run this build script if the input file changes
     println!("cargo:rerun-if-changed={}", DIAGNOSTIC_MESSAGES_PATH);
+    println!("cargo:rerun-if-changed={}", UNICODE_PROPERTIES_PATH);
+    println!("cargo:rerun-if-changed={}", UNICODE_CATEGORIES_PATH);
     println!("cargo:rerun-if-changed=build.rs");
 
     // Configure paths
     let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+
+    if Path::new(UNICODE_PROPERTIES_PATH).exists() && Path::new(UNICODE_CATEGORIES_PATH).exists() {
+        let output_path = Path::new(&manifest_dir).join(UNICODE_TABLES_OUTPUT_PATH);
+        generate_unicode_tables(
+            Path::new(UNICODE_PROPERTIES_PATH),
+            Path::new(UNICODE_CATEGORIES_PATH),
+            &output_path,
+        )?;
+    }
     let input_path = DIAGNOSTIC_MESSAGES_PATH;
+    if !Path::new(input_path).exists() {
+        return Ok(());
+    }
 
     // Write to src directory instead of OUT_DIR
     let output_path = Path::new(&manifest_dir).join(DIAGNOSTIC_MESSAGES_OUTPUT_PATH);
@@ -121,26 +145,158 @@ fn main() -> io::Result<()> {
         buffer.write_all(b"\n")?;
     }
 
+    write_if_changed(&output_path, &buffer)?;
+
+    println!(
+        "cargo:rustc-env=DIAGNOSTICS_GENERATED_PATH={}",
+        output_path.display()
+    );
+
+    Ok(())
+}
+
+fn write_if_changed(output_path: &Path, buffer: &[u8]) -> io::Result<()> {
     // Only update the file if its contents would change
-    let should_write = match fs::read(&output_path) {
+    let should_write = match fs::read(output_path) {
         Ok(existing_content) => buffer != existing_content,
         Err(_) => true, // File doesn't exist or can't be read, so write it
     };
 
     if should_write {
         println!("Writing generated file to {}", output_path.display());
-        let mut file = File::create(&output_path)?;
-        file.write_all(&buffer)?;
+        let mut file = File::create(output_path)?;
+        file.write_all(buffer)?;
     } else {
         println!("File {} unchanged, not overwriting", output_path.display());
     }
+    Ok(())
+}
 
-    println!(
-        "cargo:rustc-env=DIAGNOSTICS_GENERATED_PATH={}",
-        output_path.display()
-    );
+// Write the tables of the characters that can start and continue identifiers. ECMAScript
+// 2015 and later use the ID_Start and ID_Continue properties. ES5 names general categories
+// instead: letters (Lu, Ll, Lt, Lm, Lo) and letter numbers (Nl) start identifiers, and
+// non-spacing and spacing combining marks (Mn, Mc), decimal digits (Nd), and connector
+// punctuation (Pc) continue them too. ES5 identifiers are read one UTF-16 code unit at a
+// time, so its tables only have characters in the Basic Multilingual Plane.
+fn generate_unicode_tables(
+    properties_path: &Path,
+    categories_path: &Path,
+    output_path: &Path,
+) -> io::Result<()> {
+    let properties = fs::read_to_string(properties_path)?;
+    let categories = fs::read_to_string(categories_path)?;
+    let version = read_unicode_version(&properties, "DerivedCoreProperties");
+    if version != read_unicode_version(&categories, "DerivedGeneralCategory") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the Unicode properties and categories are of different versions",
+        ));
+    }
+    let es5_identifier_start_categories = ["Lu", "Ll", "Lt", "Lm", "Lo", "Nl"];
+    let es5_identifier_part_categories =
+        ["Lu", "Ll", "Lt", "Lm", "Lo", "Nl", "Mn", "Mc", "Nd", "Pc"];
+    let basic_multilingual_plane = |ranges: Vec<(u32, u32)>| -> Vec<(u32, u32)> {
+        ranges
+            .into_iter()
+            .filter(|&(start, _)| start <= 0xFFFF)
+            .map(|(start, end)| (start, end.min(0xFFFF)))
+            .collect()
+    };
 
-    Ok(())
+    let mut buffer = Vec::new();
+    writeln!(buffer, "// GENERATED BY build.rs; DO NOT EDIT")?;
+    writeln!(
+        buffer,
+        "//! The characters that can start and continue identifiers, as sorted ranges"
+    )?;
+    writeln!(buffer, "//!")?;
+    writeln!(
+        buffer,
+        "//! From Unicode {version}: the ID_Start and ID_Continue properties for ES2015 and later,"
+    )?;
+    writeln!(
+        buffer,
+        "//! and the general categories that ES5 names, in the Basic Multilingual Plane, for ES5."
+    )?;
+    for (name, ranges) in [
+        (
+            "ES5_IDENTIFIER_START",
+            basic_multilingual_plane(read_property_ranges(
+                &categories,
+                &es5_identifier_start_categories,
+            )),
+        ),
+        (
+            "ES5_IDENTIFIER_PART",
+            basic_multilingual_plane(read_property_ranges(
+                &categories,
+                &es5_identifier_part_categories,
+            )),
+        ),
+        (
+            "ES_NEXT_IDENTIFIER_START",
+            read_property_ranges(&properties, &["ID_Start"]),
+        ),
+        (
+            "ES_NEXT_IDENTIFIER_PART",
+            read_property_ranges(&properties, &["ID_Continue"]),
+        ),
+    ] {
+        writeln!(buffer)?;
+        writeln!(buffer, "pub const {name}: &[(char, char)] = &[")?;
+        // One range per line, as rustfmt lays the table out
+        for (start, end) in ranges {
+            writeln!(buffer, "    ('\\u{{{start:04X}}}', '\\u{{{end:04X}}}'),")?;
+        }
+        writeln!(buffer, "];")?;
+    }
+    write_if_changed(output_path, &buffer)
+}
+
+// Read the Unicode version of a file of the character database from its first line, which
+// names the file and its version, like `# DerivedCoreProperties-14.0.0.txt`
+fn read_unicode_version(contents: &str, file_name: &str) -> String {
+    contents
+        .lines()
+        .next()
+        .map(|line| line.trim_start_matches('#').trim())
+        .and_then(|name| name.strip_prefix(file_name)?.strip_prefix('-'))
+        .and_then(|name| name.strip_suffix(".txt"))
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+// Read the ranges of the code points that have any of some properties, merging adjacent ones.
+// Each line of the file is a code point or range, its property, and a comment, like
+// `0041..005A    ; ID_Start # L&  [26] LATIN CAPITAL LETTER A..LATIN CAPITAL LETTER Z`. The
+// general categories are listed the same way, like `0041..005A    ; Lu #  [26] ...`.
+fn read_property_ranges(contents: &str, properties: &[&str]) -> Vec<(u32, u32)> {
+    let mut ranges: Vec<(u32, u32)> = contents
+        .lines()
+        .filter_map(|line| {
+            let (code_points, name) = line.split('#').next()?.split_once(';')?;
+            if !properties.contains(&name.trim()) {
+                return None;
+            }
+            let (start, end) = code_points
+                .trim()
+                .split_once("..")
+                .unwrap_or((code_points.trim(), code_points.trim()));
+            Some((
+                u32::from_str_radix(start, 16).ok()?,
+                u32::from_str_radix(end, 16).ok()?,
+            ))
+        })
+        .collect();
+    ranges.sort_unstable();
+    let mut merged: Vec<(u32, u32)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
 }
 
 fn generate_constant_name(orig_name: &str, code: i32) -> (String, String) {
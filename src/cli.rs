@@ -1,15 +1,25 @@
 use std::path::PathBuf;
 
-use clap::{Parser, ValueEnum};
+use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use crate::compile::{Diagnostic, DiagnosticFormatKind, option_diagnostic};
+use crate::compiler::checker::get_spelling_suggestion;
+use crate::compiler::diagnostics;
+use crate::compiler::emitter::NewLineKind;
+use crate::compiler::module_resolution::ModuleResolutionKind;
+use crate::compiler::scanner::ScriptTarget;
+use crate::compiler::transformers::{JsxEmit, ModuleKind};
+use crate::language_service::{FormatOptions, TrailingCommas as TrailingCommaKind};
 
 /// The TypeScript Compiler
-#[derive(Parser)]
+#[derive(Clone, Parser)]
 #[command(name = "tsrs")]
 #[command(version = "Version 0.0.1")]
 #[command(about = "The TypeScript Compiler...in Rust!", long_about = None)]
 #[command(after_help = "You can learn about all of the compiler options at https://aka.ms/tsrs")]
 #[command(disable_help_flag = true)]
 #[command(disable_version_flag = true)]
+#[command(args_override_self = true)]
 pub struct Cli {
     /// Print this message.
     #[arg(short = 'h', long = "help")]
@@ -19,6 +29,10 @@ pub struct Cli {
     #[arg(short = 'w', long = "watch")]
     pub watch: bool,
 
+    /// Disable wiping the console in watch mode.
+    #[arg(long = "preserveWatchOutput")]
+    pub preserve_watch_output: bool,
+
     /// Show all compiler options.
     #[arg(long = "all")]
     pub all: bool,
@@ -45,9 +59,19 @@ pub struct Cli {
 
     // COMMON COMPILER OPTIONS
     /// Enable color and formatting in TypeScript's output to make compiler errors easier to read.
-    #[arg(long = "pretty", default_value_t = true)]
+    #[arg(
+        long = "pretty",
+        default_value_t = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        action = ArgAction::Set
+    )]
     pub pretty: bool,
 
+    /// Specify the format of reported diagnostics: text for people, or JSON or SARIF for tools.
+    #[arg(long = "diagnosticFormat", value_enum, default_value_t = DiagnosticFormat::Pretty)]
+    pub diagnostic_format: DiagnosticFormat,
+
     /// Generate .d.ts files from TypeScript and JavaScript files in your project.
     #[arg(short = 'd', long = "declaration")]
     pub declaration: bool,
@@ -68,6 +92,10 @@ pub struct Cli {
     #[arg(long = "noEmit")]
     pub no_emit: bool,
 
+    /// Disable emitting files if any type checking errors are reported.
+    #[arg(long = "noEmitOnError")]
+    pub no_emit_on_error: bool,
+
     /// Set the JavaScript language version for emitted JavaScript and include compatible library declarations.
     #[arg(short = 't', long = "target", value_enum)]
     pub target: Option<Target>,
@@ -76,10 +104,78 @@ pub struct Cli {
     #[arg(short = 'm', long = "module", value_enum)]
     pub module: Option<Module>,
 
+    /// Specify how TypeScript looks up a file from a given module specifier.
+    #[arg(long = "moduleResolution", value_enum)]
+    pub module_resolution: Option<ModuleResolution>,
+
+    /// Enable constraints that allow a TypeScript project to be used with project references.
+    #[arg(long = "composite")]
+    pub composite: bool,
+
+    /// Save .tsbuildinfo files to allow for incremental compilation of projects.
+    #[arg(long = "incremental")]
+    pub incremental: bool,
+
+    /// Specify the path to .tsbuildinfo incremental compilation file.
+    #[arg(long = "tsBuildInfoFile")]
+    pub ts_build_info_file: Option<PathBuf>,
+
     /// Specify a set of bundled library declaration files that describe the target runtime environment.
-    #[arg(long = "lib")]
+    #[arg(long = "lib", value_delimiter = ',')]
     pub lib: Vec<String>,
 
+    /// Specify the base directory to resolve non-relative module names.
+    #[arg(long = "baseUrl")]
+    pub base_url: Option<PathBuf>,
+
+    /// Allow multiple folders to be treated as one when resolving modules.
+    #[arg(long = "rootDirs", value_delimiter = ',')]
+    pub root_dirs: Option<Vec<String>>,
+
+    /// Conditions to set in addition to the resolver-specific defaults when resolving imports.
+    #[arg(long = "customConditions", value_delimiter = ',')]
+    pub custom_conditions: Option<Vec<String>>,
+
+    /// List of file name suffixes to search when resolving a module.
+    #[arg(long = "moduleSuffixes", value_delimiter = ',')]
+    pub module_suffixes: Option<Vec<String>>,
+
+    /// Enable importing .json files.
+    #[arg(long = "resolveJsonModule", num_args = 0..=1, default_missing_value = "true")]
+    pub resolve_json_module: Option<bool>,
+
+    /// Allow imports to include TypeScript file extensions. Requires '--moduleResolution bundler' and either '--noEmit' or '--emitDeclarationOnly' to be set.
+    #[arg(long = "allowImportingTsExtensions")]
+    pub allow_importing_ts_extensions: bool,
+
+    /// Enable importing files with any extension, provided a declaration file is present.
+    #[arg(long = "allowArbitraryExtensions")]
+    pub allow_arbitrary_extensions: bool,
+
+    /// Allow accessing UMD globals from modules.
+    #[arg(long = "allowUmdGlobalAccess")]
+    pub allow_umd_global_access: bool,
+
+    /// Disallow 'import's, 'require's or '<reference>'s from expanding the number of files TypeScript should add to a project.
+    #[arg(long = "noResolve")]
+    pub no_resolve: bool,
+
+    /// Check side effect imports.
+    #[arg(long = "noUncheckedSideEffectImports")]
+    pub no_unchecked_side_effect_imports: bool,
+
+    /// Disable including any library files, including the default lib.d.ts.
+    #[arg(long = "noLib")]
+    pub no_lib: bool,
+
+    /// Skip type checking .d.ts files that are included with TypeScript.
+    #[arg(long = "skipDefaultLibCheck")]
+    pub skip_default_lib_check: bool,
+
+    /// Skip type checking all .d.ts files.
+    #[arg(long = "skipLibCheck")]
+    pub skip_lib_check: bool,
+
     /// Allow JavaScript files to be a part of your program. Use the 'checkJS' option to get errors from these files.
     #[arg(long = "allowJs")]
     pub allow_js: bool,
@@ -101,26 +197,314 @@ pub struct Cli {
     #[arg(long = "outDir")]
     pub out_dir: Option<PathBuf>,
 
+    /// Specify the root folder within your source files.
+    #[arg(long = "rootDir")]
+    pub root_dir: Option<PathBuf>,
+
+    /// Emit ECMAScript-standard-compliant class fields.
+    #[arg(long = "useDefineForClassFields", num_args = 0..=1, default_missing_value = "true")]
+    pub use_define_for_class_fields: Option<bool>,
+
+    /// Enable experimental support for legacy experimental decorators.
+    #[arg(long = "experimentalDecorators")]
+    pub experimental_decorators: bool,
+
+    /// Emit design-type metadata for decorated declarations in source files.
+    #[arg(long = "emitDecoratorMetadata")]
+    pub emit_decorator_metadata: bool,
+
+    /// Specify the JSX factory function used when targeting React JSX emit, e.g. 'React.createElement' or 'h'.
+    #[arg(long = "jsxFactory")]
+    pub jsx_factory: Option<String>,
+
+    /// Specify the JSX Fragment reference used for fragments when targeting React JSX emit e.g. 'React.Fragment' or 'Fragment'.
+    #[arg(long = "jsxFragmentFactory")]
+    pub jsx_fragment_factory: Option<String>,
+
+    /// Specify module specifier used to import the JSX factory functions when using 'jsx: react-jsx*'.
+    #[arg(long = "jsxImportSource")]
+    pub jsx_import_source: Option<String>,
+
+    /// Specify the output directory for generated declaration files.
+    #[arg(long = "declarationDir")]
+    pub declaration_dir: Option<PathBuf>,
+
+    /// Include sourcemap files inside the emitted JavaScript.
+    #[arg(long = "inlineSourceMap")]
+    pub inline_source_map: bool,
+
+    /// Include source code in the sourcemaps inside the emitted JavaScript.
+    #[arg(long = "inlineSources")]
+    pub inline_sources: bool,
+
+    /// Specify the location where debugger should locate map files instead of generated locations.
+    #[arg(long = "mapRoot")]
+    pub map_root: Option<String>,
+
+    /// Specify the root path for debuggers to find the reference source code.
+    #[arg(long = "sourceRoot")]
+    pub source_root: Option<String>,
+
+    /// Emit more compliant, but verbose and less performant JavaScript for iteration.
+    #[arg(long = "downlevelIteration")]
+    pub downlevel_iteration: bool,
+
+    /// Allow importing helper functions from tslib once per project, instead of including them per-file.
+    #[arg(long = "importHelpers")]
+    pub import_helpers: bool,
+
+    /// Disable generating custom helper functions like '__extends' in compiled output.
+    #[arg(long = "noEmitHelpers")]
+    pub no_emit_helpers: bool,
+
+    /// Disable erasing 'const enum' declarations in generated code.
+    #[arg(long = "preserveConstEnums")]
+    pub preserve_const_enums: bool,
+
+    /// Disable emitting declarations that have '@internal' in their JSDoc comments.
+    #[arg(long = "stripInternal")]
+    pub strip_internal: bool,
+
     /// Disable emitting comments.
     #[arg(long = "removeComments")]
     pub remove_comments: bool,
 
+    /// Set the newline character for emitting files.
+    #[arg(long = "newLine", value_enum)]
+    pub new_line: Option<NewLine>,
+
+    /// Emit a UTF-8 Byte Order Mark (BOM) in the beginning of output files.
+    #[arg(long = "emitBOM")]
+    pub emit_bom: bool,
+
+    /// No longer supported. In early versions, manually set the text encoding for reading files.
+    #[arg(long = "charset")]
+    pub charset: Option<String>,
+
+    /// Print all of the files read during the compilation.
+    #[arg(long = "listFiles")]
+    pub list_files: bool,
+
+    /// Print names of files that are part of the compilation and then stop processing.
+    #[arg(long = "listFilesOnly")]
+    pub list_files_only: bool,
+
+    /// Print files read during the compilation including why it was included.
+    #[arg(long = "explainFiles")]
+    pub explain_files: bool,
+
+    /// Output compiler performance information after building.
+    #[arg(long = "diagnostics")]
+    pub diagnostics: bool,
+
+    /// Output more detailed compiler performance information after building.
+    #[arg(long = "extendedDiagnostics")]
+    pub extended_diagnostics: bool,
+
     /// Enable all strict type-checking options.
-    #[arg(long = "strict")]
-    pub strict: bool,
+    #[arg(long = "strict", num_args = 0..=1, default_missing_value = "true")]
+    pub strict: Option<bool>,
+
+    /// Enable error reporting for expressions and declarations with an implied 'any' type.
+    #[arg(long = "noImplicitAny", num_args = 0..=1, default_missing_value = "true")]
+    pub no_implicit_any: Option<bool>,
+
+    /// When type checking, take into account 'null' and 'undefined'.
+    #[arg(long = "strictNullChecks", num_args = 0..=1, default_missing_value = "true")]
+    pub strict_null_checks: Option<bool>,
+
+    /// When assigning functions, check to ensure parameters and the return values are subtype-compatible.
+    #[arg(long = "strictFunctionTypes", num_args = 0..=1, default_missing_value = "true")]
+    pub strict_function_types: Option<bool>,
+
+    /// Check that the arguments for 'bind', 'call', and 'apply' methods match the original function.
+    #[arg(long = "strictBindCallApply", num_args = 0..=1, default_missing_value = "true")]
+    pub strict_bind_call_apply: Option<bool>,
+
+    /// Check for class properties that are declared but not set in the constructor.
+    #[arg(
+        long = "strictPropertyInitialization",
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    pub strict_property_initialization: Option<bool>,
+
+    /// Enable error reporting when 'this' is given the type 'any'.
+    #[arg(long = "noImplicitThis", num_args = 0..=1, default_missing_value = "true")]
+    pub no_implicit_this: Option<bool>,
+
+    /// Default catch clause variables as 'unknown' instead of 'any'.
+    #[arg(
+        long = "useUnknownInCatchVariables",
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    pub use_unknown_in_catch_variables: Option<bool>,
+
+    /// Ensure 'use strict' is always emitted.
+    #[arg(long = "alwaysStrict", num_args = 0..=1, default_missing_value = "true")]
+    pub always_strict: Option<bool>,
+
+    /// Built-in iterators are instantiated with a 'TReturn' type of 'undefined' instead of 'any'.
+    #[arg(long = "strictBuiltinIteratorReturn", num_args = 0..=1, default_missing_value = "true")]
+    pub strict_builtin_iterator_return: Option<bool>,
+
+    /// Interpret optional property types as written, rather than adding 'undefined'.
+    #[arg(
+        long = "exactOptionalPropertyTypes",
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    pub exact_optional_property_types: Option<bool>,
+
+    /// Enable error reporting when local variables aren't read.
+    #[arg(long = "noUnusedLocals", num_args = 0..=1, default_missing_value = "true")]
+    pub no_unused_locals: Option<bool>,
+
+    /// Raise an error when a function parameter isn't read.
+    #[arg(long = "noUnusedParameters", num_args = 0..=1, default_missing_value = "true")]
+    pub no_unused_parameters: Option<bool>,
+
+    /// Enable error reporting for codepaths that do not explicitly return in a function.
+    #[arg(long = "noImplicitReturns", num_args = 0..=1, default_missing_value = "true")]
+    pub no_implicit_returns: Option<bool>,
+
+    /// Enable error reporting for fallthrough cases in switch statements.
+    #[arg(long = "noFallthroughCasesInSwitch", num_args = 0..=1, default_missing_value = "true")]
+    pub no_fallthrough_cases_in_switch: Option<bool>,
+
+    /// Add 'undefined' to a type when accessed using an index.
+    #[arg(long = "noUncheckedIndexedAccess", num_args = 0..=1, default_missing_value = "true")]
+    pub no_unchecked_indexed_access: Option<bool>,
+
+    /// Ensure overriding members in derived classes are marked with an override modifier.
+    #[arg(long = "noImplicitOverride", num_args = 0..=1, default_missing_value = "true")]
+    pub no_implicit_override: Option<bool>,
+
+    /// Enforces using indexed accessors for keys declared using an indexed type.
+    #[arg(
+        long = "noPropertyAccessFromIndexSignature",
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    pub no_property_access_from_index_signature: Option<bool>,
+
+    /// Disable error reporting for unreachable code.
+    #[arg(long = "allowUnreachableCode", num_args = 0..=1, default_missing_value = "true")]
+    pub allow_unreachable_code: Option<bool>,
+
+    /// Disable error reporting for unused labels.
+    #[arg(long = "allowUnusedLabels", num_args = 0..=1, default_missing_value = "true")]
+    pub allow_unused_labels: Option<bool>,
+
+    /// Ensure that casing is correct in imports.
+    #[arg(
+        long = "forceConsistentCasingInFileNames",
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    pub force_consistent_casing_in_file_names: Option<bool>,
+
+    /// Specify multiple folders that act like './node_modules/@types'.
+    #[arg(long = "typeRoots", value_delimiter = ',')]
+    pub type_roots: Option<Vec<String>>,
 
     /// Specify type package names to be included without being referenced in a source file.
-    #[arg(long = "types")]
-    pub types: Vec<String>,
+    #[arg(long = "types", value_delimiter = ',')]
+    pub types: Option<Vec<String>>,
 
     /// Emit additional JavaScript to ease support for importing CommonJS modules.
     /// This enables 'allowSyntheticDefaultImports' for type compatibility.
     #[arg(long = "esModuleInterop")]
     pub es_module_interop: bool,
 
+    /// Ensure that each file can be safely transpiled without relying on other imports.
+    #[arg(long = "isolatedModules")]
+    pub isolated_modules: bool,
+
+    /// Do not transform or elide any imports or exports not marked as type-only, ensuring they are written in the output file's format based on the 'module' setting.
+    #[arg(long = "verbatimModuleSyntax")]
+    pub verbatim_module_syntax: bool,
+
+    /// Specify emit/checking behavior for imports that are only used for types.
+    #[arg(long = "importsNotUsedAsValues", value_enum)]
+    pub imports_not_used_as_values: Option<ImportsNotUsedAsValues>,
+
+    /// Require sufficient annotation on exports so other tools can trivially generate declaration files.
+    #[arg(long = "isolatedDeclarations")]
+    pub isolated_declarations: bool,
+
+    /// Do not allow runtime constructs that are not part of ECMAScript.
+    #[arg(long = "erasableSyntaxOnly")]
+    pub erasable_syntax_only: bool,
+
+    /// Allow 'import x from y' when a module doesn't have a default export.
+    #[arg(long = "allowSyntheticDefaultImports", num_args = 0..=1, default_missing_value = "true")]
+    pub allow_synthetic_default_imports: Option<bool>,
+
+    /// Disable resolving symlinks to their realpath. This correlates to the same flag in node.
+    #[arg(long = "preserveSymlinks")]
+    pub preserve_symlinks: bool,
+
+    /// Disable full type checking (only critical parse and emit errors will be reported).
+    #[arg(long = "noCheck")]
+    pub no_check: bool,
+
+    /// Log paths used during the 'moduleResolution' process.
+    #[arg(long = "traceResolution")]
+    pub trace_resolution: bool,
+
+    /// Print the type of every declaration in the input files.
+    #[arg(long = "printTypes")]
+    pub print_types: bool,
+
+    /// Emit each file on its own, without type checking it or reading the files it imports.
+    #[arg(long = "transpileOnly")]
+    pub transpile_only: bool,
+
+    /// Parse, bind, and check the files of the program on a single thread.
+    #[arg(long = "singleThreaded")]
+    pub single_threaded: bool,
+
+    /// Apply the fixes for errors that have one to the input files before compiling them. This is experimental.
+    #[arg(long = "fix")]
+    pub fix: bool,
+
     /// Input files to compile
     #[arg(value_name = "FILES")]
     pub files: Vec<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Clone, Subcommand)]
+pub enum Command {
+    /// Format files in place.
+    Fmt(FmtArgs),
+}
+
+#[derive(Clone, Args)]
+pub struct FmtArgs {
+    /// Specify the number of spaces of an indentation level.
+    #[arg(long = "indentSize", default_value_t = 4)]
+    pub indent_size: usize,
+
+    /// Indent with tabs instead of spaces.
+    #[arg(long = "useTabs")]
+    pub use_tabs: bool,
+
+    /// Place the opening brace of functions and control blocks on a line of its own.
+    #[arg(long = "placeOpenBraceOnNewLine")]
+    pub place_open_brace_on_new_line: bool,
+
+    /// Specify whether to insert or remove the trailing commas of multi-line lists.
+    #[arg(long = "trailingCommas", value_enum, default_value = "preserve")]
+    pub trailing_commas: TrailingCommas,
+
+    /// Files to format
+    #[arg(value_name = "FILES")]
+    pub files: Vec<String>,
 }
 
 // #[derive(Parser)]
@@ -243,11 +627,21 @@ pub struct Cli {
 //     files: Vec<String>,
 // }
 
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum TrailingCommas {
+    #[value(name = "preserve")]
+    Preserve,
+    #[value(name = "insert")]
+    Insert,
+    #[value(name = "remove")]
+    Remove,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 pub enum Target {
     #[value(name = "es5")]
     Es5,
-    #[value(name = "es6")]
+    #[value(name = "es6", alias = "es2015")]
     Es2015,
     #[value(name = "es2016")]
     Es2016,
@@ -283,7 +677,7 @@ pub enum Module {
     Umd,
     #[value(name = "system")]
     System,
-    #[value(name = "es6")]
+    #[value(name = "es6", alias = "es2015")]
     Es2015,
     #[value(name = "es2020")]
     Es2020,
@@ -301,6 +695,20 @@ pub enum Module {
     Preserve,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ModuleResolution {
+    #[value(name = "classic")]
+    Classic,
+    #[value(name = "node10", alias = "node")]
+    Node10,
+    #[value(name = "node16")]
+    Node16,
+    #[value(name = "nodenext")]
+    NodeNext,
+    #[value(name = "bundler")]
+    Bundler,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 pub enum JsxMode {
     #[value(name = "preserve")]
@@ -315,44 +723,593 @@ pub enum JsxMode {
     ReactJsxDev,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ImportsNotUsedAsValues {
+    #[value(name = "remove")]
+    Remove,
+    #[value(name = "preserve")]
+    Preserve,
+    #[value(name = "error")]
+    Error,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum DiagnosticFormat {
+    #[value(name = "pretty")]
+    Pretty,
+    #[value(name = "json")]
+    Json,
+    #[value(name = "sarif")]
+    Sarif,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum NewLine {
+    #[value(name = "crlf")]
+    Crlf,
+    #[value(name = "lf")]
+    Lf,
+}
+
 // Compiler options derived from CLI arguments
+#[derive(Clone)]
 pub struct CompilerOptions {
-    pub target: String, // ES5, ES2015, etc.
-    pub module: String, // CommonJS, ESNext, etc.
+    pub target: ScriptTarget,
+    pub module: Option<ModuleKind>,
+    pub module_resolution: Option<ModuleResolutionKind>,
+    pub trace_resolution: bool,
+    pub base_url: Option<String>,
+    pub root_dirs: Option<Vec<String>>,
+    pub custom_conditions: Option<Vec<String>>,
+    pub module_suffixes: Option<Vec<String>>,
+    // Whether `.json` files can be imported, which depends on the module resolution when unset
+    pub resolve_json_module: Option<bool>,
+    pub allow_importing_ts_extensions: bool,
+    pub allow_arbitrary_extensions: bool,
+    pub allow_umd_global_access: bool,
+    pub no_resolve: bool,
+    pub no_unchecked_side_effect_imports: bool,
+    pub preserve_symlinks: bool,
+    pub composite: bool,
+    pub incremental: bool,
+    pub ts_build_info_file: Option<String>,
+    pub allow_js: bool,
+    pub lib: Vec<String>,
+    pub no_lib: bool,
+    pub skip_lib_check: bool,
+    pub skip_default_lib_check: bool,
+    // The type packages to include, or with none configured, every package in the type roots
+    pub types: Option<Vec<String>>,
+    pub type_roots: Option<Vec<String>>,
+    pub force_consistent_casing_in_file_names: bool,
+    pub es_module_interop: bool,
+    // Whether default imports of modules without one are allowed, which depends on the module
+    // system when unset
+    pub allow_synthetic_default_imports: Option<bool>,
+    pub isolated_modules: bool,
+    pub verbatim_module_syntax: bool,
+    // With `preserve` or `error`, imports whose bindings are all elided are kept for their
+    // side effects
+    pub imports_not_used_as_values: Option<ImportsNotUsedAsValues>,
+    pub isolated_declarations: bool,
+    pub erasable_syntax_only: bool,
+    pub jsx: JsxEmit,
+    pub jsx_factory: Option<String>,
+    pub jsx_fragment_factory: Option<String>,
+    pub jsx_import_source: Option<String>,
+    pub use_define_for_class_fields: bool,
+    pub experimental_decorators: bool,
+    pub emit_decorator_metadata: bool,
     pub source_map: bool,
+    pub inline_source_map: bool,
+    pub inline_sources: bool,
+    pub map_root: Option<String>,
+    pub source_root: Option<String>,
     pub declaration: bool,
+    pub declaration_map: bool,
+    pub declaration_dir: Option<String>,
+    pub emit_declaration_only: bool,
+    pub downlevel_iteration: bool,
+    // Helpers are imported from `tslib` in modules with `import_helpers`, and declared nowhere
+    // with `no_emit_helpers`
+    pub import_helpers: bool,
+    pub no_emit_helpers: bool,
+    pub preserve_const_enums: bool,
+    pub strip_internal: bool,
     pub out_dir: Option<String>,
+    pub out_file: Option<String>,
+    pub root_dir: Option<String>,
     pub no_emit: bool,
+    pub no_emit_on_error: bool,
+    pub remove_comments: bool,
+    pub new_line: NewLineKind,
+    pub emit_bom: bool,
+    // Files are always read and written as UTF-8, so any other charset is reported
+    pub charset: Option<String>,
     pub skip_type_checking: bool,
     pub pretty: bool,
+    pub preserve_watch_output: bool,
+    pub diagnostic_format: DiagnosticFormatKind,
+    pub no_implicit_any: bool,
+    pub strict_null_checks: bool,
+    pub strict_function_types: bool,
+    pub strict_bind_call_apply: bool,
+    pub strict_property_initialization: bool,
+    pub no_implicit_this: bool,
+    pub use_unknown_in_catch_variables: bool,
+    pub always_strict: bool,
+    pub strict_builtin_iterator_return: bool,
+    pub exact_optional_property_types: bool,
+    pub no_unused_locals: bool,
+    pub no_unused_parameters: bool,
+    pub no_implicit_returns: bool,
+    pub no_fallthrough_cases_in_switch: bool,
+    pub no_unchecked_indexed_access: bool,
+    pub no_implicit_override: bool,
+    pub no_property_access_from_index_signature: bool,
+    // Unreachable code and unused labels are errors when false, and suggestions when unset
+    pub allow_unreachable_code: Option<bool>,
+    pub allow_unused_labels: Option<bool>,
+    pub print_types: bool,
+    // Each file is parsed and emitted on its own, and nothing is bound or checked
+    pub transpile_only: bool,
+    pub single_threaded: bool,
+    pub list_files: bool,
+    pub list_files_only: bool,
+    pub explain_files: bool,
+    pub diagnostics: bool,
+    pub extended_diagnostics: bool,
     // Additional options as needed
 }
 
+impl CompilerOptions {
+    // The module system of the output, which depends on the target when none is configured
+    pub fn emit_module_kind(&self) -> ModuleKind {
+        self.module
+            .unwrap_or_else(|| ModuleKind::default_for_target(self.target))
+    }
+
+    // How module specifiers are resolved, which depends on the module system when no
+    // strategy is configured
+    pub fn module_resolution_kind(&self) -> ModuleResolutionKind {
+        self.module_resolution
+            .unwrap_or(match self.emit_module_kind() {
+                ModuleKind::CommonJS => ModuleResolutionKind::Node10,
+                ModuleKind::Node16 | ModuleKind::Node18 => ModuleResolutionKind::Node16,
+                ModuleKind::NodeNext => ModuleResolutionKind::NodeNext,
+                ModuleKind::Preserve => ModuleResolutionKind::Bundler,
+                _ => ModuleResolutionKind::Classic,
+            })
+    }
+
+    // Whether a module without a default export can be imported as one, as when CommonJS
+    // modules are imported with `esModuleInterop`
+    pub fn get_allow_synthetic_default_imports(&self) -> bool {
+        self.allow_synthetic_default_imports.unwrap_or(
+            self.es_module_interop
+                || self.emit_module_kind() == ModuleKind::System
+                || self.module_resolution_kind() == ModuleResolutionKind::Bundler,
+        )
+    }
+
+    // Whether `.json` files can be imported, which bundlers allow by default
+    pub fn get_resolve_json_module(&self) -> bool {
+        self.resolve_json_module
+            .unwrap_or(self.module_resolution_kind() == ModuleResolutionKind::Bundler)
+    }
+
+    // Whether each file has to be transpilable on its own, which `verbatimModuleSyntax` also
+    // requires
+    pub fn get_isolated_modules(&self) -> bool {
+        self.isolated_modules || self.verbatim_module_syntax
+    }
+}
+
+// The options of a compilation without flags or a configuration file
+impl Default for CompilerOptions {
+    fn default() -> Self {
+        create_compiler_options(&Cli::parse_from(["tsrs"]))
+    }
+}
+
+pub fn create_format_options(args: &FmtArgs) -> FormatOptions {
+    FormatOptions {
+        indent_size: args.indent_size,
+        tab_size: args.indent_size,
+        convert_tabs_to_spaces: !args.use_tabs,
+        place_open_brace_on_new_line_for_functions: args.place_open_brace_on_new_line,
+        place_open_brace_on_new_line_for_control_blocks: args.place_open_brace_on_new_line,
+        trailing_commas: match args.trailing_commas {
+            TrailingCommas::Preserve => TrailingCommaKind::Preserve,
+            TrailingCommas::Insert => TrailingCommaKind::Insert,
+            TrailingCommas::Remove => TrailingCommaKind::Remove,
+        },
+        ..FormatOptions::default()
+    }
+}
+
 pub fn create_compiler_options(cli: &Cli) -> CompilerOptions {
+    let target = match cli.target {
+        Some(Target::Es5) => ScriptTarget::ES5,
+        Some(Target::Es2015) => ScriptTarget::ES2015,
+        Some(Target::Es2016) => ScriptTarget::ES2016,
+        Some(Target::Es2017) => ScriptTarget::ES2017,
+        Some(Target::Es2018) => ScriptTarget::ES2018,
+        Some(Target::Es2019) => ScriptTarget::ES2019,
+        Some(Target::Es2020) => ScriptTarget::ES2020,
+        Some(Target::Es2021) => ScriptTarget::ES2021,
+        Some(Target::Es2022) | None => ScriptTarget::ES2022,
+        Some(Target::Es2023) => ScriptTarget::ES2023,
+        Some(Target::Es2024) => ScriptTarget::ES2024,
+        Some(Target::EsNext) => ScriptTarget::ESNext,
+    };
+    let module = cli.module.map(|module| match module {
+        Module::None => ModuleKind::None,
+        Module::CommonJs => ModuleKind::CommonJS,
+        Module::Amd => ModuleKind::AMD,
+        Module::Umd => ModuleKind::UMD,
+        Module::System => ModuleKind::System,
+        Module::Es2015 => ModuleKind::ES2015,
+        Module::Es2020 => ModuleKind::ES2020,
+        Module::Es2022 => ModuleKind::ES2022,
+        Module::EsNext => ModuleKind::ESNext,
+        Module::Node16 => ModuleKind::Node16,
+        Module::Node18 => ModuleKind::Node18,
+        Module::NodeNext => ModuleKind::NodeNext,
+        Module::Preserve => ModuleKind::Preserve,
+    });
+    let strict = cli.strict.unwrap_or(false);
     CompilerOptions {
-        target: match cli.target {
-            Some(Target::Es5) => "ES5".to_string(),
-            Some(Target::Es2015) => "ES2015".to_string(),
-            Some(Target::Es2016) => "ES2016".to_string(),
-            // Add other targets
-            _ => "ES2022".to_string(), // Default
-        },
-        module: match cli.module {
-            Some(Module::CommonJs) => "CommonJS".to_string(),
-            Some(Module::Es2015) => "ES2015".to_string(),
-            // Add other module types
-            _ => "ESNext".to_string(), // Default
+        target,
+        module,
+        module_resolution: cli.module_resolution.map(|kind| match kind {
+            ModuleResolution::Classic => ModuleResolutionKind::Classic,
+            ModuleResolution::Node10 => ModuleResolutionKind::Node10,
+            ModuleResolution::Node16 => ModuleResolutionKind::Node16,
+            ModuleResolution::NodeNext => ModuleResolutionKind::NodeNext,
+            ModuleResolution::Bundler => ModuleResolutionKind::Bundler,
+        }),
+        trace_resolution: cli.trace_resolution,
+        base_url: cli
+            .base_url
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string()),
+        root_dirs: cli.root_dirs.clone(),
+        custom_conditions: cli.custom_conditions.clone(),
+        module_suffixes: cli.module_suffixes.clone(),
+        resolve_json_module: cli.resolve_json_module,
+        allow_importing_ts_extensions: cli.allow_importing_ts_extensions,
+        allow_arbitrary_extensions: cli.allow_arbitrary_extensions,
+        allow_umd_global_access: cli.allow_umd_global_access,
+        no_resolve: cli.no_resolve,
+        no_unchecked_side_effect_imports: cli.no_unchecked_side_effect_imports,
+        preserve_symlinks: cli.preserve_symlinks,
+        composite: cli.composite,
+        // A composite project is built incrementally
+        incremental: cli.incremental || cli.composite,
+        ts_build_info_file: cli
+            .ts_build_info_file
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string()),
+        allow_js: cli.allow_js,
+        lib: cli.lib.clone(),
+        no_lib: cli.no_lib,
+        skip_lib_check: cli.skip_lib_check,
+        skip_default_lib_check: cli.skip_default_lib_check,
+        types: cli.types.clone(),
+        type_roots: cli.type_roots.clone(),
+        force_consistent_casing_in_file_names: cli
+            .force_consistent_casing_in_file_names
+            .unwrap_or(true),
+        es_module_interop: cli.es_module_interop
+            || module.is_some_and(ModuleKind::implies_es_module_interop),
+        allow_synthetic_default_imports: cli.allow_synthetic_default_imports,
+        isolated_modules: cli.isolated_modules,
+        verbatim_module_syntax: cli.verbatim_module_syntax,
+        imports_not_used_as_values: cli.imports_not_used_as_values,
+        isolated_declarations: cli.isolated_declarations,
+        erasable_syntax_only: cli.erasable_syntax_only,
+        jsx: match cli.jsx {
+            Some(JsxMode::Preserve) => JsxEmit::Preserve,
+            Some(JsxMode::React) => JsxEmit::React,
+            Some(JsxMode::ReactNative) => JsxEmit::ReactNative,
+            Some(JsxMode::ReactJsx) => JsxEmit::ReactJsx,
+            Some(JsxMode::ReactJsxDev) => JsxEmit::ReactJsxDev,
+            None => JsxEmit::None,
         },
+        jsx_factory: cli.jsx_factory.clone(),
+        jsx_fragment_factory: cli.jsx_fragment_factory.clone(),
+        jsx_import_source: cli.jsx_import_source.clone(),
+        // Class fields are defined as the standard does from the target that has them
+        use_define_for_class_fields: cli
+            .use_define_for_class_fields
+            .unwrap_or(target >= ScriptTarget::ES2022),
+        experimental_decorators: cli.experimental_decorators,
+        emit_decorator_metadata: cli.emit_decorator_metadata,
         source_map: cli.source_map,
-        declaration: cli.declaration,
+        inline_source_map: cli.inline_source_map,
+        inline_sources: cli.inline_sources,
+        map_root: cli.map_root.clone(),
+        source_root: cli.source_root.clone(),
+        // A composite project declares what it exports for the projects that reference it
+        declaration: cli.declaration || cli.composite,
+        declaration_map: cli.declaration_map,
+        declaration_dir: cli
+            .declaration_dir
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string()),
+        emit_declaration_only: cli.emit_declaration_only,
+        downlevel_iteration: cli.downlevel_iteration,
+        import_helpers: cli.import_helpers,
+        no_emit_helpers: cli.no_emit_helpers,
+        preserve_const_enums: cli.preserve_const_enums,
+        strip_internal: cli.strip_internal,
         out_dir: cli
             .out_dir
             .as_ref()
             .map(|p| p.to_string_lossy().to_string()),
+        out_file: cli
+            .out_file
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string()),
+        root_dir: cli
+            .root_dir
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string()),
         no_emit: cli.no_emit,
-        skip_type_checking: false, // Implement based on cli options
+        no_emit_on_error: cli.no_emit_on_error,
+        remove_comments: cli.remove_comments,
+        new_line: match cli.new_line {
+            Some(NewLine::Crlf) => NewLineKind::CarriageReturnLineFeed,
+            _ => NewLineKind::LineFeed,
+        },
+        emit_bom: cli.emit_bom,
+        charset: cli.charset.clone(),
+        skip_type_checking: cli.no_check || cli.transpile_only,
         pretty: cli.pretty,
+        preserve_watch_output: cli.preserve_watch_output,
+        diagnostic_format: match cli.diagnostic_format {
+            DiagnosticFormat::Pretty => DiagnosticFormatKind::Pretty,
+            DiagnosticFormat::Json => DiagnosticFormatKind::Json,
+            DiagnosticFormat::Sarif => DiagnosticFormatKind::Sarif,
+        },
+        // `--strict` turns on the strict family, and each option's own flag overrides it
+        no_implicit_any: cli.no_implicit_any.unwrap_or(strict),
+        strict_null_checks: cli.strict_null_checks.unwrap_or(strict),
+        strict_function_types: cli.strict_function_types.unwrap_or(strict),
+        strict_bind_call_apply: cli.strict_bind_call_apply.unwrap_or(strict),
+        strict_property_initialization: cli.strict_property_initialization.unwrap_or(strict),
+        no_implicit_this: cli.no_implicit_this.unwrap_or(strict),
+        use_unknown_in_catch_variables: cli.use_unknown_in_catch_variables.unwrap_or(strict),
+        always_strict: cli.always_strict.unwrap_or(strict),
+        strict_builtin_iterator_return: cli.strict_builtin_iterator_return.unwrap_or(strict),
+        // Not part of `--strict`, as in tsc
+        exact_optional_property_types: cli.exact_optional_property_types.unwrap_or(false),
+        no_unused_locals: cli.no_unused_locals.unwrap_or(false),
+        no_unused_parameters: cli.no_unused_parameters.unwrap_or(false),
+        no_implicit_returns: cli.no_implicit_returns.unwrap_or(false),
+        no_fallthrough_cases_in_switch: cli.no_fallthrough_cases_in_switch.unwrap_or(false),
+        no_unchecked_indexed_access: cli.no_unchecked_indexed_access.unwrap_or(false),
+        no_implicit_override: cli.no_implicit_override.unwrap_or(false),
+        no_property_access_from_index_signature: cli
+            .no_property_access_from_index_signature
+            .unwrap_or(false),
+        allow_unreachable_code: cli.allow_unreachable_code,
+        allow_unused_labels: cli.allow_unused_labels,
+        print_types: cli.print_types,
+        transpile_only: cli.transpile_only,
+        single_threaded: cli.single_threaded,
+        list_files: cli.list_files,
+        list_files_only: cli.list_files_only,
+        explain_files: cli.explain_files,
+        diagnostics: cli.diagnostics,
+        extended_diagnostics: cli.extended_diagnostics,
+    }
+}
+
+// Parse the command line the way tsc does, before clap sees it: a value can follow an option
+// or be joined to it with `=`, a boolean option can be followed by `true` or `false`, and an
+// unknown option is reported with the name of the closest known one. `args` does not include
+// the name of the program. The arguments of a subcommand are left to clap.
+pub fn parse_command_line(args: impl IntoIterator<Item = String>) -> Result<Cli, Vec<Diagnostic>> {
+    let mut command = Cli::command();
+    // Building the command settles the actions and values of its arguments
+    command.build();
+    let options: Vec<&clap::Arg> = command
+        .get_arguments()
+        .filter(|arg| !arg.is_positional())
+        .collect();
+    let mut diagnostics = Vec::new();
+    let mut args = expand_response_files(args, &mut diagnostics)
+        .into_iter()
+        .peekable();
+    let mut normalized = vec![command.get_name().to_string()];
+    let mut seen_positional = false;
+    while let Some(arg) = args.next() {
+        let name = if let Some(name) = arg.strip_prefix("--") {
+            name
+        } else if let Some(name) = arg.strip_prefix('-').filter(|name| !name.is_empty()) {
+            name
+        } else {
+            // Everything after a subcommand belongs to it
+            let is_subcommand = !seen_positional
+                && command
+                    .get_subcommands()
+                    .any(|subcommand| subcommand.get_name() == arg);
+            seen_positional = true;
+            normalized.push(arg);
+            if is_subcommand {
+                normalized.extend(args.by_ref());
+            }
+            continue;
+        };
+        let (name, mut value) = match name.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (name, None),
+        };
+        let option = options.iter().find(|option| {
+            option.get_long() == Some(name)
+                || (name.chars().count() == 1 && option.get_short() == name.chars().next())
+        });
+        let Some(option) = option else {
+            let long_names: Vec<&str> = options
+                .iter()
+                .filter_map(|option| option.get_long())
+                .collect();
+            let arg = format!("--{name}");
+            diagnostics.push(
+                match get_spelling_suggestion(name, &long_names, |name| name) {
+                    Some(suggestion) => option_diagnostic(
+                        diagnostics::UNKNOWN_COMPILER_OPTION_0_DID_YOU_MEAN_1_5025,
+                        &[arg, suggestion.to_string()],
+                    ),
+                    None => option_diagnostic(diagnostics::UNKNOWN_COMPILER_OPTION_0_5023, &[arg]),
+                },
+            );
+            continue;
+        };
+        let long_name = option.get_long().unwrap_or(name);
+
+        let possible_values = option.get_possible_values();
+        let is_boolean = !option.get_action().takes_values()
+            || (!possible_values.is_empty()
+                && possible_values
+                    .iter()
+                    .all(|value| matches!(value.get_name(), "true" | "false")));
+        if is_boolean {
+            // A boolean option takes `true` or `false` after it, and is otherwise set
+            let next_is_boolean = args
+                .peek()
+                .is_some_and(|next| next == "true" || next == "false");
+            if value.is_none() && next_is_boolean {
+                value = args.next();
+            }
+            let value = value.unwrap_or_else(|| "true".to_string());
+            if value != "true" && value != "false" {
+                diagnostics.push(option_diagnostic(
+                    diagnostics::COMPILER_OPTION_0_REQUIRES_A_VALUE_OF_TYPE_1_5024,
+                    &[long_name.to_string(), "boolean".to_string()],
+                ));
+            } else if option.get_action().takes_values() {
+                normalized.push(format!("--{long_name}={value}"));
+            } else if value == "true" {
+                normalized.push(format!("--{long_name}"));
+            }
+            continue;
+        }
+
+        let Some(value) = value.or_else(|| args.next()) else {
+            diagnostics.push(option_diagnostic(
+                diagnostics::COMPILER_OPTION_0_EXPECTS_AN_ARGUMENT_6044,
+                &[long_name.to_string()],
+            ));
+            continue;
+        };
+        // The values of an enumerated option are not case sensitive, and a list option takes
+        // its values separated by commas
+        let is_known = |value: &str| {
+            possible_values
+                .iter()
+                .any(|possible| possible.matches(value, true))
+        };
+        let is_valid = match option.get_value_delimiter() {
+            Some(delimiter) => value.split(delimiter).all(is_known),
+            None => is_known(&value),
+        };
+        if !possible_values.is_empty() && !is_valid {
+            let names: Vec<String> = possible_values
+                .iter()
+                .filter(|possible| !possible.is_hide_set())
+                .flat_map(|possible| possible.get_name_and_aliases())
+                .map(|name| format!("'{name}'"))
+                .collect();
+            diagnostics.push(option_diagnostic(
+                diagnostics::ARGUMENT_FOR_0_OPTION_MUST_BE_COLON_1_6046,
+                &[format!("--{long_name}"), names.join(", ")],
+            ));
+            continue;
+        }
+        let value = if possible_values.is_empty() {
+            value
+        } else {
+            value.to_lowercase()
+        };
+        normalized.push(format!("--{long_name}={value}"));
+    }
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+    // What clap could still reject is in the arguments of a subcommand, which it reports
+    Ok(Cli::try_parse_from(normalized).unwrap_or_else(|error| error.exit()))
+}
+
+// Replace each `@file` argument with the arguments the file holds, which may name response
+// files of their own
+fn expand_response_files(
+    args: impl IntoIterator<Item = String>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<String> {
+    let mut expanded = Vec::new();
+    for arg in args {
+        let Some(file_name) = arg.strip_prefix('@') else {
+            expanded.push(arg);
+            continue;
+        };
+        match std::fs::read_to_string(file_name) {
+            Ok(text) => {
+                let args = split_response_file(&text, file_name, diagnostics);
+                expanded.extend(expand_response_files(args, diagnostics));
+            }
+            Err(_) => diagnostics.push(option_diagnostic(
+                diagnostics::UNABLE_TO_OPEN_FILE_0_6050,
+                &[file_name.to_string()],
+            )),
+        }
+    }
+    expanded
+}
+
+// Split the text of a response file into arguments. They are separated by whitespace, an
+// argument in double quotes can hold whitespace, and a `#` starting an argument comments out
+// the rest of its line.
+fn split_response_file(
+    text: &str,
+    file_name: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '#' {
+            chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+        } else if c == '"' {
+            chars.next();
+            let mut arg = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => arg.push(c),
+                    None => {
+                        diagnostics.push(option_diagnostic(
+                            diagnostics::UNTERMINATED_QUOTED_STRING_IN_RESPONSE_FILE_0_6045,
+                            &[file_name.to_string()],
+                        ));
+                        break;
+                    }
+                }
+            }
+            args.push(arg);
+        } else {
+            let mut arg = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                arg.push(c);
+            }
+            args.push(arg);
+        }
     }
+    args
 }
 
 pub fn print_help(all: bool) {
@@ -379,6 +1336,12 @@ pub fn print_help(all: bool) {
     println!("  tsrs -p ./path/to/tsrsonfig.json");
     println!("  Compiles the TypeScript project located at the specified path.");
     println!();
+    println!("  tsrs @args.txt");
+    println!("  Compiles with the command line options and files listed in args.txt.");
+    println!();
+    println!("  tsrs fmt app.ts util.ts");
+    println!("  Formats the specified files in place.");
+    println!();
     println!("  tsrs --help --all");
     println!("  An expanded version of this information, showing all possible compiler options");
     println!();
@@ -418,6 +1381,12 @@ pub fn print_help(all: bool) {
     println!("                  type:  boolean");
     println!("               default:  true");
     println!();
+    println!(
+        "     --diagnosticFormat  Specify the format of reported diagnostics: text for people, or JSON or SARIF for tools."
+    );
+    println!("                one of:  pretty, json, sarif");
+    println!("               default:  pretty");
+    println!();
     println!(
         "      --declaration, -d  Generate .d.ts files from TypeScript and JavaScript files in your project."
     );
@@ -439,3 +1408,88 @@ pub fn print_help(all: bool) {
 
     println!("You can learn about all of the compiler options at https://aka.ms/tsrs");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, Vec<Diagnostic>> {
+        parse_command_line(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_values_the_way_tsc_does() {
+        let diagnostics = parse(&["--strict", "false", "--pretty", "false", "--lib"])
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(diagnostics[0].code, 6044);
+
+        let cli = parse(&[
+            "a.ts",
+            "--outDir=out",
+            "--noEmit",
+            "false",
+            "--lib",
+            "es5,dom",
+        ])
+        .unwrap_or_else(|_| panic!("the command line is valid"));
+        assert_eq!(cli.files, vec!["a.ts".to_string()]);
+        assert_eq!(cli.out_dir, Some(PathBuf::from("out")));
+        assert!(!cli.no_emit);
+        assert_eq!(cli.lib, vec!["es5".to_string(), "dom".to_string()]);
+    }
+
+    #[test]
+    fn reports_unknown_options_and_invalid_values() {
+        let codes = |args: &[&str]| {
+            parse(args)
+                .map(|_| Vec::new())
+                .unwrap_or_else(|diagnostics| {
+                    diagnostics
+                        .iter()
+                        .map(|diagnostic| diagnostic.code)
+                        .collect()
+                })
+        };
+        assert_eq!(codes(&["--outdir", "out"]), vec![5025]);
+        assert_eq!(codes(&["--bogus"]), vec![5023]);
+        assert_eq!(codes(&["--target", "es9"]), vec![6046]);
+        assert_eq!(codes(&["--strict=maybe"]), vec![5024]);
+        let diagnostics = parse(&["--outdir", "out"]).map(|_| ()).unwrap_err();
+        assert!(diagnostics[0].message.contains("Did you mean 'outDir'?"));
+    }
+
+    #[test]
+    fn derives_options_that_others_imply() {
+        let options = create_compiler_options(&Cli::parse_from(["tsrs", "--composite"]));
+        assert!(options.declaration && options.incremental);
+        assert!(options.use_define_for_class_fields);
+        assert!(!options.get_isolated_modules());
+
+        let cli = Cli::parse_from([
+            "tsrs",
+            "--verbatimModuleSyntax",
+            "--target=es2017",
+            "--moduleResolution=bundler",
+        ]);
+        let options = create_compiler_options(&cli);
+        assert!(options.get_isolated_modules());
+        assert!(!options.use_define_for_class_fields);
+        assert!(options.get_resolve_json_module());
+        assert!(options.get_allow_synthetic_default_imports());
+    }
+
+    #[test]
+    fn splits_response_files_with_quotes_and_comments() {
+        let mut diagnostics = Vec::new();
+        let text = "# the sources\n--outDir \"out dir\"  a.ts\r\n\tb.ts # and c.ts\n";
+        assert_eq!(
+            split_response_file(text, "args.txt", &mut diagnostics),
+            vec!["--outDir", "out dir", "a.ts", "b.ts"]
+        );
+        assert!(diagnostics.is_empty());
+
+        split_response_file("a.ts \"b.ts", "args.txt", &mut diagnostics);
+        assert_eq!(diagnostics[0].code, 6045);
+    }
+}
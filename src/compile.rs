@@ -1,6 +1,65 @@
 // Basic types needed for TypeScript compilation
 
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::SystemTime;
+
 use crate::cli::*;
+use crate::compiler::ast::utilities::{
+    get_error_range, get_external_module_indicator, is_external_module,
+};
+use crate::compiler::ast::visitor::for_each_descendant;
+use crate::compiler::ast::{Node, NodeFlags, NodeId, SyntaxKind};
+use crate::compiler::binder::{Bindings, remove_file_extension};
+use crate::compiler::cancellation::{CancellationToken, OperationCanceled};
+use crate::compiler::checker::{Checker, CheckerOptions, LiteralValue};
+use crate::compiler::diagnostics::{self, Category};
+use crate::compiler::emitter::{
+    PrinterOptions, SourceMapGenerator, print_file, print_file_with_mappings,
+};
+use crate::compiler::lib_files::{default_lib_file_name, lib_file_name, lib_names};
+use crate::compiler::module_resolution::{
+    self, ModuleResolutionKind, ModuleResolutionOptions, ResolutionMode, ResolvedModule,
+    get_automatic_type_directive_names, get_effective_type_roots, get_implied_node_format,
+    resolve_module_name, resolve_type_reference_directive,
+};
+use crate::compiler::parser::{get_script_kind_from_file_name, parse_source_file};
+use crate::compiler::paths;
+use crate::compiler::text::{
+    TextRange, compute_line_starts, compute_text_change_range, position_to_line_and_character,
+};
+use crate::compiler::transformers::{
+    JsxEmit, ModuleKind, TransformOptions, transform_bundle, transform_file,
+};
+use crate::config::read_directory;
+use crate::language_service::LanguageService;
+use crate::watch::{FileWatcher, POLL_INTERVAL, PollingFileWatcher};
+
+mod diagnostic_collection;
+mod output;
+mod parallel;
+pub mod performance;
+mod reporter;
+mod statistics;
+mod transpile;
+mod virtual_host;
+
+pub use diagnostic_collection::{
+    DiagnosticCollection, compare_diagnostics, sort_and_deduplicate_diagnostics,
+};
+use parallel::map_in_parallel;
+use performance::Phase;
+pub use reporter::{
+    DiagnosticFormatKind, DiagnosticReporter, JsonReporter, SarifReporter, TextReporter,
+    create_reporter, print_diagnostic, report_diagnostics,
+};
+pub use statistics::{CheckStatistics, report_statistics};
+pub use transpile::{TranspileOptions, TranspileOutput, transpile_module};
+pub use virtual_host::VirtualCompilerHost;
 
 #[derive(Clone)]
 pub struct SourceFile {
@@ -10,18 +69,24 @@ pub struct SourceFile {
 }
 
 // Compilation result diagnostics
-#[derive(Debug)]
-pub(crate) struct Diagnostic {
-    pub(crate) file_name: Option<String>,
-    pub(crate) line: usize,
-    pub(crate) character: usize,
-    pub(crate) message: String,
-    pub(crate) code: u32,
-    pub(crate) category: DiagnosticCategory,
-}
-
-#[derive(Debug)]
-pub(crate) enum DiagnosticCategory {
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub file_name: Option<String>,
+    // Where the diagnostic starts, as a 1-based line and character, or 0 and 0 outside files
+    pub line: usize,
+    pub character: usize,
+    // Where the diagnostic ends, counted like its start
+    pub end_line: usize,
+    pub end_character: usize,
+    pub message: String,
+    pub code: u32,
+    pub category: DiagnosticCategory,
+    // Other locations that explain the diagnostic, like the declaration of a name it is about
+    pub related_information: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticCategory {
     Error,
     Warning,
     Suggestion,
@@ -29,43 +94,508 @@ pub(crate) enum DiagnosticCategory {
 }
 
 // Program represents the entire TypeScript program being compiled
-pub(crate) struct Program {
-    pub(crate) source_files: Vec<SourceFile>,
-    pub(crate) diagnostics: Vec<Diagnostic>,
-    // Will eventually contain more state like:
-    // - Symbol tables
-    // - Type checker results
-    // - etc.
+pub struct Program {
+    pub source_files: Vec<SourceFile>,
+    // The parsed syntax tree of each source file, in the same order
+    pub syntax_trees: Vec<Arc<Node>>,
+    pub diagnostics: Vec<Diagnostic>,
+    // The standard library files among the source files
+    pub lib_files: Vec<String>,
+    pub options: CompilerOptions,
+    // Why each source file is part of the program, by file name
+    file_include_reasons: HashMap<String, Vec<FileIncludeReason>>,
+    current_directory: String,
+    use_case_sensitive_file_names: bool,
+    // The file each import resolved to, by the importing file and the module name
+    resolved_modules: HashMap<(String, String), String>,
+    // The symbols and control flow of the source files, which every checker of the program
+    // starts from
+    bindings: Bindings,
+    // What checking the program created, once it has been checked
+    check_statistics: CheckStatistics,
+    // Whether some options have invalid values, or values that cannot be used together,
+    // whatever the files of the program are
+    invalid_options: bool,
+}
+
+impl Program {
+    // Whether a file is a standard library file, which `skipDefaultLibCheck` skips checking
+    pub fn is_default_lib_file(&self, file_name: &str) -> bool {
+        self.lib_files.iter().any(|lib_file| lib_file == file_name)
+    }
+
+    // Get a source file of the program by its name or path
+    pub fn get_source_file(&self, file_name: &str) -> Option<&SourceFile> {
+        self.file_index(file_name)
+            .map(|index| &self.source_files[index])
+    }
+
+    // Get the syntax tree of a source file of the program by its name or path
+    pub fn get_syntax_tree(&self, file_name: &str) -> Option<&Arc<Node>> {
+        self.file_index(file_name)
+            .map(|index| &self.syntax_trees[index])
+    }
+
+    // Get the reasons a file is part of the program, in the order they were found
+    pub fn get_file_include_reasons(&self, file_name: &str) -> &[FileIncludeReason] {
+        self.file_index(file_name)
+            .and_then(|index| {
+                self.file_include_reasons
+                    .get(&self.source_files[index].file_name)
+            })
+            .map_or(&[], Vec::as_slice)
+    }
+
+    // Describe why a file is part of the program, as `--explainFiles` prints it
+    pub fn explain_file_include_reason(&self, reason: &FileIncludeReason) -> String {
+        let (message, args) = match reason {
+            FileIncludeReason::RootFile => (
+                diagnostics::ROOT_FILE_SPECIFIED_FOR_COMPILATION_1427,
+                vec![],
+            ),
+            FileIncludeReason::LibFile { lib: Some(lib) } => (
+                diagnostics::LIBRARY_0_SPECIFIED_IN_COMPILER_OPTIONS_1422,
+                vec![lib.clone()],
+            ),
+            FileIncludeReason::LibFile { lib: None } => (
+                diagnostics::DEFAULT_LIBRARY_FOR_TARGET_0_1425,
+                vec![format!("{:?}", self.options.target).to_lowercase()],
+            ),
+            FileIncludeReason::AutomaticTypeDirectiveFile { type_reference } => {
+                let message = if self.options.types.is_some() {
+                    diagnostics::ENTRY_POINT_OF_TYPE_LIBRARY_0_SPECIFIED_IN_COMPILER_OPTIONS_1417
+                } else {
+                    diagnostics::ENTRY_POINT_FOR_IMPLICIT_TYPE_LIBRARY_0_1420
+                };
+                (message, vec![type_reference.clone()])
+            }
+            FileIncludeReason::Import(reference) => (
+                diagnostics::IMPORTED_VIA_0_FROM_FILE_1_1393,
+                vec![
+                    format!("\"{}\"", reference.text),
+                    reference.file_name.clone(),
+                ],
+            ),
+            FileIncludeReason::ReferenceFile(reference) => (
+                diagnostics::REFERENCED_VIA_0_FROM_FILE_1_1400,
+                vec![reference.text.clone(), reference.file_name.clone()],
+            ),
+            FileIncludeReason::TypeReferenceDirective(reference) => (
+                diagnostics::TYPE_LIBRARY_REFERENCED_VIA_0_FROM_FILE_1_1402,
+                vec![reference.text.clone(), reference.file_name.clone()],
+            ),
+            FileIncludeReason::LibReferenceDirective(reference) => (
+                diagnostics::LIBRARY_REFERENCED_VIA_0_FROM_FILE_1_1405,
+                vec![reference.text.clone(), reference.file_name.clone()],
+            ),
+        };
+        diagnostics::format_message(message, &args)
+    }
+
+    // List the files of the program one per line, for `--listFiles`, or with `explain`, each
+    // followed by why it is included, for `--explainFiles`
+    pub fn list_files(&self, explain: bool) -> String {
+        let mut list = String::new();
+        for source_file in &self.source_files {
+            list.push_str(&source_file.file_name);
+            list.push('\n');
+            if explain {
+                for reason in self.get_file_include_reasons(&source_file.file_name) {
+                    list.push_str("  ");
+                    list.push_str(&self.explain_file_include_reason(reason));
+                    list.push('\n');
+                }
+            }
+        }
+        list
+    }
+
+    // Get the name of the file of the program that an import in a file resolved to
+    pub fn get_resolved_module(&self, file_name: &str, module_name: &str) -> Option<&str> {
+        self.resolved_modules
+            .get(&(file_name.to_string(), module_name.to_string()))
+            .map(String::as_str)
+    }
+
+    // Get the relative module name that one file of the program can import another by, like
+    // `./util` or `../lib/index`, which leaves off TypeScript extensions as imports do
+    pub fn get_relative_module_name(&self, file_name: &str, imported_file_name: &str) -> String {
+        let directory =
+            paths::get_directory_path(&paths::combine_paths(&self.current_directory, file_name));
+        let path = absolute_path(&self.current_directory, imported_file_name);
+        let mut module_name = relative_path(
+            Path::new(&directory),
+            &path,
+            !self.use_case_sensitive_file_names,
+        );
+        if !module_name.starts_with("../") {
+            module_name.insert_str(0, "./");
+        }
+        let extensions = [
+            (".d.ts", ""),
+            (".ts", ""),
+            (".tsx", ""),
+            (".js", ""),
+            (".jsx", ""),
+            (".d.mts", ".mjs"),
+            (".mts", ".mjs"),
+            (".d.cts", ".cjs"),
+            (".cts", ".cjs"),
+        ];
+        for (extension, replacement) in extensions {
+            if let Some(stem) = module_name.strip_suffix(extension) {
+                return format!("{stem}{replacement}");
+            }
+        }
+        module_name
+    }
+
+    // Create a checker over the files of the program, which binds them all
+    pub fn get_type_checker(&self) -> Checker {
+        let options = &self.options;
+        let mut checker = Checker::with_bindings(&self.syntax_trees, self.bindings.clone());
+        checker.set_options(CheckerOptions {
+            strict_null_checks: options.strict_null_checks,
+            strict_property_initialization: options.strict_property_initialization,
+            no_implicit_override: false,
+            no_implicit_any: options.no_implicit_any,
+            no_implicit_this: options.no_implicit_this,
+            strict_function_types: options.strict_function_types,
+            strict_bind_call_apply: options.strict_bind_call_apply,
+            use_unknown_in_catch_variables: options.use_unknown_in_catch_variables,
+            exact_optional_property_types: options.exact_optional_property_types,
+            no_unused_locals: options.no_unused_locals,
+            no_unused_parameters: options.no_unused_parameters,
+            allow_synthetic_default_imports: options.get_allow_synthetic_default_imports(),
+            no_unchecked_indexed_access: options.no_unchecked_indexed_access,
+            target: Some(options.target),
+            module: Some(options.emit_module_kind()),
+            downlevel_iteration: options.downlevel_iteration,
+            no_property_access_from_index_signature: options
+                .no_property_access_from_index_signature,
+        });
+        checker.set_resolved_modules(self.resolved_modules.clone());
+        checker
+    }
+
+    // Check one file of the program, or with no file name, every file, except those that
+    // `skipLibCheck` and `skipDefaultLibCheck` skip
+    pub fn get_semantic_diagnostics(&self, file_name: Option<&str>) -> Vec<Diagnostic> {
+        self.check_files(file_name, &CancellationToken::new())
+            .expect("a new token is never cancelled")
+            .0
+    }
+
+    // Get what checking the program created, which is nothing until it has been checked
+    pub fn get_check_statistics(&self) -> CheckStatistics {
+        self.check_statistics
+    }
+
+    // Check the files on several threads, each with a checker of its own. See `checker_count`
+    // for how many there are.
+    fn check_files(
+        &self,
+        file_name: Option<&str>,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(Vec<Diagnostic>, CheckStatistics), OperationCanceled> {
+        let only = file_name.map(|file_name| self.file_index(file_name));
+        let files: Vec<usize> = (0..self.source_files.len())
+            .filter(|&index| {
+                let source_file = &self.source_files[index];
+                let tree = &self.syntax_trees[index];
+                !(only.is_some_and(|only| only != Some(index))
+                    || (self.options.skip_lib_check && tree.as_source_file().is_declaration_file)
+                    || (self.options.skip_default_lib_check
+                        && self.is_default_lib_file(&source_file.file_name)))
+            })
+            .collect();
+        let checker_count = if self.options.single_threaded {
+            1
+        } else {
+            checker_count(files.len())
+        };
+        self.check_files_on(&files, checker_count, cancellation_token)
+    }
+
+    // Check `files` with `checker_count` checkers, each checking every `checker_count`th file.
+    // The checkers are copies of one that has already merged the globals and resolved the
+    // global types, so that is done once however many checkers there are. The files are
+    // dealt to the checkers by their order rather than as threads become free, and the
+    // diagnostics are gathered into a sorted collection, so the report is the same however
+    // the threads are scheduled.
+    fn check_files_on(
+        &self,
+        files: &[usize],
+        checker_count: usize,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(Vec<Diagnostic>, CheckStatistics), OperationCanceled> {
+        let partitions: Vec<Vec<usize>> = (0..checker_count)
+            .map(|first| {
+                files
+                    .iter()
+                    .copied()
+                    .skip(first)
+                    .step_by(checker_count)
+                    .collect()
+            })
+            .collect();
+        let mut prepared = self.get_type_checker();
+        prepared.resolve_global_types();
+        let bound_symbols = self.bindings.symbols.len();
+        let prepared_statistics = CheckStatistics::of_checker(&prepared, bound_symbols);
+        let results = map_in_parallel(&partitions, self.options.single_threaded, |partition| {
+            let mut checker = prepared.clone();
+            checker.set_cancellation_token(cancellation_token.clone());
+            let diagnostics = partition
+                .iter()
+                .take_while(|_| !cancellation_token.is_cancellation_requested())
+                .flat_map(|&index| {
+                    checker
+                        .get_diagnostics(&self.syntax_trees[index])
+                        .iter()
+                        .map(|diagnostic| {
+                            convert_diagnostic_in(diagnostic, &|file_name| {
+                                self.get_source_file(file_name)
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            // What the prepared checker created is counted once, rather than by every copy
+            let mut statistics = CheckStatistics::of_checker(&checker, bound_symbols);
+            statistics -= prepared_statistics;
+            (diagnostics, statistics)
+        });
+        cancellation_token.throw_if_cancellation_requested()?;
+        let mut statistics = prepared_statistics;
+        let mut diagnostics = Vec::new();
+        for (checker_diagnostics, checker_statistics) in results {
+            diagnostics.extend(checker_diagnostics);
+            statistics += checker_statistics;
+        }
+        Ok((sort_and_deduplicate_diagnostics(diagnostics), statistics))
+    }
+
+    // Find a source file by its name, or by its path from the working directory
+    fn file_index(&self, file_name: &str) -> Option<usize> {
+        let path = canonical_path(
+            &absolute_path(&self.current_directory, file_name),
+            self.use_case_sensitive_file_names,
+        );
+        self.source_files.iter().position(|source_file| {
+            source_file.file_name == file_name
+                || canonical_path(
+                    &absolute_path(&self.current_directory, &source_file.file_name),
+                    self.use_case_sensitive_file_names,
+                ) == path
+        })
+    }
+}
+
+// Why a file is part of a program
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileIncludeReason {
+    // One of the files the program was created from
+    RootFile,
+    // A standard library file of the `lib` option, named by `lib`, or with no name, the default
+    // library of the target
+    LibFile { lib: Option<String> },
+    // A type package of the `types` option, or one found in the type roots
+    AutomaticTypeDirectiveFile { type_reference: String },
+    Import(FileReference),
+    // A file named by `/// <reference path="..." />`
+    ReferenceFile(FileReference),
+    // A type package named by `/// <reference types="..." />`
+    TypeReferenceDirective(FileReference),
+    // A standard library file named by `/// <reference lib="..." />`
+    LibReferenceDirective(FileReference),
+}
+
+// Where a file refers to another, by a module specifier or the value of a directive
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileReference {
+    pub file_name: String,
+    pub text: String,
+    pub range: TextRange,
+}
+
+impl FileIncludeReason {
+    // Where the file is referred to, unless it is included by the options
+    pub fn reference(&self) -> Option<&FileReference> {
+        match self {
+            FileIncludeReason::Import(reference)
+            | FileIncludeReason::ReferenceFile(reference)
+            | FileIncludeReason::TypeReferenceDirective(reference)
+            | FileIncludeReason::LibReferenceDirective(reference) => Some(reference),
+            FileIncludeReason::RootFile
+            | FileIncludeReason::LibFile { .. }
+            | FileIncludeReason::AutomaticTypeDirectiveFile { .. } => None,
+        }
+    }
 }
 
 // Abstraction for file system operations
 pub trait CompilerHost {
     fn read_file(&self, path: &str) -> Option<String>;
-    fn write_file(&self, path: &str, data: &str) -> bool;
+    // Write a file, creating the folders it is in
+    fn write_file(&self, path: &str, data: &str) -> io::Result<()>;
     fn file_exists(&self, path: &str) -> bool;
+    fn directory_exists(&self, path: &str) -> bool;
+    fn get_accessible_entries(&self, path: &str) -> FileSystemEntries;
+    // The files under a folder with one of `extensions`, or any extension if none are given,
+    // matched by any of the `includes` patterns and none of the `excludes`, and with `depth`,
+    // at most that many folders down
+    fn read_directory(
+        &self,
+        path: &str,
+        extensions: &[&str],
+        excludes: &[String],
+        includes: &[String],
+        depth: Option<usize>,
+    ) -> Vec<String>;
+    // The time a file or folder was last changed, which watch mode polls for changes
+    fn get_modified_time(&self, path: &str) -> Option<SystemTime>;
     fn get_current_directory(&self) -> String;
+    // Whether file names that differ only in casing name different files
+    fn use_case_sensitive_file_names(&self) -> bool;
+    // The name that a file is known by whichever casing it is written in, which is the name
+    // in lowercase if file names are not case-sensitive
+    fn get_canonical_file_name(&self, file_name: &str) -> String;
+    // The path of a file or folder with the symbolic links along it followed
+    fn realpath(&self, path: &str) -> String;
+    // The folder with the standard library files, like `lib.es5.d.ts`, where they are reported
+    // missing from if they are not there, or none for a host without a standard library, whose
+    // programs leave it out
+    fn get_default_lib_location(&self) -> Option<String>;
+    // Write a line of diagnostic output, like the steps of `--traceResolution`
+    fn trace(&self, message: &str);
+    // Create the watcher that watch mode waits on for changes to files and folders. It polls
+    // their modification times, unless the host has a better way to learn of changes.
+    fn create_file_watcher(&self) -> Box<dyn FileWatcher + '_>
+    where
+        Self: Sized,
+    {
+        Box::new(PollingFileWatcher::new(self, POLL_INTERVAL))
+    }
     // Additional filesystem operations as needed
 }
 
+impl<T: CompilerHost> module_resolution::ModuleResolutionHost for T {
+    fn file_exists(&self, path: &str) -> bool {
+        CompilerHost::file_exists(self, path)
+    }
+
+    fn directory_exists(&self, path: &str) -> bool {
+        CompilerHost::directory_exists(self, path)
+    }
+
+    fn read_file(&self, path: &str) -> Option<String> {
+        CompilerHost::read_file(self, path)
+    }
+
+    fn get_directories(&self, path: &str) -> Vec<String> {
+        CompilerHost::get_accessible_entries(self, path).directories
+    }
+
+    fn trace(&self, message: &str) {
+        CompilerHost::trace(self, message)
+    }
+}
+
+// The names of the files and folders directly within a folder
+#[derive(Debug, Default)]
+pub struct FileSystemEntries {
+    pub files: Vec<String>,
+    pub directories: Vec<String>,
+}
+
 // Implement a basic filesystem-based compiler host
-struct FileSystemCompilerHost;
+struct FileSystemCompilerHost {
+    // Whether the file system tells apart names that differ only in case, probed once when
+    // the host is created
+    use_case_sensitive_file_names: bool,
+}
+
+impl FileSystemCompilerHost {
+    fn new() -> Self {
+        FileSystemCompilerHost {
+            use_case_sensitive_file_names: probe_case_sensitive_file_names(),
+        }
+    }
+}
+
+// As in tsc, the file system is case-insensitive when the path of the executable with its
+// casing swapped names it too. Without letters to swap, it goes by the platform.
+fn probe_case_sensitive_file_names() -> bool {
+    let platform_default = !cfg!(any(windows, target_os = "macos"));
+    let Ok(exe) = std::env::current_exe() else {
+        return platform_default;
+    };
+    let exe = exe.to_string_lossy();
+    let swapped: String = exe
+        .chars()
+        .map(|ch| {
+            if ch.is_uppercase() {
+                ch.to_ascii_lowercase()
+            } else {
+                ch.to_ascii_uppercase()
+            }
+        })
+        .collect();
+    if swapped == exe {
+        return platform_default;
+    }
+    !Path::new(&swapped).exists()
+}
 
 impl CompilerHost for FileSystemCompilerHost {
     fn read_file(&self, path: &str) -> Option<String> {
-        std::fs::read_to_string(path).ok()
+        performance::measure(Phase::IoRead, || std::fs::read_to_string(path).ok())
+    }
+
+    fn write_file(&self, path: &str, data: &str) -> io::Result<()> {
+        performance::measure(Phase::IoWrite, || {
+            output::write_file_atomically(Path::new(path), data)
+        })
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        std::path::Path::new(path).is_file()
+    }
+
+    fn directory_exists(&self, path: &str) -> bool {
+        std::path::Path::new(path).is_dir()
     }
 
-    fn write_file(&self, path: &str, data: &str) -> bool {
-        if let Some(parent) = std::path::Path::new(path).parent() {
-            if let Err(_) = std::fs::create_dir_all(parent) {
-                return false;
+    fn get_accessible_entries(&self, path: &str) -> FileSystemEntries {
+        let mut entries = FileSystemEntries::default();
+        for entry in std::fs::read_dir(path).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Follow symbolic links to what they link to
+            match std::fs::metadata(entry.path()) {
+                Ok(metadata) if metadata.is_dir() => entries.directories.push(name),
+                Ok(metadata) if metadata.is_file() => entries.files.push(name),
+                _ => {}
             }
         }
-        std::fs::write(path, data).is_ok()
+        entries
     }
 
-    fn file_exists(&self, path: &str) -> bool {
-        std::path::Path::new(path).exists()
+    fn read_directory(
+        &self,
+        path: &str,
+        extensions: &[&str],
+        excludes: &[String],
+        includes: &[String],
+        depth: Option<usize>,
+    ) -> Vec<String> {
+        read_directory(self, path, extensions, excludes, includes, depth)
+    }
+
+    fn get_modified_time(&self, path: &str) -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
     }
 
     fn get_current_directory(&self) -> String {
@@ -73,10 +603,57 @@ impl CompilerHost for FileSystemCompilerHost {
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_else(|_| ".".to_string())
     }
+
+    fn use_case_sensitive_file_names(&self) -> bool {
+        self.use_case_sensitive_file_names
+    }
+
+    fn get_canonical_file_name(&self, file_name: &str) -> String {
+        if self.use_case_sensitive_file_names() {
+            file_name.to_string()
+        } else {
+            file_name.to_lowercase()
+        }
+    }
+
+    fn realpath(&self, path: &str) -> String {
+        std::fs::canonicalize(path)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| path.to_string())
+    }
+
+    fn get_default_lib_location(&self) -> Option<String> {
+        // `TSRS_LIB_DIR` names the folder, or else it is the `lib` folder beside the
+        // executable, or for development builds, the library files of the TypeScript sources.
+        // Without the library files, the folder is the first of these, so that the files are
+        // reported missing from it.
+        let candidates: Vec<PathBuf> = [
+            std::env::var_os("TSRS_LIB_DIR").map(PathBuf::from),
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| Some(exe.parent()?.join("lib"))),
+            Some(PathBuf::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/typescript-go/internal/bundled/libs"
+            ))),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        candidates
+            .iter()
+            .find(|directory| directory.join("lib.es5.d.ts").is_file())
+            .or(candidates.first())
+            .map(|directory| directory.to_string_lossy().to_string())
+    }
+
+    fn trace(&self, message: &str) {
+        println!("{message}");
+    }
 }
 
 pub fn create_compiler_host() -> impl CompilerHost {
-    FileSystemCompilerHost
+    FileSystemCompilerHost::new()
 }
 
 pub fn read_source_files(file_paths: &[String], host: &impl CompilerHost) -> Vec<SourceFile> {
@@ -97,13 +674,7 @@ pub fn read_source_files(file_paths: &[String], host: &impl CompilerHost) -> Vec
 
 // Compute line start positions for error reporting
 pub fn compute_line_map(text: &str) -> Vec<usize> {
-    let mut positions = vec![0];
-    for (i, c) in text.char_indices() {
-        if c == '\n' {
-            positions.push(i + 1);
-        }
-    }
-    positions
+    compute_line_starts(text)
 }
 
 pub fn create_program(
@@ -111,95 +682,2804 @@ pub fn create_program(
     compiler_options: &CompilerOptions,
     host: &impl CompilerHost,
 ) -> Program {
-    // In a real implementation, this would parse files, create AST, etc.
-    Program {
-        source_files: source_files.to_vec(),
-        diagnostics: Vec::new(),
+    create_incremental_program(
+        source_files,
+        compiler_options,
+        host,
+        None,
+        &CancellationToken::new(),
+    )
+    .expect("a new token is never cancelled")
+}
+
+// The outcome of compiling a program
+pub struct CompileResult {
+    pub program: Program,
+    // The names of the output files written, in the order they were written
+    pub emitted_files: Vec<String>,
+    // Whether the output was left unwritten, because of errors with `noEmitOnError` or
+    // because only the files were listed
+    pub emit_skipped: bool,
+}
+
+impl CompileResult {
+    // The problems found in the options and the files of the program
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.program.diagnostics
+    }
+
+    // Whether the program compiled without errors
+    pub fn success(&self) -> bool {
+        !has_errors(self.diagnostics())
+    }
+
+    // The status the compiler exits with after this compilation
+    pub fn exit_status(&self) -> ExitStatus {
+        if self.success() {
+            ExitStatus::Success
+        } else if self.program.invalid_options {
+            ExitStatus::InvalidOptions
+        } else if self.emit_skipped {
+            ExitStatus::DiagnosticsPresentOutputsSkipped
+        } else {
+            ExitStatus::DiagnosticsPresentOutputsGenerated
+        }
     }
 }
 
-pub fn type_check(program: &mut Program) {
-    // In a real implementation, this would perform type checking
-    // and populate program.diagnostics with any type errors
-    println!("Type checking...");
+// The status the compiler exits with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    Success,
+    // The options, the command line or the configuration file were invalid
+    InvalidOptions,
+    // There were errors, and nothing was written because of them
+    DiagnosticsPresentOutputsSkipped,
+    // There were errors, but the output was written anyway
+    DiagnosticsPresentOutputsGenerated,
 }
 
-pub fn emit_files(program: &Program, options: &CompilerOptions, host: &impl CompilerHost) {
-    // In a real implementation, this would output JavaScript files,
-    // declaration files, and source maps based on compiler options
+impl ExitStatus {
+    // The code of the process: 0 on success, 1 for invalid options, and 2 for errors in the
+    // files, whether the output was written or not
+    pub fn code(self) -> u8 {
+        match self {
+            ExitStatus::Success => 0,
+            ExitStatus::InvalidOptions => 1,
+            ExitStatus::DiagnosticsPresentOutputsSkipped
+            | ExitStatus::DiagnosticsPresentOutputsGenerated => 2,
+        }
+    }
+}
 
-    if let Some(out_dir) = &options.out_dir {
-        println!("Emitting files to: {}", out_dir);
+impl From<ExitStatus> for std::process::ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        std::process::ExitCode::from(status.code())
+    }
+}
+
+// How many checkers check `file_count` files at once: one per core, but never more than there
+// are files. The checkers share the global types, but each resolves the declarations its files
+// use on its own and holds a copy of the types, so more checkers than cores would pay for that
+// without checking any faster. What is reported does not depend on
+// the count, as diagnostics are sorted and each file is checked the same by any checker.
+fn checker_count(file_count: usize) -> usize {
+    thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(file_count)
+        .max(1)
+}
+
+fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.category == DiagnosticCategory::Error)
+}
 
-        for source_file in &program.source_files {
-            let base_name = std::path::Path::new(&source_file.file_name)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("unknown");
+// Compile the files `root_names` and the files they import, as `tsrs` does: check them
+// unless checking is skipped, and write their output through the host unless `noEmit` is set,
+// or `noEmitOnError` is set and there are errors
+pub fn compile(
+    root_names: &[String],
+    options: &CompilerOptions,
+    host: &impl CompilerHost,
+) -> CompileResult {
+    compile_with_cancellation(root_names, options, host, &CancellationToken::new())
+        .expect("a new token is never cancelled")
+}
 
-            let js_path = format!("{}/{}.js", out_dir, base_name);
+// Compile like `compile`, giving up once `cancellation_token` is cancelled. Output files
+// written before then are left as they are.
+pub fn compile_with_cancellation(
+    root_names: &[String],
+    options: &CompilerOptions,
+    host: &impl CompilerHost,
+    cancellation_token: &CancellationToken,
+) -> Result<CompileResult, OperationCanceled> {
+    let source_files = read_source_files(root_names, host);
+    let mut program =
+        create_incremental_program(&source_files, options, host, None, cancellation_token)?;
+    // Listing the files only needs them parsed
+    if options.list_files_only {
+        return Ok(CompileResult {
+            program,
+            emitted_files: Vec::new(),
+            emit_skipped: true,
+        });
+    }
+    if !options.skip_type_checking {
+        type_check(&mut program, cancellation_token)?;
+    }
+    let emit_skipped = options.no_emit_on_error && has_errors(&program.diagnostics);
+    let emitted_files = if options.no_emit || emit_skipped {
+        Vec::new()
+    } else {
+        let emit = performance::measure(Phase::Emit, || {
+            emit_files(&program, options, host, cancellation_token)
+        })?;
+        program.diagnostics.extend(emit.diagnostics);
+        emit.emitted_files
+    };
+    program.diagnostics =
+        sort_and_deduplicate_diagnostics(std::mem::take(&mut program.diagnostics));
+    Ok(CompileResult {
+        program,
+        emitted_files,
+        emit_skipped,
+    })
+}
 
-            // This is where we'd emit transformed JavaScript
-            let js_content = "console.log('Hello from TypeScript!');\n";
-            host.write_file(&js_path, js_content);
+// Create a program, reusing the syntax trees of an old program for the files that have not
+// changed since, and reparsing only the changed parts of the files that have. Besides the
+// root files, the program has the files they import, which come before the files importing
+// them. Parsing and binding stop between files once `cancellation_token` is cancelled.
+pub fn create_incremental_program(
+    source_files: &[SourceFile],
+    compiler_options: &CompilerOptions,
+    host: &impl CompilerHost,
+    old_program: Option<&Program>,
+    cancellation_token: &CancellationToken,
+) -> Result<Program, OperationCanceled> {
+    let current_directory = host.get_current_directory();
+    let type_roots = get_effective_type_roots(
+        compiler_options.type_roots.as_deref(),
+        &current_directory,
+        host,
+    );
+    let mut collector = FileCollector {
+        compiler_options,
+        host,
+        old_program,
+        cancellation_token,
+        current_directory,
+        use_case_sensitive_file_names: host.use_case_sensitive_file_names(),
+        resolution_options: ModuleResolutionOptions {
+            kind: compiler_options.module_resolution_kind(),
+            kind_is_explicit: compiler_options.module_resolution.is_some(),
+            trace_resolution: compiler_options.trace_resolution,
+            type_roots,
+            type_roots_are_explicit: compiler_options.type_roots.is_some(),
+            resolve_json_module: compiler_options.get_resolve_json_module(),
+        },
+        included: Vec::new(),
+        source_files: Vec::new(),
+        syntax_trees: Vec::new(),
+        failed_imports: Vec::new(),
+        casing_conflicts: Vec::new(),
+        resolved_modules: HashMap::new(),
+        reference_diagnostics: Vec::new(),
+        lib_directory: host.get_default_lib_location(),
+        lib_files: Vec::new(),
+        prefetched: HashMap::new(),
+    };
+    // The standard library comes first, as in tsc. Transpiling files needs none of it.
+    if !compiler_options.transpile_only {
+        collector.add_default_lib_files();
+    }
+    collector.parse_in_parallel(source_files.to_vec());
+    for source_file in source_files {
+        collector.add_file(source_file.clone(), FileIncludeReason::RootFile);
+    }
+    if !compiler_options.transpile_only {
+        collector.add_type_packages();
+    }
+    cancellation_token.throw_if_cancellation_requested()?;
 
-            if options.declaration {
-                let dts_path = format!("{}/{}.d.ts", out_dir, base_name);
-                let dts_content = "// Type definitions\nexport {};\n";
-                host.write_file(&dts_path, dts_content);
-            }
+    let mut diagnostics = Vec::new();
+    for (source_file, tree) in collector.source_files.iter().zip(&collector.syntax_trees) {
+        for diagnostic in &tree.as_source_file().parse_diagnostics {
+            diagnostics.push(convert_diagnostic(source_file, diagnostic));
         }
     }
+    diagnostics.extend(collector.report_failed_imports());
+    diagnostics.append(&mut collector.reference_diagnostics);
+    diagnostics.extend(collector.report_casing_conflicts());
+    let bindings = if compiler_options.transpile_only {
+        Bindings::new()
+    } else {
+        performance::measure(Phase::Bind, || {
+            bind_files(
+                &collector.syntax_trees,
+                compiler_options.single_threaded,
+                cancellation_token,
+            )
+        })?
+    };
+    let mut program = Program {
+        source_files: collector.source_files,
+        syntax_trees: collector.syntax_trees,
+        diagnostics,
+        lib_files: collector.lib_files,
+        options: compiler_options.clone(),
+        file_include_reasons: collector
+            .included
+            .into_iter()
+            .map(|included| (included.file_name, included.reasons))
+            .collect(),
+        current_directory: collector.current_directory,
+        use_case_sensitive_file_names: collector.use_case_sensitive_file_names,
+        resolved_modules: collector.resolved_modules,
+        bindings,
+        check_statistics: CheckStatistics::default(),
+        invalid_options: false,
+    };
+    verify_compiler_options(&mut program, compiler_options);
+    Ok(program)
 }
 
-pub fn report_diagnostics(diagnostics: &[Diagnostic], pretty: bool) {
-    if diagnostics.is_empty() {
-        println!("Compilation completed successfully.");
-        return;
+// Parse a file of a program for the target of its options, as the kind of file its extension
+// makes it. Files parsed ahead on other threads are parsed the same as those parsed as they
+// are added.
+fn parse_program_file(source_file: &SourceFile, options: &CompilerOptions) -> Arc<Node> {
+    parse_source_file(
+        &source_file.file_name,
+        &source_file.text,
+        options.target,
+        get_script_kind_from_file_name(&source_file.file_name),
+    )
+}
+
+// Bind each file on its own, on all cores unless `singleThreaded` is set, and merge the
+// bindings in the order of the files
+fn bind_files(
+    trees: &[Arc<Node>],
+    single_threaded: bool,
+    cancellation_token: &CancellationToken,
+) -> Result<Bindings, OperationCanceled> {
+    let file_bindings = map_in_parallel(trees, single_threaded, |tree| {
+        let mut bindings = Bindings::new();
+        // The files left unbound once cancelled are never used
+        if !cancellation_token.is_cancellation_requested() {
+            bindings.bind_source_file(tree);
+        }
+        bindings
+    });
+    cancellation_token.throw_if_cancellation_requested()?;
+    let mut bindings = Bindings::new();
+    for file_bindings in file_bindings {
+        bindings.merge(file_bindings);
     }
+    Ok(bindings)
+}
+
+// Gathers the files of a program, following the imports and references of each file to the
+// files they resolve to. A file is added once, however many times it is reached, which on a
+// case-insensitive file system may be by names that differ in casing.
+struct FileCollector<'a, H: CompilerHost> {
+    compiler_options: &'a CompilerOptions,
+    host: &'a H,
+    old_program: Option<&'a Program>,
+    // Stops adding files, leaving the program incomplete
+    cancellation_token: &'a CancellationToken,
+    current_directory: String,
+    use_case_sensitive_file_names: bool,
+    resolution_options: ModuleResolutionOptions,
+    // The files added so far, including those still being added
+    included: Vec<IncludedFile>,
+    source_files: Vec<SourceFile>,
+    syntax_trees: Vec<Arc<Node>>,
+    failed_imports: Vec<FailedImport>,
+    casing_conflicts: Vec<CasingConflict>,
+    // The file each import resolved to, by the importing file and the module name
+    resolved_modules: HashMap<(String, String), String>,
+    // Problems with the files that options and directives refer to
+    reference_diagnostics: Vec<Diagnostic>,
+    // The folder of the standard library files, without which programs have none
+    lib_directory: Option<String>,
+    lib_files: Vec<String>,
+    // The trees of the files parsed ahead of being added, by file name
+    prefetched: HashMap<String, Arc<Node>>,
+}
+
+// A file of a program, found by its canonical path
+struct IncludedFile {
+    path: PathBuf,
+    absolute_path: PathBuf,
+    file_name: String,
+    reasons: Vec<FileIncludeReason>,
+}
+
+// A file reached by a name that differs only in casing from the name it was added by
+struct CasingConflict {
+    file_name: String,
+    existing_file_name: String,
+    reason: FileIncludeReason,
+    existing_reason: FileIncludeReason,
+}
+
+// An import that resolved to no file of the program
+struct FailedImport {
+    file_name: String,
+    module_name: String,
+    range: TextRange,
+    // The JavaScript file the import resolved to, which is not part of the program without
+    // `allowJs`
+    javascript_file: Option<String>,
+    // Whether the import is a relative ECMAScript import that failed for leaving out the
+    // extension of the file, under node16 and nodenext resolution, and the specifier it needs
+    missing_extension: Option<Option<String>>,
+}
 
-    let mut error_count = 0;
-    let mut warning_count = 0;
+impl<H: CompilerHost> FileCollector<'_, H> {
+    fn add_file(&mut self, source_file: SourceFile, reason: FileIncludeReason) {
+        if self.cancellation_token.is_cancellation_requested() {
+            return;
+        }
+        let absolute_path = absolute_path(&self.current_directory, &source_file.file_name);
+        let path = canonical_path(&absolute_path, self.use_case_sensitive_file_names);
+        if let Some(existing) = self.included.iter_mut().find(|file| file.path == path) {
+            if existing.absolute_path != absolute_path
+                && self.compiler_options.force_consistent_casing_in_file_names
+            {
+                self.casing_conflicts.push(CasingConflict {
+                    file_name: source_file.file_name,
+                    existing_file_name: existing.file_name.clone(),
+                    reason: reason.clone(),
+                    existing_reason: existing.reasons[0].clone(),
+                });
+            }
+            existing.reasons.push(reason);
+            return;
+        }
+        self.included.push(IncludedFile {
+            path,
+            absolute_path: absolute_path.clone(),
+            file_name: source_file.file_name.clone(),
+            reasons: vec![reason],
+        });
+        let tree = self.parse(&source_file);
+        // A file is transpiled without the files it references or imports
+        if self.compiler_options.transpile_only {
+            self.source_files.push(source_file);
+            self.syntax_trees.push(tree);
+            return;
+        }
 
-    for diagnostic in diagnostics {
-        match diagnostic.category {
-            DiagnosticCategory::Error => {
-                error_count += 1;
-                print_diagnostic(diagnostic, pretty);
+        let containing_file = absolute_path.to_string_lossy();
+        let reference = |range, text: &str| FileReference {
+            file_name: source_file.file_name.clone(),
+            text: text.to_string(),
+            range,
+        };
+        for (range, name) in reference_directives(&source_file.text, "path") {
+            let directory = paths::get_directory_path(&containing_file);
+            let referenced = PathBuf::from(paths::combine_paths(&directory, name));
+            match self.find_referenced_file(&referenced) {
+                Some(referenced) => self.add_resolved_file(
+                    &referenced,
+                    FileIncludeReason::ReferenceFile(reference(range, name)),
+                ),
+                None => self.reference_diagnostics.push(convert_diagnostic(
+                    &source_file,
+                    &diagnostics::Diagnostic::new(
+                        Some(&source_file.file_name),
+                        range,
+                        diagnostics::FILE_0_NOT_FOUND_6053,
+                        &[referenced.to_string_lossy().to_string()],
+                    ),
+                )),
+            }
+        }
+        for (range, name) in reference_directives(&source_file.text, "lib") {
+            match lib_file_name(name) {
+                Some(lib_file_name) => self.add_lib_file(
+                    lib_file_name,
+                    FileIncludeReason::LibReferenceDirective(reference(range, name)),
+                ),
+                None => self.reference_diagnostics.push(convert_diagnostic(
+                    &source_file,
+                    &diagnostics::Diagnostic::new(
+                        Some(&source_file.file_name),
+                        range,
+                        diagnostics::CANNOT_FIND_LIB_DEFINITION_FOR_0_2726,
+                        &[name.to_string()],
+                    ),
+                )),
+            }
+        }
+        for (range, name) in reference_directives(&source_file.text, "types") {
+            let resolved = resolve_type_reference_directive(
+                name,
+                Some(&containing_file),
+                &self.resolution_options,
+                self.host,
+            );
+            match resolved {
+                Some(resolved) => self.add_resolved_file(
+                    &self.real_path(&resolved),
+                    FileIncludeReason::TypeReferenceDirective(reference(range, name)),
+                ),
+                None => self.reference_diagnostics.push(convert_diagnostic(
+                    &source_file,
+                    &diagnostics::Diagnostic::new(
+                        Some(&source_file.file_name),
+                        range,
+                        diagnostics::CANNOT_FIND_TYPE_DEFINITION_FILE_FOR_0_2688,
+                        &[name.to_string()],
+                    ),
+                )),
             }
-            DiagnosticCategory::Warning => {
-                warning_count += 1;
-                print_diagnostic(diagnostic, pretty);
+        }
+        // Resolve every import before adding any of the files, so that the new ones can be
+        // parsed together
+        let static_imports = import_specifiers(&tree)
+            .into_iter()
+            .map(|(specifier, is_require)| (Arc::clone(specifier), is_require));
+        let dynamic_imports = dynamic_import_specifiers(&tree)
+            .into_iter()
+            .map(|specifier| (specifier, false));
+        let imports: Vec<_> = static_imports
+            .chain(dynamic_imports)
+            .map(|(specifier, is_require)| {
+                let resolution_mode = self.resolution_mode(&containing_file, is_require);
+                let resolved = performance::measure(Phase::ResolveModule, || {
+                    resolve_module_name(
+                        specifier.text(),
+                        &containing_file,
+                        resolution_mode,
+                        &self.resolution_options,
+                        self.host,
+                    )
+                });
+                (specifier, resolution_mode, resolved)
+            })
+            .collect();
+        let imported_files: Vec<String> = imports
+            .iter()
+            .filter_map(|(_, _, resolved)| resolved.as_ref())
+            .filter(|resolved| !resolved.is_javascript() || self.compiler_options.allow_js)
+            .map(|resolved| self.real_path(resolved))
+            .collect();
+        self.prefetch_files(&imported_files);
+        for (specifier, resolution_mode, resolved) in imports {
+            let range = get_error_range(&tree, &specifier);
+            let failed_import = |javascript_file| FailedImport {
+                file_name: source_file.file_name.clone(),
+                module_name: specifier.text().to_string(),
+                range,
+                javascript_file,
+                missing_extension: None,
+            };
+            match resolved {
+                Some(resolved) if resolved.is_javascript() && !self.compiler_options.allow_js => {
+                    self.failed_imports
+                        .push(failed_import(Some(resolved.resolved_file_name)));
+                }
+                Some(resolved) => {
+                    let resolved_file_name = self.real_path(&resolved);
+                    self.resolved_modules.insert(
+                        (source_file.file_name.clone(), specifier.text().to_string()),
+                        relative_path(
+                            Path::new(&self.current_directory),
+                            Path::new(&resolved_file_name),
+                            !self.use_case_sensitive_file_names,
+                        ),
+                    );
+                    self.add_resolved_file(
+                        &resolved_file_name,
+                        FileIncludeReason::Import(reference(range, specifier.text())),
+                    );
+                }
+                // The checker reports augmentations of modules that are not found
+                None if is_module_augmentation_name(&specifier) => {}
+                None => {
+                    let missing_extension = (resolution_mode == ResolutionMode::Esm)
+                        .then(|| self.suggest_extension(specifier.text(), &containing_file))
+                        .flatten();
+                    self.failed_imports.push(FailedImport {
+                        missing_extension,
+                        ..failed_import(None)
+                    });
+                }
             }
-            _ => {}
         }
+
+        self.source_files.push(source_file);
+        self.syntax_trees.push(tree);
     }
 
-    println!(
-        "Found {} error(s), {} warning(s)",
-        error_count, warning_count
-    );
-}
+    // Add a file that an import or directive resolved to, named relative to the working
+    // directory like the root files
+    fn add_resolved_file(&mut self, resolved_file_name: &str, reason: FileIncludeReason) {
+        let file_name = relative_path(
+            Path::new(&self.current_directory),
+            Path::new(resolved_file_name),
+            !self.use_case_sensitive_file_names,
+        );
+        let text = match self.prefetched.get(&file_name) {
+            Some(tree) => Some(tree.as_source_file().text.clone()),
+            None => self.host.read_file(resolved_file_name),
+        };
+        if let Some(text) = text {
+            self.add_file(
+                SourceFile {
+                    line_map: compute_line_map(&text),
+                    file_name,
+                    text,
+                },
+                reason,
+            );
+        }
+    }
 
-pub fn print_diagnostic(diagnostic: &Diagnostic, pretty: bool) {
-    let color_start = if pretty { "\x1b[31m" } else { "" };
-    let color_end = if pretty { "\x1b[0m" } else { "" };
+    // Get the path of a resolved file, with symbolic links followed for the files of installed
+    // packages, as in tsc, so that a package linked into several `node_modules` folders is
+    // added once
+    fn real_path(&self, resolved: &ResolvedModule) -> String {
+        if resolved.is_external_library_import {
+            self.host.realpath(&resolved.resolved_file_name)
+        } else {
+            resolved.resolved_file_name.clone()
+        }
+    }
 
-    if let Some(file_name) = &diagnostic.file_name {
-        println!(
-            "{}{}({}:{}) - error TS{}: {}{}",
-            color_start,
-            file_name,
-            diagnostic.line,
-            diagnostic.character,
-            diagnostic.code,
-            diagnostic.message,
-            color_end
+    // Find the file a `/// <reference path="..." />` directive names, which may leave out the
+    // extension of a TypeScript file
+    fn find_referenced_file(&self, path: &Path) -> Option<String> {
+        let path = path.to_string_lossy();
+        if Path::new(path.as_ref()).extension().is_some() && self.host.file_exists(&path) {
+            return Some(path.to_string());
+        }
+        [".ts", ".tsx", ".d.ts"]
+            .iter()
+            .map(|extension| format!("{path}{extension}"))
+            .find(|candidate| self.host.file_exists(candidate))
+    }
+
+    // Add the standard library files of the program: those the `lib` option lists, or with
+    // none listed, the default library of the target
+    fn add_default_lib_files(&mut self) {
+        if self.compiler_options.no_lib {
+            return;
+        }
+        let lib_files: Vec<(&str, Option<String>)> = if self.compiler_options.lib.is_empty() {
+            vec![(default_lib_file_name(self.compiler_options.target), None)]
+        } else {
+            // Unknown names are reported with the other problems of the options
+            self.compiler_options
+                .lib
+                .iter()
+                .filter_map(|name| Some((lib_file_name(name)?, Some(name.clone()))))
+                .collect()
+        };
+        for (lib_file_name, lib) in lib_files {
+            self.add_lib_file(lib_file_name, FileIncludeReason::LibFile { lib });
+        }
+    }
+
+    fn add_lib_file(&mut self, lib_file_name: &str, reason: FileIncludeReason) {
+        let Some(lib_directory) = &self.lib_directory else {
+            return;
+        };
+        let path = Path::new(lib_directory).join(lib_file_name);
+        let Some(text) = self.host.read_file(&path.to_string_lossy()) else {
+            self.reference_diagnostics.push(option_diagnostic(
+                diagnostics::FILE_0_NOT_FOUND_6053,
+                &[path.to_string_lossy().to_string()],
+            ));
+            return;
+        };
+        let file_name = relative_path(
+            Path::new(&self.current_directory),
+            &path,
+            !self.use_case_sensitive_file_names,
         );
-    } else {
-        println!(
-            "{}error TS{}: {}{}",
-            color_start, diagnostic.code, diagnostic.message, color_end
+        if !self.lib_files.contains(&file_name) {
+            self.lib_files.push(file_name.clone());
+        }
+        self.add_file(
+            SourceFile {
+                line_map: compute_line_map(&text),
+                file_name,
+                text,
+            },
+            reason,
         );
     }
+
+    // Add the declarations of the type packages of the program: those the `types` option
+    // lists, or with none listed, every package in the type roots
+    fn add_type_packages(&mut self) {
+        let names = match &self.compiler_options.types {
+            Some(types) => types.clone(),
+            None => {
+                get_automatic_type_directive_names(&self.resolution_options.type_roots, self.host)
+            }
+        };
+        // As in tsc, the packages are resolved as if referenced by a file in the working
+        // directory
+        let containing_file = Path::new(&self.current_directory).join("__inferred type names__.ts");
+        for name in names {
+            let resolved = resolve_type_reference_directive(
+                &name,
+                Some(&containing_file.to_string_lossy()),
+                &self.resolution_options,
+                self.host,
+            );
+            match resolved {
+                Some(resolved) => self.add_resolved_file(
+                    &self.real_path(&resolved),
+                    FileIncludeReason::AutomaticTypeDirectiveFile {
+                        type_reference: name,
+                    },
+                ),
+                None => self.reference_diagnostics.push(option_diagnostic(
+                    diagnostics::CANNOT_FIND_TYPE_DEFINITION_FILE_FOR_0_2688,
+                    &[name],
+                )),
+            }
+        }
+    }
+
+    // Get whether an import in a file loads an ECMAScript module or a CommonJS one. Only the
+    // resolution kinds that use the `exports` maps of packages tell them apart.
+    fn resolution_mode(&self, containing_file: &str, is_require: bool) -> ResolutionMode {
+        match self.resolution_options.kind {
+            _ if is_require => ResolutionMode::CommonJs,
+            ModuleResolutionKind::Node16 | ModuleResolutionKind::NodeNext => {
+                get_implied_node_format(containing_file, self.host)
+            }
+            ModuleResolutionKind::Bundler => ResolutionMode::Esm,
+            ModuleResolutionKind::Classic | ModuleResolutionKind::Node10 => {
+                ResolutionMode::CommonJs
+            }
+        }
+    }
+
+    // Check whether a relative ECMAScript import failed only for leaving out the extension of
+    // the file, which node16 and nodenext require, by resolving it as a `require`. Suggest the
+    // specifier naming the JavaScript file it resolves to, if it resolves.
+    fn suggest_extension(
+        &self,
+        module_name: &str,
+        containing_file: &str,
+    ) -> Option<Option<String>> {
+        let kind = self.resolution_options.kind;
+        if !matches!(
+            kind,
+            ModuleResolutionKind::Node16 | ModuleResolutionKind::NodeNext
+        ) || !module_resolution::is_external_module_name_relative(module_name)
+            || Path::new(module_name).extension().is_some()
+        {
+            return None;
+        }
+        let options = ModuleResolutionOptions {
+            kind,
+            kind_is_explicit: self.resolution_options.kind_is_explicit,
+            trace_resolution: false,
+            type_roots: Vec::new(),
+            type_roots_are_explicit: false,
+            resolve_json_module: false,
+        };
+        let Some(resolved) = resolve_module_name(
+            module_name,
+            containing_file,
+            ResolutionMode::CommonJs,
+            &options,
+            self.host,
+        ) else {
+            return Some(None);
+        };
+        let file_name = &resolved.resolved_file_name;
+        let javascript_extension = match resolved.extension {
+            ".mts" | ".d.mts" | ".mjs" => ".mjs",
+            ".cts" | ".d.cts" | ".cjs" => ".cjs",
+            _ => ".js",
+        };
+        let stem = &file_name[..file_name.len() - resolved.extension.len()];
+        let directory = paths::get_directory_path(containing_file);
+        let candidate = paths::combine_paths(&directory, module_name);
+        let suggestion = if stem == candidate {
+            format!("{module_name}{javascript_extension}")
+        } else {
+            format!(
+                "{}/index{javascript_extension}",
+                module_name.trim_end_matches('/')
+            )
+        };
+        Some(Some(suggestion))
+    }
+
+    // Read the files that imports resolved to and parse those not added yet together, ahead
+    // of adding them one by one
+    fn prefetch_files(&mut self, resolved_file_names: &[String]) {
+        let mut source_files: Vec<SourceFile> = Vec::new();
+        for resolved_file_name in resolved_file_names {
+            let file_name = relative_path(
+                Path::new(&self.current_directory),
+                Path::new(resolved_file_name),
+                !self.use_case_sensitive_file_names,
+            );
+            let path = canonical_path(
+                &absolute_path(&self.current_directory, &file_name),
+                self.use_case_sensitive_file_names,
+            );
+            if self.included.iter().any(|file| file.path == path)
+                || source_files.iter().any(|file| file.file_name == file_name)
+            {
+                continue;
+            }
+            if let Some(text) = self.host.read_file(resolved_file_name) {
+                source_files.push(SourceFile {
+                    line_map: Vec::new(),
+                    file_name,
+                    text,
+                });
+            }
+        }
+        self.parse_in_parallel(source_files);
+    }
+
+    // Parse files on all cores ahead of adding them, except those the old program has a tree
+    // for, which are reused or updated instead
+    fn parse_in_parallel(&mut self, source_files: Vec<SourceFile>) {
+        let source_files: Vec<SourceFile> = source_files
+            .into_iter()
+            .filter(|source_file| {
+                !self.prefetched.contains_key(&source_file.file_name)
+                    && self.old_program.is_none_or(|old_program| {
+                        old_program
+                            .source_files
+                            .iter()
+                            .all(|old_file| old_file.file_name != source_file.file_name)
+                    })
+            })
+            .collect();
+        // A single file is parsed when it is added
+        if source_files.len() < 2 || self.cancellation_token.is_cancellation_requested() {
+            return;
+        }
+        let cancellation_token = self.cancellation_token;
+        let trees = performance::measure(Phase::Parse, || {
+            map_in_parallel(
+                &source_files,
+                self.compiler_options.single_threaded,
+                |source_file| {
+                    (!cancellation_token.is_cancellation_requested())
+                        .then(|| parse_program_file(source_file, self.compiler_options))
+                },
+            )
+        });
+        for (source_file, tree) in source_files.into_iter().zip(trees) {
+            if let Some(tree) = tree {
+                self.prefetched.insert(source_file.file_name, tree);
+            }
+        }
+    }
+
+    // Parse a file, or take its tree from the old program when it is unchanged
+    fn parse(&mut self, source_file: &SourceFile) -> Arc<Node> {
+        if let Some(tree) = self.prefetched.remove(&source_file.file_name)
+            && tree.as_source_file().text == source_file.text
+        {
+            return tree;
+        }
+        let old = self.old_program.and_then(|old_program| {
+            old_program
+                .source_files
+                .iter()
+                .zip(&old_program.syntax_trees)
+                .find(|(old_file, _)| old_file.file_name == source_file.file_name)
+        });
+        performance::measure(Phase::Parse, || match old {
+            Some((old_file, old_tree)) if old_file.text == source_file.text => Arc::clone(old_tree),
+            Some((old_file, old_tree)) => old_tree.as_source_file().update(
+                &source_file.text,
+                compute_text_change_range(&old_file.text, &source_file.text),
+            ),
+            None => parse_program_file(source_file, self.compiler_options),
+        })
+    }
+
+    // Report the imports that did not resolve to a file of the program, unless a file of the
+    // program declares the module with `declare module "name"`
+    fn report_failed_imports(&self) -> Vec<Diagnostic> {
+        let ambient_modules: Vec<&str> = self
+            .syntax_trees
+            .iter()
+            .filter(|tree| !is_external_module(tree))
+            .flat_map(|tree| &tree.as_source_file().statements)
+            .filter(|statement| statement.kind == SyntaxKind::ModuleDeclaration)
+            .map(|statement| &statement.as_module_declaration().name)
+            .filter(|name| name.kind == SyntaxKind::StringLiteral)
+            .map(|name| name.text())
+            .collect();
+        let is_ambient_module = |module_name: &str| {
+            ambient_modules
+                .iter()
+                .any(|pattern| match pattern.split_once('*') {
+                    Some((prefix, suffix)) => {
+                        module_name.len() >= prefix.len() + suffix.len()
+                            && module_name.starts_with(prefix)
+                            && module_name.ends_with(suffix)
+                    }
+                    None => *pattern == module_name,
+                })
+        };
+
+        let mut diagnostics = Vec::new();
+        for failed_import in &self.failed_imports {
+            let module_name = &failed_import.module_name;
+            if is_ambient_module(module_name) {
+                continue;
+            }
+            let (message, args) = match (
+                &failed_import.missing_extension,
+                &failed_import.javascript_file,
+            ) {
+                (Some(Some(suggestion)), _) => (
+                    diagnostics::RELATIVE_IMPORT_PATHS_NEED_EXPLICIT_FILE_EXTENSIONS_IN_ECMASCRIPT_IMPORTS_WHEN_MODULE_RESOLUTION_IS_NODE16_OR_NODENEXT_DID_YOU_MEAN_0_2835,
+                    vec![suggestion.clone()],
+                ),
+                (Some(None), _) => (
+                    diagnostics::RELATIVE_IMPORT_PATHS_NEED_EXPLICIT_FILE_EXTENSIONS_IN_ECMASCRIPT_IMPORTS_WHEN_MODULE_RESOLUTION_IS_NODE16_OR_NODENEXT_CONSIDER_ADDING_AN_EXTENSION_TO_THE_IMPORT_PATH_2834,
+                    Vec::new(),
+                ),
+                // Without declarations, the module is typed as `any`, which is only an error
+                // with `noImplicitAny`
+                (None, Some(_)) if !self.compiler_options.no_implicit_any => continue,
+                (None, Some(javascript_file)) => (
+                    diagnostics::COULD_NOT_FIND_A_DECLARATION_FILE_FOR_MODULE_0_1_IMPLICITLY_HAS_AN_ANY_TYPE_7016,
+                    vec![module_name.clone(), javascript_file.clone()],
+                ),
+                // tsc suggests the option for JSON files, except under classic resolution,
+                // which never finds them
+                (None, None)
+                    if module_name.ends_with(".json")
+                        && !self.compiler_options.get_resolve_json_module()
+                        && self.compiler_options.module_resolution_kind()
+                            != ModuleResolutionKind::Classic =>
+                {
+                    (
+                        diagnostics::CANNOT_FIND_MODULE_0_CONSIDER_USING_RESOLVE_JSON_MODULE_TO_IMPORT_MODULE_WITH_JSON_EXTENSION_2732,
+                        vec![module_name.clone()],
+                    )
+                }
+                (None, None) => (
+                    diagnostics::CANNOT_FIND_MODULE_0_OR_ITS_CORRESPONDING_TYPE_DECLARATIONS_2307,
+                    vec![module_name.clone()],
+                ),
+            };
+            diagnostics.push(self.diagnostic_at(
+                &failed_import.file_name,
+                failed_import.range,
+                message,
+                &args,
+            ));
+        }
+        diagnostics
+    }
+
+    // Report the files reached by names that differ only in casing from the names they were
+    // added by, where the later name is written, or else where the earlier one is
+    fn report_casing_conflicts(&self) -> Vec<Diagnostic> {
+        self.casing_conflicts
+            .iter()
+            .map(|conflict| {
+                let file_names = [
+                    conflict.file_name.clone(),
+                    conflict.existing_file_name.clone(),
+                ];
+                match (
+                    conflict.reason.reference(),
+                    conflict.existing_reason.reference(),
+                ) {
+                    (Some(reference), _) => self.diagnostic_at(
+                        &reference.file_name,
+                        reference.range,
+                        diagnostics::FILE_NAME_0_DIFFERS_FROM_ALREADY_INCLUDED_FILE_NAME_1_ONLY_IN_CASING_1149,
+                        &file_names,
+                    ),
+                    (None, Some(reference)) => self.diagnostic_at(
+                        &reference.file_name,
+                        reference.range,
+                        diagnostics::ALREADY_INCLUDED_FILE_NAME_0_DIFFERS_FROM_FILE_NAME_1_ONLY_IN_CASING_1261,
+                        &[file_names[1].clone(), file_names[0].clone()],
+                    ),
+                    (None, None) => option_diagnostic(
+                        diagnostics::FILE_NAME_0_DIFFERS_FROM_ALREADY_INCLUDED_FILE_NAME_1_ONLY_IN_CASING_1149,
+                        &file_names,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    // Create a diagnostic at a range of a file of the program
+    fn diagnostic_at(
+        &self,
+        file_name: &str,
+        range: TextRange,
+        message: &diagnostics::Message,
+        args: &[String],
+    ) -> Diagnostic {
+        let source_file = self
+            .source_files
+            .iter()
+            .find(|source_file| source_file.file_name == file_name)
+            .expect("references are in files of the program");
+        convert_diagnostic(
+            source_file,
+            &diagnostics::Diagnostic::new(Some(file_name), range, message, args),
+        )
+    }
+}
+
+// Report options that cannot be used together, or with the files of the program
+fn verify_compiler_options(program: &mut Program, options: &CompilerOptions) {
+    if options.lib.iter().any(|name| lib_file_name(name).is_none()) {
+        let names: Vec<String> = lib_names().map(|name| format!("'{name}'")).collect();
+        program.invalid_options = true;
+        program.diagnostics.push(option_diagnostic(
+            diagnostics::ARGUMENT_FOR_0_OPTION_MUST_BE_COLON_1_6046,
+            &["--lib".to_string(), names.join(", ")],
+        ));
+    }
+    // The output folder mirrors the root folder, so every emitted file has to be under it
+    if let Some(root_dir) = &options.root_dir
+        && options.out_file.is_none()
+    {
+        let canonical = |path: &Path| canonical_path(path, program.use_case_sensitive_file_names);
+        let root_dir = absolute_path(&program.current_directory, root_dir);
+        let root_key = canonical(&root_dir);
+        for path in emitted_source_paths(program, &program.current_directory) {
+            if !canonical(&path).starts_with(&root_key) {
+                program.diagnostics.push(option_diagnostic(
+                    diagnostics::FILE_0_IS_NOT_UNDER_ROOT_DIR_1_ROOT_DIR_IS_EXPECTED_TO_CONTAIN_ALL_SOURCE_FILES_6059,
+                    &[
+                        path.to_string_lossy().to_string(),
+                        root_dir.to_string_lossy().to_string(),
+                    ],
+                ));
+            }
+        }
+    }
+    if !options.no_emit {
+        verify_output_paths(program, options);
+    }
+    // Files are read and written as UTF-8 whatever the charset says
+    if let Some(charset) = &options.charset
+        && !matches!(charset.to_ascii_lowercase().as_str(), "utf8" | "utf-8")
+    {
+        program.invalid_options = true;
+        program.diagnostics.push(option_diagnostic(
+            diagnostics::ARGUMENT_FOR_0_OPTION_MUST_BE_COLON_1_6046,
+            &["--charset".to_string(), "'utf8'".to_string()],
+        ));
+    }
+    let names =
+        |names: &[&str]| -> Vec<String> { names.iter().map(|name| name.to_string()).collect() };
+    let mut conflicts = Vec::new();
+    // Options that only make sense with declaration emit
+    let declares = options.declaration || options.composite;
+    let requires_declaration = [
+        ("emitDeclarationOnly", options.emit_declaration_only),
+        ("declarationMap", options.declaration_map),
+        ("declarationDir", options.declaration_dir.is_some()),
+        ("isolatedDeclarations", options.isolated_declarations),
+    ];
+    for (name, is_set) in requires_declaration {
+        if is_set && !declares {
+            conflicts.push((
+                diagnostics::OPTION_0_CANNOT_BE_SPECIFIED_WITHOUT_SPECIFYING_OPTION_1_OR_OPTION_2_5069,
+                names(&[name, "declaration", "composite"]),
+            ));
+        }
+    }
+    let exclusive = [
+        (
+            "noEmit",
+            options.no_emit,
+            "emitDeclarationOnly",
+            options.emit_declaration_only,
+        ),
+        (
+            "sourceMap",
+            options.source_map,
+            "inlineSourceMap",
+            options.inline_source_map,
+        ),
+        (
+            "declarationDir",
+            options.declaration_dir.is_some(),
+            "outFile",
+            options.out_file.is_some(),
+        ),
+    ];
+    for (name, is_set, other_name, other_is_set) in exclusive {
+        if is_set && other_is_set {
+            conflicts.push((
+                diagnostics::OPTION_0_CANNOT_BE_SPECIFIED_WITH_OPTION_1_5053,
+                names(&[name, other_name]),
+            ));
+        }
+    }
+    let maps_sources = options.source_map || options.inline_source_map;
+    for (name, is_set) in [
+        ("inlineSources", options.inline_sources),
+        ("sourceRoot", options.source_root.is_some()),
+    ] {
+        if is_set && !maps_sources {
+            conflicts.push((
+                diagnostics::OPTION_0_CAN_ONLY_BE_USED_WHEN_EITHER_OPTION_INLINE_SOURCE_MAP_OR_OPTION_SOURCE_MAP_IS_PROVIDED_5051,
+                names(&[name]),
+            ));
+        }
+    }
+    if options.map_root.is_some() && !(options.source_map || options.declaration_map) {
+        conflicts.push((
+            diagnostics::OPTION_0_CANNOT_BE_SPECIFIED_WITHOUT_SPECIFYING_OPTION_1_OR_OPTION_2_5069,
+            names(&["mapRoot", "sourceMap", "declarationMap"]),
+        ));
+    }
+    if options.ts_build_info_file.is_some() && !options.incremental {
+        conflicts.push((
+            diagnostics::OPTION_0_CANNOT_BE_SPECIFIED_WITHOUT_SPECIFYING_OPTION_1_OR_OPTION_2_5069,
+            names(&["tsBuildInfoFile", "incremental", "composite"]),
+        ));
+    }
+    if options.emit_decorator_metadata && !options.experimental_decorators {
+        conflicts.push((
+            diagnostics::OPTION_0_CANNOT_BE_SPECIFIED_WITHOUT_SPECIFYING_OPTION_1_5052,
+            names(&["emitDecoratorMetadata", "experimentalDecorators"]),
+        ));
+    }
+    // Imports of `.ts` files would name files that are not emitted
+    if options.allow_importing_ts_extensions && !(options.no_emit || options.emit_declaration_only)
+    {
+        conflicts.push((
+            diagnostics::OPTION_ALLOW_IMPORTING_TS_EXTENSIONS_CAN_ONLY_BE_USED_WHEN_EITHER_NO_EMIT_OR_EMIT_DECLARATION_ONLY_IS_SET_5096,
+            Vec::new(),
+        ));
+    }
+    // `verbatimModuleSyntax` decides what happens to every import on its own
+    if options.verbatim_module_syntax && options.imports_not_used_as_values.is_some() {
+        conflicts.push((
+            diagnostics::OPTION_0_IS_REDUNDANT_AND_CANNOT_BE_SPECIFIED_WITH_OPTION_1_5104,
+            names(&["importsNotUsedAsValues", "verbatimModuleSyntax"]),
+        ));
+    }
+    if options.resolve_json_module == Some(true) {
+        if options.module_resolution_kind() == ModuleResolutionKind::Classic {
+            conflicts.push((
+                diagnostics::OPTION_RESOLVE_JSON_MODULE_CANNOT_BE_SPECIFIED_WHEN_MODULE_RESOLUTION_IS_SET_TO_CLASSIC_5070,
+                Vec::new(),
+            ));
+        } else if matches!(
+            options.emit_module_kind(),
+            ModuleKind::None | ModuleKind::System | ModuleKind::UMD
+        ) {
+            conflicts.push((
+                diagnostics::OPTION_RESOLVE_JSON_MODULE_CANNOT_BE_SPECIFIED_WHEN_MODULE_IS_SET_TO_NONE_SYSTEM_OR_UMD_5071,
+                Vec::new(),
+            ));
+        }
+    }
+    program.invalid_options |= !conflicts.is_empty();
+    program.diagnostics.extend(
+        conflicts
+            .into_iter()
+            .map(|(message, args)| option_diagnostic(message, &args)),
+    );
+
+    if options.out_file.is_some() && !supports_out_file(options.module) {
+        let diagnostic = match options.module {
+            // With no module kind configured, scripts can still be bundled
+            None | Some(ModuleKind::None) => first_module_file(program).map(|(source_file, tree, indicator)| {
+                convert_diagnostic(
+                    source_file,
+                    &diagnostics::Diagnostic::new(
+                        Some(&source_file.file_name),
+                        get_error_range(tree, indicator),
+                        diagnostics::CANNOT_COMPILE_MODULES_USING_OPTION_0_UNLESS_THE_MODULE_FLAG_IS_AMD_OR_SYSTEM_6131,
+                        &["outFile".to_string()],
+                    ),
+                )
+            }),
+            Some(_) => {
+                program.invalid_options = true;
+                Some(option_diagnostic(
+                    diagnostics::ONLY_AMD_AND_SYSTEM_MODULES_ARE_SUPPORTED_ALONGSIDE_0_6082,
+                    &["outFile".to_string()],
+                ))
+            }
+        };
+        program.diagnostics.extend(diagnostic);
+    }
+}
+
+// Create a diagnostic about the compiler options, which is not in any file
+pub fn option_diagnostic(message: &diagnostics::Message, args: &[String]) -> Diagnostic {
+    Diagnostic {
+        file_name: None,
+        line: 0,
+        character: 0,
+        end_line: 0,
+        end_character: 0,
+        message: diagnostics::format_message(message, args),
+        code: message.code() as u32,
+        category: convert_category(message.category()),
+        related_information: Vec::new(),
+    }
+}
+
+// Check every file of a program, adding their diagnostics to those of the program, unless
+// `cancellation_token` is cancelled before all of them are checked
+pub fn type_check(
+    program: &mut Program,
+    cancellation_token: &CancellationToken,
+) -> Result<(), OperationCanceled> {
+    let (diagnostics, statistics) = performance::measure(Phase::Check, || {
+        program.check_files(None, cancellation_token)
+    })?;
+    program.check_statistics = statistics;
+    program.diagnostics.extend(diagnostics);
+    program.diagnostics =
+        sort_and_deduplicate_diagnostics(std::mem::take(&mut program.diagnostics));
+    if program.options.print_types {
+        print_types(program);
+    }
+    Ok(())
+}
+
+// Print the quick info of every named declaration, as `file(line,character): const name: type`
+fn print_types(program: &Program) {
+    let mut service = LanguageService::new(program);
+    for (source_file, tree) in program.source_files.iter().zip(&program.syntax_trees) {
+        let mut names = Vec::new();
+        for_each_descendant(tree, &mut |node| {
+            let is_declaration = matches!(
+                node.kind,
+                SyntaxKind::VariableDeclaration
+                    | SyntaxKind::Parameter
+                    | SyntaxKind::BindingElement
+                    | SyntaxKind::PropertyDeclaration
+                    | SyntaxKind::PropertySignature
+                    | SyntaxKind::PropertyAssignment
+                    | SyntaxKind::FunctionDeclaration
+                    | SyntaxKind::MethodDeclaration
+                    | SyntaxKind::MethodSignature
+                    | SyntaxKind::GetAccessor
+                    | SyntaxKind::SetAccessor
+                    | SyntaxKind::ClassDeclaration
+                    | SyntaxKind::EnumDeclaration
+                    | SyntaxKind::EnumMember
+            );
+            if is_declaration
+                && let Some(name) = node.name()
+                && name.kind == SyntaxKind::Identifier
+            {
+                names.push(Arc::clone(name));
+            }
+            false
+        });
+        for name in names {
+            let pos = get_error_range(tree, &name).pos();
+            let Some(quick_info) = service.get_quick_info(&source_file.file_name, pos) else {
+                continue;
+            };
+            let (line, character) = line_and_character(source_file, pos);
+            println!(
+                "{}({},{}): {}",
+                source_file.file_name,
+                line,
+                character,
+                quick_info.display_string()
+            );
+        }
+    }
+}
+
+// Convert a position to a 1-based line and character in its source file
+fn line_and_character(source_file: &SourceFile, pos: usize) -> (usize, usize) {
+    let position = position_to_line_and_character(&source_file.line_map, pos);
+    (position.line + 1, position.character + 1)
+}
+
+// Convert a compiler diagnostic to a 1-based line and character in its source file
+pub(crate) fn convert_diagnostic(
+    source_file: &SourceFile,
+    diagnostic: &diagnostics::Diagnostic,
+) -> Diagnostic {
+    convert_diagnostic_in(diagnostic, &|file_name| {
+        (file_name == source_file.file_name).then_some(source_file)
+    })
+}
+
+// Convert a compiler diagnostic and its related information, which may be in other files,
+// finding the source file of each by its name
+fn convert_diagnostic_in<'a>(
+    diagnostic: &diagnostics::Diagnostic,
+    find_source_file: &dyn Fn(&str) -> Option<&'a SourceFile>,
+) -> Diagnostic {
+    let source_file = diagnostic.file_name.as_deref().and_then(find_source_file);
+    let ((line, character), (end_line, end_character)) = match source_file {
+        Some(source_file) => (
+            line_and_character(source_file, diagnostic.pos()),
+            line_and_character(source_file, diagnostic.pos() + diagnostic.len()),
+        ),
+        None => ((0, 0), (0, 0)),
+    };
+    Diagnostic {
+        file_name: diagnostic.file_name.clone(),
+        line,
+        character,
+        end_line,
+        end_character,
+        message: diagnostic.message_text.clone(),
+        code: diagnostic.code as u32,
+        category: convert_category(diagnostic.category),
+        related_information: diagnostic
+            .related_information
+            .iter()
+            .map(|related| convert_diagnostic_in(related, find_source_file))
+            .collect(),
+    }
+}
+
+fn convert_category(category: Category) -> DiagnosticCategory {
+    match category {
+        Category::Error => DiagnosticCategory::Error,
+        Category::Warning => DiagnosticCategory::Warning,
+        Category::Suggestion => DiagnosticCategory::Suggestion,
+        Category::Message => DiagnosticCategory::Message,
+    }
+}
+
+// The outcome of emitting the files of a program
+#[derive(Default)]
+pub struct EmitResult {
+    // The names of the output files written, in the order they were written
+    pub emitted_files: Vec<String>,
+    // The output files that could not be written, and why
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+// Emit every file of a program, returning the names of the files written and the problems
+// writing the others
+pub fn emit_files(
+    program: &Program,
+    options: &CompilerOptions,
+    host: &impl CompilerHost,
+    cancellation_token: &CancellationToken,
+) -> Result<EmitResult, OperationCanceled> {
+    emit_changed_files(program, options, host, cancellation_token, |_| true)
+}
+
+// Emit the files of a program for which `changed` holds, given the index of each, like
+// `emit_files`. A bundle depends on every file, so it is always emitted whole. Emitting stops
+// between files once `cancellation_token` is cancelled.
+pub fn emit_changed_files(
+    program: &Program,
+    options: &CompilerOptions,
+    host: &impl CompilerHost,
+    cancellation_token: &CancellationToken,
+    changed: impl Fn(usize) -> bool,
+) -> Result<EmitResult, OperationCanceled> {
+    let mut output = EmitResult::default();
+    cancellation_token.throw_if_cancellation_requested()?;
+    if let Some(out_dir) = &options.out_dir {
+        println!("Emitting files to: {}", out_dir);
+    }
+    if let Some(out_file) = &options.out_file {
+        emit_bundle(program, options, host, out_file, &mut output);
+        return Ok(output);
+    }
+
+    let current_directory = host.get_current_directory();
+    let root_dir = output_root_directory(program, options, &current_directory);
+
+    let mut enum_checker = enum_checker(program, options);
+    for (index, (source_file, tree)) in program
+        .source_files
+        .iter()
+        .zip(&program.syntax_trees)
+        .enumerate()
+    {
+        if tree.as_source_file().is_declaration_file
+            || is_external_library_file(&source_file.file_name)
+            || !changed(index)
+        {
+            continue;
+        }
+        cancellation_token.throw_if_cancellation_requested()?;
+        let (js_path, dts_path) = source_output_paths(
+            options,
+            &current_directory,
+            &root_dir,
+            source_file,
+            tree,
+            !program.use_case_sensitive_file_names,
+        );
+        // A JSON file is copied as it is, and has no declarations
+        if tree.flags.contains(NodeFlags::JSON_FILE) {
+            if js_path != source_file.file_name {
+                write_output(host, &js_path, &source_file.text, &mut output);
+            }
+            continue;
+        }
+        // Without an output folder, a JavaScript input would be overwritten by its own output
+        if js_path != source_file.file_name {
+            let mut transform_options = transform_options(options);
+            if let Some((checker, inline_accesses)) = &mut enum_checker {
+                transform_options.constant_values =
+                    constant_values(checker, tree, *inline_accesses);
+            }
+            let tree = transform_file(tree, &transform_options);
+            write_javascript(host, &js_path, &[(source_file, tree)], options, &mut output);
+        }
+
+        if let Some(dts_path) = dts_path {
+            let new_line = options.new_line.as_str();
+            let dts_content = format!("// Type definitions{new_line}export {{}};{new_line}");
+            write_output(
+                host,
+                &dts_path,
+                &output_text(options, dts_content),
+                &mut output,
+            );
+        }
+    }
+    Ok(output)
+}
+
+fn printer_options(options: &CompilerOptions) -> PrinterOptions {
+    PrinterOptions {
+        remove_comments: options.remove_comments,
+        new_line: options.new_line,
+        ..PrinterOptions::default()
+    }
+}
+
+fn transform_options(options: &CompilerOptions) -> TransformOptions {
+    TransformOptions {
+        target: options.target,
+        module: options.emit_module_kind(),
+        es_module_interop: options.es_module_interop,
+        jsx: options.jsx,
+        verbatim_module_syntax: options.verbatim_module_syntax,
+        preserve_unused_imports: matches!(
+            options.imports_not_used_as_values,
+            Some(ImportsNotUsedAsValues::Preserve | ImportsNotUsedAsValues::Error)
+        ),
+        downlevel_iteration: options.downlevel_iteration,
+        // A file compiled on its own cannot inline the const enums of other files, which
+        // therefore have to exist at runtime
+        preserve_const_enums: options.preserve_const_enums
+            || options.get_isolated_modules()
+            || options.transpile_only,
+        import_helpers: options.import_helpers,
+        no_emit_helpers: options.no_emit_helpers,
+        ..TransformOptions::default()
+    }
+}
+
+// The checker that computes the values of enum members for emit, with whether the program
+// declares a const enum, without which no access needs checking for one to inline. Nothing is
+// checked when transpiling, so enums only get the values their syntax spells out.
+fn enum_checker(program: &Program, options: &CompilerOptions) -> Option<(Checker, bool)> {
+    if options.transpile_only {
+        return None;
+    }
+    let declares_const_enums = program.syntax_trees.iter().any(|tree| {
+        for_each_descendant(tree, &mut |node| {
+            node.kind == SyntaxKind::EnumDeclaration && node.has_modifier(SyntaxKind::ConstKeyword)
+        })
+    });
+    Some((program.get_type_checker(), declares_const_enums))
+}
+
+// Compute the values of the enum members of a file, and with `inline_accesses`, of the
+// accesses to const enum members that are replaced by them
+fn constant_values(
+    checker: &mut Checker,
+    tree: &Arc<Node>,
+    inline_accesses: bool,
+) -> HashMap<NodeId, LiteralValue> {
+    let mut values = HashMap::new();
+    for_each_descendant(tree, &mut |node| {
+        let is_access = matches!(
+            node.kind,
+            SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression
+        );
+        if (node.kind == SyntaxKind::EnumMember || (is_access && inline_accesses))
+            && let Some(value) = checker.get_constant_value(node)
+        {
+            values.insert(node.node_id(), value);
+        }
+        false
+    });
+    values
+}
+
+// Emit every file of the program into the one file `out_file`, each after the files it
+// references and imports. Modules are named after their paths from the common source
+// directory, and refer to each other by those names.
+fn emit_bundle(
+    program: &Program,
+    options: &CompilerOptions,
+    host: &impl CompilerHost,
+    out_file: &str,
+    output: &mut EmitResult,
+) {
+    // The module kind cannot bundle modules, as `verify_compiler_options` reported
+    if !supports_out_file(options.module) && first_module_file(program).is_some() {
+        return;
+    }
+    let current_directory = host.get_current_directory();
+    let paths: Vec<PathBuf> = program
+        .source_files
+        .iter()
+        .map(|source_file| absolute_path(&current_directory, &source_file.file_name))
+        .collect();
+    let emitted: Vec<usize> = order_by_dependencies(program, &paths)
+        .into_iter()
+        .filter(|&index| {
+            !program.syntax_trees[index]
+                .as_source_file()
+                .is_declaration_file
+                && !program.syntax_trees[index]
+                    .flags
+                    .contains(NodeFlags::JSON_FILE)
+                && !is_external_library_file(&program.source_files[index].file_name)
+        })
+        .collect();
+    let ignore_case = !program.use_case_sensitive_file_names;
+    let common_directory =
+        common_source_directory(emitted.iter().map(|&index| &paths[index]), ignore_case);
+    let module_name = |index: usize| {
+        remove_file_extension(&relative_path(
+            &common_directory,
+            &paths[index],
+            ignore_case,
+        ))
+        .to_string()
+    };
+
+    let mut enum_checker = enum_checker(program, options);
+    let files: Vec<(Arc<Node>, TransformOptions)> = emitted
+        .iter()
+        .map(|&index| {
+            let tree = &program.syntax_trees[index];
+            let mut transform_options = transform_options(options);
+            if let Some((checker, inline_accesses)) = &mut enum_checker {
+                transform_options.constant_values =
+                    constant_values(checker, tree, *inline_accesses);
+            }
+            if is_external_module(tree) {
+                transform_options.module_name = Some(module_name(index));
+                for specifier in module_specifiers(tree) {
+                    if let Some(dependency) = resolve_program_file(&paths, &paths[index], specifier)
+                        && emitted.contains(&dependency)
+                        && is_external_module(&program.syntax_trees[dependency])
+                    {
+                        transform_options
+                            .bundled_module_names
+                            .insert(specifier.to_string(), module_name(dependency));
+                    }
+                }
+            }
+            (Arc::clone(tree), transform_options)
+        })
+        .collect();
+    let transformed = transform_bundle(&files);
+    let outputs: Vec<(&SourceFile, Arc<Node>)> = emitted
+        .iter()
+        .map(|&index| &program.source_files[index])
+        .zip(transformed)
+        .collect();
+    write_javascript(host, out_file, &outputs, options, output);
+
+    if options.declaration && !options.transpile_only {
+        let dts_path = bundle_declaration_path(options, out_file);
+        let dts_content = format!("// Type definitions{}", options.new_line.as_str());
+        write_output(host, &dts_path, &output_text(options, dts_content), output);
+    }
+}
+
+// Get the path of the declaration file of a bundle, which is named after the bundle, in the
+// declaration folder if there is one
+fn bundle_declaration_path(options: &CompilerOptions, out_file: &str) -> String {
+    let base = match &options.declaration_dir {
+        Some(declaration_dir) => {
+            let name = Path::new(out_file).file_name().unwrap_or_default();
+            format!(
+                "{}/{}",
+                declaration_dir,
+                remove_file_extension(&name.to_string_lossy())
+            )
+        }
+        None => remove_file_extension(out_file).to_string(),
+    };
+    format!("{base}.d.ts")
+}
+
+// Print transformed files one after another into a JavaScript file, along with a source map
+// of them if the options ask for one
+fn write_javascript(
+    host: &impl CompilerHost,
+    js_path: &str,
+    files: &[(&SourceFile, Arc<Node>)],
+    options: &CompilerOptions,
+    output: &mut EmitResult,
+) {
+    let current_directory = host.get_current_directory();
+    let (text, map) = print_javascript(
+        &current_directory,
+        js_path,
+        files,
+        &printer_options(options),
+        options.source_map,
+        !host.use_case_sensitive_file_names(),
+    );
+    if let Some(map) = map {
+        write_output(host, &format!("{js_path}.map"), &map, output);
+    }
+    write_output(host, js_path, &output_text(options, text), output);
+}
+
+// The text an emitted JavaScript or declaration file is written with, which starts with a
+// byte order mark when `emitBOM` is set. Source maps never have one.
+fn output_text(options: &CompilerOptions, text: String) -> String {
+    if options.emit_bom {
+        format!("\u{feff}{text}")
+    } else {
+        text
+    }
+}
+
+// Print transformed files one after another as the text of the JavaScript file `js_path`,
+// along with the text of a source map of them if `source_map` is set
+fn print_javascript(
+    current_directory: &str,
+    js_path: &str,
+    files: &[(&SourceFile, Arc<Node>)],
+    printer_options: &PrinterOptions,
+    source_map: bool,
+    ignore_case: bool,
+) -> (String, Option<String>) {
+    let mut text = String::new();
+    let mut generator = SourceMapGenerator::new();
+    let output_directory =
+        paths::get_directory_path(&paths::combine_paths(current_directory, js_path));
+    for (source_file, tree) in files {
+        if source_map {
+            let (printed, mappings) = print_file_with_mappings(tree, printer_options);
+            let source = relative_path(
+                Path::new(&output_directory),
+                &absolute_path(current_directory, &source_file.file_name),
+                ignore_case,
+            );
+            generator.add_source(source, &mappings, text.matches('\n').count());
+            text.push_str(&printed);
+        } else {
+            text.push_str(&print_file(tree, printer_options));
+        }
+    }
+    if !source_map {
+        return (text, None);
+    }
+    let file_name = |path: &str| {
+        Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    text.push_str(&format!(
+        "//# sourceMappingURL={}",
+        file_name(&format!("{js_path}.map"))
+    ));
+    let map = generator.to_json(&file_name(js_path));
+    (text, Some(map))
+}
+
+// Write an output file, noting its name if it was written, or why it could not be
+fn write_output(host: &impl CompilerHost, path: &str, data: &str, output: &mut EmitResult) {
+    match host.write_file(path, data) {
+        Ok(()) => output.emitted_files.push(path.to_string()),
+        Err(error) => output.diagnostics.push(option_diagnostic(
+            diagnostics::COULD_NOT_WRITE_FILE_0_COLON_1_5033,
+            &[path.to_string(), error.to_string()],
+        )),
+    }
+}
+
+// Whether a module kind can write modules into one file, where each registers itself with
+// the module loader under its name
+fn supports_out_file(module: Option<ModuleKind>) -> bool {
+    matches!(module, Some(ModuleKind::AMD | ModuleKind::System))
+}
+
+// Get the first file of a program that is a module, along with what makes it one
+fn first_module_file(program: &Program) -> Option<(&SourceFile, &Arc<Node>, &Arc<Node>)> {
+    program
+        .source_files
+        .iter()
+        .zip(&program.syntax_trees)
+        .filter(|(_, tree)| !tree.as_source_file().is_declaration_file)
+        .find_map(|(source_file, tree)| {
+            get_external_module_indicator(tree).map(|indicator| (source_file, tree, indicator))
+        })
+}
+
+// Order the files of a program so that each comes after the files it references with
+// `/// <reference path="..." />` and the files it imports, as they must be when they are
+// written into one file. Files that depend on each other keep the order they are reached in.
+fn order_by_dependencies(program: &Program, paths: &[PathBuf]) -> Vec<usize> {
+    let dependencies: Vec<Vec<usize>> = program
+        .source_files
+        .iter()
+        .zip(&program.syntax_trees)
+        .enumerate()
+        .map(|(index, (source_file, tree))| {
+            reference_directives(&source_file.text, "path")
+                .into_iter()
+                .map(|(_, path)| path)
+                .chain(module_specifiers(tree))
+                .filter_map(|path| resolve_program_file(paths, &paths[index], path))
+                .collect()
+        })
+        .collect();
+
+    fn visit(
+        index: usize,
+        dependencies: &[Vec<usize>],
+        visited: &mut [bool],
+        order: &mut Vec<usize>,
+    ) {
+        if visited[index] {
+            return;
+        }
+        visited[index] = true;
+        for &dependency in &dependencies[index] {
+            visit(dependency, dependencies, visited, order);
+        }
+        order.push(index);
+    }
+
+    let mut visited = vec![false; paths.len()];
+    let mut order = Vec::with_capacity(paths.len());
+    for index in 0..paths.len() {
+        visit(index, &dependencies, &mut visited, &mut order);
+    }
+    order
+}
+
+// Get the values of an attribute of the `/// <reference ... />` directives at the top of a
+// file, like the paths of `/// <reference path="..." />`, with where each value is written
+fn reference_directives<'a>(text: &'a str, attribute: &str) -> Vec<(TextRange, &'a str)> {
+    let mut values = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        let Some(comment) = line.strip_prefix("//") else {
+            break;
+        };
+        let Some(directive) = comment
+            .strip_prefix('/')
+            .map(str::trim_start)
+            .and_then(|directive| directive.strip_prefix("<reference"))
+        else {
+            continue;
+        };
+        let Some(value) = directive
+            .find(attribute)
+            .map(|start| directive[start + attribute.len()..].trim_start())
+            .and_then(|rest| rest.strip_prefix('='))
+            .map(str::trim_start)
+        else {
+            continue;
+        };
+        let Some(quote) = value.chars().next().filter(|&ch| ch == '"' || ch == '\'') else {
+            continue;
+        };
+        if let Some(end) = value[1..].find(quote) {
+            // The value is a slice of the text, which locates it
+            let start = value.as_ptr() as usize - text.as_ptr() as usize + 1;
+            values.push((TextRange::new(start, start + end), &value[1..1 + end]));
+        }
+    }
+    values
+}
+
+// Get the relative module specifiers of the imports and re-exports of a file, which may
+// refer to other files of the program
+fn module_specifiers(tree: &Node) -> Vec<&str> {
+    import_specifiers(tree)
+        .into_iter()
+        .map(|(specifier, _)| specifier.text())
+        .filter(|text| module_resolution::is_external_module_name_relative(text))
+        .collect()
+}
+
+// Get the string literals naming the modules that a file imports and re-exports from, and
+// whether each is imported with `import x = require(...)`
+fn import_specifiers(tree: &Node) -> Vec<(&Arc<Node>, bool)> {
+    tree.as_source_file()
+        .statements
+        .iter()
+        .filter_map(|statement| {
+            let specifier = match statement.kind {
+                SyntaxKind::ImportDeclaration => {
+                    &statement.as_import_declaration().module_specifier
+                }
+                SyntaxKind::ExportDeclaration => statement
+                    .as_export_declaration()
+                    .module_specifier
+                    .as_ref()?,
+                SyntaxKind::ImportEqualsDeclaration => {
+                    let reference = &statement.as_import_equals_declaration().module_reference;
+                    if reference.kind != SyntaxKind::ExternalModuleReference {
+                        return None;
+                    }
+                    let specifier = &reference.as_external_module_reference().expression;
+                    return (specifier.kind == SyntaxKind::StringLiteral)
+                        .then_some((specifier, true));
+                }
+                // `declare module "x"` in a module augments the module `x` resolves to
+                SyntaxKind::ModuleDeclaration if is_external_module(tree) => {
+                    &statement.as_module_declaration().name
+                }
+                _ => return None,
+            };
+            (specifier.kind == SyntaxKind::StringLiteral).then_some((specifier, false))
+        })
+        .collect()
+}
+
+// Get the string literals naming the modules that the `import()` calls of a file load
+fn dynamic_import_specifiers(node: &Arc<Node>) -> Vec<Arc<Node>> {
+    let mut specifiers = Vec::new();
+    for_each_descendant(node, &mut |child| {
+        if child.kind == SyntaxKind::CallExpression
+            && child.as_call_expression().expression.kind == SyntaxKind::ImportKeyword
+            && let Some(specifier) = child.as_call_expression().arguments.iter().next()
+            && specifier.kind == SyntaxKind::StringLiteral
+        {
+            specifiers.push(Arc::clone(specifier));
+        }
+        false
+    });
+    specifiers
+}
+
+// Determine if a module specifier is the name of a module augmentation
+fn is_module_augmentation_name(specifier: &Node) -> bool {
+    specifier
+        .parent()
+        .is_some_and(|parent| parent.kind == SyntaxKind::ModuleDeclaration)
+}
+
+// Find the file of the program that a relative module specifier or reference path in the
+// file at `containing_path` refers to
+fn resolve_program_file(
+    paths: &[PathBuf],
+    containing_path: &Path,
+    specifier: &str,
+) -> Option<usize> {
+    let directory = paths::get_directory_path(&containing_path.to_string_lossy());
+    let base = paths::combine_paths(&directory, specifier);
+    // An import of the JavaScript a TypeScript file is emitted to refers to that file
+    let without_js_extension = [
+        (".js", ".ts"),
+        (".jsx", ".tsx"),
+        (".mjs", ".mts"),
+        (".cjs", ".cts"),
+    ]
+    .iter()
+    .find_map(|(js, ts)| base.strip_suffix(js).map(|stem| format!("{stem}{ts}")));
+    let candidates = without_js_extension.into_iter().chain(
+        [
+            "",
+            ".ts",
+            ".tsx",
+            ".d.ts",
+            ".js",
+            ".jsx",
+            "/index.ts",
+            "/index.tsx",
+            "/index.d.ts",
+        ]
+        .iter()
+        .map(|extension| format!("{base}{extension}")),
+    );
+    for candidate in candidates {
+        let candidate = Path::new(&candidate);
+        if let Some(index) = paths.iter().position(|path| path == candidate) {
+            return Some(index);
+        }
+    }
+    None
+}
+
+// Whether a file belongs to an installed package, which is compiled against but not emitted
+fn is_external_library_file(file_name: &str) -> bool {
+    paths::get_path_components(file_name)
+        .iter()
+        .any(|component| component == "node_modules")
+}
+
+// Get the longest directory that contains every file
+// Get the paths of the JavaScript file emitted for a source file, or of the copy of a JSON
+// file, and of its declaration file if it has one
+fn source_output_paths(
+    options: &CompilerOptions,
+    current_directory: &str,
+    root_dir: &Path,
+    source_file: &SourceFile,
+    tree: &Node,
+    ignore_case: bool,
+) -> (String, Option<String>) {
+    let file_name = &source_file.file_name;
+    let js_path = format!(
+        "{}{}",
+        output_base_path(
+            current_directory,
+            root_dir,
+            options.out_dir.as_deref(),
+            file_name,
+            ignore_case,
+        ),
+        output_extension(file_name, options.jsx)
+    );
+    // Declarations come from the types of the file, which transpiling does not check. A JSON
+    // file has none.
+    let has_declarations = options.declaration
+        && !options.transpile_only
+        && !tree.flags.contains(NodeFlags::JSON_FILE);
+    let dts_path = has_declarations.then(|| {
+        let directory = options
+            .declaration_dir
+            .as_deref()
+            .or(options.out_dir.as_deref());
+        format!(
+            "{}{}",
+            output_base_path(
+                current_directory,
+                root_dir,
+                directory,
+                file_name,
+                ignore_case
+            ),
+            declaration_extension(file_name)
+        )
+    });
+    (js_path, dts_path)
+}
+
+// Get the folder whose structure the output folder mirrors: the configured root folder, or
+// the folder containing all the emitted files without one
+fn output_root_directory(
+    program: &Program,
+    options: &CompilerOptions,
+    current_directory: &str,
+) -> PathBuf {
+    match &options.root_dir {
+        Some(root_dir) => absolute_path(current_directory, root_dir),
+        None => common_source_directory(
+            emitted_source_paths(program, current_directory).iter(),
+            !program.use_case_sensitive_file_names,
+        ),
+    }
+}
+
+// Get the paths of the files of a program that have output, which leaves out declaration files
+// and the files of external libraries
+fn emitted_source_paths(program: &Program, current_directory: &str) -> Vec<PathBuf> {
+    program
+        .source_files
+        .iter()
+        .zip(&program.syntax_trees)
+        .filter(|(source_file, tree)| {
+            !tree.as_source_file().is_declaration_file
+                && !is_external_library_file(&source_file.file_name)
+        })
+        .map(|(source_file, _)| absolute_path(current_directory, &source_file.file_name))
+        .collect()
+}
+
+// Report the output files that would overwrite an input file, or that more than one input
+// file would be written to
+fn verify_output_paths(program: &mut Program, options: &CompilerOptions) {
+    let current_directory = program.current_directory.clone();
+    let mut outputs = Vec::new();
+    match &options.out_file {
+        Some(out_file) => {
+            outputs.push(out_file.clone());
+            if options.declaration && !options.transpile_only {
+                outputs.push(bundle_declaration_path(options, out_file));
+            }
+        }
+        None => {
+            let root_dir = output_root_directory(program, options, &current_directory);
+            for (source_file, tree) in program.source_files.iter().zip(&program.syntax_trees) {
+                if tree.as_source_file().is_declaration_file
+                    || is_external_library_file(&source_file.file_name)
+                {
+                    continue;
+                }
+                let (js_path, dts_path) = source_output_paths(
+                    options,
+                    &current_directory,
+                    &root_dir,
+                    source_file,
+                    tree,
+                    !program.use_case_sensitive_file_names,
+                );
+                // A JSON file without an output folder is not copied onto itself
+                if !(tree.flags.contains(NodeFlags::JSON_FILE) && js_path == source_file.file_name)
+                {
+                    outputs.push(js_path);
+                }
+                outputs.extend(dts_path);
+            }
+        }
+    }
+
+    let canonical = |path: &Path| canonical_path(path, program.use_case_sensitive_file_names);
+    let inputs: HashSet<PathBuf> = program
+        .source_files
+        .iter()
+        .map(|source_file| canonical(&absolute_path(&current_directory, &source_file.file_name)))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut reported = HashSet::new();
+    for output in outputs {
+        let path = absolute_path(&current_directory, &output);
+        let key = canonical(&path);
+        let message = if inputs.contains(&key) {
+            diagnostics::CANNOT_WRITE_FILE_0_BECAUSE_IT_WOULD_OVERWRITE_INPUT_FILE_5055
+        } else if !seen.insert(key.clone()) {
+            diagnostics::CANNOT_WRITE_FILE_0_BECAUSE_IT_WOULD_BE_OVERWRITTEN_BY_MULTIPLE_INPUT_FILES_5056
+        } else {
+            continue;
+        };
+        if reported.insert(key) {
+            program.diagnostics.push(option_diagnostic(
+                message,
+                &[path.to_string_lossy().to_string()],
+            ));
+        }
+    }
+}
+
+// Get the path of an output file of a source file without its extension. The file keeps its
+// place under the root folder in the output folder `directory`, and is written next to the
+// source file without one.
+fn output_base_path(
+    current_directory: &str,
+    root_dir: &Path,
+    directory: Option<&str>,
+    file_name: &str,
+    ignore_case: bool,
+) -> String {
+    match directory {
+        Some(directory) => {
+            let path = absolute_path(current_directory, file_name);
+            format!(
+                "{}/{}",
+                directory,
+                remove_file_extension(&relative_path(root_dir, &path, ignore_case))
+            )
+        }
+        None => remove_file_extension(file_name).to_string(),
+    }
+}
+
+fn common_source_directory<'a>(
+    paths: impl Iterator<Item = &'a PathBuf>,
+    ignore_case: bool,
+) -> PathBuf {
+    let directories: Vec<String> = paths
+        .map(|path| paths::get_directory_path(&path.to_string_lossy()))
+        .collect();
+    paths::get_common_directory(directories.iter().map(String::as_str), ignore_case)
+        .map(PathBuf::from)
+        .unwrap_or_default()
+}
+
+// Make a path absolute, and resolve its `.` and `..` components
+fn absolute_path(current_directory: &str, path: &str) -> PathBuf {
+    PathBuf::from(paths::combine_paths(current_directory, path))
+}
+
+// Get the path that identifies a file, which is lowercased when file names that differ only in
+// casing name the same file
+fn canonical_path(path: &Path, use_case_sensitive_file_names: bool) -> PathBuf {
+    if use_case_sensitive_file_names {
+        path.to_path_buf()
+    } else {
+        PathBuf::from(path.to_string_lossy().to_lowercase())
+    }
+}
+
+// Get the path of `path` from `directory`, with `/` separators, comparing names without
+// regard to casing with `ignore_case`
+pub(crate) fn relative_path(directory: &Path, path: &Path, ignore_case: bool) -> String {
+    paths::get_relative_path_from_directory(
+        &directory.to_string_lossy(),
+        &path.to_string_lossy(),
+        ignore_case,
+    )
+}
+
+// Get the extension of the declaration file emitted for a source file, which is `.d.mts` or
+// `.d.cts` for the files whose JavaScript is `.mjs` or `.cjs`
+fn declaration_extension(file_name: &str) -> &'static str {
+    match output_extension(file_name, JsxEmit::None) {
+        ".mjs" => ".d.mts",
+        ".cjs" => ".d.cts",
+        _ => ".d.ts",
+    }
+}
+
+// Get the extension of the JavaScript file emitted for a source file, or of the copy of a
+// JSON file
+fn output_extension(file_name: &str, jsx: JsxEmit) -> &'static str {
+    if file_name.ends_with(".json") {
+        ".json"
+    } else if jsx == JsxEmit::Preserve
+        && (file_name.ends_with(".tsx") || file_name.ends_with(".jsx"))
+    {
+        ".jsx"
+    } else if file_name.ends_with(".mts") || file_name.ends_with(".mjs") {
+        ".mjs"
+    } else if file_name.ends_with(".cts") || file_name.ends_with(".cjs") {
+        ".cjs"
+    } else {
+        ".js"
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::compiler::emitter::NewLineKind;
+    use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+
+    /// Creates a program from the files of a host, compiled with the command line arguments
+    /// `args`, which name its root files
+    pub(crate) fn create(host: &VirtualCompilerHost, args: &[&str]) -> Program {
+        let cli = Cli::parse_from(["tsrs"].iter().chain(args));
+        let source_files = read_source_files(&cli.files, host);
+        create_program(&source_files, &create_compiler_options(&cli), host)
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<u32> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect()
+    }
+
+    #[test]
+    fn follows_imports_and_references() {
+        let host = VirtualCompilerHost::with_files(&[
+            (
+                "a.ts",
+                "/// <reference path=\"globals\" />\n/// <reference path=\"missing.ts\" />\nimport { b } from \"./b\";\n",
+            ),
+            ("b.ts", "import \"./a\";\nexport const b = 1;\n"),
+            ("globals.d.ts", "declare const version: string;\n"),
+        ]);
+        let program = create(&host, &["a.ts"]);
+        let file_names: Vec<&str> = program
+            .source_files
+            .iter()
+            .map(|source_file| source_file.file_name.as_str())
+            .collect();
+        assert_eq!(file_names, ["globals.d.ts", "b.ts", "a.ts"]);
+        assert_eq!(codes(&program.diagnostics), [6053]);
+
+        assert!(program.get_source_file("./b.ts").is_some());
+        assert!(program.get_syntax_tree("/globals.d.ts").is_some());
+        assert!(program.get_source_file("c.ts").is_none());
+        assert_eq!(
+            program.get_file_include_reasons("a.ts"),
+            [
+                FileIncludeReason::RootFile,
+                FileIncludeReason::Import(FileReference {
+                    file_name: "b.ts".to_string(),
+                    text: "./a".to_string(),
+                    range: TextRange::new(7, 12),
+                }),
+            ]
+        );
+        assert!(matches!(
+            program.get_file_include_reasons("globals.d.ts"),
+            [FileIncludeReason::ReferenceFile(reference)] if reference.text == "globals"
+        ));
+
+        let explain = |file_name| {
+            program
+                .get_file_include_reasons(file_name)
+                .iter()
+                .map(|reason| program.explain_file_include_reason(reason))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            explain("a.ts"),
+            [
+                "Root file specified for compilation",
+                "Imported via \"./a\" from file 'b.ts'"
+            ]
+        );
+        assert_eq!(
+            explain("globals.d.ts"),
+            ["Referenced via 'globals' from file 'a.ts'"]
+        );
+    }
+
+    #[test]
+    fn reports_library_files_missing_from_the_library_folder() {
+        let mut host = VirtualCompilerHost::with_files(&[("a.ts", "let a = 1;\n")]);
+        host.set_default_lib_location(Some("lib"));
+        let messages = |args: &[&str]| {
+            create(&host, args)
+                .diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages(&["--target", "es2023", "a.ts"]),
+            ["File '/lib/lib.es2023.full.d.ts' not found."]
+        );
+        assert_eq!(
+            messages(&["--lib", "es5,dom", "a.ts"]),
+            [
+                "File '/lib/lib.es5.d.ts' not found.",
+                "File '/lib/lib.dom.d.ts' not found."
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_and_explains_the_files_of_a_program() {
+        let host = VirtualCompilerHost::with_files(&[
+            (
+                "a.ts",
+                "import { b } from \"./b\";\nlet a: string = 1;\nb;\n",
+            ),
+            ("b.ts", "export const b = 1;\n"),
+        ]);
+        let compile_with = |args: &[&str]| {
+            let cli = Cli::parse_from(["tsrs"].iter().chain(args).chain(&["a.ts"]));
+            let options = create_compiler_options(&cli);
+            (compile(&cli.files, &options, &host), options)
+        };
+
+        let (result, options) = compile_with(&["--listFiles"]);
+        assert!(options.list_files && !options.list_files_only);
+        assert_eq!(codes(result.diagnostics()), [2322]);
+        assert_eq!(result.emitted_files, ["b.js", "a.js"]);
+        assert_eq!(result.program.list_files(false), "b.ts\na.ts\n");
+
+        // Only the files are listed, without checking or emitting them
+        let (result, options) = compile_with(&["--listFilesOnly"]);
+        assert!(options.list_files_only);
+        assert!(result.diagnostics().is_empty());
+        assert!(result.emit_skipped && result.emitted_files.is_empty());
+        assert_eq!(result.program.list_files(false), "b.ts\na.ts\n");
+
+        let (result, options) = compile_with(&["--explainFiles"]);
+        assert!(options.explain_files);
+        assert_eq!(
+            result.program.list_files(true),
+            "b.ts\n  Imported via \"./b\" from file 'a.ts'\na.ts\n  Root file specified for compilation\n"
+        );
+    }
+
+    #[test]
+    fn reports_names_that_differ_only_in_casing() {
+        let files = [
+            ("a.ts", "import { b } from \"./B\";\n"),
+            ("b.ts", "export const b = 1;\n"),
+        ];
+        let host = VirtualCompilerHost::new("/", false);
+        for (path, text) in files {
+            host.add_file(path, text);
+        }
+        let program = create(&host, &["b.ts", "a.ts"]);
+        assert_eq!(program.source_files.len(), 2);
+        assert_eq!(codes(&program.diagnostics), [1149]);
+        assert_eq!(program.diagnostics[0].file_name.as_deref(), Some("a.ts"));
+        assert_eq!(
+            program.diagnostics[0].message,
+            "File name 'B.ts' differs from already included file name 'b.ts' only in casing."
+        );
+
+        let program = create(
+            &host,
+            &[
+                "b.ts",
+                "a.ts",
+                "--forceConsistentCasingInFileNames",
+                "false",
+            ],
+        );
+        assert!(program.diagnostics.is_empty());
+
+        // On a case-sensitive file system, they are different files
+        let host = VirtualCompilerHost::with_files(&files);
+        let program = create(&host, &["b.ts", "a.ts"]);
+        assert_eq!(codes(&program.diagnostics), [2307]);
+    }
+
+    #[test]
+    fn adds_linked_packages_once() {
+        let host = VirtualCompilerHost::with_files(&[
+            (
+                "a.ts",
+                "import \"pkg\";\nimport \"./packages/pkg/index\";\n",
+            ),
+            ("packages/pkg/index.d.ts", "export {};\n"),
+        ]);
+        host.add_symlink("node_modules/pkg", "packages/pkg");
+        let program = create(&host, &["a.ts", "--moduleResolution", "node10"]);
+        let file_names: Vec<&str> = program
+            .source_files
+            .iter()
+            .map(|source_file| source_file.file_name.as_str())
+            .collect();
+        assert_eq!(file_names, ["packages/pkg/index.d.ts", "a.ts"]);
+        assert!(program.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn checks_files_on_demand() {
+        let host = VirtualCompilerHost::with_files(&[
+            ("a.ts", "let a: number = \"one\";\n"),
+            ("b.ts", "let b: string = 2;\nlet c: string = 3;\n"),
+        ]);
+        let program = create(&host, &["a.ts", "b.ts"]);
+        assert!(program.diagnostics.is_empty());
+        assert_eq!(
+            codes(&program.get_semantic_diagnostics(Some("b.ts"))),
+            [2322, 2322]
+        );
+        assert_eq!(codes(&program.get_semantic_diagnostics(None)).len(), 3);
+    }
+
+    #[test]
+    fn reports_the_same_diagnostics_on_one_thread_or_several() {
+        let files: Vec<(String, String)> = (0..9)
+            .map(|index| {
+                let text = if index == 0 {
+                    "export const zero: string = 0;\n".to_string()
+                } else {
+                    format!(
+                        "import {{ zero }} from \"./file0\";\nlet n{index}: number = zero;\n\
+                         let s{index}: string = {index};\n"
+                    )
+                };
+                (format!("file{index}.ts"), text)
+            })
+            .collect();
+        let files: Vec<(&str, &str)> = files
+            .iter()
+            .map(|(name, text)| (name.as_str(), text.as_str()))
+            .collect();
+        let host = VirtualCompilerHost::with_files(&files);
+        let names: Vec<&str> = files.iter().rev().map(|(name, _)| *name).collect();
+        let program = create(&host, &names);
+        let parallel = program.get_semantic_diagnostics(None);
+        let single_threaded = create(&host, &[&["--singleThreaded"], &names[..]].concat())
+            .get_semantic_diagnostics(None);
+        assert_eq!(parallel.len(), 17);
+        let locations = |diagnostics: &[Diagnostic]| {
+            diagnostics
+                .iter()
+                .map(|diagnostic| {
+                    (
+                        diagnostic.file_name.clone(),
+                        diagnostic.line,
+                        diagnostic.code,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(locations(&parallel), locations(&single_threaded));
+        // The files are reported in the order of their names, not of the program
+        assert_eq!(parallel[0].file_name.as_deref(), Some("file0.ts"));
+        assert_eq!(parallel[1].file_name.as_deref(), Some("file1.ts"));
+    }
+
+    #[test]
+    fn parses_files_ahead_for_the_target_and_as_the_kind_of_their_extension() {
+        let host = VirtualCompilerHost::with_files(&[
+            ("a.tsx", "export const a = <div>{1}</div>;\n"),
+            (
+                "b.tsx",
+                "import { a } from \"./a\";\nconst b = <span>{a}</span>;\n",
+            ),
+            ("c.ts", "export const c = 1;\n"),
+        ]);
+        let program = create(
+            &host,
+            &[
+                "--jsx", "preserve", "--target", "es5", "a.tsx", "b.tsx", "c.ts",
+            ],
+        );
+        assert_eq!(codes(&program.diagnostics), Vec::<u32>::new());
+        let parsed = |file_name: &str| {
+            let tree = program.get_syntax_tree(file_name).unwrap().as_source_file();
+            (tree.language_version, tree.script_kind)
+        };
+        assert_eq!(parsed("a.tsx"), (ScriptTarget::ES5, ScriptKind::TSX));
+        assert_eq!(parsed("c.ts"), (ScriptTarget::ES5, ScriptKind::TS));
+    }
+
+    #[test]
+    fn compiles_long_chains_of_operators_on_small_stacks() {
+        let chain = format!("let sum: string = {};\n", vec!["1"; 20000].join(" + "));
+        let host = VirtualCompilerHost::with_files(&[
+            ("a.ts", chain.as_str()),
+            ("b.ts", "export const b = 1;\n"),
+        ]);
+        let program = create(&host, &["a.ts", "b.ts"]);
+        assert_eq!(codes(&program.get_semantic_diagnostics(None)), [2322]);
+        let options = CompilerOptions::default();
+        emit_files(&program, &options, &host, &CancellationToken::new()).unwrap();
+        assert!(
+            host.read_file("a.js")
+                .is_some_and(|text| text.ends_with(" + 1 + 1;\n") && text.len() > 80000)
+        );
+    }
+
+    #[test]
+    fn reports_the_same_diagnostics_with_any_number_of_checkers() {
+        let host = VirtualCompilerHost::with_files(&[
+            (
+                "a.ts",
+                "export interface Box<T> { value: T }\nexport const a: Box<string> = { value: 1 };\n",
+            ),
+            (
+                "b.ts",
+                "import { Box, a } from \"./a\";\nlet b: Box<number> = a;\nb.missing;\n",
+            ),
+            (
+                "c.ts",
+                "import { a } from \"./a\";\nlet c: number = a.value;\n",
+            ),
+            ("d.ts", "let d: Array<string> = [1];\nundefinedName;\n"),
+            ("e.ts", "function e(x: number) { return x; }\ne(\"e\");\n"),
+        ]);
+        let program = create(&host, &["e.ts", "d.ts", "c.ts", "b.ts", "a.ts"]);
+        let files: Vec<usize> = (0..program.source_files.len()).collect();
+        let check = |checker_count| {
+            let (diagnostics, _) = program
+                .check_files_on(&files, checker_count, &CancellationToken::new())
+                .unwrap();
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| {
+                    (
+                        diagnostic.file_name,
+                        diagnostic.line,
+                        diagnostic.character,
+                        diagnostic.code,
+                        diagnostic.message,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let serial = check(1);
+        assert_eq!(
+            serial.iter().map(|(.., code, _)| *code).collect::<Vec<_>>(),
+            [2322, 2322, 2339, 2322, 2304, 2552, 2345]
+        );
+        for checker_count in [2, 3, files.len()] {
+            assert_eq!(check(checker_count), serial);
+        }
+        assert!(checker_count(files.len()) <= files.len());
+        assert_eq!(checker_count(0), 1);
+    }
+
+    #[test]
+    fn compiles_through_the_library_entry_point() {
+        let host = VirtualCompilerHost::with_files(&[
+            ("a.ts", "import { b } from \"./b\";\nlet a: string = 1;\n"),
+            ("b.ts", "export const b = 1;\n"),
+        ]);
+        let options = CompilerOptions {
+            module: Some(ModuleKind::CommonJS),
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string()], &options, &host);
+        assert!(!result.success());
+        assert_eq!(codes(result.diagnostics()), [2322]);
+        assert_eq!(result.emitted_files, ["b.js", "a.js"]);
+        assert_eq!(host.written_files(), ["/b.js", "/a.js"]);
+        assert!(
+            host.read_file("b.js")
+                .is_some_and(|text| text.contains("exports.b = 1;"))
+        );
+
+        let options = CompilerOptions {
+            no_emit: true,
+            ..options
+        };
+        let result = compile(&["b.ts".to_string()], &options, &host);
+        assert!(result.success());
+        assert!(result.emitted_files.is_empty());
+        assert_eq!(result.exit_status(), ExitStatus::Success);
+    }
+
+    #[test]
+    fn reports_options_that_conflict() {
+        let host = VirtualCompilerHost::with_files(&[("a.ts", "export const a = 1;\n")]);
+        let options = CompilerOptions {
+            no_emit: true,
+            emit_declaration_only: true,
+            inline_sources: true,
+            resolve_json_module: Some(true),
+            module_resolution: Some(ModuleResolutionKind::Classic),
+            verbatim_module_syntax: true,
+            imports_not_used_as_values: Some(ImportsNotUsedAsValues::Preserve),
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string()], &options, &host);
+        assert_eq!(codes(result.diagnostics()), [5051, 5053, 5069, 5070, 5104]);
+
+        let options = CompilerOptions {
+            composite: true,
+            declaration: true,
+            emit_declaration_only: true,
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string()], &options, &host);
+        assert!(result.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn skips_emit_on_errors_with_no_emit_on_error() {
+        let host = VirtualCompilerHost::with_files(&[("a.ts", "let a: string = 1;\n")]);
+        let result = compile(&["a.ts".to_string()], &CompilerOptions::default(), &host);
+        assert_eq!(result.emitted_files, ["a.js"]);
+        assert_eq!(
+            result.exit_status(),
+            ExitStatus::DiagnosticsPresentOutputsGenerated
+        );
+
+        let host = VirtualCompilerHost::with_files(&[("a.ts", "let a: string = 1;\n")]);
+        let options = CompilerOptions {
+            no_emit_on_error: true,
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string()], &options, &host);
+        assert_eq!(codes(result.diagnostics()), [2322]);
+        assert!(result.emit_skipped);
+        assert!(host.written_files().is_empty());
+        assert_eq!(
+            result.exit_status(),
+            ExitStatus::DiagnosticsPresentOutputsSkipped
+        );
+    }
+
+    #[test]
+    fn reports_output_files_that_cannot_be_written() {
+        // A folder is in the way of the output of `a.ts`
+        let host = VirtualCompilerHost::with_files(&[
+            ("a.ts", "let a = 1;\n"),
+            ("b.ts", "let b = 1;\n"),
+            ("out/a.js/x", ""),
+        ]);
+        let options = CompilerOptions {
+            out_dir: Some("out".to_string()),
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string(), "b.ts".to_string()], &options, &host);
+        assert_eq!(codes(result.diagnostics()), [5033]);
+        assert_eq!(
+            result.diagnostics()[0].message,
+            "Could not write file 'out/a.js': is a directory."
+        );
+        assert_eq!(result.emitted_files, ["out/b.js"]);
+        assert_eq!(
+            result.exit_status(),
+            ExitStatus::DiagnosticsPresentOutputsGenerated
+        );
+        assert_eq!(result.exit_status().code(), 2);
+    }
+
+    #[test]
+    fn exits_with_the_status_of_the_compilation() {
+        let status = |text: &str, options: &CompilerOptions| {
+            let host = VirtualCompilerHost::with_files(&[("a.ts", text)]);
+            compile(&["a.ts".to_string()], options, &host).exit_status()
+        };
+        let no_emit_on_error = CompilerOptions {
+            no_emit_on_error: true,
+            ..CompilerOptions::default()
+        };
+
+        let success = status("let a: string = \"a\";\n", &no_emit_on_error);
+        assert_eq!(success, ExitStatus::Success);
+        assert_eq!(success.code(), 0);
+
+        let conflicting = CompilerOptions {
+            no_emit: true,
+            emit_declaration_only: true,
+            ..no_emit_on_error.clone()
+        };
+        let invalid_options = status("let a: string = \"a\";\n", &conflicting);
+        assert_eq!(invalid_options, ExitStatus::InvalidOptions);
+        assert_eq!(invalid_options.code(), 1);
+        // Errors in the files do not hide that the options are invalid
+        let invalid_options = status("let a: string = 1;\n", &conflicting);
+        assert_eq!(invalid_options, ExitStatus::InvalidOptions);
+
+        let emit_skipped = status("let a: string = 1;\n", &no_emit_on_error);
+        assert_eq!(emit_skipped, ExitStatus::DiagnosticsPresentOutputsSkipped);
+        assert_eq!(emit_skipped.code(), 2);
+        // Errors about the files of the program, outside any of them, are not about the options
+        let outside_root_dir = CompilerOptions {
+            root_dir: Some("src".into()),
+            ..no_emit_on_error.clone()
+        };
+        let emit_skipped = status("let a: string = \"a\";\n", &outside_root_dir);
+        assert_eq!(emit_skipped, ExitStatus::DiagnosticsPresentOutputsSkipped);
+    }
+
+    #[test]
+    fn transpiles_files_without_their_imports() {
+        let host = VirtualCompilerHost::with_files(&[
+            ("a.ts", "import { b } from \"./b\";\nlet a: string = b;\n"),
+            ("b.ts", "export const b = 1;\n"),
+        ]);
+        let options = CompilerOptions {
+            transpile_only: true,
+            skip_type_checking: true,
+            declaration: true,
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string()], &options, &host);
+        assert!(result.diagnostics().is_empty());
+        assert_eq!(result.program.source_files.len(), 1);
+        assert_eq!(result.emitted_files, ["a.js"]);
+    }
+
+    #[test]
+    fn imports_json_files_with_resolve_json_module() {
+        let files = [
+            (
+                "a.ts",
+                "import data from \"./data.json\";\nconst n: number = data.name;\n",
+            ),
+            ("data.json", "{ \"name\": \"a\" }\n"),
+        ];
+        let host = VirtualCompilerHost::with_files(&files);
+        let options = CompilerOptions {
+            module: Some(ModuleKind::CommonJS),
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string()], &options, &host);
+        assert_eq!(codes(result.diagnostics()), [2732]);
+
+        let host = VirtualCompilerHost::with_files(&files);
+        let options = CompilerOptions {
+            resolve_json_module: Some(true),
+            module: Some(ModuleKind::CommonJS),
+            es_module_interop: true,
+            out_dir: Some("out".to_string()),
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string()], &options, &host);
+        assert_eq!(codes(result.diagnostics()), [2322]);
+        assert_eq!(result.emitted_files, ["out/data.json", "out/a.js"]);
+    }
+
+    #[test]
+    fn inlines_const_enums_from_other_files() {
+        let files = [
+            ("a.ts", "const a = E.B;\nconst b = E[\"A\"];\n"),
+            ("e.ts", "const enum E { A = 1, B = A * 2 }\n"),
+        ];
+        let host = VirtualCompilerHost::with_files(&files);
+        let options = CompilerOptions::default();
+        let result = compile(&["a.ts".to_string(), "e.ts".to_string()], &options, &host);
+        assert!(result.success());
+        assert_eq!(
+            host.read_file("a.js").unwrap(),
+            "const a = 2 /* E.B */;\nconst b = 1 /* \"A\" */;\n"
+        );
+        assert_eq!(host.read_file("e.js").unwrap(), "");
+
+        let options = CompilerOptions {
+            preserve_const_enums: true,
+            ..options
+        };
+        compile(&["a.ts".to_string(), "e.ts".to_string()], &options, &host);
+        assert!(
+            host.read_file("e.js")
+                .is_some_and(|text| text.contains("E[E[\"B\"] = 2] = \"B\";"))
+        );
+    }
+
+    #[test]
+    fn inlines_exported_const_enums_in_the_modules_that_import_them() {
+        let files = [
+            (
+                "a.ts",
+                "import { E } from \"./e\";\nexport const a = E.B;\n",
+            ),
+            ("e.ts", "export const enum E { A = 1, B = A * 2 }\n"),
+        ];
+        let host = VirtualCompilerHost::with_files(&files);
+        let options = CompilerOptions::default();
+        let result = compile(&["a.ts".to_string(), "e.ts".to_string()], &options, &host);
+        assert!(result.success());
+        assert_eq!(
+            host.read_file("a.js").unwrap(),
+            "export const a = 2 /* E.B */;\n"
+        );
+        assert_eq!(host.read_file("e.js").unwrap(), "export {};\n");
+
+        // Files compiled on their own cannot inline the enums of other files
+        let options = CompilerOptions {
+            isolated_modules: true,
+            ..options
+        };
+        compile(&["a.ts".to_string(), "e.ts".to_string()], &options, &host);
+        assert!(
+            host.read_file("e.js")
+                .is_some_and(|text| text.contains("export var E;"))
+        );
+    }
+    #[test]
+    fn mirrors_the_root_folder_in_the_output_folders() {
+        let files = [
+            ("src/a/index.ts", "export const a = 1;\n"),
+            ("src/b/index.mts", "export const b = 2;\n"),
+        ];
+        let host = VirtualCompilerHost::with_files(&files);
+        let options = CompilerOptions {
+            out_dir: Some("out".to_string()),
+            declaration: true,
+            declaration_dir: Some("types".to_string()),
+            ..CompilerOptions::default()
+        };
+        let roots = ["src/a/index.ts".to_string(), "src/b/index.mts".to_string()];
+        let result = compile(&roots, &options, &host);
+        assert!(result.success());
+        assert_eq!(
+            result.emitted_files,
+            [
+                "out/a/index.js",
+                "types/a/index.d.ts",
+                "out/b/index.mjs",
+                "types/b/index.d.mts"
+            ]
+        );
+
+        let options = CompilerOptions {
+            root_dir: Some("src/a".to_string()),
+            ..options
+        };
+        let result = compile(&roots, &options, &host);
+        assert_eq!(codes(result.diagnostics()), [6059]);
+    }
+
+    #[test]
+    fn compiles_windows_paths_on_a_case_insensitive_host() {
+        let host = VirtualCompilerHost::new("C:\\Project", false);
+        host.add_file(
+            "C:\\Project\\src\\a.ts",
+            "/// <reference path=\"..\\types\\globals.d.ts\" />\nimport { b } from \".\\\\lib\\\\B\";\nexport const a = b + version;\n",
+        );
+        host.add_file("src/lib/b.ts", "export const b = \"b\";\n");
+        host.add_file("TYPES/Globals.d.ts", "declare const version: string;\n");
+        host.add_file(
+            "//server/share/c.ts",
+            "import { a } from \"C:\\\\project\\\\SRC\\\\a\";\n",
+        );
+        let options = CompilerOptions {
+            out_dir: Some("Out".to_string()),
+            root_dir: Some("SRC".to_string()),
+            ..CompilerOptions::default()
+        };
+        let roots = ["src\\a.ts".to_string()];
+        let result = compile(&roots, &options, &host);
+        assert_eq!(codes(result.diagnostics()), []);
+        assert_eq!(result.emitted_files, ["Out/lib/B.js", "Out/a.js"]);
+        assert!(host.read_file("c:/project/out/A.js").is_some());
+
+        let program = create(&host, &["//server/share/c.ts"]);
+        assert_eq!(codes(&program.diagnostics), []);
+        let file_names: Vec<&str> = program
+            .source_files
+            .iter()
+            .map(|source_file| source_file.file_name.as_str())
+            .collect();
+        assert_eq!(
+            file_names,
+            [
+                "types/globals.d.ts",
+                "SRC/lib/B.ts",
+                "SRC/a.ts",
+                "//server/share/c.ts"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_outputs_that_overwrite_inputs_or_each_other() {
+        let files = [
+            ("a.ts", "export const a = 1;\n"),
+            ("a.tsx", "export const b = 2;\n"),
+            ("c.ts", "export const c = 3;\n"),
+            ("c.d.ts", "export declare const c: number;\n"),
+        ];
+        let host = VirtualCompilerHost::with_files(&files);
+        let options = CompilerOptions {
+            declaration: true,
+            ..CompilerOptions::default()
+        };
+        let roots = ["a.ts", "a.tsx", "c.ts", "c.d.ts"].map(String::from);
+        let result = compile(&roots, &options, &host);
+        let messages: Vec<&str> = result
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Cannot write file '/c.d.ts' because it would overwrite input file.",
+                "Cannot write file '/a.d.ts' because it would be overwritten by multiple input files.",
+                "Cannot write file '/a.js' because it would be overwritten by multiple input files.",
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_the_configured_line_breaks_and_byte_order_mark() {
+        let host = VirtualCompilerHost::with_files(&[("a.ts", "// a\nlet a = 1;\n")]);
+        let options = CompilerOptions {
+            new_line: NewLineKind::CarriageReturnLineFeed,
+            emit_bom: true,
+            source_map: true,
+            declaration: true,
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string()], &options, &host);
+        assert!(result.success());
+        assert_eq!(
+            host.read_file("a.js").unwrap(),
+            "\u{feff}// a\r\nlet a = 1;\r\n//# sourceMappingURL=a.js.map"
+        );
+        assert!(host.read_file("a.js.map").unwrap().starts_with('{'));
+        assert!(host.read_file("a.d.ts").unwrap().starts_with('\u{feff}'));
+
+        let options = CompilerOptions {
+            charset: Some("utf16le".to_string()),
+            ..CompilerOptions::default()
+        };
+        let result = compile(&["a.ts".to_string()], &options, &host);
+        assert_eq!(codes(result.diagnostics()), [6046]);
+    }
 }
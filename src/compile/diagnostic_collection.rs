@@ -0,0 +1,185 @@
+//! Collecting the diagnostics of a program in a stable order
+//!
+//! Diagnostics come from parsing, resolving, checking on several threads, and emitting, and
+//! the same problem can be found more than once, like an unresolved import that two checkers
+//! both report. A [`DiagnosticCollection`] keeps them sorted by file, position, and code, with
+//! each diagnostic once, as tsc does, so that a report does not depend on the order they were
+//! found in or on how the work was scheduled.
+
+use std::cmp::Ordering;
+
+use super::Diagnostic;
+
+/// Diagnostics sorted by file, position, code, and message, without duplicates
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticCollection {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a diagnostic in its place in the order, unless an identical one is there already.
+    /// Returns whether it was added.
+    pub fn add(&mut self, diagnostic: Diagnostic) -> bool {
+        match self
+            .diagnostics
+            .binary_search_by(|existing| compare_diagnostics(existing, &diagnostic))
+        {
+            Ok(_) => false,
+            Err(index) => {
+                self.diagnostics.insert(index, diagnostic);
+                true
+            }
+        }
+    }
+
+    /// The diagnostics that are not in a file, like those about the options
+    pub fn get_global_diagnostics(&self) -> &[Diagnostic] {
+        let end = self
+            .diagnostics
+            .partition_point(|diagnostic| diagnostic.file_name.is_none());
+        &self.diagnostics[..end]
+    }
+
+    /// The diagnostics of one file
+    pub fn get_diagnostics(&self, file_name: &str) -> &[Diagnostic] {
+        let file_name = Some(file_name);
+        let start = self
+            .diagnostics
+            .partition_point(|diagnostic| diagnostic.file_name.as_deref() < file_name);
+        let end = self
+            .diagnostics
+            .partition_point(|diagnostic| diagnostic.file_name.as_deref() <= file_name);
+        &self.diagnostics[start..end]
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
+
+impl Extend<Diagnostic> for DiagnosticCollection {
+    fn extend<I: IntoIterator<Item = Diagnostic>>(&mut self, diagnostics: I) {
+        // Sorting once is cheaper than inserting each diagnostic in its place
+        self.diagnostics.extend(diagnostics);
+        self.diagnostics.sort_by(compare_diagnostics);
+        self.diagnostics
+            .dedup_by(|a, b| compare_diagnostics(a, b) == Ordering::Equal);
+    }
+}
+
+impl FromIterator<Diagnostic> for DiagnosticCollection {
+    fn from_iter<I: IntoIterator<Item = Diagnostic>>(diagnostics: I) -> Self {
+        let mut collection = DiagnosticCollection::new();
+        collection.extend(diagnostics);
+        collection
+    }
+}
+
+/// Sorts diagnostics into the order they are reported in, keeping one of each
+pub fn sort_and_deduplicate_diagnostics(
+    diagnostics: impl IntoIterator<Item = Diagnostic>,
+) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .collect::<DiagnosticCollection>()
+        .into_vec()
+}
+
+/// Orders diagnostics by file, with those in no file first, then by where they start and end,
+/// their code, their message, and lastly the chain of related information explaining them
+pub fn compare_diagnostics(a: &Diagnostic, b: &Diagnostic) -> Ordering {
+    a.file_name
+        .cmp(&b.file_name)
+        .then_with(|| (a.line, a.character).cmp(&(b.line, b.character)))
+        .then_with(|| (a.end_line, a.end_character).cmp(&(b.end_line, b.end_character)))
+        .then_with(|| a.code.cmp(&b.code))
+        .then_with(|| a.message.cmp(&b.message))
+        .then_with(|| {
+            a.related_information
+                .len()
+                .cmp(&b.related_information.len())
+        })
+        .then_with(|| {
+            a.related_information
+                .iter()
+                .zip(&b.related_information)
+                .map(|(a, b)| compare_diagnostics(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+        .then_with(|| (a.category as u8).cmp(&(b.category as u8)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::DiagnosticCategory;
+
+    fn diagnostic(file_name: Option<&str>, line: usize, code: u32) -> Diagnostic {
+        Diagnostic {
+            file_name: file_name.map(str::to_string),
+            line,
+            character: 1,
+            end_line: line,
+            end_character: 2,
+            message: format!("Error {code}."),
+            code,
+            category: DiagnosticCategory::Error,
+            related_information: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn sorts_by_file_and_position_and_drops_duplicates() {
+        let mut related = diagnostic(Some("b.ts"), 1, 2451);
+        related.related_information = vec![diagnostic(Some("a.ts"), 3, 6203)];
+        let mut collection: DiagnosticCollection = [
+            diagnostic(Some("b.ts"), 2, 2304),
+            related.clone(),
+            diagnostic(Some("a.ts"), 5, 2322),
+            diagnostic(Some("b.ts"), 1, 2451),
+            diagnostic(None, 0, 6046),
+            diagnostic(Some("a.ts"), 5, 2322),
+        ]
+        .into_iter()
+        .collect();
+        assert!(!collection.add(related.clone()));
+        assert!(collection.add(diagnostic(Some("a.ts"), 1, 1005)));
+
+        let order: Vec<(Option<&str>, u32)> = collection
+            .iter()
+            .map(|diagnostic| (diagnostic.file_name.as_deref(), diagnostic.code))
+            .collect();
+        // A diagnostic with related information is not a duplicate of the same one without
+        assert_eq!(
+            order,
+            [
+                (None, 6046),
+                (Some("a.ts"), 1005),
+                (Some("a.ts"), 2322),
+                (Some("b.ts"), 2451),
+                (Some("b.ts"), 2451),
+                (Some("b.ts"), 2304),
+            ]
+        );
+        assert_eq!(collection.get_global_diagnostics().len(), 1);
+        assert_eq!(collection.get_diagnostics("b.ts").len(), 3);
+        assert!(collection.get_diagnostics("c.ts").is_empty());
+    }
+}
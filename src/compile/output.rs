@@ -0,0 +1,54 @@
+//! Writing output files to disk
+//!
+//! An output file is written to a temporary file next to it, which is then renamed over it, so
+//! a tool reading the output never sees a half-written file, and a compilation that is
+//! interrupted leaves the previous output in place. A file whose content would not change is
+//! not written at all, which keeps its modification time for the build tools that compare
+//! them.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Writes `data` to the file at `path`, creating the folders it is in, unless the file already
+/// has exactly that content
+pub(super) fn write_file_atomically(path: &Path, data: &str) -> io::Result<()> {
+    if fs::read(path).is_ok_and(|existing| existing == data.as_bytes()) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+    let result = fs::write(&temp_path, data).and_then(|()| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_changed_files_and_keeps_unchanged_ones() {
+        let directory = std::env::temp_dir().join(format!("tsrs-output-{}", std::process::id()));
+        let path = directory.join("out/a.js");
+        write_file_atomically(&path, "let a = 1;\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "let a = 1;\n");
+
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        write_file_atomically(&path, "let a = 1;\n").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), modified);
+
+        write_file_atomically(&path, "let a = 2;\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "let a = 2;\n");
+        // No temporary file is left behind
+        assert_eq!(fs::read_dir(directory.join("out")).unwrap().count(), 1);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
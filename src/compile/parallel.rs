@@ -0,0 +1,61 @@
+//! Running the work of a program over its files on all cores
+//!
+//! Each worker thread takes the next unclaimed item until none are left, so a worker that
+//! finishes a small file moves on to the next one while others are still on large files.
+//! Each worker keeps its own results, tagged with the index of their item, and these are put
+//! back in the order of the items once all workers are done, so the output of a compilation
+//! does not depend on scheduling.
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Applies `f` to every item, on as many threads as there are cores unless `single_threaded`
+/// is set, and returns the results in the order of the items
+pub(crate) fn map_in_parallel<T, R, F>(items: &[T], single_threaded: bool, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(items.len());
+    if single_threaded || threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let worker = || {
+        let mut results = Vec::new();
+        loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(item) = items.get(index) else {
+                break;
+            };
+            results.push((index, f(item)));
+        }
+        results
+    };
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads).map(|_| scope.spawn(worker)).collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+    results.sort_unstable_by_key(|&(index, _)| index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_order_of_the_items() {
+        let items: Vec<usize> = (0..100).collect();
+        let squares = map_in_parallel(&items, false, |&item| item * item);
+        assert_eq!(squares, map_in_parallel(&items, true, |&item| item * item));
+        assert_eq!(squares[9], 81);
+    }
+}
@@ -0,0 +1,92 @@
+//! Timing the phases of a compilation, for `--diagnostics` and `--extendedDiagnostics`
+//!
+//! As in tsc, the timers are global, so that the phases can be timed where they happen without
+//! threading a recorder through the compiler, and they cost nothing until they are enabled.
+//! Work done on several threads at once, like parsing, adds up the time of every thread.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A phase of a compilation that is timed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    IoRead,
+    IoWrite,
+    Parse,
+    ResolveModule,
+    Bind,
+    Check,
+    Emit,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 7] = [
+        Phase::IoRead,
+        Phase::IoWrite,
+        Phase::Parse,
+        Phase::ResolveModule,
+        Phase::Bind,
+        Phase::Check,
+        Phase::Emit,
+    ];
+
+    /// The name of the phase, as tsc reports it
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::IoRead => "I/O read",
+            Phase::IoWrite => "I/O write",
+            Phase::Parse => "Parse time",
+            Phase::ResolveModule => "ResolveModule time",
+            Phase::Bind => "Bind time",
+            Phase::Check => "Check time",
+            Phase::Emit => "Emit time",
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The time spent in each phase so far, in nanoseconds, in the order of [`Phase::ALL`]
+static NANOS: [AtomicU64; Phase::ALL.len()] = [const { AtomicU64::new(0) }; Phase::ALL.len()];
+
+/// Starts timing the phases, from zero
+pub fn enable() {
+    for nanos in &NANOS {
+        nanos.store(0, Ordering::Relaxed);
+    }
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, adding the time it takes to the phase when timing is enabled
+pub fn measure<R>(phase: Phase, f: impl FnOnce() -> R) -> R {
+    if !is_enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed().as_nanos() as u64;
+    NANOS[phase as usize].fetch_add(elapsed, Ordering::Relaxed);
+    result
+}
+
+/// The time spent in a phase since timing was enabled
+pub fn get_duration(phase: Phase) -> Duration {
+    Duration::from_nanos(NANOS[phase as usize].load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_phases_in_the_order_of_their_timers() {
+        for (index, phase) in Phase::ALL.iter().enumerate() {
+            assert_eq!(*phase as usize, index);
+        }
+        assert_eq!(Phase::Check.name(), "Check time");
+    }
+}
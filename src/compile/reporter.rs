@@ -0,0 +1,683 @@
+//! Reporting the diagnostics of a compilation
+//!
+//! People read diagnostics as text on a terminal, while CI systems read them as JSON, or as
+//! SARIF to upload to code scanning. Every format is a [`DiagnosticReporter`] that writes the
+//! whole list of diagnostics at once, since the machine-readable ones are single documents.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use super::{CompilerHost, Diagnostic, DiagnosticCategory, relative_path};
+use crate::compiler::diagnostics::{self, Message};
+use crate::config::write_json_string;
+
+/// The format diagnostics are reported in
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DiagnosticFormatKind {
+    /// Text for people, colored when `--pretty` is set
+    #[default]
+    Pretty,
+    /// An array of diagnostics with their locations and related information
+    Json,
+    /// A SARIF 2.1.0 log, for code scanning
+    Sarif,
+}
+
+/// Writes the diagnostics of a compilation in some format
+pub trait DiagnosticReporter {
+    fn report(&self, diagnostics: &[Diagnostic], out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Reports diagnostics as lines of text, followed by a summary of the errors. Pretty text shows
+/// the source each diagnostic is about, read through the host, underlined as tsc does.
+pub struct TextReporter<'a> {
+    pub pretty: bool,
+    pub host: &'a dyn CompilerHost,
+}
+
+/// Reports diagnostics as a JSON array
+pub struct JsonReporter;
+
+/// Reports diagnostics as a SARIF log with a single run of the compiler
+pub struct SarifReporter;
+
+/// Returns the reporter for a format
+pub fn create_reporter<'a>(
+    format: DiagnosticFormatKind,
+    pretty: bool,
+    host: &'a dyn CompilerHost,
+) -> Box<dyn DiagnosticReporter + 'a> {
+    match format {
+        DiagnosticFormatKind::Pretty => Box::new(TextReporter { pretty, host }),
+        DiagnosticFormatKind::Json => Box::new(JsonReporter),
+        DiagnosticFormatKind::Sarif => Box::new(SarifReporter),
+    }
+}
+
+/// Writes the diagnostics to standard output in the given format
+pub fn report_diagnostics(
+    diagnostics: &[Diagnostic],
+    format: DiagnosticFormatKind,
+    pretty: bool,
+    host: &dyn CompilerHost,
+) {
+    let reporter = create_reporter(format, pretty, host);
+    let mut out = io::stdout().lock();
+    // Like `println!`, but a closed pipe ends the report instead of a panic
+    let _ = reporter.report(diagnostics, &mut out);
+}
+
+/// Prints a diagnostic as text, with the source it is about when `pretty` is set
+pub fn print_diagnostic(diagnostic: &Diagnostic, pretty: bool, host: &dyn CompilerHost) {
+    let reporter = TextReporter { pretty, host };
+    let _ = reporter.write_diagnostic(diagnostic, &mut io::stdout().lock());
+}
+
+// The escape sequences of the colors tsc uses in pretty output
+const RED: &str = "\x1b[91m";
+const YELLOW: &str = "\x1b[93m";
+const BLUE: &str = "\x1b[94m";
+const CYAN: &str = "\x1b[96m";
+const GREY: &str = "\x1b[90m";
+const GUTTER: &str = "\x1b[7m";
+const RESET: &str = "\x1b[0m";
+
+// Spans longer than this show only their first and last two lines
+const MAX_CODE_SPAN_LINES: usize = 5;
+
+fn category_color(category: DiagnosticCategory) -> &'static str {
+    match category {
+        DiagnosticCategory::Error => RED,
+        DiagnosticCategory::Warning => YELLOW,
+        DiagnosticCategory::Suggestion => GREY,
+        DiagnosticCategory::Message => BLUE,
+    }
+}
+
+fn colored(text: &str, color: &str) -> String {
+    format!("{color}{text}{RESET}")
+}
+
+impl TextReporter<'_> {
+    /// Writes a single diagnostic, without the summary
+    pub fn write_diagnostic(&self, diagnostic: &Diagnostic, out: &mut dyn Write) -> io::Result<()> {
+        if !self.pretty {
+            return match &diagnostic.file_name {
+                Some(file_name) => writeln!(
+                    out,
+                    "{}({}:{}) - {} TS{}: {}",
+                    self.display_file_name(file_name),
+                    diagnostic.line,
+                    diagnostic.character,
+                    category_name(diagnostic.category),
+                    diagnostic.code,
+                    diagnostic.message
+                ),
+                None => writeln!(
+                    out,
+                    "{} TS{}: {}",
+                    category_name(diagnostic.category),
+                    diagnostic.code,
+                    diagnostic.message
+                ),
+            };
+        }
+
+        let color = category_color(diagnostic.category);
+        let mut text = String::new();
+        if let Some(file_name) = &diagnostic.file_name {
+            text += &self.format_location(file_name, diagnostic.line, diagnostic.character);
+            text += " - ";
+        }
+        text += &colored(category_name(diagnostic.category), color);
+        text += &colored(&format!(" TS{}: ", diagnostic.code), GREY);
+        text += &diagnostic.message;
+        if diagnostic.file_name.is_some() {
+            text += "\n";
+            text += &self.format_code_span(diagnostic, "", color);
+        }
+        if !diagnostic.related_information.is_empty() {
+            text += "\n";
+            for related in &diagnostic.related_information {
+                if let Some(file_name) = &related.file_name {
+                    text += "\n  ";
+                    text += &self.format_location(file_name, related.line, related.character);
+                    text += &self.format_code_span(related, "    ", CYAN);
+                }
+                text += "\n    ";
+                text += &related.message;
+            }
+        }
+        // A blank line separates the diagnostics
+        writeln!(out, "{text}\n")
+    }
+
+    fn display_file_name(&self, file_name: &str) -> String {
+        // Files of the program are named relative to the working folder already, but files found
+        // through absolute paths, like the default library, are not
+        if Path::new(file_name).is_absolute() {
+            relative_path(
+                Path::new(&self.host.get_current_directory()),
+                Path::new(file_name),
+                !self.host.use_case_sensitive_file_names(),
+            )
+        } else {
+            file_name.to_string()
+        }
+    }
+
+    fn format_location(&self, file_name: &str, line: usize, character: usize) -> String {
+        format!(
+            "{}:{}:{}",
+            colored(&self.display_file_name(file_name), CYAN),
+            colored(&line.to_string(), YELLOW),
+            colored(&character.to_string(), YELLOW)
+        )
+    }
+
+    // Show the lines of the diagnostic, each followed by a line that underlines its part of the
+    // span, with the line numbers in a gutter on the left
+    fn format_code_span(&self, diagnostic: &Diagnostic, indent: &str, color: &str) -> String {
+        let Some(text) = diagnostic
+            .file_name
+            .as_deref()
+            .and_then(|file_name| self.host.read_file(file_name))
+        else {
+            return String::new();
+        };
+        let lines: Vec<&str> = text
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+        let first_line = diagnostic.line.max(1);
+        let last_line = diagnostic
+            .end_line
+            .clamp(first_line, lines.len().max(first_line));
+        let gutter_width = last_line.to_string().len();
+        let gutter = |label: &str| colored(&format!("{label:>gutter_width$}"), GUTTER);
+
+        let mut span = String::new();
+        let mut line = first_line;
+        while line <= last_line {
+            if last_line - first_line >= MAX_CODE_SPAN_LINES && line == first_line + 2 {
+                span += &format!("\n{indent}{} ", gutter("..."));
+                line = last_line - 1;
+            }
+            // Tabs become single spaces, so that the underline stays below the text
+            let content = lines
+                .get(line - 1)
+                .copied()
+                .unwrap_or("")
+                .replace('\t', " ");
+            let content = content.trim_end();
+            let start = if line == first_line {
+                column(content, diagnostic.character)
+            } else {
+                0
+            };
+            let end = if line == last_line {
+                column(content, diagnostic.end_character)
+            } else {
+                content.chars().count()
+            };
+            // An empty span still gets a mark where it is
+            let end = end.max(start + usize::from(first_line == last_line));
+            span += &format!("\n{indent}{} {content}", gutter(&line.to_string()));
+            span += &format!(
+                "\n{indent}{} {}{}",
+                gutter(""),
+                " ".repeat(start),
+                colored(&"~".repeat(end - start), color)
+            );
+            line += 1;
+        }
+        span
+    }
+
+    // Summarize the errors like tsc: where the only error is, where the errors in a single file
+    // start, or how many errors each file has
+    fn write_error_summary(
+        &self,
+        diagnostics: &[Diagnostic],
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let errors: Vec<&Diagnostic> = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.category == DiagnosticCategory::Error)
+            .collect();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        // The files with errors, in the order of their first error, with the number of errors
+        let mut files: Vec<(&Diagnostic, usize)> = Vec::new();
+        for error in errors.iter().filter(|error| error.file_name.is_some()) {
+            match files
+                .iter_mut()
+                .find(|(first, _)| first.file_name == error.file_name)
+            {
+                Some((_, count)) => *count += 1,
+                None => files.push((error, 1)),
+            }
+        }
+        let file_reference = |error: &Diagnostic| {
+            format!(
+                "{}{}",
+                self.display_file_name(error.file_name.as_deref().unwrap_or_default()),
+                colored(&format!(":{}", error.line), GREY)
+            )
+        };
+
+        let message =
+            |message: &Message, args: &[String]| diagnostics::format_message(message, args);
+        let summary = match (errors.len(), files.first()) {
+            (1, Some((error, _))) => message(
+                diagnostics::FOUND_1_ERROR_IN_0_6259,
+                &[file_reference(error)],
+            ),
+            (1, None) => message(diagnostics::FOUND_1_ERROR_6216, &[]),
+            (count, None) => message(diagnostics::FOUND_0_ERRORS_6217, &[count.to_string()]),
+            (count, Some((error, _))) if files.len() == 1 => message(
+                diagnostics::FOUND_0_ERRORS_IN_THE_SAME_FILE_STARTING_AT_COLON_1_6260,
+                &[count.to_string(), file_reference(error)],
+            ),
+            (count, Some(_)) => message(
+                diagnostics::FOUND_0_ERRORS_IN_1_FILES_6261,
+                &[count.to_string(), files.len().to_string()],
+            ),
+        };
+        writeln!(out, "\n{summary}\n")?;
+
+        if files.len() > 1 {
+            let header = diagnostics::ERRORS_FILES_6041.message();
+            let count_width = header.split(' ').next().unwrap_or_default().len();
+            writeln!(out, "{header}")?;
+            for (error, count) in &files {
+                writeln!(out, "{count:>count_width$}  {}", file_reference(error))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// The column of a 1-based character of a line, in characters from the start of the line
+fn column(content: &str, character: usize) -> usize {
+    let mut offset = character.saturating_sub(1).min(content.len());
+    while !content.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    content[..offset].chars().count()
+}
+
+impl DiagnosticReporter for TextReporter<'_> {
+    fn report(&self, diagnostics: &[Diagnostic], out: &mut dyn Write) -> io::Result<()> {
+        if diagnostics.is_empty() {
+            return writeln!(out, "Compilation completed successfully.");
+        }
+
+        let mut error_count = 0;
+        let mut warning_count = 0;
+        for diagnostic in diagnostics {
+            match diagnostic.category {
+                DiagnosticCategory::Error => error_count += 1,
+                DiagnosticCategory::Warning => warning_count += 1,
+                _ => continue,
+            }
+            self.write_diagnostic(diagnostic, out)?;
+        }
+
+        if self.pretty {
+            return self.write_error_summary(diagnostics, out);
+        }
+        writeln!(
+            out,
+            "Found {} error(s), {} warning(s)",
+            error_count, warning_count
+        )
+    }
+}
+
+impl DiagnosticReporter for JsonReporter {
+    fn report(&self, diagnostics: &[Diagnostic], out: &mut dyn Write) -> io::Result<()> {
+        let json = Json::Array(diagnostics.iter().map(diagnostic_json).collect());
+        writeln!(out, "{}", json.to_pretty_string())
+    }
+}
+
+fn diagnostic_json(diagnostic: &Diagnostic) -> Json {
+    let position = |line, character| {
+        Json::Object(vec![
+            ("line", Json::Number(line)),
+            ("character", Json::Number(character)),
+        ])
+    };
+    Json::Object(vec![
+        (
+            "file",
+            diagnostic
+                .file_name
+                .as_ref()
+                .map_or(Json::Null, |file_name| Json::String(file_name.clone())),
+        ),
+        ("start", position(diagnostic.line, diagnostic.character)),
+        (
+            "end",
+            position(diagnostic.end_line, diagnostic.end_character),
+        ),
+        ("code", Json::Number(diagnostic.code as usize)),
+        (
+            "category",
+            Json::String(category_name(diagnostic.category).to_string()),
+        ),
+        ("message", Json::String(diagnostic.message.clone())),
+        (
+            "relatedInformation",
+            Json::Array(
+                diagnostic
+                    .related_information
+                    .iter()
+                    .map(diagnostic_json)
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+fn category_name(category: DiagnosticCategory) -> &'static str {
+    match category {
+        DiagnosticCategory::Error => "error",
+        DiagnosticCategory::Warning => "warning",
+        DiagnosticCategory::Suggestion => "suggestion",
+        DiagnosticCategory::Message => "message",
+    }
+}
+
+impl DiagnosticReporter for SarifReporter {
+    fn report(&self, diagnostics: &[Diagnostic], out: &mut dyn Write) -> io::Result<()> {
+        // Each diagnostic code is a rule, listed once in the order of the codes
+        let mut codes: Vec<u32> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        codes.sort_unstable();
+        codes.dedup();
+        let rules = codes
+            .iter()
+            .map(|&code| Json::Object(vec![("id", Json::String(rule_id(code)))]))
+            .collect();
+
+        let driver = Json::Object(vec![
+            ("name", Json::String("tsrs".to_string())),
+            ("version", Json::String("5.8.2".to_string())),
+            ("rules", Json::Array(rules)),
+        ]);
+        let run = Json::Object(vec![
+            ("tool", Json::Object(vec![("driver", driver)])),
+            (
+                "results",
+                Json::Array(
+                    diagnostics
+                        .iter()
+                        .map(|diagnostic| sarif_result(diagnostic, &codes))
+                        .collect(),
+                ),
+            ),
+        ]);
+        let log = Json::Object(vec![
+            (
+                "$schema",
+                Json::String("https://json.schemastore.org/sarif-2.1.0.json".to_string()),
+            ),
+            ("version", Json::String("2.1.0".to_string())),
+            ("runs", Json::Array(vec![run])),
+        ]);
+        writeln!(out, "{}", log.to_pretty_string())
+    }
+}
+
+fn rule_id(code: u32) -> String {
+    format!("TS{}", code)
+}
+
+fn sarif_result(diagnostic: &Diagnostic, codes: &[u32]) -> Json {
+    let level = match diagnostic.category {
+        DiagnosticCategory::Error => "error",
+        DiagnosticCategory::Warning => "warning",
+        DiagnosticCategory::Suggestion | DiagnosticCategory::Message => "note",
+    };
+    let mut result = vec![
+        ("ruleId", Json::String(rule_id(diagnostic.code))),
+        (
+            "ruleIndex",
+            Json::Number(codes.binary_search(&diagnostic.code).unwrap_or_default()),
+        ),
+        ("level", Json::String(level.to_string())),
+        ("message", sarif_message(&diagnostic.message)),
+    ];
+    // Diagnostics about the options rather than a file have no location
+    if let Some(location) = sarif_location(diagnostic) {
+        result.push(("locations", Json::Array(vec![location])));
+    }
+    let related_locations: Vec<Json> = diagnostic
+        .related_information
+        .iter()
+        .filter_map(|related| {
+            let Json::Object(mut location) = sarif_location(related)? else {
+                return None;
+            };
+            location.push(("message", sarif_message(&related.message)));
+            Some(Json::Object(location))
+        })
+        .collect();
+    if !related_locations.is_empty() {
+        result.push(("relatedLocations", Json::Array(related_locations)));
+    }
+    Json::Object(result)
+}
+
+fn sarif_message(text: &str) -> Json {
+    Json::Object(vec![("text", Json::String(text.to_string()))])
+}
+
+fn sarif_location(diagnostic: &Diagnostic) -> Option<Json> {
+    let file_name = diagnostic.file_name.as_ref()?;
+    let region = Json::Object(vec![
+        ("startLine", Json::Number(diagnostic.line)),
+        ("startColumn", Json::Number(diagnostic.character)),
+        ("endLine", Json::Number(diagnostic.end_line)),
+        ("endColumn", Json::Number(diagnostic.end_character)),
+    ]);
+    let physical_location = Json::Object(vec![
+        (
+            "artifactLocation",
+            // SARIF locations are URIs, which only use forward slashes
+            Json::Object(vec![("uri", Json::String(file_name.replace('\\', "/")))]),
+        ),
+        ("region", region),
+    ]);
+    Some(Json::Object(vec![("physicalLocation", physical_location)]))
+}
+
+/// The few JSON values the reporters write, with the members of objects in the order given
+enum Json {
+    Null,
+    Number(usize),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Number(number) => out.push_str(&number.to_string()),
+            Json::String(text) => write_json_string(text, out),
+            Json::Array(items) if items.is_empty() => out.push_str("[]"),
+            Json::Array(items) => {
+                out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    write_line_break(out, indent + 1);
+                    item.write(out, indent + 1);
+                }
+                write_line_break(out, indent);
+                out.push(']');
+            }
+            Json::Object(members) if members.is_empty() => out.push_str("{}"),
+            Json::Object(members) => {
+                out.push('{');
+                for (index, (name, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    write_line_break(out, indent + 1);
+                    write_json_string(name, out);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                }
+                write_line_break(out, indent);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn write_line_break(out: &mut String, indent: usize) {
+    out.push('\n');
+    out.push_str(&"  ".repeat(indent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::VirtualCompilerHost;
+
+    fn diagnostic(file_name: &str, line: usize, code: u32, message: &str) -> Diagnostic {
+        Diagnostic {
+            file_name: Some(file_name.to_string()),
+            line,
+            character: 7,
+            end_line: line,
+            end_character: 8,
+            message: message.to_string(),
+            code,
+            category: DiagnosticCategory::Error,
+            related_information: Vec::new(),
+        }
+    }
+
+    fn report(reporter: &dyn DiagnosticReporter, diagnostics: &[Diagnostic]) -> String {
+        let mut out = Vec::new();
+        reporter.report(diagnostics, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn reports_pretty_text_with_code_frames_and_a_summary_per_file() {
+        let host = VirtualCompilerHost::with_files(&[
+            ("a.ts", "let x = 1;\nconst y = x;\n"),
+            ("b.ts", "let x = 2;\n"),
+        ]);
+        let mut error = diagnostic(
+            "a.ts",
+            1,
+            2451,
+            "Cannot redeclare block-scoped variable 'x'.",
+        );
+        error.character = 5;
+        error.end_character = 6;
+        error.related_information =
+            vec![diagnostic("b.ts", 1, 6203, "'x' was also declared here.")];
+        let mut other = diagnostic(
+            "/b.ts",
+            1,
+            2451,
+            "Cannot redeclare block-scoped variable 'x'.",
+        );
+        other.character = 5;
+        other.end_character = 6;
+
+        let text = report(
+            &TextReporter {
+                pretty: true,
+                host: &host,
+            },
+            &[error, other],
+        );
+        let plain = text.replace(RESET, "").replace(GUTTER, "");
+        let plain = [RED, YELLOW, CYAN, GREY]
+            .iter()
+            .fold(plain, |text, color| text.replace(color, ""));
+        assert_eq!(
+            plain,
+            "a.ts:1:5 - error TS2451: Cannot redeclare block-scoped variable 'x'.\n\n\
+             1 let x = 1;\n      ~\n\n  \
+             b.ts:1:7\n    1 let x = 2;\n            ~\n    'x' was also declared here.\n\n\
+             b.ts:1:5 - error TS2451: Cannot redeclare block-scoped variable 'x'.\n\n\
+             1 let x = 2;\n      ~\n\n\n\
+             Found 2 errors in 2 files.\n\n\
+             Errors  Files\n     1  a.ts:1\n     1  b.ts:1\n"
+        );
+    }
+
+    #[test]
+    fn reports_locations_and_related_information_as_json() {
+        let mut error = diagnostic(
+            "a.ts",
+            2,
+            2451,
+            "Cannot redeclare block-scoped variable 'x'.",
+        );
+        error.related_information =
+            vec![diagnostic("b.ts", 1, 6203, "'x' was also declared here.")];
+        let json = report(&JsonReporter, &[error]);
+        assert!(
+            json.starts_with(
+                "[\n  {\n    \"file\": \"a.ts\",\n    \"start\": {\n      \"line\": 2,"
+            )
+        );
+        assert!(json.contains("\"code\": 2451,\n    \"category\": \"error\","));
+        assert!(json.contains("\"relatedInformation\": [\n      {\n        \"file\": \"b.ts\","));
+        assert_eq!(report(&JsonReporter, &[]), "[]\n");
+    }
+
+    #[test]
+    fn reports_rules_and_results_as_sarif() {
+        let mut error = diagnostic(
+            "src\\a.ts",
+            3,
+            2322,
+            "Type 'string' is not assignable to type \"number\".",
+        );
+        error.related_information = vec![diagnostic(
+            "src\\a.ts",
+            1,
+            6500,
+            "The expected type comes from here.",
+        )];
+        let sarif = report(
+            &SarifReporter,
+            &[error, diagnostic("b.ts", 1, 2304, "Cannot find name 'y'.")],
+        );
+        assert!(sarif.contains("\"version\": \"2.1.0\""));
+        // Rules are sorted by code, and results point at them
+        assert!(sarif.contains("\"rules\": [\n            {\n              \"id\": \"TS2304\"\n            },\n            {\n              \"id\": \"TS2322\""));
+        assert!(sarif.contains(
+            "\"ruleId\": \"TS2322\",\n          \"ruleIndex\": 1,\n          \"level\": \"error\","
+        ));
+        assert!(
+            sarif.contains("\"text\": \"Type 'string' is not assignable to type \\\"number\\\".\"")
+        );
+        assert!(sarif.contains("\"uri\": \"src/a.ts\""));
+        assert!(sarif.contains("\"startLine\": 3,\n                  \"startColumn\": 7,"));
+        assert!(sarif.contains("\"relatedLocations\""));
+    }
+}
@@ -0,0 +1,269 @@
+//! The statistics `--diagnostics` and `--extendedDiagnostics` print after a compilation
+//!
+//! The counts describe the program: its files and lines, the nodes of their syntax trees, and
+//! what checking created. The times come from the [`performance`](super::performance) timers.
+
+use std::ops::{AddAssign, SubAssign};
+use std::path::Path;
+use std::time::Duration;
+
+use super::Program;
+use super::performance::{self, Phase};
+use crate::compiler::ast::SyntaxKind;
+use crate::compiler::ast::visitor::for_each_descendant;
+use crate::compiler::checker::{Checker, Relation};
+
+/// What checking a program created, summed over its checkers
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CheckStatistics {
+    /// Symbols created while checking, beyond those of the binder
+    pub symbols: usize,
+    pub types: usize,
+    pub instantiations: usize,
+    pub assignability_cache_size: usize,
+    pub identity_cache_size: usize,
+    pub subtype_cache_size: usize,
+    pub comparable_cache_size: usize,
+}
+
+impl CheckStatistics {
+    /// The statistics of a checker that started from `bound_symbols` symbols of the binder
+    pub(crate) fn of_checker(checker: &Checker, bound_symbols: usize) -> Self {
+        CheckStatistics {
+            symbols: checker.get_symbol_count().saturating_sub(bound_symbols),
+            types: checker.get_type_count(),
+            instantiations: checker.get_instantiation_count(),
+            assignability_cache_size: checker.get_relation_cache_size(Relation::Assignable),
+            identity_cache_size: checker.get_relation_cache_size(Relation::Identity),
+            subtype_cache_size: checker.get_relation_cache_size(Relation::Subtype),
+            comparable_cache_size: checker.get_relation_cache_size(Relation::Comparable),
+        }
+    }
+}
+
+impl AddAssign for CheckStatistics {
+    fn add_assign(&mut self, other: Self) {
+        self.symbols += other.symbols;
+        self.types += other.types;
+        self.instantiations += other.instantiations;
+        self.assignability_cache_size += other.assignability_cache_size;
+        self.identity_cache_size += other.identity_cache_size;
+        self.subtype_cache_size += other.subtype_cache_size;
+        self.comparable_cache_size += other.comparable_cache_size;
+    }
+}
+
+impl SubAssign for CheckStatistics {
+    fn sub_assign(&mut self, other: Self) {
+        self.symbols -= other.symbols;
+        self.types -= other.types;
+        self.instantiations -= other.instantiations;
+        self.assignability_cache_size -= other.assignability_cache_size;
+        self.identity_cache_size -= other.identity_cache_size;
+        self.subtype_cache_size -= other.subtype_cache_size;
+        self.comparable_cache_size -= other.comparable_cache_size;
+    }
+}
+
+/// Prints the statistics of a compiled program, in the layout of tsc, with more of them when
+/// `extended` is set. `total_time` is the time of the whole compilation.
+pub fn report_statistics(program: &Program, extended: bool, total_time: Duration) {
+    for line in format_statistics(&collect_statistics(program, extended, total_time)) {
+        println!("{line}");
+    }
+}
+
+fn collect_statistics(
+    program: &Program,
+    extended: bool,
+    total_time: Duration,
+) -> Vec<(String, String)> {
+    let count = |name: &str, value: usize| (name.to_string(), value.to_string());
+    let mut statistics = vec![count("Files", program.source_files.len())];
+    if extended {
+        for (kind, lines) in count_lines_by_kind(program) {
+            statistics.push(count(&format!("Lines of {kind}"), lines));
+        }
+    } else {
+        let lines = program
+            .source_files
+            .iter()
+            .map(|source_file| source_file.line_map.len())
+            .sum();
+        statistics.push(count("Lines", lines));
+    }
+
+    let (nodes, identifiers) = count_nodes(program);
+    if extended {
+        statistics.push(count("Nodes", nodes));
+    }
+    statistics.push(count("Identifiers", identifiers));
+    let check_statistics = program.get_check_statistics();
+    statistics.extend([
+        count(
+            "Symbols",
+            program.bindings.symbols.len() + check_statistics.symbols,
+        ),
+        count("Types", check_statistics.types),
+        count("Instantiations", check_statistics.instantiations),
+    ]);
+    if let Some(memory) = memory_used_in_kilobytes() {
+        statistics.push(("Memory used".to_string(), format!("{memory}K")));
+    }
+    if extended {
+        statistics.extend([
+            count(
+                "Assignability cache size",
+                check_statistics.assignability_cache_size,
+            ),
+            count("Identity cache size", check_statistics.identity_cache_size),
+            count("Subtype cache size", check_statistics.subtype_cache_size),
+            count(
+                "Comparable cache size",
+                check_statistics.comparable_cache_size,
+            ),
+        ]);
+    }
+
+    for phase in Phase::ALL {
+        // Module resolution is part of building the program, which tsc only breaks down when
+        // asked for the extended statistics
+        if phase == Phase::ResolveModule && !extended {
+            continue;
+        }
+        statistics.push((
+            phase.name().to_string(),
+            format_seconds(performance::get_duration(phase)),
+        ));
+    }
+    statistics.push(("Total time".to_string(), format_seconds(total_time)));
+    statistics
+}
+
+// Align the names on the left and the values on the right
+fn format_statistics(statistics: &[(String, String)]) -> Vec<String> {
+    let name_width = statistics
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let value_width = statistics
+        .iter()
+        .map(|(_, value)| value.len())
+        .max()
+        .unwrap_or(0);
+    statistics
+        .iter()
+        .map(|(name, value)| format!("{:<name_width$}{value:>value_width$}", format!("{name}:")))
+        .collect()
+}
+
+fn format_seconds(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
+
+// Count the lines of the standard library, of declaration files, and of each language
+fn count_lines_by_kind(program: &Program) -> Vec<(&'static str, usize)> {
+    let mut lines = vec![
+        ("Library", 0),
+        ("Definitions", 0),
+        ("TypeScript", 0),
+        ("JavaScript", 0),
+        ("JSON", 0),
+        ("Other", 0),
+    ];
+    for source_file in &program.source_files {
+        let file_name = &source_file.file_name;
+        let extension = Path::new(file_name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        let kind = if program.is_default_lib_file(file_name) {
+            0
+        } else if file_name.ends_with(".d.ts")
+            || file_name.ends_with(".d.mts")
+            || file_name.ends_with(".d.cts")
+        {
+            1
+        } else {
+            match extension {
+                "ts" | "tsx" | "mts" | "cts" => 2,
+                "js" | "jsx" | "mjs" | "cjs" => 3,
+                "json" => 4,
+                _ => 5,
+            }
+        };
+        lines[kind].1 += source_file.line_map.len();
+    }
+    lines
+}
+
+// Count the nodes of the syntax trees of the program, and the identifiers among them
+fn count_nodes(program: &Program) -> (usize, usize) {
+    let mut nodes = 0;
+    let mut identifiers = 0;
+    for tree in &program.syntax_trees {
+        nodes += 1;
+        for_each_descendant(tree, &mut |node| {
+            nodes += 1;
+            if node.kind == SyntaxKind::Identifier {
+                identifiers += 1;
+            }
+            false
+        });
+    }
+    (nodes, identifiers)
+}
+
+// The memory the process holds, where the operating system reports it
+fn memory_used_in_kilobytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::cli::{Cli, create_compiler_options};
+    use crate::compile::{VirtualCompilerHost, create_program, read_source_files, type_check};
+    use crate::compiler::cancellation::CancellationToken;
+
+    #[test]
+    fn counts_the_files_lines_and_identifiers_of_a_program() {
+        let host = VirtualCompilerHost::with_files(&[
+            ("a.ts", "import { b } from \"./b\";\nlet a = b;\n"),
+            ("b.d.ts", "export declare const b: number;\n"),
+        ]);
+        let cli = Cli::parse_from(["tsrs", "a.ts"]);
+        let source_files = read_source_files(&cli.files, &host);
+        let mut program = create_program(&source_files, &create_compiler_options(&cli), &host);
+        type_check(&mut program, &CancellationToken::new()).unwrap();
+
+        let statistics = collect_statistics(&program, true, Duration::from_millis(1234));
+        let value = |name: &str| {
+            statistics
+                .iter()
+                .find(|(statistic, _)| statistic == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(value("Files"), Some("2"));
+        assert_eq!(value("Lines of TypeScript"), Some("3"));
+        assert_eq!(value("Lines of Definitions"), Some("2"));
+        assert_eq!(value("Identifiers"), Some("4"));
+        assert!(program.get_check_statistics().types > 0);
+        assert_eq!(value("Total time"), Some("1.23s"));
+
+        let lines = format_statistics(&statistics[..2]);
+        assert_eq!(lines[0], "Files:            2");
+    }
+}
@@ -0,0 +1,125 @@
+//! Transpiling one file on its own, for tools that only need TypeScript syntax removed
+//!
+//! Nothing is bound or checked and no other file is read, so a file is transformed with only
+//! what its syntax says, as with `isolatedModules`. The only diagnostics are those of parsing
+//! the file.
+
+use super::{
+    Diagnostic, SourceFile, compute_line_map, convert_diagnostic, output_extension,
+    print_javascript, printer_options, transform_options,
+};
+use crate::cli::CompilerOptions;
+use crate::compiler::binder::remove_file_extension;
+use crate::compiler::parser::parse_source_file;
+use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+use crate::compiler::transformers::{JsxEmit, transform_file};
+
+/// How [`transpile_module`] transpiles a file
+#[derive(Clone, Default)]
+pub struct TranspileOptions {
+    pub compiler_options: CompilerOptions,
+    /// The name of the file, which decides how it is parsed and names its source map. It is
+    /// `module.ts`, or `module.tsx` when JSX is configured, when there is none.
+    pub file_name: Option<String>,
+}
+
+/// The JavaScript a file was transpiled to
+#[derive(Debug, Clone)]
+pub struct TranspileOutput {
+    pub output_text: String,
+    /// The source map of the output, when the options ask for one
+    pub source_map_text: Option<String>,
+    /// The syntax errors of the file
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Transpiles the text of a TypeScript file to JavaScript without checking it
+pub fn transpile_module(source_text: &str, options: &TranspileOptions) -> TranspileOutput {
+    let compiler_options = &options.compiler_options;
+    let file_name = options.file_name.clone().unwrap_or_else(|| {
+        if compiler_options.jsx == JsxEmit::None {
+            "module.ts".to_string()
+        } else {
+            "module.tsx".to_string()
+        }
+    });
+    let source_file = SourceFile {
+        line_map: compute_line_map(source_text),
+        file_name,
+        text: source_text.to_string(),
+    };
+    let tree = parse_source_file(
+        &source_file.file_name,
+        source_text,
+        ScriptTarget::ESNext,
+        ScriptKind::Unknown,
+    );
+    let diagnostics = tree
+        .as_source_file()
+        .parse_diagnostics
+        .iter()
+        .map(|diagnostic| convert_diagnostic(&source_file, diagnostic))
+        .collect();
+
+    let js_path = format!(
+        "{}{}",
+        remove_file_extension(&source_file.file_name),
+        output_extension(&source_file.file_name, compiler_options.jsx)
+    );
+    // Other files may use the file's const enums, which only exist if they are emitted
+    let mut transform_options = transform_options(compiler_options);
+    transform_options.preserve_const_enums = true;
+    let tree = transform_file(&tree, &transform_options);
+    let (output_text, source_map_text) = print_javascript(
+        "/",
+        &js_path,
+        &[(&source_file, tree)],
+        &printer_options(compiler_options),
+        compiler_options.source_map,
+        false,
+    );
+    TranspileOutput {
+        output_text,
+        source_map_text,
+        diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::transformers::ModuleKind;
+
+    #[test]
+    fn strips_types_without_checking() {
+        let options = TranspileOptions {
+            compiler_options: CompilerOptions {
+                module: Some(ModuleKind::CommonJS),
+                source_map: true,
+                ..CompilerOptions::default()
+            },
+            file_name: Some("src/a.ts".to_string()),
+        };
+        let output = transpile_module(
+            "import { b } from \"./b\";\nlet a: string = 1;\nexport const c = b as number;\n",
+            &options,
+        );
+        assert!(output.diagnostics.is_empty());
+        assert!(!output.output_text.contains(": string"));
+        assert!(output.output_text.contains("require(\"./b\")"));
+        assert!(
+            output
+                .output_text
+                .ends_with("//# sourceMappingURL=a.js.map")
+        );
+        assert!(
+            output
+                .source_map_text
+                .unwrap()
+                .contains("\"sources\":[\"a.ts\"]")
+        );
+
+        let output = transpile_module("let a = ;\n", &TranspileOptions::default());
+        assert_eq!(output.diagnostics[0].code, 1109);
+    }
+}
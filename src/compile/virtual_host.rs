@@ -0,0 +1,414 @@
+//! A compiler host over files in memory, for embedding the compiler and for tests
+//!
+//! Paths are resolved against the working directory of the host, and a folder exists while
+//! there are files in it. Writes are kept in memory and recorded, so that the output of a
+//! compilation can be inspected without touching the file system. Modification times come
+//! from a counter that each change advances, which is all watch mode needs to see what
+//! changed.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::io;
+use std::time::{Duration, SystemTime};
+
+use super::{CompilerHost, FileSystemEntries, absolute_path};
+use crate::compiler::paths::ensure_trailing_directory_separator;
+use crate::config::read_directory;
+
+/// How many symbolic links a path may go through before it is taken to be a loop
+const MAX_SYMLINKS: usize = 40;
+
+pub struct VirtualCompilerHost {
+    current_directory: String,
+    use_case_sensitive_file_names: bool,
+    default_lib_location: Option<String>,
+    /// The files, by canonical path
+    files: RefCell<BTreeMap<String, VirtualFile>>,
+    /// When a file was last added to or removed from each folder, by canonical path
+    directory_versions: RefCell<BTreeMap<String, u64>>,
+    /// The absolute paths that symbolic links point to, by the canonical paths of the links
+    symlinks: RefCell<BTreeMap<String, String>>,
+    written_files: RefCell<Vec<String>>,
+    traces: RefCell<Vec<String>>,
+    version: Cell<u64>,
+}
+
+struct VirtualFile {
+    /// The absolute path of the file, in the casing it was created with
+    path: String,
+    text: String,
+    version: u64,
+}
+
+impl Default for VirtualCompilerHost {
+    fn default() -> Self {
+        VirtualCompilerHost::new("/", true)
+    }
+}
+
+impl VirtualCompilerHost {
+    pub fn new(current_directory: &str, use_case_sensitive_file_names: bool) -> Self {
+        VirtualCompilerHost {
+            current_directory: absolute_path("/", current_directory)
+                .to_string_lossy()
+                .to_string(),
+            use_case_sensitive_file_names,
+            default_lib_location: None,
+            files: RefCell::new(BTreeMap::new()),
+            directory_versions: RefCell::new(BTreeMap::new()),
+            symlinks: RefCell::new(BTreeMap::new()),
+            written_files: RefCell::new(Vec::new()),
+            traces: RefCell::new(Vec::new()),
+            version: Cell::new(0),
+        }
+    }
+
+    /// Creates a host in the root folder with the given files
+    pub fn with_files(files: &[(&str, &str)]) -> Self {
+        let host = VirtualCompilerHost::default();
+        for (path, text) in files {
+            host.add_file(path, text);
+        }
+        host
+    }
+
+    /// Sets the folder of the standard library files, which must be files of the host
+    pub fn set_default_lib_location(&mut self, directory: Option<&str>) {
+        self.default_lib_location = directory.map(|directory| self.absolute(directory));
+    }
+
+    /// Adds or replaces a file, without recording it as written
+    pub fn add_file(&self, path: &str, text: &str) {
+        let path = self.resolve(path);
+        let version = self.next_version();
+        let created = self
+            .files
+            .borrow_mut()
+            .insert(
+                self.key(&path),
+                VirtualFile {
+                    path: path.clone(),
+                    text: text.to_string(),
+                    version,
+                },
+            )
+            .is_none();
+        if created {
+            self.touch_directories(&path, version);
+        }
+    }
+
+    /// Removes a file, if there is one
+    pub fn remove_file(&self, path: &str) {
+        let path = self.resolve(path);
+        if self.files.borrow_mut().remove(&self.key(&path)).is_some() {
+            let version = self.next_version();
+            self.touch_directories(&path, version);
+        }
+    }
+
+    /// Makes `path` a symbolic link to `target`, which may be a file or a folder
+    pub fn add_symlink(&self, path: &str, target: &str) {
+        let path = self.absolute(path);
+        let target = self.absolute(target);
+        self.symlinks.borrow_mut().insert(self.key(&path), target);
+    }
+
+    /// The absolute paths of the files written through the host, in the order they were
+    /// first written
+    pub fn written_files(&self) -> Vec<String> {
+        self.written_files.borrow().clone()
+    }
+
+    pub fn clear_written_files(&self) {
+        self.written_files.borrow_mut().clear();
+    }
+
+    /// The lines traced through the host, like the steps of `--traceResolution`
+    pub fn traces(&self) -> Vec<String> {
+        self.traces.borrow().clone()
+    }
+
+    fn next_version(&self) -> u64 {
+        self.version.set(self.version.get() + 1);
+        self.version.get()
+    }
+
+    /// Records a change to the folders above a file that was added or removed
+    fn touch_directories(&self, path: &str, version: u64) {
+        let mut directory_versions = self.directory_versions.borrow_mut();
+        let mut directory = path;
+        while let Some((parent, _)) = directory.rsplit_once('/') {
+            directory = if parent.is_empty() { "/" } else { parent };
+            directory_versions.insert(self.key(directory), version);
+            if directory == "/" {
+                break;
+            }
+        }
+    }
+
+    fn absolute(&self, path: &str) -> String {
+        absolute_path(&self.current_directory, path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Gets the path that identifies a file or folder, which is lowercased when names that
+    /// differ only in casing are the same
+    fn key(&self, path: &str) -> String {
+        if self.use_case_sensitive_file_names {
+            path.to_string()
+        } else {
+            path.to_lowercase()
+        }
+    }
+
+    /// Makes a path absolute and follows the symbolic links along it
+    fn resolve(&self, path: &str) -> String {
+        let mut path = self.absolute(path);
+        for _ in 0..MAX_SYMLINKS {
+            let key = self.key(&path);
+            let linked = self.symlinks.borrow().iter().find_map(|(link, target)| {
+                let rest = key.strip_prefix(link.as_str())?;
+                let rest = path.get(path.len() - rest.len()..)?;
+                (rest.is_empty() || rest.starts_with('/')).then(|| format!("{target}{rest}"))
+            });
+            match linked {
+                Some(linked) => path = linked,
+                None => break,
+            }
+        }
+        path
+    }
+
+    /// Gets the prefix of the canonical paths of the entries of a folder
+    fn directory_prefix(&self, path: &str) -> String {
+        ensure_trailing_directory_separator(&self.key(&self.resolve(path)))
+    }
+}
+
+impl CompilerHost for VirtualCompilerHost {
+    fn read_file(&self, path: &str) -> Option<String> {
+        let key = self.key(&self.resolve(path));
+        self.files.borrow().get(&key).map(|file| file.text.clone())
+    }
+
+    fn write_file(&self, path: &str, data: &str) -> io::Result<()> {
+        let path = self.resolve(path);
+        if self.directory_exists(&path) {
+            return Err(io::ErrorKind::IsADirectory.into());
+        }
+        self.add_file(&path, data);
+        let mut written_files = self.written_files.borrow_mut();
+        if !written_files.contains(&path) {
+            written_files.push(path);
+        }
+        Ok(())
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        let key = self.key(&self.resolve(path));
+        self.files.borrow().contains_key(&key)
+    }
+
+    fn directory_exists(&self, path: &str) -> bool {
+        let prefix = self.directory_prefix(path);
+        prefix == "/"
+            || self
+                .files
+                .borrow()
+                .keys()
+                .any(|key| key.starts_with(&prefix))
+            || self
+                .symlinks
+                .borrow()
+                .keys()
+                .any(|link| link.starts_with(&prefix))
+    }
+
+    fn get_accessible_entries(&self, path: &str) -> FileSystemEntries {
+        let mut entries = FileSystemEntries::default();
+        let prefix = self.directory_prefix(path);
+        for file in self.files.borrow().values() {
+            let key = self.key(&file.path);
+            if !key.starts_with(&prefix) {
+                continue;
+            }
+            // The names keep the casing the files were created with
+            let Some(rest) = file.path.get(prefix.len()..) else {
+                continue;
+            };
+            match rest.split_once('/') {
+                Some((directory, _)) => {
+                    if !entries.directories.iter().any(|name| name == directory) {
+                        entries.directories.push(directory.to_string());
+                    }
+                }
+                None => entries.files.push(rest.to_string()),
+            }
+        }
+        // Links are listed as what they point to
+        for (link, target) in self.symlinks.borrow().iter() {
+            let Some(name) = link
+                .strip_prefix(&prefix)
+                .filter(|name| !name.contains('/'))
+            else {
+                continue;
+            };
+            if self.file_exists(target) {
+                entries.files.push(name.to_string());
+            } else if self.directory_exists(target) {
+                entries.directories.push(name.to_string());
+            }
+        }
+        entries
+    }
+
+    fn read_directory(
+        &self,
+        path: &str,
+        extensions: &[&str],
+        excludes: &[String],
+        includes: &[String],
+        depth: Option<usize>,
+    ) -> Vec<String> {
+        read_directory(self, path, extensions, excludes, includes, depth)
+    }
+
+    fn get_modified_time(&self, path: &str) -> Option<SystemTime> {
+        let key = self.key(&self.resolve(path));
+        let version = match self.files.borrow().get(&key) {
+            Some(file) => file.version,
+            None if self.directory_exists(path) => self
+                .directory_versions
+                .borrow()
+                .get(&key)
+                .copied()
+                .unwrap_or(0),
+            None => return None,
+        };
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(version))
+    }
+
+    fn get_current_directory(&self) -> String {
+        self.current_directory.clone()
+    }
+
+    fn use_case_sensitive_file_names(&self) -> bool {
+        self.use_case_sensitive_file_names
+    }
+
+    fn get_canonical_file_name(&self, file_name: &str) -> String {
+        self.key(file_name)
+    }
+
+    fn realpath(&self, path: &str) -> String {
+        self.resolve(path)
+    }
+
+    fn get_default_lib_location(&self) -> Option<String> {
+        self.default_lib_location.clone()
+    }
+
+    fn trace(&self, message: &str) {
+        self.traces.borrow_mut().push(message.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_files_and_folders_in_memory() {
+        let host = VirtualCompilerHost::with_files(&[
+            ("src/a.ts", "let a = 1;"),
+            ("src/lib/b.ts", "let b = 1;"),
+        ]);
+        assert_eq!(host.read_file("/src/a.ts").as_deref(), Some("let a = 1;"));
+        assert!(host.directory_exists("src/lib"));
+        assert!(!host.directory_exists("src/a.ts"));
+        assert!(!host.file_exists("src/A.ts"));
+        let entries = host.get_accessible_entries("src");
+        assert_eq!(entries.files, ["a.ts"]);
+        assert_eq!(entries.directories, ["lib"]);
+
+        assert!(host.write_file("out/a.js", "var a = 1;").is_ok());
+        assert!(host.write_file("out/a.js", "var a = 2;").is_ok());
+        assert!(host.write_file("src", "").is_err());
+        assert_eq!(host.written_files(), ["/out/a.js"]);
+        assert_eq!(host.read_file("out/a.js").as_deref(), Some("var a = 2;"));
+    }
+
+    #[test]
+    fn advances_modified_times_of_files_and_folders() {
+        let host = VirtualCompilerHost::with_files(&[("src/a.ts", "")]);
+        let folder_time = host.get_modified_time("src");
+        let file_time = host.get_modified_time("src/a.ts");
+        assert!(folder_time.is_some() && file_time.is_some());
+
+        host.add_file("src/a.ts", "let a = 1;");
+        assert!(host.get_modified_time("src/a.ts") > file_time);
+        assert_eq!(host.get_modified_time("src"), folder_time);
+
+        host.remove_file("src/a.ts");
+        assert_eq!(host.get_modified_time("src/a.ts"), None);
+        assert_eq!(host.get_modified_time("src"), None);
+        assert!(host.get_modified_time("/") > folder_time);
+    }
+
+    #[test]
+    fn ignores_casing_when_configured() {
+        let host = VirtualCompilerHost::new("/Project", false);
+        host.add_file("Src/Index.ts", "");
+        assert!(host.file_exists("/project/src/index.ts"));
+        assert!(host.directory_exists("/PROJECT/SRC"));
+        assert_eq!(host.get_accessible_entries("/project").directories, ["Src"]);
+        assert_eq!(host.get_canonical_file_name("/Project/Src"), "/project/src");
+    }
+
+    #[test]
+    fn reads_directories_by_pattern_extension_and_depth() {
+        let host = VirtualCompilerHost::with_files(&[
+            ("src/a.ts", ""),
+            ("src/b.js", ""),
+            ("src/lib/c.ts", ""),
+            ("src/lib/deep/d.ts", ""),
+            ("src/test/e.ts", ""),
+        ]);
+        let strings = |patterns: &[&str]| -> Vec<String> {
+            patterns.iter().map(|pattern| pattern.to_string()).collect()
+        };
+        assert_eq!(
+            host.read_directory("src", &[".ts"], &strings(&["test"]), &[], None),
+            ["src/a.ts", "src/lib/c.ts", "src/lib/deep/d.ts"]
+        );
+        assert_eq!(
+            host.read_directory("src", &[], &[], &strings(&["*"]), None),
+            ["src/a.ts", "src/b.js"]
+        );
+        assert_eq!(
+            host.read_directory("src", &[".ts"], &[], &strings(&["lib/**/*"]), Some(2)),
+            ["src/lib/c.ts"]
+        );
+        assert_eq!(
+            host.read_directory("src", &[".ts"], &[], &[], Some(1)),
+            ["src/a.ts"]
+        );
+    }
+
+    #[test]
+    fn follows_symbolic_links() {
+        let host = VirtualCompilerHost::with_files(&[("packages/pkg/index.d.ts", "")]);
+        host.add_symlink("node_modules/pkg", "packages/pkg");
+        assert!(host.file_exists("node_modules/pkg/index.d.ts"));
+        assert_eq!(
+            host.realpath("node_modules/pkg/index.d.ts"),
+            "/packages/pkg/index.d.ts"
+        );
+        assert_eq!(
+            host.get_accessible_entries("node_modules").directories,
+            ["pkg"]
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::compiler::ast::ids::NodeId;
+use crate::compiler::ast::node::{Node, reserve_node_ids};
+
+/// Storage for the nodes of a parsed source file
+///
+/// The parser allocates each node into the arena of its file as it finishes it, so a node comes
+/// right after the nodes of its subtree, and the subtree of any node is the contiguous run of
+/// slots that ends at the node. Walks that do not depend on source order, like looking for
+/// errors or collecting names, go over that run instead of recursing through the tree.
+///
+/// Once the file is parsed, the nodes it created are given consecutive ids in slot order, so
+/// their ids index the arena directly. Nodes an incremental reparse takes from the old tree keep
+/// their ids, since the old tree may still be in use. The source file itself and JSDoc comments
+/// are not stored.
+#[derive(Default)]
+pub struct NodeArena {
+    nodes: Vec<Arc<Node>>,
+    /// The slot of the first node in the subtree of the node in each slot
+    subtree_starts: Vec<usize>,
+    /// The id given to the first node the parser created
+    first_id: u64,
+    /// The slots of the nodes the parser created, in the order of their ids
+    created_slots: Vec<usize>,
+    /// The ids of the nodes taken from an old tree and their slots, sorted by id
+    adopted_slots: Vec<(u64, usize)>,
+}
+
+impl NodeArena {
+    /// Creates an empty arena
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the number of nodes in the arena
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Checks if the arena holds no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Stores a node the parser has finished
+    pub(crate) fn alloc(&mut self, node: &Arc<Node>) {
+        self.nodes.push(Arc::clone(node));
+    }
+
+    /// Drops the nodes allocated after the first `len`, when the parser rewinds past them or
+    /// they are not part of the tree, like JSDoc comments
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.nodes.truncate(len);
+    }
+
+    /// Stores the subtree of `node`, which `other` holds, as if it had just been parsed
+    ///
+    /// Panics if `other` does not hold `node`.
+    pub(crate) fn adopt(&mut self, other: &NodeArena, node: &Node) {
+        let subtree = other
+            .subtree(node)
+            .expect("a reused node is in the arena of its file");
+        self.nodes.extend(subtree.iter().cloned());
+    }
+
+    /// Completes the arena of a file whose top-level nodes are `roots`, dropping the nodes that
+    /// did not end up in the tree and numbering the rest
+    pub(crate) fn finish<'a>(mut self, roots: impl IntoIterator<Item = &'a Arc<Node>>) -> Self {
+        // Parents come after their children, so walking backwards meets each parent first
+        let mut attached: HashSet<*const Node> = roots.into_iter().map(Arc::as_ptr).collect();
+        let mut keep = vec![false; self.nodes.len()];
+        for (slot, node) in self.nodes.iter().enumerate().rev() {
+            keep[slot] = attached.contains(&Arc::as_ptr(node))
+                || node
+                    .parent()
+                    .is_some_and(|parent| attached.contains(&Arc::as_ptr(&parent)));
+            if keep[slot] {
+                attached.insert(Arc::as_ptr(node));
+            }
+        }
+        let mut keep = keep.into_iter();
+        self.nodes.retain(|_| keep.next().unwrap());
+
+        let mut slots = HashMap::with_capacity(self.nodes.len());
+        self.subtree_starts = Vec::with_capacity(self.nodes.len());
+        for (slot, node) in self.nodes.iter().enumerate() {
+            let mut start = slot;
+            node.for_each_child(&mut |child| match slots.get(&Arc::as_ptr(child)) {
+                Some(&child_slot) => {
+                    start = self.subtree_starts[child_slot];
+                    true
+                }
+                None => false,
+            });
+            self.subtree_starts.push(start);
+            slots.insert(Arc::as_ptr(node), slot);
+        }
+
+        for (slot, node) in self.nodes.iter().enumerate() {
+            match node.id() {
+                0 => self.created_slots.push(slot),
+                id => self.adopted_slots.push((id, slot)),
+            }
+        }
+        self.adopted_slots.sort_unstable();
+        self.first_id = reserve_node_ids(self.created_slots.len() as u64);
+        for (index, &slot) in self.created_slots.iter().enumerate() {
+            self.nodes[slot].set_id(self.first_id + index as u64);
+        }
+        self
+    }
+
+    /// Gets the node with the given id, if it is in this arena
+    pub fn get(&self, id: NodeId) -> Option<&Arc<Node>> {
+        self.slot_of_id(id.value()).map(|slot| &self.nodes[slot])
+    }
+
+    /// Determines if this arena holds `node`
+    pub fn contains(&self, node: &Node) -> bool {
+        self.slot_of(node).is_some()
+    }
+
+    /// Gets every node in the arena, each after the nodes of its subtree
+    pub fn nodes(&self) -> &[Arc<Node>] {
+        &self.nodes
+    }
+
+    /// Gets `node` and its descendants, each after the nodes of its own subtree, or `None` if
+    /// this arena does not hold `node`
+    pub fn subtree(&self, node: &Node) -> Option<&[Arc<Node>]> {
+        let slot = self.slot_of(node)?;
+        Some(&self.nodes[self.subtree_starts[slot]..=slot])
+    }
+
+    fn slot_of(&self, node: &Node) -> Option<usize> {
+        self.slot_of_id(node.id())
+            .filter(|&slot| std::ptr::eq(self.nodes[slot].as_ref(), node))
+    }
+
+    fn slot_of_id(&self, id: u64) -> Option<usize> {
+        match id.checked_sub(self.first_id) {
+            Some(index) if id != 0 => self.created_slots.get(index as usize).copied(),
+            _ => None,
+        }
+        .or_else(|| {
+            let index = self
+                .adopted_slots
+                .binary_search_by_key(&id, |&(id, _)| id)
+                .ok()?;
+            Some(self.adopted_slots[index].1)
+        })
+    }
+}
+
+impl std::fmt::Debug for NodeArena {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeArena")
+            .field("len", &self.nodes.len())
+            .field("first_id", &self.first_id)
+            .field("adopted", &self.adopted_slots.len())
+            .finish()
+    }
+}
+
+/// Arenas are only equal to themselves, as the nodes they hold are compared by identity
+impl PartialEq for NodeArena {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::{NodeFactory, NodeMapper, SyntaxKind};
+    use crate::compiler::parser::parse_source_file;
+    use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+    use crate::compiler::text::{TextChangeRange, TextRange};
+
+    const TEXT: &str = "/** Adds */\nfunction add<T>(a: T, b = (x: number) => x): T {\n    return a;\n}\nlet c = a < b > (d);\nconst e = async (f) => { await f; };\nclass G { h?: string; }\n";
+
+    fn parse(text: &str) -> Arc<Node> {
+        parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown)
+    }
+
+    fn post_order(node: &Arc<Node>, nodes: &mut Vec<Arc<Node>>) {
+        node.for_each_child(&mut |child| {
+            post_order(child, nodes);
+            nodes.push(Arc::clone(child));
+            false
+        });
+    }
+
+    /// Asserts that the arena of `file` holds exactly its tree, in the order the parser finishes
+    /// nodes
+    fn assert_holds_tree(file: &Arc<Node>) {
+        let arena = file.as_source_file().arena().unwrap();
+        let mut expected = Vec::new();
+        post_order(file, &mut expected);
+        assert_eq!(arena.len(), expected.len());
+        for (stored, node) in arena.nodes().iter().zip(&expected) {
+            assert!(Arc::ptr_eq(stored, node), "{:?} is out of place", node.kind);
+            assert!(Arc::ptr_eq(arena.get(node.node_id()).unwrap(), node));
+            let mut subtree = Vec::new();
+            post_order(node, &mut subtree);
+            subtree.push(Arc::clone(node));
+            let stored = arena.subtree(node).unwrap();
+            assert_eq!(stored.len(), subtree.len());
+            assert!(stored.iter().zip(&subtree).all(|(a, b)| Arc::ptr_eq(a, b)));
+        }
+    }
+
+    #[test]
+    fn test_parser_allocates_the_tree() {
+        let file = parse(TEXT);
+        assert_holds_tree(&file);
+        // Nodes the parser built while looking ahead, and JSDoc comments, are not kept
+        let arena = file.as_source_file().arena().unwrap();
+        assert!(
+            arena
+                .nodes()
+                .iter()
+                .all(|node| node.kind != SyntaxKind::JSDoc)
+        );
+        assert!(!arena.contains(&file.as_source_file().statements.nodes[0].jsdoc()[0]));
+    }
+
+    #[test]
+    fn test_incremental_update_allocates_the_new_tree() {
+        let old = parse(TEXT);
+        let pos = TEXT.find("class G").unwrap();
+        let new_text = format!("{}let z = 1;\n{}", &TEXT[..pos], &TEXT[pos..]);
+        let change = TextChangeRange::new(TextRange::new(pos, pos), "let z = 1;\n".len());
+        let updated = old.as_source_file().update(&new_text, change);
+        assert_holds_tree(&updated);
+        let statements = &updated.as_source_file().statements.nodes;
+        assert!(Arc::ptr_eq(
+            &statements[0],
+            &old.as_source_file().statements.nodes[0]
+        ));
+    }
+
+    /// Copies every node, as a transform that changes all of them would
+    struct Copier;
+
+    impl NodeMapper for Copier {
+        fn map_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+            Node::new_boxed(
+                node.kind,
+                node.flags,
+                node.loc,
+                node.data().map_children(self),
+            )
+        }
+    }
+
+    #[test]
+    fn test_copied_file_has_no_arena() {
+        let file = parse("let a = 1;\n");
+        let copy = Copier.map_node(&file);
+        assert!(file.as_source_file().arena().is_some());
+        assert!(copy.as_source_file().arena().is_none());
+
+        // Updating a file without changing its statements keeps the parsed file
+        let factory = NodeFactory::new();
+        let statements = file.as_source_file().statements.clone();
+        let same = factory.update_source_file(&file, statements);
+        assert!(Arc::ptr_eq(&same, &file));
+        let changed = factory.update_source_file(&file, factory.create_node_list(Vec::new()));
+        assert!(changed.as_source_file().arena().is_none());
+    }
+}
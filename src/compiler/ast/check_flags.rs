@@ -2,7 +2,7 @@ use bitflags::bitflags;
 
 bitflags! {
     /// Flags used by the type checker for tracking special properties of symbols
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct CheckFlags: u32 {
         /// No flags set
         const NONE = 0;
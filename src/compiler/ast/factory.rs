@@ -0,0 +1,1392 @@
+use std::sync::Arc;
+
+use crate::compiler::ast::kind::SyntaxKind;
+use crate::compiler::ast::node::{Node, NodeData, NodeList, NodeMapper};
+use crate::compiler::ast::node_flags::NodeFlags;
+use crate::compiler::ast::nodes::*;
+use crate::compiler::scanner::TokenFlags;
+use crate::compiler::text::TextRange;
+
+/// Creates, updates, and clones nodes outside the parser
+///
+/// Nodes are built from their data structs, for example
+/// `factory.create(SyntaxKind::ReturnStatement, ReturnStatement { expression })`. Nodes created
+/// by a factory from [`NodeFactory::new`] carry the `SYNTHESIZED` flag and have no source
+/// position unless one is given. The `update_*` methods take the children of a node of one
+/// kind, and return the original node when they are the very ones it already has.
+///
+/// Created nodes only become the parent of children that have no parent yet, so a
+/// transformer can reuse subtrees of the original tree in its output without detaching them
+/// from that tree. Use [`set_parent_recursive`] to give a finished synthesized tree
+/// consistent parent pointers.
+#[derive(Debug, Clone, Copy)]
+pub struct NodeFactory {
+    flags: NodeFlags,
+}
+
+impl Default for NodeFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeFactory {
+    /// Creates a factory for synthesized nodes
+    pub fn new() -> Self {
+        Self::with_flags(NodeFlags::SYNTHESIZED)
+    }
+
+    /// Creates a factory that adds `flags` to every node it creates
+    pub fn with_flags(flags: NodeFlags) -> Self {
+        NodeFactory { flags }
+    }
+
+    /// Creates a node without a source position
+    pub fn create(&self, kind: SyntaxKind, data: impl NodeData) -> Arc<Node> {
+        self.create_with_flags(kind, NodeFlags::NONE, data)
+    }
+
+    /// Creates a node with additional flags, such as `CONST` on a variable declaration list
+    pub fn create_with_flags(
+        &self,
+        kind: SyntaxKind,
+        flags: NodeFlags,
+        data: impl NodeData,
+    ) -> Arc<Node> {
+        Node::new_detached(
+            kind,
+            self.flags | flags,
+            TextRange::default(),
+            Box::new(data),
+        )
+    }
+
+    /// Creates a node that covers `loc` in the source text
+    pub fn create_at(&self, kind: SyntaxKind, loc: TextRange, data: impl NodeData) -> Arc<Node> {
+        Node::new_detached(kind, self.flags, loc, Box::new(data))
+    }
+
+    /// Creates a node list without a source position
+    pub fn create_node_list(&self, nodes: Vec<Arc<Node>>) -> NodeList {
+        NodeList::new(TextRange::default(), nodes)
+    }
+
+    /// Creates a punctuation or keyword token
+    pub fn create_token(&self, kind: SyntaxKind) -> Arc<Node> {
+        self.create(kind, Token {})
+    }
+
+    /// Creates an identifier
+    pub fn create_identifier(&self, text: impl Into<String>) -> Arc<Node> {
+        self.create(SyntaxKind::Identifier, Identifier { text: text.into() })
+    }
+
+    /// Creates a `#private` identifier; `text` includes the `#`
+    pub fn create_private_identifier(&self, text: impl Into<String>) -> Arc<Node> {
+        let text = text.into();
+        debug_assert!(
+            text.starts_with('#'),
+            "private identifier without #: {text}"
+        );
+        self.create(SyntaxKind::PrivateIdentifier, PrivateIdentifier { text })
+    }
+
+    /// Creates a string literal from its cooked value
+    pub fn create_string_literal(&self, text: impl Into<String>) -> Arc<Node> {
+        self.create(
+            SyntaxKind::StringLiteral,
+            StringLiteral {
+                text: text.into(),
+                token_flags: TokenFlags::NONE,
+            },
+        )
+    }
+
+    /// Creates a numeric literal
+    pub fn create_numeric_literal(&self, text: impl Into<String>) -> Arc<Node> {
+        self.create(
+            SyntaxKind::NumericLiteral,
+            NumericLiteral {
+                text: text.into(),
+                token_flags: TokenFlags::NONE,
+            },
+        )
+    }
+
+    /// Creates a bigint literal; `text` includes the trailing `n`
+    pub fn create_big_int_literal(&self, text: impl Into<String>) -> Arc<Node> {
+        self.create(
+            SyntaxKind::BigIntLiteral,
+            BigIntLiteral { text: text.into() },
+        )
+    }
+
+    /// Creates `expression.name`
+    pub fn create_property_access(&self, expression: Arc<Node>, name: &str) -> Arc<Node> {
+        self.create(
+            SyntaxKind::PropertyAccessExpression,
+            PropertyAccessExpression {
+                expression,
+                question_dot_token: None,
+                name: self.create_identifier(name),
+            },
+        )
+    }
+
+    /// Creates `expression[argument_expression]`
+    pub fn create_element_access(
+        &self,
+        expression: Arc<Node>,
+        argument_expression: Arc<Node>,
+    ) -> Arc<Node> {
+        self.create(
+            SyntaxKind::ElementAccessExpression,
+            ElementAccessExpression {
+                expression,
+                question_dot_token: None,
+                argument_expression,
+            },
+        )
+    }
+
+    /// Creates `expression(arguments)`
+    pub fn create_call(&self, expression: Arc<Node>, arguments: Vec<Arc<Node>>) -> Arc<Node> {
+        self.create(
+            SyntaxKind::CallExpression,
+            CallExpression {
+                expression,
+                question_dot_token: None,
+                type_arguments: None,
+                arguments: self.create_node_list(arguments),
+            },
+        )
+    }
+
+    /// Creates a binary expression such as `left + right` or `left = right`
+    pub fn create_binary(
+        &self,
+        left: Arc<Node>,
+        operator: SyntaxKind,
+        right: Arc<Node>,
+    ) -> Arc<Node> {
+        self.create(
+            SyntaxKind::BinaryExpression,
+            BinaryExpression {
+                left,
+                operator_token: self.create_token(operator),
+                right,
+            },
+        )
+    }
+
+    /// Creates `left = right`
+    pub fn create_assignment(&self, left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        self.create_binary(left, SyntaxKind::EqualsToken, right)
+    }
+
+    /// Creates `(expression)`
+    pub fn create_parenthesized(&self, expression: Arc<Node>) -> Arc<Node> {
+        self.create(
+            SyntaxKind::ParenthesizedExpression,
+            ParenthesizedExpression { expression },
+        )
+    }
+
+    /// Creates `void 0`, the shortest way to write `undefined`
+    pub fn create_void_zero(&self) -> Arc<Node> {
+        self.create(
+            SyntaxKind::VoidExpression,
+            VoidExpression {
+                expression: self.create_numeric_literal("0"),
+            },
+        )
+    }
+
+    /// Creates an array literal
+    pub fn create_array_literal(&self, elements: Vec<Arc<Node>>, multi_line: bool) -> Arc<Node> {
+        self.create(
+            SyntaxKind::ArrayLiteralExpression,
+            ArrayLiteralExpression {
+                elements: self.create_node_list(elements),
+                multi_line,
+            },
+        )
+    }
+
+    /// Creates an object literal
+    pub fn create_object_literal(&self, properties: Vec<Arc<Node>>, multi_line: bool) -> Arc<Node> {
+        self.create(
+            SyntaxKind::ObjectLiteralExpression,
+            ObjectLiteralExpression {
+                properties: self.create_node_list(properties),
+                multi_line,
+            },
+        )
+    }
+
+    /// Creates `name: initializer` in an object literal
+    pub fn create_property_assignment(&self, name: Arc<Node>, initializer: Arc<Node>) -> Arc<Node> {
+        self.create(
+            SyntaxKind::PropertyAssignment,
+            PropertyAssignment {
+                modifiers: None,
+                name,
+                postfix_token: None,
+                initializer,
+            },
+        )
+    }
+
+    /// Creates a plain parameter
+    pub fn create_parameter(&self, name: Arc<Node>) -> Arc<Node> {
+        self.create(
+            SyntaxKind::Parameter,
+            ParameterDeclaration {
+                modifiers: None,
+                dot_dot_dot_token: None,
+                name,
+                question_token: None,
+                type_node: None,
+                initializer: None,
+            },
+        )
+    }
+
+    /// Creates `function name(parameters) body`
+    pub fn create_function_expression(
+        &self,
+        name: Option<Arc<Node>>,
+        parameters: Vec<Arc<Node>>,
+        body: Arc<Node>,
+    ) -> Arc<Node> {
+        self.create(
+            SyntaxKind::FunctionExpression,
+            FunctionExpression {
+                modifiers: None,
+                asterisk_token: None,
+                name,
+                type_parameters: None,
+                parameters: self.create_node_list(parameters),
+                type_node: None,
+                body,
+            },
+        )
+    }
+
+    /// Creates a block of statements
+    pub fn create_block(&self, statements: Vec<Arc<Node>>, multi_line: bool) -> Arc<Node> {
+        self.create(
+            SyntaxKind::Block,
+            Block {
+                statements: self.create_node_list(statements),
+                multi_line,
+            },
+        )
+    }
+
+    /// Creates `expression;`
+    pub fn create_expression_statement(&self, expression: Arc<Node>) -> Arc<Node> {
+        self.create(
+            SyntaxKind::ExpressionStatement,
+            ExpressionStatement { expression },
+        )
+    }
+
+    /// Creates `return expression;`
+    pub fn create_return(&self, expression: Option<Arc<Node>>) -> Arc<Node> {
+        self.create(SyntaxKind::ReturnStatement, ReturnStatement { expression })
+    }
+
+    /// Creates `name = initializer` in a variable declaration list
+    pub fn create_variable_declaration(
+        &self,
+        name: Arc<Node>,
+        initializer: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.create(
+            SyntaxKind::VariableDeclaration,
+            VariableDeclaration {
+                name,
+                exclamation_token: None,
+                type_node: None,
+                initializer,
+            },
+        )
+    }
+
+    /// Creates a `var` statement declaring `declarations`
+    pub fn create_variable_statement(&self, declarations: Vec<Arc<Node>>) -> Arc<Node> {
+        let declaration_list = self.create(
+            SyntaxKind::VariableDeclarationList,
+            VariableDeclarationList {
+                declarations: self.create_node_list(declarations),
+            },
+        );
+        self.create(
+            SyntaxKind::VariableStatement,
+            VariableStatement {
+                modifiers: None,
+                declaration_list,
+            },
+        )
+    }
+
+    /// Replaces the data of `original`, returning `original` itself when nothing changed
+    ///
+    /// The data counts as unchanged when it holds the very same child nodes and equal plain
+    /// data. Otherwise the result is a new node with the kind, flags, and source range of
+    /// `original`.
+    pub fn update(&self, original: &Arc<Node>, data: impl NodeData) -> Arc<Node> {
+        if original.data().shallow_eq(&data) {
+            return Arc::clone(original);
+        }
+        self.finish_update(original, Box::new(data))
+    }
+
+    /// Replaces each child of `node` with the mapper's result, returning `node` itself when
+    /// the mapper returned every child unchanged
+    pub fn update_children(&self, node: &Arc<Node>, mapper: &mut dyn NodeMapper) -> Arc<Node> {
+        let data = node.data().map_children(mapper);
+        if node.data().shallow_eq(data.as_ref()) {
+            return Arc::clone(node);
+        }
+        self.finish_update(node, data)
+    }
+
+    /// Replaces a node list, returning a copy of `original` when it holds the same nodes
+    pub fn update_node_list(&self, original: &NodeList, nodes: Vec<Arc<Node>>) -> NodeList {
+        let list = NodeList {
+            loc: original.loc,
+            nodes,
+            has_trailing_comma: original.has_trailing_comma,
+        };
+        if list.same_nodes(original) {
+            original.clone()
+        } else {
+            list
+        }
+    }
+
+    /// Updates a block of statements
+    pub fn update_block(
+        &self,
+        node: &Arc<Node>,
+        statements: NodeList,
+        multi_line: bool,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            Block {
+                statements,
+                multi_line,
+            },
+        )
+    }
+
+    /// Updates the statements of a source file
+    pub fn update_source_file(&self, node: &Arc<Node>, statements: NodeList) -> Arc<Node> {
+        let data = node.as_source_file();
+        self.update(
+            node,
+            SourceFile {
+                statements,
+                end_of_file_token: Arc::clone(&data.end_of_file_token),
+                file_name: data.file_name.clone(),
+                text: data.text.clone(),
+                language_version: data.language_version,
+                language_variant: data.language_variant,
+                script_kind: data.script_kind,
+                is_declaration_file: data.is_declaration_file,
+                parse_diagnostics: data.parse_diagnostics.clone(),
+                jsdoc_diagnostics: data.jsdoc_diagnostics.clone(),
+                jsdoc_parsing_mode: data.jsdoc_parsing_mode,
+                node_count: data.node_count,
+                identifier_count: data.identifier_count,
+                arena: None,
+                statement_starts: data.statement_starts.clone(),
+            },
+        )
+    }
+
+    /// Updates an identifier, renaming it
+    pub fn update_identifier(&self, node: &Arc<Node>, text: impl Into<String>) -> Arc<Node> {
+        self.update(node, Identifier { text: text.into() })
+    }
+
+    /// Updates `expression.name`
+    pub fn update_property_access(
+        &self,
+        node: &Arc<Node>,
+        expression: Arc<Node>,
+        question_dot_token: Option<Arc<Node>>,
+        name: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            PropertyAccessExpression {
+                expression,
+                question_dot_token,
+                name,
+            },
+        )
+    }
+
+    /// Updates `expression[argument_expression]`
+    pub fn update_element_access(
+        &self,
+        node: &Arc<Node>,
+        expression: Arc<Node>,
+        question_dot_token: Option<Arc<Node>>,
+        argument_expression: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ElementAccessExpression {
+                expression,
+                question_dot_token,
+                argument_expression,
+            },
+        )
+    }
+
+    /// Updates `expression(arguments)`
+    pub fn update_call(
+        &self,
+        node: &Arc<Node>,
+        expression: Arc<Node>,
+        question_dot_token: Option<Arc<Node>>,
+        type_arguments: Option<NodeList>,
+        arguments: NodeList,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            CallExpression {
+                expression,
+                question_dot_token,
+                type_arguments,
+                arguments,
+            },
+        )
+    }
+
+    /// Updates `new expression(arguments)`
+    pub fn update_new(
+        &self,
+        node: &Arc<Node>,
+        expression: Arc<Node>,
+        type_arguments: Option<NodeList>,
+        arguments: Option<NodeList>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            NewExpression {
+                expression,
+                type_arguments,
+                arguments,
+            },
+        )
+    }
+
+    /// Updates `` tag`template` ``
+    pub fn update_tagged_template(
+        &self,
+        node: &Arc<Node>,
+        tag: Arc<Node>,
+        question_dot_token: Option<Arc<Node>>,
+        type_arguments: Option<NodeList>,
+        template: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            TaggedTemplateExpression {
+                tag,
+                question_dot_token,
+                type_arguments,
+                template,
+            },
+        )
+    }
+
+    /// Updates a binary expression
+    pub fn update_binary(
+        &self,
+        node: &Arc<Node>,
+        left: Arc<Node>,
+        operator_token: Arc<Node>,
+        right: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            BinaryExpression {
+                left,
+                operator_token,
+                right,
+            },
+        )
+    }
+
+    /// Updates `(expression)`
+    pub fn update_parenthesized(&self, node: &Arc<Node>, expression: Arc<Node>) -> Arc<Node> {
+        self.update(node, ParenthesizedExpression { expression })
+    }
+
+    /// Updates an object literal
+    pub fn update_object_literal(&self, node: &Arc<Node>, properties: NodeList) -> Arc<Node> {
+        let data = node.as_object_literal_expression();
+        self.update(
+            node,
+            ObjectLiteralExpression {
+                properties,
+                multi_line: data.multi_line,
+            },
+        )
+    }
+
+    /// Updates `name: initializer` in an object literal
+    pub fn update_property_assignment(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        name: Arc<Node>,
+        postfix_token: Option<Arc<Node>>,
+        initializer: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            PropertyAssignment {
+                modifiers,
+                name,
+                postfix_token,
+                initializer,
+            },
+        )
+    }
+
+    /// Updates `name` or `name = initializer` in an object literal
+    pub fn update_shorthand_property_assignment(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        name: Arc<Node>,
+        postfix_token: Option<Arc<Node>>,
+        equals_token: Option<Arc<Node>>,
+        object_assignment_initializer: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ShorthandPropertyAssignment {
+                modifiers,
+                name,
+                postfix_token,
+                equals_token,
+                object_assignment_initializer,
+            },
+        )
+    }
+
+    /// Updates `expression<type_arguments>` in a heritage clause
+    pub fn update_expression_with_type_arguments(
+        &self,
+        node: &Arc<Node>,
+        expression: Arc<Node>,
+        type_arguments: Option<NodeList>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ExpressionWithTypeArguments {
+                expression,
+                type_arguments,
+            },
+        )
+    }
+
+    /// Updates a parameter
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_parameter(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        dot_dot_dot_token: Option<Arc<Node>>,
+        name: Arc<Node>,
+        question_token: Option<Arc<Node>>,
+        type_node: Option<Arc<Node>>,
+        initializer: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ParameterDeclaration {
+                modifiers,
+                dot_dot_dot_token,
+                name,
+                question_token,
+                type_node,
+                initializer,
+            },
+        )
+    }
+
+    /// Updates an element of a binding pattern
+    pub fn update_binding_element(
+        &self,
+        node: &Arc<Node>,
+        dot_dot_dot_token: Option<Arc<Node>>,
+        property_name: Option<Arc<Node>>,
+        name: Arc<Node>,
+        initializer: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            BindingElement {
+                dot_dot_dot_token,
+                property_name,
+                name,
+                initializer,
+            },
+        )
+    }
+
+    /// Updates a function declaration
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_function_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        asterisk_token: Option<Arc<Node>>,
+        name: Option<Arc<Node>>,
+        type_parameters: Option<NodeList>,
+        parameters: NodeList,
+        type_node: Option<Arc<Node>>,
+        body: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            FunctionDeclaration {
+                modifiers,
+                asterisk_token,
+                name,
+                type_parameters,
+                parameters,
+                type_node,
+                body,
+            },
+        )
+    }
+
+    /// Updates `function name(parameters) body`
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_function_expression(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        asterisk_token: Option<Arc<Node>>,
+        name: Option<Arc<Node>>,
+        type_parameters: Option<NodeList>,
+        parameters: NodeList,
+        type_node: Option<Arc<Node>>,
+        body: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            FunctionExpression {
+                modifiers,
+                asterisk_token,
+                name,
+                type_parameters,
+                parameters,
+                type_node,
+                body,
+            },
+        )
+    }
+
+    /// Updates an arrow function
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_arrow_function(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        type_parameters: Option<NodeList>,
+        parameters: NodeList,
+        type_node: Option<Arc<Node>>,
+        equals_greater_than_token: Arc<Node>,
+        body: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ArrowFunction {
+                modifiers,
+                type_parameters,
+                parameters,
+                type_node,
+                equals_greater_than_token,
+                body,
+            },
+        )
+    }
+
+    /// Updates a method
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_method_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        asterisk_token: Option<Arc<Node>>,
+        name: Arc<Node>,
+        postfix_token: Option<Arc<Node>>,
+        type_parameters: Option<NodeList>,
+        parameters: NodeList,
+        type_node: Option<Arc<Node>>,
+        body: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            MethodDeclaration {
+                modifiers,
+                asterisk_token,
+                name,
+                postfix_token,
+                type_parameters,
+                parameters,
+                type_node,
+                body,
+            },
+        )
+    }
+
+    /// Updates a `get` accessor
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_get_accessor_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        name: Arc<Node>,
+        type_parameters: Option<NodeList>,
+        parameters: NodeList,
+        type_node: Option<Arc<Node>>,
+        body: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            GetAccessorDeclaration {
+                modifiers,
+                name,
+                type_parameters,
+                parameters,
+                type_node,
+                body,
+            },
+        )
+    }
+
+    /// Updates a `set` accessor
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_set_accessor_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        name: Arc<Node>,
+        type_parameters: Option<NodeList>,
+        parameters: NodeList,
+        type_node: Option<Arc<Node>>,
+        body: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            SetAccessorDeclaration {
+                modifiers,
+                name,
+                type_parameters,
+                parameters,
+                type_node,
+                body,
+            },
+        )
+    }
+
+    /// Updates a constructor
+    pub fn update_constructor_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        type_parameters: Option<NodeList>,
+        parameters: NodeList,
+        type_node: Option<Arc<Node>>,
+        body: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ConstructorDeclaration {
+                modifiers,
+                type_parameters,
+                parameters,
+                type_node,
+                body,
+            },
+        )
+    }
+
+    /// Updates a property declaration of a class
+    pub fn update_property_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        name: Arc<Node>,
+        postfix_token: Option<Arc<Node>>,
+        type_node: Option<Arc<Node>>,
+        initializer: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            PropertyDeclaration {
+                modifiers,
+                name,
+                postfix_token,
+                type_node,
+                initializer,
+            },
+        )
+    }
+
+    /// Updates a class declaration
+    pub fn update_class_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        name: Option<Arc<Node>>,
+        type_parameters: Option<NodeList>,
+        heritage_clauses: Option<NodeList>,
+        members: NodeList,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ClassDeclaration {
+                modifiers,
+                name,
+                type_parameters,
+                heritage_clauses,
+                members,
+            },
+        )
+    }
+
+    /// Updates a class expression
+    pub fn update_class_expression(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        name: Option<Arc<Node>>,
+        type_parameters: Option<NodeList>,
+        heritage_clauses: Option<NodeList>,
+        members: NodeList,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ClassExpression {
+                modifiers,
+                name,
+                type_parameters,
+                heritage_clauses,
+                members,
+            },
+        )
+    }
+
+    /// Updates an enum declaration
+    pub fn update_enum_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        name: Arc<Node>,
+        members: NodeList,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            EnumDeclaration {
+                modifiers,
+                name,
+                members,
+            },
+        )
+    }
+
+    /// Updates a member of an enum
+    pub fn update_enum_member(
+        &self,
+        node: &Arc<Node>,
+        name: Arc<Node>,
+        initializer: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(node, EnumMember { name, initializer })
+    }
+
+    /// Updates a namespace or module declaration
+    pub fn update_module_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        name: Arc<Node>,
+        body: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        let data = node.as_module_declaration();
+        self.update(
+            node,
+            ModuleDeclaration {
+                modifiers,
+                keyword: data.keyword,
+                name,
+                body,
+            },
+        )
+    }
+
+    /// Updates `expression;`
+    pub fn update_expression_statement(
+        &self,
+        node: &Arc<Node>,
+        expression: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(node, ExpressionStatement { expression })
+    }
+
+    /// Updates `return expression;`
+    pub fn update_return(&self, node: &Arc<Node>, expression: Option<Arc<Node>>) -> Arc<Node> {
+        self.update(node, ReturnStatement { expression })
+    }
+
+    /// Updates a variable statement
+    pub fn update_variable_statement(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        declaration_list: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            VariableStatement {
+                modifiers,
+                declaration_list,
+            },
+        )
+    }
+
+    /// Updates a variable declaration list, keeping whether it is `var`, `let`, or `const`
+    pub fn update_variable_declaration_list(
+        &self,
+        node: &Arc<Node>,
+        declarations: NodeList,
+    ) -> Arc<Node> {
+        self.update(node, VariableDeclarationList { declarations })
+    }
+
+    /// Updates `name = initializer` in a variable declaration list
+    pub fn update_variable_declaration(
+        &self,
+        node: &Arc<Node>,
+        name: Arc<Node>,
+        exclamation_token: Option<Arc<Node>>,
+        type_node: Option<Arc<Node>>,
+        initializer: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            VariableDeclaration {
+                name,
+                exclamation_token,
+                type_node,
+                initializer,
+            },
+        )
+    }
+
+    /// Updates `for (initializer; condition; incrementor) statement`
+    pub fn update_for_statement(
+        &self,
+        node: &Arc<Node>,
+        initializer: Option<Arc<Node>>,
+        condition: Option<Arc<Node>>,
+        incrementor: Option<Arc<Node>>,
+        statement: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ForStatement {
+                initializer,
+                condition,
+                incrementor,
+                statement,
+            },
+        )
+    }
+
+    /// Updates `for (initializer in expression) statement`
+    pub fn update_for_in_statement(
+        &self,
+        node: &Arc<Node>,
+        initializer: Arc<Node>,
+        expression: Arc<Node>,
+        statement: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ForInStatement {
+                initializer,
+                expression,
+                statement,
+            },
+        )
+    }
+
+    /// Updates `for (initializer of expression) statement`
+    pub fn update_for_of_statement(
+        &self,
+        node: &Arc<Node>,
+        await_modifier: Option<Arc<Node>>,
+        initializer: Arc<Node>,
+        expression: Arc<Node>,
+        statement: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ForOfStatement {
+                await_modifier,
+                initializer,
+                expression,
+                statement,
+            },
+        )
+    }
+
+    /// Updates `label: statement`
+    pub fn update_labeled_statement(
+        &self,
+        node: &Arc<Node>,
+        label: Arc<Node>,
+        statement: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(node, LabeledStatement { label, statement })
+    }
+
+    /// Updates a `try` statement
+    pub fn update_try_statement(
+        &self,
+        node: &Arc<Node>,
+        try_block: Arc<Node>,
+        catch_clause: Option<Arc<Node>>,
+        finally_block: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            TryStatement {
+                try_block,
+                catch_clause,
+                finally_block,
+            },
+        )
+    }
+
+    /// Updates `catch (variable_declaration) block`
+    pub fn update_catch_clause(
+        &self,
+        node: &Arc<Node>,
+        variable_declaration: Option<Arc<Node>>,
+        block: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            CatchClause {
+                variable_declaration,
+                block,
+            },
+        )
+    }
+
+    /// Updates an import declaration
+    pub fn update_import_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        import_clause: Option<Arc<Node>>,
+        module_specifier: Arc<Node>,
+        attributes: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ImportDeclaration {
+                modifiers,
+                import_clause,
+                module_specifier,
+                attributes,
+            },
+        )
+    }
+
+    /// Updates the clause of an import declaration that names what it imports
+    pub fn update_import_clause(
+        &self,
+        node: &Arc<Node>,
+        is_type_only: bool,
+        name: Option<Arc<Node>>,
+        named_bindings: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ImportClause {
+                is_type_only,
+                name,
+                named_bindings,
+            },
+        )
+    }
+
+    /// Updates `{ elements }` in an import declaration
+    pub fn update_named_imports(&self, node: &Arc<Node>, elements: NodeList) -> Arc<Node> {
+        self.update(node, NamedImports { elements })
+    }
+
+    /// Updates an export declaration
+    pub fn update_export_declaration(
+        &self,
+        node: &Arc<Node>,
+        modifiers: Option<NodeList>,
+        is_type_only: bool,
+        export_clause: Option<Arc<Node>>,
+        module_specifier: Option<Arc<Node>>,
+        attributes: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            ExportDeclaration {
+                modifiers,
+                is_type_only,
+                export_clause,
+                module_specifier,
+                attributes,
+            },
+        )
+    }
+
+    /// Updates `{ elements }` in an export declaration
+    pub fn update_named_exports(&self, node: &Arc<Node>, elements: NodeList) -> Arc<Node> {
+        self.update(node, NamedExports { elements })
+    }
+
+    /// Updates the opening tag of a JSX element
+    pub fn update_jsx_opening_element(
+        &self,
+        node: &Arc<Node>,
+        tag_name: Arc<Node>,
+        type_arguments: Option<NodeList>,
+        attributes: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            JsxOpeningElement {
+                tag_name,
+                type_arguments,
+                attributes,
+            },
+        )
+    }
+
+    /// Updates a self-closing JSX element
+    pub fn update_jsx_self_closing_element(
+        &self,
+        node: &Arc<Node>,
+        tag_name: Arc<Node>,
+        type_arguments: Option<NodeList>,
+        attributes: Arc<Node>,
+    ) -> Arc<Node> {
+        self.update(
+            node,
+            JsxSelfClosingElement {
+                tag_name,
+                type_arguments,
+                attributes,
+            },
+        )
+    }
+
+    /// Updates `name=initializer` on a JSX element
+    pub fn update_jsx_attribute(
+        &self,
+        node: &Arc<Node>,
+        name: Arc<Node>,
+        initializer: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        self.update(node, JsxAttribute { name, initializer })
+    }
+
+    fn finish_update(&self, original: &Node, data: Box<dyn NodeData>) -> Arc<Node> {
+        Node::new_detached(
+            original.kind,
+            original.flags | self.flags,
+            original.loc,
+            data,
+        )
+    }
+
+    /// Creates a copy of `node` that shares its children, covering `loc` instead
+    pub fn with_text_range(&self, node: &Node, loc: TextRange) -> Arc<Node> {
+        let data = node.data().map_children(&mut Identity);
+        Node::new_detached(node.kind, node.flags | self.flags, loc, data)
+    }
+
+    /// Creates a copy of `node` that shares its children and source range, with `flags` in
+    /// place of its node flags, such as a `let` declaration list turned into a `var` one
+    pub fn with_node_flags(&self, node: &Node, flags: NodeFlags) -> Arc<Node> {
+        let data = node.data().map_children(&mut Identity);
+        Node::new_detached(node.kind, flags | self.flags, node.loc, data)
+    }
+
+    /// Creates a copy of `node` that shares its children and source range
+    pub fn clone_node(&self, node: &Node) -> Arc<Node> {
+        self.with_text_range(node, node.loc)
+    }
+
+    /// Copies `node` and all of its descendants into a new tree without source positions
+    ///
+    /// JSDoc comments are not copied.
+    pub fn deep_clone(&self, node: &Arc<Node>) -> Arc<Node> {
+        DeepClone { factory: *self }.map_node(node)
+    }
+}
+
+/// Makes every node in the tree rooted at `root` the parent of its children
+///
+/// Synthesized trees often mix new nodes with reused ones whose parent pointers still lead
+/// into the tree they were taken from. Binding or checking such a tree needs the parents to
+/// match the tree being walked.
+pub fn set_parent_recursive(root: &Arc<Node>) {
+    root.for_each_child(&mut |child| {
+        child.set_parent(root);
+        set_parent_recursive(child);
+        false
+    });
+}
+
+struct Identity;
+
+impl NodeMapper for Identity {
+    fn map_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        Arc::clone(node)
+    }
+}
+
+struct DeepClone {
+    factory: NodeFactory,
+}
+
+impl NodeMapper for DeepClone {
+    fn map_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let data = node.data().map_children(self);
+        Node::new_detached(
+            node.kind,
+            node.flags | self.factory.flags,
+            TextRange::default(),
+            data,
+        )
+    }
+
+    fn map_list(&mut self, list: &NodeList) -> NodeList {
+        NodeList {
+            loc: TextRange::default(),
+            nodes: list.nodes.iter().map(|node| self.map_node(node)).collect(),
+            has_trailing_comma: list.has_trailing_comma,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::parser::parse_source_file;
+    use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+
+    fn first_statement(text: &str) -> (Arc<Node>, Arc<Node>) {
+        let file = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let statement = file.as_source_file().statements.nodes[0].clone();
+        (file, statement)
+    }
+
+    #[test]
+    fn test_update_reuses_unchanged_nodes() {
+        let factory = NodeFactory::new();
+        let (_file, statement) = first_statement("return x;");
+        let expression = statement.as_return_statement().expression.clone();
+
+        let same = factory.update(
+            &statement,
+            ReturnStatement {
+                expression: expression.clone(),
+            },
+        );
+        assert!(Arc::ptr_eq(&same, &statement));
+
+        let replacement = factory.create_identifier("y");
+        let updated = factory.update(
+            &statement,
+            ReturnStatement {
+                expression: Some(replacement.clone()),
+            },
+        );
+        assert!(!Arc::ptr_eq(&updated, &statement));
+        assert!(updated.flags.contains(NodeFlags::SYNTHESIZED));
+        assert_eq!(updated.loc, statement.loc);
+        assert!(Arc::ptr_eq(&replacement.parent().unwrap(), &updated));
+    }
+
+    #[test]
+    fn test_update_functions_reuse_unchanged_nodes() {
+        let factory = NodeFactory::new();
+        let (_file, statement) = first_statement("{ a; }");
+        let statements = statement.as_block().statements.clone();
+
+        let same = factory.update_block(&statement, statements.clone(), false);
+        assert!(Arc::ptr_eq(&same, &statement));
+
+        let updated = factory.update_block(&statement, statements, true);
+        assert!(!Arc::ptr_eq(&updated, &statement));
+        assert!(updated.as_block().multi_line);
+    }
+
+    #[test]
+    fn test_reused_children_keep_their_parent() {
+        let factory = NodeFactory::new();
+        let (_file, statement) = first_statement("f(a);");
+        let call = statement.as_expression_statement().expression.clone();
+        let wrapper = factory.create(
+            SyntaxKind::ParenthesizedExpression,
+            ParenthesizedExpression {
+                expression: call.clone(),
+            },
+        );
+        assert!(Arc::ptr_eq(&call.parent().unwrap(), &statement));
+
+        set_parent_recursive(&wrapper);
+        assert!(Arc::ptr_eq(&call.parent().unwrap(), &wrapper));
+    }
+
+    #[test]
+    fn test_deep_clone() {
+        let factory = NodeFactory::new();
+        let (_file, statement) = first_statement("let a = [1, 2];");
+        let clone = factory.deep_clone(&statement);
+        assert!(clone.flags.contains(NodeFlags::SYNTHESIZED));
+        assert_eq!(clone.loc, TextRange::default());
+
+        let mut originals = Vec::new();
+        statement.for_each_child(&mut |child| {
+            originals.push(child.clone());
+            false
+        });
+        clone.for_each_child(&mut |child| {
+            assert!(
+                originals
+                    .iter()
+                    .all(|original| !Arc::ptr_eq(original, child))
+            );
+            assert!(Arc::ptr_eq(&child.parent().unwrap(), &clone));
+            false
+        });
+    }
+}
@@ -1,5 +1,5 @@
 /// Represents a syntax token or node kind in the TypeScript AST.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(i16)]
 pub enum SyntaxKind {
     Unknown,
@@ -384,38 +384,38 @@ pub enum SyntaxKind {
 }
 
 impl SyntaxKind {
-    // // Marker constants
-    // pub const FIRST_ASSIGNMENT: Self = Self::EqualsToken;
-    // pub const LAST_ASSIGNMENT: Self = Self::CaretEqualsToken;
-    // pub const FIRST_COMPOUND_ASSIGNMENT: Self = Self::PlusEqualsToken;
-    // pub const LAST_COMPOUND_ASSIGNMENT: Self = Self::CaretEqualsToken;
-    // pub const FIRST_RESERVED_WORD: Self = Self::BreakKeyword;
-    // pub const LAST_RESERVED_WORD: Self = Self::WithKeyword;
-    // pub const FIRST_KEYWORD: Self = Self::BreakKeyword;
-    // pub const LAST_KEYWORD: Self = Self::OfKeyword;
-    // pub const FIRST_FUTURE_RESERVED_WORD: Self = Self::ImplementsKeyword;
-    // pub const LAST_FUTURE_RESERVED_WORD: Self = Self::YieldKeyword;
-    // pub const FIRST_TYPE_NODE: Self = Self::TypePredicate;
-    // pub const LAST_TYPE_NODE: Self = Self::ImportType;
-    // pub const FIRST_PUNCTUATION: Self = Self::OpenBraceToken;
-    // pub const LAST_PUNCTUATION: Self = Self::CaretEqualsToken;
-    // pub const FIRST_TOKEN: Self = Self::Unknown;
-    // pub const LAST_TOKEN: Self = Self::LastKeyword;
-    // pub const FIRST_LITERAL_TOKEN: Self = Self::NumericLiteral;
-    // pub const LAST_LITERAL_TOKEN: Self = Self::NoSubstitutionTemplateLiteral;
-    // pub const FIRST_TEMPLATE_TOKEN: Self = Self::NoSubstitutionTemplateLiteral;
-    // pub const LAST_TEMPLATE_TOKEN: Self = Self::TemplateTail;
-    // pub const FIRST_BINARY_OPERATOR: Self = Self::LessThanToken;
-    // pub const LAST_BINARY_OPERATOR: Self = Self::CaretEqualsToken;
-    // pub const FIRST_STATEMENT: Self = Self::VariableStatement;
-    // pub const LAST_STATEMENT: Self = Self::DebuggerStatement;
-    // pub const FIRST_NODE: Self = Self::QualifiedName;
-    // pub const FIRST_JSDOC_NODE: Self = Self::JSDocTypeExpression;
-    // pub const LAST_JSDOC_NODE: Self = Self::JSDocImportTag;
-    // pub const FIRST_JSDOC_TAG_NODE: Self = Self::JSDocTag;
-    // pub const LAST_JSDOC_TAG_NODE: Self = Self::JSDocImportTag;
-    // pub const FIRST_CONTEXTUAL_KEYWORD: Self = Self::AbstractKeyword;
-    // pub const LAST_CONTEXTUAL_KEYWORD: Self = Self::OfKeyword;
+    // Marker constants
+    pub const FIRST_ASSIGNMENT: Self = Self::EqualsToken;
+    pub const LAST_ASSIGNMENT: Self = Self::CaretEqualsToken;
+    pub const FIRST_COMPOUND_ASSIGNMENT: Self = Self::PlusEqualsToken;
+    pub const LAST_COMPOUND_ASSIGNMENT: Self = Self::CaretEqualsToken;
+    pub const FIRST_RESERVED_WORD: Self = Self::BreakKeyword;
+    pub const LAST_RESERVED_WORD: Self = Self::WithKeyword;
+    pub const FIRST_KEYWORD: Self = Self::BreakKeyword;
+    pub const LAST_KEYWORD: Self = Self::OfKeyword;
+    pub const FIRST_FUTURE_RESERVED_WORD: Self = Self::ImplementsKeyword;
+    pub const LAST_FUTURE_RESERVED_WORD: Self = Self::YieldKeyword;
+    pub const FIRST_TYPE_NODE: Self = Self::TypePredicate;
+    pub const LAST_TYPE_NODE: Self = Self::ImportType;
+    pub const FIRST_PUNCTUATION: Self = Self::OpenBraceToken;
+    pub const LAST_PUNCTUATION: Self = Self::CaretEqualsToken;
+    pub const FIRST_TOKEN: Self = Self::Unknown;
+    pub const LAST_TOKEN: Self = Self::OfKeyword;
+    pub const FIRST_LITERAL_TOKEN: Self = Self::NumericLiteral;
+    pub const LAST_LITERAL_TOKEN: Self = Self::NoSubstitutionTemplateLiteral;
+    pub const FIRST_TEMPLATE_TOKEN: Self = Self::NoSubstitutionTemplateLiteral;
+    pub const LAST_TEMPLATE_TOKEN: Self = Self::TemplateTail;
+    pub const FIRST_BINARY_OPERATOR: Self = Self::LessThanToken;
+    pub const LAST_BINARY_OPERATOR: Self = Self::CaretEqualsToken;
+    pub const FIRST_STATEMENT: Self = Self::VariableStatement;
+    pub const LAST_STATEMENT: Self = Self::DebuggerStatement;
+    pub const FIRST_NODE: Self = Self::QualifiedName;
+    pub const FIRST_JSDOC_NODE: Self = Self::JSDocTypeExpression;
+    pub const LAST_JSDOC_NODE: Self = Self::JSDocImportTag;
+    pub const FIRST_JSDOC_TAG_NODE: Self = Self::JSDocTag;
+    pub const LAST_JSDOC_TAG_NODE: Self = Self::JSDocImportTag;
+    pub const FIRST_CONTEXTUAL_KEYWORD: Self = Self::AbstractKeyword;
+    pub const LAST_CONTEXTUAL_KEYWORD: Self = Self::OfKeyword;
 
     /// Check if this syntax kind represents a comment
     pub fn is_comment(&self) -> bool {
@@ -424,4 +424,66 @@ impl SyntaxKind {
             Self::SingleLineCommentTrivia | Self::MultiLineCommentTrivia
         )
     }
+
+    /// Check if this syntax kind is a token (punctuation, keyword, literal, or trivia)
+    pub fn is_token(&self) -> bool {
+        *self >= Self::FIRST_TOKEN && *self <= Self::LAST_TOKEN
+    }
+
+    /// Check if this syntax kind is a literal token
+    pub fn is_literal(&self) -> bool {
+        *self >= Self::FIRST_LITERAL_TOKEN && *self <= Self::LAST_LITERAL_TOKEN
+    }
+
+    /// Check if this syntax kind is a reserved word or contextual keyword
+    pub fn is_keyword(&self) -> bool {
+        *self >= Self::FIRST_KEYWORD && *self <= Self::LAST_KEYWORD
+    }
+
+    /// Check if this syntax kind is a contextual keyword, which can also be used as an identifier
+    pub fn is_contextual_keyword(&self) -> bool {
+        *self >= Self::FIRST_CONTEXTUAL_KEYWORD && *self <= Self::LAST_CONTEXTUAL_KEYWORD
+    }
+
+    /// Check if this syntax kind is a keyword that is reserved only in strict mode
+    pub fn is_future_reserved_word(&self) -> bool {
+        *self >= Self::FIRST_FUTURE_RESERVED_WORD && *self <= Self::LAST_FUTURE_RESERVED_WORD
+    }
+
+    /// Check if this syntax kind is an assignment operator
+    pub fn is_assignment_operator(&self) -> bool {
+        *self >= Self::FIRST_ASSIGNMENT && *self <= Self::LAST_ASSIGNMENT
+    }
+
+    /// Check if this syntax kind is a type node
+    pub fn is_type_node(&self) -> bool {
+        *self >= Self::FIRST_TYPE_NODE && *self <= Self::LAST_TYPE_NODE
+    }
+
+    /// Check if this syntax kind is a JSDoc node
+    pub fn is_jsdoc_node(&self) -> bool {
+        *self >= Self::FIRST_JSDOC_NODE && *self <= Self::LAST_JSDOC_NODE
+    }
+
+    /// Check if this syntax kind is a modifier keyword
+    pub fn is_modifier(&self) -> bool {
+        matches!(
+            self,
+            Self::AbstractKeyword
+                | Self::AccessorKeyword
+                | Self::AsyncKeyword
+                | Self::ConstKeyword
+                | Self::DeclareKeyword
+                | Self::DefaultKeyword
+                | Self::ExportKeyword
+                | Self::InKeyword
+                | Self::PublicKeyword
+                | Self::PrivateKeyword
+                | Self::ProtectedKeyword
+                | Self::ReadonlyKeyword
+                | Self::StaticKeyword
+                | Self::OutKeyword
+                | Self::OverrideKeyword
+        )
+    }
 }
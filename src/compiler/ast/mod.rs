@@ -1,9 +1,26 @@
+pub mod arena;
 pub mod check_flags;
+pub mod factory;
 pub mod ids;
 pub mod kind;
 pub mod node;
 pub mod node_flags;
+pub mod nodes;
 pub mod symbol;
 pub mod symbol_flags;
+pub mod utilities;
+pub mod visitor;
 
+pub use arena::NodeArena;
+pub use check_flags::CheckFlags;
+pub use factory::{NodeFactory, set_parent_recursive};
+pub use ids::{NodeId, SymbolId};
 pub use kind::*;
+pub use node::{ModifierList, Node, NodeData, NodeList, NodeMapper};
+pub use node_flags::NodeFlags;
+pub use symbol::{Symbol, SymbolArena, SymbolTable, internal_symbol_names};
+pub use symbol_flags::SymbolFlags;
+pub use visitor::{
+    NodeVisitor, Visitor, find_ancestor, for_each_descendant, get_token_at_position,
+    visit_each_child, visit_nodes, walk,
+};
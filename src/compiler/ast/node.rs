@@ -1,17 +1,26 @@
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::any::Any;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock, Weak};
 
+use crate::compiler::ast::ids::NodeId;
 use crate::compiler::ast::kind::SyntaxKind;
 use crate::compiler::ast::node_flags::NodeFlags;
-use crate::compiler::ast::symbol::Symbol;
-use crate::compiler::ast::visitor::{NodeVisitor, Visitor};
+use crate::compiler::ast::nodes::Token;
 use crate::compiler::text::TextRange;
 
+/// The next node id to hand out; 0 means "no id"
+static NEXT_NODE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Reserves `count` consecutive node ids and returns the first
+pub(crate) fn reserve_node_ids(count: u64) -> u64 {
+    NEXT_NODE_ID.fetch_add(count, Ordering::SeqCst)
+}
+
 /// AST Node - fundamental building block of the TypeScript abstract syntax tree
 ///
-/// Interface values stored in AST nodes are never null. Construction code must ensure that
-/// interface valued properties either store None or a reference to a valid struct.
+/// Nodes are immutable once created, apart from their id, parent pointer, and attached JSDoc.
+/// Children are owned by their parent through `Arc`s; the parent pointer is a `Weak` back
+/// reference that is filled in when the parent is constructed.
 #[derive(Debug)]
 pub struct Node {
     /// Syntactic kind of the node
@@ -27,83 +36,122 @@ pub struct Node {
     id: AtomicU64,
 
     /// Parent node reference
-    pub parent: Option<Arc<Node>>,
+    parent: RwLock<Weak<Node>>,
+
+    /// JSDoc comments preceding this node, set once by the parser
+    jsdoc: OnceLock<Vec<Arc<Node>>>,
 
     /// Underlying node data (uses trait object instead of Go's interface)
-    data: Arc<dyn NodeData>,
+    data: Box<dyn NodeData>,
 }
 
 /// NodeData provides specialized data and behavior based on node type
-pub trait NodeData: std::fmt::Debug + Send + Sync {
-    /// Process each child node with the given visitor
-    fn for_each_child(&self, v: &mut dyn Visitor) -> bool;
+pub trait NodeData: Any + std::fmt::Debug + Send + Sync {
+    /// Get the data as `Any` so it can be downcast to its concrete type
+    fn as_any(&self) -> &dyn Any;
+
+    /// Invoke the callback on each child node in source order, stopping early when it returns true
+    fn for_each_child(&self, v: &mut dyn FnMut(&Arc<Node>) -> bool) -> bool;
+
+    /// Create a copy of this data with each child replaced by the mapper's result
+    fn map_children(&self, mapper: &mut dyn NodeMapper) -> Box<dyn NodeData>;
 
-    /// Create a deep copy of this node
-    fn clone_node(&self, factory: &NodeFactory) -> Arc<Node>;
+    /// Move each child node into `children`, consuming the data
+    fn take_children(self: Box<Self>, children: &mut Vec<Arc<Node>>);
 
-    /// Transform child nodes with the given visitor
-    fn visit_each_child(&self, v: &NodeVisitor) -> Arc<Node>;
+    /// Determines if `other` is the same type of data with the very same children and equal plain data
+    fn shallow_eq(&self, other: &dyn NodeData) -> bool;
 
     /// Get the declaration name if this node has one
-    fn name(&self) -> Option<Arc<DeclarationName>> {
+    fn name(&self) -> Option<&Arc<Node>> {
         None
     }
 
     /// Get modifiers if this node has them
-    fn modifiers(&self) -> Option<Arc<ModifierList>> {
+    fn modifiers(&self) -> Option<&ModifierList> {
         None
     }
 
-    /// Get flow analysis data if applicable
-    fn flow_node_data(&self) -> Option<Arc<FlowNodeBase>> {
+    /// Get type parameters if this node has them
+    fn type_parameters(&self) -> Option<&NodeList> {
         None
     }
 
-    /// Get declaration data if this node is a declaration
-    fn declaration_data(&self) -> Option<Arc<DeclarationBase>> {
+    /// Get parameters if this node is a function-like declaration
+    fn parameters(&self) -> Option<&NodeList> {
         None
     }
 
-    /// Get exportable data if this node is exportable
-    fn exportable_data(&self) -> Option<Arc<ExportableBase>> {
+    /// Get the type annotation if this node has one
+    fn type_node(&self) -> Option<&Arc<Node>> {
         None
     }
 
-    /// Get locals container data if this node contains local variables
-    fn locals_container_data(&self) -> Option<Arc<LocalsContainerBase>> {
+    /// Get the body node if this node has a body
+    fn body(&self) -> Option<&Arc<Node>> {
         None
     }
 
-    /// Get function-like data if this node is a function-like declaration
-    fn function_like_data(&self) -> Option<Arc<FunctionLikeBase>> {
+    /// Get the initializer if this node has one
+    fn initializer(&self) -> Option<&Arc<Node>> {
         None
     }
 
-    /// Get class-like data if this node is a class-like declaration
-    fn class_like_data(&self) -> Option<Arc<ClassLikeBase>> {
+    /// Get the inner expression if this node wraps one
+    fn expression(&self) -> Option<&Arc<Node>> {
         None
     }
 
-    /// Get body data if this node has a body
-    fn body_data(&self) -> Option<Arc<BodyBase>> {
+    /// Get the statement list if this node contains statements
+    fn statements(&self) -> Option<&NodeList> {
         None
     }
 
-    /// Get literal-like data if this node is a literal
-    fn literal_like_data(&self) -> Option<Arc<LiteralLikeBase>> {
+    /// Get the member list if this node has members
+    fn members(&self) -> Option<&NodeList> {
         None
     }
+}
 
-    /// Get template literal data if this node is a template literal
-    fn template_literal_like_data(&self) -> Option<Arc<TemplateLiteralLikeBase>> {
-        None
+/// Produces replacement children when copying node data with [`NodeData::map_children`]
+pub trait NodeMapper {
+    /// Map a single child node
+    fn map_node(&mut self, node: &Arc<Node>) -> Arc<Node>;
+
+    /// Map a list of child nodes, keeping its range and trailing comma by default
+    fn map_list(&mut self, list: &NodeList) -> NodeList {
+        NodeList {
+            loc: list.loc,
+            nodes: list.nodes.iter().map(|node| self.map_node(node)).collect(),
+            has_trailing_comma: list.has_trailing_comma,
+        }
     }
 }
 
-impl Node {
-    /// Get the node itself
-    pub fn as_node(&self) -> &Node {
-        self
+/// A list of nodes with the source range that encloses them
+#[derive(Debug, Clone, Default)]
+pub struct NodeList {
+    /// Source text position information, including any surrounding delimiters
+    pub loc: TextRange,
+
+    /// The nodes in the list
+    pub nodes: Vec<Arc<Node>>,
+
+    /// Whether the list ended with a trailing comma
+    pub has_trailing_comma: bool,
+}
+
+/// Modifiers and decorators of a declaration
+pub type ModifierList = NodeList;
+
+impl NodeList {
+    /// Create a new node list
+    pub fn new(loc: TextRange, nodes: Vec<Arc<Node>>) -> Self {
+        NodeList {
+            loc,
+            nodes,
+            has_trailing_comma: false,
+        }
     }
 
     /// Get start position in source
@@ -116,114 +164,299 @@ impl Node {
         self.loc.end()
     }
 
-    /// Process each child with the given visitor
-    pub fn for_each_child(&self, v: &mut dyn Visitor) -> bool {
-        self.data.for_each_child(v)
+    /// Get the number of nodes in the list
+    pub fn len(&self) -> usize {
+        self.nodes.len()
     }
 
-    /// Create a deep copy of this node
-    pub fn clone(&self, f: &NodeFactory) -> Arc<Node> {
-        self.data.clone_node(f)
+    /// Determines if the list has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
     }
 
-    /// Transform child nodes with the given visitor
-    pub fn visit_each_child(&self, v: &NodeVisitor) -> Arc<Node> {
-        self.data.visit_each_child(v)
+    /// Determines if both lists hold the very same nodes, ignoring their ranges
+    pub fn same_nodes(&self, other: &NodeList) -> bool {
+        self.has_trailing_comma == other.has_trailing_comma
+            && self.nodes.len() == other.nodes.len()
+            && self
+                .nodes
+                .iter()
+                .zip(&other.nodes)
+                .all(|(a, b)| Arc::ptr_eq(a, b))
     }
 
-    /// Get the declaration name if this node has one
-    pub fn name(&self) -> Option<Arc<DeclarationName>> {
-        self.data.name()
+    /// Iterate over the nodes in the list
+    pub fn iter(&self) -> std::slice::Iter<'_, Arc<Node>> {
+        self.nodes.iter()
     }
+}
 
-    /// Get modifiers if this node has them
-    pub fn modifiers(&self) -> Option<Arc<ModifierList>> {
-        self.data.modifiers()
+impl<'a> IntoIterator for &'a NodeList {
+    type Item = &'a Arc<Node>;
+    type IntoIter = std::slice::Iter<'a, Arc<Node>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.nodes.iter()
+    }
+}
+
+impl Drop for Node {
+    /// Drops the children only this node holds one at a time rather than each in turn dropping
+    /// its own, so deep trees such as long chains of binary expressions do not overflow the stack
+    fn drop(&mut self) {
+        let mut children = Vec::new();
+        self.take_children(&mut children);
+        while let Some(child) = children.pop() {
+            if let Some(mut child) = Arc::into_inner(child) {
+                child.take_children(&mut children);
+            }
+        }
+    }
+}
+
+impl Node {
+    /// Create a new node and make it the parent of each of its children
+    pub fn new(
+        kind: SyntaxKind,
+        flags: NodeFlags,
+        loc: TextRange,
+        data: impl NodeData,
+    ) -> Arc<Node> {
+        Self::new_boxed(kind, flags, loc, Box::new(data))
+    }
+
+    /// Create a new node from already boxed data, such as the result of [`NodeData::map_children`]
+    pub fn new_boxed(
+        kind: SyntaxKind,
+        flags: NodeFlags,
+        loc: TextRange,
+        data: Box<dyn NodeData>,
+    ) -> Arc<Node> {
+        Self::new_with_parenting(kind, flags, loc, data, false)
+    }
+
+    /// Create a new node that only becomes the parent of children that have none yet
+    ///
+    /// Used for synthesized nodes, which may reuse children from an existing tree without
+    /// detaching them from it.
+    pub fn new_detached(
+        kind: SyntaxKind,
+        flags: NodeFlags,
+        loc: TextRange,
+        data: Box<dyn NodeData>,
+    ) -> Arc<Node> {
+        Self::new_with_parenting(kind, flags, loc, data, true)
+    }
+
+    fn new_with_parenting(
+        kind: SyntaxKind,
+        flags: NodeFlags,
+        loc: TextRange,
+        data: Box<dyn NodeData>,
+        orphans_only: bool,
+    ) -> Arc<Node> {
+        Arc::new_cyclic(|this| {
+            data.for_each_child(&mut |child| {
+                let mut parent = child.parent.write().unwrap();
+                if !orphans_only || parent.strong_count() == 0 {
+                    *parent = this.clone();
+                }
+                false
+            });
+            Node {
+                kind,
+                flags,
+                loc,
+                id: AtomicU64::new(0),
+                parent: RwLock::new(Weak::new()),
+                jsdoc: OnceLock::new(),
+                data,
+            }
+        })
     }
 
-    /// Get flow analysis data if applicable
-    pub fn flow_node_data(&self) -> Option<Arc<FlowNodeBase>> {
-        self.data.flow_node_data()
+    /// Move the children and JSDoc of this node into `children`, leaving it a childless token
+    fn take_children(&mut self, children: &mut Vec<Arc<Node>>) {
+        std::mem::replace(&mut self.data, Box::new(Token {})).take_children(children);
+        children.extend(self.jsdoc.take().into_iter().flatten());
     }
 
-    /// Get declaration data if this node is a declaration
-    pub fn declaration_data(&self) -> Option<Arc<DeclarationBase>> {
-        self.data.declaration_data()
+    /// Get the node itself
+    pub fn as_node(&self) -> &Node {
+        self
     }
 
-    /// Get exportable data if this node is exportable
-    pub fn exportable_data(&self) -> Option<Arc<ExportableBase>> {
-        self.data.exportable_data()
+    /// Get start position in source
+    pub fn pos(&self) -> usize {
+        self.loc.pos()
     }
 
-    /// Get locals container data if this node contains local variables
-    pub fn locals_container_data(&self) -> Option<Arc<LocalsContainerBase>> {
-        self.data.locals_container_data()
+    /// Get end position in source
+    pub fn end(&self) -> usize {
+        self.loc.end()
     }
 
-    /// Get function-like data if this node is a function-like declaration
-    pub fn function_like_data(&self) -> Option<Arc<FunctionLikeBase>> {
-        self.data.function_like_data()
+    /// Get the parent node, if this node has been attached to one
+    pub fn parent(&self) -> Option<Arc<Node>> {
+        self.parent.read().unwrap().upgrade()
     }
 
-    /// Get parameter list if this node is a function-like declaration
-    pub fn parameter_list(&self) -> Option<Arc<ParameterList>> {
-        self.function_like_data()
-            .map(|data| data.parameters.clone())
+    /// Make `parent` the parent of this node
+    pub(crate) fn set_parent(&self, parent: &Arc<Node>) {
+        *self.parent.write().unwrap() = Arc::downgrade(parent);
     }
 
-    /// Get parameters if this node is a function-like declaration
-    pub fn parameters(&self) -> Option<Vec<Arc<ParameterDeclarationNode>>> {
-        self.parameter_list().map(|list| list.nodes.clone())
+    /// Get the underlying node data
+    pub fn data(&self) -> &dyn NodeData {
+        self.data.as_ref()
     }
 
-    /// Get class-like data if this node is a class-like declaration
-    pub fn class_like_data(&self) -> Option<Arc<ClassLikeBase>> {
-        self.data.class_like_data()
+    /// Get the underlying node data as a concrete type
+    pub fn data_as<T: NodeData>(&self) -> Option<&T> {
+        self.data.as_any().downcast_ref::<T>()
     }
 
-    /// Get body data if this node has a body
-    pub fn body_data(&self) -> Option<Arc<BodyBase>> {
-        self.data.body_data()
+    /// Invoke the callback on each child node in source order, stopping early when it returns true
+    pub fn for_each_child(&self, v: &mut dyn FnMut(&Arc<Node>) -> bool) -> bool {
+        self.data.for_each_child(v)
     }
 
-    /// Get literal-like data if this node is a literal
-    pub fn literal_like_data(&self) -> Option<Arc<LiteralLikeBase>> {
-        self.data.literal_like_data()
+    /// Get the declaration name if this node has one
+    pub fn name(&self) -> Option<&Arc<Node>> {
+        self.data.name()
     }
 
-    /// Get template literal data if this node is a template literal
-    pub fn template_literal_like_data(&self) -> Option<Arc<TemplateLiteralLikeBase>> {
-        self.data.template_literal_like_data()
+    /// Get modifiers if this node has them
+    pub fn modifiers(&self) -> Option<&ModifierList> {
+        self.data.modifiers()
     }
 
-    /// Get the symbol for this node
-    pub fn symbol(&self) -> Option<Arc<Symbol>> {
-        self.declaration_data().and_then(|data| data.symbol.clone())
+    /// Get type parameters if this node has them
+    pub fn type_parameters(&self) -> Option<&NodeList> {
+        self.data.type_parameters()
     }
 
-    /// Get the local symbol for this node if it's exportable
-    pub fn local_symbol(&self) -> Option<Arc<Symbol>> {
-        self.exportable_data()
-            .and_then(|data| data.local_symbol.clone())
+    /// Get parameters if this node is a function-like declaration
+    pub fn parameters(&self) -> Option<&NodeList> {
+        self.data.parameters()
     }
 
-    /// Get the locals table for this node if it's a locals container
-    pub fn locals(&self) -> Option<HashMap<String, Arc<Symbol>>> {
-        self.locals_container_data()
-            .and_then(|data| data.locals.clone())
+    /// Get the type annotation if this node has one
+    pub fn type_node(&self) -> Option<&Arc<Node>> {
+        self.data.type_node()
     }
 
     /// Get the body node if this node has a body
-    pub fn body(&self) -> Option<Arc<Node>> {
-        self.body_data().and_then(|data| data.body.clone())
+    pub fn body(&self) -> Option<&Arc<Node>> {
+        self.data.body()
+    }
+
+    /// Get the initializer if this node has one
+    pub fn initializer(&self) -> Option<&Arc<Node>> {
+        self.data.initializer()
+    }
+
+    /// Get the inner expression if this node wraps one
+    pub fn expression(&self) -> Option<&Arc<Node>> {
+        self.data.expression()
+    }
+
+    /// Get the statement list if this node contains statements
+    pub fn statements(&self) -> Option<&NodeList> {
+        self.data.statements()
+    }
+
+    /// Get the member list if this node has members
+    pub fn members(&self) -> Option<&NodeList> {
+        self.data.members()
+    }
+
+    /// Get the text of an identifier, private identifier, or literal node
+    pub fn text(&self) -> &str {
+        use crate::compiler::ast::nodes::*;
+        let data = self.data.as_any();
+        if let Some(d) = data.downcast_ref::<Identifier>() {
+            &d.text
+        } else if let Some(d) = data.downcast_ref::<PrivateIdentifier>() {
+            &d.text
+        } else if let Some(d) = data.downcast_ref::<StringLiteral>() {
+            &d.text
+        } else if let Some(d) = data.downcast_ref::<NumericLiteral>() {
+            &d.text
+        } else if let Some(d) = data.downcast_ref::<BigIntLiteral>() {
+            &d.text
+        } else if let Some(d) = data.downcast_ref::<RegularExpressionLiteral>() {
+            &d.text
+        } else if let Some(d) = data.downcast_ref::<TemplateLiteralLikeNode>() {
+            &d.text
+        } else if let Some(d) = data.downcast_ref::<JsxText>() {
+            &d.text
+        } else {
+            ""
+        }
     }
 
-    /// Get the unique ID for this node
+    /// Determines if this node has a modifier of the given kind
+    pub fn has_modifier(&self, kind: SyntaxKind) -> bool {
+        self.modifiers()
+            .is_some_and(|modifiers| modifiers.iter().any(|m| m.kind == kind))
+    }
+
+    /// Get the JSDoc comments attached to this node
+    pub fn jsdoc(&self) -> &[Arc<Node>] {
+        self.jsdoc.get().map_or(&[], Vec::as_slice)
+    }
+
+    /// Attach JSDoc comments to this node and make it their parent
+    ///
+    /// Panics if JSDoc has already been attached.
+    pub(crate) fn set_jsdoc(self: &Arc<Self>, jsdoc: Vec<Arc<Node>>) {
+        for comment in &jsdoc {
+            *comment.parent.write().unwrap() = Arc::downgrade(self);
+        }
+        if self.jsdoc.set(jsdoc).is_err() {
+            panic!("JSDoc already attached to {:?}", self.kind);
+        }
+    }
+
+    /// Determines if this node has a JSDoc `@deprecated` tag
+    pub fn is_deprecated(&self) -> bool {
+        self.jsdoc().iter().any(|comment| {
+            comment
+                .data_as::<crate::compiler::ast::nodes::JSDoc>()
+                .is_some_and(|jsdoc| {
+                    jsdoc
+                        .tags
+                        .iter()
+                        .any(|tag| tag.kind == SyntaxKind::JSDocDeprecatedTag)
+                })
+        })
+    }
+
+    /// Get the unique ID for this node, or 0 if it has not been given one yet
     pub fn id(&self) -> u64 {
         self.id.load(Ordering::SeqCst)
     }
 
+    /// Get the id of this node, giving it the next unused id on first use
+    ///
+    /// Ids are unique across every tree in the process, so side tables keyed by [`NodeId`] can
+    /// hold nodes from any number of source files.
+    pub fn node_id(&self) -> NodeId {
+        let id = self.id();
+        if id != 0 {
+            return NodeId::new(id);
+        }
+        let new_id = reserve_node_ids(1);
+        match self
+            .id
+            .compare_exchange(0, new_id, Ordering::SeqCst, Ordering::SeqCst)
+        {
+            Ok(_) => NodeId::new(new_id),
+            Err(existing) => NodeId::new(existing),
+        }
+    }
+
     /// Set the unique ID for this node
     pub fn set_id(&self, id: u64) {
         self.id.store(id, Ordering::SeqCst);
@@ -235,25 +468,29 @@ impl Node {
     /// panic if descendant or one of its ancestors is not parented, except when
     /// that node is a SourceFile.
     pub fn contains(&self, descendant: &Node) -> bool {
-        let mut current = Some(descendant);
+        if std::ptr::eq(descendant, self) {
+            return true;
+        }
+        let mut current = descendant.parent_or_panic();
 
         while let Some(node) = current {
-            if std::ptr::eq(node, self) {
+            if std::ptr::eq(node.as_ref(), self) {
                 return true;
             }
-
-            // Handle SourceFile as a special case
-            if node.kind == SyntaxKind::SourceFile {
-                return false;
-            }
-
-            // Get the parent or panic if there's no parent
-            match &node.parent {
-                Some(parent) => current = Some(parent.as_ref()),
-                None => panic!("Node not parented: {:?}", node),
-            }
+            current = node.parent_or_panic();
         }
 
         false
     }
+
+    fn parent_or_panic(&self) -> Option<Arc<Node>> {
+        // Handle SourceFile as a special case
+        if self.kind == SyntaxKind::SourceFile {
+            return None;
+        }
+        match self.parent() {
+            Some(parent) => Some(parent),
+            None => panic!("Node not parented: {:?}", self.kind),
+        }
+    }
 }
@@ -0,0 +1,1370 @@
+//! Node data definitions for every syntax kind.
+//!
+//! Each entry in the `define_nodes!` invocation below declares the data struct stored in a
+//! [`Node`] of that shape. Fields are tagged with how they participate in the tree:
+//!
+//! - `node` — a required child node
+//! - `opt` — an optional child node
+//! - `list` — a required list of child nodes
+//! - `opt_list` — an optional list of child nodes
+//! - `data` — plain data that is not part of the tree
+//! - `parsed` — what the parser recorded about the tree it built, which copies of the data
+//!   start without, since their trees may differ, and which comparisons of the data ignore
+//!
+//! Children are visited in declaration order, which must match source order.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::compiler::ast::arena::NodeArena;
+use crate::compiler::ast::kind::SyntaxKind;
+use crate::compiler::ast::node::{Node, NodeData, NodeList, NodeMapper};
+use crate::compiler::diagnostics::Diagnostic;
+use crate::compiler::scanner::{
+    JSDocParsingMode, LanguageVariant, ScriptKind, ScriptTarget, TokenFlags,
+};
+
+macro_rules! field_type {
+    (node) => { Arc<Node> };
+    (opt) => { Option<Arc<Node>> };
+    (list) => { NodeList };
+    (opt_list) => { Option<NodeList> };
+    (data $t:ty) => { $t };
+    (parsed $t:ty) => { $t };
+}
+
+macro_rules! visit_field {
+    (node, $field:expr, $v:ident) => {
+        if $v($field) {
+            return true;
+        }
+    };
+    (opt, $field:expr, $v:ident) => {
+        if let Some(child) = $field {
+            if $v(child) {
+                return true;
+            }
+        }
+    };
+    (list, $field:expr, $v:ident) => {
+        for child in &$field.nodes {
+            if $v(child) {
+                return true;
+            }
+        }
+    };
+    (opt_list, $field:expr, $v:ident) => {
+        if let Some(list) = $field {
+            for child in &list.nodes {
+                if $v(child) {
+                    return true;
+                }
+            }
+        }
+    };
+    (data, $field:expr, $v:ident) => {};
+    (parsed, $field:expr, $v:ident) => {};
+}
+
+macro_rules! map_field {
+    (node, $field:expr, $mapper:ident) => {
+        $mapper.map_node($field)
+    };
+    (opt, $field:expr, $mapper:ident) => {
+        $field.as_ref().map(|child| $mapper.map_node(child))
+    };
+    (list, $field:expr, $mapper:ident) => {
+        $mapper.map_list($field)
+    };
+    (opt_list, $field:expr, $mapper:ident) => {
+        $field.as_ref().map(|list| $mapper.map_list(list))
+    };
+    (data, $field:expr, $mapper:ident) => {
+        $field.clone()
+    };
+    (parsed, $field:expr, $mapper:ident) => {
+        Default::default()
+    };
+}
+
+macro_rules! take_field {
+    (node, $field:expr, $children:ident) => {
+        $children.push($field)
+    };
+    (opt, $field:expr, $children:ident) => {
+        $children.extend($field)
+    };
+    (list, $field:expr, $children:ident) => {
+        $children.extend($field.nodes)
+    };
+    (opt_list, $field:expr, $children:ident) => {
+        $children.extend($field.into_iter().flat_map(|list| list.nodes))
+    };
+    (data, $field:expr, $children:ident) => {};
+    (parsed, $field:expr, $children:ident) => {};
+}
+
+macro_rules! field_eq {
+    (node, $a:expr, $b:expr) => {
+        Arc::ptr_eq($a, $b)
+    };
+    (opt, $a:expr, $b:expr) => {
+        match ($a, $b) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    };
+    (list, $a:expr, $b:expr) => {
+        $a.same_nodes($b)
+    };
+    (opt_list, $a:expr, $b:expr) => {
+        match ($a, $b) {
+            (Some(a), Some(b)) => a.same_nodes(b),
+            (None, None) => true,
+            _ => false,
+        }
+    };
+    (data, $a:expr, $b:expr) => {
+        $a == $b
+    };
+    (parsed, $a:expr, $b:expr) => {
+        true
+    };
+}
+
+/// Implements the well-known `NodeData` accessors for fields with conventional names
+macro_rules! node_accessor {
+    (node name) => {
+        fn name(&self) -> Option<&Arc<Node>> {
+            Some(&self.name)
+        }
+    };
+    (opt name) => {
+        fn name(&self) -> Option<&Arc<Node>> {
+            self.name.as_ref()
+        }
+    };
+    (opt_list modifiers) => {
+        fn modifiers(&self) -> Option<&NodeList> {
+            self.modifiers.as_ref()
+        }
+    };
+    (opt_list type_parameters) => {
+        fn type_parameters(&self) -> Option<&NodeList> {
+            self.type_parameters.as_ref()
+        }
+    };
+    (list parameters) => {
+        fn parameters(&self) -> Option<&NodeList> {
+            Some(&self.parameters)
+        }
+    };
+    (node type_node) => {
+        fn type_node(&self) -> Option<&Arc<Node>> {
+            Some(&self.type_node)
+        }
+    };
+    (opt type_node) => {
+        fn type_node(&self) -> Option<&Arc<Node>> {
+            self.type_node.as_ref()
+        }
+    };
+    (node body) => {
+        fn body(&self) -> Option<&Arc<Node>> {
+            Some(&self.body)
+        }
+    };
+    (opt body) => {
+        fn body(&self) -> Option<&Arc<Node>> {
+            self.body.as_ref()
+        }
+    };
+    (node initializer) => {
+        fn initializer(&self) -> Option<&Arc<Node>> {
+            Some(&self.initializer)
+        }
+    };
+    (opt initializer) => {
+        fn initializer(&self) -> Option<&Arc<Node>> {
+            self.initializer.as_ref()
+        }
+    };
+    (node expression) => {
+        fn expression(&self) -> Option<&Arc<Node>> {
+            Some(&self.expression)
+        }
+    };
+    (opt expression) => {
+        fn expression(&self) -> Option<&Arc<Node>> {
+            self.expression.as_ref()
+        }
+    };
+    (list statements) => {
+        fn statements(&self) -> Option<&NodeList> {
+            Some(&self.statements)
+        }
+    };
+    (list members) => {
+        fn members(&self) -> Option<&NodeList> {
+            Some(&self.members)
+        }
+    };
+    ($($other:tt)*) => {};
+}
+
+macro_rules! define_nodes {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident $accessor:ident {
+            $($fkind:ident $field:ident $(: $fty:ty)?;)*
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug)]
+            pub struct $name {
+                $(pub $field: field_type!($fkind $($fty)?),)*
+            }
+
+            impl NodeData for $name {
+                fn as_any(&self) -> &dyn Any {
+                    self
+                }
+
+                #[allow(unused_variables)]
+                fn for_each_child(&self, v: &mut dyn FnMut(&Arc<Node>) -> bool) -> bool {
+                    $(visit_field!($fkind, &self.$field, v);)*
+                    false
+                }
+
+                #[allow(unused_variables)]
+                fn map_children(&self, mapper: &mut dyn NodeMapper) -> Box<dyn NodeData> {
+                    Box::new($name {
+                        $($field: map_field!($fkind, &self.$field, mapper),)*
+                    })
+                }
+
+                #[allow(unused_variables)]
+                fn take_children(self: Box<Self>, children: &mut Vec<Arc<Node>>) {
+                    let data = *self;
+                    $(take_field!($fkind, data.$field, children);)*
+                }
+
+                #[allow(unused_variables)]
+                fn shallow_eq(&self, other: &dyn NodeData) -> bool {
+                    other.as_any().downcast_ref::<$name>().is_some_and(|other| {
+                        true $(&& field_eq!($fkind, &self.$field, &other.$field))*
+                    })
+                }
+
+                $(node_accessor!($fkind $field);)*
+            }
+        )*
+
+        impl Node {
+            $(
+                #[doc = concat!("Gets the data of this node as a `", stringify!($name), "`")]
+                ///
+                /// Panics if the node holds different data.
+                pub fn $accessor(&self) -> &$name {
+                    self.data_as::<$name>().unwrap_or_else(|| {
+                        panic!(
+                            concat!("expected ", stringify!($name), " data, found {:?}"),
+                            self.kind
+                        )
+                    })
+                }
+            )*
+        }
+    };
+}
+
+define_nodes! {
+    /// Punctuation, keywords, and keyword-like expressions (`this`, `null`, `true`, ...)
+    Token as_token {}
+
+    /// An identifier
+    Identifier as_identifier {
+        data text: String;
+    }
+
+    /// A `#private` identifier
+    PrivateIdentifier as_private_identifier {
+        data text: String;
+    }
+
+    /// `left.right` in a type or namespace reference
+    QualifiedName as_qualified_name {
+        node left;
+        node right;
+    }
+
+    /// `[expression]` used as a property name
+    ComputedPropertyName as_computed_property_name {
+        node expression;
+    }
+
+    /// A type parameter declaration, `T extends C = D`
+    TypeParameterDeclaration as_type_parameter {
+        opt_list modifiers;
+        node name;
+        opt constraint;
+        opt default_type;
+    }
+
+    /// A parameter declaration
+    ParameterDeclaration as_parameter {
+        opt_list modifiers;
+        opt dot_dot_dot_token;
+        node name;
+        opt question_token;
+        opt type_node;
+        opt initializer;
+    }
+
+    /// `@expression`
+    Decorator as_decorator {
+        node expression;
+    }
+
+    /// A property in an interface or type literal
+    PropertySignature as_property_signature {
+        opt_list modifiers;
+        node name;
+        opt postfix_token;
+        opt type_node;
+        opt initializer;
+    }
+
+    /// A property in a class
+    PropertyDeclaration as_property_declaration {
+        opt_list modifiers;
+        node name;
+        opt postfix_token;
+        opt type_node;
+        opt initializer;
+    }
+
+    /// A method in an interface or type literal
+    MethodSignature as_method_signature {
+        opt_list modifiers;
+        node name;
+        opt postfix_token;
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+    }
+
+    /// A method in a class or object literal
+    MethodDeclaration as_method_declaration {
+        opt_list modifiers;
+        opt asterisk_token;
+        node name;
+        opt postfix_token;
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+        opt body;
+    }
+
+    /// `static { ... }` in a class
+    ClassStaticBlockDeclaration as_class_static_block_declaration {
+        opt_list modifiers;
+        node body;
+    }
+
+    /// A class constructor
+    ConstructorDeclaration as_constructor_declaration {
+        opt_list modifiers;
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+        opt body;
+    }
+
+    /// A `get` accessor
+    GetAccessorDeclaration as_get_accessor_declaration {
+        opt_list modifiers;
+        node name;
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+        opt body;
+    }
+
+    /// A `set` accessor
+    SetAccessorDeclaration as_set_accessor_declaration {
+        opt_list modifiers;
+        node name;
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+        opt body;
+    }
+
+    /// `(x: T): U` in a type literal
+    CallSignatureDeclaration as_call_signature_declaration {
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+    }
+
+    /// `new (x: T): U` in a type literal
+    ConstructSignatureDeclaration as_construct_signature_declaration {
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+    }
+
+    /// `[key: K]: T`
+    IndexSignatureDeclaration as_index_signature_declaration {
+        opt_list modifiers;
+        list parameters;
+        opt type_node;
+    }
+
+    /// `x is T` or `asserts x is T`
+    TypePredicateNode as_type_predicate_node {
+        opt asserts_modifier;
+        node parameter_name;
+        opt type_node;
+    }
+
+    /// A reference to a named type, `Foo<T>`
+    TypeReferenceNode as_type_reference_node {
+        node type_name;
+        opt_list type_arguments;
+    }
+
+    /// `(x: T) => U`
+    FunctionTypeNode as_function_type_node {
+        opt_list modifiers;
+        opt_list type_parameters;
+        list parameters;
+        node type_node;
+    }
+
+    /// `new (x: T) => U`
+    ConstructorTypeNode as_constructor_type_node {
+        opt_list modifiers;
+        opt_list type_parameters;
+        list parameters;
+        node type_node;
+    }
+
+    /// `typeof x`
+    TypeQueryNode as_type_query_node {
+        node expr_name;
+        opt_list type_arguments;
+    }
+
+    /// `{ ... }` in a type position
+    TypeLiteralNode as_type_literal_node {
+        list members;
+    }
+
+    /// `T[]`
+    ArrayTypeNode as_array_type_node {
+        node element_type;
+    }
+
+    /// `[A, B]`
+    TupleTypeNode as_tuple_type_node {
+        list elements;
+    }
+
+    /// `T?` in a tuple
+    OptionalTypeNode as_optional_type_node {
+        node type_node;
+    }
+
+    /// `...T` in a tuple
+    RestTypeNode as_rest_type_node {
+        node type_node;
+    }
+
+    /// `A | B`
+    UnionTypeNode as_union_type_node {
+        list types;
+    }
+
+    /// `A & B`
+    IntersectionTypeNode as_intersection_type_node {
+        list types;
+    }
+
+    /// `C extends E ? T : F`
+    ConditionalTypeNode as_conditional_type_node {
+        node check_type;
+        node extends_type;
+        node true_type;
+        node false_type;
+    }
+
+    /// `infer T`
+    InferTypeNode as_infer_type_node {
+        node type_parameter;
+    }
+
+    /// `(T)`
+    ParenthesizedTypeNode as_parenthesized_type_node {
+        node type_node;
+    }
+
+    /// `this` in a type position
+    ThisTypeNode as_this_type_node {}
+
+    /// `keyof T`, `unique symbol`, `readonly T[]`
+    TypeOperatorNode as_type_operator_node {
+        data operator: SyntaxKind;
+        node type_node;
+    }
+
+    /// `T[K]`
+    IndexedAccessTypeNode as_indexed_access_type_node {
+        node object_type;
+        node index_type;
+    }
+
+    /// `{ [K in C as N]?: T }`
+    MappedTypeNode as_mapped_type_node {
+        opt readonly_token;
+        node type_parameter;
+        opt name_type;
+        opt question_token;
+        opt type_node;
+        opt_list members;
+    }
+
+    /// A literal in a type position, `"a"`, `1`, `true`, `null`
+    LiteralTypeNode as_literal_type_node {
+        node literal;
+    }
+
+    /// `name: T` in a tuple
+    NamedTupleMember as_named_tuple_member {
+        opt dot_dot_dot_token;
+        node name;
+        opt question_token;
+        node type_node;
+    }
+
+    /// `` `a${T}b` `` in a type position
+    TemplateLiteralTypeNode as_template_literal_type_node {
+        node head;
+        list template_spans;
+    }
+
+    /// `${T}b` in a template literal type
+    TemplateLiteralTypeSpan as_template_literal_type_span {
+        node type_node;
+        node literal;
+    }
+
+    /// `import("mod").Name<T>`
+    ImportTypeNode as_import_type_node {
+        data is_type_of: bool;
+        node argument;
+        opt attributes;
+        opt qualifier;
+        opt_list type_arguments;
+    }
+
+    /// `{ a, b: c }` in a binding position
+    ObjectBindingPattern as_object_binding_pattern {
+        list elements;
+    }
+
+    /// `[a, b]` in a binding position
+    ArrayBindingPattern as_array_binding_pattern {
+        list elements;
+    }
+
+    /// An element of a binding pattern
+    BindingElement as_binding_element {
+        opt dot_dot_dot_token;
+        opt property_name;
+        node name;
+        opt initializer;
+    }
+
+    /// `[a, b]`
+    ArrayLiteralExpression as_array_literal_expression {
+        list elements;
+        data multi_line: bool;
+    }
+
+    /// `{ a: b }`
+    ObjectLiteralExpression as_object_literal_expression {
+        list properties;
+        data multi_line: bool;
+    }
+
+    /// `a.b` or `a?.b`
+    PropertyAccessExpression as_property_access_expression {
+        node expression;
+        opt question_dot_token;
+        node name;
+    }
+
+    /// `a[b]` or `a?.[b]`
+    ElementAccessExpression as_element_access_expression {
+        node expression;
+        opt question_dot_token;
+        node argument_expression;
+    }
+
+    /// `f<T>(a)` or `f?.(a)`
+    CallExpression as_call_expression {
+        node expression;
+        opt question_dot_token;
+        opt_list type_arguments;
+        list arguments;
+    }
+
+    /// `new F<T>(a)`
+    NewExpression as_new_expression {
+        node expression;
+        opt_list type_arguments;
+        opt_list arguments;
+    }
+
+    /// ``tag<T>`a${b}` ``
+    TaggedTemplateExpression as_tagged_template_expression {
+        node tag;
+        opt question_dot_token;
+        opt_list type_arguments;
+        node template;
+    }
+
+    /// `<T>expr`
+    TypeAssertion as_type_assertion {
+        node type_node;
+        node expression;
+    }
+
+    /// `(expr)`
+    ParenthesizedExpression as_parenthesized_expression {
+        node expression;
+    }
+
+    /// `function name<T>(a) { ... }` in an expression position
+    FunctionExpression as_function_expression {
+        opt_list modifiers;
+        opt asterisk_token;
+        opt name;
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+        node body;
+    }
+
+    /// `<T>(a) => b`
+    ArrowFunction as_arrow_function {
+        opt_list modifiers;
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+        node equals_greater_than_token;
+        node body;
+    }
+
+    /// `delete expr`
+    DeleteExpression as_delete_expression {
+        node expression;
+    }
+
+    /// `typeof expr`
+    TypeOfExpression as_type_of_expression {
+        node expression;
+    }
+
+    /// `void expr`
+    VoidExpression as_void_expression {
+        node expression;
+    }
+
+    /// `await expr`
+    AwaitExpression as_await_expression {
+        node expression;
+    }
+
+    /// `-x`, `!x`, `++x`, ...
+    PrefixUnaryExpression as_prefix_unary_expression {
+        data operator: SyntaxKind;
+        node operand;
+    }
+
+    /// `x++` or `x--`
+    PostfixUnaryExpression as_postfix_unary_expression {
+        node operand;
+        data operator: SyntaxKind;
+    }
+
+    /// `a op b`
+    BinaryExpression as_binary_expression {
+        node left;
+        node operator_token;
+        node right;
+    }
+
+    /// `a ? b : c`
+    ConditionalExpression as_conditional_expression {
+        node condition;
+        node question_token;
+        node when_true;
+        node colon_token;
+        node when_false;
+    }
+
+    /// `` `a${b}c` ``
+    TemplateExpression as_template_expression {
+        node head;
+        list template_spans;
+    }
+
+    /// `yield expr` or `yield* expr`
+    YieldExpression as_yield_expression {
+        opt asterisk_token;
+        opt expression;
+    }
+
+    /// `...expr` in an array literal or argument list
+    SpreadElement as_spread_element {
+        node expression;
+    }
+
+    /// `class Name { ... }` in an expression position
+    ClassExpression as_class_expression {
+        opt_list modifiers;
+        opt name;
+        opt_list type_parameters;
+        opt_list heritage_clauses;
+        list members;
+    }
+
+    /// A hole in an array literal or array binding pattern
+    OmittedExpression as_omitted_expression {}
+
+    /// `Base<T>` in a heritage clause, or an instantiation expression `f<T>`
+    ExpressionWithTypeArguments as_expression_with_type_arguments {
+        node expression;
+        opt_list type_arguments;
+    }
+
+    /// `expr as T`
+    AsExpression as_as_expression {
+        node expression;
+        node type_node;
+    }
+
+    /// `expr!`
+    NonNullExpression as_non_null_expression {
+        node expression;
+    }
+
+    /// `new.target` or `import.meta`
+    MetaProperty as_meta_property {
+        data keyword_token: SyntaxKind;
+        node name;
+    }
+
+    /// `expr satisfies T`
+    SatisfiesExpression as_satisfies_expression {
+        node expression;
+        node type_node;
+    }
+
+    /// `${expr}text` in a template expression
+    TemplateSpan as_template_span {
+        node expression;
+        node literal;
+    }
+
+    /// A stray `;` in a class body
+    SemicolonClassElement as_semicolon_class_element {}
+
+    /// `{ ... }`
+    Block as_block {
+        list statements;
+        data multi_line: bool;
+    }
+
+    /// `;`
+    EmptyStatement as_empty_statement {}
+
+    /// `var`, `let`, `const`, or `using` statement
+    VariableStatement as_variable_statement {
+        opt_list modifiers;
+        node declaration_list;
+    }
+
+    /// An expression used as a statement
+    ExpressionStatement as_expression_statement {
+        node expression;
+    }
+
+    /// `if (a) b else c`
+    IfStatement as_if_statement {
+        node expression;
+        node then_statement;
+        opt else_statement;
+    }
+
+    /// `do a while (b)`
+    DoStatement as_do_statement {
+        node statement;
+        node expression;
+    }
+
+    /// `while (a) b`
+    WhileStatement as_while_statement {
+        node expression;
+        node statement;
+    }
+
+    /// `for (a; b; c) d`
+    ForStatement as_for_statement {
+        opt initializer;
+        opt condition;
+        opt incrementor;
+        node statement;
+    }
+
+    /// `for (a in b) c`
+    ForInStatement as_for_in_statement {
+        node initializer;
+        node expression;
+        node statement;
+    }
+
+    /// `for (a of b) c` or `for await (a of b) c`
+    ForOfStatement as_for_of_statement {
+        opt await_modifier;
+        node initializer;
+        node expression;
+        node statement;
+    }
+
+    /// `continue label;`
+    ContinueStatement as_continue_statement {
+        opt label;
+    }
+
+    /// `break label;`
+    BreakStatement as_break_statement {
+        opt label;
+    }
+
+    /// `return expr;`
+    ReturnStatement as_return_statement {
+        opt expression;
+    }
+
+    /// `with (a) b`
+    WithStatement as_with_statement {
+        node expression;
+        node statement;
+    }
+
+    /// `switch (a) { ... }`
+    SwitchStatement as_switch_statement {
+        node expression;
+        node case_block;
+    }
+
+    /// `label: statement`
+    LabeledStatement as_labeled_statement {
+        node label;
+        node statement;
+    }
+
+    /// `throw expr;`
+    ThrowStatement as_throw_statement {
+        node expression;
+    }
+
+    /// `try { } catch { } finally { }`
+    TryStatement as_try_statement {
+        node try_block;
+        opt catch_clause;
+        opt finally_block;
+    }
+
+    /// `debugger;`
+    DebuggerStatement as_debugger_statement {}
+
+    /// A single declaration in a variable declaration list
+    VariableDeclaration as_variable_declaration {
+        node name;
+        opt exclamation_token;
+        opt type_node;
+        opt initializer;
+    }
+
+    /// The declarations of a variable statement; `let`/`const`/`using` are recorded in the node flags
+    VariableDeclarationList as_variable_declaration_list {
+        list declarations;
+    }
+
+    /// `function name<T>(a) { ... }`
+    FunctionDeclaration as_function_declaration {
+        opt_list modifiers;
+        opt asterisk_token;
+        opt name;
+        opt_list type_parameters;
+        list parameters;
+        opt type_node;
+        opt body;
+    }
+
+    /// `class Name<T> extends B implements I { ... }`
+    ClassDeclaration as_class_declaration {
+        opt_list modifiers;
+        opt name;
+        opt_list type_parameters;
+        opt_list heritage_clauses;
+        list members;
+    }
+
+    /// `interface Name<T> extends B { ... }`
+    InterfaceDeclaration as_interface_declaration {
+        opt_list modifiers;
+        node name;
+        opt_list type_parameters;
+        opt_list heritage_clauses;
+        list members;
+    }
+
+    /// `type Name<T> = ...`
+    TypeAliasDeclaration as_type_alias_declaration {
+        opt_list modifiers;
+        node name;
+        opt_list type_parameters;
+        node type_node;
+    }
+
+    /// `enum Name { ... }`
+    EnumDeclaration as_enum_declaration {
+        opt_list modifiers;
+        node name;
+        list members;
+    }
+
+    /// `namespace A.B { }`, `module "x" { }`, or `declare global { }`
+    ModuleDeclaration as_module_declaration {
+        opt_list modifiers;
+        data keyword: SyntaxKind;
+        node name;
+        opt body;
+    }
+
+    /// The body of a namespace or module declaration
+    ModuleBlock as_module_block {
+        list statements;
+    }
+
+    /// The `{ case ...: }` block of a switch statement
+    CaseBlock as_case_block {
+        list clauses;
+    }
+
+    /// `export as namespace Name;`
+    NamespaceExportDeclaration as_namespace_export_declaration {
+        opt_list modifiers;
+        node name;
+    }
+
+    /// `import x = require("y")` or `import x = a.b`
+    ImportEqualsDeclaration as_import_equals_declaration {
+        opt_list modifiers;
+        data is_type_only: bool;
+        node name;
+        node module_reference;
+    }
+
+    /// `import x from "y"`
+    ImportDeclaration as_import_declaration {
+        opt_list modifiers;
+        opt import_clause;
+        node module_specifier;
+        opt attributes;
+    }
+
+    /// The `x, { y }` part of an import declaration
+    ImportClause as_import_clause {
+        data is_type_only: bool;
+        opt name;
+        opt named_bindings;
+    }
+
+    /// `* as ns`
+    NamespaceImport as_namespace_import {
+        node name;
+    }
+
+    /// `{ a, b as c }` in an import declaration
+    NamedImports as_named_imports {
+        list elements;
+    }
+
+    /// `a` or `a as b` in named imports
+    ImportSpecifier as_import_specifier {
+        data is_type_only: bool;
+        opt property_name;
+        node name;
+    }
+
+    /// `export = x` or `export default x`
+    ExportAssignment as_export_assignment {
+        opt_list modifiers;
+        data is_export_equals: bool;
+        node expression;
+    }
+
+    /// `export { a } from "x"` or `export * from "x"`
+    ExportDeclaration as_export_declaration {
+        opt_list modifiers;
+        data is_type_only: bool;
+        opt export_clause;
+        opt module_specifier;
+        opt attributes;
+    }
+
+    /// `{ a, b as c }` in an export declaration
+    NamedExports as_named_exports {
+        list elements;
+    }
+
+    /// `* as ns` in an export declaration
+    NamespaceExport as_namespace_export {
+        node name;
+    }
+
+    /// `a` or `a as b` in named exports
+    ExportSpecifier as_export_specifier {
+        data is_type_only: bool;
+        opt property_name;
+        node name;
+    }
+
+    /// A declaration that could not be parsed, kept so its modifiers and decorators are not lost
+    MissingDeclaration as_missing_declaration {
+        opt_list modifiers;
+    }
+
+    /// `require("x")` in an import-equals declaration
+    ExternalModuleReference as_external_module_reference {
+        node expression;
+    }
+
+    /// `<a>...</a>`
+    JsxElement as_jsx_element {
+        node opening_element;
+        list children;
+        node closing_element;
+    }
+
+    /// `<a />`
+    JsxSelfClosingElement as_jsx_self_closing_element {
+        node tag_name;
+        opt_list type_arguments;
+        node attributes;
+    }
+
+    /// `<a>`
+    JsxOpeningElement as_jsx_opening_element {
+        node tag_name;
+        opt_list type_arguments;
+        node attributes;
+    }
+
+    /// `</a>`
+    JsxClosingElement as_jsx_closing_element {
+        node tag_name;
+    }
+
+    /// `<>...</>`
+    JsxFragment as_jsx_fragment {
+        node opening_fragment;
+        list children;
+        node closing_fragment;
+    }
+
+    /// `<>`
+    JsxOpeningFragment as_jsx_opening_fragment {}
+
+    /// `</>`
+    JsxClosingFragment as_jsx_closing_fragment {}
+
+    /// `name="value"` or `name={value}`
+    JsxAttribute as_jsx_attribute {
+        node name;
+        opt initializer;
+    }
+
+    /// The attributes of a JSX element
+    JsxAttributes as_jsx_attributes {
+        list properties;
+    }
+
+    /// `{...expr}` in a JSX element's attributes
+    JsxSpreadAttribute as_jsx_spread_attribute {
+        node expression;
+    }
+
+    /// `{expr}` in JSX
+    JsxExpression as_jsx_expression {
+        opt dot_dot_dot_token;
+        opt expression;
+    }
+
+    /// `ns:name` in JSX
+    JsxNamespacedName as_jsx_namespaced_name {
+        node namespace;
+        node name;
+    }
+
+    /// Text content of a JSX element
+    JsxText as_jsx_text {
+        data text: String;
+        data contains_only_trivia_white_spaces: bool;
+    }
+
+    /// `case expr: ...`
+    CaseClause as_case_clause {
+        node expression;
+        list statements;
+    }
+
+    /// `default: ...`
+    DefaultClause as_default_clause {
+        list statements;
+    }
+
+    /// `extends A` or `implements A, B`
+    HeritageClause as_heritage_clause {
+        data token: SyntaxKind;
+        list types;
+    }
+
+    /// `catch (e) { ... }`
+    CatchClause as_catch_clause {
+        opt variable_declaration;
+        node block;
+    }
+
+    /// `with { type: "json" }` on an import or export
+    ImportAttributes as_import_attributes {
+        data token: SyntaxKind;
+        list elements;
+        data multi_line: bool;
+    }
+
+    /// `type: "json"` in import attributes
+    ImportAttribute as_import_attribute {
+        node name;
+        node value;
+    }
+
+    /// `a: b` in an object literal
+    PropertyAssignment as_property_assignment {
+        opt_list modifiers;
+        node name;
+        opt postfix_token;
+        node initializer;
+    }
+
+    /// `a` or `a = b` (in a destructuring assignment) in an object literal
+    ShorthandPropertyAssignment as_shorthand_property_assignment {
+        opt_list modifiers;
+        node name;
+        opt postfix_token;
+        opt equals_token;
+        opt object_assignment_initializer;
+    }
+
+    /// `...expr` in an object literal
+    SpreadAssignment as_spread_assignment {
+        node expression;
+    }
+
+    /// A member of an enum declaration
+    EnumMember as_enum_member {
+        node name;
+        opt initializer;
+    }
+
+    /// A numeric literal
+    NumericLiteral as_numeric_literal {
+        data text: String;
+        data token_flags: TokenFlags;
+    }
+
+    /// A bigint literal, `10n`
+    BigIntLiteral as_big_int_literal {
+        data text: String;
+    }
+
+    /// A string literal; `text` holds the cooked value without quotes
+    StringLiteral as_string_literal {
+        data text: String;
+        data token_flags: TokenFlags;
+    }
+
+    /// A regular expression literal, including its slashes and flags
+    RegularExpressionLiteral as_regular_expression_literal {
+        data text: String;
+    }
+
+    /// A template literal without substitutions, or the head/middle/tail of one with substitutions
+    TemplateLiteralLikeNode as_template_literal_like_node {
+        data text: String;
+        data raw_text: String;
+        data token_flags: TokenFlags;
+    }
+
+    /// A type in a JSDoc comment, such as `{string}` in `@param {string} name`
+    JSDocTypeExpression as_jsdoc_type_expression {
+        node type_node;
+    }
+
+    /// `*` or `?` as a JSDoc type
+    JSDocAllType as_jsdoc_all_type {}
+
+    /// `?T` or `T?` in a JSDoc type
+    JSDocNullableType as_jsdoc_nullable_type {
+        node type_node;
+        data postfix: bool;
+    }
+
+    /// `!T` or `T!` in a JSDoc type
+    JSDocNonNullableType as_jsdoc_non_nullable_type {
+        node type_node;
+        data postfix: bool;
+    }
+
+    /// `T=` in a JSDoc type, marking an optional parameter
+    JSDocOptionalType as_jsdoc_optional_type {
+        node type_node;
+    }
+
+    /// `...T` in a JSDoc type, marking a rest parameter
+    JSDocVariadicType as_jsdoc_variadic_type {
+        node type_node;
+    }
+
+    /// A `/** ... */` comment attached to a declaration
+    JSDoc as_jsdoc {
+        data comment: Option<String>;
+        list tags;
+    }
+
+    /// A JSDoc tag without specialized parsing, such as `@see` or `@example`
+    JSDocTag as_jsdoc_tag {
+        node tag_name;
+        data comment: Option<String>;
+    }
+
+    /// `@param {T} name` or `@param {T} [name=default]`
+    JSDocParameterTag as_jsdoc_parameter_tag {
+        node tag_name;
+        opt type_expression;
+        node name;
+        data is_bracketed: bool;
+        data comment: Option<String>;
+    }
+
+    /// `@returns {T}`
+    JSDocReturnTag as_jsdoc_return_tag {
+        node tag_name;
+        opt type_expression;
+        data comment: Option<String>;
+    }
+
+    /// `@type {T}`
+    JSDocTypeTag as_jsdoc_type_tag {
+        node tag_name;
+        node type_expression;
+        data comment: Option<String>;
+    }
+
+    /// `@template {Constraint} T, U`
+    JSDocTemplateTag as_jsdoc_template_tag {
+        node tag_name;
+        opt constraint;
+        list type_parameters;
+        data comment: Option<String>;
+    }
+
+    /// `@typedef {T} Name`
+    JSDocTypedefTag as_jsdoc_typedef_tag {
+        node tag_name;
+        opt type_expression;
+        opt full_name;
+        data comment: Option<String>;
+    }
+
+    /// `@deprecated`
+    JSDocDeprecatedTag as_jsdoc_deprecated_tag {
+        node tag_name;
+        data comment: Option<String>;
+    }
+
+    /// A complete source file
+    SourceFile as_source_file {
+        list statements;
+        node end_of_file_token;
+        data file_name: String;
+        data text: String;
+        data language_version: ScriptTarget;
+        data language_variant: LanguageVariant;
+        data script_kind: ScriptKind;
+        data is_declaration_file: bool;
+        data parse_diagnostics: Vec<Diagnostic>;
+        data jsdoc_diagnostics: Vec<Diagnostic>;
+        data jsdoc_parsing_mode: JSDocParsingMode;
+        data node_count: usize;
+        data identifier_count: usize;
+        parsed arena: Option<Arc<NodeArena>>;
+        data statement_starts: Vec<StatementStart>;
+    }
+
+    /// A statement that exists only to carry comments during emit
+    NotEmittedStatement as_not_emitted_statement {}
+
+    /// An expression whose emit has been partially replaced by a transform; the trailing
+    /// comment is the text of a `/* */` comment written after it, such as the name of the const
+    /// enum member whose value replaced an access
+    PartiallyEmittedExpression as_partially_emitted_expression {
+        node expression;
+        data trailing_comment: Option<String>;
+    }
+
+    /// A synthesized comma-separated list of expressions
+    CommaListExpression as_comma_list_expression {
+        list elements;
+    }
+}
+
+/// The state of the parser when it started a top-level statement, which an incremental reparse
+/// starting at that statement picks up from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatementStart {
+    /// How many parse diagnostics had been reported
+    pub diagnostics_len: usize,
+    /// How many JSDoc diagnostics had been reported
+    pub jsdoc_diagnostics_len: usize,
+    /// Whether an error had been reported that the next node finished is marked with
+    pub error_pending: bool,
+}
+
+impl SourceFile {
+    /// Gets the arena the parser allocated the nodes of this file into
+    ///
+    /// A file a transform has changed has a tree the parser did not build, so it has no arena.
+    pub fn arena(&self) -> Option<&NodeArena> {
+        self.arena.as_deref()
+    }
+}
@@ -1,12 +1,18 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::ops::{Index, IndexMut};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::compiler::ast::check_flags::CheckFlags;
+use crate::compiler::ast::ids::{NodeId, SymbolId};
+use crate::compiler::ast::kind::SyntaxKind;
+use crate::compiler::ast::node::Node;
 use crate::compiler::ast::symbol_flags::SymbolFlags;
 
 /// Represents a symbol in the TypeScript AST
-#[derive(Debug)]
+///
+/// Symbols live in a [`SymbolArena`] and refer to each other by [`SymbolId`].
+#[derive(Debug, Clone)]
 pub struct Symbol {
     /// Flags describing this symbol
     pub flags: SymbolFlags,
@@ -23,19 +29,98 @@ pub struct Symbol {
     /// Symbols for exported members of this symbol
     pub exports: SymbolTable,
     /// The parent symbol
-    pub parent: Option<Arc<Symbol>>,
+    pub parent: Option<SymbolId>,
     /// Export symbol (if this is an export specifier)
-    pub export_symbol: Option<Arc<Symbol>>,
+    pub export_symbol: Option<SymbolId>,
     /// Set of detected assignment declarations
     pub assignment_declaration_members: HashSet<NodeId>,
     /// Conditional global UMD exports
     pub global_exports: SymbolTable,
     /// Unique identifier for this symbol
-    id: AtomicU64,
+    id: SymbolId,
 }
 
 /// Maps names to symbols
-pub type SymbolTable = HashMap<String, Arc<Symbol>>;
+///
+/// Symbol ids grow in the order symbols are created, which for bound symbols is source order,
+/// so sorting a table's ids recovers declaration order.
+pub type SymbolTable = HashMap<String, SymbolId>;
+
+/// Storage for the symbols of a program
+///
+/// Ids start at 1 and index the arena directly.
+#[derive(Debug, Default, Clone)]
+pub struct SymbolArena {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolArena {
+    /// Creates an empty arena
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a symbol and returns its id
+    pub fn alloc(&mut self, name: impl Into<String>, flags: SymbolFlags) -> SymbolId {
+        let id = SymbolId::new(self.symbols.len() as u64 + 1);
+        let mut symbol = Symbol::new(name.into(), flags);
+        symbol.id = id;
+        self.symbols.push(symbol);
+        id
+    }
+
+    /// Gets the number of symbols in the arena
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Checks if the arena holds no symbols
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Iterates over every symbol in creation order
+    pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.iter()
+    }
+
+    /// Moves the symbols of another arena after those of this one, renumbering them and the
+    /// symbols they refer to, and returns how much their ids grew
+    pub fn append(&mut self, other: SymbolArena) -> u64 {
+        let offset = self.symbols.len() as u64;
+        let shift = |id: SymbolId| SymbolId::new(id.value() + offset);
+        for mut symbol in other.symbols {
+            symbol.id = shift(symbol.id);
+            for table in [
+                &mut symbol.members,
+                &mut symbol.exports,
+                &mut symbol.global_exports,
+            ] {
+                for id in table.values_mut() {
+                    *id = shift(*id);
+                }
+            }
+            symbol.parent = symbol.parent.map(shift);
+            symbol.export_symbol = symbol.export_symbol.map(shift);
+            self.symbols.push(symbol);
+        }
+        offset
+    }
+}
+
+impl Index<SymbolId> for SymbolArena {
+    type Output = Symbol;
+
+    fn index(&self, id: SymbolId) -> &Symbol {
+        &self.symbols[id.value() as usize - 1]
+    }
+}
+
+impl IndexMut<SymbolId> for SymbolArena {
+    fn index_mut(&mut self, id: SymbolId) -> &mut Symbol {
+        &mut self.symbols[id.value() as usize - 1]
+    }
+}
 
 /// Prefix for internal symbol names (invalid UTF-8 sequence, will never occur as IdentifierName)
 pub const INTERNAL_SYMBOL_NAME_PREFIX: &str = "\u{FE}";
@@ -97,6 +182,8 @@ internal_symbol! {
 
 impl Symbol {
     /// Creates a new symbol with the given name and flags
+    ///
+    /// The symbol has no id until it is stored with [`SymbolArena::alloc`].
     pub fn new(name: String, flags: SymbolFlags) -> Self {
         Symbol {
             flags,
@@ -110,34 +197,46 @@ impl Symbol {
             export_symbol: None,
             assignment_declaration_members: HashSet::new(),
             global_exports: HashMap::new(),
-            id: AtomicU64::new(0),
+            id: SymbolId::new(0),
         }
     }
 
     /// Gets the unique ID for this symbol
-    pub fn id(&self) -> u64 {
-        self.id.load(Ordering::SeqCst)
-    }
-
-    /// Sets the unique ID for this symbol
-    pub fn set_id(&self, id: u64) {
-        self.id.store(id, Ordering::SeqCst);
+    pub fn id(&self) -> SymbolId {
+        self.id
     }
 }
 
 /// Gets the display name of a symbol
-pub fn symbol_name(symbol: &Symbol) -> &str {
+///
+/// A property keyed by a well-known symbol is displayed as `[Symbol.<name>]`.
+pub fn symbol_name(symbol: &Symbol) -> Cow<'_, str> {
     if let Some(value_decl) = &symbol.value_declaration {
         if is_private_identifier_class_element_declaration(value_decl) {
-            return value_decl.name().text();
+            if let Some(name) = value_decl.name() {
+                return Cow::Borrowed(name.text());
+            }
         }
     }
-    &symbol.name
+    match symbol
+        .name
+        .strip_prefix(INTERNAL_SYMBOL_NAME_PREFIX)
+        .and_then(|name| name.strip_prefix('@'))
+    {
+        Some(name) => Cow::Owned(format!("[Symbol.{name}]")),
+        None => Cow::Borrowed(&symbol.name),
+    }
 }
 
 /// Checks if a node is a private identifier class element declaration
 pub fn is_private_identifier_class_element_declaration(node: &Node) -> bool {
-    // Implementation would check if the node represents a private class member
-    // This is a placeholder - would need to be implemented based on Node structure
-    false
+    matches!(
+        node.kind,
+        SyntaxKind::PropertyDeclaration
+            | SyntaxKind::MethodDeclaration
+            | SyntaxKind::GetAccessor
+            | SyntaxKind::SetAccessor
+    ) && node
+        .name()
+        .is_some_and(|name| name.kind == SyntaxKind::PrivateIdentifier)
 }
@@ -2,7 +2,7 @@ use bitflags::bitflags;
 
 bitflags! {
     /// Flags used to track special properties of TypeScript symbols
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct SymbolFlags: u32 {
         /// No flags
         const NONE = 0;
@@ -0,0 +1,372 @@
+use std::sync::Arc;
+
+use crate::compiler::ast::kind::SyntaxKind;
+use crate::compiler::ast::node::Node;
+use crate::compiler::ast::node_flags::NodeFlags;
+use crate::compiler::ast::symbol::INTERNAL_SYMBOL_NAME_PREFIX;
+use crate::compiler::scanner::skip_trivia;
+use crate::compiler::text::TextRange;
+
+/// Determines if `kind` is a declaration with parameters and a signature
+pub fn is_function_like_kind(kind: SyntaxKind) -> bool {
+    is_function_like_declaration_kind(kind)
+        || matches!(
+            kind,
+            SyntaxKind::MethodSignature
+                | SyntaxKind::CallSignature
+                | SyntaxKind::ConstructSignature
+                | SyntaxKind::IndexSignature
+                | SyntaxKind::FunctionType
+                | SyntaxKind::ConstructorType
+        )
+}
+
+/// Determines if `kind` is a function-like declaration that can have a body
+pub fn is_function_like_declaration_kind(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::FunctionDeclaration
+            | SyntaxKind::MethodDeclaration
+            | SyntaxKind::Constructor
+            | SyntaxKind::GetAccessor
+            | SyntaxKind::SetAccessor
+            | SyntaxKind::FunctionExpression
+            | SyntaxKind::ArrowFunction
+    )
+}
+
+/// Determines if `node` is a class declaration or expression
+pub fn is_class_like(node: &Node) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::ClassDeclaration | SyntaxKind::ClassExpression
+    )
+}
+
+/// Determines if `node` is an object or array binding pattern
+pub fn is_binding_pattern(node: &Node) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::ObjectBindingPattern | SyntaxKind::ArrayBindingPattern
+    )
+}
+
+/// Determines if `node` is a string literal or a template literal without substitutions
+pub fn is_string_literal_like(node: &Node) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::StringLiteral | SyntaxKind::NoSubstitutionTemplateLiteral
+    )
+}
+
+/// Gets the flags of the variable declaration list a variable declaration or binding element
+/// belongs to, combined with the node's own flags
+pub fn get_combined_node_flags(node: &Arc<Node>) -> NodeFlags {
+    let mut flags = node.flags;
+    let mut current = root_declaration(node);
+    if current.kind == SyntaxKind::VariableDeclaration {
+        flags |= current.flags;
+        if let Some(parent) = current.parent() {
+            current = parent;
+            flags |= current.flags;
+        }
+    }
+    flags
+}
+
+/// Walks up from a binding element to the variable declaration or parameter its pattern
+/// belongs to
+pub fn root_declaration(node: &Arc<Node>) -> Arc<Node> {
+    let mut current = Arc::clone(node);
+    while current.kind == SyntaxKind::BindingElement {
+        match current.parent().and_then(|pattern| pattern.parent()) {
+            Some(declaration) => current = declaration,
+            None => break,
+        }
+    }
+    current
+}
+
+/// Determines if a variable declaration or binding element is declared with `let`, `const`,
+/// `using`, or `await using`
+pub fn is_block_scoped_variable(node: &Arc<Node>) -> bool {
+    get_combined_node_flags(node).intersects(NodeFlags::BLOCK_SCOPED)
+}
+
+/// Determines if a variable declaration or binding element is declared with `const`, `using`,
+/// or `await using`
+pub fn is_const_variable(node: &Arc<Node>) -> bool {
+    get_combined_node_flags(node).intersects(NodeFlags::CONSTANT)
+}
+
+/// Determines if the declaration carries the `export` modifier, taking the variable statement
+/// of a variable declaration into account
+///
+/// The inner declarations of a dotted namespace such as `namespace a.b {}` count as exported.
+pub fn has_export_modifier(node: &Arc<Node>) -> bool {
+    has_combined_modifier(node, SyntaxKind::ExportKeyword)
+        || (node.kind == SyntaxKind::ModuleDeclaration
+            && node
+                .parent()
+                .is_some_and(|parent| parent.kind == SyntaxKind::ModuleDeclaration))
+}
+
+/// Determines if the declaration, or the variable statement of a variable declaration, has a
+/// modifier of the given kind
+pub fn has_combined_modifier(node: &Arc<Node>, kind: SyntaxKind) -> bool {
+    let root = root_declaration(node);
+    if root.kind == SyntaxKind::VariableDeclaration {
+        return root
+            .parent()
+            .and_then(|list| list.parent())
+            .is_some_and(|statement| {
+                statement.kind == SyntaxKind::VariableStatement && statement.has_modifier(kind)
+            });
+    }
+    root.has_modifier(kind)
+}
+
+/// Determines if the node is a static class member
+pub fn is_static(node: &Node) -> bool {
+    node.has_modifier(SyntaxKind::StaticKeyword)
+        || node.kind == SyntaxKind::ClassStaticBlockDeclaration
+}
+
+/// Determines if the node is a constructor parameter that also declares a property, such
+/// as `private x: number`
+pub fn is_parameter_property_declaration(node: &Node) -> bool {
+    node.parent()
+        .is_some_and(|parent| parent.kind == SyntaxKind::Constructor)
+        && node.modifiers().is_some_and(|modifiers| {
+            modifiers.iter().any(|modifier| {
+                matches!(
+                    modifier.kind,
+                    SyntaxKind::PublicKeyword
+                        | SyntaxKind::PrivateKeyword
+                        | SyntaxKind::ProtectedKeyword
+                        | SyntaxKind::ReadonlyKeyword
+                        | SyntaxKind::OverrideKeyword
+                )
+            })
+        })
+}
+
+/// Gets the name of a property name node as it appears in a symbol table
+///
+/// Computed names only have a static name when their expression is a literal.
+pub fn get_property_name_text(name: &Node) -> Option<String> {
+    match name.kind {
+        SyntaxKind::Identifier
+        | SyntaxKind::PrivateIdentifier
+        | SyntaxKind::StringLiteral
+        | SyntaxKind::NoSubstitutionTemplateLiteral => Some(name.text().to_string()),
+        SyntaxKind::NumericLiteral => Some(number_to_string(parse_numeric_literal(name.text()))),
+        SyntaxKind::ComputedPropertyName => {
+            let expression = &name.as_computed_property_name().expression;
+            match expression.kind {
+                SyntaxKind::StringLiteral | SyntaxKind::NoSubstitutionTemplateLiteral => {
+                    Some(expression.text().to_string())
+                }
+                SyntaxKind::NumericLiteral => {
+                    Some(number_to_string(parse_numeric_literal(expression.text())))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Gets the name a property named by a well-known symbol, like `[Symbol.iterator]`, is declared
+/// under, or None if `name` does not name one
+pub fn get_well_known_symbol_property_name(name: &Node) -> Option<String> {
+    if name.kind != SyntaxKind::ComputedPropertyName {
+        return None;
+    }
+    let expression = &name.as_computed_property_name().expression;
+    if expression.kind != SyntaxKind::PropertyAccessExpression {
+        return None;
+    }
+    let access = expression.as_property_access_expression();
+    if access.expression.kind != SyntaxKind::Identifier || access.expression.text() != "Symbol" {
+        return None;
+    }
+    Some(well_known_symbol_name(access.name.text()))
+}
+
+/// Gets the internal name of the property keyed by the well-known symbol `Symbol.<name>`,
+/// which no property written with a string name can have
+pub fn well_known_symbol_name(name: &str) -> String {
+    format!("{INTERNAL_SYMBOL_NAME_PREFIX}@{name}")
+}
+
+///
+/// Files that are not modules are scripts, whose top-level declarations are global.
+pub fn is_external_module(source_file: &Node) -> bool {
+    get_external_module_indicator(source_file).is_some()
+}
+
+/// Gets the first statement of a source file that makes it a module
+pub fn get_external_module_indicator(source_file: &Node) -> Option<&Arc<Node>> {
+    source_file
+        .as_source_file()
+        .statements
+        .iter()
+        .find(|statement| match statement.kind {
+            SyntaxKind::ImportDeclaration
+            | SyntaxKind::ExportDeclaration
+            | SyntaxKind::ExportAssignment => true,
+            SyntaxKind::ImportEqualsDeclaration => {
+                statement
+                    .as_import_equals_declaration()
+                    .module_reference
+                    .kind
+                    == SyntaxKind::ExternalModuleReference
+                    || statement.has_modifier(SyntaxKind::ExportKeyword)
+            }
+            _ => statement.has_modifier(SyntaxKind::ExportKeyword),
+        })
+}
+
+/// Gets the source file that contains `node`
+pub fn get_source_file_of_node(node: &Arc<Node>) -> Arc<Node> {
+    let mut current = Arc::clone(node);
+    while current.kind != SyntaxKind::SourceFile {
+        current = current
+            .parent()
+            .unwrap_or_else(|| panic!("{:?} is not part of a source file", current.kind));
+    }
+    current
+}
+
+/// Gets the range of `node` without its leading trivia, which is where errors on it are
+/// reported
+pub fn get_error_range(source_file: &Node, node: &Node) -> TextRange {
+    let text = &source_file.as_source_file().text;
+    if node.kind == SyntaxKind::SourceFile {
+        return TextRange::new(0, 0);
+    }
+    let pos = skip_trivia(text, node.pos()).min(node.end());
+    TextRange::new(pos, node.end())
+}
+
+/// Gets the source text of `node` without its leading trivia
+pub fn get_text_of_node<'a>(source_file: &'a Node, node: &Node) -> &'a str {
+    let range = get_error_range(source_file, node);
+    &source_file.as_source_file().text[range.pos()..range.end()]
+}
+
+/// Removes any parentheses around `node`
+pub fn skip_parentheses(node: &Arc<Node>) -> Arc<Node> {
+    let mut current = Arc::clone(node);
+    while current.kind == SyntaxKind::ParenthesizedExpression {
+        current = Arc::clone(&current.as_parenthesized_expression().expression);
+    }
+    current
+}
+
+/// Converts the text of a numeric literal to its value
+pub fn parse_numeric_literal(text: &str) -> f64 {
+    let text = text.replace('_', "");
+    let radix = match text.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0b" | "0B") => 2,
+        Some("0o" | "0O") => 8,
+        _ if text.len() > 1
+            && text.starts_with('0')
+            && text.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            // Legacy octal literals such as `010`; `089` is decimal
+            if text.bytes().all(|b| b < b'8') {
+                return parse_digits(&text[1..], 8);
+            }
+            10
+        }
+        _ => 10,
+    };
+    if radix == 10 {
+        text.parse().unwrap_or(f64::NAN)
+    } else {
+        parse_digits(&text[2..], radix)
+    }
+}
+
+fn parse_digits(digits: &str, radix: u32) -> f64 {
+    digits
+        .chars()
+        .try_fold(0.0, |value, ch| {
+            ch.to_digit(radix)
+                .map(|digit| value * radix as f64 + digit as f64)
+        })
+        .unwrap_or(f64::NAN)
+}
+
+/// Converts a number to a string the way JavaScript's `Number.prototype.toString` does
+pub fn number_to_string(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    // `{:e}` gives the shortest digits that round-trip, such as `1.25e-7`
+    let formatted = format!("{:e}", value.abs());
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let digits: String = mantissa.chars().filter(|&ch| ch != '.').collect();
+    let exponent: i32 = exponent.parse().unwrap();
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+    let body = if k <= n && n <= 21 {
+        format!("{digits}{}", "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{digits}", "0".repeat(-n as usize))
+    } else {
+        let exponent_sign = if n - 1 < 0 { '-' } else { '+' };
+        let fraction = if k > 1 {
+            format!(".{}", &digits[1..])
+        } else {
+            String::new()
+        };
+        format!(
+            "{}{fraction}e{exponent_sign}{}",
+            &digits[..1],
+            (n - 1).abs()
+        )
+    };
+    format!("{sign}{body}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_to_string() {
+        let cases = [
+            (1.0, "1"),
+            (-1.5, "-1.5"),
+            (0.1, "0.1"),
+            (1e21, "1e+21"),
+            (123456789012345680000.0, "123456789012345680000"),
+            (1e-7, "1e-7"),
+            (0.000001, "0.000001"),
+            (1.25e-10, "1.25e-10"),
+            (f64::INFINITY, "Infinity"),
+            (-0.0, "0"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(number_to_string(value), expected);
+        }
+        assert!(parse_numeric_literal("0x1F") == 31.0);
+        assert!(parse_numeric_literal("0b101") == 5.0);
+        assert!(parse_numeric_literal("010") == 8.0);
+        assert!(parse_numeric_literal("1_000.5") == 1000.5);
+        assert_eq!(number_to_string(parse_numeric_literal("1.0")), "1");
+    }
+}
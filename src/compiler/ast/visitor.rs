@@ -0,0 +1,342 @@
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::kind::SyntaxKind;
+use crate::compiler::ast::node::{Node, NodeList, NodeMapper};
+use crate::compiler::ast::node_flags::NodeFlags;
+use crate::compiler::ast::nodes::Token;
+use crate::compiler::scanner::Scanner;
+use crate::compiler::text::TextRange;
+
+/// Observes a tree walk started with [`walk`]
+pub trait Visitor {
+    /// Called before the children of `node` are walked; returning false skips them
+    fn enter(&mut self, node: &Arc<Node>) -> bool {
+        let _ = node;
+        true
+    }
+
+    /// Called after the children of `node` have been walked or skipped
+    fn leave(&mut self, node: &Arc<Node>) {
+        let _ = node;
+    }
+}
+
+/// Walks the tree rooted at `node` in source order
+pub fn walk(node: &Arc<Node>, visitor: &mut dyn Visitor) {
+    if visitor.enter(node) {
+        node.for_each_child(&mut |child| {
+            walk(child, visitor);
+            false
+        });
+    }
+    visitor.leave(node);
+}
+
+/// Produces the replacement nodes of a tree transformation
+///
+/// Implementations usually handle the kinds they care about and fall back to
+/// [`visit_each_child`] for everything else, so that the transformation reaches the whole tree.
+pub trait NodeVisitor {
+    /// Visits a node that fills a single-node position, returning its replacement
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node>;
+
+    /// Visits an element of a node list, returning the nodes that take its place
+    ///
+    /// Returning no nodes removes the element, and returning several splices them in.
+    fn visit_list_element(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        vec![self.visit_node(node)]
+    }
+}
+
+/// Visits each child of `node`, returning `node` itself when every child came back unchanged
+pub fn visit_each_child(
+    node: &Arc<Node>,
+    visitor: &mut dyn NodeVisitor,
+    factory: &NodeFactory,
+) -> Arc<Node> {
+    ensure_sufficient_stack(|| factory.update_children(node, &mut VisitorMapper { visitor }))
+}
+
+/// Runs `f` on a new stack segment when the current one is nearly used up
+///
+/// Recursive walks such as the visitors of the transformers nest as deeply as the tree, which
+/// a long chain of binary expressions makes deeper than a thread's stack; each level of such
+/// a walk goes through here.
+pub fn ensure_sufficient_stack<R>(f: impl FnOnce() -> R) -> R {
+    const RED_ZONE: usize = 128 * 1024;
+    const NEW_STACK_SIZE: usize = 4 * 1024 * 1024;
+    stacker::maybe_grow(RED_ZONE, NEW_STACK_SIZE, f)
+}
+
+/// Visits each node of `list`, returning a copy of `list` when every node came back unchanged
+pub fn visit_nodes(
+    list: &NodeList,
+    visitor: &mut dyn NodeVisitor,
+    factory: &NodeFactory,
+) -> NodeList {
+    let nodes = list
+        .nodes
+        .iter()
+        .flat_map(|node| visitor.visit_list_element(node))
+        .collect();
+    factory.update_node_list(list, nodes)
+}
+
+struct VisitorMapper<'a> {
+    visitor: &'a mut dyn NodeVisitor,
+}
+
+impl NodeMapper for VisitorMapper<'_> {
+    fn map_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        self.visitor.visit_node(node)
+    }
+
+    fn map_list(&mut self, list: &NodeList) -> NodeList {
+        NodeList {
+            loc: list.loc,
+            nodes: list
+                .nodes
+                .iter()
+                .flat_map(|node| self.visitor.visit_list_element(node))
+                .collect(),
+            has_trailing_comma: list.has_trailing_comma,
+        }
+    }
+}
+
+/// Invokes the callback on each descendant of `node` in source order, not including `node`
+/// itself, stopping early when it returns true
+///
+/// The descendants are walked with a stack of their own, so trees too deep to recurse through,
+/// like long chains of binary expressions, can be walked.
+pub fn for_each_descendant(node: &Node, callback: &mut dyn FnMut(&Arc<Node>) -> bool) -> bool {
+    let mut pending = Vec::new();
+    push_children_in_reverse(node, &mut pending);
+    while let Some(node) = pending.pop() {
+        if callback(&node) {
+            return true;
+        }
+        push_children_in_reverse(&node, &mut pending);
+    }
+    false
+}
+
+/// Pushes the children of `node` onto a stack so that they are popped in source order
+fn push_children_in_reverse(node: &Node, pending: &mut Vec<Arc<Node>>) {
+    let start = pending.len();
+    node.for_each_child(&mut |child| {
+        pending.push(Arc::clone(child));
+        false
+    });
+    pending[start..].reverse();
+}
+
+/// Finds the closest node, starting with `node` itself and moving up through its parents, that
+/// satisfies the predicate
+pub fn find_ancestor(
+    node: &Arc<Node>,
+    mut predicate: impl FnMut(&Arc<Node>) -> bool,
+) -> Option<Arc<Node>> {
+    let mut current = Some(Arc::clone(node));
+    while let Some(node) = current {
+        if predicate(&node) {
+            return Some(node);
+        }
+        current = node.parent();
+    }
+    None
+}
+
+/// Gets the innermost token whose range, including its leading trivia, contains `position`
+///
+/// Tokens the tree does not store as nodes, such as punctuation and some keywords, are scanned
+/// from the source text and returned as new `Token` nodes parented to the node they belong to.
+/// Positions at or past the end of the file give the end-of-file token.
+pub fn get_token_at_position(source_file: &Arc<Node>, position: usize) -> Arc<Node> {
+    let data = source_file.as_source_file();
+    if position >= data.end_of_file_token.pos() && position >= data.statements.end() {
+        return Arc::clone(&data.end_of_file_token);
+    }
+
+    let mut current = Arc::clone(source_file);
+    loop {
+        let mut has_children = false;
+        let mut containing = None;
+        let mut gap_start = current.pos();
+        let mut gap_end = current.end();
+        current.for_each_child(&mut |child| {
+            has_children = true;
+            if child.loc.is_empty() {
+                return false;
+            }
+            if position < child.pos() {
+                gap_end = child.pos();
+                return true;
+            }
+            if position < child.end() {
+                containing = Some(Arc::clone(child));
+                return true;
+            }
+            gap_start = child.end();
+            false
+        });
+        if let Some(child) = containing {
+            current = child;
+            continue;
+        }
+        if !has_children {
+            return current;
+        }
+        return scan_token_at_position(source_file, &current, gap_start, gap_end, position)
+            .unwrap_or(current);
+    }
+}
+
+/// Scans the tokens between `start` and `end`, which belong to `parent` but are not stored as
+/// nodes, for the one containing `position`
+fn scan_token_at_position(
+    source_file: &Node,
+    parent: &Arc<Node>,
+    start: usize,
+    end: usize,
+    position: usize,
+) -> Option<Arc<Node>> {
+    let data = source_file.as_source_file();
+    let mut scanner = Scanner::new();
+    scanner.set_text(data.text.clone());
+    scanner.set_script_target(data.language_version);
+    scanner.set_script_kind(data.script_kind);
+    scanner.set_language_variant(data.language_variant);
+    scanner.reset_pos(start);
+    loop {
+        let kind = scanner.scan();
+        if kind == SyntaxKind::EndOfFile || scanner.token_full_start() >= end {
+            return None;
+        }
+        if position < scanner.token_end() {
+            let loc = TextRange::new(scanner.token_full_start(), scanner.token_end());
+            let token = Node::new(kind, NodeFlags::NONE, loc, Token {});
+            token.set_parent(parent);
+            return Some(token);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::nodes::Identifier;
+    use crate::compiler::parser::parse_source_file;
+    use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+
+    fn parse(text: &str) -> Arc<Node> {
+        parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown)
+    }
+
+    #[test]
+    fn test_walk_and_for_each_descendant() {
+        struct Depth {
+            current: usize,
+            max: usize,
+            entered: usize,
+        }
+        impl Visitor for Depth {
+            fn enter(&mut self, _node: &Arc<Node>) -> bool {
+                self.entered += 1;
+                self.current += 1;
+                self.max = self.max.max(self.current);
+                true
+            }
+            fn leave(&mut self, _node: &Arc<Node>) {
+                self.current -= 1;
+            }
+        }
+
+        let file = parse("f(a, [b]);");
+        let mut depth = Depth {
+            current: 0,
+            max: 0,
+            entered: 0,
+        };
+        walk(&file, &mut depth);
+        assert_eq!(depth.current, 0);
+        // SourceFile > ExpressionStatement > CallExpression > ArrayLiteralExpression > Identifier
+        assert_eq!(depth.max, 5);
+
+        let mut descendants = 0;
+        for_each_descendant(&file, &mut |_| {
+            descendants += 1;
+            false
+        });
+        assert_eq!(descendants + 1, depth.entered);
+    }
+
+    #[test]
+    fn test_visit_each_child() {
+        struct Rename {
+            factory: NodeFactory,
+        }
+        impl NodeVisitor for Rename {
+            fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+                if node.kind == SyntaxKind::Identifier && node.text() == "a" {
+                    return self.factory.update(node, Identifier { text: "b".into() });
+                }
+                let factory = self.factory;
+                visit_each_child(node, self, &factory)
+            }
+
+            fn visit_list_element(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+                if node.kind == SyntaxKind::EmptyStatement {
+                    return Vec::new();
+                }
+                vec![self.visit_node(node)]
+            }
+        }
+
+        let factory = NodeFactory::new();
+        let mut rename = Rename { factory };
+        let file = parse("x;\n;\nf(a);");
+        let renamed = rename.visit_node(&file);
+        let statements = &renamed.as_source_file().statements.nodes;
+        assert_eq!(statements.len(), 2);
+        let original = &file.as_source_file().statements.nodes;
+        assert!(Arc::ptr_eq(&statements[0], &original[0]));
+        let call = statements[1].as_expression_statement().expression.clone();
+        assert_eq!(call.as_call_expression().arguments.nodes[0].text(), "b");
+
+        let unchanged = parse("x;");
+        assert!(Arc::ptr_eq(&rename.visit_node(&unchanged), &unchanged));
+    }
+
+    #[test]
+    fn test_get_token_at_position() {
+        let text = "let x = (1 + foo);";
+        let file = parse(text);
+
+        let foo = get_token_at_position(&file, text.find("foo").unwrap() + 1);
+        assert_eq!(foo.kind, SyntaxKind::Identifier);
+        assert_eq!(foo.text(), "foo");
+
+        let paren = get_token_at_position(&file, text.find('(').unwrap());
+        assert_eq!(paren.kind, SyntaxKind::OpenParenToken);
+        assert_eq!(
+            paren.parent().unwrap().kind,
+            SyntaxKind::ParenthesizedExpression
+        );
+
+        let let_keyword = get_token_at_position(&file, 0);
+        assert_eq!(let_keyword.kind, SyntaxKind::LetKeyword);
+
+        // Trivia belongs to the token that follows it
+        let equals = get_token_at_position(&file, text.find('=').unwrap() - 1);
+        assert_eq!(equals.kind, SyntaxKind::EqualsToken);
+
+        let end = get_token_at_position(&file, text.len());
+        assert_eq!(end.kind, SyntaxKind::EndOfFile);
+
+        let statement = find_ancestor(&foo, |node| node.kind == SyntaxKind::VariableStatement);
+        assert!(statement.is_some());
+        assert!(find_ancestor(&foo, |node| node.kind == SyntaxKind::Block).is_none());
+    }
+}
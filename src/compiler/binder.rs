@@ -0,0 +1,2380 @@
+//! Binding of declarations to symbols
+//!
+//! The binder walks a parsed source file and creates a [`Symbol`] for every declaration,
+//! merging declarations that share a name where TypeScript allows it and reporting duplicate
+//! identifiers where it does not. Each symbol is entered into the table of the scope that
+//! declares it:
+//!
+//! - the locals of a function, block, source file, or other node that introduces a scope
+//! - the members or exports of a class, interface, enum, namespace, or module
+//!
+//! The results are kept in a [`Bindings`] shared by every file of a program, so the checker
+//! can look up the symbol of any declaration and the scope of any container by node id.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use bitflags::bitflags;
+
+use crate::compiler::ast::internal_symbol_names;
+use crate::compiler::ast::utilities::{
+    get_error_range, get_property_name_text, get_well_known_symbol_property_name,
+    has_export_modifier, is_binding_pattern, is_block_scoped_variable, is_external_module,
+    is_function_like_kind, is_parameter_property_declaration, is_static, root_declaration,
+};
+use crate::compiler::ast::{
+    Node, NodeId, SymbolArena, SymbolFlags, SymbolId, SymbolTable, SyntaxKind,
+};
+use crate::compiler::diagnostics::{self, Diagnostic, Message};
+
+bitflags! {
+    /// Describes the scopes a node introduces
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct ContainerFlags: u32 {
+        /// The node does not introduce a scope
+        const NONE = 0;
+        /// Declarations inside the node become its members, exports, or locals
+        const IS_CONTAINER = 1 << 0;
+        /// Block-scoped declarations inside the node are local to it
+        const IS_BLOCK_SCOPED_CONTAINER = 1 << 1;
+        /// The node starts a new control flow graph
+        const IS_CONTROL_FLOW_CONTAINER = 1 << 2;
+        /// The node is a function-like declaration
+        const IS_FUNCTION_LIKE = 1 << 3;
+        /// The node is a function expression or arrow function
+        const IS_FUNCTION_EXPRESSION = 1 << 4;
+        /// The node has a table of locals
+        const HAS_LOCALS = 1 << 5;
+        /// The node is an interface
+        const IS_INTERFACE = 1 << 6;
+    }
+}
+
+/// How much runtime code a namespace declaration produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModuleInstanceState {
+    /// Only types, so nothing is emitted
+    NonInstantiated,
+    /// Values that exist at runtime
+    Instantiated,
+    /// Only const enums, which are inlined at their uses
+    ConstEnumOnly,
+}
+
+/// Identifies a node of the control flow graphs built while binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FlowId(u32);
+
+impl FlowId {
+    /// Gets the index of the flow node in the list of flow nodes
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A point in the control flow graph of a function, source file, or other control flow
+/// container
+///
+/// Each flow node records what happens between its antecedents, the nodes control comes from,
+/// and itself. The checker walks the graph backwards from a reference to find the assignments
+/// and conditions that narrow its type.
+#[derive(Debug, Clone)]
+pub enum FlowNode {
+    /// The start of a control flow container
+    ///
+    /// Function expressions and arrow functions remember the flow where they are created, so
+    /// references to constants can be narrowed by what precedes the function.
+    Start { container_flow: Option<FlowId> },
+    /// Code that control cannot reach
+    Unreachable,
+    /// The join of branches of an `if`, a conditional, a `switch`, or a logical expression
+    BranchLabel { antecedents: Vec<FlowId> },
+    /// The top of a loop, joining its entry with its back edges
+    LoopLabel { antecedents: Vec<FlowId> },
+    /// An assignment to a reference, or a variable declaration that initializes it
+    Assignment { node: Arc<Node>, antecedent: FlowId },
+    /// A call `x.push(...)` or `x.unshift(...)`, or an assignment `x[i] = v`, which adds
+    /// elements to an array whose type evolves with the elements added to it
+    ArrayMutation { node: Arc<Node>, antecedent: FlowId },
+    /// A condition known to be true or false
+    Condition {
+        expression: Arc<Node>,
+        assume_true: bool,
+        antecedent: FlowId,
+    },
+    /// Entry into the clauses `clause_start..clause_end` of a switch statement
+    ///
+    /// An empty range is the implicit default of a switch without a `default` clause.
+    SwitchClause {
+        switch_statement: Arc<Node>,
+        clause_start: usize,
+        clause_end: usize,
+        antecedent: FlowId,
+    },
+}
+
+/// The symbols and scopes of the bound source files of a program
+#[derive(Debug, Default, Clone)]
+pub struct Bindings {
+    /// Every symbol created while binding
+    pub symbols: SymbolArena,
+    /// The symbol of each declaration
+    node_symbols: HashMap<NodeId, SymbolId>,
+    /// The local symbol of each exported declaration that also has one
+    local_symbols: HashMap<NodeId, SymbolId>,
+    /// The locals of each node that introduces a scope
+    locals: HashMap<NodeId, SymbolTable>,
+    /// The diagnostics reported while binding each source file
+    diagnostics: HashMap<NodeId, Vec<Diagnostic>>,
+    /// The nodes of the control flow graphs of every bound file
+    flow_nodes: Vec<FlowNode>,
+    /// The flow node control is at when it reaches each reference
+    node_flows: HashMap<NodeId, FlowId>,
+    /// The flow node control is at when each constructor returns
+    return_flows: HashMap<NodeId, FlowId>,
+    /// The functions whose bodies control can reach the end of
+    implicit_returns: HashSet<NodeId>,
+}
+
+impl Bindings {
+    /// Creates empty bindings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds the declarations of `file`, doing nothing if it has been bound before
+    pub fn bind_source_file(&mut self, file: &Arc<Node>) {
+        if self.diagnostics.contains_key(&file.node_id()) {
+            return;
+        }
+        let unreachable_flow = self.add_flow_node(FlowNode::Unreachable);
+        let mut binder = Binder {
+            bindings: self,
+            file: Arc::clone(file),
+            container: Arc::clone(file),
+            block_scope_container: Arc::clone(file),
+            export_context: HashSet::new(),
+            diagnostics: Vec::new(),
+            current_flow: unreachable_flow,
+            unreachable_flow,
+            current_break_target: None,
+            current_continue_target: None,
+            current_true_target: None,
+            current_false_target: None,
+            current_exception_target: None,
+            pre_switch_case_flow: None,
+            current_return_target: None,
+            active_labels: Vec::new(),
+        };
+        binder.bind_source_file();
+        let diagnostics = binder.diagnostics;
+        self.diagnostics.insert(file.node_id(), diagnostics);
+    }
+
+    /// Gets the symbol of a declaration
+    ///
+    /// For an exported declaration this is the symbol in its container's exports.
+    pub fn symbol_of_node(&self, node: &Node) -> Option<SymbolId> {
+        self.node_symbols.get(&node.node_id()).copied()
+    }
+
+    /// Gets the local symbol of an exported declaration that can also be referenced by name
+    /// inside its container, such as an exported function or class
+    pub fn local_symbol_of_node(&self, node: &Node) -> Option<SymbolId> {
+        self.local_symbols.get(&node.node_id()).copied()
+    }
+
+    /// Gets the locals of a node that introduces a scope
+    pub fn locals(&self, node: &Node) -> Option<&SymbolTable> {
+        self.locals.get(&node.node_id())
+    }
+
+    /// Gets the diagnostics reported while binding `file`
+    pub fn diagnostics(&self, file: &Node) -> &[Diagnostic] {
+        self.diagnostics
+            .get(&file.node_id())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Gets a node of a control flow graph
+    pub fn flow_node(&self, flow: FlowId) -> &FlowNode {
+        &self.flow_nodes[flow.index()]
+    }
+
+    /// Gets the flow node control is at when it reaches a reference to a variable, `this`, or
+    /// a property
+    pub fn flow_of_node(&self, node: &Node) -> Option<FlowId> {
+        self.node_flows.get(&node.node_id()).copied()
+    }
+
+    /// Gets the flow node control is at when a constructor returns, whether by a `return`
+    /// statement or by reaching the end of its body
+    pub fn return_flow_of_node(&self, node: &Node) -> Option<FlowId> {
+        self.return_flows.get(&node.node_id()).copied()
+    }
+
+    /// Determines if control can reach the end of the body of a function, which then returns
+    /// without a `return` statement
+    pub fn has_implicit_return(&self, node: &Node) -> bool {
+        self.implicit_returns.contains(&node.node_id())
+    }
+
+    /// Adds the bindings of other files, bound apart from these as on another thread
+    ///
+    /// Their symbols and flow nodes are renumbered to follow those already here, so merging
+    /// the bindings of each file in order gives the same ids as binding the files in order.
+    /// The files must not have been bound here already.
+    pub fn merge(&mut self, other: Bindings) {
+        let symbol_offset = self.symbols.append(other.symbols);
+        let shift_symbol = |id: SymbolId| SymbolId::new(id.value() + symbol_offset);
+        let flow_offset = self.flow_nodes.len() as u32;
+        let shift_flow = |id: FlowId| FlowId(id.0 + flow_offset);
+        self.node_symbols.extend(
+            other
+                .node_symbols
+                .into_iter()
+                .map(|(node, symbol)| (node, shift_symbol(symbol))),
+        );
+        self.local_symbols.extend(
+            other
+                .local_symbols
+                .into_iter()
+                .map(|(node, symbol)| (node, shift_symbol(symbol))),
+        );
+        self.locals
+            .extend(other.locals.into_iter().map(|(node, mut table)| {
+                for symbol in table.values_mut() {
+                    *symbol = shift_symbol(*symbol);
+                }
+                (node, table)
+            }));
+        self.diagnostics.extend(other.diagnostics);
+        self.flow_nodes.extend(
+            other
+                .flow_nodes
+                .into_iter()
+                .map(|flow| flow.map_flow_ids(shift_flow)),
+        );
+        self.node_flows.extend(
+            other
+                .node_flows
+                .into_iter()
+                .map(|(node, flow)| (node, shift_flow(flow))),
+        );
+        self.return_flows.extend(
+            other
+                .return_flows
+                .into_iter()
+                .map(|(node, flow)| (node, shift_flow(flow))),
+        );
+        self.implicit_returns.extend(other.implicit_returns);
+    }
+
+    fn add_flow_node(&mut self, flow: FlowNode) -> FlowId {
+        let id = FlowId(self.flow_nodes.len() as u32);
+        self.flow_nodes.push(flow);
+        id
+    }
+}
+
+impl FlowNode {
+    /// Replaces the flow nodes this one refers to
+    fn map_flow_ids(self, f: impl Fn(FlowId) -> FlowId) -> FlowNode {
+        match self {
+            FlowNode::Start { container_flow } => FlowNode::Start {
+                container_flow: container_flow.map(f),
+            },
+            FlowNode::Unreachable => FlowNode::Unreachable,
+            FlowNode::BranchLabel { antecedents } => FlowNode::BranchLabel {
+                antecedents: antecedents.into_iter().map(f).collect(),
+            },
+            FlowNode::LoopLabel { antecedents } => FlowNode::LoopLabel {
+                antecedents: antecedents.into_iter().map(f).collect(),
+            },
+            FlowNode::Assignment { node, antecedent } => FlowNode::Assignment {
+                node,
+                antecedent: f(antecedent),
+            },
+            FlowNode::ArrayMutation { node, antecedent } => FlowNode::ArrayMutation {
+                node,
+                antecedent: f(antecedent),
+            },
+            FlowNode::Condition {
+                expression,
+                assume_true,
+                antecedent,
+            } => FlowNode::Condition {
+                expression,
+                assume_true,
+                antecedent: f(antecedent),
+            },
+            FlowNode::SwitchClause {
+                switch_statement,
+                clause_start,
+                clause_end,
+                antecedent,
+            } => FlowNode::SwitchClause {
+                switch_statement,
+                clause_start,
+                clause_end,
+                antecedent: f(antecedent),
+            },
+        }
+    }
+}
+
+/// A labeled statement enclosing the node being bound
+#[derive(Debug)]
+struct ActiveLabel {
+    name: String,
+    break_target: FlowId,
+    /// The loop the label is on continues here, if it is on a loop
+    continue_target: Option<FlowId>,
+}
+
+/// A symbol table being declared into
+#[derive(Debug, Clone, Copy)]
+enum Table {
+    Locals(NodeId),
+    Members(SymbolId),
+    Exports(SymbolId),
+}
+
+struct Binder<'a> {
+    bindings: &'a mut Bindings,
+    file: Arc<Node>,
+    /// The closest node whose declarations become its locals, members, or exports
+    container: Arc<Node>,
+    /// The closest node that block-scoped declarations are local to
+    block_scope_container: Arc<Node>,
+    /// Ambient namespaces and declaration files whose declarations are all exported
+    export_context: HashSet<NodeId>,
+    diagnostics: Vec<Diagnostic>,
+    /// The flow node control is at in the code being bound
+    current_flow: FlowId,
+    /// Stands in for every point control cannot reach in the file
+    unreachable_flow: FlowId,
+    /// Where an unlabeled `break` goes
+    current_break_target: Option<FlowId>,
+    /// Where an unlabeled `continue` goes
+    current_continue_target: Option<FlowId>,
+    /// Where control goes when the condition being bound is true
+    current_true_target: Option<FlowId>,
+    /// Where control goes when the condition being bound is false
+    current_false_target: Option<FlowId>,
+    /// Where control goes when the try block being bound throws
+    current_exception_target: Option<FlowId>,
+    /// The flow before the clauses of the switch statement being bound
+    pre_switch_case_flow: Option<FlowId>,
+    /// Where a `return` goes in the constructor being bound
+    current_return_target: Option<FlowId>,
+    active_labels: Vec<ActiveLabel>,
+}
+
+impl Binder<'_> {
+    fn bind_source_file(&mut self) {
+        let file = Arc::clone(&self.file);
+        self.set_export_context(&file);
+        let is_json_file = file
+            .flags
+            .contains(crate::compiler::ast::NodeFlags::JSON_FILE);
+        if is_external_module(&file) || is_json_file {
+            let name = format!(
+                "\"{}\"",
+                remove_file_extension(&file.as_source_file().file_name)
+            );
+            let module = self.bind_anonymous_declaration(&file, SymbolFlags::VALUE_MODULE, &name);
+            if is_json_file {
+                // A JSON file exports its value as a whole, as though by `export =`. The file
+                // stays the declaration of its module symbol.
+                let symbol = self
+                    .bindings
+                    .symbols
+                    .alloc(internal_symbol_names::EXPORT_EQUALS, SymbolFlags::PROPERTY);
+                let export_equals = &mut self.bindings.symbols[symbol];
+                export_equals.declarations.push(Arc::clone(&file));
+                export_equals.value_declaration = Some(Arc::clone(&file));
+                export_equals.parent = Some(module);
+                self.table(Table::Exports(module))
+                    .insert(internal_symbol_names::EXPORT_EQUALS.to_string(), symbol);
+            }
+        }
+        self.bind(&file);
+    }
+
+    fn bind(&mut self, node: &Arc<Node>) {
+        self.bind_worker(node);
+        let flags = container_flags(node);
+        if flags == ContainerFlags::NONE {
+            self.bind_children(node);
+        } else {
+            self.bind_container(node, flags);
+        }
+    }
+
+    /// Binds the children of `node`, building the control flow graph of statements and
+    /// expressions that branch
+    fn bind_children(&mut self, node: &Arc<Node>) {
+        match node.kind {
+            SyntaxKind::IfStatement => self.bind_if_statement(node),
+            SyntaxKind::WhileStatement => self.bind_while_statement(node),
+            SyntaxKind::DoStatement => self.bind_do_statement(node),
+            SyntaxKind::ForStatement => self.bind_for_statement(node),
+            SyntaxKind::ForInStatement | SyntaxKind::ForOfStatement => {
+                self.bind_for_in_or_for_of_statement(node)
+            }
+            SyntaxKind::ReturnStatement | SyntaxKind::ThrowStatement => {
+                self.bind_each_child(node);
+                if node.kind == SyntaxKind::ReturnStatement
+                    && let Some(return_target) = self.current_return_target
+                {
+                    self.add_antecedent(return_target, self.current_flow);
+                }
+                self.current_flow = self.unreachable_flow;
+            }
+            SyntaxKind::BreakStatement | SyntaxKind::ContinueStatement => {
+                self.bind_break_or_continue_statement(node)
+            }
+            SyntaxKind::TryStatement => self.bind_try_statement(node),
+            SyntaxKind::SwitchStatement => self.bind_switch_statement(node),
+            SyntaxKind::CaseBlock => self.bind_case_block(node),
+            SyntaxKind::CaseClause => {
+                // Case expressions are evaluated before control falls into any clause
+                let saved_flow = self.current_flow;
+                self.current_flow = self.pre_switch_case_flow.unwrap_or(saved_flow);
+                self.bind(&node.as_case_clause().expression);
+                self.current_flow = saved_flow;
+                for statement in &node.as_case_clause().statements {
+                    self.bind(statement);
+                }
+            }
+            SyntaxKind::LabeledStatement => self.bind_labeled_statement(node),
+            SyntaxKind::PrefixUnaryExpression => self.bind_prefix_unary_expression(node),
+            SyntaxKind::PostfixUnaryExpression => {
+                self.bind_each_child(node);
+                let operator = node.as_postfix_unary_expression().operator;
+                if matches!(
+                    operator,
+                    SyntaxKind::PlusPlusToken | SyntaxKind::MinusMinusToken
+                ) {
+                    self.bind_assignment_target_flow(&node.as_postfix_unary_expression().operand);
+                }
+            }
+            SyntaxKind::BinaryExpression => self.bind_binary_expression(node),
+            SyntaxKind::ConditionalExpression => self.bind_conditional_expression(node),
+            SyntaxKind::CallExpression => {
+                self.bind_each_child(node);
+                let callee = &node.as_call_expression().expression;
+                if callee.kind == SyntaxKind::PropertyAccessExpression {
+                    let access = callee.as_property_access_expression();
+                    if matches!(access.name.text(), "push" | "unshift")
+                        && is_narrowable_operand(&access.expression)
+                    {
+                        self.create_flow_array_mutation(node);
+                    }
+                }
+            }
+            SyntaxKind::VariableDeclaration => {
+                self.bind_each_child(node);
+                let in_for_in_or_of =
+                    node.parent()
+                        .and_then(|list| list.parent())
+                        .is_some_and(|statement| {
+                            matches!(
+                                statement.kind,
+                                SyntaxKind::ForInStatement | SyntaxKind::ForOfStatement
+                            )
+                        });
+                if node.initializer().is_some() || in_for_in_or_of {
+                    self.bind_initialized_variable_flow(node);
+                }
+            }
+            _ => self.bind_each_child(node),
+        }
+    }
+
+    fn bind_each_child(&mut self, node: &Arc<Node>) {
+        node.for_each_child(&mut |child| {
+            self.bind(child);
+            false
+        });
+    }
+
+    fn bind_container(&mut self, node: &Arc<Node>, flags: ContainerFlags) {
+        let saved_container = Arc::clone(&self.container);
+        let saved_block_scope_container = Arc::clone(&self.block_scope_container);
+        if flags.contains(ContainerFlags::IS_CONTAINER) {
+            self.container = Arc::clone(node);
+            self.block_scope_container = Arc::clone(node);
+        } else if flags.contains(ContainerFlags::IS_BLOCK_SCOPED_CONTAINER) {
+            self.block_scope_container = Arc::clone(node);
+        }
+        if flags.contains(ContainerFlags::HAS_LOCALS) {
+            self.bindings.locals.entry(node.node_id()).or_default();
+        }
+        if flags.contains(ContainerFlags::IS_CONTROL_FLOW_CONTAINER) {
+            self.bind_control_flow_container(node, flags);
+        } else {
+            self.bind_children(node);
+        }
+        self.container = saved_container;
+        self.block_scope_container = saved_block_scope_container;
+    }
+
+    /// Binds a container with a control flow graph of its own, which starts afresh and leaves
+    /// the flow around the container as it was
+    fn bind_control_flow_container(&mut self, node: &Arc<Node>, flags: ContainerFlags) {
+        let saved_flow = self.current_flow;
+        let saved_break_target = self.current_break_target.take();
+        let saved_continue_target = self.current_continue_target.take();
+        let saved_true_target = self.current_true_target.take();
+        let saved_false_target = self.current_false_target.take();
+        let saved_exception_target = self.current_exception_target.take();
+        let saved_pre_switch_case_flow = self.pre_switch_case_flow.take();
+        let saved_return_target = self.current_return_target.take();
+        let saved_active_labels = std::mem::take(&mut self.active_labels);
+
+        let container_flow = flags
+            .contains(ContainerFlags::IS_FUNCTION_EXPRESSION)
+            .then_some(saved_flow);
+        self.current_flow = self
+            .bindings
+            .add_flow_node(FlowNode::Start { container_flow });
+        if node.kind == SyntaxKind::Constructor {
+            self.current_return_target = Some(self.create_branch_label());
+        }
+        self.bind_children(node);
+        if node.body().is_some() && self.current_flow != self.unreachable_flow {
+            self.bindings.implicit_returns.insert(node.node_id());
+        }
+        if let Some(return_target) = self.current_return_target {
+            self.add_antecedent(return_target, self.current_flow);
+            let return_flow = self.finish_flow_label(return_target);
+            self.bindings
+                .return_flows
+                .insert(node.node_id(), return_flow);
+        }
+
+        self.current_flow = saved_flow;
+        self.current_break_target = saved_break_target;
+        self.current_continue_target = saved_continue_target;
+        self.current_true_target = saved_true_target;
+        self.current_false_target = saved_false_target;
+        self.current_exception_target = saved_exception_target;
+        self.pre_switch_case_flow = saved_pre_switch_case_flow;
+        self.current_return_target = saved_return_target;
+        self.active_labels = saved_active_labels;
+    }
+
+    fn create_branch_label(&mut self) -> FlowId {
+        self.bindings.add_flow_node(FlowNode::BranchLabel {
+            antecedents: Vec::new(),
+        })
+    }
+
+    fn create_loop_label(&mut self) -> FlowId {
+        self.bindings.add_flow_node(FlowNode::LoopLabel {
+            antecedents: Vec::new(),
+        })
+    }
+
+    fn is_unreachable(&self, flow: FlowId) -> bool {
+        matches!(self.bindings.flow_node(flow), FlowNode::Unreachable)
+    }
+
+    /// Adds a way control can reach a label, ignoring flows control cannot reach
+    fn add_antecedent(&mut self, label: FlowId, antecedent: FlowId) {
+        if self.is_unreachable(antecedent) {
+            return;
+        }
+        match &mut self.bindings.flow_nodes[label.index()] {
+            FlowNode::BranchLabel { antecedents } | FlowNode::LoopLabel { antecedents } => {
+                if !antecedents.contains(&antecedent) {
+                    antecedents.push(antecedent);
+                }
+            }
+            flow => panic!("{flow:?} is not a label"),
+        }
+    }
+
+    /// Gets the flow after a branch label: unreachable when nothing reaches it, and the only
+    /// antecedent when just one does
+    fn finish_flow_label(&mut self, label: FlowId) -> FlowId {
+        let FlowNode::BranchLabel { antecedents } = self.bindings.flow_node(label) else {
+            return label;
+        };
+        match antecedents.as_slice() {
+            [] => self.unreachable_flow,
+            [antecedent] => *antecedent,
+            _ => label,
+        }
+    }
+
+    fn create_flow_condition(
+        &mut self,
+        assume_true: bool,
+        antecedent: FlowId,
+        expression: Option<&Arc<Node>>,
+    ) -> FlowId {
+        if self.is_unreachable(antecedent) {
+            return antecedent;
+        }
+        // A missing condition, as in `for (;;)`, is always true
+        let Some(expression) = expression else {
+            return if assume_true {
+                antecedent
+            } else {
+                self.unreachable_flow
+            };
+        };
+        if (expression.kind == SyntaxKind::TrueKeyword && !assume_true)
+            || (expression.kind == SyntaxKind::FalseKeyword && assume_true)
+        {
+            return self.unreachable_flow;
+        }
+        self.bindings.add_flow_node(FlowNode::Condition {
+            expression: Arc::clone(expression),
+            assume_true,
+            antecedent,
+        })
+    }
+
+    fn create_flow_assignment(&mut self, node: &Arc<Node>) {
+        self.current_flow = self.bindings.add_flow_node(FlowNode::Assignment {
+            node: Arc::clone(node),
+            antecedent: self.current_flow,
+        });
+        // A try block can throw after any assignment in it
+        if let Some(target) = self.current_exception_target {
+            self.add_antecedent(target, self.current_flow);
+        }
+    }
+
+    fn create_flow_array_mutation(&mut self, node: &Arc<Node>) {
+        if self.is_unreachable(self.current_flow) {
+            return;
+        }
+        self.current_flow = self.bindings.add_flow_node(FlowNode::ArrayMutation {
+            node: Arc::clone(node),
+            antecedent: self.current_flow,
+        });
+        if let Some(target) = self.current_exception_target {
+            self.add_antecedent(target, self.current_flow);
+        }
+    }
+
+    /// Binds `node` as a condition, sending control to `true_target` or `false_target`
+    /// depending on its value
+    fn bind_condition(
+        &mut self,
+        node: Option<&Arc<Node>>,
+        true_target: FlowId,
+        false_target: FlowId,
+    ) {
+        if let Some(node) = node {
+            let saved_true_target = self.current_true_target.replace(true_target);
+            let saved_false_target = self.current_false_target.replace(false_target);
+            self.bind(node);
+            self.current_true_target = saved_true_target;
+            self.current_false_target = saved_false_target;
+        }
+        // Logical expressions send control to the targets themselves
+        if node.is_none_or(|node| !is_logical_expression(node)) {
+            let true_flow = self.create_flow_condition(true, self.current_flow, node);
+            self.add_antecedent(true_target, true_flow);
+            let false_flow = self.create_flow_condition(false, self.current_flow, node);
+            self.add_antecedent(false_target, false_flow);
+        }
+    }
+
+    fn bind_iterative_statement(
+        &mut self,
+        node: &Arc<Node>,
+        break_target: FlowId,
+        continue_target: FlowId,
+    ) {
+        let saved_break_target = self.current_break_target.replace(break_target);
+        let saved_continue_target = self.current_continue_target.replace(continue_target);
+        self.bind(node);
+        self.current_break_target = saved_break_target;
+        self.current_continue_target = saved_continue_target;
+    }
+
+    /// Makes `target` where a labeled `continue` goes for the labels on the loop `node`
+    fn set_continue_target(&mut self, node: &Arc<Node>, target: FlowId) -> FlowId {
+        let mut node = Arc::clone(node);
+        let mut index = self.active_labels.len();
+        while index > 0
+            && let Some(parent) = node
+                .parent()
+                .filter(|parent| parent.kind == SyntaxKind::LabeledStatement)
+        {
+            index -= 1;
+            self.active_labels[index].continue_target = Some(target);
+            node = parent;
+        }
+        target
+    }
+
+    fn bind_if_statement(&mut self, node: &Arc<Node>) {
+        let statement = node.as_if_statement();
+        let then_label = self.create_branch_label();
+        let else_label = self.create_branch_label();
+        let post_if_label = self.create_branch_label();
+        self.bind_condition(Some(&statement.expression), then_label, else_label);
+        self.current_flow = self.finish_flow_label(then_label);
+        self.bind(&statement.then_statement);
+        self.add_antecedent(post_if_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(else_label);
+        if let Some(else_statement) = &statement.else_statement {
+            self.bind(else_statement);
+        }
+        self.add_antecedent(post_if_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(post_if_label);
+    }
+
+    fn bind_while_statement(&mut self, node: &Arc<Node>) {
+        let statement = node.as_while_statement();
+        let loop_label = self.create_loop_label();
+        let pre_while_label = self.set_continue_target(node, loop_label);
+        let pre_body_label = self.create_branch_label();
+        let post_while_label = self.create_branch_label();
+        self.add_antecedent(pre_while_label, self.current_flow);
+        self.current_flow = pre_while_label;
+        self.bind_condition(
+            Some(&statement.expression),
+            pre_body_label,
+            post_while_label,
+        );
+        self.current_flow = self.finish_flow_label(pre_body_label);
+        self.bind_iterative_statement(&statement.statement, post_while_label, pre_while_label);
+        self.add_antecedent(pre_while_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(post_while_label);
+    }
+
+    fn bind_do_statement(&mut self, node: &Arc<Node>) {
+        let statement = node.as_do_statement();
+        let pre_do_label = self.create_loop_label();
+        let branch_label = self.create_branch_label();
+        let pre_condition_label = self.set_continue_target(node, branch_label);
+        let post_do_label = self.create_branch_label();
+        self.add_antecedent(pre_do_label, self.current_flow);
+        self.current_flow = pre_do_label;
+        self.bind_iterative_statement(&statement.statement, post_do_label, pre_condition_label);
+        self.add_antecedent(pre_condition_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(pre_condition_label);
+        self.bind_condition(Some(&statement.expression), pre_do_label, post_do_label);
+        self.current_flow = self.finish_flow_label(post_do_label);
+    }
+
+    fn bind_for_statement(&mut self, node: &Arc<Node>) {
+        let statement = node.as_for_statement();
+        let loop_label = self.create_loop_label();
+        let pre_loop_label = self.set_continue_target(node, loop_label);
+        let pre_body_label = self.create_branch_label();
+        let pre_incrementor_label = self.create_branch_label();
+        let post_loop_label = self.create_branch_label();
+        if let Some(initializer) = &statement.initializer {
+            self.bind(initializer);
+        }
+        self.add_antecedent(pre_loop_label, self.current_flow);
+        self.current_flow = pre_loop_label;
+        self.bind_condition(
+            statement.condition.as_ref(),
+            pre_body_label,
+            post_loop_label,
+        );
+        self.current_flow = self.finish_flow_label(pre_body_label);
+        self.bind_iterative_statement(&statement.statement, post_loop_label, pre_incrementor_label);
+        self.add_antecedent(pre_incrementor_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(pre_incrementor_label);
+        if let Some(incrementor) = &statement.incrementor {
+            self.bind(incrementor);
+        }
+        self.add_antecedent(pre_loop_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(post_loop_label);
+    }
+
+    fn bind_for_in_or_for_of_statement(&mut self, node: &Arc<Node>) {
+        let (initializer, expression, statement) = match node.kind {
+            SyntaxKind::ForInStatement => {
+                let statement = node.as_for_in_statement();
+                (
+                    &statement.initializer,
+                    &statement.expression,
+                    &statement.statement,
+                )
+            }
+            _ => {
+                let statement = node.as_for_of_statement();
+                (
+                    &statement.initializer,
+                    &statement.expression,
+                    &statement.statement,
+                )
+            }
+        };
+        let loop_label = self.create_loop_label();
+        let pre_loop_label = self.set_continue_target(node, loop_label);
+        let post_loop_label = self.create_branch_label();
+        self.bind(expression);
+        self.add_antecedent(pre_loop_label, self.current_flow);
+        self.current_flow = pre_loop_label;
+        // The loop ends when the iteration runs out of elements
+        self.add_antecedent(post_loop_label, self.current_flow);
+        self.bind(initializer);
+        if initializer.kind != SyntaxKind::VariableDeclarationList {
+            self.bind_assignment_target_flow(initializer);
+        }
+        self.bind_iterative_statement(statement, post_loop_label, pre_loop_label);
+        self.add_antecedent(pre_loop_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(post_loop_label);
+    }
+
+    fn bind_break_or_continue_statement(&mut self, node: &Arc<Node>) {
+        self.bind_each_child(node);
+        let is_break = node.kind == SyntaxKind::BreakStatement;
+        let label = if is_break {
+            &node.as_break_statement().label
+        } else {
+            &node.as_continue_statement().label
+        };
+        let target = match label {
+            Some(label) => self
+                .active_labels
+                .iter()
+                .rev()
+                .find(|active| active.name == label.text())
+                .and_then(|active| {
+                    if is_break {
+                        Some(active.break_target)
+                    } else {
+                        active.continue_target
+                    }
+                }),
+            None if is_break => self.current_break_target,
+            None => self.current_continue_target,
+        };
+        // Jumps to missing targets are errors reported by the checker
+        if let Some(target) = target {
+            self.add_antecedent(target, self.current_flow);
+            self.current_flow = self.unreachable_flow;
+        }
+    }
+
+    fn bind_labeled_statement(&mut self, node: &Arc<Node>) {
+        let statement = node.as_labeled_statement();
+        let post_statement_label = self.create_branch_label();
+        self.active_labels.push(ActiveLabel {
+            name: statement.label.text().to_string(),
+            break_target: post_statement_label,
+            continue_target: None,
+        });
+        self.bind(&statement.label);
+        self.bind(&statement.statement);
+        self.active_labels.pop();
+        self.add_antecedent(post_statement_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(post_statement_label);
+    }
+
+    /// Binds a try statement
+    ///
+    /// Control enters the catch clause from the start of the try block or from after any
+    /// assignment in it, and enters the finally block from wherever the try block or the catch
+    /// clause can finish or throw.
+    fn bind_try_statement(&mut self, node: &Arc<Node>) {
+        let statement = node.as_try_statement();
+        let saved_exception_target = self.current_exception_target;
+        let exception_label = self.create_branch_label();
+        self.add_antecedent(exception_label, self.current_flow);
+        self.current_exception_target = Some(exception_label);
+        self.bind(&statement.try_block);
+        self.current_exception_target = saved_exception_target;
+
+        let post_try_label = self.create_branch_label();
+        self.add_antecedent(post_try_label, self.current_flow);
+        let mut exception_flow = self.finish_flow_label(exception_label);
+        if let Some(catch_clause) = &statement.catch_clause {
+            let catch_exception_label = self.create_branch_label();
+            self.add_antecedent(catch_exception_label, exception_flow);
+            self.current_exception_target = Some(catch_exception_label);
+            self.current_flow = exception_flow;
+            self.bind(catch_clause);
+            self.current_exception_target = saved_exception_target;
+            self.add_antecedent(post_try_label, self.current_flow);
+            exception_flow = self.finish_flow_label(catch_exception_label);
+        }
+        let post_try_flow = self.finish_flow_label(post_try_label);
+        let Some(finally_block) = &statement.finally_block else {
+            self.current_flow = post_try_flow;
+            return;
+        };
+        let pre_finally_label = self.create_branch_label();
+        self.add_antecedent(pre_finally_label, post_try_flow);
+        self.add_antecedent(pre_finally_label, exception_flow);
+        self.current_flow = self.finish_flow_label(pre_finally_label);
+        self.bind(finally_block);
+        // Control only continues past the finally block if the try or catch can finish
+        if self.is_unreachable(post_try_flow) {
+            self.current_flow = self.unreachable_flow;
+        }
+    }
+
+    fn bind_switch_statement(&mut self, node: &Arc<Node>) {
+        let statement = node.as_switch_statement();
+        let post_switch_label = self.create_branch_label();
+        self.bind(&statement.expression);
+        let saved_break_target = self.current_break_target.replace(post_switch_label);
+        let saved_pre_switch_case_flow = self.pre_switch_case_flow.replace(self.current_flow);
+        self.bind(&statement.case_block);
+        self.add_antecedent(post_switch_label, self.current_flow);
+        let has_default = statement
+            .case_block
+            .as_case_block()
+            .clauses
+            .iter()
+            .any(|clause| clause.kind == SyntaxKind::DefaultClause);
+        // Without a default clause, control can skip every clause
+        if !has_default && let Some(pre_switch_case_flow) = self.pre_switch_case_flow {
+            let flow = self.create_flow_switch_clause(pre_switch_case_flow, node, 0, 0);
+            self.add_antecedent(post_switch_label, flow);
+        }
+        self.current_break_target = saved_break_target;
+        self.pre_switch_case_flow = saved_pre_switch_case_flow;
+        self.current_flow = self.finish_flow_label(post_switch_label);
+    }
+
+    /// Binds the clauses of a switch statement
+    ///
+    /// Consecutive clauses without statements share the flow into the first clause with
+    /// statements after them, and each clause with statements is also reached by falling
+    /// through from the clause before it.
+    fn bind_case_block(&mut self, node: &Arc<Node>) {
+        let Some(switch_statement) = node.parent() else {
+            return self.bind_each_child(node);
+        };
+        let pre_switch_case_flow = self.pre_switch_case_flow.unwrap_or(self.current_flow);
+        let clauses = &node.as_case_block().clauses.nodes;
+        let mut fallthrough_flow = self.unreachable_flow;
+        let mut i = 0;
+        while i < clauses.len() {
+            let clause_start = i;
+            while clause_statements(&clauses[i]).is_empty() && i + 1 < clauses.len() {
+                if self.is_unreachable(fallthrough_flow) {
+                    self.current_flow = pre_switch_case_flow;
+                }
+                self.bind(&clauses[i]);
+                i += 1;
+            }
+            let pre_case_label = self.create_branch_label();
+            let clause_flow = self.create_flow_switch_clause(
+                pre_switch_case_flow,
+                &switch_statement,
+                clause_start,
+                i + 1,
+            );
+            self.add_antecedent(pre_case_label, clause_flow);
+            self.add_antecedent(pre_case_label, fallthrough_flow);
+            self.current_flow = self.finish_flow_label(pre_case_label);
+            self.bind(&clauses[i]);
+            fallthrough_flow = self.current_flow;
+            i += 1;
+        }
+    }
+
+    fn create_flow_switch_clause(
+        &mut self,
+        antecedent: FlowId,
+        switch_statement: &Arc<Node>,
+        clause_start: usize,
+        clause_end: usize,
+    ) -> FlowId {
+        if self.is_unreachable(antecedent) {
+            return antecedent;
+        }
+        self.bindings.add_flow_node(FlowNode::SwitchClause {
+            switch_statement: Arc::clone(switch_statement),
+            clause_start,
+            clause_end,
+            antecedent,
+        })
+    }
+
+    fn bind_prefix_unary_expression(&mut self, node: &Arc<Node>) {
+        let expression = node.as_prefix_unary_expression();
+        match expression.operator {
+            // `!` swaps where control goes when its operand is true and when it is false
+            SyntaxKind::ExclamationToken => {
+                std::mem::swap(
+                    &mut self.current_true_target,
+                    &mut self.current_false_target,
+                );
+                self.bind_each_child(node);
+                std::mem::swap(
+                    &mut self.current_true_target,
+                    &mut self.current_false_target,
+                );
+            }
+            SyntaxKind::PlusPlusToken | SyntaxKind::MinusMinusToken => {
+                self.bind_each_child(node);
+                self.bind_assignment_target_flow(&expression.operand);
+            }
+            _ => self.bind_each_child(node),
+        }
+    }
+
+    /// Binds a binary expression, walking the chain of binary expressions down its left operand,
+    /// as in `a + b + c`, with a stack of its own rather than recursing once per operator, which
+    /// would overflow the stack on long chains
+    fn bind_binary_expression(&mut self, node: &Arc<Node>) {
+        if is_logical_expression(node) {
+            self.bind_logical_binary_expression(node);
+            return;
+        }
+        let mut chain = vec![Arc::clone(node)];
+        let mut left = Arc::clone(&node.as_binary_expression().left);
+        // Binary expressions are never containers, so binding one is its worker then its
+        // children
+        while left.kind == SyntaxKind::BinaryExpression && !is_logical_expression(&left) {
+            self.bind_worker(&left);
+            let next = Arc::clone(&left.as_binary_expression().left);
+            chain.push(std::mem::replace(&mut left, next));
+        }
+        self.bind(&left);
+        while let Some(node) = chain.pop() {
+            let binary = node.as_binary_expression();
+            self.bind(&binary.operator_token);
+            self.bind(&binary.right);
+            self.bind_binary_expression_flow(&node);
+        }
+    }
+
+    /// Binds a `&&`, `||`, or `??` expression, which sends control to the targets of the
+    /// condition it is part of, or else joins its branches after it
+    fn bind_logical_binary_expression(&mut self, node: &Arc<Node>) {
+        match (self.current_true_target, self.current_false_target) {
+            (Some(true_target), Some(false_target)) if !is_top_level_logical_expression(node) => {
+                self.bind_logical_expression(node, true_target, false_target);
+            }
+            _ => {
+                let post_expression_label = self.create_branch_label();
+                self.bind_logical_expression(node, post_expression_label, post_expression_label);
+                self.current_flow = self.finish_flow_label(post_expression_label);
+            }
+        }
+    }
+
+    /// Records the assignment a binary expression makes, once its operands are bound
+    fn bind_binary_expression_flow(&mut self, node: &Arc<Node>) {
+        let binary = node.as_binary_expression();
+        let operator = binary.operator_token.kind;
+        // Defaults in destructuring targets are bound with the enclosing assignment
+        let is_destructuring_default = node.parent().is_some_and(|parent| {
+            matches!(
+                parent.kind,
+                SyntaxKind::ArrayLiteralExpression
+                    | SyntaxKind::PropertyAssignment
+                    | SyntaxKind::ShorthandPropertyAssignment
+            )
+        });
+        if operator.is_assignment_operator() && !is_destructuring_default {
+            self.bind_assignment_target_flow(&binary.left);
+            if operator == SyntaxKind::EqualsToken
+                && binary.left.kind == SyntaxKind::ElementAccessExpression
+                && is_narrowable_operand(&binary.left.as_element_access_expression().expression)
+            {
+                self.create_flow_array_mutation(node);
+            }
+        }
+    }
+
+    fn bind_logical_expression(
+        &mut self,
+        node: &Arc<Node>,
+        true_target: FlowId,
+        false_target: FlowId,
+    ) {
+        let binary = node.as_binary_expression();
+        let pre_right_label = self.create_branch_label();
+        if binary.operator_token.kind == SyntaxKind::AmpersandAmpersandToken {
+            self.bind_condition(Some(&binary.left), pre_right_label, false_target);
+        } else {
+            self.bind_condition(Some(&binary.left), true_target, pre_right_label);
+        }
+        self.current_flow = self.finish_flow_label(pre_right_label);
+        self.bind(&binary.operator_token);
+        self.bind_condition(Some(&binary.right), true_target, false_target);
+    }
+
+    fn bind_conditional_expression(&mut self, node: &Arc<Node>) {
+        let expression = node.as_conditional_expression();
+        let true_label = self.create_branch_label();
+        let false_label = self.create_branch_label();
+        let post_expression_label = self.create_branch_label();
+        self.bind_condition(Some(&expression.condition), true_label, false_label);
+        self.current_flow = self.finish_flow_label(true_label);
+        self.bind(&expression.question_token);
+        self.bind(&expression.when_true);
+        self.add_antecedent(post_expression_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(false_label);
+        self.bind(&expression.colon_token);
+        self.bind(&expression.when_false);
+        self.add_antecedent(post_expression_label, self.current_flow);
+        self.current_flow = self.finish_flow_label(post_expression_label);
+    }
+
+    /// Records assignments to the references in an assignment target, which may be a
+    /// destructuring pattern
+    fn bind_assignment_target_flow(&mut self, node: &Arc<Node>) {
+        match node.kind {
+            SyntaxKind::ArrayLiteralExpression => {
+                for element in &node.as_array_literal_expression().elements {
+                    match element.kind {
+                        SyntaxKind::SpreadElement => self
+                            .bind_assignment_target_flow(&element.as_spread_element().expression),
+                        _ => self.bind_destructuring_assignment_flow(element),
+                    }
+                }
+            }
+            SyntaxKind::ObjectLiteralExpression => {
+                for property in &node.as_object_literal_expression().properties {
+                    match property.kind {
+                        SyntaxKind::PropertyAssignment => self.bind_destructuring_assignment_flow(
+                            &property.as_property_assignment().initializer,
+                        ),
+                        SyntaxKind::ShorthandPropertyAssignment => self.create_flow_assignment(
+                            &property.as_shorthand_property_assignment().name,
+                        ),
+                        SyntaxKind::SpreadAssignment => self.bind_assignment_target_flow(
+                            &property.as_spread_assignment().expression,
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            SyntaxKind::ParenthesizedExpression => {
+                self.bind_assignment_target_flow(&node.as_parenthesized_expression().expression)
+            }
+            _ if is_narrowable_reference(node) => self.create_flow_assignment(node),
+            _ => {}
+        }
+    }
+
+    /// Records the assignment of an element of a destructuring assignment, which may have a
+    /// default value
+    fn bind_destructuring_assignment_flow(&mut self, node: &Arc<Node>) {
+        if node.kind == SyntaxKind::BinaryExpression
+            && node.as_binary_expression().operator_token.kind == SyntaxKind::EqualsToken
+        {
+            self.bind_assignment_target_flow(&node.as_binary_expression().left);
+        } else {
+            self.bind_assignment_target_flow(node);
+        }
+    }
+
+    /// Records the assignments of a variable declaration or binding element that initializes
+    /// the names it declares
+    fn bind_initialized_variable_flow(&mut self, node: &Arc<Node>) {
+        let Some(name) = node.name() else {
+            return;
+        };
+        if is_binding_pattern(name) {
+            let name = Arc::clone(name);
+            name.for_each_child(&mut |element| {
+                if element.kind == SyntaxKind::BindingElement {
+                    self.bind_initialized_variable_flow(element);
+                }
+                false
+            });
+        } else {
+            self.create_flow_assignment(node);
+        }
+    }
+
+    fn bind_worker(&mut self, node: &Arc<Node>) {
+        match node.kind {
+            SyntaxKind::Identifier
+            | SyntaxKind::ThisKeyword
+            | SyntaxKind::SuperKeyword
+            | SyntaxKind::PropertyAccessExpression
+            | SyntaxKind::ElementAccessExpression => {
+                self.bindings
+                    .node_flows
+                    .insert(node.node_id(), self.current_flow);
+            }
+            SyntaxKind::TypeParameter => self.bind_type_parameter(node),
+            SyntaxKind::Parameter => self.bind_parameter(node),
+            SyntaxKind::VariableDeclaration | SyntaxKind::BindingElement => {
+                self.bind_variable_declaration_or_binding_element(node)
+            }
+            SyntaxKind::PropertyDeclaration | SyntaxKind::PropertySignature => {
+                let mut flags = SymbolFlags::PROPERTY | optional_flag(node);
+                // An auto-accessor stores its value behind a getter and setter pair
+                if node.has_modifier(SyntaxKind::AccessorKeyword) {
+                    flags |= SymbolFlags::ACCESSOR;
+                }
+                self.bind_property_or_method_or_accessor(
+                    node,
+                    flags,
+                    SymbolFlags::PROPERTY_EXCLUDES,
+                )
+            }
+            SyntaxKind::PropertyAssignment | SyntaxKind::ShorthandPropertyAssignment => self
+                .bind_property_or_method_or_accessor(
+                    node,
+                    SymbolFlags::PROPERTY,
+                    SymbolFlags::PROPERTY_EXCLUDES,
+                ),
+            SyntaxKind::EnumMember => self.bind_property_or_method_or_accessor(
+                node,
+                SymbolFlags::ENUM_MEMBER,
+                SymbolFlags::ENUM_MEMBER_EXCLUDES,
+            ),
+            SyntaxKind::CallSignature
+            | SyntaxKind::ConstructSignature
+            | SyntaxKind::IndexSignature => {
+                self.declare_symbol_and_add_to_symbol_table(
+                    node,
+                    SymbolFlags::SIGNATURE,
+                    SymbolFlags::NONE,
+                );
+            }
+            SyntaxKind::MethodDeclaration | SyntaxKind::MethodSignature => {
+                // Object literal methods are properties that happen to be functions
+                let excludes = if self.container.kind == SyntaxKind::ObjectLiteralExpression {
+                    SymbolFlags::PROPERTY_EXCLUDES
+                } else {
+                    SymbolFlags::METHOD_EXCLUDES
+                };
+                let flags = SymbolFlags::METHOD | optional_flag(node);
+                self.bind_property_or_method_or_accessor(node, flags, excludes)
+            }
+            SyntaxKind::FunctionDeclaration => self.bind_function_declaration(node),
+            SyntaxKind::Constructor => {
+                self.declare_symbol_and_add_to_symbol_table(
+                    node,
+                    SymbolFlags::CONSTRUCTOR,
+                    SymbolFlags::NONE,
+                );
+            }
+            SyntaxKind::GetAccessor => self.bind_property_or_method_or_accessor(
+                node,
+                SymbolFlags::GET_ACCESSOR,
+                SymbolFlags::GET_ACCESSOR_EXCLUDES,
+            ),
+            SyntaxKind::SetAccessor => self.bind_property_or_method_or_accessor(
+                node,
+                SymbolFlags::SET_ACCESSOR,
+                SymbolFlags::SET_ACCESSOR_EXCLUDES,
+            ),
+            SyntaxKind::FunctionType | SyntaxKind::ConstructorType => {
+                self.bind_function_or_constructor_type(node)
+            }
+            SyntaxKind::TypeLiteral | SyntaxKind::MappedType => {
+                self.bind_anonymous_declaration(
+                    node,
+                    SymbolFlags::TYPE_LITERAL,
+                    internal_symbol_names::TYPE,
+                );
+            }
+            SyntaxKind::ObjectLiteralExpression => {
+                self.bind_anonymous_declaration(
+                    node,
+                    SymbolFlags::OBJECT_LITERAL,
+                    internal_symbol_names::OBJECT,
+                );
+            }
+            SyntaxKind::FunctionExpression | SyntaxKind::ArrowFunction => {
+                let name = node
+                    .name()
+                    .map_or(internal_symbol_names::FUNCTION, |name| name.text());
+                self.bind_anonymous_declaration(node, SymbolFlags::FUNCTION, name);
+            }
+            SyntaxKind::ClassDeclaration | SyntaxKind::ClassExpression => {
+                self.bind_class_like_declaration(node)
+            }
+            SyntaxKind::InterfaceDeclaration => self.bind_block_scoped_declaration(
+                node,
+                SymbolFlags::INTERFACE,
+                SymbolFlags::INTERFACE_EXCLUDES,
+            ),
+            SyntaxKind::TypeAliasDeclaration => self.bind_block_scoped_declaration(
+                node,
+                SymbolFlags::TYPE_ALIAS,
+                SymbolFlags::TYPE_ALIAS_EXCLUDES,
+            ),
+            SyntaxKind::EnumDeclaration => {
+                if node.has_modifier(SyntaxKind::ConstKeyword) {
+                    self.bind_block_scoped_declaration(
+                        node,
+                        SymbolFlags::CONST_ENUM,
+                        SymbolFlags::CONST_ENUM_EXCLUDES,
+                    )
+                } else {
+                    self.bind_block_scoped_declaration(
+                        node,
+                        SymbolFlags::REGULAR_ENUM,
+                        SymbolFlags::REGULAR_ENUM_EXCLUDES,
+                    )
+                }
+            }
+            SyntaxKind::ModuleDeclaration => self.bind_module_declaration(node),
+            SyntaxKind::ImportEqualsDeclaration
+            | SyntaxKind::NamespaceImport
+            | SyntaxKind::ImportSpecifier
+            | SyntaxKind::ExportSpecifier => {
+                self.declare_symbol_and_add_to_symbol_table(
+                    node,
+                    SymbolFlags::ALIAS,
+                    SymbolFlags::ALIAS_EXCLUDES,
+                );
+            }
+            SyntaxKind::ImportClause if node.as_import_clause().name.is_some() => {
+                self.declare_symbol_and_add_to_symbol_table(
+                    node,
+                    SymbolFlags::ALIAS,
+                    SymbolFlags::ALIAS_EXCLUDES,
+                );
+            }
+            SyntaxKind::ExportDeclaration => self.bind_export_declaration(node),
+            SyntaxKind::ExportAssignment => self.bind_export_assignment(node),
+            _ => {}
+        }
+    }
+
+    fn bind_type_parameter(&mut self, node: &Arc<Node>) {
+        // `infer T` declares `T` for the true branch of the enclosing conditional type
+        if let Some(parent) = node.parent()
+            && parent.kind == SyntaxKind::InferType
+        {
+            if let Some(conditional) = infer_type_container(&parent) {
+                self.declare_symbol(
+                    Table::Locals(conditional.node_id()),
+                    None,
+                    node,
+                    SymbolFlags::TYPE_PARAMETER,
+                    SymbolFlags::TYPE_PARAMETER_EXCLUDES,
+                );
+            } else {
+                self.bind_anonymous_declaration(
+                    node,
+                    SymbolFlags::TYPE_PARAMETER,
+                    node.name().map_or("", |name| name.text()),
+                );
+            }
+            return;
+        }
+        self.declare_symbol_and_add_to_symbol_table(
+            node,
+            SymbolFlags::TYPE_PARAMETER,
+            SymbolFlags::TYPE_PARAMETER_EXCLUDES,
+        );
+    }
+
+    fn bind_parameter(&mut self, node: &Arc<Node>) {
+        let parameter = node.as_parameter();
+        if is_binding_pattern(&parameter.name) {
+            let index = node
+                .parent()
+                .and_then(|function| {
+                    function
+                        .parameters()?
+                        .iter()
+                        .position(|p| Arc::ptr_eq(p, node))
+                })
+                .unwrap_or(0);
+            self.bind_anonymous_declaration(
+                node,
+                SymbolFlags::FUNCTION_SCOPED_VARIABLE,
+                &format!("__{index}"),
+            );
+        } else {
+            self.declare_symbol_and_add_to_symbol_table(
+                node,
+                SymbolFlags::FUNCTION_SCOPED_VARIABLE,
+                SymbolFlags::PARAMETER_EXCLUDES,
+            );
+        }
+
+        // A constructor parameter with an accessibility or readonly modifier also declares a
+        // property of the class
+        if is_parameter_property_declaration(node)
+            && let Some(class) = node.parent().and_then(|constructor| constructor.parent())
+            && let Some(class_symbol) = self.bindings.symbol_of_node(&class)
+        {
+            self.declare_symbol(
+                Table::Members(class_symbol),
+                Some(class_symbol),
+                node,
+                SymbolFlags::PROPERTY | optional_flag(node),
+                SymbolFlags::PROPERTY_EXCLUDES,
+            );
+        }
+    }
+
+    fn bind_variable_declaration_or_binding_element(&mut self, node: &Arc<Node>) {
+        let name = node.name().expect("variable declarations have names");
+        if is_binding_pattern(name) {
+            return;
+        }
+        if is_block_scoped_variable(node) || is_catch_clause_variable(node) {
+            self.bind_block_scoped_declaration(
+                node,
+                SymbolFlags::BLOCK_SCOPED_VARIABLE,
+                SymbolFlags::BLOCK_SCOPED_VARIABLE_EXCLUDES,
+            );
+        } else if root_declaration(node).kind == SyntaxKind::Parameter {
+            // Names in a destructured parameter are locals of the function, like parameters.
+            // They cannot be exported, so they go straight into the function's locals.
+            self.declare_symbol(
+                Table::Locals(self.container.node_id()),
+                None,
+                node,
+                SymbolFlags::FUNCTION_SCOPED_VARIABLE,
+                SymbolFlags::PARAMETER_EXCLUDES,
+            );
+        } else {
+            self.declare_symbol_and_add_to_symbol_table(
+                node,
+                SymbolFlags::FUNCTION_SCOPED_VARIABLE,
+                SymbolFlags::FUNCTION_SCOPED_VARIABLE_EXCLUDES,
+            );
+        }
+    }
+
+    fn bind_property_or_method_or_accessor(
+        &mut self,
+        node: &Arc<Node>,
+        flags: SymbolFlags,
+        excludes: SymbolFlags,
+    ) {
+        if has_dynamic_name(node) {
+            self.bind_anonymous_declaration(node, flags, internal_symbol_names::COMPUTED);
+        } else {
+            self.declare_symbol_and_add_to_symbol_table(node, flags, excludes);
+        }
+    }
+
+    fn bind_function_declaration(&mut self, node: &Arc<Node>) {
+        // Function declarations in nested blocks are block scoped, as they are in strict mode
+        if !Arc::ptr_eq(&self.block_scope_container, &self.container) {
+            self.bind_block_scoped_declaration(
+                node,
+                SymbolFlags::FUNCTION,
+                SymbolFlags::FUNCTION_EXCLUDES,
+            );
+        } else {
+            self.declare_symbol_and_add_to_symbol_table(
+                node,
+                SymbolFlags::FUNCTION,
+                SymbolFlags::FUNCTION_EXCLUDES,
+            );
+        }
+    }
+
+    /// Function and constructor types get a signature symbol wrapped in an anonymous type
+    /// literal symbol, as if they were written `{ (): T }` or `{ new (): T }`
+    fn bind_function_or_constructor_type(&mut self, node: &Arc<Node>) {
+        let name = declaration_name(node, &self.file).expect("signatures have names");
+        let signature = self.bindings.symbols.alloc(&*name, SymbolFlags::SIGNATURE);
+        self.add_declaration_to_symbol(signature, node, SymbolFlags::SIGNATURE);
+        let type_literal = self
+            .bindings
+            .symbols
+            .alloc(internal_symbol_names::TYPE, SymbolFlags::TYPE_LITERAL);
+        self.add_declaration_to_symbol(type_literal, node, SymbolFlags::TYPE_LITERAL);
+        self.bindings.symbols[type_literal]
+            .members
+            .insert(name, signature);
+    }
+
+    fn bind_class_like_declaration(&mut self, node: &Arc<Node>) {
+        let symbol = if node.kind == SyntaxKind::ClassDeclaration {
+            self.bind_block_scoped_declaration(
+                node,
+                SymbolFlags::CLASS,
+                SymbolFlags::CLASS_EXCLUDES,
+            );
+            self.bindings.symbol_of_node(node)
+        } else {
+            let name = node
+                .name()
+                .map_or(internal_symbol_names::CLASS, |name| name.text())
+                .to_string();
+            Some(self.bind_anonymous_declaration(node, SymbolFlags::CLASS, &name))
+        };
+
+        // Every class has a static `prototype` property of the instance type
+        if let Some(symbol) = symbol
+            && !self.bindings.symbols[symbol]
+                .exports
+                .contains_key("prototype")
+        {
+            let prototype = self
+                .bindings
+                .symbols
+                .alloc("prototype", SymbolFlags::PROPERTY | SymbolFlags::PROTOTYPE);
+            self.bindings.symbols[prototype].parent = Some(symbol);
+            self.bindings.symbols[symbol]
+                .exports
+                .insert("prototype".to_string(), prototype);
+        }
+    }
+
+    fn bind_module_declaration(&mut self, node: &Arc<Node>) {
+        self.set_export_context(node);
+        let name = &node.as_module_declaration().name;
+        if name.kind == SyntaxKind::StringLiteral
+            || node.as_module_declaration().keyword == SyntaxKind::GlobalKeyword
+        {
+            self.declare_symbol_and_add_to_symbol_table(
+                node,
+                SymbolFlags::VALUE_MODULE,
+                SymbolFlags::VALUE_MODULE_EXCLUDES,
+            );
+            return;
+        }
+        let state = module_instance_state(node);
+        if state == ModuleInstanceState::NonInstantiated {
+            self.declare_symbol_and_add_to_symbol_table(
+                node,
+                SymbolFlags::NAMESPACE_MODULE,
+                SymbolFlags::NAMESPACE_MODULE_EXCLUDES,
+            );
+        } else {
+            self.declare_symbol_and_add_to_symbol_table(
+                node,
+                SymbolFlags::VALUE_MODULE,
+                SymbolFlags::VALUE_MODULE_EXCLUDES,
+            );
+        }
+        let symbol = self.bindings.symbol_of_node(node).unwrap();
+        let symbol = &mut self.bindings.symbols[symbol];
+        if state == ModuleInstanceState::ConstEnumOnly {
+            // Stays const-enum-only only as long as every declaration is
+            if symbol.declarations.len() == 1 {
+                symbol.flags |= SymbolFlags::CONST_ENUM_ONLY_MODULE;
+            }
+        } else {
+            symbol.flags &= !SymbolFlags::CONST_ENUM_ONLY_MODULE;
+        }
+    }
+
+    fn bind_export_declaration(&mut self, node: &Arc<Node>) {
+        let Some(container_symbol) = self.bindings.symbol_of_node(&self.container) else {
+            // Export declarations outside of a module are errors reported by the checker
+            self.bind_anonymous_declaration(
+                node,
+                SymbolFlags::EXPORT_STAR,
+                internal_symbol_names::EXPORT_STAR,
+            );
+            return;
+        };
+        match &node.as_export_declaration().export_clause {
+            None => {
+                self.declare_symbol(
+                    Table::Exports(container_symbol),
+                    Some(container_symbol),
+                    node,
+                    SymbolFlags::EXPORT_STAR,
+                    SymbolFlags::NONE,
+                );
+            }
+            Some(clause) if clause.kind == SyntaxKind::NamespaceExport => {
+                self.declare_symbol(
+                    Table::Exports(container_symbol),
+                    Some(container_symbol),
+                    clause,
+                    SymbolFlags::ALIAS,
+                    SymbolFlags::ALIAS_EXCLUDES,
+                );
+            }
+            // The export specifiers bind themselves
+            Some(_) => {}
+        }
+    }
+
+    fn bind_export_assignment(&mut self, node: &Arc<Node>) {
+        let Some(container_symbol) = self.bindings.symbol_of_node(&self.container) else {
+            // Export assignments outside of a module are errors reported by the checker
+            let name = declaration_name(node, &self.file).unwrap_or_default();
+            self.bind_anonymous_declaration(node, SymbolFlags::VALUE, &name);
+            return;
+        };
+        // `export default x` exports whatever `x` refers to; other expressions are values
+        let expression = &node.as_export_assignment().expression;
+        let flags = if is_entity_name_expression(expression) {
+            SymbolFlags::ALIAS
+        } else {
+            SymbolFlags::PROPERTY
+        };
+        self.declare_symbol(
+            Table::Exports(container_symbol),
+            Some(container_symbol),
+            node,
+            flags,
+            SymbolFlags::ALL,
+        );
+    }
+
+    fn declare_symbol_and_add_to_symbol_table(
+        &mut self,
+        node: &Arc<Node>,
+        flags: SymbolFlags,
+        excludes: SymbolFlags,
+    ) -> SymbolId {
+        let container = Arc::clone(&self.container);
+        match container.kind {
+            SyntaxKind::ModuleDeclaration => self.declare_module_member(node, flags, excludes),
+            SyntaxKind::SourceFile => self.declare_source_file_member(node, flags, excludes),
+            SyntaxKind::ClassDeclaration | SyntaxKind::ClassExpression => {
+                self.declare_class_member(node, flags, excludes)
+            }
+            SyntaxKind::EnumDeclaration => {
+                let symbol = self.container_symbol();
+                self.declare_symbol(Table::Exports(symbol), Some(symbol), node, flags, excludes)
+            }
+            SyntaxKind::TypeLiteral
+            | SyntaxKind::ObjectLiteralExpression
+            | SyntaxKind::InterfaceDeclaration
+            | SyntaxKind::JsxAttributes => {
+                let symbol = self.container_symbol();
+                self.declare_symbol(Table::Members(symbol), Some(symbol), node, flags, excludes)
+            }
+            _ => self.declare_symbol(
+                Table::Locals(container.node_id()),
+                None,
+                node,
+                flags,
+                excludes,
+            ),
+        }
+    }
+
+    fn container_symbol(&self) -> SymbolId {
+        self.bindings
+            .symbol_of_node(&self.container)
+            .unwrap_or_else(|| panic!("{:?} container has no symbol", self.container.kind))
+    }
+
+    fn declare_class_member(
+        &mut self,
+        node: &Arc<Node>,
+        flags: SymbolFlags,
+        excludes: SymbolFlags,
+    ) -> SymbolId {
+        let class_symbol = self.container_symbol();
+        let table = if is_static(node) {
+            Table::Exports(class_symbol)
+        } else {
+            Table::Members(class_symbol)
+        };
+        self.declare_symbol(table, Some(class_symbol), node, flags, excludes)
+    }
+
+    fn declare_source_file_member(
+        &mut self,
+        node: &Arc<Node>,
+        flags: SymbolFlags,
+        excludes: SymbolFlags,
+    ) -> SymbolId {
+        if is_external_module(&self.file) {
+            self.declare_module_member(node, flags, excludes)
+        } else {
+            self.declare_symbol(
+                Table::Locals(self.file.node_id()),
+                None,
+                node,
+                flags,
+                excludes,
+            )
+        }
+    }
+
+    /// Declares a member of a module or namespace, which goes into its exports when exported
+    /// and into its locals otherwise
+    ///
+    /// Exported functions, classes, enums, and namespaces can also be referenced by name from
+    /// inside their container, so they get a local symbol too that links to the exported one.
+    fn declare_module_member(
+        &mut self,
+        node: &Arc<Node>,
+        flags: SymbolFlags,
+        excludes: SymbolFlags,
+    ) -> SymbolId {
+        let container = Arc::clone(&self.container);
+        let exported =
+            has_export_modifier(node) || self.export_context.contains(&container.node_id());
+        let container_symbol = self.bindings.symbol_of_node(&container);
+        let locals = Table::Locals(container.node_id());
+        if flags.contains(SymbolFlags::ALIAS) {
+            if let Some(container_symbol) = container_symbol
+                && (node.kind == SyntaxKind::ExportSpecifier
+                    || (node.kind == SyntaxKind::ImportEqualsDeclaration && exported))
+            {
+                return self.declare_symbol(
+                    Table::Exports(container_symbol),
+                    Some(container_symbol),
+                    node,
+                    flags,
+                    excludes,
+                );
+            }
+            return self.declare_symbol(locals, None, node, flags, excludes);
+        }
+
+        let Some(container_symbol) = container_symbol.filter(|_| exported) else {
+            return self.declare_symbol(locals, None, node, flags, excludes);
+        };
+        let exports = Table::Exports(container_symbol);
+        if node.has_modifier(SyntaxKind::DefaultKeyword) && node.name().is_none() {
+            // An unnamed default export has no local name
+            return self.declare_symbol(exports, Some(container_symbol), node, flags, excludes);
+        }
+        let export_kind = if flags.intersects(SymbolFlags::VALUE) {
+            SymbolFlags::EXPORT_VALUE
+        } else {
+            SymbolFlags::NONE
+        };
+        let local = self.declare_symbol(locals, None, node, export_kind, excludes);
+        let export = self.declare_symbol(exports, Some(container_symbol), node, flags, excludes);
+        self.bindings.symbols[local].export_symbol = Some(export);
+        self.bindings.local_symbols.insert(node.node_id(), local);
+        local
+    }
+
+    fn bind_block_scoped_declaration(
+        &mut self,
+        node: &Arc<Node>,
+        flags: SymbolFlags,
+        excludes: SymbolFlags,
+    ) {
+        let scope = Arc::clone(&self.block_scope_container);
+        match scope.kind {
+            SyntaxKind::ModuleDeclaration => {
+                self.declare_module_member(node, flags, excludes);
+            }
+            SyntaxKind::SourceFile => {
+                self.declare_source_file_member(node, flags, excludes);
+            }
+            _ => {
+                self.bindings.locals.entry(scope.node_id()).or_default();
+                self.declare_symbol(Table::Locals(scope.node_id()), None, node, flags, excludes);
+            }
+        }
+    }
+
+    fn bind_anonymous_declaration(
+        &mut self,
+        node: &Arc<Node>,
+        flags: SymbolFlags,
+        name: &str,
+    ) -> SymbolId {
+        let symbol = self.bindings.symbols.alloc(name, SymbolFlags::NONE);
+        self.add_declaration_to_symbol(symbol, node, flags);
+        symbol
+    }
+
+    /// Declares `node` in `table`, merging it into an existing symbol of the same name unless
+    /// that symbol has any of the `excludes` flags
+    fn declare_symbol(
+        &mut self,
+        table: Table,
+        parent: Option<SymbolId>,
+        node: &Arc<Node>,
+        includes: SymbolFlags,
+        excludes: SymbolFlags,
+    ) -> SymbolId {
+        let is_default_export = node.has_modifier(SyntaxKind::DefaultKeyword)
+            || (node.kind == SyntaxKind::ExportSpecifier
+                && node.name().is_some_and(|name| name.text() == "default"));
+        let name = if is_default_export && parent.is_some() {
+            Some(internal_symbol_names::DEFAULT.to_string())
+        } else {
+            declaration_name(node, &self.file)
+        };
+
+        let symbol = match name {
+            None => self
+                .bindings
+                .symbols
+                .alloc(internal_symbol_names::MISSING, SymbolFlags::NONE),
+            Some(name) => match self.table(table).get(&name).copied() {
+                None => {
+                    let symbol = self.bindings.symbols.alloc(&*name, SymbolFlags::NONE);
+                    self.table(table).insert(name, symbol);
+                    symbol
+                }
+                Some(existing) if self.bindings.symbols[existing].flags.intersects(excludes) => {
+                    self.report_duplicate(existing, node, includes);
+                    // The new declaration gets a symbol of its own that is not in the table
+                    self.bindings.symbols.alloc(name, SymbolFlags::NONE)
+                }
+                Some(existing) => existing,
+            },
+        };
+        self.add_declaration_to_symbol(symbol, node, includes);
+        if parent.is_some() {
+            self.bindings.symbols[symbol].parent = parent;
+        }
+        symbol
+    }
+
+    fn report_duplicate(&mut self, existing: SymbolId, node: &Arc<Node>, includes: SymbolFlags) {
+        let symbol = &self.bindings.symbols[existing];
+        let mut message: &Message = if symbol.flags.contains(SymbolFlags::BLOCK_SCOPED_VARIABLE) {
+            diagnostics::CANNOT_REDECLARE_BLOCK_SCOPED_VARIABLE_0_2451
+        } else {
+            diagnostics::DUPLICATE_IDENTIFIER_0_2300
+        };
+        let mut needs_name = true;
+        if symbol.flags.intersects(SymbolFlags::ENUM) || includes.intersects(SymbolFlags::ENUM) {
+            message =
+                diagnostics::ENUM_DECLARATIONS_CAN_ONLY_MERGE_WITH_NAMESPACE_OR_OTHER_ENUM_DECLARATIONS_2567;
+            needs_name = false;
+        }
+        if node.kind == SyntaxKind::ExportAssignment
+            && !node.as_export_assignment().is_export_equals
+        {
+            message = diagnostics::A_MODULE_CANNOT_HAVE_MULTIPLE_DEFAULT_EXPORTS_2528;
+            needs_name = false;
+        }
+
+        let declarations = symbol.declarations.clone();
+        for declaration in declarations.iter().chain(std::iter::once(node)) {
+            let error_node = declaration.name().unwrap_or(declaration);
+            let args = if needs_name {
+                vec![display_name(declaration, &self.file)]
+            } else {
+                Vec::new()
+            };
+            let diagnostic = Diagnostic::new(
+                Some(&self.file.as_source_file().file_name),
+                get_error_range(&self.file, error_node),
+                message,
+                &args,
+            );
+            if !self.diagnostics.contains(&diagnostic) {
+                self.diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    fn add_declaration_to_symbol(
+        &mut self,
+        symbol: SymbolId,
+        node: &Arc<Node>,
+        flags: SymbolFlags,
+    ) {
+        self.bindings.node_symbols.insert(node.node_id(), symbol);
+        let symbol = &mut self.bindings.symbols[symbol];
+        symbol.flags |= flags;
+        if !symbol
+            .declarations
+            .iter()
+            .any(|declaration| Arc::ptr_eq(declaration, node))
+        {
+            symbol.declarations.push(Arc::clone(node));
+        }
+        // A namespace merged with a value is represented by the value
+        if flags.intersects(SymbolFlags::VALUE)
+            && symbol.value_declaration.as_ref().is_none_or(|value| {
+                value.kind == SyntaxKind::ModuleDeclaration && node.kind != value.kind
+            })
+        {
+            symbol.value_declaration = Some(Arc::clone(node));
+        }
+    }
+
+    fn table(&mut self, table: Table) -> &mut SymbolTable {
+        match table {
+            Table::Locals(node) => self.bindings.locals.entry(node).or_default(),
+            Table::Members(symbol) => &mut self.bindings.symbols[symbol].members,
+            Table::Exports(symbol) => &mut self.bindings.symbols[symbol].exports,
+        }
+    }
+
+    /// Ambient namespaces and declaration files without explicit exports export everything
+    fn set_export_context(&mut self, node: &Arc<Node>) {
+        let ambient = if node.kind == SyntaxKind::SourceFile {
+            node.as_source_file().is_declaration_file
+        } else {
+            node.flags
+                .contains(crate::compiler::ast::NodeFlags::AMBIENT)
+        };
+        let statements = match node.kind {
+            SyntaxKind::SourceFile => Some(&node.as_source_file().statements),
+            _ => node
+                .body()
+                .filter(|body| body.kind == SyntaxKind::ModuleBlock)
+                .map(|body| &body.as_module_block().statements),
+        };
+        let has_export_declarations = statements.is_some_and(|statements| {
+            statements.iter().any(|statement| {
+                matches!(
+                    statement.kind,
+                    SyntaxKind::ExportDeclaration | SyntaxKind::ExportAssignment
+                )
+            })
+        });
+        if ambient && !has_export_declarations {
+            self.export_context.insert(node.node_id());
+        }
+    }
+}
+
+fn container_flags(node: &Node) -> ContainerFlags {
+    match node.kind {
+        SyntaxKind::ClassExpression
+        | SyntaxKind::ClassDeclaration
+        | SyntaxKind::EnumDeclaration
+        | SyntaxKind::ObjectLiteralExpression
+        | SyntaxKind::TypeLiteral
+        | SyntaxKind::JsxAttributes => ContainerFlags::IS_CONTAINER,
+        SyntaxKind::InterfaceDeclaration => {
+            ContainerFlags::IS_CONTAINER | ContainerFlags::IS_INTERFACE
+        }
+        SyntaxKind::ModuleDeclaration
+        | SyntaxKind::TypeAliasDeclaration
+        | SyntaxKind::MappedType
+        | SyntaxKind::IndexSignature => ContainerFlags::IS_CONTAINER | ContainerFlags::HAS_LOCALS,
+        SyntaxKind::SourceFile => {
+            ContainerFlags::IS_CONTAINER
+                | ContainerFlags::IS_CONTROL_FLOW_CONTAINER
+                | ContainerFlags::HAS_LOCALS
+        }
+        SyntaxKind::FunctionExpression | SyntaxKind::ArrowFunction => {
+            ContainerFlags::IS_CONTAINER
+                | ContainerFlags::IS_CONTROL_FLOW_CONTAINER
+                | ContainerFlags::HAS_LOCALS
+                | ContainerFlags::IS_FUNCTION_LIKE
+                | ContainerFlags::IS_FUNCTION_EXPRESSION
+        }
+        kind if is_function_like_kind(kind) || kind == SyntaxKind::ClassStaticBlockDeclaration => {
+            ContainerFlags::IS_CONTAINER
+                | ContainerFlags::IS_CONTROL_FLOW_CONTAINER
+                | ContainerFlags::HAS_LOCALS
+                | ContainerFlags::IS_FUNCTION_LIKE
+        }
+        SyntaxKind::ModuleBlock => ContainerFlags::IS_CONTROL_FLOW_CONTAINER,
+        SyntaxKind::PropertyDeclaration if node.initializer().is_some() => {
+            ContainerFlags::IS_CONTROL_FLOW_CONTAINER
+        }
+        SyntaxKind::CatchClause
+        | SyntaxKind::ForStatement
+        | SyntaxKind::ForInStatement
+        | SyntaxKind::ForOfStatement
+        | SyntaxKind::CaseBlock => {
+            ContainerFlags::IS_BLOCK_SCOPED_CONTAINER | ContainerFlags::HAS_LOCALS
+        }
+        SyntaxKind::Block => {
+            // A function body shares the function's scope
+            let is_body = node.parent().is_some_and(|parent| {
+                is_function_like_kind(parent.kind)
+                    || parent.kind == SyntaxKind::ClassStaticBlockDeclaration
+            });
+            if is_body {
+                ContainerFlags::NONE
+            } else {
+                ContainerFlags::IS_BLOCK_SCOPED_CONTAINER | ContainerFlags::HAS_LOCALS
+            }
+        }
+        _ => ContainerFlags::NONE,
+    }
+}
+
+/// Gets the name `node` is declared under, or None if it has no static name
+pub fn declaration_name(node: &Node, file: &Node) -> Option<String> {
+    if let Some(name) = node.name() {
+        // Ambient modules are named by their quoted module names, which no identifier can be
+        if node.kind == SyntaxKind::ModuleDeclaration && name.kind == SyntaxKind::StringLiteral {
+            return Some(format!("\"{}\"", name.text()));
+        }
+        return get_property_name_text(name).or_else(|| get_well_known_symbol_property_name(name));
+    }
+    let name = match node.kind {
+        SyntaxKind::Constructor => internal_symbol_names::CONSTRUCTOR,
+        SyntaxKind::FunctionType | SyntaxKind::CallSignature => internal_symbol_names::CALL,
+        SyntaxKind::ConstructorType | SyntaxKind::ConstructSignature => internal_symbol_names::NEW,
+        SyntaxKind::IndexSignature => internal_symbol_names::INDEX,
+        SyntaxKind::ExportDeclaration => internal_symbol_names::EXPORT_STAR,
+        SyntaxKind::ExportAssignment => {
+            if node.as_export_assignment().is_export_equals {
+                internal_symbol_names::EXPORT_EQUALS
+            } else {
+                internal_symbol_names::DEFAULT
+            }
+        }
+        SyntaxKind::SourceFile => {
+            return Some(format!(
+                "\"{}\"",
+                remove_file_extension(&file.as_source_file().file_name)
+            ));
+        }
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Gets the name of a declaration as written, for use in diagnostics
+fn display_name(node: &Node, file: &Node) -> String {
+    match node.name() {
+        Some(name) if name.kind == SyntaxKind::ComputedPropertyName => {
+            crate::compiler::ast::utilities::get_text_of_node(file, name).to_string()
+        }
+        Some(name) => name.text().to_string(),
+        None => declaration_name(node, file).unwrap_or_default(),
+    }
+}
+
+pub(crate) fn remove_file_extension(file_name: &str) -> &str {
+    const EXTENSIONS: [&str; 10] = [
+        ".d.ts", ".d.mts", ".d.cts", ".ts", ".tsx", ".mts", ".cts", ".js", ".jsx", ".json",
+    ];
+    EXTENSIONS
+        .iter()
+        .find_map(|extension| file_name.strip_suffix(extension))
+        .unwrap_or(file_name)
+}
+
+fn optional_flag(node: &Node) -> SymbolFlags {
+    let question = match node.kind {
+        SyntaxKind::PropertyDeclaration => node.as_property_declaration().postfix_token.as_ref(),
+        SyntaxKind::PropertySignature => node.as_property_signature().postfix_token.as_ref(),
+        SyntaxKind::MethodDeclaration => node.as_method_declaration().postfix_token.as_ref(),
+        SyntaxKind::MethodSignature => node.as_method_signature().postfix_token.as_ref(),
+        SyntaxKind::Parameter => node.as_parameter().question_token.as_ref(),
+        _ => None,
+    };
+    if question.is_some_and(|token| token.kind == SyntaxKind::QuestionToken) {
+        SymbolFlags::OPTIONAL
+    } else {
+        SymbolFlags::NONE
+    }
+}
+
+/// Determines if a declaration has a computed name whose value is only known at runtime
+pub fn has_dynamic_name(node: &Node) -> bool {
+    node.name().is_some_and(|name| {
+        name.kind == SyntaxKind::ComputedPropertyName
+            && get_property_name_text(name).is_none()
+            && get_well_known_symbol_property_name(name).is_none()
+    })
+}
+
+fn clause_statements(clause: &Node) -> &[Arc<Node>] {
+    match clause.kind {
+        SyntaxKind::CaseClause => &clause.as_case_clause().statements.nodes,
+        _ => &clause.as_default_clause().statements.nodes,
+    }
+}
+
+/// Determines if `node` is a `&&`, `||`, or `??` expression, looking through parentheses and
+/// `!`
+fn is_logical_expression(node: &Arc<Node>) -> bool {
+    let mut node = Arc::clone(node);
+    loop {
+        node = match node.kind {
+            SyntaxKind::ParenthesizedExpression => {
+                Arc::clone(&node.as_parenthesized_expression().expression)
+            }
+            SyntaxKind::PrefixUnaryExpression
+                if node.as_prefix_unary_expression().operator == SyntaxKind::ExclamationToken =>
+            {
+                Arc::clone(&node.as_prefix_unary_expression().operand)
+            }
+            SyntaxKind::BinaryExpression => {
+                return matches!(
+                    node.as_binary_expression().operator_token.kind,
+                    SyntaxKind::AmpersandAmpersandToken
+                        | SyntaxKind::BarBarToken
+                        | SyntaxKind::QuestionQuestionToken
+                );
+            }
+            _ => return false,
+        };
+    }
+}
+
+/// Determines if a logical expression is not part of a condition whose targets are already
+/// known, so it needs a label of its own to join its branches
+fn is_top_level_logical_expression(node: &Arc<Node>) -> bool {
+    let mut node = Arc::clone(node);
+    while let Some(parent) = node.parent()
+        && (parent.kind == SyntaxKind::ParenthesizedExpression
+            || (parent.kind == SyntaxKind::PrefixUnaryExpression
+                && parent.as_prefix_unary_expression().operator == SyntaxKind::ExclamationToken))
+    {
+        node = parent;
+    }
+    let Some(parent) = node.parent() else {
+        return true;
+    };
+    let is_condition = match parent.kind {
+        SyntaxKind::IfStatement => Arc::ptr_eq(&parent.as_if_statement().expression, &node),
+        SyntaxKind::WhileStatement => Arc::ptr_eq(&parent.as_while_statement().expression, &node),
+        SyntaxKind::DoStatement => Arc::ptr_eq(&parent.as_do_statement().expression, &node),
+        SyntaxKind::ForStatement => parent
+            .as_for_statement()
+            .condition
+            .as_ref()
+            .is_some_and(|condition| Arc::ptr_eq(condition, &node)),
+        SyntaxKind::ConditionalExpression => {
+            Arc::ptr_eq(&parent.as_conditional_expression().condition, &node)
+        }
+        _ => false,
+    };
+    !is_condition && !is_logical_expression(&parent)
+}
+
+/// Determines if `node` is a reference whose type control flow can narrow: a name, `this`,
+/// `super`, or a property of one
+fn is_narrowable_reference(node: &Node) -> bool {
+    match node.kind {
+        SyntaxKind::Identifier | SyntaxKind::ThisKeyword | SyntaxKind::SuperKeyword => true,
+        SyntaxKind::PropertyAccessExpression => {
+            is_narrowable_reference(&node.as_property_access_expression().expression)
+        }
+        SyntaxKind::ElementAccessExpression => {
+            let access = node.as_element_access_expression();
+            matches!(
+                access.argument_expression.kind,
+                SyntaxKind::StringLiteral | SyntaxKind::NumericLiteral
+            ) && is_narrowable_reference(&access.expression)
+        }
+        SyntaxKind::ParenthesizedExpression => {
+            is_narrowable_reference(&node.as_parenthesized_expression().expression)
+        }
+        _ => false,
+    }
+}
+
+/// Determines if `node` is a reference that may be an array whose type evolves, allowing for
+/// parentheses and assignments around it
+fn is_narrowable_operand(node: &Node) -> bool {
+    match node.kind {
+        SyntaxKind::ParenthesizedExpression => {
+            is_narrowable_operand(&node.as_parenthesized_expression().expression)
+        }
+        SyntaxKind::BinaryExpression => {
+            let binary = node.as_binary_expression();
+            match binary.operator_token.kind {
+                SyntaxKind::EqualsToken => is_narrowable_operand(&binary.left),
+                SyntaxKind::CommaToken => is_narrowable_operand(&binary.right),
+                _ => false,
+            }
+        }
+        _ => is_narrowable_reference(node),
+    }
+}
+
+fn is_catch_clause_variable(node: &Arc<Node>) -> bool {
+    root_declaration(node)
+        .parent()
+        .is_some_and(|parent| parent.kind == SyntaxKind::CatchClause)
+}
+
+/// Determines if `node` is an identifier or a chain of property accesses on one
+pub fn is_entity_name_expression(node: &Node) -> bool {
+    match node.kind {
+        SyntaxKind::Identifier => true,
+        SyntaxKind::PropertyAccessExpression => {
+            let access = node.as_property_access_expression();
+            access.name.kind == SyntaxKind::Identifier
+                && is_entity_name_expression(&access.expression)
+        }
+        _ => false,
+    }
+}
+
+/// Finds the conditional type whose extends clause contains the `infer` type
+pub fn infer_type_container(infer_type: &Arc<Node>) -> Option<Arc<Node>> {
+    let mut child = Arc::clone(infer_type);
+    while let Some(parent) = child.parent() {
+        if parent.kind == SyntaxKind::ConditionalType
+            && Arc::ptr_eq(&parent.as_conditional_type_node().extends_type, &child)
+        {
+            return Some(parent);
+        }
+        child = parent;
+    }
+    None
+}
+
+/// Determines if a namespace declaration declares values that exist at runtime
+pub fn is_instantiated_module(node: &Node) -> bool {
+    module_instance_state(node) == ModuleInstanceState::Instantiated
+}
+
+fn module_instance_state(node: &Node) -> ModuleInstanceState {
+    match node.kind {
+        SyntaxKind::InterfaceDeclaration | SyntaxKind::TypeAliasDeclaration => {
+            ModuleInstanceState::NonInstantiated
+        }
+        SyntaxKind::EnumDeclaration if node.has_modifier(SyntaxKind::ConstKeyword) => {
+            ModuleInstanceState::ConstEnumOnly
+        }
+        SyntaxKind::ImportDeclaration | SyntaxKind::ImportEqualsDeclaration
+            if !node.has_modifier(SyntaxKind::ExportKeyword) =>
+        {
+            ModuleInstanceState::NonInstantiated
+        }
+        SyntaxKind::ExportDeclaration if node.as_export_declaration().is_type_only => {
+            ModuleInstanceState::NonInstantiated
+        }
+        SyntaxKind::ModuleBlock => {
+            let mut state = ModuleInstanceState::NonInstantiated;
+            for statement in &node.as_module_block().statements {
+                match module_instance_state(statement) {
+                    ModuleInstanceState::NonInstantiated => {}
+                    ModuleInstanceState::ConstEnumOnly => {
+                        state = ModuleInstanceState::ConstEnumOnly
+                    }
+                    ModuleInstanceState::Instantiated => return ModuleInstanceState::Instantiated,
+                }
+            }
+            state
+        }
+        SyntaxKind::ModuleDeclaration => node
+            .body()
+            .map_or(ModuleInstanceState::Instantiated, |body| {
+                module_instance_state(body)
+            }),
+        _ => ModuleInstanceState::Instantiated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::parser::parse_source_file;
+    use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+
+    fn bind(text: &str) -> (Arc<Node>, Bindings) {
+        let file = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let mut bindings = Bindings::new();
+        bindings.bind_source_file(&file);
+        (file, bindings)
+    }
+
+    fn local(bindings: &Bindings, scope: &Node, name: &str) -> SymbolId {
+        bindings.locals(scope).unwrap()[name]
+    }
+
+    #[test]
+    fn merges_separately_bound_files_like_files_bound_in_order() {
+        let files: Vec<Arc<Node>> = [
+            "export function f(a: number) { if (a) { return a; } }",
+            "import { f } from './a';\nlet x = f(1);\nwhile (x) { x = 0; }",
+        ]
+        .iter()
+        .map(|text| parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown))
+        .collect();
+        let mut in_order = Bindings::new();
+        let mut merged = Bindings::new();
+        for file in &files {
+            in_order.bind_source_file(file);
+            let mut separate = Bindings::new();
+            separate.bind_source_file(file);
+            merged.merge(separate);
+        }
+        assert_eq!(merged.symbols.len(), in_order.symbols.len());
+        assert_eq!(merged.flow_nodes.len(), in_order.flow_nodes.len());
+        for file in &files {
+            assert_eq!(merged.locals(file), in_order.locals(file));
+        }
+        assert_eq!(merged.node_symbols, in_order.node_symbols);
+        assert_eq!(merged.node_flows, in_order.node_flows);
+        let f = &files[0].as_source_file().statements.nodes[0];
+        let symbol = merged.symbol_of_node(f).unwrap();
+        assert_eq!(merged.symbols[symbol].id(), symbol);
+        assert_eq!(merged.symbols[symbol].name, "f");
+    }
+
+    #[test]
+    fn test_scopes() {
+        let (file, bindings) = bind(
+            "var a = 1;\nfunction f(p: number) { let b = p; { const c = b; var d; } }\nclass C<T> { x: T; static y = 1; m() {} }",
+        );
+        let a = local(&bindings, &file, "a");
+        assert!(
+            bindings.symbols[a]
+                .flags
+                .contains(SymbolFlags::FUNCTION_SCOPED_VARIABLE)
+        );
+
+        let f = &file.as_source_file().statements.nodes[1];
+        assert_eq!(
+            bindings.symbol_of_node(f),
+            Some(local(&bindings, &file, "f"))
+        );
+        let function_locals = bindings.locals(f).unwrap();
+        assert!(function_locals.contains_key("p"));
+        assert!(function_locals.contains_key("b"));
+        // `var` is hoisted to the function, `const` stays in its block
+        assert!(function_locals.contains_key("d"));
+        assert!(!function_locals.contains_key("c"));
+
+        let class = &bindings.symbols[local(&bindings, &file, "C")];
+        assert!(class.members.contains_key("x"));
+        assert!(class.members.contains_key("m"));
+        assert!(class.members.contains_key("T"));
+        assert!(class.exports.contains_key("y"));
+        assert!(class.exports.contains_key("prototype"));
+        assert!(bindings.diagnostics(&file).is_empty());
+    }
+
+    #[test]
+    fn test_module_exports() {
+        let (file, bindings) =
+            bind("export function f() {}\nexport const x = 1;\nconst y = 2;\nexport { y as z };");
+        let module = bindings.symbol_of_node(&file).unwrap();
+        assert_eq!(bindings.symbols[module].name, "\"test\"");
+        let exports = &bindings.symbols[module].exports;
+        for name in ["f", "x", "z"] {
+            assert!(exports.contains_key(name), "missing export {name}");
+        }
+        assert!(!exports.contains_key("y"));
+
+        // Exported functions can be referenced by their local name
+        let f = local(&bindings, &file, "f");
+        assert_eq!(bindings.symbols[f].export_symbol, Some(exports["f"]));
+        let x = local(&bindings, &file, "x");
+        assert_eq!(bindings.symbols[x].flags, SymbolFlags::EXPORT_VALUE);
+        assert_eq!(
+            bindings.symbol_of_node(&bindings.symbols[x].declarations[0]),
+            Some(exports["x"])
+        );
+    }
+
+    #[test]
+    fn test_merging_and_duplicates() {
+        let (file, bindings) = bind(
+            "interface I { a: string }\ninterface I { b: string }\nnamespace N {}\nfunction N() {}\nlet x;\nlet x;\nclass D {}\nvar D;",
+        );
+        let interface = &bindings.symbols[local(&bindings, &file, "I")];
+        assert_eq!(interface.declarations.len(), 2);
+        assert_eq!(interface.members.len(), 2);
+        let namespace = &bindings.symbols[local(&bindings, &file, "N")];
+        assert!(
+            namespace
+                .flags
+                .contains(SymbolFlags::FUNCTION | SymbolFlags::NAMESPACE_MODULE)
+        );
+
+        let messages: Vec<_> = bindings
+            .diagnostics(&file)
+            .iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.message_text.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (2451, "Cannot redeclare block-scoped variable 'x'."),
+                (2451, "Cannot redeclare block-scoped variable 'x'."),
+                (2300, "Duplicate identifier 'D'."),
+                (2300, "Duplicate identifier 'D'."),
+            ]
+        );
+    }
+}
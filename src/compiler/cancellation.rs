@@ -0,0 +1,73 @@
+//! Cancelling compilations that are no longer wanted
+//!
+//! A compilation started for files that have changed again since, in watch mode or an editor,
+//! does work whose results will be thrown away. A `CancellationToken` is shared between the
+//! compilation and whoever started it, who cancels it when new edits arrive. Parsing,
+//! binding, checking, and emitting look at the token between files, and checking between
+//! statements too, and give up with `OperationCanceled` once it is cancelled.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A flag, shared by its clones, that asks the operations given it to stop
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    requested: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the operations given this token, or any of its clones, to stop
+    pub fn cancel(&self) {
+        self.requested.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancellation_requested(&self) -> bool {
+        self.requested.load(Ordering::Relaxed)
+    }
+
+    /// Fails with `OperationCanceled` if the token has been cancelled
+    pub fn throw_if_cancellation_requested(&self) -> Result<(), OperationCanceled> {
+        if self.is_cancellation_requested() {
+            Err(OperationCanceled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The error of an operation that stopped because its token was cancelled, leaving its
+/// results incomplete
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationCanceled;
+
+impl fmt::Display for OperationCanceled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the operation was canceled")
+    }
+}
+
+impl std::error::Error for OperationCanceled {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancels_every_clone_of_a_token() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert_eq!(clone.throw_if_cancellation_requested(), Ok(()));
+        token.cancel();
+        assert!(clone.is_cancellation_requested());
+        assert_eq!(
+            clone.throw_if_cancellation_requested(),
+            Err(OperationCanceled)
+        );
+    }
+}
@@ -0,0 +1,3662 @@
+//! Type checking
+//!
+//! The [`Checker`] binds the source files of a program, merges the declarations of script files
+//! into a global scope, and answers questions about the types of symbols and expressions. Types
+//! are created on demand and cached, so checking a file only computes the types the file
+//! actually needs. Checking a file reports the semantic errors in it.
+//!
+//! Side tables keyed by [`SymbolId`] and [`NodeId`] remember what has been computed about
+//! symbols and nodes, so repeated questions are answered from the cache.
+
+mod accessors;
+mod ambient;
+mod assertions;
+mod circularity;
+mod classes;
+mod conditional;
+mod contextual;
+mod declarations;
+mod definite_assignment;
+mod destructuring;
+mod discriminants;
+mod enums;
+mod evolving_arrays;
+mod expressions;
+mod flow;
+mod index_signatures;
+mod inference;
+mod instantiate;
+mod iterators;
+mod jsx;
+mod mapped;
+mod members;
+mod modules;
+mod names;
+mod printer;
+mod promises;
+mod relations;
+mod returns;
+mod spread;
+mod suggestions;
+mod type_nodes;
+mod types;
+mod unique_symbols;
+mod unused;
+mod visibility;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+pub use printer::TypeFormatFlags;
+pub use relations::Relation;
+pub use suggestions::get_spelling_suggestion;
+pub use types::*;
+pub use visibility::{SymbolAccessibility, SymbolAccessibilityResult};
+
+use crate::compiler::ast::utilities::{
+    get_error_range, get_source_file_of_node, is_external_module,
+};
+use crate::compiler::ast::{Node, NodeId, Symbol, SymbolFlags, SymbolId, SymbolTable, SyntaxKind};
+use crate::compiler::binder::{Bindings, FlowId};
+use crate::compiler::cancellation::CancellationToken;
+use crate::compiler::diagnostics::{Diagnostic, DiagnosticMessageChain, Message};
+use crate::compiler::scanner::ScriptTarget;
+use crate::compiler::text::TextRange;
+use crate::compiler::transformers::ModuleKind;
+
+use iterators::IterationUse;
+use promises::{is_async_function, is_in_async_function};
+
+/// What the checker has computed about a symbol
+#[derive(Debug, Default, Clone)]
+struct SymbolLinks {
+    /// The type of a value symbol
+    type_: Option<TypeId>,
+    /// The type a type symbol declares
+    declared_type: Option<TypeId>,
+    /// The symbol an instantiated symbol was instantiated from
+    target: Option<SymbolId>,
+    /// The mapper an instantiated symbol was instantiated with
+    mapper: Option<Arc<TypeMapper>>,
+    /// The symbol an alias refers to
+    alias_target: Option<SymbolId>,
+    /// The local type parameters of a type alias
+    type_parameters: Option<Vec<TypeId>>,
+    /// The instantiations of a generic type alias by type arguments
+    instantiations: HashMap<Vec<TypeId>, TypeId>,
+    /// The literal type of the key a mapped property was created for
+    name_type: Option<TypeId>,
+    /// Whether the symbol is read anywhere outside its own declaration
+    referenced: bool,
+    /// The exports of a module, with those it re-exports from other modules
+    resolved_exports: Option<SymbolTable>,
+    /// The unique symbol type of a declaration that holds a unique symbol
+    unique_es_symbol_type: Option<TypeId>,
+    /// The members of a class, interface, or type literal named by late-bound names
+    late_bound_members: Option<SymbolTable>,
+    /// The static members of a class named by late-bound names
+    late_bound_exports: Option<SymbolTable>,
+}
+
+/// The compiler options that change how the checker checks a program, all off by default
+#[derive(Debug, Default, Clone)]
+pub struct CheckerOptions {
+    /// Makes `null` and `undefined` distinct types that are not assignable to other types,
+    /// and checks that variables are assigned before they are used
+    pub strict_null_checks: bool,
+    /// Requires instance properties to be initialized in the constructor, under
+    /// `strictNullChecks`
+    pub strict_property_initialization: bool,
+    /// Requires members that override a base class member to have an `override` modifier
+    pub no_implicit_override: bool,
+    /// Reports declarations and element accesses whose types are implicitly `any`
+    pub no_implicit_any: bool,
+    /// Reports `this` in functions that do not declare the type of `this`
+    pub no_implicit_this: bool,
+    /// Compares the parameters of function types contravariantly, rather than bivariantly,
+    /// except for the parameters of methods and constructors
+    pub strict_function_types: bool,
+    /// Types `bind`, `call`, and `apply` by the global `CallableFunction` and
+    /// `NewableFunction` types instead of `Function`
+    pub strict_bind_call_apply: bool,
+    /// Gives catch clause variables the type `unknown` instead of `any`
+    pub use_unknown_in_catch_variables: bool,
+    /// Distinguishes an optional property that is missing from one that is `undefined`, so
+    /// `undefined` is only assignable to optional properties that include it in their types
+    pub exact_optional_property_types: bool,
+    /// Reports local declarations, imports, and private class members that are never read
+    pub no_unused_locals: bool,
+    /// Reports parameters that are never read, unless their names start with `_`
+    pub no_unused_parameters: bool,
+    /// Allows default imports of modules that assign their exports as a whole, like JSON files
+    pub allow_synthetic_default_imports: bool,
+    /// Adds `undefined` to the types read through index signatures
+    pub no_unchecked_indexed_access: bool,
+    /// The language version the program is compiled to, which decides whether top-level
+    /// `await` is allowed and what can be iterated, or None to allow every feature
+    pub target: Option<ScriptTarget>,
+    /// The module system the program is compiled to, which decides whether top-level `await`
+    /// is allowed, or None to allow every feature
+    pub module: Option<ModuleKind>,
+    /// Iterates with the iterator protocol when compiling for ES5, instead of only arrays
+    pub downlevel_iteration: bool,
+    /// Requires properties that only index signatures declare to be accessed with brackets
+    pub no_property_access_from_index_signature: bool,
+}
+
+/// What the checker has computed about a node
+#[derive(Debug, Default, Clone)]
+struct NodeLinks {
+    /// The type of an expression or type node
+    resolved_type: Option<TypeId>,
+    /// The symbol an identifier or property access refers to
+    resolved_symbol: Option<SymbolId>,
+    /// The signature a call or new expression resolved to
+    resolved_signature: Option<SignatureId>,
+    /// Whether a call or new expression is being resolved, while its arguments only have
+    /// the contextual types the candidate signatures give them
+    resolving_signature: bool,
+    /// The signature a function-like declaration declares
+    declared_signature: Option<SignatureId>,
+    /// The type of the parameter a call argument is passed to, from the signature the call
+    /// is being resolved to
+    contextual_type: Option<TypeId>,
+    /// The type control flow gives a reference, with the declared and initial types it was
+    /// narrowed from
+    flow_type: Option<(TypeId, TypeId, TypeId)>,
+    /// The value of an enum member, if it is not computed at runtime
+    enum_member_value: Option<LiteralValue>,
+    /// Whether the values of the members of an enum declaration have been computed
+    enum_values_computed: bool,
+    /// Whether a statement of an ambient block, or an ambient function body, was reported
+    reported_statement_in_ambient_context: bool,
+}
+
+/// A computation that can depend on itself, guarded against infinite recursion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResolutionTarget {
+    Type(SymbolId),
+    DeclaredType(SymbolId),
+    ReturnType(SignatureId),
+    BaseTypes(TypeId),
+    AliasTarget(SymbolId),
+}
+
+/// Answers questions about the types in a program and reports the semantic errors in it
+#[derive(Clone)]
+pub struct Checker {
+    files: Vec<Arc<Node>>,
+    bindings: Bindings,
+    globals: SymbolTable,
+    /// The global symbol each merged script-level symbol was merged into
+    merged_symbols: HashMap<SymbolId, SymbolId>,
+    /// Stands in for symbols that could not be resolved
+    unknown_symbol: SymbolId,
+    options: CheckerOptions,
+    /// The file each import resolved to, by the name of the importing file and the module name
+    resolved_modules: HashMap<(String, String), String>,
+
+    types: Vec<Type>,
+    signatures: Vec<Signature>,
+
+    any_type: TypeId,
+    /// The `any` produced by errors, which suppresses further errors
+    error_type: TypeId,
+    unknown_type: TypeId,
+    undefined_type: TypeId,
+    /// The `undefined` of an optional property that is missing, which is distinct from
+    /// `undefined` under `exactOptionalPropertyTypes`
+    missing_type: TypeId,
+    null_type: TypeId,
+    string_type: TypeId,
+    number_type: TypeId,
+    bigint_type: TypeId,
+    false_type: TypeId,
+    true_type: TypeId,
+    boolean_type: TypeId,
+    es_symbol_type: TypeId,
+    void_type: TypeId,
+    never_type: TypeId,
+    non_primitive_type: TypeId,
+    empty_object_type: TypeId,
+
+    literal_types: HashMap<LiteralValue, TypeId>,
+    union_types: HashMap<Vec<TypeId>, TypeId>,
+    intersection_types: HashMap<Vec<TypeId>, TypeId>,
+    type_references: HashMap<(TypeId, Vec<TypeId>), TypeId>,
+    /// Type references whose type arguments are resolved on first use
+    deferred_type_references: HashMap<TypeId, DeferredTypeReference>,
+    instantiations: HashMap<(TypeId, Vec<TypeId>), TypeId>,
+    index_types: HashMap<TypeId, TypeId>,
+    indexed_access_types: HashMap<(TypeId, TypeId), TypeId>,
+    /// Instantiations of conditional types, by their node and the types that replace the type
+    /// parameters in scope
+    conditional_instantiations: HashMap<(NodeId, Vec<TypeId>), TypeId>,
+    /// Copies of constrained type parameters without their constraints
+    restrictive_type_parameters: HashMap<TypeId, TypeId>,
+    signature_instantiations: HashMap<(SignatureId, Vec<TypeId>), SignatureId>,
+    tuple_targets: HashMap<(Vec<ElementFlags>, bool), TypeId>,
+    union_properties: HashMap<(TypeId, String), Option<SymbolId>>,
+    resolved_members: HashMap<TypeId, Arc<ResolvedMembers>>,
+    resolved_base_types: HashMap<TypeId, Vec<TypeId>>,
+    /// The results of relating types, keyed by relation, source, and target
+    relation_cache: HashMap<(Relation, TypeId, TypeId), bool>,
+    /// The variances of generic types' type parameters, None while being measured
+    variances: HashMap<TypeId, Option<Vec<relations::Variance>>>,
+    /// The marker types that stand in for type parameters while measuring variance
+    variance_markers: Option<[TypeId; 3]>,
+    /// The regular versions of fresh literal and object literal types
+    regular_types: HashMap<TypeId, TypeId>,
+    /// The fresh versions of literal types, which literal expressions have
+    fresh_literal_types: HashMap<TypeId, TypeId>,
+    /// Global types by name, with the empty stand-ins used when a program does not declare them
+    global_types: HashMap<&'static str, TypeId>,
+    /// Stand-ins for undeclared global types, whose missing members resolve to `any`
+    fallback_types: HashSet<TypeId>,
+    instantiation_depth: u32,
+    /// Whether an instantiation went deeper than the limit since the outermost one started
+    instantiation_depth_exceeded: bool,
+    /// How many types have been instantiated, for `--diagnostics`
+    instantiation_count: usize,
+    /// How many types have been instantiated while checking the current statement or
+    /// expression, which is limited so that pathological types fail instead of hanging
+    current_instantiation_count: usize,
+    /// The expression or type node being checked, where instantiation depth errors are reported
+    current_node: Option<Arc<Node>>,
+
+    symbol_links: HashMap<SymbolId, SymbolLinks>,
+    node_links: HashMap<NodeId, NodeLinks>,
+    resolution_stack: Vec<(ResolutionTarget, bool)>,
+
+    diagnostics: HashMap<NodeId, Vec<Diagnostic>>,
+    checked_files: HashSet<NodeId>,
+    /// Top-level statements that have been checked, which checking their files skips when
+    /// they were checked on their own or before checking was cancelled
+    checked_statements: HashSet<NodeId>,
+    /// Stops checking between statements, leaving the rest of a file to be checked later
+    cancellation_token: CancellationToken,
+    /// Function and class expressions whose bodies are checked after the enclosing file
+    deferred_nodes: Vec<Arc<Node>>,
+    /// Loops whose types for references are being computed, innermost last
+    flow_loops: Vec<flow::FlowLoop>,
+    /// Types of references at flow labels, by label and reference
+    flow_types: HashMap<(FlowId, String), TypeId>,
+    /// Types of references at flow labels computed while `flow_loops` is not empty, which are
+    /// forgotten whenever the type of a pending loop changes
+    flow_loop_types: HashMap<(FlowId, String), TypeId>,
+    /// The `any[]` declared type of variables initialized with empty array literals, whose
+    /// types evolve with the elements added to them
+    auto_array_type: Option<TypeId>,
+    /// The evolving array types, by their element types
+    evolving_array_types: HashMap<TypeId, TypeId>,
+    /// The constituents of large unions by their key properties, None for unions without one
+    key_property_maps: HashMap<TypeId, Option<Arc<discriminants::KeyPropertyMap>>>,
+}
+
+impl Checker {
+    /// Creates a checker for the given source files, binding any that have not been bound
+    pub fn new(files: &[Arc<Node>]) -> Self {
+        Self::with_bindings(files, Bindings::new())
+    }
+
+    /// Creates a checker for the given source files with the bindings of some or all of them,
+    /// binding the rest
+    pub fn with_bindings(files: &[Arc<Node>], mut bindings: Bindings) -> Self {
+        for file in files {
+            bindings.bind_source_file(file);
+        }
+        let unknown_symbol = bindings
+            .symbols
+            .alloc("unknown", SymbolFlags::PROPERTY | SymbolFlags::TRANSIENT);
+        let placeholder = TypeId(0);
+        let mut checker = Checker {
+            files: files.to_vec(),
+            bindings,
+            globals: SymbolTable::new(),
+            merged_symbols: HashMap::new(),
+            unknown_symbol,
+            options: CheckerOptions::default(),
+            resolved_modules: HashMap::new(),
+            types: Vec::new(),
+            signatures: Vec::new(),
+            any_type: placeholder,
+            error_type: placeholder,
+            unknown_type: placeholder,
+            undefined_type: placeholder,
+            missing_type: placeholder,
+            null_type: placeholder,
+            string_type: placeholder,
+            number_type: placeholder,
+            bigint_type: placeholder,
+            false_type: placeholder,
+            true_type: placeholder,
+            boolean_type: placeholder,
+            es_symbol_type: placeholder,
+            void_type: placeholder,
+            never_type: placeholder,
+            non_primitive_type: placeholder,
+            empty_object_type: placeholder,
+            literal_types: HashMap::new(),
+            union_types: HashMap::new(),
+            intersection_types: HashMap::new(),
+            type_references: HashMap::new(),
+            deferred_type_references: HashMap::new(),
+            instantiations: HashMap::new(),
+            index_types: HashMap::new(),
+            indexed_access_types: HashMap::new(),
+            conditional_instantiations: HashMap::new(),
+            restrictive_type_parameters: HashMap::new(),
+            signature_instantiations: HashMap::new(),
+            tuple_targets: HashMap::new(),
+            union_properties: HashMap::new(),
+            resolved_members: HashMap::new(),
+            resolved_base_types: HashMap::new(),
+            relation_cache: HashMap::new(),
+            variances: HashMap::new(),
+            variance_markers: None,
+            regular_types: HashMap::new(),
+            fresh_literal_types: HashMap::new(),
+            global_types: HashMap::new(),
+            fallback_types: HashSet::new(),
+            instantiation_depth: 0,
+            instantiation_depth_exceeded: false,
+            instantiation_count: 0,
+            current_instantiation_count: 0,
+            current_node: None,
+            symbol_links: HashMap::new(),
+            node_links: HashMap::new(),
+            resolution_stack: Vec::new(),
+            diagnostics: HashMap::new(),
+            checked_files: HashSet::new(),
+            checked_statements: HashSet::new(),
+            cancellation_token: CancellationToken::new(),
+            deferred_nodes: Vec::new(),
+            flow_loops: Vec::new(),
+            flow_types: HashMap::new(),
+            flow_loop_types: HashMap::new(),
+            auto_array_type: None,
+            evolving_array_types: HashMap::new(),
+            key_property_maps: HashMap::new(),
+        };
+        checker.create_intrinsic_types();
+        checker
+            .symbol_links
+            .entry(unknown_symbol)
+            .or_default()
+            .type_ = Some(checker.error_type);
+        checker.initialize_globals();
+        checker
+    }
+
+    /// Creates the intrinsic types in the order that makes unions of them print the way
+    /// TypeScript prints them
+    fn create_intrinsic_types(&mut self) {
+        self.any_type = self.create_intrinsic_type(TypeFlags::ANY, "any");
+        self.error_type = self.create_intrinsic_type(TypeFlags::ANY, "error");
+        self.unknown_type = self.create_intrinsic_type(TypeFlags::UNKNOWN, "unknown");
+        self.undefined_type = self.create_intrinsic_type(TypeFlags::UNDEFINED, "undefined");
+        self.missing_type = self.create_intrinsic_type(TypeFlags::UNDEFINED, "undefined");
+        self.null_type = self.create_intrinsic_type(TypeFlags::NULL, "null");
+        self.string_type = self.create_intrinsic_type(TypeFlags::STRING, "string");
+        self.number_type = self.create_intrinsic_type(TypeFlags::NUMBER, "number");
+        self.bigint_type = self.create_intrinsic_type(TypeFlags::BIG_INT, "bigint");
+        self.false_type = self.create_intrinsic_type(TypeFlags::BOOLEAN_LITERAL, "false");
+        self.true_type = self.create_intrinsic_type(TypeFlags::BOOLEAN_LITERAL, "true");
+        self.boolean_type = self.get_union_type(&[self.false_type, self.true_type]);
+        let boolean = &mut self.types[self.boolean_type.index()];
+        boolean.flags |= TypeFlags::BOOLEAN;
+        boolean.object_flags |= ObjectFlags::PRIMITIVE_UNION;
+        self.es_symbol_type = self.create_intrinsic_type(TypeFlags::ES_SYMBOL, "symbol");
+        self.void_type = self.create_intrinsic_type(TypeFlags::VOID, "void");
+        self.never_type = self.create_intrinsic_type(TypeFlags::NEVER, "never");
+        self.non_primitive_type = self.create_intrinsic_type(TypeFlags::NON_PRIMITIVE, "object");
+        self.empty_object_type = self.create_resolved_type(None, ResolvedMembers::default());
+    }
+
+    fn create_intrinsic_type(&mut self, flags: TypeFlags, name: &'static str) -> TypeId {
+        self.create_type(flags, ObjectFlags::NONE, None, TypeData::Intrinsic { name })
+    }
+
+    fn create_type(
+        &mut self,
+        flags: TypeFlags,
+        object_flags: ObjectFlags,
+        symbol: Option<SymbolId>,
+        data: TypeData,
+    ) -> TypeId {
+        let id = TypeId(self.types.len() as u32);
+        self.types.push(Type {
+            flags,
+            object_flags,
+            symbol,
+            alias_symbol: None,
+            alias_type_arguments: Vec::new(),
+            data,
+        });
+        id
+    }
+
+    /// Sets the options that change how the program is checked
+    ///
+    /// Set before checking, since the types already created do not change.
+    pub fn set_options(&mut self, options: CheckerOptions) {
+        self.options = options;
+    }
+
+    /// Sets the token that cancels checking, after which files are left partly checked until
+    /// they are checked again
+    pub fn set_cancellation_token(&mut self, cancellation_token: CancellationToken) {
+        self.cancellation_token = cancellation_token;
+    }
+
+    /// Sets the file each import of the checked files resolved to, by the name of the
+    /// importing file and the module name
+    ///
+    /// Set before checking, since the module augmentations of the files are merged into the
+    /// modules they resolve to here.
+    pub fn set_resolved_modules(&mut self, resolved_modules: HashMap<(String, String), String>) {
+        self.resolved_modules = resolved_modules;
+        self.merge_module_augmentations();
+    }
+
+    /// Resolves the global types that checking any program uses, such as `Array` and
+    /// `Function`, with their members
+    ///
+    /// Checkers cloned from this one start with them resolved, which is how the checkers of
+    /// a program share the work of resolving the default library.
+    pub fn resolve_global_types(&mut self) {
+        let mut types = vec![
+            self.get_global_array_type(),
+            self.get_global_readonly_array_type(),
+            self.get_global_function_type(false),
+            self.get_global_function_type(true),
+        ];
+        types.extend(self.get_global_promise_type());
+        for name in [
+            "Object",
+            "Function",
+            "String",
+            "Number",
+            "Boolean",
+            "BigInt",
+            "Symbol",
+            "RegExp",
+            "IArguments",
+        ] {
+            types.push(self.get_global_type(name));
+        }
+        for type_ in types {
+            self.resolve_structured_type_members(type_);
+        }
+    }
+
+    /// Gets a type by id
+    pub fn get_type(&self, id: TypeId) -> &Type {
+        &self.types[id.index()]
+    }
+
+    /// Gets a signature by id
+    pub fn get_signature(&self, id: SignatureId) -> &Signature {
+        &self.signatures[id.index()]
+    }
+
+    /// Gets a symbol by id
+    pub fn symbol(&self, id: SymbolId) -> &Symbol {
+        &self.bindings.symbols[id]
+    }
+
+    fn flags(&self, id: TypeId) -> TypeFlags {
+        self.types[id.index()].flags
+    }
+
+    fn object_flags(&self, id: TypeId) -> ObjectFlags {
+        self.types[id.index()].object_flags
+    }
+
+    /// Gets the `any` type
+    pub fn any_type(&self) -> TypeId {
+        self.any_type
+    }
+
+    /// Gets the `string` type
+    pub fn string_type(&self) -> TypeId {
+        self.string_type
+    }
+
+    /// Gets the `number` type
+    pub fn number_type(&self) -> TypeId {
+        self.number_type
+    }
+
+    /// Gets the `boolean` type
+    pub fn boolean_type(&self) -> TypeId {
+        self.boolean_type
+    }
+
+    /// The number of symbols, both those of the binder and those created while checking
+    pub fn get_symbol_count(&self) -> usize {
+        self.bindings.symbols.len()
+    }
+
+    /// The number of types created, including the intrinsic types every checker starts with
+    pub fn get_type_count(&self) -> usize {
+        self.types.len()
+    }
+
+    /// The number of times a generic type has been instantiated
+    pub fn get_instantiation_count(&self) -> usize {
+        self.instantiation_count
+    }
+
+    /// The number of pairs of types whose relation has been computed and cached
+    pub fn get_relation_cache_size(&self, relation: Relation) -> usize {
+        self.relation_cache
+            .keys()
+            .filter(|(cached, _, _)| *cached == relation)
+            .count()
+    }
+
+    fn symbol_links(&mut self, symbol: SymbolId) -> &mut SymbolLinks {
+        self.symbol_links.entry(symbol).or_default()
+    }
+
+    fn node_links(&mut self, node: &Node) -> &mut NodeLinks {
+        self.node_links.entry(node.node_id()).or_default()
+    }
+
+    /// Gets the symbol of a declaration, following merges into global symbols
+    pub fn get_symbol_of_declaration(&self, node: &Node) -> Option<SymbolId> {
+        self.bindings
+            .symbol_of_node(node)
+            .map(|symbol| self.get_merged_symbol(symbol))
+    }
+
+    /// Reports an error on `node`
+    fn error(&mut self, node: &Arc<Node>, message: &Message, args: &[String]) {
+        let file = get_source_file_of_node(node);
+        let range = get_error_range(&file, node);
+        self.error_at_range(node, range, message, args);
+    }
+
+    /// Reports an error on a range of the file containing `node`
+    fn error_at_range(
+        &mut self,
+        node: &Arc<Node>,
+        range: TextRange,
+        message: &Message,
+        args: &[String],
+    ) {
+        let file = get_source_file_of_node(node);
+        let diagnostic =
+            Diagnostic::new(Some(&file.as_source_file().file_name), range, message, args);
+        self.add_diagnostic(&file, diagnostic);
+    }
+
+    /// Reports an error made of a chain of messages on `node`, with related information
+    fn error_with_chain(
+        &mut self,
+        node: &Arc<Node>,
+        chain: &DiagnosticMessageChain,
+        related_information: Vec<Diagnostic>,
+    ) {
+        let file = get_source_file_of_node(node);
+        let mut range = get_error_range(&file, node);
+        if node.kind == SyntaxKind::ReturnStatement {
+            // Errors on return statements go on the `return` keyword
+            range = TextRange::new(range.pos(), range.pos() + "return".len());
+        }
+        let mut diagnostic =
+            Diagnostic::from_message_chain(Some(&file.as_source_file().file_name), range, chain);
+        for related in related_information {
+            diagnostic = diagnostic.with_related_information(related);
+        }
+        self.add_diagnostic(&file, diagnostic);
+    }
+
+    fn add_diagnostic(&mut self, file: &Node, diagnostic: Diagnostic) {
+        let diagnostics = self.diagnostics.entry(file.node_id()).or_default();
+        if !diagnostics.contains(&diagnostic) {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Creates a diagnostic on the name of a declaration, or on `node` itself if it has
+    /// none, for use as related information
+    fn create_diagnostic_for_node(
+        &self,
+        node: &Arc<Node>,
+        message: &Message,
+        args: &[String],
+    ) -> Diagnostic {
+        let node = node.name().unwrap_or(node);
+        let file = get_source_file_of_node(node);
+        let range = get_error_range(&file, node);
+        Diagnostic::new(Some(&file.as_source_file().file_name), range, message, args)
+    }
+
+    /// Starts a computation that may depend on itself, returning false if it is already in
+    /// progress, in which case every computation since its start is marked circular
+    fn push_type_resolution(&mut self, target: ResolutionTarget) -> bool {
+        if let Some(start) = self
+            .resolution_stack
+            .iter()
+            .position(|&(entry, _)| entry == target)
+        {
+            for entry in &mut self.resolution_stack[start..] {
+                entry.1 = false;
+            }
+            return false;
+        }
+        self.resolution_stack.push((target, true));
+        true
+    }
+
+    /// Finishes the latest computation, returning false if it turned out to depend on itself
+    fn pop_type_resolution(&mut self) -> bool {
+        self.resolution_stack
+            .pop()
+            .expect("unbalanced type resolution")
+            .1
+    }
+
+    /// Gets the semantic diagnostics of a file, including those reported while binding it,
+    /// sorted by position
+    pub fn get_diagnostics(&mut self, file: &Arc<Node>) -> Vec<Diagnostic> {
+        self.check_source_file(file);
+        self.get_diagnostics_of_file(file)
+    }
+
+    /// Gets the diagnostics reported in a file so far, sorted by position
+    fn get_diagnostics_of_file(&self, file: &Arc<Node>) -> Vec<Diagnostic> {
+        let mut diagnostics = self.bindings.diagnostics(file).to_vec();
+        if let Some(checker_diagnostics) = self.diagnostics.get(&file.node_id()) {
+            diagnostics.extend(checker_diagnostics.iter().cloned());
+        }
+        diagnostics.sort_by_key(|diagnostic| (diagnostic.pos(), diagnostic.len(), diagnostic.code));
+        diagnostics
+    }
+
+    /// Checks every source file of the program and returns all of their diagnostics
+    pub fn get_all_diagnostics(&mut self) -> Vec<Diagnostic> {
+        let files = self.files.clone();
+        files
+            .iter()
+            .flat_map(|file| self.get_diagnostics(file))
+            .collect()
+    }
+
+    /// Checks a source file, reporting the semantic errors in it
+    ///
+    /// If checking is cancelled, the rest of the file is checked when it is checked again.
+    pub fn check_source_file(&mut self, file: &Arc<Node>) {
+        if !self.checked_files.insert(file.node_id()) {
+            return;
+        }
+        if file.as_source_file().is_declaration_file {
+            self.check_declaration_file_top_level(file);
+        }
+        for statement in &file.as_source_file().statements {
+            if self.cancellation_token.is_cancellation_requested() {
+                self.checked_files.remove(&file.node_id());
+                return;
+            }
+            if self.checked_statements.insert(statement.node_id()) {
+                self.check_source_element(statement);
+            }
+        }
+        if is_external_module(file) {
+            self.check_external_module_exports(file);
+        }
+        self.check_deferred_nodes();
+        if self.cancellation_token.is_cancellation_requested() {
+            self.checked_files.remove(&file.node_id());
+            return;
+        }
+        if self.options.no_unused_locals || self.options.no_unused_parameters {
+            self.check_unused_identifiers(file);
+        }
+    }
+
+    /// Gets the semantic diagnostics of the top-level statement of a file that contains a
+    /// position, checking only that statement and whatever it depends on, rather than the
+    /// whole file
+    ///
+    /// Checks that take in a whole file, like those of unused declarations, are left to
+    /// `get_diagnostics`.
+    pub fn get_diagnostics_at_position(&mut self, file: &Arc<Node>, pos: usize) -> Vec<Diagnostic> {
+        let Some(statement) = file
+            .as_source_file()
+            .statements
+            .iter()
+            .find(|statement| statement.pos() <= pos && pos <= statement.end())
+            .cloned()
+        else {
+            return Vec::new();
+        };
+        if !self.checked_files.contains(&file.node_id())
+            && self.checked_statements.insert(statement.node_id())
+        {
+            self.check_source_element(&statement);
+            self.check_deferred_nodes();
+        }
+        self.get_diagnostics_of_file(file)
+            .into_iter()
+            .filter(|diagnostic| {
+                statement.pos() <= diagnostic.pos() && diagnostic.pos() < statement.end()
+            })
+            .collect()
+    }
+
+    fn check_deferred_nodes(&mut self) {
+        while !self.cancellation_token.is_cancellation_requested()
+            && let Some(node) = self.deferred_nodes.pop()
+        {
+            match node.kind {
+                SyntaxKind::ClassExpression => self.check_class_like_declaration(&node),
+                _ => self.check_function_like_declaration(&node),
+            }
+        }
+    }
+
+    /// Gets the signature a call, new, or tagged template expression resolves to, resolving
+    /// it if the expression has not been checked
+    pub fn get_resolved_signature(&mut self, node: &Arc<Node>) -> Option<SignatureId> {
+        let links = self.node_links(node);
+        if links.resolved_signature.is_some() || links.resolving_signature {
+            return links.resolved_signature;
+        }
+        self.check_expression(node);
+        self.node_links(node).resolved_signature
+    }
+
+    /// Gets the type of an expression, a type node, or the declaration a name belongs to
+    pub fn get_type_at_location(&mut self, node: &Arc<Node>) -> TypeId {
+        if node.kind.is_type_node() {
+            return self.get_type_from_type_node(node);
+        }
+        if let Some(parent) = node.parent() {
+            if parent.name().is_some_and(|name| Arc::ptr_eq(name, node))
+                && let Some(symbol) = self.get_symbol_of_declaration(&parent)
+            {
+                let flags = self.symbol(symbol).flags;
+                if flags.intersects(SymbolFlags::VALUE) {
+                    return self.get_type_of_symbol(symbol);
+                }
+                return self.get_declared_type_of_symbol(symbol);
+            }
+            if parent.kind == SyntaxKind::TypeReference {
+                return self.get_type_from_type_node(&parent);
+            }
+            if parent.kind == SyntaxKind::PropertyAccessExpression
+                && Arc::ptr_eq(&parent.as_property_access_expression().name, node)
+            {
+                return self.check_expression(&parent);
+            }
+        }
+        if is_expression_node(node) {
+            return self.check_expression(node);
+        }
+        self.error_type
+    }
+
+    /// Gets the symbol an alias refers to, if it resolves within the program
+    pub fn get_aliased_symbol(&mut self, symbol: SymbolId) -> Option<SymbolId> {
+        let target = self.resolve_alias(symbol);
+        (target != self.unknown_symbol).then_some(target)
+    }
+
+    /// Gets the symbol of the value that a shorthand property assignment takes, rather than
+    /// the property it declares
+    pub fn get_shorthand_assignment_value_symbol(&mut self, node: &Arc<Node>) -> Option<SymbolId> {
+        if node.kind != SyntaxKind::ShorthandPropertyAssignment {
+            return None;
+        }
+        let name = node.name()?;
+        self.check_expression(name);
+        self.node_links(name).resolved_symbol
+    }
+
+    /// Gets the symbol a name refers to or declares
+    pub fn get_symbol_at_location(&mut self, node: &Arc<Node>) -> Option<SymbolId> {
+        let parent = node.parent()?;
+        if parent.name().is_some_and(|name| Arc::ptr_eq(name, node)) {
+            if parent.kind == SyntaxKind::PropertyAccessExpression {
+                self.check_expression(&parent);
+                return self.node_links(&parent).resolved_symbol;
+            }
+            return self.get_symbol_of_declaration(&parent);
+        }
+        if parent.kind == SyntaxKind::TypeReference {
+            self.get_type_from_type_node(&parent);
+            return self.node_links(&parent).resolved_symbol;
+        }
+        // The property name of a binding element is a property of the type it destructures
+        if parent.kind == SyntaxKind::BindingElement
+            && let Some(property_name) = &parent.as_binding_element().property_name
+            && Arc::ptr_eq(property_name, node)
+        {
+            if node.kind == SyntaxKind::ComputedPropertyName {
+                return None;
+            }
+            let pattern = parent.parent()?;
+            let type_ = self.get_type_of_binding_pattern_source(&pattern);
+            return self.get_property_of_type(type_, node.text());
+        }
+        // The name of an `implements` clause is a type, though it is written as an expression
+        if parent.kind == SyntaxKind::ExpressionWithTypeArguments
+            && parent.parent().is_some_and(|clause| {
+                clause.kind == SyntaxKind::HeritageClause
+                    && clause.as_heritage_clause().token == SyntaxKind::ImplementsKeyword
+            })
+        {
+            self.get_type_from_type_node(&parent);
+            return self.node_links(&parent).resolved_symbol;
+        }
+        if is_expression_node(node) {
+            self.check_expression(node);
+            return self.node_links(node).resolved_symbol;
+        }
+        None
+    }
+
+    /// Checks a statement, declaration, or other node that is not an expression
+    fn check_source_element(&mut self, node: &Arc<Node>) {
+        self.current_instantiation_count = 0;
+        self.check_accessor_modifier(node);
+        self.check_ambient_context(node);
+        match node.kind {
+            SyntaxKind::VariableDeclaration
+            | SyntaxKind::PropertyDeclaration
+            | SyntaxKind::PropertySignature
+            | SyntaxKind::Parameter
+            | SyntaxKind::BindingElement => self.check_variable_like_declaration(node),
+            SyntaxKind::FunctionDeclaration
+            | SyntaxKind::MethodDeclaration
+            | SyntaxKind::MethodSignature
+            | SyntaxKind::CallSignature
+            | SyntaxKind::ConstructSignature => self.check_function_like_declaration(node),
+            SyntaxKind::GetAccessor | SyntaxKind::SetAccessor => {
+                self.check_accessor_declaration(node)
+            }
+            SyntaxKind::IndexSignature => {
+                self.check_index_signature_parameter(node);
+                self.check_function_like_declaration(node);
+            }
+            SyntaxKind::Constructor => self.check_constructor_declaration(node),
+            SyntaxKind::ClassDeclaration => self.check_class_like_declaration(node),
+            SyntaxKind::InterfaceDeclaration => self.check_interface_declaration(node),
+            SyntaxKind::TypeAliasDeclaration => self.check_type_alias_declaration(node),
+            SyntaxKind::EnumDeclaration => self.check_enum_declaration(node),
+            SyntaxKind::ExpressionStatement
+            | SyntaxKind::ThrowStatement
+            | SyntaxKind::Decorator
+            | SyntaxKind::ComputedPropertyName => {
+                let expression = node.expression().expect("node has an expression");
+                self.check_expression(expression);
+            }
+            SyntaxKind::ReturnStatement => {
+                if let Some(expression) = &node.as_return_statement().expression {
+                    let mut type_ = self.check_expression(expression);
+                    if let Some(return_type) = self.get_annotated_return_type(node) {
+                        // An async function resolves to what the promises it returns resolve to
+                        if is_in_async_function(node) {
+                            type_ = self.get_awaited_type(type_);
+                        }
+                        self.check_type_assignable_to_and_optionally_elaborate(
+                            type_,
+                            return_type,
+                            node,
+                            Some(expression),
+                            None,
+                        );
+                    }
+                }
+            }
+            SyntaxKind::IfStatement => {
+                let statement = node.as_if_statement();
+                self.check_expression(&statement.expression);
+                self.check_source_element(&statement.then_statement);
+                if let Some(else_statement) = &statement.else_statement {
+                    self.check_source_element(else_statement);
+                }
+            }
+            SyntaxKind::DoStatement | SyntaxKind::WhileStatement | SyntaxKind::WithStatement => {
+                self.check_expression(node.expression().expect("statement has an expression"));
+                self.check_children(node);
+            }
+            SyntaxKind::ForStatement => {
+                let statement = node.as_for_statement();
+                if let Some(initializer) = &statement.initializer {
+                    self.check_for_initializer(initializer);
+                }
+                for expression in [&statement.condition, &statement.incrementor]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.check_expression(expression);
+                }
+                self.check_source_element(&statement.statement);
+            }
+            SyntaxKind::ForInStatement | SyntaxKind::ForOfStatement => {
+                let (initializer, expression, statement) = match node.kind {
+                    SyntaxKind::ForInStatement => {
+                        let statement = node.as_for_in_statement();
+                        (
+                            &statement.initializer,
+                            &statement.expression,
+                            &statement.statement,
+                        )
+                    }
+                    _ => {
+                        let statement = node.as_for_of_statement();
+                        (
+                            &statement.initializer,
+                            &statement.expression,
+                            &statement.statement,
+                        )
+                    }
+                };
+                let iterated = self.check_expression(expression);
+                if node.kind == SyntaxKind::ForOfStatement {
+                    if node.as_for_of_statement().await_modifier.is_some() {
+                        self.check_for_await_statement(node, iterated);
+                    } else {
+                        self.get_iterated_type(iterated, IterationUse::ForOf, Some(expression));
+                    }
+                }
+                self.check_for_initializer(initializer);
+                self.check_source_element(statement);
+            }
+            SyntaxKind::SwitchStatement => {
+                let statement = node.as_switch_statement();
+                self.check_expression(&statement.expression);
+                for clause in &statement.case_block.as_case_block().clauses {
+                    if clause.kind == SyntaxKind::CaseClause {
+                        self.check_expression(&clause.as_case_clause().expression);
+                    }
+                    for statement in clause.statements().into_iter().flatten() {
+                        self.check_source_element(statement);
+                    }
+                }
+            }
+            SyntaxKind::ExportDeclaration => self.check_export_declaration(node),
+            SyntaxKind::ImportDeclaration => self.check_import_declaration(node),
+            SyntaxKind::ModuleDeclaration => {
+                self.check_module_declaration(node);
+                self.check_children(node);
+            }
+            SyntaxKind::ImportEqualsDeclaration => self.check_import_equals_declaration(node),
+            SyntaxKind::ExportAssignment => self.check_export_assignment(node),
+            SyntaxKind::NamespaceExportDeclaration => {}
+            kind if kind.is_type_node() => self.check_type_node(node),
+            _ => self.check_children(node),
+        }
+    }
+
+    fn check_children(&mut self, node: &Arc<Node>) {
+        node.for_each_child(&mut |child| {
+            if is_expression_node(child) {
+                self.check_expression(child);
+            } else {
+                self.check_source_element(child);
+            }
+            false
+        });
+    }
+
+    fn check_for_initializer(&mut self, initializer: &Arc<Node>) {
+        if initializer.kind == SyntaxKind::VariableDeclarationList {
+            self.check_children(initializer);
+        } else {
+            self.check_expression(initializer);
+        }
+    }
+
+    /// Checks a type node and the type nodes and expressions nested in it
+    fn check_type_node(&mut self, node: &Arc<Node>) {
+        self.get_type_from_type_node(node);
+        match node.kind {
+            // The expression of a type query is resolved by the type node itself
+            SyntaxKind::TypeQuery => {
+                if let Some(arguments) = &node.as_type_query_node().type_arguments {
+                    for argument in arguments {
+                        self.check_type_node(argument);
+                    }
+                }
+            }
+            SyntaxKind::TypeReference => {
+                if let Some(arguments) = &node.as_type_reference_node().type_arguments {
+                    for argument in arguments {
+                        self.check_type_node(argument);
+                    }
+                }
+            }
+            SyntaxKind::ExpressionWithTypeArguments => {
+                if let Some(arguments) = &node.as_expression_with_type_arguments().type_arguments {
+                    for argument in arguments {
+                        self.check_type_node(argument);
+                    }
+                }
+            }
+            SyntaxKind::LiteralType => {}
+            SyntaxKind::InferType => self.check_infer_type(node),
+            SyntaxKind::TypeLiteral => {
+                for member in &node.as_type_literal_node().members {
+                    self.check_source_element(member);
+                }
+                let type_ = self.get_type_from_type_node(node);
+                self.check_index_constraints(node, type_);
+            }
+            SyntaxKind::TypeOperator => self.check_type_operator(node),
+            SyntaxKind::IndexedAccessType => {
+                let access = node.as_indexed_access_type_node();
+                self.check_type_node(&access.object_type);
+                self.check_type_node(&access.index_type);
+                self.check_indexed_access_type(node);
+            }
+            _ => {
+                node.for_each_child(&mut |child| {
+                    if child.kind.is_type_node() {
+                        self.check_type_node(child);
+                    } else if !matches!(
+                        child.kind,
+                        SyntaxKind::Identifier | SyntaxKind::QualifiedName
+                    ) {
+                        self.check_source_element(child);
+                    }
+                    false
+                });
+            }
+        }
+    }
+
+    fn check_variable_like_declaration(&mut self, node: &Arc<Node>) {
+        if let Some(type_node) = node.type_node() {
+            self.check_type_node(type_node);
+        }
+        self.check_definite_assignment_assertion(node);
+        self.check_computed_property_name_of_member(node);
+        if let Some(name) = node.name() {
+            let elements = match name.kind {
+                SyntaxKind::ObjectBindingPattern => {
+                    Some(&name.as_object_binding_pattern().elements)
+                }
+                SyntaxKind::ArrayBindingPattern => Some(&name.as_array_binding_pattern().elements),
+                _ => None,
+            };
+            for element in elements.into_iter().flatten() {
+                if element.kind == SyntaxKind::BindingElement {
+                    self.check_variable_like_declaration(element);
+                }
+            }
+        }
+        let initializer_type = node
+            .initializer()
+            .map(|initializer| self.check_expression(initializer));
+        if node.kind == SyntaxKind::BindingElement {
+            // Reports the properties and elements the element destructures that do not exist
+            self.get_type_of_binding_element(node);
+            return;
+        }
+        let type_ = match self.get_symbol_of_declaration(node) {
+            Some(symbol) => self.get_type_of_symbol(symbol),
+            // A destructured variable declares only the names in its pattern
+            None if node.kind == SyntaxKind::VariableDeclaration => {
+                self.get_type_for_variable_like_declaration(node)
+            }
+            None => return,
+        };
+        if self.options.no_implicit_any && node.type_node().is_none() {
+            self.report_implicit_any_declaration(node, type_, initializer_type);
+        }
+        // Only annotated declarations can disagree with their initializers
+        if node.type_node().is_some()
+            && let (Some(initializer), Some(initializer_type)) =
+                (node.initializer(), initializer_type)
+        {
+            let error_node = node.name().unwrap_or(node);
+            self.check_type_assignable_to_and_optionally_elaborate(
+                initializer_type,
+                type_,
+                error_node,
+                Some(initializer),
+                None,
+            );
+        }
+    }
+
+    fn check_function_like_declaration(&mut self, node: &Arc<Node>) {
+        for type_parameter in node.type_parameters().into_iter().flatten() {
+            self.check_type_parameter(type_parameter);
+        }
+        for parameter in node.parameters().into_iter().flatten() {
+            self.check_variable_like_declaration(parameter);
+        }
+        if let Some(type_node) = node.type_node() {
+            self.check_type_node(type_node);
+            if is_async_function(node) {
+                self.check_async_function_return_type(node);
+            }
+        } else if self.options.no_implicit_any && node.body().is_none() {
+            self.report_implicit_any_return_type(node);
+        }
+        self.check_computed_property_name_of_member(node);
+        if let Some(body) = node.body() {
+            if body.kind == SyntaxKind::Block {
+                self.check_source_element(body);
+            } else {
+                let mut type_ = self.check_expression(body);
+                if node.type_node().is_some()
+                    && let Some(return_type) = self.get_contextual_type(body)
+                {
+                    if is_async_function(node) {
+                        type_ = self.get_awaited_type(type_);
+                    }
+                    self.check_type_assignable_to_and_optionally_elaborate(
+                        type_,
+                        return_type,
+                        body,
+                        Some(body),
+                        None,
+                    );
+                }
+            }
+            // Infers the return type, which checks the returned expressions against each other
+            if let Some(signature) = self.get_signature_of_declaration(node) {
+                self.get_return_type_of_signature(signature);
+            }
+            if matches!(
+                node.kind,
+                SyntaxKind::FunctionDeclaration
+                    | SyntaxKind::MethodDeclaration
+                    | SyntaxKind::FunctionExpression
+                    | SyntaxKind::ArrowFunction
+            ) {
+                self.check_all_code_paths_return_or_throw(node, body);
+            }
+        }
+    }
+
+    fn check_type_parameter(&mut self, node: &Arc<Node>) {
+        self.check_type_parameter_constraint(node);
+        let type_parameter = node.as_type_parameter();
+        for type_node in [&type_parameter.constraint, &type_parameter.default_type]
+            .into_iter()
+            .flatten()
+        {
+            self.check_type_node(type_node);
+        }
+    }
+
+    fn check_class_like_declaration(&mut self, node: &Arc<Node>) {
+        for type_parameter in node.type_parameters().into_iter().flatten() {
+            self.check_type_parameter(type_parameter);
+        }
+        let heritage_clauses = match node.kind {
+            SyntaxKind::ClassDeclaration => &node.as_class_declaration().heritage_clauses,
+            _ => &node.as_class_expression().heritage_clauses,
+        };
+        for clause in heritage_clauses.iter().flatten() {
+            for heritage_type in &clause.as_heritage_clause().types {
+                if clause.as_heritage_clause().token == SyntaxKind::ExtendsKeyword {
+                    // A class extends a value, so only its type arguments are types
+                    let heritage_type = heritage_type.as_expression_with_type_arguments();
+                    self.check_expression(&heritage_type.expression);
+                    for argument in heritage_type.type_arguments.iter().flatten() {
+                        self.check_type_node(argument);
+                    }
+                } else {
+                    self.check_type_node(heritage_type);
+                }
+            }
+        }
+        for member in node.members().into_iter().flatten() {
+            self.check_source_element(member);
+        }
+        if let Some(symbol) = self.get_symbol_of_declaration(node) {
+            self.check_class_heritage(node, symbol);
+            self.check_member_overrides(node, symbol);
+            let instance_type = self.get_declared_type_of_symbol(symbol);
+            self.check_index_constraints(node, instance_type);
+        }
+        self.check_property_initialization(node);
+    }
+
+    fn check_interface_declaration(&mut self, node: &Arc<Node>) {
+        let declaration = node.as_interface_declaration();
+        for type_parameter in declaration.type_parameters.iter().flatten() {
+            self.check_type_parameter(type_parameter);
+        }
+        for clause in declaration.heritage_clauses.iter().flatten() {
+            for heritage_type in &clause.as_heritage_clause().types {
+                self.check_type_node(heritage_type);
+            }
+        }
+        if let Some(symbol) = self.get_symbol_of_declaration(node) {
+            let interface_type = self.get_declared_type_of_symbol(symbol);
+            self.get_base_types(interface_type);
+            self.check_index_constraints(node, interface_type);
+        }
+        for member in &declaration.members {
+            self.check_source_element(member);
+        }
+    }
+
+    fn check_type_alias_declaration(&mut self, node: &Arc<Node>) {
+        let declaration = node.as_type_alias_declaration();
+        for type_parameter in declaration.type_parameters.iter().flatten() {
+            self.check_type_parameter(type_parameter);
+        }
+        if let Some(symbol) = self.get_symbol_of_declaration(node) {
+            self.get_declared_type_of_symbol(symbol);
+        }
+        self.check_type_node(&declaration.type_node);
+    }
+
+    /// Resolves the local names an export declaration exports, which reads them
+    fn check_export_declaration(&mut self, node: &Arc<Node>) {
+        let declaration = node.as_export_declaration();
+        let Some(clause) = &declaration.export_clause else {
+            return;
+        };
+        if clause.kind != SyntaxKind::NamedExports {
+            return;
+        }
+        for specifier in &clause.as_named_exports().elements {
+            if let Some(symbol) = self.bindings.symbol_of_node(specifier) {
+                self.resolve_alias(symbol);
+            }
+        }
+    }
+}
+
+/// Determines if `node` is an expression, as opposed to a name, type, or statement
+fn is_expression_node(node: &Node) -> bool {
+    match node.kind {
+        SyntaxKind::Identifier => {
+            // Identifiers are expressions unless they name something
+            node.parent().is_none_or(|parent| {
+                !(parent
+                    .name()
+                    .is_some_and(|name| std::ptr::eq(name.as_ref(), node))
+                    && parent.kind != SyntaxKind::ShorthandPropertyAssignment
+                    || matches!(
+                        parent.kind,
+                        SyntaxKind::QualifiedName
+                            | SyntaxKind::TypeReference
+                            | SyntaxKind::LabeledStatement
+                            | SyntaxKind::BreakStatement
+                            | SyntaxKind::ContinueStatement
+                    ))
+            })
+        }
+        SyntaxKind::ThisKeyword
+        | SyntaxKind::SuperKeyword
+        | SyntaxKind::NullKeyword
+        | SyntaxKind::TrueKeyword
+        | SyntaxKind::FalseKeyword
+        | SyntaxKind::NumericLiteral
+        | SyntaxKind::BigIntLiteral
+        | SyntaxKind::StringLiteral
+        | SyntaxKind::NoSubstitutionTemplateLiteral
+        | SyntaxKind::RegularExpressionLiteral
+        | SyntaxKind::TemplateExpression
+        | SyntaxKind::ArrayLiteralExpression
+        | SyntaxKind::ObjectLiteralExpression
+        | SyntaxKind::PropertyAccessExpression
+        | SyntaxKind::ElementAccessExpression
+        | SyntaxKind::CallExpression
+        | SyntaxKind::NewExpression
+        | SyntaxKind::TaggedTemplateExpression
+        | SyntaxKind::TypeAssertionExpression
+        | SyntaxKind::ParenthesizedExpression
+        | SyntaxKind::FunctionExpression
+        | SyntaxKind::ArrowFunction
+        | SyntaxKind::DeleteExpression
+        | SyntaxKind::TypeOfExpression
+        | SyntaxKind::VoidExpression
+        | SyntaxKind::AwaitExpression
+        | SyntaxKind::PrefixUnaryExpression
+        | SyntaxKind::PostfixUnaryExpression
+        | SyntaxKind::BinaryExpression
+        | SyntaxKind::ConditionalExpression
+        | SyntaxKind::YieldExpression
+        | SyntaxKind::SpreadElement
+        | SyntaxKind::ClassExpression
+        | SyntaxKind::OmittedExpression
+        | SyntaxKind::AsExpression
+        | SyntaxKind::NonNullExpression
+        | SyntaxKind::MetaProperty
+        | SyntaxKind::SatisfiesExpression
+        | SyntaxKind::JsxElement
+        | SyntaxKind::JsxSelfClosingElement
+        | SyntaxKind::JsxFragment => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::visitor::for_each_descendant;
+    use crate::compiler::parser::parse_source_file;
+    use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+
+    fn check(text: &str) -> (Checker, Arc<Node>) {
+        let file = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let checker = Checker::new(std::slice::from_ref(&file));
+        (checker, file)
+    }
+
+    fn codes(text: &str) -> Vec<i32> {
+        let (mut checker, file) = check(text);
+        checker
+            .get_diagnostics(&file)
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect()
+    }
+
+    /// Prints the type of the first declaration in each variable statement of the file
+    fn declared_types(text: &str) -> Vec<String> {
+        let (mut checker, file) = check(text);
+        let names: Vec<Arc<Node>> = file
+            .as_source_file()
+            .statements
+            .iter()
+            .filter(|statement| statement.kind == SyntaxKind::VariableStatement)
+            .map(|statement| {
+                let list = &statement.as_variable_statement().declaration_list;
+                list.as_variable_declaration_list().declarations.nodes[0]
+                    .name()
+                    .unwrap()
+                    .clone()
+            })
+            .collect();
+        names
+            .iter()
+            .map(|name| {
+                let type_ = checker.get_type_at_location(name);
+                checker.type_to_string(type_)
+            })
+            .collect()
+    }
+
+    /// Prints the type of each reference to `name` in the file, in source order
+    fn reference_types(text: &str, name: &str) -> Vec<String> {
+        let (mut checker, file) = check(text);
+        reference_types_of(&mut checker, &file, name)
+    }
+
+    fn reference_types_of(checker: &mut Checker, file: &Arc<Node>, name: &str) -> Vec<String> {
+        let mut references = Vec::new();
+        for_each_descendant(file, &mut |node| {
+            let is_declaration_name = node
+                .parent()
+                .and_then(|parent| parent.name().cloned())
+                .is_some_and(|parent_name| Arc::ptr_eq(&parent_name, node));
+            if node.kind == SyntaxKind::Identifier && node.text() == name && !is_declaration_name {
+                references.push(Arc::clone(node));
+            }
+            false
+        });
+        references
+            .iter()
+            .map(|reference| {
+                let type_ = checker.check_expression(reference);
+                checker.type_to_string(type_)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_unresolved_names() {
+        assert_eq!(codes("let x = y;"), vec![2304]);
+    }
+
+    #[test]
+    fn reports_missing_properties() {
+        assert_eq!(codes("let o = { a: 1 };\no.b;"), vec![2339]);
+    }
+
+    #[test]
+    fn reports_arithmetic_operand_errors() {
+        assert_eq!(codes("let s = 'a';\nlet x = s * 2;"), vec![2362]);
+        assert_eq!(codes("let s = 'a';\nlet x = 2 - s;"), vec![2363]);
+    }
+
+    #[test]
+    fn reports_argument_count_errors() {
+        assert_eq!(codes("function f(a: number) {}\nf();"), vec![2554]);
+        assert_eq!(codes("function f(a: number) {}\nf(1, 2);"), vec![2554]);
+    }
+
+    #[test]
+    fn reports_circular_type_aliases() {
+        assert_eq!(codes("type A = B;\ntype B = A;"), vec![2456, 2456]);
+        assert_eq!(codes("type A = A;"), vec![2456]);
+        assert_eq!(codes("type A = string | A;"), vec![2456]);
+    }
+
+    #[test]
+    fn defers_recursive_references_in_type_aliases() {
+        let text = "type Json = string | number | Json[] | { [k: string]: Json };
+type Rec = Rec[];
+type Pair = [number, Pair | null];
+type Tree<T> = T | readonly Tree<T>[];
+let j: Json = { a: [1, 'b', { c: [] }] };
+let r: Rec = [[], [[]]];
+let p: Pair = [1, [2, null]];
+let t: Tree<number> = [1, [2, [3]]];";
+        assert!(codes(text).is_empty());
+        assert_eq!(
+            codes(
+                "type Json = string | Json[];\ndeclare let j: Json;\nlet n: number = j;\n\
+                 type Pair = [number, Pair | null];\nlet p: Pair = [1, ['x', null]];"
+            ),
+            vec![2322, 2322]
+        );
+        assert_eq!(
+            declared_types(
+                "type Rec = Rec[];\nlet r: Rec = [];\ntype Json = string | Json[];\nlet j: Json = '';"
+            ),
+            ["Rec", "Json"]
+        );
+    }
+
+    #[test]
+    fn widens_mutable_literal_declarations() {
+        assert_eq!(
+            declared_types("let x = 1;\nconst y = 1;\nlet s = 'a';\nconst t = 'a';"),
+            ["number", "1", "string", "\"a\""]
+        );
+    }
+
+    #[test]
+    fn prints_declared_types() {
+        assert_eq!(
+            declared_types(
+                "let a: string | number | boolean;\n\
+                 let b: (x: number, y?: string) => boolean;\n\
+                 let c: { a: number; readonly b: string[] };\n\
+                 let d: [number, string?];"
+            ),
+            [
+                "string | number | boolean",
+                "(x: number, y?: string) => boolean",
+                "{ a: number; readonly b: string[]; }",
+                "[number, string?]",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_unassignable_initializers() {
+        assert_eq!(codes("let x: number = 'a';"), vec![2322]);
+        assert_eq!(codes("let s: string;\ns = 1;"), vec![2322]);
+        assert_eq!(codes("function f(): string { return 1; }"), vec![2322]);
+        assert_eq!(codes("function f(a: number) {}\nf('a');"), vec![2345]);
+    }
+
+    #[test]
+    fn accepts_assignable_initializers() {
+        assert!(codes("let t: [number, string] = [1, 'a'];").is_empty());
+        assert!(codes("let k: { kind: 'a' | 'b' } = { kind: 'a' };").is_empty());
+        assert!(
+            codes("let u: string | number = 1;\nlet a: any = 1;\nlet n: number = a;").is_empty()
+        );
+        assert!(
+            codes(
+                "interface L<T> { v: T }\ndeclare let a: L<string>;\nlet b: L<string | number> = a;"
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_excess_properties() {
+        assert_eq!(
+            codes("interface P { x: number; y: number }\nlet p: P = { x: 1 };"),
+            vec![2741]
+        );
+        assert_eq!(codes("let p: { x: number } = { x: 1, y: 2 };"), vec![2353]);
+        assert!(codes("let o = { x: 1, y: 2 };\nlet p: { x: number } = o;").is_empty());
+    }
+
+    #[test]
+    fn explains_incompatible_nested_properties() {
+        let (mut checker, file) = check(
+            "interface A { a: { b: string } }\ninterface B { a: { b: number } }\ndeclare let a: A;\nlet b: B = a;",
+        );
+        let diagnostics = checker.get_diagnostics(&file);
+        assert_eq!(
+            diagnostics[0].message_text,
+            "Type 'A' is not assignable to type 'B'.\n  The types of 'a.b' are incompatible between these types.\n    Type 'string' is not assignable to type 'number'."
+        );
+    }
+
+    #[test]
+    fn reports_tuple_length_mismatches() {
+        let (mut checker, file) = check("let t: [number, string] = [1];");
+        let diagnostics = checker.get_diagnostics(&file);
+        assert_eq!(
+            diagnostics[0].message_text,
+            "Type '[number]' is not assignable to type '[number, string]'.\n  Source has 1 element(s) but target requires 2."
+        );
+    }
+
+    #[test]
+    fn reports_overloads_that_do_not_match() {
+        assert_eq!(
+            codes(
+                "declare function g(a: string): void;\ndeclare function g(a: number): void;\ng(true);"
+            ),
+            vec![2769]
+        );
+    }
+
+    #[test]
+    fn relates_types_through_the_public_api() {
+        let (mut checker, _) = check("");
+        let string = checker.string_type();
+        let number = checker.number_type();
+        let union = checker.get_union_type(&[string, number]);
+        assert!(checker.is_type_assignable_to(string, union));
+        assert!(!checker.is_type_assignable_to(union, string));
+        assert!(checker.is_type_comparable_to(union, string));
+        assert!(checker.is_type_identical_to(union, union));
+        assert!(!checker.is_type_identical_to(string, number));
+    }
+
+    #[test]
+    fn infers_type_arguments_from_arguments() {
+        assert_eq!(
+            declared_types(
+                "declare function map<T, U>(a: T[], f: (x: T) => U): U[];\nlet r = map([1, 2], x => x + 1);"
+            ),
+            ["number[]"]
+        );
+        assert_eq!(
+            declared_types(
+                "declare function id<T>(x: T): T;\nlet a = id(1);\nconst b = id(1);\nlet c = id({ a: 1 });"
+            ),
+            ["number", "1", "{ a: number; }"]
+        );
+        assert_eq!(
+            declared_types(
+                "interface Box<T> { value: T }\ndeclare function unbox<T>(b: Box<T>): T;\nlet v = unbox({ value: 'x' });"
+            ),
+            ["string"]
+        );
+    }
+
+    #[test]
+    fn infers_type_arguments_from_other_sources() {
+        // Callback parameters are contravariant candidates
+        assert_eq!(
+            declared_types(
+                "declare function cb<T>(f: (x: T) => void): T;\nlet z = cb((x: string) => {});"
+            ),
+            ["string"]
+        );
+        // The contextual type of the call infers to the return type
+        assert!(codes("declare function g<T>(): T[];\nlet x: string[] = g();").is_empty());
+        // Type parameters without inferences take their defaults
+        assert_eq!(
+            declared_types("declare function h<T = number>(): T;\nlet y = h();"),
+            ["number"]
+        );
+    }
+
+    #[test]
+    fn checks_arguments_against_inferred_constraints() {
+        assert_eq!(
+            codes("declare function f<T extends string>(x: T): T;\nf(1);"),
+            vec![2345]
+        );
+    }
+
+    #[test]
+    fn unions_literal_candidates_of_the_same_base_type() {
+        assert!(
+            codes(
+                "declare function f<T>(a: T, b: T): T;\nf(1, 2);\nf(\"a\", \"b\");\nf(true, false);"
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            declared_types("declare function f<T>(a: T, b: T): T;\nconst a = f(1, 2);"),
+            ["1 | 2"]
+        );
+        // Candidates of different primitives still pick one of them
+        assert_eq!(
+            codes("declare function f<T>(a: T, b: T): T;\nf(1, \"a\");"),
+            vec![2345]
+        );
+    }
+
+    #[test]
+    fn types_parameters_from_contextual_signatures() {
+        assert_eq!(
+            codes("let f: (a: number) => string = (a) => a;"),
+            vec![2322]
+        );
+    }
+
+    #[test]
+    fn narrows_by_typeof() {
+        let text = "declare const x: string | number | boolean;\n\
+                    if (typeof x === \"string\") { x; } else { x; }\n\
+                    if (typeof x !== \"number\" && typeof x !== \"boolean\") { x; }\n\
+                    x;";
+        assert_eq!(
+            reference_types(text, "x"),
+            [
+                "string | number | boolean",
+                "string",
+                "number | boolean",
+                "string | number | boolean",
+                "string | boolean",
+                "string",
+                "string | number | boolean",
+            ]
+        );
+    }
+
+    #[test]
+    fn narrows_by_truthiness_and_equality() {
+        let text = "declare const x: \"a\" | \"b\" | 0 | 1;\n\
+                    if (x) { x; } else { x; }\n\
+                    if (x === \"a\") { x; } else if (x == 0) { x; }\n\
+                    declare const s: string | number;\n\
+                    if (s === \"c\") { s; }";
+        assert_eq!(
+            reference_types(text, "x"),
+            [
+                "\"a\" | \"b\" | 0 | 1",
+                "\"a\" | \"b\" | 1",
+                "0",
+                "\"a\" | \"b\" | 0 | 1",
+                "\"a\"",
+                "\"b\" | 0 | 1",
+                "0",
+            ]
+        );
+        assert_eq!(reference_types(text, "s"), ["string | number", "\"c\""]);
+    }
+
+    #[test]
+    fn narrows_nullable_types_under_strict_null_checks() {
+        let text = "declare const x: string | null | undefined;\n\
+                    if (x != null) { x; } else { x; }\n\
+                    if (x !== undefined) { x; }";
+        let (mut checker, file) = check(text);
+        checker.options.strict_null_checks = true;
+        assert_eq!(
+            reference_types_of(&mut checker, &file, "x"),
+            [
+                "string | null | undefined",
+                "string",
+                "null | undefined",
+                "string | null | undefined",
+                "string | null",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_definite_assignment_and_nullability_under_strict_null_checks() {
+        let text = "declare let s: string;\n\
+                    s = null;\n\
+                    s = undefined;\n\
+                    let n: number;\n\
+                    n;\n\
+                    let m: number;\n\
+                    if (s) { m = 1; } else { m = 2; }\n\
+                    m;\n\
+                    let k: number;\n\
+                    function f() { k; }\n\
+                    let d!: number;\n\
+                    d;\n\
+                    let u: number;\n\
+                    u!;\n\
+                    declare const o: { a?: number };\n\
+                    const a: number = o.a;\n\
+                    declare const p: { x: number } | undefined;\n\
+                    p.x;\n\
+                    p?.x;\n\
+                    p!.x;\n\
+                    const x: number = p?.x;\n\
+                    declare const g: (() => void) | null;\n\
+                    g();\n\
+                    g?.();";
+        assert!(codes(text).is_empty());
+        let (mut checker, file) = check(text);
+        checker.options.strict_null_checks = true;
+        let diagnostics = checker.get_diagnostics(&file);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect::<Vec<_>>(),
+            vec![2322, 2322, 2454, 2322, 18048, 2322, 2721]
+        );
+        assert_eq!(
+            diagnostics[2].message_text,
+            "Variable 'n' is used before being assigned."
+        );
+        assert_eq!(diagnostics[4].message_text, "'p' is possibly 'undefined'.");
+    }
+
+    #[test]
+    fn adds_undefined_to_optional_types_under_strict_null_checks() {
+        let text = "declare const o: { a?: number, b: string };\n\
+                    declare const p: { x: number } | undefined;\n\
+                    function f(n?: string) { n; }\n\
+                    type M = { [K in \"b\"]?: string };\n\
+                    declare const m: M;\n\
+                    type R = { [K in keyof typeof o]-?: (typeof o)[K] };\n\
+                    declare const r: R;\n\
+                    o.a; p?.x; p!.x; m.b; r.a;";
+        let (mut checker, file) = check(text);
+        checker.options.strict_null_checks = true;
+        assert_eq!(
+            reference_types_of(&mut checker, &file, "n"),
+            ["string | undefined"]
+        );
+        let types: Vec<String> = file.as_source_file().statements.nodes[7..]
+            .iter()
+            .map(|statement| {
+                let type_ =
+                    checker.check_expression(&statement.as_expression_statement().expression);
+                checker.type_to_string(type_)
+            })
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "number | undefined",
+                "number | undefined",
+                "number",
+                "string | undefined",
+                "number"
+            ]
+        );
+    }
+
+    #[test]
+    fn narrows_discriminated_unions() {
+        let text = "type Shape = { kind: \"circle\", radius: number } | { kind: \"square\", size: number } | { kind: \"point\" };\n\
+                    declare const s: Shape;\n\
+                    if (s.kind === \"circle\") { s; }\n\
+                    switch (s.kind) {\n\
+                        case \"square\": s; break;\n\
+                        case \"circle\":\n\
+                        case \"point\": s; break;\n\
+                        default: s;\n\
+                    }";
+        assert_eq!(
+            reference_types(text, "s"),
+            [
+                "Shape",
+                "{ kind: \"circle\"; radius: number; }",
+                "Shape",
+                "{ kind: \"square\"; size: number; }",
+                "{ kind: \"circle\"; radius: number; } | { kind: \"point\"; }",
+                "never",
+            ]
+        );
+    }
+
+    #[test]
+    fn narrows_by_instanceof_and_in() {
+        let text = "class A { a = 1; }\n\
+                    class B extends A { b = 2; }\n\
+                    class C { c = 3; }\n\
+                    declare const x: B | C;\n\
+                    if (x instanceof A) { x; } else { x; }\n\
+                    if (\"c\" in x) { x; } else { x; }";
+        assert_eq!(
+            reference_types(text, "x"),
+            ["B | C", "B", "C", "B | C", "C", "B"]
+        );
+    }
+
+    #[test]
+    fn narrows_through_assignments_and_loops() {
+        let text = "declare function f(): boolean;\n\
+                    let x: string | number | boolean = \"a\";\n\
+                    x;\n\
+                    x = 1;\n\
+                    x;\n\
+                    while (f()) { x; x = true; }\n\
+                    x;\n\
+                    declare const y: string | number;\n\
+                    if (typeof y === \"string\") { const g = () => y; }";
+        // Assignment targets have the declared type
+        assert_eq!(
+            reference_types(text, "x"),
+            [
+                "string",
+                "string | number | boolean",
+                "number",
+                "number | true",
+                "string | number | boolean",
+                "number | true",
+            ]
+        );
+        assert_eq!(reference_types(text, "y"), ["string | number", "string"]);
+    }
+
+    #[test]
+    fn resolves_conditional_types() {
+        let text = "type IsString<T> = T extends string ? \"yes\" : \"no\";\n\
+                    declare let a: IsString<string>;\n\
+                    declare let b: IsString<number>;\n\
+                    declare let c: IsString<any>;\n\
+                    declare let d: IsString<never>;";
+        // `any` takes both branches and `never` distributes to nothing
+        assert_eq!(
+            declared_types(text),
+            ["\"yes\"", "\"no\"", "\"yes\" | \"no\"", "never"]
+        );
+        // Unions distribute over the naked type parameter, but not over a wrapped one
+        let text = "type IsString<T> = T extends string ? \"yes\" : \"no\";\n\
+                    type AllString<T> = [T] extends [string] ? \"yes\" : \"no\";\n\
+                    declare const a: IsString<string | number>;\n\
+                    let b: \"yes\" | \"no\" = a;\n\
+                    let c: \"yes\" = a;\n\
+                    declare const d: AllString<string | number>;\n\
+                    let e: \"no\" = d;";
+        assert_eq!(codes(text), vec![2322]);
+    }
+
+    #[test]
+    fn infers_types_in_conditional_types() {
+        let text = "type Unbox<T> = T extends { value: infer U } ? U : never;\n\
+                    type ReturnOf<T> = T extends (...args: any[]) => infer R ? R : never;\n\
+                    type First<T> = T extends [infer H extends string, ...any[]] ? H : never;\n\
+                    declare let a: Unbox<{ value: number }>;\n\
+                    declare let b: Unbox<{ other: number }>;\n\
+                    declare let c: ReturnOf<() => boolean>;\n\
+                    declare let d: First<[\"a\", 1]>;\n\
+                    declare let e: First<[1, \"a\"]>;";
+        assert_eq!(
+            declared_types(text),
+            ["number", "never", "boolean", "\"a\"", "never"]
+        );
+        assert_eq!(codes("let x: infer U;"), vec![1338]);
+    }
+
+    #[test]
+    fn defers_generic_conditional_types() {
+        let text = "type IsString<T> = T extends string ? \"yes\" : \"no\";\n\
+                    function f<T>(x: IsString<T>) { let y: \"yes\" | \"no\" = x; }";
+        let (mut checker, file) = check(text);
+        assert_eq!(
+            reference_types_of(&mut checker, &file, "x"),
+            ["IsString<T>"]
+        );
+        assert!(checker.get_diagnostics(&file).is_empty());
+        assert_eq!(
+            codes("function f<T>(x: T extends string ? number : boolean) { let n: number = x; }"),
+            vec![2322]
+        );
+    }
+
+    #[test]
+    fn resolves_recursive_conditional_types() {
+        let text = "type Unwrap<T> = T extends { value: infer U } ? Unwrap<U> : T;\n\
+                    declare let a: Unwrap<{ value: { value: { value: { done: true } } } }>;";
+        assert_eq!(declared_types(text), ["{ done: true; }"]);
+    }
+
+    #[test]
+    fn resolves_mapped_type_members() {
+        let text = "interface P { a: number; readonly b: string; c?: boolean }\n\
+                    type Exclude<T, U> = T extends U ? never : T;\n\
+                    declare let a: { [K in keyof P]?: P[K] };\n\
+                    declare let b: { -readonly [K in keyof P]-?: P[K] };\n\
+                    declare let c: { [K in \"x\" | \"y\"]: K };\n\
+                    declare let d: { readonly [K in string]: number };\n\
+                    declare let e: { [K in keyof P as Exclude<K, \"a\">]: P[K] };";
+        assert_eq!(
+            declared_types(text),
+            [
+                "{ a?: number; readonly b?: string; c?: boolean; }",
+                "{ a: number; b: string; c: boolean; }",
+                "{ x: \"x\"; y: \"y\"; }",
+                "{ readonly [x: string]: number; }",
+                "{ readonly b: string; c?: boolean; }",
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_modifiers_of_picked_properties() {
+        let declarations = "interface P { a: number; readonly b: string; c?: boolean }\n\
+                            type Exclude<T, U> = T extends U ? never : T;\n\
+                            type Pick<T, K extends keyof T> = { [Q in K]: T[Q] };\n\
+                            type Omit<T, K> = Pick<T, Exclude<keyof T, K>>;\n";
+        assert!(
+            codes(&format!(
+                "{declarations}let a: Pick<P, \"b\" | \"c\"> = {{ b: \"\" }};\n\
+                 let b: Omit<P, \"a\"> = {{ b: \"\" }};"
+            ))
+            .is_empty()
+        );
+        assert_eq!(
+            codes(&format!(
+                "{declarations}let a: Pick<P, \"a\" | \"c\"> = {{ c: true }};"
+            )),
+            vec![2741]
+        );
+        assert_eq!(
+            codes(&format!(
+                "{declarations}declare let a: Pick<P, \"b\">;\n\
+                 declare let b: Omit<P, \"a\" | \"c\">;\n\
+                 a.b = \"\";\n\
+                 b.b = \"\";"
+            )),
+            vec![2540, 2540]
+        );
+    }
+
+    #[test]
+    fn instantiates_homomorphic_mapped_types() {
+        let text = "type Box<T> = { [K in keyof T]: { value: T[K] } };\n\
+                    declare let a: Box<number[]>;\n\
+                    declare let b: Box<readonly [string, number?]>;\n\
+                    declare let c: Box<string>;\n\
+                    declare let d: Box<{ x: number }>;";
+        assert_eq!(
+            declared_types(text),
+            [
+                "{ value: number; }[]",
+                "readonly [{ value: string; }, { value: number; }?]",
+                "string",
+                "Box<{ x: number; }>",
+            ]
+        );
+        assert_eq!(
+            codes(
+                "type Box<T> = { [K in keyof T]: { value: T[K] } };\n\
+                 declare let u: Box<string | { a: number }>;\n\
+                 let v: string | { a: { value: number } } = u;\n\
+                 let w: { a: { value: string } } = u;"
+            ),
+            vec![2322]
+        );
+    }
+
+    #[test]
+    fn defers_generic_mapped_and_indexed_access_types() {
+        let text = "type Partial<T> = { [K in keyof T]?: T[K] };\n\
+                    function f<T, K extends \"a\">(p: { readonly [P in keyof T]-?: T[P] }, v: Partial<T>[K]) { p; v; }";
+        assert_eq!(
+            reference_types(text, "p"),
+            ["{ readonly [P in keyof T]-?: T[P]; }"]
+        );
+        assert_eq!(reference_types(text, "v"), ["T[K]"]);
+        assert!(
+            codes("function f<T extends { a: number }>(v: T[\"a\"]) { let n: number = v; }")
+                .is_empty()
+        );
+        assert_eq!(
+            codes("function f<T extends { a: number }>(v: T[\"a\"]) { let s: string = v; }"),
+            vec![2322]
+        );
+    }
+
+    #[test]
+    fn resolves_keyof_and_indexed_access_types() {
+        let text = "class C { a = 1; 0 = \"\"; private p = 1; }\n\
+                    interface D { [key: string]: boolean; b: true }\n\
+                    declare let a: keyof C;\n\
+                    declare let b: keyof D;\n\
+                    declare let c: keyof (C | { a: string });\n\
+                    declare let d: C[\"a\" | 0];\n\
+                    declare let e: D[string];";
+        assert_eq!(
+            declared_types(text),
+            [
+                "\"a\" | 0",
+                "string | number",
+                "\"a\"",
+                "string | number",
+                "boolean"
+            ]
+        );
+        assert_eq!(
+            codes(
+                "interface P { a: number }\n\
+                 type A = P[\"b\"];\n\
+                 type B = P[string];\n\
+                 type C = P[boolean];"
+            ),
+            vec![2339, 2537, 2538]
+        );
+    }
+
+    #[test]
+    fn defers_keyof_generic_types() {
+        let text = "function f<T extends { a: number }, K extends keyof T>(k: K, v: T[K]) {\n\
+                        let key: keyof T = \"a\";\n\
+                        let s: string | number | symbol = k;\n\
+                        let n: number = v;\n\
+                        k; }";
+        let (mut checker, file) = check(text);
+        assert_eq!(reference_types_of(&mut checker, &file, "k"), ["K", "K"]);
+        assert_eq!(
+            checker
+                .get_diagnostics(&file)
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect::<Vec<_>>(),
+            vec![2322]
+        );
+        assert_eq!(
+            codes("function f<T, K>(v: T[K]) {}\nfunction g<T>(k: keyof T) { let s: string = k; }"),
+            vec![2536, 2322]
+        );
+    }
+
+    #[test]
+    fn resolves_type_queries() {
+        let text = "const o = { a: 1, b: { c: \"\" } };\n\
+                    namespace N { export const x = true; }\n\
+                    declare let a: typeof o.b;\n\
+                    declare let b: typeof o.b.c;\n\
+                    declare let c: typeof N.x;\n\
+                    declare let d: typeof o.z;";
+        assert_eq!(
+            declared_types(text),
+            [
+                "{ a: number; b: { c: string; }; }",
+                "{ c: string; }",
+                "string",
+                "true",
+                "any"
+            ]
+        );
+        assert_eq!(codes(text), vec![2339]);
+    }
+
+    #[test]
+    fn checks_class_heritage() {
+        assert_eq!(
+            codes("class B { x = 1; }\nclass D extends B { x = \"\"; }"),
+            vec![2416]
+        );
+        assert_eq!(
+            codes(
+                "interface I { f(): number; }\nclass C implements I { g() {} }\ntype S = string;\nclass E implements S {}"
+            ),
+            vec![2420, 2422]
+        );
+        assert_eq!(
+            codes(
+                "abstract class A { abstract f(): void; abstract g(): void; }\n\
+                   class C extends A {}\nclass D extends A { f() {} }\nabstract class E extends A {}"
+            ),
+            vec![2654, 2515]
+        );
+        assert_eq!(
+            codes(
+                "class B { get x() { return 1; } y = 1; z = 1; }\n\
+                   class D extends B { x = 2; get y() { return 1; } z() { return 1; } }"
+            ),
+            vec![2610, 2611, 2416, 2425]
+        );
+    }
+
+    #[test]
+    fn reports_construction_of_abstract_classes() {
+        assert_eq!(
+            codes(
+                "abstract class A { constructor(x: number) {} }\n\
+                   class B extends A { constructor() { super(1); } }\n\
+                   new A(1);\nnew B();\n\
+                   function f(c: typeof A, d: abstract new () => object) { new c(1); new d(); }"
+            ),
+            vec![2511, 2511, 2511]
+        );
+    }
+
+    #[test]
+    fn checks_override_modifiers() {
+        let text = "class A { override f() {} }\n\
+                    class B { f() {} }\n\
+                    class C extends B { override g() {} f() {} }";
+        assert_eq!(codes(text), vec![4112, 4113]);
+        let (mut checker, file) = check(text);
+        checker.options.no_implicit_override = true;
+        let codes: Vec<i32> = checker
+            .get_diagnostics(&file)
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        assert_eq!(codes, vec![4112, 4113, 4114]);
+    }
+
+    #[test]
+    fn checks_super_calls_in_derived_constructors() {
+        assert_eq!(
+            codes("class B {}\nclass C extends B { constructor() { this; } }"),
+            vec![2377, 17009]
+        );
+        assert_eq!(
+            codes(
+                "class B { m() {} }\nclass C extends B { x = 1; constructor() { super.m(); this.x; super(); } }"
+            ),
+            vec![2376, 17011, 17009]
+        );
+        assert_eq!(
+            codes(
+                "class B {}\nclass C extends B { x = 1; constructor() { if (true) { super(); } } }"
+            ),
+            vec![2401]
+        );
+        assert!(codes("class B {}\nclass C extends B { x = 1; constructor() { let y = 1; super(); this.x = y; } }").is_empty());
+    }
+
+    #[test]
+    fn checks_strict_property_initialization() {
+        let text = "class C {\n\
+                    a: number;\n\
+                    b: number;\n\
+                    c: number;\n\
+                    d?: number;\n\
+                    e!: number;\n\
+                    f: number | undefined;\n\
+                    constructor(x: boolean) {\n\
+                    this.a = 1;\n\
+                    if (x) { this.b = 1; return; }\n\
+                    this.b = 2;\n\
+                    if (x) { this.c = 1; }\n\
+                    }\n\
+                    }";
+        assert!(codes(text).is_empty());
+        let (mut checker, file) = check(text);
+        checker.options.strict_null_checks = true;
+        checker.options.strict_property_initialization = true;
+        let diagnostics = checker.get_diagnostics(&file);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect::<Vec<_>>(),
+            vec![2564]
+        );
+        assert_eq!(
+            diagnostics[0].message_text,
+            "Property 'c' has no initializer and is not definitely assigned in the constructor."
+        );
+    }
+
+    #[test]
+    fn checks_property_accessibility() {
+        let text = "class A {\n\
+                    private p = 1;\n\
+                    protected q = 2;\n\
+                    #r = 3;\n\
+                    m(other: A) { return this.p + other.q + other.#r; }\n\
+                    }\n\
+                    class B extends A { n() { return this.q + this.p; } }\n\
+                    class C<T> { protected t?: T; }\n\
+                    class D extends C<number> { n() { return this.t; } }\n\
+                    declare const a: A;\n\
+                    a.p;\n\
+                    a.q;\n\
+                    a[\"p\"];";
+        assert_eq!(codes(text), vec![2341, 2341, 2445]);
+        let (mut checker, file) = check(text);
+        let messages: Vec<String> = checker
+            .get_diagnostics(&file)
+            .iter()
+            .map(|diagnostic| diagnostic.message_text.clone())
+            .collect();
+        assert_eq!(
+            messages[2],
+            "Property 'q' is protected and only accessible within class 'A' and its subclasses."
+        );
+        assert_eq!(
+            codes("class A { #r = 3; }\ndeclare const a: A;\na.#r;"),
+            vec![18013]
+        );
+    }
+
+    #[test]
+    fn adds_undefined_to_index_signature_reads_under_no_unchecked_indexed_access() {
+        let text = "declare const o: { [key: string]: number };\n\
+                    let a = o.x;\n\
+                    let b = o[\"y\"];\n\
+                    o.z = 1;\n\
+                    a;\n\
+                    b;";
+        assert_eq!(
+            declared_types(text),
+            vec!["{ [x: string]: number; }", "number", "number"]
+        );
+        let (mut checker, file) = check(text);
+        checker.set_options(CheckerOptions {
+            strict_null_checks: true,
+            no_unchecked_indexed_access: true,
+            ..CheckerOptions::default()
+        });
+        assert!(checker.get_diagnostics(&file).is_empty());
+        let types: Vec<String> = ["a", "b"]
+            .iter()
+            .flat_map(|name| reference_types_of(&mut checker, &file, name))
+            .collect();
+        assert_eq!(types, vec!["number | undefined", "number | undefined"]);
+    }
+
+    /// Gets the codes of the errors in a file checked with the given options
+    #[test]
+    fn types_names_in_binding_patterns() {
+        let text = "declare const o: { a: number; b?: string; c: { d: boolean } };\n\
+                    const { a, b = 1, ...others } = o;\n\
+                    const { c: { d } } = o;\n\
+                    const [x, , ...rest] = [1, \"s\", true] as [number, string, boolean];\n\
+                    function f({ m, n = \"n\" }, [p]) { m; n; p; }\n\
+                    a; b; d; others; x; rest;";
+        let types: Vec<String> = ["a", "b", "d", "others", "x", "rest", "m", "n", "p"]
+            .iter()
+            .flat_map(|name| reference_types(text, name))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                "number",
+                "string | 1",
+                "boolean",
+                "{ c: { d: boolean; }; }",
+                "number",
+                "[boolean]",
+                "any",
+                "string",
+                "any",
+            ]
+        );
+        assert_eq!(
+            declared_types("function f({ a, b = 1 }, [c, d = \"\"]) {}\nvar g = f;"),
+            vec!["({ a, b }: { a: any; b?: number; }, [c, d]: [any, string?]) => void"]
+        );
+    }
+
+    #[test]
+    fn reports_names_missing_from_destructured_types() {
+        let text = "declare const o: { a: number };\n\
+                    const { a, z } = o;\n\
+                    const [x, y] = [1] as [number];\n\
+                    const { q = 1 }: { q?: number } = {};";
+        assert_eq!(codes(text), vec![2339, 2493]);
+    }
+
+    #[test]
+    fn merges_spread_properties() {
+        let text = "declare const a: { x: number; y?: string; readonly r: boolean };\n\
+                    declare const b: { y?: number; z: string };\n\
+                    class C { m() {} p = 1; }\n\
+                    declare const c: C;\n\
+                    declare const t: [number, string];\n\
+                    let s = { ...a, ...b };\n\
+                    let o = { x: \"x\", ...b, ...c };\n\
+                    let u: [boolean, number, string] = [true, ...t];\n\
+                    let v = [true, ...t];";
+        assert_eq!(
+            declared_types(text),
+            vec![
+                "{ x: number; y?: string; readonly r: boolean; }",
+                "{ y?: number; z: string; }",
+                "C",
+                "[number, string]",
+                "{ x: number; r: boolean; y?: string | number; z: string; }",
+                "{ x: string; y?: number; z: string; p: number; }",
+                "[boolean, number, string]",
+                "(string | number | boolean)[]",
+            ]
+        );
+        let overwritten = "declare const a: { x: number };\nlet o = { x: 1, ...a };";
+        assert_eq!(codes(overwritten), Vec::<i32>::new());
+        let options = CheckerOptions {
+            strict_null_checks: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(codes_with_options(overwritten, options), vec![2783]);
+    }
+
+    #[test]
+    fn checks_spread_arguments_and_tuple_rest_parameters() {
+        let text = "declare const t: [number, string];\n\
+                    declare const arr: number[];\n\
+                    function f(a: number, b: string) {}\n\
+                    function g(...args: [number, string?]) {}\n\
+                    function h(...args: number[]) {}\n\
+                    f(...t);\n\
+                    g(1);\n\
+                    g(1, \"a\");\n\
+                    h(...arr);\n\
+                    f(...arr);\n\
+                    g(1, 2);\n\
+                    g();\n\
+                    g(1, \"a\", 3);\n\
+                    h(...t);";
+        assert_eq!(codes(text), vec![2556, 2345, 2554, 2554, 2345]);
+        assert_eq!(
+            declared_types("function g(...args: [number, string?]) {}\nvar v = g;"),
+            vec!["(...args: [number, string?]) => void"]
+        );
+    }
+
+    #[test]
+    fn infers_promises_from_async_functions() {
+        let text = "interface Promise<T> { then<R>(f: (value: T) => R): Promise<R>; }\n\
+                    interface Thenable { then(f: (value: boolean) => void): void; }\n\
+                    declare const t: Thenable;\n\
+                    async function f() { return 1; }\n\
+                    async function g() { return await t; }\n\
+                    async function h() { return f(); }\n\
+                    var a = f;\n\
+                    var b = g;\n\
+                    var c = h;\n\
+                    var d = async () => {};";
+        assert_eq!(
+            declared_types(text),
+            vec![
+                "Thenable",
+                "() => Promise<number>",
+                "() => Promise<boolean>",
+                "() => Promise<number>",
+                "() => Promise<void>",
+            ]
+        );
+        assert_eq!(codes(text), Vec::<i32>::new());
+        assert_eq!(
+            codes("async function f(): number { return 1; }"),
+            vec![1064]
+        );
+        assert_eq!(codes("async function f() { return 1; }"), vec![2697]);
+    }
+
+    #[test]
+    fn checks_where_await_is_allowed() {
+        let promise = "interface Promise<T> { then<R>(f: (value: T) => R): Promise<R>; }\n\
+                       declare const p: Promise<number>;\n";
+        assert_eq!(
+            codes(&format!(
+                "{promise}function f() {{ await p; }}\n\
+                 async function g() {{ const n: string = await p; }}\n\
+                 await p;\n\
+                 for await (const x of [p]) {{}}"
+            )),
+            vec![1308, 2322, 1375, 1431]
+        );
+        let module = "declare const p: { then(f: (value: number) => void): void };\n\
+                      await p;\n\
+                      for await (const x of 1) {}\n\
+                      export {};";
+        let options = |target, module| CheckerOptions {
+            target: Some(target),
+            module: Some(module),
+            ..CheckerOptions::default()
+        };
+        assert_eq!(
+            codes_with_options(module, options(ScriptTarget::ES2022, ModuleKind::ESNext)),
+            vec![2504]
+        );
+        assert_eq!(
+            codes_with_options(module, options(ScriptTarget::ES2015, ModuleKind::ESNext)),
+            vec![1378, 1432, 2504]
+        );
+        assert_eq!(
+            codes_with_options(module, options(ScriptTarget::ES2022, ModuleKind::CommonJS)),
+            vec![1378, 1432, 2504]
+        );
+    }
+
+    #[test]
+    fn iterates_with_the_iterator_protocol() {
+        let text = "declare var Symbol: { readonly iterator: unique symbol };\n\
+                    interface Number {}\n\
+                    interface YieldResult<T> { done?: false; value: T; }\n\
+                    interface ReturnResult<T> { done: true; value: T; }\n\
+                    interface Iterator<T> { next(): YieldResult<T> | ReturnResult<void>; }\n\
+                    interface Generator<T, R, N> extends Iterator<T> {\n\
+                        [Symbol.iterator](): Generator<T, R, N>;\n\
+                    }\n\
+                    class Range { *[Symbol.iterator]() { yield 1; } }\n\
+                    function* gen() { yield \"a\"; yield* new Range(); return true; }\n\
+                    var g = gen;\n\
+                    var r = new Range();\n\
+                    var s = [...r];";
+        assert_eq!(
+            declared_types(text),
+            vec![
+                "{ readonly iterator: unique symbol; }",
+                "() => Generator<string | number, boolean, unknown>",
+                "Range",
+                "number[]",
+            ]
+        );
+        assert_eq!(
+            reference_types(&format!("{text}\nvar [first] = gen();\nfirst;"), "first"),
+            vec!["string | number"]
+        );
+        assert_eq!(
+            reference_types(&format!("{text}\nfor (const x of r) {{ x; }}"), "x"),
+            vec!["number"]
+        );
+        assert_eq!(
+            codes(&format!(
+                "{text}\nfor (const x of 1) {{}}\nvar [y] = 1;\nvar z = [...1];\n\
+                 function* f() {{ yield* 1; }}"
+            )),
+            vec![2488, 2488, 2488, 2488]
+        );
+    }
+
+    #[test]
+    fn iterates_by_index_for_es5() {
+        let text = "declare var Symbol: { readonly iterator: unique symbol };\n\
+                    interface Iterator<T> { next(): { done?: false; value: T }; }\n\
+                    declare const iterable: { [Symbol.iterator](): Iterator<number> };\n\
+                    declare const array: number[];\n\
+                    for (const x of \"abc\") {}\n\
+                    for (const x of array) {}\n\
+                    var copy = [...array];\n\
+                    for (const x of iterable) {}\n\
+                    var chars = [...\"abc\"];\n\
+                    for (const x of 1) {}\n\
+                    var [y] = 1;";
+        let options = |downlevel_iteration| CheckerOptions {
+            target: Some(ScriptTarget::ES5),
+            downlevel_iteration,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(
+            codes_with_options(text, options(false)),
+            vec![2802, 2461, 2495, 2461]
+        );
+        assert_eq!(codes_with_options(text, options(true)), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn checks_members_against_index_signatures() {
+        let text = "interface A { [key: string]: number; a: number; b: string; }\n\
+                    interface B extends A { c: boolean; }\n\
+                    interface N { [i: number]: string; [k: string]: number; }\n\
+                    type T = { [k: string]: string; 0: string };\n\
+                    class C { [i: number]: number; 1 = \"s\"; m = \"s\"; }\n\
+                    interface L { [k: \"a\" | \"b\"]: number; }\n\
+                    interface P { [k: boolean]: number; }";
+        assert_eq!(codes(text), vec![2411, 2411, 2413, 2411, 1337, 1268]);
+    }
+
+    #[test]
+    fn checks_accesses_through_index_signatures() {
+        let text = "declare const map: { [key: string]: number; known: number };\n\
+                    declare const list: { [index: number]: string };\n\
+                    declare const key: string;\n\
+                    map.known;\n\
+                    map.other;\n\
+                    map[\"other\"];\n\
+                    list[0];\n\
+                    list[key];";
+        assert_eq!(codes(text), Vec::<i32>::new());
+        let options = CheckerOptions {
+            no_implicit_any: true,
+            no_property_access_from_index_signature: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(codes_with_options(text, options), vec![4111, 7015]);
+    }
+
+    #[test]
+    fn checks_accessor_pairs() {
+        let text = "abstract class A {\n\
+                        get a() {}\n\
+                        get b(x: number) { return x; }\n\
+                        set c(value?: number) {}\n\
+                        protected get d() { return 1; }\n\
+                        set d(value: number) {}\n\
+                        abstract get e(): number;\n\
+                        set e(value: number) {}\n\
+                        abstract get f(): number { return 1; }\n\
+                        accessor g = 1;\n\
+                    }\n\
+                    interface I { accessor h: number }";
+        assert_eq!(
+            codes(text),
+            vec![2378, 1054, 1051, 2808, 2808, 2676, 2676, 1318, 1275]
+        );
+    }
+
+    #[test]
+    fn writes_properties_through_their_setters() {
+        let text = "class A {\n\
+                        readonly id: number;\n\
+                        get size(): number { return 0; }\n\
+                        set size(value: number | string) {}\n\
+                        get count() { return 0; }\n\
+                        constructor() { this.id = 1; }\n\
+                    }\n\
+                    const a = new A();\n\
+                    a.size = \"large\";\n\
+                    a.size = true;\n\
+                    a.count = 1;\n\
+                    a.id = 2;";
+        assert_eq!(codes(text), vec![2322, 2540, 2540]);
+    }
+
+    #[test]
+    fn checks_satisfies_without_widening() {
+        let text = "type Color = \"red\" | \"green\";\n\
+                    let a = { k: \"red\" } satisfies { k: Color };\n\
+                    const b = [1, 2] satisfies [number, number];\n\
+                    const c = 1 satisfies string;\n\
+                    const d = { x: 1, z: 2 } satisfies { x: number };";
+        assert_eq!(
+            declared_types(text),
+            vec![
+                "{ k: \"red\"; }",
+                "[number, number]",
+                "1",
+                "{ x: number; z: number; }"
+            ]
+        );
+        assert_eq!(codes(text), vec![1360, 2353]);
+    }
+
+    #[test]
+    fn infers_const_assertions() {
+        let text = "let a = [1, \"x\"] as const;\n\
+                    let b = { k: 1, n: { m: [true] } } as const;\n\
+                    let c = <const>\"s\";";
+        assert_eq!(
+            declared_types(text),
+            vec![
+                "readonly [1, \"x\"]",
+                "{ readonly k: 1; readonly n: { readonly m: readonly [true]; }; }",
+                "\"s\""
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_assertions_between_overlapping_types() {
+        let text = "declare const value: string | number;\n\
+                    value as string;\n\
+                    \"a\" as \"a\" | \"b\";\n\
+                    1 as unknown as string;\n\
+                    1 as string;\n\
+                    <number>\"s\";\n\
+                    value as const;\n\
+                    const o = { a: 1 } as const;\n\
+                    o.a = 2;";
+        assert_eq!(codes(text), vec![2352, 2352, 1355, 2540]);
+    }
+
+    #[test]
+    fn checks_definite_assignment() {
+        let text = "function f(c: boolean) {\n\
+                    let a: number;\n\
+                    a += 1;\n\
+                    let b: number;\n\
+                    if (c) { b = 1; }\n\
+                    b++;\n\
+                    let d!: number;\n\
+                    d;\n\
+                    let e!: number = 1;\n\
+                    let g!;\n\
+                    }\n\
+                    class C {\n\
+                    static s!: number;\n\
+                    a: number;\n\
+                    constructor() { this.a; this.a = 1; this.a; }\n\
+                    }";
+        let options = CheckerOptions {
+            strict_null_checks: true,
+            strict_property_initialization: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(
+            codes_with_options(text, options),
+            vec![2454, 2454, 1263, 1264, 1255, 2565]
+        );
+    }
+
+    #[test]
+    fn reports_circular_declarations() {
+        let text = "var a = b;\n\
+                    var b = a;\n\
+                    function h() { return h(); }\n\
+                    const k = () => k();\n\
+                    class C { get p() { return this.p; } }\n\
+                    type R<F> = F extends () => infer T ? T : never;\n\
+                    function r(): R<typeof r> { return 1; }";
+        assert_eq!(codes(text), vec![2577]);
+        let options = CheckerOptions {
+            no_implicit_any: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(
+            codes_with_options(text, options),
+            vec![7022, 7022, 7023, 7023, 7023, 2577]
+        );
+    }
+
+    #[test]
+    fn reports_circular_constraints_and_aliases() {
+        let text = "function f<T extends U, U extends T>(t: T) { t.x; }\n\
+                    type G<T extends T> = T;\n\
+                    type Grow<T> = T extends unknown[] ? Grow<[...T, 1]> : T;\n\
+                    let g: Grow<[]>;\n\
+                    namespace M { export import A = M.B; export import B = M.A; }\n\
+                    let m = M.A;";
+        assert_eq!(codes(text), vec![2313, 2313, 2339, 2313, 2589, 2303]);
+    }
+
+    #[test]
+    fn reports_only_the_unresolved_name_of_a_type_argument() {
+        let text = "function identity<T>(value: T): T { return value; }\n\
+                    identity<Foo<number>>(1);";
+        assert_eq!(codes(text), vec![2304]);
+    }
+
+    #[test]
+    fn reports_assignments_to_constants() {
+        let text = "const c = 1;\nc = 2;\nc++;\n[c] = [3];\nlet d = 1;\nd = 2;\nd++;";
+        assert_eq!(codes(text), vec![2588, 2588, 2588]);
+    }
+
+    #[test]
+    fn reports_functions_that_do_not_return_a_value() {
+        let text = "function f(): number {}\n\
+                    function g(x: boolean): number { if (x) return 1; }\n\
+                    function h(x: boolean): number | undefined { if (x) return 1; }\n\
+                    function i(): void {}\nfunction j(): any {}\n\
+                    function k(): number { throw 1; }\n\
+                    function l(): never { while (true) {} }\nfunction m(): never {}\n\
+                    const n = (): string => {};\nconst o = (): string => \"\";\n\
+                    declare function p(): number;";
+        assert_eq!(codes(text), vec![2355, 2534, 2355]);
+        let options = CheckerOptions {
+            strict_null_checks: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(
+            codes_with_options(text, options),
+            vec![2355, 2366, 2534, 2355]
+        );
+    }
+
+    #[test]
+    fn resolves_imports_and_merges_augmentations() {
+        let parse =
+            |name, text| parse_source_file(name, text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let a = parse(
+            "a.ts",
+            "export interface Box { width: number }\n\
+             export default function f() { return 1; }\n\
+             function hidden() {}\n\
+             namespace Shared { export const a = 1; }",
+        );
+        let b = parse(
+            "b.ts",
+            "import f, { Box, missing, hidden } from \"./a\";\n\
+             export * from \"./a\";\n\
+             declare module \"./a\" { interface Box { height: number } }\n\
+             declare module \"./nowhere\" {}\n\
+             declare global { var counter: number; }\n\
+             const box: Box = { width: 1 };\n\
+             const n: string = f();\n\
+             counter = \"x\";\n\
+             export {};",
+        );
+        let c = parse(
+            "c.ts",
+            "namespace Shared { export const b = \"\"; }\n\
+             const s: number = Shared.b;\n\
+             namespace Early { export const a = 1; }\n\
+             class Early {}",
+        );
+        let mut checker = Checker::new(&[Arc::clone(&a), Arc::clone(&b), Arc::clone(&c)]);
+        checker.set_resolved_modules(HashMap::from([(
+            ("b.ts".to_string(), "./a".to_string()),
+            "a.ts".to_string(),
+        )]));
+        let codes = |checker: &mut Checker, file| -> Vec<i32> {
+            let diagnostics = checker.get_diagnostics(file);
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect()
+        };
+        assert_eq!(
+            codes(&mut checker, &b),
+            [2305, 2459, 2664, 2741, 2322, 2322]
+        );
+        assert_eq!(codes(&mut checker, &c), [2322, 2434]);
+    }
+
+    #[test]
+    fn checks_ambient_declarations() {
+        let text = "declare let x = 1;\n\
+                    declare const y = 2, z = [];\n\
+                    declare function f() { return 1; }\n\
+                    declare class C { m() {} readonly r = 1; p = 1; }\n\
+                    declare namespace N { let a = 1; if (a) {} a; }";
+        assert_eq!(codes(text), [1039, 1254, 1183, 1183, 1039, 1039, 1036]);
+
+        let parse =
+            |name, text| parse_source_file(name, text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let declarations = parse(
+            "types.d.ts",
+            "let a = 1;\n\
+             function f() {}\n\
+             declare module \"*.css\" { const classes: string; export default classes; }\n\
+             declare module \"lib\" { function g(): number; }",
+        );
+        let main = parse(
+            "main.ts",
+            "import styles from \"./site.css\";\n\
+             import { g } from \"lib\";\n\
+             const n: number = styles;\n\
+             const s: string = g();",
+        );
+        let mut checker = Checker::new(&[Arc::clone(&declarations), Arc::clone(&main)]);
+        let codes = |checker: &mut Checker, file| -> Vec<i32> {
+            let diagnostics = checker.get_diagnostics(file);
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect()
+        };
+        assert_eq!(codes(&mut checker, &declarations), [1046, 1039, 1183]);
+        assert_eq!(codes(&mut checker, &main), [2322, 2322]);
+    }
+
+    #[test]
+    fn checks_export_assignments_and_import_assignments() {
+        let parse =
+            |name, text| parse_source_file(name, text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let greet = parse(
+            "greet.ts",
+            "function greet(): string { return \"\"; }\n\
+             namespace greet { export const version = 1; }\n\
+             export = greet;",
+        );
+        let main = parse(
+            "main.ts",
+            "import greet = require(\"./greet\");\n\
+             import { version } from \"./greet\";\n\
+             import * as ns from \"./greet\";\n\
+             import g from \"./greet\";\n\
+             const n: number = greet();\n\
+             const s: string = version;\n\
+             export = 1;\n\
+             export const other = 1;",
+        );
+        let codes = |module, allow_synthetic_default_imports| -> Vec<i32> {
+            let mut checker = Checker::new(&[Arc::clone(&greet), Arc::clone(&main)]);
+            checker.set_options(CheckerOptions {
+                module: Some(module),
+                allow_synthetic_default_imports,
+                ..CheckerOptions::default()
+            });
+            checker.set_resolved_modules(HashMap::from([(
+                ("main.ts".to_string(), "./greet".to_string()),
+                "greet.ts".to_string(),
+            )]));
+            let diagnostics = checker.get_diagnostics(&main);
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect()
+        };
+        assert_eq!(codes(ModuleKind::CommonJS, false), [1259, 2322, 2322, 2309]);
+        assert_eq!(codes(ModuleKind::CommonJS, true), [2322, 2322, 2309]);
+        assert_eq!(
+            codes(ModuleKind::ESNext, false),
+            [1202, 1259, 2322, 2322, 1203, 2309]
+        );
+    }
+
+    #[test]
+    fn types_dynamic_imports_and_import_meta() {
+        let parse =
+            |name, text| parse_source_file(name, text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let globals = parse(
+            "globals.ts",
+            "interface Promise<T> { then<U>(f: (value: T) => U): Promise<U>; }\n\
+             interface ImportMeta { url: string; }",
+        );
+        let a = parse("a.ts", "export const x = 1;");
+        let main = parse(
+            "main.ts",
+            "export async function f() {\n\
+                 const m = await import(\"./a\");\n\
+                 const s: string = m.x;\n\
+                 import(1);\n\
+                 import();\n\
+                 import(\"./a\", {});\n\
+                 const n: number = import.meta.url;\n\
+             }",
+        );
+        let codes = |module| -> Vec<i32> {
+            let files = [Arc::clone(&globals), Arc::clone(&a), Arc::clone(&main)];
+            let mut checker = Checker::new(&files);
+            checker.set_options(CheckerOptions {
+                module: Some(module),
+                ..CheckerOptions::default()
+            });
+            checker.set_resolved_modules(HashMap::from([(
+                ("main.ts".to_string(), "./a".to_string()),
+                "a.ts".to_string(),
+            )]));
+            let diagnostics = checker.get_diagnostics(&main);
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect()
+        };
+        assert_eq!(codes(ModuleKind::ESNext), [2322, 7036, 1450, 2322]);
+        assert_eq!(
+            codes(ModuleKind::CommonJS),
+            [2322, 7036, 1450, 1324, 2322, 1343]
+        );
+        assert_eq!(
+            codes(ModuleKind::ES2015),
+            [1323, 2322, 1323, 7036, 1323, 1323, 2322, 1343]
+        );
+    }
+
+    #[test]
+    fn resolves_re_exports_and_reports_ambiguous_stars() {
+        let parse =
+            |name, text| parse_source_file(name, text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let files = [
+            parse(
+                "a.ts",
+                "export const x = 1;\nexport const shared = 1;\nexport * from \"./c\";",
+            ),
+            parse(
+                "b.ts",
+                "export const y = \"\";\nexport const shared = \"\";",
+            ),
+            parse("c.ts", "export * from \"./a\";\nexport const z = true;"),
+            parse(
+                "d.ts",
+                "export * from \"./a\";\n\
+                 export * from \"./b\";\n\
+                 export * as ns from \"./b\";\n\
+                 export { x as renamed } from \"./e\";",
+            ),
+            parse("e.ts", "export { x } from \"./a\";\nexport * from \"./a\";"),
+            parse(
+                "f.ts",
+                "export * from \"./a\";\nexport * from \"./b\";\nexport const shared = null;",
+            ),
+            parse(
+                "main.ts",
+                "import { x, y, z, ns, renamed, shared } from \"./d\";\n\
+                 const a: string = x;\n\
+                 const b: number = y;\n\
+                 const c: number = z;\n\
+                 const d: number = ns.y;\n\
+                 const e: string = renamed;\n\
+                 const f: boolean = shared;",
+            ),
+        ];
+        let mut checker = Checker::new(&files);
+        let resolutions = [
+            ("a.ts", "./c", "c.ts"),
+            ("c.ts", "./a", "a.ts"),
+            ("d.ts", "./a", "a.ts"),
+            ("d.ts", "./b", "b.ts"),
+            ("d.ts", "./e", "e.ts"),
+            ("e.ts", "./a", "a.ts"),
+            ("f.ts", "./a", "a.ts"),
+            ("f.ts", "./b", "b.ts"),
+            ("main.ts", "./d", "d.ts"),
+        ];
+        checker.set_resolved_modules(
+            resolutions
+                .iter()
+                .map(|&(from, name, to)| ((from.to_string(), name.to_string()), to.to_string()))
+                .collect(),
+        );
+        let mut codes = |file: &Arc<Node>| -> Vec<i32> {
+            let diagnostics = checker.get_diagnostics(file);
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect()
+        };
+        assert_eq!(codes(&files[6]), [2322; 6]);
+        assert_eq!(codes(&files[3]), [2308]);
+        // The star from the module re-exporting `x` itself exports the same `x`
+        assert!(codes(&files[4]).is_empty());
+        assert!(codes(&files[5]).is_empty());
+    }
+
+    #[test]
+    fn types_unique_symbols_and_late_bound_members() {
+        let text = "interface SymbolConstructor { (): symbol; readonly iterator: unique symbol; }\n\
+                    declare var Symbol: SymbolConstructor;\n\
+                    const key = Symbol();\n\
+                    let widened = key;\n\
+                    const iterator = Symbol.iterator;\n\
+                    class C { static readonly tag: unique symbol = Symbol(); [key] = \"\"; }\n\
+                    const value = new C()[key];\n\
+                    const literal = { [key]: 1 };";
+        assert_eq!(
+            declared_types(text),
+            vec![
+                "SymbolConstructor",
+                "typeof key",
+                "symbol",
+                "typeof Symbol.iterator",
+                "string",
+                "{ [key]: number; }",
+            ]
+        );
+        assert!(codes(text).is_empty());
+        let errors = "const key = Symbol();\n\
+                      const other = Symbol();\n\
+                      const same: typeof key = other;\n\
+                      let mutable: unique symbol = Symbol();\n\
+                      type T = unique symbol;\n\
+                      class C { [Symbol()] = 1; }";
+        let prefix =
+            "interface SymbolConstructor { (): symbol; }\ndeclare var Symbol: SymbolConstructor;";
+        assert_eq!(
+            codes(&format!("{prefix}\n{errors}")),
+            [2322, 1332, 1335, 1166]
+        );
+    }
+
+    #[test]
+    fn checks_readonly_arrays_tuples_and_index_signatures() {
+        let text = "declare let ra: readonly number[];\n\
+                    declare let t: readonly [number, number];\n\
+                    let spread = [...ra];\n\
+                    type Mutable<T> = { -readonly [K in keyof T]: T[K] };\n\
+                    let mutable: Mutable<typeof ra> = [];\n\
+                    let widened: readonly (string | number)[] = t;\n\
+                    for (const x of ra) { x; }";
+        assert_eq!(
+            declared_types(text),
+            [
+                "readonly number[]",
+                "readonly [number, number]",
+                "number[]",
+                "number[]",
+                "readonly (string | number)[]",
+            ]
+        );
+        assert_eq!(reference_types(text, "x"), ["number"]);
+        assert!(codes(text).is_empty());
+        let errors = "declare let ra: readonly number[];\n\
+                      declare let t: readonly [number];\n\
+                      declare let o: { readonly a: number; readonly [k: string]: number };\n\
+                      let a: number[] = ra;\n\
+                      let b: number[] = t;\n\
+                      ra[0] = 1;\n\
+                      t[0] = 1;\n\
+                      o[\"a\"] = 1;\n\
+                      o.b = 1;\n\
+                      let c: readonly string = \"\";";
+        assert_eq!(codes(errors), [4104, 4104, 2542, 2540, 2540, 2542, 1354]);
+    }
+
+    #[test]
+    fn types_functions_from_contextual_types() {
+        let text = "declare function f(cb?: (x: number) => void): void;\n\
+                    f(a => { a; });\n\
+                    let o: { m(b: string): number } | undefined = { m(b) { b; return 1; } };\n\
+                    let s: ((c: boolean) => void)[] = [...[c => { c; }]];\n\
+                    let u: ((d: number) => void) | ((d: string) => void) = d => { d; };";
+        let (mut checker, file) = check(text);
+        assert!(checker.get_diagnostics(&file).is_empty());
+        assert_eq!(reference_types_of(&mut checker, &file, "a"), ["number"]);
+        assert_eq!(reference_types_of(&mut checker, &file, "b"), ["string"]);
+        assert_eq!(reference_types_of(&mut checker, &file, "c"), ["boolean"]);
+        // Signatures that differ in their parameters give no contextual signature
+        assert_eq!(reference_types_of(&mut checker, &file, "d"), ["any"]);
+        assert_eq!(
+            codes("let o: { m(): number } = { m() { return \"\"; } };"),
+            [2322]
+        );
+    }
+
+    #[test]
+    fn answers_queries_about_nodes_before_their_files_are_checked() {
+        let text = "declare function f(cb: (x: number) => void): void;\n\
+                    declare function g<T>(value: T, cb: (y: T) => void): void;\n\
+                    f(x => { x; });\n\
+                    g(\"\", y => { y; });";
+        // A callback's parameter is typed by the call it is passed to, resolved on demand
+        assert_eq!(reference_types(text, "x"), ["number"]);
+        assert_eq!(reference_types(text, "y"), ["string"]);
+        let (mut checker, file) = check(text);
+        let call = file.as_source_file().statements.nodes[2]
+            .as_expression_statement()
+            .expression
+            .clone();
+        let signature = checker.get_resolved_signature(&call);
+        assert!(signature.is_some());
+        assert_eq!(checker.get_resolved_signature(&call), signature);
+        assert!(checker.get_diagnostics(&file).is_empty());
+    }
+
+    #[test]
+    fn checks_only_the_statement_at_a_position() {
+        let text = "let a: string = 1;\nlet b: number = a;\nlet c: string = 2;";
+        let (mut checker, file) = check(text);
+        let diagnostics = checker.get_diagnostics_at_position(&file, text.find("b:").unwrap());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pos(), text.find("b:").unwrap());
+        // The statements around it are left until the file is checked
+        assert_eq!(checker.diagnostics[&file.node_id()].len(), 1);
+        let codes: Vec<_> = checker
+            .get_diagnostics(&file)
+            .iter()
+            .map(|d| d.code)
+            .collect();
+        assert_eq!(codes, [2322, 2322, 2322]);
+    }
+
+    #[test]
+    fn checks_the_rest_of_a_file_after_checking_is_cancelled() {
+        let (mut checker, file) = check("let a: string = 1;\nlet b: number = \"b\";");
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+        checker.set_cancellation_token(cancellation_token);
+        assert!(checker.get_diagnostics(&file).is_empty());
+        checker.set_cancellation_token(CancellationToken::new());
+        let codes: Vec<_> = checker
+            .get_diagnostics(&file)
+            .iter()
+            .map(|d| d.code)
+            .collect();
+        assert_eq!(codes, [2322, 2322]);
+    }
+
+    #[test]
+    fn types_jsx_attributes_from_component_props() {
+        let text = "declare namespace JSX {\n\
+                        interface IntrinsicElements { div: { onClick?: (e: number) => void } }\n\
+                    }\n\
+                    declare function Comp(props: { onSelect: (v: string) => void }): any;\n\
+                    let a = <div onClick={e => { e; }} />;\n\
+                    let b = <Comp onSelect={v => { v; }}><div>{(w => w)}</div></Comp>;\n\
+                    let c = <Missing />;";
+        let file = parse_source_file("test.tsx", text, ScriptTarget::ESNext, ScriptKind::TSX);
+        let mut checker = Checker::new(std::slice::from_ref(&file));
+        let codes: Vec<i32> = checker
+            .get_diagnostics(&file)
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        assert_eq!(codes, [2304]);
+        assert_eq!(reference_types_of(&mut checker, &file, "e"), ["number"]);
+        assert_eq!(reference_types_of(&mut checker, &file, "v"), ["string"]);
+        assert_eq!(reference_types_of(&mut checker, &file, "w"), ["any"]);
+    }
+
+    #[test]
+    fn widens_fresh_literal_types_in_mutable_locations() {
+        let text = "const c = \"a\";\n\
+                    let x = c;\n\
+                    declare const d: \"a\";\n\
+                    let y = d;\n\
+                    const e = \"a\" as const;\n\
+                    let z = e;\n\
+                    let t: typeof c = \"a\";\n\
+                    let u = t;\n\
+                    const o = { k: 1 } as const;\n\
+                    let w = o.k;\n\
+                    const n = -1;\n\
+                    let m = n;";
+        assert_eq!(
+            declared_types(text),
+            vec![
+                "\"a\"",
+                "string",
+                "\"a\"",
+                "\"a\"",
+                "\"a\"",
+                "\"a\"",
+                "\"a\"",
+                "\"a\"",
+                "{ readonly k: 1; }",
+                "1",
+                "-1",
+                "number"
+            ]
+        );
+    }
+
+    #[test]
+    fn evolves_the_types_of_arrays_declared_empty() {
+        let text = "interface Array<T> { push(...items: T[]): number; length: number;\n\
+                    [n: number]: T; }\n\
+                    declare function cond(): boolean;\n\
+                    const a = [];\n\
+                    a.push(1);\n\
+                    a;\n\
+                    if (cond()) { a.push(\"x\"); } else { a[0] = true; }\n\
+                    a;\n\
+                    const b = [];\n\
+                    b.length;\n\
+                    b;";
+        let (mut checker, file) = check(text);
+        checker.set_options(CheckerOptions {
+            no_implicit_any: true,
+            ..CheckerOptions::default()
+        });
+        let codes: Vec<i32> = checker
+            .get_diagnostics(&file)
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        assert_eq!(codes, vec![7034, 7005]);
+        assert_eq!(
+            reference_types_of(&mut checker, &file, "a"),
+            vec![
+                "any[]",
+                "number[]",
+                "any[]",
+                "any[]",
+                "(string | number | boolean)[]"
+            ]
+        );
+        assert_eq!(
+            reference_types_of(&mut checker, &file, "b"),
+            vec!["any[]", "any[]"]
+        );
+        // Without noImplicitAny, an empty array literal is simply `any[]`
+        assert_eq!(declared_types(text), vec!["any[]", "any[]"]);
+    }
+
+    #[test]
+    fn elaborates_the_constituent_discriminants_pick_out() {
+        let members: Vec<String> = (0..12)
+            .map(|i| format!("{{ kind: \"k{i}\"; v: {} }}", ["number", "boolean"][i % 2]))
+            .collect();
+        let text = format!(
+            "type S = {{ kind: \"b\"; v: boolean }} | {{ kind: \"a\"; v: number }};\n\
+             declare let s: {{ kind: \"b\"; v: number }};\n\
+             let t: S = s;\n\
+             type L = {};\n\
+             declare let l: {{ kind: \"k4\"; v: string }};\n\
+             let m: L = l;\n\
+             let n: L = {{ kind: \"k5\", v: true }};",
+            members.join(" | ")
+        );
+        let (mut checker, file) = check(&text);
+        let diagnostics = checker.get_diagnostics(&file);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(
+            diagnostics[0]
+                .message_text
+                .contains("to type '{ kind: \"b\"; v: boolean; }'")
+        );
+        assert!(
+            diagnostics[1]
+                .message_text
+                .contains("to type '{ kind: \"k4\"; v: number; }'")
+        );
+    }
+
+    fn codes_with_options(text: &str, options: CheckerOptions) -> Vec<i32> {
+        let (mut checker, file) = check(text);
+        checker.set_options(options);
+        checker
+            .get_diagnostics(&file)
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect()
+    }
+
+    #[test]
+    fn checks_no_implicit_any() {
+        let text = "function f(a, b: number, ...rest) {}\n\
+                    declare function g(x);\n\
+                    const h: (x: number) => void = (x) => {};\n\
+                    declare let v;\n\
+                    let w = null;\n\
+                    class C { p; q = 1; }\n\
+                    interface I { m(); (): void; new (); }\n\
+                    declare const o: { a: number };\n\
+                    o[\"b\"];";
+        assert!(codes(text).is_empty());
+        let options = CheckerOptions {
+            no_implicit_any: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(
+            codes_with_options(text, options),
+            vec![7006, 7019, 7010, 7006, 7005, 7005, 7008, 7010, 7013, 7053]
+        );
+    }
+
+    #[test]
+    fn compares_function_parameters_contravariantly_under_strict_function_types() {
+        let text = "declare let f1: (x: string | number) => void;\n\
+                    declare let f2: (x: string) => void;\n\
+                    f1 = f2;\n\
+                    f2 = f1;\n\
+                    interface M { m(x: string | number): void }\n\
+                    declare let m1: M;\n\
+                    declare let m2: { m(x: string): void };\n\
+                    m1 = m2;";
+        assert!(codes(text).is_empty());
+        let options = CheckerOptions {
+            strict_function_types: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(codes_with_options(text, options), vec![2322]);
+    }
+
+    #[test]
+    fn checks_implicit_this_and_catch_variables() {
+        let text = "function f() { return this; }\n\
+                    function g(this: { x: number }) { return this.x; }\n\
+                    const o = { x: 1, m: function () { return this.x; } };\n\
+                    try {} catch (e) { e.foo; }";
+        assert!(codes(text).is_empty());
+        let options = CheckerOptions {
+            no_implicit_this: true,
+            use_unknown_in_catch_variables: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(codes_with_options(text, options), vec![2683, 2571]);
+    }
+
+    #[test]
+    fn types_bind_call_apply_by_callable_function_under_strict_bind_call_apply() {
+        let text = "interface Function { apply(): string }\n\
+                    interface CallableFunction { apply(): number }\n\
+                    interface NewableFunction { apply(): boolean }\n\
+                    declare function fn(): void;\n\
+                    declare class K {}\n\
+                    const r = fn.apply();\n\
+                    const s = K.apply();";
+        assert_eq!(declared_types(text), ["string", "string"]);
+        let (mut checker, file) = check(text);
+        checker.options.strict_bind_call_apply = true;
+        checker.get_diagnostics(&file);
+        let types: Vec<String> = file.as_source_file().statements.nodes[5..]
+            .iter()
+            .map(|statement| {
+                let list = &statement.as_variable_statement().declaration_list;
+                let name = list.as_variable_declaration_list().declarations.nodes[0]
+                    .name()
+                    .unwrap()
+                    .clone();
+                let type_ = checker.get_type_at_location(&name);
+                checker.type_to_string(type_)
+            })
+            .collect();
+        assert_eq!(types, ["number", "boolean"]);
+    }
+
+    #[test]
+    fn checks_exact_optional_property_types() {
+        let text = "declare const o: { a?: number };\n\
+                    const s = o.a;\n\
+                    o.a = undefined;\n\
+                    o.a = 1;\n\
+                    const p: { a?: number } = { a: undefined };\n\
+                    const q: { a?: number } = {};\n\
+                    const r: { a?: number | undefined } = { a: undefined };";
+        let options = CheckerOptions {
+            strict_null_checks: true,
+            ..CheckerOptions::default()
+        };
+        assert!(codes_with_options(text, options.clone()).is_empty());
+        let options = CheckerOptions {
+            exact_optional_property_types: true,
+            ..options
+        };
+        let (mut checker, file) = check(text);
+        checker.set_options(options);
+        let diagnostics = checker.get_diagnostics(&file);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect::<Vec<_>>(),
+            vec![2412, 2322]
+        );
+        let name = file.as_source_file().statements.nodes[1]
+            .as_variable_statement()
+            .declaration_list
+            .as_variable_declaration_list()
+            .declarations
+            .nodes[0]
+            .name()
+            .unwrap()
+            .clone();
+        let type_ = checker.get_type_at_location(&name);
+        assert_eq!(checker.type_to_string(type_), "number | undefined");
+    }
+
+    #[test]
+    fn computes_enum_member_values() {
+        let text = "enum E { A, B = 5, C, D = B * 2 + 1, E = \"x\" + \"y\", F = `${E}!`, G = ~0, H = 1 << 3, I = E.C - 1 }";
+        let (mut checker, file) = check(text);
+        let members = file.as_source_file().statements.nodes[0]
+            .as_enum_declaration()
+            .members
+            .nodes
+            .clone();
+        let values: Vec<Option<LiteralValue>> = members
+            .iter()
+            .map(|member| checker.get_constant_value(member))
+            .collect();
+        let number = |value: f64| Some(LiteralValue::Number(Number(value)));
+        let string = |value: &str| Some(LiteralValue::String(value.to_string()));
+        assert_eq!(
+            values,
+            [
+                number(0.0),
+                number(5.0),
+                number(6.0),
+                number(11.0),
+                string("xy"),
+                string("xy!"),
+                number(-1.0),
+                number(8.0),
+                number(5.0),
+            ]
+        );
+        assert!(checker.get_diagnostics(&file).is_empty());
+    }
+
+    #[test]
+    fn resolves_enum_literal_types() {
+        let text = "enum E { A, B }\n\
+                    enum S { X = \"x\" }\n\
+                    declare let a: E;\n\
+                    declare let b: E.A;\n\
+                    let c = E.B;\n\
+                    const d = E.B;\n\
+                    declare let e: S;\n\
+                    declare let f: E | boolean;";
+        assert_eq!(
+            declared_types(text),
+            ["E", "E.A", "E", "E.B", "S", "boolean | E"]
+        );
+        assert_eq!(
+            codes(
+                "enum E { A, B }\nenum S { X = \"x\" }\ndeclare let n: number;\n\
+                 let a: E = 1;\nlet b: E = n;\nlet c: E = 3;\nlet d: E.A = E.B;\nlet e: S = \"x\";\nlet f: number = E.A;"
+            ),
+            vec![2322, 2322, 2322]
+        );
+    }
+
+    #[test]
+    fn checks_enum_declarations() {
+        assert_eq!(
+            codes(
+                "enum A { X = \"a\", Y }\n\
+                 declare function f(): number;\n\
+                 const enum B { X = f() }\n\
+                 const enum C { X = 1 / 0, Y = 0 / 0 }\n\
+                 enum D { X = Y, Y = 1 }\n\
+                 declare const s: string;\n\
+                 enum E { X = s, Y = f() }"
+            ),
+            vec![1061, 2474, 2477, 2478, 2651, 18033]
+        );
+        assert_eq!(
+            codes(
+                "const enum C { A }\ndeclare let k: string;\n\
+                 let a = C.A;\nlet b = C[\"A\"];\nlet c = C;\nlet d = C[k];"
+            ),
+            vec![2475, 2476]
+        );
+        assert_eq!(
+            codes("enum E { A }\nenum E { B }\nenum E { C = 1 }"),
+            vec![2432]
+        );
+    }
+
+    #[test]
+    fn suggests_similarly_spelled_names_and_properties() {
+        let (mut checker, file) = check(
+            "const length = 1;\n\
+             lenght;\n\
+             declare const o: { length: number };\n\
+             o.lenght;\n\
+             interface Foo {}\n\
+             let f: Fooo;\n\
+             let myValue = 1;\n\
+             myvalue;\n\
+             abcdef;",
+        );
+        let diagnostics = checker.get_diagnostics(&file);
+        let codes: Vec<i32> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        assert_eq!(codes, vec![2552, 2551, 2552, 2552, 2304]);
+        assert_eq!(
+            diagnostics[0].message_text,
+            "Cannot find name 'lenght'. Did you mean 'length'?"
+        );
+        assert_eq!(diagnostics[0].related_information[0].code, 2728);
+        assert_eq!(
+            diagnostics[1].message_text,
+            "Property 'lenght' does not exist on type '{ length: number; }'. Did you mean 'length'?"
+        );
+        assert_eq!(
+            diagnostics[2].message_text,
+            "Cannot find name 'Fooo'. Did you mean 'Foo'?"
+        );
+        assert_eq!(
+            diagnostics[3].message_text,
+            "Cannot find name 'myvalue'. Did you mean 'myValue'?"
+        );
+    }
+
+    #[test]
+    fn reports_unused_locals_and_parameters() {
+        let text = "import { a, b } from \"m\";\n\
+                    import * as ns from \"m\";\n\
+                    import d, { e } from \"m\";\n\
+                    export function f(x: number, _y: number, z: number) {\n\
+                        let unused = 1;\n\
+                        let u1 = 1, u2 = 2;\n\
+                        const { p, q } = { p: 1, q: 2 };\n\
+                        let written = 0;\n\
+                        written = 1;\n\
+                        for (const _k in {}) {}\n\
+                        try {} catch (err) {}\n\
+                        return a + x;\n\
+                    }\n\
+                    function rec(): void { rec(); }\n\
+                    interface Unused {}\n\
+                    export class K {\n\
+                        private used = 1;\n\
+                        private never = 2;\n\
+                        private writeOnly = 3;\n\
+                        #hidden = 4;\n\
+                        constructor(private kept: number, private dropped: number) {}\n\
+                        m() { this.writeOnly = 5; return this.used + this.kept; }\n\
+                    }";
+        assert!(codes(text).is_empty());
+        let parameters = CheckerOptions {
+            no_unused_parameters: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(codes_with_options(text, parameters), vec![6133]);
+        let options = CheckerOptions {
+            no_unused_locals: true,
+            no_unused_parameters: true,
+            ..CheckerOptions::default()
+        };
+        assert_eq!(
+            codes_with_options(text, options),
+            vec![
+                6133, 6133, 6192, 6133, 6133, 6199, 6198, 6133, 6133, 6196, 6133, 6133, 6133, 6138
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_types_with_flags() {
+        let properties: Vec<String> = (0..30).map(|i| format!("p{i}: number;")).collect();
+        let text = format!(
+            "type U = \"a\" | \"b\";\n\
+             declare let u: U;\n\
+             declare let a: number[];\n\
+             declare let o: {{ {} }};",
+            properties.join(" ")
+        );
+        let (mut checker, file) = check(&text);
+        let types: Vec<TypeId> = file.as_source_file().statements.nodes[1..]
+            .iter()
+            .map(|statement| {
+                let list = &statement.as_variable_statement().declaration_list;
+                let name = list.as_variable_declaration_list().declarations.nodes[0]
+                    .name()
+                    .unwrap()
+                    .clone();
+                checker.get_type_at_location(&name)
+            })
+            .collect();
+        assert_eq!(checker.type_to_string(types[0]), "U");
+        assert_eq!(
+            checker.type_to_string_with_flags(types[0], TypeFormatFlags::IN_TYPE_ALIAS),
+            "\"a\" | \"b\""
+        );
+        assert_eq!(
+            checker.type_to_string_with_flags(
+                types[0],
+                TypeFormatFlags::IN_TYPE_ALIAS
+                    | TypeFormatFlags::USE_SINGLE_QUOTES_FOR_STRING_LITERAL_TYPE
+            ),
+            "'a' | 'b'"
+        );
+        assert_eq!(checker.type_to_string(types[1]), "number[]");
+        assert_eq!(
+            checker
+                .type_to_string_with_flags(types[1], TypeFormatFlags::WRITE_ARRAY_AS_GENERIC_TYPE),
+            "Array<number>"
+        );
+        let shown: Vec<&str> = properties[..13].iter().map(String::as_str).collect();
+        assert_eq!(
+            checker.type_to_string(types[2]),
+            format!("{{ {} ... 16 more ...; p29: number; }}", shown.join(" "))
+        );
+        assert_eq!(
+            checker.type_to_string_with_flags(types[2], TypeFormatFlags::NO_TRUNCATION),
+            format!("{{ {} }}", properties.join(" "))
+        );
+    }
+
+    #[test]
+    fn names_symbols_by_where_they_are_referenced() {
+        let lib = parse_source_file(
+            "src/lib/b.ts",
+            "export interface B {}",
+            ScriptTarget::ESNext,
+            ScriptKind::Unknown,
+        );
+        let file = parse_source_file(
+            "src/a.ts",
+            "namespace N {\n\
+             \x20   export namespace M {\n\
+             \x20       export interface I {}\n\
+             \x20       export let i: I;\n\
+             \x20   }\n\
+             \x20   interface Hidden {}\n\
+             \x20   export let h: Hidden;\n\
+             }\n\
+             export let x: N.M.I;",
+            ScriptTarget::ESNext,
+            ScriptKind::Unknown,
+        );
+        let mut checker = Checker::new(&[Arc::clone(&lib), Arc::clone(&file)]);
+        let identifier = |file: &Arc<Node>, text: &str| {
+            let mut found = None;
+            for_each_descendant(file, &mut |node| {
+                if found.is_none() && node.kind == SyntaxKind::Identifier && node.text() == text {
+                    found = Some(Arc::clone(node));
+                }
+                found.is_some()
+            });
+            found.unwrap()
+        };
+        let i = identifier(&file, "I");
+        let i_symbol = checker.get_symbol_at_location(&i).unwrap();
+        let hidden = identifier(&file, "Hidden");
+        let hidden_symbol = checker.get_symbol_at_location(&hidden).unwrap();
+        let b_symbol = checker
+            .get_symbol_at_location(&identifier(&lib, "B"))
+            .unwrap();
+        let i_reference = identifier(&file, "i");
+        let h_reference = identifier(&file, "h");
+        let x_reference = identifier(&file, "x");
+
+        assert_eq!(
+            checker.symbol_to_string(i_symbol, None, SymbolFlags::TYPE),
+            "N.M.I"
+        );
+        assert_eq!(
+            checker.symbol_to_string(i_symbol, Some(&i_reference), SymbolFlags::TYPE),
+            "I"
+        );
+        assert_eq!(
+            checker.symbol_to_string(i_symbol, Some(&h_reference), SymbolFlags::TYPE),
+            "M.I"
+        );
+        assert_eq!(
+            checker.symbol_to_string(b_symbol, Some(&x_reference), SymbolFlags::TYPE),
+            "import(\"./lib/b\").B"
+        );
+        assert_eq!(
+            checker.symbol_to_string(b_symbol, None, SymbolFlags::TYPE),
+            "B"
+        );
+        let x_type = checker.get_type_at_location(&x_reference);
+        assert_eq!(checker.type_to_string(x_type), "N.M.I");
+        assert_eq!(
+            checker.type_to_string_in_scope(x_type, Some(&i_reference), TypeFormatFlags::NONE),
+            "I"
+        );
+
+        let accessibility = |checker: &mut Checker, symbol, location| {
+            checker
+                .is_symbol_accessible(symbol, location, SymbolFlags::TYPE)
+                .accessibility
+        };
+        assert_eq!(
+            accessibility(&mut checker, i_symbol, &x_reference),
+            SymbolAccessibility::Accessible
+        );
+        assert_eq!(
+            accessibility(&mut checker, b_symbol, &x_reference),
+            SymbolAccessibility::Accessible
+        );
+        assert_eq!(
+            accessibility(&mut checker, hidden_symbol, &h_reference),
+            SymbolAccessibility::Accessible
+        );
+        assert_eq!(
+            checker.is_symbol_accessible(hidden_symbol, &x_reference, SymbolFlags::TYPE),
+            SymbolAccessibilityResult {
+                accessibility: SymbolAccessibility::NotAccessible,
+                error_symbol_name: Some("Hidden".to_string()),
+                error_module_name: None,
+            }
+        );
+    }
+}
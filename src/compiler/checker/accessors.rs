@@ -0,0 +1,289 @@
+//! Get and set accessors, and properties with the `accessor` modifier
+//!
+//! A property with accessors is read through its getter and written through its setter, whose
+//! parameter may declare a different type than the getter returns. A getter without a setter
+//! makes the property readonly. The two halves of a pair must agree on whether they are
+//! abstract, and the getter must be at least as visible as the setter.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{is_class_like, is_function_like_kind};
+use crate::compiler::ast::{CheckFlags, Node, NodeFlags, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics;
+use crate::compiler::scanner::ScriptTarget;
+
+use super::{Checker, TypeId};
+
+impl Checker {
+    /// Checks a get or set accessor, along with the pair it forms when checking a getter
+    pub(super) fn check_accessor_declaration(&mut self, node: &Arc<Node>) {
+        self.check_grammar_accessor(node);
+        if node.kind == SyntaxKind::GetAccessor
+            && let Some(name) = node.name().cloned()
+        {
+            self.check_getter_returns(node, &name);
+            self.check_accessor_pair(node, &name);
+        }
+        self.check_function_like_declaration(node);
+    }
+
+    /// Reports what the syntax of accessors allows but the language does not: a getter takes
+    /// no parameters, and a setter exactly one, which is required and has no default
+    fn check_grammar_accessor(&mut self, node: &Arc<Node>) {
+        let Some(name) = node.name().cloned() else {
+            return;
+        };
+        if let Some(body) = node.body() {
+            if node.has_modifier(SyntaxKind::AbstractKeyword) {
+                self.error(
+                    &name,
+                    diagnostics::AN_ABSTRACT_ACCESSOR_CANNOT_HAVE_AN_IMPLEMENTATION_1318,
+                    &[],
+                );
+                return;
+            }
+            if node.parent().is_some_and(|parent| {
+                matches!(
+                    parent.kind,
+                    SyntaxKind::TypeLiteral | SyntaxKind::InterfaceDeclaration
+                )
+            }) {
+                self.error(
+                    body,
+                    diagnostics::AN_IMPLEMENTATION_CANNOT_BE_DECLARED_IN_AMBIENT_CONTEXTS_1183,
+                    &[],
+                );
+                return;
+            }
+        }
+        if node.type_parameters().is_some() {
+            self.error(
+                &name,
+                diagnostics::AN_ACCESSOR_CANNOT_HAVE_TYPE_PARAMETERS_1094,
+                &[],
+            );
+            return;
+        }
+        let parameters: Vec<Arc<Node>> = node
+            .parameters()
+            .into_iter()
+            .flatten()
+            .filter(|parameter| !is_this_parameter(parameter))
+            .cloned()
+            .collect();
+        if node.kind == SyntaxKind::GetAccessor {
+            if !parameters.is_empty() {
+                self.error(
+                    &name,
+                    diagnostics::A_GET_ACCESSOR_CANNOT_HAVE_PARAMETERS_1054,
+                    &[],
+                );
+            }
+            return;
+        }
+        let [parameter] = parameters.as_slice() else {
+            self.error(
+                &name,
+                diagnostics::A_SET_ACCESSOR_MUST_HAVE_EXACTLY_ONE_PARAMETER_1049,
+                &[],
+            );
+            return;
+        };
+        if node.type_node().is_some() {
+            self.error(
+                &name,
+                diagnostics::A_SET_ACCESSOR_CANNOT_HAVE_A_RETURN_TYPE_ANNOTATION_1095,
+                &[],
+            );
+            return;
+        }
+        let parameter_data = parameter.as_parameter();
+        if let Some(dot_dot_dot) = &parameter_data.dot_dot_dot_token {
+            self.error(
+                dot_dot_dot,
+                diagnostics::A_SET_ACCESSOR_CANNOT_HAVE_REST_PARAMETER_1053,
+                &[],
+            );
+        } else if let Some(question) = &parameter_data.question_token {
+            self.error(
+                question,
+                diagnostics::A_SET_ACCESSOR_CANNOT_HAVE_AN_OPTIONAL_PARAMETER_1051,
+                &[],
+            );
+        } else if parameter_data.initializer.is_some() {
+            self.error(
+                &name,
+                diagnostics::A_SET_ACCESSOR_PARAMETER_CANNOT_HAVE_AN_INITIALIZER_1052,
+                &[],
+            );
+        }
+    }
+
+    /// Reports a getter whose body can end without returning a value
+    fn check_getter_returns(&mut self, node: &Arc<Node>, name: &Arc<Node>) {
+        let Some(body) = node.body() else {
+            return;
+        };
+        if node.flags.contains(NodeFlags::AMBIENT)
+            || !self.bindings.has_implicit_return(node)
+            || has_return_statement(body)
+        {
+            return;
+        }
+        self.error(
+            name,
+            diagnostics::A_GET_ACCESSOR_MUST_RETURN_A_VALUE_2378,
+            &[],
+        );
+    }
+
+    /// Checks that a getter and the setter declared with it are both abstract or neither, and
+    /// that the getter is not less visible than the setter
+    fn check_accessor_pair(&mut self, getter: &Arc<Node>, getter_name: &Arc<Node>) {
+        let Some(symbol) = self.get_symbol_of_declaration(getter) else {
+            return;
+        };
+        let Some(setter) = self
+            .symbol(symbol)
+            .declarations
+            .iter()
+            .find(|declaration| declaration.kind == SyntaxKind::SetAccessor)
+            .cloned()
+        else {
+            return;
+        };
+        let Some(setter_name) = setter.name().cloned() else {
+            return;
+        };
+        let report = |checker: &mut Checker, message| {
+            checker.error(getter_name, message, &[]);
+            checker.error(&setter_name, message, &[]);
+        };
+        if getter.has_modifier(SyntaxKind::AbstractKeyword)
+            != setter.has_modifier(SyntaxKind::AbstractKeyword)
+        {
+            report(
+                self,
+                diagnostics::ACCESSORS_MUST_BOTH_BE_ABSTRACT_OR_NON_ABSTRACT_2676,
+            );
+        }
+        let less_visible = if getter.has_modifier(SyntaxKind::PrivateKeyword) {
+            !setter.has_modifier(SyntaxKind::PrivateKeyword)
+        } else if getter.has_modifier(SyntaxKind::ProtectedKeyword) {
+            !setter.has_modifier(SyntaxKind::PrivateKeyword)
+                && !setter.has_modifier(SyntaxKind::ProtectedKeyword)
+        } else {
+            false
+        };
+        if less_visible {
+            report(
+                self,
+                diagnostics::A_GET_ACCESSOR_MUST_BE_AT_LEAST_AS_ACCESSIBLE_AS_THE_SETTER_2808,
+            );
+        }
+    }
+
+    /// Checks where the `accessor` modifier is, which only declares class properties, and only
+    /// required ones on targets that have the class fields it compiles to
+    pub(super) fn check_accessor_modifier(&mut self, node: &Arc<Node>) {
+        let Some(modifier) = node.modifiers().and_then(|modifiers| {
+            modifiers
+                .iter()
+                .find(|modifier| modifier.kind == SyntaxKind::AccessorKeyword)
+                .cloned()
+        }) else {
+            return;
+        };
+        if node.kind != SyntaxKind::PropertyDeclaration {
+            self.error(
+                &modifier,
+                diagnostics::ACCESSOR_MODIFIER_CAN_ONLY_APPEAR_ON_A_PROPERTY_DECLARATION_1275,
+                &[],
+            );
+            return;
+        }
+        if let Some(question) = node
+            .as_property_declaration()
+            .postfix_token
+            .as_ref()
+            .filter(|token| token.kind == SyntaxKind::QuestionToken)
+        {
+            self.error(
+                question,
+                diagnostics::AN_ACCESSOR_PROPERTY_CANNOT_BE_DECLARED_OPTIONAL_1276,
+                &[],
+            );
+        }
+        let below_es2015 = self
+            .options
+            .target
+            .is_some_and(|target| target < ScriptTarget::ES2015);
+        if below_es2015 && !node.flags.contains(NodeFlags::AMBIENT) {
+            let error_node = node.name().cloned().unwrap_or_else(|| Arc::clone(node));
+            self.error(
+                &error_node,
+                diagnostics::PROPERTIES_WITH_THE_ACCESSOR_MODIFIER_ARE_ONLY_AVAILABLE_WHEN_TARGETING_ECMASCRIPT_2015_AND_HIGHER_18045,
+                &[],
+            );
+        }
+    }
+
+    /// Gets the type a property accepts when it is written: the type of the parameter of its
+    /// setter, when that is declared, or else the type it has when read
+    pub(super) fn get_write_type_of_symbol(&mut self, symbol: SymbolId) -> TypeId {
+        if self
+            .symbol(symbol)
+            .check_flags
+            .contains(CheckFlags::INSTANTIATED)
+        {
+            let links = self.symbol_links(symbol).clone();
+            if let (Some(target), Some(mapper)) = (links.target, links.mapper) {
+                let type_ = self.get_write_type_of_symbol(target);
+                return self.instantiate_type(type_, &mapper);
+            }
+        }
+        if !self
+            .symbol(symbol)
+            .flags
+            .contains(SymbolFlags::SET_ACCESSOR)
+        {
+            return self.get_type_of_symbol(symbol);
+        }
+        let setter_parameter_type = self
+            .symbol(symbol)
+            .declarations
+            .iter()
+            .find(|declaration| declaration.kind == SyntaxKind::SetAccessor)
+            .and_then(|setter| setter.parameters())
+            .and_then(|parameters| {
+                parameters
+                    .iter()
+                    .find(|parameter| !is_this_parameter(parameter))
+            })
+            .and_then(|parameter| parameter.type_node())
+            .cloned();
+        match setter_parameter_type {
+            Some(type_node) => self.get_type_from_type_node(&type_node),
+            None => self.get_type_of_symbol(symbol),
+        }
+    }
+}
+
+/// Determines if a parameter is the `this` parameter, which declares the type of `this` rather
+/// than an argument
+fn is_this_parameter(parameter: &Node) -> bool {
+    let name = &parameter.as_parameter().name;
+    name.kind == SyntaxKind::Identifier && name.text() == "this"
+}
+
+/// Determines if a function body has a `return` statement of its own, not counting those of
+/// nested functions and classes
+pub(super) fn has_return_statement(node: &Arc<Node>) -> bool {
+    node.for_each_child(&mut |child| {
+        child.kind == SyntaxKind::ReturnStatement
+            || !is_function_like_kind(child.kind)
+                && !is_class_like(child)
+                && child.kind != SyntaxKind::ClassStaticBlockDeclaration
+                && has_return_statement(child)
+    })
+}
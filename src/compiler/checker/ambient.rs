@@ -0,0 +1,190 @@
+//! Ambient declarations: those of declaration files and those marked `declare`
+//!
+//! An ambient declaration only describes what exists at runtime, so it has no implementation:
+//! its functions have no bodies, its blocks no statements, and its variables and properties no
+//! initializers, except that a constant may be initialized with the literal it stands for. The
+//! top-level declarations of a declaration file must say they are ambient or exported.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{
+    get_source_file_of_node, is_const_variable, is_function_like_kind,
+};
+use crate::compiler::ast::{Node, NodeFlags, SyntaxKind};
+use crate::compiler::binder::is_entity_name_expression;
+use crate::compiler::diagnostics;
+use crate::compiler::scanner::Scanner;
+use crate::compiler::text::TextRange;
+
+use super::Checker;
+
+impl Checker {
+    /// Reports what an ambient declaration cannot contain: an initializer that is not a
+    /// constant literal, a function body, or a statement
+    pub(super) fn check_ambient_context(&mut self, node: &Arc<Node>) {
+        if !node.flags.contains(NodeFlags::AMBIENT) {
+            return;
+        }
+        match node.kind {
+            SyntaxKind::VariableDeclaration | SyntaxKind::PropertyDeclaration => {
+                self.check_ambient_initializer(node)
+            }
+            SyntaxKind::Block
+            | SyntaxKind::EmptyStatement
+            | SyntaxKind::ExpressionStatement
+            | SyntaxKind::IfStatement
+            | SyntaxKind::DoStatement
+            | SyntaxKind::WhileStatement
+            | SyntaxKind::ForStatement
+            | SyntaxKind::ForInStatement
+            | SyntaxKind::ForOfStatement
+            | SyntaxKind::ContinueStatement
+            | SyntaxKind::BreakStatement
+            | SyntaxKind::ReturnStatement
+            | SyntaxKind::WithStatement
+            | SyntaxKind::SwitchStatement
+            | SyntaxKind::LabeledStatement
+            | SyntaxKind::ThrowStatement
+            | SyntaxKind::TryStatement
+            | SyntaxKind::DebuggerStatement => self.check_statement_in_ambient_context(node),
+            _ => {}
+        }
+    }
+
+    /// Reports the initializer of an ambient variable or property, which only a constant
+    /// without an annotation may have, and only to give it the type of a literal
+    fn check_ambient_initializer(&mut self, node: &Arc<Node>) {
+        let Some(initializer) = node.initializer().cloned() else {
+            return;
+        };
+        let is_const = match node.kind {
+            SyntaxKind::VariableDeclaration => is_const_variable(node),
+            _ => node.has_modifier(SyntaxKind::ReadonlyKeyword),
+        };
+        if !is_const || node.type_node().is_some() {
+            self.error(
+                &initializer,
+                diagnostics::INITIALIZERS_ARE_NOT_ALLOWED_IN_AMBIENT_CONTEXTS_1039,
+                &[],
+            );
+        } else if !is_constant_literal(&initializer) {
+            self.error(
+                &initializer,
+                diagnostics::A_CONST_INITIALIZER_IN_AN_AMBIENT_CONTEXT_MUST_BE_A_STRING_OR_NUMERIC_LITERAL_OR_LITERAL_ENUM_REFERENCE_1254,
+                &[],
+            );
+        }
+    }
+
+    /// Reports the body of an ambient function or a statement of an ambient block, once for
+    /// each block rather than for each of its statements
+    fn check_statement_in_ambient_context(&mut self, node: &Arc<Node>) {
+        let Some(parent) = node.parent() else {
+            return;
+        };
+        if is_function_like_kind(parent.kind) {
+            if !self.node_links(node).reported_statement_in_ambient_context {
+                self.node_links(node).reported_statement_in_ambient_context = true;
+                self.error_at_range(
+                    node,
+                    get_first_token_range(node),
+                    diagnostics::AN_IMPLEMENTATION_CANNOT_BE_DECLARED_IN_AMBIENT_CONTEXTS_1183,
+                    &[],
+                );
+            }
+            return;
+        }
+        // Statements nested in other statements are covered by the outermost one
+        if !matches!(
+            parent.kind,
+            SyntaxKind::Block | SyntaxKind::ModuleBlock | SyntaxKind::SourceFile
+        ) || self
+            .node_links(&parent)
+            .reported_statement_in_ambient_context
+        {
+            return;
+        }
+        self.node_links(&parent)
+            .reported_statement_in_ambient_context = true;
+        self.error_at_range(
+            node,
+            get_first_token_range(node),
+            diagnostics::STATEMENTS_ARE_NOT_ALLOWED_IN_AMBIENT_CONTEXTS_1036,
+            &[],
+        );
+    }
+
+    /// Reports the first top-level declaration of a declaration file that has neither a
+    /// `declare` nor an `export` modifier
+    pub(super) fn check_declaration_file_top_level(&mut self, file: &Arc<Node>) {
+        let statements = &file.as_source_file().statements;
+        let Some(statement) = statements.iter().find(|statement| {
+            matches!(
+                statement.kind,
+                SyntaxKind::VariableStatement
+                    | SyntaxKind::FunctionDeclaration
+                    | SyntaxKind::ClassDeclaration
+                    | SyntaxKind::EnumDeclaration
+                    | SyntaxKind::ModuleDeclaration
+            ) && !statement.has_modifier(SyntaxKind::DeclareKeyword)
+                && !statement.has_modifier(SyntaxKind::ExportKeyword)
+                && !statement.has_modifier(SyntaxKind::DefaultKeyword)
+        }) else {
+            return;
+        };
+        self.error_at_range(
+            statement,
+            get_first_token_range(statement),
+            diagnostics::TOP_LEVEL_DECLARATIONS_IN_D_TS_FILES_MUST_START_WITH_EITHER_A_DECLARE_OR_EXPORT_MODIFIER_1046,
+            &[],
+        );
+    }
+}
+
+/// Determines if an ambient constant's initializer is a literal it can take the type of: a
+/// string, number, bigint, or boolean, or a reference to an enum member
+fn is_constant_literal(initializer: &Node) -> bool {
+    match initializer.kind {
+        SyntaxKind::StringLiteral
+        | SyntaxKind::NoSubstitutionTemplateLiteral
+        | SyntaxKind::NumericLiteral
+        | SyntaxKind::BigIntLiteral
+        | SyntaxKind::TrueKeyword
+        | SyntaxKind::FalseKeyword => true,
+        SyntaxKind::PrefixUnaryExpression => {
+            let unary = initializer.as_prefix_unary_expression();
+            matches!(
+                unary.operator,
+                SyntaxKind::MinusToken | SyntaxKind::PlusToken
+            ) && matches!(
+                unary.operand.kind,
+                SyntaxKind::NumericLiteral | SyntaxKind::BigIntLiteral
+            )
+        }
+        SyntaxKind::PropertyAccessExpression => is_entity_name_expression(initializer),
+        SyntaxKind::ElementAccessExpression => {
+            let access = initializer.as_element_access_expression();
+            is_entity_name_expression(&access.expression)
+                && matches!(
+                    access.argument_expression.kind,
+                    SyntaxKind::StringLiteral | SyntaxKind::NoSubstitutionTemplateLiteral
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Gets the range of the first token of a node, where errors about the whole of a statement
+/// are reported
+pub(super) fn get_first_token_range(node: &Arc<Node>) -> TextRange {
+    let file = get_source_file_of_node(node);
+    let data = file.as_source_file();
+    let mut scanner = Scanner::new();
+    scanner.set_text(data.text.clone());
+    scanner.set_script_target(data.language_version);
+    scanner.set_script_kind(data.script_kind);
+    scanner.set_language_variant(data.language_variant);
+    scanner.reset_pos(node.pos());
+    scanner.scan();
+    TextRange::new(scanner.token_start(), scanner.token_end().min(node.end()))
+}
@@ -0,0 +1,145 @@
+//! Type assertions, `expr as T` and `<T>expr`, and const assertions
+//!
+//! An assertion gives an expression the type it asserts, as long as one of the two types
+//! could be the other. A const assertion instead keeps the type of the expression, made as
+//! specific as it can be: its literals are not widened, its array literals are readonly
+//! tuples, and the properties of its object literals are readonly.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::skip_parentheses;
+use crate::compiler::ast::{Node, SymbolFlags, SyntaxKind};
+use crate::compiler::binder::is_entity_name_expression;
+use crate::compiler::diagnostics;
+
+use super::{Checker, Relation, TypeId};
+
+impl Checker {
+    /// Gets the type of an `as` or angle-bracket assertion, reporting an assertion between
+    /// types that do not overlap
+    pub(super) fn check_assertion(&mut self, node: &Arc<Node>) -> TypeId {
+        let (expression, type_node) = match node.kind {
+            SyntaxKind::AsExpression => {
+                let assertion = node.as_as_expression();
+                (&assertion.expression, &assertion.type_node)
+            }
+            _ => {
+                let assertion = node.as_type_assertion();
+                (&assertion.expression, &assertion.type_node)
+            }
+        };
+        let expression_type = self.check_expression(expression);
+        if is_const_type_reference(type_node) {
+            if !self.is_valid_const_assertion_argument(expression) {
+                self.error(
+                    expression,
+                    diagnostics::A_CONST_ASSERTIONS_CAN_ONLY_BE_APPLIED_TO_REFERENCES_TO_ENUM_MEMBERS_OR_STRING_NUMBER_BOOLEAN_ARRAY_OR_OBJECT_LITERALS_1355,
+                    &[],
+                );
+            }
+            return self.get_regular_type_of_literal_type(expression_type);
+        }
+        self.check_type_node(type_node);
+        let target_type = self.get_type_from_type_node(type_node);
+        if target_type == self.error_type {
+            return target_type;
+        }
+        // Either side may be the more specific one, but only after widening the literals of
+        // the expression, which would otherwise never overlap with other literals
+        let source_type = self.get_base_type_of_literal_type(expression_type);
+        let source_type = self.get_regular_type_of_object_literal(source_type);
+        let widened_type = self.get_widened_type(source_type);
+        if !self.is_type_comparable_to(target_type, widened_type) {
+            self.check_type_related_to(
+                source_type,
+                target_type,
+                Relation::Comparable,
+                node,
+                Some(diagnostics::CONVERSION_OF_TYPE_0_TO_TYPE_1_MAY_BE_A_MISTAKE_BECAUSE_NEITHER_TYPE_SUFFICIENTLY_OVERLAPS_WITH_THE_OTHER_IF_THIS_WAS_INTENTIONAL_CONVERT_THE_EXPRESSION_TO_UNKNOWN_FIRST_2352),
+            );
+        }
+        target_type
+    }
+
+    /// Determines if an expression can be made more specific by a const assertion: a
+    /// literal, an array or object literal, or a reference to an enum member
+    fn is_valid_const_assertion_argument(&mut self, node: &Arc<Node>) -> bool {
+        match node.kind {
+            SyntaxKind::StringLiteral
+            | SyntaxKind::NoSubstitutionTemplateLiteral
+            | SyntaxKind::NumericLiteral
+            | SyntaxKind::BigIntLiteral
+            | SyntaxKind::TrueKeyword
+            | SyntaxKind::FalseKeyword
+            | SyntaxKind::ArrayLiteralExpression
+            | SyntaxKind::ObjectLiteralExpression
+            | SyntaxKind::TemplateExpression => true,
+            SyntaxKind::ParenthesizedExpression => self
+                .is_valid_const_assertion_argument(&node.as_parenthesized_expression().expression),
+            SyntaxKind::PrefixUnaryExpression => {
+                let unary = node.as_prefix_unary_expression();
+                match unary.operator {
+                    SyntaxKind::MinusToken => matches!(
+                        unary.operand.kind,
+                        SyntaxKind::NumericLiteral | SyntaxKind::BigIntLiteral
+                    ),
+                    SyntaxKind::PlusToken => unary.operand.kind == SyntaxKind::NumericLiteral,
+                    _ => false,
+                }
+            }
+            SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression => {
+                let Some(expression) = node.expression() else {
+                    return false;
+                };
+                let expression = skip_parentheses(expression);
+                is_entity_name_expression(&expression)
+                    && self
+                        .resolve_entity_name(&expression, SymbolFlags::VALUE, true)
+                        .is_some_and(|symbol| {
+                            self.symbol(symbol).flags.intersects(SymbolFlags::ENUM)
+                        })
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Determines if a type node is the `const` of a const assertion
+pub(super) fn is_const_type_reference(node: &Node) -> bool {
+    node.kind == SyntaxKind::TypeReference && {
+        let name = &node.as_type_reference_node().type_name;
+        name.kind == SyntaxKind::Identifier && name.text() == "const"
+    }
+}
+
+/// Determines if an expression is a const assertion, possibly in parentheses
+pub(super) fn is_const_assertion(node: &Arc<Node>) -> bool {
+    let node = skip_parentheses(node);
+    matches!(
+        node.kind,
+        SyntaxKind::AsExpression | SyntaxKind::TypeAssertionExpression
+    ) && node
+        .type_node()
+        .is_some_and(|type_node| is_const_type_reference(type_node))
+}
+
+/// Determines if an expression is in a const assertion, directly or as an element of an array
+/// literal or a property of an object literal in one, so that its type is kept as specific
+/// as it can be
+pub(super) fn is_const_context(node: &Arc<Node>) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match parent.kind {
+        SyntaxKind::AsExpression | SyntaxKind::TypeAssertionExpression => parent
+            .type_node()
+            .is_some_and(|type_node| is_const_type_reference(type_node)),
+        SyntaxKind::ParenthesizedExpression
+        | SyntaxKind::ArrayLiteralExpression
+        | SyntaxKind::SpreadElement => is_const_context(&parent),
+        SyntaxKind::PropertyAssignment | SyntaxKind::ShorthandPropertyAssignment => parent
+            .parent()
+            .is_some_and(|object_literal| is_const_context(&object_literal)),
+        _ => false,
+    }
+}
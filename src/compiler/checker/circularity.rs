@@ -0,0 +1,166 @@
+//! Declarations whose types depend on themselves
+//!
+//! Resolving the type of a declaration can lead back to the same declaration, through its
+//! annotation, its initializer, or the expressions its function returns. The resolution stack
+//! detects the cycle, the declarations in it get `any` rather than recursing forever, and the
+//! cycle is reported where a declaration could have broken it with an annotation. Type
+//! parameters constrained by each other and aliases of each other are cycles too.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::symbol::symbol_name;
+use crate::compiler::ast::utilities::{get_source_file_of_node, get_text_of_node};
+use crate::compiler::ast::{Node, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics;
+
+use super::{Checker, TypeFlags, TypeId};
+
+impl Checker {
+    /// Reports a variable, parameter, or property whose type depends on itself: on its
+    /// annotation when it has one, or else under `noImplicitAny` for having become `any`
+    ///
+    /// A parameter without an initializer only depends on itself through its contextual type,
+    /// which reports its implicit `any` already.
+    pub(super) fn report_circular_type(&mut self, symbol: SymbolId, declaration: &Arc<Node>) {
+        let Some(name) = declaration.name() else {
+            return;
+        };
+        let args = [symbol_name(self.symbol(symbol)).into_owned()];
+        if declaration.type_node().is_some() {
+            self.error(
+                name,
+                diagnostics::D_0_IS_REFERENCED_DIRECTLY_OR_INDIRECTLY_IN_ITS_OWN_TYPE_ANNOTATION_2502,
+                &args,
+            );
+        } else if self.options.no_implicit_any
+            && (declaration.kind != SyntaxKind::Parameter || declaration.initializer().is_some())
+        {
+            self.error(
+                name,
+                diagnostics::D_0_IMPLICITLY_HAS_TYPE_ANY_BECAUSE_IT_DOES_NOT_HAVE_A_TYPE_ANNOTATION_AND_IS_REFERENCED_DIRECTLY_OR_INDIRECTLY_IN_ITS_OWN_INITIALIZER_7022,
+                &args,
+            );
+        }
+    }
+
+    /// Reports under `noImplicitAny` an unannotated getter whose type depends on itself
+    pub(super) fn report_circular_accessor_type(&mut self, symbol: SymbolId, getter: &Arc<Node>) {
+        if !self.options.no_implicit_any {
+            return;
+        }
+        let name = symbol_name(self.symbol(symbol)).into_owned();
+        self.error(
+            getter.name().unwrap_or(getter),
+            diagnostics::D_0_IMPLICITLY_HAS_RETURN_TYPE_ANY_BECAUSE_IT_DOES_NOT_HAVE_A_RETURN_TYPE_ANNOTATION_AND_IS_REFERENCED_DIRECTLY_OR_INDIRECTLY_IN_ONE_OF_ITS_RETURN_EXPRESSIONS_7023,
+            &[name],
+        );
+    }
+
+    /// Reports a function whose return type depends on itself: on its return type annotation
+    /// when it has one, or else under `noImplicitAny` for having become `any`
+    pub(super) fn report_circular_return_type(&mut self, declaration: &Arc<Node>) {
+        if let Some(type_node) = declaration.type_node() {
+            self.error(
+                type_node,
+                diagnostics::RETURN_TYPE_ANNOTATION_CIRCULARLY_REFERENCES_ITSELF_2577,
+                &[],
+            );
+            return;
+        }
+        if !self.options.no_implicit_any {
+            return;
+        }
+        match get_name_of_declaration(declaration) {
+            Some(name) => {
+                let file = get_source_file_of_node(&name);
+                let text = get_text_of_node(&file, &name).to_string();
+                self.error(
+                    &name,
+                    diagnostics::D_0_IMPLICITLY_HAS_RETURN_TYPE_ANY_BECAUSE_IT_DOES_NOT_HAVE_A_RETURN_TYPE_ANNOTATION_AND_IS_REFERENCED_DIRECTLY_OR_INDIRECTLY_IN_ONE_OF_ITS_RETURN_EXPRESSIONS_7023,
+                    &[text],
+                );
+            }
+            None => self.error(
+                declaration,
+                diagnostics::FUNCTION_IMPLICITLY_HAS_RETURN_TYPE_ANY_BECAUSE_IT_DOES_NOT_HAVE_A_RETURN_TYPE_ANNOTATION_AND_IS_REFERENCED_DIRECTLY_OR_INDIRECTLY_IN_ONE_OF_ITS_RETURN_EXPRESSIONS_7024,
+                &[],
+            ),
+        }
+    }
+
+    /// Reports an alias that refers back to itself through other aliases
+    pub(super) fn report_circular_alias(&mut self, symbol: SymbolId) {
+        let Some(declaration) = self.symbol(symbol).declarations.first().cloned() else {
+            return;
+        };
+        let name = symbol_name(self.symbol(symbol)).into_owned();
+        self.error(
+            declaration.name().unwrap_or(&declaration),
+            diagnostics::CIRCULAR_DEFINITION_OF_IMPORT_ALIAS_0_2303,
+            &[name],
+        );
+    }
+
+    /// Determines if following the constraints of a type parameter from one type parameter to
+    /// the next leads around in a circle, which leaves it without a constraint
+    pub(super) fn has_circular_constraint(&mut self, type_: TypeId) -> bool {
+        let mut seen = vec![type_];
+        let mut current = type_;
+        while let Some(constraint) = self.get_constraint_from_type_parameter(current) {
+            if seen.contains(&constraint) {
+                return true;
+            }
+            if !self.flags(constraint).contains(TypeFlags::TYPE_PARAMETER) {
+                return false;
+            }
+            seen.push(constraint);
+            current = constraint;
+        }
+        false
+    }
+
+    /// Reports a type parameter whose constraint circularly constrains it
+    pub(super) fn check_type_parameter_constraint(&mut self, node: &Arc<Node>) {
+        let Some(constraint) = node.as_type_parameter().constraint.clone() else {
+            return;
+        };
+        let Some(symbol) = self.get_symbol_of_declaration(node) else {
+            return;
+        };
+        let type_ = self.get_declared_type_of_symbol(symbol);
+        if self.has_circular_constraint(type_) {
+            let name = self.type_to_string(type_);
+            self.error(
+                &constraint,
+                diagnostics::TYPE_PARAMETER_0_HAS_A_CIRCULAR_CONSTRAINT_2313,
+                &[name],
+            );
+        }
+    }
+}
+
+/// Gets the name of a declaration, or for a function or class expression the name of the
+/// variable, property, or parameter it initializes
+fn get_name_of_declaration(node: &Arc<Node>) -> Option<Arc<Node>> {
+    if let Some(name) = node.name() {
+        return Some(Arc::clone(name));
+    }
+    if !matches!(
+        node.kind,
+        SyntaxKind::FunctionExpression | SyntaxKind::ArrowFunction | SyntaxKind::ClassExpression
+    ) {
+        return None;
+    }
+    let parent = node.parent()?;
+    match parent.kind {
+        SyntaxKind::VariableDeclaration
+        | SyntaxKind::PropertyDeclaration
+        | SyntaxKind::PropertyAssignment
+        | SyntaxKind::Parameter
+        | SyntaxKind::BindingElement => parent
+            .initializer()
+            .filter(|initializer| Arc::ptr_eq(initializer, node))
+            .and(parent.name().cloned()),
+        _ => None,
+    }
+}
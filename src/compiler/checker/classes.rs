@@ -0,0 +1,631 @@
+//! Checks of classes against the classes they extend and the interfaces they implement
+//!
+//! A class must be assignable to its base class and to each interface it implements, must
+//! implement the abstract members it inherits unless it is abstract itself, and must not
+//! override a property with a method or an accessor with a property. Its `override` modifiers
+//! must match the members of the base class, and under `noImplicitOverride` every member that
+//! overrides one needs the modifier. The constructor of a derived class calls `super` before
+//! it uses `this`, and under `strictPropertyInitialization` it assigns every property that has
+//! no initializer.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{
+    get_error_range, get_property_name_text, get_source_file_of_node, get_text_of_node,
+    is_class_like, is_function_like_kind, is_parameter_property_declaration, is_static,
+    skip_parentheses,
+};
+use crate::compiler::ast::{Node, NodeFlags, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics::{self, DiagnosticMessageChain, Message};
+use crate::compiler::text::TextRange;
+
+use super::{Checker, Relation, ResolvedMembers, TypeFlags, TypeId};
+
+impl Checker {
+    /// Checks a class against the class it extends and the interfaces it implements
+    pub(super) fn check_class_heritage(&mut self, node: &Arc<Node>, symbol: SymbolId) {
+        let class_type = self.get_declared_type_of_symbol(symbol);
+        let error_node = node.name().cloned().unwrap_or_else(|| Arc::clone(node));
+        if let Some(&base_type) = self.get_base_types(class_type).first() {
+            if !self.is_type_assignable_to(class_type, base_type) {
+                self.issue_member_specific_error(
+                    node,
+                    class_type,
+                    base_type,
+                    diagnostics::CLASS_0_INCORRECTLY_EXTENDS_BASE_CLASS_1_2415,
+                );
+            } else if let Some(base_constructor_type) = self.get_base_constructor_type(symbol) {
+                // The static side is only compared once the instance side is assignable
+                let static_type = self.get_type_of_symbol(symbol);
+                let static_base_type = self.get_type_without_signatures(base_constructor_type);
+                self.check_type_assignable_to(
+                    static_type,
+                    static_base_type,
+                    &error_node,
+                    Some(diagnostics::CLASS_STATIC_SIDE_0_INCORRECTLY_EXTENDS_BASE_CLASS_STATIC_SIDE_1_2417),
+                );
+            }
+            self.check_kinds_of_property_member_overrides(node, class_type, base_type);
+        }
+        for implemented in get_class_implements_types(node) {
+            let type_ = self.get_type_from_type_node(&implemented);
+            if type_ == self.error_type {
+                continue;
+            }
+            if !self
+                .flags(type_)
+                .intersects(TypeFlags::OBJECT | TypeFlags::INTERSECTION)
+            {
+                self.error(
+                    &implemented,
+                    diagnostics::A_CLASS_CAN_ONLY_IMPLEMENT_AN_OBJECT_TYPE_OR_INTERSECTION_OF_OBJECT_TYPES_WITH_STATICALLY_KNOWN_MEMBERS_2422,
+                    &[],
+                );
+                continue;
+            }
+            if !self.is_type_assignable_to(class_type, type_) {
+                let implements_class = self
+                    .get_type(type_)
+                    .symbol
+                    .is_some_and(|symbol| self.symbol(symbol).flags.contains(SymbolFlags::CLASS));
+                let message = if implements_class {
+                    diagnostics::CLASS_0_INCORRECTLY_IMPLEMENTS_CLASS_1_DID_YOU_MEAN_TO_EXTEND_1_AND_INHERIT_ITS_MEMBERS_AS_A_SUBCLASS_2720
+                } else {
+                    diagnostics::CLASS_0_INCORRECTLY_IMPLEMENTS_INTERFACE_1_2420
+                };
+                self.issue_member_specific_error(node, class_type, type_, message);
+            }
+        }
+    }
+
+    /// Reports the members of a class whose types are not assignable to the same members of a
+    /// base type, or `broad_message` on the class when no single member is to blame
+    fn issue_member_specific_error(
+        &mut self,
+        node: &Arc<Node>,
+        class_type: TypeId,
+        base_type: TypeId,
+        broad_message: &'static Message,
+    ) {
+        let mut issued_member_error = false;
+        for member in node.members().into_iter().flatten() {
+            if is_static(member) {
+                continue;
+            }
+            let Some(name) = member.name() else {
+                continue;
+            };
+            let Some(name_text) = get_property_name_text(name) else {
+                continue;
+            };
+            let (Some(property), Some(base_property)) = (
+                self.get_property_of_type(class_type, &name_text),
+                self.get_property_of_type(base_type, &name_text),
+            ) else {
+                continue;
+            };
+            let property_type = self.get_type_of_symbol(property);
+            let base_property_type = self.get_type_of_symbol(base_property);
+            let Some(error) = self.get_relation_error(
+                property_type,
+                base_property_type,
+                Relation::Assignable,
+                None,
+            ) else {
+                continue;
+            };
+            let chain = DiagnosticMessageChain::chain(
+                Some(error.chain),
+                diagnostics::PROPERTY_0_IN_TYPE_1_IS_NOT_ASSIGNABLE_TO_THE_SAME_PROPERTY_IN_BASE_TYPE_2_2416,
+                &[
+                    name_text,
+                    self.type_to_string(class_type),
+                    self.type_to_string(base_type),
+                ],
+            );
+            let error_node = error.error_node.unwrap_or_else(|| Arc::clone(name));
+            self.error_with_chain(&error_node, &chain, error.related_information);
+            issued_member_error = true;
+        }
+        if !issued_member_error {
+            let error_node = node.name().cloned().unwrap_or_else(|| Arc::clone(node));
+            self.check_type_assignable_to(class_type, base_type, &error_node, Some(broad_message));
+        }
+    }
+
+    /// Gets a type with the properties and index signatures of `type_` but none of its call or
+    /// construct signatures, which is what the static side of a derived class must match
+    fn get_type_without_signatures(&mut self, type_: TypeId) -> TypeId {
+        let members = self.resolve_structured_type_members(type_);
+        if members.call_signatures.is_empty() && members.construct_signatures.is_empty() {
+            return type_;
+        }
+        let members = ResolvedMembers {
+            properties: members.properties.clone(),
+            members: members.members.clone(),
+            call_signatures: Vec::new(),
+            construct_signatures: Vec::new(),
+            index_infos: members.index_infos.clone(),
+        };
+        let symbol = self.get_type(type_).symbol;
+        self.create_resolved_type(symbol, members)
+    }
+
+    /// Checks that the members of a class override the members of its base class with members
+    /// of the same kind, and that it implements the abstract members it inherits
+    fn check_kinds_of_property_member_overrides(
+        &mut self,
+        node: &Arc<Node>,
+        class_type: TypeId,
+        base_type: TypeId,
+    ) {
+        let class_is_abstract = node.has_modifier(SyntaxKind::AbstractKeyword);
+        let mut missing_abstract_members = Vec::new();
+        for base_property in self.get_properties_of_type(base_type) {
+            let name = self.symbol(base_property).name.clone();
+            if name.starts_with('#') {
+                continue;
+            }
+            let Some(derived) = self.get_property_of_type(class_type, &name) else {
+                continue;
+            };
+            let base_is_abstract = self.is_abstract_property(base_property);
+            if derived == base_property {
+                // The class inherits the member, so it must not be abstract
+                if base_is_abstract && !class_is_abstract {
+                    missing_abstract_members.push(name);
+                }
+                continue;
+            }
+            let base_flags = self.symbol(base_property).flags;
+            let derived_flags = self.symbol(derived).flags;
+            let base_property_flags = base_flags & (SymbolFlags::PROPERTY | SymbolFlags::ACCESSOR);
+            let derived_property_flags =
+                derived_flags & (SymbolFlags::PROPERTY | SymbolFlags::ACCESSOR);
+            let Some(derived_declaration) = self.symbol(derived).value_declaration.clone() else {
+                continue;
+            };
+            let error_node = derived_declaration
+                .name()
+                .cloned()
+                .unwrap_or_else(|| Arc::clone(&derived_declaration));
+            if base_flags.contains(SymbolFlags::METHOD)
+                || !base_property_flags.is_empty() && !derived_property_flags.is_empty()
+            {
+                // Methods may be overridden by anything, and properties and accessors by either
+                // unless one would shadow the other
+                if base_property_flags.is_empty()
+                    || derived_property_flags.is_empty()
+                    || base_is_abstract
+                    || self.is_declared_in_interface(base_property)
+                {
+                    continue;
+                }
+                let message = if base_property_flags == SymbolFlags::PROPERTY
+                    && derived_property_flags != SymbolFlags::PROPERTY
+                {
+                    diagnostics::D_0_IS_DEFINED_AS_A_PROPERTY_IN_CLASS_1_BUT_IS_OVERRIDDEN_HERE_IN_2_AS_AN_ACCESSOR_2611
+                } else if base_property_flags != SymbolFlags::PROPERTY
+                    && derived_property_flags == SymbolFlags::PROPERTY
+                {
+                    diagnostics::D_0_IS_DEFINED_AS_AN_ACCESSOR_IN_CLASS_1_BUT_IS_OVERRIDDEN_HERE_IN_2_AS_AN_INSTANCE_PROPERTY_2610
+                } else {
+                    continue;
+                };
+                let args = [
+                    name,
+                    self.type_to_string(base_type),
+                    self.type_to_string(class_type),
+                ];
+                self.error(&error_node, message, &args);
+                continue;
+            }
+            if !derived_flags.contains(SymbolFlags::METHOD) {
+                continue;
+            }
+            let message = if base_flags.intersects(SymbolFlags::ACCESSOR) {
+                diagnostics::CLASS_0_DEFINES_INSTANCE_MEMBER_ACCESSOR_1_BUT_EXTENDED_CLASS_2_DEFINES_IT_AS_INSTANCE_MEMBER_FUNCTION_2426
+            } else {
+                diagnostics::CLASS_0_DEFINES_INSTANCE_MEMBER_PROPERTY_1_BUT_EXTENDED_CLASS_2_DEFINES_IT_AS_INSTANCE_MEMBER_FUNCTION_2425
+            };
+            let args = [
+                self.type_to_string(base_type),
+                name,
+                self.type_to_string(class_type),
+            ];
+            self.error(&error_node, message, &args);
+        }
+        self.report_missing_abstract_members(node, class_type, base_type, missing_abstract_members);
+    }
+
+    /// Reports the abstract members a non-abstract class inherits without implementing them
+    fn report_missing_abstract_members(
+        &mut self,
+        node: &Arc<Node>,
+        class_type: TypeId,
+        base_type: TypeId,
+        missing: Vec<String>,
+    ) {
+        if missing.is_empty() {
+            return;
+        }
+        let error_node = node.name().cloned().unwrap_or_else(|| Arc::clone(node));
+        let is_expression = node.kind == SyntaxKind::ClassExpression;
+        let class_name = self.type_to_string(class_type);
+        let base_name = self.type_to_string(base_type);
+        let quote = |names: &[String]| {
+            names
+                .iter()
+                .map(|name| format!("'{name}'"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match missing.len() {
+            1 if is_expression => self.error(
+                &error_node,
+                diagnostics::NON_ABSTRACT_CLASS_EXPRESSION_DOES_NOT_IMPLEMENT_INHERITED_ABSTRACT_MEMBER_0_FROM_CLASS_1_2653,
+                &[missing[0].clone(), base_name],
+            ),
+            1 => self.error(
+                &error_node,
+                diagnostics::NON_ABSTRACT_CLASS_0_DOES_NOT_IMPLEMENT_INHERITED_ABSTRACT_MEMBER_1_FROM_CLASS_2_2515,
+                &[class_name, missing[0].clone(), base_name],
+            ),
+            2..=5 if is_expression => self.error(
+                &error_node,
+                diagnostics::NON_ABSTRACT_CLASS_EXPRESSION_IS_MISSING_IMPLEMENTATIONS_FOR_THE_FOLLOWING_MEMBERS_OF_0_COLON_1_2656,
+                &[base_name, quote(&missing)],
+            ),
+            2..=5 => self.error(
+                &error_node,
+                diagnostics::NON_ABSTRACT_CLASS_0_IS_MISSING_IMPLEMENTATIONS_FOR_THE_FOLLOWING_MEMBERS_OF_1_COLON_2_2654,
+                &[class_name, base_name, quote(&missing)],
+            ),
+            count if is_expression => self.error(
+                &error_node,
+                diagnostics::NON_ABSTRACT_CLASS_EXPRESSION_IS_MISSING_IMPLEMENTATIONS_FOR_THE_FOLLOWING_MEMBERS_OF_0_COLON_1_AND_2_MORE_2650,
+                &[base_name, quote(&missing[..4]), (count - 4).to_string()],
+            ),
+            count => self.error(
+                &error_node,
+                diagnostics::NON_ABSTRACT_CLASS_0_IS_MISSING_IMPLEMENTATIONS_FOR_THE_FOLLOWING_MEMBERS_OF_1_COLON_2_AND_3_MORE_2655,
+                &[
+                    class_name,
+                    base_name,
+                    quote(&missing[..4]),
+                    (count - 4).to_string(),
+                ],
+            ),
+        }
+    }
+
+    /// Determines if every declaration of a property is abstract without an initializer
+    fn is_abstract_property(&self, property: SymbolId) -> bool {
+        let declarations = &self.symbol(property).declarations;
+        !declarations.is_empty()
+            && declarations.iter().all(|declaration| {
+                declaration.has_modifier(SyntaxKind::AbstractKeyword)
+                    && declaration.initializer().is_none()
+            })
+    }
+
+    fn is_declared_in_interface(&self, property: SymbolId) -> bool {
+        self.symbol(property)
+            .declarations
+            .iter()
+            .any(|declaration| {
+                declaration
+                    .parent()
+                    .is_some_and(|parent| parent.kind == SyntaxKind::InterfaceDeclaration)
+            })
+    }
+
+    /// Checks the `override` modifiers of the members of a class, and under
+    /// `noImplicitOverride` that members overriding a base class member have one
+    pub(super) fn check_member_overrides(&mut self, node: &Arc<Node>, symbol: SymbolId) {
+        let class_type = self.get_declared_type_of_symbol(symbol);
+        let base_type = self.get_base_types(class_type).first().copied();
+        let base_constructor_type = base_type.and(self.get_base_constructor_type(symbol));
+        let is_ambient = node.flags.contains(NodeFlags::AMBIENT);
+        let mut members = Vec::new();
+        for member in node.members().into_iter().flatten() {
+            if member.kind == SyntaxKind::Constructor {
+                members.extend(
+                    member
+                        .parameters()
+                        .into_iter()
+                        .flatten()
+                        .filter(|parameter| is_parameter_property_declaration(parameter))
+                        .cloned(),
+                );
+            } else {
+                members.push(Arc::clone(member));
+            }
+        }
+        for member in members {
+            let Some(name) = member.name() else {
+                continue;
+            };
+            let Some(name_text) = get_property_name_text(name) else {
+                continue;
+            };
+            let is_parameter = member.kind == SyntaxKind::Parameter;
+            let error_node = if is_parameter {
+                Arc::clone(&member)
+            } else {
+                Arc::clone(name)
+            };
+            let has_override = member.has_modifier(SyntaxKind::OverrideKeyword);
+            let Some(base_type) = base_type else {
+                if has_override {
+                    let class_name = self.type_to_string(class_type);
+                    self.error(
+                        &error_node,
+                        diagnostics::THIS_MEMBER_CANNOT_HAVE_AN_OVERRIDE_MODIFIER_BECAUSE_ITS_CONTAINING_CLASS_0_DOES_NOT_EXTEND_ANOTHER_CLASS_4112,
+                        &[class_name],
+                    );
+                }
+                continue;
+            };
+            let lookup_type = if is_static(&member) {
+                base_constructor_type
+            } else {
+                Some(base_type)
+            };
+            let base_property =
+                lookup_type.and_then(|type_| self.get_property_of_type(type_, &name_text));
+            let Some(base_property) = base_property else {
+                if has_override {
+                    let base_name = self.type_to_string(base_type);
+                    self.error(
+                        &error_node,
+                        diagnostics::THIS_MEMBER_CANNOT_HAVE_AN_OVERRIDE_MODIFIER_BECAUSE_IT_IS_NOT_DECLARED_IN_THE_BASE_CLASS_0_4113,
+                        &[base_name],
+                    );
+                }
+                continue;
+            };
+            if has_override || !self.options.no_implicit_override || is_ambient {
+                continue;
+            }
+            let message = if !self.is_abstract_property(base_property) {
+                if is_parameter {
+                    diagnostics::THIS_PARAMETER_PROPERTY_MUST_HAVE_AN_OVERRIDE_MODIFIER_BECAUSE_IT_OVERRIDES_A_MEMBER_IN_BASE_CLASS_0_4115
+                } else {
+                    diagnostics::THIS_MEMBER_MUST_HAVE_AN_OVERRIDE_MODIFIER_BECAUSE_IT_OVERRIDES_A_MEMBER_IN_THE_BASE_CLASS_0_4114
+                }
+            } else if member.has_modifier(SyntaxKind::AbstractKeyword) {
+                diagnostics::THIS_MEMBER_MUST_HAVE_AN_OVERRIDE_MODIFIER_BECAUSE_IT_OVERRIDES_AN_ABSTRACT_METHOD_THAT_IS_DECLARED_IN_THE_BASE_CLASS_0_4116
+            } else {
+                continue;
+            };
+            let base_name = self.type_to_string(base_type);
+            self.error(&error_node, message, &[base_name]);
+        }
+    }
+
+    /// Checks a constructor, and in a derived class that it calls `super` where it must
+    pub(super) fn check_constructor_declaration(&mut self, node: &Arc<Node>) {
+        self.check_function_like_declaration(node);
+        let Some(body) = node.body() else {
+            return;
+        };
+        let Some(class) = node.parent().filter(|parent| is_class_like(parent)) else {
+            return;
+        };
+        if !is_derived_class(&class) {
+            return;
+        }
+        let Some(super_call) = find_first_super_call(body) else {
+            self.error_at_range(
+                node,
+                get_constructor_keyword_range(node),
+                diagnostics::CONSTRUCTORS_FOR_DERIVED_CLASSES_MUST_CONTAIN_A_SUPER_CALL_2377,
+                &[],
+            );
+            return;
+        };
+        // Properties are initialized right after `super` returns, so it must come first when
+        // the class has any
+        let initializes_properties = class.members().into_iter().flatten().any(|member| {
+            member.kind == SyntaxKind::PropertyDeclaration
+                && !is_static(member)
+                && member.initializer().is_some()
+                || member
+                    .name()
+                    .is_some_and(|name| name.kind == SyntaxKind::PrivateIdentifier)
+        }) || node
+            .parameters()
+            .into_iter()
+            .flatten()
+            .any(|parameter| is_parameter_property_declaration(parameter));
+        if !initializes_properties {
+            return;
+        }
+        let super_statement = super_call
+            .parent()
+            .filter(|parent| parent.kind == SyntaxKind::ExpressionStatement)
+            .filter(|statement| {
+                statement
+                    .parent()
+                    .is_some_and(|parent| Arc::ptr_eq(&parent, body))
+            });
+        let Some(super_statement) = super_statement else {
+            self.error(
+                &super_call,
+                diagnostics::A_SUPER_CALL_MUST_BE_A_ROOT_LEVEL_STATEMENT_WITHIN_A_CONSTRUCTOR_OF_A_DERIVED_CLASS_THAT_CONTAINS_INITIALIZED_PROPERTIES_PARAMETER_PROPERTIES_OR_PRIVATE_IDENTIFIERS_2401,
+                &[],
+            );
+            return;
+        };
+        for statement in body.statements().into_iter().flatten() {
+            if Arc::ptr_eq(statement, &super_statement) {
+                return;
+            }
+            if immediately_references_super_or_this(statement) {
+                break;
+            }
+        }
+        self.error_at_range(
+            node,
+            get_constructor_keyword_range(node),
+            diagnostics::A_SUPER_CALL_MUST_BE_THE_FIRST_STATEMENT_IN_THE_CONSTRUCTOR_TO_REFER_TO_SUPER_OR_THIS_WHEN_A_DERIVED_CLASS_CONTAINS_INITIALIZED_PROPERTIES_PARAMETER_PROPERTIES_OR_PRIVATE_IDENTIFIERS_2376,
+            &[],
+        );
+    }
+
+    /// Reports `this` or `super` used in the constructor of a derived class before it calls
+    /// `super`
+    pub(super) fn check_this_before_super(
+        &mut self,
+        node: &Arc<Node>,
+        container: &Arc<Node>,
+        message: &'static Message,
+    ) {
+        if container.kind != SyntaxKind::Constructor
+            || !container
+                .parent()
+                .is_some_and(|class| is_derived_class(&class))
+        {
+            return;
+        }
+        let Some(body) = container.body() else {
+            return;
+        };
+        if find_first_super_call(body).is_none_or(|call| call.end() > node.pos()) {
+            self.error(node, message, &[]);
+        }
+    }
+
+    /// Checks that the instance properties of a class without initializers are assigned in
+    /// its constructor, under `strictPropertyInitialization`
+    pub(super) fn check_property_initialization(&mut self, node: &Arc<Node>) {
+        if !self.options.strict_null_checks
+            || !self.options.strict_property_initialization
+            || node.flags.contains(NodeFlags::AMBIENT)
+        {
+            return;
+        }
+        let members: Vec<Arc<Node>> = node.members().into_iter().flatten().cloned().collect();
+        let constructor = members
+            .iter()
+            .find(|member| member.kind == SyntaxKind::Constructor && member.body().is_some())
+            .cloned();
+        for member in &members {
+            if member.kind != SyntaxKind::PropertyDeclaration
+                || is_static(member)
+                || member.has_modifier(SyntaxKind::AbstractKeyword)
+                || member.has_modifier(SyntaxKind::DeclareKeyword)
+            {
+                continue;
+            }
+            let property = member.as_property_declaration();
+            if property.initializer.is_some() || property.postfix_token.is_some() {
+                continue;
+            }
+            let name = &property.name;
+            if !matches!(
+                name.kind,
+                SyntaxKind::Identifier | SyntaxKind::PrivateIdentifier
+            ) {
+                continue;
+            }
+            let Some(symbol) = self.get_symbol_of_declaration(member) else {
+                continue;
+            };
+            let type_ = self.get_type_of_symbol(symbol);
+            if self
+                .flags(type_)
+                .intersects(TypeFlags::ANY | TypeFlags::UNKNOWN)
+                || self.maybe_type_of_kind(type_, TypeFlags::UNDEFINED)
+            {
+                continue;
+            }
+            if let Some(constructor) = &constructor
+                && self.is_property_initialized_in_constructor(name, type_, constructor)
+            {
+                continue;
+            }
+            self.error(
+                name,
+                diagnostics::PROPERTY_0_HAS_NO_INITIALIZER_AND_IS_NOT_DEFINITELY_ASSIGNED_IN_THE_CONSTRUCTOR_2564,
+                &[name.text().to_string()],
+            );
+        }
+    }
+}
+
+/// Gets the range of the `constructor` keyword of a constructor, where errors about the
+/// whole constructor are reported
+fn get_constructor_keyword_range(node: &Arc<Node>) -> TextRange {
+    let file = get_source_file_of_node(node);
+    let range = get_error_range(&file, node);
+    let text = get_text_of_node(&file, node);
+    let start = range.pos() + text.find("constructor").unwrap_or(0);
+    TextRange::new(start, start + "constructor".len())
+}
+
+/// Gets the type nodes of the `implements` clause of a class
+fn get_class_implements_types(class: &Node) -> Vec<Arc<Node>> {
+    let clauses = match class.kind {
+        SyntaxKind::ClassDeclaration => class.as_class_declaration().heritage_clauses.as_ref(),
+        _ => class.as_class_expression().heritage_clauses.as_ref(),
+    };
+    clauses
+        .into_iter()
+        .flatten()
+        .filter(|clause| clause.as_heritage_clause().token == SyntaxKind::ImplementsKeyword)
+        .flat_map(|clause| clause.as_heritage_clause().types.iter().cloned())
+        .collect()
+}
+
+/// Determines if a class extends another class, rather than nothing or `null`
+fn is_derived_class(class: &Node) -> bool {
+    let clauses = match class.kind {
+        SyntaxKind::ClassDeclaration => class.as_class_declaration().heritage_clauses.as_ref(),
+        SyntaxKind::ClassExpression => class.as_class_expression().heritage_clauses.as_ref(),
+        _ => None,
+    };
+    clauses
+        .into_iter()
+        .flatten()
+        .filter(|clause| clause.as_heritage_clause().token == SyntaxKind::ExtendsKeyword)
+        .flat_map(|clause| clause.as_heritage_clause().types.iter())
+        .next()
+        .is_some_and(|heritage_type| {
+            skip_parentheses(&heritage_type.as_expression_with_type_arguments().expression).kind
+                != SyntaxKind::NullKeyword
+        })
+}
+
+/// Finds the first `super(...)` call in a constructor body, in source order, outside of any
+/// nested function
+fn find_first_super_call(node: &Arc<Node>) -> Option<Arc<Node>> {
+    let mut found = None;
+    node.for_each_child(&mut |child| {
+        if child.kind == SyntaxKind::CallExpression
+            && child.as_call_expression().expression.kind == SyntaxKind::SuperKeyword
+        {
+            found = Some(Arc::clone(child));
+        } else if !is_function_like_kind(child.kind) {
+            found = find_first_super_call(child);
+        }
+        found.is_some()
+    });
+    found
+}
+
+/// Determines if a statement uses `this` or `super` when it runs, rather than in a nested
+/// function or class
+fn immediately_references_super_or_this(node: &Arc<Node>) -> bool {
+    let mut found = false;
+    node.for_each_child(&mut |child| {
+        found = match child.kind {
+            SyntaxKind::ThisKeyword | SyntaxKind::SuperKeyword => true,
+            kind if is_function_like_kind(kind) || is_class_like(child) => false,
+            _ => immediately_references_super_or_this(child),
+        };
+        found
+    });
+    found
+}
@@ -0,0 +1,456 @@
+//! Conditional types
+//!
+//! A conditional type `T extends U ? X : Y` resolves to one of its branches once its check and
+//! extends types are known, and is deferred while either is still generic. When the check type
+//! is a naked type parameter, instantiating it with a union distributes the conditional type
+//! over the constituents. The `infer` type parameters of the extends type are inferred from the
+//! check type before the branch is chosen, and a branch that is itself a conditional type is
+//! resolved in a loop rather than recursively, so that recursive type aliases can go deep.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId};
+use crate::compiler::binder::infer_type_container;
+use crate::compiler::diagnostics;
+
+use super::inference::{InferenceContext, InferencePriority};
+use super::{
+    Checker, ConditionalRoot, ElementFlags, ObjectFlags, TypeData, TypeFlags, TypeId, TypeMapper,
+};
+
+/// How many times resolving a conditional type may continue into a conditional type in the
+/// chosen branch before it is assumed to be infinite, as in tsc
+const MAX_TAIL_RECURSION: u32 = 1000;
+
+impl Checker {
+    pub(super) fn get_type_from_conditional_type_node(&mut self, node: &Arc<Node>) -> TypeId {
+        let conditional = node.as_conditional_type_node();
+        let check_type = self.get_type_from_type_node(&conditional.check_type);
+        let extends_type = self.get_type_from_type_node(&conditional.extends_type);
+        let root = Arc::new(ConditionalRoot {
+            node: Arc::clone(node),
+            check_type,
+            extends_type,
+            is_distributive: self.flags(check_type).contains(TypeFlags::TYPE_PARAMETER),
+            infer_type_parameters: self.get_infer_type_parameters(node),
+            outer_type_parameters: self.get_outer_type_parameters(node),
+        });
+        self.get_conditional_type(root, None, None)
+    }
+
+    /// Gets the type parameters a conditional type declares with `infer`, in source order
+    pub(super) fn get_infer_type_parameters(&mut self, node: &Node) -> Vec<TypeId> {
+        let Some(locals) = self.bindings.locals(node) else {
+            return Vec::new();
+        };
+        let mut symbols: Vec<SymbolId> = locals.values().copied().collect();
+        symbols.retain(|&symbol| {
+            self.symbol(symbol)
+                .flags
+                .contains(SymbolFlags::TYPE_PARAMETER)
+        });
+        symbols.sort_by_key(|&symbol| {
+            self.symbol(symbol)
+                .declarations
+                .first()
+                .map_or(0, |declaration| declaration.pos())
+        });
+        symbols
+            .into_iter()
+            .map(|symbol| self.get_declared_type_of_symbol(symbol))
+            .collect()
+    }
+
+    /// Resolves a conditional type with its type parameters mapped by `mapper`, deferring it if
+    /// the check or extends type is still generic
+    fn get_conditional_type(
+        &mut self,
+        root: Arc<ConditionalRoot>,
+        mapper: Option<Arc<TypeMapper>>,
+        alias: Option<(SymbolId, Vec<TypeId>)>,
+    ) -> TypeId {
+        let mut root = root;
+        let mut mapper = mapper;
+        let mut alias = alias;
+        let mut extra_types = Vec::new();
+        let mut tail_count = 0;
+        let result = loop {
+            if tail_count == MAX_TAIL_RECURSION {
+                self.report_excessive_instantiation();
+                return self.error_type;
+            }
+            let check_type = self.instantiate_type_if_mapped(root.check_type, mapper.as_ref());
+            let extends_type = self.instantiate_type_if_mapped(root.extends_type, mapper.as_ref());
+            if check_type == self.error_type || extends_type == self.error_type {
+                break self.error_type;
+            }
+            let check_type_deferred = self.is_generic_type(check_type);
+            let mut combined_mapper = None;
+            if !root.infer_type_parameters.is_empty() {
+                let mut context = InferenceContext::new(&root.infer_type_parameters, None);
+                if !check_type_deferred {
+                    self.infer_types(
+                        &mut context,
+                        check_type,
+                        extends_type,
+                        InferencePriority::NONE,
+                    );
+                }
+                let inferred = self.get_inferred_types(&mut context);
+                let context_mapper = TypeMapper::new(root.infer_type_parameters.clone(), inferred);
+                combined_mapper = Some(match &mapper {
+                    Some(mapper) => {
+                        Arc::new(TypeMapper::Composite(context_mapper, Arc::clone(mapper)))
+                    }
+                    None => context_mapper,
+                });
+            }
+            let inferred_extends_type = match &combined_mapper {
+                Some(combined_mapper) => self.instantiate_type(root.extends_type, combined_mapper),
+                None => extends_type,
+            };
+            if !check_type_deferred && !self.is_generic_type(inferred_extends_type) {
+                let extends_flags = self.flags(inferred_extends_type);
+                let check_is_any = self.flags(check_type).contains(TypeFlags::ANY);
+                // The false branch is taken when not even erasing the type parameters nested
+                // in the types makes the check type assignable
+                if !extends_flags.intersects(TypeFlags::ANY_OR_UNKNOWN)
+                    && (check_is_any
+                        || !self.is_type_assignable_to_instantiated(
+                            check_type,
+                            inferred_extends_type,
+                            TypeMapper::Permissive,
+                        ))
+                {
+                    // `any` takes both branches
+                    if check_is_any {
+                        let true_type = self.get_type_from_branch(&root, true);
+                        let true_mapper = combined_mapper.clone().or_else(|| mapper.clone());
+                        extra_types
+                            .push(self.instantiate_type_if_mapped(true_type, true_mapper.as_ref()));
+                    }
+                    let false_type = self.get_type_from_branch(&root, false);
+                    if let Some((new_root, new_mapper)) =
+                        self.get_tail_recursion_target(false_type, mapper.as_ref())
+                    {
+                        root = new_root;
+                        mapper = Some(new_mapper);
+                        alias = self.get_alias_of_type(false_type);
+                        tail_count += 1;
+                        continue;
+                    }
+                    break self.instantiate_type_if_mapped(false_type, mapper.as_ref());
+                }
+                // The true branch is taken when the check type is assignable even with the type
+                // parameters nested in the types stripped of their constraints
+                if extends_flags.intersects(TypeFlags::ANY_OR_UNKNOWN)
+                    || self.is_type_assignable_to_instantiated(
+                        check_type,
+                        inferred_extends_type,
+                        TypeMapper::Restrictive,
+                    )
+                {
+                    let true_type = self.get_type_from_branch(&root, true);
+                    let true_mapper = combined_mapper.or(mapper);
+                    if let Some((new_root, new_mapper)) =
+                        self.get_tail_recursion_target(true_type, true_mapper.as_ref())
+                    {
+                        root = new_root;
+                        mapper = Some(new_mapper);
+                        alias = self.get_alias_of_type(true_type);
+                        tail_count += 1;
+                        continue;
+                    }
+                    break self.instantiate_type_if_mapped(true_type, true_mapper.as_ref());
+                }
+            }
+            let result = self.create_type(
+                TypeFlags::CONDITIONAL,
+                ObjectFlags::NONE,
+                None,
+                TypeData::Conditional {
+                    root,
+                    check_type,
+                    extends_type,
+                    mapper,
+                    combined_mapper,
+                },
+            );
+            if let Some((alias_symbol, alias_type_arguments)) = alias {
+                let deferred = &mut self.types[result.index()];
+                deferred.alias_symbol = Some(alias_symbol);
+                deferred.alias_type_arguments = alias_type_arguments;
+            }
+            break result;
+        };
+        if extra_types.is_empty() {
+            return result;
+        }
+        extra_types.push(result);
+        self.get_union_type(&extra_types)
+    }
+
+    /// Instantiates a conditional type, distributing it over a union that replaces its check
+    /// type
+    pub(super) fn get_conditional_type_instantiation(
+        &mut self,
+        type_: TypeId,
+        mapper: &Arc<TypeMapper>,
+        alias: Option<(SymbolId, Vec<TypeId>)>,
+    ) -> TypeId {
+        let TypeData::Conditional {
+            root,
+            mapper: existing_mapper,
+            ..
+        } = self.get_type(type_).data.clone()
+        else {
+            return type_;
+        };
+        let outer_type_parameters = root.outer_type_parameters.clone();
+        if outer_type_parameters.is_empty() {
+            return type_;
+        }
+        let arguments =
+            self.instantiate_outer_type_parameters(&outer_type_parameters, existing_mapper, mapper);
+        let key = (root.node.node_id(), arguments);
+        if let Some(&instantiation) = self.conditional_instantiations.get(&key) {
+            return instantiation;
+        }
+        let new_mapper = TypeMapper::new(outer_type_parameters, key.1.clone());
+        let check_type = root.check_type;
+        let distribution_type = if root.is_distributive {
+            self.map_type(check_type, &new_mapper)
+        } else {
+            check_type
+        };
+        let result = if distribution_type != check_type
+            && self.flags(distribution_type).contains(TypeFlags::NEVER)
+        {
+            self.never_type
+        } else if distribution_type != check_type
+            && self.flags(distribution_type).contains(TypeFlags::UNION)
+        {
+            let mut results = Vec::new();
+            for constituent in self.get_constituents(distribution_type) {
+                let constituent_mapper = Arc::new(TypeMapper::Merged(
+                    TypeMapper::new(vec![check_type], vec![constituent]),
+                    Arc::clone(&new_mapper),
+                ));
+                results.push(self.get_conditional_type(
+                    Arc::clone(&root),
+                    Some(constituent_mapper),
+                    None,
+                ));
+            }
+            let union = self.get_union_type(&results);
+            match alias {
+                Some((alias_symbol, alias_type_arguments)) => {
+                    self.get_type_with_alias(union, alias_symbol, alias_type_arguments)
+                }
+                None => union,
+            }
+        } else {
+            self.get_conditional_type(root, Some(new_mapper), alias)
+        };
+        self.conditional_instantiations.insert(key, result);
+        result
+    }
+
+    /// Instantiates the type parameters in scope of a conditional type, as they are mapped by
+    /// the mapper that created it and then by `mapper`
+    fn instantiate_outer_type_parameters(
+        &mut self,
+        outer_type_parameters: &[TypeId],
+        existing_mapper: Option<Arc<TypeMapper>>,
+        mapper: &Arc<TypeMapper>,
+    ) -> Vec<TypeId> {
+        let mut arguments = Vec::with_capacity(outer_type_parameters.len());
+        for &parameter in outer_type_parameters {
+            let current = match &existing_mapper {
+                Some(existing) => self.map_type(parameter, existing),
+                None => parameter,
+            };
+            arguments.push(self.instantiate_type(current, mapper));
+        }
+        arguments
+    }
+
+    /// Gets the root and mapper to continue resolving with when the chosen branch of a
+    /// conditional type is another conditional type that does not distribute over a union
+    fn get_tail_recursion_target(
+        &mut self,
+        type_: TypeId,
+        mapper: Option<&Arc<TypeMapper>>,
+    ) -> Option<(Arc<ConditionalRoot>, Arc<TypeMapper>)> {
+        let mapper = mapper?;
+        let TypeData::Conditional {
+            root,
+            mapper: existing_mapper,
+            ..
+        } = self.get_type(type_).data.clone()
+        else {
+            return None;
+        };
+        let outer_type_parameters = root.outer_type_parameters.clone();
+        let arguments =
+            self.instantiate_outer_type_parameters(&outer_type_parameters, existing_mapper, mapper);
+        let new_mapper = TypeMapper::new(outer_type_parameters, arguments);
+        if root.is_distributive {
+            let check_type = self.map_type(root.check_type, &new_mapper);
+            if check_type != root.check_type
+                && self
+                    .flags(check_type)
+                    .intersects(TypeFlags::UNION | TypeFlags::NEVER)
+            {
+                return None;
+            }
+        }
+        Some((root, new_mapper))
+    }
+
+    fn get_alias_of_type(&self, type_: TypeId) -> Option<(SymbolId, Vec<TypeId>)> {
+        let type_data = self.get_type(type_);
+        type_data
+            .alias_symbol
+            .map(|symbol| (symbol, type_data.alias_type_arguments.clone()))
+    }
+
+    /// Gets the uninstantiated type of the true or false branch of a conditional type
+    fn get_type_from_branch(&mut self, root: &ConditionalRoot, true_branch: bool) -> TypeId {
+        let conditional = root.node.as_conditional_type_node();
+        let branch = if true_branch {
+            &conditional.true_type
+        } else {
+            &conditional.false_type
+        };
+        self.get_type_from_type_node(branch)
+    }
+
+    /// Gets the true branch of a deferred conditional type, with the `infer` type parameters
+    /// left in place
+    pub(super) fn get_true_type_from_conditional_type(&mut self, type_: TypeId) -> TypeId {
+        let TypeData::Conditional { root, mapper, .. } = self.get_type(type_).data.clone() else {
+            return type_;
+        };
+        let true_type = self.get_type_from_branch(&root, true);
+        self.instantiate_type_if_mapped(true_type, mapper.as_ref())
+    }
+
+    pub(super) fn get_false_type_from_conditional_type(&mut self, type_: TypeId) -> TypeId {
+        let TypeData::Conditional { root, mapper, .. } = self.get_type(type_).data.clone() else {
+            return type_;
+        };
+        let false_type = self.get_type_from_branch(&root, false);
+        self.instantiate_type_if_mapped(false_type, mapper.as_ref())
+    }
+
+    /// Gets the type a deferred conditional type is known to be assignable to: the union of its
+    /// branches, with the `infer` type parameters replaced by what was inferred for them
+    pub(super) fn get_default_constraint_of_conditional_type(&mut self, type_: TypeId) -> TypeId {
+        let TypeData::Conditional {
+            root,
+            mapper,
+            combined_mapper,
+            ..
+        } = self.get_type(type_).data.clone()
+        else {
+            return type_;
+        };
+        let true_type = self.get_type_from_branch(&root, true);
+        let true_type =
+            self.instantiate_type_if_mapped(true_type, combined_mapper.or(mapper).as_ref());
+        let false_type = self.get_false_type_from_conditional_type(type_);
+        if self.flags(true_type).contains(TypeFlags::ANY) {
+            return false_type;
+        }
+        if self.flags(false_type).contains(TypeFlags::ANY) {
+            return true_type;
+        }
+        self.get_union_type(&[true_type, false_type])
+    }
+
+    /// Determines if a type is a type parameter, a deferred type that depends on one, or a
+    /// union, intersection, or tuple containing one, whose conditional types cannot be resolved yet
+    pub(super) fn is_generic_type(&mut self, type_: TypeId) -> bool {
+        if self.flags(type_).intersects(TypeFlags::INSTANTIABLE) {
+            return true;
+        }
+        match &self.get_type(type_).data {
+            TypeData::UnionOrIntersection { types } => {
+                let types = types.clone();
+                types.into_iter().any(|type_| self.is_generic_type(type_))
+            }
+            TypeData::Mapped { .. } => self.is_generic_mapped_type(type_),
+            // A tuple spreading a type parameter has elements that are not known yet
+            TypeData::Reference { .. } => match self.get_tuple_elements(type_) {
+                Some((element_types, element_flags, _)) => element_types
+                    .into_iter()
+                    .zip(element_flags)
+                    .any(|(element_type, flags)| {
+                        flags.intersects(ElementFlags::VARIABLE)
+                            && self.is_generic_type(element_type)
+                    }),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Gets a copy of a type parameter without its constraint
+    pub(super) fn get_restrictive_type_parameter(&mut self, type_: TypeId) -> TypeId {
+        if !self.flags(type_).contains(TypeFlags::TYPE_PARAMETER)
+            || self.get_constraint_of_type_parameter(type_).is_none()
+        {
+            return type_;
+        }
+        if let Some(&restrictive) = self.restrictive_type_parameters.get(&type_) {
+            return restrictive;
+        }
+        let symbol = self.get_type(type_).symbol;
+        let restrictive = self.create_type(
+            TypeFlags::TYPE_PARAMETER,
+            ObjectFlags::NONE,
+            symbol,
+            TypeData::TypeParameter {
+                constraint: Some(None),
+                is_this_type: false,
+            },
+        );
+        self.restrictive_type_parameters.insert(type_, restrictive);
+        restrictive
+    }
+
+    fn is_type_assignable_to_instantiated(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        mapper: TypeMapper,
+    ) -> bool {
+        let mapper = Arc::new(mapper);
+        let source = self.instantiate_type(source, &mapper);
+        let target = self.instantiate_type(target, &mapper);
+        self.is_type_assignable_to(source, target)
+    }
+
+    pub(super) fn instantiate_type_if_mapped(
+        &mut self,
+        type_: TypeId,
+        mapper: Option<&Arc<TypeMapper>>,
+    ) -> TypeId {
+        match mapper {
+            Some(mapper) => self.instantiate_type(type_, mapper),
+            None => type_,
+        }
+    }
+
+    /// Reports `infer` used outside the extends clause of a conditional type
+    pub(super) fn check_infer_type(&mut self, node: &Arc<Node>) {
+        if infer_type_container(node).is_none() {
+            self.error(
+                node,
+                diagnostics::INFER_DECLARATIONS_ARE_ONLY_PERMITTED_IN_THE_EXTENDS_CLAUSE_OF_A_CONDITIONAL_TYPE_1338,
+                &[],
+            );
+        }
+    }
+}
@@ -0,0 +1,532 @@
+//! Contextual types
+//!
+//! An expression's contextual type is the type its surroundings expect it to have, such as
+//! the declared type of the variable it initializes. It decides whether literals keep their
+//! literal types and whether array literals become tuples, and it gives the unannotated
+//! parameters of function expressions, arrow functions, and object literal methods the types
+//! of the parameters of its signature.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{is_binding_pattern, is_function_like_declaration_kind};
+use crate::compiler::ast::{Node, SyntaxKind};
+
+use super::assertions::is_const_type_reference;
+use super::{Checker, SignatureId, TypeData, TypeFlags, TypeId};
+
+impl Checker {
+    /// Gets the type the surroundings of an expression expect it to have, if any
+    pub(super) fn get_contextual_type(&mut self, node: &Arc<Node>) -> Option<TypeId> {
+        let parent = node.parent()?;
+        match parent.kind {
+            SyntaxKind::VariableDeclaration
+            | SyntaxKind::Parameter
+            | SyntaxKind::PropertyDeclaration
+            | SyntaxKind::PropertySignature => {
+                if !parent
+                    .initializer()
+                    .is_some_and(|initializer| Arc::ptr_eq(initializer, node))
+                {
+                    return None;
+                }
+                if let Some(type_node) = parent.type_node() {
+                    return Some(self.get_type_from_type_node(type_node));
+                }
+                // A destructured declaration expects the type its pattern implies
+                let name = parent.name().filter(|name| is_binding_pattern(name))?;
+                Some(self.get_type_from_binding_pattern(name))
+            }
+            SyntaxKind::BindingElement => {
+                if !parent
+                    .initializer()
+                    .is_some_and(|initializer| Arc::ptr_eq(initializer, node))
+                {
+                    return None;
+                }
+                self.get_contextual_type_of_binding_element(&parent)
+            }
+            SyntaxKind::ReturnStatement => {
+                let function = get_containing_function(&parent)?;
+                self.get_contextual_return_type(&function)
+            }
+            SyntaxKind::ArrowFunction => {
+                if !parent.body().is_some_and(|body| Arc::ptr_eq(body, node)) {
+                    return None;
+                }
+                self.get_contextual_return_type(&parent)
+            }
+            SyntaxKind::BinaryExpression => {
+                let binary = parent.as_binary_expression();
+                match binary.operator_token.kind {
+                    SyntaxKind::EqualsToken if Arc::ptr_eq(&binary.right, node) => {
+                        Some(self.check_expression(&binary.left))
+                    }
+                    SyntaxKind::BarBarToken | SyntaxKind::QuestionQuestionToken => {
+                        self.get_contextual_type(&parent)
+                    }
+                    SyntaxKind::CommaToken if Arc::ptr_eq(&binary.right, node) => {
+                        self.get_contextual_type(&parent)
+                    }
+                    _ => None,
+                }
+            }
+            SyntaxKind::CallExpression | SyntaxKind::NewExpression => {
+                if let Some(contextual_type) = self.node_links(node).contextual_type {
+                    return Some(contextual_type);
+                }
+                self.get_contextual_type_for_argument(&parent, node)
+            }
+            SyntaxKind::ParenthesizedExpression => self.get_contextual_type(&parent),
+            SyntaxKind::ConditionalExpression => {
+                if Arc::ptr_eq(&parent.as_conditional_expression().condition, node) {
+                    return None;
+                }
+                self.get_contextual_type(&parent)
+            }
+            SyntaxKind::AsExpression
+            | SyntaxKind::TypeAssertionExpression
+            | SyntaxKind::SatisfiesExpression => {
+                let type_node = parent.type_node()?;
+                if is_const_type_reference(type_node) {
+                    return None;
+                }
+                Some(self.get_type_from_type_node(type_node))
+            }
+            SyntaxKind::ArrayLiteralExpression => {
+                let index = parent
+                    .as_array_literal_expression()
+                    .elements
+                    .iter()
+                    .position(|element| Arc::ptr_eq(element, node))?;
+                let contextual_type = self.get_contextual_type(&parent)?;
+                self.map_contextual_type(contextual_type, |checker, type_| {
+                    checker.get_element_type_at_index(type_, index)
+                })
+            }
+            SyntaxKind::PropertyAssignment => {
+                let assignment = parent.as_property_assignment();
+                if !Arc::ptr_eq(&assignment.initializer, node) {
+                    return None;
+                }
+                let literal = parent.parent()?;
+                self.get_contextual_type_of_object_literal_element(&literal, &assignment.name)
+            }
+            // A method of an object literal is typed by the property it defines
+            SyntaxKind::ObjectLiteralExpression if node.kind == SyntaxKind::MethodDeclaration => {
+                self.get_contextual_type_of_object_literal_element(&parent, node.name()?)
+            }
+            // A spread in an array literal is expected to be an array like the literal, and
+            // one in an object literal an object like it
+            SyntaxKind::SpreadElement => {
+                let literal = parent
+                    .parent()
+                    .filter(|literal| literal.kind == SyntaxKind::ArrayLiteralExpression)?;
+                self.get_contextual_type(&literal)
+            }
+            SyntaxKind::SpreadAssignment => self.get_contextual_type(&parent.parent()?),
+            SyntaxKind::JsxExpression => {
+                let attribute = parent
+                    .parent()
+                    .filter(|attribute| attribute.kind == SyntaxKind::JsxAttribute)?;
+                self.get_contextual_type_of_jsx_attribute(&attribute)
+            }
+            SyntaxKind::JsxSpreadAttribute => {
+                let element = parent.parent()?.parent()?;
+                self.get_jsx_props_type(&element)
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the type of the parameter an argument is passed to, resolving the call if the
+    /// argument's contextual type is asked for before the call is checked
+    fn get_contextual_type_for_argument(
+        &mut self,
+        call: &Arc<Node>,
+        argument: &Arc<Node>,
+    ) -> Option<TypeId> {
+        let arguments = match call.kind {
+            SyntaxKind::CallExpression => &call.as_call_expression().arguments,
+            _ => call.as_new_expression().arguments.as_ref()?,
+        };
+        let index = arguments
+            .iter()
+            .position(|candidate| Arc::ptr_eq(candidate, argument))?;
+        let signature = self.get_resolved_signature(call)?;
+        // Resolving the call gives the arguments it checks their contextual types
+        if let Some(contextual_type) = self.node_links(argument).contextual_type {
+            return Some(contextual_type);
+        }
+        self.get_type_at_position(signature, index)
+    }
+
+    /// Gets the type a property or method of an object literal is expected to have, from the
+    /// property of the same name of the literal's contextual type
+    fn get_contextual_type_of_object_literal_element(
+        &mut self,
+        literal: &Arc<Node>,
+        name: &Arc<Node>,
+    ) -> Option<TypeId> {
+        if name.kind == SyntaxKind::ComputedPropertyName {
+            return None;
+        }
+        let name = name.text().to_string();
+        let contextual_type = self.get_contextual_type(literal)?;
+        self.map_contextual_type(contextual_type, |checker, type_| {
+            checker.get_indexed_access_type_or_undefined(type_, &name)
+        })
+    }
+
+    /// Gets the signature that types the unannotated parameters and the return of a function
+    /// expression, arrow function, or object literal method
+    ///
+    /// Each constituent of a union contextual type contributes its signature, so that an
+    /// optional callback is typed by the function it may be. Constituents with differing
+    /// signatures leave the function without one.
+    fn get_contextual_signature(&mut self, function: &Arc<Node>) -> Option<SignatureId> {
+        if !is_contextually_typable_function(function) {
+            return None;
+        }
+        let contextual_type = self.get_contextual_type(function)?;
+        let constituents = match &self.get_type(contextual_type).data {
+            TypeData::UnionOrIntersection { types }
+                if self.flags(contextual_type).contains(TypeFlags::UNION) =>
+            {
+                types.clone()
+            }
+            _ => vec![contextual_type],
+        };
+        let mut lists: Vec<Vec<SignatureId>> = constituents
+            .into_iter()
+            .map(|type_| self.get_signatures_of_type(type_, false))
+            .filter(|signatures| !signatures.is_empty())
+            .collect();
+        let signatures = match lists.len() {
+            0 => return None,
+            1 => lists.pop().unwrap(),
+            _ => {
+                let mut signatures = lists.iter().flatten().copied();
+                let first = signatures.next()?;
+                if lists.iter().any(|list| list.len() != 1)
+                    || !signatures.all(|signature| self.have_identical_parameters(first, signature))
+                {
+                    return None;
+                }
+                self.get_union_signatures(lists)
+            }
+        };
+        let [signature] = signatures[..] else {
+            return None;
+        };
+        Some(signature)
+    }
+
+    fn have_identical_parameters(&mut self, first: SignatureId, second: SignatureId) -> bool {
+        let count = self.get_signature(first).parameters.len();
+        if count != self.get_signature(second).parameters.len() {
+            return false;
+        }
+        (0..count).all(|position| {
+            match (
+                self.get_type_at_position(first, position),
+                self.get_type_at_position(second, position),
+            ) {
+                (Some(first), Some(second)) => self.is_type_identical_to(first, second),
+                _ => false,
+            }
+        })
+    }
+
+    /// Gets the type of an unannotated parameter of a function expression from the
+    /// signature of the function's contextual type
+    pub(super) fn get_contextually_typed_parameter_type(
+        &mut self,
+        parameter: &Arc<Node>,
+    ) -> Option<TypeId> {
+        let function = parameter.parent()?;
+        if !is_contextually_typable_function(&function) || !self.is_context_sensitive(&function) {
+            return None;
+        }
+        let index = function
+            .parameters()?
+            .iter()
+            .position(|candidate| Arc::ptr_eq(candidate, parameter))?;
+        let signature = self.get_contextual_signature(&function)?;
+        if parameter.as_parameter().dot_dot_dot_token.is_some() {
+            // A rest parameter takes the type of the rest parameter at its position
+            let contextual_signature = self.get_signature(signature);
+            if !contextual_signature.has_rest_parameter()
+                || index + 1 != contextual_signature.parameters.len()
+            {
+                return None;
+            }
+            let rest = contextual_signature.parameters[index];
+            return Some(self.get_type_of_symbol(rest));
+        }
+        self.get_type_at_position(signature, index)
+    }
+
+    /// Resolves the call a context sensitive function is passed to as an argument, if it has
+    /// not been resolved, since that is what gives the function's parameters their types
+    pub(super) fn resolve_call_contextually_typing_parameter(&mut self, parameter: &Arc<Node>) {
+        let Some(function) = parameter.parent() else {
+            return;
+        };
+        if !is_contextually_typable_function(&function) || !self.is_context_sensitive(&function) {
+            return;
+        }
+        let mut argument = function;
+        while let Some(parent) = argument
+            .parent()
+            .filter(|parent| parent.kind == SyntaxKind::ParenthesizedExpression)
+        {
+            argument = parent;
+        }
+        let Some(call) = argument.parent().filter(|parent| {
+            matches!(
+                parent.kind,
+                SyntaxKind::CallExpression | SyntaxKind::NewExpression
+            )
+        }) else {
+            return;
+        };
+        if !call
+            .expression()
+            .is_some_and(|callee| Arc::ptr_eq(callee, &argument))
+        {
+            self.get_resolved_signature(&call);
+        }
+    }
+
+    /// Determines if the type of an expression depends on its contextual type, because it
+    /// contains functions whose parameters are typed by it
+    pub(super) fn is_context_sensitive(&mut self, node: &Arc<Node>) -> bool {
+        match node.kind {
+            SyntaxKind::ArrowFunction | SyntaxKind::FunctionExpression => {
+                has_context_sensitive_parameters(node)
+                    || node.kind == SyntaxKind::ArrowFunction
+                        && node.body().is_some_and(|body| {
+                            body.kind != SyntaxKind::Block && self.is_context_sensitive(body)
+                        })
+            }
+            SyntaxKind::MethodDeclaration => has_context_sensitive_parameters(node),
+            SyntaxKind::ObjectLiteralExpression => {
+                node.as_object_literal_expression()
+                    .properties
+                    .iter()
+                    .any(|property| match property.kind {
+                        SyntaxKind::PropertyAssignment => self
+                            .is_context_sensitive(&property.as_property_assignment().initializer),
+                        SyntaxKind::MethodDeclaration => has_context_sensitive_parameters(property),
+                        _ => false,
+                    })
+            }
+            SyntaxKind::ArrayLiteralExpression => node
+                .as_array_literal_expression()
+                .elements
+                .iter()
+                .any(|element| self.is_context_sensitive(element)),
+            SyntaxKind::ConditionalExpression => {
+                let conditional = node.as_conditional_expression();
+                self.is_context_sensitive(&conditional.when_true)
+                    || self.is_context_sensitive(&conditional.when_false)
+            }
+            SyntaxKind::BinaryExpression => {
+                let binary = node.as_binary_expression();
+                matches!(
+                    binary.operator_token.kind,
+                    SyntaxKind::BarBarToken | SyntaxKind::QuestionQuestionToken
+                ) && (self.is_context_sensitive(&binary.left)
+                    || self.is_context_sensitive(&binary.right))
+            }
+            SyntaxKind::ParenthesizedExpression => {
+                self.is_context_sensitive(&node.as_parenthesized_expression().expression)
+            }
+            _ => false,
+        }
+    }
+
+    /// Gets the annotated return type of the function containing a return statement
+    pub(super) fn get_annotated_return_type(&mut self, node: &Arc<Node>) -> Option<TypeId> {
+        let function = get_containing_function(node)?;
+        function.type_node()?;
+        self.get_contextual_return_type(&function)
+    }
+
+    /// Gets the type the body of a function is expected to return, from its annotation or
+    /// from the contextual type of the function itself
+    fn get_contextual_return_type(&mut self, function: &Arc<Node>) -> Option<TypeId> {
+        if is_generator(function) {
+            return None;
+        }
+        // An async function returns what the promise it returns resolves to
+        if function.has_modifier(SyntaxKind::AsyncKeyword) {
+            let return_type = self.get_contextual_return_type_of_signature(function)?;
+            return Some(self.get_awaited_type(return_type));
+        }
+        self.get_contextual_return_type_of_signature(function)
+    }
+
+    /// Gets the return type of a function from its annotation or the signature of its
+    /// contextual type
+    fn get_contextual_return_type_of_signature(&mut self, function: &Arc<Node>) -> Option<TypeId> {
+        if let Some(type_node) = function.type_node() {
+            return Some(self.get_type_from_type_node(type_node));
+        }
+        let signature = self.get_contextual_signature(function)?;
+        Some(self.get_return_type_of_signature(signature))
+    }
+
+    /// Maps each constituent of a contextual type, ignoring those `f` has nothing for
+    fn map_contextual_type(
+        &mut self,
+        type_: TypeId,
+        mut f: impl FnMut(&mut Checker, TypeId) -> Option<TypeId>,
+    ) -> Option<TypeId> {
+        let TypeData::UnionOrIntersection { types } = &self.get_type(type_).data else {
+            return f(self, type_);
+        };
+        if !self.flags(type_).contains(TypeFlags::UNION) {
+            return f(self, type_);
+        }
+        let mapped: Vec<TypeId> = types
+            .clone()
+            .into_iter()
+            .filter_map(|t| f(self, t))
+            .collect();
+        if mapped.is_empty() {
+            return None;
+        }
+        Some(self.get_union_type(&mapped))
+    }
+
+    /// Determines if a literal type should keep its literal type in a context expecting
+    /// `contextual_type`, as `"a"` does where `"a" | "b"` is expected
+    pub(super) fn is_literal_of_contextual_type(
+        &mut self,
+        candidate: TypeId,
+        contextual_type: TypeId,
+    ) -> bool {
+        let flags = self.flags(contextual_type);
+        if flags.contains(TypeFlags::UNION)
+            && let TypeData::UnionOrIntersection { types } = &self.get_type(contextual_type).data
+        {
+            return types
+                .clone()
+                .into_iter()
+                .any(|type_| self.is_literal_of_contextual_type(candidate, type_));
+        }
+        if flags.contains(TypeFlags::TYPE_PARAMETER) {
+            // A type parameter constrained to a primitive keeps literals of that primitive
+            let constraint = self
+                .get_constraint_of_type_parameter(contextual_type)
+                .unwrap_or(self.unknown_type);
+            return self.maybe_type_of_kind(constraint, TypeFlags::STRING)
+                && self.maybe_type_of_kind(candidate, TypeFlags::STRING_LITERAL)
+                || self.maybe_type_of_kind(constraint, TypeFlags::NUMBER)
+                    && self.maybe_type_of_kind(candidate, TypeFlags::NUMBER_LITERAL)
+                || self.maybe_type_of_kind(constraint, TypeFlags::BIG_INT)
+                    && self.maybe_type_of_kind(candidate, TypeFlags::BIG_INT_LITERAL)
+                || self.maybe_type_of_kind(constraint, TypeFlags::BOOLEAN)
+                    && self.maybe_type_of_kind(candidate, TypeFlags::BOOLEAN_LITERAL)
+                || self.is_literal_of_contextual_type(candidate, constraint);
+        }
+        let maybe = |checker: &mut Checker, kind| checker.maybe_type_of_kind(candidate, kind);
+        flags.intersects(
+            TypeFlags::STRING_LITERAL
+                | TypeFlags::INDEX
+                | TypeFlags::TEMPLATE_LITERAL
+                | TypeFlags::STRING_MAPPING,
+        ) && maybe(self, TypeFlags::STRING_LITERAL)
+            || flags.intersects(TypeFlags::NUMBER_LITERAL) && maybe(self, TypeFlags::NUMBER_LITERAL)
+            || flags.intersects(TypeFlags::BIG_INT_LITERAL)
+                && maybe(self, TypeFlags::BIG_INT_LITERAL)
+            || flags.intersects(TypeFlags::BOOLEAN_LITERAL)
+                && maybe(self, TypeFlags::BOOLEAN_LITERAL)
+            || flags.intersects(TypeFlags::UNIQUE_ES_SYMBOL)
+                && maybe(self, TypeFlags::UNIQUE_ES_SYMBOL)
+    }
+
+    /// Widens a literal type unless its contextual type expects literals of its kind, in
+    /// which case it stays a regular literal type wherever it flows from there
+    pub(super) fn get_widened_literal_like_type_for_contextual_type(
+        &mut self,
+        type_: TypeId,
+        contextual_type: Option<TypeId>,
+    ) -> TypeId {
+        let type_ = match contextual_type {
+            Some(contextual_type) if self.is_literal_of_contextual_type(type_, contextual_type) => {
+                type_
+            }
+            _ => {
+                let type_ = self.get_widened_literal_type(type_);
+                self.get_widened_unique_es_symbol_type(type_)
+            }
+        };
+        self.get_regular_type_of_literal_type(type_)
+    }
+}
+
+/// Gets the nearest function-like declaration containing `node`
+pub(super) fn get_containing_function(node: &Arc<Node>) -> Option<Arc<Node>> {
+    let mut current = node.parent();
+    while let Some(node) = current {
+        if is_function_like_declaration_kind(node.kind) {
+            return Some(node);
+        }
+        if node.kind == SyntaxKind::SourceFile {
+            return None;
+        }
+        current = node.parent();
+    }
+    None
+}
+
+/// Determines if a function has parameters whose types come from its contextual type
+/// Determines if a function can take the types of its parameters and return from its
+/// contextual type: a function expression, arrow function, or object literal method
+fn is_contextually_typable_function(function: &Node) -> bool {
+    match function.kind {
+        SyntaxKind::ArrowFunction | SyntaxKind::FunctionExpression => true,
+        SyntaxKind::MethodDeclaration => function
+            .parent()
+            .is_some_and(|parent| parent.kind == SyntaxKind::ObjectLiteralExpression),
+        _ => false,
+    }
+}
+
+fn has_context_sensitive_parameters(function: &Node) -> bool {
+    if function
+        .type_parameters()
+        .is_some_and(|parameters| !parameters.is_empty())
+    {
+        return false;
+    }
+    let mut parameters = function.parameters().into_iter().flatten();
+    if parameters
+        .clone()
+        .any(|parameter| parameter.type_node().is_none())
+    {
+        return true;
+    }
+    // Functions other than arrow functions also take `this` from their contextual type
+    function.kind != SyntaxKind::ArrowFunction
+        && !parameters.next().is_some_and(|parameter| {
+            parameter
+                .name()
+                .is_some_and(|name| name.kind == SyntaxKind::Identifier && name.text() == "this")
+        })
+}
+
+pub(super) fn is_generator(function: &Node) -> bool {
+    match function.kind {
+        SyntaxKind::FunctionDeclaration => {
+            function.as_function_declaration().asterisk_token.is_some()
+        }
+        SyntaxKind::FunctionExpression => {
+            function.as_function_expression().asterisk_token.is_some()
+        }
+        SyntaxKind::MethodDeclaration => function.as_method_declaration().asterisk_token.is_some(),
+        _ => false,
+    }
+}
@@ -0,0 +1,1005 @@
+//! Types of symbols, declared types, and signatures of declarations
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{
+    is_binding_pattern, is_class_like, is_const_variable, is_function_like_kind, root_declaration,
+};
+use crate::compiler::ast::{
+    CheckFlags, Node, NodeFlags, NodeList, SymbolFlags, SymbolId, SyntaxKind,
+};
+use crate::compiler::binder::is_entity_name_expression;
+use crate::compiler::diagnostics;
+
+use super::assertions::{is_const_assertion, is_const_context};
+use super::contextual::is_generator;
+use super::iterators::IterationUse;
+use super::promises::is_async_function;
+use super::{
+    Checker, InterfaceType, ObjectFlags, ResolutionTarget, Signature, SignatureFlags, SignatureId,
+    TypeData, TypeFlags, TypeId,
+};
+
+impl Checker {
+    /// Gets the type of a value symbol, such as a variable, property, function, or class
+    pub fn get_type_of_symbol(&mut self, symbol: SymbolId) -> TypeId {
+        let symbol = self.get_merged_symbol(symbol);
+        if let Some(type_) = self.symbol_links(symbol).type_ {
+            return type_;
+        }
+        if self
+            .symbol(symbol)
+            .check_flags
+            .contains(CheckFlags::INSTANTIATED)
+        {
+            return self.get_type_of_instantiated_symbol(symbol);
+        }
+        let flags = self.symbol(symbol).flags;
+        let type_ = if flags.contains(SymbolFlags::PROTOTYPE) {
+            self.get_type_of_prototype_property(symbol)
+        } else if flags.intersects(SymbolFlags::VARIABLE | SymbolFlags::PROPERTY) {
+            // Resolving the call a callback is passed to types the callback's parameters, so
+            // it is resolved before the parameter's type is, which would otherwise be circular
+            if let Some(declaration) = self.symbol(symbol).value_declaration.clone()
+                && declaration.kind == SyntaxKind::Parameter
+            {
+                self.resolve_call_contextually_typing_parameter(&declaration);
+                if let Some(type_) = self.symbol_links(symbol).type_ {
+                    return type_;
+                }
+            }
+            match self.get_type_of_variable_or_property(symbol) {
+                Some(type_) => type_,
+                // Circular, so not cached
+                None => return self.any_type,
+            }
+        } else if flags.intersects(
+            SymbolFlags::FUNCTION
+                | SymbolFlags::METHOD
+                | SymbolFlags::CLASS
+                | SymbolFlags::ENUM
+                | SymbolFlags::VALUE_MODULE,
+        ) {
+            self.create_type(
+                TypeFlags::OBJECT,
+                ObjectFlags::ANONYMOUS,
+                Some(symbol),
+                TypeData::Anonymous {
+                    target: None,
+                    mapper: None,
+                },
+            )
+        } else if flags.contains(SymbolFlags::ENUM_MEMBER) {
+            self.get_declared_type_of_enum_member(symbol)
+        } else if flags.intersects(SymbolFlags::ACCESSOR) {
+            match self.get_type_of_accessors(symbol) {
+                Some(type_) => type_,
+                None => return self.any_type,
+            }
+        } else if flags.contains(SymbolFlags::ALIAS) {
+            let target = self.resolve_alias(symbol);
+            if self.symbol(target).flags.intersects(SymbolFlags::VALUE) {
+                self.get_type_of_symbol(target)
+            } else {
+                self.error_type
+            }
+        } else {
+            self.error_type
+        };
+        self.symbol_links(symbol).type_ = Some(type_);
+        type_
+    }
+
+    fn get_type_of_instantiated_symbol(&mut self, symbol: SymbolId) -> TypeId {
+        let links = self.symbol_links(symbol).clone();
+        let target = links.target.expect("instantiated symbols have a target");
+        let mapper = links.mapper.expect("instantiated symbols have a mapper");
+        let target_type = self.get_type_of_symbol(target);
+        let type_ = self.instantiate_type(target_type, &mapper);
+        self.symbol_links(symbol).type_ = Some(type_);
+        type_
+    }
+
+    /// The `prototype` property of a class has the instance type with `any` type arguments
+    fn get_type_of_prototype_property(&mut self, symbol: SymbolId) -> TypeId {
+        let Some(class) = self.symbol(symbol).parent else {
+            return self.error_type;
+        };
+        let class_type = self.get_declared_type_of_symbol(class);
+        match &self.get_type(class_type).data {
+            TypeData::Interface(interface) if interface.is_generic() => {
+                let arguments = vec![self.any_type; interface.type_parameters().len()];
+                self.create_type_reference(class_type, arguments)
+            }
+            _ => class_type,
+        }
+    }
+
+    /// Gets the type of a variable, parameter, or property, or None if it depends on itself
+    fn get_type_of_variable_or_property(&mut self, symbol: SymbolId) -> Option<TypeId> {
+        let Some(declaration) = self.symbol(symbol).value_declaration.clone() else {
+            return Some(self.error_type);
+        };
+        if !self.push_type_resolution(ResolutionTarget::Type(symbol)) {
+            return None;
+        }
+        let type_ = self.get_type_for_variable_like_declaration(&declaration);
+        if !self.pop_type_resolution() {
+            self.report_circular_type(symbol, &declaration);
+            return Some(self.any_type);
+        }
+        if declaration.kind == SyntaxKind::Parameter
+            && declaration.as_parameter().question_token.is_some()
+        {
+            return Some(self.get_optional_type(type_, false));
+        }
+        if self.symbol(symbol).flags.contains(SymbolFlags::OPTIONAL) {
+            return Some(self.get_optional_type(type_, true));
+        }
+        Some(type_)
+    }
+
+    pub(super) fn get_type_for_variable_like_declaration(
+        &mut self,
+        declaration: &Arc<Node>,
+    ) -> TypeId {
+        match declaration.kind {
+            SyntaxKind::VariableDeclaration => {
+                let list = declaration.parent();
+                let statement = list.as_ref().and_then(|list| list.parent());
+                match statement.as_ref().map(|statement| statement.kind) {
+                    Some(SyntaxKind::ForInStatement) => return self.string_type,
+                    Some(SyntaxKind::ForOfStatement) => {
+                        let statement = statement.unwrap();
+                        let statement = statement.as_for_of_statement();
+                        let iterated = self.check_expression(&statement.expression);
+                        // The statement reports what cannot be iterated
+                        if statement.await_modifier.is_some() {
+                            let element_type = self.get_element_type_of_iterable(iterated);
+                            return self.get_awaited_type(element_type);
+                        }
+                        return self.get_iterated_type(iterated, IterationUse::ForOf, None);
+                    }
+                    _ => {}
+                }
+                if let Some(type_node) = declaration.type_node() {
+                    return self.get_type_from_type_node(type_node);
+                }
+                if root_declaration(declaration)
+                    .parent()
+                    .is_some_and(|parent| parent.kind == SyntaxKind::CatchClause)
+                {
+                    return if self.options.use_unknown_in_catch_variables {
+                        self.unknown_type
+                    } else {
+                        self.any_type
+                    };
+                }
+                if self.is_auto_array_declaration(declaration) {
+                    return self.get_auto_array_type();
+                }
+                match declaration.initializer() {
+                    Some(initializer) => {
+                        let type_ = self.check_expression(initializer);
+                        let keep_literals =
+                            is_const_variable(declaration) || is_const_assertion(initializer);
+                        self.widen_type_for_declaration(type_, keep_literals)
+                    }
+                    None => match declaration.name() {
+                        Some(name) if is_binding_pattern(name) => {
+                            self.get_type_from_binding_pattern(name)
+                        }
+                        _ => self.any_type,
+                    },
+                }
+            }
+            SyntaxKind::BindingElement => {
+                let type_ = self.get_type_of_binding_element(declaration);
+                self.widen_type_for_declaration(type_, is_const_variable(declaration))
+            }
+            SyntaxKind::Parameter => {
+                let parameter = declaration.as_parameter();
+                if let Some(type_node) = &parameter.type_node {
+                    return self.get_type_from_type_node(type_node);
+                }
+                if let Some(type_) = self.get_contextually_typed_parameter_type(declaration) {
+                    return type_;
+                }
+                if let Some(initializer) = &parameter.initializer {
+                    let type_ = self.check_expression(initializer);
+                    return self.widen_type_for_declaration(type_, false);
+                }
+                if parameter.dot_dot_dot_token.is_some() {
+                    return self.create_array_type(self.any_type);
+                }
+                if is_binding_pattern(&parameter.name) {
+                    return self.get_type_from_binding_pattern(&parameter.name);
+                }
+                self.any_type
+            }
+            SyntaxKind::PropertyDeclaration | SyntaxKind::PropertySignature => {
+                if let Some(type_node) = declaration.type_node() {
+                    return self.get_type_from_type_node(type_node);
+                }
+                match declaration.initializer() {
+                    Some(initializer) => {
+                        let type_ = self.check_expression(initializer);
+                        let keep_literals = declaration.has_modifier(SyntaxKind::ReadonlyKeyword)
+                            || is_const_assertion(initializer);
+                        self.widen_type_for_declaration(type_, keep_literals)
+                    }
+                    None => self.any_type,
+                }
+            }
+            SyntaxKind::PropertyAssignment => {
+                let initializer = &declaration.as_property_assignment().initializer;
+                let type_ = self.check_expression(initializer);
+                if is_const_context(initializer) {
+                    return self.get_regular_type_of_literal_type(type_);
+                }
+                let contextual_type = self.get_contextual_type(initializer);
+                self.get_widened_literal_like_type_for_contextual_type(type_, contextual_type)
+            }
+            SyntaxKind::ShorthandPropertyAssignment => {
+                let name = &declaration.as_shorthand_property_assignment().name;
+                let type_ = self.check_identifier(name);
+                if is_const_context(name) {
+                    return self.get_regular_type_of_literal_type(type_);
+                }
+                let type_ = self.get_widened_literal_type(type_);
+                self.get_widened_unique_es_symbol_type(type_)
+            }
+            // The value a JSON file exports is the one expression it holds
+            SyntaxKind::SourceFile => {
+                let statements = &declaration.as_source_file().statements;
+                match statements.nodes.first() {
+                    Some(statement) => {
+                        let expression = &statement.as_expression_statement().expression;
+                        let type_ = self.check_expression(expression);
+                        self.widen_type_for_declaration(type_, false)
+                    }
+                    None => self.empty_object_type,
+                }
+            }
+            _ => self.any_type,
+        }
+    }
+
+    /// Reports a declaration without a type annotation whose type is implicitly `any`, because
+    /// nothing it is initialized with or contextually typed by gives it a type
+    ///
+    /// Local variables without initializers are typed by what they are assigned, so only
+    /// ambient ones are reported, and variables initialized with `any` are explicitly `any`.
+    pub(super) fn report_implicit_any_declaration(
+        &mut self,
+        node: &Arc<Node>,
+        type_: TypeId,
+        initializer_type: Option<TypeId>,
+    ) {
+        let Some(name) = node
+            .name()
+            .filter(|name| name.kind == SyntaxKind::Identifier)
+        else {
+            return;
+        };
+        let initialized_with_any =
+            initializer_type.is_some_and(|type_| self.flags(type_).contains(TypeFlags::ANY));
+        match node.kind {
+            SyntaxKind::Parameter => {
+                let parameter = node.as_parameter();
+                if parameter.initializer.is_some()
+                    || name.text() == "this"
+                    || node
+                        .parent()
+                        .is_some_and(|parent| parent.kind == SyntaxKind::SetAccessor)
+                    || self.get_contextually_typed_parameter_type(node).is_some()
+                {
+                    return;
+                }
+                if parameter.dot_dot_dot_token.is_some() {
+                    self.error(
+                        node,
+                        diagnostics::REST_PARAMETER_0_IMPLICITLY_HAS_AN_ANY_TYPE_7019,
+                        &[name.text().to_string()],
+                    );
+                } else if type_ == self.any_type {
+                    self.error(
+                        node,
+                        diagnostics::PARAMETER_0_IMPLICITLY_HAS_AN_1_TYPE_7006,
+                        &[name.text().to_string(), "any".to_string()],
+                    );
+                }
+            }
+            SyntaxKind::VariableDeclaration => {
+                let reported = match initializer_type {
+                    Some(_) => !initialized_with_any,
+                    None => node.flags.contains(NodeFlags::AMBIENT),
+                };
+                if reported && type_ == self.any_type {
+                    self.error(
+                        name,
+                        diagnostics::VARIABLE_0_IMPLICITLY_HAS_AN_1_TYPE_7005,
+                        &[name.text().to_string(), "any".to_string()],
+                    );
+                }
+            }
+            SyntaxKind::PropertyDeclaration | SyntaxKind::PropertySignature
+                if !initialized_with_any && type_ == self.any_type =>
+            {
+                self.error(
+                    name,
+                    diagnostics::MEMBER_0_IMPLICITLY_HAS_AN_1_TYPE_7008,
+                    &[name.text().to_string(), "any".to_string()],
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Reports a signature without a body or a return type annotation, whose return type is
+    /// implicitly `any`
+    pub(super) fn report_implicit_any_return_type(&mut self, node: &Arc<Node>) {
+        match node.kind {
+            SyntaxKind::CallSignature => self.error(
+                node,
+                diagnostics::CALL_SIGNATURE_WHICH_LACKS_RETURN_TYPE_ANNOTATION_IMPLICITLY_HAS_AN_ANY_RETURN_TYPE_7020,
+                &[],
+            ),
+            SyntaxKind::ConstructSignature => self.error(
+                node,
+                diagnostics::CONSTRUCT_SIGNATURE_WHICH_LACKS_RETURN_TYPE_ANNOTATION_IMPLICITLY_HAS_AN_ANY_RETURN_TYPE_7013,
+                &[],
+            ),
+            SyntaxKind::FunctionDeclaration
+            | SyntaxKind::MethodDeclaration
+            | SyntaxKind::MethodSignature => {
+                let Some(name) = node.name() else {
+                    return;
+                };
+                self.error(
+                    name,
+                    diagnostics::D_0_WHICH_LACKS_RETURN_TYPE_ANNOTATION_IMPLICITLY_HAS_AN_1_RETURN_TYPE_7010,
+                    &[name.text().to_string(), "any".to_string()],
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Widens the type of an initializer for the declaration it initializes
+    ///
+    /// Constants and readonly properties keep literal types and unique symbols; other
+    /// declarations get the primitive type of the literal, and `symbol`.
+    fn widen_type_for_declaration(&mut self, type_: TypeId, keep_literals: bool) -> TypeId {
+        let type_ = if keep_literals {
+            type_
+        } else {
+            let type_ = self.get_widened_literal_type(type_);
+            self.get_widened_unique_es_symbol_type(type_)
+        };
+        self.get_widened_type(type_)
+    }
+
+    fn get_type_of_accessors(&mut self, symbol: SymbolId) -> Option<TypeId> {
+        let declarations = self.symbol(symbol).declarations.clone();
+        let getter = declarations
+            .iter()
+            .find(|declaration| declaration.kind == SyntaxKind::GetAccessor);
+        let setter = declarations
+            .iter()
+            .find(|declaration| declaration.kind == SyntaxKind::SetAccessor);
+        if let Some(type_node) = getter.and_then(|getter| getter.type_node()) {
+            return Some(self.get_type_from_type_node(type_node));
+        }
+        let setter_parameter_type = setter
+            .and_then(|setter| setter.parameters())
+            .and_then(|parameters| parameters.iter().next())
+            .and_then(|parameter| parameter.type_node());
+        if let Some(type_node) = setter_parameter_type {
+            return Some(self.get_type_from_type_node(type_node));
+        }
+        if let Some(getter) = getter {
+            if !self.push_type_resolution(ResolutionTarget::Type(symbol)) {
+                return None;
+            }
+            let signature = self.get_signature_of_declaration(getter);
+            let type_ = match signature {
+                Some(signature) => self.get_return_type_of_signature(signature),
+                None => self.any_type,
+            };
+            if !self.pop_type_resolution() {
+                self.report_circular_accessor_type(symbol, getter);
+                return Some(self.any_type);
+            }
+            return Some(type_);
+        }
+        Some(self.any_type)
+    }
+
+    /// Gets the type a type symbol declares, such as the instance type of a class or the type
+    /// a type alias stands for
+    pub fn get_declared_type_of_symbol(&mut self, symbol: SymbolId) -> TypeId {
+        let symbol = self.get_merged_symbol(symbol);
+        if let Some(type_) = self.symbol_links(symbol).declared_type {
+            return type_;
+        }
+        let flags = self.symbol(symbol).flags;
+        let type_ = if flags.intersects(SymbolFlags::CLASS | SymbolFlags::INTERFACE) {
+            self.get_declared_type_of_class_or_interface(symbol)
+        } else if flags.contains(SymbolFlags::TYPE_ALIAS) {
+            self.get_declared_type_of_type_alias(symbol)
+        } else if flags.contains(SymbolFlags::TYPE_PARAMETER) {
+            self.create_type(
+                TypeFlags::TYPE_PARAMETER,
+                ObjectFlags::NONE,
+                Some(symbol),
+                TypeData::TypeParameter {
+                    constraint: None,
+                    is_this_type: false,
+                },
+            )
+        } else if flags.intersects(SymbolFlags::ENUM) {
+            self.get_declared_type_of_enum(symbol)
+        } else if flags.contains(SymbolFlags::ENUM_MEMBER) {
+            self.get_declared_type_of_enum_member(symbol)
+        } else if flags.contains(SymbolFlags::ALIAS) {
+            let target = self.resolve_alias(symbol);
+            if target != self.unknown_symbol
+                && self.symbol(target).flags.intersects(SymbolFlags::TYPE)
+            {
+                self.get_declared_type_of_symbol(target)
+            } else {
+                self.error_type
+            }
+        } else {
+            self.error_type
+        };
+        self.symbol_links(symbol).declared_type = Some(type_);
+        type_
+    }
+
+    fn get_declared_type_of_class_or_interface(&mut self, symbol: SymbolId) -> TypeId {
+        let flags = self.symbol(symbol).flags;
+        let object_flags = if flags.contains(SymbolFlags::CLASS) {
+            ObjectFlags::CLASS
+        } else {
+            ObjectFlags::INTERFACE
+        };
+        let declarations = self.symbol(symbol).declarations.clone();
+        let outer_type_parameters = declarations
+            .iter()
+            .find(|declaration| {
+                is_class_like(declaration) || declaration.kind == SyntaxKind::InterfaceDeclaration
+            })
+            .map(|declaration| self.get_outer_type_parameters(declaration))
+            .unwrap_or_default();
+        // The declared type is created before its type parameters, which may refer back to it
+        let type_ = self.create_type(
+            TypeFlags::OBJECT,
+            object_flags,
+            Some(symbol),
+            TypeData::Interface(InterfaceType::default()),
+        );
+        self.symbol_links(symbol).declared_type = Some(type_);
+        let mut local_type_parameters = Vec::new();
+        for declaration in &declarations {
+            if !is_class_like(declaration) && declaration.kind != SyntaxKind::InterfaceDeclaration {
+                continue;
+            }
+            for type_parameter in declaration.type_parameters().into_iter().flatten() {
+                if let Some(parameter_symbol) = self.get_symbol_of_declaration(type_parameter) {
+                    let parameter = self.get_declared_type_of_symbol(parameter_symbol);
+                    if !local_type_parameters.contains(&parameter) {
+                        local_type_parameters.push(parameter);
+                    }
+                }
+            }
+        }
+        let interface = InterfaceType {
+            outer_type_parameters,
+            local_type_parameters,
+            ..InterfaceType::default()
+        };
+        if interface.is_generic() {
+            self.types[type_.index()].object_flags |= ObjectFlags::REFERENCE;
+        }
+        self.types[type_.index()].data = TypeData::Interface(interface);
+        type_
+    }
+
+    fn get_declared_type_of_type_alias(&mut self, symbol: SymbolId) -> TypeId {
+        let Some(declaration) = self
+            .symbol(symbol)
+            .declarations
+            .iter()
+            .find(|declaration| declaration.kind == SyntaxKind::TypeAliasDeclaration)
+            .cloned()
+        else {
+            return self.error_type;
+        };
+        if !self.push_type_resolution(ResolutionTarget::DeclaredType(symbol)) {
+            return self.error_type;
+        }
+        let type_parameters = self.get_local_type_parameters(declaration.type_parameters());
+        self.symbol_links(symbol).type_parameters = Some(type_parameters.clone());
+        let type_node = &declaration.as_type_alias_declaration().type_node;
+        let type_ = self.get_type_from_type_node(type_node);
+        if !self.pop_type_resolution() {
+            let name = &declaration.as_type_alias_declaration().name;
+            self.error(
+                name,
+                diagnostics::TYPE_ALIAS_0_CIRCULARLY_REFERENCES_ITSELF_2456,
+                &[name.text().to_string()],
+            );
+            return self.error_type;
+        }
+        if writes_new_type(type_node) {
+            self.get_type_with_alias(type_, symbol, type_parameters)
+        } else {
+            type_
+        }
+    }
+
+    /// Gets the local type parameters of a type alias, loading them if needed
+    pub(super) fn get_type_parameters_of_type_alias(&mut self, symbol: SymbolId) -> Vec<TypeId> {
+        if let Some(type_parameters) = &self.symbol_links(symbol).type_parameters {
+            return type_parameters.clone();
+        }
+        let declaration = self
+            .symbol(symbol)
+            .declarations
+            .iter()
+            .find(|declaration| declaration.kind == SyntaxKind::TypeAliasDeclaration)
+            .cloned();
+        let type_parameters = match declaration {
+            Some(declaration) => self.get_local_type_parameters(declaration.type_parameters()),
+            None => Vec::new(),
+        };
+        self.symbol_links(symbol).type_parameters = Some(type_parameters.clone());
+        type_parameters
+    }
+
+    pub(super) fn get_local_type_parameters(&mut self, nodes: Option<&NodeList>) -> Vec<TypeId> {
+        let mut result = Vec::new();
+        for node in nodes.into_iter().flatten() {
+            if let Some(symbol) = self.get_symbol_of_declaration(node) {
+                result.push(self.get_declared_type_of_symbol(symbol));
+            }
+        }
+        result
+    }
+
+    /// Gets the type parameters in scope at a declaration, outermost first, not including the
+    /// declaration's own
+    pub(super) fn get_outer_type_parameters(&mut self, node: &Arc<Node>) -> Vec<TypeId> {
+        let mut containers = Vec::new();
+        let mut current = node.parent();
+        while let Some(container) = current {
+            // A conditional type contributes its `infer` type parameters
+            if is_function_like_kind(container.kind)
+                || is_class_like(&container)
+                || matches!(
+                    container.kind,
+                    SyntaxKind::InterfaceDeclaration
+                        | SyntaxKind::TypeAliasDeclaration
+                        | SyntaxKind::MappedType
+                        | SyntaxKind::ConditionalType
+                )
+            {
+                containers.push(Arc::clone(&container));
+            }
+            current = container.parent();
+        }
+        let mut result = Vec::new();
+        for container in containers.iter().rev() {
+            if container.kind == SyntaxKind::ConditionalType {
+                result.extend(self.get_infer_type_parameters(container));
+                continue;
+            }
+            if container.kind == SyntaxKind::MappedType {
+                let parameter = &container.as_mapped_type_node().type_parameter;
+                if let Some(symbol) = self.get_symbol_of_declaration(parameter) {
+                    result.push(self.get_declared_type_of_symbol(symbol));
+                }
+                continue;
+            }
+            if is_class_like(container) || container.kind == SyntaxKind::InterfaceDeclaration {
+                // Merged declarations share the type parameters of the declared type
+                if let Some(symbol) = self.get_symbol_of_declaration(container) {
+                    let declared = self.get_declared_type_of_symbol(symbol);
+                    if let TypeData::Interface(interface) = &self.get_type(declared).data {
+                        result.extend(interface.local_type_parameters.clone());
+                    }
+                }
+                continue;
+            }
+            let parameters = self.get_local_type_parameters(container.type_parameters());
+            result.extend(parameters);
+        }
+        result
+    }
+
+    /// Gets the constraint of a type parameter, if it has one that does not circularly
+    /// constrain it
+    pub(super) fn get_constraint_of_type_parameter(&mut self, type_: TypeId) -> Option<TypeId> {
+        if self.has_circular_constraint(type_) {
+            return None;
+        }
+        self.get_constraint_from_type_parameter(type_)
+    }
+
+    /// Gets the constraint a type parameter declares, even one that circularly constrains it
+    pub(super) fn get_constraint_from_type_parameter(&mut self, type_: TypeId) -> Option<TypeId> {
+        let TypeData::TypeParameter { constraint, .. } = &self.get_type(type_).data else {
+            return None;
+        };
+        if let Some(constraint) = constraint {
+            return *constraint;
+        }
+        let constraint_node = self
+            .get_type(type_)
+            .symbol
+            .and_then(|symbol| self.symbol(symbol).declarations.first().cloned())
+            .filter(|declaration| declaration.kind == SyntaxKind::TypeParameter)
+            .and_then(|declaration| declaration.as_type_parameter().constraint.clone());
+        // A constraint that depends on itself while it is resolved is no constraint
+        self.set_type_parameter_constraint(type_, None);
+        let constraint = constraint_node.map(|node| self.get_type_from_type_node(&node));
+        self.set_type_parameter_constraint(type_, constraint);
+        constraint
+    }
+
+    fn set_type_parameter_constraint(&mut self, type_: TypeId, value: Option<TypeId>) {
+        if let TypeData::TypeParameter { constraint, .. } = &mut self.types[type_.index()].data {
+            *constraint = Some(value);
+        }
+    }
+
+    /// Gets the default of a type parameter, if it has one
+    pub(super) fn get_default_of_type_parameter(&mut self, type_: TypeId) -> Option<TypeId> {
+        let default_node = self
+            .get_type(type_)
+            .symbol
+            .and_then(|symbol| self.symbol(symbol).declarations.first().cloned())
+            .filter(|declaration| declaration.kind == SyntaxKind::TypeParameter)
+            .and_then(|declaration| declaration.as_type_parameter().default_type.clone())?;
+        Some(self.get_type_from_type_node(&default_node))
+    }
+
+    /// Gets the base types of a class or interface type
+    pub(super) fn get_base_types(&mut self, type_: TypeId) -> Vec<TypeId> {
+        if let Some(bases) = self.resolved_base_types.get(&type_) {
+            return bases.clone();
+        }
+        self.resolve_deferred_type_arguments(type_);
+        let (target, arguments) = match &self.get_type(type_).data {
+            TypeData::Reference {
+                target,
+                type_arguments,
+            } => (*target, Some(type_arguments.clone())),
+            TypeData::Interface(_) => (type_, None),
+            _ => return Vec::new(),
+        };
+        if let Some(arguments) = arguments {
+            let bases = self.get_base_types(target);
+            let mapper = self.create_type_reference_mapper(target, &arguments);
+            let bases: Vec<TypeId> = bases
+                .into_iter()
+                .map(|base| self.instantiate_type(base, &mapper))
+                .collect();
+            self.resolved_base_types.insert(type_, bases.clone());
+            return bases;
+        }
+        if !self.push_type_resolution(ResolutionTarget::BaseTypes(type_)) {
+            return Vec::new();
+        }
+        let bases = self.resolve_base_types(type_);
+        if !self.pop_type_resolution() {
+            self.report_circular_base_type(type_);
+            self.resolved_base_types.insert(type_, Vec::new());
+            return Vec::new();
+        }
+        self.resolved_base_types.insert(type_, bases.clone());
+        bases
+    }
+
+    fn resolve_base_types(&mut self, type_: TypeId) -> Vec<TypeId> {
+        let Some(symbol) = self.get_type(type_).symbol else {
+            return self
+                .resolved_base_types
+                .get(&type_)
+                .cloned()
+                .unwrap_or_default();
+        };
+        let mut bases = Vec::new();
+        for declaration in self.symbol(symbol).declarations.clone() {
+            let clauses = match declaration.kind {
+                SyntaxKind::ClassDeclaration => {
+                    declaration.as_class_declaration().heritage_clauses.clone()
+                }
+                SyntaxKind::ClassExpression => {
+                    declaration.as_class_expression().heritage_clauses.clone()
+                }
+                SyntaxKind::InterfaceDeclaration => declaration
+                    .as_interface_declaration()
+                    .heritage_clauses
+                    .clone(),
+                _ => continue,
+            };
+            for clause in clauses.iter().flatten() {
+                let heritage = clause.as_heritage_clause();
+                let is_class_extends =
+                    is_class_like(&declaration) && heritage.token == SyntaxKind::ExtendsKeyword;
+                if is_class_like(&declaration) && !is_class_extends {
+                    // Implemented interfaces are not base types
+                    continue;
+                }
+                for node in &heritage.types {
+                    let base = if is_class_extends {
+                        self.get_base_type_of_class_extends(node)
+                    } else {
+                        Some(self.get_type_from_type_node(node))
+                    };
+                    if let Some(base) = base
+                        && self
+                            .flags(base)
+                            .intersects(TypeFlags::OBJECT | TypeFlags::INTERSECTION)
+                        && base != type_
+                    {
+                        bases.push(base);
+                    }
+                }
+            }
+        }
+        bases
+    }
+
+    /// Gets the instance type a class extends, from the class it names or from the construct
+    /// signatures of the expression
+    fn get_base_type_of_class_extends(&mut self, node: &Arc<Node>) -> Option<TypeId> {
+        let expression = &node.as_expression_with_type_arguments().expression;
+        if is_entity_name_expression(expression)
+            && let Some(symbol) = self.resolve_entity_name(expression, SymbolFlags::VALUE, true)
+        {
+            let target = self.resolve_alias_if_alias(symbol);
+            if self.symbol(target).flags.contains(SymbolFlags::CLASS) {
+                return Some(self.get_type_from_type_node(node));
+            }
+        }
+        let constructor_type = self.check_expression(expression);
+        if self.flags(constructor_type).intersects(TypeFlags::ANY) {
+            return None;
+        }
+        let signatures = self.get_signatures_of_type(constructor_type, true);
+        let signature = *signatures.first()?;
+        Some(self.get_return_type_of_signature(signature))
+    }
+
+    fn report_circular_base_type(&mut self, type_: TypeId) {
+        let Some(symbol) = self.get_type(type_).symbol else {
+            return;
+        };
+        let name = self.symbol(symbol).name.clone();
+        for declaration in self.symbol(symbol).declarations.clone() {
+            let Some(name_node) = declaration.name().cloned() else {
+                continue;
+            };
+            if is_class_like(&declaration) {
+                self.error(
+                    &name_node,
+                    diagnostics::D_0_IS_REFERENCED_DIRECTLY_OR_INDIRECTLY_IN_ITS_OWN_BASE_EXPRESSION_2506,
+                    std::slice::from_ref(&name),
+                );
+            } else if declaration.kind == SyntaxKind::InterfaceDeclaration {
+                let type_name = self.type_to_string(type_);
+                self.error(
+                    &name_node,
+                    diagnostics::TYPE_0_RECURSIVELY_REFERENCES_ITSELF_AS_A_BASE_TYPE_2310,
+                    &[type_name],
+                );
+            }
+        }
+    }
+
+    /// Gets the signature a function-like declaration declares
+    pub(super) fn get_signature_of_declaration(
+        &mut self,
+        declaration: &Arc<Node>,
+    ) -> Option<SignatureId> {
+        if !is_function_like_kind(declaration.kind) {
+            return None;
+        }
+        if let Some(signature) = self.node_links(declaration).declared_signature {
+            return Some(signature);
+        }
+        let mut flags = SignatureFlags::NONE;
+        if matches!(
+            declaration.kind,
+            SyntaxKind::Constructor | SyntaxKind::ConstructSignature | SyntaxKind::ConstructorType
+        ) {
+            flags |= SignatureFlags::CONSTRUCT;
+        }
+        if declaration.has_modifier(SyntaxKind::AbstractKeyword) {
+            flags |= SignatureFlags::ABSTRACT;
+        }
+        let mut parameters = Vec::new();
+        let mut this_parameter = None;
+        let mut min_argument_count = 0;
+        for parameter in declaration.parameters().into_iter().flatten() {
+            let Some(symbol) = self.get_symbol_of_declaration(parameter) else {
+                continue;
+            };
+            let node = parameter.as_parameter();
+            if node.name.kind == SyntaxKind::Identifier && node.name.text() == "this" {
+                this_parameter = Some(symbol);
+                continue;
+            }
+            parameters.push(symbol);
+            if node.dot_dot_dot_token.is_some() {
+                flags |= SignatureFlags::HAS_REST_PARAMETER;
+            } else if node.question_token.is_none() && node.initializer.is_none() {
+                min_argument_count = parameters.len();
+            }
+        }
+        let type_parameters = if declaration.kind == SyntaxKind::Constructor {
+            // Constructors are generic in the type parameters of their class
+            let class = declaration.parent();
+            match class.and_then(|class| self.get_symbol_of_declaration(&class)) {
+                Some(class) => {
+                    let class_type = self.get_declared_type_of_symbol(class);
+                    match &self.get_type(class_type).data {
+                        TypeData::Interface(interface) => interface.local_type_parameters.clone(),
+                        _ => Vec::new(),
+                    }
+                }
+                None => Vec::new(),
+            }
+        } else {
+            self.get_local_type_parameters(declaration.type_parameters())
+        };
+        let signature = self.create_signature(Signature {
+            flags,
+            declaration: Some(Arc::clone(declaration)),
+            type_parameters,
+            this_parameter,
+            parameters,
+            min_argument_count,
+            resolved_return_type: None,
+            target: None,
+            mapper: None,
+        });
+        self.node_links(declaration).declared_signature = Some(signature);
+        Some(signature)
+    }
+
+    pub(super) fn create_signature(&mut self, signature: Signature) -> SignatureId {
+        let id = SignatureId(self.signatures.len() as u32);
+        self.signatures.push(signature);
+        id
+    }
+
+    /// Gets the return type of a signature, inferring it from the body of its declaration when
+    /// it has no annotation
+    pub fn get_return_type_of_signature(&mut self, signature: SignatureId) -> TypeId {
+        if let Some(type_) = self.get_signature(signature).resolved_return_type {
+            return type_;
+        }
+        let type_ = if let (Some(target), Some(mapper)) = (
+            self.get_signature(signature).target,
+            self.get_signature(signature).mapper.clone(),
+        ) {
+            let target_type = self.get_return_type_of_signature(target);
+            self.instantiate_type(target_type, &mapper)
+        } else if let Some(declaration) = self.get_signature(signature).declaration.clone() {
+            if !self.push_type_resolution(ResolutionTarget::ReturnType(signature)) {
+                return self.any_type;
+            }
+            let type_ = self.get_return_type_from_declaration(&declaration);
+            if !self.pop_type_resolution() {
+                self.report_circular_return_type(&declaration);
+                self.any_type
+            } else {
+                type_
+            }
+        } else {
+            self.any_type
+        };
+        self.signatures[signature.index()].resolved_return_type = Some(type_);
+        type_
+    }
+
+    fn get_return_type_from_declaration(&mut self, declaration: &Arc<Node>) -> TypeId {
+        if declaration.kind == SyntaxKind::Constructor {
+            let class = declaration.parent();
+            return match class.and_then(|class| self.get_symbol_of_declaration(&class)) {
+                Some(class) => self.get_declared_type_of_symbol(class),
+                None => self.error_type,
+            };
+        }
+        if let Some(type_node) = declaration.type_node() {
+            return self.get_type_from_type_node(type_node);
+        }
+        if declaration.kind == SyntaxKind::SetAccessor {
+            return self.void_type;
+        }
+        let Some(body) = declaration.body() else {
+            return self.any_type;
+        };
+        if is_generator(declaration) {
+            return self.get_generator_return_type(declaration, body);
+        }
+        if !is_async_function(declaration) {
+            return self.infer_return_type(body, false);
+        }
+        let promised_type = self.infer_return_type(body, true);
+        self.create_promise_return_type(declaration, promised_type)
+    }
+
+    /// Infers a return type from the expressions a function body returns, or for an async
+    /// function the type its promise resolves to, from the awaited types of those expressions
+    pub(super) fn infer_return_type(&mut self, body: &Arc<Node>, is_async: bool) -> TypeId {
+        if body.kind != SyntaxKind::Block {
+            let type_ = self.check_expression(body);
+            let type_ = if is_async {
+                self.get_awaited_type(type_)
+            } else {
+                type_
+            };
+            let type_ = self.get_widened_literal_type(type_);
+            return self.get_widened_type(type_);
+        }
+        let mut returned = Vec::new();
+        collect_return_expressions(body, &mut returned);
+        if returned.is_empty() {
+            return self.void_type;
+        }
+        let mut types = Vec::new();
+        for expression in &returned {
+            let type_ = self.check_expression(expression);
+            let type_ = if is_async {
+                self.get_awaited_type(type_)
+            } else {
+                type_
+            };
+            types.push(self.get_widened_literal_type(type_));
+        }
+        let type_ = self.get_union_type(&types);
+        self.get_widened_type(type_)
+    }
+}
+
+/// Determines if a type alias writes a type that only exists through the alias, which is then
+/// printed by the alias name
+fn writes_new_type(type_node: &Node) -> bool {
+    match type_node.kind {
+        SyntaxKind::ParenthesizedType => {
+            writes_new_type(&type_node.as_parenthesized_type_node().type_node)
+        }
+        SyntaxKind::UnionType
+        | SyntaxKind::IntersectionType
+        | SyntaxKind::ConditionalType
+        | SyntaxKind::TypeLiteral
+        | SyntaxKind::MappedType
+        | SyntaxKind::FunctionType
+        | SyntaxKind::ConstructorType => true,
+        _ => false,
+    }
+}
+
+/// Collects the expressions returned from a function body, not including those of nested
+/// functions and classes
+fn collect_return_expressions(node: &Arc<Node>, result: &mut Vec<Arc<Node>>) {
+    node.for_each_child(&mut |child| {
+        if child.kind == SyntaxKind::ReturnStatement {
+            if let Some(expression) = &child.as_return_statement().expression {
+                result.push(Arc::clone(expression));
+            }
+        } else if !is_function_like_kind(child.kind)
+            && !is_class_like(child)
+            && child.kind != SyntaxKind::ClassStaticBlockDeclaration
+        {
+            collect_return_expressions(child, result);
+        }
+        false
+    });
+}
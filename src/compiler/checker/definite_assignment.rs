@@ -0,0 +1,100 @@
+//! Definite assignment: whether variables and properties declared without initializers are
+//! assigned before they are read
+//!
+//! Under `strictNullChecks`, reading a local variable walks the control flow graph back to its
+//! assignments, starting from a declaration that leaves it `undefined`, and a read that may
+//! still see `undefined` is reported. Under `strictPropertyInitialization` the constructor
+//! does the same for the properties of `this` it must initialize. A definite assignment
+//! assertion `!` on a declaration opts out of both.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{is_class_like, is_static};
+use crate::compiler::ast::{Node, NodeFlags, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics;
+
+use super::Checker;
+use super::expressions::get_control_flow_container;
+
+impl Checker {
+    /// Reports a definite assignment assertion `!` that has nothing to assert: one on a
+    /// declaration with an initializer or without a type annotation, or on a declaration other
+    /// than a variable of a variable statement or an instance property of a class
+    pub(super) fn check_definite_assignment_assertion(&mut self, node: &Arc<Node>) {
+        let exclamation = match node.kind {
+            SyntaxKind::VariableDeclaration => {
+                node.as_variable_declaration().exclamation_token.clone()
+            }
+            SyntaxKind::PropertyDeclaration => node
+                .as_property_declaration()
+                .postfix_token
+                .clone()
+                .filter(|token| token.kind == SyntaxKind::ExclamationToken),
+            _ => None,
+        };
+        let Some(exclamation) = exclamation else {
+            return;
+        };
+        let permitted = !node.flags.contains(NodeFlags::AMBIENT)
+            && if node.kind == SyntaxKind::VariableDeclaration {
+                node.parent()
+                    .and_then(|list| list.parent())
+                    .is_some_and(|statement| statement.kind == SyntaxKind::VariableStatement)
+            } else {
+                node.parent().is_some_and(|parent| is_class_like(&parent))
+                    && !is_static(node)
+                    && !node.has_modifier(SyntaxKind::AbstractKeyword)
+            };
+        let message = if node.initializer().is_some() {
+            diagnostics::DECLARATIONS_WITH_INITIALIZERS_CANNOT_ALSO_HAVE_DEFINITE_ASSIGNMENT_ASSERTIONS_1263
+        } else if node.type_node().is_none() {
+            diagnostics::DECLARATIONS_WITH_DEFINITE_ASSIGNMENT_ASSERTIONS_MUST_ALSO_HAVE_TYPE_ANNOTATIONS_1264
+        } else if !permitted {
+            diagnostics::A_DEFINITE_ASSIGNMENT_ASSERTION_IS_NOT_PERMITTED_IN_THIS_CONTEXT_1255
+        } else {
+            return;
+        };
+        self.error(&exclamation, message, &[]);
+    }
+
+    /// Determines if a property access reads a property of `this` in the constructor of the
+    /// class that declares it without an initializer, so that the constructor must assign it
+    /// before reading it
+    pub(super) fn is_uninitialized_property_access(
+        &self,
+        node: &Arc<Node>,
+        property: SymbolId,
+    ) -> bool {
+        if !self.options.strict_null_checks || !self.options.strict_property_initialization {
+            return false;
+        }
+        if node
+            .expression()
+            .is_none_or(|expression| expression.kind != SyntaxKind::ThisKeyword)
+        {
+            return false;
+        }
+        let Some(declaration) = &self.symbol(property).value_declaration else {
+            return false;
+        };
+        if declaration.kind != SyntaxKind::PropertyDeclaration
+            || is_static(declaration)
+            || declaration.has_modifier(SyntaxKind::AccessorKeyword)
+        {
+            return false;
+        }
+        let property_declaration = declaration.as_property_declaration();
+        if property_declaration.initializer.is_some()
+            || property_declaration.postfix_token.is_some()
+        {
+            return false;
+        }
+        get_control_flow_container(node).is_some_and(|container| {
+            container.kind == SyntaxKind::Constructor
+                && container
+                    .parent()
+                    .zip(declaration.parent())
+                    .is_some_and(|(class, parent)| Arc::ptr_eq(&class, &parent))
+        })
+    }
+}
@@ -0,0 +1,308 @@
+//! Types of the names binding patterns declare
+//!
+//! A name in a binding pattern takes the type of the property or element it destructures
+//! from the type of its parent: the declared type of the variable or parameter the pattern
+//! names, or the type of the enclosing binding element. A pattern with nothing to destructure
+//! implies a type itself, with a property or element for each of its elements.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{get_property_name_text, is_binding_pattern};
+use crate::compiler::ast::{Node, SymbolFlags, SyntaxKind};
+use crate::compiler::diagnostics;
+
+use super::iterators::IterationUse;
+use super::{Checker, ElementFlags, ResolvedMembers, TypeFlags, TypeId};
+
+impl Checker {
+    /// Gets the type of a binding element from the type it destructures, including the type
+    /// of its default value, reporting properties and tuple elements that do not exist
+    pub(super) fn get_type_of_binding_element(&mut self, element: &Arc<Node>) -> TypeId {
+        if let Some(type_) = self.node_links(element).resolved_type {
+            return type_;
+        }
+        let type_ = self.get_type_of_binding_element_from_parent(element, true);
+        let type_ = match &element.as_binding_element().initializer {
+            Some(initializer) => {
+                let initializer_type = self.check_expression(initializer);
+                if self.flags(type_).intersects(TypeFlags::ANY) {
+                    type_
+                } else {
+                    // The default replaces an `undefined` value
+                    let type_ = self.filter_type(type_, |checker, type_| {
+                        type_ != checker.undefined_type && type_ != checker.missing_type
+                    });
+                    self.get_union_type(&[type_, initializer_type])
+                }
+            }
+            None => type_,
+        };
+        self.node_links(element).resolved_type = Some(type_);
+        type_
+    }
+
+    /// Gets the contextual type of the default value of a binding element, which is the type
+    /// it destructures when that type does not depend on the pattern itself
+    pub(super) fn get_contextual_type_of_binding_element(
+        &mut self,
+        element: &Arc<Node>,
+    ) -> Option<TypeId> {
+        let pattern = element.parent()?;
+        if !has_explicit_source(&pattern) {
+            return None;
+        }
+        Some(self.get_type_of_binding_element_from_parent(element, false))
+    }
+
+    /// Gets the type of the property or element a binding element destructures, without its
+    /// default value
+    fn get_type_of_binding_element_from_parent(
+        &mut self,
+        element: &Arc<Node>,
+        report_errors: bool,
+    ) -> TypeId {
+        let Some(pattern) = element.parent() else {
+            return self.any_type;
+        };
+        let parent_type = self.get_type_of_binding_pattern_source(&pattern);
+        if self.flags(parent_type).intersects(TypeFlags::ANY) {
+            return parent_type;
+        }
+        let binding_element = element.as_binding_element();
+        if pattern.kind == SyntaxKind::ObjectBindingPattern {
+            if binding_element.dot_dot_dot_token.is_some() {
+                return self.get_rest_type(parent_type, &pattern);
+            }
+            let name = binding_element
+                .property_name
+                .as_ref()
+                .unwrap_or(&binding_element.name);
+            if name.kind == SyntaxKind::ComputedPropertyName {
+                self.check_expression(&name.as_computed_property_name().expression);
+            }
+            let Some(text) = get_property_name_text(name) else {
+                return self.any_type;
+            };
+            let apparent_type = self.get_apparent_type(parent_type);
+            if let Some(type_) = self.get_indexed_access_type_or_undefined(apparent_type, &text) {
+                return type_;
+            }
+            if report_errors {
+                let type_name = self.type_to_string(parent_type);
+                self.error(
+                    name,
+                    diagnostics::PROPERTY_0_DOES_NOT_EXIST_ON_TYPE_1_2339,
+                    &[text, type_name],
+                );
+            }
+            return self.error_type;
+        }
+
+        let elements = &pattern.as_array_binding_pattern().elements;
+        let index = elements
+            .iter()
+            .position(|candidate| Arc::ptr_eq(candidate, element))
+            .unwrap_or(0);
+        if binding_element.dot_dot_dot_token.is_some() {
+            if let Some((element_types, element_flags, readonly)) =
+                self.get_tuple_elements(parent_type)
+            {
+                let start = index.min(element_types.len());
+                return self.create_tuple_type(
+                    element_types[start..].to_vec(),
+                    element_flags[start..].to_vec(),
+                    readonly,
+                );
+            }
+            let element_type =
+                self.get_iterated_type_of_pattern(parent_type, &pattern, report_errors);
+            return self.create_array_type(element_type);
+        }
+        if let Some((element_types, element_flags, _)) = self.get_tuple_elements(parent_type) {
+            if index >= element_types.len()
+                && !element_flags
+                    .iter()
+                    .any(|flags| flags.intersects(ElementFlags::VARIABLE))
+            {
+                if report_errors {
+                    let type_name = self.type_to_string(parent_type);
+                    self.error(
+                        element,
+                        diagnostics::TUPLE_TYPE_0_OF_LENGTH_1_HAS_NO_ELEMENT_AT_INDEX_2_2493,
+                        &[
+                            type_name,
+                            element_types.len().to_string(),
+                            index.to_string(),
+                        ],
+                    );
+                }
+                return self.error_type;
+            }
+            let type_ = self
+                .get_element_type_at_index(parent_type, index)
+                .unwrap_or(self.any_type);
+            let optional = element_flags
+                .get(index)
+                .is_none_or(|flags| !flags.contains(ElementFlags::REQUIRED));
+            return if optional {
+                self.get_optional_type(type_, false)
+            } else {
+                type_
+            };
+        }
+        self.get_iterated_type_of_pattern(parent_type, &pattern, report_errors)
+    }
+
+    /// Gets the type of the values an array binding pattern takes from the value it
+    /// destructures, reporting the pattern if that value cannot be iterated
+    fn get_iterated_type_of_pattern(
+        &mut self,
+        type_: TypeId,
+        pattern: &Arc<Node>,
+        report_errors: bool,
+    ) -> TypeId {
+        let error_node = report_errors.then_some(pattern);
+        self.get_iterated_type(type_, IterationUse::Destructuring, error_node)
+    }
+
+    /// Gets the type of the value a binding pattern destructures
+    pub(super) fn get_type_of_binding_pattern_source(&mut self, pattern: &Arc<Node>) -> TypeId {
+        if let Some(type_) = self.node_links(pattern).resolved_type {
+            return type_;
+        }
+        let Some(declaration) = pattern.parent() else {
+            return self.any_type;
+        };
+        let type_ = match declaration.kind {
+            SyntaxKind::BindingElement => self.get_type_of_binding_element(&declaration),
+            SyntaxKind::Parameter => match self.get_symbol_of_declaration(&declaration) {
+                Some(symbol) => self.get_type_of_symbol(symbol),
+                None => self.any_type,
+            },
+            _ => self.get_type_for_variable_like_declaration(&declaration),
+        };
+        self.node_links(pattern).resolved_type = Some(type_);
+        type_
+    }
+
+    /// Gets the type of an object rest element: the properties of the destructured type that
+    /// the other elements of the pattern do not name
+    fn get_rest_type(&mut self, source: TypeId, pattern: &Arc<Node>) -> TypeId {
+        let omitted: Vec<String> = pattern
+            .as_object_binding_pattern()
+            .elements
+            .iter()
+            .filter(|element| element.as_binding_element().dot_dot_dot_token.is_none())
+            .filter_map(|element| {
+                let element = element.as_binding_element();
+                get_property_name_text(element.property_name.as_ref().unwrap_or(&element.name))
+            })
+            .collect();
+        let apparent_type = self.get_apparent_type(source);
+        let mut members = ResolvedMembers::default();
+        for property in self.get_properties_of_type(apparent_type) {
+            let name = self.symbol(property).name.clone();
+            if !omitted.contains(&name) {
+                members.add_property(&name, property);
+            }
+        }
+        self.create_resolved_type(None, members)
+    }
+
+    /// Gets the type a binding pattern implies when nothing gives the value it destructures a
+    /// type: an object type with a property for each element, or a tuple type
+    ///
+    /// Elements with default values are optional and take the types of their defaults.
+    pub(super) fn get_type_from_binding_pattern(&mut self, pattern: &Arc<Node>) -> TypeId {
+        if pattern.kind == SyntaxKind::ObjectBindingPattern {
+            let mut members = ResolvedMembers::default();
+            for element in &pattern.as_object_binding_pattern().elements {
+                let binding_element = element.as_binding_element();
+                if binding_element.dot_dot_dot_token.is_some() {
+                    continue;
+                }
+                let name = binding_element
+                    .property_name
+                    .as_ref()
+                    .unwrap_or(&binding_element.name);
+                let Some(text) = get_property_name_text(name) else {
+                    continue;
+                };
+                let type_ = self.get_type_from_binding_element(element);
+                let property = self.create_transient_property(&text, type_);
+                if binding_element.initializer.is_some() {
+                    self.bindings.symbols[property].flags |= SymbolFlags::OPTIONAL;
+                }
+                members.add_property(&text, property);
+            }
+            return self.create_resolved_type(None, members);
+        }
+
+        let mut element_types = Vec::new();
+        let mut element_flags = Vec::new();
+        for element in &pattern.as_array_binding_pattern().elements {
+            if element.kind != SyntaxKind::BindingElement {
+                element_types.push(self.any_type);
+                element_flags.push(ElementFlags::REQUIRED);
+                continue;
+            }
+            let binding_element = element.as_binding_element();
+            let flags = if binding_element.dot_dot_dot_token.is_some() {
+                ElementFlags::REST
+            } else if binding_element.initializer.is_some() {
+                ElementFlags::OPTIONAL
+            } else {
+                ElementFlags::REQUIRED
+            };
+            let type_ = if flags == ElementFlags::REST {
+                self.any_type
+            } else {
+                self.get_type_from_binding_element(element)
+            };
+            element_types.push(type_);
+            element_flags.push(flags);
+        }
+        self.create_tuple_type(element_types, element_flags, false)
+    }
+
+    /// Gets the type a binding element implies for the property or element it destructures
+    fn get_type_from_binding_element(&mut self, element: &Arc<Node>) -> TypeId {
+        let binding_element = element.as_binding_element();
+        if let Some(initializer) = &binding_element.initializer {
+            let type_ = self.check_expression(initializer);
+            let type_ = self.get_widened_literal_type(type_);
+            return self.get_widened_type(type_);
+        }
+        if is_binding_pattern(&binding_element.name) {
+            return self.get_type_from_binding_pattern(&binding_element.name);
+        }
+        self.any_type
+    }
+}
+
+/// Determines if the type of the value a binding pattern destructures is known without
+/// looking at the pattern, from a type annotation or the statement that declares it
+fn has_explicit_source(pattern: &Arc<Node>) -> bool {
+    let Some(declaration) = pattern.parent() else {
+        return false;
+    };
+    match declaration.kind {
+        SyntaxKind::BindingElement => declaration
+            .parent()
+            .is_some_and(|pattern| is_binding_pattern(&pattern) && has_explicit_source(&pattern)),
+        SyntaxKind::VariableDeclaration => {
+            declaration.type_node().is_some()
+                || declaration
+                    .parent()
+                    .and_then(|list| list.parent())
+                    .is_some_and(|statement| {
+                        matches!(
+                            statement.kind,
+                            SyntaxKind::ForInStatement | SyntaxKind::ForOfStatement
+                        )
+                    })
+        }
+        SyntaxKind::Parameter => declaration.type_node().is_some(),
+        _ => false,
+    }
+}
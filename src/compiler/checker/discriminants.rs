@@ -0,0 +1,211 @@
+//! Discriminant properties of union types
+//!
+//! A discriminant property is one whose literal types tell the constituents of a union apart,
+//! like `kind` in `{ kind: "a"; x: number } | { kind: "b"; y: string }`. Control flow narrows
+//! a union by comparisons of its discriminants, and relating a source to a union uses them
+//! to pick the constituent the source is meant to be. For a large union of object types,
+//! the constituents are mapped by the unit types of a key property once, so that a source
+//! is related to the one constituent its key picks out before it is related to every other,
+//! and errors explain why the source is not that constituent.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{Checker, ObjectFlags, TypeFlags, TypeId};
+
+/// How many object types a union needs before its constituents are mapped by a key property
+const MIN_KEYED_UNION_SIZE: usize = 10;
+
+/// The constituents of a large union of object types, by the unit types of a property that
+/// tells them apart
+#[derive(Debug)]
+pub(super) struct KeyPropertyMap {
+    name: String,
+    /// The constituent each unit type of the property picks out, or None for a unit type
+    /// that more than one constituent has
+    constituents: HashMap<TypeId, Option<TypeId>>,
+}
+
+impl Checker {
+    /// Determines if the constituents of a union type have a property of literal types that
+    /// differs between them
+    pub(super) fn is_discriminant_property(&mut self, type_: TypeId, name: &str) -> bool {
+        let mut property_types = Vec::new();
+        for constituent in self.get_constituents(type_) {
+            if let Some(property_type) = self.get_type_of_property_of_type(constituent, name) {
+                property_types.push(property_type);
+            }
+        }
+        let has_literal_type = property_types
+            .iter()
+            .any(|&property_type| self.is_literal_type(property_type));
+        has_literal_type
+            && property_types
+                .iter()
+                .any(|&property_type| property_type != property_types[0])
+    }
+
+    /// Gets the constituent of a large union type that the key property of `source` picks
+    /// out, if the union has a key property
+    pub(super) fn get_matching_union_constituent_for_type(
+        &mut self,
+        union: TypeId,
+        source: TypeId,
+    ) -> Option<TypeId> {
+        let map = self.get_key_property_map(union)?;
+        let key_type = self.get_type_of_property_of_type(source, &map.name)?;
+        let key_type = self.get_regular_type_of_literal_type(key_type);
+        *map.constituents.get(&key_type)?
+    }
+
+    /// Maps the object constituents of a large union type by the first property of a unit
+    /// type, if it tells at least half of the constituents apart
+    fn get_key_property_map(&mut self, union: TypeId) -> Option<Arc<KeyPropertyMap>> {
+        if let Some(map) = self.key_property_maps.get(&union) {
+            return map.clone();
+        }
+        let map = self.create_key_property_map(union).map(Arc::new);
+        self.key_property_maps.insert(union, map.clone());
+        map
+    }
+
+    fn create_key_property_map(&mut self, union: TypeId) -> Option<KeyPropertyMap> {
+        let object = TypeFlags::OBJECT | TypeFlags::INSTANTIABLE_NON_PRIMITIVE;
+        let types = self.get_constituents(union);
+        if !self.flags(union).contains(TypeFlags::UNION)
+            || self
+                .object_flags(union)
+                .contains(ObjectFlags::PRIMITIVE_UNION)
+            || types
+                .iter()
+                .filter(|&&type_| self.flags(type_).intersects(object))
+                .count()
+                < MIN_KEYED_UNION_SIZE
+        {
+            return None;
+        }
+        let mut name = None;
+        for &type_ in &types {
+            if !self.flags(type_).intersects(object) {
+                continue;
+            }
+            for property in self.get_properties_of_type(type_) {
+                let property_type = self.get_type_of_symbol(property);
+                if self.is_unit_type(property_type) {
+                    name = Some(self.symbol(property).name.clone());
+                    break;
+                }
+            }
+            if name.is_some() {
+                break;
+            }
+        }
+        let name = name?;
+        let mut constituents = HashMap::new();
+        let mut count = 0;
+        for &type_ in &types {
+            if !self
+                .flags(type_)
+                .intersects(object | TypeFlags::INTERSECTION)
+            {
+                continue;
+            }
+            let Some(discriminant) = self.get_type_of_property_of_type(type_, &name) else {
+                continue;
+            };
+            if !self.is_literal_type(discriminant) {
+                return None;
+            }
+            let mut duplicate = false;
+            for key_type in self.get_constituents(discriminant) {
+                let key_type = self.get_regular_type_of_literal_type(key_type);
+                match constituents.get(&key_type) {
+                    None => {
+                        constituents.insert(key_type, Some(type_));
+                    }
+                    Some(Some(_)) => {
+                        constituents.insert(key_type, None);
+                        duplicate = true;
+                    }
+                    Some(None) => duplicate = true,
+                }
+            }
+            if !duplicate {
+                count += 1;
+            }
+        }
+        (count >= MIN_KEYED_UNION_SIZE && count * 2 >= types.len())
+            .then_some(KeyPropertyMap { name, constituents })
+    }
+
+    /// Narrows a union target to the constituents whose discriminant properties the
+    /// properties of the same names of `source` are assignable to, or None if that does not
+    /// rule any constituent out
+    pub(super) fn find_matching_discriminant_type(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+    ) -> Option<TypeId> {
+        if !self.flags(target).contains(TypeFlags::UNION)
+            || !self
+                .flags(source)
+                .intersects(TypeFlags::OBJECT | TypeFlags::INTERSECTION)
+        {
+            return None;
+        }
+        if let Some(matching) = self.get_matching_union_constituent_for_type(target, source) {
+            return Some(matching);
+        }
+        let types = self.get_constituents(target);
+        let mut included: Vec<bool> = types
+            .iter()
+            .map(|&type_| !self.flags(type_).intersects(TypeFlags::PRIMITIVE))
+            .collect();
+        let mut discriminated = false;
+        for property in self.get_properties_of_type(source) {
+            let name = self.symbol(property).name.clone();
+            if !self.is_discriminant_property(target, &name) {
+                continue;
+            }
+            discriminated = true;
+            let source_type = self.get_type_of_symbol(property);
+            let matches: Vec<bool> = types
+                .iter()
+                .map(|&type_| {
+                    self.get_type_of_property_of_type(type_, &name)
+                        .is_some_and(|target_type| {
+                            self.some_type(source_type, |checker, source_type| {
+                                checker.is_type_assignable_to(source_type, target_type)
+                            })
+                        })
+                })
+                .collect();
+            // A discriminant that matches no constituent rules none out
+            if matches
+                .iter()
+                .zip(&included)
+                .any(|(&matched, &included)| matched && included)
+            {
+                for (included, matched) in included.iter_mut().zip(matches) {
+                    *included &= matched;
+                }
+            }
+        }
+        if !discriminated || included.iter().all(|&included| included) {
+            return None;
+        }
+        let matching: Vec<TypeId> = types
+            .into_iter()
+            .zip(included)
+            .filter_map(|(type_, included)| included.then_some(type_))
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        Some(self.get_union_type(&matching))
+    }
+
+    fn is_unit_type(&self, type_: TypeId) -> bool {
+        self.flags(type_).intersects(TypeFlags::UNIT)
+    }
+}
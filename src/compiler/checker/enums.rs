@@ -0,0 +1,533 @@
+//! Enum member values and enum types
+//!
+//! The values of the members of an enum declaration are computed together when the enum is
+//! first used. A member without an initializer is one more than the member before it, and an
+//! initializer is evaluated as a constant expression of literals, operators, and references to
+//! other enum members and constants. A non-const enum may also initialize a member with any
+//! number-typed expression, which leaves its value unknown.
+//!
+//! Each member with a known value has an enum literal type, such as `E.A`, and each computed
+//! member has a type of its own. The type of the enum is the union of its member types.
+//! Const enums are inlined by the emitter, so they may only be used to access their members.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{
+    get_property_name_text, get_source_file_of_node, is_const_variable, is_string_literal_like,
+    number_to_string, parse_numeric_literal,
+};
+use crate::compiler::ast::{Node, NodeFlags, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics;
+
+use super::{Checker, LiteralValue, Number, ObjectFlags, TypeData, TypeFlags, TypeId};
+
+impl Checker {
+    /// Gets the value of an enum member, or None if it is computed at runtime
+    pub(super) fn get_enum_member_value(&mut self, member: &Arc<Node>) -> Option<LiteralValue> {
+        if let Some(declaration) = member.parent() {
+            self.compute_enum_member_values(&declaration);
+        }
+        self.node_links(member).enum_member_value.clone()
+    }
+
+    /// Gets the value the emitter inlines for an enum member declaration, or for an access of
+    /// a member of a const enum
+    pub fn get_constant_value(&mut self, node: &Arc<Node>) -> Option<LiteralValue> {
+        match node.kind {
+            SyntaxKind::EnumMember => self.get_enum_member_value(node),
+            SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression => {
+                self.check_expression(node);
+                let symbol = self.node_links(node).resolved_symbol?;
+                if !self.symbol(symbol).flags.contains(SymbolFlags::ENUM_MEMBER) {
+                    return None;
+                }
+                let member = self.symbol(symbol).value_declaration.clone()?;
+                let is_const_enum = member
+                    .parent()
+                    .is_some_and(|declaration| is_enum_const(&declaration));
+                if is_const_enum {
+                    self.get_enum_member_value(&member)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn compute_enum_member_values(&mut self, declaration: &Arc<Node>) {
+        if self.node_links(declaration).enum_values_computed {
+            return;
+        }
+        // Set first, so that a member that refers to itself sees no value instead of looping
+        self.node_links(declaration).enum_values_computed = true;
+        let mut auto_value = Some(0.0);
+        for member in &declaration.as_enum_declaration().members {
+            let value = self.compute_enum_member_value(member, auto_value);
+            auto_value = match value {
+                Some(LiteralValue::Number(Number(value))) => Some(value + 1.0),
+                _ => None,
+            };
+            self.node_links(member).enum_member_value = value;
+        }
+    }
+
+    fn compute_enum_member_value(
+        &mut self,
+        member: &Arc<Node>,
+        auto_value: Option<f64>,
+    ) -> Option<LiteralValue> {
+        let name = &member.as_enum_member().name;
+        if let Some(text) = get_property_name_text(name)
+            && is_numeric_literal_name(&text)
+        {
+            self.error(
+                name,
+                diagnostics::AN_ENUM_MEMBER_CANNOT_HAVE_A_NUMERIC_NAME_2452,
+                &[],
+            );
+        }
+        if let Some(initializer) = member.initializer() {
+            return self.compute_constant_enum_member_value(member, initializer);
+        }
+        let declaration = member.parent()?;
+        if declaration.flags.contains(NodeFlags::AMBIENT) && !is_enum_const(&declaration) {
+            // Members of ambient enums are computed unless they say otherwise
+            return None;
+        }
+        match auto_value {
+            Some(value) => Some(LiteralValue::Number(Number(value))),
+            None => {
+                self.error(
+                    name,
+                    diagnostics::ENUM_MEMBER_MUST_HAVE_INITIALIZER_1061,
+                    &[],
+                );
+                None
+            }
+        }
+    }
+
+    fn compute_constant_enum_member_value(
+        &mut self,
+        member: &Arc<Node>,
+        initializer: &Arc<Node>,
+    ) -> Option<LiteralValue> {
+        let declaration = member.parent()?;
+        let is_const = is_enum_const(&declaration);
+        let value = self.evaluate(initializer, member);
+        match &value {
+            Some(LiteralValue::Number(Number(number))) if is_const && !number.is_finite() => {
+                let message = if number.is_nan() {
+                    diagnostics::CONST_ENUM_MEMBER_INITIALIZER_WAS_EVALUATED_TO_DISALLOWED_VALUE_NA_N_2478
+                } else {
+                    diagnostics::CONST_ENUM_MEMBER_INITIALIZER_WAS_EVALUATED_TO_A_NON_FINITE_VALUE_2477
+                };
+                self.error(initializer, message, &[]);
+            }
+            Some(_) => {}
+            None if is_const => self.error(
+                initializer,
+                diagnostics::CONST_ENUM_MEMBER_INITIALIZERS_MUST_BE_CONSTANT_EXPRESSIONS_2474,
+                &[],
+            ),
+            None if declaration.flags.contains(NodeFlags::AMBIENT) => self.error(
+                initializer,
+                diagnostics::IN_AMBIENT_ENUM_DECLARATIONS_MEMBER_INITIALIZER_MUST_BE_CONSTANT_EXPRESSION_1066,
+                &[],
+            ),
+            // Other computed initializers must be numbers, which is checked with the enum
+            None => {}
+        }
+        value
+    }
+
+    /// Evaluates a constant expression in the initializer of the enum member `location`
+    fn evaluate(&mut self, node: &Arc<Node>, location: &Arc<Node>) -> Option<LiteralValue> {
+        match node.kind {
+            SyntaxKind::NumericLiteral => Some(LiteralValue::Number(Number(
+                parse_numeric_literal(node.text()),
+            ))),
+            _ if is_string_literal_like(node) => {
+                Some(LiteralValue::String(node.text().to_string()))
+            }
+            SyntaxKind::ParenthesizedExpression => {
+                self.evaluate(&node.as_parenthesized_expression().expression, location)
+            }
+            SyntaxKind::PrefixUnaryExpression => {
+                let unary = node.as_prefix_unary_expression();
+                let Some(LiteralValue::Number(Number(value))) =
+                    self.evaluate(&unary.operand, location)
+                else {
+                    return None;
+                };
+                let result = match unary.operator {
+                    SyntaxKind::PlusToken => value,
+                    SyntaxKind::MinusToken => -value,
+                    SyntaxKind::TildeToken => f64::from(!to_int32(value)),
+                    _ => return None,
+                };
+                Some(LiteralValue::Number(Number(result)))
+            }
+            SyntaxKind::BinaryExpression => {
+                let binary = node.as_binary_expression();
+                let left = self.evaluate(&binary.left, location)?;
+                let right = self.evaluate(&binary.right, location)?;
+                evaluate_binary(binary.operator_token.kind, left, right)
+            }
+            SyntaxKind::TemplateExpression => {
+                let template = node.as_template_expression();
+                let mut text = template.head.text().to_string();
+                for span in &template.template_spans {
+                    let span = span.as_template_span();
+                    text.push_str(&literal_value_to_string(
+                        &self.evaluate(&span.expression, location)?,
+                    ));
+                    text.push_str(span.literal.text());
+                }
+                Some(LiteralValue::String(text))
+            }
+            SyntaxKind::Identifier
+            | SyntaxKind::PropertyAccessExpression
+            | SyntaxKind::ElementAccessExpression => {
+                self.evaluate_entity_name_expression(node, location)
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluates a reference to an enum member or a constant
+    fn evaluate_entity_name_expression(
+        &mut self,
+        node: &Arc<Node>,
+        location: &Arc<Node>,
+    ) -> Option<LiteralValue> {
+        let symbol = if node.kind == SyntaxKind::ElementAccessExpression {
+            let access = node.as_element_access_expression();
+            if !is_string_literal_like(&access.argument_expression) {
+                return None;
+            }
+            let object = self.resolve_entity_name(&access.expression, SymbolFlags::VALUE, true)?;
+            let object = self.resolve_alias_if_alias(object);
+            if !self.symbol(object).flags.intersects(SymbolFlags::ENUM) {
+                return None;
+            }
+            let exports = self.symbol(object).exports.clone();
+            self.lookup(
+                &exports,
+                access.argument_expression.text(),
+                SymbolFlags::ENUM_MEMBER,
+            )?
+        } else {
+            let symbol = self.resolve_entity_name(node, SymbolFlags::VALUE, true);
+            match symbol {
+                Some(symbol) => self.resolve_alias_if_alias(symbol),
+                // `Infinity` and `NaN` are globals, which may not be declared
+                None if node.kind == SyntaxKind::Identifier => {
+                    return match node.text() {
+                        "Infinity" => Some(LiteralValue::Number(Number(f64::INFINITY))),
+                        "NaN" => Some(LiteralValue::Number(Number(f64::NAN))),
+                        _ => None,
+                    };
+                }
+                None => return None,
+            }
+        };
+        let flags = self.symbol(symbol).flags;
+        let declaration = self.symbol(symbol).value_declaration.clone()?;
+        if flags.contains(SymbolFlags::ENUM_MEMBER) {
+            if !is_declared_before(&declaration, location) {
+                self.error(
+                    node,
+                    diagnostics::A_MEMBER_INITIALIZER_IN_A_ENUM_DECLARATION_CANNOT_REFERENCE_MEMBERS_DECLARED_AFTER_IT_INCLUDING_MEMBERS_DEFINED_IN_OTHER_ENUMS_2651,
+                    &[],
+                );
+                return Some(LiteralValue::Number(Number(0.0)));
+            }
+            return self.get_enum_member_value(&declaration);
+        }
+        if declaration.kind == SyntaxKind::VariableDeclaration
+            && is_const_variable(&declaration)
+            && declaration.type_node().is_none()
+            && let Some(initializer) = declaration.initializer()
+            && is_declared_before(&declaration, location)
+        {
+            return self.evaluate(&Arc::clone(initializer), &declaration);
+        }
+        match node.text() {
+            "Infinity" if node.kind == SyntaxKind::Identifier => {
+                Some(LiteralValue::Number(Number(f64::INFINITY)))
+            }
+            "NaN" if node.kind == SyntaxKind::Identifier => {
+                Some(LiteralValue::Number(Number(f64::NAN)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the declared type of an enum: the union of the types of its members
+    pub(super) fn get_declared_type_of_enum(&mut self, symbol: SymbolId) -> TypeId {
+        let mut member_types = Vec::new();
+        for declaration in self.symbol(symbol).declarations.clone() {
+            if declaration.kind != SyntaxKind::EnumDeclaration {
+                continue;
+            }
+            for member in &declaration.as_enum_declaration().members {
+                let Some(member_symbol) = self.get_symbol_of_declaration(member) else {
+                    continue;
+                };
+                if let Some(type_) = self.symbol_links(member_symbol).declared_type {
+                    member_types.push(type_);
+                    continue;
+                }
+                let type_ = match self.get_enum_member_value(member) {
+                    Some(value) => {
+                        let flags = match value {
+                            LiteralValue::String(_) => TypeFlags::STRING_LITERAL,
+                            _ => TypeFlags::NUMBER_LITERAL,
+                        };
+                        self.create_type(
+                            flags | TypeFlags::ENUM_LITERAL,
+                            ObjectFlags::NONE,
+                            Some(member_symbol),
+                            TypeData::Literal { value },
+                        )
+                    }
+                    None => self.create_type(
+                        TypeFlags::ENUM,
+                        ObjectFlags::NONE,
+                        Some(member_symbol),
+                        TypeData::Enum,
+                    ),
+                };
+                self.symbol_links(member_symbol).declared_type = Some(type_);
+                member_types.push(type_);
+            }
+        }
+        if member_types.is_empty() {
+            return self.create_type(
+                TypeFlags::ENUM,
+                ObjectFlags::NONE,
+                Some(symbol),
+                TypeData::Enum,
+            );
+        }
+        let enum_type = self.get_union_type(&member_types);
+        if self.flags(enum_type).contains(TypeFlags::UNION) {
+            let type_ = &mut self.types[enum_type.index()];
+            type_.flags |= TypeFlags::ENUM_LITERAL;
+            type_.symbol = Some(symbol);
+            type_.alias_symbol = Some(symbol);
+        }
+        enum_type
+    }
+
+    /// Gets the type of an enum member, which is created with the type of its enum
+    pub(super) fn get_declared_type_of_enum_member(&mut self, symbol: SymbolId) -> TypeId {
+        if let Some(parent) = self.symbol(symbol).parent {
+            self.get_declared_type_of_symbol(parent);
+        }
+        self.symbol_links(symbol)
+            .declared_type
+            .unwrap_or(self.error_type)
+    }
+
+    /// Gets the enum an enum literal type is a member of, which is what it widens to
+    pub(super) fn get_base_type_of_enum_literal_type(&mut self, type_: TypeId) -> TypeId {
+        let flags = self.flags(type_);
+        if !flags.intersects(TypeFlags::ENUM_LITERAL | TypeFlags::ENUM)
+            || flags.contains(TypeFlags::UNION)
+        {
+            return type_;
+        }
+        let parent = self
+            .get_type(type_)
+            .symbol
+            .filter(|&symbol| self.symbol(symbol).flags.contains(SymbolFlags::ENUM_MEMBER))
+            .and_then(|symbol| self.symbol(symbol).parent);
+        match parent {
+            Some(parent) => self.get_declared_type_of_symbol(parent),
+            None => type_,
+        }
+    }
+
+    pub(super) fn check_enum_declaration(&mut self, node: &Arc<Node>) {
+        let Some(symbol) = self.get_symbol_of_declaration(node) else {
+            return;
+        };
+        self.get_declared_type_of_symbol(symbol);
+        let is_const = is_enum_const(node);
+        let is_ambient = node.flags.contains(NodeFlags::AMBIENT);
+        for member in &node.as_enum_declaration().members {
+            let Some(initializer) = member.initializer() else {
+                continue;
+            };
+            let type_ = self.check_expression(initializer);
+            if !is_const && !is_ambient && self.get_enum_member_value(member).is_none() {
+                self.check_type_assignable_to(
+                    type_,
+                    self.number_type,
+                    initializer,
+                    Some(diagnostics::TYPE_0_IS_NOT_ASSIGNABLE_TO_TYPE_1_AS_REQUIRED_FOR_COMPUTED_ENUM_MEMBER_VALUES_18033),
+                );
+            }
+        }
+
+        // Merged enum declarations are checked together, by the first of them
+        let declarations: Vec<Arc<Node>> = self
+            .symbol(symbol)
+            .declarations
+            .iter()
+            .filter(|declaration| declaration.kind == SyntaxKind::EnumDeclaration)
+            .cloned()
+            .collect();
+        if !declarations
+            .first()
+            .is_some_and(|first| Arc::ptr_eq(first, node))
+        {
+            return;
+        }
+        for declaration in &declarations {
+            if is_enum_const(declaration) != is_const {
+                self.error(
+                    &declaration.as_enum_declaration().name,
+                    diagnostics::ENUM_DECLARATIONS_MUST_ALL_BE_CONST_OR_NON_CONST_2473,
+                    &[],
+                );
+            }
+        }
+        let mut seen_missing_initial_initializer = false;
+        for declaration in &declarations {
+            let Some(first_member) = declaration.as_enum_declaration().members.iter().next() else {
+                continue;
+            };
+            if first_member.initializer().is_some() {
+                continue;
+            }
+            if seen_missing_initial_initializer {
+                self.error(
+                    &first_member.as_enum_member().name,
+                    diagnostics::IN_AN_ENUM_WITH_MULTIPLE_DECLARATIONS_ONLY_ONE_DECLARATION_CAN_OMIT_AN_INITIALIZER_FOR_ITS_FIRST_ENUM_ELEMENT_2432,
+                    &[],
+                );
+            } else {
+                seen_missing_initial_initializer = true;
+            }
+        }
+    }
+
+    /// Determines if a type is the type of a const enum object, which only exists at compile
+    /// time
+    pub(super) fn is_const_enum_object_type(&self, type_: TypeId) -> bool {
+        let type_ = self.get_type(type_);
+        type_.object_flags.contains(ObjectFlags::ANONYMOUS)
+            && type_
+                .symbol
+                .is_some_and(|symbol| self.symbol(symbol).flags.contains(SymbolFlags::CONST_ENUM))
+    }
+
+    /// Reports a use of a const enum object other than to access one of its members
+    pub(super) fn check_const_enum_access(&mut self, node: &Arc<Node>) {
+        let Some(parent) = node.parent() else {
+            return;
+        };
+        let ok = match parent.kind {
+            SyntaxKind::PropertyAccessExpression => {
+                Arc::ptr_eq(&parent.as_property_access_expression().expression, node)
+            }
+            SyntaxKind::ElementAccessExpression => {
+                Arc::ptr_eq(&parent.as_element_access_expression().expression, node)
+            }
+            SyntaxKind::TypeQuery | SyntaxKind::ExportSpecifier | SyntaxKind::ExportAssignment => {
+                true
+            }
+            SyntaxKind::ImportEqualsDeclaration | SyntaxKind::QualifiedName => true,
+            _ => false,
+        };
+        if !ok {
+            self.error(
+                node,
+                diagnostics::CONST_ENUMS_CAN_ONLY_BE_USED_IN_PROPERTY_OR_INDEX_ACCESS_EXPRESSIONS_OR_THE_RIGHT_HAND_SIDE_OF_AN_IMPORT_DECLARATION_OR_EXPORT_ASSIGNMENT_OR_TYPE_QUERY_2475,
+                &[],
+            );
+        }
+    }
+}
+
+fn is_enum_const(declaration: &Node) -> bool {
+    declaration.has_modifier(SyntaxKind::ConstKeyword)
+}
+
+/// Determines if a name is the canonical text of a number, which would make an enum member
+/// indistinguishable from the reverse mapping of a value
+fn is_numeric_literal_name(text: &str) -> bool {
+    text.parse::<f64>()
+        .is_ok_and(|value| value.is_finite() && number_to_string(value) == text)
+}
+
+/// Determines if `declaration` comes before `location`, or is in another file
+fn is_declared_before(declaration: &Arc<Node>, location: &Arc<Node>) -> bool {
+    !Arc::ptr_eq(
+        &get_source_file_of_node(declaration),
+        &get_source_file_of_node(location),
+    ) || declaration.end() <= location.pos()
+}
+
+/// Converts a number to a 32-bit integer the way the bitwise operators of JavaScript do
+fn to_int32(value: f64) -> i32 {
+    if !value.is_finite() {
+        return 0;
+    }
+    value.trunc().rem_euclid(4294967296.0) as u32 as i32
+}
+
+fn literal_value_to_string(value: &LiteralValue) -> String {
+    match value {
+        LiteralValue::String(text) => text.clone(),
+        LiteralValue::Number(Number(number)) => number_to_string(*number),
+        LiteralValue::BigInt(digits) => digits.clone(),
+    }
+}
+
+/// Evaluates a binary operator on constant operands
+fn evaluate_binary(
+    operator: SyntaxKind,
+    left: LiteralValue,
+    right: LiteralValue,
+) -> Option<LiteralValue> {
+    let (LiteralValue::Number(Number(left)), LiteralValue::Number(Number(right))) = (&left, &right)
+    else {
+        // Only `+` applies to strings, concatenating them
+        return match operator {
+            SyntaxKind::PlusToken
+                if !matches!(left, LiteralValue::BigInt(_))
+                    && !matches!(right, LiteralValue::BigInt(_)) =>
+            {
+                Some(LiteralValue::String(
+                    literal_value_to_string(&left) + &literal_value_to_string(&right),
+                ))
+            }
+            _ => None,
+        };
+    };
+    let (left, right) = (*left, *right);
+    let shift = (to_int32(right) as u32) & 31;
+    let result = match operator {
+        SyntaxKind::BarToken => f64::from(to_int32(left) | to_int32(right)),
+        SyntaxKind::AmpersandToken => f64::from(to_int32(left) & to_int32(right)),
+        SyntaxKind::CaretToken => f64::from(to_int32(left) ^ to_int32(right)),
+        SyntaxKind::LessThanLessThanToken => f64::from(to_int32(left).wrapping_shl(shift)),
+        SyntaxKind::GreaterThanGreaterThanToken => f64::from(to_int32(left) >> shift),
+        SyntaxKind::GreaterThanGreaterThanGreaterThanToken => {
+            f64::from((to_int32(left) as u32) >> shift)
+        }
+        SyntaxKind::AsteriskToken => left * right,
+        SyntaxKind::SlashToken => left / right,
+        SyntaxKind::PlusToken => left + right,
+        SyntaxKind::MinusToken => left - right,
+        SyntaxKind::PercentToken => left % right,
+        SyntaxKind::AsteriskAsteriskToken => left.powf(right),
+        _ => return None,
+    };
+    Some(LiteralValue::Number(Number(result)))
+}
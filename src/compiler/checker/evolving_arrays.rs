@@ -0,0 +1,205 @@
+//! Arrays whose types evolve
+//!
+//! Under `noImplicitAny`, a local variable initialized with an empty array literal, as in
+//! `const x = []`, has no element type to begin with. Rather than being `any[]`, its type
+//! evolves along control flow: each `x.push(...)`, `x.unshift(...)`, or `x[i] = v` adds the
+//! widened types of the new elements to its element type, and where branches join, the
+//! element types along them are unioned. A reference that reads the array sees the elements
+//! added so far as `T[]`, and one that reads it before anything is added, where its type
+//! cannot be determined, is implicitly `any[]`.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::skip_parentheses;
+use crate::compiler::ast::{Node, NodeFlags, SyntaxKind};
+
+use super::{Checker, ObjectFlags, TypeData, TypeFlags, TypeId};
+
+impl Checker {
+    /// Determines if a variable's declared type is the `any[]` its type evolves from, as it
+    /// is for a local variable initialized with an empty array literal under `noImplicitAny`
+    pub(super) fn is_auto_array_declaration(&self, declaration: &Arc<Node>) -> bool {
+        self.options.no_implicit_any
+            && declaration.kind == SyntaxKind::VariableDeclaration
+            && declaration
+                .name()
+                .is_some_and(|name| name.kind == SyntaxKind::Identifier)
+            && !declaration.flags.contains(NodeFlags::AMBIENT)
+            && !declaration
+                .parent()
+                .and_then(|list| list.parent())
+                .is_some_and(|statement| statement.has_modifier(SyntaxKind::ExportKeyword))
+            && declaration
+                .initializer()
+                .is_some_and(is_empty_array_literal)
+    }
+
+    /// Gets the declared type of variables whose types evolve from empty array literals,
+    /// which is an `any[]` distinct from every other
+    pub(super) fn get_auto_array_type(&mut self) -> TypeId {
+        if let Some(type_) = self.auto_array_type {
+            return type_;
+        }
+        let array = self.get_global_array_type();
+        let symbol = self.get_type(array).symbol;
+        let type_ = self.create_type(
+            TypeFlags::OBJECT,
+            ObjectFlags::REFERENCE,
+            symbol,
+            TypeData::Reference {
+                target: array,
+                type_arguments: vec![self.any_type],
+            },
+        );
+        self.auto_array_type = Some(type_);
+        type_
+    }
+
+    pub(super) fn is_auto_array_type(&self, type_: TypeId) -> bool {
+        self.auto_array_type == Some(type_)
+    }
+
+    /// Gets the evolving array type with the element type `element_type`, which starts out
+    /// as `never`
+    pub(super) fn get_evolving_array_type(&mut self, element_type: TypeId) -> TypeId {
+        if let Some(&type_) = self.evolving_array_types.get(&element_type) {
+            return type_;
+        }
+        let type_ = self.create_type(
+            TypeFlags::OBJECT,
+            ObjectFlags::EVOLVING_ARRAY,
+            None,
+            TypeData::EvolvingArray { element_type },
+        );
+        self.evolving_array_types.insert(element_type, type_);
+        type_
+    }
+
+    pub(super) fn is_evolving_array_type(&self, type_: TypeId) -> bool {
+        self.object_flags(type_)
+            .contains(ObjectFlags::EVOLVING_ARRAY)
+    }
+
+    pub(super) fn get_element_type_of_evolving_array_type(&self, type_: TypeId) -> TypeId {
+        match self.get_type(type_).data {
+            TypeData::EvolvingArray { element_type } => element_type,
+            _ => self.never_type,
+        }
+    }
+
+    /// Adds the widened type of the element `node` to an evolving array type
+    pub(super) fn add_evolving_array_element_type(
+        &mut self,
+        evolving_array_type: TypeId,
+        node: &Arc<Node>,
+    ) -> TypeId {
+        let type_ = self.check_expression(node);
+        let type_ = self.get_base_type_of_literal_type(type_);
+        let element_type = self.get_regular_type_of_object_literal(type_);
+        let previous = self.get_element_type_of_evolving_array_type(evolving_array_type);
+        let union = self.get_union_type(&[previous, element_type]);
+        if union == previous {
+            return evolving_array_type;
+        }
+        self.get_evolving_array_type(union)
+    }
+
+    /// Gets the array type an evolving array type has evolved into, which is the implicitly
+    /// `any[]` declared type while nothing has been added to it
+    pub(super) fn finalize_evolving_array_type(&mut self, type_: TypeId) -> TypeId {
+        if !self.is_evolving_array_type(type_) {
+            return type_;
+        }
+        let element_type = self.get_element_type_of_evolving_array_type(type_);
+        if self.flags(element_type).contains(TypeFlags::NEVER) {
+            self.get_auto_array_type()
+        } else {
+            self.create_array_type(element_type)
+        }
+    }
+
+    /// Determines if a reference is the array of an operation that evolves its type, or
+    /// reads its `length`, neither of which needs its element type to be known
+    pub(super) fn is_evolving_array_operation_target(&mut self, node: &Arc<Node>) -> bool {
+        let root = get_reference_root(node);
+        let Some(parent) = root.parent() else {
+            return false;
+        };
+        match parent.kind {
+            SyntaxKind::PropertyAccessExpression => {
+                let name = parent.as_property_access_expression().name.text();
+                name == "length"
+                    || matches!(name, "push" | "unshift")
+                        && parent
+                            .parent()
+                            .is_some_and(|call| call.kind == SyntaxKind::CallExpression)
+            }
+            SyntaxKind::ElementAccessExpression => {
+                let access = parent.as_element_access_expression();
+                let is_element_assignment = Arc::ptr_eq(&access.expression, &root)
+                    && parent.parent().is_some_and(|assignment| {
+                        assignment.kind == SyntaxKind::BinaryExpression
+                            && assignment.as_binary_expression().operator_token.kind
+                                == SyntaxKind::EqualsToken
+                            && Arc::ptr_eq(&assignment.as_binary_expression().left, &parent)
+                    });
+                if !is_element_assignment {
+                    return false;
+                }
+                let index_type = self.check_expression(&access.argument_expression);
+                self.is_type_assignable_to(index_type, self.number_type)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Determines if an expression is `[]`, possibly parenthesized
+pub(super) fn is_empty_array_literal(node: &Arc<Node>) -> bool {
+    let node = skip_parentheses(node);
+    node.kind == SyntaxKind::ArrayLiteralExpression
+        && node.as_array_literal_expression().elements.is_empty()
+}
+
+/// Gets the reference an expression evaluates to, looking through parentheses, the target of
+/// an assignment, and the last operand of a comma
+pub(super) fn get_reference_candidate(node: &Arc<Node>) -> Arc<Node> {
+    match node.kind {
+        SyntaxKind::ParenthesizedExpression => {
+            get_reference_candidate(&node.as_parenthesized_expression().expression)
+        }
+        SyntaxKind::BinaryExpression => {
+            let binary = node.as_binary_expression();
+            match binary.operator_token.kind {
+                SyntaxKind::EqualsToken => get_reference_candidate(&binary.left),
+                SyntaxKind::CommaToken => get_reference_candidate(&binary.right),
+                _ => Arc::clone(node),
+            }
+        }
+        _ => Arc::clone(node),
+    }
+}
+
+/// Gets the outermost expression that evaluates to the reference `node`, the inverse of
+/// `get_reference_candidate`
+fn get_reference_root(node: &Arc<Node>) -> Arc<Node> {
+    let Some(parent) = node.parent() else {
+        return Arc::clone(node);
+    };
+    let is_root = match parent.kind {
+        SyntaxKind::ParenthesizedExpression => false,
+        SyntaxKind::BinaryExpression => {
+            let binary = parent.as_binary_expression();
+            !(binary.operator_token.kind == SyntaxKind::EqualsToken
+                && Arc::ptr_eq(&binary.left, node)
+                || binary.operator_token.kind == SyntaxKind::CommaToken
+                    && Arc::ptr_eq(&binary.right, node))
+        }
+        _ => true,
+    };
+    if is_root {
+        Arc::clone(node)
+    } else {
+        get_reference_root(&parent)
+    }
+}
@@ -0,0 +1,2292 @@
+//! Types of expressions
+
+use std::sync::Arc;
+
+use crate::compiler::ast::symbol::symbol_name;
+use crate::compiler::ast::utilities::{
+    get_error_range, get_source_file_of_node, get_text_of_node, is_class_like, is_const_variable,
+    is_function_like_declaration_kind, is_string_literal_like, parse_numeric_literal,
+    root_declaration,
+};
+use crate::compiler::ast::visitor::find_ancestor;
+use crate::compiler::ast::{
+    CheckFlags, Node, NodeFlags, NodeList, SymbolFlags, SymbolId, SyntaxKind,
+};
+use crate::compiler::binder::{has_dynamic_name, is_entity_name_expression};
+use crate::compiler::diagnostics::{self, DiagnosticMessageChain};
+use crate::compiler::text::TextRange;
+
+use super::assertions::is_const_context;
+use super::contextual::get_containing_function;
+use super::flow::{is_assignment_target, is_compound_assignment_target, is_write_only_access};
+use super::inference::{InferenceContext, InferencePriority};
+use super::iterators::IterationUse;
+use super::{
+    Checker, LiteralValue, ObjectFlags, Relation, ResolvedMembers, SignatureId, TypeData,
+    TypeFlags, TypeId,
+};
+
+/// The strings `typeof` can produce
+pub(super) const TYPEOF_RESULTS: [&str; 8] = [
+    "string",
+    "number",
+    "bigint",
+    "boolean",
+    "symbol",
+    "undefined",
+    "object",
+    "function",
+];
+
+impl Checker {
+    /// Gets the type of an expression, reporting the errors in it
+    pub fn check_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        self.check_expression_with(node, Self::check_expression_worker)
+    }
+
+    /// Checks an expression whose type `check` works out, once, as [`Self::check_expression`]
+    /// does
+    fn check_expression_with(
+        &mut self,
+        node: &Arc<Node>,
+        check: impl FnOnce(&mut Self, &Arc<Node>) -> TypeId,
+    ) -> TypeId {
+        if let Some(type_) = self.node_links(node).resolved_type {
+            return type_;
+        }
+        let saved_node = self.current_node.replace(Arc::clone(node));
+        self.current_instantiation_count = 0;
+        let type_ = check(self, node);
+        self.current_node = saved_node;
+        if self.is_const_enum_object_type(type_) {
+            self.check_const_enum_access(node);
+        }
+        // Types found while the type of a reference around a loop is pending may change
+        if self.flow_loops.is_empty() {
+            self.node_links(node).resolved_type = Some(type_);
+        }
+        type_
+    }
+
+    fn check_expression_worker(&mut self, node: &Arc<Node>) -> TypeId {
+        match node.kind {
+            SyntaxKind::Identifier => self.check_identifier(node),
+            SyntaxKind::PrivateIdentifier => self.any_type,
+            SyntaxKind::ThisKeyword => self.check_this_expression(node),
+            SyntaxKind::SuperKeyword => self.check_super_expression(node),
+            SyntaxKind::NullKeyword => self.null_type,
+            SyntaxKind::TrueKeyword => self.true_type,
+            SyntaxKind::FalseKeyword => self.false_type,
+            SyntaxKind::NumericLiteral => {
+                let value = parse_numeric_literal(&node.as_numeric_literal().text);
+                let type_ = self.get_number_literal_type(value);
+                self.get_fresh_type_of_literal_type(type_)
+            }
+            SyntaxKind::BigIntLiteral => {
+                let digits = parse_big_int_literal(&node.as_big_int_literal().text);
+                let type_ = self.get_literal_type(LiteralValue::BigInt(digits));
+                self.get_fresh_type_of_literal_type(type_)
+            }
+            SyntaxKind::StringLiteral => {
+                let text = node.as_string_literal().text.clone();
+                let type_ = self.get_string_literal_type(&text);
+                self.get_fresh_type_of_literal_type(type_)
+            }
+            SyntaxKind::NoSubstitutionTemplateLiteral => {
+                let text = node.as_template_literal_like_node().text.clone();
+                let type_ = self.get_string_literal_type(&text);
+                self.get_fresh_type_of_literal_type(type_)
+            }
+            SyntaxKind::TemplateExpression => {
+                for span in &node.as_template_expression().template_spans {
+                    self.check_expression(&span.as_template_span().expression);
+                }
+                self.string_type
+            }
+            SyntaxKind::RegularExpressionLiteral => self.get_global_type("RegExp"),
+            SyntaxKind::ArrayLiteralExpression => self.check_array_literal(node),
+            SyntaxKind::ObjectLiteralExpression => self.check_object_literal(node),
+            SyntaxKind::PropertyAccessExpression => {
+                let type_ = self.check_property_access_expression(node);
+                self.propagate_optional_chain_type(node, type_)
+            }
+            SyntaxKind::ElementAccessExpression => {
+                let type_ = self.check_element_access_expression(node);
+                self.propagate_optional_chain_type(node, type_)
+            }
+            SyntaxKind::CallExpression
+                if node.as_call_expression().expression.kind == SyntaxKind::ImportKeyword =>
+            {
+                self.check_import_call_expression(node)
+            }
+            SyntaxKind::CallExpression => {
+                let type_ = self.check_call_expression(node);
+                self.propagate_optional_chain_type(node, type_)
+            }
+            SyntaxKind::NewExpression => self.check_call_expression(node),
+            SyntaxKind::TaggedTemplateExpression => self.check_tagged_template_expression(node),
+            SyntaxKind::ParenthesizedExpression => {
+                self.check_expression(&node.as_parenthesized_expression().expression)
+            }
+            SyntaxKind::TypeAssertionExpression | SyntaxKind::AsExpression => {
+                self.check_assertion(node)
+            }
+            SyntaxKind::SatisfiesExpression => self.check_satisfies_expression(node),
+            SyntaxKind::NonNullExpression => {
+                let type_ = self.check_expression(&node.as_non_null_expression().expression);
+                self.get_non_nullable_type(type_)
+            }
+            SyntaxKind::FunctionExpression | SyntaxKind::ArrowFunction => {
+                // Bodies are checked after the enclosing file, since they may refer to
+                // declarations that follow them
+                self.deferred_nodes.push(Arc::clone(node));
+                match self.get_symbol_of_declaration(node) {
+                    Some(symbol) => self.get_type_of_symbol(symbol),
+                    None => self.error_type,
+                }
+            }
+            SyntaxKind::ClassExpression => {
+                self.deferred_nodes.push(Arc::clone(node));
+                match self.get_symbol_of_declaration(node) {
+                    Some(symbol) => self.get_type_of_symbol(symbol),
+                    None => self.error_type,
+                }
+            }
+            SyntaxKind::DeleteExpression => {
+                self.check_expression(&node.as_delete_expression().expression);
+                self.boolean_type
+            }
+            SyntaxKind::TypeOfExpression => {
+                self.check_expression(&node.as_type_of_expression().expression);
+                let results: Vec<TypeId> = TYPEOF_RESULTS
+                    .iter()
+                    .map(|result| self.get_string_literal_type(result))
+                    .collect();
+                self.get_union_type(&results)
+            }
+            SyntaxKind::VoidExpression => {
+                self.check_expression(&node.as_void_expression().expression);
+                self.undefined_type
+            }
+            SyntaxKind::AwaitExpression => self.check_await_expression(node),
+            SyntaxKind::YieldExpression => {
+                let yield_expression = node.as_yield_expression();
+                if let Some(expression) = &yield_expression.expression {
+                    let type_ = self.check_expression(expression);
+                    if yield_expression.asterisk_token.is_some() {
+                        self.get_iterated_type(type_, IterationUse::YieldStar, Some(expression));
+                    }
+                }
+                self.any_type
+            }
+            SyntaxKind::PrefixUnaryExpression => self.check_prefix_unary_expression(node),
+            SyntaxKind::PostfixUnaryExpression => self.check_postfix_unary_expression(node),
+            SyntaxKind::BinaryExpression => self.check_binary_expression(node),
+            SyntaxKind::ConditionalExpression => {
+                let conditional = node.as_conditional_expression();
+                self.check_expression(&conditional.condition);
+                let when_true = self.check_expression(&conditional.when_true);
+                let when_false = self.check_expression(&conditional.when_false);
+                self.get_union_type(&[when_true, when_false])
+            }
+            SyntaxKind::SpreadElement => {
+                let expression = &node.as_spread_element().expression;
+                let type_ = self.check_expression(expression);
+                self.get_iterated_type(type_, IterationUse::Spread, Some(expression))
+            }
+            SyntaxKind::OmittedExpression => self.undefined_type,
+            SyntaxKind::MetaProperty
+                if node.as_meta_property().keyword_token == SyntaxKind::ImportKeyword =>
+            {
+                self.check_import_meta_property(node)
+            }
+            SyntaxKind::MetaProperty => self.any_type,
+            SyntaxKind::JsxElement
+            | SyntaxKind::JsxSelfClosingElement
+            | SyntaxKind::JsxFragment => self.check_jsx_element(node),
+            _ => self.error_type,
+        }
+    }
+
+    /// Gets the type of the value an identifier refers to
+    pub(super) fn check_identifier(&mut self, node: &Arc<Node>) -> TypeId {
+        if let Some(type_) = self.node_links(node).resolved_type {
+            return type_;
+        }
+        let name = node.text().to_string();
+        let Some(symbol) = self.resolve_name(node, &name, SymbolFlags::VALUE) else {
+            if name == "arguments" && is_in_function(node) {
+                return self.get_global_type("IArguments");
+            }
+            self.report_unresolved_name(node, SymbolFlags::VALUE);
+            return self.error_type;
+        };
+        let symbol = self.get_merged_symbol(symbol);
+        self.node_links(node).resolved_symbol = Some(symbol);
+        if !is_write_only_access(node) {
+            self.mark_symbol_referenced(symbol, node);
+        }
+        let target = self.resolve_alias_if_alias(symbol);
+        if target == self.unknown_symbol {
+            return self.error_type;
+        }
+        if !self.symbol(target).flags.intersects(SymbolFlags::VALUE) {
+            self.error(
+                node,
+                diagnostics::D_0_ONLY_REFERS_TO_A_TYPE_BUT_IS_BEING_USED_AS_A_VALUE_HERE_2693,
+                &[name],
+            );
+            return self.error_type;
+        }
+        let target = self.get_export_symbol_of_value_symbol_if_exported(target);
+        let is_target = is_assignment_target(node);
+        if is_target
+            && self.symbol(target).flags.intersects(SymbolFlags::VARIABLE)
+            && self
+                .symbol(target)
+                .value_declaration
+                .as_ref()
+                .is_some_and(is_const_variable)
+        {
+            self.error(
+                node,
+                diagnostics::CANNOT_ASSIGN_TO_0_BECAUSE_IT_IS_A_CONSTANT_2588,
+                &[name],
+            );
+            return self.error_type;
+        }
+        let type_ = self.get_type_of_symbol(target);
+        // A compound assignment reads the variable before it writes it
+        if !self.symbol(target).flags.intersects(SymbolFlags::VARIABLE)
+            || is_target && !is_compound_assignment_target(node)
+        {
+            return type_;
+        }
+        let assume_initialized = self.is_assumed_initialized(node, target, type_);
+        let initial_type = if assume_initialized {
+            type_
+        } else {
+            self.get_optional_type(type_, false)
+        };
+        let flow_type = self.get_flow_type_of_reference(node, type_, initial_type);
+        // An array declared empty is implicitly `any[]` where nothing has been added to it yet
+        if self.is_auto_array_type(type_) && !self.is_evolving_array_operation_target(node) {
+            if flow_type == type_ {
+                let type_name = self.type_to_string(type_);
+                if let Some(declaration) = self.symbol(target).value_declaration.clone() {
+                    self.error(
+                        declaration.name().unwrap_or(&declaration),
+                        diagnostics::VARIABLE_0_IMPLICITLY_HAS_TYPE_1_IN_SOME_LOCATIONS_WHERE_ITS_TYPE_CANNOT_BE_DETERMINED_7034,
+                        &[name.clone(), type_name.clone()],
+                    );
+                }
+                self.error(
+                    node,
+                    diagnostics::VARIABLE_0_IMPLICITLY_HAS_AN_1_TYPE_7005,
+                    &[name, type_name],
+                );
+                return self.create_array_type(self.any_type);
+            }
+        } else if !assume_initialized
+            && !self.maybe_type_of_kind(type_, TypeFlags::UNDEFINED)
+            && self.maybe_type_of_kind(flow_type, TypeFlags::UNDEFINED)
+        {
+            self.error(
+                node,
+                diagnostics::VARIABLE_0_IS_USED_BEFORE_BEING_ASSIGNED_2454,
+                &[name],
+            );
+            return type_;
+        }
+        if is_target {
+            return type_;
+        }
+        flow_type
+    }
+
+    /// Determines if a reference to a variable can be assumed to see an assigned value, rather
+    /// than having definite assignment analysis check that every path to it assigns one
+    ///
+    /// Only local variables declared without an initializer under `strictNullChecks` are
+    /// checked. Variables of outer functions may be assigned before the inner function is
+    /// called, and `!` on the declaration or the reference opts out of the check.
+    fn is_assumed_initialized(
+        &mut self,
+        node: &Arc<Node>,
+        symbol: SymbolId,
+        type_: TypeId,
+    ) -> bool {
+        if !self.options.strict_null_checks
+            || self
+                .flags(type_)
+                .intersects(TypeFlags::ANY_OR_UNKNOWN | TypeFlags::VOID)
+        {
+            return true;
+        }
+        let Some(declaration) = self.symbol(symbol).value_declaration.clone() else {
+            return true;
+        };
+        if declaration.kind != SyntaxKind::VariableDeclaration
+            || declaration
+                .as_variable_declaration()
+                .exclamation_token
+                .is_some()
+            || declaration.flags.contains(NodeFlags::AMBIENT)
+            || root_declaration(&declaration)
+                .parent()
+                .is_some_and(|parent| parent.kind == SyntaxKind::CatchClause)
+        {
+            return true;
+        }
+        if node
+            .parent()
+            .is_some_and(|parent| parent.kind == SyntaxKind::NonNullExpression)
+            || is_in_type_query(node)
+            || is_in_type_declaration(node)
+        {
+            return true;
+        }
+        let flow_container = get_control_flow_container(node);
+        let declaration_container = get_control_flow_container(&declaration);
+        match (flow_container, declaration_container) {
+            (Some(flow_container), Some(declaration_container)) => {
+                !Arc::ptr_eq(&flow_container, &declaration_container)
+            }
+            _ => true,
+        }
+    }
+
+    /// Adds `undefined` to a type, for optional properties and parameters and variables that
+    /// may not be assigned yet
+    ///
+    /// Under `exactOptionalPropertyTypes` optional properties get the `undefined` of a
+    /// missing property instead, which `undefined` values are not assignable to.
+    pub(super) fn get_optional_type(&mut self, type_: TypeId, is_property: bool) -> TypeId {
+        if !self.options.strict_null_checks || self.maybe_type_of_kind(type_, TypeFlags::UNDEFINED)
+        {
+            return type_;
+        }
+        let missing_or_undefined = if is_property && self.options.exact_optional_property_types {
+            self.missing_type
+        } else {
+            self.undefined_type
+        };
+        self.get_union_type(&[type_, missing_or_undefined])
+    }
+
+    /// Determines if an assignment target is an optional property that `undefined` cannot be
+    /// written to under `exactOptionalPropertyTypes`
+    fn is_exact_optional_property_write(&mut self, target: &Arc<Node>) -> bool {
+        if !self.options.exact_optional_property_types
+            || !matches!(
+                target.kind,
+                SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression
+            )
+        {
+            return false;
+        }
+        self.node_links(target)
+            .resolved_symbol
+            .is_some_and(|property| self.symbol(property).flags.contains(SymbolFlags::OPTIONAL))
+    }
+
+    /// Removes the `undefined` of a missing optional property from a type, giving the type of
+    /// the values that can be written to the property
+    pub(super) fn remove_missing_type(&mut self, type_: TypeId) -> TypeId {
+        if !self.options.exact_optional_property_types {
+            return type_;
+        }
+        self.filter_type(type_, |checker, type_| type_ != checker.missing_type)
+    }
+
+    fn check_this_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let Some(container) = get_this_container(node) else {
+            return self.any_type;
+        };
+        if container.kind == SyntaxKind::ObjectLiteralExpression {
+            return self.check_expression(&container);
+        }
+        let Some(class) = container.parent().filter(|parent| is_class_like(parent)) else {
+            return self.get_this_type_of_function(node, &container);
+        };
+        self.check_this_before_super(
+            node,
+            &container,
+            diagnostics::SUPER_MUST_BE_CALLED_BEFORE_ACCESSING_THIS_IN_THE_CONSTRUCTOR_OF_A_DERIVED_CLASS_17009,
+        );
+        let Some(symbol) = self.get_symbol_of_declaration(&class) else {
+            return self.error_type;
+        };
+        if container.has_modifier(SyntaxKind::StaticKeyword) {
+            self.get_type_of_symbol(symbol)
+        } else {
+            self.get_declared_type_of_symbol(symbol)
+        }
+    }
+
+    /// Gets the type of `this` in a function that is not a class member: the type of its
+    /// `this` parameter, or the object literal whose property it is the value of
+    fn get_this_type_of_function(&mut self, node: &Arc<Node>, container: &Arc<Node>) -> TypeId {
+        if !is_function_like_declaration_kind(container.kind) {
+            return self.any_type;
+        }
+        if let Some(signature) = self.get_signature_of_declaration(container)
+            && let Some(this_parameter) = self.get_signature(signature).this_parameter
+        {
+            return self.get_type_of_symbol(this_parameter);
+        }
+        if container.kind == SyntaxKind::FunctionExpression
+            && let Some(object) = container
+                .parent()
+                .filter(|parent| parent.kind == SyntaxKind::PropertyAssignment)
+                .and_then(|property| property.parent())
+        {
+            return self.check_expression(&object);
+        }
+        if self.options.no_implicit_this {
+            self.error(
+                node,
+                diagnostics::THIS_IMPLICITLY_HAS_TYPE_ANY_BECAUSE_IT_DOES_NOT_HAVE_A_TYPE_ANNOTATION_2683,
+                &[],
+            );
+        }
+        self.any_type
+    }
+
+    /// Gets the type `super` refers to: the base class instance type in instance members, or
+    /// the base class constructor in static members and constructor calls
+    fn check_super_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let is_call = node.parent().is_some_and(|parent| {
+            parent.kind == SyntaxKind::CallExpression
+                && Arc::ptr_eq(&parent.as_call_expression().expression, node)
+        });
+        let container = get_super_container(node);
+        let class = container
+            .as_ref()
+            .and_then(|container| container.parent())
+            .filter(|parent| is_class_like(parent));
+        let Some(class) = class else {
+            self.error(
+                node,
+                diagnostics::SUPER_CAN_ONLY_BE_REFERENCED_IN_MEMBERS_OF_DERIVED_CLASSES_OR_OBJECT_LITERAL_EXPRESSIONS_2660,
+                &[],
+            );
+            return self.error_type;
+        };
+        let Some(symbol) = self.get_symbol_of_declaration(&class) else {
+            return self.error_type;
+        };
+        let class_type = self.get_declared_type_of_symbol(symbol);
+        let Some(&base_type) = self.get_base_types(class_type).first() else {
+            self.error(
+                node,
+                diagnostics::SUPER_CAN_ONLY_BE_REFERENCED_IN_A_DERIVED_CLASS_2335,
+                &[],
+            );
+            return self.error_type;
+        };
+        let is_static = container
+            .as_ref()
+            .is_some_and(|container| container.has_modifier(SyntaxKind::StaticKeyword));
+        if !is_call && let Some(container) = &container {
+            self.check_this_before_super(
+                node,
+                container,
+                diagnostics::SUPER_MUST_BE_CALLED_BEFORE_ACCESSING_A_PROPERTY_OF_SUPER_IN_THE_CONSTRUCTOR_OF_A_DERIVED_CLASS_17011,
+            );
+        }
+        if is_call || is_static {
+            let heritage_type = get_class_extends_expression(&class);
+            match heritage_type {
+                Some(expression) => self.check_expression(&expression),
+                None => self.error_type,
+            }
+        } else {
+            base_type
+        }
+    }
+
+    fn check_array_literal(&mut self, node: &Arc<Node>) -> TypeId {
+        let elements = &node.as_array_literal_expression().elements;
+        let (element_types, element_flags) = self.get_array_literal_elements(&elements.nodes);
+        // An array literal in a const assertion is a readonly tuple
+        if is_const_context(node) {
+            return self.create_tuple_type(element_types, element_flags, true);
+        }
+        // An array literal where a tuple is expected is a tuple
+        if let Some(contextual_type) = self.get_contextual_type(node)
+            && self.some_type(contextual_type, |checker, type_| {
+                checker.is_tuple_type(type_)
+            })
+        {
+            return self.create_tuple_type(element_types, element_flags, false);
+        }
+        if element_types.is_empty() {
+            return self.create_array_type(self.any_type);
+        }
+        let element_type = self.get_union_type(&element_types);
+        let element_type = self.get_widened_type(element_type);
+        self.create_array_type(element_type)
+    }
+
+    fn check_object_literal(&mut self, node: &Arc<Node>) -> TypeId {
+        let mut members = ResolvedMembers::default();
+        for property in &node.as_object_literal_expression().properties {
+            match property.kind {
+                SyntaxKind::SpreadAssignment => {
+                    let spread = self.check_expression(&property.as_spread_assignment().expression);
+                    if self.flags(spread).intersects(TypeFlags::ANY) {
+                        continue;
+                    }
+                    self.add_spread_members(&mut members, spread);
+                }
+                SyntaxKind::PropertyAssignment | SyntaxKind::ShorthandPropertyAssignment => {
+                    if let Some(name) = property.name()
+                        && name.kind == SyntaxKind::ComputedPropertyName
+                    {
+                        self.check_expression(&name.as_computed_property_name().expression);
+                    }
+                    match property.kind {
+                        SyntaxKind::PropertyAssignment => {
+                            self.check_expression(&property.as_property_assignment().initializer);
+                        }
+                        _ => {
+                            let name = &property.as_shorthand_property_assignment().name;
+                            self.check_identifier(name);
+                        }
+                    }
+                    self.add_object_literal_member(&mut members, property);
+                }
+                SyntaxKind::MethodDeclaration
+                | SyntaxKind::GetAccessor
+                | SyntaxKind::SetAccessor => {
+                    self.deferred_nodes.push(Arc::clone(property));
+                    self.add_object_literal_member(&mut members, property);
+                }
+                _ => {}
+            }
+        }
+        // The properties of an object literal in a const assertion are readonly
+        if is_const_context(node) {
+            for property in &node.as_object_literal_expression().properties {
+                if !matches!(
+                    property.kind,
+                    SyntaxKind::PropertyAssignment
+                        | SyntaxKind::ShorthandPropertyAssignment
+                        | SyntaxKind::MethodDeclaration
+                ) {
+                    continue;
+                }
+                if let Some(symbol) = self.get_symbol_of_declaration(property) {
+                    self.bindings.symbols[symbol].check_flags |= CheckFlags::READONLY;
+                }
+            }
+        }
+        let symbol = self.get_symbol_of_declaration(node);
+        let type_ = self.create_resolved_type(symbol, members);
+        self.types[type_.index()].object_flags |=
+            ObjectFlags::OBJECT_LITERAL | ObjectFlags::FRESH_LITERAL;
+        type_
+    }
+
+    fn add_object_literal_member(&mut self, members: &mut ResolvedMembers, property: &Arc<Node>) {
+        if let Some(symbol) = self.get_symbol_of_declaration(property) {
+            // A property with a computed name that names a property is bound under that name
+            if has_dynamic_name(property)
+                && let Some(name) = property
+                    .name()
+                    .and_then(|name| self.get_late_bound_name(name))
+            {
+                self.bindings.symbols[symbol].name = name;
+            }
+            let name = self.symbol(symbol).name.clone();
+            members.add_property(&name, symbol);
+        }
+    }
+
+    /// Checks that an expression satisfies a type, which is the contextual type of the
+    /// expression but not its type: the expression keeps its own type, literals included
+    fn check_satisfies_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let satisfies = node.as_satisfies_expression();
+        self.check_type_node(&satisfies.type_node);
+        let expression_type = self.check_expression(&satisfies.expression);
+        let target_type = self.get_type_from_type_node(&satisfies.type_node);
+        if target_type == self.error_type {
+            return expression_type;
+        }
+        self.check_type_assignable_to_and_optionally_elaborate(
+            expression_type,
+            target_type,
+            node,
+            Some(&satisfies.expression),
+            Some(diagnostics::TYPE_0_DOES_NOT_SATISFY_THE_EXPECTED_TYPE_1_1360),
+        );
+        expression_type
+    }
+
+    fn check_property_access_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let access = node.as_property_access_expression();
+        let left_type = self.check_expression(&access.expression);
+        let left_type = if access.question_dot_token.is_some() {
+            self.get_non_nullable_type(left_type)
+        } else {
+            self.check_non_null_type(left_type, &access.expression, false)
+        };
+        self.get_type_of_property_access(node, &access.expression, left_type, &access.name)
+    }
+
+    /// Reports an error if a value that is accessed or called may be `null` or `undefined`,
+    /// and gets its type without them
+    fn check_non_null_type(&mut self, type_: TypeId, node: &Arc<Node>, is_call: bool) -> TypeId {
+        if !self.options.strict_null_checks
+            || self.flags(type_).intersects(TypeFlags::ANY_OR_UNKNOWN)
+        {
+            return type_;
+        }
+        let is_null = self.maybe_type_of_kind(type_, TypeFlags::NULL);
+        let is_undefined = self.maybe_type_of_kind(type_, TypeFlags::UNDEFINED | TypeFlags::VOID);
+        if !is_null && !is_undefined {
+            return type_;
+        }
+        let literal = match node.kind {
+            SyntaxKind::NullKeyword => Some("null"),
+            SyntaxKind::Identifier if node.text() == "undefined" => Some("undefined"),
+            _ => None,
+        };
+        if let Some(literal) = literal {
+            self.error(
+                node,
+                diagnostics::THE_VALUE_0_CANNOT_BE_USED_HERE_18050,
+                &[literal.to_string()],
+            );
+            return self.error_type;
+        }
+        let message = if is_call {
+            match (is_null, is_undefined) {
+                (true, true) => {
+                    diagnostics::CANNOT_INVOKE_AN_OBJECT_WHICH_IS_POSSIBLY_NULL_OR_UNDEFINED_2723
+                }
+                (true, false) => diagnostics::CANNOT_INVOKE_AN_OBJECT_WHICH_IS_POSSIBLY_NULL_2721,
+                _ => diagnostics::CANNOT_INVOKE_AN_OBJECT_WHICH_IS_POSSIBLY_UNDEFINED_2722,
+            }
+        } else if is_entity_name_expression(node) {
+            match (is_null, is_undefined) {
+                (true, true) => diagnostics::D_0_IS_POSSIBLY_NULL_OR_UNDEFINED_18049,
+                (true, false) => diagnostics::D_0_IS_POSSIBLY_NULL_18047,
+                _ => diagnostics::D_0_IS_POSSIBLY_UNDEFINED_18048,
+            }
+        } else {
+            match (is_null, is_undefined) {
+                (true, true) => diagnostics::OBJECT_IS_POSSIBLY_NULL_OR_UNDEFINED_2533,
+                (true, false) => diagnostics::OBJECT_IS_POSSIBLY_NULL_2531,
+                _ => diagnostics::OBJECT_IS_POSSIBLY_UNDEFINED_2532,
+            }
+        };
+        let file = get_source_file_of_node(node);
+        let text = get_text_of_node(&file, node).to_string();
+        self.error(node, message, &[text]);
+        let non_nullable = self.get_non_nullable_type(type_);
+        if self
+            .flags(non_nullable)
+            .intersects(TypeFlags::NULLABLE | TypeFlags::VOID | TypeFlags::NEVER)
+        {
+            return self.error_type;
+        }
+        non_nullable
+    }
+
+    /// Adds `undefined` to the type of the end of an optional chain such as `a?.b.c`, which
+    /// short-circuits to `undefined` when a `?.` link's object is `null` or `undefined`
+    fn propagate_optional_chain_type(&mut self, node: &Arc<Node>, type_: TypeId) -> TypeId {
+        if !self.options.strict_null_checks || is_inner_chain_link(node) {
+            return type_;
+        }
+        let mut current = get_chain_expression(node);
+        let mut link = Arc::clone(node);
+        while let Some(expression) = current {
+            if has_question_dot_token(&link) {
+                let object_type = self.check_expression(&expression);
+                if self.maybe_type_of_kind(object_type, TypeFlags::NULLABLE) {
+                    return self.get_optional_type(type_, false);
+                }
+            }
+            current = get_chain_expression(&expression);
+            link = expression;
+        }
+        type_
+    }
+
+    /// Gets the type of a property of the type of `left`, reporting an error if the property
+    /// does not exist
+    pub(super) fn get_type_of_property_access(
+        &mut self,
+        node: &Arc<Node>,
+        left: &Arc<Node>,
+        left_type: TypeId,
+        name: &Arc<Node>,
+    ) -> TypeId {
+        let flags = self.flags(left_type);
+        if left_type == self.error_type {
+            return self.error_type;
+        }
+        if flags.intersects(TypeFlags::ANY | TypeFlags::NEVER | TypeFlags::NULLABLE) {
+            return self.any_type;
+        }
+        if flags.contains(TypeFlags::UNKNOWN) {
+            self.error(left, diagnostics::OBJECT_IS_OF_TYPE_UNKNOWN_2571, &[]);
+            return self.error_type;
+        }
+        let text = name.text().to_string();
+        if let Some(property) = self.get_property_of_type(left_type, &text) {
+            self.node_links(node).resolved_symbol = Some(property);
+            self.mark_property_referenced(node, property);
+            self.check_property_accessibility(node, name, property);
+            if is_assignment_target(node) && self.is_assignment_to_readonly_property(node, property)
+            {
+                self.error(
+                    name,
+                    diagnostics::CANNOT_ASSIGN_TO_0_BECAUSE_IT_IS_A_READ_ONLY_PROPERTY_2540,
+                    &[text],
+                );
+                return self.error_type;
+            }
+            return self.get_flow_type_of_property(node, property);
+        }
+        // A private name is only ever a declared property
+        if name.kind != SyntaxKind::PrivateIdentifier
+            && let Some(info) = self.get_applicable_index_info(left_type, self.string_type)
+        {
+            if self.options.no_property_access_from_index_signature
+                && node.kind == SyntaxKind::PropertyAccessExpression
+            {
+                self.error(
+                    name,
+                    diagnostics::PROPERTY_0_COMES_FROM_AN_INDEX_SIGNATURE_SO_IT_MUST_BE_ACCESSED_WITH_0_4111,
+                    &[text],
+                );
+            }
+            if info.is_readonly && is_assignment_target(node) {
+                self.report_readonly_index_signature(node, left_type);
+            }
+            return self.get_index_access_type(node, info.value_type);
+        }
+        if self.is_permissive_type(left_type) {
+            return self.any_type;
+        }
+        self.report_nonexistent_property(name, left_type);
+        self.error_type
+    }
+
+    /// Determines if the access `node` writes to a readonly property
+    ///
+    /// The constructor of the class that declares a readonly property may still assign it
+    /// through `this`, which is how it initializes it.
+    fn is_assignment_to_readonly_property(&self, node: &Arc<Node>, property: SymbolId) -> bool {
+        if !self.is_readonly_property(property) {
+            return false;
+        }
+        let symbol = self.symbol(property);
+        let through_this = node
+            .expression()
+            .is_some_and(|expression| expression.kind == SyntaxKind::ThisKeyword);
+        if !symbol.flags.contains(SymbolFlags::PROPERTY) || !through_this {
+            return true;
+        }
+        let Some(constructor) = get_containing_function(node)
+            .filter(|function| function.kind == SyntaxKind::Constructor)
+        else {
+            return true;
+        };
+        let Some(declaration) = &symbol.value_declaration else {
+            return true;
+        };
+        let Some(parent) = declaration.parent() else {
+            return true;
+        };
+        let declared_by_constructor = if declaration.kind == SyntaxKind::Parameter {
+            Arc::ptr_eq(&parent, &constructor)
+        } else {
+            constructor
+                .parent()
+                .is_some_and(|class| Arc::ptr_eq(&class, &parent))
+        };
+        !declared_by_constructor
+    }
+
+    /// Reports an access to a private or protected property from outside the classes that can
+    /// see it
+    ///
+    /// Private properties are visible in the body of the class that declares them, and
+    /// protected ones also in the bodies of the classes derived from it. Element accesses are
+    /// not checked, which is how code gets around the modifiers on purpose.
+    fn check_property_accessibility(
+        &mut self,
+        node: &Arc<Node>,
+        name: &Arc<Node>,
+        property: SymbolId,
+    ) {
+        let Some(declaration) = self.symbol(property).value_declaration.clone() else {
+            return;
+        };
+        let is_private_name = name.kind == SyntaxKind::PrivateIdentifier;
+        let is_private = declaration.has_modifier(SyntaxKind::PrivateKeyword);
+        let is_protected = declaration.has_modifier(SyntaxKind::ProtectedKeyword);
+        if !is_private_name && !is_private && !is_protected {
+            return;
+        }
+        let Some(declaring_class) = get_declaring_class(&declaration) else {
+            return;
+        };
+        let enclosing_classes = get_enclosing_classes(node);
+        if enclosing_classes
+            .iter()
+            .any(|class| Arc::ptr_eq(class, &declaring_class))
+        {
+            return;
+        }
+        let class_name = declaring_class.name().map_or_else(
+            || "(Anonymous class)".to_string(),
+            |name| name.text().to_string(),
+        );
+        let text = name.text().to_string();
+        if is_private_name {
+            self.error(
+                name,
+                diagnostics::PROPERTY_0_IS_NOT_ACCESSIBLE_OUTSIDE_CLASS_1_BECAUSE_IT_HAS_A_PRIVATE_IDENTIFIER_18013,
+                &[text, class_name],
+            );
+        } else if is_private {
+            self.error(
+                name,
+                diagnostics::PROPERTY_0_IS_PRIVATE_AND_ONLY_ACCESSIBLE_WITHIN_CLASS_1_2341,
+                &[text, class_name],
+            );
+        } else {
+            let Some(declaring_type) = self.get_class_declared_type(&declaring_class) else {
+                return;
+            };
+            for class in enclosing_classes {
+                if let Some(class_type) = self.get_class_declared_type(&class)
+                    && self.derives_from(class_type, declaring_type)
+                {
+                    return;
+                }
+            }
+            self.error(
+                name,
+                diagnostics::PROPERTY_0_IS_PROTECTED_AND_ONLY_ACCESSIBLE_WITHIN_CLASS_1_AND_ITS_SUBCLASSES_2445,
+                &[text, class_name],
+            );
+        }
+    }
+
+    fn get_class_declared_type(&mut self, class: &Node) -> Option<TypeId> {
+        let symbol = self.get_symbol_of_declaration(class)?;
+        Some(self.get_declared_type_of_symbol(symbol))
+    }
+
+    /// Determines if a class or interface type is `base`, or extends it directly or through
+    /// other base types
+    fn derives_from(&mut self, type_: TypeId, base: TypeId) -> bool {
+        let target = self
+            .get_reference_target(type_)
+            .map_or(type_, |(target, _)| target);
+        target == base
+            || self
+                .get_base_types(target)
+                .into_iter()
+                .any(|base_type| self.derives_from(base_type, base))
+    }
+
+    /// Gets the type an access through an index signature reads, which may be `undefined`
+    /// under `noUncheckedIndexedAccess`, since no key is guaranteed to be present
+    fn get_index_access_type(&mut self, node: &Arc<Node>, value_type: TypeId) -> TypeId {
+        if self.options.no_unchecked_indexed_access && !is_assignment_target(node) {
+            self.get_optional_type(value_type, true)
+        } else {
+            value_type
+        }
+    }
+
+    /// Reports a write through a readonly index signature of `object_type`
+    fn report_readonly_index_signature(&mut self, node: &Arc<Node>, object_type: TypeId) {
+        let type_name = self.type_to_string(object_type);
+        self.error(
+            node,
+            diagnostics::INDEX_SIGNATURE_IN_TYPE_0_ONLY_PERMITS_READING_2542,
+            &[type_name],
+        );
+    }
+
+    /// Reports that the property `name` does not exist on `containing_type`, suggesting a
+    /// similarly named property if there is one
+    fn report_nonexistent_property(&mut self, name: &Arc<Node>, containing_type: TypeId) {
+        let text = name.text().to_string();
+        let type_name = self.type_to_string(containing_type);
+        let Some(suggestion) =
+            self.get_suggested_symbol_for_nonexistent_property(&text, containing_type)
+        else {
+            self.error(
+                name,
+                diagnostics::PROPERTY_0_DOES_NOT_EXIST_ON_TYPE_1_2339,
+                &[text, type_name],
+            );
+            return;
+        };
+        let suggestion_name = self.symbol(suggestion).name.clone();
+        let chain = DiagnosticMessageChain::new(
+            diagnostics::PROPERTY_0_DOES_NOT_EXIST_ON_TYPE_1_DID_YOU_MEAN_2_2551,
+            &[text, type_name, suggestion_name.clone()],
+        );
+        let related = self
+            .symbol(suggestion)
+            .value_declaration
+            .clone()
+            .map(|declaration| {
+                self.create_diagnostic_for_node(
+                    &declaration,
+                    diagnostics::D_0_IS_DECLARED_HERE_2728,
+                    &[suggestion_name],
+                )
+            });
+        self.error_with_chain(name, &chain, related.into_iter().collect());
+    }
+
+    /// Gets the type of a property where an access reads it, narrowed by control flow
+    fn get_flow_type_of_property(&mut self, node: &Arc<Node>, property: SymbolId) -> TypeId {
+        // A write goes through the setter, which may take another type than the getter returns
+        let type_ = if is_write_only_access(node) {
+            self.get_write_type_of_symbol(property)
+        } else {
+            self.get_type_of_symbol(property)
+        };
+        let flags = self.symbol(property).flags;
+        if is_assignment_target(node) && flags.contains(SymbolFlags::OPTIONAL) {
+            return self.remove_missing_type(type_);
+        }
+        if !flags.intersects(SymbolFlags::PROPERTY | SymbolFlags::ACCESSOR)
+            || is_assignment_target(node)
+        {
+            return type_;
+        }
+        if !self.is_uninitialized_property_access(node, property) {
+            return self.get_flow_type_of_reference(node, type_, type_);
+        }
+        let initial_type = self.get_optional_type(type_, false);
+        let flow_type = self.get_flow_type_of_reference(node, type_, initial_type);
+        if !self.maybe_type_of_kind(type_, TypeFlags::UNDEFINED)
+            && self.maybe_type_of_kind(flow_type, TypeFlags::UNDEFINED)
+        {
+            let name = symbol_name(self.symbol(property)).into_owned();
+            self.error(
+                node.name().unwrap_or(node),
+                diagnostics::PROPERTY_0_IS_USED_BEFORE_BEING_ASSIGNED_2565,
+                &[name],
+            );
+            return type_;
+        }
+        flow_type
+    }
+
+    fn check_element_access_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let access = node.as_element_access_expression();
+        let object_type = self.check_expression(&access.expression);
+        let object_type = if access.question_dot_token.is_some() {
+            self.get_non_nullable_type(object_type)
+        } else {
+            self.check_non_null_type(object_type, &access.expression, false)
+        };
+        let index_type = self.check_expression(&access.argument_expression);
+        let flags = self.flags(object_type);
+        if object_type == self.error_type || index_type == self.error_type {
+            return self.error_type;
+        }
+        if flags.intersects(TypeFlags::ANY | TypeFlags::NEVER | TypeFlags::NULLABLE) {
+            return self.any_type;
+        }
+        if flags.contains(TypeFlags::UNKNOWN) {
+            self.error(
+                &access.expression,
+                diagnostics::OBJECT_IS_OF_TYPE_UNKNOWN_2571,
+                &[],
+            );
+            return self.error_type;
+        }
+        if self.is_const_enum_object_type(object_type)
+            && !is_string_literal_like(&access.argument_expression)
+        {
+            self.error(
+                &access.argument_expression,
+                diagnostics::A_CONST_ENUM_MEMBER_CAN_ONLY_BE_ACCESSED_USING_A_STRING_LITERAL_2476,
+                &[],
+            );
+            return self.error_type;
+        }
+        if let Some(name) = self.get_property_name_from_type(index_type)
+            && let Some(property) = self.get_property_of_type(object_type, &name)
+        {
+            self.node_links(node).resolved_symbol = Some(property);
+            self.mark_property_referenced(node, property);
+            if is_assignment_target(node) && self.is_assignment_to_readonly_property(node, property)
+            {
+                self.error(
+                    &access.argument_expression,
+                    diagnostics::CANNOT_ASSIGN_TO_0_BECAUSE_IT_IS_A_READ_ONLY_PROPERTY_2540,
+                    &[name],
+                );
+                return self.error_type;
+            }
+            return self.get_flow_type_of_property(node, property);
+        }
+        if let Some(info) = self.get_applicable_index_info(object_type, index_type) {
+            if info.is_readonly && is_assignment_target(node) {
+                self.report_readonly_index_signature(node, object_type);
+            }
+            return self.get_index_access_type(node, info.value_type);
+        }
+        // Without an index signature the access is implicitly `any`
+        if self.options.no_implicit_any
+            && !self.flags(index_type).contains(TypeFlags::ANY)
+            && !self.is_permissive_type(object_type)
+        {
+            if self
+                .get_applicable_index_info(object_type, self.number_type)
+                .is_some()
+            {
+                self.error(
+                    &access.argument_expression,
+                    diagnostics::ELEMENT_IMPLICITLY_HAS_AN_ANY_TYPE_BECAUSE_INDEX_EXPRESSION_IS_NOT_OF_TYPE_NUMBER_7015,
+                    &[],
+                );
+                return self.any_type;
+            }
+            let index_name = self.type_to_string(index_type);
+            let object_name = self.type_to_string(object_type);
+            self.error(
+                node,
+                diagnostics::ELEMENT_IMPLICITLY_HAS_AN_ANY_TYPE_BECAUSE_EXPRESSION_OF_TYPE_0_CAN_T_BE_USED_TO_INDEX_TYPE_1_7053,
+                &[index_name, object_name],
+            );
+        }
+        self.any_type
+    }
+
+    fn check_call_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let Some(signature) = self.resolve_call_like_expression(node) else {
+            return match self.node_links(node).resolved_type {
+                Some(type_) => type_,
+                None => self.any_type,
+            };
+        };
+        self.node_links(node).resolved_signature = Some(signature);
+        if node.kind == SyntaxKind::CallExpression
+            && node
+                .expression()
+                .is_some_and(|callee| callee.kind == SyntaxKind::SuperKeyword)
+        {
+            return self.void_type;
+        }
+        if node.kind == SyntaxKind::NewExpression {
+            let declaration = self.get_signature(signature).declaration.clone();
+            let is_call_signature = declaration.is_some_and(|declaration| {
+                !matches!(
+                    declaration.kind,
+                    SyntaxKind::Constructor
+                        | SyntaxKind::ConstructSignature
+                        | SyntaxKind::ConstructorType
+                )
+            }) && !self
+                .get_signature(signature)
+                .flags
+                .contains(super::SignatureFlags::CONSTRUCT);
+            if is_call_signature {
+                return self.any_type;
+            }
+        }
+        let return_type = self.get_return_type_of_signature(signature);
+        if node.kind == SyntaxKind::CallExpression {
+            return self.get_type_of_es_symbol_call(node, return_type);
+        }
+        return_type
+    }
+
+    /// Resolves the signature a call or `new` expression invokes, reporting an error if
+    /// there is none
+    ///
+    /// Returns None for untyped calls, such as calls of `any`, whose result is `any`.
+    fn resolve_call_like_expression(&mut self, node: &Arc<Node>) -> Option<SignatureId> {
+        self.node_links(node).resolving_signature = true;
+        let signature = self.resolve_call_signature(node);
+        self.node_links(node).resolving_signature = false;
+        // Arguments are checked against the signature while resolving it, except when
+        // there is none to check them against
+        let arguments = match node.kind {
+            SyntaxKind::CallExpression => Some(&node.as_call_expression().arguments),
+            _ => node.as_new_expression().arguments.as_ref(),
+        };
+        for argument in arguments.into_iter().flatten() {
+            self.check_expression(argument);
+        }
+        signature
+    }
+
+    fn resolve_call_signature(&mut self, node: &Arc<Node>) -> Option<SignatureId> {
+        let (callee, type_arguments, arguments) = match node.kind {
+            SyntaxKind::CallExpression => {
+                let call = node.as_call_expression();
+                (
+                    &call.expression,
+                    call.type_arguments.as_ref(),
+                    call.arguments.nodes.clone(),
+                )
+            }
+            _ => {
+                let new = node.as_new_expression();
+                (
+                    &new.expression,
+                    new.type_arguments.as_ref(),
+                    new.arguments
+                        .as_ref()
+                        .map(|arguments| arguments.nodes.clone())
+                        .unwrap_or_default(),
+                )
+            }
+        };
+        let is_new = node.kind == SyntaxKind::NewExpression;
+        let is_super_call = callee.kind == SyntaxKind::SuperKeyword;
+        let callee_type = self.check_expression(callee);
+        let callee_type = if has_question_dot_token(node) {
+            self.get_non_nullable_type(callee_type)
+        } else if is_super_call {
+            callee_type
+        } else {
+            self.check_non_null_type(callee_type, callee, !is_new)
+        };
+        for type_argument in type_arguments.into_iter().flatten() {
+            self.get_type_from_type_node(type_argument);
+        }
+        if callee_type == self.error_type {
+            self.node_links(node).resolved_type = Some(self.error_type);
+            return None;
+        }
+        if self.flags(callee_type).intersects(TypeFlags::ANY) {
+            if let Some(type_arguments) = type_arguments.filter(|arguments| !arguments.is_empty()) {
+                let range = self.get_range_of_nodes(node, &type_arguments.nodes);
+                self.error_at_range(
+                    node,
+                    range,
+                    diagnostics::UNTYPED_FUNCTION_CALLS_MAY_NOT_ACCEPT_TYPE_ARGUMENTS_2347,
+                    &[],
+                );
+            }
+            return None;
+        }
+        let apparent_type = self.get_apparent_type(callee_type);
+        let signatures = if is_new || is_super_call {
+            self.get_signatures_of_type(apparent_type, true)
+        } else {
+            self.get_signatures_of_type(apparent_type, false)
+        };
+        if signatures.is_empty() {
+            return self.report_invocation_error(
+                node,
+                callee,
+                callee_type,
+                is_new || is_super_call,
+            );
+        }
+        // An abstract class can only be constructed as the base of a derived class
+        if is_new
+            && signatures.iter().any(|&signature| {
+                self.get_signature(signature)
+                    .flags
+                    .contains(super::SignatureFlags::ABSTRACT)
+            })
+        {
+            self.error(
+                node,
+                diagnostics::CANNOT_CREATE_AN_INSTANCE_OF_AN_ABSTRACT_CLASS_2511,
+                &[],
+            );
+            self.node_links(node).resolved_type = Some(self.error_type);
+            return None;
+        }
+        self.choose_signature(node, callee, &signatures, type_arguments, &arguments)
+    }
+
+    /// Reports that a value cannot be called or constructed, unless it is the global
+    /// `Function` type, which can be called with anything
+    fn report_invocation_error(
+        &mut self,
+        node: &Arc<Node>,
+        callee: &Arc<Node>,
+        callee_type: TypeId,
+        construct: bool,
+    ) -> Option<SignatureId> {
+        let apparent_type = self.get_apparent_type(callee_type);
+        if apparent_type == self.get_global_type("Function") {
+            return None;
+        }
+        if construct {
+            let call_signatures = self.get_signatures_of_type(apparent_type, false);
+            if let Some(&signature) = call_signatures.first() {
+                let return_type = self.get_return_type_of_signature(signature);
+                if self.get_signature(signature).declaration.is_some()
+                    && return_type != self.void_type
+                {
+                    self.error(
+                        node,
+                        diagnostics::ONLY_A_VOID_FUNCTION_CAN_BE_CALLED_WITH_THE_NEW_KEYWORD_2350,
+                        &[],
+                    );
+                }
+                return Some(signature);
+            }
+            self.error(
+                callee,
+                diagnostics::THIS_EXPRESSION_IS_NOT_CONSTRUCTABLE_2351,
+                &[],
+            );
+        } else if !self.get_signatures_of_type(apparent_type, true).is_empty() {
+            let type_name = self.type_to_string(callee_type);
+            self.error(
+                node,
+                diagnostics::VALUE_OF_TYPE_0_IS_NOT_CALLABLE_DID_YOU_MEAN_TO_INCLUDE_NEW_2348,
+                &[type_name],
+            );
+        } else {
+            self.error(
+                callee,
+                diagnostics::THIS_EXPRESSION_IS_NOT_CALLABLE_2349,
+                &[],
+            );
+        }
+        self.node_links(node).resolved_type = Some(self.error_type);
+        None
+    }
+
+    /// Chooses the first signature whose arity fits the type arguments and arguments,
+    /// reporting an arity error if none does
+    fn choose_signature(
+        &mut self,
+        node: &Arc<Node>,
+        callee: &Arc<Node>,
+        signatures: &[SignatureId],
+        type_arguments: Option<&NodeList>,
+        arguments: &[Arc<Node>],
+    ) -> Option<SignatureId> {
+        let explicit_type_arguments = type_arguments.map(|nodes| {
+            nodes
+                .iter()
+                .map(|node| self.get_type_from_type_node(node))
+                .collect::<Vec<_>>()
+        });
+        let candidates: Vec<SignatureId> = match &explicit_type_arguments {
+            Some(type_arguments) => signatures
+                .iter()
+                .copied()
+                .filter(|&signature| {
+                    self.has_correct_type_argument_arity(signature, type_arguments.len())
+                })
+                .collect(),
+            None => signatures.to_vec(),
+        };
+        if candidates.is_empty() {
+            let type_arguments = type_arguments.expect("only type arguments rule out signatures");
+            self.report_type_argument_arity_error(node, signatures, type_arguments);
+            self.node_links(node).resolved_type = Some(self.error_type);
+            return None;
+        }
+
+        let (argument_count, spread) = self.get_effective_argument_count(arguments);
+        let spread_index = spread.map(|(position, _)| position);
+        let mut applicable = Vec::new();
+        for &signature in &candidates {
+            if self.has_correct_arity(signature, argument_count, spread_index) {
+                applicable.push(signature);
+            }
+        }
+        if applicable.is_empty() {
+            let mut accepts_spread = false;
+            for &signature in &candidates {
+                accepts_spread |= self.has_effective_rest_parameter(signature);
+            }
+            if let Some((_, spread_argument)) = spread
+                && !accepts_spread
+            {
+                self.error(
+                    &arguments[spread_argument],
+                    diagnostics::A_SPREAD_ARGUMENT_MUST_EITHER_HAVE_A_TUPLE_TYPE_OR_BE_PASSED_TO_A_REST_PARAMETER_2556,
+                    &[],
+                );
+            } else {
+                self.report_argument_arity_error(
+                    node,
+                    callee,
+                    &candidates,
+                    arguments,
+                    argument_count,
+                );
+            }
+            return Some(self.instantiate_candidate(
+                node,
+                candidates[0],
+                &explicit_type_arguments,
+                arguments,
+            ));
+        }
+        let applicable: Vec<SignatureId> = applicable
+            .into_iter()
+            .map(|signature| {
+                self.instantiate_candidate(node, signature, &explicit_type_arguments, arguments)
+            })
+            .collect();
+        for &signature in &applicable {
+            if self.get_argument_error(signature, arguments).is_none() {
+                return Some(signature);
+            }
+        }
+        self.report_argument_type_errors(callee, &applicable, arguments);
+        Some(applicable[0])
+    }
+
+    /// Instantiates a generic candidate with its explicit type arguments, or with type
+    /// arguments inferred from the arguments of the call
+    fn instantiate_candidate(
+        &mut self,
+        node: &Arc<Node>,
+        signature: SignatureId,
+        explicit_type_arguments: &Option<Vec<TypeId>>,
+        arguments: &[Arc<Node>],
+    ) -> SignatureId {
+        let type_parameters = self.get_signature(signature).type_parameters.clone();
+        if type_parameters.is_empty() {
+            return signature;
+        }
+        let type_arguments = match explicit_type_arguments {
+            Some(type_arguments) => {
+                self.fill_missing_type_arguments(type_arguments.clone(), &type_parameters)
+            }
+            None => self.infer_type_arguments(node, signature, &type_parameters, arguments),
+        };
+        self.get_signature_instantiation(signature, type_arguments)
+    }
+
+    /// Infers the type arguments of a call, first from the arguments whose types do not
+    /// depend on their contextual types, and then from functions whose parameters take
+    /// their types from what was inferred so far
+    fn infer_type_arguments(
+        &mut self,
+        node: &Arc<Node>,
+        signature: SignatureId,
+        type_parameters: &[TypeId],
+        arguments: &[Arc<Node>],
+    ) -> Vec<TypeId> {
+        let mut context = InferenceContext::new(type_parameters, Some(signature));
+        // The type the call is expected to produce infers to the return type, at the
+        // lowest priority
+        if let Some(contextual_type) = self.get_contextual_type(node) {
+            let return_type = self.get_return_type_of_signature(signature);
+            self.infer_types(
+                &mut context,
+                contextual_type,
+                return_type,
+                InferencePriority::RETURN_TYPE,
+            );
+        }
+        for context_sensitive in [false, true] {
+            for (index, argument) in arguments.iter().enumerate() {
+                if argument.kind == SyntaxKind::SpreadElement {
+                    break;
+                }
+                if self.is_context_sensitive(argument) != context_sensitive {
+                    continue;
+                }
+                let Some(parameter_type) = self.get_type_at_position(signature, index) else {
+                    continue;
+                };
+                let contextual_type = if context_sensitive {
+                    let mapper = self.get_inference_mapper(&mut context);
+                    self.instantiate_type(parameter_type, &mapper)
+                } else {
+                    parameter_type
+                };
+                self.node_links(argument).contextual_type = Some(contextual_type);
+                let argument_type = self.check_expression(argument);
+                self.infer_types(
+                    &mut context,
+                    argument_type,
+                    parameter_type,
+                    InferencePriority::NONE,
+                );
+            }
+        }
+        self.get_inferred_types(&mut context)
+    }
+
+    /// Finds the first argument not assignable to its parameter, returning its index, its
+    /// type, and the parameter's type
+    fn get_argument_error(
+        &mut self,
+        signature: SignatureId,
+        arguments: &[Arc<Node>],
+    ) -> Option<(usize, TypeId, TypeId)> {
+        // A spread of a tuple passes its elements, so positions run ahead of the arguments
+        let mut position = 0;
+        for (index, argument) in arguments.iter().enumerate() {
+            if argument.kind == SyntaxKind::SpreadElement {
+                if let Some((argument_type, parameter_type)) =
+                    self.get_spread_argument_error(signature, argument, &mut position)
+                {
+                    return Some((index, argument_type, parameter_type));
+                }
+                continue;
+            }
+            let parameter_type = self.get_type_at_position(signature, position);
+            position += 1;
+            let Some(parameter_type) = parameter_type else {
+                continue;
+            };
+            let links = self.node_links(argument);
+            if links.resolved_type.is_none() {
+                links.contextual_type = Some(parameter_type);
+            }
+            let argument_type = self.check_expression(argument);
+            if !self.is_type_assignable_to(argument_type, parameter_type) {
+                return Some((index, argument_type, parameter_type));
+            }
+        }
+        None
+    }
+
+    /// Reports the arguments no candidate accepts: for a single candidate on the argument
+    /// itself, and for overloads as the error each overload gave
+    fn report_argument_type_errors(
+        &mut self,
+        callee: &Arc<Node>,
+        candidates: &[SignatureId],
+        arguments: &[Arc<Node>],
+    ) {
+        let head = diagnostics::ARGUMENT_OF_TYPE_0_IS_NOT_ASSIGNABLE_TO_PARAMETER_OF_TYPE_1_2345;
+        if let [signature] = candidates {
+            if let Some((index, argument_type, parameter_type)) =
+                self.get_argument_error(*signature, arguments)
+            {
+                let argument = &arguments[index];
+                self.check_type_assignable_to_and_optionally_elaborate(
+                    argument_type,
+                    parameter_type,
+                    argument,
+                    Some(argument),
+                    Some(head),
+                );
+            }
+            return;
+        }
+        let mut chain =
+            DiagnosticMessageChain::new(diagnostics::NO_OVERLOAD_MATCHES_THIS_CALL_2769, &[]);
+        let mut related_information = Vec::new();
+        let mut failing_indices = Vec::new();
+        for (position, &signature) in candidates.iter().enumerate() {
+            let Some((index, argument_type, parameter_type)) =
+                self.get_argument_error(signature, arguments)
+            else {
+                continue;
+            };
+            failing_indices.push(index);
+            let Some(error) = self.get_relation_error(
+                argument_type,
+                parameter_type,
+                Relation::Assignable,
+                Some(head),
+            ) else {
+                continue;
+            };
+            let signature_text = self.signature_to_string(signature);
+            chain.next.push(DiagnosticMessageChain::chain(
+                Some(error.chain),
+                diagnostics::OVERLOAD_0_OF_1_2_GAVE_THE_FOLLOWING_ERROR_2772,
+                &[
+                    (position + 1).to_string(),
+                    candidates.len().to_string(),
+                    signature_text,
+                ],
+            ));
+            related_information.extend(error.related_information);
+        }
+        // The error goes on the argument every overload rejected, if they agree on one
+        let error_node = match failing_indices[..] {
+            [first, ref rest @ ..] if rest.iter().all(|&index| index == first) => {
+                Arc::clone(&arguments[first])
+            }
+            _ => match callee.kind {
+                SyntaxKind::PropertyAccessExpression => {
+                    Arc::clone(&callee.as_property_access_expression().name)
+                }
+                _ => Arc::clone(callee),
+            },
+        };
+        self.error_with_chain(&error_node, &chain, related_information);
+    }
+
+    fn has_correct_type_argument_arity(&mut self, signature: SignatureId, count: usize) -> bool {
+        let type_parameters = self.get_signature(signature).type_parameters.clone();
+        let min = self.get_min_type_argument_count(&type_parameters);
+        (min..=type_parameters.len()).contains(&count)
+    }
+
+    fn get_min_type_argument_count(&mut self, type_parameters: &[TypeId]) -> usize {
+        type_parameters
+            .iter()
+            .take_while(|&&parameter| self.get_default_of_type_parameter(parameter).is_none())
+            .count()
+    }
+
+    fn has_correct_arity(
+        &mut self,
+        signature: SignatureId,
+        argument_count: usize,
+        spread_index: Option<usize>,
+    ) -> bool {
+        let min_argument_count = self.get_min_argument_count(signature);
+        let parameter_count = self.get_parameter_count(signature);
+        let has_rest_parameter = self.has_effective_rest_parameter(signature);
+        if let Some(spread_index) = spread_index {
+            // A spread of an array supplies an unknown number of arguments from its position
+            return spread_index >= min_argument_count
+                && (has_rest_parameter || spread_index < parameter_count);
+        }
+        if argument_count < min_argument_count {
+            return false;
+        }
+        has_rest_parameter || argument_count <= parameter_count
+    }
+
+    fn report_type_argument_arity_error(
+        &mut self,
+        node: &Arc<Node>,
+        signatures: &[SignatureId],
+        type_arguments: &NodeList,
+    ) {
+        let mut min = usize::MAX;
+        let mut max = 0;
+        for &signature in signatures {
+            let type_parameters = self.get_signature(signature).type_parameters.clone();
+            min = min.min(self.get_min_type_argument_count(&type_parameters));
+            max = max.max(type_parameters.len());
+        }
+        let expected = if min < max {
+            format!("{min}-{max}")
+        } else {
+            min.to_string()
+        };
+        let range = self.get_range_of_nodes(node, &type_arguments.nodes);
+        self.error_at_range(
+            node,
+            range,
+            diagnostics::EXPECTED_0_TYPE_ARGUMENTS_BUT_GOT_1_2558,
+            &[expected, type_arguments.len().to_string()],
+        );
+    }
+
+    fn report_argument_arity_error(
+        &mut self,
+        node: &Arc<Node>,
+        callee: &Arc<Node>,
+        signatures: &[SignatureId],
+        arguments: &[Arc<Node>],
+        count: usize,
+    ) {
+        let mut min = usize::MAX;
+        let mut max = 0;
+        let mut has_rest_parameter = false;
+        let mut below = None;
+        let mut above = None;
+        for &signature in signatures {
+            let parameter_count = self.get_parameter_count(signature);
+            let min_argument_count = self.get_min_argument_count(signature);
+            min = min.min(min_argument_count);
+            if self.has_effective_rest_parameter(signature) {
+                has_rest_parameter = true;
+            } else {
+                max = max.max(parameter_count);
+            }
+            if parameter_count < count {
+                below = below.max(Some(parameter_count));
+            }
+            if min_argument_count > count {
+                above = Some(above.map_or(min_argument_count, |above: usize| {
+                    above.min(min_argument_count)
+                }));
+            }
+        }
+        if has_rest_parameter {
+            max = usize::MAX;
+        }
+        if min < count
+            && count < max
+            && let (Some(below), Some(above)) = (below, above)
+        {
+            self.error(
+                node,
+                diagnostics::NO_OVERLOAD_EXPECTS_0_ARGUMENTS_BUT_OVERLOADS_DO_EXIST_THAT_EXPECT_EITHER_1_OR_2_ARGUMENTS_2575,
+                &[count.to_string(), below.to_string(), above.to_string()],
+            );
+            return;
+        }
+        let (message, expected) = if has_rest_parameter {
+            (
+                diagnostics::EXPECTED_AT_LEAST_0_ARGUMENTS_BUT_GOT_1_2555,
+                min.to_string(),
+            )
+        } else if min < max {
+            (
+                diagnostics::EXPECTED_0_ARGUMENTS_BUT_GOT_1_2554,
+                format!("{min}-{max}"),
+            )
+        } else {
+            (
+                diagnostics::EXPECTED_0_ARGUMENTS_BUT_GOT_1_2554,
+                min.to_string(),
+            )
+        };
+        let args = [expected, count.to_string()];
+        if count < min {
+            // Too few arguments are reported on the function being called
+            let error_node = match callee.kind {
+                _ if node.kind == SyntaxKind::NewExpression => Arc::clone(node),
+                SyntaxKind::PropertyAccessExpression => {
+                    Arc::clone(&callee.as_property_access_expression().name)
+                }
+                _ => Arc::clone(callee),
+            };
+            self.error(&error_node, message, &args);
+        } else {
+            // Too many arguments are reported on the excess arguments
+            // A spread of a tuple can pass the excess arguments along with allowed ones
+            let first_excess = max.min(arguments.len() - 1);
+            let range = self.get_range_of_nodes(node, &arguments[first_excess..]);
+            self.error_at_range(node, range, message, &args);
+        }
+    }
+
+    /// Gets the range of a list of nodes, without leading trivia
+    fn get_range_of_nodes(&self, node: &Arc<Node>, nodes: &[Arc<Node>]) -> TextRange {
+        let file = get_source_file_of_node(node);
+        let (Some(first), Some(last)) = (nodes.first(), nodes.last()) else {
+            return get_error_range(&file, node);
+        };
+        let pos = get_error_range(&file, first).pos();
+        let end = last.end().max(pos + 1);
+        TextRange::new(pos, end)
+    }
+
+    fn check_tagged_template_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let tagged = node.as_tagged_template_expression();
+        let tag_type = self.check_expression(&tagged.tag);
+        if tagged.template.kind == SyntaxKind::TemplateExpression {
+            self.check_expression(&tagged.template);
+        }
+        if self.flags(tag_type).intersects(TypeFlags::ANY) {
+            return self.any_type;
+        }
+        let signatures = self.get_signatures_of_type(tag_type, false);
+        let Some(&signature) = signatures.first() else {
+            return self
+                .report_invocation_error(node, &tagged.tag, tag_type, false)
+                .map_or(self.any_type, |signature| {
+                    self.get_return_type_of_signature(signature)
+                });
+        };
+        self.node_links(node).resolved_signature = Some(signature);
+        self.get_return_type_of_signature(signature)
+    }
+
+    /// Removes `null` and `undefined` from a type
+    pub fn get_non_nullable_type(&mut self, type_: TypeId) -> TypeId {
+        if !self.flags(type_).contains(TypeFlags::UNION) {
+            return type_;
+        }
+        let TypeData::UnionOrIntersection { types } = self.get_type(type_).data.clone() else {
+            return type_;
+        };
+        let remaining: Vec<TypeId> = types
+            .into_iter()
+            .filter(|&type_| !self.flags(type_).intersects(TypeFlags::NULLABLE))
+            .collect();
+        self.get_union_type(&remaining)
+    }
+
+    /// Removes the types whose values are always falsy from a type
+    fn remove_definitely_falsy_types(&mut self, type_: TypeId) -> TypeId {
+        let types = match self.get_type(type_).data.clone() {
+            TypeData::UnionOrIntersection { types }
+                if self.flags(type_).contains(TypeFlags::UNION) =>
+            {
+                types
+            }
+            _ => vec![type_],
+        };
+        let remaining: Vec<TypeId> = types
+            .into_iter()
+            .filter(|&type_| !self.is_definitely_falsy_type(type_))
+            .collect();
+        if remaining.is_empty() {
+            return self.never_type;
+        }
+        self.get_union_type(&remaining)
+    }
+
+    pub(super) fn is_definitely_falsy_type(&self, type_: TypeId) -> bool {
+        let type_data = self.get_type(type_);
+        if type_data
+            .flags
+            .intersects(TypeFlags::VOID_LIKE | TypeFlags::NULL)
+        {
+            return true;
+        }
+        if type_ == self.false_type {
+            return true;
+        }
+        match &type_data.data {
+            TypeData::Literal { value } => match value {
+                LiteralValue::String(text) => text.is_empty(),
+                LiteralValue::Number(number) => number.0 == 0.0 || number.0.is_nan(),
+                LiteralValue::BigInt(digits) => digits == "0",
+            },
+            _ => false,
+        }
+    }
+
+    fn check_prefix_unary_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let unary = node.as_prefix_unary_expression();
+        let operand = &unary.operand;
+        match unary.operator {
+            SyntaxKind::MinusToken if operand.kind == SyntaxKind::NumericLiteral => {
+                let value = parse_numeric_literal(&operand.as_numeric_literal().text);
+                self.check_expression(operand);
+                let type_ = self.get_number_literal_type(-value);
+                return self.get_fresh_type_of_literal_type(type_);
+            }
+            SyntaxKind::MinusToken if operand.kind == SyntaxKind::BigIntLiteral => {
+                let digits = parse_big_int_literal(&operand.as_big_int_literal().text);
+                self.check_expression(operand);
+                let value = if digits == "0" {
+                    digits
+                } else {
+                    format!("-{digits}")
+                };
+                let type_ = self.get_literal_type(LiteralValue::BigInt(value));
+                return self.get_fresh_type_of_literal_type(type_);
+            }
+            _ => {}
+        }
+        let operand_type = self.check_expression(operand);
+        match unary.operator {
+            SyntaxKind::ExclamationToken => self.boolean_type,
+            SyntaxKind::PlusToken => self.number_type,
+            SyntaxKind::MinusToken | SyntaxKind::TildeToken => {
+                self.get_unary_result_type(operand_type)
+            }
+            SyntaxKind::PlusPlusToken | SyntaxKind::MinusMinusToken => {
+                self.check_increment_operand(operand, operand_type)
+            }
+            _ => self.error_type,
+        }
+    }
+
+    fn check_postfix_unary_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let unary = node.as_postfix_unary_expression();
+        let operand_type = self.check_expression(&unary.operand);
+        self.check_increment_operand(&unary.operand, operand_type)
+    }
+
+    fn check_increment_operand(&mut self, operand: &Arc<Node>, operand_type: TypeId) -> TypeId {
+        if !self.is_arithmetic_operand_type(operand_type) {
+            self.error(
+                operand,
+                diagnostics::AN_ARITHMETIC_OPERAND_MUST_BE_OF_TYPE_ANY_NUMBER_BIGINT_OR_AN_ENUM_TYPE_2356,
+                &[],
+            );
+            return self.number_type;
+        }
+        self.get_unary_result_type(operand_type)
+    }
+
+    fn get_unary_result_type(&mut self, operand_type: TypeId) -> TypeId {
+        if self.is_type_of_kind(operand_type, TypeFlags::BIG_INT_LIKE) {
+            self.bigint_type
+        } else {
+            self.number_type
+        }
+    }
+
+    /// Determines if every constituent of a type has a kind in `kind`, looking through the
+    /// constraints of type parameters
+    fn is_type_of_kind(&mut self, type_: TypeId, kind: TypeFlags) -> bool {
+        let flags = self.flags(type_);
+        if flags.intersects(kind) {
+            return true;
+        }
+        if flags.contains(TypeFlags::TYPE_PARAMETER) {
+            return self
+                .get_constraint_of_type_parameter(type_)
+                .is_some_and(|constraint| self.is_type_of_kind(constraint, kind));
+        }
+        if flags.intersects(TypeFlags::UNION) {
+            let TypeData::UnionOrIntersection { types } = self.get_type(type_).data.clone() else {
+                return false;
+            };
+            return types
+                .into_iter()
+                .all(|type_| self.is_type_of_kind(type_, kind));
+        }
+        false
+    }
+
+    /// Determines if some constituent of a type has a kind in `kind`
+    pub(super) fn maybe_type_of_kind(&mut self, type_: TypeId, kind: TypeFlags) -> bool {
+        if self.flags(type_).intersects(kind) {
+            return true;
+        }
+        match self.get_type(type_).data.clone() {
+            TypeData::UnionOrIntersection { types } => types
+                .into_iter()
+                .any(|type_| self.maybe_type_of_kind(type_, kind)),
+            TypeData::TypeParameter { .. } => self
+                .get_constraint_of_type_parameter(type_)
+                .is_some_and(|constraint| self.maybe_type_of_kind(constraint, kind)),
+            _ => false,
+        }
+    }
+
+    /// Determines if a type can be an operand of an arithmetic operator
+    fn is_arithmetic_operand_type(&mut self, type_: TypeId) -> bool {
+        self.is_type_of_kind(
+            type_,
+            TypeFlags::ANY | TypeFlags::NUMBER_LIKE | TypeFlags::BIG_INT_LIKE | TypeFlags::NEVER,
+        ) || (!self.options.strict_null_checks && self.is_type_of_kind(type_, TypeFlags::NULLABLE))
+    }
+
+    /// Checks a binary expression, checking the chain of binary expressions down its left
+    /// operand, as in `a + b + c`, innermost first with a stack of its own rather than recursing
+    /// once per operator, which would overflow the stack on long chains
+    fn check_binary_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let mut chain = Vec::new();
+        let mut left = Arc::clone(&node.as_binary_expression().left);
+        while left.kind == SyntaxKind::BinaryExpression
+            && self.node_links(&left).resolved_type.is_none()
+        {
+            let next = Arc::clone(&left.as_binary_expression().left);
+            chain.push(std::mem::replace(&mut left, next));
+        }
+        let mut left_type = self.check_expression(&left);
+        while let Some(operand) = chain.pop() {
+            left_type = self.check_expression_with(&operand, |checker, operand| {
+                checker.check_binary_operands(operand, left_type)
+            });
+        }
+        self.check_binary_operands(node, left_type)
+    }
+
+    /// Checks the right operand of a binary expression and the operator applied to the type of
+    /// its left operand
+    fn check_binary_operands(&mut self, node: &Arc<Node>, left_type: TypeId) -> TypeId {
+        let binary = node.as_binary_expression();
+        let operator = binary.operator_token.kind;
+        let right_type = self.check_expression(&binary.right);
+        match operator {
+            SyntaxKind::EqualsToken => {
+                // Destructuring assignments are checked element by element
+                if !matches!(
+                    binary.left.kind,
+                    SyntaxKind::ObjectLiteralExpression | SyntaxKind::ArrayLiteralExpression
+                ) {
+                    let head_message = if self.is_exact_optional_property_write(&binary.left)
+                        && self.maybe_type_of_kind(right_type, TypeFlags::UNDEFINED)
+                    {
+                        Some(diagnostics::TYPE_0_IS_NOT_ASSIGNABLE_TO_TYPE_1_WITH_EXACT_OPTIONAL_PROPERTY_TYPES_COLON_TRUE_CONSIDER_ADDING_UNDEFINED_TO_THE_TYPE_OF_THE_TARGET_2412)
+                    } else {
+                        None
+                    };
+                    self.check_type_assignable_to_and_optionally_elaborate(
+                        right_type,
+                        left_type,
+                        &binary.left,
+                        Some(&binary.right),
+                        head_message,
+                    );
+                }
+                right_type
+            }
+            SyntaxKind::CommaToken => right_type,
+            SyntaxKind::AmpersandAmpersandToken | SyntaxKind::AmpersandAmpersandEqualsToken => {
+                right_type
+            }
+            SyntaxKind::BarBarToken | SyntaxKind::BarBarEqualsToken => {
+                let left = self.remove_definitely_falsy_types(left_type);
+                self.get_union_type(&[left, right_type])
+            }
+            SyntaxKind::QuestionQuestionToken | SyntaxKind::QuestionQuestionEqualsToken => {
+                let left = self.get_non_nullable_type(left_type);
+                self.get_union_type(&[left, right_type])
+            }
+            SyntaxKind::PlusToken | SyntaxKind::PlusEqualsToken => {
+                self.check_addition(node, operator, left_type, right_type)
+            }
+            SyntaxKind::MinusToken
+            | SyntaxKind::MinusEqualsToken
+            | SyntaxKind::AsteriskToken
+            | SyntaxKind::AsteriskEqualsToken
+            | SyntaxKind::AsteriskAsteriskToken
+            | SyntaxKind::AsteriskAsteriskEqualsToken
+            | SyntaxKind::SlashToken
+            | SyntaxKind::SlashEqualsToken
+            | SyntaxKind::PercentToken
+            | SyntaxKind::PercentEqualsToken
+            | SyntaxKind::LessThanLessThanToken
+            | SyntaxKind::LessThanLessThanEqualsToken
+            | SyntaxKind::GreaterThanGreaterThanToken
+            | SyntaxKind::GreaterThanGreaterThanEqualsToken
+            | SyntaxKind::GreaterThanGreaterThanGreaterThanToken
+            | SyntaxKind::GreaterThanGreaterThanGreaterThanEqualsToken
+            | SyntaxKind::AmpersandToken
+            | SyntaxKind::AmpersandEqualsToken
+            | SyntaxKind::BarToken
+            | SyntaxKind::BarEqualsToken
+            | SyntaxKind::CaretToken
+            | SyntaxKind::CaretEqualsToken => {
+                self.check_arithmetic(node, operator, left_type, right_type)
+            }
+            _ => self.boolean_type,
+        }
+    }
+
+    fn check_addition(
+        &mut self,
+        node: &Arc<Node>,
+        operator: SyntaxKind,
+        left_type: TypeId,
+        right_type: TypeId,
+    ) -> TypeId {
+        if left_type == self.error_type || right_type == self.error_type {
+            return self.error_type;
+        }
+        let strict_kinds = TypeFlags::ANY_OR_UNKNOWN | TypeFlags::VOID_LIKE | TypeFlags::NULL;
+        let left_is_number = self.is_type_of_kind(left_type, TypeFlags::NUMBER_LIKE)
+            && !self.maybe_type_of_kind(left_type, strict_kinds);
+        let right_is_number = self.is_type_of_kind(right_type, TypeFlags::NUMBER_LIKE)
+            && !self.maybe_type_of_kind(right_type, strict_kinds);
+        if left_is_number && right_is_number {
+            return self.number_type;
+        }
+        if self.is_type_of_kind(left_type, TypeFlags::BIG_INT_LIKE)
+            && self.is_type_of_kind(right_type, TypeFlags::BIG_INT_LIKE)
+        {
+            return self.bigint_type;
+        }
+        if self.is_type_of_kind(left_type, TypeFlags::STRING_LIKE)
+            || self.is_type_of_kind(right_type, TypeFlags::STRING_LIKE)
+        {
+            return self.string_type;
+        }
+        if self.flags(left_type).intersects(TypeFlags::ANY)
+            || self.flags(right_type).intersects(TypeFlags::ANY)
+        {
+            return self.any_type;
+        }
+        self.report_operator_error(node, operator, left_type, right_type);
+        self.any_type
+    }
+
+    fn check_arithmetic(
+        &mut self,
+        node: &Arc<Node>,
+        operator: SyntaxKind,
+        left_type: TypeId,
+        right_type: TypeId,
+    ) -> TypeId {
+        let binary = node.as_binary_expression();
+        if let Some(suggestion) = get_suggested_boolean_operator(operator)
+            && self.is_type_of_kind(left_type, TypeFlags::BOOLEAN_LIKE)
+            && self.is_type_of_kind(right_type, TypeFlags::BOOLEAN_LIKE)
+        {
+            self.error(
+                &binary.operator_token,
+                diagnostics::THE_0_OPERATOR_IS_NOT_ALLOWED_FOR_BOOLEAN_TYPES_CONSIDER_USING_1_INSTEAD_2447,
+                &[token_to_string(operator).to_string(), suggestion.to_string()],
+            );
+            return self.number_type;
+        }
+        let left_ok = self.is_arithmetic_operand_type(left_type);
+        let right_ok = self.is_arithmetic_operand_type(right_type);
+        if !left_ok {
+            self.error(
+                &binary.left,
+                diagnostics::THE_LEFT_HAND_SIDE_OF_AN_ARITHMETIC_OPERATION_MUST_BE_OF_TYPE_ANY_NUMBER_BIGINT_OR_AN_ENUM_TYPE_2362,
+                &[],
+            );
+        }
+        if !right_ok {
+            self.error(
+                &binary.right,
+                diagnostics::THE_RIGHT_HAND_SIDE_OF_AN_ARITHMETIC_OPERATION_MUST_BE_OF_TYPE_ANY_NUMBER_BIGINT_OR_AN_ENUM_TYPE_2363,
+                &[],
+            );
+        }
+        if !left_ok || !right_ok {
+            return self.number_type;
+        }
+        let both_any = self.is_type_of_kind(left_type, TypeFlags::ANY_OR_UNKNOWN)
+            && self.is_type_of_kind(right_type, TypeFlags::ANY_OR_UNKNOWN);
+        let maybe_big_int = self.maybe_type_of_kind(left_type, TypeFlags::BIG_INT_LIKE)
+            || self.maybe_type_of_kind(right_type, TypeFlags::BIG_INT_LIKE);
+        if both_any || !maybe_big_int {
+            return self.number_type;
+        }
+        let big_int_or_any = TypeFlags::BIG_INT_LIKE | TypeFlags::ANY;
+        if self.is_type_of_kind(left_type, big_int_or_any)
+            && self.is_type_of_kind(right_type, big_int_or_any)
+        {
+            return self.bigint_type;
+        }
+        self.report_operator_error(node, operator, left_type, right_type);
+        self.error_type
+    }
+
+    /// Reports that a binary operator cannot be applied to the types of its operands
+    fn report_operator_error(
+        &mut self,
+        node: &Arc<Node>,
+        operator: SyntaxKind,
+        left_type: TypeId,
+        right_type: TypeId,
+    ) {
+        let left = self.get_base_type_of_literal_type(left_type);
+        let right = self.get_base_type_of_literal_type(right_type);
+        let left_name = self.type_to_string(left);
+        let right_name = self.type_to_string(right);
+        self.error(
+            node,
+            diagnostics::OPERATOR_0_CANNOT_BE_APPLIED_TO_TYPES_1_AND_2_2365,
+            &[token_to_string(operator).to_string(), left_name, right_name],
+        );
+    }
+}
+
+/// Converts the text of a bigint literal to its decimal digits
+fn parse_big_int_literal(text: &str) -> String {
+    let text = text.trim_end_matches('n').replace('_', "");
+    let (digits, radix) = match text.get(..2) {
+        Some("0x" | "0X") => (&text[2..], 16),
+        Some("0b" | "0B") => (&text[2..], 2),
+        Some("0o" | "0O") => (&text[2..], 8),
+        _ => (text.as_str(), 10),
+    };
+    match u128::from_str_radix(digits, radix) {
+        Ok(value) => value.to_string(),
+        Err(_) => digits.trim_start_matches('0').to_string(),
+    }
+}
+
+fn is_in_function(node: &Node) -> bool {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        match parent.kind {
+            SyntaxKind::FunctionDeclaration
+            | SyntaxKind::FunctionExpression
+            | SyntaxKind::MethodDeclaration
+            | SyntaxKind::Constructor
+            | SyntaxKind::GetAccessor
+            | SyntaxKind::SetAccessor => return true,
+            _ => current = parent.parent(),
+        }
+    }
+    false
+}
+
+/// Gets the object or function of a property access, element access, or call, which is the
+/// previous link of the chain it ends
+fn get_chain_expression(node: &Node) -> Option<Arc<Node>> {
+    match node.kind {
+        SyntaxKind::PropertyAccessExpression => {
+            Some(Arc::clone(&node.as_property_access_expression().expression))
+        }
+        SyntaxKind::ElementAccessExpression => {
+            Some(Arc::clone(&node.as_element_access_expression().expression))
+        }
+        SyntaxKind::CallExpression => Some(Arc::clone(&node.as_call_expression().expression)),
+        _ => None,
+    }
+}
+
+fn has_question_dot_token(node: &Node) -> bool {
+    match node.kind {
+        SyntaxKind::PropertyAccessExpression => node
+            .as_property_access_expression()
+            .question_dot_token
+            .is_some(),
+        SyntaxKind::ElementAccessExpression => node
+            .as_element_access_expression()
+            .question_dot_token
+            .is_some(),
+        SyntaxKind::CallExpression => node.as_call_expression().question_dot_token.is_some(),
+        _ => false,
+    }
+}
+
+/// Determines if a node is the object or function of a further link of a chain, so it does
+/// not end the chain
+fn is_inner_chain_link(node: &Arc<Node>) -> bool {
+    node.parent().is_some_and(|parent| {
+        get_chain_expression(&parent).is_some_and(|expression| Arc::ptr_eq(&expression, node))
+    })
+}
+
+/// Gets the function, module block, or source file whose control flow a node is part of
+pub(super) fn get_control_flow_container(node: &Node) -> Option<Arc<Node>> {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if is_function_like_declaration_kind(parent.kind)
+            || matches!(
+                parent.kind,
+                SyntaxKind::ModuleBlock
+                    | SyntaxKind::ClassStaticBlockDeclaration
+                    | SyntaxKind::PropertyDeclaration
+                    | SyntaxKind::SourceFile
+            )
+        {
+            return Some(parent);
+        }
+        current = parent.parent();
+    }
+    None
+}
+
+/// Determines if an identifier is in a `typeof` type query
+fn is_in_type_query(node: &Node) -> bool {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        match parent.kind {
+            SyntaxKind::TypeQuery => return true,
+            SyntaxKind::Identifier | SyntaxKind::QualifiedName => current = parent.parent(),
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Determines if a reference is in an interface, type alias, or type literal, like the name
+/// of a computed member, which describes a type rather than running
+fn is_in_type_declaration(node: &Arc<Node>) -> bool {
+    find_ancestor(node, |ancestor| {
+        matches!(
+            ancestor.kind,
+            SyntaxKind::InterfaceDeclaration
+                | SyntaxKind::TypeAliasDeclaration
+                | SyntaxKind::TypeLiteral
+        )
+    })
+    .is_some()
+}
+
+/// Gets the declaration whose `this` a `this` expression refers to: a class member, a
+/// function, or an object literal for its methods
+fn get_this_container(node: &Node) -> Option<Arc<Node>> {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        match parent.kind {
+            SyntaxKind::MethodDeclaration | SyntaxKind::GetAccessor | SyntaxKind::SetAccessor
+                if parent.parent().is_some_and(|grandparent| {
+                    grandparent.kind == SyntaxKind::ObjectLiteralExpression
+                }) =>
+            {
+                return parent.parent();
+            }
+            SyntaxKind::FunctionDeclaration
+            | SyntaxKind::FunctionExpression
+            | SyntaxKind::MethodDeclaration
+            | SyntaxKind::Constructor
+            | SyntaxKind::GetAccessor
+            | SyntaxKind::SetAccessor
+            | SyntaxKind::PropertyDeclaration
+            | SyntaxKind::ClassStaticBlockDeclaration
+            | SyntaxKind::ModuleDeclaration
+            | SyntaxKind::SourceFile => return Some(parent),
+            _ => current = parent.parent(),
+        }
+    }
+    None
+}
+
+/// Gets the class a member, or a parameter property, is declared in
+fn get_declaring_class(declaration: &Node) -> Option<Arc<Node>> {
+    let parent = declaration.parent()?;
+    let class = if declaration.kind == SyntaxKind::Parameter {
+        parent.parent()?
+    } else {
+        parent
+    };
+    is_class_like(&class).then_some(class)
+}
+
+/// Gets the classes whose bodies contain a node, innermost first
+fn get_enclosing_classes(node: &Node) -> Vec<Arc<Node>> {
+    let mut classes = Vec::new();
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if is_class_like(&parent) {
+            classes.push(Arc::clone(&parent));
+        }
+        current = parent.parent();
+    }
+    classes
+}
+
+/// Gets the class member a `super` expression is in
+fn get_super_container(node: &Node) -> Option<Arc<Node>> {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        match parent.kind {
+            SyntaxKind::MethodDeclaration
+            | SyntaxKind::Constructor
+            | SyntaxKind::GetAccessor
+            | SyntaxKind::SetAccessor
+            | SyntaxKind::PropertyDeclaration
+            | SyntaxKind::ClassStaticBlockDeclaration => return Some(parent),
+            SyntaxKind::FunctionDeclaration
+            | SyntaxKind::FunctionExpression
+            | SyntaxKind::SourceFile => return None,
+            _ => current = parent.parent(),
+        }
+    }
+    None
+}
+
+fn get_class_extends_expression(class: &Node) -> Option<Arc<Node>> {
+    let clauses = match class.kind {
+        SyntaxKind::ClassDeclaration => class.as_class_declaration().heritage_clauses.as_ref(),
+        _ => class.as_class_expression().heritage_clauses.as_ref(),
+    }?;
+    let clause = clauses
+        .iter()
+        .find(|clause| clause.as_heritage_clause().token == SyntaxKind::ExtendsKeyword)?;
+    let heritage_type = clause.as_heritage_clause().types.iter().next()?;
+    Some(Arc::clone(
+        &heritage_type.as_expression_with_type_arguments().expression,
+    ))
+}
+
+/// Gets the logical operator to suggest instead of a bitwise operator on booleans
+fn get_suggested_boolean_operator(operator: SyntaxKind) -> Option<&'static str> {
+    match operator {
+        SyntaxKind::BarToken | SyntaxKind::BarEqualsToken => Some("||"),
+        SyntaxKind::CaretToken | SyntaxKind::CaretEqualsToken => Some("!=="),
+        SyntaxKind::AmpersandToken | SyntaxKind::AmpersandEqualsToken => Some("&&"),
+        _ => None,
+    }
+}
+
+fn token_to_string(kind: SyntaxKind) -> &'static str {
+    crate::compiler::scanner::token_to_string(kind).unwrap_or("")
+}
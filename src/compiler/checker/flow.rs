@@ -0,0 +1,1530 @@
+//! Narrowing the types of references by control flow
+//!
+//! The binder records the flow node control is at when it reaches each reference to a
+//! variable, `this`, or a property. The type of a reference where it is used is found by
+//! walking the control flow graph backwards from there to the last assignment to the
+//! reference, then narrowing the assigned type by the conditions on the way, such as
+//! `typeof x === "string"`, `x != null`, `x instanceof C`, `"p" in x`, or a comparison of a
+//! discriminant property. Where branches join, the types along each branch are unioned, and
+//! around loops the types flowing through the back edges are recomputed until they settle.
+//! The type of an array declared empty evolves the same way with the elements added to it.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::nodes::PropertyAccessExpression;
+use crate::compiler::ast::utilities::{
+    is_const_variable, is_string_literal_like, number_to_string, parse_numeric_literal,
+    skip_parentheses,
+};
+use crate::compiler::ast::{Node, NodeFactory, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::binder::{FlowId, FlowNode};
+
+use super::evolving_arrays::{get_reference_candidate, is_empty_array_literal};
+use super::expressions::TYPEOF_RESULTS;
+use super::{Checker, LiteralValue, TypeData, TypeFlags, TypeId};
+
+/// How many times the types flowing around a loop are recomputed before they are assumed not
+/// to settle
+const MAX_LOOP_ITERATIONS: usize = 10;
+
+/// A reference whose type is being narrowed
+struct FlowReference {
+    node: Arc<Node>,
+    /// Identifies the reference and its declared type in the caches of flow types
+    key: String,
+    declared_type: TypeId,
+    /// The type of the reference where control enters its container, before any assignment
+    initial_type: TypeId,
+}
+
+/// A loop whose type for a reference is being computed
+#[derive(Debug, Clone)]
+pub(super) struct FlowLoop {
+    flow: FlowId,
+    key: String,
+    /// The type found so far, which back edges to the loop see
+    type_: TypeId,
+}
+
+impl Checker {
+    /// Gets the type of a reference where it is used, narrowing its declared type by the
+    /// assignments and conditions control passes through to get there
+    ///
+    /// Paths that reach the start of the reference's container without assigning it give it
+    /// `initial_type`, which includes `undefined` for variables that are not yet assigned.
+    pub(super) fn get_flow_type_of_reference(
+        &mut self,
+        reference: &Arc<Node>,
+        declared_type: TypeId,
+        initial_type: TypeId,
+    ) -> TypeId {
+        let Some(flow) = self.bindings.flow_of_node(reference) else {
+            return declared_type;
+        };
+        if !self.flags(declared_type).intersects(TypeFlags::NARROWABLE) {
+            return declared_type;
+        }
+        if let Some((declared, initial, flow_type)) = self.node_links(reference).flow_type
+            && (declared, initial) == (declared_type, initial_type)
+        {
+            return flow_type;
+        }
+        let Some(key) = self.get_flow_cache_key(reference) else {
+            return declared_type;
+        };
+        let reference = FlowReference {
+            node: Arc::clone(reference),
+            key: format!("{key}@{}@{}", declared_type.0, initial_type.0),
+            declared_type,
+            initial_type,
+        };
+        let type_ = self.get_type_at_flow_node(&reference, flow);
+        // An operation that adds to an evolving array sees it as the `any[]` it evolves from
+        let flow_type = if self.is_evolving_array_type(type_)
+            && self.is_evolving_array_operation_target(&reference.node)
+        {
+            self.get_auto_array_type()
+        } else {
+            self.finalize_evolving_array_type(type_)
+        };
+        // Types found while the type of a reference around a loop is pending may change
+        if self.flow_loops.is_empty() {
+            self.node_links(&reference.node).flow_type =
+                Some((declared_type, initial_type, flow_type));
+        }
+        flow_type
+    }
+
+    /// Determines if every path through a constructor assigns the property named `name` of
+    /// `this` before the constructor returns
+    pub(super) fn is_property_initialized_in_constructor(
+        &mut self,
+        name: &Arc<Node>,
+        type_: TypeId,
+        constructor: &Arc<Node>,
+    ) -> bool {
+        let Some(flow) = self.bindings.return_flow_of_node(constructor) else {
+            return false;
+        };
+        let key = format!("this.{}", name.text());
+        let factory = NodeFactory::new();
+        let name = match name.kind {
+            SyntaxKind::PrivateIdentifier => factory.create_private_identifier(name.text()),
+            _ => factory.create_identifier(name.text()),
+        };
+        let node = factory.create(
+            SyntaxKind::PropertyAccessExpression,
+            PropertyAccessExpression {
+                expression: factory.create_token(SyntaxKind::ThisKeyword),
+                question_dot_token: None,
+                name,
+            },
+        );
+        // The property starts out undefined, and is initialized if no path leaves it that way
+        let declared_type = self.get_union_type(&[type_, self.undefined_type]);
+        let reference = FlowReference {
+            node,
+            key: format!("{key}@{}", declared_type.0),
+            declared_type,
+            initial_type: declared_type,
+        };
+        let flow_type = self.get_type_at_flow_node(&reference, flow);
+        !self.maybe_type_of_kind(flow_type, TypeFlags::UNDEFINED)
+    }
+
+    /// Gets a key that identifies what a reference refers to, or None if it cannot be narrowed
+    fn get_flow_cache_key(&mut self, node: &Arc<Node>) -> Option<String> {
+        match node.kind {
+            SyntaxKind::Identifier => self
+                .get_resolved_symbol(node)
+                .map(|symbol| symbol.value().to_string()),
+            SyntaxKind::ThisKeyword => Some("this".to_string()),
+            SyntaxKind::SuperKeyword => Some("super".to_string()),
+            SyntaxKind::ParenthesizedExpression => {
+                self.get_flow_cache_key(&node.as_parenthesized_expression().expression)
+            }
+            SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression => {
+                let name = get_accessed_property_name(node)?;
+                let key = self.get_flow_cache_key(&get_accessed_expression(node)?)?;
+                Some(format!("{key}.{name}"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the value symbol an identifier refers to, resolving it if it has not been checked
+    fn get_resolved_symbol(&mut self, node: &Arc<Node>) -> Option<SymbolId> {
+        if let Some(symbol) = self.node_links(node).resolved_symbol {
+            return Some(symbol);
+        }
+        let symbol = self.resolve_name(node, node.text(), SymbolFlags::VALUE)?;
+        let symbol = self.get_merged_symbol(symbol);
+        self.node_links(node).resolved_symbol = Some(symbol);
+        Some(symbol)
+    }
+
+    fn get_type_at_flow_node(&mut self, reference: &FlowReference, flow: FlowId) -> TypeId {
+        // The conditions and switch clauses passed through, latest first
+        let mut narrowings = Vec::new();
+        let mut flow = flow;
+        let mut type_ = loop {
+            match self.bindings.flow_node(flow).clone() {
+                FlowNode::Assignment { node, antecedent } => {
+                    if self.is_matching_reference(&reference.node, &node) {
+                        break self.get_assigned_type(reference, &node);
+                    }
+                    // Assigning to `o` forgets what was known about `o.p`
+                    if self.contains_matching_reference(&reference.node, &node) {
+                        break reference.declared_type;
+                    }
+                    flow = antecedent;
+                }
+                FlowNode::ArrayMutation { node, antecedent } => {
+                    if let Some(type_) =
+                        self.get_type_at_flow_array_mutation(reference, &node, antecedent)
+                    {
+                        break type_;
+                    }
+                    flow = antecedent;
+                }
+                FlowNode::Condition { antecedent, .. }
+                | FlowNode::SwitchClause { antecedent, .. } => {
+                    narrowings.push(flow);
+                    flow = antecedent;
+                }
+                FlowNode::BranchLabel { antecedents } => {
+                    break self.get_type_at_flow_branch_label(reference, flow, &antecedents);
+                }
+                FlowNode::LoopLabel { antecedents } => {
+                    break self.get_type_at_flow_loop_label(reference, flow, &antecedents);
+                }
+                // Constants keep their narrowed types inside functions created after the
+                // narrowing
+                FlowNode::Start {
+                    container_flow: Some(container_flow),
+                } if self.is_constant_reference(&reference.node) => flow = container_flow,
+                FlowNode::Start { .. } => break reference.initial_type,
+                FlowNode::Unreachable => break reference.declared_type,
+            }
+        };
+        for flow in narrowings.into_iter().rev() {
+            // An evolving array is narrowed as the array it has evolved into, and keeps
+            // evolving unless the narrowing changes it
+            let evolving_type = type_;
+            if self.is_evolving_array_type(type_) {
+                type_ = self.finalize_evolving_array_type(type_);
+            }
+            let unnarrowed_type = type_;
+            type_ = match self.bindings.flow_node(flow).clone() {
+                FlowNode::Condition {
+                    expression,
+                    assume_true,
+                    ..
+                } => self.narrow_type(reference, type_, &expression, assume_true),
+                FlowNode::SwitchClause {
+                    switch_statement,
+                    clause_start,
+                    clause_end,
+                    ..
+                } => self.narrow_type_by_switch_clause(
+                    reference,
+                    type_,
+                    &switch_statement,
+                    clause_start,
+                    clause_end,
+                ),
+                _ => type_,
+            };
+            if type_ == unnarrowed_type {
+                type_ = evolving_type;
+            }
+        }
+        type_
+    }
+
+    /// Gets the type of an evolving array after elements are pushed or assigned to it, or
+    /// None if the mutation is not of the reference
+    fn get_type_at_flow_array_mutation(
+        &mut self,
+        reference: &FlowReference,
+        node: &Arc<Node>,
+        antecedent: FlowId,
+    ) -> Option<TypeId> {
+        if !self.is_auto_array_type(reference.declared_type) {
+            return None;
+        }
+        let array = match node.kind {
+            SyntaxKind::CallExpression => {
+                let callee = &node.as_call_expression().expression;
+                Arc::clone(&callee.as_property_access_expression().expression)
+            }
+            _ => {
+                let left = &node.as_binary_expression().left;
+                Arc::clone(&left.as_element_access_expression().expression)
+            }
+        };
+        if !self.is_matching_reference(&reference.node, &get_reference_candidate(&array)) {
+            return None;
+        }
+        let type_ = self.get_type_at_flow_node(reference, antecedent);
+        if !self.is_evolving_array_type(type_) {
+            return Some(type_);
+        }
+        let mut evolved_type = type_;
+        if node.kind == SyntaxKind::CallExpression {
+            for argument in &node.as_call_expression().arguments.nodes {
+                evolved_type = self.add_evolving_array_element_type(evolved_type, argument);
+            }
+        } else {
+            // Only elements at number indexes are elements of the array
+            let binary = node.as_binary_expression();
+            let index = &binary
+                .left
+                .as_element_access_expression()
+                .argument_expression;
+            let index_type = self.check_expression(index);
+            if self.is_type_assignable_to(index_type, self.number_type) {
+                evolved_type = self.add_evolving_array_element_type(evolved_type, &binary.right);
+            }
+        }
+        Some(evolved_type)
+    }
+
+    fn get_type_at_flow_branch_label(
+        &mut self,
+        reference: &FlowReference,
+        flow: FlowId,
+        antecedents: &[FlowId],
+    ) -> TypeId {
+        if let Some(type_) = self.get_cached_flow_type(flow, &reference.key) {
+            return type_;
+        }
+        let types: Vec<TypeId> = antecedents
+            .iter()
+            .map(|&antecedent| self.get_type_at_flow_node(reference, antecedent))
+            .collect();
+        let type_ = self.get_union_of_flow_types(reference, &types);
+        self.cache_flow_type(flow, &reference.key, type_);
+        type_
+    }
+
+    /// Unions the types of a reference along the branches into a label, keeping the declared
+    /// type, and the alias it may print by, when no branch narrowed it
+    fn get_union_of_flow_types(&mut self, reference: &FlowReference, types: &[TypeId]) -> TypeId {
+        // Evolving arrays joined only with unreachable branches keep evolving together
+        let reachable: Vec<TypeId> = types
+            .iter()
+            .copied()
+            .filter(|&type_| !self.flags(type_).contains(TypeFlags::NEVER))
+            .collect();
+        if !reachable.is_empty()
+            && reachable
+                .iter()
+                .all(|&type_| self.is_evolving_array_type(type_))
+        {
+            let element_types: Vec<TypeId> = reachable
+                .into_iter()
+                .map(|type_| self.get_element_type_of_evolving_array_type(type_))
+                .collect();
+            let element_type = self.get_union_type(&element_types);
+            return self.get_evolving_array_type(element_type);
+        }
+        let types: Vec<TypeId> = types
+            .iter()
+            .map(|&type_| self.finalize_evolving_array_type(type_))
+            .collect();
+        let type_ = self.get_union_type(&types);
+        if type_ != reference.declared_type
+            && self.get_constituents(type_) == self.get_constituents(reference.declared_type)
+        {
+            return reference.declared_type;
+        }
+        type_
+    }
+
+    /// Gets the type of a reference at the top of a loop
+    ///
+    /// The types coming around the back edges depend on the type at the top, so they are
+    /// recomputed from the type found so far until the union of the types settles.
+    fn get_type_at_flow_loop_label(
+        &mut self,
+        reference: &FlowReference,
+        flow: FlowId,
+        antecedents: &[FlowId],
+    ) -> TypeId {
+        if let Some(type_) = self.get_cached_flow_type(flow, &reference.key) {
+            return type_;
+        }
+        if let Some(pending) = self
+            .flow_loops
+            .iter()
+            .rev()
+            .find(|pending| pending.flow == flow && pending.key == reference.key)
+        {
+            return pending.type_;
+        }
+        let index = self.flow_loops.len();
+        self.flow_loops.push(FlowLoop {
+            flow,
+            key: reference.key.clone(),
+            type_: self.never_type,
+        });
+        let mut type_ = self.never_type;
+        let mut settled = false;
+        for _ in 0..MAX_LOOP_ITERATIONS {
+            let mut types = vec![type_];
+            for &antecedent in antecedents {
+                types.push(self.get_type_at_flow_node(reference, antecedent));
+            }
+            let next = self.get_union_of_flow_types(reference, &types);
+            if next == type_ {
+                settled = true;
+                break;
+            }
+            type_ = next;
+            self.flow_loops[index].type_ = next;
+            self.flow_loop_types.clear();
+        }
+        self.flow_loops.pop();
+        self.flow_loop_types.clear();
+        if !settled {
+            type_ = reference.declared_type;
+        }
+        self.cache_flow_type(flow, &reference.key, type_);
+        type_
+    }
+
+    /// Gets the type of a reference at a label computed before, if it is still valid
+    ///
+    /// Types computed while the type at the top of an enclosing loop is still being found are
+    /// only valid until that type changes.
+    fn get_cached_flow_type(&self, flow: FlowId, key: &str) -> Option<TypeId> {
+        let key = (flow, key.to_string());
+        if self.flow_loops.is_empty() {
+            self.flow_types.get(&key).copied()
+        } else {
+            self.flow_loop_types.get(&key).copied()
+        }
+    }
+
+    fn cache_flow_type(&mut self, flow: FlowId, key: &str, type_: TypeId) {
+        let key = (flow, key.to_string());
+        if self.flow_loops.is_empty() {
+            self.flow_types.insert(key, type_);
+        } else {
+            self.flow_loop_types.insert(key, type_);
+        }
+    }
+
+    /// Gets the type an assignment or initialized declaration gives a reference, which is the
+    /// declared type reduced to the constituents the assigned value could be
+    fn get_assigned_type(&mut self, reference: &FlowReference, node: &Arc<Node>) -> TypeId {
+        let declared_type = reference.declared_type;
+        let is_auto_array = self.is_auto_array_type(declared_type);
+        let assigned_value = match node.kind {
+            SyntaxKind::VariableDeclaration if node.type_node().is_some() || is_auto_array => {
+                node.initializer().cloned()
+            }
+            SyntaxKind::VariableDeclaration | SyntaxKind::BindingElement => None,
+            _ => {
+                let mut target = Arc::clone(node);
+                while let Some(parent) = target
+                    .parent()
+                    .filter(|parent| parent.kind == SyntaxKind::ParenthesizedExpression)
+                {
+                    target = parent;
+                }
+                target
+                    .parent()
+                    .filter(|parent| {
+                        parent.kind == SyntaxKind::BinaryExpression
+                            && parent.as_binary_expression().operator_token.kind
+                                == SyntaxKind::EqualsToken
+                            && Arc::ptr_eq(&parent.as_binary_expression().left, &target)
+                    })
+                    .map(|parent| Arc::clone(&parent.as_binary_expression().right))
+            }
+        };
+        let Some(value) = assigned_value else {
+            return declared_type;
+        };
+        // An array declared empty evolves again from each empty array assigned to it
+        if is_auto_array {
+            if is_empty_array_literal(&value) {
+                return self.get_evolving_array_type(self.never_type);
+            }
+            let assigned_type = self.check_expression(&value);
+            let assigned_type = self.get_widened_literal_type(assigned_type);
+            return if self.is_type_assignable_to(assigned_type, declared_type) {
+                assigned_type
+            } else {
+                self.create_array_type(self.any_type)
+            };
+        }
+        let assigned_type = self.check_expression(&value);
+        self.get_assignment_reduced_type(declared_type, assigned_type)
+    }
+
+    fn get_assignment_reduced_type(
+        &mut self,
+        declared_type: TypeId,
+        assigned_type: TypeId,
+    ) -> TypeId {
+        if declared_type == assigned_type {
+            return declared_type;
+        }
+        let reduced = self.filter_type(declared_type, |checker, type_| {
+            checker.type_maybe_assignable_to(assigned_type, type_)
+        });
+        if self.is_type_assignable_to(assigned_type, reduced) {
+            reduced
+        } else {
+            declared_type
+        }
+    }
+
+    fn type_maybe_assignable_to(&mut self, source: TypeId, target: TypeId) -> bool {
+        self.some_type(source, |checker, source| {
+            checker.is_type_assignable_to(source, target)
+        })
+    }
+
+    /// Determines if `source` and `target` refer to the same variable, `this`, or property;
+    /// `target` may also be the declaration of a variable
+    fn is_matching_reference(&mut self, source: &Arc<Node>, target: &Arc<Node>) -> bool {
+        let source = skip_parentheses(source);
+        let target = skip_parentheses(target);
+        if matches!(
+            target.kind,
+            SyntaxKind::VariableDeclaration | SyntaxKind::BindingElement
+        ) {
+            if source.kind != SyntaxKind::Identifier {
+                return false;
+            }
+            let Some(symbol) = self.get_resolved_symbol(&source) else {
+                return false;
+            };
+            return self.get_symbol_of_declaration(&target) == Some(symbol)
+                || self.bindings.local_symbol_of_node(&target) == Some(symbol);
+        }
+        match source.kind {
+            SyntaxKind::Identifier => {
+                target.kind == SyntaxKind::Identifier
+                    && source.text() == target.text()
+                    && self.get_resolved_symbol(&source) == self.get_resolved_symbol(&target)
+            }
+            SyntaxKind::ThisKeyword | SyntaxKind::SuperKeyword => target.kind == source.kind,
+            SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression => {
+                let (Some(source_name), Some(target_name)) = (
+                    get_accessed_property_name(&source),
+                    get_accessed_property_name(&target),
+                ) else {
+                    return false;
+                };
+                let (Some(source_object), Some(target_object)) = (
+                    get_accessed_expression(&source),
+                    get_accessed_expression(&target),
+                ) else {
+                    return false;
+                };
+                source_name == target_name
+                    && self.is_matching_reference(&source_object, &target_object)
+            }
+            _ => false,
+        }
+    }
+
+    /// Determines if `target` is an object whose property `source` accesses, at any depth
+    fn contains_matching_reference(&mut self, source: &Arc<Node>, target: &Arc<Node>) -> bool {
+        let mut source = skip_parentheses(source);
+        while let Some(object) = get_accessed_expression(&source) {
+            if self.is_matching_reference(&object, target) {
+                return true;
+            }
+            source = skip_parentheses(&object);
+        }
+        false
+    }
+
+    /// Determines if a reference is to a constant, whose value cannot change after it is
+    /// narrowed
+    fn is_constant_reference(&mut self, node: &Arc<Node>) -> bool {
+        if node.kind != SyntaxKind::Identifier {
+            return false;
+        }
+        let Some(symbol) = self.get_resolved_symbol(node) else {
+            return false;
+        };
+        let symbol = self.symbol(symbol);
+        symbol.flags.contains(SymbolFlags::BLOCK_SCOPED_VARIABLE)
+            && symbol
+                .value_declaration
+                .as_ref()
+                .is_some_and(is_const_variable)
+    }
+
+    /// Determines if `expression` accesses a discriminant property of the reference, whose
+    /// value tells the constituents of the union type of the reference apart
+    fn is_matching_reference_discriminant(
+        &mut self,
+        reference: &FlowReference,
+        expression: &Arc<Node>,
+        type_: TypeId,
+    ) -> bool {
+        if !self.flags(type_).contains(TypeFlags::UNION) {
+            return false;
+        }
+        let expression = skip_parentheses(expression);
+        let (Some(name), Some(object)) = (
+            get_accessed_property_name(&expression),
+            get_accessed_expression(&expression),
+        ) else {
+            return false;
+        };
+        self.is_matching_reference(&reference.node, &object)
+            && self.is_discriminant_property(type_, &name)
+    }
+
+    /// Narrows a union type to the constituents whose discriminant property `access` can have
+    /// the values `narrow` narrows the type of the property to
+    fn narrow_type_by_discriminant(
+        &mut self,
+        type_: TypeId,
+        access: &Arc<Node>,
+        narrow: impl Fn(&mut Checker, TypeId) -> TypeId,
+    ) -> TypeId {
+        let Some(name) = get_accessed_property_name(&skip_parentheses(access)) else {
+            return type_;
+        };
+        let Some(property_type) = self.get_type_of_property_of_type(type_, &name) else {
+            return type_;
+        };
+        let narrowed_property_type = narrow(self, property_type);
+        if self
+            .flags(narrowed_property_type)
+            .contains(TypeFlags::NEVER)
+        {
+            return self.never_type;
+        }
+        self.filter_type(type_, |checker, constituent| {
+            let discriminant_type = checker
+                .get_type_of_property_of_type(constituent, &name)
+                .unwrap_or(checker.unknown_type);
+            !checker.flags(discriminant_type).contains(TypeFlags::NEVER)
+                && checker.are_types_comparable(narrowed_property_type, discriminant_type)
+        })
+    }
+
+    /// Narrows a type by a condition known to be true or false
+    fn narrow_type(
+        &mut self,
+        reference: &FlowReference,
+        type_: TypeId,
+        expression: &Arc<Node>,
+        assume_true: bool,
+    ) -> TypeId {
+        match expression.kind {
+            SyntaxKind::Identifier
+            | SyntaxKind::ThisKeyword
+            | SyntaxKind::PropertyAccessExpression
+            | SyntaxKind::ElementAccessExpression => {
+                self.narrow_type_by_truthiness(reference, type_, expression, assume_true)
+            }
+            SyntaxKind::ParenthesizedExpression => self.narrow_type(
+                reference,
+                type_,
+                &expression.as_parenthesized_expression().expression,
+                assume_true,
+            ),
+            SyntaxKind::PrefixUnaryExpression
+                if expression.as_prefix_unary_expression().operator
+                    == SyntaxKind::ExclamationToken =>
+            {
+                self.narrow_type(
+                    reference,
+                    type_,
+                    &expression.as_prefix_unary_expression().operand,
+                    !assume_true,
+                )
+            }
+            SyntaxKind::BinaryExpression => {
+                self.narrow_type_by_binary_expression(reference, type_, expression, assume_true)
+            }
+            _ => type_,
+        }
+    }
+
+    fn narrow_type_by_truthiness(
+        &mut self,
+        reference: &FlowReference,
+        type_: TypeId,
+        expression: &Arc<Node>,
+        assume_true: bool,
+    ) -> TypeId {
+        if self.is_matching_reference(&reference.node, expression) {
+            return self.get_type_with_truthiness(type_, assume_true);
+        }
+        if self.is_matching_reference_discriminant(reference, expression, type_) {
+            return self.narrow_type_by_discriminant(type_, expression, |checker, type_| {
+                checker.get_type_with_truthiness(type_, assume_true)
+            });
+        }
+        type_
+    }
+
+    /// Removes the constituents of a type that are definitely falsy, when the value is known to
+    /// be truthy, or definitely truthy, when it is known to be falsy
+    fn get_type_with_truthiness(&mut self, type_: TypeId, assume_true: bool) -> TypeId {
+        self.filter_type(type_, |checker, type_| {
+            if assume_true {
+                !checker.is_definitely_falsy_type(type_)
+            } else {
+                !checker.is_definitely_truthy_type(type_)
+            }
+        })
+    }
+
+    fn is_definitely_truthy_type(&self, type_: TypeId) -> bool {
+        let flags = self.flags(type_);
+        if flags.intersects(TypeFlags::ES_SYMBOL_LIKE | TypeFlags::NON_PRIMITIVE) {
+            return true;
+        }
+        // `{}` also admits the falsy primitives
+        if flags.contains(TypeFlags::OBJECT) {
+            return type_ != self.empty_object_type;
+        }
+        if type_ == self.true_type {
+            return true;
+        }
+        match &self.get_type(type_).data {
+            TypeData::Literal { value } => match value {
+                LiteralValue::String(text) => !text.is_empty(),
+                LiteralValue::Number(number) => number.0 != 0.0 && !number.0.is_nan(),
+                LiteralValue::BigInt(digits) => digits != "0",
+            },
+            _ => false,
+        }
+    }
+
+    fn narrow_type_by_binary_expression(
+        &mut self,
+        reference: &FlowReference,
+        type_: TypeId,
+        expression: &Arc<Node>,
+        assume_true: bool,
+    ) -> TypeId {
+        let binary = expression.as_binary_expression();
+        let operator = binary.operator_token.kind;
+        match operator {
+            SyntaxKind::EqualsToken
+            | SyntaxKind::BarBarEqualsToken
+            | SyntaxKind::AmpersandAmpersandEqualsToken
+            | SyntaxKind::QuestionQuestionEqualsToken => {
+                self.narrow_type_by_truthiness(reference, type_, &binary.left, assume_true)
+            }
+            SyntaxKind::EqualsEqualsToken
+            | SyntaxKind::ExclamationEqualsToken
+            | SyntaxKind::EqualsEqualsEqualsToken
+            | SyntaxKind::ExclamationEqualsEqualsToken => {
+                let left = skip_parentheses(&binary.left);
+                let right = skip_parentheses(&binary.right);
+                for (typeof_expression, literal) in [(&left, &right), (&right, &left)] {
+                    if typeof_expression.kind == SyntaxKind::TypeOfExpression
+                        && let Some(name) = get_string_literal_text(literal)
+                    {
+                        return self.narrow_type_by_typeof(
+                            reference,
+                            type_,
+                            typeof_expression,
+                            operator,
+                            &name,
+                            assume_true,
+                        );
+                    }
+                }
+                for (operand, value) in [(&left, &right), (&right, &left)] {
+                    if self.is_matching_reference(&reference.node, operand) {
+                        return self.narrow_type_by_equality(type_, operator, value, assume_true);
+                    }
+                }
+                for (operand, value) in [(&left, &right), (&right, &left)] {
+                    if self.is_matching_reference_discriminant(reference, operand, type_) {
+                        return self.narrow_type_by_discriminant(
+                            type_,
+                            operand,
+                            |checker, type_| {
+                                checker.narrow_type_by_equality(type_, operator, value, assume_true)
+                            },
+                        );
+                    }
+                }
+                type_
+            }
+            SyntaxKind::InstanceOfKeyword => {
+                self.narrow_type_by_instanceof(reference, type_, expression, assume_true)
+            }
+            SyntaxKind::InKeyword => {
+                let left = skip_parentheses(&binary.left);
+                match get_string_literal_text(&left) {
+                    Some(name) if self.is_matching_reference(&reference.node, &binary.right) => {
+                        self.narrow_type_by_in(type_, &name, assume_true)
+                    }
+                    _ => type_,
+                }
+            }
+            SyntaxKind::CommaToken => {
+                self.narrow_type(reference, type_, &binary.right, assume_true)
+            }
+            SyntaxKind::AmpersandAmpersandToken => {
+                let when_left_true = self.narrow_type(reference, type_, &binary.left, true);
+                if assume_true {
+                    return self.narrow_type(reference, when_left_true, &binary.right, true);
+                }
+                let when_left_false = self.narrow_type(reference, type_, &binary.left, false);
+                let when_right_false =
+                    self.narrow_type(reference, when_left_true, &binary.right, false);
+                self.get_union_type(&[when_left_false, when_right_false])
+            }
+            SyntaxKind::BarBarToken => {
+                let when_left_false = self.narrow_type(reference, type_, &binary.left, false);
+                if !assume_true {
+                    return self.narrow_type(reference, when_left_false, &binary.right, false);
+                }
+                let when_left_true = self.narrow_type(reference, type_, &binary.left, true);
+                let when_right_true =
+                    self.narrow_type(reference, when_left_false, &binary.right, true);
+                self.get_union_type(&[when_left_true, when_right_true])
+            }
+            _ => type_,
+        }
+    }
+
+    /// Narrows a type by a comparison of `typeof` the reference, or of a discriminant
+    /// property of it, with a string
+    fn narrow_type_by_typeof(
+        &mut self,
+        reference: &FlowReference,
+        type_: TypeId,
+        typeof_expression: &Arc<Node>,
+        operator: SyntaxKind,
+        name: &str,
+        assume_true: bool,
+    ) -> TypeId {
+        let assume_true = assume_true ^ is_negated_equality(operator);
+        let target = skip_parentheses(&typeof_expression.as_type_of_expression().expression);
+        if self.is_matching_reference(&reference.node, &target) {
+            return self.narrow_type_by_type_name(type_, name, assume_true);
+        }
+        if self.is_matching_reference_discriminant(reference, &target, type_) {
+            return self.narrow_type_by_discriminant(type_, &target, |checker, type_| {
+                checker.narrow_type_by_type_name(type_, name, assume_true)
+            });
+        }
+        type_
+    }
+
+    /// Narrows a type to the constituents whose `typeof` can be `name`, or to those whose
+    /// `typeof` can be something else
+    fn narrow_type_by_type_name(&mut self, type_: TypeId, name: &str, assume_true: bool) -> TypeId {
+        if !assume_true {
+            return self.filter_type(type_, |checker, type_| {
+                checker.get_typeof_results(type_) != [name]
+            });
+        }
+        let implied_type = self.get_type_of_typeof_result(name);
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::ANY) && matches!(name, "object" | "function") {
+            return type_;
+        }
+        if flags.intersects(TypeFlags::ANY_OR_UNKNOWN) {
+            return implied_type.unwrap_or(type_);
+        }
+        self.map_type_constituents(type_, |checker, type_| {
+            if !checker.get_typeof_results(type_).contains(&name) {
+                return checker.never_type;
+            }
+            // `{}` admits every primitive, so it narrows to the primitive the name implies
+            match implied_type {
+                Some(implied_type)
+                    if type_ == checker.empty_object_type
+                        && !matches!(name, "object" | "function") =>
+                {
+                    implied_type
+                }
+                _ => type_,
+            }
+        })
+    }
+
+    /// Gets the values `typeof` can produce for a value of a type that is not a union
+    fn get_typeof_results(&mut self, type_: TypeId) -> Vec<&'static str> {
+        let flags = self.flags(type_);
+        let result = if flags.intersects(TypeFlags::STRING_LIKE) {
+            "string"
+        } else if flags.intersects(TypeFlags::NUMBER_LIKE | TypeFlags::ENUM_LITERAL) {
+            "number"
+        } else if flags.intersects(TypeFlags::BIG_INT_LIKE) {
+            "bigint"
+        } else if flags.intersects(TypeFlags::BOOLEAN_LIKE) {
+            "boolean"
+        } else if flags.intersects(TypeFlags::ES_SYMBOL_LIKE) {
+            "symbol"
+        } else if flags.intersects(TypeFlags::VOID_LIKE) {
+            "undefined"
+        } else if flags.contains(TypeFlags::NULL) {
+            "object"
+        } else if flags.contains(TypeFlags::NON_PRIMITIVE) {
+            return vec!["object", "function"];
+        } else if flags.contains(TypeFlags::OBJECT) && !self.is_empty_object_type(type_) {
+            if self.is_function_object_type(type_) {
+                "function"
+            } else {
+                "object"
+            }
+        } else if flags.contains(TypeFlags::TYPE_PARAMETER)
+            && let Some(constraint) = self.get_constraint_of_type_parameter(type_)
+            && constraint != type_
+        {
+            let mut results = Vec::new();
+            for constituent in self.get_constituents(constraint) {
+                for result in self.get_typeof_results(constituent) {
+                    if !results.contains(&result) {
+                        results.push(result);
+                    }
+                }
+            }
+            return results;
+        } else {
+            let mut results = TYPEOF_RESULTS.to_vec();
+            // `{}` admits everything but `null` and `undefined`
+            if flags.contains(TypeFlags::OBJECT) {
+                results.retain(|&result| result != "undefined");
+            }
+            return results;
+        };
+        vec![result]
+    }
+
+    fn is_function_object_type(&mut self, type_: TypeId) -> bool {
+        !self.get_signatures_of_type(type_, false).is_empty()
+            || !self.get_signatures_of_type(type_, true).is_empty()
+            || type_ == self.get_global_type("Function")
+    }
+
+    /// Gets the type of the values for which `typeof` produces `name`
+    fn get_type_of_typeof_result(&mut self, name: &str) -> Option<TypeId> {
+        Some(match name {
+            "string" => self.string_type,
+            "number" => self.number_type,
+            "bigint" => self.bigint_type,
+            "boolean" => self.boolean_type,
+            "symbol" => self.es_symbol_type,
+            "undefined" => self.undefined_type,
+            "object" => self.get_union_type(&[self.non_primitive_type, self.null_type]),
+            "function" => self.get_global_type("Function"),
+            _ => return None,
+        })
+    }
+
+    /// Narrows a type by a comparison of the reference with another value
+    fn narrow_type_by_equality(
+        &mut self,
+        type_: TypeId,
+        operator: SyntaxKind,
+        value: &Arc<Node>,
+        assume_true: bool,
+    ) -> TypeId {
+        if self.flags(type_).contains(TypeFlags::ANY) {
+            return type_;
+        }
+        let assume_true = assume_true ^ is_negated_equality(operator);
+        let double_equals = matches!(
+            operator,
+            SyntaxKind::EqualsEqualsToken | SyntaxKind::ExclamationEqualsToken
+        );
+        let value_type = self.check_expression(value);
+        let value_type = self.get_regular_type_of_literal_type(value_type);
+        let value_flags = self.flags(value_type);
+        if value_flags.intersects(TypeFlags::NULLABLE) {
+            if !self.options.strict_null_checks {
+                return type_;
+            }
+            // `== null` and `== undefined` match both
+            let matched = if double_equals {
+                TypeFlags::NULLABLE | TypeFlags::VOID
+            } else if value_flags.contains(TypeFlags::NULL) {
+                TypeFlags::NULL
+            } else {
+                TypeFlags::VOID_LIKE
+            };
+            if assume_true && self.flags(type_).contains(TypeFlags::UNKNOWN) {
+                return if double_equals {
+                    self.get_union_type(&[self.null_type, self.undefined_type])
+                } else {
+                    value_type
+                };
+            }
+            return self.filter_type(type_, |checker, type_| {
+                checker.flags(type_).intersects(matched) == assume_true
+                    || checker.flags(type_).contains(TypeFlags::UNKNOWN)
+            });
+        }
+        if assume_true {
+            if !double_equals
+                && self.flags(type_).contains(TypeFlags::UNKNOWN)
+                && value_flags.intersects(TypeFlags::PRIMITIVE | TypeFlags::NON_PRIMITIVE)
+            {
+                return value_type;
+            }
+            let filtered = self.filter_type(type_, |checker, type_| {
+                checker.are_types_comparable(type_, value_type)
+                    || (double_equals
+                        && is_coercible_under_double_equals(checker, type_, value_type))
+            });
+            return self.replace_primitives_with_literals(filtered, value_type);
+        }
+        if value_flags.intersects(TypeFlags::UNIT) {
+            return self.filter_type(type_, |checker, type_| {
+                !(checker.flags(type_).intersects(TypeFlags::UNIT) && type_ == value_type)
+            });
+        }
+        type_
+    }
+
+    /// Replaces the primitive constituents of a type with the literals of the same primitive
+    /// in another type, so that `x: string | number` is `"a"` where `x === "a"`
+    fn replace_primitives_with_literals(
+        &mut self,
+        type_with_primitives: TypeId,
+        type_with_literals: TypeId,
+    ) -> TypeId {
+        let kinds = [
+            (TypeFlags::STRING, TypeFlags::STRING_LITERAL),
+            (TypeFlags::NUMBER, TypeFlags::NUMBER_LITERAL),
+            (TypeFlags::BIG_INT, TypeFlags::BIG_INT_LITERAL),
+        ];
+        self.map_type_constituents(type_with_primitives, |checker, type_| {
+            for (primitive, literal) in kinds {
+                if !checker.flags(type_).contains(primitive) {
+                    continue;
+                }
+                let literals: Vec<TypeId> = checker
+                    .get_constituents(type_with_literals)
+                    .into_iter()
+                    .filter(|&type_| checker.flags(type_).intersects(literal))
+                    .collect();
+                if !literals.is_empty() {
+                    return checker.get_union_type(&literals);
+                }
+            }
+            type_
+        })
+    }
+
+    fn narrow_type_by_switch_clause(
+        &mut self,
+        reference: &FlowReference,
+        type_: TypeId,
+        switch_statement: &Arc<Node>,
+        clause_start: usize,
+        clause_end: usize,
+    ) -> TypeId {
+        let expression = skip_parentheses(&switch_statement.as_switch_statement().expression);
+        if self.is_matching_reference(&reference.node, &expression) {
+            return self.narrow_type_by_switch_on_discriminant(
+                type_,
+                switch_statement,
+                clause_start,
+                clause_end,
+            );
+        }
+        if expression.kind == SyntaxKind::TypeOfExpression
+            && self.is_matching_reference(
+                &reference.node,
+                &expression.as_type_of_expression().expression,
+            )
+        {
+            return self.narrow_type_by_switch_on_typeof(
+                type_,
+                switch_statement,
+                clause_start,
+                clause_end,
+            );
+        }
+        if self.is_matching_reference_discriminant(reference, &expression, type_) {
+            return self.narrow_type_by_discriminant(type_, &expression, |checker, type_| {
+                checker.narrow_type_by_switch_on_discriminant(
+                    type_,
+                    switch_statement,
+                    clause_start,
+                    clause_end,
+                )
+            });
+        }
+        type_
+    }
+
+    /// Gets the types of the case expressions of a switch statement, with `never` for the
+    /// default clause
+    fn get_switch_clause_types(&mut self, switch_statement: &Arc<Node>) -> Vec<TypeId> {
+        let case_block = &switch_statement.as_switch_statement().case_block;
+        let clauses = case_block.as_case_block().clauses.nodes.clone();
+        clauses
+            .iter()
+            .map(|clause| match clause.kind {
+                SyntaxKind::CaseClause => {
+                    let type_ = self.check_expression(&clause.as_case_clause().expression);
+                    self.get_regular_type_of_literal_type(type_)
+                }
+                _ => self.never_type,
+            })
+            .collect()
+    }
+
+    /// Narrows a type by entry into the clauses `clause_start..clause_end` of a switch on the
+    /// reference, or on a discriminant property of it
+    fn narrow_type_by_switch_on_discriminant(
+        &mut self,
+        type_: TypeId,
+        switch_statement: &Arc<Node>,
+        clause_start: usize,
+        clause_end: usize,
+    ) -> TypeId {
+        let switch_types = self.get_switch_clause_types(switch_statement);
+        if switch_types.is_empty() {
+            return type_;
+        }
+        let clause_types = switch_types[clause_start..clause_end].to_vec();
+        let has_default_clause = has_default_clause(switch_statement, clause_start, clause_end);
+        if self.flags(type_).contains(TypeFlags::UNKNOWN) && !has_default_clause {
+            return self.get_union_type(&clause_types);
+        }
+        let discriminant_type = self.get_union_type(&clause_types);
+        let case_type = if self.flags(discriminant_type).contains(TypeFlags::NEVER) {
+            self.never_type
+        } else {
+            let filtered = self.filter_type(type_, |checker, type_| {
+                checker.are_types_comparable(discriminant_type, type_)
+            });
+            self.replace_primitives_with_literals(filtered, discriminant_type)
+        };
+        if !has_default_clause {
+            return case_type;
+        }
+        // The default clause is entered when no case matched
+        let default_type = self.filter_type(type_, |checker, type_| {
+            !(checker.flags(type_).intersects(TypeFlags::UNIT) && switch_types.contains(&type_))
+        });
+        self.get_union_type(&[case_type, default_type])
+    }
+
+    /// Narrows a type by entry into the clauses `clause_start..clause_end` of a switch on
+    /// `typeof` the reference
+    fn narrow_type_by_switch_on_typeof(
+        &mut self,
+        type_: TypeId,
+        switch_statement: &Arc<Node>,
+        clause_start: usize,
+        clause_end: usize,
+    ) -> TypeId {
+        let case_block = &switch_statement.as_switch_statement().case_block;
+        let names: Vec<Option<String>> = case_block
+            .as_case_block()
+            .clauses
+            .iter()
+            .map(|clause| match clause.kind {
+                SyntaxKind::CaseClause => {
+                    get_string_literal_text(&skip_parentheses(&clause.as_case_clause().expression))
+                }
+                _ => None,
+            })
+            .collect();
+        let clause_names = &names[clause_start..clause_end];
+        let has_default_clause = has_default_clause(switch_statement, clause_start, clause_end);
+        let mut types = Vec::new();
+        for name in clause_names.iter().flatten() {
+            types.push(self.narrow_type_by_type_name(type_, name, true));
+        }
+        if has_default_clause {
+            let other_names: Vec<&String> = names
+                .iter()
+                .flatten()
+                .filter(|name| !clause_names.contains(&Some(name.to_string())))
+                .collect();
+            types.push(self.filter_type(type_, |checker, type_| {
+                let results = checker.get_typeof_results(type_);
+                !other_names.iter().any(|name| results == [name.as_str()])
+            }));
+        }
+        self.get_union_type(&types)
+    }
+
+    /// Narrows a type by an `instanceof` check of the reference
+    fn narrow_type_by_instanceof(
+        &mut self,
+        reference: &FlowReference,
+        type_: TypeId,
+        expression: &Arc<Node>,
+        assume_true: bool,
+    ) -> TypeId {
+        let binary = expression.as_binary_expression();
+        if !self.is_matching_reference(&reference.node, &binary.left) {
+            return type_;
+        }
+        let constructor_type = self.check_expression(&binary.right);
+        let Some(instance_type) = self.get_instance_type_of_constructor(constructor_type) else {
+            return type_;
+        };
+        if !assume_true {
+            return self.filter_type(type_, |checker, type_| {
+                !checker.is_type_derived_from(type_, instance_type)
+            });
+        }
+        if self.flags(type_).intersects(TypeFlags::ANY_OR_UNKNOWN) {
+            return instance_type;
+        }
+        let narrowed = self.map_type_constituents(type_, |checker, type_| {
+            if checker.is_type_derived_from(type_, instance_type) {
+                type_
+            } else if checker.is_type_derived_from(instance_type, type_) {
+                instance_type
+            } else {
+                checker.never_type
+            }
+        });
+        if !self.flags(narrowed).contains(TypeFlags::NEVER) {
+            return narrowed;
+        }
+        self.get_intersection_type(&[type_, instance_type])
+    }
+
+    /// Gets the type of the instances of a constructor: the type of its `prototype`, or what
+    /// its construct signatures return
+    fn get_instance_type_of_constructor(&mut self, constructor_type: TypeId) -> Option<TypeId> {
+        if self.flags(constructor_type).intersects(TypeFlags::ANY) {
+            return None;
+        }
+        if let Some(prototype) = self.get_type_of_property_of_type(constructor_type, "prototype")
+            && !self.flags(prototype).intersects(TypeFlags::ANY)
+        {
+            return Some(prototype);
+        }
+        let signatures = self.get_signatures_of_type(constructor_type, true);
+        if signatures.is_empty() {
+            return None;
+        }
+        let return_types: Vec<TypeId> = signatures
+            .into_iter()
+            .map(|signature| self.get_return_type_of_signature(signature))
+            .collect();
+        Some(self.get_union_type(&return_types))
+    }
+
+    /// Determines if `source` is `target`, or a class or interface that extends it
+    fn is_type_derived_from(&mut self, source: TypeId, target: TypeId) -> bool {
+        let source_flags = self.flags(source);
+        if source_flags.contains(TypeFlags::UNION) {
+            return self
+                .get_constituents(source)
+                .into_iter()
+                .all(|source| self.is_type_derived_from(source, target));
+        }
+        if self.flags(target).contains(TypeFlags::UNION) {
+            return self
+                .get_constituents(target)
+                .into_iter()
+                .any(|target| self.is_type_derived_from(source, target));
+        }
+        if source_flags.contains(TypeFlags::TYPE_PARAMETER) {
+            let constraint = self
+                .get_constraint_of_type_parameter(source)
+                .unwrap_or(self.unknown_type);
+            return constraint != source && self.is_type_derived_from(constraint, target);
+        }
+        if target == self.get_global_type("Object") {
+            return source_flags.intersects(TypeFlags::OBJECT | TypeFlags::NON_PRIMITIVE);
+        }
+        if target == self.get_global_type("Function") {
+            return source_flags.contains(TypeFlags::OBJECT)
+                && self.is_function_object_type(source);
+        }
+        let target = self.get_target_type(target);
+        self.has_base_type(source, target)
+    }
+
+    fn has_base_type(&mut self, type_: TypeId, base: TypeId) -> bool {
+        if self.get_target_type(type_) == base {
+            return true;
+        }
+        self.get_base_types(self.get_target_type(type_))
+            .into_iter()
+            .any(|base_type| self.has_base_type(base_type, base))
+    }
+
+    /// Gets the generic type a type reference instantiates, or the type itself
+    fn get_target_type(&self, type_: TypeId) -> TypeId {
+        match &self.get_type(type_).data {
+            TypeData::Reference { target, .. } => *target,
+            _ => type_,
+        }
+    }
+
+    /// Narrows a type by an `in` check of a property on the reference
+    fn narrow_type_by_in(&mut self, type_: TypeId, name: &str, assume_true: bool) -> TypeId {
+        self.filter_type(type_, |checker, type_| {
+            match checker.get_property_of_type(type_, name) {
+                Some(property) => {
+                    assume_true
+                        || checker
+                            .symbol(property)
+                            .flags
+                            .contains(SymbolFlags::OPTIONAL)
+                }
+                None => {
+                    !assume_true
+                        || checker
+                            .get_applicable_index_info(type_, checker.string_type)
+                            .is_some()
+                }
+            }
+        })
+    }
+
+    fn are_types_comparable(&mut self, source: TypeId, target: TypeId) -> bool {
+        self.is_type_comparable_to(source, target) || self.is_type_comparable_to(target, source)
+    }
+
+    /// Keeps the constituents of a union type that satisfy `f`, or the type itself if it is
+    /// not a union and satisfies it
+    pub(super) fn filter_type(
+        &mut self,
+        type_: TypeId,
+        f: impl Fn(&mut Checker, TypeId) -> bool,
+    ) -> TypeId {
+        if !self.flags(type_).contains(TypeFlags::UNION) {
+            return if f(self, type_) {
+                type_
+            } else {
+                self.never_type
+            };
+        }
+        let mut kept = Vec::new();
+        for constituent in self.get_constituents(type_) {
+            if f(self, constituent) {
+                kept.push(constituent);
+            }
+        }
+        self.get_union_type(&kept)
+    }
+
+    /// Maps each constituent of a union type, or the type itself if it is not a union
+    pub(super) fn map_type_constituents(
+        &mut self,
+        type_: TypeId,
+        f: impl Fn(&mut Checker, TypeId) -> TypeId,
+    ) -> TypeId {
+        if !self.flags(type_).contains(TypeFlags::UNION) {
+            return f(self, type_);
+        }
+        let mut mapped = Vec::new();
+        for constituent in self.get_constituents(type_) {
+            mapped.push(f(self, constituent));
+        }
+        self.get_union_type(&mapped)
+    }
+}
+
+/// Determines if `node` is written to rather than read: the target of an assignment, an
+/// increment or decrement, or a `for`-`in` or `for`-`of` loop, possibly inside a
+/// destructuring pattern
+pub(super) fn is_assignment_target(node: &Arc<Node>) -> bool {
+    let mut node = Arc::clone(node);
+    while let Some(parent) = node.parent() {
+        match parent.kind {
+            SyntaxKind::ParenthesizedExpression
+            | SyntaxKind::SpreadElement
+            | SyntaxKind::SpreadAssignment
+            | SyntaxKind::ArrayLiteralExpression
+            | SyntaxKind::ObjectLiteralExpression
+            | SyntaxKind::ShorthandPropertyAssignment => {}
+            SyntaxKind::PropertyAssignment => {
+                if !Arc::ptr_eq(&parent.as_property_assignment().initializer, &node) {
+                    return false;
+                }
+            }
+            SyntaxKind::BinaryExpression => {
+                let binary = parent.as_binary_expression();
+                return binary.operator_token.kind.is_assignment_operator()
+                    && Arc::ptr_eq(&binary.left, &node);
+            }
+            SyntaxKind::PrefixUnaryExpression => {
+                return matches!(
+                    parent.as_prefix_unary_expression().operator,
+                    SyntaxKind::PlusPlusToken | SyntaxKind::MinusMinusToken
+                );
+            }
+            SyntaxKind::PostfixUnaryExpression => return true,
+            SyntaxKind::ForInStatement => {
+                return Arc::ptr_eq(&parent.as_for_in_statement().initializer, &node);
+            }
+            SyntaxKind::ForOfStatement => {
+                return Arc::ptr_eq(&parent.as_for_of_statement().initializer, &node);
+            }
+            _ => return false,
+        }
+        node = parent;
+    }
+    false
+}
+
+/// Determines if `node` is both read and written: the target of a compound assignment, an
+/// increment, or a decrement
+pub(super) fn is_compound_assignment_target(node: &Arc<Node>) -> bool {
+    let mut node = Arc::clone(node);
+    while let Some(parent) = node.parent() {
+        match parent.kind {
+            SyntaxKind::ParenthesizedExpression => {}
+            SyntaxKind::BinaryExpression => {
+                let binary = parent.as_binary_expression();
+                let operator = binary.operator_token.kind;
+                return operator.is_assignment_operator()
+                    && operator != SyntaxKind::EqualsToken
+                    && Arc::ptr_eq(&binary.left, &node);
+            }
+            SyntaxKind::PrefixUnaryExpression => {
+                return matches!(
+                    parent.as_prefix_unary_expression().operator,
+                    SyntaxKind::PlusPlusToken | SyntaxKind::MinusMinusToken
+                );
+            }
+            SyntaxKind::PostfixUnaryExpression => return true,
+            _ => return false,
+        }
+        node = parent;
+    }
+    false
+}
+
+/// Determines if `node` is written to without its value being read: the target of a plain
+/// assignment, or of a compound assignment, increment, or decrement whose result is unused
+pub(super) fn is_write_only_access(node: &Arc<Node>) -> bool {
+    let mut node = Arc::clone(node);
+    while let Some(parent) = node.parent() {
+        match parent.kind {
+            SyntaxKind::ParenthesizedExpression
+            | SyntaxKind::SpreadElement
+            | SyntaxKind::SpreadAssignment
+            | SyntaxKind::ArrayLiteralExpression
+            | SyntaxKind::ObjectLiteralExpression
+            | SyntaxKind::ShorthandPropertyAssignment => {}
+            SyntaxKind::PropertyAssignment => {
+                if !Arc::ptr_eq(&parent.as_property_assignment().initializer, &node) {
+                    return false;
+                }
+            }
+            SyntaxKind::BinaryExpression => {
+                let binary = parent.as_binary_expression();
+                let operator = binary.operator_token.kind;
+                if !operator.is_assignment_operator() || !Arc::ptr_eq(&binary.left, &node) {
+                    return false;
+                }
+                return operator == SyntaxKind::EqualsToken || is_result_unused(&parent);
+            }
+            SyntaxKind::PrefixUnaryExpression => {
+                return matches!(
+                    parent.as_prefix_unary_expression().operator,
+                    SyntaxKind::PlusPlusToken | SyntaxKind::MinusMinusToken
+                ) && is_result_unused(&parent);
+            }
+            SyntaxKind::PostfixUnaryExpression => return is_result_unused(&parent),
+            _ => return false,
+        }
+        node = parent;
+    }
+    false
+}
+
+/// Determines if the value of an expression is discarded by an expression statement
+fn is_result_unused(node: &Arc<Node>) -> bool {
+    let mut node = Arc::clone(node);
+    while let Some(parent) = node.parent() {
+        if parent.kind != SyntaxKind::ParenthesizedExpression {
+            return parent.kind == SyntaxKind::ExpressionStatement;
+        }
+        node = parent;
+    }
+    false
+}
+
+/// Gets the name of the property a property access or an element access with a literal
+/// argument reads
+fn get_accessed_property_name(node: &Node) -> Option<String> {
+    match node.kind {
+        SyntaxKind::PropertyAccessExpression => {
+            Some(node.as_property_access_expression().name.text().to_string())
+        }
+        SyntaxKind::ElementAccessExpression => {
+            let argument =
+                skip_parentheses(&node.as_element_access_expression().argument_expression);
+            match argument.kind {
+                SyntaxKind::NumericLiteral => Some(number_to_string(parse_numeric_literal(
+                    &argument.as_numeric_literal().text,
+                ))),
+                _ => get_string_literal_text(&argument),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Gets the object a property access or element access reads a property of
+fn get_accessed_expression(node: &Node) -> Option<Arc<Node>> {
+    match node.kind {
+        SyntaxKind::PropertyAccessExpression => {
+            Some(Arc::clone(&node.as_property_access_expression().expression))
+        }
+        SyntaxKind::ElementAccessExpression => {
+            Some(Arc::clone(&node.as_element_access_expression().expression))
+        }
+        _ => None,
+    }
+}
+
+fn get_string_literal_text(node: &Node) -> Option<String> {
+    match node.kind {
+        SyntaxKind::StringLiteral => Some(node.as_string_literal().text.clone()),
+        _ if is_string_literal_like(node) => {
+            Some(node.as_template_literal_like_node().text.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Determines if the clauses `clause_start..clause_end` of a switch statement are entered when
+/// no case matches, with an empty range standing for leaving a switch without a default clause
+fn has_default_clause(switch_statement: &Node, clause_start: usize, clause_end: usize) -> bool {
+    let case_block = &switch_statement.as_switch_statement().case_block;
+    clause_start == clause_end
+        || case_block.as_case_block().clauses.nodes[clause_start..clause_end]
+            .iter()
+            .any(|clause| clause.kind == SyntaxKind::DefaultClause)
+}
+
+/// Determines if an equality operator is `!=` or `!==`
+fn is_negated_equality(operator: SyntaxKind) -> bool {
+    matches!(
+        operator,
+        SyntaxKind::ExclamationEqualsToken | SyntaxKind::ExclamationEqualsEqualsToken
+    )
+}
+
+/// Determines if `==` can find a value of `source` equal to a value of the primitive `target`
+/// by converting one of them
+fn is_coercible_under_double_equals(checker: &Checker, source: TypeId, target: TypeId) -> bool {
+    checker
+        .flags(source)
+        .intersects(TypeFlags::NUMBER | TypeFlags::STRING | TypeFlags::BOOLEAN_LITERAL)
+        && checker
+            .flags(target)
+            .intersects(TypeFlags::NUMBER | TypeFlags::STRING | TypeFlags::BOOLEAN)
+}
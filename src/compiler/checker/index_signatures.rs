@@ -0,0 +1,169 @@
+//! Index signatures: the types of their keys, and the constraints they put on the other
+//! members of the types that declare them
+//!
+//! A string index signature describes every property of its type, and a number index
+//! signature every property with a numeric name, so those properties must be assignable to
+//! them. Numeric keys are strings at runtime, so a number index signature must in turn be
+//! assignable to a string index signature.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::symbol::{INTERNAL_SYMBOL_NAME_PREFIX, symbol_name};
+use crate::compiler::ast::{Node, SymbolId};
+use crate::compiler::diagnostics;
+
+use super::relations::is_numeric_literal_name;
+use super::{Checker, IndexInfo, TypeFlags, TypeId};
+
+impl Checker {
+    /// Checks the parameter of an index signature, whose type must be one that keys of every
+    /// name can have, rather than particular names
+    pub(super) fn check_index_signature_parameter(&mut self, node: &Arc<Node>) {
+        let Some(parameter) = node
+            .parameters()
+            .and_then(|parameters| parameters.iter().next())
+            .cloned()
+        else {
+            return;
+        };
+        let (Some(type_node), Some(name)) = (parameter.type_node(), parameter.name()) else {
+            return;
+        };
+        let key_type = self.get_type_from_type_node(type_node);
+        let is_literal_or_generic = self.some_type(key_type, |checker, type_| {
+            checker.flags(type_).intersects(
+                TypeFlags::STRING_OR_NUMBER_LITERAL
+                    | TypeFlags::UNIQUE_ES_SYMBOL
+                    | TypeFlags::INSTANTIABLE_NON_PRIMITIVE,
+            )
+        });
+        if is_literal_or_generic {
+            self.error(
+                name,
+                diagnostics::AN_INDEX_SIGNATURE_PARAMETER_TYPE_CANNOT_BE_A_LITERAL_TYPE_OR_GENERIC_TYPE_CONSIDER_USING_A_MAPPED_OBJECT_TYPE_INSTEAD_1337,
+                &[],
+            );
+            return;
+        }
+        let is_valid = !self.some_type(key_type, |checker, type_| {
+            !checker.flags(type_).intersects(
+                TypeFlags::STRING
+                    | TypeFlags::NUMBER
+                    | TypeFlags::ES_SYMBOL
+                    | TypeFlags::TEMPLATE_LITERAL,
+            )
+        });
+        if !is_valid {
+            self.error(
+                name,
+                diagnostics::AN_INDEX_SIGNATURE_PARAMETER_TYPE_MUST_BE_STRING_NUMBER_SYMBOL_OR_A_TEMPLATE_LITERAL_TYPE_1268,
+                &[],
+            );
+        }
+    }
+
+    /// Checks that the properties and index signatures of a class, interface, or type literal
+    /// are assignable to the index signatures that apply to them
+    ///
+    /// Only what `node` declares itself is reported, since the types it inherits from are
+    /// checked where they are declared.
+    pub(super) fn check_index_constraints(&mut self, node: &Arc<Node>, type_: TypeId) {
+        let members = self.resolve_structured_type_members(type_);
+        if members.index_infos.is_empty() {
+            return;
+        }
+        let index_infos = members.index_infos.clone();
+        let properties = members.properties.clone();
+        for property in properties {
+            for info in &index_infos {
+                self.check_index_constraint_for_property(node, property, info);
+            }
+        }
+        for info in &index_infos {
+            let Some(declaration) = info
+                .declaration
+                .clone()
+                .filter(|declaration| is_declared_in(declaration, node))
+            else {
+                continue;
+            };
+            for other in &index_infos {
+                if other.key_type == info.key_type
+                    || !self.is_applicable_index_type(info.key_type, other.key_type)
+                    || self.is_type_assignable_to(info.value_type, other.value_type)
+                {
+                    continue;
+                }
+                let args = [
+                    self.type_to_string(info.key_type),
+                    self.type_to_string(info.value_type),
+                    self.type_to_string(other.key_type),
+                    self.type_to_string(other.value_type),
+                ];
+                self.error(
+                    &declaration,
+                    diagnostics::D_0_INDEX_TYPE_1_IS_NOT_ASSIGNABLE_TO_2_INDEX_TYPE_3_2413,
+                    &args,
+                );
+            }
+        }
+    }
+
+    /// Reports a property that is not assignable to an index signature that applies to its
+    /// name, when either of them is declared by `node`
+    fn check_index_constraint_for_property(
+        &mut self,
+        node: &Arc<Node>,
+        property: SymbolId,
+        info: &IndexInfo,
+    ) {
+        let name = self.symbol(property).name.clone();
+        let applies = if info.key_type == self.number_type {
+            is_numeric_literal_name(&name)
+        } else if info.key_type == self.string_type {
+            !name.starts_with(INTERNAL_SYMBOL_NAME_PREFIX)
+        } else {
+            false
+        };
+        if !applies {
+            return;
+        }
+        let property_declaration = self
+            .symbol(property)
+            .value_declaration
+            .clone()
+            .filter(|declaration| is_declared_in(declaration, node));
+        let error_node = match &property_declaration {
+            Some(declaration) => declaration.name().cloned(),
+            None => info
+                .declaration
+                .clone()
+                .filter(|declaration| is_declared_in(declaration, node)),
+        };
+        let Some(error_node) = error_node else {
+            return;
+        };
+        let property_type = self.get_type_of_symbol(property);
+        if self.is_type_assignable_to(property_type, info.value_type) {
+            return;
+        }
+        let args = [
+            symbol_name(self.symbol(property)).into_owned(),
+            self.type_to_string(property_type),
+            self.type_to_string(info.key_type),
+            self.type_to_string(info.value_type),
+        ];
+        self.error(
+            &error_node,
+            diagnostics::PROPERTY_0_OF_TYPE_1_IS_NOT_ASSIGNABLE_TO_2_INDEX_TYPE_3_2411,
+            &args,
+        );
+    }
+}
+
+/// Determines if a member declaration is declared directly in `node`, rather than inherited
+fn is_declared_in(declaration: &Node, node: &Arc<Node>) -> bool {
+    declaration
+        .parent()
+        .is_some_and(|parent| Arc::ptr_eq(&parent, node))
+}
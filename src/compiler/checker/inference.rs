@@ -0,0 +1,576 @@
+//! Type argument inference
+//!
+//! The type arguments of a call to a generic function are inferred by walking the types of
+//! the arguments alongside the types of the parameters and collecting, for each type
+//! parameter, the types found where the parameter occurs. Candidates found in parameter
+//! positions of callbacks are contravariant and are only used when there are no covariant
+//! candidates that fit them.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use bitflags::bitflags;
+
+use super::relations::Variance;
+use super::{Checker, ObjectFlags, SignatureId, TypeData, TypeFlags, TypeId, TypeMapper};
+
+bitflags! {
+    /// Where an inference was made, with lower values taking priority over higher ones
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub(super) struct InferencePriority: u32 {
+        const NONE = 0;
+        /// Inferred to a type parameter that is one constituent of a union
+        const NAKED_TYPE_VARIABLE = 1 << 0;
+        /// Inferred from the contextual type of the call to the return type
+        const RETURN_TYPE = 1 << 7;
+
+        /// Candidates of these priorities are combined into a union rather than reduced to
+        /// a common supertype
+        const PRIORITY_IMPLIES_COMBINATION = Self::RETURN_TYPE.bits();
+    }
+}
+
+/// The candidates collected for one type parameter
+#[derive(Debug, Clone)]
+pub(super) struct InferenceInfo {
+    type_parameter: TypeId,
+    candidates: Vec<TypeId>,
+    contra_candidates: Vec<TypeId>,
+    /// The priority of the candidates, or None before any were found
+    priority: Option<InferencePriority>,
+    /// Whether every candidate was inferred directly to the type parameter, rather than to
+    /// a type containing it
+    top_level: bool,
+    /// Whether the inferred type has been used, after which no candidates are added
+    is_fixed: bool,
+    inferred_type: Option<TypeId>,
+}
+
+/// The inferences for the type parameters of a signature
+#[derive(Debug, Clone)]
+pub(super) struct InferenceContext {
+    signature: Option<SignatureId>,
+    inferences: Vec<InferenceInfo>,
+}
+
+impl InferenceContext {
+    pub(super) fn new(type_parameters: &[TypeId], signature: Option<SignatureId>) -> Self {
+        InferenceContext {
+            signature,
+            inferences: type_parameters
+                .iter()
+                .map(|&type_parameter| InferenceInfo {
+                    type_parameter,
+                    candidates: Vec::new(),
+                    contra_candidates: Vec::new(),
+                    priority: None,
+                    top_level: true,
+                    is_fixed: false,
+                    inferred_type: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn type_parameters(&self) -> Vec<TypeId> {
+        self.inferences
+            .iter()
+            .map(|inference| inference.type_parameter)
+            .collect()
+    }
+}
+
+impl Checker {
+    /// Infers from `source` to the type parameters of the context occurring in `target`
+    pub(super) fn infer_types(
+        &mut self,
+        context: &mut InferenceContext,
+        source: TypeId,
+        target: TypeId,
+        priority: InferencePriority,
+    ) {
+        let mut inferrer = Inferrer {
+            checker: self,
+            context,
+            priority,
+            contravariant: false,
+            original_target: target,
+            visited: HashSet::new(),
+        };
+        inferrer.infer_from_types(source, target);
+    }
+
+    /// Gets the inferred type arguments of a context
+    pub(super) fn get_inferred_types(&mut self, context: &mut InferenceContext) -> Vec<TypeId> {
+        (0..context.inferences.len())
+            .map(|index| self.get_inferred_type(context, index))
+            .collect()
+    }
+
+    /// Maps the type parameters that have candidates to their current inferences, leaving
+    /// the rest as they are, for the contextual types of arguments still to be checked
+    pub(super) fn get_inference_mapper(
+        &mut self,
+        context: &mut InferenceContext,
+    ) -> Arc<TypeMapper> {
+        let mut sources = Vec::new();
+        let mut targets = Vec::new();
+        for index in 0..context.inferences.len() {
+            let inference = &context.inferences[index];
+            if inference.candidates.is_empty() && inference.contra_candidates.is_empty() {
+                continue;
+            }
+            sources.push(inference.type_parameter);
+            let inferred = self.get_inferred_type(context, index);
+            context.inferences[index].is_fixed = true;
+            targets.push(inferred);
+        }
+        TypeMapper::new(sources, targets)
+    }
+
+    fn get_inferred_type(&mut self, context: &mut InferenceContext, index: usize) -> TypeId {
+        if let Some(inferred) = context.inferences[index].inferred_type {
+            return inferred;
+        }
+        let inference = context.inferences[index].clone();
+        // Without a signature, as for the `infer` type parameters of a conditional type, the
+        // candidates are taken as they are
+        let (covariant, contravariant) = match context.signature {
+            Some(signature) => (
+                (!inference.candidates.is_empty())
+                    .then(|| self.get_covariant_inference(&inference, Some(signature))),
+                (!inference.contra_candidates.is_empty())
+                    .then(|| self.get_contravariant_inference(&inference)),
+            ),
+            None if !inference.candidates.is_empty() => {
+                (Some(self.get_union_type(&inference.candidates)), None)
+            }
+            None => (
+                None,
+                (!inference.contra_candidates.is_empty())
+                    .then(|| self.get_intersection_type(&inference.contra_candidates)),
+            ),
+        };
+        let inferred = match (covariant, contravariant) {
+            // A covariant inference is preferred when it fits the contravariant candidates
+            (Some(covariant), Some(contravariant)) => {
+                if !self.flags(covariant).contains(TypeFlags::NEVER)
+                    && inference
+                        .contra_candidates
+                        .iter()
+                        .any(|&candidate| self.is_type_assignable_to(covariant, candidate))
+                {
+                    covariant
+                } else {
+                    contravariant
+                }
+            }
+            (Some(inferred), None) | (None, Some(inferred)) => inferred,
+            (None, None) => match self.get_default_of_type_parameter(inference.type_parameter) {
+                Some(default) => {
+                    // Defaults may refer to other type parameters, which are being inferred
+                    context.inferences[index].inferred_type = Some(self.unknown_type);
+                    let mapper = self.create_inference_result_mapper(context);
+                    self.instantiate_type(default, &mapper)
+                }
+                None => self.unknown_type,
+            },
+        };
+        context.inferences[index].inferred_type = Some(inferred);
+
+        // An inference that violates the constraint is replaced by the constraint
+        let mut inferred = inferred;
+        if let Some(constraint) = self.get_constraint_of_type_parameter(inference.type_parameter) {
+            let mapper = self.create_inference_result_mapper(context);
+            let constraint = self.instantiate_type(constraint, &mapper);
+            if !self.is_type_assignable_to(inferred, constraint) {
+                inferred = constraint;
+                context.inferences[index].inferred_type = Some(inferred);
+            }
+        }
+        inferred
+    }
+
+    /// Maps every type parameter of a context to its inferred type
+    fn create_inference_result_mapper(
+        &mut self,
+        context: &mut InferenceContext,
+    ) -> Arc<TypeMapper> {
+        let sources = context.type_parameters();
+        let targets = (0..sources.len())
+            .map(|index| self.get_inferred_type(context, index))
+            .collect();
+        TypeMapper::new(sources, targets)
+    }
+
+    fn get_covariant_inference(
+        &mut self,
+        inference: &InferenceInfo,
+        signature: Option<SignatureId>,
+    ) -> TypeId {
+        // Literals are widened unless the type parameter is constrained to primitives or
+        // is returned as is, so `id(1)` is `1` but `box(1)` is `Box<number>`
+        let primitive_constraint = self.has_primitive_constraint(inference.type_parameter);
+        let widen_literal_types = !primitive_constraint
+            && inference.top_level
+            && (inference.is_fixed
+                || !signature.is_some_and(|signature| {
+                    let return_type = self.get_return_type_of_signature(signature);
+                    self.is_type_parameter_at_top_level(return_type, inference.type_parameter)
+                }));
+        let candidates: Vec<TypeId> = if widen_literal_types {
+            inference
+                .candidates
+                .iter()
+                .map(|&candidate| self.get_widened_literal_type(candidate))
+                .collect()
+        } else {
+            inference.candidates.clone()
+        };
+        let unwidened = if inference.priority.is_some_and(|priority| {
+            priority.intersects(InferencePriority::PRIORITY_IMPLIES_COMBINATION)
+        }) {
+            self.get_union_type(&candidates)
+        } else {
+            self.get_common_supertype(&candidates)
+        };
+        self.get_widened_type(unwidened)
+    }
+
+    fn get_contravariant_inference(&mut self, inference: &InferenceInfo) -> TypeId {
+        if inference.priority.is_some_and(|priority| {
+            priority.intersects(InferencePriority::PRIORITY_IMPLIES_COMBINATION)
+        }) {
+            return self.get_intersection_type(&inference.contra_candidates);
+        }
+        self.get_common_subtype(&inference.contra_candidates)
+    }
+
+    /// Gets the candidate every other candidate is a subtype of, or the first that is not a
+    /// subtype of those before it. Literal candidates of the same primitive are unioned, so
+    /// `f(1, 2)` infers `1 | 2` rather than rejecting the `2`
+    fn get_common_supertype(&mut self, types: &[TypeId]) -> TypeId {
+        if types.len() > 1 && self.literal_types_with_same_base_type(types) {
+            return self.get_union_type(types);
+        }
+        let mut supertype = types[0];
+        for &type_ in &types[1..] {
+            if self.is_type_subtype_of(supertype, type_) {
+                supertype = type_;
+            }
+        }
+        supertype
+    }
+
+    /// Whether all the (non-`never`) types are literals widening to the same base type
+    fn literal_types_with_same_base_type(&mut self, types: &[TypeId]) -> bool {
+        let mut common_base_type = None;
+        for &type_ in types {
+            if self.flags(type_).contains(TypeFlags::NEVER) {
+                continue;
+            }
+            let base_type = self.get_base_type_of_literal_type(type_);
+            if base_type == type_ || common_base_type.is_some_and(|common| common != base_type) {
+                return false;
+            }
+            common_base_type = Some(base_type);
+        }
+        true
+    }
+
+    fn get_common_subtype(&mut self, types: &[TypeId]) -> TypeId {
+        let mut subtype = types[0];
+        for &type_ in &types[1..] {
+            if self.is_type_subtype_of(type_, subtype) {
+                subtype = type_;
+            }
+        }
+        subtype
+    }
+
+    fn has_primitive_constraint(&mut self, type_parameter: TypeId) -> bool {
+        self.get_constraint_of_type_parameter(type_parameter)
+            .is_some_and(|constraint| {
+                self.maybe_type_of_kind(
+                    constraint,
+                    TypeFlags::PRIMITIVE
+                        | TypeFlags::INDEX
+                        | TypeFlags::TEMPLATE_LITERAL
+                        | TypeFlags::STRING_MAPPING,
+                )
+            })
+    }
+
+    /// Determines if a type is a type parameter, or a union or intersection containing it
+    fn is_type_parameter_at_top_level(&self, type_: TypeId, type_parameter: TypeId) -> bool {
+        type_ == type_parameter
+            || match &self.get_type(type_).data {
+                TypeData::UnionOrIntersection { types } => types
+                    .iter()
+                    .any(|&type_| self.is_type_parameter_at_top_level(type_, type_parameter)),
+                _ => false,
+            }
+    }
+}
+
+/// Walks a source type alongside a target type, collecting candidates for the type
+/// parameters of a context
+struct Inferrer<'a> {
+    checker: &'a mut Checker,
+    context: &'a mut InferenceContext,
+    priority: InferencePriority,
+    /// Whether the walk is in a parameter position, where candidates are contravariant
+    contravariant: bool,
+    original_target: TypeId,
+    /// The pairs of object types already walked, which recursive types reach again
+    visited: HashSet<(TypeId, TypeId)>,
+}
+
+impl Inferrer<'_> {
+    fn get_inference_index(&self, type_: TypeId) -> Option<usize> {
+        self.context
+            .inferences
+            .iter()
+            .position(|inference| inference.type_parameter == type_)
+    }
+
+    fn infer_from_types(&mut self, source: TypeId, target: TypeId) {
+        let checker = &mut *self.checker;
+        let target_flags = checker.flags(target);
+        if target_flags.contains(TypeFlags::TYPE_PARAMETER) {
+            if let Some(index) = self.get_inference_index(target) {
+                self.add_candidate(index, source, target);
+            }
+            return;
+        }
+        if target_flags.contains(TypeFlags::UNION) {
+            self.infer_to_union(source, target);
+            return;
+        }
+        let source_flags = checker.flags(source);
+        if source_flags.contains(TypeFlags::UNION) {
+            for source in checker.get_constituents(source) {
+                self.infer_from_types(source, target);
+            }
+            return;
+        }
+        if target_flags.contains(TypeFlags::INTERSECTION) {
+            for target in checker.get_constituents(target) {
+                self.infer_from_types(source, target);
+            }
+            return;
+        }
+        if target_flags.contains(TypeFlags::CONDITIONAL) {
+            // The source may match either branch
+            let true_type = checker.get_true_type_from_conditional_type(target);
+            let false_type = checker.get_false_type_from_conditional_type(target);
+            self.infer_from_types(source, true_type);
+            self.infer_from_types(source, false_type);
+            return;
+        }
+        if !checker.flags(source).contains(TypeFlags::OBJECT)
+            || !target_flags.contains(TypeFlags::OBJECT)
+        {
+            return;
+        }
+        if !self.visited.insert((source, target)) {
+            return;
+        }
+        self.infer_from_object_types(source, target);
+    }
+
+    fn add_candidate(&mut self, index: usize, candidate: TypeId, target: TypeId) {
+        let priority = self.priority;
+        let contravariant = self.contravariant;
+        let is_top_level = self
+            .checker
+            .is_type_parameter_at_top_level(self.original_target, target);
+        let inference = &mut self.context.inferences[index];
+        if inference.is_fixed {
+            return;
+        }
+        // Candidates of a higher priority replace those of a lower one
+        if inference
+            .priority
+            .is_none_or(|existing| priority < existing)
+        {
+            inference.candidates.clear();
+            inference.contra_candidates.clear();
+            inference.top_level = true;
+            inference.priority = Some(priority);
+        }
+        if inference.priority == Some(priority) {
+            let candidates = if contravariant {
+                &mut inference.contra_candidates
+            } else {
+                &mut inference.candidates
+            };
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+                inference.inferred_type = None;
+            }
+        }
+        if !priority.contains(InferencePriority::RETURN_TYPE) && !is_top_level {
+            inference.top_level = false;
+            inference.inferred_type = None;
+        }
+    }
+
+    /// Infers to the constituents of a union, giving lower priority to inferences to the
+    /// type parameters among them, which only get what the other constituents do not match
+    fn infer_to_union(&mut self, source: TypeId, target: TypeId) {
+        let targets = self.checker.get_constituents(target);
+        let sources: Vec<TypeId> = self
+            .checker
+            .get_constituents(source)
+            .into_iter()
+            .filter(|source| !targets.contains(source))
+            .collect();
+        if sources.is_empty() {
+            return;
+        }
+        let (naked, others): (Vec<TypeId>, Vec<TypeId>) = targets
+            .into_iter()
+            .partition(|&target| self.get_inference_index(target).is_some());
+        let source = self.checker.get_union_type(&sources);
+        for target in others {
+            self.infer_from_types(source, target);
+        }
+        if let [type_variable] = naked[..] {
+            let saved_priority = self.priority;
+            self.priority |= InferencePriority::NAKED_TYPE_VARIABLE;
+            self.infer_from_types(source, type_variable);
+            self.priority = saved_priority;
+        }
+    }
+
+    fn infer_from_object_types(&mut self, source: TypeId, target: TypeId) {
+        let checker = &mut *self.checker;
+        let source_reference = checker.get_reference_target(source).filter(|_| {
+            checker
+                .object_flags(source)
+                .contains(ObjectFlags::REFERENCE)
+        });
+        let target_reference = checker.get_reference_target(target).filter(|_| {
+            checker
+                .object_flags(target)
+                .contains(ObjectFlags::REFERENCE)
+        });
+        if let (Some((source_target, source_arguments)), Some((target_target, target_arguments))) =
+            (&source_reference, &target_reference)
+            && source_target == target_target
+        {
+            // Instantiations of the same generic type infer between their type arguments
+            let variances = self
+                .checker
+                .get_variances(*source_target)
+                .unwrap_or_default();
+            for (index, (&source, &target)) in
+                source_arguments.iter().zip(target_arguments).enumerate()
+            {
+                if variances.get(index) == Some(&Variance::Contravariant) {
+                    self.infer_from_contravariant_types(source, target);
+                } else {
+                    self.infer_from_types(source, target);
+                }
+            }
+            return;
+        }
+        if self.checker.is_tuple_type(source)
+            && self.checker.is_array_type(target)
+            && let Some((_, target_arguments)) = target_reference
+        {
+            // The elements of a tuple together infer to the element type of an array
+            let (_, source_arguments) = source_reference.unwrap();
+            let element_type = self.checker.get_union_type(&source_arguments);
+            self.infer_from_types(element_type, target_arguments[0]);
+            return;
+        }
+        self.infer_from_properties(source, target);
+        self.infer_from_signatures(source, target, false);
+        self.infer_from_signatures(source, target, true);
+        self.infer_from_index_types(source, target);
+    }
+
+    fn infer_from_contravariant_types(&mut self, source: TypeId, target: TypeId) {
+        self.contravariant = !self.contravariant;
+        self.infer_from_types(source, target);
+        self.contravariant = !self.contravariant;
+    }
+
+    fn infer_from_properties(&mut self, source: TypeId, target: TypeId) {
+        for target_property in self.checker.get_properties_of_type(target) {
+            let name = self.checker.symbol(target_property).name.clone();
+            if let Some(source_property) = self.checker.get_property_of_type(source, &name) {
+                let source_type = self.checker.get_type_of_symbol(source_property);
+                let target_type = self.checker.get_type_of_symbol(target_property);
+                self.infer_from_types(source_type, target_type);
+            }
+        }
+    }
+
+    /// Infers between the signatures of two types, pairing them from the last, since
+    /// overloads are declared from most to least specific
+    fn infer_from_signatures(&mut self, source: TypeId, target: TypeId, construct: bool) {
+        let source_signatures = self.checker.get_signatures_of_type(source, construct);
+        let target_signatures = self.checker.get_signatures_of_type(target, construct);
+        let count = source_signatures.len().min(target_signatures.len());
+        let source_signatures = &source_signatures[source_signatures.len() - count..];
+        let target_signatures = &target_signatures[target_signatures.len() - count..];
+        for (&source, &target) in source_signatures.iter().zip(target_signatures) {
+            self.infer_from_signature(source, target);
+        }
+    }
+
+    fn infer_from_signature(&mut self, source: SignatureId, target: SignatureId) {
+        let source_count = self.checker.get_signature(source).parameters.len();
+        let target_count = self.checker.get_signature(target).parameters.len();
+        for position in 0..source_count.min(target_count) {
+            if let (Some(source_type), Some(target_type)) = (
+                self.checker.get_type_at_position(source, position),
+                self.checker.get_type_at_position(target, position),
+            ) {
+                self.infer_from_contravariant_types(source_type, target_type);
+            }
+        }
+        let source_return = self.checker.get_return_type_of_signature(source);
+        let target_return = self.checker.get_return_type_of_signature(target);
+        self.infer_from_types(source_return, target_return);
+    }
+
+    fn infer_from_index_types(&mut self, source: TypeId, target: TypeId) {
+        let target_infos = self
+            .checker
+            .resolve_structured_type_members(target)
+            .index_infos
+            .clone();
+        for target_info in target_infos {
+            let source_type = match self
+                .checker
+                .get_applicable_index_info(source, target_info.key_type)
+            {
+                Some(source_info) => source_info.value_type,
+                // The properties of an object literal infer to a string index signature
+                None if target_info.key_type == self.checker.string_type
+                    && self
+                        .checker
+                        .object_flags(source)
+                        .contains(ObjectFlags::OBJECT_LITERAL) =>
+                {
+                    let types: Vec<TypeId> = self
+                        .checker
+                        .get_properties_of_type(source)
+                        .into_iter()
+                        .map(|property| self.checker.get_type_of_symbol(property))
+                        .collect();
+                    if types.is_empty() {
+                        continue;
+                    }
+                    self.checker.get_union_type(&types)
+                }
+                None => continue,
+            };
+            self.infer_from_types(source_type, target_info.value_type);
+        }
+    }
+}
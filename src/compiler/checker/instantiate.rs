@@ -0,0 +1,1277 @@
+//! Creation and interning of literal, union, intersection, reference, and tuple types, and
+//! instantiation of generic types
+
+use std::sync::Arc;
+
+use crate::compiler::ast::{CheckFlags, Node, SymbolFlags, SymbolId};
+use crate::compiler::diagnostics;
+
+use super::{
+    Checker, DeferredTypeReference, ElementFlags, IndexInfo, InterfaceType, LiteralValue, Number,
+    ObjectFlags, ResolvedMembers, Signature, SignatureId, TypeData, TypeFlags, TypeId, TypeMapper,
+};
+
+/// How deeply instantiations can nest before they are considered infinite
+const MAX_INSTANTIATION_DEPTH: u32 = 100;
+
+/// How many types checking one statement or expression can instantiate
+const MAX_INSTANTIATION_COUNT: usize = 5_000_000;
+
+impl Checker {
+    /// Gets the interned literal type of a value
+    pub(super) fn get_literal_type(&mut self, value: LiteralValue) -> TypeId {
+        if let Some(&type_) = self.literal_types.get(&value) {
+            return type_;
+        }
+        let flags = match value {
+            LiteralValue::String(_) => TypeFlags::STRING_LITERAL,
+            LiteralValue::Number(_) => TypeFlags::NUMBER_LITERAL,
+            LiteralValue::BigInt(_) => TypeFlags::BIG_INT_LITERAL,
+        };
+        let type_ = self.create_type(
+            flags,
+            ObjectFlags::NONE,
+            None,
+            TypeData::Literal {
+                value: value.clone(),
+            },
+        );
+        self.literal_types.insert(value, type_);
+        type_
+    }
+
+    /// Gets the fresh version of a string, number, or bigint literal type, the type of a
+    /// literal expression, which is widened where it initializes a mutable location
+    pub(super) fn get_fresh_type_of_literal_type(&mut self, type_: TypeId) -> TypeId {
+        let flags = self.flags(type_);
+        let literal =
+            TypeFlags::STRING_LITERAL | TypeFlags::NUMBER_LITERAL | TypeFlags::BIG_INT_LITERAL;
+        if !flags.intersects(literal)
+            || flags.contains(TypeFlags::ENUM_LITERAL)
+            || self.is_fresh_literal_type(type_)
+        {
+            return type_;
+        }
+        if let Some(&fresh) = self.fresh_literal_types.get(&type_) {
+            return fresh;
+        }
+        let data = self.get_type(type_).data.clone();
+        let fresh = self.create_type(flags, ObjectFlags::FRESH_LITERAL, None, data);
+        self.fresh_literal_types.insert(type_, fresh);
+        self.regular_types.insert(fresh, type_);
+        fresh
+    }
+
+    /// Gets the regular version of a fresh literal type, or of the fresh literals in a union,
+    /// which keeps its literals wherever it flows
+    pub(super) fn get_regular_type_of_literal_type(&mut self, type_: TypeId) -> TypeId {
+        if self.is_fresh_literal_type(type_) {
+            return self.get_regular_type_of_fresh_literal_type(type_);
+        }
+        if self.flags(type_).contains(TypeFlags::UNION)
+            && let TypeData::UnionOrIntersection { types } = &self.get_type(type_).data
+            && types.iter().any(|&type_| self.is_fresh_literal_type(type_))
+        {
+            let types = types.clone();
+            let regular: Vec<TypeId> = types
+                .into_iter()
+                .map(|type_| self.get_regular_type_of_literal_type(type_))
+                .collect();
+            return self.get_union_type(&regular);
+        }
+        type_
+    }
+
+    /// Gets the regular version of a literal type if it is fresh, leaving any other type,
+    /// including unions of fresh literals, as is
+    pub(super) fn get_regular_type_of_fresh_literal_type(&self, type_: TypeId) -> TypeId {
+        if self.is_fresh_literal_type(type_) {
+            self.regular_types[&type_]
+        } else {
+            type_
+        }
+    }
+
+    pub(super) fn is_fresh_literal_type(&self, type_: TypeId) -> bool {
+        self.flags(type_).intersects(TypeFlags::LITERAL)
+            && self
+                .object_flags(type_)
+                .contains(ObjectFlags::FRESH_LITERAL)
+    }
+
+    pub(super) fn get_string_literal_type(&mut self, value: &str) -> TypeId {
+        self.get_literal_type(LiteralValue::String(value.to_string()))
+    }
+
+    pub(super) fn get_number_literal_type(&mut self, value: f64) -> TypeId {
+        self.get_literal_type(LiteralValue::Number(Number(value)))
+    }
+
+    /// Creates an object type whose members are already known
+    pub(super) fn create_resolved_type(
+        &mut self,
+        symbol: Option<SymbolId>,
+        members: ResolvedMembers,
+    ) -> TypeId {
+        let members = Arc::new(members);
+        let type_ = self.create_type(
+            TypeFlags::OBJECT,
+            ObjectFlags::ANONYMOUS,
+            symbol,
+            TypeData::Resolved {
+                members: Arc::clone(&members),
+            },
+        );
+        self.resolved_members.insert(type_, members);
+        type_
+    }
+
+    /// Gets the union of `types`, flattening nested unions, removing duplicates and subsumed
+    /// literals, and interning the result
+    pub fn get_union_type(&mut self, types: &[TypeId]) -> TypeId {
+        let mut set = Vec::new();
+        let mut includes = TypeFlags::NONE;
+        self.add_types_to_union(&mut set, &mut includes, types);
+        if includes.intersects(TypeFlags::ANY) {
+            return if set.contains(&self.any_type) {
+                self.any_type
+            } else {
+                self.error_type
+            };
+        }
+        if includes.intersects(TypeFlags::UNKNOWN) {
+            return self.unknown_type;
+        }
+        // A property that may be missing or `undefined` may be `undefined`
+        if set.contains(&self.undefined_type) {
+            let missing_type = self.missing_type;
+            set.retain(|&type_| type_ != missing_type);
+        }
+        let reduced_literals = [
+            (TypeFlags::STRING, TypeFlags::STRING_LITERAL),
+            (TypeFlags::NUMBER, TypeFlags::NUMBER_LITERAL),
+            (TypeFlags::BIG_INT, TypeFlags::BIG_INT_LITERAL),
+            (TypeFlags::VOID, TypeFlags::UNDEFINED),
+        ];
+        for (primitive, literal) in reduced_literals {
+            if includes.contains(primitive) && includes.intersects(literal) {
+                set.retain(|&type_| !self.flags(type_).intersects(literal));
+            }
+        }
+        // A fresh literal adds nothing to a union with its regular version
+        if set.iter().any(|&type_| self.is_fresh_literal_type(type_)) {
+            let regular: Vec<TypeId> = set
+                .iter()
+                .filter(|&&type_| !self.is_fresh_literal_type(type_))
+                .copied()
+                .collect();
+            set.retain(|&type_| {
+                !self.is_fresh_literal_type(type_) || !regular.contains(&self.regular_types[&type_])
+            });
+        }
+        match set.len() {
+            0 => {
+                if includes.contains(TypeFlags::NULL) {
+                    self.null_type
+                } else if includes.contains(TypeFlags::UNDEFINED) {
+                    self.undefined_type
+                } else {
+                    self.never_type
+                }
+            }
+            1 => set[0],
+            _ => {
+                if let Some(&type_) = self.union_types.get(&set) {
+                    return type_;
+                }
+                let type_ = self.create_type(
+                    TypeFlags::UNION,
+                    ObjectFlags::NONE,
+                    None,
+                    TypeData::UnionOrIntersection { types: set.clone() },
+                );
+                self.union_types.insert(set, type_);
+                type_
+            }
+        }
+    }
+
+    fn add_types_to_union(
+        &self,
+        set: &mut Vec<TypeId>,
+        includes: &mut TypeFlags,
+        types: &[TypeId],
+    ) {
+        for &type_ in types {
+            let flags = self.flags(type_);
+            if flags.contains(TypeFlags::UNION) {
+                if let TypeData::UnionOrIntersection { types } = &self.get_type(type_).data {
+                    self.add_types_to_union(set, includes, types);
+                }
+                continue;
+            }
+            *includes |= flags;
+            if flags.contains(TypeFlags::NEVER) {
+                continue;
+            }
+            // Without strict null checks, null and undefined are in the domain of every type
+            if !self.options.strict_null_checks && flags.intersects(TypeFlags::NULLABLE) {
+                continue;
+            }
+            if let Err(index) = set.binary_search(&type_) {
+                set.insert(index, type_);
+            }
+        }
+    }
+
+    /// Gets the intersection of `types`, distributing it over unions and reducing it to
+    /// `never` when the constituents cannot overlap
+    pub fn get_intersection_type(&mut self, types: &[TypeId]) -> TypeId {
+        let mut set = Vec::new();
+        let mut includes = TypeFlags::NONE;
+        self.add_types_to_intersection(&mut set, &mut includes, types);
+        if includes.contains(TypeFlags::NEVER) {
+            return self.never_type;
+        }
+        if includes.intersects(TypeFlags::ANY) {
+            return if set.contains(&self.any_type) {
+                self.any_type
+            } else {
+                self.error_type
+            };
+        }
+        if !self.options.strict_null_checks && includes.intersects(TypeFlags::NULLABLE) {
+            return if includes.contains(TypeFlags::UNDEFINED) {
+                self.undefined_type
+            } else {
+                self.null_type
+            };
+        }
+        let domains = [
+            TypeFlags::STRING_LIKE,
+            TypeFlags::NUMBER_LIKE,
+            TypeFlags::BIG_INT_LIKE,
+            TypeFlags::BOOLEAN_LIKE,
+            TypeFlags::ES_SYMBOL_LIKE,
+            TypeFlags::VOID_LIKE,
+            TypeFlags::NULL,
+        ];
+        let primitive_domains = domains
+            .iter()
+            .filter(|&&domain| includes.intersects(domain))
+            .count();
+        if primitive_domains > 1
+            || (primitive_domains == 1 && includes.contains(TypeFlags::NON_PRIMITIVE))
+        {
+            return self.never_type;
+        }
+        // A literal is a subtype of its primitive, and distinct literals do not overlap
+        let literal_primitives = [
+            (TypeFlags::STRING, TypeFlags::STRING_LITERAL),
+            (TypeFlags::NUMBER, TypeFlags::NUMBER_LITERAL),
+            (TypeFlags::BIG_INT, TypeFlags::BIG_INT_LITERAL),
+        ];
+        for (primitive, literal) in literal_primitives {
+            if includes.intersects(literal) {
+                set.retain(|&type_| !self.flags(type_).contains(primitive));
+            }
+        }
+        let units = set
+            .iter()
+            .filter(|&&type_| self.flags(type_).intersects(TypeFlags::UNIT))
+            .count();
+        if units > 1 {
+            return self.never_type;
+        }
+        match set.len() {
+            0 => self.unknown_type,
+            1 => set[0],
+            _ => {
+                if let Some(union_index) = set
+                    .iter()
+                    .position(|&type_| self.flags(type_).contains(TypeFlags::UNION))
+                {
+                    // Distributes over the first union: (A | B) & C = (A & C) | (B & C)
+                    let union = set[union_index];
+                    let TypeData::UnionOrIntersection {
+                        types: constituents,
+                    } = self.get_type(union).data.clone()
+                    else {
+                        unreachable!();
+                    };
+                    let mut results = Vec::new();
+                    for constituent in constituents {
+                        let mut types = set.clone();
+                        types[union_index] = constituent;
+                        results.push(self.get_intersection_type(&types));
+                    }
+                    return self.get_union_type(&results);
+                }
+                if let Some(&type_) = self.intersection_types.get(&set) {
+                    return type_;
+                }
+                let type_ = self.create_type(
+                    TypeFlags::INTERSECTION,
+                    ObjectFlags::NONE,
+                    None,
+                    TypeData::UnionOrIntersection { types: set.clone() },
+                );
+                self.intersection_types.insert(set, type_);
+                type_
+            }
+        }
+    }
+
+    fn add_types_to_intersection(
+        &self,
+        set: &mut Vec<TypeId>,
+        includes: &mut TypeFlags,
+        types: &[TypeId],
+    ) {
+        for &type_ in types {
+            let flags = self.flags(type_);
+            if flags.contains(TypeFlags::INTERSECTION) {
+                if let TypeData::UnionOrIntersection { types } = &self.get_type(type_).data {
+                    self.add_types_to_intersection(set, includes, types);
+                }
+                continue;
+            }
+            *includes |= flags;
+            if flags.contains(TypeFlags::UNKNOWN) {
+                continue;
+            }
+            if type_ == self.empty_object_type && !set.is_empty() {
+                continue;
+            }
+            if let Err(index) = set.binary_search(&type_) {
+                set.insert(index, type_);
+            }
+        }
+    }
+
+    /// Gets a copy of a union, intersection, or object type that prints by the name of the type
+    /// alias it was written through
+    pub(super) fn get_type_with_alias(
+        &mut self,
+        type_: TypeId,
+        alias_symbol: SymbolId,
+        alias_type_arguments: Vec<TypeId>,
+    ) -> TypeId {
+        let flags = self.flags(type_);
+        let is_aliasable = flags
+            .intersects(TypeFlags::UNION | TypeFlags::INTERSECTION | TypeFlags::CONDITIONAL)
+            || (flags.contains(TypeFlags::OBJECT)
+                && self
+                    .object_flags(type_)
+                    .intersects(ObjectFlags::ANONYMOUS | ObjectFlags::MAPPED)
+                && !self
+                    .object_flags(type_)
+                    .contains(ObjectFlags::PRIMITIVE_UNION));
+        if !is_aliasable || type_ == self.boolean_type {
+            return type_;
+        }
+        let mut aliased = self.get_type(type_).clone();
+        if aliased.alias_symbol.is_some() {
+            return type_;
+        }
+        aliased.alias_symbol = Some(alias_symbol);
+        aliased.alias_type_arguments = alias_type_arguments;
+        let id = TypeId(self.types.len() as u32);
+        self.types.push(aliased);
+        if let Some(members) = self.resolved_members.get(&type_).cloned() {
+            self.resolved_members.insert(id, members);
+        }
+        id
+    }
+
+    /// Gets the interned instantiation of a generic class, interface, or tuple type
+    pub(super) fn create_type_reference(
+        &mut self,
+        target: TypeId,
+        type_arguments: Vec<TypeId>,
+    ) -> TypeId {
+        if let TypeData::Interface(interface) = &self.get_type(target).data
+            && interface.type_parameters() == type_arguments
+        {
+            // A generic type applied to its own type parameters is the type itself
+            return target;
+        }
+        let key = (target, type_arguments);
+        if let Some(&type_) = self.type_references.get(&key) {
+            return type_;
+        }
+        let symbol = self.get_type(target).symbol;
+        let type_ = self.create_type(
+            TypeFlags::OBJECT,
+            ObjectFlags::REFERENCE,
+            symbol,
+            TypeData::Reference {
+                target,
+                type_arguments: key.1.clone(),
+            },
+        );
+        self.type_references.insert(key, type_);
+        type_
+    }
+
+    /// Creates a reference to an array or tuple type written in a type alias whose type
+    /// arguments are resolved from `node` on first use, so that the alias may refer to itself
+    /// through its elements
+    pub(super) fn create_deferred_type_reference(
+        &mut self,
+        target: TypeId,
+        node: &Arc<Node>,
+        mapper: Option<Arc<TypeMapper>>,
+        alias: Option<(SymbolId, Vec<TypeId>)>,
+    ) -> TypeId {
+        // The type arguments are errors until resolved, which only a circular reference sees
+        let count = match &self.get_type(target).data {
+            TypeData::Interface(interface) => interface.type_parameters().len(),
+            _ => 0,
+        };
+        let symbol = self.get_type(target).symbol;
+        let type_ = self.create_type(
+            TypeFlags::OBJECT,
+            ObjectFlags::REFERENCE,
+            symbol,
+            TypeData::Reference {
+                target,
+                type_arguments: vec![self.error_type; count],
+            },
+        );
+        if let Some((alias_symbol, alias_type_arguments)) = alias {
+            let deferred = &mut self.types[type_.index()];
+            deferred.alias_symbol = Some(alias_symbol);
+            deferred.alias_type_arguments = alias_type_arguments;
+        }
+        self.deferred_type_references.insert(
+            type_,
+            DeferredTypeReference {
+                node: Arc::clone(node),
+                mapper,
+                resolved: false,
+            },
+        );
+        type_
+    }
+
+    /// Resolves the type arguments of a deferred type reference, if it is one that has not
+    /// been resolved yet
+    pub(super) fn resolve_deferred_type_arguments(&mut self, type_: TypeId) {
+        let Some(deferred) = self.deferred_type_references.get_mut(&type_) else {
+            return;
+        };
+        if deferred.resolved {
+            return;
+        }
+        deferred.resolved = true;
+        let deferred = deferred.clone();
+        let mut arguments = self.get_type_arguments_from_node(&deferred.node);
+        if let Some(mapper) = &deferred.mapper {
+            for argument in &mut arguments {
+                *argument = self.instantiate_type(*argument, mapper);
+            }
+        }
+        if let TypeData::Reference { type_arguments, .. } = &mut self.types[type_.index()].data {
+            *type_arguments = arguments;
+        }
+    }
+
+    /// Instantiates a deferred type reference by the type parameters in scope at its node,
+    /// leaving its type arguments to be resolved on first use
+    fn instantiate_deferred_type_reference(
+        &mut self,
+        type_: TypeId,
+        mapper: &Arc<TypeMapper>,
+    ) -> TypeId {
+        let deferred = self.deferred_type_references[&type_].clone();
+        let Some(target) = self.node_links(&deferred.node).resolved_type else {
+            return type_;
+        };
+        let outer_type_parameters = self.get_outer_type_parameters(&deferred.node);
+        if outer_type_parameters.is_empty() {
+            return type_;
+        }
+        let mut arguments = Vec::with_capacity(outer_type_parameters.len());
+        for &parameter in &outer_type_parameters {
+            let current = match &deferred.mapper {
+                Some(existing) => self.map_type(parameter, existing),
+                None => parameter,
+            };
+            arguments.push(self.instantiate_type(current, mapper));
+        }
+        if arguments == outer_type_parameters {
+            return target;
+        }
+        let key = (target, arguments);
+        if let Some(&instantiation) = self.instantiations.get(&key) {
+            return instantiation;
+        }
+        let (reference_target, _) = self
+            .get_reference_target(target)
+            .unwrap_or((target, vec![]));
+        let alias = match self.get_type(target).alias_symbol {
+            Some(alias_symbol) => {
+                let mut alias_type_arguments = Vec::new();
+                for argument in self.get_type(target).alias_type_arguments.clone() {
+                    alias_type_arguments.push(self.instantiate_type(argument, mapper));
+                }
+                Some((alias_symbol, alias_type_arguments))
+            }
+            None => None,
+        };
+        let instantiation = self.create_deferred_type_reference(
+            reference_target,
+            &deferred.node,
+            Some(TypeMapper::new(outer_type_parameters, key.1.clone())),
+            alias,
+        );
+        self.instantiations.insert(key, instantiation);
+        instantiation
+    }
+
+    /// Gets the generic target and the type arguments of a type reference, treating a generic
+    /// class or interface as applied to its own type parameters
+    pub(super) fn get_reference_target(&mut self, type_: TypeId) -> Option<(TypeId, Vec<TypeId>)> {
+        self.resolve_deferred_type_arguments(type_);
+        match &self.get_type(type_).data {
+            TypeData::Reference {
+                target,
+                type_arguments,
+            } => Some((*target, type_arguments.clone())),
+            TypeData::Interface(interface) if interface.is_generic() => {
+                Some((type_, interface.type_parameters()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Creates the mapper from the type parameters of a generic type to type arguments
+    pub(super) fn create_type_reference_mapper(
+        &mut self,
+        target: TypeId,
+        type_arguments: &[TypeId],
+    ) -> Arc<TypeMapper> {
+        let type_parameters = match &self.get_type(target).data {
+            TypeData::Interface(interface) => interface.type_parameters(),
+            _ => Vec::new(),
+        };
+        let mut targets = type_arguments.to_vec();
+        targets.resize(type_parameters.len(), self.error_type);
+        TypeMapper::new(type_parameters, targets)
+    }
+
+    /// Gets `T[]`
+    pub(super) fn create_array_type(&mut self, element_type: TypeId) -> TypeId {
+        let array = self.get_global_array_type();
+        self.create_type_reference(array, vec![element_type])
+    }
+
+    /// Gets `readonly T[]`
+    pub(super) fn create_readonly_array_type(&mut self, element_type: TypeId) -> TypeId {
+        let array = self.get_global_readonly_array_type();
+        self.create_type_reference(array, vec![element_type])
+    }
+
+    /// Gets the global `Array<T>` type, or a stand-in with only `length` and a number index
+    /// signature when the program does not declare it
+    pub(super) fn get_global_array_type(&mut self) -> TypeId {
+        self.get_global_array_like_type("Array", false)
+    }
+
+    /// Gets the global `ReadonlyArray<T>` type, or a stand-in like that of `Array<T>` whose
+    /// index signature is readonly when the program does not declare it
+    pub(super) fn get_global_readonly_array_type(&mut self) -> TypeId {
+        self.get_global_array_like_type("ReadonlyArray", true)
+    }
+
+    fn get_global_array_like_type(&mut self, name: &'static str, readonly: bool) -> TypeId {
+        if let Some(&array) = self.global_types.get(name) {
+            return array;
+        }
+        let declared = self.get_declared_global_type(name, 1);
+        let array = match declared {
+            Some(array) => array,
+            None => {
+                let symbol = self
+                    .bindings
+                    .symbols
+                    .alloc("T", SymbolFlags::TYPE_PARAMETER | SymbolFlags::TRANSIENT);
+                let type_parameter = self.create_type(
+                    TypeFlags::TYPE_PARAMETER,
+                    ObjectFlags::NONE,
+                    Some(symbol),
+                    TypeData::TypeParameter {
+                        constraint: Some(None),
+                        is_this_type: false,
+                    },
+                );
+                let array = self.create_fallback_type(name, vec![type_parameter]);
+                let length = self.create_transient_property("length", self.number_type);
+                if readonly {
+                    self.bindings.symbols[length].check_flags |= CheckFlags::READONLY;
+                }
+                let mut members = ResolvedMembers::default();
+                members.add_property("length", length);
+                members.index_infos.push(IndexInfo {
+                    key_type: self.number_type,
+                    value_type: type_parameter,
+                    is_readonly: readonly,
+                    declaration: None,
+                });
+                self.resolved_members.insert(array, Arc::new(members));
+                array
+            }
+        };
+        self.global_types.insert(name, array);
+        array
+    }
+
+    /// Gets a global interface such as `String` or `Function`, or an empty stand-in whose
+    /// missing members are `any` when the program does not declare it
+    pub(super) fn get_global_type(&mut self, name: &'static str) -> TypeId {
+        if let Some(&type_) = self.global_types.get(name) {
+            return type_;
+        }
+        let type_ = match self.get_declared_global_type(name, 0) {
+            Some(type_) => type_,
+            None => {
+                let type_ = self.create_fallback_type(name, Vec::new());
+                self.resolved_members
+                    .insert(type_, Arc::new(ResolvedMembers::default()));
+                type_
+            }
+        };
+        self.global_types.insert(name, type_);
+        type_
+    }
+
+    pub(super) fn get_declared_global_type(&mut self, name: &str, arity: usize) -> Option<TypeId> {
+        let symbol = *self.globals.get(name)?;
+        if !self
+            .symbol(symbol)
+            .flags
+            .intersects(SymbolFlags::CLASS | SymbolFlags::INTERFACE)
+        {
+            return None;
+        }
+        let type_ = self.get_declared_type_of_symbol(symbol);
+        match &self.get_type(type_).data {
+            TypeData::Interface(interface) if interface.type_parameters().len() == arity => {
+                Some(type_)
+            }
+            _ => None,
+        }
+    }
+
+    fn create_fallback_type(&mut self, name: &str, type_parameters: Vec<TypeId>) -> TypeId {
+        let symbol = self
+            .bindings
+            .symbols
+            .alloc(name, SymbolFlags::INTERFACE | SymbolFlags::TRANSIENT);
+        let object_flags = if type_parameters.is_empty() {
+            ObjectFlags::INTERFACE
+        } else {
+            ObjectFlags::INTERFACE | ObjectFlags::REFERENCE
+        };
+        let type_ = self.create_type(
+            TypeFlags::OBJECT,
+            object_flags,
+            Some(symbol),
+            TypeData::Interface(InterfaceType {
+                local_type_parameters: type_parameters,
+                ..InterfaceType::default()
+            }),
+        );
+        self.symbol_links(symbol).declared_type = Some(type_);
+        self.fallback_types.insert(type_);
+        type_
+    }
+
+    /// Creates a property symbol of the given type that belongs to no declaration
+    pub(super) fn create_transient_property(&mut self, name: &str, type_: TypeId) -> SymbolId {
+        let symbol = self
+            .bindings
+            .symbols
+            .alloc(name, SymbolFlags::PROPERTY | SymbolFlags::TRANSIENT);
+        self.symbol_links(symbol).type_ = Some(type_);
+        symbol
+    }
+
+    /// Determines if a type is a stand-in for a global type the program does not declare, or
+    /// an instantiation of one
+    pub(super) fn is_fallback_type(&self, type_: TypeId) -> bool {
+        let target = match &self.get_type(type_).data {
+            TypeData::Reference { target, .. } => *target,
+            _ => type_,
+        };
+        self.fallback_types.contains(&target)
+    }
+
+    /// Determines if a type is an instantiation of the global `Array` or `ReadonlyArray` type
+    pub(super) fn is_array_type(&self, type_: TypeId) -> bool {
+        self.is_global_type_reference(type_, "Array")
+            || self.is_global_type_reference(type_, "ReadonlyArray")
+    }
+
+    /// Determines if a type is an instantiation of the global `ReadonlyArray` type
+    pub(super) fn is_readonly_array_type(&self, type_: TypeId) -> bool {
+        self.is_global_type_reference(type_, "ReadonlyArray")
+    }
+
+    /// Determines if a type is an instantiation of the global generic type `name`, which may
+    /// be referenced before the checker has looked it up itself
+    fn is_global_type_reference(&self, type_: TypeId, name: &'static str) -> bool {
+        let TypeData::Reference { target, .. } = &self.get_type(type_).data else {
+            return false;
+        };
+        self.global_types.get(name) == Some(target)
+            || self
+                .get_type(*target)
+                .symbol
+                .is_some_and(|symbol| self.globals.get(name) == Some(&symbol))
+    }
+
+    /// Determines if a type is a tuple type
+    pub(super) fn is_tuple_type(&self, type_: TypeId) -> bool {
+        match &self.get_type(type_).data {
+            TypeData::Reference { target, .. } => {
+                self.object_flags(*target).contains(ObjectFlags::TUPLE)
+            }
+            _ => false,
+        }
+    }
+
+    /// Gets the tuple type with the given element types
+    pub(super) fn create_tuple_type(
+        &mut self,
+        element_types: Vec<TypeId>,
+        element_flags: Vec<ElementFlags>,
+        readonly: bool,
+    ) -> TypeId {
+        let target = self.get_tuple_target(element_flags, readonly);
+        self.create_type_reference(target, element_types)
+    }
+
+    /// Gets the generic target shared by tuple types with the same element kinds
+    pub(super) fn get_tuple_target(
+        &mut self,
+        element_flags: Vec<ElementFlags>,
+        readonly: bool,
+    ) -> TypeId {
+        let key = (element_flags, readonly);
+        if let Some(&target) = self.tuple_targets.get(&key) {
+            return target;
+        }
+        let mut type_parameters = Vec::new();
+        for index in 0..key.0.len() {
+            let symbol = self.bindings.symbols.alloc(
+                format!("T{index}"),
+                SymbolFlags::TYPE_PARAMETER | SymbolFlags::TRANSIENT,
+            );
+            type_parameters.push(self.create_type(
+                TypeFlags::TYPE_PARAMETER,
+                ObjectFlags::NONE,
+                Some(symbol),
+                TypeData::TypeParameter {
+                    constraint: Some(None),
+                    is_this_type: false,
+                },
+            ));
+        }
+        let target = self.create_type(
+            TypeFlags::OBJECT,
+            ObjectFlags::TUPLE | ObjectFlags::REFERENCE,
+            None,
+            TypeData::Interface(InterfaceType {
+                local_type_parameters: type_parameters.clone(),
+                element_flags: key.0.clone(),
+                readonly,
+                ..InterfaceType::default()
+            }),
+        );
+
+        let mut members = ResolvedMembers::default();
+        let fixed_length = key
+            .0
+            .iter()
+            .all(|flags| flags.contains(ElementFlags::REQUIRED));
+        for (index, (&flags, &parameter)) in key.0.iter().zip(&type_parameters).enumerate() {
+            if !flags.intersects(ElementFlags::FIXED) {
+                continue;
+            }
+            let property = self.create_transient_property(&index.to_string(), parameter);
+            if flags.contains(ElementFlags::OPTIONAL) {
+                self.bindings.symbols[property].flags |= SymbolFlags::OPTIONAL;
+            }
+            if readonly {
+                self.bindings.symbols[property].check_flags |= CheckFlags::READONLY;
+            }
+            members.add_property(&index.to_string(), property);
+        }
+        let length_type = if fixed_length {
+            self.get_number_literal_type(key.0.len() as f64)
+        } else {
+            self.number_type
+        };
+        let length = self.create_transient_property("length", length_type);
+        members.add_property("length", length);
+        let element_type = self.get_union_type(&type_parameters);
+        let array = self.create_array_type(element_type);
+        let array_members = self.resolve_structured_type_members(array);
+        for &property in &array_members.properties {
+            let name = self.symbol(property).name.clone();
+            if !members.members.contains_key(&name) {
+                members.add_property(&name, property);
+            }
+        }
+        members.call_signatures = array_members.call_signatures.clone();
+        members.construct_signatures = array_members.construct_signatures.clone();
+        members.index_infos = array_members.index_infos.clone();
+        self.resolved_members.insert(target, Arc::new(members));
+        self.resolved_base_types.insert(target, vec![array]);
+        self.tuple_targets.insert(key, target);
+        target
+    }
+
+    /// Widens fresh literal types to their primitive types, and enum and boolean literal types
+    /// to their enums and `boolean`, as for the type of a mutable location
+    pub(super) fn get_widened_literal_type(&mut self, type_: TypeId) -> TypeId {
+        self.widen_literal_type(type_, false)
+    }
+
+    /// Widens literal types to their primitive types whether or not they are fresh, and enum
+    /// and boolean literal types to their enums and `boolean`
+    pub(super) fn get_base_type_of_literal_type(&mut self, type_: TypeId) -> TypeId {
+        self.widen_literal_type(type_, true)
+    }
+
+    fn widen_literal_type(&mut self, type_: TypeId, widen_regular: bool) -> TypeId {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::ENUM_LITERAL) {
+            self.get_base_type_of_enum_literal_type(type_)
+        } else if flags.contains(TypeFlags::BOOLEAN_LITERAL) {
+            self.boolean_type
+        } else if flags.intersects(TypeFlags::LITERAL)
+            && !widen_regular
+            && !self.is_fresh_literal_type(type_)
+        {
+            type_
+        } else if flags.contains(TypeFlags::STRING_LITERAL) {
+            self.string_type
+        } else if flags.contains(TypeFlags::NUMBER_LITERAL) {
+            self.number_type
+        } else if flags.contains(TypeFlags::BIG_INT_LITERAL) {
+            self.bigint_type
+        } else if flags.contains(TypeFlags::UNION) && type_ != self.boolean_type {
+            let TypeData::UnionOrIntersection { types } = self.get_type(type_).data.clone() else {
+                return type_;
+            };
+            let widened: Vec<TypeId> = types
+                .into_iter()
+                .map(|type_| self.widen_literal_type(type_, widen_regular))
+                .collect();
+            self.get_union_type(&widened)
+        } else {
+            type_
+        }
+    }
+
+    /// Widens `null` and `undefined` to `any` when they are not checked strictly, including in
+    /// the properties of object literals
+    pub(super) fn get_widened_type(&mut self, type_: TypeId) -> TypeId {
+        let flags = self.flags(type_);
+        if !self.options.strict_null_checks && flags.intersects(TypeFlags::NULLABLE) {
+            return self.any_type;
+        }
+        if self
+            .object_flags(type_)
+            .contains(ObjectFlags::OBJECT_LITERAL)
+        {
+            return self.get_widened_object_literal_type(type_);
+        }
+        if flags.contains(TypeFlags::UNION)
+            && let TypeData::UnionOrIntersection { types } = &self.get_type(type_).data
+            && types.iter().any(|&type_| {
+                self.object_flags(type_)
+                    .contains(ObjectFlags::OBJECT_LITERAL)
+            })
+        {
+            let types = types.clone();
+            let widened: Vec<TypeId> = types
+                .into_iter()
+                .map(|type_| self.get_widened_type(type_))
+                .collect();
+            return self.get_union_type(&widened);
+        }
+        type_
+    }
+
+    /// Gets the type of an object literal without its freshness, which is what subjects it
+    /// to excess property checks
+    pub(super) fn get_regular_type_of_object_literal(&mut self, type_: TypeId) -> TypeId {
+        if !self
+            .object_flags(type_)
+            .contains(ObjectFlags::FRESH_LITERAL)
+        {
+            return type_;
+        }
+        if let Some(&regular) = self.regular_types.get(&type_) {
+            return regular;
+        }
+        let source = self.get_type(type_);
+        let (flags, object_flags, symbol, data) = (
+            source.flags,
+            source.object_flags - ObjectFlags::FRESH_LITERAL,
+            source.symbol,
+            source.data.clone(),
+        );
+        let regular = self.create_type(flags, object_flags, symbol, data);
+        self.regular_types.insert(type_, regular);
+        regular
+    }
+
+    fn get_widened_object_literal_type(&mut self, type_: TypeId) -> TypeId {
+        let members = self.resolve_structured_type_members(type_);
+        let mut widened = ResolvedMembers {
+            index_infos: members.index_infos.clone(),
+            ..ResolvedMembers::default()
+        };
+        let mut changed = false;
+        for &property in &members.properties {
+            let property_type = self.get_type_of_symbol(property);
+            let widened_type = self.get_widened_type(property_type);
+            let name = self.symbol(property).name.clone();
+            if widened_type == property_type {
+                widened.add_property(&name, property);
+            } else {
+                changed = true;
+                let symbol = self.create_transient_property(&name, widened_type);
+                let original = self.symbol(property).clone();
+                let symbol_data = &mut self.bindings.symbols[symbol];
+                symbol_data.flags |= original.flags & SymbolFlags::OPTIONAL;
+                symbol_data.check_flags |= original.check_flags & CheckFlags::READONLY;
+                symbol_data.declarations = original.declarations;
+                symbol_data.value_declaration = original.value_declaration;
+                widened.add_property(&name, symbol);
+            }
+        }
+        if !changed {
+            return self.get_regular_type_of_object_literal(type_);
+        }
+        let symbol = self.get_type(type_).symbol;
+        let result = self.create_resolved_type(symbol, widened);
+        self.types[result.index()].object_flags |= ObjectFlags::OBJECT_LITERAL;
+        result
+    }
+
+    /// Maps a type through a mapper if it is one of the mapper's sources
+    pub(super) fn map_type(&mut self, type_: TypeId, mapper: &Arc<TypeMapper>) -> TypeId {
+        match mapper.as_ref() {
+            TypeMapper::Array { sources, targets } => sources
+                .iter()
+                .position(|&source| source == type_)
+                .map_or(type_, |index| targets[index]),
+            TypeMapper::Composite(first, second) => {
+                let mapped = self.map_type(type_, first);
+                self.instantiate_type(mapped, second)
+            }
+            TypeMapper::Merged(first, second) => {
+                let mapped = self.map_type(type_, first);
+                if mapped == type_ {
+                    self.map_type(type_, second)
+                } else {
+                    mapped
+                }
+            }
+            TypeMapper::Permissive => {
+                if self.flags(type_).contains(TypeFlags::TYPE_PARAMETER) {
+                    self.any_type
+                } else {
+                    type_
+                }
+            }
+            TypeMapper::Restrictive => self.get_restrictive_type_parameter(type_),
+        }
+    }
+
+    /// Instantiates a type, replacing the type parameters the mapper maps
+    pub(super) fn instantiate_type(&mut self, type_: TypeId, mapper: &Arc<TypeMapper>) -> TypeId {
+        let flags = self.flags(type_);
+        if !flags.intersects(
+            TypeFlags::TYPE_PARAMETER
+                | TypeFlags::OBJECT
+                | TypeFlags::UNION_OR_INTERSECTION
+                | TypeFlags::INDEX
+                | TypeFlags::INDEXED_ACCESS
+                | TypeFlags::CONDITIONAL,
+        ) {
+            return type_;
+        }
+        if self.instantiation_depth >= MAX_INSTANTIATION_DEPTH {
+            self.instantiation_depth_exceeded = true;
+            return self.error_type;
+        }
+        if self.current_instantiation_count >= MAX_INSTANTIATION_COUNT {
+            self.report_excessive_instantiation();
+            return self.error_type;
+        }
+        self.instantiation_depth += 1;
+        self.instantiation_count += 1;
+        self.current_instantiation_count += 1;
+        let result = self.instantiate_type_worker(type_, mapper);
+        self.instantiation_depth -= 1;
+        // The limit is reported once, for the outermost instantiation that hit it
+        if self.instantiation_depth == 0 && self.instantiation_depth_exceeded {
+            self.instantiation_depth_exceeded = false;
+            self.report_excessive_instantiation();
+        }
+        result
+    }
+
+    pub(super) fn report_excessive_instantiation(&mut self) {
+        let Some(node) = self.current_node.clone() else {
+            return;
+        };
+        self.error(
+            &node,
+            diagnostics::TYPE_INSTANTIATION_IS_EXCESSIVELY_DEEP_AND_POSSIBLY_INFINITE_2589,
+            &[],
+        );
+    }
+
+    fn instantiate_type_worker(&mut self, type_: TypeId, mapper: &Arc<TypeMapper>) -> TypeId {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::TYPE_PARAMETER) {
+            return self.map_type(type_, mapper);
+        }
+        let (alias_symbol, alias_arguments) = {
+            let type_data = self.get_type(type_);
+            (
+                type_data.alias_symbol,
+                type_data.alias_type_arguments.clone(),
+            )
+        };
+        if let TypeData::Conditional { .. } = self.get_type(type_).data {
+            let mut arguments = Vec::with_capacity(alias_arguments.len());
+            for argument in alias_arguments {
+                arguments.push(self.instantiate_type(argument, mapper));
+            }
+            let alias = alias_symbol.map(|symbol| (symbol, arguments));
+            return self.get_conditional_type_instantiation(type_, mapper, alias);
+        }
+        let result = match self.get_type(type_).data.clone() {
+            TypeData::UnionOrIntersection { types } => {
+                let mut instantiated = Vec::with_capacity(types.len());
+                for type_ in types {
+                    instantiated.push(self.instantiate_type(type_, mapper));
+                }
+                if flags.contains(TypeFlags::UNION) {
+                    self.get_union_type(&instantiated)
+                } else {
+                    self.get_intersection_type(&instantiated)
+                }
+            }
+            TypeData::Reference { .. } if self.deferred_type_references.contains_key(&type_) => {
+                self.instantiate_deferred_type_reference(type_, mapper)
+            }
+            TypeData::Reference {
+                target,
+                type_arguments,
+            } => {
+                let mut instantiated = Vec::with_capacity(type_arguments.len());
+                for type_ in type_arguments {
+                    instantiated.push(self.instantiate_type(type_, mapper));
+                }
+                self.create_type_reference(target, instantiated)
+            }
+            TypeData::Interface(interface) if interface.is_generic() => {
+                let mut instantiated = Vec::new();
+                for type_ in interface.type_parameters() {
+                    instantiated.push(self.instantiate_type(type_, mapper));
+                }
+                self.create_type_reference(type_, instantiated)
+            }
+            TypeData::Anonymous { .. } => self.instantiate_anonymous_type(type_, mapper),
+            TypeData::Mapped { .. } => self.instantiate_mapped_type(type_, mapper),
+            TypeData::IndexedAccess {
+                object_type,
+                index_type,
+            } => {
+                let object_type = self.instantiate_type(object_type, mapper);
+                let index_type = self.instantiate_type(index_type, mapper);
+                self.get_indexed_access_type(object_type, index_type, None)
+            }
+            TypeData::Index { type_: target } => {
+                let target = self.instantiate_type(target, mapper);
+                self.get_index_type(target)
+            }
+            _ => type_,
+        };
+        if let Some(alias_symbol) = alias_symbol
+            && result != type_
+        {
+            let mut arguments = Vec::with_capacity(alias_arguments.len());
+            for argument in alias_arguments {
+                arguments.push(self.instantiate_type(argument, mapper));
+            }
+            return self.get_type_with_alias(result, alias_symbol, arguments);
+        }
+        result
+    }
+
+    /// Instantiates an anonymous type by the type parameters in scope at its declaration, so
+    /// that instantiations with the same type arguments are the same type
+    fn instantiate_anonymous_type(&mut self, type_: TypeId, mapper: &Arc<TypeMapper>) -> TypeId {
+        let TypeData::Anonymous {
+            target,
+            mapper: existing_mapper,
+        } = self.get_type(type_).data.clone()
+        else {
+            return type_;
+        };
+        let target = target.unwrap_or(type_);
+        let Some(declaration) = self
+            .get_type(target)
+            .symbol
+            .and_then(|symbol| self.symbol(symbol).declarations.first().cloned())
+        else {
+            return type_;
+        };
+        let outer_type_parameters = self.get_outer_type_parameters(&declaration);
+        if outer_type_parameters.is_empty() {
+            return type_;
+        }
+        let mut arguments = Vec::with_capacity(outer_type_parameters.len());
+        for &parameter in &outer_type_parameters {
+            let current = match &existing_mapper {
+                Some(existing) => self.map_type(parameter, existing),
+                None => parameter,
+            };
+            arguments.push(self.instantiate_type(current, mapper));
+        }
+        if arguments == outer_type_parameters {
+            return target;
+        }
+        let key = (target, arguments);
+        if let Some(&instantiation) = self.instantiations.get(&key) {
+            return instantiation;
+        }
+        let symbol = self.get_type(target).symbol;
+        let instantiation = self.create_type(
+            TypeFlags::OBJECT,
+            ObjectFlags::ANONYMOUS | ObjectFlags::INSTANTIATED,
+            symbol,
+            TypeData::Anonymous {
+                target: Some(target),
+                mapper: Some(TypeMapper::new(outer_type_parameters, key.1.clone())),
+            },
+        );
+        self.instantiations.insert(key, instantiation);
+        instantiation
+    }
+
+    /// Creates an instantiation of a symbol whose type is instantiated on demand
+    pub(super) fn instantiate_symbol(
+        &mut self,
+        symbol: SymbolId,
+        mapper: &Arc<TypeMapper>,
+    ) -> SymbolId {
+        let links = self.symbol_links(symbol).clone();
+        let original = self.symbol(symbol).clone();
+        // Instantiating an instantiation composes the mappers over the original
+        let (target, mapper) = match (links.target, links.mapper) {
+            (Some(target), Some(existing))
+                if original.check_flags.contains(CheckFlags::INSTANTIATED) =>
+            {
+                (
+                    target,
+                    Arc::new(TypeMapper::Composite(existing, Arc::clone(mapper))),
+                )
+            }
+            _ => (symbol, Arc::clone(mapper)),
+        };
+        let result = self.bindings.symbols.alloc(
+            original.name.clone(),
+            original.flags | SymbolFlags::TRANSIENT,
+        );
+        let result_symbol = &mut self.bindings.symbols[result];
+        result_symbol.check_flags = original.check_flags | CheckFlags::INSTANTIATED;
+        result_symbol.declarations = original.declarations;
+        result_symbol.value_declaration = original.value_declaration;
+        result_symbol.parent = original.parent;
+        let result_links = self.symbol_links(result);
+        result_links.target = Some(target);
+        result_links.mapper = Some(mapper);
+        result
+    }
+
+    /// Instantiates a signature, leaving its own type parameters in place
+    pub(super) fn instantiate_signature(
+        &mut self,
+        signature: SignatureId,
+        mapper: &Arc<TypeMapper>,
+    ) -> SignatureId {
+        let original = self.get_signature(signature).clone();
+        let parameters = original
+            .parameters
+            .iter()
+            .map(|&parameter| self.instantiate_symbol(parameter, mapper))
+            .collect();
+        let this_parameter = original
+            .this_parameter
+            .map(|parameter| self.instantiate_symbol(parameter, mapper));
+        self.create_signature(Signature {
+            flags: original.flags,
+            declaration: original.declaration,
+            type_parameters: original.type_parameters,
+            this_parameter,
+            parameters,
+            min_argument_count: original.min_argument_count,
+            resolved_return_type: None,
+            target: Some(signature),
+            mapper: Some(Arc::clone(mapper)),
+        })
+    }
+
+    /// Gets a generic signature instantiated with type arguments for its type parameters
+    pub(super) fn get_signature_instantiation(
+        &mut self,
+        signature: SignatureId,
+        type_arguments: Vec<TypeId>,
+    ) -> SignatureId {
+        let key = (signature, type_arguments);
+        if let Some(&instantiation) = self.signature_instantiations.get(&key) {
+            return instantiation;
+        }
+        let type_parameters = self.get_signature(signature).type_parameters.clone();
+        let mapper = TypeMapper::new(type_parameters, key.1.clone());
+        let instantiation = self.instantiate_signature(signature, &mapper);
+        self.signatures[instantiation.index()].type_parameters = Vec::new();
+        self.signature_instantiations.insert(key, instantiation);
+        instantiation
+    }
+
+    /// Fills in the defaults of omitted type arguments, or `any` for parameters without one
+    pub(super) fn fill_missing_type_arguments(
+        &mut self,
+        mut type_arguments: Vec<TypeId>,
+        type_parameters: &[TypeId],
+    ) -> Vec<TypeId> {
+        for index in type_arguments.len()..type_parameters.len() {
+            let default = self.get_default_of_type_parameter(type_parameters[index]);
+            let type_ = match default {
+                Some(default) => {
+                    // Defaults may refer to earlier type parameters
+                    let mut targets = type_arguments.clone();
+                    targets.resize(type_parameters.len(), self.any_type);
+                    let mapper = TypeMapper::new(type_parameters.to_vec(), targets);
+                    self.instantiate_type(default, &mapper)
+                }
+                None => self.any_type,
+            };
+            type_arguments.push(type_);
+        }
+        type_arguments
+    }
+}
@@ -0,0 +1,274 @@
+//! The iteration protocol: what `for...of`, spreads, array destructuring, and `yield*` take
+//! out of the values they iterate
+//!
+//! An iterable has a `[Symbol.iterator]()` method returning an iterator, whose `next()` method
+//! returns results with a `value` until one is `done`. Arrays, tuples, and strings are iterated
+//! directly. Compiling for ES5 without `downlevelIteration` only iterates arrays, and strings
+//! in `for...of`, by index, so other iterables are reported there.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{
+    is_class_like, is_function_like_kind, well_known_symbol_name,
+};
+use crate::compiler::ast::{Node, SyntaxKind};
+use crate::compiler::diagnostics::{self, Message};
+use crate::compiler::scanner::ScriptTarget;
+
+use super::{Checker, TypeFlags, TypeId};
+
+/// How a value is iterated, which decides what it can be when iterating by index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum IterationUse {
+    ForOf,
+    Spread,
+    Destructuring,
+    YieldStar,
+}
+
+impl IterationUse {
+    /// Determines if iterating by index also accepts strings
+    fn allows_strings(self) -> bool {
+        self == IterationUse::ForOf
+    }
+}
+
+impl Checker {
+    /// Gets the type of the values iterating a value of a type produces, reporting a type that
+    /// cannot be iterated on `error_node`
+    pub(super) fn get_iterated_type(
+        &mut self,
+        type_: TypeId,
+        use_: IterationUse,
+        error_node: Option<&Arc<Node>>,
+    ) -> TypeId {
+        if self.flags(type_).intersects(TypeFlags::ANY) {
+            return type_;
+        }
+        if !self.iterates_by_index() {
+            if let Some(iterated) = self.get_iterated_type_of_iterable(type_) {
+                return iterated;
+            }
+            if let Some(error_node) = error_node {
+                self.report_not_iterable(
+                    error_node,
+                    type_,
+                    diagnostics::TYPE_0_MUST_HAVE_A_SYMBOL_ITERATOR_METHOD_THAT_RETURNS_AN_ITERATOR_2488,
+                );
+            }
+            return self.error_type;
+        }
+
+        // Iterating by index, strings are only allowed as a whole
+        let mut has_string = false;
+        let mut array_type = type_;
+        if use_.allows_strings() && self.maybe_type_of_kind(type_, TypeFlags::STRING_LIKE) {
+            has_string = true;
+            array_type = self.filter_type(type_, |checker, type_| {
+                !checker.flags(type_).intersects(TypeFlags::STRING_LIKE)
+            });
+            if self.flags(array_type).contains(TypeFlags::NEVER) {
+                return self.string_type;
+            }
+        }
+        let is_array_like = !self
+            .get_constituents(array_type)
+            .into_iter()
+            .any(|type_| !self.is_array_type(type_) && !self.is_tuple_type(type_));
+        if !is_array_like {
+            if let Some(error_node) = error_node {
+                let message = self.get_index_iteration_error(array_type, use_, has_string);
+                self.report_not_iterable(error_node, array_type, message);
+            }
+            return if has_string {
+                self.string_type
+            } else {
+                self.error_type
+            };
+        }
+        let element_type = self.get_element_type_of_iterable(array_type);
+        if has_string {
+            self.get_union_type(&[element_type, self.string_type])
+        } else {
+            element_type
+        }
+    }
+
+    /// Gets the type of the values iterating a value of a type produces, or `any` if it cannot
+    /// be iterated, without reporting it
+    pub(super) fn get_element_type_of_iterable(&mut self, type_: TypeId) -> TypeId {
+        if self.flags(type_).intersects(TypeFlags::ANY) {
+            return type_;
+        }
+        self.get_iterated_type_of_iterable(type_)
+            .unwrap_or(self.any_type)
+    }
+
+    /// Determines if values are iterated by index, because the target has no iterators and
+    /// `downlevelIteration` does not emulate them
+    fn iterates_by_index(&self) -> bool {
+        self.options
+            .target
+            .is_some_and(|target| target < ScriptTarget::ES2015)
+            && !self.options.downlevel_iteration
+    }
+
+    /// Gets the type of the values an iterable produces, or None if the type is not iterable
+    fn get_iterated_type_of_iterable(&mut self, type_: TypeId) -> Option<TypeId> {
+        let flags = self.flags(type_);
+        if flags.intersects(TypeFlags::ANY) {
+            return Some(type_);
+        }
+        if flags.contains(TypeFlags::UNION) {
+            let mut iterated = Vec::new();
+            for constituent in self.get_constituents(type_) {
+                iterated.push(self.get_iterated_type_of_iterable(constituent)?);
+            }
+            return Some(self.get_union_type(&iterated));
+        }
+        if flags.intersects(TypeFlags::STRING_LIKE) {
+            return Some(self.string_type);
+        }
+        // Arrays and tuples produce their elements, even without the declarations of their
+        // iterators
+        if self.is_array_type(type_) || self.is_tuple_type(type_) {
+            let members = self.resolve_structured_type_members(type_);
+            if let Some(info) = members
+                .index_infos
+                .iter()
+                .find(|info| info.key_type == self.number_type)
+            {
+                return Some(info.value_type);
+            }
+        }
+        let Some(method) =
+            self.get_type_of_property_of_type(type_, &well_known_symbol_name("iterator"))
+        else {
+            return self.is_permissive_type(type_).then_some(self.any_type);
+        };
+        let mut iterated = Vec::new();
+        for signature in self.get_signatures_of_type(method, false) {
+            let iterator = self.get_return_type_of_signature(signature);
+            iterated.push(self.get_iterated_type_of_iterator(iterator)?);
+        }
+        if iterated.is_empty() {
+            return None;
+        }
+        Some(self.get_union_type(&iterated))
+    }
+
+    /// Gets the type of the values an iterator produces: the values of the results of its
+    /// `next()` method that are not `done`
+    fn get_iterated_type_of_iterator(&mut self, iterator: TypeId) -> Option<TypeId> {
+        if self.flags(iterator).intersects(TypeFlags::ANY) {
+            return Some(iterator);
+        }
+        let next = self.get_type_of_property_of_type(iterator, "next")?;
+        let mut values = Vec::new();
+        for signature in self.get_signatures_of_type(next, false) {
+            let result = self.get_return_type_of_signature(signature);
+            if self.flags(result).intersects(TypeFlags::ANY) {
+                return Some(result);
+            }
+            for constituent in self.get_constituents(result) {
+                let done = self.get_type_of_property_of_type(constituent, "done");
+                if done == Some(self.true_type) {
+                    continue;
+                }
+                values.push(self.get_type_of_property_of_type(constituent, "value")?);
+            }
+        }
+        if values.is_empty() {
+            return None;
+        }
+        Some(self.get_union_type(&values))
+    }
+
+    /// Gets the message for a type that cannot be iterated by index, which says when it could
+    /// be iterated with the iterator protocol
+    fn get_index_iteration_error(
+        &mut self,
+        type_: TypeId,
+        use_: IterationUse,
+        has_string: bool,
+    ) -> &'static Message {
+        if self.get_iterated_type_of_iterable(type_).is_some() && !self.is_permissive_type(type_) {
+            return diagnostics::TYPE_0_CAN_ONLY_BE_ITERATED_THROUGH_WHEN_USING_THE_DOWNLEVEL_ITERATION_FLAG_OR_WITH_A_TARGET_OF_ES2015_OR_HIGHER_2802;
+        }
+        if use_.allows_strings() && !has_string {
+            diagnostics::TYPE_0_IS_NOT_AN_ARRAY_TYPE_OR_A_STRING_TYPE_2495
+        } else {
+            diagnostics::TYPE_0_IS_NOT_AN_ARRAY_TYPE_2461
+        }
+    }
+
+    fn report_not_iterable(&mut self, error_node: &Arc<Node>, type_: TypeId, message: &Message) {
+        let type_name = self.type_to_string(type_);
+        self.error(error_node, message, &[type_name]);
+    }
+
+    /// Gets the type a generator function returns: a `Generator` of the types it yields and
+    /// returns, or an `AsyncGenerator` for an async generator function
+    pub(super) fn get_generator_return_type(
+        &mut self,
+        function: &Arc<Node>,
+        body: &Arc<Node>,
+    ) -> TypeId {
+        let is_async = function.has_modifier(SyntaxKind::AsyncKeyword);
+        let mut yielded = Vec::new();
+        collect_yield_expressions(body, &mut yielded);
+        let mut yield_types = Vec::new();
+        for expression in &yielded {
+            let yield_expression = expression.as_yield_expression();
+            let type_ = match &yield_expression.expression {
+                Some(operand) => {
+                    let type_ = self.check_expression(operand);
+                    if yield_expression.asterisk_token.is_some() {
+                        self.get_element_type_of_iterable(type_)
+                    } else {
+                        type_
+                    }
+                }
+                None => self.undefined_type,
+            };
+            let type_ = if is_async {
+                self.get_awaited_type(type_)
+            } else {
+                type_
+            };
+            yield_types.push(self.get_widened_literal_type(type_));
+        }
+        let yield_type = self.get_union_type(&yield_types);
+        let yield_type = self.get_widened_type(yield_type);
+        let return_type = self.infer_return_type(body, is_async);
+        let (generator, iterable_iterator) = if is_async {
+            ("AsyncGenerator", "AsyncIterableIterator")
+        } else {
+            ("Generator", "IterableIterator")
+        };
+        if let Some(generator) = self.get_declared_global_type(generator, 3) {
+            let next_type = self.unknown_type;
+            return self.create_type_reference(generator, vec![yield_type, return_type, next_type]);
+        }
+        match self.get_declared_global_type(iterable_iterator, 1) {
+            Some(iterator) => self.create_type_reference(iterator, vec![yield_type]),
+            None => self.any_type,
+        }
+    }
+}
+
+/// Collects the `yield` expressions of a generator function body, not including those of
+/// nested functions and classes
+fn collect_yield_expressions(node: &Arc<Node>, result: &mut Vec<Arc<Node>>) {
+    node.for_each_child(&mut |child| {
+        if is_function_like_kind(child.kind) || is_class_like(child) {
+            return false;
+        }
+        // The operand of a `yield` may itself yield
+        collect_yield_expressions(child, result);
+        if child.kind == SyntaxKind::YieldExpression {
+            result.push(Arc::clone(child));
+        }
+        false
+    });
+}
@@ -0,0 +1,141 @@
+//! JSX elements
+//!
+//! A JSX element's type is `any`, but its tag, its attributes, and its children are checked
+//! like any other expressions. The attributes are the props of the component the tag names:
+//! for an intrinsic element such as `<div>`, the property of `JSX.IntrinsicElements` named by
+//! the tag, and for any other, the first parameter of the function or class the tag refers
+//! to. Each attribute takes its contextual type from the prop of the same name.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::{Node, SymbolFlags, SyntaxKind};
+
+use super::{Checker, TypeId};
+
+impl Checker {
+    /// Checks a JSX element or fragment
+    pub(super) fn check_jsx_element(&mut self, node: &Arc<Node>) -> TypeId {
+        match node.kind {
+            SyntaxKind::JsxElement => {
+                let element = node.as_jsx_element();
+                self.check_jsx_opening_like_element(&element.opening_element);
+                self.check_jsx_children(&element.children.nodes);
+            }
+            SyntaxKind::JsxSelfClosingElement => self.check_jsx_opening_like_element(node),
+            _ => self.check_jsx_children(&node.as_jsx_fragment().children.nodes),
+        }
+        self.any_type
+    }
+
+    fn check_jsx_opening_like_element(&mut self, node: &Arc<Node>) {
+        let (tag_name, attributes) = jsx_tag_name_and_attributes(node);
+        if !is_intrinsic_jsx_name(tag_name) {
+            self.check_expression(tag_name);
+        }
+        for attribute in &attributes.as_jsx_attributes().properties.nodes {
+            match attribute.kind {
+                SyntaxKind::JsxAttribute => {
+                    if let Some(initializer) = &attribute.as_jsx_attribute().initializer
+                        && initializer.kind == SyntaxKind::JsxExpression
+                        && let Some(expression) = &initializer.as_jsx_expression().expression
+                    {
+                        self.check_expression(expression);
+                    }
+                }
+                _ => {
+                    self.check_expression(&attribute.as_jsx_spread_attribute().expression);
+                }
+            }
+        }
+    }
+
+    fn check_jsx_children(&mut self, children: &[Arc<Node>]) {
+        for child in children {
+            match child.kind {
+                SyntaxKind::JsxExpression => {
+                    if let Some(expression) = &child.as_jsx_expression().expression {
+                        self.check_expression(expression);
+                    }
+                }
+                SyntaxKind::JsxElement
+                | SyntaxKind::JsxSelfClosingElement
+                | SyntaxKind::JsxFragment => {
+                    self.check_jsx_element(child);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Gets the props of the component an opening or self-closing element names, if it
+    /// declares them
+    pub(super) fn get_jsx_props_type(&mut self, node: &Arc<Node>) -> Option<TypeId> {
+        let (tag_name, _) = jsx_tag_name_and_attributes(node);
+        if is_intrinsic_jsx_name(tag_name) {
+            let intrinsic_elements = self.get_jsx_intrinsic_elements_type()?;
+            let name = tag_name.text().to_string();
+            return self.get_indexed_access_type_or_undefined(intrinsic_elements, &name);
+        }
+        // A function component takes its props as its parameter, and a class component as
+        // the parameter of its constructor
+        let tag_type = self.check_expression(tag_name);
+        for construct in [false, true] {
+            if let Some(&signature) = self.get_signatures_of_type(tag_type, construct).first() {
+                return self.get_type_at_position(signature, 0);
+            }
+        }
+        None
+    }
+
+    /// Gets the type an attribute of a JSX element is expected to have, from the prop of the
+    /// same name
+    pub(super) fn get_contextual_type_of_jsx_attribute(
+        &mut self,
+        attribute: &Arc<Node>,
+    ) -> Option<TypeId> {
+        let name = &attribute.as_jsx_attribute().name;
+        if name.kind != SyntaxKind::Identifier {
+            return None;
+        }
+        let element = attribute.parent()?.parent()?;
+        let props = self.get_jsx_props_type(&element)?;
+        self.get_indexed_access_type_or_undefined(props, name.text())
+    }
+
+    /// Gets the global `JSX.IntrinsicElements` interface, which declares the props of each
+    /// intrinsic element
+    fn get_jsx_intrinsic_elements_type(&mut self) -> Option<TypeId> {
+        let namespace = *self.globals.get("JSX")?;
+        let symbol = *self.symbol(namespace).exports.get("IntrinsicElements")?;
+        if !self.symbol(symbol).flags.intersects(SymbolFlags::TYPE) {
+            return None;
+        }
+        Some(self.get_declared_type_of_symbol(symbol))
+    }
+}
+
+fn jsx_tag_name_and_attributes(node: &Arc<Node>) -> (&Arc<Node>, &Arc<Node>) {
+    match node.kind {
+        SyntaxKind::JsxOpeningElement => {
+            let element = node.as_jsx_opening_element();
+            (&element.tag_name, &element.attributes)
+        }
+        _ => {
+            let element = node.as_jsx_self_closing_element();
+            (&element.tag_name, &element.attributes)
+        }
+    }
+}
+
+/// Determines if a tag names an intrinsic element, as a lowercase or dashed name does,
+/// rather than a component in scope
+fn is_intrinsic_jsx_name(tag_name: &Node) -> bool {
+    match tag_name.kind {
+        SyntaxKind::Identifier => {
+            let name = tag_name.text();
+            name.starts_with(|c: char| c.is_ascii_lowercase()) || name.contains('-')
+        }
+        SyntaxKind::JsxNamespacedName => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,583 @@
+//! Mapped types
+//!
+//! A mapped type `{ [K in C as N]: X }` has a member for each key in its constraint `C`, named
+//! by the name type `N` if there is one, whose type is the template `X` with `K` replaced by the
+//! key. A mapped type whose constraint is written `keyof T` is homomorphic: its members keep the
+//! `readonly` and `?` modifiers of the properties of `T` unless the mapped type adds or removes
+//! them, and when `T` is a type parameter, instantiating it distributes over unions, maps arrays
+//! and tuples to arrays and tuples, and leaves primitives as they are. A mapped type whose
+//! constraint is a type parameter constrained to `keyof T`, like `Pick`, also keeps the
+//! modifiers of the properties of `T` it maps.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::{CheckFlags, Node, SymbolFlags, SymbolId, SyntaxKind};
+
+use super::{
+    Checker, ElementFlags, IndexInfo, ObjectFlags, ResolvedMembers, TypeData, TypeFlags, TypeId,
+    TypeMapper,
+};
+
+/// How a mapped type changes the `readonly` or `?` modifier of the members it maps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ModifierChange {
+    /// `readonly` or `+readonly`, `?` or `+?`
+    Add,
+    /// `-readonly` or `-?`
+    Remove,
+    /// No modifier, which keeps the modifier of a homomorphic mapped type's source property
+    Keep,
+}
+
+impl ModifierChange {
+    fn of(token: Option<&Arc<Node>>) -> Self {
+        match token.map(|token| token.kind) {
+            None => ModifierChange::Keep,
+            Some(SyntaxKind::MinusToken) => ModifierChange::Remove,
+            Some(_) => ModifierChange::Add,
+        }
+    }
+
+    /// Determines if a member has the modifier, given whether the member it maps had it
+    fn apply(self, had_modifier: bool) -> bool {
+        match self {
+            ModifierChange::Add => true,
+            ModifierChange::Remove => false,
+            ModifierChange::Keep => had_modifier,
+        }
+    }
+}
+
+/// What resolving the members of a mapped type needs for each key
+struct MappedTypeMapping {
+    type_parameter: TypeId,
+    template_type: TypeId,
+    name_type: Option<TypeId>,
+    mapper: Option<Arc<TypeMapper>>,
+    readonly: ModifierChange,
+    optional: ModifierChange,
+}
+
+impl Checker {
+    pub(super) fn get_type_from_mapped_type_node(&mut self, node: &Arc<Node>) -> TypeId {
+        let symbol = self.get_symbol_of_declaration(node);
+        self.create_type(
+            TypeFlags::OBJECT,
+            ObjectFlags::MAPPED,
+            symbol,
+            TypeData::Mapped {
+                target: None,
+                mapper: None,
+            },
+        )
+    }
+
+    /// Gets the declaration of a mapped type and the mapper that instantiated it
+    fn get_mapped_type_declaration(
+        &self,
+        type_: TypeId,
+    ) -> Option<(Arc<Node>, Option<Arc<TypeMapper>>)> {
+        let type_data = self.get_type(type_);
+        let TypeData::Mapped { mapper, .. } = &type_data.data else {
+            return None;
+        };
+        let declaration = type_data
+            .symbol
+            .and_then(|symbol| self.symbol(symbol).declarations.first().cloned())?;
+        Some((declaration, mapper.clone()))
+    }
+
+    /// Gets the type parameter `K` of a mapped type declaration
+    fn get_type_parameter_of_mapped_type(&mut self, declaration: &Node) -> Option<TypeId> {
+        let parameter = &declaration.as_mapped_type_node().type_parameter;
+        let symbol = self.get_symbol_of_declaration(parameter)?;
+        Some(self.get_declared_type_of_symbol(symbol))
+    }
+
+    /// Gets the type parameter `T` of a mapped type whose constraint is `keyof T`, which the
+    /// mapped type is homomorphic in
+    fn get_homomorphic_type_variable(&mut self, declaration: &Node) -> Option<TypeId> {
+        let modifiers_node = get_modifiers_type_node(declaration)?;
+        let type_ = self.get_type_from_type_node(&modifiers_node);
+        self.flags(type_)
+            .contains(TypeFlags::TYPE_PARAMETER)
+            .then_some(type_)
+    }
+
+    /// Gets the type parameter `K` of a mapped type
+    pub(super) fn get_type_parameter_from_mapped_type(&mut self, type_: TypeId) -> Option<TypeId> {
+        let (declaration, _) = self.get_mapped_type_declaration(type_)?;
+        self.get_type_parameter_of_mapped_type(&declaration)
+    }
+
+    /// Gets the template `X` of a mapped type, instantiated as the mapped type is
+    pub(super) fn get_template_type_from_mapped_type(&mut self, type_: TypeId) -> TypeId {
+        let Some((declaration, mapper)) = self.get_mapped_type_declaration(type_) else {
+            return self.any_type;
+        };
+        match &declaration.as_mapped_type_node().type_node {
+            Some(type_node) => {
+                let template_type = self.get_type_from_type_node(type_node);
+                self.instantiate_type_if_mapped(template_type, mapper.as_ref())
+            }
+            None => self.any_type,
+        }
+    }
+
+    /// Gets the name type `N` of a mapped type with an `as N` clause, instantiated as the
+    /// mapped type is
+    pub(super) fn get_name_type_from_mapped_type(&mut self, type_: TypeId) -> Option<TypeId> {
+        let (declaration, mapper) = self.get_mapped_type_declaration(type_)?;
+        let name_type = declaration.as_mapped_type_node().name_type.as_ref()?;
+        let name_type = self.get_type_from_type_node(name_type);
+        Some(self.instantiate_type_if_mapped(name_type, mapper.as_ref()))
+    }
+
+    /// Gets how a mapped type changes the `readonly` and `?` modifiers of its members
+    pub(super) fn get_mapped_type_modifiers(
+        &self,
+        type_: TypeId,
+    ) -> (ModifierChange, ModifierChange) {
+        match self.get_mapped_type_declaration(type_) {
+            Some((declaration, _)) => {
+                let mapped = declaration.as_mapped_type_node();
+                (
+                    ModifierChange::of(mapped.readonly_token.as_ref()),
+                    ModifierChange::of(mapped.question_token.as_ref()),
+                )
+            }
+            None => (ModifierChange::Keep, ModifierChange::Keep),
+        }
+    }
+
+    /// Gets the type whose property keys a homomorphic mapped type maps
+    pub(super) fn get_modifiers_type_from_mapped_type(&mut self, type_: TypeId) -> Option<TypeId> {
+        let (declaration, mapper) = self.get_mapped_type_declaration(type_)?;
+        let modifiers_node = get_modifiers_type_node(&declaration)?;
+        let modifiers_type = self.get_type_from_type_node(&modifiers_node);
+        Some(self.instantiate_type_if_mapped(modifiers_type, mapper.as_ref()))
+    }
+
+    /// Gets the type of the keys a mapped type maps, which is `unknown` if the declaration has
+    /// no constraint to speak of
+    pub(super) fn get_constraint_type_from_mapped_type(&mut self, type_: TypeId) -> TypeId {
+        let Some((declaration, mapper)) = self.get_mapped_type_declaration(type_) else {
+            return self.unknown_type;
+        };
+        let constraint = self
+            .get_type_parameter_of_mapped_type(&declaration)
+            .and_then(|parameter| self.get_constraint_of_type_parameter(parameter));
+        match constraint {
+            Some(constraint) => self.instantiate_type_if_mapped(constraint, mapper.as_ref()),
+            None => self.unknown_type,
+        }
+    }
+
+    /// Determines if a type is a mapped type whose keys are not known until its type
+    /// parameters are
+    pub(super) fn is_generic_mapped_type(&mut self, type_: TypeId) -> bool {
+        if !self.object_flags(type_).contains(ObjectFlags::MAPPED) {
+            return false;
+        }
+        let keys = match self.get_modifiers_type_from_mapped_type(type_) {
+            Some(modifiers_type) => modifiers_type,
+            None => self.get_constraint_type_from_mapped_type(type_),
+        };
+        self.is_generic_type(keys)
+    }
+
+    /// Gets the template of a mapped type with `K` replaced by `key_type`, for indexing a
+    /// generic mapped type
+    pub(super) fn substitute_indexed_mapped_type(
+        &mut self,
+        type_: TypeId,
+        key_type: TypeId,
+    ) -> Option<TypeId> {
+        let (declaration, mapper) = self.get_mapped_type_declaration(type_)?;
+        let mapped = declaration.as_mapped_type_node();
+        if mapped.name_type.is_some() {
+            return None;
+        }
+        let type_parameter = self.get_type_parameter_of_mapped_type(&declaration)?;
+        let template_type = match &mapped.type_node {
+            Some(type_node) => self.get_type_from_type_node(type_node),
+            None => self.any_type,
+        };
+        let key_mapper = create_key_mapper(type_parameter, key_type, mapper.as_ref());
+        Some(self.instantiate_type(template_type, &key_mapper))
+    }
+
+    /// Resolves the members of a mapped type, one for each key of its constraint
+    pub(super) fn resolve_mapped_type_members(&mut self, type_: TypeId) -> ResolvedMembers {
+        let mut members = ResolvedMembers::default();
+        let Some((declaration, mapper)) = self.get_mapped_type_declaration(type_) else {
+            return members;
+        };
+        let Some(type_parameter) = self.get_type_parameter_of_mapped_type(&declaration) else {
+            return members;
+        };
+        let mapped = declaration.as_mapped_type_node();
+        let template_type = match &mapped.type_node {
+            Some(type_node) => self.get_type_from_type_node(type_node),
+            None => self.any_type,
+        };
+        let name_type = mapped
+            .name_type
+            .as_ref()
+            .map(|name_type| self.get_type_from_type_node(name_type));
+        let mapping = MappedTypeMapping {
+            type_parameter,
+            template_type,
+            name_type,
+            mapper,
+            readonly: ModifierChange::of(mapped.readonly_token.as_ref()),
+            optional: ModifierChange::of(mapped.question_token.as_ref()),
+        };
+        if let Some(modifiers_type) = self.get_modifiers_type_from_mapped_type(type_) {
+            if self.flags(modifiers_type).contains(TypeFlags::ANY) {
+                // `keyof any` is every key an object can have
+                for key_type in [self.string_type, self.number_type, self.es_symbol_type] {
+                    self.add_member_for_key_type(&mut members, &mapping, key_type, None);
+                }
+                return members;
+            }
+            for property in self.get_properties_of_type(modifiers_type) {
+                let Some(key_type) = self.get_literal_type_from_property(property) else {
+                    continue;
+                };
+                self.add_member_for_key_type(&mut members, &mapping, key_type, Some(property));
+            }
+            let apparent = self.get_apparent_type(modifiers_type);
+            if self.flags(apparent).contains(TypeFlags::OBJECT) {
+                let index_infos = self
+                    .resolve_structured_type_members(apparent)
+                    .index_infos
+                    .clone();
+                for info in index_infos {
+                    let key_type = info.key_type;
+                    let index = members.index_infos.len();
+                    self.add_member_for_key_type(&mut members, &mapping, key_type, None);
+                    // The readonly-ness of a homomorphic index signature is kept like that of
+                    // a property
+                    if let Some(added) = members.index_infos.get_mut(index) {
+                        added.is_readonly = mapping.readonly.apply(info.is_readonly);
+                    }
+                }
+            }
+        } else {
+            let constraint = self.get_constraint_type_from_mapped_type(type_);
+            let modifiers_type = self.get_modifiers_type_from_key_constraint(type_);
+            for key_type in self.get_constituents(constraint) {
+                let modifiers_property = modifiers_type.and_then(|modifiers_type| {
+                    let name = self.get_property_name_from_type(key_type)?;
+                    self.get_property_of_type(modifiers_type, &name)
+                });
+                self.add_member_for_key_type(&mut members, &mapping, key_type, modifiers_property);
+            }
+        }
+        members
+    }
+
+    /// Gets `T` for a mapped type whose constraint is a type parameter constrained to
+    /// `keyof T`, as in `Pick`, whose members keep the modifiers of the properties of `T` they
+    /// pick
+    fn get_modifiers_type_from_key_constraint(&mut self, type_: TypeId) -> Option<TypeId> {
+        let (declaration, mapper) = self.get_mapped_type_declaration(type_)?;
+        let constraint = self
+            .get_type_parameter_of_mapped_type(&declaration)
+            .and_then(|parameter| self.get_constraint_of_type_parameter(parameter))?;
+        if !self.flags(constraint).contains(TypeFlags::TYPE_PARAMETER) {
+            return None;
+        }
+        let key_constraint = self.get_constraint_of_type_parameter(constraint)?;
+        let TypeData::Index { type_: keyed } = self.get_type(key_constraint).data else {
+            return None;
+        };
+        Some(self.instantiate_type_if_mapped(keyed, mapper.as_ref()))
+    }
+
+    /// Adds the member for one key of a mapped type, which is a property for a literal key and
+    /// an index signature for `string`, `number`, or `symbol`
+    fn add_member_for_key_type(
+        &mut self,
+        members: &mut ResolvedMembers,
+        mapping: &MappedTypeMapping,
+        key_type: TypeId,
+        modifiers_property: Option<SymbolId>,
+    ) {
+        let key_mapper =
+            create_key_mapper(mapping.type_parameter, key_type, mapping.mapper.as_ref());
+        let property_name_type = match mapping.name_type {
+            Some(name_type) => self.instantiate_type(name_type, &key_mapper),
+            None => key_type,
+        };
+        let (had_optional, had_readonly) = match modifiers_property {
+            Some(property) => (
+                self.symbol(property).flags.contains(SymbolFlags::OPTIONAL),
+                self.is_readonly_property(property),
+            ),
+            None => (false, false),
+        };
+        let is_optional = mapping.optional.apply(had_optional);
+        let is_readonly = mapping.readonly.apply(had_readonly);
+        for name_type in self.get_constituents(property_name_type) {
+            if let Some(name) = self.get_property_name_from_type(name_type) {
+                let property_type = self.instantiate_type(mapping.template_type, &key_mapper);
+                // Optional properties may be `undefined`, and `-?` removes that
+                let property_type = if is_optional {
+                    self.get_optional_type(property_type, true)
+                } else if matches!(mapping.optional, ModifierChange::Remove) {
+                    self.filter_type(property_type, |checker, type_| {
+                        !checker.flags(type_).contains(TypeFlags::UNDEFINED)
+                    })
+                } else {
+                    property_type
+                };
+                if let Some(&existing) = members.members.get(&name) {
+                    // Keys the name type maps to the same name make one property
+                    let existing_type = self.get_type_of_symbol(existing);
+                    let union = self.get_union_type(&[existing_type, property_type]);
+                    self.symbol_links(existing).type_ = Some(union);
+                    continue;
+                }
+                let symbol = self.create_transient_property(&name, property_type);
+                self.symbol_links(symbol).name_type = Some(name_type);
+                let declarations = modifiers_property
+                    .map(|property| self.symbol(property).declarations.clone())
+                    .unwrap_or_default();
+                let symbol_data = &mut self.bindings.symbols[symbol];
+                if is_optional {
+                    symbol_data.flags |= SymbolFlags::OPTIONAL;
+                }
+                symbol_data.check_flags |= CheckFlags::MAPPED;
+                if is_readonly {
+                    symbol_data.check_flags |= CheckFlags::READONLY;
+                }
+                symbol_data.value_declaration = declarations.first().cloned();
+                symbol_data.declarations = declarations;
+                members.add_property(&name, symbol);
+                continue;
+            }
+            let flags = self.flags(name_type);
+            let index_key_type = if flags.intersects(TypeFlags::ANY | TypeFlags::STRING) {
+                self.string_type
+            } else if flags.intersects(TypeFlags::NUMBER | TypeFlags::ENUM) {
+                self.number_type
+            } else if flags.contains(TypeFlags::ES_SYMBOL) {
+                self.es_symbol_type
+            } else {
+                continue;
+            };
+            let value_type = self.instantiate_type(mapping.template_type, &key_mapper);
+            match members
+                .index_infos
+                .iter()
+                .position(|info| info.key_type == index_key_type)
+            {
+                Some(position) => {
+                    let existing = members.index_infos[position].value_type;
+                    members.index_infos[position].value_type =
+                        self.get_union_type(&[existing, value_type]);
+                }
+                None => members.index_infos.push(IndexInfo {
+                    key_type: index_key_type,
+                    value_type,
+                    is_readonly,
+                    declaration: None,
+                }),
+            }
+        }
+    }
+
+    /// Instantiates a mapped type by the type parameters in scope at its declaration
+    pub(super) fn instantiate_mapped_type(
+        &mut self,
+        type_: TypeId,
+        mapper: &Arc<TypeMapper>,
+    ) -> TypeId {
+        let TypeData::Mapped {
+            target,
+            mapper: existing_mapper,
+        } = self.get_type(type_).data.clone()
+        else {
+            return type_;
+        };
+        let target = target.unwrap_or(type_);
+        let Some((declaration, _)) = self.get_mapped_type_declaration(target) else {
+            return type_;
+        };
+        let outer_type_parameters = self.get_outer_type_parameters(&declaration);
+        if outer_type_parameters.is_empty() {
+            return type_;
+        }
+        let mut arguments = Vec::with_capacity(outer_type_parameters.len());
+        for &parameter in &outer_type_parameters {
+            let current = match &existing_mapper {
+                Some(existing) => self.map_type(parameter, existing),
+                None => parameter,
+            };
+            arguments.push(self.instantiate_type(current, mapper));
+        }
+        let homomorphic_index =
+            self.get_homomorphic_type_variable(&declaration)
+                .and_then(|variable| {
+                    outer_type_parameters
+                        .iter()
+                        .position(|&parameter| parameter == variable)
+                });
+        if let Some(index) = homomorphic_index
+            && arguments[index] != outer_type_parameters[index]
+        {
+            let mut results = Vec::new();
+            for constituent in self.get_constituents(arguments[index]) {
+                let mut constituent_arguments = arguments.clone();
+                constituent_arguments[index] = constituent;
+                results.push(self.instantiate_mapped_type_constituent(
+                    target,
+                    &declaration,
+                    outer_type_parameters.clone(),
+                    constituent_arguments,
+                    constituent,
+                ));
+            }
+            return self.get_union_type(&results);
+        }
+        self.get_mapped_type_instantiation(target, outer_type_parameters, arguments)
+    }
+
+    /// Instantiates a homomorphic mapped type for one constituent of the type replacing the
+    /// type parameter it is homomorphic in
+    fn instantiate_mapped_type_constituent(
+        &mut self,
+        target: TypeId,
+        declaration: &Node,
+        outer_type_parameters: Vec<TypeId>,
+        arguments: Vec<TypeId>,
+        constituent: TypeId,
+    ) -> TypeId {
+        let flags = self.flags(constituent);
+        if constituent == self.error_type
+            || !flags.intersects(
+                TypeFlags::ANY_OR_UNKNOWN
+                    | TypeFlags::INSTANTIABLE_NON_PRIMITIVE
+                    | TypeFlags::OBJECT
+                    | TypeFlags::INTERSECTION,
+            )
+        {
+            // Primitives have no properties to map
+            return constituent;
+        }
+        if declaration.as_mapped_type_node().name_type.is_none()
+            && (self.is_array_type(constituent) || self.is_tuple_type(constituent))
+        {
+            let mapper = TypeMapper::new(outer_type_parameters, arguments);
+            return self.instantiate_mapped_array_or_tuple_type(declaration, constituent, &mapper);
+        }
+        self.get_mapped_type_instantiation(target, outer_type_parameters, arguments)
+    }
+
+    /// Maps the elements of an array or tuple type, keeping it an array or tuple
+    fn instantiate_mapped_array_or_tuple_type(
+        &mut self,
+        declaration: &Node,
+        type_: TypeId,
+        mapper: &Arc<TypeMapper>,
+    ) -> TypeId {
+        let Some(type_parameter) = self.get_type_parameter_of_mapped_type(declaration) else {
+            return type_;
+        };
+        let mapped = declaration.as_mapped_type_node();
+        let template_type = match &mapped.type_node {
+            Some(type_node) => self.get_type_from_type_node(type_node),
+            None => self.any_type,
+        };
+        let Some((_, element_flags, readonly)) = self.get_tuple_elements(type_) else {
+            let key_mapper = create_key_mapper(type_parameter, self.number_type, Some(mapper));
+            let element_type = self.instantiate_type(template_type, &key_mapper);
+            let readonly = ModifierChange::of(mapped.readonly_token.as_ref())
+                .apply(self.is_readonly_array_type(type_));
+            return if readonly {
+                self.create_readonly_array_type(element_type)
+            } else {
+                self.create_array_type(element_type)
+            };
+        };
+        let optional = ModifierChange::of(mapped.question_token.as_ref());
+        let mut mapped_types = Vec::with_capacity(element_flags.len());
+        let mut mapped_flags = Vec::with_capacity(element_flags.len());
+        for (index, flags) in element_flags.into_iter().enumerate() {
+            let key_type = if flags.intersects(ElementFlags::VARIABLE) {
+                self.number_type
+            } else {
+                self.get_string_literal_type(&index.to_string())
+            };
+            let key_mapper = create_key_mapper(type_parameter, key_type, Some(mapper));
+            mapped_types.push(self.instantiate_type(template_type, &key_mapper));
+            mapped_flags.push(if flags.intersects(ElementFlags::FIXED) {
+                if optional.apply(flags.contains(ElementFlags::OPTIONAL)) {
+                    ElementFlags::OPTIONAL
+                } else {
+                    ElementFlags::REQUIRED
+                }
+            } else {
+                flags
+            });
+        }
+        let readonly = ModifierChange::of(mapped.readonly_token.as_ref()).apply(readonly);
+        self.create_tuple_type(mapped_types, mapped_flags, readonly)
+    }
+
+    /// Gets the interned instantiation of a mapped type with the given type arguments for the
+    /// type parameters in scope at its declaration
+    fn get_mapped_type_instantiation(
+        &mut self,
+        target: TypeId,
+        outer_type_parameters: Vec<TypeId>,
+        arguments: Vec<TypeId>,
+    ) -> TypeId {
+        if arguments == outer_type_parameters {
+            return target;
+        }
+        let key = (target, arguments);
+        if let Some(&instantiation) = self.instantiations.get(&key) {
+            return instantiation;
+        }
+        let symbol = self.get_type(target).symbol;
+        let instantiation = self.create_type(
+            TypeFlags::OBJECT,
+            ObjectFlags::MAPPED | ObjectFlags::INSTANTIATED,
+            symbol,
+            TypeData::Mapped {
+                target: Some(target),
+                mapper: Some(TypeMapper::new(outer_type_parameters, key.1.clone())),
+            },
+        );
+        self.instantiations.insert(key, instantiation);
+        instantiation
+    }
+}
+
+/// Gets the operand `T` of a mapped type constraint written `keyof T`
+fn get_modifiers_type_node(declaration: &Node) -> Option<Arc<Node>> {
+    let parameter = &declaration.as_mapped_type_node().type_parameter;
+    let constraint = parameter.as_type_parameter().constraint.as_ref()?;
+    if constraint.kind != SyntaxKind::TypeOperator {
+        return None;
+    }
+    let operator = constraint.as_type_operator_node();
+    (operator.operator == SyntaxKind::KeyOfKeyword).then(|| Arc::clone(&operator.type_node))
+}
+
+/// Creates a mapper that replaces the type parameter of a mapped type with one of its keys and
+/// the type parameters in scope as `mapper` does
+fn create_key_mapper(
+    type_parameter: TypeId,
+    key_type: TypeId,
+    mapper: Option<&Arc<TypeMapper>>,
+) -> Arc<TypeMapper> {
+    let key_mapper = TypeMapper::new(vec![type_parameter], vec![key_type]);
+    match mapper {
+        Some(mapper) => Arc::new(TypeMapper::Merged(key_mapper, Arc::clone(mapper))),
+        None => key_mapper,
+    }
+}
@@ -0,0 +1,724 @@
+//! Members of object, union, and intersection types
+
+use std::sync::Arc;
+
+use crate::compiler::ast::{
+    CheckFlags, Node, SymbolFlags, SymbolId, SyntaxKind, internal_symbol_names,
+};
+
+use super::{
+    Checker, IndexInfo, ResolvedMembers, Signature, SignatureFlags, SignatureId, TypeData,
+    TypeFlags, TypeId, TypeMapper,
+};
+
+impl Checker {
+    /// Resolves the properties, signatures, and index signatures of an object type
+    pub(super) fn resolve_structured_type_members(
+        &mut self,
+        type_: TypeId,
+    ) -> Arc<ResolvedMembers> {
+        if let Some(members) = self.resolved_members.get(&type_) {
+            return Arc::clone(members);
+        }
+        if !self.flags(type_).contains(TypeFlags::OBJECT) {
+            return Arc::default();
+        }
+        // A type whose members depend on themselves sees no members while they resolve
+        self.resolved_members.insert(type_, Arc::default());
+        self.resolve_deferred_type_arguments(type_);
+        let members = match self.get_type(type_).data.clone() {
+            TypeData::Interface(_) => self.resolve_class_or_interface_members(type_),
+            TypeData::Reference {
+                target,
+                type_arguments,
+            } => {
+                let target_members = self.resolve_structured_type_members(target);
+                let mapper = self.create_type_reference_mapper(target, &type_arguments);
+                self.instantiate_members(&target_members, &mapper)
+            }
+            TypeData::Anonymous {
+                target: Some(target),
+                mapper: Some(mapper),
+            } => {
+                let target_members = self.resolve_structured_type_members(target);
+                self.instantiate_members(&target_members, &mapper)
+            }
+            TypeData::Anonymous { .. } => self.resolve_anonymous_type_members(type_),
+            TypeData::Mapped { .. } => self.resolve_mapped_type_members(type_),
+            TypeData::Resolved { members } => members.as_ref().clone(),
+            _ => ResolvedMembers::default(),
+        };
+        let members = Arc::new(members);
+        self.resolved_members.insert(type_, Arc::clone(&members));
+        members
+    }
+
+    fn instantiate_members(
+        &mut self,
+        members: &ResolvedMembers,
+        mapper: &Arc<TypeMapper>,
+    ) -> ResolvedMembers {
+        let mut result = ResolvedMembers::default();
+        for &property in &members.properties {
+            let name = self.symbol(property).name.clone();
+            let instantiated = self.instantiate_symbol(property, mapper);
+            result.add_property(&name, instantiated);
+        }
+        for &signature in &members.call_signatures {
+            let instantiated = self.instantiate_signature(signature, mapper);
+            result.call_signatures.push(instantiated);
+        }
+        for &signature in &members.construct_signatures {
+            let instantiated = self.instantiate_signature(signature, mapper);
+            result.construct_signatures.push(instantiated);
+        }
+        for info in &members.index_infos {
+            let value_type = self.instantiate_type(info.value_type, mapper);
+            result.index_infos.push(IndexInfo {
+                value_type,
+                ..info.clone()
+            });
+        }
+        result
+    }
+
+    fn resolve_class_or_interface_members(&mut self, type_: TypeId) -> ResolvedMembers {
+        let mut members = ResolvedMembers::default();
+        if let Some(symbol) = self.get_type(type_).symbol {
+            let table = self.symbol(symbol).members.clone();
+            self.add_declared_members(&mut members, &table);
+            let late_bound = self.get_late_bound_members(symbol, false);
+            self.add_declared_members(&mut members, &late_bound);
+        }
+        for base in self.get_base_types(type_) {
+            let base = self.get_apparent_type(base);
+            let base_members = self.resolve_structured_type_members(base);
+            for &property in &base_members.properties {
+                let name = self.symbol(property).name.clone();
+                if !members.members.contains_key(&name) {
+                    members.add_property(&name, property);
+                }
+            }
+            members
+                .call_signatures
+                .extend(base_members.call_signatures.iter().copied());
+            members
+                .construct_signatures
+                .extend(base_members.construct_signatures.iter().copied());
+            for info in &base_members.index_infos {
+                if !members
+                    .index_infos
+                    .iter()
+                    .any(|existing| existing.key_type == info.key_type)
+                {
+                    members.index_infos.push(info.clone());
+                }
+            }
+        }
+        members
+    }
+
+    /// Adds the properties and signatures declared in a member table, in declaration order
+    fn add_declared_members(&mut self, members: &mut ResolvedMembers, table: &super::SymbolTable) {
+        let mut entries: Vec<(&String, &SymbolId)> = table.iter().collect();
+        entries.sort_by_key(|&(_, &symbol)| symbol);
+        for (name, &symbol) in entries {
+            let symbol = self.get_merged_symbol(symbol);
+            let flags = self.symbol(symbol).flags;
+            if flags.intersects(SymbolFlags::TYPE_PARAMETER | SymbolFlags::CONSTRUCTOR) {
+                continue;
+            }
+            match name.as_str() {
+                internal_symbol_names::CALL => {
+                    let signatures = self.get_signatures_of_symbol(symbol);
+                    members.call_signatures.extend(signatures);
+                }
+                internal_symbol_names::NEW => {
+                    let signatures = self.get_signatures_of_symbol(symbol);
+                    members.construct_signatures.extend(signatures);
+                }
+                internal_symbol_names::INDEX => {
+                    for declaration in self.symbol(symbol).declarations.clone() {
+                        if let Some(info) = self.get_index_info_of_declaration(&declaration) {
+                            members.index_infos.push(info);
+                        }
+                    }
+                }
+                _ => members.add_property(name, symbol),
+            }
+        }
+    }
+
+    fn get_index_info_of_declaration(&mut self, declaration: &Arc<Node>) -> Option<IndexInfo> {
+        let parameter = declaration.parameters()?.iter().next()?.clone();
+        let key_type = match parameter.type_node() {
+            Some(type_node) => self.get_type_from_type_node(type_node),
+            None => self.string_type,
+        };
+        let value_type = match declaration.type_node() {
+            Some(type_node) => self.get_type_from_type_node(type_node),
+            None => self.any_type,
+        };
+        Some(IndexInfo {
+            key_type,
+            value_type,
+            is_readonly: declaration.has_modifier(SyntaxKind::ReadonlyKeyword),
+            declaration: Some(Arc::clone(declaration)),
+        })
+    }
+
+    /// Gets the signatures of the function-like declarations of a symbol
+    ///
+    /// When a function has overloads, the implementation is not one of its signatures.
+    pub(super) fn get_signatures_of_symbol(&mut self, symbol: SymbolId) -> Vec<SignatureId> {
+        let declarations = self.symbol(symbol).declarations.clone();
+        let has_overloads = declarations.iter().any(|declaration| {
+            declaration.body().is_none()
+                && matches!(
+                    declaration.kind,
+                    SyntaxKind::FunctionDeclaration
+                        | SyntaxKind::MethodDeclaration
+                        | SyntaxKind::Constructor
+                )
+        });
+        let mut signatures = Vec::new();
+        for declaration in &declarations {
+            if has_overloads
+                && declaration.body().is_some()
+                && matches!(
+                    declaration.kind,
+                    SyntaxKind::FunctionDeclaration
+                        | SyntaxKind::MethodDeclaration
+                        | SyntaxKind::Constructor
+                )
+            {
+                continue;
+            }
+            if let Some(signature) = self.get_signature_of_declaration(declaration) {
+                signatures.push(signature);
+            }
+        }
+        signatures
+    }
+
+    /// Resolves the members of a type literal, or of the type of a function, class, enum, or
+    /// namespace
+    fn resolve_anonymous_type_members(&mut self, type_: TypeId) -> ResolvedMembers {
+        let mut members = ResolvedMembers::default();
+        let Some(symbol) = self.get_type(type_).symbol else {
+            return members;
+        };
+        let flags = self.symbol(symbol).flags;
+        if flags.contains(SymbolFlags::TYPE_LITERAL) {
+            let table = self.symbol(symbol).members.clone();
+            self.add_declared_members(&mut members, &table);
+            let late_bound = self.get_late_bound_members(symbol, false);
+            self.add_declared_members(&mut members, &late_bound);
+            return members;
+        }
+        if flags.intersects(SymbolFlags::FUNCTION | SymbolFlags::METHOD) {
+            members.call_signatures = self.get_signatures_of_symbol(symbol);
+        }
+        if flags.intersects(SymbolFlags::CLASS) {
+            self.resolve_class_constructor_members(symbol, &mut members);
+        }
+        if flags.intersects(SymbolFlags::ENUM | SymbolFlags::VALUE_MODULE | SymbolFlags::CLASS) {
+            let exports = self.get_exports_of_module(symbol);
+            let mut entries: Vec<(String, SymbolId)> = exports.into_iter().collect();
+            entries.sort_by_key(|&(_, symbol)| symbol);
+            for (name, export) in entries {
+                let export = self.get_merged_symbol(export);
+                let target = self.resolve_alias_if_alias(export);
+                let is_value = target == self.unknown_symbol
+                    || self.symbol(target).flags.intersects(SymbolFlags::VALUE);
+                if is_value && name != internal_symbol_names::EXPORT_STAR {
+                    members.add_property(&name, export);
+                }
+            }
+        }
+        if flags.intersects(SymbolFlags::CLASS) {
+            let late_bound = self.get_late_bound_members(symbol, true);
+            self.add_declared_members(&mut members, &late_bound);
+        }
+        members
+    }
+
+    /// Adds the construct signatures and inherited static members of a class constructor
+    fn resolve_class_constructor_members(
+        &mut self,
+        symbol: SymbolId,
+        members: &mut ResolvedMembers,
+    ) {
+        let class_type = self.get_declared_type_of_symbol(symbol);
+        let type_parameters = match &self.get_type(class_type).data {
+            TypeData::Interface(interface) => interface.local_type_parameters.clone(),
+            _ => Vec::new(),
+        };
+        let is_abstract = self
+            .symbol(symbol)
+            .declarations
+            .iter()
+            .any(|declaration| declaration.has_modifier(SyntaxKind::AbstractKeyword));
+        let base_constructor_type = self.get_base_constructor_type(symbol);
+
+        if let Some(constructor) = self
+            .symbol(symbol)
+            .members
+            .get(internal_symbol_names::CONSTRUCTOR)
+            .copied()
+        {
+            members.construct_signatures = self.get_signatures_of_symbol(constructor);
+        }
+        if members.construct_signatures.is_empty() {
+            let base_signatures = match base_constructor_type {
+                Some(base) => self.get_signatures_of_type(base, true),
+                None => Vec::new(),
+            };
+            if base_signatures.is_empty() {
+                members
+                    .construct_signatures
+                    .push(self.create_signature(Signature {
+                        flags: SignatureFlags::CONSTRUCT,
+                        declaration: None,
+                        type_parameters: type_parameters.clone(),
+                        this_parameter: None,
+                        parameters: Vec::new(),
+                        min_argument_count: 0,
+                        resolved_return_type: Some(class_type),
+                        target: None,
+                        mapper: None,
+                    }));
+            } else {
+                // A derived class without a constructor has the constructors of its base
+                for base_signature in base_signatures {
+                    let mut signature = self.get_signature(base_signature).clone();
+                    signature.type_parameters = type_parameters.clone();
+                    signature.resolved_return_type = Some(class_type);
+                    signature.target = None;
+                    signature.mapper = None;
+                    if signature.target.is_none() && signature.declaration.is_none() {
+                        signature.flags |= SignatureFlags::CONSTRUCT;
+                    }
+                    members
+                        .construct_signatures
+                        .push(self.create_signature(signature));
+                }
+            }
+        }
+        if is_abstract {
+            for &signature in &members.construct_signatures {
+                self.signatures[signature.index()].flags |= SignatureFlags::ABSTRACT;
+            }
+        }
+
+        // Static members are inherited from the base class constructor
+        if let Some(base) = base_constructor_type {
+            let base_members = self.resolve_structured_type_members(base);
+            for &property in &base_members.properties {
+                let name = self.symbol(property).name.clone();
+                if name != "prototype" && !self.symbol(symbol).exports.contains_key(&name) {
+                    members.add_property(&name, property);
+                }
+            }
+        }
+    }
+
+    /// Gets the type of the expression a class extends, if it extends an object type
+    pub(super) fn get_base_constructor_type(&mut self, symbol: SymbolId) -> Option<TypeId> {
+        let declaration = self
+            .symbol(symbol)
+            .declarations
+            .iter()
+            .find(|declaration| {
+                matches!(
+                    declaration.kind,
+                    SyntaxKind::ClassDeclaration | SyntaxKind::ClassExpression
+                )
+            })?
+            .clone();
+        let clauses = match declaration.kind {
+            SyntaxKind::ClassDeclaration => {
+                declaration.as_class_declaration().heritage_clauses.clone()
+            }
+            _ => declaration.as_class_expression().heritage_clauses.clone(),
+        };
+        let extends = clauses?
+            .iter()
+            .find(|clause| clause.as_heritage_clause().token == SyntaxKind::ExtendsKeyword)?
+            .as_heritage_clause()
+            .types
+            .iter()
+            .next()?
+            .clone();
+        let expression = &extends.as_expression_with_type_arguments().expression;
+        let type_ = self.check_expression(expression);
+        self.flags(type_)
+            .contains(TypeFlags::OBJECT)
+            .then_some(type_)
+    }
+
+    /// Gets the type whose members a type has when its properties are accessed, such as the
+    /// global `String` interface for string types
+    pub fn get_apparent_type(&mut self, type_: TypeId) -> TypeId {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::TYPE_PARAMETER) {
+            return match self.get_constraint_of_type_parameter(type_) {
+                Some(constraint) if constraint != type_ => self.get_apparent_type(constraint),
+                _ => self.empty_object_type,
+            };
+        }
+        if flags.contains(TypeFlags::CONDITIONAL) {
+            let constraint = self.get_default_constraint_of_conditional_type(type_);
+            return self.get_apparent_type(constraint);
+        }
+        if flags.contains(TypeFlags::INDEX) {
+            return self.get_keyof_constraint_type();
+        }
+        if flags.contains(TypeFlags::INDEXED_ACCESS) {
+            return match self.get_constraint_of_indexed_access(type_) {
+                Some(constraint) => self.get_apparent_type(constraint),
+                None => self.empty_object_type,
+            };
+        }
+        if flags.intersects(TypeFlags::STRING_LIKE) {
+            self.get_global_type("String")
+        } else if flags.intersects(TypeFlags::NUMBER_LIKE) {
+            self.get_global_type("Number")
+        } else if flags.intersects(TypeFlags::BIG_INT_LIKE) {
+            self.get_global_type("BigInt")
+        } else if flags.intersects(TypeFlags::BOOLEAN_LIKE) {
+            self.get_global_type("Boolean")
+        } else if flags.intersects(TypeFlags::ES_SYMBOL_LIKE) {
+            self.get_global_type("Symbol")
+        } else if flags.contains(TypeFlags::NON_PRIMITIVE) {
+            self.empty_object_type
+        } else {
+            type_
+        }
+    }
+
+    /// Determines if property accesses on a type are unchecked because the global type it
+    /// stands for is not declared in the program
+    pub(super) fn is_permissive_type(&mut self, type_: TypeId) -> bool {
+        let apparent = self.get_apparent_type(type_);
+        if self.is_fallback_type(apparent) {
+            return true;
+        }
+        match self.get_type(apparent).data.clone() {
+            TypeData::UnionOrIntersection { types } => types
+                .into_iter()
+                .any(|type_| self.is_permissive_type(type_)),
+            TypeData::Resolved { .. } | TypeData::Anonymous { .. } => {
+                // Functions and classes have the members of the global `Function` type
+                let members = self.resolve_structured_type_members(apparent);
+                if !members.call_signatures.is_empty() || !members.construct_signatures.is_empty() {
+                    let construct = members.call_signatures.is_empty();
+                    let function = self.get_global_function_type(construct);
+                    self.is_fallback_type(function)
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Gets the property of a type with the given name
+    pub fn get_property_of_type(&mut self, type_: TypeId, name: &str) -> Option<SymbolId> {
+        let type_ = self.get_apparent_type(type_);
+        let flags = self.flags(type_);
+        if flags.intersects(TypeFlags::UNION_OR_INTERSECTION) {
+            return self.get_union_or_intersection_property(type_, name);
+        }
+        if !flags.contains(TypeFlags::OBJECT) {
+            return None;
+        }
+        let members = self.resolve_structured_type_members(type_);
+        if let Some(&property) = members.members.get(name) {
+            return Some(property);
+        }
+        if members.call_signatures.is_empty() && members.construct_signatures.is_empty() {
+            return None;
+        }
+        let function = self.get_global_function_type(members.call_signatures.is_empty());
+        if function == type_ {
+            return None;
+        }
+        self.get_property_of_type(function, name)
+    }
+
+    /// Gets the global type whose members functions have, or classes when `construct` is set
+    ///
+    /// Under `strictBindCallApply` these are `CallableFunction` and `NewableFunction`, which
+    /// type `bind`, `call`, and `apply` by the function's signature, when the program declares
+    /// them.
+    pub(super) fn get_global_function_type(&mut self, construct: bool) -> TypeId {
+        if self.options.strict_bind_call_apply {
+            let name = if construct {
+                "NewableFunction"
+            } else {
+                "CallableFunction"
+            };
+            let type_ = self.get_global_type(name);
+            if !self.is_fallback_type(type_) {
+                return type_;
+            }
+        }
+        self.get_global_type("Function")
+    }
+
+    /// Gets the property of a union that every constituent has, or of an intersection that
+    /// any constituent has, combining their types
+    fn get_union_or_intersection_property(
+        &mut self,
+        type_: TypeId,
+        name: &str,
+    ) -> Option<SymbolId> {
+        let key = (type_, name.to_string());
+        if let Some(&property) = self.union_properties.get(&key) {
+            return property;
+        }
+        let is_union = self.flags(type_).contains(TypeFlags::UNION);
+        let TypeData::UnionOrIntersection { types } = self.get_type(type_).data.clone() else {
+            return None;
+        };
+        let mut properties = Vec::new();
+        let mut missing = false;
+        for constituent in types {
+            match self.get_property_of_type(constituent, name) {
+                Some(property) => {
+                    if !properties.contains(&property) {
+                        properties.push(property);
+                    }
+                }
+                None => missing = true,
+            }
+        }
+        let result = if properties.is_empty() || (is_union && missing) {
+            None
+        } else if properties.len() == 1 {
+            Some(properties[0])
+        } else {
+            Some(self.create_synthetic_property(name, &properties, is_union))
+        };
+        self.union_properties.insert(key, result);
+        result
+    }
+
+    fn create_synthetic_property(
+        &mut self,
+        name: &str,
+        properties: &[SymbolId],
+        is_union: bool,
+    ) -> SymbolId {
+        let mut types = Vec::new();
+        let mut declarations: Vec<Arc<Node>> = Vec::new();
+        let mut optional = !is_union;
+        let mut readonly = false;
+        for &property in properties {
+            types.push(self.get_type_of_symbol(property));
+            let symbol = self.symbol(property);
+            let is_optional = symbol.flags.contains(SymbolFlags::OPTIONAL);
+            optional = if is_union {
+                optional || is_optional
+            } else {
+                optional && is_optional
+            };
+            readonly |= symbol.check_flags.contains(CheckFlags::READONLY);
+            declarations.extend(symbol.declarations.iter().cloned());
+        }
+        let type_ = if is_union {
+            self.get_union_type(&types)
+        } else {
+            self.get_intersection_type(&types)
+        };
+        let symbol = self.create_transient_property(name, type_);
+        let symbol_data = &mut self.bindings.symbols[symbol];
+        if optional {
+            symbol_data.flags |= SymbolFlags::OPTIONAL;
+        }
+        symbol_data.check_flags |= CheckFlags::SYNTHETIC_PROPERTY;
+        if readonly {
+            symbol_data.check_flags |= CheckFlags::READONLY;
+        }
+        symbol_data.value_declaration = declarations.first().cloned();
+        symbol_data.declarations = declarations;
+        symbol
+    }
+
+    /// Determines if a property is readonly, either by its declaration, because it has a
+    /// getter but no setter, or because it was synthesized as readonly. Mapped properties
+    /// decide for themselves, since `-readonly` removes the modifier of the property they map
+    pub(super) fn is_readonly_property(&self, property: SymbolId) -> bool {
+        let symbol = self.symbol(property);
+        symbol.check_flags.contains(CheckFlags::READONLY)
+            || symbol.flags.contains(SymbolFlags::GET_ACCESSOR)
+                && !symbol.flags.contains(SymbolFlags::SET_ACCESSOR)
+            || !symbol.check_flags.contains(CheckFlags::MAPPED)
+                && symbol
+                    .declarations
+                    .iter()
+                    .any(|declaration| declaration.has_modifier(SyntaxKind::ReadonlyKeyword))
+    }
+
+    /// Gets the properties of a type in declaration order
+    pub fn get_properties_of_type(&mut self, type_: TypeId) -> Vec<SymbolId> {
+        let type_ = self.get_apparent_type(type_);
+        let flags = self.flags(type_);
+        if flags.intersects(TypeFlags::UNION_OR_INTERSECTION) {
+            let TypeData::UnionOrIntersection { types } = self.get_type(type_).data.clone() else {
+                return Vec::new();
+            };
+            let mut names = Vec::new();
+            let candidates: &[TypeId] = if flags.contains(TypeFlags::UNION) {
+                &types[..1]
+            } else {
+                &types
+            };
+            for &constituent in candidates {
+                for property in self.get_properties_of_type(constituent) {
+                    let name = self.symbol(property).name.clone();
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+            return names
+                .iter()
+                .filter_map(|name| self.get_union_or_intersection_property(type_, name))
+                .collect();
+        }
+        if !flags.contains(TypeFlags::OBJECT) {
+            return Vec::new();
+        }
+        self.resolve_structured_type_members(type_)
+            .properties
+            .clone()
+    }
+
+    /// Gets the call signatures of a type, or its construct signatures if `construct` is set
+    pub fn get_signatures_of_type(&mut self, type_: TypeId, construct: bool) -> Vec<SignatureId> {
+        let type_ = self.get_apparent_type(type_);
+        let flags = self.flags(type_);
+        if flags.intersects(TypeFlags::UNION_OR_INTERSECTION) {
+            let TypeData::UnionOrIntersection { types } = self.get_type(type_).data.clone() else {
+                return Vec::new();
+            };
+            let lists: Vec<Vec<SignatureId>> = types
+                .iter()
+                .map(|&constituent| self.get_signatures_of_type(constituent, construct))
+                .collect();
+            if flags.contains(TypeFlags::INTERSECTION) {
+                return lists.into_iter().flatten().collect();
+            }
+            return self.get_union_signatures(lists);
+        }
+        if !flags.contains(TypeFlags::OBJECT) {
+            return Vec::new();
+        }
+        let members = self.resolve_structured_type_members(type_);
+        if construct {
+            members.construct_signatures.clone()
+        } else {
+            members.call_signatures.clone()
+        }
+    }
+
+    /// Combines the signatures of the constituents of a union, which can only be called when
+    /// each constituent has exactly one signature
+    pub(super) fn get_union_signatures(
+        &mut self,
+        lists: Vec<Vec<SignatureId>>,
+    ) -> Vec<SignatureId> {
+        if lists.iter().any(|list| list.len() != 1) {
+            // Identical signature lists can be called as any one constituent
+            return match lists.first() {
+                Some(first) if !first.is_empty() && lists.iter().all(|list| list == first) => {
+                    first.clone()
+                }
+                _ => Vec::new(),
+            };
+        }
+        let signatures: Vec<SignatureId> = lists.into_iter().map(|list| list[0]).collect();
+        if signatures
+            .iter()
+            .all(|&signature| signature == signatures[0])
+        {
+            return vec![signatures[0]];
+        }
+        let return_types: Vec<TypeId> = signatures
+            .iter()
+            .map(|&signature| self.get_return_type_of_signature(signature))
+            .collect();
+        let return_type = self.get_union_type(&return_types);
+        let longest = *signatures
+            .iter()
+            .max_by_key(|&&signature| self.get_signature(signature).parameters.len())
+            .unwrap();
+        let mut union_signature = self.get_signature(longest).clone();
+        union_signature.min_argument_count = signatures
+            .iter()
+            .map(|&signature| self.get_signature(signature).min_argument_count)
+            .max()
+            .unwrap_or(0);
+        union_signature.resolved_return_type = Some(return_type);
+        union_signature.target = None;
+        union_signature.mapper = None;
+        vec![self.create_signature(union_signature)]
+    }
+
+    /// Gets the index signature that applies to keys of the given type
+    ///
+    /// A string index signature also applies to number keys.
+    pub(super) fn get_applicable_index_info(
+        &mut self,
+        type_: TypeId,
+        key_type: TypeId,
+    ) -> Option<IndexInfo> {
+        let type_ = self.get_apparent_type(type_);
+        let flags = self.flags(type_);
+        if flags.intersects(TypeFlags::UNION_OR_INTERSECTION) {
+            let TypeData::UnionOrIntersection { types } = self.get_type(type_).data.clone() else {
+                return None;
+            };
+            let mut value_types = Vec::new();
+            for constituent in types {
+                match self.get_applicable_index_info(constituent, key_type) {
+                    Some(info) => value_types.push(info.value_type),
+                    None if flags.contains(TypeFlags::UNION) => return None,
+                    None => {}
+                }
+            }
+            if value_types.is_empty() {
+                return None;
+            }
+            let value_type = if flags.contains(TypeFlags::UNION) {
+                self.get_union_type(&value_types)
+            } else {
+                self.get_intersection_type(&value_types)
+            };
+            return Some(IndexInfo {
+                key_type,
+                value_type,
+                is_readonly: false,
+                declaration: None,
+            });
+        }
+        if !flags.contains(TypeFlags::OBJECT) {
+            return None;
+        }
+        let members = self.resolve_structured_type_members(type_);
+        let is_number_key = self.flags(key_type).intersects(TypeFlags::NUMBER_LIKE);
+        members
+            .index_infos
+            .iter()
+            .find(|info| is_number_key && info.key_type == self.number_type)
+            .or_else(|| {
+                members
+                    .index_infos
+                    .iter()
+                    .find(|info| info.key_type == self.string_type)
+            })
+            .cloned()
+    }
+}
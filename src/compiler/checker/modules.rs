@@ -0,0 +1,669 @@
+//! External modules: the modules that module names resolve to, what they export, and the
+//! declarations in module files that add to other scopes
+//!
+//! A module name resolves to a file of the program or, when it is not a relative name, to an
+//! ambient module declared with `declare module "name"`. Names that resolve to neither may
+//! still match the pattern of an ambient module like `declare module "*.css"`. A module exports
+//! what it declares as exported along with what its `export *` declarations re-export, where a
+//! name two stars re-export different declarations by is ambiguous. In a module file,
+//! `declare global` adds to the global scope, and `declare module "name"` augments the module
+//! the name resolves to, merging into it as though declared there. An `import()` call loads a
+//! module at runtime, so its type is a promise of the module's type.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{get_source_file_of_node, is_external_module};
+use crate::compiler::ast::visitor::find_ancestor;
+use crate::compiler::ast::{
+    Node, NodeFlags, SymbolFlags, SymbolId, SymbolTable, SyntaxKind, internal_symbol_names,
+};
+use crate::compiler::binder::is_instantiated_module;
+use crate::compiler::diagnostics;
+use crate::compiler::module_resolution::is_external_module_name_relative;
+use crate::compiler::transformers::ModuleKind;
+
+use super::{Checker, TypeFlags, TypeId};
+
+impl Checker {
+    /// Finds the symbol of the module a module name refers to: an ambient module declared
+    /// with the name, or the file the name resolved to from the file containing `location`
+    pub(super) fn resolve_external_module_name(
+        &mut self,
+        location: &Arc<Node>,
+        specifier: &Node,
+    ) -> Option<SymbolId> {
+        let module_name = specifier.text();
+        if !is_external_module_name_relative(module_name)
+            && let Some(&ambient) = self.globals.get(&format!("\"{module_name}\""))
+        {
+            return Some(self.get_merged_symbol(ambient));
+        }
+        let importing_file = get_source_file_of_node(location);
+        let key = (
+            importing_file.as_source_file().file_name.clone(),
+            module_name.to_string(),
+        );
+        let file = self.resolved_modules.get(&key).and_then(|file_name| {
+            self.files
+                .iter()
+                .find(|file| file.as_source_file().file_name == *file_name)
+        });
+        let module = match file {
+            Some(file) => self.bindings.symbol_of_node(file)?,
+            None => self.find_pattern_ambient_module(module_name)?,
+        };
+        Some(self.get_merged_symbol(module))
+    }
+
+    /// Finds the ambient module declared with a pattern like `"*.css"` that matches a module
+    /// name, preferring the pattern with the longest text before its `*`
+    fn find_pattern_ambient_module(&self, module_name: &str) -> Option<SymbolId> {
+        self.globals
+            .iter()
+            .filter_map(|(name, &symbol)| {
+                let pattern = name.strip_prefix('"')?.strip_suffix('"')?;
+                let (prefix, suffix) = pattern.split_once('*')?;
+                let matches = module_name.len() >= prefix.len() + suffix.len()
+                    && module_name.starts_with(prefix)
+                    && module_name.ends_with(suffix);
+                matches.then_some((prefix.len(), symbol))
+            })
+            .max()
+            .map(|(_, symbol)| symbol)
+    }
+
+    /// Gets the exports of a module, including those its `export *` declarations re-export
+    /// from other modules, which never include their default exports
+    pub(super) fn get_exports_of_module(&mut self, module: SymbolId) -> SymbolTable {
+        if let Some(exports) = &self.symbol_links(module).resolved_exports {
+            return exports.clone();
+        }
+        let exports = self.collect_exports_of_module(module, &mut Vec::new());
+        self.symbol_links(module).resolved_exports = Some(exports.clone());
+        exports
+    }
+
+    fn collect_exports_of_module(
+        &mut self,
+        module: SymbolId,
+        visited: &mut Vec<SymbolId>,
+    ) -> SymbolTable {
+        // A module that assigns its exports as a whole exports what that value does
+        let target = self.resolve_external_module_symbol(module);
+        if target != module {
+            return self.symbol(target).exports.clone();
+        }
+        let mut exports = self.symbol(module).exports.clone();
+        let Some(export_star) = exports.remove(internal_symbol_names::EXPORT_STAR) else {
+            return exports;
+        };
+        // Modules that re-export each other stop at the first module seen again
+        if visited.contains(&module) {
+            return exports;
+        }
+        visited.push(module);
+        // Names re-exported by more than one star, with the specifier of the first star to
+        // export each and the declarations of the stars that export something else by it
+        let mut nested = SymbolTable::new();
+        let mut first_specifiers: HashMap<String, String> = HashMap::new();
+        let mut duplicates: Vec<(String, Arc<Node>)> = Vec::new();
+        let declarations = self.symbol(export_star).declarations.clone();
+        for declaration in declarations {
+            let Some(specifier) = &declaration.as_export_declaration().module_specifier else {
+                continue;
+            };
+            let Some(target) = self.resolve_external_module_name(&declaration, specifier) else {
+                continue;
+            };
+            let mut reexports: Vec<(String, SymbolId)> = self
+                .collect_exports_of_module(target, visited)
+                .into_iter()
+                .collect();
+            reexports.sort_by_key(|&(_, symbol)| symbol);
+            for (name, symbol) in reexports {
+                if name == internal_symbol_names::DEFAULT
+                    || name == internal_symbol_names::EXPORT_EQUALS
+                {
+                    continue;
+                }
+                match nested.get(&name) {
+                    Some(&existing) => {
+                        if self.resolve_alias_if_alias(existing)
+                            != self.resolve_alias_if_alias(symbol)
+                        {
+                            duplicates.push((name, Arc::clone(&declaration)));
+                        }
+                    }
+                    None => {
+                        first_specifiers.insert(name.clone(), specifier.text().to_string());
+                        nested.insert(name, symbol);
+                    }
+                }
+            }
+        }
+        // Names the module declares itself are unambiguous, as they shadow what stars export
+        for (name, declaration) in duplicates {
+            if !exports.contains_key(&name) {
+                self.error(
+                    &declaration,
+                    diagnostics::MODULE_0_HAS_ALREADY_EXPORTED_A_MEMBER_NAMED_1_CONSIDER_EXPLICITLY_RE_EXPORTING_TO_RESOLVE_THE_AMBIGUITY_2308,
+                    &[format!("\"{}\"", first_specifiers[&name]), name],
+                );
+            }
+        }
+        for (name, symbol) in nested {
+            exports.entry(name).or_insert(symbol);
+        }
+        exports
+    }
+
+    /// Resolves the exports of a module file, which reports the names its `export *`
+    /// declarations re-export ambiguously
+    pub(super) fn check_external_module_exports(&mut self, file: &Arc<Node>) {
+        if let Some(module) = self.bindings.symbol_of_node(file) {
+            let module = self.get_merged_symbol(module);
+            self.get_exports_of_module(module);
+        }
+    }
+
+    /// Resolves a module to what it exports as a whole: what its `export =` assigns, when it
+    /// has one, or else the module itself
+    pub(super) fn resolve_external_module_symbol(&mut self, module: SymbolId) -> SymbolId {
+        let export_equals = self
+            .symbol(module)
+            .exports
+            .get(internal_symbol_names::EXPORT_EQUALS)
+            .copied();
+        match export_equals {
+            Some(export_equals) => {
+                let target = self.resolve_alias_if_alias(export_equals);
+                self.get_merged_symbol(target)
+            }
+            None => module,
+        }
+    }
+
+    /// Resolves a module imported as a namespace, reporting a module whose `export =` assigns
+    /// something that is neither a namespace nor a variable, which ECMAScript imports cannot
+    /// refer to as a whole
+    fn resolve_es_module_symbol(&mut self, module: SymbolId, specifier: &Arc<Node>) -> SymbolId {
+        let target = self.resolve_external_module_symbol(module);
+        if target != self.unknown_symbol
+            && !self
+                .symbol(target)
+                .flags
+                .intersects(SymbolFlags::MODULE | SymbolFlags::VARIABLE)
+        {
+            let flag = self.synthetic_default_imports_flag_name();
+            self.error(
+                specifier,
+                diagnostics::THIS_MODULE_CAN_ONLY_BE_REFERENCED_WITH_ECMASCRIPT_IMPORTS_SLASHEXPORTS_BY_TURNING_ON_THE_0_FLAG_AND_REFERENCING_ITS_DEFAULT_EXPORT_2497,
+                &[flag.to_string()],
+            );
+        }
+        target
+    }
+
+    /// Gets the name of the flag that allows default imports of modules that assign their
+    /// exports as a whole, which for ECMAScript module output only changes the types
+    fn synthetic_default_imports_flag_name(&self) -> &'static str {
+        match self.options.module {
+            None
+            | Some(
+                ModuleKind::None
+                | ModuleKind::CommonJS
+                | ModuleKind::AMD
+                | ModuleKind::UMD
+                | ModuleKind::System,
+            ) => "esModuleInterop",
+            Some(_) => "allowSyntheticDefaultImports",
+        }
+    }
+
+    /// Determines if the program is compiled to ECMAScript modules, which have no equivalent of
+    /// `import x = require("m")` or `export =`
+    fn targets_ecmascript_modules(&self) -> bool {
+        matches!(
+            self.options.module,
+            Some(ModuleKind::ES2015 | ModuleKind::ES2020 | ModuleKind::ES2022 | ModuleKind::ESNext)
+        )
+    }
+
+    /// Resolves an import, or a re-export from another module, to what the module exports
+    /// by that name, or to the module itself for a namespace import or export
+    ///
+    /// Imports of modules that did not resolve are `any`; the program has reported them.
+    pub(super) fn resolve_import(&mut self, declaration: &Arc<Node>) -> Option<SymbolId> {
+        let statement = find_ancestor(declaration, |ancestor| {
+            matches!(
+                ancestor.kind,
+                SyntaxKind::ImportDeclaration | SyntaxKind::ExportDeclaration
+            )
+        })?;
+        let specifier = match statement.kind {
+            SyntaxKind::ImportDeclaration => {
+                Arc::clone(&statement.as_import_declaration().module_specifier)
+            }
+            _ => statement.as_export_declaration().module_specifier.clone()?,
+        };
+        let module = self.resolve_external_module_name(declaration, &specifier)?;
+        let module_name = format!("\"{}\"", specifier.text());
+        let is_json_module = self
+            .symbol(module)
+            .declarations
+            .iter()
+            .any(|declaration| declaration.flags.contains(NodeFlags::JSON_FILE));
+        if is_json_module {
+            return self.resolve_json_import(declaration, module, &module_name);
+        }
+        let exports = self.get_exports_of_module(module);
+        let export_equals = self.resolve_external_module_symbol(module);
+        match declaration.kind {
+            SyntaxKind::NamespaceImport | SyntaxKind::NamespaceExport => {
+                Some(self.resolve_es_module_symbol(module, &specifier))
+            }
+            SyntaxKind::ImportClause => {
+                if let Some(&default) = exports.get(internal_symbol_names::DEFAULT) {
+                    return Some(self.get_merged_symbol(default));
+                }
+                if export_equals != module {
+                    if self.options.allow_synthetic_default_imports {
+                        return Some(export_equals);
+                    }
+                    let flag = self.synthetic_default_imports_flag_name();
+                    self.error(
+                        declaration.name().unwrap_or(declaration),
+                        diagnostics::MODULE_0_CAN_ONLY_BE_DEFAULT_IMPORTED_USING_THE_1_FLAG_1259,
+                        &[module_name, flag.to_string()],
+                    );
+                    return None;
+                }
+                self.error(
+                    declaration.name().unwrap_or(declaration),
+                    diagnostics::MODULE_0_HAS_NO_DEFAULT_EXPORT_1192,
+                    &[module_name],
+                );
+                None
+            }
+            _ => {
+                let name = match declaration.kind {
+                    SyntaxKind::ImportSpecifier => {
+                        let specifier = declaration.as_import_specifier();
+                        Arc::clone(specifier.property_name.as_ref().unwrap_or(&specifier.name))
+                    }
+                    _ => {
+                        let specifier = declaration.as_export_specifier();
+                        Arc::clone(specifier.property_name.as_ref().unwrap_or(&specifier.name))
+                    }
+                };
+                if let Some(&export) = exports.get(name.text()) {
+                    return Some(self.get_merged_symbol(export));
+                }
+                // The properties of a value assigned with `export =` can be imported by name
+                if export_equals != module
+                    && self
+                        .symbol(export_equals)
+                        .flags
+                        .intersects(SymbolFlags::VALUE)
+                {
+                    let type_ = self.get_type_of_symbol(export_equals);
+                    if let Some(property) = self.get_property_of_type(type_, name.text()) {
+                        return Some(property);
+                    }
+                }
+                self.report_missing_export(module, &module_name, &name);
+                None
+            }
+        }
+    }
+
+    /// Reports a name a module does not export, saying when the module declares it without
+    /// exporting it
+    fn report_missing_export(&mut self, module: SymbolId, module_name: &str, name: &Arc<Node>) {
+        let declared_locally = self
+            .symbol(module)
+            .declarations
+            .iter()
+            .filter(|declaration| declaration.kind == SyntaxKind::SourceFile)
+            .any(|file| {
+                self.bindings
+                    .locals(file)
+                    .is_some_and(|locals| locals.contains_key(name.text()))
+            });
+        let message = if declared_locally {
+            diagnostics::MODULE_0_DECLARES_1_LOCALLY_BUT_IT_IS_NOT_EXPORTED_2459
+        } else {
+            diagnostics::MODULE_0_HAS_NO_EXPORTED_MEMBER_1_2305
+        };
+        self.error(
+            name,
+            message,
+            &[module_name.to_string(), name.text().to_string()],
+        );
+    }
+
+    /// Resolves the names an import declaration imports, reporting those the module does not
+    /// export
+    pub(super) fn check_import_declaration(&mut self, node: &Arc<Node>) {
+        let Some(clause) = &node.as_import_declaration().import_clause else {
+            return;
+        };
+        let mut declarations = Vec::new();
+        if clause.as_import_clause().name.is_some() {
+            declarations.push(Arc::clone(clause));
+        }
+        if let Some(bindings) = &clause.as_import_clause().named_bindings {
+            match bindings.kind {
+                SyntaxKind::NamedImports => {
+                    declarations.extend(bindings.as_named_imports().elements.iter().cloned())
+                }
+                _ => declarations.push(Arc::clone(bindings)),
+            }
+        }
+        for declaration in declarations {
+            if let Some(symbol) = self.bindings.symbol_of_node(&declaration) {
+                self.resolve_alias(symbol);
+            }
+        }
+    }
+
+    /// Checks that a namespace merged with a class or function follows it in the same file,
+    /// since the namespace adds its values to the class or function when it runs
+    pub(super) fn check_module_declaration(&mut self, node: &Arc<Node>) {
+        if node.flags.contains(NodeFlags::AMBIENT)
+            || node.as_module_declaration().name.kind != SyntaxKind::Identifier
+            || !is_instantiated_module(node)
+        {
+            return;
+        }
+        let Some(symbol) = self.get_symbol_of_declaration(node) else {
+            return;
+        };
+        let Some(merged) = self
+            .symbol(symbol)
+            .declarations
+            .iter()
+            .find(|declaration| {
+                matches!(
+                    declaration.kind,
+                    SyntaxKind::ClassDeclaration | SyntaxKind::FunctionDeclaration
+                ) && !declaration.flags.contains(NodeFlags::AMBIENT)
+            })
+            .cloned()
+        else {
+            return;
+        };
+        let name = &node.as_module_declaration().name;
+        if !Arc::ptr_eq(
+            &get_source_file_of_node(node),
+            &get_source_file_of_node(&merged),
+        ) {
+            self.error(
+                name,
+                diagnostics::A_NAMESPACE_DECLARATION_CANNOT_BE_IN_A_DIFFERENT_FILE_FROM_A_CLASS_OR_FUNCTION_WITH_WHICH_IT_IS_MERGED_2433,
+                &[],
+            );
+        } else if node.pos() < merged.pos() {
+            self.error(
+                name,
+                diagnostics::A_NAMESPACE_DECLARATION_CANNOT_BE_LOCATED_PRIOR_TO_A_CLASS_OR_FUNCTION_WITH_WHICH_IT_IS_MERGED_2434,
+                &[],
+            );
+        }
+    }
+
+    /// Gets the type of an `import()` call: a promise of the module its specifier names, or
+    /// of `any` when the specifier is not a string literal or names no module
+    pub(super) fn check_import_call_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let arguments: Vec<Arc<Node>> = node
+            .as_call_expression()
+            .arguments
+            .iter()
+            .cloned()
+            .collect();
+        self.check_grammar_import_call(node, &arguments);
+        for argument in &arguments {
+            self.check_expression(argument);
+        }
+        let Some(specifier) = arguments.first() else {
+            return self.create_promise_return_type(node, self.any_type);
+        };
+        let specifier_type = self.check_expression(specifier);
+        if self
+            .flags(specifier_type)
+            .intersects(TypeFlags::UNDEFINED | TypeFlags::NULL)
+            || !self.is_type_assignable_to(specifier_type, self.string_type)
+        {
+            let type_name = self.type_to_string(specifier_type);
+            self.error(
+                specifier,
+                diagnostics::DYNAMIC_IMPORT_S_SPECIFIER_MUST_BE_OF_TYPE_STRING_BUT_HERE_HAS_TYPE_0_7036,
+                &[type_name],
+            );
+        }
+        let module = match specifier.kind {
+            SyntaxKind::StringLiteral | SyntaxKind::NoSubstitutionTemplateLiteral => {
+                self.resolve_external_module_name(node, specifier)
+            }
+            _ => None,
+        };
+        let module_type = match module {
+            Some(module) => {
+                let target = self.resolve_external_module_symbol(module);
+                self.get_type_of_symbol(target)
+            }
+            None => self.any_type,
+        };
+        self.create_promise_return_type(node, module_type)
+    }
+
+    /// Reports an `import()` call the module option does not allow, or one with arguments
+    /// other than a specifier and import attributes
+    fn check_grammar_import_call(&mut self, node: &Arc<Node>, arguments: &[Arc<Node>]) {
+        if self.options.module == Some(ModuleKind::ES2015) {
+            self.error(
+                node,
+                diagnostics::DYNAMIC_IMPORTS_ARE_ONLY_SUPPORTED_WHEN_THE_MODULE_FLAG_IS_SET_TO_ES2020_ES2022_ESNEXT_COMMONJS_AMD_SYSTEM_UMD_NODE16_OR_NODENEXT_1323,
+                &[],
+            );
+            return;
+        }
+        let supports_attributes = self.options.module.is_none_or(|module| {
+            matches!(
+                module,
+                ModuleKind::ESNext
+                    | ModuleKind::Node16
+                    | ModuleKind::Node18
+                    | ModuleKind::NodeNext
+                    | ModuleKind::Preserve
+            )
+        });
+        if !supports_attributes && let Some(attributes) = arguments.get(1) {
+            self.error(
+                attributes,
+                diagnostics::DYNAMIC_IMPORTS_ONLY_SUPPORT_A_SECOND_ARGUMENT_WHEN_THE_MODULE_OPTION_IS_SET_TO_ESNEXT_NODE16_NODENEXT_OR_PRESERVE_1324,
+                &[],
+            );
+            return;
+        }
+        if arguments.is_empty() || arguments.len() > 2 {
+            self.error(
+                node,
+                diagnostics::DYNAMIC_IMPORTS_CAN_ONLY_ACCEPT_A_MODULE_SPECIFIER_AND_AN_OPTIONAL_SET_OF_ATTRIBUTES_AS_ARGUMENTS_1450,
+                &[],
+            );
+            return;
+        }
+        if let Some(spread) = arguments
+            .iter()
+            .find(|argument| argument.kind == SyntaxKind::SpreadElement)
+        {
+            self.error(
+                spread,
+                diagnostics::ARGUMENT_OF_DYNAMIC_IMPORT_CANNOT_BE_SPREAD_ELEMENT_1325,
+                &[],
+            );
+        }
+    }
+
+    /// Gets the type of `import.meta`, the global `ImportMeta` interface, reporting it when
+    /// the module option compiles to a module system without it
+    pub(super) fn check_import_meta_property(&mut self, node: &Arc<Node>) -> TypeId {
+        let unsupported = matches!(
+            self.options.module,
+            Some(
+                ModuleKind::None
+                    | ModuleKind::CommonJS
+                    | ModuleKind::AMD
+                    | ModuleKind::UMD
+                    | ModuleKind::ES2015
+            )
+        );
+        if unsupported {
+            self.error(
+                node,
+                diagnostics::THE_IMPORT_META_META_PROPERTY_IS_ONLY_ALLOWED_WHEN_THE_MODULE_OPTION_IS_ES2020_ES2022_ESNEXT_SYSTEM_NODE16_OR_NODENEXT_1343,
+                &[],
+            );
+        }
+        self.get_global_type("ImportMeta")
+    }
+
+    /// Checks an `import x = require("m")` declaration, which ECMAScript modules cannot
+    /// compile, or an import of a namespace member with `import x = N.m`
+    pub(super) fn check_import_equals_declaration(&mut self, node: &Arc<Node>) {
+        let reference = &node.as_import_equals_declaration().module_reference;
+        if reference.kind == SyntaxKind::ExternalModuleReference
+            && self.targets_ecmascript_modules()
+            && !node.flags.contains(NodeFlags::AMBIENT)
+        {
+            self.error(
+                node,
+                diagnostics::IMPORT_ASSIGNMENT_CANNOT_BE_USED_WHEN_TARGETING_ECMASCRIPT_MODULES_CONSIDER_USING_IMPORT_ASTERISK_AS_NS_FROM_MOD_IMPORT_A_FROM_MOD_IMPORT_D_FROM_MOD_OR_ANOTHER_MODULE_FORMAT_INSTEAD_1202,
+                &[],
+            );
+        }
+        if let Some(symbol) = self.bindings.symbol_of_node(node) {
+            self.resolve_alias(symbol);
+        }
+    }
+
+    /// Checks an `export =` or `export default` assignment; a module that assigns its exports
+    /// as a whole cannot export anything else, and ECMAScript modules cannot compile it
+    pub(super) fn check_export_assignment(&mut self, node: &Arc<Node>) {
+        let assignment = node.as_export_assignment();
+        self.check_expression(&assignment.expression);
+        if !assignment.is_export_equals {
+            return;
+        }
+        if self.targets_ecmascript_modules() && !node.flags.contains(NodeFlags::AMBIENT) {
+            self.error(
+                node,
+                diagnostics::EXPORT_ASSIGNMENT_CANNOT_BE_USED_WHEN_TARGETING_ECMASCRIPT_MODULES_CONSIDER_USING_EXPORT_DEFAULT_OR_ANOTHER_MODULE_FORMAT_INSTEAD_1203,
+                &[],
+            );
+        }
+        let has_other_exports = node
+            .parent()
+            .and_then(|container| {
+                let container = match container.kind {
+                    SyntaxKind::ModuleBlock => container.parent()?,
+                    _ => container,
+                };
+                self.bindings.symbol_of_node(&container)
+            })
+            .is_some_and(|module| {
+                self.symbol(module)
+                    .exports
+                    .keys()
+                    .any(|name| name != internal_symbol_names::EXPORT_EQUALS)
+            });
+        if has_other_exports {
+            self.error(
+                node,
+                diagnostics::AN_EXPORT_ASSIGNMENT_CANNOT_BE_USED_IN_A_MODULE_WITH_OTHER_EXPORTED_ELEMENTS_2309,
+                &[],
+            );
+        }
+    }
+
+    /// Merges the declarations of the `declare global` blocks of module files into the global
+    /// scope
+    pub(super) fn merge_global_augmentations(&mut self) {
+        for file in self.files.clone() {
+            if !is_external_module(&file) {
+                continue;
+            }
+            for statement in &file.as_source_file().statements {
+                if !is_global_augmentation(statement) {
+                    continue;
+                }
+                let Some(augmentation) = self.bindings.symbol_of_node(statement) else {
+                    continue;
+                };
+                let mut exports: Vec<(String, SymbolId)> = self
+                    .symbol(augmentation)
+                    .exports
+                    .clone()
+                    .into_iter()
+                    .collect();
+                exports.sort_by_key(|&(_, symbol)| symbol);
+                for (name, symbol) in exports {
+                    match self.globals.get(&name).copied() {
+                        Some(target) => self.merge_symbol(target, symbol),
+                        None => {
+                            self.bindings.symbols[symbol].parent = None;
+                            self.globals.insert(name, symbol);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Merges each `declare module "name"` block of a module file into the module the name
+    /// resolves to, reporting names that resolve to no module
+    pub(super) fn merge_module_augmentations(&mut self) {
+        for file in self.files.clone() {
+            if !is_external_module(&file) {
+                continue;
+            }
+            for statement in &file.as_source_file().statements {
+                if !is_module_augmentation(statement) {
+                    continue;
+                }
+                let name = &statement.as_module_declaration().name;
+                let Some(augmentation) = self.bindings.symbol_of_node(statement) else {
+                    continue;
+                };
+                match self.resolve_external_module_name(statement, name) {
+                    Some(module) if module != augmentation => {
+                        self.merge_symbol(module, augmentation);
+                    }
+                    Some(_) => {}
+                    None => {
+                        self.error(
+                            name,
+                            diagnostics::INVALID_MODULE_NAME_IN_AUGMENTATION_MODULE_0_CANNOT_BE_FOUND_2664,
+                            &[name.text().to_string()],
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Determines if a statement of a module file is a `declare global` block
+fn is_global_augmentation(statement: &Node) -> bool {
+    statement.kind == SyntaxKind::ModuleDeclaration
+        && statement.as_module_declaration().keyword == SyntaxKind::GlobalKeyword
+}
+
+/// Determines if a statement of a module file is a `declare module "name"` block, which
+/// augments another module rather than declaring an ambient one
+fn is_module_augmentation(statement: &Node) -> bool {
+    statement.kind == SyntaxKind::ModuleDeclaration
+        && statement.as_module_declaration().name.kind == SyntaxKind::StringLiteral
+}
@@ -0,0 +1,665 @@
+//! Global scope merging and name resolution
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{get_source_file_of_node, is_external_module};
+use crate::compiler::ast::{
+    Node, SymbolFlags, SymbolId, SymbolTable, SyntaxKind, internal_symbol_names,
+};
+use crate::compiler::binder::is_entity_name_expression;
+use crate::compiler::diagnostics::{self, DiagnosticMessageChain, Message};
+
+use super::{Checker, ResolutionTarget};
+
+/// Looks a name up in a symbol table during name resolution
+pub(super) type LookupFn = fn(&mut Checker, &SymbolTable, &str, SymbolFlags) -> Option<SymbolId>;
+
+impl Checker {
+    /// Merges the top-level declarations of every script file into the global scope
+    pub(super) fn initialize_globals(&mut self) {
+        for file in self.files.clone() {
+            if is_external_module(&file) {
+                continue;
+            }
+            if let Some(locals) = self.bindings.locals(&file).cloned() {
+                let mut locals: Vec<(String, SymbolId)> = locals.into_iter().collect();
+                locals.sort_by_key(|&(_, symbol)| symbol);
+                for (name, symbol) in locals {
+                    match self.globals.get(&name).copied() {
+                        Some(target) => {
+                            self.merge_symbol(target, symbol);
+                        }
+                        None => {
+                            self.globals.insert(name, symbol);
+                        }
+                    }
+                }
+            }
+        }
+        self.merge_global_augmentations();
+        if !self.globals.contains_key("undefined") {
+            let undefined = self
+                .bindings
+                .symbols
+                .alloc("undefined", SymbolFlags::PROPERTY | SymbolFlags::TRANSIENT);
+            self.symbol_links(undefined).type_ = Some(self.undefined_type);
+            self.globals.insert("undefined".to_string(), undefined);
+        }
+    }
+
+    /// Merges the declarations of `source` into `target`, reporting both as duplicates if they
+    /// cannot merge
+    pub(super) fn merge_symbol(&mut self, target: SymbolId, source: SymbolId) {
+        let source_flags = self.symbol(source).flags;
+        let target_flags = self.symbol(target).flags;
+        if target_flags.intersects(excluded_symbol_flags(source_flags)) {
+            self.report_merge_conflict(target, source);
+            return;
+        }
+        let source_symbol = self.bindings.symbols[source].clone();
+        let target_symbol = &mut self.bindings.symbols[target];
+        target_symbol.flags |= source_flags;
+        for declaration in &source_symbol.declarations {
+            if !target_symbol
+                .declarations
+                .iter()
+                .any(|existing| Arc::ptr_eq(existing, declaration))
+            {
+                target_symbol.declarations.push(Arc::clone(declaration));
+            }
+        }
+        if let Some(value_declaration) = &source_symbol.value_declaration
+            && target_symbol
+                .value_declaration
+                .as_ref()
+                .is_none_or(|existing| {
+                    existing.kind == SyntaxKind::ModuleDeclaration
+                        && value_declaration.kind != SyntaxKind::ModuleDeclaration
+                })
+        {
+            target_symbol.value_declaration = Some(Arc::clone(value_declaration));
+        }
+        self.merged_symbols.insert(source, target);
+        self.merge_symbol_table(target, &source_symbol.members, false);
+        self.merge_symbol_table(target, &source_symbol.exports, true);
+    }
+
+    fn merge_symbol_table(&mut self, target: SymbolId, source: &SymbolTable, exports: bool) {
+        let mut entries: Vec<(&String, &SymbolId)> = source.iter().collect();
+        entries.sort_by_key(|&(_, &symbol)| symbol);
+        for (name, &symbol) in entries {
+            let table = if exports {
+                &self.bindings.symbols[target].exports
+            } else {
+                &self.bindings.symbols[target].members
+            };
+            match table.get(name).copied() {
+                Some(existing) if existing != symbol => self.merge_symbol(existing, symbol),
+                Some(_) => {}
+                None => {
+                    let target_symbol = &mut self.bindings.symbols[target];
+                    let table = if exports {
+                        &mut target_symbol.exports
+                    } else {
+                        &mut target_symbol.members
+                    };
+                    table.insert(name.clone(), symbol);
+                    self.bindings.symbols[symbol].parent = Some(target);
+                }
+            }
+        }
+    }
+
+    fn report_merge_conflict(&mut self, target: SymbolId, source: SymbolId) {
+        let target_flags = self.symbol(target).flags;
+        let source_flags = self.symbol(source).flags;
+        let is_enum = (target_flags | source_flags).intersects(SymbolFlags::ENUM);
+        let message: &Message = if is_enum {
+            diagnostics::ENUM_DECLARATIONS_CAN_ONLY_MERGE_WITH_NAMESPACE_OR_OTHER_ENUM_DECLARATIONS_2567
+        } else if (target_flags | source_flags).intersects(SymbolFlags::BLOCK_SCOPED_VARIABLE) {
+            diagnostics::CANNOT_REDECLARE_BLOCK_SCOPED_VARIABLE_0_2451
+        } else {
+            diagnostics::DUPLICATE_IDENTIFIER_0_2300
+        };
+        let name = self.symbol(source).name.clone();
+        let args = if is_enum { Vec::new() } else { vec![name] };
+        let declarations: Vec<Arc<Node>> = self
+            .symbol(target)
+            .declarations
+            .iter()
+            .chain(&self.symbol(source).declarations)
+            .cloned()
+            .collect();
+        for declaration in declarations {
+            let error_node = declaration.name().cloned().unwrap_or(declaration);
+            self.error(&error_node, message, &args);
+        }
+    }
+
+    /// Gets the global symbol `symbol` was merged into, or `symbol` itself
+    pub(super) fn get_merged_symbol(&self, symbol: SymbolId) -> SymbolId {
+        self.merged_symbols.get(&symbol).copied().unwrap_or(symbol)
+    }
+
+    /// Gets the exported symbol of the local symbol of an exported value
+    pub(super) fn get_export_symbol_of_value_symbol_if_exported(
+        &self,
+        symbol: SymbolId,
+    ) -> SymbolId {
+        let symbol = self.get_merged_symbol(symbol);
+        match self.symbol(symbol).export_symbol {
+            Some(export)
+                if self
+                    .symbol(symbol)
+                    .flags
+                    .contains(SymbolFlags::EXPORT_VALUE) =>
+            {
+                self.get_merged_symbol(export)
+            }
+            _ => symbol,
+        }
+    }
+
+    /// Looks up `name` in `table`, returning it if it has a meaning in `meaning` or is an alias
+    /// to something that does
+    pub(super) fn lookup(
+        &mut self,
+        table: &SymbolTable,
+        name: &str,
+        meaning: SymbolFlags,
+    ) -> Option<SymbolId> {
+        let symbol = self.get_merged_symbol(*table.get(name)?);
+        let flags = self.symbol(symbol).flags;
+        if flags.intersects(meaning) {
+            return Some(symbol);
+        }
+        if flags.contains(SymbolFlags::ALIAS) {
+            let target = self.resolve_alias(symbol);
+            if target == self.unknown_symbol || self.symbol(target).flags.intersects(meaning) {
+                return Some(symbol);
+            }
+        }
+        None
+    }
+
+    /// Finds the symbol `name` refers to at `location`, walking outward through the enclosing
+    /// scopes to the global scope
+    pub(super) fn resolve_name(
+        &mut self,
+        location: &Arc<Node>,
+        name: &str,
+        meaning: SymbolFlags,
+    ) -> Option<SymbolId> {
+        self.resolve_name_helper(location, name, meaning, Checker::lookup)
+    }
+
+    /// Walks the scopes enclosing `location` like `resolve_name`, looking `name` up in each
+    /// symbol table with `lookup`
+    pub(super) fn resolve_name_helper(
+        &mut self,
+        location: &Arc<Node>,
+        name: &str,
+        meaning: SymbolFlags,
+        lookup: LookupFn,
+    ) -> Option<SymbolId> {
+        let mut last_location: Option<Arc<Node>> = None;
+        let mut current = Some(Arc::clone(location));
+        while let Some(node) = current {
+            let is_global_file = node.kind == SyntaxKind::SourceFile && !is_external_module(&node);
+            if !is_global_file && let Some(locals) = self.bindings.locals(&node).cloned() {
+                // `infer` type parameters are only in scope in the true branch
+                let in_scope = node.kind != SyntaxKind::ConditionalType
+                    || last_location.as_ref().is_some_and(|last| {
+                        Arc::ptr_eq(last, &node.as_conditional_type_node().true_type)
+                    });
+                if in_scope && let Some(result) = lookup(self, &locals, name, meaning) {
+                    return Some(result);
+                }
+            }
+            match node.kind {
+                SyntaxKind::SourceFile if is_global_file => break,
+                SyntaxKind::SourceFile | SyntaxKind::ModuleDeclaration => {
+                    if let Some(module) = self.get_symbol_of_declaration(&node)
+                        && name != "default"
+                    {
+                        let exports = self.symbol(module).exports.clone();
+                        // Export specifiers refer to local names, so they do not declare them
+                        let only_reexported = exports.get(name).is_some_and(|&export| {
+                            self.symbol(export).declarations.iter().all(|declaration| {
+                                matches!(
+                                    declaration.kind,
+                                    SyntaxKind::ExportSpecifier | SyntaxKind::NamespaceExport
+                                )
+                            })
+                        });
+                        if !only_reexported
+                            && let Some(result) =
+                                lookup(self, &exports, name, meaning & SymbolFlags::MODULE_MEMBER)
+                        {
+                            return Some(result);
+                        }
+                    }
+                }
+                SyntaxKind::EnumDeclaration => {
+                    if let Some(symbol) = self.get_symbol_of_declaration(&node) {
+                        let exports = self.symbol(symbol).exports.clone();
+                        if let Some(result) =
+                            lookup(self, &exports, name, meaning & SymbolFlags::ENUM_MEMBER)
+                        {
+                            return Some(result);
+                        }
+                    }
+                }
+                SyntaxKind::ClassDeclaration
+                | SyntaxKind::ClassExpression
+                | SyntaxKind::InterfaceDeclaration => {
+                    if let Some(symbol) = self.get_symbol_of_declaration(&node) {
+                        // Type parameters are members; other members are not in scope by name
+                        let members = self.symbol(symbol).members.clone();
+                        if let Some(result) =
+                            lookup(self, &members, name, meaning & SymbolFlags::TYPE)
+                        {
+                            return Some(result);
+                        }
+                        if node.kind == SyntaxKind::ClassExpression
+                            && meaning.intersects(SymbolFlags::CLASS)
+                            && node
+                                .name()
+                                .is_some_and(|class_name| class_name.text() == name)
+                        {
+                            return Some(symbol);
+                        }
+                    }
+                }
+                SyntaxKind::FunctionExpression
+                    if meaning.intersects(SymbolFlags::FUNCTION)
+                        && node
+                            .name()
+                            .is_some_and(|function_name| function_name.text() == name) =>
+                {
+                    return self.get_symbol_of_declaration(&node);
+                }
+                _ => {}
+            }
+            current = node.parent();
+            last_location = Some(node);
+        }
+        let globals = self.globals.clone();
+        lookup(self, &globals, name, meaning)
+    }
+
+    /// Gets the symbols that names at `location` can refer to with a meaning in `meaning`,
+    /// walking the scopes like `resolve_name`, so that a symbol hides those of the same name
+    /// in the scopes around its own
+    pub fn get_symbols_in_scope(
+        &mut self,
+        location: &Arc<Node>,
+        meaning: SymbolFlags,
+    ) -> Vec<SymbolId> {
+        let mut symbols = Vec::new();
+        let mut names = HashSet::new();
+        let mut current = Some(Arc::clone(location));
+        while let Some(node) = current {
+            let is_global_file = node.kind == SyntaxKind::SourceFile && !is_external_module(&node);
+            if !is_global_file && let Some(locals) = self.bindings.locals(&node).cloned() {
+                self.add_symbols_in_scope(&locals, meaning, &mut names, &mut symbols);
+            }
+            match node.kind {
+                SyntaxKind::SourceFile if is_global_file => break,
+                SyntaxKind::SourceFile | SyntaxKind::ModuleDeclaration => {
+                    if let Some(module) = self.get_symbol_of_declaration(&node) {
+                        let exports = self.symbol(module).exports.clone();
+                        let meaning = meaning & SymbolFlags::MODULE_MEMBER;
+                        self.add_symbols_in_scope(&exports, meaning, &mut names, &mut symbols);
+                    }
+                }
+                SyntaxKind::EnumDeclaration => {
+                    if let Some(symbol) = self.get_symbol_of_declaration(&node) {
+                        let exports = self.symbol(symbol).exports.clone();
+                        let meaning = meaning & SymbolFlags::ENUM_MEMBER;
+                        self.add_symbols_in_scope(&exports, meaning, &mut names, &mut symbols);
+                    }
+                }
+                SyntaxKind::ClassDeclaration
+                | SyntaxKind::ClassExpression
+                | SyntaxKind::InterfaceDeclaration => {
+                    if let Some(symbol) = self.get_symbol_of_declaration(&node) {
+                        let members = self.symbol(symbol).members.clone();
+                        let meaning = meaning & SymbolFlags::TYPE;
+                        self.add_symbols_in_scope(&members, meaning, &mut names, &mut symbols);
+                    }
+                }
+                _ => {}
+            }
+            current = node.parent();
+        }
+        let globals = self.globals.clone();
+        self.add_symbols_in_scope(&globals, meaning, &mut names, &mut symbols);
+        symbols
+    }
+
+    fn add_symbols_in_scope(
+        &mut self,
+        table: &SymbolTable,
+        meaning: SymbolFlags,
+        names: &mut HashSet<String>,
+        symbols: &mut Vec<SymbolId>,
+    ) {
+        if meaning.is_empty() {
+            return;
+        }
+        let mut table_names: Vec<&String> = table.keys().collect();
+        table_names.sort();
+        for name in table_names {
+            if names.contains(name) || name == "default" {
+                continue;
+            }
+            if let Some(symbol) = self.lookup(table, name, meaning) {
+                names.insert(name.clone());
+                symbols.push(symbol);
+            }
+        }
+    }
+
+    /// Resolves an identifier, qualified name, or property access chain to a symbol,
+    /// reporting an error if it does not resolve unless `ignore_errors` is set
+    ///
+    /// Names that resolve to an unresolvable alias resolve to the unknown symbol.
+    pub(super) fn resolve_entity_name(
+        &mut self,
+        name: &Arc<Node>,
+        meaning: SymbolFlags,
+        ignore_errors: bool,
+    ) -> Option<SymbolId> {
+        let (left, right) = match name.kind {
+            SyntaxKind::Identifier => {
+                let Some(symbol) = self.resolve_name(name, name.text(), meaning) else {
+                    if !ignore_errors {
+                        self.report_unresolved_name(name, meaning);
+                    }
+                    return None;
+                };
+                let symbol = self.get_merged_symbol(symbol);
+                self.mark_symbol_referenced(symbol, name);
+                return Some(symbol);
+            }
+            SyntaxKind::QualifiedName => {
+                let qualified = name.as_qualified_name();
+                (&qualified.left, &qualified.right)
+            }
+            SyntaxKind::PropertyAccessExpression => {
+                let access = name.as_property_access_expression();
+                (&access.expression, &access.name)
+            }
+            _ => return None,
+        };
+        let namespace = self.resolve_entity_name(left, SymbolFlags::NAMESPACE, ignore_errors)?;
+        let namespace = self.resolve_alias_if_alias(namespace);
+        if namespace == self.unknown_symbol {
+            return Some(namespace);
+        }
+        let exports = self.get_exports_of_module(namespace);
+        match self.lookup(&exports, right.text(), meaning) {
+            Some(symbol) => Some(symbol),
+            None => {
+                if !ignore_errors {
+                    let file = get_source_file_of_node(left);
+                    let namespace_name =
+                        crate::compiler::ast::utilities::get_text_of_node(&file, left).to_string();
+                    self.error(
+                        right,
+                        diagnostics::NAMESPACE_0_HAS_NO_EXPORTED_MEMBER_1_2694,
+                        &[namespace_name, right.text().to_string()],
+                    );
+                }
+                None
+            }
+        }
+    }
+
+    /// Reports that `name` does not resolve with `meaning`, explaining when it does resolve
+    /// with another meaning
+    pub(super) fn report_unresolved_name(&mut self, name: &Arc<Node>, meaning: SymbolFlags) {
+        let text = name.text().to_string();
+        if meaning.intersects(SymbolFlags::VALUE) {
+            if let Some(symbol) =
+                self.resolve_name(name, &text, SymbolFlags::TYPE | SymbolFlags::NAMESPACE)
+            {
+                let target = self.resolve_alias_if_alias(symbol);
+                let flags = self.symbol(target).flags;
+                let message = if flags.intersects(SymbolFlags::NAMESPACE_MODULE)
+                    && !flags.intersects(SymbolFlags::TYPE & !SymbolFlags::NAMESPACE_MODULE)
+                {
+                    diagnostics::CANNOT_USE_NAMESPACE_0_AS_A_VALUE_2708
+                } else {
+                    diagnostics::D_0_ONLY_REFERS_TO_A_TYPE_BUT_IS_BEING_USED_AS_A_VALUE_HERE_2693
+                };
+                self.error(name, message, &[text]);
+                return;
+            }
+        } else if meaning.intersects(SymbolFlags::TYPE)
+            && let Some(symbol) =
+                self.resolve_name(name, &text, SymbolFlags::VALUE | SymbolFlags::NAMESPACE)
+        {
+            let target = self.resolve_alias_if_alias(symbol);
+            let flags = self.symbol(target).flags;
+            if flags.intersects(SymbolFlags::VALUE_MODULE | SymbolFlags::NAMESPACE_MODULE)
+                && !flags.intersects(SymbolFlags::VALUE & !SymbolFlags::VALUE_MODULE)
+            {
+                self.error(
+                    name,
+                    diagnostics::CANNOT_USE_NAMESPACE_0_AS_A_TYPE_2709,
+                    &[text],
+                );
+            } else {
+                self.error(
+                        name,
+                        diagnostics::D_0_REFERS_TO_A_VALUE_BUT_IS_BEING_USED_AS_A_TYPE_HERE_DID_YOU_MEAN_TYPEOF_0_2749,
+                        &[text],
+                    );
+            }
+            return;
+        }
+        if let Some(suggestion) =
+            self.get_suggested_symbol_for_nonexistent_symbol(name, &text, meaning)
+        {
+            let message = if meaning == SymbolFlags::NAMESPACE {
+                diagnostics::CANNOT_FIND_NAMESPACE_0_DID_YOU_MEAN_1_2833
+            } else {
+                diagnostics::CANNOT_FIND_NAME_0_DID_YOU_MEAN_1_2552
+            };
+            let suggestion_name = self.symbol(suggestion).name.clone();
+            let chain = DiagnosticMessageChain::new(message, &[text, suggestion_name.clone()]);
+            let related = self
+                .symbol(suggestion)
+                .value_declaration
+                .clone()
+                .or_else(|| self.symbol(suggestion).declarations.first().cloned())
+                .map(|declaration| {
+                    self.create_diagnostic_for_node(
+                        &declaration,
+                        diagnostics::D_0_IS_DECLARED_HERE_2728,
+                        &[suggestion_name],
+                    )
+                });
+            self.error_with_chain(name, &chain, related.into_iter().collect());
+            return;
+        }
+        let message = if meaning == SymbolFlags::NAMESPACE {
+            diagnostics::CANNOT_FIND_NAMESPACE_0_2503
+        } else {
+            diagnostics::CANNOT_FIND_NAME_0_2304
+        };
+        self.error(name, message, &[text]);
+    }
+
+    /// Follows an alias to the symbol it refers to, leaving other symbols unchanged
+    pub(super) fn resolve_alias_if_alias(&mut self, symbol: SymbolId) -> SymbolId {
+        if self.symbol(symbol).flags.contains(SymbolFlags::ALIAS) {
+            self.resolve_alias(symbol)
+        } else {
+            symbol
+        }
+    }
+
+    /// Resolves an import or export alias to the symbol it refers to
+    ///
+    /// Aliases that do not resolve refer to the unknown symbol, which makes their uses `any`
+    /// without further errors.
+    pub(super) fn resolve_alias(&mut self, symbol: SymbolId) -> SymbolId {
+        if let Some(target) = self.symbol_links(symbol).alias_target {
+            return target;
+        }
+        // Circular aliases resolve to the unknown symbol, and are reported where the cycle
+        // was entered
+        let unknown = self.unknown_symbol;
+        if !self.push_type_resolution(ResolutionTarget::AliasTarget(symbol)) {
+            self.report_circular_alias(symbol);
+            return unknown;
+        }
+        let declaration = self.symbol(symbol).declarations.first().cloned();
+        let meaning = SymbolFlags::VALUE | SymbolFlags::TYPE | SymbolFlags::NAMESPACE;
+        let target = declaration.and_then(|declaration| match declaration.kind {
+            SyntaxKind::ImportEqualsDeclaration => {
+                let reference = &declaration.as_import_equals_declaration().module_reference;
+                if reference.kind == SyntaxKind::ExternalModuleReference {
+                    let expression = &reference.as_external_module_reference().expression;
+                    self.resolve_external_module_name(&declaration, expression)
+                        .map(|module| self.resolve_external_module_symbol(module))
+                } else {
+                    self.resolve_entity_name(reference, meaning, false)
+                }
+            }
+            SyntaxKind::ExportSpecifier => {
+                let specifier = declaration.as_export_specifier();
+                let has_module_specifier = declaration
+                    .parent()
+                    .and_then(|exports| exports.parent())
+                    .is_some_and(|export| {
+                        export.as_export_declaration().module_specifier.is_some()
+                    });
+                if has_module_specifier {
+                    self.resolve_import(&declaration)
+                } else {
+                    let local = specifier.property_name.as_ref().unwrap_or(&specifier.name);
+                    self.resolve_entity_name(local, meaning, false)
+                }
+            }
+            SyntaxKind::ExportAssignment => {
+                let expression = &declaration.as_export_assignment().expression;
+                if is_entity_name_expression(expression) {
+                    self.resolve_entity_name(expression, meaning, true)
+                } else {
+                    None
+                }
+            }
+            SyntaxKind::ImportClause
+            | SyntaxKind::NamespaceImport
+            | SyntaxKind::ImportSpecifier
+            | SyntaxKind::NamespaceExport => self.resolve_import(&declaration),
+            _ => None,
+        });
+        let target = match target {
+            Some(target) => self.resolve_alias_if_alias(target),
+            None => unknown,
+        };
+        let target = if self.pop_type_resolution() {
+            target
+        } else {
+            unknown
+        };
+        self.symbol_links(symbol).alias_target = Some(target);
+        target
+    }
+
+    /// Resolves an import from a JSON module to the value the module exports, or to a
+    /// property of that value
+    pub(super) fn resolve_json_import(
+        &mut self,
+        declaration: &Arc<Node>,
+        module: SymbolId,
+        module_name: &str,
+    ) -> Option<SymbolId> {
+        let export_equals = *self
+            .symbol(module)
+            .exports
+            .get(internal_symbol_names::EXPORT_EQUALS)?;
+        match declaration.kind {
+            SyntaxKind::ImportClause if !self.options.allow_synthetic_default_imports => {
+                let name = declaration.name().unwrap_or(declaration);
+                self.error(
+                    name,
+                    diagnostics::MODULE_0_CAN_ONLY_BE_DEFAULT_IMPORTED_USING_THE_1_FLAG_1259,
+                    &[module_name.to_string(), "esModuleInterop".to_string()],
+                );
+                None
+            }
+            SyntaxKind::ImportSpecifier => {
+                let specifier = declaration.as_import_specifier();
+                let name = specifier.property_name.as_ref().unwrap_or(&specifier.name);
+                let type_ = self.get_type_of_symbol(export_equals);
+                let property = self.get_property_of_type(type_, name.text());
+                if property.is_none() {
+                    self.error(
+                        name,
+                        diagnostics::MODULE_0_HAS_NO_EXPORTED_MEMBER_1_2305,
+                        &[module_name.to_string(), name.text().to_string()],
+                    );
+                }
+                property
+            }
+            _ => Some(export_equals),
+        }
+    }
+}
+
+/// Gets the flags of the symbols a declaration with `flags` cannot merge with
+pub(super) fn excluded_symbol_flags(flags: SymbolFlags) -> SymbolFlags {
+    let mut result = SymbolFlags::NONE;
+    let exclusions = [
+        (
+            SymbolFlags::BLOCK_SCOPED_VARIABLE,
+            SymbolFlags::BLOCK_SCOPED_VARIABLE_EXCLUDES,
+        ),
+        (
+            SymbolFlags::FUNCTION_SCOPED_VARIABLE,
+            SymbolFlags::FUNCTION_SCOPED_VARIABLE_EXCLUDES,
+        ),
+        (SymbolFlags::PROPERTY, SymbolFlags::PROPERTY_EXCLUDES),
+        (SymbolFlags::ENUM_MEMBER, SymbolFlags::ENUM_MEMBER_EXCLUDES),
+        (SymbolFlags::FUNCTION, SymbolFlags::FUNCTION_EXCLUDES),
+        (SymbolFlags::CLASS, SymbolFlags::CLASS_EXCLUDES),
+        (SymbolFlags::INTERFACE, SymbolFlags::INTERFACE_EXCLUDES),
+        (
+            SymbolFlags::REGULAR_ENUM,
+            SymbolFlags::REGULAR_ENUM_EXCLUDES,
+        ),
+        (SymbolFlags::CONST_ENUM, SymbolFlags::CONST_ENUM_EXCLUDES),
+        (
+            SymbolFlags::VALUE_MODULE,
+            SymbolFlags::VALUE_MODULE_EXCLUDES,
+        ),
+        (SymbolFlags::METHOD, SymbolFlags::METHOD_EXCLUDES),
+        (
+            SymbolFlags::GET_ACCESSOR,
+            SymbolFlags::GET_ACCESSOR_EXCLUDES,
+        ),
+        (
+            SymbolFlags::SET_ACCESSOR,
+            SymbolFlags::SET_ACCESSOR_EXCLUDES,
+        ),
+        (
+            SymbolFlags::TYPE_PARAMETER,
+            SymbolFlags::TYPE_PARAMETER_EXCLUDES,
+        ),
+        (SymbolFlags::TYPE_ALIAS, SymbolFlags::TYPE_ALIAS_EXCLUDES),
+        (SymbolFlags::ALIAS, SymbolFlags::ALIAS_EXCLUDES),
+    ];
+    for (flag, excludes) in exclusions {
+        if flags.intersects(flag) {
+            result |= excludes;
+        }
+    }
+    result
+}
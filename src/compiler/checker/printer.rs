@@ -0,0 +1,900 @@
+//! Printing types the way they appear in diagnostics
+
+use std::sync::Arc;
+
+use bitflags::bitflags;
+
+use crate::compiler::ast::symbol::INTERNAL_SYMBOL_NAME_PREFIX;
+use crate::compiler::ast::utilities::{
+    get_source_file_of_node, get_text_of_node, is_binding_pattern, number_to_string,
+};
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::scanner::{ScriptTarget, is_identifier_part, is_identifier_start};
+
+use super::mapped::ModifierChange;
+use super::{
+    Checker, ElementFlags, LiteralValue, ObjectFlags, SignatureId, TypeData, TypeFlags, TypeId,
+};
+
+/// How deeply nested object types are printed before being elided
+const MAX_PRINT_DEPTH: usize = 8;
+
+/// How long a printed type grows before the rest of its members and union constituents are
+/// elided
+const DEFAULT_MAXIMUM_TRUNCATION_LENGTH: usize = 160;
+
+/// The same limit under [`TypeFormatFlags::NO_TRUNCATION`], which only guards against
+/// runaway output
+const NO_TRUNCATION_MAXIMUM_TRUNCATION_LENGTH: usize = 1_000_000;
+
+bitflags! {
+    /// Options for how [`Checker::type_to_string_with_flags`] prints a type
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct TypeFormatFlags: u32 {
+        const NONE = 0;
+        /// Prints long types in full rather than eliding members and union constituents
+        const NO_TRUNCATION = 1 << 0;
+        /// Writes array types as `Array<T>` rather than `T[]`
+        const WRITE_ARRAY_AS_GENERIC_TYPE = 1 << 1;
+        /// Writes the structure of a type with an alias rather than the alias name, as when
+        /// printing the declaration of that alias
+        const IN_TYPE_ALIAS = 1 << 2;
+        /// Quotes string literal types and property names with `'` rather than `"`
+        const USE_SINGLE_QUOTES_FOR_STRING_LITERAL_TYPE = 1 << 3;
+    }
+}
+
+impl Checker {
+    /// Prints a type as TypeScript writes it in diagnostics
+    pub fn type_to_string(&mut self, type_: TypeId) -> String {
+        self.type_to_string_with_flags(type_, TypeFormatFlags::NONE)
+    }
+
+    /// Prints a type with the given formatting options
+    ///
+    /// Once the output passes a length limit, the remaining members of object types and
+    /// constituents of unions and intersections are elided as `... N more ...`, keeping the
+    /// last one, and output twice as long as the limit is cut off with `...`.
+    pub fn type_to_string_with_flags(&mut self, type_: TypeId, flags: TypeFormatFlags) -> String {
+        self.type_to_string_in_scope(type_, None, flags)
+    }
+
+    /// Prints a type with the names of the symbols it refers to qualified as they must be to
+    /// be referenced from `enclosing_declaration`
+    ///
+    /// Without an enclosing declaration, names are fully qualified by the namespaces they are
+    /// declared in.
+    pub fn type_to_string_in_scope(
+        &mut self,
+        type_: TypeId,
+        enclosing_declaration: Option<&Arc<Node>>,
+        flags: TypeFormatFlags,
+    ) -> String {
+        let mut printer = TypePrinter::new(flags);
+        printer.enclosing_declaration = enclosing_declaration.cloned();
+        printer.write_type(self, type_);
+        let maximum_length = 2 * printer.maximum_truncation_length();
+        if printer.out.len() >= maximum_length {
+            let end = printer
+                .out
+                .char_indices()
+                .map(|(index, _)| index)
+                .take_while(|&index| index <= maximum_length - "...".len())
+                .last()
+                .unwrap_or(0);
+            printer.out.truncate(end);
+            printer.out.push_str("...");
+        }
+        printer.out
+    }
+
+    /// Prints a signature without its return type arrow, as in `<T>(x: T): T`
+    pub fn signature_to_string(&mut self, signature: SignatureId) -> String {
+        let mut printer = TypePrinter::new(TypeFormatFlags::NONE);
+        printer.write_signature(self, signature, SignatureStyle::Method);
+        printer.out
+    }
+
+    /// Prints the name of a generic type with its type parameters, as in `Map<K, V>`
+    pub(super) fn symbol_to_string_with_type_parameters(
+        &mut self,
+        symbol: SymbolId,
+        type_parameters: &[TypeId],
+    ) -> String {
+        let mut printer = TypePrinter::new(TypeFormatFlags::NONE);
+        printer.out.push_str(&self.symbol(symbol).name);
+        printer.write_type_arguments(self, type_parameters);
+        printer.out
+    }
+}
+
+/// How a signature is written
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SignatureStyle {
+    /// `(x: T) => R`
+    Arrow,
+    /// `new (x: T) => R`
+    ConstructorArrow,
+    /// `(x: T): R`, as a member of an object type or after a method name
+    Method,
+    /// `new (x: T): R`, as a member of an object type
+    Construct,
+}
+
+struct TypePrinter {
+    out: String,
+    flags: TypeFormatFlags,
+    /// Where the type is printed, which decides how symbol names are qualified
+    enclosing_declaration: Option<Arc<Node>>,
+    /// Whether the type being printed is the declaration of its alias, which is written out
+    /// rather than named
+    expand_alias: bool,
+    /// Whether the output has grown past the truncation length, after which the rest of
+    /// every list is elided
+    truncating: bool,
+    /// The object types being printed, which are elided if they occur within themselves
+    stack: Vec<TypeId>,
+    /// The `infer` type parameters of the extends type being printed
+    infer_type_parameters: Vec<TypeId>,
+}
+
+impl TypePrinter {
+    fn new(flags: TypeFormatFlags) -> Self {
+        TypePrinter {
+            out: String::new(),
+            flags,
+            enclosing_declaration: None,
+            expand_alias: flags.contains(TypeFormatFlags::IN_TYPE_ALIAS),
+            truncating: false,
+            stack: Vec::new(),
+            infer_type_parameters: Vec::new(),
+        }
+    }
+
+    fn maximum_truncation_length(&self) -> usize {
+        if self.flags.contains(TypeFormatFlags::NO_TRUNCATION) {
+            NO_TRUNCATION_MAXIMUM_TRUNCATION_LENGTH
+        } else {
+            DEFAULT_MAXIMUM_TRUNCATION_LENGTH
+        }
+    }
+
+    /// Checks if the output has grown long enough that the rest of a list should be elided
+    fn check_truncation_length(&mut self) -> bool {
+        if !self.truncating {
+            self.truncating = self.out.len() > self.maximum_truncation_length();
+        }
+        self.truncating
+    }
+
+    fn write_type(&mut self, checker: &mut Checker, type_: TypeId) {
+        let expand_alias = std::mem::take(&mut self.expand_alias);
+        if type_ == checker.error_type {
+            self.out.push_str("any");
+            return;
+        }
+        if type_ == checker.boolean_type {
+            self.out.push_str("boolean");
+            return;
+        }
+        checker.resolve_deferred_type_arguments(type_);
+        let data = checker.get_type(type_).clone();
+        if let Some(alias_symbol) = data.alias_symbol
+            && !expand_alias
+        {
+            self.write_symbol(checker, alias_symbol, SymbolFlags::TYPE);
+            self.write_type_arguments(checker, &data.alias_type_arguments);
+            return;
+        }
+        if data
+            .flags
+            .intersects(TypeFlags::ENUM_LITERAL | TypeFlags::ENUM)
+            && !data.flags.contains(TypeFlags::UNION)
+            && let Some(symbol) = data.symbol
+            && checker
+                .symbol(symbol)
+                .flags
+                .contains(SymbolFlags::ENUM_MEMBER)
+        {
+            self.write_enum_member_type(checker, type_, symbol);
+            return;
+        }
+        match &data.data {
+            TypeData::Intrinsic { name } => self.out.push_str(name),
+            TypeData::Literal { value } => match value {
+                LiteralValue::String(text) => self.write_string_literal(text),
+                LiteralValue::Number(number) => self.out.push_str(&number_to_string(number.0)),
+                LiteralValue::BigInt(digits) => {
+                    self.out.push_str(digits);
+                    self.out.push('n');
+                }
+            },
+            TypeData::UniqueESSymbol { escaped_name } => {
+                let well_known = escaped_name
+                    .strip_prefix(INTERNAL_SYMBOL_NAME_PREFIX)
+                    .and_then(|name| name.strip_prefix('@'))
+                    .filter(|name| !name.contains('@'));
+                // Only variables and class properties can be named by `typeof`
+                let holder = data.symbol.filter(|&symbol| {
+                    let symbol = checker.symbol(symbol);
+                    symbol.flags.intersects(SymbolFlags::VARIABLE)
+                        || symbol
+                            .value_declaration
+                            .as_ref()
+                            .is_some_and(|declaration| {
+                                declaration.kind == SyntaxKind::PropertyDeclaration
+                            })
+                });
+                match (well_known, holder) {
+                    (Some(name), _) => self.out.push_str(&format!("typeof Symbol.{name}")),
+                    (None, Some(symbol)) => {
+                        self.out.push_str("typeof ");
+                        self.write_symbol(checker, symbol, SymbolFlags::VALUE);
+                    }
+                    (None, None) => self.out.push_str("unique symbol"),
+                }
+            }
+            TypeData::TypeParameter { is_this_type, .. } => {
+                if *is_this_type {
+                    self.out.push_str("this");
+                } else if let Some(symbol) = data.symbol {
+                    if self.infer_type_parameters.contains(&type_) {
+                        self.out.push_str("infer ");
+                    }
+                    self.out.push_str(&checker.symbol(symbol).name);
+                }
+            }
+            TypeData::Enum => {
+                if let Some(symbol) = data.symbol {
+                    self.write_symbol(checker, symbol, SymbolFlags::TYPE);
+                }
+            }
+            // Only control flow analysis sees evolving arrays, but they print as the arrays
+            // they evolve into
+            TypeData::EvolvingArray { element_type } => {
+                let element_type = *element_type;
+                self.write_array_element_type(checker, element_type);
+                self.out.push_str("[]");
+            }
+            TypeData::UnionOrIntersection { types } => {
+                if data.flags.contains(TypeFlags::UNION) {
+                    self.write_union(checker, types);
+                } else {
+                    self.write_list(checker, types, " & ", true);
+                }
+            }
+            TypeData::Interface(interface) => {
+                if let Some(symbol) = data.symbol {
+                    self.write_symbol(checker, symbol, SymbolFlags::TYPE);
+                }
+                self.write_type_arguments(checker, &interface.local_type_parameters);
+            }
+            TypeData::Reference {
+                target,
+                type_arguments,
+            } => self.write_type_reference(checker, *target, type_arguments),
+            TypeData::Anonymous { .. } | TypeData::Resolved { .. } => {
+                self.write_anonymous_type(checker, type_, data.symbol)
+            }
+            TypeData::Mapped { .. } => {
+                if checker.is_generic_mapped_type(type_) {
+                    self.write_mapped_type(checker, type_);
+                } else {
+                    self.write_anonymous_type(checker, type_, data.symbol);
+                }
+            }
+            TypeData::Index { type_: target } => {
+                self.out.push_str("keyof ");
+                self.write_type_parenthesized(checker, *target);
+            }
+            TypeData::IndexedAccess {
+                object_type,
+                index_type,
+            } => {
+                self.write_array_element_type(checker, *object_type);
+                self.out.push('[');
+                self.write_type(checker, *index_type);
+                self.out.push(']');
+            }
+            TypeData::Conditional {
+                root,
+                check_type,
+                extends_type,
+                ..
+            } => {
+                let true_type = checker.get_true_type_from_conditional_type(type_);
+                let false_type = checker.get_false_type_from_conditional_type(type_);
+                self.write_conditional_operand(checker, *check_type);
+                self.out.push_str(" extends ");
+                let saved_len = self.infer_type_parameters.len();
+                self.infer_type_parameters
+                    .extend(&root.infer_type_parameters);
+                self.write_conditional_operand(checker, *extends_type);
+                self.infer_type_parameters.truncate(saved_len);
+                self.out.push_str(" ? ");
+                self.write_type(checker, true_type);
+                self.out.push_str(" : ");
+                self.write_type(checker, false_type);
+            }
+        }
+    }
+
+    /// Writes a mapped type whose members are not known yet as `{ [K in C]: X; }`
+    fn write_mapped_type(&mut self, checker: &mut Checker, type_: TypeId) {
+        let (readonly, optional) = checker.get_mapped_type_modifiers(type_);
+        self.out.push_str("{ ");
+        match readonly {
+            ModifierChange::Add => self.out.push_str("readonly "),
+            ModifierChange::Remove => self.out.push_str("-readonly "),
+            ModifierChange::Keep => {}
+        }
+        self.out.push('[');
+        if let Some(type_parameter) = checker.get_type_parameter_from_mapped_type(type_) {
+            self.write_type(checker, type_parameter);
+        }
+        self.out.push_str(" in ");
+        // The constraint of a homomorphic mapped type is written as the `keyof` it was
+        // declared as
+        match checker.get_modifiers_type_from_mapped_type(type_) {
+            Some(modifiers_type) => {
+                self.out.push_str("keyof ");
+                self.write_type_parenthesized(checker, modifiers_type);
+            }
+            None => {
+                let constraint = checker.get_constraint_type_from_mapped_type(type_);
+                self.write_type(checker, constraint);
+            }
+        }
+        if let Some(name_type) = checker.get_name_type_from_mapped_type(type_) {
+            self.out.push_str(" as ");
+            self.write_type(checker, name_type);
+        }
+        self.out.push(']');
+        match optional {
+            ModifierChange::Add => self.out.push('?'),
+            ModifierChange::Remove => self.out.push_str("-?"),
+            ModifierChange::Keep => {}
+        }
+        self.out.push_str(": ");
+        let template_type = checker.get_template_type_from_mapped_type(type_);
+        self.write_type(checker, template_type);
+        self.out.push_str("; }");
+    }
+
+    /// Writes the type of an enum member as `E.A`, or as `E` when it is the only member
+    fn write_enum_member_type(&mut self, checker: &mut Checker, type_: TypeId, symbol: SymbolId) {
+        let Some(parent) = checker.symbol(symbol).parent else {
+            self.out.push_str(&checker.symbol(symbol).name);
+            return;
+        };
+        self.write_symbol(checker, parent, SymbolFlags::TYPE);
+        if checker.get_declared_type_of_symbol(parent) == type_ {
+            return;
+        }
+        let name = checker.symbol(symbol).name.clone();
+        let mut chars = name.chars();
+        let is_identifier = chars
+            .next()
+            .is_some_and(|first| is_identifier_start(first, ScriptTarget::ESNext))
+            && chars.all(|ch| is_identifier_part(ch, ScriptTarget::ESNext));
+        if is_identifier {
+            self.out.push('.');
+            self.out.push_str(&name);
+        } else {
+            self.out.push('[');
+            self.write_string_literal(&name);
+            self.out.push(']');
+        }
+    }
+
+    fn write_union(&mut self, checker: &mut Checker, types: &[TypeId]) {
+        let mut printed = Vec::with_capacity(types.len());
+        let has_boolean = types.contains(&checker.false_type) && types.contains(&checker.true_type);
+        for &type_ in types {
+            if has_boolean && (type_ == checker.false_type || type_ == checker.true_type) {
+                if type_ == checker.false_type {
+                    printed.push(checker.boolean_type);
+                }
+                continue;
+            }
+            // The members of an enum are written as the enum when they are all present
+            if checker
+                .flags(type_)
+                .intersects(TypeFlags::ENUM_LITERAL | TypeFlags::ENUM)
+            {
+                let enum_type = checker.get_base_type_of_enum_literal_type(type_);
+                if enum_type != type_
+                    && let TypeData::UnionOrIntersection { types: members } =
+                        &checker.get_type(enum_type).data
+                    && members.iter().all(|member| types.contains(member))
+                {
+                    if !printed.contains(&enum_type) {
+                        printed.push(enum_type);
+                    }
+                    continue;
+                }
+            }
+            printed.push(type_);
+        }
+        // `null` and `undefined` are written last
+        let (nullable, mut rest): (Vec<TypeId>, Vec<TypeId>) = printed
+            .into_iter()
+            .partition(|&type_| checker.flags(type_).intersects(TypeFlags::NULLABLE));
+        rest.extend(nullable.iter().rev());
+        self.write_list(checker, &rest, " | ", true);
+    }
+
+    /// Writes a list of types, which is elided entirely once the output is too long unless it
+    /// is the bare list of operands of a union or intersection
+    ///
+    /// Bare lists have their operands parenthesized where needed, and only elide the operands
+    /// between the ones written before the output grew too long and the last one.
+    fn write_list(
+        &mut self,
+        checker: &mut Checker,
+        types: &[TypeId],
+        separator: &str,
+        is_bare_list: bool,
+    ) {
+        if self.check_truncation_length() {
+            if !is_bare_list {
+                self.out.push_str("...");
+                return;
+            }
+            if let [first, .., last] = types
+                && types.len() > 2
+            {
+                self.write_type_parenthesized(checker, *first);
+                self.out.push_str(separator);
+                self.out
+                    .push_str(&format!("... {} more ...", types.len() - 2));
+                self.out.push_str(separator);
+                self.write_type_parenthesized(checker, *last);
+                return;
+            }
+        }
+        for (index, &type_) in types.iter().enumerate() {
+            if index > 0 {
+                self.out.push_str(separator);
+            }
+            if is_bare_list && self.check_truncation_length() && index + 4 < types.len() {
+                self.out
+                    .push_str(&format!("... {} more ...", types.len() - index - 1));
+                self.out.push_str(separator);
+                self.write_type_parenthesized(checker, types[types.len() - 1]);
+                break;
+            }
+            if is_bare_list {
+                self.write_type_parenthesized(checker, type_);
+            } else {
+                self.write_type(checker, type_);
+            }
+        }
+    }
+
+    /// Writes a type that is an operand of a union, intersection, or array type, adding
+    /// parentheses where the type would not parse back as written
+    fn write_type_parenthesized(&mut self, checker: &mut Checker, type_: TypeId) {
+        let needs_parentheses = {
+            let data = checker.get_type(type_);
+            data.alias_symbol.is_none()
+                && type_ != checker.boolean_type
+                && data
+                    .flags
+                    .intersects(TypeFlags::UNION_OR_INTERSECTION | TypeFlags::CONDITIONAL)
+        } || self.infer_type_parameters.contains(&type_)
+            || self.is_function_type(checker, type_);
+        if needs_parentheses {
+            self.out.push('(');
+            self.write_type(checker, type_);
+            self.out.push(')');
+        } else {
+            self.write_type(checker, type_);
+        }
+    }
+
+    /// Writes the element type of an array type or the object type of an indexed access, which
+    /// also needs parentheses if it is a `keyof` type
+    fn write_array_element_type(&mut self, checker: &mut Checker, type_: TypeId) {
+        let data = checker.get_type(type_);
+        if data.alias_symbol.is_none() && data.flags.contains(TypeFlags::INDEX) {
+            self.out.push('(');
+            self.write_type(checker, type_);
+            self.out.push(')');
+        } else {
+            self.write_type_parenthesized(checker, type_);
+        }
+    }
+
+    /// Writes the check or extends type of a conditional type, which only needs parentheses
+    /// if it is a conditional type itself
+    fn write_conditional_operand(&mut self, checker: &mut Checker, type_: TypeId) {
+        let data = checker.get_type(type_);
+        if data.alias_symbol.is_none() && data.flags.contains(TypeFlags::CONDITIONAL) {
+            self.out.push('(');
+            self.write_type(checker, type_);
+            self.out.push(')');
+        } else {
+            self.write_type(checker, type_);
+        }
+    }
+
+    fn is_function_type(&self, checker: &mut Checker, type_: TypeId) -> bool {
+        let data = checker.get_type(type_);
+        if data.alias_symbol.is_some()
+            || !data.object_flags.contains(ObjectFlags::ANONYMOUS)
+            || data.symbol.is_some_and(|symbol| {
+                checker
+                    .symbol(symbol)
+                    .flags
+                    .intersects(SymbolFlags::CLASS | SymbolFlags::ENUM | SymbolFlags::VALUE_MODULE)
+            })
+        {
+            return false;
+        }
+        let members = checker.resolve_structured_type_members(type_);
+        members.properties.is_empty()
+            && members.index_infos.is_empty()
+            && members.call_signatures.len() + members.construct_signatures.len() == 1
+    }
+
+    /// Writes the name of a symbol, qualified as it must be where the type is printed
+    fn write_symbol(&mut self, checker: &mut Checker, symbol: SymbolId, meaning: SymbolFlags) {
+        let name = checker.symbol_to_string(symbol, self.enclosing_declaration.as_ref(), meaning);
+        self.out.push_str(&name);
+    }
+
+    fn write_type_arguments(&mut self, checker: &mut Checker, type_arguments: &[TypeId]) {
+        if type_arguments.is_empty() {
+            return;
+        }
+        self.out.push('<');
+        self.write_list(checker, type_arguments, ", ", false);
+        self.out.push('>');
+    }
+
+    fn write_type_reference(
+        &mut self,
+        checker: &mut Checker,
+        target: TypeId,
+        type_arguments: &[TypeId],
+    ) {
+        let target_data = checker.get_type(target).clone();
+        let TypeData::Interface(interface) = &target_data.data else {
+            return;
+        };
+        if target_data.object_flags.contains(ObjectFlags::TUPLE) {
+            if interface.readonly {
+                self.out.push_str("readonly ");
+            }
+            self.out.push('[');
+            for (index, (&type_, flags)) in type_arguments
+                .iter()
+                .zip(&interface.element_flags)
+                .enumerate()
+            {
+                if index > 0 {
+                    self.out.push_str(", ");
+                }
+                if flags.contains(ElementFlags::REST) {
+                    self.out.push_str("...");
+                    self.write_array_element_type(checker, type_);
+                    self.out.push_str("[]");
+                } else {
+                    self.write_type(checker, type_);
+                    if flags.contains(ElementFlags::OPTIONAL) {
+                        self.out.push('?');
+                    }
+                }
+            }
+            self.out.push(']');
+            return;
+        }
+        let readonly = checker.get_global_readonly_array_type() == target;
+        if (readonly || checker.get_global_array_type() == target) && type_arguments.len() == 1 {
+            if self
+                .flags
+                .contains(TypeFormatFlags::WRITE_ARRAY_AS_GENERIC_TYPE)
+            {
+                self.out
+                    .push_str(if readonly { "ReadonlyArray" } else { "Array" });
+                self.write_type_arguments(checker, type_arguments);
+            } else {
+                if readonly {
+                    self.out.push_str("readonly ");
+                }
+                self.write_array_element_type(checker, type_arguments[0]);
+                self.out.push_str("[]");
+            }
+            return;
+        }
+        if let Some(symbol) = target_data.symbol {
+            self.write_symbol(checker, symbol, SymbolFlags::TYPE);
+        }
+        // Only the type's own type arguments are written, not those of enclosing declarations
+        let outer_count = interface
+            .outer_type_parameters
+            .len()
+            .min(type_arguments.len());
+        self.write_type_arguments(checker, &type_arguments[outer_count..]);
+    }
+
+    fn write_anonymous_type(
+        &mut self,
+        checker: &mut Checker,
+        type_: TypeId,
+        symbol: Option<SymbolId>,
+    ) {
+        if let Some(symbol) = symbol {
+            let flags = checker.symbol(symbol).flags;
+            if flags.intersects(SymbolFlags::CLASS | SymbolFlags::ENUM | SymbolFlags::VALUE_MODULE)
+            {
+                self.out.push_str("typeof ");
+                self.write_symbol(checker, symbol, SymbolFlags::VALUE);
+                return;
+            }
+        }
+        if self.stack.contains(&type_) || self.stack.len() >= MAX_PRINT_DEPTH {
+            self.out.push_str("...");
+            return;
+        }
+        self.stack.push(type_);
+        self.write_object_literal_type(checker, type_);
+        self.stack.pop();
+    }
+
+    fn write_object_literal_type(&mut self, checker: &mut Checker, type_: TypeId) {
+        let members = checker.resolve_structured_type_members(type_);
+        if members.properties.is_empty() && members.index_infos.is_empty() {
+            match (
+                members.call_signatures.as_slice(),
+                members.construct_signatures.as_slice(),
+            ) {
+                ([], []) => {
+                    self.out.push_str("{}");
+                    return;
+                }
+                ([signature], []) => {
+                    self.write_signature(checker, *signature, SignatureStyle::Arrow);
+                    return;
+                }
+                ([], [signature]) => {
+                    self.write_signature(checker, *signature, SignatureStyle::ConstructorArrow);
+                    return;
+                }
+                _ => {}
+            }
+        }
+        if self.check_truncation_length() {
+            self.out.push_str("{ ...; }");
+            return;
+        }
+        self.out.push_str("{ ");
+        for &signature in &members.call_signatures {
+            self.write_signature(checker, signature, SignatureStyle::Method);
+            self.out.push_str("; ");
+        }
+        for &signature in &members.construct_signatures {
+            self.write_signature(checker, signature, SignatureStyle::Construct);
+            self.out.push_str("; ");
+        }
+        for info in &members.index_infos {
+            if info.is_readonly {
+                self.out.push_str("readonly ");
+            }
+            self.out.push_str("[x: ");
+            self.write_type(checker, info.key_type);
+            self.out.push_str("]: ");
+            self.write_type(checker, info.value_type);
+            self.out.push_str("; ");
+        }
+        let properties = &members.properties;
+        for (index, &property) in properties.iter().enumerate() {
+            if self.check_truncation_length() && index + 4 < properties.len() {
+                let remaining = properties.len() - index - 1;
+                self.out.push_str(&format!("... {remaining} more ...; "));
+                self.write_property(checker, properties[properties.len() - 1]);
+                break;
+            }
+            self.write_property(checker, property);
+        }
+        self.out.push('}');
+    }
+
+    fn write_property(&mut self, checker: &mut Checker, property: SymbolId) {
+        let symbol = checker.symbol(property).clone();
+        let optional = if symbol.flags.contains(SymbolFlags::OPTIONAL) {
+            "?"
+        } else {
+            ""
+        };
+        let property_type = checker.get_type_of_symbol(property);
+        if symbol.flags.contains(SymbolFlags::METHOD) {
+            let signatures = checker.get_signatures_of_type(property_type, false);
+            if !signatures.is_empty() {
+                for signature in signatures {
+                    self.write_property_name(&symbol.name);
+                    self.out.push_str(optional);
+                    self.write_signature(checker, signature, SignatureStyle::Method);
+                    self.out.push_str("; ");
+                }
+                return;
+            }
+        }
+        if checker.is_readonly_property(property) {
+            self.out.push_str("readonly ");
+        }
+        self.write_property_name(&symbol.name);
+        self.out.push_str(optional);
+        self.out.push_str(": ");
+        self.write_type(checker, property_type);
+        self.out.push_str("; ");
+    }
+
+    fn write_property_name(&mut self, name: &str) {
+        if let Some(symbol_name) = name
+            .strip_prefix(INTERNAL_SYMBOL_NAME_PREFIX)
+            .and_then(|name| name.strip_prefix('@'))
+        {
+            match symbol_name.split_once('@') {
+                // A property keyed by a unique symbol, written as the name of its holder
+                Some((holder, _)) => self.out.push_str(&format!("[{holder}]")),
+                // A property keyed by a well-known symbol
+                None => self.out.push_str(&format!("[Symbol.{symbol_name}]")),
+            }
+            return;
+        }
+        let mut chars = name.chars();
+        let is_identifier = chars
+            .next()
+            .is_some_and(|first| is_identifier_start(first, ScriptTarget::ESNext))
+            && chars.all(|ch| is_identifier_part(ch, ScriptTarget::ESNext));
+        let is_numeric = name
+            .parse::<f64>()
+            .is_ok_and(|value| number_to_string(value) == name);
+        if is_identifier || is_numeric {
+            self.out.push_str(name);
+        } else {
+            self.write_string_literal(name);
+        }
+    }
+
+    fn write_signature(
+        &mut self,
+        checker: &mut Checker,
+        signature_id: SignatureId,
+        style: SignatureStyle,
+    ) {
+        let signature = checker.get_signature(signature_id).clone();
+        if matches!(
+            style,
+            SignatureStyle::ConstructorArrow | SignatureStyle::Construct
+        ) {
+            if signature.flags.contains(super::SignatureFlags::ABSTRACT) {
+                self.out.push_str("abstract ");
+            }
+            self.out.push_str("new ");
+        }
+        if !signature.type_parameters.is_empty() {
+            self.out.push('<');
+            for (index, &parameter) in signature.type_parameters.iter().enumerate() {
+                if index > 0 {
+                    self.out.push_str(", ");
+                }
+                self.write_type(checker, parameter);
+                if let Some(constraint) = checker.get_constraint_of_type_parameter(parameter) {
+                    self.out.push_str(" extends ");
+                    self.write_type(checker, constraint);
+                }
+            }
+            self.out.push('>');
+        }
+        self.out.push('(');
+        let mut index = 0;
+        if let Some(this_parameter) = signature.this_parameter {
+            self.out.push_str("this: ");
+            let this_type = checker.get_type_of_symbol(this_parameter);
+            self.write_type(checker, this_type);
+            index += 1;
+        }
+        let count = signature.parameters.len();
+        for (position, &parameter) in signature.parameters.iter().enumerate() {
+            if index > 0 {
+                self.out.push_str(", ");
+            }
+            index += 1;
+            let is_rest = signature.has_rest_parameter() && position + 1 == count;
+            if is_rest {
+                self.out.push_str("...");
+            }
+            // A destructured parameter is named by its pattern
+            match checker
+                .symbol(parameter)
+                .value_declaration
+                .as_ref()
+                .and_then(|declaration| declaration.name())
+                .filter(|name| is_binding_pattern(name))
+            {
+                Some(pattern) => self.out.push_str(&binding_pattern_to_string(pattern)),
+                None => self.out.push_str(&checker.symbol(parameter).name),
+            }
+            if !is_rest && position >= signature.min_argument_count {
+                self.out.push('?');
+            }
+            self.out.push_str(": ");
+            let parameter_type = checker.get_type_of_symbol(parameter);
+            self.write_type(checker, parameter_type);
+        }
+        self.out.push(')');
+        self.out.push_str(match style {
+            SignatureStyle::Arrow | SignatureStyle::ConstructorArrow => " => ",
+            SignatureStyle::Method | SignatureStyle::Construct => ": ",
+        });
+        let return_type = checker.get_return_type_of_signature(signature_id);
+        self.write_type(checker, return_type);
+    }
+
+    fn write_string_literal(&mut self, text: &str) {
+        let quote = if self
+            .flags
+            .contains(TypeFormatFlags::USE_SINGLE_QUOTES_FOR_STRING_LITERAL_TYPE)
+        {
+            '\''
+        } else {
+            '"'
+        };
+        self.out.push(quote);
+        for ch in text.chars() {
+            match ch {
+                _ if ch == quote => {
+                    self.out.push('\\');
+                    self.out.push(ch);
+                }
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                _ => self.out.push(ch),
+            }
+        }
+        self.out.push(quote);
+    }
+}
+
+/// Prints a binding pattern the way it names a parameter, without its default values
+fn binding_pattern_to_string(pattern: &Node) -> String {
+    let element_to_string = |element: &Arc<Node>| {
+        if element.kind != SyntaxKind::BindingElement {
+            return String::new();
+        }
+        let binding_element = element.as_binding_element();
+        let name = if is_binding_pattern(&binding_element.name) {
+            binding_pattern_to_string(&binding_element.name)
+        } else {
+            binding_element.name.text().to_string()
+        };
+        let mut text = String::new();
+        if binding_element.dot_dot_dot_token.is_some() {
+            text.push_str("...");
+        }
+        if let Some(property_name) = &binding_element.property_name {
+            let source_file = get_source_file_of_node(property_name);
+            text.push_str(get_text_of_node(&source_file, property_name));
+            text.push_str(": ");
+        }
+        text.push_str(&name);
+        text
+    };
+    if pattern.kind == SyntaxKind::ObjectBindingPattern {
+        let elements = &pattern.as_object_binding_pattern().elements;
+        if elements.is_empty() {
+            return "{}".to_string();
+        }
+        let elements: Vec<String> = elements.iter().map(element_to_string).collect();
+        format!("{{ {} }}", elements.join(", "))
+    } else {
+        let elements = &pattern.as_array_binding_pattern().elements;
+        let elements: Vec<String> = elements.iter().map(element_to_string).collect();
+        format!("[{}]", elements.join(", "))
+    }
+}
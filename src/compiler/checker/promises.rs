@@ -0,0 +1,249 @@
+//! Awaited types and the checking of async functions, `await`, and `for await`
+//!
+//! Awaiting a value unwraps promises and other thenables, whose `then` methods pass the
+//! value they resolve to to their callbacks, until what is left is not a thenable. An async
+//! function returns a promise of the awaited types of what it returns.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{
+    get_error_range, get_source_file_of_node, is_external_module,
+};
+use crate::compiler::ast::{Node, SyntaxKind};
+use crate::compiler::diagnostics::{self, Message};
+use crate::compiler::scanner::{ScriptTarget, skip_trivia};
+use crate::compiler::text::TextRange;
+use crate::compiler::transformers::ModuleKind;
+
+use super::contextual::{get_containing_function, is_generator};
+use super::{Checker, TypeData, TypeFlags, TypeId};
+
+/// How many thenables deep awaiting a type unwraps before giving up on a thenable that
+/// resolves to itself
+const MAX_AWAITED_DEPTH: usize = 50;
+
+impl Checker {
+    /// Gets the global `Promise<T>` type, if the program declares it
+    pub(super) fn get_global_promise_type(&mut self) -> Option<TypeId> {
+        if let Some(&promise) = self.global_types.get("Promise") {
+            return Some(promise);
+        }
+        let promise = self.get_declared_global_type("Promise", 1)?;
+        self.global_types.insert("Promise", promise);
+        Some(promise)
+    }
+
+    /// Gets the type a promise or other thenable resolves to: the type of the value its
+    /// `then` method passes to its fulfillment callback
+    fn get_promised_type_of_promise(&mut self, type_: TypeId) -> Option<TypeId> {
+        let promise = self.get_global_promise_type();
+        if let TypeData::Reference {
+            target,
+            type_arguments,
+        } = &self.get_type(type_).data
+            && Some(*target) == promise
+        {
+            return type_arguments.first().copied();
+        }
+        if !self.flags(type_).contains(TypeFlags::OBJECT) {
+            return None;
+        }
+        let then_type = self.get_type_of_property_of_type(type_, "then")?;
+        let mut promised = Vec::new();
+        for signature in self.get_signatures_of_type(then_type, false) {
+            let Some(on_fulfilled) = self.get_type_at_position(signature, 0) else {
+                continue;
+            };
+            let on_fulfilled = self.get_non_nullable_type(on_fulfilled);
+            for callback in self.get_signatures_of_type(on_fulfilled, false) {
+                if let Some(value) = self.get_type_at_position(callback, 0) {
+                    promised.push(value);
+                }
+            }
+        }
+        if promised.is_empty() {
+            return None;
+        }
+        Some(self.get_union_type(&promised))
+    }
+
+    /// Gets the type awaiting a value of a type produces, unwrapping nested promises and
+    /// thenables, or the type itself if it is not one
+    pub(super) fn get_awaited_type(&mut self, type_: TypeId) -> TypeId {
+        self.get_awaited_type_worker(type_, 0)
+    }
+
+    fn get_awaited_type_worker(&mut self, type_: TypeId, depth: usize) -> TypeId {
+        if self.flags(type_).intersects(TypeFlags::ANY) || depth > MAX_AWAITED_DEPTH {
+            return type_;
+        }
+        if self.flags(type_).contains(TypeFlags::UNION) {
+            return self.map_type_constituents(type_, |checker, constituent| {
+                checker.get_awaited_type_worker(constituent, depth)
+            });
+        }
+        match self.get_promised_type_of_promise(type_) {
+            Some(promised) => self.get_awaited_type_worker(promised, depth + 1),
+            None => type_,
+        }
+    }
+
+    /// Gets `Promise<T>` for the type an async function or `import()` call resolves to,
+    /// reporting it when the program does not declare `Promise`
+    pub(super) fn create_promise_return_type(
+        &mut self,
+        function: &Arc<Node>,
+        promised_type: TypeId,
+    ) -> TypeId {
+        match self.get_global_promise_type() {
+            Some(promise) => self.create_type_reference(promise, vec![promised_type]),
+            None if function.kind == SyntaxKind::CallExpression => {
+                self.error(
+                    function,
+                    diagnostics::A_DYNAMIC_IMPORT_CALL_RETURNS_A_PROMISE_MAKE_SURE_YOU_HAVE_A_DECLARATION_FOR_PROMISE_OR_INCLUDE_ES2015_IN_YOUR_LIB_OPTION_2711,
+                    &[],
+                );
+                self.unknown_type
+            }
+            None => {
+                let error_node = function.name().cloned().unwrap_or(Arc::clone(function));
+                self.error(
+                    &error_node,
+                    diagnostics::AN_ASYNC_FUNCTION_OR_METHOD_MUST_RETURN_A_PROMISE_MAKE_SURE_YOU_HAVE_A_DECLARATION_FOR_PROMISE_OR_INCLUDE_ES2015_IN_YOUR_LIB_OPTION_2697,
+                    &[],
+                );
+                self.unknown_type
+            }
+        }
+    }
+
+    /// Checks that the annotated return type of an async function is the global `Promise`
+    /// type
+    pub(super) fn check_async_function_return_type(&mut self, function: &Arc<Node>) {
+        let Some(type_node) = function.type_node() else {
+            return;
+        };
+        let return_type = self.get_type_from_type_node(type_node);
+        if self.flags(return_type).intersects(TypeFlags::ANY) {
+            return;
+        }
+        let promise = self.get_global_promise_type();
+        let is_global_promise = matches!(
+            &self.get_type(return_type).data,
+            TypeData::Reference { target, .. } if Some(*target) == promise
+        );
+        if is_global_promise {
+            return;
+        }
+        let awaited_type = self.get_awaited_type(return_type);
+        let type_name = self.type_to_string(awaited_type);
+        self.error(
+            type_node,
+            diagnostics::THE_RETURN_TYPE_OF_AN_ASYNC_FUNCTION_OR_METHOD_MUST_BE_THE_GLOBAL_PROMISE_T_TYPE_DID_YOU_MEAN_TO_WRITE_PROMISE_0_1064,
+            &[type_name],
+        );
+    }
+
+    /// Gets the type of an `await` expression, reporting one outside of async functions and
+    /// module top levels that allow it
+    pub(super) fn check_await_expression(&mut self, node: &Arc<Node>) -> TypeId {
+        let file = get_source_file_of_node(node);
+        let start = skip_trivia(&file.as_source_file().text, node.pos());
+        self.check_await_context(
+            node,
+            TextRange::new(start, start + "await".len()),
+            diagnostics::AWAIT_EXPRESSIONS_ARE_ONLY_ALLOWED_WITHIN_ASYNC_FUNCTIONS_AND_AT_THE_TOP_LEVELS_OF_MODULES_1308,
+            diagnostics::AWAIT_EXPRESSIONS_ARE_ONLY_ALLOWED_AT_THE_TOP_LEVEL_OF_A_FILE_WHEN_THAT_FILE_IS_A_MODULE_BUT_THIS_FILE_HAS_NO_IMPORTS_OR_EXPORTS_CONSIDER_ADDING_AN_EMPTY_EXPORT_TO_MAKE_THIS_FILE_A_MODULE_1375,
+            diagnostics::TOP_LEVEL_AWAIT_EXPRESSIONS_ARE_ONLY_ALLOWED_WHEN_THE_MODULE_OPTION_IS_SET_TO_ES2022_ESNEXT_SYSTEM_NODE16_NODENEXT_OR_PRESERVE_AND_THE_TARGET_OPTION_IS_SET_TO_ES2017_OR_HIGHER_1378,
+        );
+        let type_ = self.check_expression(&node.as_await_expression().expression);
+        self.get_awaited_type(type_)
+    }
+
+    /// Checks where a `for await` loop is, and that what it iterates can be iterated
+    /// asynchronously
+    pub(super) fn check_for_await_statement(&mut self, node: &Arc<Node>, iterated: TypeId) {
+        let statement = node.as_for_of_statement();
+        let await_modifier = statement.await_modifier.as_ref().unwrap_or(node);
+        let file = get_source_file_of_node(node);
+        self.check_await_context(
+            node,
+            get_error_range(&file, await_modifier),
+            diagnostics::FOR_AWAIT_LOOPS_ARE_ONLY_ALLOWED_WITHIN_ASYNC_FUNCTIONS_AND_AT_THE_TOP_LEVELS_OF_MODULES_1103,
+            diagnostics::FOR_AWAIT_LOOPS_ARE_ONLY_ALLOWED_AT_THE_TOP_LEVEL_OF_A_FILE_WHEN_THAT_FILE_IS_A_MODULE_BUT_THIS_FILE_HAS_NO_IMPORTS_OR_EXPORTS_CONSIDER_ADDING_AN_EMPTY_EXPORT_TO_MAKE_THIS_FILE_A_MODULE_1431,
+            diagnostics::TOP_LEVEL_FOR_AWAIT_LOOPS_ARE_ONLY_ALLOWED_WHEN_THE_MODULE_OPTION_IS_SET_TO_ES2022_ESNEXT_SYSTEM_NODE16_NODENEXT_OR_PRESERVE_AND_THE_TARGET_OPTION_IS_SET_TO_ES2017_OR_HIGHER_1432,
+        );
+        // Only objects and strings can be iterated
+        let iterable = self.get_non_nullable_type(iterated);
+        if !self
+            .flags(iterable)
+            .intersects(TypeFlags::ANY | TypeFlags::UNKNOWN)
+            && !self.maybe_type_of_kind(
+                iterable,
+                TypeFlags::OBJECT
+                    | TypeFlags::INTERSECTION
+                    | TypeFlags::STRING_LIKE
+                    | TypeFlags::TYPE_PARAMETER,
+            )
+        {
+            let type_name = self.type_to_string(iterated);
+            self.error(
+                &statement.expression,
+                diagnostics::TYPE_0_MUST_HAVE_A_SYMBOL_ASYNC_ITERATOR_METHOD_THAT_RETURNS_AN_ASYNC_ITERATOR_2504,
+                &[type_name],
+            );
+        }
+    }
+
+    /// Reports an `await` or `for await` that is neither in an async function nor at the top
+    /// level of a module compiled for a module system and target that support it
+    fn check_await_context(
+        &mut self,
+        node: &Arc<Node>,
+        range: TextRange,
+        outside_async_function: &'static Message,
+        outside_module: &'static Message,
+        unsupported_options: &'static Message,
+    ) {
+        if let Some(function) = get_containing_function(node) {
+            if !function.has_modifier(SyntaxKind::AsyncKeyword) {
+                self.error_at_range(node, range, outside_async_function, &[]);
+            }
+            return;
+        }
+        if !is_external_module(&get_source_file_of_node(node)) {
+            self.error_at_range(node, range, outside_module, &[]);
+            return;
+        }
+        let module_supported = self.options.module.is_none_or(|module| {
+            matches!(
+                module,
+                ModuleKind::ES2022
+                    | ModuleKind::ESNext
+                    | ModuleKind::System
+                    | ModuleKind::Node16
+                    | ModuleKind::Node18
+                    | ModuleKind::NodeNext
+                    | ModuleKind::Preserve
+            )
+        });
+        let target_supported = self
+            .options
+            .target
+            .is_none_or(|target| target >= ScriptTarget::ES2017);
+        if !module_supported || !target_supported {
+            self.error_at_range(node, range, unsupported_options, &[]);
+        }
+    }
+}
+
+/// Determines if a function is async and not a generator, so that it returns a promise of
+/// the awaited types of what it returns
+pub(super) fn is_async_function(function: &Node) -> bool {
+    function.has_modifier(SyntaxKind::AsyncKeyword) && !is_generator(function)
+}
+
+/// Determines if a node is in the body of an async function that is not a generator
+pub(super) fn is_in_async_function(node: &Arc<Node>) -> bool {
+    get_containing_function(node).is_some_and(|function| is_async_function(&function))
+}
@@ -0,0 +1,3071 @@
+//! Relations between types
+//!
+//! Assignability, comparability, subtyping, and identity are decided by the same structural
+//! comparison, which differs only in a few rules. A [`Relater`] performs one comparison and,
+//! when asked to, collects the chain of messages explaining why two types are unrelated, such
+//! as the property whose types are incompatible and the types of that property.
+
+use std::collections::HashSet;
+use std::mem;
+use std::sync::Arc;
+
+use crate::compiler::ast::symbol::symbol_name;
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics::{self, Diagnostic, DiagnosticMessageChain, Message};
+
+use super::{
+    Checker, ElementFlags, IndexInfo, LiteralValue, ObjectFlags, SignatureFlags, SignatureId,
+    TypeData, TypeFlags, TypeId,
+};
+
+/// A relation between types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relation {
+    /// The types are the same type
+    Identity,
+    /// Values of the source are values of the target, without the allowances for `any`
+    /// and enums that assignability makes
+    Subtype,
+    /// Values of the source can be assigned to locations of the target
+    Assignable,
+    /// Values of the two types may be compared, as in `===` and `switch`
+    Comparable,
+}
+
+/// How a generic type's relation follows the relation of a type argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Variance {
+    /// Both ways: `T` is used in both input and output positions
+    Invariant,
+    /// `Foo<Sub>` is related to `Foo<Super>`
+    Covariant,
+    /// `Foo<Super>` is related to `Foo<Sub>`
+    Contravariant,
+    /// Either way, as with method parameters
+    Bivariant,
+    /// The type argument does not affect the relation
+    Independent,
+}
+
+/// The result of relating two types, where `Maybe` means the comparison depends on a
+/// comparison of the same types that is still in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Ternary {
+    False,
+    Maybe,
+    True,
+}
+
+/// How many times a generic type may recur on both sides of a comparison before the
+/// comparison is assumed to succeed
+const MAX_NESTED_TYPE_DEPTH: usize = 3;
+
+/// The error explaining why a source type is not related to a target type
+pub(super) struct RelationError {
+    pub chain: DiagnosticMessageChain,
+    pub related_information: Vec<Diagnostic>,
+    /// A node the error belongs on instead of the node being checked, such as the name of an
+    /// excess property in an object literal
+    pub error_node: Option<Arc<Node>>,
+}
+
+impl Checker {
+    /// Determines if values of type `source` can be assigned to locations of type `target`
+    pub fn is_type_assignable_to(&mut self, source: TypeId, target: TypeId) -> bool {
+        self.is_type_related_to(source, target, Relation::Assignable)
+    }
+
+    /// Determines if two types are the same type
+    pub fn is_type_identical_to(&mut self, source: TypeId, target: TypeId) -> bool {
+        self.is_type_related_to(source, target, Relation::Identity)
+    }
+
+    /// Determines if values of two types may be compared with each other
+    pub fn is_type_comparable_to(&mut self, source: TypeId, target: TypeId) -> bool {
+        self.is_type_related_to(source, target, Relation::Comparable)
+    }
+
+    /// Determines if `source` is a subtype of `target`
+    pub fn is_type_subtype_of(&mut self, source: TypeId, target: TypeId) -> bool {
+        self.is_type_related_to(source, target, Relation::Subtype)
+    }
+
+    pub(super) fn is_type_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        relation: Relation,
+    ) -> bool {
+        // A literal relates the same way whether or not it is fresh
+        let source = self.get_regular_type_of_fresh_literal_type(source);
+        let target = self.get_regular_type_of_fresh_literal_type(target);
+        if source == target {
+            return true;
+        }
+        if relation != Relation::Identity {
+            if (relation == Relation::Comparable
+                && !self.flags(target).contains(TypeFlags::NEVER)
+                && self.is_simple_type_related_to(target, source, relation))
+                || self.is_simple_type_related_to(source, target, relation)
+            {
+                return true;
+            }
+        } else if self.flags(source) != self.flags(target) {
+            return false;
+        }
+        if (self.flags(source) | self.flags(target))
+            .intersects(TypeFlags::STRUCTURED_OR_INSTANTIABLE)
+        {
+            if let Some(&related) = self.relation_cache.get(&(relation, source, target)) {
+                return related;
+            }
+            return self
+                .get_relation_error(source, target, relation, None)
+                .is_none();
+        }
+        false
+    }
+
+    /// Relates two types, reporting why they are unrelated on `error_node` if they are not
+    pub(super) fn check_type_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        relation: Relation,
+        error_node: &Arc<Node>,
+        head_message: Option<&'static Message>,
+    ) -> bool {
+        self.check_type_related_to_with_related_information(
+            source,
+            target,
+            relation,
+            error_node,
+            head_message,
+            Vec::new(),
+        )
+    }
+
+    /// Relates two types like [`Checker::check_type_related_to`], attaching extra related
+    /// information to the error
+    pub(super) fn check_type_related_to_with_related_information(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        relation: Relation,
+        error_node: &Arc<Node>,
+        head_message: Option<&'static Message>,
+        related_information: Vec<Diagnostic>,
+    ) -> bool {
+        let Some(error) = self.get_relation_error(source, target, relation, head_message) else {
+            return true;
+        };
+        let node = error.error_node.as_ref().unwrap_or(error_node);
+        let mut related = error.related_information;
+        related.extend(related_information);
+        self.error_with_chain(node, &error.chain, related);
+        false
+    }
+
+    /// Determines if `source` is assignable to `target`, reporting an error on `error_node`
+    /// if it is not
+    pub(super) fn check_type_assignable_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        error_node: &Arc<Node>,
+        head_message: Option<&'static Message>,
+    ) -> bool {
+        self.check_type_related_to(
+            source,
+            target,
+            Relation::Assignable,
+            error_node,
+            head_message,
+        )
+    }
+
+    /// Determines if `source` is assignable to `target`, reporting errors on the parts of
+    /// `expression` that are not assignable when it is a literal, or on `error_node` otherwise
+    pub(super) fn check_type_assignable_to_and_optionally_elaborate(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        error_node: &Arc<Node>,
+        expression: Option<&Arc<Node>>,
+        head_message: Option<&'static Message>,
+    ) -> bool {
+        if self.is_type_assignable_to(source, target) {
+            return true;
+        }
+        if expression.is_none_or(|expression| !self.elaborate_error(expression, source, target)) {
+            self.check_type_assignable_to(source, target, error_node, head_message);
+        }
+        false
+    }
+
+    /// Relates two types, returning the error explaining why they are unrelated if they are
+    /// not
+    pub(super) fn get_relation_error(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        relation: Relation,
+        head_message: Option<&'static Message>,
+    ) -> Option<RelationError> {
+        let mut relater = Relater::new(self, relation, head_message);
+        let result = relater.is_related_to(source, target, true, head_message);
+        if relater.incompatible_stack.is_some() {
+            relater.report_incompatible_stack();
+        }
+        if result != Ternary::False {
+            return None;
+        }
+        let chain = relater.error_info.take().unwrap_or_else(|| {
+            DiagnosticMessageChain::new(
+                head_message.unwrap_or(diagnostics::TYPE_0_IS_NOT_ASSIGNABLE_TO_TYPE_1_2322),
+                &[String::new(), String::new()],
+            )
+        });
+        Some(RelationError {
+            chain,
+            related_information: mem::take(&mut relater.related_information),
+            error_node: relater.error_node.take(),
+        })
+    }
+
+    /// Determines if two types are related by the rules that need no structural comparison
+    fn is_simple_type_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        relation: Relation,
+    ) -> bool {
+        let s = self.flags(source);
+        let t = self.flags(target);
+        if t.contains(TypeFlags::ANY) || s.contains(TypeFlags::NEVER) {
+            return true;
+        }
+        if t.contains(TypeFlags::UNKNOWN) {
+            return true;
+        }
+        if t.contains(TypeFlags::NEVER) {
+            return false;
+        }
+        if s.intersects(TypeFlags::STRING_LIKE) && t.contains(TypeFlags::STRING)
+            || s.intersects(TypeFlags::NUMBER_LIKE) && t.contains(TypeFlags::NUMBER)
+            || s.intersects(TypeFlags::BIG_INT_LIKE) && t.contains(TypeFlags::BIG_INT)
+            || s.intersects(TypeFlags::BOOLEAN_LIKE) && t.contains(TypeFlags::BOOLEAN)
+            || s.intersects(TypeFlags::ES_SYMBOL_LIKE) && t.contains(TypeFlags::ES_SYMBOL)
+        {
+            return true;
+        }
+        if s.contains(TypeFlags::UNDEFINED)
+            && (!self.options.strict_null_checks && !t.intersects(TypeFlags::UNION_OR_INTERSECTION)
+                || t.intersects(TypeFlags::UNDEFINED | TypeFlags::VOID))
+        {
+            return true;
+        }
+        if s.contains(TypeFlags::NULL)
+            && (!self.options.strict_null_checks && !t.intersects(TypeFlags::UNION_OR_INTERSECTION)
+                || t.contains(TypeFlags::NULL))
+        {
+            return true;
+        }
+        if s.contains(TypeFlags::OBJECT) && t.contains(TypeFlags::NON_PRIMITIVE) {
+            return true;
+        }
+        if matches!(relation, Relation::Assignable | Relation::Comparable) {
+            if s.contains(TypeFlags::ANY) {
+                return true;
+            }
+            // Numbers are assignable to numeric enums, so that enums can be used as bit flags,
+            // but a number literal only to the members with its value
+            let is_numeric_enum_literal =
+                t.contains(TypeFlags::NUMBER_LITERAL) && t.contains(TypeFlags::ENUM_LITERAL);
+            if s.contains(TypeFlags::NUMBER)
+                && (t.contains(TypeFlags::ENUM) || is_numeric_enum_literal)
+            {
+                return true;
+            }
+            if s.contains(TypeFlags::NUMBER_LITERAL)
+                && !s.contains(TypeFlags::ENUM_LITERAL)
+                && (t.contains(TypeFlags::ENUM)
+                    || is_numeric_enum_literal
+                        && matches!(
+                            (&self.get_type(source).data, &self.get_type(target).data),
+                            (
+                                TypeData::Literal { value: source_value },
+                                TypeData::Literal { value: target_value },
+                            ) if source_value == target_value
+                        ))
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Gets the variances of the type parameters of a generic class, interface, or tuple
+    /// type, or None while they are being measured
+    ///
+    /// The variances are measured by relating instantiations with marker types, a supertype
+    /// and a subtype of it, in place of each type parameter.
+    pub(super) fn get_variances(&mut self, generic: TypeId) -> Option<Vec<Variance>> {
+        let TypeData::Interface(interface) = &self.get_type(generic).data else {
+            return None;
+        };
+        let type_parameters = interface.type_parameters();
+        if self.object_flags(generic).contains(ObjectFlags::TUPLE)
+            || self.is_fallback_type(generic)
+            || self.global_types.get("Array") == Some(&generic)
+            || self.global_types.get("ReadonlyArray") == Some(&generic)
+        {
+            // Arrays and tuples are covariant by fiat, ignoring their mutating methods
+            return Some(vec![Variance::Covariant; type_parameters.len()]);
+        }
+        if let Some(variances) = self.variances.get(&generic) {
+            return variances.clone();
+        }
+        self.variances.insert(generic, None);
+        let [marker_super, marker_sub, marker_other] = self.get_variance_markers();
+        let mut variances = Vec::new();
+        for index in 0..type_parameters.len() {
+            let with_marker = |checker: &mut Checker, marker: TypeId| {
+                let mut arguments = type_parameters.clone();
+                arguments[index] = marker;
+                checker.create_type_reference(generic, arguments)
+            };
+            let type_with_super = with_marker(self, marker_super);
+            let type_with_sub = with_marker(self, marker_sub);
+            let covariant = self.is_type_assignable_to(type_with_sub, type_with_super);
+            let contravariant = self.is_type_assignable_to(type_with_super, type_with_sub);
+            let mut variance = match (covariant, contravariant) {
+                (true, true) => Variance::Bivariant,
+                (true, false) => Variance::Covariant,
+                (false, true) => Variance::Contravariant,
+                (false, false) => Variance::Invariant,
+            };
+            if variance == Variance::Bivariant {
+                // A type argument that relates both ways may not be used at all
+                let type_with_other = with_marker(self, marker_other);
+                if self.is_type_assignable_to(type_with_other, type_with_super) {
+                    variance = Variance::Independent;
+                }
+            }
+            variances.push(variance);
+        }
+        self.variances.insert(generic, Some(variances.clone()));
+        Some(variances)
+    }
+
+    /// Gets the marker types used to measure variance: a supertype, a subtype of it, and an
+    /// unrelated type
+    fn get_variance_markers(&mut self) -> [TypeId; 3] {
+        if let Some(markers) = self.variance_markers {
+            return markers;
+        }
+        let create_marker = |checker: &mut Checker, name: &str, constraint| {
+            let symbol = checker
+                .bindings
+                .symbols
+                .alloc(name, SymbolFlags::TYPE_PARAMETER | SymbolFlags::TRANSIENT);
+            checker.create_type(
+                TypeFlags::TYPE_PARAMETER,
+                ObjectFlags::NONE,
+                Some(symbol),
+                TypeData::TypeParameter {
+                    constraint: Some(constraint),
+                    is_this_type: false,
+                },
+            )
+        };
+        let marker_super = create_marker(self, "T", None);
+        let marker_sub = create_marker(self, "T", Some(marker_super));
+        let marker_other = create_marker(self, "T", None);
+        let markers = [marker_super, marker_sub, marker_other];
+        self.variance_markers = Some(markers);
+        markers
+    }
+
+    /// Gets the type of a signature's parameter at a position, reading past the end of a
+    /// rest parameter as its element type
+    pub(super) fn get_type_at_position(
+        &mut self,
+        signature: SignatureId,
+        position: usize,
+    ) -> Option<TypeId> {
+        let signature = self.get_signature(signature);
+        let count = signature.parameters.len();
+        let has_rest_parameter = signature.has_rest_parameter();
+        if has_rest_parameter && position + 1 >= count {
+            let rest = signature.parameters[count - 1];
+            let rest_type = self.get_type_of_symbol(rest);
+            // A rest parameter of a tuple type takes one argument per element
+            if let Some((_, element_flags, _)) = self.get_tuple_elements(rest_type) {
+                let index = position + 1 - count;
+                let type_ = self.get_element_type_at_index(rest_type, index)?;
+                return Some(match element_flags.get(index) {
+                    Some(&flags) if flags.contains(ElementFlags::OPTIONAL) => {
+                        self.get_optional_type(type_, false)
+                    }
+                    _ => type_,
+                });
+            }
+            return Some(self.get_element_type_of_iterable(rest_type));
+        }
+        let parameter = *signature.parameters.get(position)?;
+        Some(self.get_type_of_symbol(parameter))
+    }
+
+    fn get_parameter_name_at_position(&self, signature: SignatureId, position: usize) -> String {
+        let parameters = &self.get_signature(signature).parameters;
+        let index = position.min(parameters.len().saturating_sub(1));
+        parameters
+            .get(index)
+            .map(|&parameter| self.symbol(parameter).name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replaces the type parameters of a generic signature with `any`
+    fn get_erased_signature(&mut self, signature: SignatureId) -> SignatureId {
+        let count = self.get_signature(signature).type_parameters.len();
+        if count == 0 {
+            return signature;
+        }
+        self.get_signature_instantiation(signature, vec![self.any_type; count])
+    }
+
+    pub(super) fn get_type_of_property_of_type(
+        &mut self,
+        type_: TypeId,
+        name: &str,
+    ) -> Option<TypeId> {
+        let property = self.get_property_of_type(type_, name)?;
+        Some(self.get_type_of_symbol(property))
+    }
+
+    /// Determines if some constituent of a union, or the type itself, satisfies `f`
+    pub(super) fn some_type(
+        &mut self,
+        type_: TypeId,
+        f: impl Fn(&mut Checker, TypeId) -> bool,
+    ) -> bool {
+        if !self.flags(type_).contains(TypeFlags::UNION) {
+            return f(self, type_);
+        }
+        self.get_constituents(type_)
+            .into_iter()
+            .any(|constituent| f(self, constituent))
+    }
+
+    pub(super) fn get_constituents(&self, type_: TypeId) -> Vec<TypeId> {
+        match &self.get_type(type_).data {
+            TypeData::UnionOrIntersection { types } => types.clone(),
+            _ => vec![type_],
+        }
+    }
+
+    /// Gets the element types, flags, and readonly-ness of a tuple type
+    pub(super) fn get_tuple_elements(
+        &mut self,
+        type_: TypeId,
+    ) -> Option<(Vec<TypeId>, Vec<ElementFlags>, bool)> {
+        self.resolve_deferred_type_arguments(type_);
+        let TypeData::Reference {
+            target,
+            type_arguments,
+        } = &self.get_type(type_).data
+        else {
+            return None;
+        };
+        let TypeData::Interface(interface) = &self.get_type(*target).data else {
+            return None;
+        };
+        if !self.object_flags(*target).contains(ObjectFlags::TUPLE) {
+            return None;
+        }
+        Some((
+            type_arguments.clone(),
+            interface.element_flags.clone(),
+            interface.readonly,
+        ))
+    }
+
+    fn is_array_or_tuple_type(&self, type_: TypeId) -> bool {
+        self.is_array_type(type_) || self.is_tuple_type(type_)
+    }
+
+    fn is_mutable_array_or_tuple(&mut self, type_: TypeId) -> bool {
+        self.is_array_type(type_) && !self.is_readonly_array_type(type_)
+            || self
+                .get_tuple_elements(type_)
+                .is_some_and(|(_, _, readonly)| !readonly)
+    }
+
+    /// Determines if a type is a literal type, or a union of them
+    pub(super) fn is_literal_type(&self, type_: TypeId) -> bool {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::BOOLEAN) {
+            return true;
+        }
+        if flags.contains(TypeFlags::UNION) {
+            return self
+                .get_constituents(type_)
+                .iter()
+                .all(|&constituent| self.flags(constituent).intersects(TypeFlags::UNIT));
+        }
+        flags.intersects(TypeFlags::UNIT)
+    }
+
+    /// Determines if a type could be a single value, in which case literal sources are
+    /// printed as themselves rather than as their primitive types
+    fn type_could_have_top_level_singleton_types(&mut self, type_: TypeId) -> bool {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::BOOLEAN) {
+            return false;
+        }
+        if flags.intersects(TypeFlags::UNION_OR_INTERSECTION) {
+            return self
+                .get_constituents(type_)
+                .into_iter()
+                .any(|constituent| self.type_could_have_top_level_singleton_types(constituent));
+        }
+        if flags.contains(TypeFlags::TYPE_PARAMETER) {
+            return self
+                .get_constraint_of_type_parameter(type_)
+                .is_some_and(|constraint| {
+                    constraint != type_
+                        && self.type_could_have_top_level_singleton_types(constraint)
+                });
+        }
+        flags.intersects(TypeFlags::UNIT | TypeFlags::TEMPLATE_LITERAL | TypeFlags::STRING_MAPPING)
+    }
+
+    /// Determines if a type has no members at all
+    pub(super) fn is_empty_object_type(&mut self, type_: TypeId) -> bool {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::OBJECT) {
+            if self.is_array_or_tuple_type(type_) {
+                return false;
+            }
+            let members = self.resolve_structured_type_members(type_);
+            return members.properties.is_empty()
+                && members.call_signatures.is_empty()
+                && members.construct_signatures.is_empty()
+                && members.index_infos.is_empty();
+        }
+        if flags.contains(TypeFlags::NON_PRIMITIVE) {
+            return true;
+        }
+        if flags.contains(TypeFlags::UNION) {
+            return self
+                .get_constituents(type_)
+                .into_iter()
+                .any(|constituent| self.is_empty_object_type(constituent));
+        }
+        if flags.contains(TypeFlags::INTERSECTION) {
+            return self
+                .get_constituents(type_)
+                .into_iter()
+                .all(|constituent| self.is_empty_object_type(constituent));
+        }
+        false
+    }
+
+    /// Determines if a type's properties are all optional, so that any object with none of
+    /// them would be assignable to it
+    fn is_weak_type(&mut self, type_: TypeId) -> bool {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::OBJECT) {
+            let members = self.resolve_structured_type_members(type_);
+            return members.call_signatures.is_empty()
+                && members.construct_signatures.is_empty()
+                && members.index_infos.is_empty()
+                && !members.properties.is_empty()
+                && members
+                    .properties
+                    .iter()
+                    .all(|&property| self.symbol(property).flags.contains(SymbolFlags::OPTIONAL));
+        }
+        if flags.contains(TypeFlags::INTERSECTION) {
+            return self
+                .get_constituents(type_)
+                .into_iter()
+                .all(|constituent| self.is_weak_type(constituent));
+        }
+        false
+    }
+
+    /// Determines if a type has a property of the given name, or an index signature that
+    /// covers it
+    fn is_known_property(&mut self, target: TypeId, name: &str) -> bool {
+        let flags = self.flags(target);
+        if flags.intersects(TypeFlags::UNION_OR_INTERSECTION) {
+            return self
+                .get_constituents(target)
+                .into_iter()
+                .any(|constituent| self.is_known_property(constituent, name));
+        }
+        if !flags.contains(TypeFlags::OBJECT) {
+            return false;
+        }
+        if self.get_property_of_type(target, name).is_some() {
+            return true;
+        }
+        let key_type = if is_numeric_literal_name(name) {
+            self.number_type
+        } else {
+            self.string_type
+        };
+        self.get_applicable_index_info(target, key_type).is_some()
+            || self.is_permissive_type(target)
+    }
+
+    /// Determines if a type is an object type whose properties an object literal must
+    /// match exactly
+    fn is_excess_property_check_target(&mut self, type_: TypeId) -> bool {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::OBJECT) {
+            return !self.is_permissive_type(type_);
+        }
+        if flags.contains(TypeFlags::NON_PRIMITIVE) {
+            return true;
+        }
+        if flags.contains(TypeFlags::UNION) {
+            return self
+                .get_constituents(type_)
+                .into_iter()
+                .any(|constituent| self.is_excess_property_check_target(constituent));
+        }
+        if flags.contains(TypeFlags::INTERSECTION) {
+            return self
+                .get_constituents(type_)
+                .into_iter()
+                .all(|constituent| self.is_excess_property_check_target(constituent));
+        }
+        false
+    }
+
+    /// Determines if a type's index signatures can be inferred from its properties, as for
+    /// object literal and type literal types
+    fn is_object_type_with_inferable_index(&mut self, type_: TypeId) -> bool {
+        if self.flags(type_).contains(TypeFlags::INTERSECTION) {
+            return self
+                .get_constituents(type_)
+                .into_iter()
+                .all(|constituent| self.is_object_type_with_inferable_index(constituent));
+        }
+        let Some(symbol) = self.get_type(type_).symbol else {
+            return false;
+        };
+        let flags = self.symbol(symbol).flags;
+        if !flags.intersects(
+            SymbolFlags::OBJECT_LITERAL
+                | SymbolFlags::TYPE_LITERAL
+                | SymbolFlags::ENUM
+                | SymbolFlags::VALUE_MODULE,
+        ) || flags.contains(SymbolFlags::CLASS)
+        {
+            return false;
+        }
+        let members = self.resolve_structured_type_members(type_);
+        members.call_signatures.is_empty() && members.construct_signatures.is_empty()
+    }
+
+    /// Determines if keys of type `source` can index an index signature with key type
+    /// `target`
+    pub(super) fn is_applicable_index_type(&mut self, source: TypeId, target: TypeId) -> bool {
+        if self.is_type_assignable_to(source, target) {
+            return true;
+        }
+        if target == self.string_type && self.is_type_assignable_to(source, self.number_type) {
+            return true;
+        }
+        target == self.number_type
+            && matches!(
+                &self.get_type(source).data,
+                TypeData::Literal { value: LiteralValue::String(text) } if is_numeric_literal_name(text)
+            )
+    }
+
+    /// Determines if a property is declared private
+    fn is_private_property(&self, property: SymbolId) -> bool {
+        self.symbol(property)
+            .value_declaration
+            .as_ref()
+            .is_some_and(|declaration| {
+                declaration.has_modifier(SyntaxKind::PrivateKeyword)
+                    || declaration
+                        .name()
+                        .is_some_and(|name| name.kind == SyntaxKind::PrivateIdentifier)
+            })
+    }
+
+    /// Gets the constituent of a union target that an unrelated source was most likely
+    /// meant to be, so that errors can explain why it is not that constituent
+    fn get_best_matching_type(&mut self, source: TypeId, target: TypeId) -> Option<TypeId> {
+        // The constituents whose discriminants the source's properties match
+        if let Some(matching) = self.find_matching_discriminant_type(source, target) {
+            return Some(matching);
+        }
+        let types = self.get_constituents(target);
+        // A reference to the same generic type
+        if let Some((source_target, _)) = self
+            .get_reference_target(source)
+            .filter(|_| self.object_flags(source).contains(ObjectFlags::REFERENCE))
+            && let Some(&matching) = types.iter().find(|&&type_| {
+                self.get_reference_target(type_)
+                    .is_some_and(|(type_target, _)| type_target == source_target)
+            })
+        {
+            return Some(matching);
+        }
+        // An object literal is meant for a constituent that is not an array
+        if self
+            .object_flags(source)
+            .contains(ObjectFlags::OBJECT_LITERAL)
+            && types
+                .iter()
+                .any(|&type_| self.is_array_or_tuple_type(type_))
+        {
+            return types
+                .iter()
+                .copied()
+                .find(|&type_| !self.is_array_or_tuple_type(type_));
+        }
+        // A function is meant for a constituent with the same kind of signatures
+        for construct in [false, true] {
+            if !self.get_signatures_of_type(source, construct).is_empty() {
+                for &type_ in &types {
+                    if !self.get_signatures_of_type(type_, construct).is_empty() {
+                        return Some(type_);
+                    }
+                }
+            }
+        }
+        // Otherwise the object type sharing the most property names with the source
+        if !self
+            .flags(source)
+            .intersects(TypeFlags::OBJECT | TypeFlags::INTERSECTION)
+        {
+            return None;
+        }
+        let names: Vec<String> = self
+            .get_properties_of_type(source)
+            .into_iter()
+            .map(|property| self.symbol(property).name.clone())
+            .collect();
+        let mut best = None;
+        let mut best_count = 1;
+        for type_ in types {
+            if self
+                .flags(type_)
+                .intersects(TypeFlags::PRIMITIVE | TypeFlags::INSTANTIABLE_PRIMITIVE)
+            {
+                continue;
+            }
+            let count = names
+                .iter()
+                .filter(|name| self.get_property_of_type(type_, name).is_some())
+                .count();
+            if count >= best_count {
+                best = Some(type_);
+                best_count = count;
+            }
+        }
+        best
+    }
+
+    /// Reports errors on the properties or elements of a literal expression whose types are
+    /// not assignable to the target, returning true if any was reported
+    fn elaborate_error(&mut self, node: &Arc<Node>, source: TypeId, target: TypeId) -> bool {
+        if self
+            .flags(target)
+            .intersects(TypeFlags::PRIMITIVE | TypeFlags::NEVER)
+        {
+            return false;
+        }
+        match node.kind {
+            SyntaxKind::ParenthesizedExpression => {
+                let expression = &node.as_parenthesized_expression().expression;
+                self.elaborate_error(expression, source, target)
+            }
+            SyntaxKind::BinaryExpression => {
+                let binary = node.as_binary_expression();
+                match binary.operator_token.kind {
+                    SyntaxKind::EqualsToken | SyntaxKind::CommaToken => {
+                        self.elaborate_error(&binary.right, source, target)
+                    }
+                    _ => false,
+                }
+            }
+            SyntaxKind::ObjectLiteralExpression => {
+                self.elaborate_object_literal(node, source, target)
+            }
+            SyntaxKind::ArrayLiteralExpression => self.elaborate_array_literal(node, target),
+            SyntaxKind::ArrowFunction => self.elaborate_arrow_function(node, source, target),
+            _ => false,
+        }
+    }
+
+    fn elaborate_object_literal(
+        &mut self,
+        node: &Arc<Node>,
+        source: TypeId,
+        target: TypeId,
+    ) -> bool {
+        let mut reported = false;
+        for property in &node.as_object_literal_expression().properties {
+            let (name_node, initializer) = match property.kind {
+                SyntaxKind::PropertyAssignment => {
+                    let assignment = property.as_property_assignment();
+                    (&assignment.name, Some(&assignment.initializer))
+                }
+                SyntaxKind::ShorthandPropertyAssignment => {
+                    (&property.as_shorthand_property_assignment().name, None)
+                }
+                _ => continue,
+            };
+            if name_node.kind == SyntaxKind::ComputedPropertyName {
+                continue;
+            }
+            let name = name_node.text().to_string();
+            let Some(target_property_type) =
+                self.get_indexed_access_type_or_undefined(target, &name)
+            else {
+                continue;
+            };
+            let Some(source_property_type) = self.get_type_of_property_of_type(source, &name)
+            else {
+                continue;
+            };
+            if self.is_type_assignable_to(source_property_type, target_property_type) {
+                continue;
+            }
+            reported = true;
+            if initializer.is_some_and(|initializer| {
+                self.elaborate_error(initializer, source_property_type, target_property_type)
+            }) {
+                continue;
+            }
+            let related_information = self.get_expected_property_information(target, &name);
+            self.check_type_related_to_with_related_information(
+                source_property_type,
+                target_property_type,
+                Relation::Assignable,
+                name_node,
+                None,
+                related_information,
+            );
+        }
+        reported
+    }
+
+    /// Gets the related information pointing at the declaration of the target property a
+    /// property of an object literal was checked against
+    fn get_expected_property_information(&mut self, target: TypeId, name: &str) -> Vec<Diagnostic> {
+        let target_property = self.get_property_of_type(target, name);
+        let declaration = match target_property {
+            Some(property) => self.symbol(property).declarations.first().cloned(),
+            None => {
+                let key_type = self.get_string_literal_type(name);
+                if let Some(declaration) = self
+                    .get_applicable_index_info(target, key_type)
+                    .and_then(|info| info.declaration)
+                {
+                    return vec![self.create_diagnostic_for_node(
+                        &declaration,
+                        diagnostics::THE_EXPECTED_TYPE_COMES_FROM_THIS_INDEX_SIGNATURE_6501,
+                        &[],
+                    )];
+                }
+                None
+            }
+        };
+        let Some(declaration) = declaration else {
+            return Vec::new();
+        };
+        let target_name = self.type_to_string(target);
+        vec![self.create_diagnostic_for_node(
+            &declaration,
+            diagnostics::THE_EXPECTED_TYPE_COMES_FROM_PROPERTY_0_WHICH_IS_DECLARED_HERE_ON_TYPE_1_6500,
+            &[name.to_string(), target_name],
+        )]
+    }
+
+    fn elaborate_array_literal(&mut self, node: &Arc<Node>, target: TypeId) -> bool {
+        let mut reported = false;
+        for (index, element) in node
+            .as_array_literal_expression()
+            .elements
+            .iter()
+            .enumerate()
+        {
+            if matches!(
+                element.kind,
+                SyntaxKind::SpreadElement | SyntaxKind::OmittedExpression
+            ) {
+                continue;
+            }
+            let Some(target_element_type) = self.get_element_type_at_index(target, index) else {
+                continue;
+            };
+            let element_type = self.check_expression(element);
+            let source_element_type = self.get_widened_literal_like_type_for_contextual_type(
+                element_type,
+                Some(target_element_type),
+            );
+            if self.is_type_assignable_to(source_element_type, target_element_type) {
+                continue;
+            }
+            reported = true;
+            if !self.elaborate_error(element, source_element_type, target_element_type) {
+                self.check_type_assignable_to(
+                    source_element_type,
+                    target_element_type,
+                    element,
+                    None,
+                );
+            }
+        }
+        reported
+    }
+
+    fn elaborate_arrow_function(
+        &mut self,
+        node: &Arc<Node>,
+        source: TypeId,
+        target: TypeId,
+    ) -> bool {
+        let Some(body) = node.body().filter(|body| body.kind != SyntaxKind::Block) else {
+            return false;
+        };
+        if node
+            .parameters()
+            .into_iter()
+            .flatten()
+            .any(|parameter| parameter.type_node().is_some())
+        {
+            return false;
+        }
+        let source_signatures = self.get_signatures_of_type(source, false);
+        let [source_signature] = source_signatures[..] else {
+            return false;
+        };
+        let target_signatures = self.get_signatures_of_type(target, false);
+        if target_signatures.is_empty() {
+            return false;
+        }
+        let source_return = self.get_return_type_of_signature(source_signature);
+        let target_returns: Vec<TypeId> = target_signatures
+            .iter()
+            .map(|&signature| self.get_return_type_of_signature(signature))
+            .collect();
+        let target_return = self.get_union_type(&target_returns);
+        if self.is_type_assignable_to(source_return, target_return) {
+            return false;
+        }
+        if self.elaborate_error(body, source_return, target_return) {
+            return true;
+        }
+        let related_information = match self
+            .get_type(target)
+            .symbol
+            .and_then(|symbol| self.symbol(symbol).declarations.first().cloned())
+        {
+            Some(declaration) => vec![self.create_diagnostic_for_node(
+                &declaration,
+                diagnostics::THE_EXPECTED_TYPE_COMES_FROM_THE_RETURN_TYPE_OF_THIS_SIGNATURE_6502,
+                &[],
+            )],
+            None => Vec::new(),
+        };
+        self.check_type_related_to_with_related_information(
+            source_return,
+            target_return,
+            Relation::Assignable,
+            body,
+            None,
+            related_information,
+        );
+        true
+    }
+
+    /// Gets the type of a property of a type, or of the index signature that covers it
+    pub(super) fn get_indexed_access_type_or_undefined(
+        &mut self,
+        type_: TypeId,
+        name: &str,
+    ) -> Option<TypeId> {
+        if let Some(type_) = self.get_type_of_property_of_type(type_, name) {
+            return Some(type_);
+        }
+        let key_type = if is_numeric_literal_name(name) {
+            self.number_type
+        } else {
+            self.string_type
+        };
+        self.get_applicable_index_info(type_, key_type)
+            .map(|info| info.value_type)
+    }
+
+    /// Gets the type of the element at an index of an array or tuple type
+    pub(super) fn get_element_type_at_index(
+        &mut self,
+        type_: TypeId,
+        index: usize,
+    ) -> Option<TypeId> {
+        if let Some((element_types, element_flags, _)) = self.get_tuple_elements(type_) {
+            if let Some(&flags) = element_flags.get(index)
+                && !flags.intersects(ElementFlags::VARIABLE)
+            {
+                return Some(element_types[index]);
+            }
+            let rest = element_flags
+                .iter()
+                .position(|flags| flags.intersects(ElementFlags::VARIABLE))?;
+            return Some(element_types[rest]);
+        }
+        self.get_applicable_index_info(type_, self.number_type)
+            .map(|info| info.value_type)
+    }
+}
+
+/// The state of the error being built while relating two types
+struct ErrorState {
+    error_info: Option<DiagnosticMessageChain>,
+    last_skipped_info: Option<(TypeId, TypeId)>,
+    incompatible_stack: Option<Vec<(&'static Message, Vec<String>)>>,
+    override_next_error_info: usize,
+    related_information_count: usize,
+}
+
+/// Relates a source type to a target type, building the chain of messages that explains
+/// why they are unrelated
+struct Relater<'a> {
+    checker: &'a mut Checker,
+    relation: Relation,
+    /// The message the caller wants the error to start with
+    head_message: Option<&'static Message>,
+    error_node: Option<Arc<Node>>,
+    error_info: Option<DiagnosticMessageChain>,
+    related_information: Vec<Diagnostic>,
+    /// The messages about properties and signatures whose relation failed, innermost first,
+    /// which are combined into one message when the error is reported
+    incompatible_stack: Option<Vec<(&'static Message, Vec<String>)>>,
+    /// The types whose relation error was skipped in favor of a more specific message
+    last_skipped_info: Option<(TypeId, TypeId)>,
+    /// How many of the next relation errors to leave out because a more specific message
+    /// already explains them
+    override_next_error_info: usize,
+    /// How many of the next messages to leave out because a more specific message already
+    /// replaced them
+    skip_parent_counter: usize,
+    /// The comparisons in progress, whose results are assumed to be `Maybe`
+    maybe_keys: Vec<(TypeId, TypeId)>,
+    maybe_key_set: HashSet<(TypeId, TypeId)>,
+    source_stack: Vec<TypeId>,
+    target_stack: Vec<TypeId>,
+}
+
+impl<'a> Relater<'a> {
+    fn new(
+        checker: &'a mut Checker,
+        relation: Relation,
+        head_message: Option<&'static Message>,
+    ) -> Self {
+        Relater {
+            checker,
+            relation,
+            head_message,
+            error_node: None,
+            error_info: None,
+            related_information: Vec::new(),
+            incompatible_stack: None,
+            last_skipped_info: None,
+            override_next_error_info: 0,
+            skip_parent_counter: 0,
+            maybe_keys: Vec::new(),
+            maybe_key_set: HashSet::new(),
+            source_stack: Vec::new(),
+            target_stack: Vec::new(),
+        }
+    }
+
+    fn capture_error_state(&self) -> ErrorState {
+        ErrorState {
+            error_info: self.error_info.clone(),
+            last_skipped_info: self.last_skipped_info,
+            incompatible_stack: self.incompatible_stack.clone(),
+            override_next_error_info: self.override_next_error_info,
+            related_information_count: self.related_information.len(),
+        }
+    }
+
+    fn reset_error_state(&mut self, state: ErrorState) {
+        self.error_info = state.error_info;
+        self.last_skipped_info = state.last_skipped_info;
+        self.incompatible_stack = state.incompatible_stack;
+        self.override_next_error_info = state.override_next_error_info;
+        self.related_information
+            .truncate(state.related_information_count);
+    }
+
+    fn type_to_string(&mut self, type_: TypeId) -> String {
+        self.checker.type_to_string(type_)
+    }
+
+    fn report_error(&mut self, message: &'static Message, args: Vec<String>) {
+        if self.incompatible_stack.is_some() {
+            self.report_incompatible_stack();
+        }
+        if self.skip_parent_counter > 0 {
+            self.skip_parent_counter -= 1;
+            return;
+        }
+        self.error_info = Some(DiagnosticMessageChain::chain(
+            self.error_info.take(),
+            message,
+            &args,
+        ));
+    }
+
+    /// Reports an error that replaces the message of the comparison that contains it
+    fn report_parent_skipped_error(&mut self, message: &'static Message, args: Vec<String>) {
+        self.report_error(message, args);
+        self.skip_parent_counter += 1;
+    }
+
+    /// Records that a property or signature was incompatible, to be reported together with
+    /// any enclosing incompatibilities
+    fn report_incompatible_error(&mut self, message: &'static Message, args: Vec<String>) {
+        self.override_next_error_info += 1;
+        self.last_skipped_info = None;
+        self.incompatible_stack
+            .get_or_insert_with(Vec::new)
+            .push((message, args));
+    }
+
+    fn report_incompatible_stack(&mut self) {
+        let stack = self.incompatible_stack.take().unwrap_or_default();
+        let info = self.last_skipped_info.take();
+        if stack.len() == 1 {
+            let (message, args) = stack.into_iter().next().unwrap();
+            self.report_error(message, args);
+        } else if !stack.is_empty() {
+            let is_property_path = stack.iter().all(|(message, _)| {
+                message.code() == diagnostics::TYPES_OF_PROPERTY_0_ARE_INCOMPATIBLE_2326.code()
+            });
+            if is_property_path {
+                // Nested incompatible properties are reported as one path
+                let path = stack
+                    .iter()
+                    .rev()
+                    .map(|(_, args)| args[0].as_str())
+                    .collect::<Vec<_>>()
+                    .join(".");
+                self.report_error(
+                    diagnostics::THE_TYPES_OF_0_ARE_INCOMPATIBLE_BETWEEN_THESE_TYPES_2200,
+                    vec![path],
+                );
+            } else {
+                for (message, args) in stack {
+                    self.report_error(message, args);
+                }
+            }
+        }
+        if let Some((source, target)) = info {
+            self.report_relation_error(None, source, target);
+        }
+    }
+
+    fn report_relation_error(
+        &mut self,
+        message: Option<&'static Message>,
+        source: TypeId,
+        target: TypeId,
+    ) {
+        if self.incompatible_stack.is_some() {
+            self.report_incompatible_stack();
+        }
+        let source_name = self.type_to_string(source);
+        let target_name = self.type_to_string(target);
+        let generalized_source = if self.checker.is_literal_type(source)
+            && !self
+                .checker
+                .type_could_have_top_level_singleton_types(target)
+        {
+            self.checker.get_base_type_of_literal_type(source)
+        } else {
+            source
+        };
+        let generalized_name = self.type_to_string(generalized_source);
+
+        let target_flags = self.checker.flags(target);
+        if target_flags.contains(TypeFlags::TYPE_PARAMETER)
+            && self
+                .checker
+                .variance_markers
+                .is_none_or(|markers| !markers.contains(&target))
+        {
+            let constraint = self.checker.get_constraint_of_type_parameter(target);
+            let mut needs_original_source = false;
+            match constraint {
+                Some(constraint)
+                    if self
+                        .checker
+                        .is_type_assignable_to(generalized_source, constraint)
+                        || {
+                            needs_original_source =
+                                self.checker.is_type_assignable_to(source, constraint);
+                            needs_original_source
+                        } =>
+                {
+                    let constraint_name = self.type_to_string(constraint);
+                    let name = if needs_original_source {
+                        source_name.clone()
+                    } else {
+                        generalized_name.clone()
+                    };
+                    self.report_error(
+                        diagnostics::D_0_IS_ASSIGNABLE_TO_THE_CONSTRAINT_OF_TYPE_1_BUT_1_COULD_BE_INSTANTIATED_WITH_A_DIFFERENT_SUBTYPE_OF_CONSTRAINT_2_5075,
+                        vec![name, target_name.clone(), constraint_name],
+                    );
+                }
+                _ => {
+                    self.error_info = None;
+                    self.report_error(
+                        diagnostics::D_0_COULD_BE_INSTANTIATED_WITH_AN_ARBITRARY_TYPE_WHICH_COULD_BE_UNRELATED_TO_1_5082,
+                        vec![target_name.clone(), generalized_name.clone()],
+                    );
+                }
+            }
+        }
+
+        let message = message.unwrap_or(match self.relation {
+            Relation::Comparable => diagnostics::TYPE_0_IS_NOT_COMPARABLE_TO_TYPE_1_2678,
+            _ if source_name == target_name => {
+                diagnostics::TYPE_0_IS_NOT_ASSIGNABLE_TO_TYPE_1_TWO_DIFFERENT_TYPES_WITH_THIS_NAME_EXIST_BUT_THEY_ARE_UNRELATED_2719
+            }
+            _ => diagnostics::TYPE_0_IS_NOT_ASSIGNABLE_TO_TYPE_1_2322,
+        });
+        self.report_error(message, vec![generalized_name, target_name]);
+    }
+
+    /// Reports that two types are unrelated, unless a more specific message about their
+    /// members already explains it
+    fn report_error_results(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        head_message: Option<&'static Message>,
+    ) {
+        let mut maybe_suppress = self.override_next_error_info > 0;
+        if maybe_suppress {
+            self.override_next_error_info -= 1;
+        }
+        if self.checker.flags(source).contains(TypeFlags::OBJECT)
+            && self.checker.flags(target).contains(TypeFlags::OBJECT)
+        {
+            let current = self.error_info.clone();
+            self.try_elaborate_array_like_errors(source, target, true);
+            if self.error_info != current {
+                maybe_suppress = self.error_info.is_some();
+            }
+        }
+        if head_message.is_none() && maybe_suppress {
+            self.last_skipped_info = Some((source, target));
+            return;
+        }
+        self.report_relation_error(head_message, source, target);
+    }
+
+    /// Reports when a readonly array or tuple is related to a mutable one, returning
+    /// whether the types are similar enough for a list of missing properties to help
+    fn try_elaborate_array_like_errors(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> bool {
+        if let Some((_, _, readonly)) = self.checker.get_tuple_elements(source) {
+            if readonly && self.checker.is_mutable_array_or_tuple(target) {
+                if report_errors {
+                    let source_name = self.type_to_string(source);
+                    let target_name = self.type_to_string(target);
+                    self.report_error(
+                        diagnostics::THE_TYPE_0_IS_READONLY_AND_CANNOT_BE_ASSIGNED_TO_THE_MUTABLE_TYPE_1_4104,
+                        vec![source_name, target_name],
+                    );
+                }
+                return false;
+            }
+            return self.checker.is_array_or_tuple_type(target);
+        }
+        if self.checker.is_readonly_array_type(source)
+            && self.checker.is_mutable_array_or_tuple(target)
+        {
+            if report_errors {
+                let source_name = self.type_to_string(source);
+                let target_name = self.type_to_string(target);
+                self.report_error(
+                    diagnostics::THE_TYPE_0_IS_READONLY_AND_CANNOT_BE_ASSIGNED_TO_THE_MUTABLE_TYPE_1_4104,
+                    vec![source_name, target_name],
+                );
+            }
+            return false;
+        }
+        if self.checker.is_tuple_type(target) {
+            return self.checker.is_array_type(source);
+        }
+        true
+    }
+
+    fn is_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+        head_message: Option<&'static Message>,
+    ) -> Ternary {
+        let source = self.checker.get_regular_type_of_fresh_literal_type(source);
+        let target = self.checker.get_regular_type_of_fresh_literal_type(target);
+        if source == target {
+            return Ternary::True;
+        }
+        let relation = self.relation;
+        let source_flags = self.checker.flags(source);
+        let target_flags = self.checker.flags(target);
+        if source_flags.contains(TypeFlags::OBJECT) && target_flags.intersects(TypeFlags::PRIMITIVE)
+        {
+            if relation == Relation::Comparable
+                && !target_flags.contains(TypeFlags::NEVER)
+                && self
+                    .checker
+                    .is_simple_type_related_to(target, source, relation)
+                || self
+                    .checker
+                    .is_simple_type_related_to(source, target, relation)
+            {
+                return Ternary::True;
+            }
+            if report_errors {
+                self.report_error_results(source, target, head_message);
+            }
+            return Ternary::False;
+        }
+        if relation == Relation::Identity {
+            if source_flags != target_flags {
+                return Ternary::False;
+            }
+            if !source_flags.intersects(TypeFlags::STRUCTURED_OR_INSTANTIABLE) {
+                // Distinct intrinsic types with the same flags are `any` and its error type
+                return if source_flags.contains(TypeFlags::ANY) {
+                    Ternary::True
+                } else {
+                    Ternary::False
+                };
+            }
+            return self.recursive_type_related_to(source, target, false);
+        }
+        if source_flags.contains(TypeFlags::TYPE_PARAMETER)
+            && self.checker.get_constraint_of_type_parameter(source) == Some(target)
+        {
+            return Ternary::True;
+        }
+        if relation == Relation::Comparable
+            && !target_flags.contains(TypeFlags::NEVER)
+            && self
+                .checker
+                .is_simple_type_related_to(target, source, relation)
+            || self
+                .checker
+                .is_simple_type_related_to(source, target, relation)
+        {
+            return Ternary::True;
+        }
+        if (source_flags | target_flags).intersects(TypeFlags::STRUCTURED_OR_INSTANTIABLE) {
+            let is_fresh_object_literal = self
+                .checker
+                .object_flags(source)
+                .contains(ObjectFlags::FRESH_LITERAL);
+            if is_fresh_object_literal && self.has_excess_properties(source, target, report_errors)
+            {
+                if report_errors {
+                    self.report_relation_error(head_message, source, target);
+                }
+                return Ternary::False;
+            }
+            if self.is_weak_type_violation(source, target) {
+                if report_errors {
+                    let source_name = self.type_to_string(source);
+                    let target_name = self.type_to_string(target);
+                    let has_call_signatures = !self
+                        .checker
+                        .get_signatures_of_type(source, false)
+                        .is_empty()
+                        || !self.checker.get_signatures_of_type(source, true).is_empty();
+                    let message = if has_call_signatures {
+                        diagnostics::VALUE_OF_TYPE_0_HAS_NO_PROPERTIES_IN_COMMON_WITH_TYPE_1_DID_YOU_MEAN_TO_CALL_IT_2560
+                    } else {
+                        diagnostics::TYPE_0_HAS_NO_PROPERTIES_IN_COMMON_WITH_TYPE_1_2559
+                    };
+                    self.report_error(message, vec![source_name, target_name]);
+                }
+                return Ternary::False;
+            }
+            let source = if is_fresh_object_literal {
+                self.checker.get_regular_type_of_object_literal(source)
+            } else {
+                source
+            };
+            let result = self.recursive_type_related_to(source, target, report_errors);
+            if result != Ternary::False {
+                return result;
+            }
+        }
+        if report_errors {
+            self.report_error_results(source, target, head_message);
+        }
+        Ternary::False
+    }
+
+    /// Determines if a source with no properties in common with a target whose properties
+    /// are all optional is being related to it, which is almost always a mistake
+    fn is_weak_type_violation(&mut self, source: TypeId, target: TypeId) -> bool {
+        if !matches!(self.relation, Relation::Assignable | Relation::Comparable)
+            || !self
+                .checker
+                .flags(source)
+                .intersects(TypeFlags::OBJECT | TypeFlags::INTERSECTION)
+            || !self.checker.is_weak_type(target)
+        {
+            return false;
+        }
+        let properties = self.checker.get_properties_of_type(source);
+        if properties.is_empty()
+            && self
+                .checker
+                .get_signatures_of_type(source, false)
+                .is_empty()
+            && self.checker.get_signatures_of_type(source, true).is_empty()
+        {
+            return false;
+        }
+        !properties.into_iter().any(|property| {
+            let name = self.checker.symbol(property).name.clone();
+            self.checker.is_known_property(target, &name)
+        })
+    }
+
+    /// Determines if a fresh object literal has properties its target does not declare,
+    /// which are likely misspellings
+    fn has_excess_properties(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> bool {
+        if !self.checker.is_excess_property_check_target(target)
+            || matches!(self.relation, Relation::Assignable | Relation::Comparable)
+                && self.checker.is_empty_object_type(target)
+        {
+            return false;
+        }
+        let target_flags = self.checker.flags(target);
+        let check_types = target_flags.contains(TypeFlags::UNION).then(|| {
+            self.checker
+                .get_constituents(target)
+                .into_iter()
+                .filter(|&type_| {
+                    self.checker
+                        .flags(type_)
+                        .intersects(TypeFlags::OBJECT | TypeFlags::INTERSECTION)
+                })
+                .collect::<Vec<_>>()
+        });
+        let literal = self
+            .checker
+            .get_type(source)
+            .symbol
+            .and_then(|symbol| self.checker.symbol(symbol).declarations.first().cloned());
+        for property in self.checker.get_properties_of_type(source) {
+            // Properties spread into the literal are not checked
+            let declaration = self.checker.symbol(property).value_declaration.clone();
+            let is_own_property = match (&declaration, &literal) {
+                (Some(declaration), Some(literal)) => declaration
+                    .parent()
+                    .is_some_and(|parent| Arc::ptr_eq(&parent, literal)),
+                _ => false,
+            };
+            if !is_own_property {
+                continue;
+            }
+            let name = self.checker.symbol(property).name.clone();
+            if !self.checker.is_known_property(target, &name) {
+                if report_errors {
+                    if let Some(name_node) = declaration
+                        .as_ref()
+                        .and_then(|declaration| declaration.name().cloned())
+                    {
+                        self.error_node = Some(name_node);
+                    }
+                    let target_name = self.type_to_string(target);
+                    self.report_parent_skipped_error(
+                        diagnostics::OBJECT_LITERAL_MAY_ONLY_SPECIFY_KNOWN_PROPERTIES_AND_0_DOES_NOT_EXIST_IN_TYPE_1_2353,
+                        vec![name, target_name],
+                    );
+                }
+                return true;
+            }
+            if let Some(check_types) = &check_types {
+                let mut property_types = Vec::new();
+                for &type_ in check_types {
+                    if let Some(property_type) = self
+                        .checker
+                        .get_indexed_access_type_or_undefined(type_, &name)
+                    {
+                        property_types.push(property_type);
+                    }
+                }
+                if property_types.is_empty() {
+                    continue;
+                }
+                let target_property_type = self.checker.get_union_type(&property_types);
+                let source_property_type = self.checker.get_type_of_symbol(property);
+                if self.is_related_to(
+                    source_property_type,
+                    target_property_type,
+                    report_errors,
+                    None,
+                ) == Ternary::False
+                {
+                    if report_errors {
+                        self.report_incompatible_error(
+                            diagnostics::TYPES_OF_PROPERTY_0_ARE_INCOMPATIBLE_2326,
+                            vec![name],
+                        );
+                    }
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Relates two types whose comparison may depend on itself, caching the result
+    fn recursive_type_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> Ternary {
+        let key = (self.relation, source, target);
+        if let Some(&related) = self.checker.relation_cache.get(&key) {
+            // Failures are related again when reporting, to explain them
+            if related || !report_errors {
+                return if related {
+                    Ternary::True
+                } else {
+                    Ternary::False
+                };
+            }
+        }
+        if self.maybe_key_set.contains(&(source, target)) {
+            return Ternary::Maybe;
+        }
+        if self.is_deeply_nested_type(source, true) && self.is_deeply_nested_type(target, false) {
+            return Ternary::Maybe;
+        }
+        let maybe_start = self.maybe_keys.len();
+        self.maybe_keys.push((source, target));
+        self.maybe_key_set.insert((source, target));
+        self.source_stack.push(source);
+        self.target_stack.push(target);
+        let result = self.structured_type_related_to(source, target, report_errors);
+        self.source_stack.pop();
+        self.target_stack.pop();
+        if result == Ternary::False {
+            self.checker.relation_cache.insert(key, false);
+            self.reset_maybe_stack(maybe_start, false);
+        } else if result == Ternary::True || maybe_start == 0 {
+            // The assumptions made since this comparison started held
+            self.reset_maybe_stack(maybe_start, true);
+        }
+        result
+    }
+
+    fn reset_maybe_stack(&mut self, start: usize, mark_succeeded: bool) {
+        for (source, target) in self.maybe_keys.drain(start..) {
+            self.maybe_key_set.remove(&(source, target));
+            if mark_succeeded {
+                self.checker
+                    .relation_cache
+                    .insert((self.relation, source, target), true);
+            }
+        }
+    }
+
+    /// Determines if instantiations of the same generic type are nested deeply enough in the
+    /// comparisons in progress that the comparison is likely to expand forever
+    fn is_deeply_nested_type(&self, type_: TypeId, is_source: bool) -> bool {
+        let stack = if is_source {
+            &self.source_stack
+        } else {
+            &self.target_stack
+        };
+        if stack.len() < MAX_NESTED_TYPE_DEPTH {
+            return false;
+        }
+        let identity = self.get_recursion_identity(type_);
+        stack
+            .iter()
+            .filter(|&&entry| self.get_recursion_identity(entry) == identity)
+            .count()
+            >= MAX_NESTED_TYPE_DEPTH
+    }
+
+    fn get_recursion_identity(&self, type_: TypeId) -> (Option<TypeId>, Option<SymbolId>) {
+        match &self.checker.get_type(type_).data {
+            TypeData::Reference { target, .. } => (Some(*target), None),
+            _ => match self.checker.get_type(type_).symbol {
+                Some(symbol) => (None, Some(symbol)),
+                None => (Some(type_), None),
+            },
+        }
+    }
+
+    fn structured_type_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> Ternary {
+        let relation = self.relation;
+        let source_flags = self.checker.flags(source);
+        let target_flags = self.checker.flags(target);
+        let save_error_info = self.capture_error_state();
+
+        if relation == Relation::Identity {
+            if source_flags.intersects(TypeFlags::UNION_OR_INTERSECTION) {
+                let result = self.each_type_related_to_some_type(source, target);
+                if result == Ternary::False {
+                    return result;
+                }
+                return result.min(self.each_type_related_to_some_type(target, source));
+            }
+            if source_flags.contains(TypeFlags::CONDITIONAL) {
+                return self.conditional_types_related_to(source, target, false);
+            }
+            if !source_flags.contains(TypeFlags::OBJECT) {
+                return Ternary::False;
+            }
+        } else {
+            // Primitives in unions are not explained further, since the union says it all
+            let report_nested = report_errors && !source_flags.intersects(TypeFlags::PRIMITIVE);
+            if source_flags.contains(TypeFlags::UNION) {
+                return if relation == Relation::Comparable {
+                    self.some_type_related_to_type(source, target, report_nested)
+                } else {
+                    self.each_type_related_to_type(source, target, report_nested)
+                };
+            }
+            if target_flags.contains(TypeFlags::UNION) {
+                let source = self.checker.get_regular_type_of_object_literal(source);
+                let is_deferred = source_flags.intersects(
+                    TypeFlags::INDEX | TypeFlags::INDEXED_ACCESS | TypeFlags::CONDITIONAL,
+                );
+                let result = self.type_related_to_some_type(
+                    source,
+                    target,
+                    report_nested && !is_deferred && !target_flags.intersects(TypeFlags::PRIMITIVE),
+                );
+                // A deferred type may be related to the union as a whole by its constraint, even
+                // when it fits no single constituent
+                if result != Ternary::False || !is_deferred {
+                    return result;
+                }
+            }
+            if target_flags.contains(TypeFlags::INTERSECTION) {
+                return self.type_related_to_each_type(source, target, report_errors);
+            }
+            if source_flags.contains(TypeFlags::INTERSECTION) {
+                // A constituent related to the target makes the intersection related; otherwise
+                // the members of all constituents together may be
+                let result = self.some_type_related_to_type(source, target, false);
+                if result != Ternary::False {
+                    return result;
+                }
+            }
+
+            if let TypeData::Index { type_: keyed } = self.checker.get_type(target).data {
+                // `keyof S` is related to `keyof T` if `T` is related to `S`
+                if let TypeData::Index {
+                    type_: source_keyed,
+                } = self.checker.get_type(source).data
+                {
+                    let result = self.is_related_to(keyed, source_keyed, false, None);
+                    if result != Ternary::False {
+                        return result;
+                    }
+                }
+                // The keys of the constraint of `T` are keys of `T`
+                let constraint = self.checker.get_apparent_type(keyed);
+                if constraint != keyed {
+                    let keys = self.checker.get_index_type(constraint);
+                    if self.is_related_to(source, keys, false, None) == Ternary::True {
+                        return Ternary::True;
+                    }
+                }
+            }
+            if source_flags.contains(TypeFlags::CONDITIONAL)
+                && target_flags.contains(TypeFlags::CONDITIONAL)
+            {
+                let result = self.conditional_types_related_to(source, target, report_errors);
+                if result != Ternary::False {
+                    return result;
+                }
+            }
+            if let TypeData::Conditional { root, .. } = &self.checker.get_type(target).data
+                && root.infer_type_parameters.is_empty()
+            {
+                // A type related to both branches is related whichever is chosen
+                let true_type = self.checker.get_true_type_from_conditional_type(target);
+                let false_type = self.checker.get_false_type_from_conditional_type(target);
+                let result = self.is_related_to(source, true_type, false, None);
+                if result != Ternary::False {
+                    let result = result.min(self.is_related_to(source, false_type, false, None));
+                    if result != Ternary::False {
+                        return result;
+                    }
+                }
+            }
+            if source_flags.contains(TypeFlags::CONDITIONAL) {
+                // A deferred conditional type is one of its branches
+                let constraint = self
+                    .checker
+                    .get_default_constraint_of_conditional_type(source);
+                return self.is_related_to(constraint, target, report_errors, None);
+            }
+            if source_flags.contains(TypeFlags::INDEX) {
+                let constraint = self.checker.get_keyof_constraint_type();
+                return self.is_related_to(constraint, target, report_errors, None);
+            }
+            if source_flags.contains(TypeFlags::INDEXED_ACCESS) {
+                return match self.checker.get_constraint_of_indexed_access(source) {
+                    Some(constraint) => self.is_related_to(constraint, target, report_errors, None),
+                    None => Ternary::False,
+                };
+            }
+            if source_flags.contains(TypeFlags::TYPE_PARAMETER) {
+                if relation == Relation::Comparable
+                    && target_flags.contains(TypeFlags::TYPE_PARAMETER)
+                {
+                    // A type parameter is comparable to a type parameter its constraint is
+                    // comparable to
+                    let mut constraint = self.checker.get_constraint_of_type_parameter(source);
+                    while let Some(current) = constraint {
+                        if current == target {
+                            return Ternary::True;
+                        }
+                        if !self
+                            .checker
+                            .flags(current)
+                            .contains(TypeFlags::TYPE_PARAMETER)
+                        {
+                            break;
+                        }
+                        constraint = self.checker.get_constraint_of_type_parameter(current);
+                    }
+                }
+                let constraint = self.checker.get_constraint_of_type_parameter(source);
+                return match constraint {
+                    Some(constraint)
+                        if !self.checker.flags(constraint).contains(TypeFlags::ANY) =>
+                    {
+                        let report = report_errors
+                            && !(target_flags & source_flags).contains(TypeFlags::TYPE_PARAMETER);
+                        self.is_related_to(constraint, target, report, None)
+                    }
+                    // An unconstrained type parameter is related to what `{}` is related to
+                    _ => {
+                        let empty = self.checker.empty_object_type;
+                        if target_flags.contains(TypeFlags::NON_PRIMITIVE) {
+                            Ternary::False
+                        } else {
+                            self.is_related_to(empty, target, false, None)
+                        }
+                    }
+                };
+            }
+            if target_flags.intersects(
+                TypeFlags::TYPE_PARAMETER
+                    | TypeFlags::INDEX
+                    | TypeFlags::INDEXED_ACCESS
+                    | TypeFlags::CONDITIONAL,
+            ) {
+                return Ternary::False;
+            }
+        }
+
+        let source_is_primitive = source_flags.intersects(TypeFlags::PRIMITIVE);
+        let source = if relation != Relation::Identity {
+            self.checker.get_apparent_type(source)
+        } else {
+            source
+        };
+        let source_flags = self.checker.flags(source);
+
+        let source_reference = self.checker.get_reference_target(source).filter(|_| {
+            self.checker
+                .object_flags(source)
+                .contains(ObjectFlags::REFERENCE)
+        });
+        let target_reference = self.checker.get_reference_target(target).filter(|_| {
+            self.checker
+                .object_flags(target)
+                .contains(ObjectFlags::REFERENCE)
+        });
+        if relation != Relation::Identity
+            && self.checker.is_mutable_array_or_tuple(target)
+            && (self.checker.is_readonly_array_type(source)
+                || self
+                    .checker
+                    .get_tuple_elements(source)
+                    .is_some_and(|(_, _, readonly)| readonly))
+        {
+            // Readonly arrays and tuples lack the mutating methods, even when the stand-in
+            // for `Array` has none to compare
+            return Ternary::False;
+        }
+        if let (Some((source_target, source_arguments)), Some((target_target, target_arguments))) =
+            (&source_reference, &target_reference)
+            && source_target == target_target
+            && !self.checker.is_tuple_type(source)
+        {
+            // Instantiations of the same generic type are related by their type arguments
+            if let Some(variances) = self.checker.get_variances(*source_target) {
+                return self.type_arguments_related_to(
+                    source_arguments,
+                    target_arguments,
+                    &variances,
+                    report_errors,
+                );
+            }
+        } else if self.checker.is_readonly_array_type(target)
+            && self.every_type(source, |checker, type_| {
+                checker.is_array_or_tuple_type(type_)
+            })
+            || self.checker.is_array_type(target)
+                && self.every_type(source, |checker, type_| {
+                    checker
+                        .get_tuple_elements(type_)
+                        .is_some_and(|(_, _, readonly)| !readonly)
+                })
+        {
+            // Arrays and tuples are related to readonly arrays, and mutable tuples to arrays,
+            // by their element types
+            if relation != Relation::Identity {
+                let number = self.checker.number_type;
+                let any = self.checker.any_type;
+                let source_element = self
+                    .checker
+                    .get_applicable_index_info(source, number)
+                    .map_or(any, |info| info.value_type);
+                let target_element = self
+                    .checker
+                    .get_applicable_index_info(target, number)
+                    .map_or(any, |info| info.value_type);
+                return self.is_related_to(source_element, target_element, report_errors, None);
+            }
+        }
+
+        if source_flags.intersects(TypeFlags::OBJECT | TypeFlags::INTERSECTION)
+            && self.checker.flags(target).contains(TypeFlags::OBJECT)
+        {
+            let report_structural_errors = report_errors
+                && self.error_info == save_error_info.error_info
+                && !source_is_primitive;
+            let mut result = self.properties_related_to(source, target, report_structural_errors);
+            if result != Ternary::False {
+                result = result.min(self.signatures_related_to(
+                    source,
+                    target,
+                    false,
+                    report_structural_errors,
+                ));
+            }
+            if result != Ternary::False {
+                result = result.min(self.signatures_related_to(
+                    source,
+                    target,
+                    true,
+                    report_structural_errors,
+                ));
+            }
+            if result != Ternary::False {
+                result = result.min(self.index_signatures_related_to(
+                    source,
+                    target,
+                    source_is_primitive,
+                    report_structural_errors,
+                ));
+            }
+            if result != Ternary::False {
+                self.reset_error_state(save_error_info);
+                return result;
+            }
+        }
+        Ternary::False
+    }
+
+    /// Relates instantiations of the same conditional type by their check types and branches,
+    /// provided their extends types are identical
+    fn conditional_types_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> Ternary {
+        let (
+            TypeData::Conditional {
+                root: source_root,
+                check_type: source_check,
+                extends_type: source_extends,
+                ..
+            },
+            TypeData::Conditional {
+                root: target_root,
+                check_type: target_check,
+                extends_type: target_extends,
+                ..
+            },
+        ) = (
+            self.checker.get_type(source).data.clone(),
+            self.checker.get_type(target).data.clone(),
+        )
+        else {
+            return Ternary::False;
+        };
+        if !Arc::ptr_eq(&source_root, &target_root)
+            || !self
+                .checker
+                .is_type_identical_to(source_extends, target_extends)
+        {
+            return Ternary::False;
+        }
+        let mut result = self.is_related_to(source_check, target_check, false, None);
+        if result == Ternary::False && self.relation != Relation::Identity {
+            result = self.is_related_to(target_check, source_check, false, None);
+        }
+        if result == Ternary::False {
+            return result;
+        }
+        let source_true = self.checker.get_true_type_from_conditional_type(source);
+        let target_true = self.checker.get_true_type_from_conditional_type(target);
+        result = result.min(self.is_related_to(source_true, target_true, report_errors, None));
+        if result == Ternary::False {
+            return result;
+        }
+        let source_false = self.checker.get_false_type_from_conditional_type(source);
+        let target_false = self.checker.get_false_type_from_conditional_type(target);
+        result.min(self.is_related_to(source_false, target_false, report_errors, None))
+    }
+
+    fn every_type(
+        &mut self,
+        type_: TypeId,
+        predicate: impl Fn(&mut Checker, TypeId) -> bool,
+    ) -> bool {
+        self.checker
+            .get_constituents(type_)
+            .into_iter()
+            .all(|constituent| predicate(self.checker, constituent))
+    }
+
+    fn each_type_related_to_some_type(&mut self, source: TypeId, target: TypeId) -> Ternary {
+        let mut result = Ternary::True;
+        for source_type in self.checker.get_constituents(source) {
+            let related = self.type_related_to_some_type(source_type, target, false);
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+
+    fn type_related_to_some_type(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> Ternary {
+        let target_types = self.checker.get_constituents(target);
+        if target_types.contains(&source) {
+            return Ternary::True;
+        }
+        // The constituent a large union's key property picks out is the likeliest match
+        let matching = self
+            .checker
+            .get_matching_union_constituent_for_type(target, source);
+        if let Some(matching) = matching {
+            let related = self.is_related_to(source, matching, false, None);
+            if related != Ternary::False {
+                return related;
+            }
+        }
+        for &target_type in &target_types {
+            let related = self.is_related_to(source, target_type, false, None);
+            if related != Ternary::False {
+                return related;
+            }
+        }
+        if report_errors
+            && let Some(best_match) = self.checker.get_best_matching_type(source, target)
+        {
+            self.is_related_to(source, best_match, true, None);
+        }
+        Ternary::False
+    }
+
+    fn type_related_to_each_type(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> Ternary {
+        let mut result = Ternary::True;
+        for target_type in self.checker.get_constituents(target) {
+            let related = self.is_related_to(source, target_type, report_errors, None);
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+
+    fn some_type_related_to_type(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> Ternary {
+        let source_types = self.checker.get_constituents(source);
+        let count = source_types.len();
+        for (index, source_type) in source_types.into_iter().enumerate() {
+            let related = self.is_related_to(
+                source_type,
+                target,
+                report_errors && index + 1 == count,
+                None,
+            );
+            if related != Ternary::False {
+                return related;
+            }
+        }
+        Ternary::False
+    }
+
+    fn each_type_related_to_type(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> Ternary {
+        let mut result = Ternary::True;
+        for source_type in self.checker.get_constituents(source) {
+            let related = self.is_related_to(source_type, target, report_errors, None);
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+
+    fn type_arguments_related_to(
+        &mut self,
+        sources: &[TypeId],
+        targets: &[TypeId],
+        variances: &[Variance],
+        report_errors: bool,
+    ) -> Ternary {
+        let mut result = Ternary::True;
+        for (index, (&source, &target)) in sources.iter().zip(targets).enumerate() {
+            let variance = variances.get(index).copied().unwrap_or(Variance::Covariant);
+            let related = match variance {
+                Variance::Independent => continue,
+                Variance::Covariant => self.is_related_to(source, target, report_errors, None),
+                Variance::Contravariant => self.is_related_to(target, source, report_errors, None),
+                Variance::Bivariant => {
+                    // The reverse is tried first, since covariance is the more common
+                    // explanation to report
+                    match self.is_related_to(target, source, false, None) {
+                        Ternary::False => self.is_related_to(source, target, report_errors, None),
+                        related => related,
+                    }
+                }
+                Variance::Invariant => {
+                    match self.is_related_to(source, target, report_errors, None) {
+                        Ternary::False => Ternary::False,
+                        related => {
+                            related.min(self.is_related_to(target, source, report_errors, None))
+                        }
+                    }
+                }
+            };
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+
+    fn properties_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> Ternary {
+        if self.relation == Relation::Identity {
+            return self.properties_identical_to(source, target);
+        }
+        if self.checker.is_tuple_type(target) {
+            if self.checker.is_array_or_tuple_type(source) {
+                return self.tuple_elements_related_to(source, target, report_errors);
+            }
+            let (_, element_flags, _) = self.checker.get_tuple_elements(target).unwrap();
+            if element_flags
+                .iter()
+                .any(|flags| flags.intersects(ElementFlags::VARIABLE))
+            {
+                return Ternary::False;
+            }
+        }
+        let require_optional_properties = self.relation == Relation::Subtype
+            && !self
+                .checker
+                .object_flags(source)
+                .contains(ObjectFlags::OBJECT_LITERAL)
+            && !self.checker.is_tuple_type(source);
+        let unmatched = self.get_unmatched_properties(source, target, require_optional_properties);
+        if !unmatched.is_empty() {
+            if report_errors && self.should_report_unmatched_property_error(source, target) {
+                self.report_unmatched_properties(source, target, &unmatched);
+            }
+            return Ternary::False;
+        }
+
+        let mut result = Ternary::True;
+        let numeric_names_only =
+            self.checker.is_tuple_type(source) && self.checker.is_tuple_type(target);
+        for target_property in self.checker.get_properties_of_type(target) {
+            let target_symbol = self.checker.symbol(target_property);
+            let name = target_symbol.name.clone();
+            if target_symbol.flags.contains(SymbolFlags::PROTOTYPE)
+                || numeric_names_only && !is_numeric_literal_name(&name) && name != "length"
+            {
+                continue;
+            }
+            let Some(source_property) = self.checker.get_property_of_type(source, &name) else {
+                continue;
+            };
+            if source_property == target_property {
+                continue;
+            }
+            let related = self.property_related_to(
+                source,
+                target,
+                source_property,
+                target_property,
+                report_errors,
+            );
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+
+    /// Gets the properties of the target that the source lacks, ignoring optional ones
+    /// unless `require_optional_properties` is set
+    fn get_unmatched_properties(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        require_optional_properties: bool,
+    ) -> Vec<SymbolId> {
+        if self.checker.is_permissive_type(source) {
+            // Missing members of undeclared global types are assumed to exist
+            return Vec::new();
+        }
+        let mut unmatched = Vec::new();
+        for target_property in self.checker.get_properties_of_type(target) {
+            let symbol = self.checker.symbol(target_property);
+            if symbol.flags.contains(SymbolFlags::PROTOTYPE)
+                || !require_optional_properties && symbol.flags.contains(SymbolFlags::OPTIONAL)
+            {
+                continue;
+            }
+            let name = symbol.name.clone();
+            if self.checker.get_property_of_type(source, &name).is_none() {
+                unmatched.push(target_property);
+            }
+        }
+        unmatched
+    }
+
+    /// Determines if missing properties explain the failure better than the signatures of
+    /// a source that has nothing but signatures
+    fn should_report_unmatched_property_error(&mut self, source: TypeId, target: TypeId) -> bool {
+        let source_calls = self.checker.get_signatures_of_type(source, false);
+        let source_constructs = self.checker.get_signatures_of_type(source, true);
+        if (!source_calls.is_empty() || !source_constructs.is_empty())
+            && self.checker.get_properties_of_type(source).is_empty()
+        {
+            return !source_calls.is_empty()
+                && !self
+                    .checker
+                    .get_signatures_of_type(target, false)
+                    .is_empty()
+                || !source_constructs.is_empty()
+                    && !self.checker.get_signatures_of_type(target, true).is_empty();
+        }
+        true
+    }
+
+    fn report_unmatched_properties(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        unmatched: &[SymbolId],
+    ) {
+        // Classes that fail to implement an interface list what they lack in every case
+        let should_skip_elaboration = self.head_message.is_none_or(|message| {
+            message.code() != diagnostics::CLASS_0_INCORRECTLY_IMPLEMENTS_INTERFACE_1_2420.code()
+                && message.code()
+                    != diagnostics::CLASS_0_INCORRECTLY_IMPLEMENTS_CLASS_1_DID_YOU_MEAN_TO_EXTEND_1_AND_INHERIT_ITS_MEMBERS_AS_A_SUBCLASS_2720.code()
+        });
+        let source_name = self.type_to_string(source);
+        let target_name = self.type_to_string(target);
+        if let [property] = unmatched {
+            let name = symbol_name(self.checker.symbol(*property)).into_owned();
+            self.report_error(
+                diagnostics::PROPERTY_0_IS_MISSING_IN_TYPE_1_BUT_REQUIRED_IN_TYPE_2_2741,
+                vec![name.clone(), source_name, target_name],
+            );
+            if let Some(declaration) = self.checker.symbol(*property).declarations.first().cloned()
+            {
+                let related = self.checker.create_diagnostic_for_node(
+                    &declaration,
+                    diagnostics::D_0_IS_DECLARED_HERE_2728,
+                    &[name],
+                );
+                self.related_information.push(related);
+            }
+        } else if self.try_elaborate_array_like_errors(source, target, false) {
+            let names: Vec<String> = unmatched
+                .iter()
+                .map(|&property| symbol_name(self.checker.symbol(property)).into_owned())
+                .collect();
+            if names.len() > 5 {
+                self.report_error(
+                    diagnostics::TYPE_0_IS_MISSING_THE_FOLLOWING_PROPERTIES_FROM_TYPE_1_COLON_2_AND_3_MORE_2740,
+                    vec![
+                        source_name,
+                        target_name,
+                        names[..4].join(", "),
+                        (names.len() - 4).to_string(),
+                    ],
+                );
+            } else {
+                self.report_error(
+                    diagnostics::TYPE_0_IS_MISSING_THE_FOLLOWING_PROPERTIES_FROM_TYPE_1_COLON_2_2739,
+                    vec![source_name, target_name, names.join(", ")],
+                );
+            }
+        } else {
+            return;
+        }
+        if should_skip_elaboration && self.error_info.is_some() {
+            self.override_next_error_info += 1;
+        }
+    }
+
+    fn property_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        source_property: SymbolId,
+        target_property: SymbolId,
+        report_errors: bool,
+    ) -> Ternary {
+        let name = symbol_name(self.checker.symbol(target_property)).into_owned();
+        let source_private = self.checker.is_private_property(source_property);
+        let target_private = self.checker.is_private_property(target_property);
+        if source_private || target_private {
+            let source_declaration = self
+                .checker
+                .symbol(source_property)
+                .value_declaration
+                .clone();
+            let target_declaration = self
+                .checker
+                .symbol(target_property)
+                .value_declaration
+                .clone();
+            let same_declaration = match (&source_declaration, &target_declaration) {
+                (Some(source), Some(target)) => Arc::ptr_eq(source, target),
+                _ => false,
+            };
+            if !same_declaration {
+                if report_errors {
+                    if source_private && target_private {
+                        self.report_error(
+                            diagnostics::TYPES_HAVE_SEPARATE_DECLARATIONS_OF_A_PRIVATE_PROPERTY_0_2442,
+                            vec![name],
+                        );
+                    } else {
+                        let (private_type, other_type) = if source_private {
+                            (source, target)
+                        } else {
+                            (target, source)
+                        };
+                        let private_name = self.type_to_string(private_type);
+                        let other_name = self.type_to_string(other_type);
+                        self.report_error(
+                            diagnostics::PROPERTY_0_IS_PRIVATE_IN_TYPE_1_BUT_NOT_IN_TYPE_2_2325,
+                            vec![name, private_name, other_name],
+                        );
+                    }
+                }
+                return Ternary::False;
+            }
+        }
+
+        let mut source_type = self.checker.get_type_of_symbol(source_property);
+        let mut target_type = self.checker.get_type_of_symbol(target_property);
+        // Only a missing source property fits where the target property may be missing
+        let target_optional = self
+            .checker
+            .symbol(target_property)
+            .flags
+            .contains(SymbolFlags::OPTIONAL);
+        target_type = self.checker.remove_missing_type(target_type);
+        if target_optional {
+            source_type = self.checker.remove_missing_type(source_type);
+        }
+        let related = self.is_related_to(source_type, target_type, report_errors, None);
+        if related == Ternary::False {
+            if report_errors {
+                self.report_incompatible_error(
+                    diagnostics::TYPES_OF_PROPERTY_0_ARE_INCOMPATIBLE_2326,
+                    vec![name],
+                );
+            }
+            return Ternary::False;
+        }
+
+        let source_optional = self
+            .checker
+            .symbol(source_property)
+            .flags
+            .contains(SymbolFlags::OPTIONAL);
+        let target_flags = self.checker.symbol(target_property).flags;
+        if self.relation != Relation::Comparable
+            && source_optional
+            && target_flags.intersects(SymbolFlags::CLASS_MEMBER)
+            && !target_flags.contains(SymbolFlags::OPTIONAL)
+        {
+            if report_errors {
+                let source_name = self.type_to_string(source);
+                let target_name = self.type_to_string(target);
+                self.report_error(
+                    diagnostics::PROPERTY_0_IS_OPTIONAL_IN_TYPE_1_BUT_REQUIRED_IN_TYPE_2_2327,
+                    vec![name, source_name, target_name],
+                );
+            }
+            return Ternary::False;
+        }
+        related
+    }
+
+    /// Relates the elements of an array or tuple source to those of a tuple target
+    fn tuple_elements_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        report_errors: bool,
+    ) -> Ternary {
+        let (target_types, target_flags, target_readonly) =
+            self.checker.get_tuple_elements(target).unwrap();
+        let (source_types, source_flags, source_readonly) =
+            match self.checker.get_tuple_elements(source) {
+                Some(elements) => elements,
+                None => {
+                    let element = self
+                        .checker
+                        .get_reference_target(source)
+                        .map_or(self.checker.any_type, |(_, arguments)| arguments[0]);
+                    let readonly = self.checker.is_readonly_array_type(source);
+                    (vec![element], vec![ElementFlags::REST], readonly)
+                }
+            };
+        if source_readonly && !target_readonly {
+            return Ternary::False;
+        }
+        let source_arity = source_types.len();
+        let target_arity = target_types.len();
+        let source_has_rest = source_flags
+            .iter()
+            .any(|flags| flags.intersects(ElementFlags::VARIABLE));
+        let target_has_rest = target_flags
+            .iter()
+            .any(|flags| flags.intersects(ElementFlags::VARIABLE));
+        let required_count = |flags: &[ElementFlags]| {
+            flags
+                .iter()
+                .filter(|flags| flags.contains(ElementFlags::REQUIRED))
+                .count()
+        };
+        let source_min_length = required_count(&source_flags);
+        let target_min_length = required_count(&target_flags);
+
+        if !source_has_rest && source_arity < target_min_length {
+            if report_errors {
+                self.report_error(
+                    diagnostics::SOURCE_HAS_0_ELEMENT_S_BUT_TARGET_REQUIRES_1_2618,
+                    vec![source_arity.to_string(), target_min_length.to_string()],
+                );
+            }
+            return Ternary::False;
+        }
+        if !target_has_rest && target_arity < source_min_length {
+            if report_errors {
+                self.report_error(
+                    diagnostics::SOURCE_HAS_0_ELEMENT_S_BUT_TARGET_ALLOWS_ONLY_1_2619,
+                    vec![source_min_length.to_string(), target_arity.to_string()],
+                );
+            }
+            return Ternary::False;
+        }
+        if !target_has_rest && (source_has_rest || target_arity < source_arity) {
+            if report_errors {
+                if source_min_length < target_min_length {
+                    self.report_error(
+                        diagnostics::TARGET_REQUIRES_0_ELEMENT_S_BUT_SOURCE_MAY_HAVE_FEWER_2620,
+                        vec![target_min_length.to_string()],
+                    );
+                } else {
+                    self.report_error(
+                        diagnostics::TARGET_ALLOWS_ONLY_0_ELEMENT_S_BUT_SOURCE_MAY_HAVE_MORE_2621,
+                        vec![target_arity.to_string()],
+                    );
+                }
+            }
+            return Ternary::False;
+        }
+
+        // Elements before the target's rest element match by position from the start, and
+        // elements after it by position from the end
+        let target_start = target_flags
+            .iter()
+            .position(|flags| flags.intersects(ElementFlags::VARIABLE))
+            .unwrap_or(target_arity);
+        let target_end = target_flags
+            .iter()
+            .rposition(|flags| flags.intersects(ElementFlags::VARIABLE))
+            .map_or(0, |rest| target_arity - rest - 1);
+        let mut result = Ternary::True;
+        for source_position in 0..source_arity {
+            let source_position_from_end = source_arity - 1 - source_position;
+            let target_position = if source_position < target_start {
+                source_position
+            } else if source_position_from_end < target_end {
+                target_arity - 1 - source_position_from_end
+            } else {
+                target_start
+            };
+            let element_flags = source_flags[source_position];
+            if target_flags[target_position].contains(ElementFlags::REQUIRED)
+                && !element_flags.contains(ElementFlags::REQUIRED)
+            {
+                if report_errors {
+                    self.report_error(
+                        diagnostics::SOURCE_PROVIDES_NO_MATCH_FOR_REQUIRED_ELEMENT_AT_POSITION_0_IN_TARGET_2623,
+                        vec![target_position.to_string()],
+                    );
+                }
+                return Ternary::False;
+            }
+            let related = self.is_related_to(
+                source_types[source_position],
+                target_types[target_position],
+                report_errors,
+                None,
+            );
+            if related == Ternary::False {
+                if report_errors && (source_arity > 1 || target_arity > 1) {
+                    if target_has_rest
+                        && source_position >= target_start
+                        && source_position_from_end >= target_end
+                        && target_start + target_end + 1 != source_arity
+                    {
+                        self.report_incompatible_error(
+                            diagnostics::TYPE_AT_POSITIONS_0_THROUGH_1_IN_SOURCE_IS_NOT_COMPATIBLE_WITH_TYPE_AT_POSITION_2_IN_TARGET_2627,
+                            vec![
+                                target_start.to_string(),
+                                (source_arity - target_end - 1).to_string(),
+                                target_position.to_string(),
+                            ],
+                        );
+                    } else {
+                        self.report_incompatible_error(
+                            diagnostics::TYPE_AT_POSITION_0_IN_SOURCE_IS_NOT_COMPATIBLE_WITH_TYPE_AT_POSITION_1_IN_TARGET_2626,
+                            vec![source_position.to_string(), target_position.to_string()],
+                        );
+                    }
+                }
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+
+    fn properties_identical_to(&mut self, source: TypeId, target: TypeId) -> Ternary {
+        let source_properties = self.checker.get_properties_of_type(source);
+        let target_properties = self.checker.get_properties_of_type(target);
+        if source_properties.len() != target_properties.len() {
+            return Ternary::False;
+        }
+        let mut result = Ternary::True;
+        for source_property in source_properties {
+            let name = self.checker.symbol(source_property).name.clone();
+            let Some(target_property) = self.checker.get_property_of_type(target, &name) else {
+                return Ternary::False;
+            };
+            let optional = SymbolFlags::OPTIONAL;
+            if self.checker.symbol(source_property).flags & optional
+                != self.checker.symbol(target_property).flags & optional
+                || self.checker.is_private_property(source_property)
+                    != self.checker.is_private_property(target_property)
+            {
+                return Ternary::False;
+            }
+            let source_type = self.checker.get_type_of_symbol(source_property);
+            let target_type = self.checker.get_type_of_symbol(target_property);
+            let related = self.is_related_to(source_type, target_type, false, None);
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+
+    fn signatures_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        construct: bool,
+        report_errors: bool,
+    ) -> Ternary {
+        if self.relation == Relation::Identity {
+            return self.signatures_identical_to(source, target, construct);
+        }
+        let source_signatures = self.checker.get_signatures_of_type(source, construct);
+        let target_signatures = self.checker.get_signatures_of_type(target, construct);
+        if target_signatures.is_empty() {
+            return Ternary::True;
+        }
+        if construct && !source_signatures.is_empty() {
+            let is_abstract = |checker: &Checker, signatures: &[SignatureId]| {
+                signatures.iter().any(|&signature| {
+                    checker
+                        .get_signature(signature)
+                        .flags
+                        .contains(SignatureFlags::ABSTRACT)
+                })
+            };
+            if is_abstract(self.checker, &source_signatures)
+                && !is_abstract(self.checker, &target_signatures)
+            {
+                if report_errors {
+                    self.report_error(
+                        diagnostics::CANNOT_ASSIGN_AN_ABSTRACT_CONSTRUCTOR_TYPE_TO_A_NON_ABSTRACT_CONSTRUCTOR_TYPE_2517,
+                        Vec::new(),
+                    );
+                }
+                return Ternary::False;
+            }
+        }
+
+        let mut result = Ternary::True;
+        let same_declarations = source_signatures.len() == target_signatures.len()
+            && source_signatures
+                .iter()
+                .zip(&target_signatures)
+                .all(|(&s, &t)| {
+                    match (
+                        &self.checker.get_signature(s).declaration,
+                        &self.checker.get_signature(t).declaration,
+                    ) {
+                        (Some(s), Some(t)) => Arc::ptr_eq(s, t),
+                        _ => false,
+                    }
+                });
+        if same_declarations {
+            // Instantiations of the same declarations are related pairwise
+            for (&source_signature, &target_signature) in
+                source_signatures.iter().zip(&target_signatures)
+            {
+                let related = self.signature_related_to(
+                    source_signature,
+                    target_signature,
+                    true,
+                    construct,
+                    report_errors,
+                );
+                if related == Ternary::False {
+                    return Ternary::False;
+                }
+                result = result.min(related);
+            }
+        } else if let ([source_signature], [target_signature]) =
+            (&source_signatures[..], &target_signatures[..])
+        {
+            let erase = self.relation == Relation::Comparable;
+            return self.signature_related_to(
+                *source_signature,
+                *target_signature,
+                erase,
+                construct,
+                report_errors,
+            );
+        } else {
+            'targets: for &target_signature in &target_signatures {
+                let save_error_info = self.capture_error_state();
+                let mut should_elaborate_errors = report_errors;
+                for &source_signature in &source_signatures {
+                    let related = self.signature_related_to(
+                        source_signature,
+                        target_signature,
+                        true,
+                        construct,
+                        should_elaborate_errors,
+                    );
+                    if related != Ternary::False {
+                        result = result.min(related);
+                        self.reset_error_state(save_error_info);
+                        continue 'targets;
+                    }
+                    should_elaborate_errors = false;
+                }
+                if should_elaborate_errors || report_errors {
+                    let source_name = self.type_to_string(source);
+                    let signature_text = self.checker.signature_to_string(target_signature);
+                    self.report_error(
+                        diagnostics::TYPE_0_PROVIDES_NO_MATCH_FOR_THE_SIGNATURE_1_2658,
+                        vec![source_name, signature_text],
+                    );
+                }
+                return Ternary::False;
+            }
+        }
+        result
+    }
+
+    fn signature_related_to(
+        &mut self,
+        source: SignatureId,
+        target: SignatureId,
+        erase: bool,
+        construct: bool,
+        report_errors: bool,
+    ) -> Ternary {
+        let (source, target) = if erase {
+            (
+                self.checker.get_erased_signature(source),
+                self.checker.get_erased_signature(target),
+            )
+        } else {
+            (source, target)
+        };
+        self.compare_signatures_related(source, target, construct, report_errors)
+    }
+
+    fn compare_signatures_related(
+        &mut self,
+        source: SignatureId,
+        target: SignatureId,
+        construct: bool,
+        report_errors: bool,
+    ) -> Ternary {
+        if source == target {
+            return Ternary::True;
+        }
+        let target_count = self.checker.get_parameter_count(target);
+        let target_has_rest = self.checker.has_effective_rest_parameter(target);
+        let source_min_count = self.checker.get_min_argument_count(source);
+        if !target_has_rest && source_min_count > target_count {
+            if report_errors {
+                self.report_error(
+                    diagnostics::TARGET_SIGNATURE_PROVIDES_TOO_FEW_ARGUMENTS_EXPECTED_0_OR_MORE_BUT_GOT_1_2849,
+                    vec![source_min_count.to_string(), target_count.to_string()],
+                );
+            }
+            return Ternary::False;
+        }
+        // Without inference, generic sources are compared with their type parameters erased
+        let source = self.checker.get_erased_signature(source);
+
+        let source_count = self.checker.get_parameter_count(source);
+        let source_has_rest = self.checker.has_effective_rest_parameter(source);
+        let parameter_count = if source_has_rest || target_has_rest {
+            source_count.max(target_count)
+        } else {
+            source_count.min(target_count)
+        };
+        // Method and constructor parameters stay bivariant, since methods of subtypes commonly
+        // take more specific parameters
+        let strict_variance = self.checker.options.strict_function_types
+            && !self
+                .checker
+                .get_signature(target)
+                .declaration
+                .as_ref()
+                .is_some_and(|declaration| {
+                    matches!(
+                        declaration.kind,
+                        SyntaxKind::MethodDeclaration
+                            | SyntaxKind::MethodSignature
+                            | SyntaxKind::Constructor
+                    )
+                });
+        let mut result = Ternary::True;
+        for position in 0..parameter_count {
+            let (Some(source_type), Some(target_type)) = (
+                self.checker.get_type_at_position(source, position),
+                self.checker.get_type_at_position(target, position),
+            ) else {
+                continue;
+            };
+            if source_type == target_type {
+                continue;
+            }
+            let related = if strict_variance {
+                self.is_related_to(target_type, source_type, report_errors, None)
+            } else {
+                match self.is_related_to(source_type, target_type, false, None) {
+                    Ternary::False => {
+                        self.is_related_to(target_type, source_type, report_errors, None)
+                    }
+                    related => related,
+                }
+            };
+            if related == Ternary::False {
+                if report_errors {
+                    let source_name = self
+                        .checker
+                        .get_parameter_name_at_position(source, position);
+                    let target_name = self
+                        .checker
+                        .get_parameter_name_at_position(target, position);
+                    self.report_error(
+                        diagnostics::TYPES_OF_PARAMETERS_0_AND_1_ARE_INCOMPATIBLE_2328,
+                        vec![source_name, target_name],
+                    );
+                }
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+
+        let target_return = self.checker.get_return_type_of_signature(target);
+        if target_return == self.checker.void_type
+            || self.checker.flags(target_return).contains(TypeFlags::ANY)
+        {
+            return result;
+        }
+        let source_return = self.checker.get_return_type_of_signature(source);
+        let related = self.is_related_to(source_return, target_return, report_errors, None);
+        if related == Ternary::False {
+            if report_errors {
+                let no_parameters = source_count == 0 && target_count == 0;
+                let message = match (construct, no_parameters) {
+                    (false, false) => {
+                        diagnostics::CALL_SIGNATURE_RETURN_TYPES_0_AND_1_ARE_INCOMPATIBLE_2202
+                    }
+                    (false, true) => {
+                        diagnostics::CALL_SIGNATURES_WITH_NO_ARGUMENTS_HAVE_INCOMPATIBLE_RETURN_TYPES_0_AND_1_2204
+                    }
+                    (true, false) => {
+                        diagnostics::CONSTRUCT_SIGNATURE_RETURN_TYPES_0_AND_1_ARE_INCOMPATIBLE_2203
+                    }
+                    (true, true) => {
+                        diagnostics::CONSTRUCT_SIGNATURES_WITH_NO_ARGUMENTS_HAVE_INCOMPATIBLE_RETURN_TYPES_0_AND_1_2205
+                    }
+                };
+                let source_name = self.type_to_string(source_return);
+                let target_name = self.type_to_string(target_return);
+                self.report_incompatible_error(message, vec![source_name, target_name]);
+            }
+            return Ternary::False;
+        }
+        result.min(related)
+    }
+
+    fn signatures_identical_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        construct: bool,
+    ) -> Ternary {
+        let source_signatures = self.checker.get_signatures_of_type(source, construct);
+        let target_signatures = self.checker.get_signatures_of_type(target, construct);
+        if source_signatures.len() != target_signatures.len() {
+            return Ternary::False;
+        }
+        let mut result = Ternary::True;
+        for (source_signature, target_signature) in
+            source_signatures.into_iter().zip(target_signatures)
+        {
+            let related = self.compare_signatures_identical(source_signature, target_signature);
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+
+    fn compare_signatures_identical(
+        &mut self,
+        source: SignatureId,
+        target: SignatureId,
+    ) -> Ternary {
+        if source == target {
+            return Ternary::True;
+        }
+        let source = self.checker.get_erased_signature(source);
+        let target = self.checker.get_erased_signature(target);
+        let (source_data, target_data) = (
+            self.checker.get_signature(source),
+            self.checker.get_signature(target),
+        );
+        if source_data.parameters.len() != target_data.parameters.len()
+            || source_data.min_argument_count != target_data.min_argument_count
+            || source_data.has_rest_parameter() != target_data.has_rest_parameter()
+        {
+            return Ternary::False;
+        }
+        let mut result = Ternary::True;
+        for position in 0..source_data.parameters.len() {
+            let (Some(source_type), Some(target_type)) = (
+                self.checker.get_type_at_position(source, position),
+                self.checker.get_type_at_position(target, position),
+            ) else {
+                return Ternary::False;
+            };
+            let related = self.is_related_to(source_type, target_type, false, None);
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        let source_return = self.checker.get_return_type_of_signature(source);
+        let target_return = self.checker.get_return_type_of_signature(target);
+        result.min(self.is_related_to(source_return, target_return, false, None))
+    }
+
+    fn index_signatures_related_to(
+        &mut self,
+        source: TypeId,
+        target: TypeId,
+        source_is_primitive: bool,
+        report_errors: bool,
+    ) -> Ternary {
+        if self.relation == Relation::Identity {
+            return self.index_signatures_identical_to(source, target);
+        }
+        let target_infos = self.get_index_infos(target);
+        let string_type = self.checker.string_type;
+        let target_has_string_index = target_infos.iter().any(|info| info.key_type == string_type);
+        let mut result = Ternary::True;
+        for target_info in target_infos {
+            let related = if !source_is_primitive
+                && target_has_string_index
+                && self
+                    .checker
+                    .flags(target_info.value_type)
+                    .contains(TypeFlags::ANY)
+            {
+                // Any object is assignable to `{ [key: string]: any }`
+                Ternary::True
+            } else {
+                self.type_related_to_index_info(source, &target_info, report_errors)
+            };
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+
+    fn get_index_infos(&mut self, type_: TypeId) -> Vec<IndexInfo> {
+        if !self.checker.flags(type_).contains(TypeFlags::OBJECT) {
+            return Vec::new();
+        }
+        self.checker
+            .resolve_structured_type_members(type_)
+            .index_infos
+            .clone()
+    }
+
+    fn type_related_to_index_info(
+        &mut self,
+        source: TypeId,
+        target_info: &IndexInfo,
+        report_errors: bool,
+    ) -> Ternary {
+        if let Some(source_info) = self
+            .checker
+            .get_applicable_index_info(source, target_info.key_type)
+        {
+            return self.index_info_related_to(&source_info, target_info, report_errors);
+        }
+        if self.checker.is_object_type_with_inferable_index(source) {
+            // The properties of object literal and type literal types are checked against the
+            // index signature instead
+            return self.members_related_to_indexer(source, target_info, report_errors);
+        }
+        if self.checker.is_permissive_type(source) {
+            return Ternary::True;
+        }
+        if report_errors {
+            let key_name = self.type_to_string(target_info.key_type);
+            let source_name = self.type_to_string(source);
+            self.report_error(
+                diagnostics::INDEX_SIGNATURE_FOR_TYPE_0_IS_MISSING_IN_TYPE_1_2329,
+                vec![key_name, source_name],
+            );
+        }
+        Ternary::False
+    }
+
+    fn index_info_related_to(
+        &mut self,
+        source_info: &IndexInfo,
+        target_info: &IndexInfo,
+        report_errors: bool,
+    ) -> Ternary {
+        let related = self.is_related_to(
+            source_info.value_type,
+            target_info.value_type,
+            report_errors,
+            None,
+        );
+        if related == Ternary::False && report_errors {
+            if source_info.key_type == target_info.key_type {
+                let key_name = self.type_to_string(source_info.key_type);
+                self.report_error(
+                    diagnostics::D_0_INDEX_SIGNATURES_ARE_INCOMPATIBLE_2634,
+                    vec![key_name],
+                );
+            } else {
+                let source_key = self.type_to_string(source_info.key_type);
+                let target_key = self.type_to_string(target_info.key_type);
+                self.report_error(
+                    diagnostics::D_0_AND_1_INDEX_SIGNATURES_ARE_INCOMPATIBLE_2330,
+                    vec![source_key, target_key],
+                );
+            }
+        }
+        related
+    }
+
+    fn members_related_to_indexer(
+        &mut self,
+        source: TypeId,
+        target_info: &IndexInfo,
+        report_errors: bool,
+    ) -> Ternary {
+        let mut result = Ternary::True;
+        for property in self.checker.get_properties_of_type(source) {
+            let name = self.checker.symbol(property).name.clone();
+            let name_type = self.checker.get_string_literal_type(&name);
+            if !self
+                .checker
+                .is_applicable_index_type(name_type, target_info.key_type)
+            {
+                continue;
+            }
+            let property_type = self.checker.get_type_of_symbol(property);
+            let related =
+                self.is_related_to(property_type, target_info.value_type, report_errors, None);
+            if related == Ternary::False {
+                if report_errors {
+                    self.report_error(
+                        diagnostics::PROPERTY_0_IS_INCOMPATIBLE_WITH_INDEX_SIGNATURE_2530,
+                        vec![name],
+                    );
+                }
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        for source_info in self.get_index_infos(source) {
+            if self
+                .checker
+                .is_applicable_index_type(source_info.key_type, target_info.key_type)
+            {
+                let related = self.index_info_related_to(&source_info, target_info, report_errors);
+                if related == Ternary::False {
+                    return Ternary::False;
+                }
+                result = result.min(related);
+            }
+        }
+        result
+    }
+
+    fn index_signatures_identical_to(&mut self, source: TypeId, target: TypeId) -> Ternary {
+        let source_infos = self.get_index_infos(source);
+        let target_infos = self.get_index_infos(target);
+        if source_infos.len() != target_infos.len() {
+            return Ternary::False;
+        }
+        let mut result = Ternary::True;
+        for target_info in target_infos {
+            let Some(source_info) = source_infos
+                .iter()
+                .find(|info| info.key_type == target_info.key_type)
+            else {
+                return Ternary::False;
+            };
+            if source_info.is_readonly != target_info.is_readonly {
+                return Ternary::False;
+            }
+            let related =
+                self.is_related_to(source_info.value_type, target_info.value_type, false, None);
+            if related == Ternary::False {
+                return Ternary::False;
+            }
+            result = result.min(related);
+        }
+        result
+    }
+}
+
+/// Determines if a property name is the canonical text of a number, such as `0` or `1.5`,
+/// and so can be indexed by number keys
+pub(super) fn is_numeric_literal_name(name: &str) -> bool {
+    name.parse::<f64>()
+        .is_ok_and(|value| crate::compiler::ast::utilities::number_to_string(value) == name)
+}
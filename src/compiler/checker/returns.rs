@@ -0,0 +1,68 @@
+//! Checking that functions with an annotated return type return a value
+//!
+//! A function whose declared return type is neither `void`, `any`, nor `undefined` must
+//! return a value on every path through its body. One with no `return` statement at all is
+//! reported on its own, and under `strictNullChecks` so is one that can also reach the end of
+//! its body, unless its return type includes `undefined`.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::{Node, SyntaxKind};
+use crate::compiler::diagnostics;
+
+use super::accessors::has_return_statement;
+use super::contextual::is_generator;
+use super::promises::is_async_function;
+use super::{Checker, TypeFlags};
+
+impl Checker {
+    /// Reports a function whose annotated return type needs a value, but whose body can end
+    /// without returning one
+    pub(super) fn check_all_code_paths_return_or_throw(
+        &mut self,
+        node: &Arc<Node>,
+        body: &Arc<Node>,
+    ) {
+        let Some(type_node) = node.type_node() else {
+            return;
+        };
+        if body.kind != SyntaxKind::Block
+            || is_generator(node)
+            || !self.bindings.has_implicit_return(node)
+        {
+            return;
+        }
+        let mut return_type = self.get_type_from_type_node(type_node);
+        if is_async_function(node) {
+            return_type = self.get_awaited_type(return_type);
+        }
+        if self.maybe_type_of_kind(return_type, TypeFlags::VOID)
+            || self
+                .flags(return_type)
+                .intersects(TypeFlags::ANY | TypeFlags::UNDEFINED)
+        {
+            return;
+        }
+        if self.flags(return_type).contains(TypeFlags::NEVER) {
+            self.error(
+                type_node,
+                diagnostics::A_FUNCTION_RETURNING_NEVER_CANNOT_HAVE_A_REACHABLE_END_POINT_2534,
+                &[],
+            );
+        } else if !has_return_statement(body) {
+            self.error(
+                type_node,
+                diagnostics::A_FUNCTION_WHOSE_DECLARED_TYPE_IS_NEITHER_UNDEFINED_VOID_NOR_ANY_MUST_RETURN_A_VALUE_2355,
+                &[],
+            );
+        } else if self.options.strict_null_checks
+            && !self.is_type_assignable_to(self.undefined_type, return_type)
+        {
+            self.error(
+                type_node,
+                diagnostics::FUNCTION_LACKS_ENDING_RETURN_STATEMENT_AND_RETURN_TYPE_DOES_NOT_INCLUDE_UNDEFINED_2366,
+                &[],
+            );
+        }
+    }
+}
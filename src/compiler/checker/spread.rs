@@ -0,0 +1,289 @@
+//! Spread and rest types
+//!
+//! Spreading an object into an object literal merges its properties into the literal, and
+//! spreading a tuple into an array literal or the arguments of a call supplies its elements
+//! one by one. A rest parameter of a tuple type stands for a parameter per element, so the
+//! signature's arity depends on the tuple.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::is_class_like;
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics;
+
+use super::assertions::is_const_context;
+use super::{Checker, ElementFlags, ResolvedMembers, SignatureId, TypeFlags, TypeId};
+
+impl Checker {
+    /// Merges the properties of a spread into the members of an object literal
+    ///
+    /// A required property of the spread overwrites the property of the same name before
+    /// it, while an optional one may leave it in place, so the two types are combined.
+    pub(super) fn add_spread_members(&mut self, members: &mut ResolvedMembers, spread: TypeId) {
+        let spread = self.get_non_nullable_type(spread);
+        if !self
+            .flags(spread)
+            .intersects(TypeFlags::OBJECT | TypeFlags::INTERSECTION)
+        {
+            return;
+        }
+        for property in self.get_properties_of_type(spread) {
+            if !self.is_spreadable_property(property) {
+                continue;
+            }
+            let name = self.symbol(property).name.clone();
+            let flags = self.symbol(property).flags;
+            let existing = members.members.get(&name).copied();
+            if !flags.contains(SymbolFlags::OPTIONAL) {
+                if let Some(existing) = existing {
+                    self.report_overwritten_property(existing);
+                }
+                let spread_property = if self.is_readonly_property(property) {
+                    let type_ = self.get_type_of_symbol(property);
+                    self.create_transient_property(&name, type_)
+                } else {
+                    property
+                };
+                members.add_property(&name, spread_property);
+                continue;
+            }
+            let type_ = self.get_type_of_symbol(property);
+            let type_ = match existing {
+                Some(existing) => {
+                    let existing_type = self.get_type_of_symbol(existing);
+                    let type_ = self.filter_type(type_, |checker, type_| {
+                        type_ != checker.undefined_type && type_ != checker.missing_type
+                    });
+                    self.get_union_type(&[existing_type, type_])
+                }
+                None => type_,
+            };
+            let merged = self.create_transient_property(&name, type_);
+            let still_optional = existing
+                .is_none_or(|existing| self.symbol(existing).flags.contains(SymbolFlags::OPTIONAL));
+            if still_optional {
+                self.bindings.symbols[merged].flags |= SymbolFlags::OPTIONAL;
+            }
+            members.add_property(&name, merged);
+        }
+        let index_infos = self
+            .resolve_structured_type_members(spread)
+            .index_infos
+            .clone();
+        for info in index_infos {
+            if !members
+                .index_infos
+                .iter()
+                .any(|existing| existing.key_type == info.key_type)
+            {
+                members.index_infos.push(info);
+            }
+        }
+    }
+
+    /// Determines if a property is copied by a spread: methods and accessors of classes
+    /// live on their prototypes, and private names are not properties at all
+    fn is_spreadable_property(&self, property: SymbolId) -> bool {
+        let symbol = self.symbol(property);
+        if symbol.name.starts_with('#') {
+            return false;
+        }
+        !symbol
+            .flags
+            .intersects(SymbolFlags::METHOD | SymbolFlags::ACCESSOR)
+            || !symbol.declarations.iter().any(|declaration| {
+                declaration
+                    .parent()
+                    .is_some_and(|parent| is_class_like(&parent))
+            })
+    }
+
+    /// Reports a property of an object literal that a spread after it always overwrites
+    fn report_overwritten_property(&mut self, property: SymbolId) {
+        if !self.options.strict_null_checks {
+            return;
+        }
+        let Some(declaration) = self.symbol(property).value_declaration.clone() else {
+            return;
+        };
+        if !matches!(
+            declaration.kind,
+            SyntaxKind::PropertyAssignment | SyntaxKind::ShorthandPropertyAssignment
+        ) {
+            return;
+        }
+        let name = self.symbol(property).name.clone();
+        let error_node = declaration.name().cloned().unwrap_or(declaration);
+        self.error(
+            &error_node,
+            diagnostics::D_0_IS_SPECIFIED_MORE_THAN_ONCE_SO_THIS_USAGE_WILL_BE_OVERWRITTEN_2783,
+            &[name],
+        );
+    }
+
+    /// Gets the element types and flags of the elements of an array literal, where spreads
+    /// of tuples supply their elements and spreads of arrays a rest element
+    pub(super) fn get_array_literal_elements(
+        &mut self,
+        elements: &[Arc<Node>],
+    ) -> (Vec<TypeId>, Vec<ElementFlags>) {
+        let mut element_types = Vec::new();
+        let mut element_flags = Vec::new();
+        for element in elements {
+            if element.kind == SyntaxKind::SpreadElement {
+                let element_type = self.check_expression(element);
+                let spread = self.check_expression(&element.as_spread_element().expression);
+                match self.get_tuple_elements(spread) {
+                    Some((types, flags, _)) => {
+                        element_types.extend(types);
+                        element_flags.extend(flags);
+                    }
+                    None => {
+                        element_types.push(element_type);
+                        element_flags.push(ElementFlags::REST);
+                    }
+                }
+                continue;
+            }
+            let type_ = self.check_expression(element);
+            if is_const_context(element) {
+                element_types.push(self.get_regular_type_of_literal_type(type_));
+                element_flags.push(ElementFlags::REQUIRED);
+                continue;
+            }
+            let contextual_type = self.get_contextual_type(element);
+            element_types.push(
+                self.get_widened_literal_like_type_for_contextual_type(type_, contextual_type),
+            );
+            element_flags.push(ElementFlags::REQUIRED);
+        }
+        // Everything after the first rest element joins it, since its length is unknown
+        if let Some(rest) = element_flags
+            .iter()
+            .position(|flags| flags.intersects(ElementFlags::VARIABLE))
+            && rest + 1 < element_flags.len()
+        {
+            let rest_type = self.get_union_type(&element_types[rest..]);
+            element_types.truncate(rest);
+            element_types.push(rest_type);
+            element_flags.truncate(rest);
+            element_flags.push(ElementFlags::REST);
+        }
+        (element_types, element_flags)
+    }
+
+    /// Gets the elements of the tuple type of a rest parameter, if it has one
+    fn get_rest_tuple_elements(
+        &mut self,
+        signature: SignatureId,
+    ) -> Option<(Vec<TypeId>, Vec<ElementFlags>)> {
+        let signature = self.get_signature(signature);
+        if !signature.has_rest_parameter() {
+            return None;
+        }
+        let rest = *signature.parameters.last()?;
+        let rest_type = self.get_type_of_symbol(rest);
+        self.get_tuple_elements(rest_type)
+            .map(|(element_types, element_flags, _)| (element_types, element_flags))
+    }
+
+    /// Gets the number of parameters of a signature, counting each element of a rest
+    /// parameter of a tuple type
+    pub(super) fn get_parameter_count(&mut self, signature: SignatureId) -> usize {
+        let count = self.get_signature(signature).parameters.len();
+        match self.get_rest_tuple_elements(signature) {
+            Some((element_types, _)) => count - 1 + element_types.len(),
+            None => count,
+        }
+    }
+
+    /// Gets the number of arguments a call must pass, including the required elements of a
+    /// rest parameter of a tuple type
+    pub(super) fn get_min_argument_count(&mut self, signature: SignatureId) -> usize {
+        let min_argument_count = self.get_signature(signature).min_argument_count;
+        match self.get_rest_tuple_elements(signature) {
+            Some((_, element_flags)) => {
+                let count = self.get_signature(signature).parameters.len();
+                let required = element_flags
+                    .iter()
+                    .take_while(|flags| flags.contains(ElementFlags::REQUIRED))
+                    .count();
+                min_argument_count.max(count - 1 + required)
+            }
+            None => min_argument_count,
+        }
+    }
+
+    /// Determines if a signature accepts any number of arguments: it has a rest parameter,
+    /// and the rest parameter is not a tuple of a fixed length
+    pub(super) fn has_effective_rest_parameter(&mut self, signature: SignatureId) -> bool {
+        if !self.get_signature(signature).has_rest_parameter() {
+            return false;
+        }
+        match self.get_rest_tuple_elements(signature) {
+            Some((_, element_flags)) => element_flags
+                .iter()
+                .any(|flags| flags.intersects(ElementFlags::VARIABLE)),
+            None => true,
+        }
+    }
+
+    /// Gets the number of arguments a call passes, with a spread of a tuple counting as its
+    /// elements, and the first spread that passes an unknown number of them: the position
+    /// it starts at, and its index in the argument list
+    pub(super) fn get_effective_argument_count(
+        &mut self,
+        arguments: &[Arc<Node>],
+    ) -> (usize, Option<(usize, usize)>) {
+        let mut count = 0;
+        let mut spread = None;
+        for (index, argument) in arguments.iter().enumerate() {
+            if argument.kind != SyntaxKind::SpreadElement {
+                count += 1;
+                continue;
+            }
+            let spread_type = self.check_expression(&argument.as_spread_element().expression);
+            match self.get_tuple_elements(spread_type) {
+                Some((_, element_flags, _)) => {
+                    for flags in element_flags {
+                        if flags.intersects(ElementFlags::VARIABLE) {
+                            spread.get_or_insert((count, index));
+                        }
+                        count += 1;
+                    }
+                }
+                None => {
+                    spread.get_or_insert((count, index));
+                    count += 1;
+                }
+            }
+        }
+        (count, spread)
+    }
+
+    /// Finds the first element of a spread argument at `position` that is not assignable to
+    /// the parameter it is passed to, returning the element's type and the parameter's type,
+    /// and advancing `position` past the spread
+    pub(super) fn get_spread_argument_error(
+        &mut self,
+        signature: SignatureId,
+        argument: &Arc<Node>,
+        position: &mut usize,
+    ) -> Option<(TypeId, TypeId)> {
+        let spread = self.check_expression(&argument.as_spread_element().expression);
+        let element_types = match self.get_tuple_elements(spread) {
+            Some((element_types, _, _)) => element_types,
+            None => vec![self.check_expression(argument)],
+        };
+        for element_type in element_types {
+            let parameter_type = self.get_type_at_position(signature, *position);
+            *position += 1;
+            if let Some(parameter_type) = parameter_type
+                && !self.is_type_assignable_to(element_type, parameter_type)
+            {
+                return Some((element_type, parameter_type));
+            }
+        }
+        None
+    }
+}
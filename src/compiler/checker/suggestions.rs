@@ -0,0 +1,166 @@
+//! Spelling suggestions for names and properties that do not exist
+
+use std::sync::Arc;
+
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId, SymbolTable};
+
+use super::{Checker, TypeId};
+
+impl Checker {
+    /// Finds an in-scope symbol with `meaning` whose name is close to `name`, searching the
+    /// scopes enclosing `location` from the innermost outward
+    pub(super) fn get_suggested_symbol_for_nonexistent_symbol(
+        &mut self,
+        location: &Arc<Node>,
+        name: &str,
+        meaning: SymbolFlags,
+    ) -> Option<SymbolId> {
+        self.resolve_name_helper(location, name, meaning, |checker, table, name, meaning| {
+            checker
+                .lookup(table, name, meaning)
+                .or_else(|| checker.get_spelling_suggestion_for_table(name, table, meaning))
+        })
+    }
+
+    /// Finds a property of `type_` whose name is close to `name`
+    pub(super) fn get_suggested_symbol_for_nonexistent_property(
+        &mut self,
+        name: &str,
+        type_: TypeId,
+    ) -> Option<SymbolId> {
+        let properties = self.get_properties_of_type(type_);
+        self.get_spelling_suggestion_for_name(name, &properties, SymbolFlags::VALUE)
+    }
+
+    fn get_spelling_suggestion_for_table(
+        &mut self,
+        name: &str,
+        table: &SymbolTable,
+        meaning: SymbolFlags,
+    ) -> Option<SymbolId> {
+        let mut candidates: Vec<SymbolId> = table.values().copied().collect();
+        candidates.sort();
+        self.get_spelling_suggestion_for_name(name, &candidates, meaning)
+    }
+
+    /// Picks the candidate with `meaning` whose name is closest to `name`
+    fn get_spelling_suggestion_for_name(
+        &mut self,
+        name: &str,
+        candidates: &[SymbolId],
+        meaning: SymbolFlags,
+    ) -> Option<SymbolId> {
+        let mut names = Vec::with_capacity(candidates.len());
+        for &candidate in candidates {
+            let candidate = self.get_merged_symbol(candidate);
+            let symbol = self.symbol(candidate);
+            // Quoted names belong to ambient modules, which cannot be referenced by name
+            if symbol.name.starts_with('"') {
+                continue;
+            }
+            let mut has_meaning = symbol.flags.intersects(meaning);
+            if !has_meaning && symbol.flags.contains(SymbolFlags::ALIAS) {
+                let target = self.resolve_alias(candidate);
+                has_meaning =
+                    target != self.unknown_symbol && self.symbol(target).flags.intersects(meaning);
+            }
+            if has_meaning {
+                names.push((candidate, self.symbol(candidate).name.clone()));
+            }
+        }
+        get_spelling_suggestion(name, &names, |(_, name)| name.as_str()).map(|&(symbol, _)| symbol)
+    }
+}
+
+/// Picks the candidate whose name is closest to `name`, if any is close enough to be a likely
+/// misspelling
+///
+/// Candidates must be within roughly a third of the length of `name` to be considered, and
+/// within roughly 40% of its length in edits to be suggested. Differences in case are cheaper
+/// than other substitutions, and names shorter than three characters are only suggested when
+/// they differ from `name` in case alone. Ties go to the earliest candidate.
+pub fn get_spelling_suggestion<'a, T>(
+    name: &str,
+    candidates: &'a [T],
+    get_name: impl Fn(&T) -> &str,
+) -> Option<&'a T> {
+    let name_length = name.chars().count();
+    let maximum_length_difference = 2.max(name_length * 34 / 100);
+    // Only suggest a candidate strictly closer than this
+    let mut best_distance = (name_length * 4 / 10) as f64 + 1.0;
+    let mut best_candidate = None;
+    for candidate in candidates {
+        let candidate_name = get_name(candidate);
+        let candidate_length = candidate_name.chars().count();
+        if candidate_length.abs_diff(name_length) > maximum_length_difference
+            || candidate_name == name
+        {
+            continue;
+        }
+        if candidate_length < 3 && candidate_name.to_lowercase() != name.to_lowercase() {
+            continue;
+        }
+        if let Some(distance) = levenshtein_with_max(name, candidate_name, best_distance - 0.1) {
+            best_distance = distance;
+            best_candidate = Some(candidate);
+        }
+    }
+    best_candidate
+}
+
+/// Computes the edit distance between `s1` and `s2`, or `None` if it exceeds `max`
+///
+/// Substituting a character for the same character in a different case costs 0.1, other
+/// substitutions cost 2, and insertions and deletions cost 1.
+fn levenshtein_with_max(s1: &str, s2: &str, max: f64) -> Option<f64> {
+    let s1: Vec<char> = s1.chars().collect();
+    let s2: Vec<char> = s2.chars().collect();
+    let big = max + 0.01;
+    let mut previous: Vec<f64> = (0..=s2.len()).map(|i| i as f64).collect();
+    let mut current = vec![0.0; s2.len() + 1];
+    for i in 1..=s1.len() {
+        let c1 = s1[i - 1];
+        let min_j = if i as f64 > max {
+            (i as f64 - max).ceil() as usize
+        } else {
+            1
+        };
+        let max_j = if s2.len() as f64 > max + i as f64 {
+            (max + i as f64).floor() as usize
+        } else {
+            s2.len()
+        };
+        current[0] = i as f64;
+        // The smallest distance in this row; once it exceeds `max`, so does the result
+        let mut column_min = i as f64;
+        for value in current.iter_mut().take(min_j).skip(1) {
+            *value = big;
+        }
+        for j in min_j..=max_j {
+            let c2 = s2[j - 1];
+            let distance = if c1 == c2 {
+                previous[j - 1]
+            } else {
+                let substitution_distance = if c1.to_lowercase().eq(c2.to_lowercase()) {
+                    previous[j - 1] + 0.1
+                } else {
+                    previous[j - 1] + 2.0
+                };
+                (previous[j] + 1.0)
+                    .min(current[j - 1] + 1.0)
+                    .min(substitution_distance)
+            };
+            current[j] = distance;
+            column_min = column_min.min(distance);
+        }
+        for value in current.iter_mut().skip(max_j + 1) {
+            *value = big;
+        }
+        if column_min > max {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    let result = previous[s2.len()];
+    (result <= max).then_some(result)
+}
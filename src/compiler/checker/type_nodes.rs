@@ -0,0 +1,796 @@
+//! Types written as type nodes
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::is_class_like;
+use crate::compiler::ast::{Node, NodeList, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics;
+
+use super::{Checker, ElementFlags, ObjectFlags, TypeData, TypeFlags, TypeId, TypeMapper};
+
+impl Checker {
+    /// Gets the type a type node denotes
+    pub fn get_type_from_type_node(&mut self, node: &Arc<Node>) -> TypeId {
+        if let Some(type_) = self.node_links(node).resolved_type {
+            return type_;
+        }
+        let saved_node = self.current_node.replace(Arc::clone(node));
+        let type_ = self.get_type_from_type_node_worker(node);
+        self.current_node = saved_node;
+        self.node_links(node).resolved_type = Some(type_);
+        type_
+    }
+
+    fn get_type_from_type_node_worker(&mut self, node: &Arc<Node>) -> TypeId {
+        match node.kind {
+            SyntaxKind::AnyKeyword | SyntaxKind::JSDocAllType => self.any_type,
+            SyntaxKind::UnknownKeyword => self.unknown_type,
+            SyntaxKind::StringKeyword => self.string_type,
+            SyntaxKind::NumberKeyword => self.number_type,
+            SyntaxKind::BigIntKeyword => self.bigint_type,
+            SyntaxKind::BooleanKeyword => self.boolean_type,
+            SyntaxKind::SymbolKeyword => self.es_symbol_type,
+            SyntaxKind::VoidKeyword => self.void_type,
+            SyntaxKind::UndefinedKeyword => self.undefined_type,
+            SyntaxKind::NullKeyword => self.null_type,
+            SyntaxKind::NeverKeyword => self.never_type,
+            SyntaxKind::ObjectKeyword => self.non_primitive_type,
+            SyntaxKind::LiteralType => {
+                let literal = &node.as_literal_type_node().literal;
+                match literal.kind {
+                    SyntaxKind::NullKeyword => self.null_type,
+                    _ => {
+                        let type_ = self.check_expression(literal);
+                        self.get_regular_type_of_literal_type(type_)
+                    }
+                }
+            }
+            SyntaxKind::ThisType => self.get_this_type(node),
+            SyntaxKind::TypeReference => {
+                let reference = node.as_type_reference_node();
+                self.get_type_from_type_reference(
+                    node,
+                    &reference.type_name,
+                    reference.type_arguments.as_ref(),
+                )
+            }
+            SyntaxKind::ExpressionWithTypeArguments => {
+                let reference = node.as_expression_with_type_arguments();
+                self.get_type_from_type_reference(
+                    node,
+                    &reference.expression,
+                    reference.type_arguments.as_ref(),
+                )
+            }
+            SyntaxKind::ArrayType | SyntaxKind::TupleType => {
+                self.get_type_from_array_or_tuple_type_node(node, node, false)
+            }
+            SyntaxKind::UnionType => {
+                let types = self.get_types_from_type_nodes(&node.as_union_type_node().types);
+                self.get_union_type(&types)
+            }
+            SyntaxKind::IntersectionType => {
+                let types = self.get_types_from_type_nodes(&node.as_intersection_type_node().types);
+                self.get_intersection_type(&types)
+            }
+            SyntaxKind::ParenthesizedType
+            | SyntaxKind::OptionalType
+            | SyntaxKind::RestType
+            | SyntaxKind::NamedTupleMember
+            | SyntaxKind::JSDocTypeExpression
+            | SyntaxKind::JSDocNullableType
+            | SyntaxKind::JSDocNonNullableType
+            | SyntaxKind::JSDocOptionalType => {
+                let type_node = node.type_node().expect("type node wraps a type");
+                self.get_type_from_type_node(type_node)
+            }
+            SyntaxKind::TypeOperator => self.get_type_from_type_operator_node(node),
+            SyntaxKind::TypeLiteral | SyntaxKind::FunctionType | SyntaxKind::ConstructorType => {
+                let symbol = self.get_symbol_of_declaration(node);
+                self.create_type(
+                    TypeFlags::OBJECT,
+                    ObjectFlags::ANONYMOUS,
+                    symbol,
+                    TypeData::Anonymous {
+                        target: None,
+                        mapper: None,
+                    },
+                )
+            }
+            SyntaxKind::TypeQuery => self.get_type_from_type_query_node(node),
+            SyntaxKind::TypePredicate => self.boolean_type,
+            SyntaxKind::IndexedAccessType => {
+                let access = node.as_indexed_access_type_node();
+                let object_type = self.get_type_from_type_node(&access.object_type);
+                let index_type = self.get_type_from_type_node(&access.index_type);
+                self.get_indexed_access_type(object_type, index_type, Some(node))
+            }
+            SyntaxKind::ConditionalType => self.get_type_from_conditional_type_node(node),
+            SyntaxKind::InferType => {
+                let parameter = &node.as_infer_type_node().type_parameter;
+                match self.get_symbol_of_declaration(parameter) {
+                    Some(symbol) => self.get_declared_type_of_symbol(symbol),
+                    None => self.error_type,
+                }
+            }
+            SyntaxKind::MappedType => self.get_type_from_mapped_type_node(node),
+            // Template literal types are not resolved yet, so they accept anything rather than
+            // report errors they do not cause
+            SyntaxKind::TemplateLiteralType
+            | SyntaxKind::ImportType
+            | SyntaxKind::JSDocVariadicType => self.any_type,
+            _ => self.error_type,
+        }
+    }
+
+    fn get_types_from_type_nodes(&mut self, nodes: &NodeList) -> Vec<TypeId> {
+        nodes
+            .iter()
+            .map(|node| self.get_type_from_type_node(node))
+            .collect()
+    }
+
+    /// Gets the type of `this` in the class or interface enclosing a `this` type node
+    fn get_this_type(&mut self, node: &Arc<Node>) -> TypeId {
+        let mut current = node.parent();
+        while let Some(container) = current {
+            if is_class_like(&container) || container.kind == SyntaxKind::InterfaceDeclaration {
+                return match self.get_symbol_of_declaration(&container) {
+                    Some(symbol) => self.get_declared_type_of_symbol(symbol),
+                    None => self.error_type,
+                };
+            }
+            current = container.parent();
+        }
+        self.error_type
+    }
+
+    /// Gets the type a name refers to, applying type arguments to generic types
+    fn get_type_from_type_reference(
+        &mut self,
+        node: &Arc<Node>,
+        name: &Arc<Node>,
+        type_arguments: Option<&NodeList>,
+    ) -> TypeId {
+        let Some(symbol) = self.resolve_entity_name(name, SymbolFlags::TYPE, false) else {
+            return self.error_type;
+        };
+        self.node_links(node).resolved_symbol = Some(symbol);
+        let symbol = self.resolve_alias_if_alias(symbol);
+        if symbol == self.unknown_symbol {
+            return self.error_type;
+        }
+        let arguments = match type_arguments {
+            Some(nodes) => self.get_types_from_type_nodes(nodes),
+            None => Vec::new(),
+        };
+        let flags = self.symbol(symbol).flags;
+        if flags.intersects(SymbolFlags::CLASS | SymbolFlags::INTERFACE) {
+            let declared = self.get_declared_type_of_symbol(symbol);
+            let TypeData::Interface(interface) = self.get_type(declared).data.clone() else {
+                return declared;
+            };
+            let type_parameters = interface.local_type_parameters.clone();
+            if type_parameters.is_empty() {
+                return self.check_no_type_arguments(node, declared, type_arguments);
+            }
+            if !self.check_type_argument_count(node, symbol, &type_parameters, arguments.len()) {
+                return self.error_type;
+            }
+            let mut all_arguments = interface.outer_type_parameters.clone();
+            all_arguments.extend(self.fill_missing_type_arguments(arguments, &type_parameters));
+            return self.create_type_reference(declared, all_arguments);
+        }
+        if flags.contains(SymbolFlags::TYPE_ALIAS) {
+            let declared = self.get_declared_type_of_symbol(symbol);
+            let type_parameters = self.get_type_parameters_of_type_alias(symbol);
+            if type_parameters.is_empty() {
+                return self.check_no_type_arguments(node, declared, type_arguments);
+            }
+            if !self.check_type_argument_count(node, symbol, &type_parameters, arguments.len()) {
+                return self.error_type;
+            }
+            let arguments = self.fill_missing_type_arguments(arguments, &type_parameters);
+            return self.get_type_alias_instantiation(symbol, declared, type_parameters, arguments);
+        }
+        if flags.intersects(SymbolFlags::TYPE) {
+            let declared = self.get_declared_type_of_symbol(symbol);
+            return self.check_no_type_arguments(node, declared, type_arguments);
+        }
+        self.error_type
+    }
+
+    /// Reports type arguments applied to a type that is not generic
+    fn check_no_type_arguments(
+        &mut self,
+        node: &Arc<Node>,
+        type_: TypeId,
+        type_arguments: Option<&NodeList>,
+    ) -> TypeId {
+        if type_arguments.is_some_and(|arguments| !arguments.is_empty()) {
+            let name = self.type_to_string(type_);
+            self.error(node, diagnostics::TYPE_0_IS_NOT_GENERIC_2315, &[name]);
+            return self.error_type;
+        }
+        type_
+    }
+
+    /// Checks the number of type arguments applied to a generic type, reporting an error if
+    /// there are too few or too many
+    fn check_type_argument_count(
+        &mut self,
+        node: &Arc<Node>,
+        symbol: SymbolId,
+        type_parameters: &[TypeId],
+        count: usize,
+    ) -> bool {
+        let min = type_parameters
+            .iter()
+            .take_while(|&&parameter| self.get_default_of_type_parameter(parameter).is_none())
+            .count();
+        if (min..=type_parameters.len()).contains(&count) {
+            return true;
+        }
+        let name = self.symbol_to_string_with_type_parameters(symbol, type_parameters);
+        if min == type_parameters.len() {
+            self.error(
+                node,
+                diagnostics::GENERIC_TYPE_0_REQUIRES_1_TYPE_ARGUMENT_S_2314,
+                &[name, min.to_string()],
+            );
+        } else {
+            self.error(
+                node,
+                diagnostics::GENERIC_TYPE_0_REQUIRES_BETWEEN_1_AND_2_TYPE_ARGUMENTS_2707,
+                &[name, min.to_string(), type_parameters.len().to_string()],
+            );
+        }
+        false
+    }
+
+    /// Gets the instantiation of a generic type alias, interned by its type arguments
+    fn get_type_alias_instantiation(
+        &mut self,
+        symbol: SymbolId,
+        declared: TypeId,
+        type_parameters: Vec<TypeId>,
+        arguments: Vec<TypeId>,
+    ) -> TypeId {
+        if let Some(&instantiation) = self.symbol_links(symbol).instantiations.get(&arguments) {
+            return instantiation;
+        }
+        let mapper = TypeMapper::new(type_parameters, arguments.clone());
+        let instantiation = self.instantiate_type(declared, &mapper);
+        self.symbol_links(symbol)
+            .instantiations
+            .insert(arguments, instantiation);
+        instantiation
+    }
+
+    /// Gets the type of an array or tuple type node, which `owner` is or makes `readonly`.
+    /// Its type arguments are resolved on first use when they may refer to the type alias it
+    /// is written in, so that `type Json = string | Json[]` is not circular.
+    fn get_type_from_array_or_tuple_type_node(
+        &mut self,
+        owner: &Arc<Node>,
+        node: &Arc<Node>,
+        readonly: bool,
+    ) -> TypeId {
+        let target = match node.kind {
+            SyntaxKind::TupleType => {
+                let element_flags = node
+                    .as_tuple_type_node()
+                    .elements
+                    .iter()
+                    .map(|element| get_tuple_element_flags(element).0)
+                    .collect();
+                self.get_tuple_target(element_flags, readonly)
+            }
+            _ if readonly => self.get_global_readonly_array_type(),
+            _ => self.get_global_array_type(),
+        };
+        if is_resolved_by_type_alias(node) && self.may_resolve_type_alias_in_elements(node) {
+            let alias = self.get_alias_for_type_node(owner);
+            return self.create_deferred_type_reference(target, owner, None, alias);
+        }
+        let arguments = self.get_type_arguments_from_node(node);
+        self.create_type_reference(target, arguments)
+    }
+
+    /// Gets the element types of an array or tuple type node, or of one made `readonly`
+    pub(super) fn get_type_arguments_from_node(&mut self, node: &Arc<Node>) -> Vec<TypeId> {
+        match node.kind {
+            SyntaxKind::TypeOperator => {
+                self.get_type_arguments_from_node(&node.as_type_operator_node().type_node)
+            }
+            SyntaxKind::ArrayType => {
+                vec![self.get_type_from_type_node(&node.as_array_type_node().element_type)]
+            }
+            SyntaxKind::TupleType => {
+                let mut element_types = Vec::new();
+                for element in &node.as_tuple_type_node().elements {
+                    let (flags, type_node) = get_tuple_element_flags(element);
+                    let mut type_ = self.get_type_from_type_node(type_node);
+                    if flags.contains(ElementFlags::REST) {
+                        // A rest element is written as an array of its elements
+                        if let Some((_, arguments)) = self
+                            .get_reference_target(type_)
+                            .filter(|_| self.is_array_type(type_))
+                        {
+                            type_ = arguments[0];
+                        }
+                    }
+                    element_types.push(type_);
+                }
+                element_types
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Determines if an element type of an array or tuple type node may refer to a type alias,
+    /// directly or through the types it is made of
+    fn may_resolve_type_alias_in_elements(&mut self, node: &Arc<Node>) -> bool {
+        match node.kind {
+            SyntaxKind::ArrayType => {
+                self.may_resolve_type_alias(&node.as_array_type_node().element_type)
+            }
+            _ => node
+                .as_tuple_type_node()
+                .elements
+                .iter()
+                .any(|element| self.may_resolve_type_alias(element)),
+        }
+    }
+
+    /// Determines if a type node may refer to a type alias, so that resolving it may need the
+    /// declared type of the alias it is written in
+    fn may_resolve_type_alias(&mut self, node: &Arc<Node>) -> bool {
+        match node.kind {
+            SyntaxKind::TypeReference => {
+                let name = &node.as_type_reference_node().type_name;
+                self.resolve_entity_name(name, SymbolFlags::TYPE, true)
+                    .map(|symbol| self.resolve_alias_if_alias(symbol))
+                    .is_some_and(|symbol| {
+                        self.symbol(symbol).flags.contains(SymbolFlags::TYPE_ALIAS)
+                    })
+            }
+            SyntaxKind::TypeQuery => true,
+            SyntaxKind::TypeOperator => {
+                let operator = node.as_type_operator_node();
+                operator.operator != SyntaxKind::UniqueKeyword
+                    && self.may_resolve_type_alias(&operator.type_node)
+            }
+            SyntaxKind::ParenthesizedType
+            | SyntaxKind::OptionalType
+            | SyntaxKind::NamedTupleMember => {
+                let type_node = node.type_node().expect("type node wraps a type");
+                self.may_resolve_type_alias(type_node)
+            }
+            SyntaxKind::RestType => {
+                let type_node = node.type_node().expect("type node wraps a type");
+                type_node.kind != SyntaxKind::ArrayType || self.may_resolve_type_alias(type_node)
+            }
+            SyntaxKind::ArrayType => {
+                self.may_resolve_type_alias(&node.as_array_type_node().element_type)
+            }
+            SyntaxKind::TupleType | SyntaxKind::UnionType | SyntaxKind::IntersectionType => {
+                let types = match node.kind {
+                    SyntaxKind::TupleType => &node.as_tuple_type_node().elements,
+                    SyntaxKind::UnionType => &node.as_union_type_node().types,
+                    _ => &node.as_intersection_type_node().types,
+                };
+                types
+                    .iter()
+                    .any(|type_node| self.may_resolve_type_alias(type_node))
+            }
+            SyntaxKind::IndexedAccessType => {
+                let access = node.as_indexed_access_type_node();
+                self.may_resolve_type_alias(&access.object_type)
+                    || self.may_resolve_type_alias(&access.index_type)
+            }
+            SyntaxKind::ConditionalType => {
+                let conditional = node.as_conditional_type_node();
+                self.may_resolve_type_alias(&conditional.check_type)
+                    || self.may_resolve_type_alias(&conditional.extends_type)
+                    || self.may_resolve_type_alias(&conditional.true_type)
+                    || self.may_resolve_type_alias(&conditional.false_type)
+            }
+            _ => false,
+        }
+    }
+
+    /// Gets the type alias a type node is the whole of, with the alias's type parameters
+    fn get_alias_for_type_node(&mut self, node: &Arc<Node>) -> Option<(SymbolId, Vec<TypeId>)> {
+        let mut parent = node.parent()?;
+        while parent.kind == SyntaxKind::ParenthesizedType {
+            parent = parent.parent()?;
+        }
+        if parent.kind != SyntaxKind::TypeAliasDeclaration {
+            return None;
+        }
+        let symbol = self.get_symbol_of_declaration(&parent)?;
+        let type_parameters = self.get_type_parameters_of_type_alias(symbol);
+        Some((symbol, type_parameters))
+    }
+
+    fn get_type_from_type_operator_node(&mut self, node: &Arc<Node>) -> TypeId {
+        let operator = node.as_type_operator_node();
+        match operator.operator {
+            SyntaxKind::ReadonlyKeyword => match operator.type_node.kind {
+                SyntaxKind::ArrayType | SyntaxKind::TupleType => {
+                    self.get_type_from_array_or_tuple_type_node(node, &operator.type_node, true)
+                }
+                _ => self.get_type_from_type_node(&operator.type_node),
+            },
+            SyntaxKind::KeyOfKeyword => {
+                let type_ = self.get_type_from_type_node(&operator.type_node);
+                self.get_index_type(type_)
+            }
+            SyntaxKind::UniqueKeyword => self.get_type_from_unique_type_operator(node),
+            _ => self.error_type,
+        }
+    }
+
+    /// Gets `keyof T`: the literal types of the names of the public properties of `T`, and
+    /// `string`, `number`, or `symbol` for the keys its index signatures accept
+    pub(super) fn get_index_type(&mut self, type_: TypeId) -> TypeId {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::ANY) || type_ == self.never_type {
+            return self.get_keyof_constraint_type();
+        }
+        if flags.contains(TypeFlags::UNKNOWN) {
+            return self.never_type;
+        }
+        // A generic mapped type has the keys it maps, unless it renames them
+        if self.is_generic_mapped_type(type_)
+            && self.get_name_type_from_mapped_type(type_).is_none()
+        {
+            return match self.get_modifiers_type_from_mapped_type(type_) {
+                Some(modifiers_type) => self.get_index_type(modifiers_type),
+                None => self.get_constraint_type_from_mapped_type(type_),
+            };
+        }
+        if self.is_generic_type(type_) && !flags.contains(TypeFlags::UNION_OR_INTERSECTION) {
+            return self.get_deferred_index_type(type_);
+        }
+        // The keys of a union are those every constituent has, and the keys of an
+        // intersection are those any constituent has
+        if let TypeData::UnionOrIntersection { types } = self.get_type(type_).data.clone() {
+            let mut key_types = Vec::with_capacity(types.len());
+            for constituent in types {
+                key_types.push(self.get_index_type(constituent));
+            }
+            return if flags.contains(TypeFlags::UNION) {
+                self.get_intersection_type(&key_types)
+            } else {
+                self.get_union_type(&key_types)
+            };
+        }
+        let apparent = self.get_apparent_type(type_);
+        let mut key_types = Vec::new();
+        for property in self.get_properties_of_type(apparent) {
+            if let Some(key_type) = self.get_literal_type_from_property(property) {
+                key_types.push(key_type);
+            }
+        }
+        if self.flags(apparent).contains(TypeFlags::OBJECT) {
+            let index_infos = self
+                .resolve_structured_type_members(apparent)
+                .index_infos
+                .clone();
+            for info in index_infos {
+                // Number keys are written as strings, so a string index signature accepts both
+                if info.key_type == self.string_type {
+                    key_types.push(self.number_type);
+                }
+                key_types.push(info.key_type);
+            }
+        }
+        self.get_union_type(&key_types)
+    }
+
+    /// Gets `string | number | symbol`, the type of any key
+    pub(super) fn get_keyof_constraint_type(&mut self) -> TypeId {
+        let string_number_symbol = [self.string_type, self.number_type, self.es_symbol_type];
+        self.get_union_type(&string_number_symbol)
+    }
+
+    /// Gets the interned `keyof T` for a type `T` that is generic
+    fn get_deferred_index_type(&mut self, type_: TypeId) -> TypeId {
+        if let Some(&index_type) = self.index_types.get(&type_) {
+            return index_type;
+        }
+        let index_type = self.create_type(
+            TypeFlags::INDEX,
+            ObjectFlags::NONE,
+            None,
+            TypeData::Index { type_ },
+        );
+        self.index_types.insert(type_, index_type);
+        index_type
+    }
+
+    /// Gets the literal type of the key a property is accessed by, which is a number literal
+    /// for a property named by a numeric literal
+    ///
+    /// Private and protected properties have no key outside their class.
+    pub(super) fn get_literal_type_from_property(&mut self, property: SymbolId) -> Option<TypeId> {
+        if let Some(name_type) = self.symbol_links(property).name_type {
+            return Some(name_type);
+        }
+        let symbol = self.symbol(property);
+        let name = symbol.name.clone();
+        let declaration = symbol.value_declaration.clone();
+        if let Some(declaration) = &declaration {
+            if declaration.has_modifier(SyntaxKind::PrivateKeyword)
+                || declaration.has_modifier(SyntaxKind::ProtectedKeyword)
+            {
+                return None;
+            }
+            match declaration.name().map(|name| name.kind) {
+                Some(SyntaxKind::PrivateIdentifier) => return None,
+                // A member named by a unique symbol is keyed by that symbol
+                Some(SyntaxKind::ComputedPropertyName) => {
+                    let name = declaration.name().expect("declaration has a name");
+                    let expression = &name.as_computed_property_name().expression;
+                    let name_type = self.check_expression(expression);
+                    if self.flags(name_type).contains(TypeFlags::UNIQUE_ES_SYMBOL) {
+                        return Some(name_type);
+                    }
+                }
+                Some(SyntaxKind::NumericLiteral) => {
+                    if let Ok(value) = name.parse::<f64>() {
+                        return Some(self.get_number_literal_type(value));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(self.get_string_literal_type(&name))
+    }
+
+    /// Gets `T[K]`, reporting the keys `T` does not have at `access_node` if there is one
+    pub(super) fn get_indexed_access_type(
+        &mut self,
+        object_type: TypeId,
+        index_type: TypeId,
+        access_node: Option<&Arc<Node>>,
+    ) -> TypeId {
+        let object_flags = self.flags(object_type);
+        let index_flags = self.flags(index_type);
+        if object_flags.intersects(TypeFlags::ANY) || index_flags.intersects(TypeFlags::ANY) {
+            return self.any_type;
+        }
+        if object_type == self.error_type || index_type == self.error_type {
+            return self.error_type;
+        }
+        // Indexing a generic mapped type applies its template to the key
+        if self.is_generic_mapped_type(object_type)
+            && let Some(type_) = self.substitute_indexed_mapped_type(object_type, index_type)
+        {
+            return type_;
+        }
+        if self.is_generic_type(object_type) || self.is_generic_type(index_type) {
+            return self.get_deferred_indexed_access_type(object_type, index_type);
+        }
+        if index_flags.contains(TypeFlags::UNION) {
+            let TypeData::UnionOrIntersection { types } = self.get_type(index_type).data.clone()
+            else {
+                return self.error_type;
+            };
+            let mut results = Vec::with_capacity(types.len());
+            for type_ in types {
+                let result = self.get_indexed_access_type(object_type, type_, access_node);
+                if result == self.error_type {
+                    return self.error_type;
+                }
+                results.push(result);
+            }
+            return self.get_union_type(&results);
+        }
+        let property_name = self.get_property_name_from_type(index_type);
+        if let Some(name) = &property_name
+            && let Some(property) = self.get_property_of_type(object_type, name)
+        {
+            return self.get_type_of_symbol(property);
+        }
+        let is_key_type = index_flags.intersects(
+            TypeFlags::STRING_LIKE | TypeFlags::NUMBER_LIKE | TypeFlags::ES_SYMBOL_LIKE,
+        );
+        if is_key_type && let Some(info) = self.get_applicable_index_info(object_type, index_type) {
+            return info.value_type;
+        }
+        if self.is_permissive_type(object_type) {
+            return self.any_type;
+        }
+        if let Some(access_node) = access_node {
+            let error_node = match access_node.kind {
+                SyntaxKind::IndexedAccessType => {
+                    Arc::clone(&access_node.as_indexed_access_type_node().index_type)
+                }
+                _ => Arc::clone(access_node),
+            };
+            let index_name = self.type_to_string(index_type);
+            if let Some(name) = property_name {
+                let object_name = self.type_to_string(object_type);
+                self.error(
+                    &error_node,
+                    diagnostics::PROPERTY_0_DOES_NOT_EXIST_ON_TYPE_1_2339,
+                    &[name, object_name],
+                );
+            } else if is_key_type {
+                let object_name = self.type_to_string(object_type);
+                self.error(
+                    &error_node,
+                    diagnostics::TYPE_0_HAS_NO_MATCHING_INDEX_SIGNATURE_FOR_TYPE_1_2537,
+                    &[object_name, index_name],
+                );
+            } else {
+                self.error(
+                    &error_node,
+                    diagnostics::TYPE_0_CANNOT_BE_USED_AS_AN_INDEX_TYPE_2538,
+                    &[index_name],
+                );
+            }
+        }
+        self.error_type
+    }
+
+    /// Reports an indexed access type `T[K]` whose key `K` is not known to be a key of `T`
+    pub(super) fn check_indexed_access_type(&mut self, node: &Arc<Node>) {
+        let type_ = self.get_type_from_type_node(node);
+        let TypeData::IndexedAccess {
+            object_type,
+            index_type,
+        } = self.get_type(type_).data
+        else {
+            return;
+        };
+        let keys = self.get_index_type(object_type);
+        if self.is_type_assignable_to(index_type, keys) {
+            return;
+        }
+        let index_name = self.type_to_string(index_type);
+        let object_name = self.type_to_string(object_type);
+        self.error(
+            &node.as_indexed_access_type_node().index_type,
+            diagnostics::TYPE_0_CANNOT_BE_USED_TO_INDEX_TYPE_1_2536,
+            &[index_name, object_name],
+        );
+    }
+
+    /// Gets the interned indexed access type for an object or index type that is generic
+    fn get_deferred_indexed_access_type(
+        &mut self,
+        object_type: TypeId,
+        index_type: TypeId,
+    ) -> TypeId {
+        if let Some(&type_) = self.indexed_access_types.get(&(object_type, index_type)) {
+            return type_;
+        }
+        let type_ = self.create_type(
+            TypeFlags::INDEXED_ACCESS,
+            ObjectFlags::NONE,
+            None,
+            TypeData::IndexedAccess {
+                object_type,
+                index_type,
+            },
+        );
+        self.indexed_access_types
+            .insert((object_type, index_type), type_);
+        type_
+    }
+
+    /// Gets the type an indexed access `T[K]` is known to be assignable to, by indexing the
+    /// apparent type of `T` with the constraint of `K`
+    pub(super) fn get_constraint_of_indexed_access(&mut self, type_: TypeId) -> Option<TypeId> {
+        let TypeData::IndexedAccess {
+            object_type,
+            index_type,
+        } = self.get_type(type_).data
+        else {
+            return None;
+        };
+        let object_constraint = self.get_apparent_type(object_type);
+        let index_constraint = self.get_base_constraint_of_key(index_type);
+        if object_constraint == object_type && index_constraint == index_type {
+            return None;
+        }
+        let constraint = self.get_indexed_access_type(object_constraint, index_constraint, None);
+        (constraint != self.error_type).then_some(constraint)
+    }
+
+    /// Gets the keys a generic key type stands for, resolving `keyof T` by the constraint of `T`
+    fn get_base_constraint_of_key(&mut self, type_: TypeId) -> TypeId {
+        match self.get_type(type_).data {
+            TypeData::TypeParameter { .. } => match self.get_constraint_of_type_parameter(type_) {
+                Some(constraint) if constraint != type_ => {
+                    self.get_base_constraint_of_key(constraint)
+                }
+                _ => self.unknown_type,
+            },
+            TypeData::Index { type_: target } => {
+                let apparent = self.get_apparent_type(target);
+                self.get_index_type(apparent)
+            }
+            _ => type_,
+        }
+    }
+
+    /// Gets the type of the value a `typeof` type query names
+    fn get_type_from_type_query_node(&mut self, node: &Arc<Node>) -> TypeId {
+        let name = &node.as_type_query_node().expr_name;
+        if name.kind == SyntaxKind::QualifiedName
+            && let Some(symbol) = self.resolve_entity_name(name, SymbolFlags::VALUE, true)
+        {
+            self.node_links(node).resolved_symbol = Some(symbol);
+            let symbol = self.resolve_alias_if_alias(symbol);
+            let type_ = self.get_type_of_symbol(symbol);
+            return self.get_regular_type_of_literal_type(type_);
+        }
+        // The literal type of a const is no longer fresh once a type query names it, so a
+        // variable declared with that type keeps it
+        let type_ = self.check_type_query_name(name);
+        self.get_regular_type_of_literal_type(type_)
+    }
+
+    /// Gets the type of a name in a type query as an expression reads it, so that `typeof x.y`
+    /// is the type of the property `y` of the value `x` when `x` is not a namespace
+    fn check_type_query_name(&mut self, name: &Arc<Node>) -> TypeId {
+        match name.kind {
+            SyntaxKind::ThisKeyword => self.check_expression(name),
+            SyntaxKind::Identifier => self.check_identifier(name),
+            SyntaxKind::QualifiedName => {
+                let qualified = name.as_qualified_name();
+                let left_type = self.check_type_query_name(&qualified.left);
+                self.get_type_of_property_access(name, &qualified.left, left_type, &qualified.right)
+            }
+            _ => self.error_type,
+        }
+    }
+}
+
+/// Gets the kind of a tuple element, and the type node of its type
+fn get_tuple_element_flags(element: &Arc<Node>) -> (ElementFlags, &Arc<Node>) {
+    match element.kind {
+        SyntaxKind::OptionalType => (ElementFlags::OPTIONAL, element),
+        SyntaxKind::RestType => (ElementFlags::REST, element),
+        SyntaxKind::NamedTupleMember => {
+            let member = element.as_named_tuple_member();
+            let flags = if member.dot_dot_dot_token.is_some() {
+                ElementFlags::REST
+            } else if member.question_token.is_some() {
+                ElementFlags::OPTIONAL
+            } else {
+                ElementFlags::REQUIRED
+            };
+            (flags, &member.type_node)
+        }
+        _ => (ElementFlags::REQUIRED, element),
+    }
+}
+
+/// Determines if a type node is written in a type alias, directly or nested in other type
+/// nodes that do not defer their own resolution
+fn is_resolved_by_type_alias(node: &Arc<Node>) -> bool {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        match parent.kind {
+            SyntaxKind::TypeAliasDeclaration => return true,
+            SyntaxKind::ParenthesizedType
+            | SyntaxKind::NamedTupleMember
+            | SyntaxKind::TypeReference
+            | SyntaxKind::UnionType
+            | SyntaxKind::IntersectionType
+            | SyntaxKind::IndexedAccessType
+            | SyntaxKind::ConditionalType
+            | SyntaxKind::TypeOperator
+            | SyntaxKind::ArrayType
+            | SyntaxKind::TupleType => current = parent.parent(),
+            _ => return false,
+        }
+    }
+    false
+}
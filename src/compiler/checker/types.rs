@@ -0,0 +1,442 @@
+//! The checker's representation of types and signatures
+//!
+//! Types and signatures are owned by a [`Checker`](super::Checker) and referred to by
+//! [`TypeId`] and [`SignatureId`]. Structurally identical unions, intersections, literals, and
+//! type references are interned, so two of them are the same type exactly when their ids are
+//! equal.
+
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use bitflags::bitflags;
+
+use crate::compiler::ast::{Node, SymbolId, SymbolTable};
+
+/// Identifies a type owned by a checker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TypeId(pub(crate) u32);
+
+impl TypeId {
+    /// Gets the index of the type in the checker's type list
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Identifies a signature owned by a checker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SignatureId(pub(crate) u32);
+
+impl SignatureId {
+    /// Gets the index of the signature in the checker's signature list
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+bitflags! {
+    /// Describes what kind of type a [`Type`] is
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct TypeFlags: u32 {
+        const NONE = 0;
+        const ANY = 1 << 0;
+        const UNKNOWN = 1 << 1;
+        const STRING = 1 << 2;
+        const NUMBER = 1 << 3;
+        const BOOLEAN = 1 << 4;
+        const ENUM = 1 << 5;
+        const BIG_INT = 1 << 6;
+        const STRING_LITERAL = 1 << 7;
+        const NUMBER_LITERAL = 1 << 8;
+        const BOOLEAN_LITERAL = 1 << 9;
+        const ENUM_LITERAL = 1 << 10;
+        const BIG_INT_LITERAL = 1 << 11;
+        const ES_SYMBOL = 1 << 12;
+        const UNIQUE_ES_SYMBOL = 1 << 13;
+        const VOID = 1 << 14;
+        const UNDEFINED = 1 << 15;
+        const NULL = 1 << 16;
+        const NEVER = 1 << 17;
+        const TYPE_PARAMETER = 1 << 18;
+        const OBJECT = 1 << 19;
+        const UNION = 1 << 20;
+        const INTERSECTION = 1 << 21;
+        const INDEX = 1 << 22;
+        const INDEXED_ACCESS = 1 << 23;
+        const CONDITIONAL = 1 << 24;
+        const SUBSTITUTION = 1 << 25;
+        /// The `object` type
+        const NON_PRIMITIVE = 1 << 26;
+        const TEMPLATE_LITERAL = 1 << 27;
+        const STRING_MAPPING = 1 << 28;
+
+        const ANY_OR_UNKNOWN = Self::ANY.bits() | Self::UNKNOWN.bits();
+        const NULLABLE = Self::UNDEFINED.bits() | Self::NULL.bits();
+        const LITERAL = Self::STRING_LITERAL.bits() | Self::NUMBER_LITERAL.bits()
+            | Self::BIG_INT_LITERAL.bits() | Self::BOOLEAN_LITERAL.bits();
+        const UNIT = Self::ENUM.bits() | Self::LITERAL.bits() | Self::UNIQUE_ES_SYMBOL.bits()
+            | Self::NULLABLE.bits();
+        const STRING_OR_NUMBER_LITERAL = Self::STRING_LITERAL.bits() | Self::NUMBER_LITERAL.bits();
+        const PRIMITIVE = Self::STRING.bits() | Self::NUMBER.bits() | Self::BIG_INT.bits()
+            | Self::BOOLEAN.bits() | Self::ENUM.bits() | Self::ENUM_LITERAL.bits()
+            | Self::ES_SYMBOL.bits() | Self::VOID.bits() | Self::UNDEFINED.bits()
+            | Self::NULL.bits() | Self::LITERAL.bits() | Self::UNIQUE_ES_SYMBOL.bits()
+            | Self::TEMPLATE_LITERAL.bits();
+        const STRING_LIKE = Self::STRING.bits() | Self::STRING_LITERAL.bits()
+            | Self::TEMPLATE_LITERAL.bits() | Self::STRING_MAPPING.bits();
+        const NUMBER_LIKE = Self::NUMBER.bits() | Self::NUMBER_LITERAL.bits() | Self::ENUM.bits();
+        const BIG_INT_LIKE = Self::BIG_INT.bits() | Self::BIG_INT_LITERAL.bits();
+        const BOOLEAN_LIKE = Self::BOOLEAN.bits() | Self::BOOLEAN_LITERAL.bits();
+        const ENUM_LIKE = Self::ENUM.bits() | Self::ENUM_LITERAL.bits();
+        const ES_SYMBOL_LIKE = Self::ES_SYMBOL.bits() | Self::UNIQUE_ES_SYMBOL.bits();
+        const VOID_LIKE = Self::VOID.bits() | Self::UNDEFINED.bits();
+        const UNION_OR_INTERSECTION = Self::UNION.bits() | Self::INTERSECTION.bits();
+        const STRUCTURED_TYPE = Self::OBJECT.bits() | Self::UNION.bits()
+            | Self::INTERSECTION.bits();
+        const TYPE_VARIABLE = Self::TYPE_PARAMETER.bits() | Self::INDEXED_ACCESS.bits();
+        const INSTANTIABLE_NON_PRIMITIVE = Self::TYPE_VARIABLE.bits() | Self::CONDITIONAL.bits()
+            | Self::SUBSTITUTION.bits();
+        const INSTANTIABLE_PRIMITIVE = Self::INDEX.bits() | Self::TEMPLATE_LITERAL.bits()
+            | Self::STRING_MAPPING.bits();
+        const INSTANTIABLE = Self::INSTANTIABLE_NON_PRIMITIVE.bits()
+            | Self::INSTANTIABLE_PRIMITIVE.bits();
+        const STRUCTURED_OR_INSTANTIABLE = Self::STRUCTURED_TYPE.bits()
+            | Self::INSTANTIABLE.bits();
+        /// Types control flow can narrow
+        const NARROWABLE = Self::ANY_OR_UNKNOWN.bits() | Self::STRUCTURED_OR_INSTANTIABLE.bits()
+            | Self::STRING_LIKE.bits() | Self::NUMBER_LIKE.bits() | Self::BIG_INT_LIKE.bits()
+            | Self::BOOLEAN_LIKE.bits() | Self::ES_SYMBOL_LIKE.bits()
+            | Self::NON_PRIMITIVE.bits();
+        /// Types that can be narrowed away by truthiness
+        const POSSIBLY_FALSY = Self::STRING_LIKE.bits() | Self::NUMBER_LIKE.bits()
+            | Self::BIG_INT_LIKE.bits() | Self::BOOLEAN_LIKE.bits() | Self::VOID.bits()
+            | Self::UNDEFINED.bits() | Self::NULL.bits();
+    }
+}
+
+bitflags! {
+    /// Describes what kind of object type a [`Type`] with [`TypeFlags::OBJECT`] is
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ObjectFlags: u32 {
+        const NONE = 0;
+        /// The declared type of a class
+        const CLASS = 1 << 0;
+        /// The declared type of an interface
+        const INTERFACE = 1 << 1;
+        /// A generic type instantiated with type arguments
+        const REFERENCE = 1 << 2;
+        /// The generic target of tuple types
+        const TUPLE = 1 << 3;
+        /// A type literal, function type, object literal, or the type of a function, class,
+        /// enum, or namespace
+        const ANONYMOUS = 1 << 4;
+        const MAPPED = 1 << 5;
+        /// An anonymous or mapped type created by instantiation
+        const INSTANTIATED = 1 << 6;
+        /// The type of an object literal expression
+        const OBJECT_LITERAL = 1 << 7;
+        /// The type of an array literal expression
+        const ARRAY_LITERAL = 1 << 8;
+        /// A literal type an expression has, which widens in mutable locations, or an object
+        /// literal type whose properties have not been widened yet
+        const FRESH_LITERAL = 1 << 9;
+        /// The union of `true` and `false` that is the `boolean` type
+        const PRIMITIVE_UNION = 1 << 10;
+        /// The type of an array whose element type control flow is still evolving
+        const EVOLVING_ARRAY = 1 << 11;
+
+        const CLASS_OR_INTERFACE = Self::CLASS.bits() | Self::INTERFACE.bits();
+    }
+}
+
+bitflags! {
+    /// Describes the elements of a tuple type
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ElementFlags: u32 {
+        const NONE = 0;
+        /// `T`
+        const REQUIRED = 1 << 0;
+        /// `T?`
+        const OPTIONAL = 1 << 1;
+        /// `...T[]`
+        const REST = 1 << 2;
+        /// `...T` where `T` is a type parameter
+        const VARIADIC = 1 << 3;
+
+        const FIXED = Self::REQUIRED.bits() | Self::OPTIONAL.bits();
+        const VARIABLE = Self::REST.bits() | Self::VARIADIC.bits();
+    }
+}
+
+bitflags! {
+    /// Describes a [`Signature`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct SignatureFlags: u32 {
+        const NONE = 0;
+        /// The last parameter is a rest parameter
+        const HAS_REST_PARAMETER = 1 << 0;
+        /// The signature has a literal type among its parameter types
+        const HAS_LITERAL_TYPES = 1 << 1;
+        /// The signature belongs to an abstract class or method
+        const ABSTRACT = 1 << 2;
+        /// The signature is a construct signature
+        const CONSTRUCT = 1 << 3;
+    }
+}
+
+/// A number that can be used as a key, comparing by value with all NaNs equal
+#[derive(Debug, Clone, Copy)]
+pub struct Number(pub f64);
+
+impl Number {
+    fn key(self) -> u64 {
+        if self.0.is_nan() {
+            f64::NAN.to_bits()
+        } else if self.0 == 0.0 {
+            0
+        } else {
+            self.0.to_bits()
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Number {}
+
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// The value of a literal type
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LiteralValue {
+    String(String),
+    Number(Number),
+    /// The digits of a bigint, in decimal and without the trailing `n`
+    BigInt(String),
+}
+
+/// A type owned by a checker
+#[derive(Debug, Clone)]
+pub struct Type {
+    pub flags: TypeFlags,
+    pub object_flags: ObjectFlags,
+    /// The symbol that declared the type, such as the class of a class type
+    pub symbol: Option<SymbolId>,
+    /// The type alias the type was written through, used when printing it
+    pub alias_symbol: Option<SymbolId>,
+    /// The type arguments of `alias_symbol`
+    pub alias_type_arguments: Vec<TypeId>,
+    pub data: TypeData,
+}
+
+/// The data that depends on the kind of a [`Type`]
+#[derive(Debug, Clone)]
+pub enum TypeData {
+    /// A built-in type such as `string` or `any`
+    Intrinsic { name: &'static str },
+    /// A string, number, or bigint literal type
+    Literal { value: LiteralValue },
+    /// The `unique symbol` type of a declaration, with the name of the members it keys
+    UniqueESSymbol { escaped_name: String },
+    /// A union or intersection, with its constituents sorted by id
+    UnionOrIntersection { types: Vec<TypeId> },
+    /// A type parameter; the constraint is resolved on first use
+    TypeParameter {
+        constraint: Option<Option<TypeId>>,
+        is_this_type: bool,
+    },
+    /// The declared type of a class or interface, or the target of tuple types
+    Interface(InterfaceType),
+    /// A generic class, interface, or tuple type instantiated with type arguments
+    Reference {
+        target: TypeId,
+        type_arguments: Vec<TypeId>,
+    },
+    /// A type whose members come from its symbol, instantiated with the mapper when it is the
+    /// instantiation of another anonymous type
+    Anonymous {
+        target: Option<TypeId>,
+        mapper: Option<Arc<TypeMapper>>,
+    },
+    /// A mapped type `{ [K in C]: T }`, instantiated with the mapper when it is the
+    /// instantiation of another mapped type
+    Mapped {
+        target: Option<TypeId>,
+        mapper: Option<Arc<TypeMapper>>,
+    },
+    /// An object type created with its members already known, such as an object literal type
+    Resolved { members: Arc<ResolvedMembers> },
+    /// The declared type of an enum
+    Enum,
+    /// An array whose element type evolves with the elements added to it, which control flow
+    /// analysis finalizes to `T[]`
+    EvolvingArray { element_type: TypeId },
+    /// `keyof T` for a type `T` that is still generic
+    Index { type_: TypeId },
+    /// An indexed access `T[K]` whose object or index type is still generic
+    IndexedAccess {
+        object_type: TypeId,
+        index_type: TypeId,
+    },
+    /// A conditional type whose check or extends type is still generic, instantiated with the
+    /// mapper when it is the instantiation of another conditional type
+    Conditional {
+        root: Arc<ConditionalRoot>,
+        check_type: TypeId,
+        extends_type: TypeId,
+        mapper: Option<Arc<TypeMapper>>,
+        /// The mapper extended with the types inferred for the `infer` type parameters
+        combined_mapper: Option<Arc<TypeMapper>>,
+    },
+}
+
+/// What every instantiation of a conditional type `T extends U ? X : Y` shares
+#[derive(Debug)]
+pub struct ConditionalRoot {
+    /// The conditional type node, whose branches are resolved when they are chosen
+    pub node: Arc<Node>,
+    pub check_type: TypeId,
+    pub extends_type: TypeId,
+    /// Whether the check type is a naked type parameter, which makes the conditional type
+    /// distribute over unions
+    pub is_distributive: bool,
+    /// The type parameters declared by `infer` in the extends type
+    pub infer_type_parameters: Vec<TypeId>,
+    /// The type parameters in scope that instantiations may replace
+    pub outer_type_parameters: Vec<TypeId>,
+}
+
+/// An array or tuple type written in a type alias that may refer to the alias, whose type
+/// arguments are resolved from its node on first use
+#[derive(Debug, Clone)]
+pub struct DeferredTypeReference {
+    /// The array, tuple, or `readonly` type operator node
+    pub node: Arc<Node>,
+    /// Maps the type parameters in scope at the node to those of this instantiation
+    pub mapper: Option<Arc<TypeMapper>>,
+    /// Whether the type arguments have been (or are being) resolved
+    pub resolved: bool,
+}
+
+/// The data of the declared type of a class or interface
+#[derive(Debug, Clone, Default)]
+pub struct InterfaceType {
+    /// The type parameters of the declarations that contain the class or interface
+    pub outer_type_parameters: Vec<TypeId>,
+    /// The type parameters declared by the class or interface itself
+    pub local_type_parameters: Vec<TypeId>,
+    /// The element flags of a tuple target
+    pub element_flags: Vec<ElementFlags>,
+    /// Whether a tuple target is readonly
+    pub readonly: bool,
+}
+
+impl InterfaceType {
+    /// Gets the outer type parameters followed by the local ones
+    pub fn type_parameters(&self) -> Vec<TypeId> {
+        self.outer_type_parameters
+            .iter()
+            .chain(&self.local_type_parameters)
+            .copied()
+            .collect()
+    }
+
+    /// Determines if the type has type parameters, making its declared type a reference to
+    /// itself
+    pub fn is_generic(&self) -> bool {
+        !self.outer_type_parameters.is_empty() || !self.local_type_parameters.is_empty()
+    }
+}
+
+/// The members of an object type
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedMembers {
+    /// The properties in declaration order
+    pub properties: Vec<SymbolId>,
+    /// The properties by name
+    pub members: SymbolTable,
+    pub call_signatures: Vec<SignatureId>,
+    pub construct_signatures: Vec<SignatureId>,
+    pub index_infos: Vec<IndexInfo>,
+}
+
+impl ResolvedMembers {
+    /// Adds a property, replacing any existing property with the same name
+    pub fn add_property(&mut self, name: &str, symbol: SymbolId) {
+        if let Some(existing) = self.members.insert(name.to_string(), symbol) {
+            self.properties.retain(|&property| property != existing);
+        }
+        self.properties.push(symbol);
+    }
+}
+
+/// An index signature such as `[key: string]: T`
+#[derive(Debug, Clone)]
+pub struct IndexInfo {
+    pub key_type: TypeId,
+    pub value_type: TypeId,
+    pub is_readonly: bool,
+    pub declaration: Option<Arc<Node>>,
+}
+
+/// A call or construct signature
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub flags: SignatureFlags,
+    /// The declaration the signature was created from, if any
+    pub declaration: Option<Arc<Node>>,
+    pub type_parameters: Vec<TypeId>,
+    /// The `this` parameter, if declared
+    pub this_parameter: Option<SymbolId>,
+    pub parameters: Vec<SymbolId>,
+    /// The number of parameters that are neither optional nor rest parameters
+    pub min_argument_count: usize,
+    /// The return type, resolved on first use
+    pub resolved_return_type: Option<TypeId>,
+    /// The signature this one is an instantiation of
+    pub target: Option<SignatureId>,
+    /// The mapper that instantiated `target` into this signature
+    pub mapper: Option<Arc<TypeMapper>>,
+}
+
+impl Signature {
+    /// Determines if the last parameter is a rest parameter
+    pub fn has_rest_parameter(&self) -> bool {
+        self.flags.contains(SignatureFlags::HAS_REST_PARAMETER)
+    }
+}
+
+/// Maps type parameters to the types that replace them during instantiation
+#[derive(Debug, Clone)]
+pub enum TypeMapper {
+    /// Maps each source to the target at the same index
+    Array {
+        sources: Vec<TypeId>,
+        targets: Vec<TypeId>,
+    },
+    /// Applies the first mapper, then instantiates the result with the second
+    Composite(Arc<TypeMapper>, Arc<TypeMapper>),
+    /// Applies the first mapper, then applies the second to types the first left unchanged
+    Merged(Arc<TypeMapper>, Arc<TypeMapper>),
+    /// Maps every type parameter to `any`, for the most permissive check of a conditional type
+    Permissive,
+    /// Maps every type parameter to a copy without its constraint, for the most restrictive
+    /// check of a conditional type
+    Restrictive,
+}
+
+impl TypeMapper {
+    /// Creates a mapper from each source to the target at the same index
+    pub fn new(sources: Vec<TypeId>, targets: Vec<TypeId>) -> Arc<Self> {
+        debug_assert_eq!(sources.len(), targets.len());
+        Arc::new(TypeMapper::Array { sources, targets })
+    }
+}
@@ -0,0 +1,443 @@
+//! Unique symbols and the members they name
+//!
+//! A `unique symbol` is the type of one particular symbol value, which a const variable, a
+//! readonly static property, or a readonly property signature holds when it is declared with
+//! that type or, for a variable or property, initialized by a call of `Symbol()`. Each such
+//! declaration has a unique symbol type of its own, which widens to `symbol` in mutable
+//! locations.
+//!
+//! A member named by a computed property name the binder cannot resolve, like `[key]`, is
+//! bound late, once the checker knows the type of the name: when that is a string or number
+//! literal or a unique symbol, the member becomes a property like any other. The well-known
+//! symbols that `SymbolConstructor` declares, like `Symbol.iterator`, name members the way the
+//! binder names `[Symbol.iterator]` members.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::symbol::INTERNAL_SYMBOL_NAME_PREFIX;
+use crate::compiler::ast::utilities::{
+    get_source_file_of_node, get_text_of_node, is_const_variable, number_to_string,
+    well_known_symbol_name,
+};
+use crate::compiler::ast::{Node, SymbolId, SymbolTable, SyntaxKind};
+use crate::compiler::binder::{has_dynamic_name, is_entity_name_expression};
+use crate::compiler::diagnostics;
+
+use super::ambient::get_first_token_range;
+use super::names::excluded_symbol_flags;
+use super::{Checker, LiteralValue, ObjectFlags, TypeData, TypeFlags, TypeId};
+
+impl Checker {
+    /// Gets the type of the symbol a declaration holds: a unique symbol of its own when the
+    /// declaration can hold one, and `symbol` otherwise
+    pub(super) fn get_es_symbol_like_type_for_node(&mut self, node: &Arc<Node>) -> TypeId {
+        if !is_valid_es_symbol_declaration(node) {
+            return self.es_symbol_type;
+        }
+        let Some(symbol) = self.get_symbol_of_declaration(node) else {
+            return self.es_symbol_type;
+        };
+        if let Some(type_) = self.symbol_links(symbol).unique_es_symbol_type {
+            return type_;
+        }
+        let name = self.symbol(symbol).name.clone();
+        let escaped_name = if is_well_known_symbol_declaration(node) {
+            well_known_symbol_name(&name)
+        } else {
+            format!("{INTERNAL_SYMBOL_NAME_PREFIX}@{name}@{}", symbol.value())
+        };
+        let type_ = self.create_type(
+            TypeFlags::UNIQUE_ES_SYMBOL,
+            ObjectFlags::NONE,
+            Some(symbol),
+            TypeData::UniqueESSymbol { escaped_name },
+        );
+        self.symbol_links(symbol).unique_es_symbol_type = Some(type_);
+        type_
+    }
+
+    /// Gets the type of `unique symbol` as written in a type annotation
+    pub(super) fn get_type_from_unique_type_operator(&mut self, node: &Arc<Node>) -> TypeId {
+        if node.as_type_operator_node().type_node.kind != SyntaxKind::SymbolKeyword {
+            return self.error_type;
+        }
+        match walk_up_parenthesized_types(node) {
+            Some(declaration) => self.get_es_symbol_like_type_for_node(&declaration),
+            None => self.es_symbol_type,
+        }
+    }
+
+    /// Widens the unique symbols in a type to `symbol`, as a mutable location holds any
+    /// symbol
+    pub(super) fn get_widened_unique_es_symbol_type(&mut self, type_: TypeId) -> TypeId {
+        let flags = self.flags(type_);
+        if flags.contains(TypeFlags::UNIQUE_ES_SYMBOL) {
+            return self.es_symbol_type;
+        }
+        if flags.contains(TypeFlags::UNION)
+            && let TypeData::UnionOrIntersection { types } = self.get_type(type_).data.clone()
+        {
+            let widened: Vec<TypeId> = types
+                .into_iter()
+                .map(|type_| self.get_widened_unique_es_symbol_type(type_))
+                .collect();
+            return self.get_union_type(&widened);
+        }
+        type_
+    }
+
+    /// Gets the type of a call, which for a call of the global `Symbol` or `Symbol.for` is a
+    /// new unique symbol when the call initializes a declaration that can hold one
+    pub(super) fn get_type_of_es_symbol_call(
+        &mut self,
+        node: &Arc<Node>,
+        return_type: TypeId,
+    ) -> TypeId {
+        if !self
+            .flags(return_type)
+            .intersects(TypeFlags::ES_SYMBOL_LIKE)
+            || !self.is_symbol_or_symbol_for_call(node)
+        {
+            return return_type;
+        }
+        let mut parent = node.parent();
+        while let Some(node) = parent
+            .as_ref()
+            .filter(|node| node.kind == SyntaxKind::ParenthesizedExpression)
+        {
+            parent = node.parent();
+        }
+        match parent {
+            Some(parent) => self.get_es_symbol_like_type_for_node(&parent),
+            None => return_type,
+        }
+    }
+
+    /// Determines if a call calls the global `Symbol` or `Symbol.for`
+    fn is_symbol_or_symbol_for_call(&mut self, node: &Arc<Node>) -> bool {
+        let mut callee = &node.as_call_expression().expression;
+        if callee.kind == SyntaxKind::PropertyAccessExpression {
+            let access = callee.as_property_access_expression();
+            if access.name.text() != "for" {
+                return false;
+            }
+            callee = &access.expression;
+        }
+        if callee.kind != SyntaxKind::Identifier || callee.text() != "Symbol" {
+            return false;
+        }
+        let Some(&global) = self.globals.get("Symbol") else {
+            return false;
+        };
+        self.node_links(callee)
+            .resolved_symbol
+            .is_some_and(|symbol| self.get_merged_symbol(symbol) == self.get_merged_symbol(global))
+    }
+
+    /// Gets the name of the property a type names when used as a key: the text of a string
+    /// or number literal, or the name members keyed by a unique symbol are bound under
+    pub(super) fn get_property_name_from_type(&self, type_: TypeId) -> Option<String> {
+        match &self.get_type(type_).data {
+            TypeData::Literal {
+                value: LiteralValue::String(name),
+            } => Some(name.clone()),
+            TypeData::Literal {
+                value: LiteralValue::Number(number),
+            } => Some(number_to_string(number.0)),
+            TypeData::UniqueESSymbol { escaped_name } => Some(escaped_name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Gets the name a member with a computed name like `[key]` is bound under, if the name
+    /// is an entity name whose type can name a property
+    pub(super) fn get_late_bound_name(&mut self, name: &Arc<Node>) -> Option<String> {
+        if name.kind != SyntaxKind::ComputedPropertyName {
+            return None;
+        }
+        let expression = &name.as_computed_property_name().expression;
+        if !is_entity_name_expression(expression) {
+            return None;
+        }
+        let type_ = self.check_expression(expression);
+        self.get_property_name_from_type(type_)
+    }
+
+    /// Gets the members of a class, interface, or type literal that are named by computed
+    /// names bound late, or the static members of a class so named when `is_static`
+    pub(super) fn get_late_bound_members(
+        &mut self,
+        symbol: SymbolId,
+        is_static: bool,
+    ) -> SymbolTable {
+        let links = self.symbol_links(symbol);
+        let cached = if is_static {
+            &mut links.late_bound_exports
+        } else {
+            &mut links.late_bound_members
+        };
+        if let Some(table) = cached {
+            return table.clone();
+        }
+        // Names that refer to the members being bound see none of them
+        *cached = Some(SymbolTable::new());
+        let mut table = SymbolTable::new();
+        for declaration in self.symbol(symbol).declarations.clone() {
+            let Some(members) = declaration.members() else {
+                continue;
+            };
+            for member in members.iter() {
+                if member.has_modifier(SyntaxKind::StaticKeyword) != is_static
+                    || !has_dynamic_name(member)
+                {
+                    continue;
+                }
+                let Some(name) = member
+                    .name()
+                    .and_then(|name| self.get_late_bound_name(name))
+                else {
+                    continue;
+                };
+                let Some(member_symbol) = self.bindings.symbol_of_node(member) else {
+                    continue;
+                };
+                self.late_bind_member(&mut table, symbol, member_symbol, member, name);
+            }
+        }
+        let links = self.symbol_links(symbol);
+        if is_static {
+            links.late_bound_exports = Some(table.clone());
+        } else {
+            links.late_bound_members = Some(table.clone());
+        }
+        table
+    }
+
+    /// Binds a member under its late-bound name, merging it with a member already bound
+    /// under the name when their declarations can merge, like a `get` and `set` accessor
+    fn late_bind_member(
+        &mut self,
+        table: &mut SymbolTable,
+        parent: SymbolId,
+        member_symbol: SymbolId,
+        member: &Arc<Node>,
+        name: String,
+    ) {
+        let flags = self.symbol(member_symbol).flags;
+        let Some(&existing) = table.get(&name) else {
+            let symbol = &mut self.bindings.symbols[member_symbol];
+            symbol.name = name.clone();
+            symbol.parent = Some(parent);
+            table.insert(name, member_symbol);
+            return;
+        };
+        if self
+            .symbol(existing)
+            .flags
+            .intersects(excluded_symbol_flags(flags))
+        {
+            let declarations = self.symbol(existing).declarations.clone();
+            for declaration in declarations.iter().chain([member]) {
+                let name = declaration.name().unwrap_or(declaration);
+                let file = get_source_file_of_node(name);
+                let text = get_text_of_node(&file, name);
+                self.error(
+                    name,
+                    diagnostics::DUPLICATE_IDENTIFIER_0_2300,
+                    &[text.to_string()],
+                );
+            }
+            return;
+        }
+        let symbol = &mut self.bindings.symbols[existing];
+        symbol.flags |= flags;
+        symbol.declarations.push(Arc::clone(member));
+        self.merged_symbols.insert(member_symbol, existing);
+    }
+
+    /// Checks the computed name of a member, which a property of a class, or a member of an
+    /// interface or type literal, can only have if it is bound late
+    pub(super) fn check_computed_property_name_of_member(&mut self, node: &Arc<Node>) {
+        let Some(name) = node.name() else {
+            return;
+        };
+        if name.kind != SyntaxKind::ComputedPropertyName {
+            return;
+        }
+        self.check_expression(&name.as_computed_property_name().expression);
+        let Some(parent) = node.parent() else {
+            return;
+        };
+        let message = match (node.kind, parent.kind) {
+            (
+                SyntaxKind::PropertyDeclaration,
+                SyntaxKind::ClassDeclaration | SyntaxKind::ClassExpression,
+            ) => Some(diagnostics::A_COMPUTED_PROPERTY_NAME_IN_A_CLASS_PROPERTY_DECLARATION_MUST_HAVE_A_SIMPLE_LITERAL_TYPE_OR_A_UNIQUE_SYMBOL_TYPE_1166),
+            (
+                SyntaxKind::MethodDeclaration,
+                SyntaxKind::ClassDeclaration | SyntaxKind::ClassExpression,
+            ) if node.body().is_none() && !node.has_modifier(SyntaxKind::AbstractKeyword) => {
+                Some(diagnostics::A_COMPUTED_PROPERTY_NAME_IN_A_METHOD_OVERLOAD_MUST_REFER_TO_AN_EXPRESSION_WHOSE_TYPE_IS_A_LITERAL_TYPE_OR_A_UNIQUE_SYMBOL_TYPE_1168)
+            }
+            (
+                SyntaxKind::PropertySignature | SyntaxKind::MethodSignature,
+                SyntaxKind::InterfaceDeclaration,
+            ) => Some(diagnostics::A_COMPUTED_PROPERTY_NAME_IN_AN_INTERFACE_MUST_REFER_TO_AN_EXPRESSION_WHOSE_TYPE_IS_A_LITERAL_TYPE_OR_A_UNIQUE_SYMBOL_TYPE_1169),
+            (
+                SyntaxKind::PropertySignature | SyntaxKind::MethodSignature,
+                SyntaxKind::TypeLiteral,
+            ) => Some(diagnostics::A_COMPUTED_PROPERTY_NAME_IN_A_TYPE_LITERAL_MUST_REFER_TO_AN_EXPRESSION_WHOSE_TYPE_IS_A_LITERAL_TYPE_OR_A_UNIQUE_SYMBOL_TYPE_1170),
+            _ => None,
+        };
+        if !has_dynamic_name(node) {
+            return;
+        }
+        if self.get_late_bound_name(name).is_none() {
+            if let Some(message) = message {
+                self.error(name, message, &[]);
+            }
+        } else if let Some(parent_symbol) = self.get_symbol_of_declaration(&parent) {
+            // Binds the member now, which reports it if it conflicts with another
+            let is_static = node.has_modifier(SyntaxKind::StaticKeyword);
+            self.get_late_bound_members(parent_symbol, is_static);
+        }
+    }
+
+    /// Checks a type operator, reporting a `unique symbol` anywhere but the annotation of a
+    /// declaration that can hold one, and a `readonly` on anything but an array or tuple type
+    pub(super) fn check_type_operator(&mut self, node: &Arc<Node>) {
+        let operator = node.as_type_operator_node();
+        match operator.operator {
+            SyntaxKind::UniqueKeyword => self.check_grammar_unique_symbol(node),
+            SyntaxKind::ReadonlyKeyword
+                if !matches!(
+                    operator.type_node.kind,
+                    SyntaxKind::ArrayType | SyntaxKind::TupleType
+                ) =>
+            {
+                self.error_at_range(
+                    node,
+                    get_first_token_range(node),
+                    diagnostics::READONLY_TYPE_MODIFIER_IS_ONLY_PERMITTED_ON_ARRAY_AND_TUPLE_LITERAL_TYPES_1354,
+                    &[],
+                );
+            }
+            _ => {}
+        }
+        self.check_type_node(&operator.type_node);
+    }
+
+    fn check_grammar_unique_symbol(&mut self, node: &Arc<Node>) {
+        let type_node = &node.as_type_operator_node().type_node;
+        if type_node.kind != SyntaxKind::SymbolKeyword {
+            self.error(
+                type_node,
+                diagnostics::D_0_EXPECTED_1005,
+                &["symbol".to_string()],
+            );
+            return;
+        }
+        let Some(parent) = walk_up_parenthesized_types(node) else {
+            return;
+        };
+        let name = parent
+            .name()
+            .cloned()
+            .unwrap_or_else(|| Arc::clone(&parent));
+        match parent.kind {
+            SyntaxKind::VariableDeclaration => {
+                if name.kind != SyntaxKind::Identifier {
+                    self.error(
+                        node,
+                        diagnostics::UNIQUE_SYMBOL_TYPES_MAY_NOT_BE_USED_ON_A_VARIABLE_DECLARATION_WITH_A_BINDING_NAME_1333,
+                        &[],
+                    );
+                } else if !is_variable_declaration_in_variable_statement(&parent) {
+                    self.error(
+                        node,
+                        diagnostics::UNIQUE_SYMBOL_TYPES_ARE_ONLY_ALLOWED_ON_VARIABLES_IN_A_VARIABLE_STATEMENT_1334,
+                        &[],
+                    );
+                } else if !is_const_variable(&parent) {
+                    self.error(
+                        &name,
+                        diagnostics::A_VARIABLE_WHOSE_TYPE_IS_A_UNIQUE_SYMBOL_TYPE_MUST_BE_CONST_1332,
+                        &[],
+                    );
+                }
+            }
+            SyntaxKind::PropertyDeclaration => {
+                if !parent.has_modifier(SyntaxKind::StaticKeyword)
+                    || !parent.has_modifier(SyntaxKind::ReadonlyKeyword)
+                {
+                    self.error(
+                        &name,
+                        diagnostics::A_PROPERTY_OF_A_CLASS_WHOSE_TYPE_IS_A_UNIQUE_SYMBOL_TYPE_MUST_BE_BOTH_STATIC_AND_READONLY_1331,
+                        &[],
+                    );
+                }
+            }
+            SyntaxKind::PropertySignature => {
+                if !parent.has_modifier(SyntaxKind::ReadonlyKeyword) {
+                    self.error(
+                        &name,
+                        diagnostics::A_PROPERTY_OF_AN_INTERFACE_OR_TYPE_LITERAL_WHOSE_TYPE_IS_A_UNIQUE_SYMBOL_TYPE_MUST_BE_READONLY_1330,
+                        &[],
+                    );
+                }
+            }
+            _ => self.error(
+                node,
+                diagnostics::UNIQUE_SYMBOL_TYPES_ARE_NOT_ALLOWED_HERE_1335,
+                &[],
+            ),
+        }
+    }
+}
+
+/// Gets the node a type annotation belongs to, looking through the parentheses around it
+fn walk_up_parenthesized_types(node: &Arc<Node>) -> Option<Arc<Node>> {
+    let mut parent = node.parent()?;
+    while parent.kind == SyntaxKind::ParenthesizedType {
+        parent = parent.parent()?;
+    }
+    Some(parent)
+}
+
+/// Determines if a declaration can hold a unique symbol: a const variable of a variable
+/// statement, a readonly static property, or a readonly property signature
+fn is_valid_es_symbol_declaration(node: &Arc<Node>) -> bool {
+    match node.kind {
+        SyntaxKind::VariableDeclaration => {
+            is_const_variable(node)
+                && node
+                    .name()
+                    .is_some_and(|name| name.kind == SyntaxKind::Identifier)
+                && is_variable_declaration_in_variable_statement(node)
+        }
+        SyntaxKind::PropertyDeclaration => {
+            node.has_modifier(SyntaxKind::ReadonlyKeyword)
+                && node.has_modifier(SyntaxKind::StaticKeyword)
+        }
+        SyntaxKind::PropertySignature => node.has_modifier(SyntaxKind::ReadonlyKeyword),
+        _ => false,
+    }
+}
+
+/// Determines if a declaration is a property of `SymbolConstructor`, which declares the
+/// well-known symbols
+fn is_well_known_symbol_declaration(node: &Node) -> bool {
+    node.kind == SyntaxKind::PropertySignature
+        && node.parent().is_some_and(|parent| {
+            parent.kind == SyntaxKind::InterfaceDeclaration
+                && parent
+                    .name()
+                    .is_some_and(|name| name.text() == "SymbolConstructor")
+        })
+}
+
+fn is_variable_declaration_in_variable_statement(node: &Node) -> bool {
+    node.parent()
+        .and_then(|list| list.parent())
+        .is_some_and(|statement| statement.kind == SyntaxKind::VariableStatement)
+}
@@ -0,0 +1,512 @@
+//! Reporting of declarations that are never read, under `noUnusedLocals` and
+//! `noUnusedParameters`
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::{
+    has_export_modifier, is_class_like, is_external_module, is_parameter_property_declaration,
+    root_declaration,
+};
+use crate::compiler::ast::visitor::for_each_descendant;
+use crate::compiler::ast::{Node, NodeFlags, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics::{self, Message};
+
+use super::Checker;
+use super::flow::is_write_only_access;
+
+/// Unused declarations that are reported together when all of their siblings are unused too,
+/// each keyed by the node that contains the siblings
+#[derive(Default)]
+struct UnusedGroups {
+    /// Import clauses and their unused bindings
+    imports: Vec<(Arc<Node>, Vec<Arc<Node>>)>,
+    /// Binding patterns and their unused elements
+    destructures: Vec<(Arc<Node>, Vec<Arc<Node>>)>,
+    /// Variable declaration lists and their unused declarations
+    variables: Vec<(Arc<Node>, Vec<Arc<Node>>)>,
+}
+
+fn add_to_group(groups: &mut Vec<(Arc<Node>, Vec<Arc<Node>>)>, key: Arc<Node>, node: Arc<Node>) {
+    match groups
+        .iter_mut()
+        .find(|(existing, _)| Arc::ptr_eq(existing, &key))
+    {
+        Some((_, nodes)) => nodes.push(node),
+        None => groups.push((key, vec![node])),
+    }
+}
+
+impl Checker {
+    /// Records that `symbol` is read at `location`, unless `location` is inside the
+    /// declaration of `symbol` itself, as in a recursive call
+    pub(super) fn mark_symbol_referenced(&mut self, symbol: SymbolId, location: &Arc<Node>) {
+        let mut current = location.parent();
+        while let Some(node) = current {
+            if matches!(
+                node.kind,
+                SyntaxKind::FunctionDeclaration
+                    | SyntaxKind::ClassDeclaration
+                    | SyntaxKind::InterfaceDeclaration
+                    | SyntaxKind::EnumDeclaration
+                    | SyntaxKind::TypeAliasDeclaration
+                    | SyntaxKind::ModuleDeclaration
+            ) && self.get_symbol_of_declaration(&node) == Some(symbol)
+            {
+                return;
+            }
+            current = node.parent();
+        }
+        self.symbol_links(symbol).referenced = true;
+    }
+
+    /// Records that a property is read by the property access or element access `node`,
+    /// along with the property it was instantiated from
+    ///
+    /// Writes only count for properties with setters, since a setter runs code.
+    pub(super) fn mark_property_referenced(&mut self, node: &Arc<Node>, property: SymbolId) {
+        if is_write_only_access(node)
+            && !self
+                .symbol(property)
+                .flags
+                .contains(SymbolFlags::SET_ACCESSOR)
+        {
+            return;
+        }
+        let mut property = property;
+        loop {
+            let links = self.symbol_links(property);
+            links.referenced = true;
+            match links.target {
+                Some(target) if target != property => property = target,
+                _ => break,
+            }
+        }
+    }
+
+    fn is_referenced(&self, symbol: SymbolId) -> bool {
+        self.symbol_links
+            .get(&symbol)
+            .is_some_and(|links| links.referenced)
+    }
+
+    /// Reports the locals, parameters, imports, and private class members of a file that are
+    /// never read
+    pub(super) fn check_unused_identifiers(&mut self, file: &Arc<Node>) {
+        if file.as_source_file().is_declaration_file {
+            return;
+        }
+        // The top-level declarations of scripts are global, so other files may read them
+        let mut containers = Vec::new();
+        if is_external_module(file) {
+            containers.push(Arc::clone(file));
+        }
+        for_each_descendant(file, &mut |node| {
+            if !node.flags.contains(NodeFlags::AMBIENT) && is_potentially_unused_container(node) {
+                containers.push(Arc::clone(node));
+            }
+            false
+        });
+        let mut groups = UnusedGroups::default();
+        for container in &containers {
+            if is_class_like(container) {
+                self.check_unused_class_members(container);
+            } else {
+                self.check_unused_locals_and_parameters(container, &mut groups);
+            }
+        }
+        self.report_unused_groups(groups);
+    }
+
+    fn check_unused_locals_and_parameters(
+        &mut self,
+        container: &Arc<Node>,
+        groups: &mut UnusedGroups,
+    ) {
+        let Some(locals) = self.bindings.locals(container) else {
+            return;
+        };
+        let mut locals: Vec<SymbolId> = locals.values().copied().collect();
+        locals.sort();
+        for local in locals {
+            let symbol = self.symbol(local);
+            if symbol.flags.contains(SymbolFlags::TYPE_PARAMETER)
+                || symbol.export_symbol.is_some()
+                || self.is_referenced(local)
+                || self.is_referenced(self.get_merged_symbol(local))
+            {
+                continue;
+            }
+            let name = symbol.name.clone();
+            for declaration in symbol.declarations.clone() {
+                if has_export_modifier(&declaration)
+                    || is_valid_unused_local_declaration(&declaration)
+                {
+                    continue;
+                }
+                match declaration.kind {
+                    SyntaxKind::ImportClause
+                    | SyntaxKind::ImportSpecifier
+                    | SyntaxKind::NamespaceImport => {
+                        let clause = import_clause_of_imported(&declaration);
+                        add_to_group(&mut groups.imports, clause, declaration);
+                    }
+                    SyntaxKind::BindingElement
+                        if declaration.parent().is_some_and(|pattern| {
+                            pattern.kind == SyntaxKind::ObjectBindingPattern
+                        }) =>
+                    {
+                        let pattern = declaration.parent().expect("binding element has a pattern");
+                        // Binding an element removes it from a rest element after it, so it is
+                        // used unless it is the rest element
+                        let elements = &pattern.as_object_binding_pattern().elements;
+                        let last = elements.nodes.last().expect("pattern has an element");
+                        if Arc::ptr_eq(last, &declaration)
+                            || last.as_binding_element().dot_dot_dot_token.is_none()
+                        {
+                            add_to_group(&mut groups.destructures, pattern, declaration);
+                        }
+                    }
+                    SyntaxKind::VariableDeclaration
+                        if declaration.parent().is_some_and(|list| {
+                            list.kind == SyntaxKind::VariableDeclarationList
+                        }) =>
+                    {
+                        let list = declaration.parent().expect("declaration has a list");
+                        add_to_group(&mut groups.variables, list, declaration);
+                    }
+                    _ => {
+                        let root = root_declaration(&declaration);
+                        if root.kind != SyntaxKind::Parameter {
+                            self.report_unused_local(&declaration, &name);
+                            continue;
+                        }
+                        let parameter_name = declaration.name().expect("parameters have names");
+                        if is_parameter_property_declaration(&root)
+                            || name == "this"
+                            || parameter_name.text().starts_with('_')
+                        {
+                            continue;
+                        }
+                        if declaration.kind == SyntaxKind::BindingElement {
+                            let pattern =
+                                declaration.parent().expect("binding element has a pattern");
+                            add_to_group(&mut groups.destructures, pattern, declaration);
+                        } else {
+                            self.report_unused(
+                                parameter_name,
+                                true,
+                                diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133,
+                                std::slice::from_ref(&name),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reports a local declaration that is not a variable, a parameter, or an import
+    fn report_unused_local(&mut self, declaration: &Arc<Node>, name: &str) {
+        let message = match declaration.kind {
+            SyntaxKind::ClassDeclaration
+            | SyntaxKind::InterfaceDeclaration
+            | SyntaxKind::TypeAliasDeclaration
+            | SyntaxKind::EnumDeclaration => diagnostics::D_0_IS_DECLARED_BUT_NEVER_USED_6196,
+            _ => diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133,
+        };
+        let node = declaration.name().unwrap_or(declaration);
+        self.report_unused(node, false, message, &[name.to_string()]);
+    }
+
+    /// Reports the private members of a class, and the private parameter properties of its
+    /// constructor, that are never read
+    fn check_unused_class_members(&mut self, class: &Arc<Node>) {
+        if !self.options.no_unused_locals {
+            return;
+        }
+        for member in class.members().into_iter().flatten() {
+            match member.kind {
+                SyntaxKind::MethodDeclaration
+                | SyntaxKind::PropertyDeclaration
+                | SyntaxKind::GetAccessor
+                | SyntaxKind::SetAccessor => {
+                    let Some(symbol) = self.get_symbol_of_declaration(member) else {
+                        continue;
+                    };
+                    // An accessor pair is reported once, on its getter
+                    if member.kind == SyntaxKind::SetAccessor
+                        && self
+                            .symbol(symbol)
+                            .flags
+                            .contains(SymbolFlags::GET_ACCESSOR)
+                    {
+                        continue;
+                    }
+                    let Some(name) = member.name() else {
+                        continue;
+                    };
+                    let is_private = member.has_modifier(SyntaxKind::PrivateKeyword)
+                        || name.kind == SyntaxKind::PrivateIdentifier;
+                    if is_private && !self.is_referenced(symbol) {
+                        let text = self.symbol(symbol).name.clone();
+                        self.error(
+                            name,
+                            diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133,
+                            &[text],
+                        );
+                    }
+                }
+                SyntaxKind::Constructor => {
+                    for parameter in member.parameters().into_iter().flatten() {
+                        if !parameter.has_modifier(SyntaxKind::PrivateKeyword) {
+                            continue;
+                        }
+                        let name = parameter.name().expect("parameters have names");
+                        if name.kind != SyntaxKind::Identifier {
+                            continue;
+                        }
+                        // The parameter is read either by name in the constructor or as a
+                        // property of the instance
+                        let local = self
+                            .bindings
+                            .locals(member)
+                            .and_then(|locals| locals.get(name.text()).copied());
+                        let property = self
+                            .get_symbol_of_declaration(class)
+                            .and_then(|class| self.symbol(class).members.get(name.text()).copied());
+                        let referenced = local
+                            .into_iter()
+                            .chain(property)
+                            .any(|symbol| self.is_referenced(symbol));
+                        if !referenced {
+                            self.error(
+                                name,
+                                diagnostics::PROPERTY_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6138,
+                                &[name.text().to_string()],
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reports the unused imports, destructured elements, and variables collected while
+    /// checking locals, together when none of their siblings are used
+    fn report_unused_groups(&mut self, groups: UnusedGroups) {
+        for (clause, unused) in groups.imports {
+            let declaration = clause.parent().expect("import clause has a declaration");
+            if import_binding_count(&clause) == unused.len() {
+                if let [binding] = unused.as_slice() {
+                    let name = binding
+                        .name()
+                        .expect("imports have names")
+                        .text()
+                        .to_string();
+                    self.report_unused(
+                        &declaration,
+                        false,
+                        diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133,
+                        &[name],
+                    );
+                } else {
+                    self.report_unused(
+                        &declaration,
+                        false,
+                        diagnostics::ALL_IMPORTS_IN_IMPORT_DECLARATION_ARE_UNUSED_6192,
+                        &[],
+                    );
+                }
+            } else {
+                for binding in unused {
+                    let name = binding.name().expect("imports have names");
+                    self.report_unused(
+                        name,
+                        false,
+                        diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133,
+                        &[name.text().to_string()],
+                    );
+                }
+            }
+        }
+        for (pattern, unused) in groups.destructures {
+            let is_parameter = root_declaration(&unused[0]).kind == SyntaxKind::Parameter;
+            let element_count = match pattern.kind {
+                SyntaxKind::ObjectBindingPattern => {
+                    pattern.as_object_binding_pattern().elements.len()
+                }
+                _ => pattern.as_array_binding_pattern().elements.len(),
+            };
+            if element_count == unused.len() {
+                if let [element] = unused.as_slice() {
+                    let name = element.name().expect("binding elements have names");
+                    self.report_unused(
+                        &pattern,
+                        is_parameter,
+                        diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133,
+                        &[name.text().to_string()],
+                    );
+                } else {
+                    self.report_unused(
+                        &pattern,
+                        is_parameter,
+                        diagnostics::ALL_DESTRUCTURED_ELEMENTS_ARE_UNUSED_6198,
+                        &[],
+                    );
+                }
+            } else {
+                for element in unused {
+                    let name = element.name().expect("binding elements have names");
+                    self.report_unused(
+                        &element,
+                        is_parameter,
+                        diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133,
+                        &[name.text().to_string()],
+                    );
+                }
+            }
+        }
+        for (list, unused) in groups.variables {
+            let declaration_count = list.as_variable_declaration_list().declarations.len();
+            if declaration_count == unused.len() {
+                if let [declaration] = unused.as_slice() {
+                    let name = declaration.name().expect("variables have names");
+                    self.report_unused(
+                        name,
+                        false,
+                        diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133,
+                        &[name.text().to_string()],
+                    );
+                } else {
+                    let node = match list.parent() {
+                        Some(statement) if statement.kind == SyntaxKind::VariableStatement => {
+                            statement
+                        }
+                        _ => Arc::clone(&list),
+                    };
+                    self.report_unused(
+                        &node,
+                        false,
+                        diagnostics::ALL_VARIABLES_ARE_UNUSED_6199,
+                        &[],
+                    );
+                }
+            } else {
+                for declaration in unused {
+                    let name = declaration.name().expect("variables have names");
+                    self.report_unused(
+                        &declaration,
+                        false,
+                        diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133,
+                        &[name.text().to_string()],
+                    );
+                }
+            }
+        }
+    }
+
+    /// Reports an unused declaration if the option for its kind is enabled
+    fn report_unused(
+        &mut self,
+        node: &Arc<Node>,
+        is_parameter: bool,
+        message: &Message,
+        args: &[String],
+    ) {
+        let enabled = if is_parameter {
+            self.options.no_unused_parameters
+        } else {
+            self.options.no_unused_locals
+        };
+        if enabled {
+            self.error(node, message, args);
+        }
+    }
+}
+
+/// Determines if `node` has locals or members whose uses are checked once the file is checked
+fn is_potentially_unused_container(node: &Arc<Node>) -> bool {
+    match node.kind {
+        SyntaxKind::ModuleDeclaration
+        | SyntaxKind::Block
+        | SyntaxKind::CaseBlock
+        | SyntaxKind::ForStatement
+        | SyntaxKind::ForInStatement
+        | SyntaxKind::ForOfStatement
+        | SyntaxKind::ClassDeclaration
+        | SyntaxKind::ClassExpression => true,
+        // Parameters of signatures without bodies cannot be read
+        SyntaxKind::FunctionDeclaration
+        | SyntaxKind::FunctionExpression
+        | SyntaxKind::ArrowFunction
+        | SyntaxKind::MethodDeclaration
+        | SyntaxKind::Constructor
+        | SyntaxKind::GetAccessor
+        | SyntaxKind::SetAccessor => node.body().is_some(),
+        _ => false,
+    }
+}
+
+/// Determines if an unused declaration is allowed: an ambient module, a destructured element
+/// renamed to a name starting with `_`, or a `for`-`in` or `for`-`of` variable or an import
+/// whose name starts with `_`
+fn is_valid_unused_local_declaration(declaration: &Arc<Node>) -> bool {
+    let name_starts_with_underscore = declaration
+        .name()
+        .is_some_and(|name| name.kind == SyntaxKind::Identifier && name.text().starts_with('_'));
+    match declaration.kind {
+        SyntaxKind::BindingElement => {
+            let in_object_pattern = declaration
+                .parent()
+                .is_some_and(|pattern| pattern.kind == SyntaxKind::ObjectBindingPattern);
+            if in_object_pattern {
+                declaration.as_binding_element().property_name.is_some()
+                    && name_starts_with_underscore
+            } else {
+                name_starts_with_underscore
+            }
+        }
+        SyntaxKind::ModuleDeclaration => declaration
+            .name()
+            .is_some_and(|name| name.kind == SyntaxKind::StringLiteral),
+        SyntaxKind::VariableDeclaration => {
+            let in_for_in_or_of = declaration
+                .parent()
+                .and_then(|list| list.parent())
+                .is_some_and(|statement| {
+                    matches!(
+                        statement.kind,
+                        SyntaxKind::ForInStatement | SyntaxKind::ForOfStatement
+                    )
+                });
+            in_for_in_or_of && name_starts_with_underscore
+        }
+        SyntaxKind::ImportClause | SyntaxKind::ImportSpecifier | SyntaxKind::NamespaceImport => {
+            name_starts_with_underscore
+        }
+        _ => false,
+    }
+}
+
+/// Gets the import clause an import clause, namespace import, or import specifier belongs to
+fn import_clause_of_imported(declaration: &Arc<Node>) -> Arc<Node> {
+    let mut current = Arc::clone(declaration);
+    while current.kind != SyntaxKind::ImportClause {
+        current = current.parent().expect("imports are in an import clause");
+    }
+    current
+}
+
+/// Counts the names an import clause declares
+fn import_binding_count(clause: &Arc<Node>) -> usize {
+    let clause = clause.as_import_clause();
+    let named_count = clause
+        .named_bindings
+        .as_ref()
+        .map_or(0, |bindings| match bindings.kind {
+            SyntaxKind::NamedImports => bindings.as_named_imports().elements.len(),
+            _ => 1,
+        });
+    usize::from(clause.name.is_some()) + named_count
+}
@@ -0,0 +1,290 @@
+//! Naming symbols from a location, and checking whether declaration emit can name them there
+
+use std::sync::Arc;
+
+use crate::compiler::ast::utilities::get_source_file_of_node;
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::binder::remove_file_extension;
+use crate::compiler::scanner::{ScriptTarget, is_valid_identifier};
+
+use super::Checker;
+
+/// Whether a symbol can be named from a location
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolAccessibility {
+    /// The symbol, or a chain of namespaces leading to it, is in scope, or it is exported from
+    /// a module that can be imported
+    Accessible,
+    /// The symbol has a name, but it is not visible from the location
+    NotAccessible,
+    /// The symbol has no name to refer to it by, like an object literal type
+    CannotBeNamed,
+}
+
+/// The result of checking whether a symbol can be named from a location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolAccessibilityResult {
+    pub accessibility: SymbolAccessibility,
+    /// The name of the symbol that is not accessible
+    pub error_symbol_name: Option<String>,
+    /// The module that declares the symbol without exporting it
+    pub error_module_name: Option<String>,
+}
+
+impl SymbolAccessibilityResult {
+    fn accessible() -> Self {
+        SymbolAccessibilityResult {
+            accessibility: SymbolAccessibility::Accessible,
+            error_symbol_name: None,
+            error_module_name: None,
+        }
+    }
+}
+
+/// A link in the chain of symbols a qualified name is made of
+enum ChainLink {
+    /// A symbol written by name
+    Symbol(SymbolId),
+    /// A module written as `import("specifier")`
+    Import(String),
+}
+
+impl Checker {
+    /// Prints the name of a symbol, qualified by the namespaces, classes, and enums it is a
+    /// member of, as in `N.C.m`
+    ///
+    /// With an `enclosing_declaration`, qualification stops at the first symbol that is in
+    /// scope there, and a symbol exported from another module that is not imported there is
+    /// qualified by an `import("./module")` type.
+    pub fn symbol_to_string(
+        &mut self,
+        symbol: SymbolId,
+        enclosing_declaration: Option<&Arc<Node>>,
+        meaning: SymbolFlags,
+    ) -> String {
+        let chain = self.get_symbol_chain(symbol, enclosing_declaration, meaning);
+        let mut out = String::new();
+        for (index, link) in chain.iter().enumerate() {
+            match link {
+                ChainLink::Import(specifier) => {
+                    out.push_str("import(\"");
+                    out.push_str(specifier);
+                    out.push_str("\")");
+                }
+                ChainLink::Symbol(symbol) => {
+                    let name = &self.symbol(*symbol).name;
+                    if index == 0 {
+                        out.push_str(name);
+                    } else if is_valid_identifier(name, ScriptTarget::ESNext) {
+                        out.push('.');
+                        out.push_str(name);
+                    } else {
+                        out.push_str("[\"");
+                        out.push_str(name);
+                        out.push_str("\"]");
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Builds the chain of symbols that names `symbol`, outermost first
+    fn get_symbol_chain(
+        &mut self,
+        symbol: SymbolId,
+        enclosing_declaration: Option<&Arc<Node>>,
+        meaning: SymbolFlags,
+    ) -> Vec<ChainLink> {
+        let mut chain = Vec::new();
+        let mut current = symbol;
+        let mut meaning = meaning;
+        loop {
+            if let Some(enclosing) = enclosing_declaration
+                && let Some(accessible) = self.get_accessible_symbol(current, enclosing, meaning)
+            {
+                chain.push(ChainLink::Symbol(accessible));
+                break;
+            }
+            chain.push(ChainLink::Symbol(current));
+            let Some(parent) = self.get_qualifying_parent(current) else {
+                break;
+            };
+            if self.is_external_module_symbol(parent) {
+                if let Some(enclosing) = enclosing_declaration {
+                    let file = get_source_file_of_node(enclosing);
+                    if let Some(import) = self.get_alias_to_module(&file, parent) {
+                        chain.push(ChainLink::Symbol(import));
+                    } else if let Some(specifier) = self.get_module_specifier(&file, parent) {
+                        chain.push(ChainLink::Import(specifier));
+                    }
+                }
+                break;
+            }
+            current = parent;
+            meaning = SymbolFlags::NAMESPACE;
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// Checks whether `symbol` can be named at `enclosing_declaration`, either because it or
+    /// a namespace it is exported from is in scope, or because it is exported from a module
+    /// that declaration emit can import
+    pub fn is_symbol_accessible(
+        &mut self,
+        symbol: SymbolId,
+        enclosing_declaration: &Arc<Node>,
+        meaning: SymbolFlags,
+    ) -> SymbolAccessibilityResult {
+        if self.symbol(symbol).name.starts_with("__") {
+            return SymbolAccessibilityResult {
+                accessibility: SymbolAccessibility::CannotBeNamed,
+                error_symbol_name: None,
+                error_module_name: None,
+            };
+        }
+        let mut current = symbol;
+        let mut current_meaning = meaning;
+        loop {
+            if self
+                .get_accessible_symbol(current, enclosing_declaration, current_meaning)
+                .is_some()
+            {
+                return SymbolAccessibilityResult::accessible();
+            }
+            let Some(parent) = self.get_qualifying_parent(current) else {
+                break;
+            };
+            if !self.is_exported_from(current, parent) {
+                break;
+            }
+            if self.is_external_module_symbol(parent) {
+                return SymbolAccessibilityResult::accessible();
+            }
+            current = parent;
+            current_meaning = SymbolFlags::NAMESPACE;
+        }
+        let error_module_name = self
+            .get_qualifying_parent(current)
+            .filter(|&parent| self.is_external_module_symbol(parent))
+            .map(|module| self.symbol(module).name.clone());
+        SymbolAccessibilityResult {
+            accessibility: SymbolAccessibility::NotAccessible,
+            error_symbol_name: Some(self.symbol_to_string(
+                symbol,
+                Some(enclosing_declaration),
+                meaning,
+            )),
+            error_module_name,
+        }
+    }
+
+    /// Gets the symbol, either `symbol` itself or an alias to it, that its name resolves to
+    /// at `location`
+    fn get_accessible_symbol(
+        &mut self,
+        symbol: SymbolId,
+        location: &Arc<Node>,
+        meaning: SymbolFlags,
+    ) -> Option<SymbolId> {
+        let name = self.symbol(symbol).name.clone();
+        if name.starts_with('"') {
+            return None;
+        }
+        let resolved = self.resolve_name(location, &name, meaning)?;
+        let resolved = self.get_merged_symbol(resolved);
+        let target = self.resolve_alias_if_alias(resolved);
+        let target = self.get_export_symbol_of_value_symbol_if_exported(target);
+        (target == self.get_merged_symbol(symbol)).then_some(resolved)
+    }
+
+    /// Gets the symbol whose name qualifies the name of `symbol`: the namespace, enum, class,
+    /// or module it is a member or export of
+    fn get_qualifying_parent(&self, symbol: SymbolId) -> Option<SymbolId> {
+        let symbol = self.symbol(symbol);
+        if symbol.flags.contains(SymbolFlags::TYPE_PARAMETER) {
+            return None;
+        }
+        let parent = self.get_merged_symbol(symbol.parent?);
+        let flags = self.symbol(parent).flags;
+        flags
+            .intersects(
+                SymbolFlags::NAMESPACE
+                    | SymbolFlags::ENUM
+                    | SymbolFlags::CLASS
+                    | SymbolFlags::INTERFACE,
+            )
+            .then_some(parent)
+    }
+
+    fn is_exported_from(&self, symbol: SymbolId, parent: SymbolId) -> bool {
+        let parent = self.symbol(parent);
+        let name = &self.symbol(symbol).name;
+        [&parent.exports, &parent.members].into_iter().any(|table| {
+            table
+                .get(name)
+                .is_some_and(|&member| self.get_merged_symbol(member) == symbol)
+        })
+    }
+
+    fn is_external_module_symbol(&self, symbol: SymbolId) -> bool {
+        self.symbol(symbol)
+            .declarations
+            .first()
+            .is_some_and(|declaration| declaration.kind == SyntaxKind::SourceFile)
+    }
+
+    /// Finds an import in `file` that refers to the module `module`, like
+    /// `import * as m from "./m"`
+    fn get_alias_to_module(&mut self, file: &Arc<Node>, module: SymbolId) -> Option<SymbolId> {
+        let mut locals: Vec<SymbolId> = self.bindings.locals(file)?.values().copied().collect();
+        locals.sort();
+        locals.into_iter().find(|&local| {
+            self.symbol(local).flags.contains(SymbolFlags::ALIAS)
+                && self.resolve_alias(local) == module
+        })
+    }
+
+    /// Gets the relative module specifier that imports the module `module` from `file`, or
+    /// `None` if `module` is declared in `file`
+    fn get_module_specifier(&self, file: &Arc<Node>, module: SymbolId) -> Option<String> {
+        let declaration = self.symbol(module).declarations.first()?;
+        if Arc::ptr_eq(declaration, file) {
+            return None;
+        }
+        let from = &file.as_source_file().file_name;
+        let to = remove_file_extension(&declaration.as_source_file().file_name);
+        Some(get_relative_module_specifier(from, to))
+    }
+}
+
+/// Gets the path of `to` relative to the directory of the file `from`, starting with `./` or
+/// `../` as module specifiers do
+fn get_relative_module_specifier(from: &str, to: &str) -> String {
+    let from_directory: Vec<&str> = match from.rsplit_once('/') {
+        Some((directory, _)) => directory.split('/').collect(),
+        None => Vec::new(),
+    };
+    let to_components: Vec<&str> = to.split('/').collect();
+    let (to_directory, to_file) = to_components.split_at(to_components.len() - 1);
+    let common = from_directory
+        .iter()
+        .zip(to_directory)
+        .take_while(|(from, to)| from == to)
+        .count();
+    let mut specifier = String::new();
+    if common == from_directory.len() {
+        specifier.push_str("./");
+    } else {
+        for _ in common..from_directory.len() {
+            specifier.push_str("../");
+        }
+    }
+    for component in &to_directory[common..] {
+        specifier.push_str(component);
+        specifier.push('/');
+    }
+    specifier.push_str(to_file[0]);
+    specifier
+}
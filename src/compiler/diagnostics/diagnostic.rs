@@ -0,0 +1,133 @@
+use crate::compiler::text::TextRange;
+
+use super::generated::{Category, Message};
+
+/// A diagnostic produced while parsing, binding, or checking a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The file the diagnostic was reported in, if any
+    pub file_name: Option<String>,
+    /// The location of the diagnostic within the file
+    pub loc: TextRange,
+    /// The numeric diagnostic code (the `TS1005` in `error TS1005`)
+    pub code: i32,
+    /// The diagnostic category
+    pub category: Category,
+    /// The formatted message text
+    pub message_text: String,
+    /// Additional locations that help explain this diagnostic
+    pub related_information: Vec<Diagnostic>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic for `message`, substituting `args` into its placeholders
+    pub fn new(
+        file_name: Option<&str>,
+        loc: TextRange,
+        message: &Message,
+        args: &[String],
+    ) -> Self {
+        Diagnostic {
+            file_name: file_name.map(str::to_string),
+            loc,
+            code: message.code(),
+            category: message.category(),
+            message_text: format_message(message, args),
+            related_information: Vec::new(),
+        }
+    }
+
+    /// Creates a diagnostic from a chain of messages, whose head gives the code and category
+    /// and whose details are indented beneath it
+    pub fn from_message_chain(
+        file_name: Option<&str>,
+        loc: TextRange,
+        chain: &DiagnosticMessageChain,
+    ) -> Self {
+        Diagnostic {
+            file_name: file_name.map(str::to_string),
+            loc,
+            code: chain.code,
+            category: chain.category,
+            message_text: chain.flatten(),
+            related_information: Vec::new(),
+        }
+    }
+
+    /// Gets the start position of the diagnostic
+    pub fn pos(&self) -> usize {
+        self.loc.pos()
+    }
+
+    /// Gets the length of the diagnostic span
+    pub fn len(&self) -> usize {
+        self.loc.len()
+    }
+
+    /// Checks if the diagnostic span is empty
+    pub fn is_empty(&self) -> bool {
+        self.loc.is_empty()
+    }
+
+    /// Attaches related information to this diagnostic
+    pub fn with_related_information(mut self, related: Diagnostic) -> Self {
+        self.related_information.push(related);
+        self
+    }
+}
+
+/// A message followed by the messages that explain it, such as the property and type that
+/// made an assignment fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticMessageChain {
+    pub message_text: String,
+    pub code: i32,
+    pub category: Category,
+    pub next: Vec<DiagnosticMessageChain>,
+}
+
+impl DiagnosticMessageChain {
+    /// Creates a chain of a single message
+    pub fn new(message: &Message, args: &[String]) -> Self {
+        DiagnosticMessageChain {
+            message_text: format_message(message, args),
+            code: message.code(),
+            category: message.category(),
+            next: Vec::new(),
+        }
+    }
+
+    /// Puts `message` at the head of the chain, explained by the existing chain if any
+    pub fn chain(details: Option<Self>, message: &Message, args: &[String]) -> Self {
+        let mut head = Self::new(message, args);
+        head.next.extend(details);
+        head
+    }
+
+    /// Joins the messages of the chain into one text, indenting each level by two spaces
+    pub fn flatten(&self) -> String {
+        let mut text = String::new();
+        self.flatten_into(&mut text, 0);
+        text
+    }
+
+    fn flatten_into(&self, text: &mut String, indent: usize) {
+        if indent > 0 {
+            text.push('\n');
+            text.push_str(&"  ".repeat(indent));
+        }
+        text.push_str(&self.message_text);
+        for next in &self.next {
+            next.flatten_into(text, indent + 1);
+        }
+    }
+}
+
+/// Formats a diagnostic message, replacing `{0}`, `{1}`, ... with the given arguments
+pub fn format_message(message: &Message, args: &[String]) -> String {
+    let mut text = message.message().to_string();
+    for (i, arg) in args.iter().enumerate() {
+        text = text.replace(&format!("{{{}}}", i), arg);
+    }
+    text
+}
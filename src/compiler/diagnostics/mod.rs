@@ -1,3 +1,5 @@
+pub mod diagnostic;
 pub mod generated;
 
+pub use diagnostic::*;
 pub use generated::*;
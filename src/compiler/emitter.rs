@@ -0,0 +1,321 @@
+//! Printing syntax trees as JavaScript
+//!
+//! The printer writes a tree back out as source text. It prints whatever syntax it is given,
+//! so TypeScript-only syntax that no transform has removed is printed as TypeScript. Nodes
+//! parsed from a source file keep the text of their identifiers and literals and the comments
+//! around them; synthesized nodes are printed from their data, with parentheses added wherever
+//! the shape of the tree differs from how its text would parse.
+
+mod comments;
+mod precedence;
+mod printer;
+mod source_map;
+mod writer;
+
+use std::sync::Arc;
+
+use crate::compiler::ast::{Node, SyntaxKind};
+
+use printer::Printer;
+
+pub use source_map::{Mapping, SourceMapGenerator};
+
+/// The line terminator written between lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NewLineKind {
+    #[default]
+    LineFeed,
+    CarriageReturnLineFeed,
+}
+
+impl NewLineKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NewLineKind::LineFeed => "\n",
+            NewLineKind::CarriageReturnLineFeed => "\r\n",
+        }
+    }
+}
+
+/// What each level of indentation is written as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Spaces(usize),
+    Tabs,
+}
+
+impl Default for IndentStyle {
+    fn default() -> Self {
+        IndentStyle::Spaces(4)
+    }
+}
+
+/// Options controlling how a tree is printed
+#[derive(Debug, Clone, Default)]
+pub struct PrinterOptions {
+    /// Drop comments, except `/*!` comments, which conventionally hold license text
+    pub remove_comments: bool,
+    pub new_line: NewLineKind,
+    pub indent_style: IndentStyle,
+}
+
+/// Prints a source file, ending with a line break unless it prints nothing
+///
+/// `source_file` may be the result of transforming a parsed file, as long as the nodes it
+/// keeps from the parse still refer to the file's text.
+pub fn print_file(source_file: &Arc<Node>, options: &PrinterOptions) -> String {
+    Printer::new(options, Some(source_file)).print_source_file(source_file)
+}
+
+/// Prints a source file as [`print_file`] does, also returning where each node parsed from the
+/// file was printed, for a source map
+pub fn print_file_with_mappings(
+    source_file: &Arc<Node>,
+    options: &PrinterOptions,
+) -> (String, Vec<Mapping>) {
+    Printer::new(options, Some(source_file))
+        .with_source_map()
+        .print_source_file_with_mappings(source_file)
+}
+
+/// Prints a single node, taking identifier and literal text and comments from `source_file`
+/// for the nodes that were parsed from it
+pub fn print_node(
+    node: &Arc<Node>,
+    source_file: Option<&Arc<Node>>,
+    options: &PrinterOptions,
+) -> String {
+    if node.kind == SyntaxKind::SourceFile {
+        return print_file(node, options);
+    }
+    Printer::new(options, source_file.map(|file| file.as_ref())).print(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::factory::NodeFactory;
+    use crate::compiler::ast::nodes::{
+        BinaryExpression, CallExpression, ExpressionStatement, PrefixUnaryExpression,
+        PropertyAccessExpression,
+    };
+    use crate::compiler::parser::parse_source_file;
+    use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+
+    fn print_with(text: &str, options: &PrinterOptions) -> String {
+        let file = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        print_file(&file, options)
+    }
+
+    fn print(text: &str) -> String {
+        print_with(text, &PrinterOptions::default())
+    }
+
+    #[test]
+    fn prints_statements_in_the_standard_layout() {
+        assert_eq!(
+            print("if (a) b(); else { c(); }\nfor (let i = 0; i < n; i++) {}\n"),
+            "if (a)\n    b();\nelse {\n    c();\n}\nfor (let i = 0; i < n; i++) { }\n"
+        );
+        assert_eq!(
+            print("try { a() } catch (e) { b() } finally { c() }"),
+            "try {\n    a();\n}\ncatch (e) {\n    b();\n}\nfinally {\n    c();\n}\n"
+        );
+        assert_eq!(
+            print("switch (x) { case 1: a(); break;\ncase 2:\n  b();\ndefault: c(); }"),
+            "switch (x) {\n    case 1:\n        a();\n        break;\n    case 2:\n        b();\n    default: c();\n}\n"
+        );
+        assert_eq!(
+            print("function f(a, ...b) { return a }\nclass C extends B {}\n"),
+            "function f(a, ...b) { return a; }\nclass C extends B {\n}\n"
+        );
+        assert_eq!(
+            print("const o = {a: 1, b,\n  ...c};\nconst p = {\n  x: [1, 2,],\n};\n"),
+            "const o = { a: 1, b, ...c };\nconst p = {\n    x: [1, 2,]\n};\n"
+        );
+        assert_eq!(
+            print("const f = x => x, g = (x) => ({ x }), h = async () => { await g(1); };"),
+            "const f = x => x, g = (x) => ({ x }), h = async () => { await g(1); };\n"
+        );
+    }
+
+    #[test]
+    fn keeps_the_text_of_parsed_literals() {
+        assert_eq!(
+            print("let s = 'it\\'s', n = 0x1_0, t = `a${b}c`, r = /x+/g;"),
+            "let s = 'it\\'s', n = 0x1_0, t = `a${b}c`, r = /x+/g;\n"
+        );
+        assert_eq!(
+            print("1..toString(); (1).x; 1.5.x;"),
+            "1..toString();\n(1).x;\n1.5.x;\n"
+        );
+    }
+
+    #[test]
+    fn prints_typescript_syntax_it_is_given() {
+        assert_eq!(
+            print(
+                "interface I<T> extends J { a?: T; m(): void }\ntype U = keyof { [K in A]?: K[] };"
+            ),
+            "interface I<T> extends J {\n    a?: T;\n    m(): void;\n}\ntype U = keyof { [K in A]?: K[]; };\n"
+        );
+        assert_eq!(
+            print("enum E { A = 1, B }\nnamespace A.B { export const x = 1; }"),
+            "enum E {\n    A = 1,\n    B\n}\nnamespace A.B {\n    export const x = 1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn parenthesizes_synthesized_trees() {
+        let factory = NodeFactory::new();
+        let binary = |left, operator, right| {
+            factory.create(
+                SyntaxKind::BinaryExpression,
+                BinaryExpression {
+                    left,
+                    operator_token: factory.create_token(operator),
+                    right,
+                },
+            )
+        };
+        let id = |name: &str| factory.create_identifier(name);
+        let options = PrinterOptions::default();
+
+        let sum = binary(id("a"), SyntaxKind::PlusToken, id("b"));
+        let product = binary(sum.clone(), SyntaxKind::AsteriskToken, id("c"));
+        assert_eq!(print_node(&product, None, &options), "(a + b) * c");
+
+        let difference = binary(
+            id("a"),
+            SyntaxKind::MinusToken,
+            binary(id("b"), SyntaxKind::MinusToken, id("c")),
+        );
+        assert_eq!(print_node(&difference, None, &options), "a - (b - c)");
+
+        let power = binary(
+            factory.create(
+                SyntaxKind::PrefixUnaryExpression,
+                PrefixUnaryExpression {
+                    operator: SyntaxKind::MinusToken,
+                    operand: id("a"),
+                },
+            ),
+            SyntaxKind::AsteriskAsteriskToken,
+            id("b"),
+        );
+        assert_eq!(print_node(&power, None, &options), "(-a) ** b");
+
+        let negated = factory.create(
+            SyntaxKind::PrefixUnaryExpression,
+            PrefixUnaryExpression {
+                operator: SyntaxKind::MinusToken,
+                operand: factory.create(
+                    SyntaxKind::PrefixUnaryExpression,
+                    PrefixUnaryExpression {
+                        operator: SyntaxKind::MinusMinusToken,
+                        operand: id("x"),
+                    },
+                ),
+            },
+        );
+        assert_eq!(print_node(&negated, None, &options), "- --x");
+
+        let call = factory.create(
+            SyntaxKind::CallExpression,
+            CallExpression {
+                expression: factory.create(
+                    SyntaxKind::PropertyAccessExpression,
+                    PropertyAccessExpression {
+                        expression: sum,
+                        question_dot_token: None,
+                        name: id("f"),
+                    },
+                ),
+                question_dot_token: None,
+                type_arguments: None,
+                arguments: factory.create_node_list(vec![binary(
+                    id("x"),
+                    SyntaxKind::CommaToken,
+                    id("y"),
+                )]),
+            },
+        );
+        let statement = factory.create(
+            SyntaxKind::ExpressionStatement,
+            ExpressionStatement { expression: call },
+        );
+        assert_eq!(print_node(&statement, None, &options), "(a + b).f((x, y));");
+    }
+
+    #[test]
+    fn escapes_synthesized_strings() {
+        let factory = NodeFactory::new();
+        let literal = factory.create_string_literal("say \"hi\"\n\\ é\u{0}1");
+        assert_eq!(
+            print_node(&literal, None, &PrinterOptions::default()),
+            "\"say \\\"hi\\\"\\n\\\\ \\u00E9\\x001\""
+        );
+    }
+
+    #[test]
+    fn avoids_automatic_semicolon_insertion_hazards() {
+        assert_eq!(
+            print("if (a) { if (b) c(); } else d();"),
+            "if (a) {\n    if (b)\n        c();\n}\nelse\n    d();\n"
+        );
+    }
+
+    #[test]
+    fn writes_comments_unless_removed() {
+        let text = "#!/usr/bin/env node\n/*! license */\n// leading\nlet x = /* inline */ 1; // trailing\nfunction f() {\n    /**\n     * doc\n     */\n    g();\n    // last\n}\n// end\n";
+        assert_eq!(
+            print(text),
+            "#!/usr/bin/env node\n/*! license */\n// leading\nlet x = /* inline */ 1; // trailing\nfunction f() {\n    /**\n     * doc\n     */\n    g();\n    // last\n}\n// end\n"
+        );
+        let options = PrinterOptions {
+            remove_comments: true,
+            ..PrinterOptions::default()
+        };
+        assert_eq!(
+            print_with(text, &options),
+            "#!/usr/bin/env node\n/*! license */\nlet x = 1;\nfunction f() {\n    g();\n}\n"
+        );
+    }
+
+    #[test]
+    fn uses_the_configured_line_breaks_and_indentation() {
+        let options = PrinterOptions {
+            new_line: NewLineKind::CarriageReturnLineFeed,
+            indent_style: IndentStyle::Tabs,
+            ..PrinterOptions::default()
+        };
+        assert_eq!(
+            print_with("while (x) {\n  y();\n}\n", &options),
+            "while (x) {\r\n\ty();\r\n}\r\n"
+        );
+        let options = PrinterOptions {
+            indent_style: IndentStyle::Spaces(2),
+            ..PrinterOptions::default()
+        };
+        assert_eq!(print_with("if (x) y();", &options), "if (x)\n  y();\n");
+    }
+
+    #[test]
+    fn maps_printed_nodes_back_to_their_sources() {
+        let file = parse_source_file(
+            "test.ts",
+            "let a = 1;\n\nf(a);\n",
+            ScriptTarget::ESNext,
+            ScriptKind::Unknown,
+        );
+        let (text, mappings) = print_file_with_mappings(&file, &PrinterOptions::default());
+        assert_eq!(text, "let a = 1;\nf(a);\n");
+        let mut generator = SourceMapGenerator::new();
+        generator.add_source("a.ts", &mappings, 0);
+        generator.add_source("b.ts", &mappings, 2);
+        assert_eq!(
+            generator.to_json("out.js"),
+            "{\"version\":3,\"file\":\"out.js\",\"sourceRoot\":\"\",\"sources\":[\"a.ts\",\"b.ts\"],\"names\":[],\"mappings\":\"AAAA,IAAI,IAAI;AAER,EAAE;ACFF,IAAI,IAAI;AAER,EAAE\"}"
+        );
+    }
+}
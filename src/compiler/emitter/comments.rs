@@ -0,0 +1,145 @@
+//! Writing the comments around nodes parsed from source text
+
+use crate::compiler::ast::{Node, NodeList, SyntaxKind};
+use crate::compiler::scanner::{
+    CommentRange, get_leading_comment_ranges, get_trailing_comment_ranges, is_line_break,
+};
+
+use super::printer::Printer;
+
+impl Printer<'_> {
+    /// Writes the comments before a node. JSX text has none, since what looks like a comment
+    /// in it is text.
+    pub(super) fn emit_leading_comments(&mut self, node: &Node) {
+        if self.has_source_position(node) && node.kind != SyntaxKind::JsxText {
+            self.emit_leading_comments_at(node.pos());
+        }
+    }
+
+    /// Writes the comments between `pos` and the next token, such as the comments before the
+    /// closing brace of a block
+    pub(super) fn emit_leading_comments_at(&mut self, pos: usize) {
+        let Some(text) = self.source_text else {
+            return;
+        };
+        for comment in get_leading_comment_ranges(text, pos) {
+            if !self.take_comment(text, &comment) {
+                continue;
+            }
+            self.write_comment(text, &comment);
+            if comment.has_trailing_new_line || comment.kind == SyntaxKind::SingleLineCommentTrivia
+            {
+                self.writer.write_line();
+            } else {
+                self.writer.write_space();
+            }
+        }
+    }
+
+    /// Writes the comments after a node. The children of a JSX element have none, since the
+    /// JSX text after them is not trivia.
+    pub(super) fn emit_trailing_comments(&mut self, node: &Node) {
+        if !self.has_source_position(node) || is_jsx_child(node) {
+            return;
+        }
+        let Some(text) = self.source_text else {
+            return;
+        };
+        for comment in get_trailing_comment_ranges(text, node.end()) {
+            if !self.take_comment(text, &comment) {
+                continue;
+            }
+            if !self.writer.is_at_start_of_line()
+                && !self.writer.last_char().is_some_and(char::is_whitespace)
+            {
+                self.writer.write_space();
+            }
+            self.write_comment(text, &comment);
+            if comment.has_trailing_new_line || comment.kind == SyntaxKind::SingleLineCommentTrivia
+            {
+                self.writer.write_line();
+            }
+        }
+    }
+
+    /// Whether a comment that has not been written yet ends its line before `node`, so that
+    /// writing the node after `return`, `throw`, or `yield` would end the statement early
+    pub(super) fn has_leading_line_break(&self, node: &Node) -> bool {
+        let Some(text) = self.source_text else {
+            return false;
+        };
+        self.has_source_position(node)
+            && get_leading_comment_ranges(text, node.pos())
+                .iter()
+                .any(|comment| {
+                    self.should_emit_comment(text, comment)
+                        && (comment.has_trailing_new_line
+                            || comment.kind == SyntaxKind::SingleLineCommentTrivia)
+                })
+    }
+
+    /// Whether there are comments to write before the token closing `list`, which keeps an
+    /// otherwise empty block from being written as `{ }`
+    pub(super) fn has_comments_before_close(&self, node: &Node, list: &NodeList) -> bool {
+        let Some(text) = self.source_text else {
+            return false;
+        };
+        self.has_source_position(node)
+            && get_leading_comment_ranges(text, list.end())
+                .iter()
+                .any(|comment| self.should_emit_comment(text, comment))
+    }
+
+    /// Whether a comment should be written: it has not been yet, and comments are not being
+    /// removed, or it is a `/*!` comment, which conventionally holds license text
+    fn should_emit_comment(&self, text: &str, comment: &CommentRange) -> bool {
+        !self.emitted_comments.contains(&comment.loc.pos())
+            && (!self.options.remove_comments || text[comment.loc.pos()..].starts_with("/*!"))
+    }
+
+    /// Checks whether a comment should be written, recording that it is
+    fn take_comment(&mut self, text: &str, comment: &CommentRange) -> bool {
+        let emit = self.should_emit_comment(text, comment);
+        if emit {
+            self.emitted_comments.insert(comment.loc.pos());
+        }
+        emit
+    }
+
+    /// Writes a comment, re-indenting the lines after the first of a multi-line comment to the
+    /// current indentation
+    fn write_comment(&mut self, text: &str, comment: &CommentRange) {
+        let comment_text = &text[comment.loc.pos()..comment.loc.end()];
+        if comment.kind == SyntaxKind::SingleLineCommentTrivia {
+            self.writer.write(comment_text.trim_end());
+            return;
+        }
+        let line_start = text[..comment.loc.pos()]
+            .rfind(is_line_break)
+            .map_or(0, |index| index + 1);
+        let column = comment.loc.pos() - line_start;
+        for (index, line) in comment_text.lines().enumerate() {
+            if index == 0 {
+                self.writer.write(line.trim_end());
+                continue;
+            }
+            self.writer.force_write_line();
+            let indentation = line
+                .bytes()
+                .take(column)
+                .take_while(|&byte| byte == b' ' || byte == b'\t')
+                .count();
+            self.writer.write(line[indentation..].trim_end());
+        }
+    }
+}
+
+fn is_jsx_child(node: &Node) -> bool {
+    node.kind == SyntaxKind::JsxText
+        || node.parent().is_some_and(|parent| {
+            matches!(
+                parent.kind,
+                SyntaxKind::JsxElement | SyntaxKind::JsxFragment
+            )
+        })
+}
@@ -0,0 +1,344 @@
+//! Operator precedence, and where expressions need parentheses to keep their meaning
+
+use crate::compiler::ast::{Node, SyntaxKind};
+
+/// How tightly an expression binds, from loosest to tightest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum OperatorPrecedence {
+    /// `a, b`
+    Comma,
+    /// `...a`
+    Spread,
+    /// `yield a`
+    Yield,
+    /// `a = b`, `a += b`, `(a) => b`
+    Assignment,
+    /// `a ? b : c`
+    Conditional,
+    /// `a ?? b`
+    Coalesce,
+    /// `a || b`
+    LogicalOr,
+    /// `a && b`
+    LogicalAnd,
+    /// `a | b`
+    BitwiseOr,
+    /// `a ^ b`
+    BitwiseXor,
+    /// `a & b`
+    BitwiseAnd,
+    /// `a == b`, `a === b`, ...
+    Equality,
+    /// `a < b`, `a in b`, `a instanceof b`, `a as T`, ...
+    Relational,
+    /// `a << b`, `a >> b`, `a >>> b`
+    Shift,
+    /// `a + b`, `a - b`
+    Additive,
+    /// `a * b`, `a / b`, `a % b`
+    Multiplicative,
+    /// `a ** b`
+    Exponentiation,
+    /// `!a`, `typeof a`, `await a`, ...
+    Unary,
+    /// `a++`, `a--`
+    Update,
+    /// `a()`, `new A`
+    LeftHandSide,
+    /// `a.b`, `a[b]`, `new A()`, ``a`b` ``
+    Member,
+    /// Identifiers, literals, and parenthesized expressions
+    Primary,
+}
+
+/// Gets the precedence of a binary operator
+pub(super) fn get_binary_operator_precedence(operator: SyntaxKind) -> OperatorPrecedence {
+    match operator {
+        SyntaxKind::CommaToken => OperatorPrecedence::Comma,
+        SyntaxKind::QuestionQuestionToken => OperatorPrecedence::Coalesce,
+        SyntaxKind::BarBarToken => OperatorPrecedence::LogicalOr,
+        SyntaxKind::AmpersandAmpersandToken => OperatorPrecedence::LogicalAnd,
+        SyntaxKind::BarToken => OperatorPrecedence::BitwiseOr,
+        SyntaxKind::CaretToken => OperatorPrecedence::BitwiseXor,
+        SyntaxKind::AmpersandToken => OperatorPrecedence::BitwiseAnd,
+        SyntaxKind::EqualsEqualsToken
+        | SyntaxKind::ExclamationEqualsToken
+        | SyntaxKind::EqualsEqualsEqualsToken
+        | SyntaxKind::ExclamationEqualsEqualsToken => OperatorPrecedence::Equality,
+        SyntaxKind::LessThanToken
+        | SyntaxKind::GreaterThanToken
+        | SyntaxKind::LessThanEqualsToken
+        | SyntaxKind::GreaterThanEqualsToken
+        | SyntaxKind::InstanceOfKeyword
+        | SyntaxKind::InKeyword => OperatorPrecedence::Relational,
+        SyntaxKind::LessThanLessThanToken
+        | SyntaxKind::GreaterThanGreaterThanToken
+        | SyntaxKind::GreaterThanGreaterThanGreaterThanToken => OperatorPrecedence::Shift,
+        SyntaxKind::PlusToken | SyntaxKind::MinusToken => OperatorPrecedence::Additive,
+        SyntaxKind::AsteriskToken | SyntaxKind::SlashToken | SyntaxKind::PercentToken => {
+            OperatorPrecedence::Multiplicative
+        }
+        SyntaxKind::AsteriskAsteriskToken => OperatorPrecedence::Exponentiation,
+        _ => OperatorPrecedence::Assignment,
+    }
+}
+
+/// Gets the precedence of the operator at the root of an expression
+pub(super) fn get_expression_precedence(node: &Node) -> OperatorPrecedence {
+    match node.kind {
+        SyntaxKind::CommaListExpression => OperatorPrecedence::Comma,
+        SyntaxKind::SpreadElement => OperatorPrecedence::Spread,
+        SyntaxKind::YieldExpression => OperatorPrecedence::Yield,
+        SyntaxKind::ArrowFunction => OperatorPrecedence::Assignment,
+        SyntaxKind::ConditionalExpression => OperatorPrecedence::Conditional,
+        SyntaxKind::BinaryExpression => {
+            get_binary_operator_precedence(node.as_binary_expression().operator_token.kind)
+        }
+        SyntaxKind::AsExpression | SyntaxKind::SatisfiesExpression => {
+            OperatorPrecedence::Relational
+        }
+        SyntaxKind::TypeAssertionExpression
+        | SyntaxKind::PrefixUnaryExpression
+        | SyntaxKind::TypeOfExpression
+        | SyntaxKind::VoidExpression
+        | SyntaxKind::DeleteExpression
+        | SyntaxKind::AwaitExpression => OperatorPrecedence::Unary,
+        SyntaxKind::PostfixUnaryExpression => OperatorPrecedence::Update,
+        SyntaxKind::CallExpression => OperatorPrecedence::LeftHandSide,
+        SyntaxKind::NewExpression if node.as_new_expression().arguments.is_none() => {
+            OperatorPrecedence::LeftHandSide
+        }
+        SyntaxKind::NewExpression
+        | SyntaxKind::PropertyAccessExpression
+        | SyntaxKind::ElementAccessExpression
+        | SyntaxKind::TaggedTemplateExpression
+        | SyntaxKind::NonNullExpression
+        | SyntaxKind::MetaProperty => OperatorPrecedence::Member,
+        SyntaxKind::PartiallyEmittedExpression => {
+            get_expression_precedence(&node.as_partially_emitted_expression().expression)
+        }
+        _ => OperatorPrecedence::Primary,
+    }
+}
+
+/// Whether an operator groups from the right, as `a = b = c` and `a ** b ** c` do
+fn is_right_associative(node: &Node) -> bool {
+    match node.kind {
+        SyntaxKind::BinaryExpression => {
+            let operator = node.as_binary_expression().operator_token.kind;
+            operator == SyntaxKind::AsteriskAsteriskToken || operator.is_assignment_operator()
+        }
+        SyntaxKind::NewExpression => node.as_new_expression().arguments.is_none(),
+        SyntaxKind::PrefixUnaryExpression
+        | SyntaxKind::TypeOfExpression
+        | SyntaxKind::VoidExpression
+        | SyntaxKind::DeleteExpression
+        | SyntaxKind::AwaitExpression
+        | SyntaxKind::ConditionalExpression
+        | SyntaxKind::YieldExpression
+        | SyntaxKind::ArrowFunction => true,
+        _ => false,
+    }
+}
+
+fn binary_operator(node: &Node) -> Option<SyntaxKind> {
+    (node.kind == SyntaxKind::BinaryExpression)
+        .then(|| node.as_binary_expression().operator_token.kind)
+}
+
+/// Whether the operand of a binary expression needs parentheses
+pub(super) fn binary_operand_needs_parens(
+    operator: SyntaxKind,
+    operand: &Node,
+    is_left: bool,
+) -> bool {
+    let operand_operator = binary_operator(operand);
+    // `??` cannot be mixed with `||` or `&&` without parentheses
+    let is_logical =
+        |kind| kind == SyntaxKind::BarBarToken || kind == SyntaxKind::AmpersandAmpersandToken;
+    if operand_operator.is_some_and(|operand_operator| {
+        (operator == SyntaxKind::QuestionQuestionToken && is_logical(operand_operator))
+            || (is_logical(operator) && operand_operator == SyntaxKind::QuestionQuestionToken)
+    }) {
+        return true;
+    }
+    // `-a ** b` is a syntax error
+    if operator == SyntaxKind::AsteriskAsteriskToken
+        && is_left
+        && get_expression_precedence(operand) == OperatorPrecedence::Unary
+    {
+        return true;
+    }
+    let operator_precedence = get_binary_operator_precedence(operator);
+    let right_associative =
+        operator == SyntaxKind::AsteriskAsteriskToken || operator.is_assignment_operator();
+    let operand_precedence = get_expression_precedence(operand);
+    if operand_precedence < operator_precedence {
+        // `a = yield b` and `a = () => b` bind as intended
+        return !(right_associative
+            && !is_left
+            && matches!(
+                operand.kind,
+                SyntaxKind::YieldExpression | SyntaxKind::ArrowFunction
+            ));
+    }
+    if operand_precedence > operator_precedence {
+        return false;
+    }
+    if is_left {
+        return right_associative;
+    }
+    // `a * (b * c)` means the same as `a * b * c`, but `a - (b - c)` does not
+    if operand_operator == Some(operator)
+        && matches!(
+            operator,
+            SyntaxKind::AsteriskToken
+                | SyntaxKind::BarToken
+                | SyntaxKind::AmpersandToken
+                | SyntaxKind::CaretToken
+        )
+    {
+        return false;
+    }
+    !is_right_associative(operand)
+}
+
+/// Whether an expression needs parentheses to be the object of a property access, the callee of
+/// a call, or the tag of a tagged template
+pub(super) fn left_side_needs_parens(expression: &Node) -> bool {
+    get_expression_precedence(expression) < OperatorPrecedence::LeftHandSide
+        || (expression.kind == SyntaxKind::NewExpression
+            && expression.as_new_expression().arguments.is_none())
+}
+
+/// Whether an expression needs parentheses to be the callee of `new`, which would otherwise take
+/// the arguments of a call within it as its own
+pub(super) fn new_callee_needs_parens(expression: &Node) -> bool {
+    if get_expression_precedence(expression) < OperatorPrecedence::Member {
+        return true;
+    }
+    let mut current = expression;
+    loop {
+        match current.kind {
+            SyntaxKind::CallExpression => return true,
+            SyntaxKind::PropertyAccessExpression => {
+                current = &current.as_property_access_expression().expression;
+            }
+            SyntaxKind::ElementAccessExpression => {
+                current = &current.as_element_access_expression().expression;
+            }
+            SyntaxKind::TaggedTemplateExpression => {
+                current = &current.as_tagged_template_expression().tag;
+            }
+            SyntaxKind::NonNullExpression => {
+                current = &current.as_non_null_expression().expression;
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Whether the operand of a prefix unary, `typeof`, `void`, `delete`, or `await` expression
+/// needs parentheses
+pub(super) fn unary_operand_needs_parens(operand: &Node) -> bool {
+    get_expression_precedence(operand) < OperatorPrecedence::Unary
+}
+
+/// Whether the operand of a postfix `++` or `--` needs parentheses
+pub(super) fn postfix_operand_needs_parens(operand: &Node) -> bool {
+    get_expression_precedence(operand) < OperatorPrecedence::LeftHandSide
+}
+
+/// Whether an expression needs parentheses where a comma would separate it from the next one,
+/// as in arguments, array elements, and initializers
+pub(super) fn comma_disallowed_needs_parens(expression: &Node) -> bool {
+    get_expression_precedence(expression) <= OperatorPrecedence::Comma
+}
+
+/// Whether the condition of a conditional expression needs parentheses
+pub(super) fn condition_needs_parens(condition: &Node) -> bool {
+    get_expression_precedence(condition) <= OperatorPrecedence::Conditional
+}
+
+/// Gets the expression whose first token begins `expression`
+pub(super) fn get_leftmost_expression(expression: &Node) -> &Node {
+    let mut current = expression;
+    while let Some(left) = get_left_operand(current) {
+        current = left;
+    }
+    current
+}
+
+/// Gets the operand an expression starts with, if it does not start with its own token
+fn get_left_operand(expression: &Node) -> Option<&Node> {
+    let left = match expression.kind {
+        SyntaxKind::BinaryExpression => &expression.as_binary_expression().left,
+        SyntaxKind::ConditionalExpression => &expression.as_conditional_expression().condition,
+        SyntaxKind::PropertyAccessExpression => {
+            &expression.as_property_access_expression().expression
+        }
+        SyntaxKind::ElementAccessExpression => {
+            &expression.as_element_access_expression().expression
+        }
+        SyntaxKind::CallExpression => &expression.as_call_expression().expression,
+        SyntaxKind::TaggedTemplateExpression => &expression.as_tagged_template_expression().tag,
+        SyntaxKind::PostfixUnaryExpression => &expression.as_postfix_unary_expression().operand,
+        SyntaxKind::AsExpression => &expression.as_as_expression().expression,
+        SyntaxKind::SatisfiesExpression => &expression.as_satisfies_expression().expression,
+        SyntaxKind::NonNullExpression => &expression.as_non_null_expression().expression,
+        SyntaxKind::PartiallyEmittedExpression => {
+            &expression.as_partially_emitted_expression().expression
+        }
+        SyntaxKind::CommaListExpression => {
+            return expression
+                .as_comma_list_expression()
+                .elements
+                .nodes
+                .first()
+                .map(|first| first.as_ref());
+        }
+        _ => return None,
+    };
+    Some(left)
+}
+
+/// Whether an expression statement needs parentheses so that it is not read as a declaration
+/// or block, as `({})`, `(function () {})()`, and `(let[0])` would be without them
+pub(super) fn expression_statement_needs_parens(expression: &Node) -> bool {
+    let mut current = expression;
+    loop {
+        match current.kind {
+            SyntaxKind::ObjectLiteralExpression
+            | SyntaxKind::FunctionExpression
+            | SyntaxKind::ClassExpression => return true,
+            SyntaxKind::ElementAccessExpression => {
+                let object = &current.as_element_access_expression().expression;
+                if object.kind == SyntaxKind::Identifier && object.text() == "let" {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        match get_left_operand(current) {
+            Some(left) => current = left,
+            None => return false,
+        }
+    }
+}
+
+/// Whether the concise body of an arrow function needs parentheses, as an object literal
+/// would otherwise be read as a block
+pub(super) fn arrow_body_needs_parens(body: &Node) -> bool {
+    get_leftmost_expression(body).kind == SyntaxKind::ObjectLiteralExpression
+        || comma_disallowed_needs_parens(body)
+}
+
+/// Whether the expression of `export default` needs parentheses, as a leading function or
+/// class would otherwise be read as a declaration
+pub(super) fn export_default_needs_parens(expression: &Node) -> bool {
+    comma_disallowed_needs_parens(expression)
+        || matches!(
+            get_leftmost_expression(expression).kind,
+            SyntaxKind::FunctionExpression | SyntaxKind::ClassExpression
+        )
+}
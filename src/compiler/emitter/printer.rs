@@ -0,0 +1,1800 @@
+//! Writing each kind of node as source text
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::compiler::ast::{Node, NodeFlags, NodeList, SyntaxKind};
+use crate::compiler::scanner::{is_line_break, skip_trivia, token_to_string};
+use crate::compiler::text::{compute_line_starts, position_to_utf16_line_and_character};
+
+use super::PrinterOptions;
+use super::precedence::{
+    arrow_body_needs_parens, binary_operand_needs_parens, comma_disallowed_needs_parens,
+    condition_needs_parens, export_default_needs_parens, expression_statement_needs_parens,
+    left_side_needs_parens, new_callee_needs_parens, postfix_operand_needs_parens,
+    unary_operand_needs_parens,
+};
+use super::source_map::Mapping;
+use super::writer::TextWriter;
+
+/// Prints nodes into a [`TextWriter`], taking literal text and comments from the source file
+/// for nodes that were parsed from it
+pub(super) struct Printer<'a> {
+    pub(super) writer: TextWriter,
+    pub(super) options: &'a PrinterOptions,
+    /// The text of the file the printed nodes were parsed from
+    pub(super) source_text: Option<&'a str>,
+    /// The start positions of the comments that have been written, so that a comment shared
+    /// by nested nodes starting at the same position is written once
+    pub(super) emitted_comments: HashSet<usize>,
+    /// The mappings of a source map being built for the printed text
+    mappings: Option<Vec<Mapping>>,
+    /// The start of each line of the source text, when a source map is being built
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Printer<'a> {
+    pub(super) fn new(options: &'a PrinterOptions, source_file: Option<&'a Node>) -> Self {
+        let indent_unit = match options.indent_style {
+            super::IndentStyle::Spaces(width) => " ".repeat(width),
+            super::IndentStyle::Tabs => "\t".to_string(),
+        };
+        Printer {
+            writer: TextWriter::new(options.new_line.as_str(), indent_unit),
+            options,
+            source_text: source_file.map(|file| file.as_source_file().text.as_str()),
+            emitted_comments: HashSet::new(),
+            mappings: None,
+            line_starts: Vec::new(),
+        }
+    }
+
+    /// Makes the printer record where each node parsed from the source file is printed
+    pub(super) fn with_source_map(mut self) -> Self {
+        self.mappings = Some(Vec::new());
+        self.line_starts = self
+            .source_text
+            .map(compute_line_starts)
+            .unwrap_or_default();
+        self
+    }
+
+    pub(super) fn print_source_file(self, node: &Node) -> String {
+        self.print_source_file_with_mappings(node).0
+    }
+
+    /// Prints a source file, returning the mappings recorded if the printer builds a source
+    /// map
+    pub(super) fn print_source_file_with_mappings(mut self, node: &Node) -> (String, Vec<Mapping>) {
+        let file = node.as_source_file();
+        if let Some(text) = self.source_text
+            && text.starts_with("#!")
+        {
+            let end = text.find(is_line_break).unwrap_or(text.len());
+            self.write(&text[..end]);
+            self.writer.write_line();
+        }
+        for statement in &file.statements.nodes {
+            self.emit(statement);
+            self.writer.write_line();
+        }
+        if self.has_source_position(node) {
+            self.emit_leading_comments_at(file.end_of_file_token.pos());
+        }
+        self.writer.write_line();
+        (self.writer.into_text(), self.mappings.unwrap_or_default())
+    }
+
+    pub(super) fn print(mut self, node: &Arc<Node>) -> String {
+        self.emit(node);
+        self.writer.into_text()
+    }
+
+    /// Whether the node's range is a range of the source text, so comments can be found around
+    /// it. Transformed nodes keep the range of the nodes they replace.
+    pub(super) fn has_source_position(&self, node: &Node) -> bool {
+        self.source_text
+            .is_some_and(|text| !node.loc.is_empty() && node.end() <= text.len())
+    }
+
+    /// Gets the source text a node covers, which for a transformed node is the text of the
+    /// node it replaces
+    fn get_text_of_range(&self, node: &Node) -> Option<&'a str> {
+        if !self.has_source_position(node) {
+            return None;
+        }
+        let text = self.source_text?;
+        let start = skip_trivia(text, node.pos()).min(node.end());
+        Some(&text[start..node.end()])
+    }
+
+    /// Gets the source text of a node that was parsed and not changed since
+    fn get_source_text(&self, node: &Node) -> Option<&'a str> {
+        if node.flags.contains(NodeFlags::SYNTHESIZED) {
+            return None;
+        }
+        self.get_text_of_range(node)
+    }
+
+    /// Whether a node is written on a single line in the source. Nodes without a source
+    /// position are treated as if they were.
+    fn is_on_single_line(&self, node: &Node) -> bool {
+        self.get_text_of_range(node)
+            .is_none_or(|text| !text.contains(is_line_break))
+    }
+
+    fn write(&mut self, text: &str) {
+        self.writer.write(text);
+    }
+
+    fn write_token(&mut self, kind: SyntaxKind) {
+        self.write(token_to_string(kind).unwrap_or_default());
+    }
+
+    /// Writes a node with its comments
+    pub(super) fn emit(&mut self, node: &Arc<Node>) {
+        self.emit_leading_comments(node);
+        self.emit_source_position(node);
+        self.emit_node(node);
+        self.emit_trailing_comments(node);
+    }
+
+    /// Maps where a node is about to be printed to where it starts in the source, when
+    /// building a source map
+    fn emit_source_position(&mut self, node: &Node) {
+        if self.mappings.is_none() || !self.has_source_position(node) {
+            return;
+        }
+        let text = self
+            .source_text
+            .expect("nodes with positions have source text");
+        let pos = skip_trivia(text, node.pos()).min(node.end());
+        let source = position_to_utf16_line_and_character(text, &self.line_starts, pos);
+        let (source_line, source_column) = (source.line, source.character);
+        let (generated_line, generated_column) = self.writer.position();
+        let mappings = self.mappings.as_mut().expect("checked above");
+        // Nested nodes that start together are mapped once, to the start of the outermost
+        if mappings.last().is_some_and(|last| {
+            last.generated_line == generated_line && last.generated_column == generated_column
+        }) {
+            return;
+        }
+        mappings.push(Mapping {
+            generated_line,
+            generated_column,
+            source_line,
+            source_column,
+        });
+    }
+
+    fn emit_with_parens(&mut self, node: &Arc<Node>, parenthesize: bool) {
+        if parenthesize {
+            self.write("(");
+            self.emit(node);
+            self.write(")");
+        } else {
+            self.emit(node);
+        }
+    }
+
+    /// Writes an expression where a comma would end it
+    fn emit_comma_disallowed(&mut self, node: &Arc<Node>) {
+        self.emit_with_parens(node, comma_disallowed_needs_parens(node));
+    }
+
+    fn emit_optional(&mut self, prefix: &str, node: Option<&Arc<Node>>) {
+        if let Some(node) = node {
+            self.write(prefix);
+            self.emit(node);
+        }
+    }
+
+    fn emit_list(&mut self, nodes: &[Arc<Node>], separator: &str) {
+        for (index, node) in nodes.iter().enumerate() {
+            if index > 0 {
+                self.write(separator);
+            }
+            self.emit(node);
+        }
+    }
+
+    fn emit_comma_list(&mut self, list: &NodeList) {
+        for (index, node) in list.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            self.emit_comma_disallowed(node);
+        }
+    }
+
+    fn emit_type_annotation(&mut self, type_node: Option<&Arc<Node>>) {
+        self.emit_optional(": ", type_node);
+    }
+
+    fn emit_initializer(&mut self, initializer: Option<&Arc<Node>>) {
+        if let Some(initializer) = initializer {
+            self.write(" = ");
+            self.emit_comma_disallowed(initializer);
+        }
+    }
+
+    /// Writes type parameters, keeping a trailing comma, which tells `<T,>() => {}` apart from
+    /// a JSX element in a `.tsx` file
+    fn emit_type_parameters(&mut self, list: Option<&NodeList>) {
+        if let Some(list) = list {
+            self.write("<");
+            self.emit_list(&list.nodes, ", ");
+            if list.has_trailing_comma {
+                self.write(",");
+            }
+            self.write(">");
+        }
+    }
+
+    fn emit_type_arguments(&mut self, list: Option<&NodeList>) {
+        if let Some(list) = list {
+            self.write("<");
+            self.emit_list(&list.nodes, ", ");
+            self.write(">");
+        }
+    }
+
+    fn emit_parameters(&mut self, list: &NodeList) {
+        self.write("(");
+        self.emit_list(&list.nodes, ", ");
+        self.write(")");
+    }
+
+    /// Writes decorators and modifier keywords, each followed by a space, except that decorators
+    /// of declarations other than parameters go on lines of their own
+    fn emit_modifiers(&mut self, node: &Node) {
+        let Some(modifiers) = node.modifiers() else {
+            return;
+        };
+        for modifier in modifiers.iter() {
+            self.emit(modifier);
+            if modifier.kind == SyntaxKind::Decorator && node.kind != SyntaxKind::Parameter {
+                self.writer.write_line();
+            } else {
+                self.writer.write_space();
+            }
+        }
+    }
+
+    /// Writes the statements of a block-like node on lines of their own between braces
+    fn emit_block_statements(&mut self, node: &Node, statements: &NodeList) {
+        self.write("{");
+        self.emit_lines(node, statements, "");
+        self.write("}");
+    }
+
+    /// Writes each node of a list on its own indented line, followed by the comments before the
+    /// closing token
+    fn emit_lines(&mut self, node: &Node, list: &NodeList, separator: &str) {
+        self.writer.increase_indent();
+        for (index, child) in list.iter().enumerate() {
+            self.writer.write_line();
+            self.emit(child);
+            if index + 1 < list.len() {
+                self.write(separator);
+            }
+        }
+        if self.has_source_position(node) {
+            self.writer.write_line();
+            self.emit_leading_comments_at(list.end());
+        }
+        self.writer.decrease_indent();
+        self.writer.write_line();
+    }
+
+    /// Writes the body of a loop, `if`, `with`, or label: a block after a space, and any other
+    /// statement on an indented line of its own
+    fn emit_embedded_statement(&mut self, statement: &Arc<Node>) {
+        if statement.kind == SyntaxKind::Block {
+            self.writer.write_space();
+            self.emit(statement);
+        } else {
+            self.writer.write_line();
+            self.writer.increase_indent();
+            self.emit(statement);
+            self.writer.decrease_indent();
+        }
+    }
+
+    fn emit_node(&mut self, node: &Arc<Node>) {
+        use SyntaxKind as K;
+        match node.kind {
+            K::Identifier | K::PrivateIdentifier => self.emit_identifier(node),
+            K::NumericLiteral
+            | K::BigIntLiteral
+            | K::StringLiteral
+            | K::RegularExpressionLiteral
+            | K::NoSubstitutionTemplateLiteral
+            | K::TemplateHead
+            | K::TemplateMiddle
+            | K::TemplateTail => self.emit_literal(node),
+            K::JsxText => self.emit_jsx_text(node),
+            K::QualifiedName => {
+                let data = node.as_qualified_name();
+                self.emit(&data.left);
+                self.write(".");
+                self.emit(&data.right);
+            }
+            K::ComputedPropertyName => {
+                self.write("[");
+                self.emit_comma_disallowed(&node.as_computed_property_name().expression);
+                self.write("]");
+            }
+            K::TypeParameter => {
+                let data = node.as_type_parameter();
+                self.emit_modifiers(node);
+                self.emit(&data.name);
+                self.emit_optional(" extends ", data.constraint.as_ref());
+                self.emit_optional(" = ", data.default_type.as_ref());
+            }
+            K::Parameter => {
+                let data = node.as_parameter();
+                self.emit_modifiers(node);
+                if data.dot_dot_dot_token.is_some() {
+                    self.write("...");
+                }
+                self.emit(&data.name);
+                if data.question_token.is_some() {
+                    self.write("?");
+                }
+                self.emit_type_annotation(data.type_node.as_ref());
+                self.emit_initializer(data.initializer.as_ref());
+            }
+            K::Decorator => {
+                let expression = &node.as_decorator().expression;
+                self.write("@");
+                self.emit_with_parens(expression, left_side_needs_parens(expression));
+            }
+
+            // Class and type members
+            K::PropertySignature => {
+                let data = node.as_property_signature();
+                self.emit_modifiers(node);
+                self.emit(&data.name);
+                self.emit_postfix_token(data.postfix_token.as_ref());
+                self.emit_type_annotation(data.type_node.as_ref());
+                self.emit_initializer(data.initializer.as_ref());
+                self.write(";");
+            }
+            K::PropertyDeclaration => {
+                let data = node.as_property_declaration();
+                self.emit_modifiers(node);
+                self.emit(&data.name);
+                self.emit_postfix_token(data.postfix_token.as_ref());
+                self.emit_type_annotation(data.type_node.as_ref());
+                self.emit_initializer(data.initializer.as_ref());
+                self.write(";");
+            }
+            K::MethodSignature => {
+                let data = node.as_method_signature();
+                self.emit_modifiers(node);
+                self.emit(&data.name);
+                self.emit_postfix_token(data.postfix_token.as_ref());
+                self.emit_signature(node);
+                self.write(";");
+            }
+            K::MethodDeclaration => {
+                let data = node.as_method_declaration();
+                self.emit_modifiers(node);
+                if data.asterisk_token.is_some() {
+                    self.write("*");
+                }
+                self.emit(&data.name);
+                self.emit_postfix_token(data.postfix_token.as_ref());
+                self.emit_signature_and_body(node);
+            }
+            K::ClassStaticBlockDeclaration => {
+                self.write("static ");
+                self.emit(&node.as_class_static_block_declaration().body);
+            }
+            K::Constructor => {
+                self.emit_modifiers(node);
+                self.write("constructor");
+                self.emit_signature_and_body(node);
+            }
+            K::GetAccessor | K::SetAccessor => {
+                self.emit_modifiers(node);
+                self.write(if node.kind == K::GetAccessor {
+                    "get "
+                } else {
+                    "set "
+                });
+                self.emit(node.name().expect("accessors have names"));
+                self.emit_signature_and_body(node);
+            }
+            K::CallSignature => {
+                self.emit_signature(node);
+                self.write(";");
+            }
+            K::ConstructSignature => {
+                self.write("new ");
+                self.emit_signature(node);
+                self.write(";");
+            }
+            K::IndexSignature => {
+                let data = node.as_index_signature_declaration();
+                self.emit_modifiers(node);
+                self.write("[");
+                self.emit_list(&data.parameters.nodes, ", ");
+                self.write("]");
+                self.emit_type_annotation(data.type_node.as_ref());
+                self.write(";");
+            }
+            K::SemicolonClassElement | K::EmptyStatement => self.write(";"),
+
+            // Types
+            K::TypePredicate => {
+                let data = node.as_type_predicate_node();
+                if data.asserts_modifier.is_some() {
+                    self.write("asserts ");
+                }
+                self.emit(&data.parameter_name);
+                self.emit_optional(" is ", data.type_node.as_ref());
+            }
+            K::TypeReference => {
+                let data = node.as_type_reference_node();
+                self.emit(&data.type_name);
+                self.emit_type_arguments(data.type_arguments.as_ref());
+            }
+            K::FunctionType => {
+                self.emit_type_parameters(node.type_parameters());
+                self.emit_parameters(node.parameters().expect("function types have parameters"));
+                self.emit_optional(" => ", node.type_node());
+            }
+            K::ConstructorType => {
+                self.emit_modifiers(node);
+                self.write("new ");
+                self.emit_type_parameters(node.type_parameters());
+                self.emit_parameters(
+                    node.parameters()
+                        .expect("constructor types have parameters"),
+                );
+                self.emit_optional(" => ", node.type_node());
+            }
+            K::TypeQuery => {
+                let data = node.as_type_query_node();
+                self.write("typeof ");
+                self.emit(&data.expr_name);
+                self.emit_type_arguments(data.type_arguments.as_ref());
+            }
+            K::TypeLiteral => self.emit_type_literal(node),
+            K::ArrayType => {
+                self.emit(&node.as_array_type_node().element_type);
+                self.write("[]");
+            }
+            K::TupleType => {
+                self.write("[");
+                self.emit_list(&node.as_tuple_type_node().elements.nodes, ", ");
+                self.write("]");
+            }
+            K::OptionalType => {
+                self.emit(&node.as_optional_type_node().type_node);
+                self.write("?");
+            }
+            K::RestType => {
+                self.write("...");
+                self.emit(&node.as_rest_type_node().type_node);
+            }
+            K::UnionType => self.emit_list(&node.as_union_type_node().types.nodes, " | "),
+            K::IntersectionType => {
+                self.emit_list(&node.as_intersection_type_node().types.nodes, " & ")
+            }
+            K::ConditionalType => {
+                let data = node.as_conditional_type_node();
+                self.emit(&data.check_type);
+                self.write(" extends ");
+                self.emit(&data.extends_type);
+                self.write(" ? ");
+                self.emit(&data.true_type);
+                self.write(" : ");
+                self.emit(&data.false_type);
+            }
+            K::InferType => {
+                self.write("infer ");
+                self.emit(&node.as_infer_type_node().type_parameter);
+            }
+            K::ParenthesizedType => {
+                self.write("(");
+                self.emit(&node.as_parenthesized_type_node().type_node);
+                self.write(")");
+            }
+            K::ThisType => self.write("this"),
+            K::TypeOperator => {
+                let data = node.as_type_operator_node();
+                self.write_token(data.operator);
+                self.writer.write_space();
+                self.emit(&data.type_node);
+            }
+            K::IndexedAccessType => {
+                let data = node.as_indexed_access_type_node();
+                self.emit(&data.object_type);
+                self.write("[");
+                self.emit(&data.index_type);
+                self.write("]");
+            }
+            K::MappedType => self.emit_mapped_type(node),
+            K::LiteralType => self.emit(&node.as_literal_type_node().literal),
+            K::NamedTupleMember => {
+                let data = node.as_named_tuple_member();
+                if data.dot_dot_dot_token.is_some() {
+                    self.write("...");
+                }
+                self.emit(&data.name);
+                if data.question_token.is_some() {
+                    self.write("?");
+                }
+                self.write(": ");
+                self.emit(&data.type_node);
+            }
+            K::TemplateLiteralType => {
+                let data = node.as_template_literal_type_node();
+                self.emit(&data.head);
+                self.emit_list(&data.template_spans.nodes, "");
+            }
+            K::TemplateLiteralTypeSpan => {
+                let data = node.as_template_literal_type_span();
+                self.emit(&data.type_node);
+                self.emit(&data.literal);
+            }
+            K::ImportType => {
+                let data = node.as_import_type_node();
+                if data.is_type_of {
+                    self.write("typeof ");
+                }
+                self.write("import(");
+                self.emit(&data.argument);
+                if let Some(attributes) = &data.attributes {
+                    self.write(", { with: ");
+                    self.emit_import_attribute_elements(attributes);
+                    self.write(" }");
+                }
+                self.write(")");
+                self.emit_optional(".", data.qualifier.as_ref());
+                self.emit_type_arguments(data.type_arguments.as_ref());
+            }
+
+            // Binding patterns
+            K::ObjectBindingPattern => {
+                let elements = &node.as_object_binding_pattern().elements;
+                if elements.is_empty() {
+                    self.write("{}");
+                } else {
+                    self.write("{ ");
+                    self.emit_list(&elements.nodes, ", ");
+                    self.write(" }");
+                }
+            }
+            K::ArrayBindingPattern => {
+                let elements = &node.as_array_binding_pattern().elements;
+                self.write("[");
+                self.emit_list(&elements.nodes, ", ");
+                if elements.has_trailing_comma {
+                    self.write(",");
+                }
+                self.write("]");
+            }
+            K::BindingElement => {
+                let data = node.as_binding_element();
+                if data.dot_dot_dot_token.is_some() {
+                    self.write("...");
+                }
+                if let Some(property_name) = &data.property_name {
+                    self.emit(property_name);
+                    self.write(": ");
+                }
+                self.emit(&data.name);
+                self.emit_initializer(data.initializer.as_ref());
+            }
+
+            // Expressions
+            K::ArrayLiteralExpression => self.emit_array_literal(node),
+            K::ObjectLiteralExpression => self.emit_object_literal(node),
+            K::PropertyAccessExpression => self.emit_property_access(node),
+            K::ElementAccessExpression => {
+                let data = node.as_element_access_expression();
+                self.emit_with_parens(&data.expression, left_side_needs_parens(&data.expression));
+                if data.question_dot_token.is_some() {
+                    self.write("?.");
+                }
+                self.write("[");
+                self.emit(&data.argument_expression);
+                self.write("]");
+            }
+            K::CallExpression => {
+                let data = node.as_call_expression();
+                self.emit_with_parens(&data.expression, left_side_needs_parens(&data.expression));
+                if data.question_dot_token.is_some() {
+                    self.write("?.");
+                }
+                self.emit_type_arguments(data.type_arguments.as_ref());
+                self.emit_arguments(&data.arguments);
+            }
+            K::NewExpression => {
+                let data = node.as_new_expression();
+                self.write("new ");
+                self.emit_with_parens(&data.expression, new_callee_needs_parens(&data.expression));
+                self.emit_type_arguments(data.type_arguments.as_ref());
+                if let Some(arguments) = &data.arguments {
+                    self.emit_arguments(arguments);
+                }
+            }
+            K::TaggedTemplateExpression => {
+                let data = node.as_tagged_template_expression();
+                self.emit_with_parens(&data.tag, left_side_needs_parens(&data.tag));
+                if data.question_dot_token.is_some() {
+                    self.write("?.");
+                }
+                self.emit_type_arguments(data.type_arguments.as_ref());
+                self.emit(&data.template);
+            }
+            K::TypeAssertionExpression => {
+                let data = node.as_type_assertion();
+                self.write("<");
+                self.emit(&data.type_node);
+                self.write(">");
+                self.emit_with_parens(
+                    &data.expression,
+                    unary_operand_needs_parens(&data.expression),
+                );
+            }
+            K::ParenthesizedExpression => {
+                self.write("(");
+                self.emit(&node.as_parenthesized_expression().expression);
+                self.write(")");
+            }
+            K::FunctionExpression | K::FunctionDeclaration => self.emit_function(node),
+            K::ArrowFunction => self.emit_arrow_function(node),
+            K::DeleteExpression => self.emit_keyword_operand("delete ", node.expression()),
+            K::TypeOfExpression => self.emit_keyword_operand("typeof ", node.expression()),
+            K::VoidExpression => self.emit_keyword_operand("void ", node.expression()),
+            K::AwaitExpression => self.emit_keyword_operand("await ", node.expression()),
+            K::PrefixUnaryExpression => self.emit_prefix_unary(node),
+            K::PostfixUnaryExpression => {
+                let data = node.as_postfix_unary_expression();
+                self.emit_with_parens(&data.operand, postfix_operand_needs_parens(&data.operand));
+                self.write_token(data.operator);
+            }
+            K::BinaryExpression => self.emit_binary(node),
+            K::ConditionalExpression => {
+                let data = node.as_conditional_expression();
+                self.emit_with_parens(&data.condition, condition_needs_parens(&data.condition));
+                self.write(" ? ");
+                self.emit_comma_disallowed(&data.when_true);
+                self.write(" : ");
+                self.emit_comma_disallowed(&data.when_false);
+            }
+            K::TemplateExpression => {
+                let data = node.as_template_expression();
+                self.emit(&data.head);
+                self.emit_list(&data.template_spans.nodes, "");
+            }
+            K::TemplateSpan => {
+                let data = node.as_template_span();
+                self.emit(&data.expression);
+                self.emit(&data.literal);
+            }
+            K::YieldExpression => {
+                let data = node.as_yield_expression();
+                self.write("yield");
+                if data.asterisk_token.is_some() {
+                    self.write("*");
+                }
+                if let Some(expression) = &data.expression {
+                    self.writer.write_space();
+                    self.emit_with_parens(
+                        expression,
+                        comma_disallowed_needs_parens(expression)
+                            || self.has_leading_line_break(expression),
+                    );
+                }
+            }
+            K::SpreadElement | K::SpreadAssignment | K::JsxSpreadAttribute => {
+                let expression = node.expression().expect("spreads have expressions");
+                if node.kind == K::JsxSpreadAttribute {
+                    self.write("{...");
+                    self.emit(expression);
+                    self.write("}");
+                } else {
+                    self.write("...");
+                    self.emit_comma_disallowed(expression);
+                }
+            }
+            K::ClassExpression | K::ClassDeclaration => self.emit_class(node),
+            K::OmittedExpression | K::NotEmittedStatement | K::MissingDeclaration => {}
+            K::ExpressionWithTypeArguments => {
+                let data = node.as_expression_with_type_arguments();
+                self.emit_with_parens(&data.expression, left_side_needs_parens(&data.expression));
+                self.emit_type_arguments(data.type_arguments.as_ref());
+            }
+            K::AsExpression | K::SatisfiesExpression => {
+                let expression = node.expression().expect("assertions have expressions");
+                self.emit_with_parens(
+                    expression,
+                    binary_operand_needs_parens(K::LessThanToken, expression, true),
+                );
+                self.write(if node.kind == K::AsExpression {
+                    " as "
+                } else {
+                    " satisfies "
+                });
+                self.emit(node.type_node().expect("assertions have types"));
+            }
+            K::NonNullExpression => {
+                let expression = &node.as_non_null_expression().expression;
+                self.emit_with_parens(expression, left_side_needs_parens(expression));
+                self.write("!");
+            }
+            K::MetaProperty => {
+                let data = node.as_meta_property();
+                self.write_token(data.keyword_token);
+                self.write(".");
+                self.emit(&data.name);
+            }
+            K::PartiallyEmittedExpression => {
+                let data = node.as_partially_emitted_expression();
+                self.emit(&data.expression);
+                if let Some(comment) = &data.trailing_comment
+                    && !self.options.remove_comments
+                {
+                    self.write(&format!(" /*{comment}*/"));
+                }
+            }
+            K::CommaListExpression => {
+                self.emit_list(&node.as_comma_list_expression().elements.nodes, ", ")
+            }
+
+            // Statements
+            K::Block => {
+                let statements = &node.as_block().statements;
+                if statements.is_empty() && !self.has_comments_before_close(node, statements) {
+                    self.write("{ }");
+                } else {
+                    self.emit_block_statements(node, statements);
+                }
+            }
+            K::VariableStatement => {
+                self.emit_modifiers(node);
+                self.emit(&node.as_variable_statement().declaration_list);
+                self.write(";");
+            }
+            K::ExpressionStatement => {
+                let expression = &node.as_expression_statement().expression;
+                self.emit_with_parens(expression, expression_statement_needs_parens(expression));
+                self.write(";");
+            }
+            K::IfStatement => self.emit_if_statement(node),
+            K::DoStatement => {
+                let data = node.as_do_statement();
+                self.write("do");
+                self.emit_embedded_statement(&data.statement);
+                if data.statement.kind == K::Block {
+                    self.writer.write_space();
+                } else {
+                    self.writer.write_line();
+                }
+                self.write("while (");
+                self.emit(&data.expression);
+                self.write(");");
+            }
+            K::WhileStatement => {
+                let data = node.as_while_statement();
+                self.write("while (");
+                self.emit(&data.expression);
+                self.write(")");
+                self.emit_embedded_statement(&data.statement);
+            }
+            K::ForStatement => {
+                let data = node.as_for_statement();
+                self.write("for (");
+                if let Some(initializer) = &data.initializer {
+                    self.emit(initializer);
+                }
+                self.write(";");
+                self.emit_optional(" ", data.condition.as_ref());
+                self.write(";");
+                self.emit_optional(" ", data.incrementor.as_ref());
+                self.write(")");
+                self.emit_embedded_statement(&data.statement);
+            }
+            K::ForInStatement => {
+                let data = node.as_for_in_statement();
+                self.write("for (");
+                self.emit(&data.initializer);
+                self.write(" in ");
+                self.emit(&data.expression);
+                self.write(")");
+                self.emit_embedded_statement(&data.statement);
+            }
+            K::ForOfStatement => {
+                let data = node.as_for_of_statement();
+                self.write("for ");
+                if data.await_modifier.is_some() {
+                    self.write("await ");
+                }
+                self.write("(");
+                self.emit(&data.initializer);
+                self.write(" of ");
+                self.emit_comma_disallowed(&data.expression);
+                self.write(")");
+                self.emit_embedded_statement(&data.statement);
+            }
+            K::ContinueStatement => {
+                self.write("continue");
+                self.emit_optional(" ", node.as_continue_statement().label.as_ref());
+                self.write(";");
+            }
+            K::BreakStatement => {
+                self.write("break");
+                self.emit_optional(" ", node.as_break_statement().label.as_ref());
+                self.write(";");
+            }
+            K::ReturnStatement => {
+                self.write("return");
+                self.emit_restricted_expression(node.expression());
+                self.write(";");
+            }
+            K::WithStatement => {
+                let data = node.as_with_statement();
+                self.write("with (");
+                self.emit(&data.expression);
+                self.write(")");
+                self.emit_embedded_statement(&data.statement);
+            }
+            K::SwitchStatement => {
+                let data = node.as_switch_statement();
+                self.write("switch (");
+                self.emit(&data.expression);
+                self.write(") ");
+                self.emit(&data.case_block);
+            }
+            K::LabeledStatement => {
+                let data = node.as_labeled_statement();
+                self.emit(&data.label);
+                self.write(": ");
+                self.emit(&data.statement);
+            }
+            K::ThrowStatement => {
+                self.write("throw");
+                self.emit_restricted_expression(node.expression());
+                self.write(";");
+            }
+            K::TryStatement => {
+                let data = node.as_try_statement();
+                self.write("try ");
+                self.emit(&data.try_block);
+                if let Some(catch_clause) = &data.catch_clause {
+                    self.writer.write_line();
+                    self.emit(catch_clause);
+                }
+                if let Some(finally_block) = &data.finally_block {
+                    self.writer.write_line();
+                    self.write("finally ");
+                    self.emit(finally_block);
+                }
+            }
+            K::DebuggerStatement => self.write("debugger;"),
+
+            // Declarations
+            K::VariableDeclaration => {
+                let data = node.as_variable_declaration();
+                self.emit(&data.name);
+                if data.exclamation_token.is_some() {
+                    self.write("!");
+                }
+                self.emit_type_annotation(data.type_node.as_ref());
+                self.emit_initializer(data.initializer.as_ref());
+            }
+            K::VariableDeclarationList => {
+                let keyword = if node.flags.contains(NodeFlags::AWAIT_USING) {
+                    "await using "
+                } else if node.flags.contains(NodeFlags::USING) {
+                    "using "
+                } else if node.flags.contains(NodeFlags::CONST) {
+                    "const "
+                } else if node.flags.contains(NodeFlags::LET) {
+                    "let "
+                } else {
+                    "var "
+                };
+                self.write(keyword);
+                self.emit_list(
+                    &node.as_variable_declaration_list().declarations.nodes,
+                    ", ",
+                );
+            }
+            K::InterfaceDeclaration => {
+                let data = node.as_interface_declaration();
+                self.emit_modifiers(node);
+                self.write("interface ");
+                self.emit(&data.name);
+                self.emit_type_parameters(data.type_parameters.as_ref());
+                self.emit_heritage_clauses(data.heritage_clauses.as_ref());
+                self.write(" ");
+                self.emit_block_statements(node, &data.members);
+            }
+            K::TypeAliasDeclaration => {
+                let data = node.as_type_alias_declaration();
+                self.emit_modifiers(node);
+                self.write("type ");
+                self.emit(&data.name);
+                self.emit_type_parameters(data.type_parameters.as_ref());
+                self.write(" = ");
+                self.emit(&data.type_node);
+                self.write(";");
+            }
+            K::EnumDeclaration => {
+                let data = node.as_enum_declaration();
+                self.emit_modifiers(node);
+                self.write("enum ");
+                self.emit(&data.name);
+                self.write(" {");
+                self.emit_lines(node, &data.members, ",");
+                self.write("}");
+            }
+            K::EnumMember => {
+                let data = node.as_enum_member();
+                self.emit(&data.name);
+                self.emit_initializer(data.initializer.as_ref());
+            }
+            K::ModuleDeclaration => self.emit_module_declaration(node),
+            K::ModuleBlock => {
+                let statements = &node.as_module_block().statements;
+                if statements.is_empty() && !self.has_comments_before_close(node, statements) {
+                    self.write("{ }");
+                } else {
+                    self.emit_block_statements(node, statements);
+                }
+            }
+            K::CaseBlock => {
+                self.write("{");
+                self.emit_lines(node, &node.as_case_block().clauses, "");
+                self.write("}");
+            }
+            K::CaseClause => {
+                let data = node.as_case_clause();
+                self.write("case ");
+                self.emit_comma_disallowed(&data.expression);
+                self.write(":");
+                self.emit_case_clause_statements(node, &data.statements);
+            }
+            K::DefaultClause => {
+                self.write("default:");
+                self.emit_case_clause_statements(node, &node.as_default_clause().statements);
+            }
+            K::HeritageClause => {
+                let data = node.as_heritage_clause();
+                self.write_token(data.token);
+                self.writer.write_space();
+                self.emit_list(&data.types.nodes, ", ");
+            }
+            K::CatchClause => {
+                let data = node.as_catch_clause();
+                self.write("catch ");
+                if let Some(declaration) = &data.variable_declaration {
+                    self.write("(");
+                    self.emit(declaration);
+                    self.write(") ");
+                }
+                self.emit(&data.block);
+            }
+
+            // Modules
+            K::NamespaceExportDeclaration => {
+                self.write("export as namespace ");
+                self.emit(&node.as_namespace_export_declaration().name);
+                self.write(";");
+            }
+            K::ImportEqualsDeclaration => {
+                let data = node.as_import_equals_declaration();
+                self.emit_modifiers(node);
+                self.write("import ");
+                if data.is_type_only {
+                    self.write("type ");
+                }
+                self.emit(&data.name);
+                self.write(" = ");
+                self.emit(&data.module_reference);
+                self.write(";");
+            }
+            K::ExternalModuleReference => {
+                self.write("require(");
+                self.emit(&node.as_external_module_reference().expression);
+                self.write(")");
+            }
+            K::ImportDeclaration => {
+                let data = node.as_import_declaration();
+                self.emit_modifiers(node);
+                self.write("import ");
+                if let Some(import_clause) = &data.import_clause {
+                    self.emit(import_clause);
+                    self.write(" from ");
+                }
+                self.emit(&data.module_specifier);
+                self.emit_optional(" ", data.attributes.as_ref());
+                self.write(";");
+            }
+            K::ImportClause => {
+                let data = node.as_import_clause();
+                if data.is_type_only {
+                    self.write("type ");
+                }
+                if let Some(name) = &data.name {
+                    self.emit(name);
+                    if data.named_bindings.is_some() {
+                        self.write(", ");
+                    }
+                }
+                if let Some(named_bindings) = &data.named_bindings {
+                    self.emit(named_bindings);
+                }
+            }
+            K::NamespaceImport => {
+                self.write("* as ");
+                self.emit(&node.as_namespace_import().name);
+            }
+            K::NamespaceExport => {
+                self.write("* as ");
+                self.emit(&node.as_namespace_export().name);
+            }
+            K::NamedImports | K::NamedExports => {
+                let elements = if node.kind == K::NamedImports {
+                    &node.as_named_imports().elements
+                } else {
+                    &node.as_named_exports().elements
+                };
+                if elements.is_empty() {
+                    self.write("{}");
+                } else {
+                    self.write("{ ");
+                    self.emit_list(&elements.nodes, ", ");
+                    self.write(" }");
+                }
+            }
+            K::ImportSpecifier => {
+                let data = node.as_import_specifier();
+                self.emit_specifier(data.is_type_only, data.property_name.as_ref(), &data.name);
+            }
+            K::ExportSpecifier => {
+                let data = node.as_export_specifier();
+                self.emit_specifier(data.is_type_only, data.property_name.as_ref(), &data.name);
+            }
+            K::ExportAssignment => {
+                let data = node.as_export_assignment();
+                self.emit_modifiers(node);
+                if data.is_export_equals {
+                    self.write("export = ");
+                    self.emit_comma_disallowed(&data.expression);
+                } else {
+                    self.write("export default ");
+                    self.emit_with_parens(
+                        &data.expression,
+                        export_default_needs_parens(&data.expression),
+                    );
+                }
+                self.write(";");
+            }
+            K::ExportDeclaration => {
+                let data = node.as_export_declaration();
+                self.emit_modifiers(node);
+                self.write("export ");
+                if data.is_type_only {
+                    self.write("type ");
+                }
+                match &data.export_clause {
+                    Some(export_clause) => self.emit(export_clause),
+                    None => self.write("*"),
+                }
+                self.emit_optional(" from ", data.module_specifier.as_ref());
+                self.emit_optional(" ", data.attributes.as_ref());
+                self.write(";");
+            }
+            K::ImportAttributes => {
+                self.write_token(node.as_import_attributes().token);
+                self.writer.write_space();
+                self.emit_import_attribute_elements(node);
+            }
+            K::ImportAttribute => {
+                let data = node.as_import_attribute();
+                self.emit(&data.name);
+                self.write(": ");
+                self.emit(&data.value);
+            }
+
+            // Object literal members
+            K::PropertyAssignment => {
+                let data = node.as_property_assignment();
+                self.emit(&data.name);
+                self.write(": ");
+                self.emit_comma_disallowed(&data.initializer);
+            }
+            K::ShorthandPropertyAssignment => {
+                let data = node.as_shorthand_property_assignment();
+                self.emit(&data.name);
+                if let Some(initializer) = &data.object_assignment_initializer {
+                    self.write(" = ");
+                    self.emit_comma_disallowed(initializer);
+                }
+            }
+
+            // JSX
+            K::JsxElement => {
+                let data = node.as_jsx_element();
+                self.emit(&data.opening_element);
+                self.emit_list(&data.children.nodes, "");
+                self.emit(&data.closing_element);
+            }
+            K::JsxSelfClosingElement => {
+                let data = node.as_jsx_self_closing_element();
+                self.write("<");
+                self.emit(&data.tag_name);
+                self.emit_type_arguments(data.type_arguments.as_ref());
+                self.write(" ");
+                if !data.attributes.as_jsx_attributes().properties.is_empty() {
+                    self.emit(&data.attributes);
+                    self.write(" ");
+                }
+                self.write("/>");
+            }
+            K::JsxOpeningElement => {
+                let data = node.as_jsx_opening_element();
+                self.write("<");
+                self.emit(&data.tag_name);
+                self.emit_type_arguments(data.type_arguments.as_ref());
+                if !data.attributes.as_jsx_attributes().properties.is_empty() {
+                    self.write(" ");
+                    self.emit(&data.attributes);
+                }
+                self.write(">");
+            }
+            K::JsxClosingElement => {
+                self.write("</");
+                self.emit(&node.as_jsx_closing_element().tag_name);
+                self.write(">");
+            }
+            K::JsxFragment => {
+                let data = node.as_jsx_fragment();
+                self.write("<>");
+                self.emit_list(&data.children.nodes, "");
+                self.write("</>");
+            }
+            K::JsxAttributes => self.emit_list(&node.as_jsx_attributes().properties.nodes, " "),
+            K::JsxAttribute => {
+                let data = node.as_jsx_attribute();
+                self.emit(&data.name);
+                self.emit_optional("=", data.initializer.as_ref());
+            }
+            K::JsxExpression => {
+                let data = node.as_jsx_expression();
+                self.write("{");
+                if data.dot_dot_dot_token.is_some() {
+                    self.write("...");
+                }
+                if let Some(expression) = &data.expression {
+                    self.emit(expression);
+                }
+                self.write("}");
+            }
+            K::JsxNamespacedName => {
+                let data = node.as_jsx_namespaced_name();
+                self.emit(&data.namespace);
+                self.write(":");
+                self.emit(&data.name);
+            }
+
+            // Keywords, modifiers, and keyword-like expressions such as `this`; JSDoc types
+            // never appear in emitted code
+            kind => self.write_token(kind),
+        }
+    }
+
+    fn emit_identifier(&mut self, node: &Node) {
+        match self.get_source_text(node) {
+            Some(text) => self.write(text),
+            None => self.write(node.text()),
+        }
+    }
+
+    fn emit_literal(&mut self, node: &Node) {
+        if let Some(text) = self.get_source_text(node) {
+            self.write(text);
+            return;
+        }
+        let text = match node.kind {
+            SyntaxKind::StringLiteral => escape_string(node.text(), '"'),
+            SyntaxKind::NoSubstitutionTemplateLiteral
+            | SyntaxKind::TemplateHead
+            | SyntaxKind::TemplateMiddle
+            | SyntaxKind::TemplateTail => {
+                let data = node.as_template_literal_like_node();
+                let raw = if data.raw_text.is_empty() && !data.text.is_empty() {
+                    escape_template(&data.text)
+                } else {
+                    data.raw_text.clone()
+                };
+                let (open, close) = match node.kind {
+                    SyntaxKind::NoSubstitutionTemplateLiteral => ("`", "`"),
+                    SyntaxKind::TemplateHead => ("`", "${"),
+                    SyntaxKind::TemplateMiddle => ("}", "${"),
+                    _ => ("}", "`"),
+                };
+                format!("{open}{raw}{close}")
+            }
+            _ => node.text().to_string(),
+        };
+        self.write(&text);
+    }
+
+    /// Writes JSX text with the whitespace around it, which is part of the text rather than
+    /// trivia before it
+    fn emit_jsx_text(&mut self, node: &Node) {
+        match self.source_text {
+            Some(text)
+                if self.has_source_position(node)
+                    && !node.flags.contains(NodeFlags::SYNTHESIZED) =>
+            {
+                self.write(&text[node.pos()..node.end()]);
+            }
+            _ => self.write(node.text()),
+        }
+    }
+
+    fn emit_postfix_token(&mut self, token: Option<&Arc<Node>>) {
+        if let Some(token) = token {
+            self.write_token(token.kind);
+        }
+    }
+
+    /// Writes the type parameters, parameters, and return type of a signature
+    fn emit_signature(&mut self, node: &Node) {
+        self.emit_type_parameters(node.type_parameters());
+        self.emit_parameters(node.parameters().expect("signatures have parameters"));
+        self.emit_type_annotation(node.type_node());
+    }
+
+    fn emit_signature_and_body(&mut self, node: &Node) {
+        self.emit_signature(node);
+        match node.body() {
+            Some(body) => {
+                self.writer.write_space();
+                self.emit_function_body(body);
+            }
+            None => self.write(";"),
+        }
+    }
+
+    /// Writes a function body on a single line if it was written on one and has at most one
+    /// statement, and otherwise as a block
+    fn emit_function_body(&mut self, body: &Arc<Node>) {
+        let block = body.as_block();
+        let single_line = !block.multi_line
+            && block.statements.len() <= 1
+            && self.is_on_single_line(body)
+            && !self.has_comments_before_close(body, &block.statements);
+        if !single_line {
+            self.emit(body);
+            return;
+        }
+        self.emit_leading_comments(body);
+        if block.statements.is_empty() {
+            self.write("{ }");
+        } else {
+            self.write("{ ");
+            self.emit_list(&block.statements.nodes, " ");
+            self.write(" }");
+        }
+        self.emit_trailing_comments(body);
+    }
+
+    fn emit_function(&mut self, node: &Arc<Node>) {
+        self.emit_modifiers(node);
+        self.write("function");
+        let asterisk = if node.kind == SyntaxKind::FunctionDeclaration {
+            node.as_function_declaration().asterisk_token.is_some()
+        } else {
+            node.as_function_expression().asterisk_token.is_some()
+        };
+        if asterisk {
+            self.write("*");
+        }
+        self.writer.write_space();
+        if let Some(name) = node.name() {
+            self.emit(name);
+        }
+        self.emit_signature_and_body(node);
+    }
+
+    fn emit_arrow_function(&mut self, node: &Arc<Node>) {
+        let data = node.as_arrow_function();
+        self.emit_modifiers(node);
+        if self.can_emit_simple_arrow_head(node) {
+            self.emit(&data.parameters.nodes[0]);
+        } else {
+            self.emit_signature(node);
+        }
+        self.write(" =>");
+        self.writer.write_space();
+        if data.body.kind == SyntaxKind::Block {
+            self.emit_function_body(&data.body);
+        } else {
+            self.emit_with_parens(&data.body, arrow_body_needs_parens(&data.body));
+        }
+    }
+
+    /// Whether an arrow function can be written as `x => ...`, which is when it has a single
+    /// plain parameter that was not written in parentheses
+    fn can_emit_simple_arrow_head(&self, node: &Node) -> bool {
+        let data = node.as_arrow_function();
+        let [parameter] = data.parameters.nodes.as_slice() else {
+            return false;
+        };
+        let parameter_data = parameter.as_parameter();
+        parameter.pos() == node.pos()
+            && data.modifiers.is_none()
+            && data.type_parameters.is_none()
+            && data.type_node.is_none()
+            && parameter_data.modifiers.is_none()
+            && parameter_data.dot_dot_dot_token.is_none()
+            && parameter_data.question_token.is_none()
+            && parameter_data.type_node.is_none()
+            && parameter_data.initializer.is_none()
+            && parameter_data.name.kind == SyntaxKind::Identifier
+    }
+
+    fn emit_keyword_operand(&mut self, keyword: &str, operand: Option<&Arc<Node>>) {
+        let operand = operand.expect("unary expressions have operands");
+        self.write(keyword);
+        self.emit_with_parens(operand, unary_operand_needs_parens(operand));
+    }
+
+    fn emit_prefix_unary(&mut self, node: &Arc<Node>) {
+        let data = node.as_prefix_unary_expression();
+        self.write_token(data.operator);
+        // `+ +x` and `- --x` must not run together into `++x` and `---x`
+        if data.operand.kind == SyntaxKind::PrefixUnaryExpression {
+            let operand_operator = data.operand.as_prefix_unary_expression().operator;
+            let needs_space = match data.operator {
+                SyntaxKind::PlusToken => matches!(
+                    operand_operator,
+                    SyntaxKind::PlusToken | SyntaxKind::PlusPlusToken
+                ),
+                SyntaxKind::MinusToken => matches!(
+                    operand_operator,
+                    SyntaxKind::MinusToken | SyntaxKind::MinusMinusToken
+                ),
+                _ => false,
+            };
+            if needs_space {
+                self.writer.write_space();
+            }
+        }
+        self.emit_with_parens(&data.operand, unary_operand_needs_parens(&data.operand));
+    }
+
+    /// Writes a binary expression, and the binary expressions among its operands, from a stack
+    /// of pending steps rather than by recursion, as long chains of operators nest as deeply
+    /// as they are long
+    fn emit_binary(&mut self, node: &Arc<Node>) {
+        let mut steps = Vec::new();
+        push_binary_steps(&mut steps, node);
+        while let Some(step) = steps.pop() {
+            match step {
+                BinaryStep::Operand(operand, parenthesize) => {
+                    if operand.kind != SyntaxKind::BinaryExpression {
+                        self.emit_with_parens(operand, parenthesize);
+                        continue;
+                    }
+                    // What `emit` would do for the operand, with its operands left on the stack
+                    if parenthesize {
+                        self.write("(");
+                    }
+                    self.emit_leading_comments(operand);
+                    self.emit_source_position(operand);
+                    steps.push(BinaryStep::End(operand, parenthesize));
+                    push_binary_steps(&mut steps, operand);
+                }
+                BinaryStep::Operator(operator_token) => {
+                    if operator_token.kind == SyntaxKind::CommaToken {
+                        self.write(",");
+                    } else {
+                        self.writer.write_space();
+                        self.emit(operator_token);
+                    }
+                    self.writer.write_space();
+                }
+                BinaryStep::End(operand, parenthesize) => {
+                    self.emit_trailing_comments(operand);
+                    if parenthesize {
+                        self.write(")");
+                    }
+                }
+            }
+        }
+    }
+
+    fn emit_property_access(&mut self, node: &Arc<Node>) {
+        let data = node.as_property_access_expression();
+        self.emit_with_parens(&data.expression, left_side_needs_parens(&data.expression));
+        // `1.toString()` would read the dot as a decimal point
+        if data.question_dot_token.is_none() && self.is_integer_literal(&data.expression) {
+            self.write(".");
+        }
+        self.write(if data.question_dot_token.is_some() {
+            "?."
+        } else {
+            "."
+        });
+        self.emit(&data.name);
+    }
+
+    fn is_integer_literal(&self, node: &Node) -> bool {
+        // An inlined constant is followed by its comment, when one is written, and not the dot
+        if node.kind == SyntaxKind::PartiallyEmittedExpression {
+            let data = node.as_partially_emitted_expression();
+            return (data.trailing_comment.is_none() || self.options.remove_comments)
+                && self.is_integer_literal(&data.expression);
+        }
+        if node.kind != SyntaxKind::NumericLiteral {
+            return false;
+        }
+        let text = self.get_source_text(node).unwrap_or(node.text());
+        text.bytes()
+            .all(|byte| byte.is_ascii_digit() || byte == b'_')
+    }
+
+    fn emit_arguments(&mut self, arguments: &NodeList) {
+        self.write("(");
+        self.emit_comma_list(arguments);
+        self.write(")");
+    }
+
+    /// Writes the expression of a `return` or `throw`, which cannot start on a later line, so
+    /// it is parenthesized if a comment before it ends its line
+    fn emit_restricted_expression(&mut self, expression: Option<&Arc<Node>>) {
+        if let Some(expression) = expression {
+            self.writer.write_space();
+            let parenthesize = self.has_leading_line_break(expression);
+            self.emit_with_parens(expression, parenthesize);
+        }
+    }
+
+    fn emit_array_literal(&mut self, node: &Arc<Node>) {
+        let data = node.as_array_literal_expression();
+        let elements = &data.elements;
+        self.write("[");
+        if data.multi_line && !elements.is_empty() {
+            self.writer.increase_indent();
+            for (index, element) in elements.iter().enumerate() {
+                self.writer.write_line();
+                self.emit_comma_disallowed(element);
+                if index + 1 < elements.len() || elements.has_trailing_comma {
+                    self.write(",");
+                }
+            }
+            self.writer.decrease_indent();
+            self.writer.write_line();
+        } else {
+            self.emit_comma_list(elements);
+            if elements.has_trailing_comma {
+                self.write(",");
+            }
+        }
+        self.write("]");
+    }
+
+    fn emit_object_literal(&mut self, node: &Arc<Node>) {
+        let data = node.as_object_literal_expression();
+        let properties = &data.properties;
+        if properties.is_empty() {
+            self.write("{}");
+        } else if data.multi_line {
+            self.write("{");
+            self.emit_lines(node, properties, ",");
+            self.write("}");
+        } else {
+            self.write("{ ");
+            self.emit_list(&properties.nodes, ", ");
+            self.write(" }");
+        }
+    }
+
+    fn emit_type_literal(&mut self, node: &Arc<Node>) {
+        let members = &node.as_type_literal_node().members;
+        if members.is_empty() {
+            self.write("{}");
+        } else if self.is_on_single_line(node) {
+            self.write("{ ");
+            self.emit_list(&members.nodes, " ");
+            self.write(" }");
+        } else {
+            self.emit_block_statements(node, members);
+        }
+    }
+
+    fn emit_mapped_type(&mut self, node: &Arc<Node>) {
+        let data = node.as_mapped_type_node();
+        let single_line = self.is_on_single_line(node);
+        self.write("{");
+        if single_line {
+            self.writer.write_space();
+        } else {
+            self.writer.increase_indent();
+            self.writer.write_line();
+        }
+        if let Some(readonly_token) = &data.readonly_token {
+            self.write_token(readonly_token.kind);
+            if readonly_token.kind != SyntaxKind::ReadonlyKeyword {
+                self.write("readonly");
+            }
+            self.writer.write_space();
+        }
+        self.write("[");
+        let type_parameter = data.type_parameter.as_type_parameter();
+        self.emit(&type_parameter.name);
+        self.emit_optional(" in ", type_parameter.constraint.as_ref());
+        self.emit_optional(" as ", data.name_type.as_ref());
+        self.write("]");
+        if let Some(question_token) = &data.question_token {
+            self.write_token(question_token.kind);
+            if question_token.kind != SyntaxKind::QuestionToken {
+                self.write("?");
+            }
+        }
+        self.emit_type_annotation(data.type_node.as_ref());
+        self.write(";");
+        if single_line {
+            self.writer.write_space();
+        } else {
+            self.writer.decrease_indent();
+            self.writer.write_line();
+        }
+        self.write("}");
+    }
+
+    fn emit_class(&mut self, node: &Arc<Node>) {
+        self.emit_modifiers(node);
+        self.write("class");
+        if let Some(name) = node.name() {
+            self.writer.write_space();
+            self.emit(name);
+        }
+        let heritage_clauses = if node.kind == SyntaxKind::ClassDeclaration {
+            node.as_class_declaration().heritage_clauses.as_ref()
+        } else {
+            node.as_class_expression().heritage_clauses.as_ref()
+        };
+        self.emit_type_parameters(node.type_parameters());
+        self.emit_heritage_clauses(heritage_clauses);
+        self.write(" ");
+        self.emit_block_statements(node, node.members().expect("classes have members"));
+    }
+
+    fn emit_heritage_clauses(&mut self, heritage_clauses: Option<&NodeList>) {
+        if let Some(heritage_clauses) = heritage_clauses {
+            for clause in heritage_clauses.iter() {
+                self.writer.write_space();
+                self.emit(clause);
+            }
+        }
+    }
+
+    fn emit_if_statement(&mut self, node: &Arc<Node>) {
+        let data = node.as_if_statement();
+        self.write("if (");
+        self.emit(&data.expression);
+        self.write(")");
+        if data.else_statement.is_some() && ends_with_if_without_else(&data.then_statement) {
+            // The `else` would otherwise belong to the nested `if`
+            self.write(" {");
+            self.writer.increase_indent();
+            self.writer.write_line();
+            self.emit(&data.then_statement);
+            self.writer.decrease_indent();
+            self.writer.write_line();
+            self.write("}");
+        } else {
+            self.emit_embedded_statement(&data.then_statement);
+        }
+        if let Some(else_statement) = &data.else_statement {
+            self.writer.write_line();
+            self.write("else");
+            if else_statement.kind == SyntaxKind::IfStatement {
+                self.writer.write_space();
+                self.emit(else_statement);
+            } else {
+                self.emit_embedded_statement(else_statement);
+            }
+        }
+    }
+
+    /// Writes the statements of a case clause, keeping a single statement on the line of its
+    /// label if it was written there
+    fn emit_case_clause_statements(&mut self, node: &Node, statements: &NodeList) {
+        let same_line = statements.len() == 1
+            && match self.source_text {
+                Some(text)
+                    if self.has_source_position(node)
+                        && self.has_source_position(&statements.nodes[0]) =>
+                {
+                    let start = skip_trivia(text, node.pos());
+                    let statement_start = skip_trivia(text, statements.nodes[0].pos());
+                    !text[start..statement_start].contains(is_line_break)
+                }
+                _ => true,
+            };
+        if same_line {
+            self.writer.write_space();
+            self.emit(&statements.nodes[0]);
+            return;
+        }
+        self.writer.increase_indent();
+        for statement in statements.iter() {
+            self.writer.write_line();
+            self.emit(statement);
+        }
+        self.writer.decrease_indent();
+    }
+
+    fn emit_module_declaration(&mut self, node: &Arc<Node>) {
+        let data = node.as_module_declaration();
+        self.emit_modifiers(node);
+        if data.keyword != SyntaxKind::GlobalKeyword {
+            self.write_token(data.keyword);
+            self.writer.write_space();
+        }
+        self.emit(&data.name);
+        let mut body = data.body.as_ref();
+        while let Some(inner) = body.filter(|body| body.kind == SyntaxKind::ModuleDeclaration) {
+            let inner = inner.as_module_declaration();
+            self.write(".");
+            self.emit(&inner.name);
+            body = inner.body.as_ref();
+        }
+        match body {
+            Some(body) => {
+                self.writer.write_space();
+                self.emit(body);
+            }
+            None => self.write(";"),
+        }
+    }
+
+    fn emit_specifier(
+        &mut self,
+        is_type_only: bool,
+        property_name: Option<&Arc<Node>>,
+        name: &Arc<Node>,
+    ) {
+        if is_type_only {
+            self.write("type ");
+        }
+        if let Some(property_name) = property_name {
+            self.emit(property_name);
+            self.write(" as ");
+        }
+        self.emit(name);
+    }
+
+    fn emit_import_attribute_elements(&mut self, node: &Node) {
+        let elements = &node.as_import_attributes().elements;
+        if elements.is_empty() {
+            self.write("{}");
+        } else {
+            self.write("{ ");
+            self.emit_list(&elements.nodes, ", ");
+            self.write(" }");
+        }
+    }
+}
+
+/// A step in writing a binary expression
+enum BinaryStep<'a> {
+    /// Writing an operand, in parentheses if the flag is set
+    Operand(&'a Arc<Node>, bool),
+    /// Writing the operator between the operands
+    Operator(&'a Arc<Node>),
+    /// Finishing a binary expression written as an operand, closing its parentheses if the
+    /// flag is set
+    End(&'a Arc<Node>, bool),
+}
+
+/// Pushes the steps that write the operands and operator of a binary expression, last first
+fn push_binary_steps<'a>(steps: &mut Vec<BinaryStep<'a>>, node: &'a Arc<Node>) {
+    let data = node.as_binary_expression();
+    let operator = data.operator_token.kind;
+    steps.push(BinaryStep::Operand(
+        &data.right,
+        binary_operand_needs_parens(operator, &data.right, false),
+    ));
+    steps.push(BinaryStep::Operator(&data.operator_token));
+    steps.push(BinaryStep::Operand(
+        &data.left,
+        binary_operand_needs_parens(operator, &data.left, true),
+    ));
+}
+
+/// Whether a statement ends with an `if` that has no `else`, which a following `else` would
+/// attach to
+fn ends_with_if_without_else(statement: &Node) -> bool {
+    match statement.kind {
+        SyntaxKind::IfStatement => statement
+            .as_if_statement()
+            .else_statement
+            .as_ref()
+            .is_none_or(|else_statement| ends_with_if_without_else(else_statement)),
+        SyntaxKind::WhileStatement => {
+            ends_with_if_without_else(&statement.as_while_statement().statement)
+        }
+        SyntaxKind::ForStatement => {
+            ends_with_if_without_else(&statement.as_for_statement().statement)
+        }
+        SyntaxKind::ForInStatement => {
+            ends_with_if_without_else(&statement.as_for_in_statement().statement)
+        }
+        SyntaxKind::ForOfStatement => {
+            ends_with_if_without_else(&statement.as_for_of_statement().statement)
+        }
+        SyntaxKind::WithStatement => {
+            ends_with_if_without_else(&statement.as_with_statement().statement)
+        }
+        SyntaxKind::LabeledStatement => {
+            ends_with_if_without_else(&statement.as_labeled_statement().statement)
+        }
+        _ => false,
+    }
+}
+
+/// Quotes a string, escaping the quote, backslashes, line terminators, control characters,
+/// and characters outside ASCII
+pub(super) fn escape_string(text: &str, quote: char) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push(quote);
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{b}' => out.push_str("\\v"),
+            '\u{c}' => out.push_str("\\f"),
+            // `\0` followed by a digit would be read as an octal escape
+            '\0' if chars.peek().is_some_and(|next| next.is_ascii_digit()) => out.push_str("\\x00"),
+            '\0' => out.push_str("\\0"),
+            ch if ch == quote => {
+                out.push('\\');
+                out.push(ch);
+            }
+            ch if ch.is_ascii_control() || !ch.is_ascii() => {
+                let mut units = [0; 2];
+                for unit in ch.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{unit:04X}"));
+                }
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push(quote);
+    out
+}
+
+/// Escapes the cooked text of a template literal part so it can be written between backticks
+fn escape_template(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '`' => out.push_str("\\`"),
+            '\r' => out.push_str("\\r"),
+            '$' if chars.peek() == Some(&'{') => out.push_str("\\$"),
+            ch => out.push(ch),
+        }
+    }
+    out
+}
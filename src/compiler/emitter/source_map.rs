@@ -0,0 +1,135 @@
+//! Source maps, which map positions in printed text back to the source it was printed from
+//!
+//! Maps are written in the version 3 format. Its `mappings` field lists the mapped positions
+//! of each generated line, separated by `;`, with each position written as base64 VLQs of the
+//! differences from the position before it.
+
+/// A position in printed text and the position in the source it was printed from, as 0-based
+/// lines and UTF-16 columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub generated_line: usize,
+    pub generated_column: usize,
+    pub source_line: usize,
+    pub source_column: usize,
+}
+
+/// Builds the source map of one output file from the mappings of each source printed into it
+#[derive(Debug, Default)]
+pub struct SourceMapGenerator {
+    sources: Vec<String>,
+    /// Each mapping with the index of its source, in the order of the generated positions
+    mappings: Vec<(usize, Mapping)>,
+}
+
+impl SourceMapGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a source whose printed text starts `line_offset` lines into the output, with the
+    /// mappings from printing it on its own
+    pub fn add_source(
+        &mut self,
+        source: impl Into<String>,
+        mappings: &[Mapping],
+        line_offset: usize,
+    ) {
+        let index = self.sources.len();
+        self.sources.push(source.into());
+        self.mappings.extend(mappings.iter().map(|&mapping| {
+            (
+                index,
+                Mapping {
+                    generated_line: mapping.generated_line + line_offset,
+                    ..mapping
+                },
+            )
+        }));
+    }
+
+    /// Writes the map of the output file `file` as JSON
+    pub fn to_json(&self, file: &str) -> String {
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| json_string(source))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"version\":3,\"file\":{},\"sourceRoot\":\"\",\"sources\":[{}],\"names\":[],\"mappings\":\"{}\"}}",
+            json_string(file),
+            sources,
+            self.encode_mappings()
+        )
+    }
+
+    fn encode_mappings(&self) -> String {
+        let mut encoded = String::new();
+        let mut line = 0;
+        let mut previous_column = 0;
+        let mut previous_source = 0;
+        let mut previous_source_line = 0;
+        let mut previous_source_column = 0;
+        let mut first_on_line = true;
+        for &(source, mapping) in &self.mappings {
+            while line < mapping.generated_line {
+                encoded.push(';');
+                line += 1;
+                previous_column = 0;
+                first_on_line = true;
+            }
+            if !first_on_line {
+                encoded.push(',');
+            }
+            first_on_line = false;
+            for (value, previous) in [
+                (mapping.generated_column, &mut previous_column),
+                (source, &mut previous_source),
+                (mapping.source_line, &mut previous_source_line),
+                (mapping.source_column, &mut previous_source_column),
+            ] {
+                encode_vlq(&mut encoded, value as i64 - *previous as i64);
+                *previous = value;
+            }
+        }
+        encoded
+    }
+}
+
+/// Writes a number as a base64 VLQ: groups of five bits, least significant first, each with a
+/// continuation bit, after a sign bit in the lowest place
+fn encode_vlq(out: &mut String, value: i64) {
+    const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut remaining = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = remaining & 0b11111;
+        remaining >>= 5;
+        if remaining > 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64[digit as usize] as char);
+        if remaining == 0 {
+            break;
+        }
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            ch if (ch as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
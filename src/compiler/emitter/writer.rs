@@ -0,0 +1,98 @@
+//! Accumulating printed text with indentation
+
+/// Collects printed text, indenting each line as it is started
+pub(super) struct TextWriter {
+    out: String,
+    new_line: &'static str,
+    indent_unit: String,
+    indent: usize,
+    line_start: bool,
+    /// The number of line breaks written
+    line: usize,
+    /// Where the current line starts in the text
+    line_offset: usize,
+}
+
+impl TextWriter {
+    pub(super) fn new(new_line: &'static str, indent_unit: String) -> Self {
+        TextWriter {
+            out: String::new(),
+            new_line,
+            indent_unit,
+            indent: 0,
+            line_start: true,
+            line: 0,
+            line_offset: 0,
+        }
+    }
+
+    /// Writes text, first indenting the line if nothing has been written on it yet
+    pub(super) fn write(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.line_start {
+            for _ in 0..self.indent {
+                self.out.push_str(&self.indent_unit);
+            }
+            self.line_start = false;
+        }
+        self.out.push_str(text);
+        // Comments and template literals can span lines
+        if let Some(last_break) = text.rfind('\n') {
+            self.line += text.matches('\n').count();
+            self.line_offset = self.out.len() - text.len() + last_break + 1;
+        }
+    }
+
+    pub(super) fn write_space(&mut self) {
+        self.write(" ");
+    }
+
+    /// Ends the current line, unless nothing has been written on it
+    pub(super) fn write_line(&mut self) {
+        if !self.line_start {
+            self.force_write_line();
+        }
+    }
+
+    /// Ends the current line even if nothing has been written on it, as for a blank line
+    pub(super) fn force_write_line(&mut self) {
+        self.out.push_str(self.new_line);
+        self.line_start = true;
+        self.line += 1;
+        self.line_offset = self.out.len();
+    }
+
+    /// Gets the 0-based line and UTF-16 column the next text will be written at, after the
+    /// indentation of the line if it is not written yet
+    pub(super) fn position(&self) -> (usize, usize) {
+        let column = if self.line_start {
+            self.indent * self.indent_unit.encode_utf16().count()
+        } else {
+            self.out[self.line_offset..].encode_utf16().count()
+        };
+        (self.line, column)
+    }
+
+    pub(super) fn is_at_start_of_line(&self) -> bool {
+        self.line_start
+    }
+
+    pub(super) fn increase_indent(&mut self) {
+        self.indent += 1;
+    }
+
+    pub(super) fn decrease_indent(&mut self) {
+        self.indent -= 1;
+    }
+
+    /// Gets the last character written
+    pub(super) fn last_char(&self) -> Option<char> {
+        self.out.chars().next_back()
+    }
+
+    pub(super) fn into_text(self) -> String {
+        self.out
+    }
+}
@@ -0,0 +1,180 @@
+//! The standard library declaration files, which describe the globals of the runtime
+//!
+//! Each library has a name, like `es2015.promise` or `dom`, which the `lib` option and
+//! `/// <reference lib="..." />` directives use, and a file, like `lib.es2015.promise.d.ts`.
+//! Without the `lib` option, a program includes the default library of its target, which
+//! references the libraries that make it up.
+
+use crate::compiler::scanner::ScriptTarget;
+
+/// The names of the libraries and their files, in the order tsc lists them
+const LIBS: &[(&str, &str)] = &[
+    // JavaScript only
+    ("es5", "lib.es5.d.ts"),
+    ("es6", "lib.es2015.d.ts"),
+    ("es2015", "lib.es2015.d.ts"),
+    ("es7", "lib.es2016.d.ts"),
+    ("es2016", "lib.es2016.d.ts"),
+    ("es2017", "lib.es2017.d.ts"),
+    ("es2018", "lib.es2018.d.ts"),
+    ("es2019", "lib.es2019.d.ts"),
+    ("es2020", "lib.es2020.d.ts"),
+    ("es2021", "lib.es2021.d.ts"),
+    ("es2022", "lib.es2022.d.ts"),
+    ("es2023", "lib.es2023.d.ts"),
+    ("es2024", "lib.es2024.d.ts"),
+    ("esnext", "lib.esnext.d.ts"),
+    // Host only
+    ("dom", "lib.dom.d.ts"),
+    ("dom.iterable", "lib.dom.iterable.d.ts"),
+    ("dom.asynciterable", "lib.dom.asynciterable.d.ts"),
+    ("webworker", "lib.webworker.d.ts"),
+    (
+        "webworker.importscripts",
+        "lib.webworker.importscripts.d.ts",
+    ),
+    ("webworker.iterable", "lib.webworker.iterable.d.ts"),
+    (
+        "webworker.asynciterable",
+        "lib.webworker.asynciterable.d.ts",
+    ),
+    ("scripthost", "lib.scripthost.d.ts"),
+    // ES2015 and later, by feature
+    ("es2015.core", "lib.es2015.core.d.ts"),
+    ("es2015.collection", "lib.es2015.collection.d.ts"),
+    ("es2015.generator", "lib.es2015.generator.d.ts"),
+    ("es2015.iterable", "lib.es2015.iterable.d.ts"),
+    ("es2015.promise", "lib.es2015.promise.d.ts"),
+    ("es2015.proxy", "lib.es2015.proxy.d.ts"),
+    ("es2015.reflect", "lib.es2015.reflect.d.ts"),
+    ("es2015.symbol", "lib.es2015.symbol.d.ts"),
+    (
+        "es2015.symbol.wellknown",
+        "lib.es2015.symbol.wellknown.d.ts",
+    ),
+    ("es2016.array.include", "lib.es2016.array.include.d.ts"),
+    ("es2016.intl", "lib.es2016.intl.d.ts"),
+    ("es2017.arraybuffer", "lib.es2017.arraybuffer.d.ts"),
+    ("es2017.date", "lib.es2017.date.d.ts"),
+    ("es2017.object", "lib.es2017.object.d.ts"),
+    ("es2017.sharedmemory", "lib.es2017.sharedmemory.d.ts"),
+    ("es2017.string", "lib.es2017.string.d.ts"),
+    ("es2017.intl", "lib.es2017.intl.d.ts"),
+    ("es2017.typedarrays", "lib.es2017.typedarrays.d.ts"),
+    ("es2018.asyncgenerator", "lib.es2018.asyncgenerator.d.ts"),
+    ("es2018.asynciterable", "lib.es2018.asynciterable.d.ts"),
+    ("es2018.intl", "lib.es2018.intl.d.ts"),
+    ("es2018.promise", "lib.es2018.promise.d.ts"),
+    ("es2018.regexp", "lib.es2018.regexp.d.ts"),
+    ("es2019.array", "lib.es2019.array.d.ts"),
+    ("es2019.object", "lib.es2019.object.d.ts"),
+    ("es2019.string", "lib.es2019.string.d.ts"),
+    ("es2019.symbol", "lib.es2019.symbol.d.ts"),
+    ("es2019.intl", "lib.es2019.intl.d.ts"),
+    ("es2020.bigint", "lib.es2020.bigint.d.ts"),
+    ("es2020.date", "lib.es2020.date.d.ts"),
+    ("es2020.promise", "lib.es2020.promise.d.ts"),
+    ("es2020.sharedmemory", "lib.es2020.sharedmemory.d.ts"),
+    ("es2020.string", "lib.es2020.string.d.ts"),
+    (
+        "es2020.symbol.wellknown",
+        "lib.es2020.symbol.wellknown.d.ts",
+    ),
+    ("es2020.intl", "lib.es2020.intl.d.ts"),
+    ("es2020.number", "lib.es2020.number.d.ts"),
+    ("es2021.promise", "lib.es2021.promise.d.ts"),
+    ("es2021.string", "lib.es2021.string.d.ts"),
+    ("es2021.weakref", "lib.es2021.weakref.d.ts"),
+    ("es2021.intl", "lib.es2021.intl.d.ts"),
+    ("es2022.array", "lib.es2022.array.d.ts"),
+    ("es2022.error", "lib.es2022.error.d.ts"),
+    ("es2022.intl", "lib.es2022.intl.d.ts"),
+    ("es2022.object", "lib.es2022.object.d.ts"),
+    ("es2022.regexp", "lib.es2022.regexp.d.ts"),
+    ("es2022.string", "lib.es2022.string.d.ts"),
+    ("es2023.array", "lib.es2023.array.d.ts"),
+    ("es2023.collection", "lib.es2023.collection.d.ts"),
+    ("es2023.intl", "lib.es2023.intl.d.ts"),
+    ("es2024.arraybuffer", "lib.es2024.arraybuffer.d.ts"),
+    ("es2024.collection", "lib.es2024.collection.d.ts"),
+    ("es2024.object", "lib.es2024.object.d.ts"),
+    ("es2024.promise", "lib.es2024.promise.d.ts"),
+    ("es2024.regexp", "lib.es2024.regexp.d.ts"),
+    ("es2024.sharedmemory", "lib.es2024.sharedmemory.d.ts"),
+    ("es2024.string", "lib.es2024.string.d.ts"),
+    ("esnext.array", "lib.es2023.array.d.ts"),
+    ("esnext.collection", "lib.esnext.collection.d.ts"),
+    ("esnext.symbol", "lib.es2019.symbol.d.ts"),
+    ("esnext.asynciterable", "lib.es2018.asynciterable.d.ts"),
+    ("esnext.intl", "lib.esnext.intl.d.ts"),
+    ("esnext.disposable", "lib.esnext.disposable.d.ts"),
+    ("esnext.bigint", "lib.es2020.bigint.d.ts"),
+    ("esnext.string", "lib.es2022.string.d.ts"),
+    ("esnext.promise", "lib.es2021.promise.d.ts"),
+    ("esnext.weakref", "lib.es2021.weakref.d.ts"),
+    ("esnext.decorators", "lib.esnext.decorators.d.ts"),
+    ("esnext.object", "lib.es2024.object.d.ts"),
+    ("esnext.regexp", "lib.es2024.regexp.d.ts"),
+    ("esnext.iterator", "lib.esnext.iterator.d.ts"),
+    ("esnext.float16", "lib.esnext.float16.d.ts"),
+    ("decorators", "lib.decorators.d.ts"),
+    ("decorators.legacy", "lib.decorators.legacy.d.ts"),
+];
+
+/// The names of the libraries, as the `lib` option takes them
+pub fn lib_names() -> impl Iterator<Item = &'static str> {
+    LIBS.iter().map(|(name, _)| *name)
+}
+
+/// Gets the file of a library, whose name is not case-sensitive
+pub fn lib_file_name(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
+    LIBS.iter()
+        .find(|(lib, _)| *lib == name)
+        .map(|(_, file_name)| *file_name)
+}
+
+/// Gets the file of the library a program includes when the `lib` option is not set, which
+/// has the JavaScript globals of the target, and those of the DOM
+pub fn default_lib_file_name(target: ScriptTarget) -> &'static str {
+    match target {
+        ScriptTarget::ES3 | ScriptTarget::ES5 => "lib.d.ts",
+        ScriptTarget::ES2015 => "lib.es6.d.ts",
+        ScriptTarget::ES2016 => "lib.es2016.full.d.ts",
+        ScriptTarget::ES2017 => "lib.es2017.full.d.ts",
+        ScriptTarget::ES2018 => "lib.es2018.full.d.ts",
+        ScriptTarget::ES2019 => "lib.es2019.full.d.ts",
+        ScriptTarget::ES2020 => "lib.es2020.full.d.ts",
+        ScriptTarget::ES2021 => "lib.es2021.full.d.ts",
+        ScriptTarget::ES2022 => "lib.es2022.full.d.ts",
+        ScriptTarget::ES2023 => "lib.es2023.full.d.ts",
+        ScriptTarget::ES2024 => "lib.es2024.full.d.ts",
+        ScriptTarget::ESNext | ScriptTarget::Latest => "lib.esnext.full.d.ts",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_names_to_files() {
+        assert_eq!(
+            lib_file_name("es2015.promise"),
+            Some("lib.es2015.promise.d.ts")
+        );
+        assert_eq!(lib_file_name("ES6"), Some("lib.es2015.d.ts"));
+        assert_eq!(lib_file_name("DOM.Iterable"), Some("lib.dom.iterable.d.ts"));
+        assert_eq!(lib_file_name("es2015.promises"), None);
+        assert_eq!(default_lib_file_name(ScriptTarget::ES5), "lib.d.ts");
+        assert_eq!(default_lib_file_name(ScriptTarget::ES2015), "lib.es6.d.ts");
+        assert_eq!(
+            default_lib_file_name(ScriptTarget::ES2023),
+            "lib.es2023.full.d.ts"
+        );
+        assert_eq!(
+            default_lib_file_name(ScriptTarget::ESNext),
+            "lib.esnext.full.d.ts"
+        );
+    }
+}
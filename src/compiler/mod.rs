@@ -1,4 +1,14 @@
 pub mod ast;
+pub mod binder;
+pub mod cancellation;
+pub mod checker;
 pub mod diagnostics;
+pub mod emitter;
+pub mod lib_files;
+pub mod module_resolution;
+pub mod parser;
+pub mod paths;
 pub mod scanner;
+pub mod text;
+pub mod transformers;
 // pub mod types;
@@ -0,0 +1,1204 @@
+//! Resolution of module specifiers to the files they refer to
+//!
+//! Relative specifiers like `./util` are looked up next to the importing file. How other
+//! specifiers are looked up depends on the resolution kind:
+//!
+//! - `classic` looks for the module in the folder of the importing file and each folder above
+//!   it, and only then in `node_modules/@types`.
+//! - `node10` follows Node's CommonJS lookup: each `node_modules` folder from the importing
+//!   file up to the root is searched for the package, which is resolved through the `types`,
+//!   `typings`, and `main` fields of its `package.json`, or its `index` file.
+//! - `node16` and `nodenext` follow Node's newer lookup, where the `exports` map of a package
+//!   decides which of its files can be imported, and `#name` specifiers go through the
+//!   `imports` map of the importing package. Which conditions of those maps apply depends on
+//!   whether the import is an ECMAScript import or a `require`, and ECMAScript imports of
+//!   relative paths must name the file exactly, extension included.
+//! - `bundler` uses the `exports` and `imports` maps like `node16`, but lets any import leave
+//!   out extensions and `index` files, as bundlers do.
+//!
+//! Each lookup first tries TypeScript files and declaration files, and only when none is
+//! found, JavaScript files. With `--traceResolution`, every step is written to the host, with
+//! the messages tsc uses.
+//!
+//! Type reference directives, like `/// <reference types="node" />`, name packages of
+//! declarations, which are looked up in the type roots before the `node_modules` folders.
+
+mod exports;
+mod type_reference;
+
+use bitflags::bitflags;
+
+use crate::compiler::diagnostics::{self, Message};
+use crate::compiler::paths::{
+    combine_paths, get_ancestor_directories, get_base_file_name, get_directory_path,
+    is_rooted_disk_path, path_is_relative,
+};
+use crate::config::{JsonNode, JsonValue, parse_json_text};
+
+pub use exports::get_implied_node_format;
+pub use type_reference::{
+    get_automatic_type_directive_names, get_effective_type_roots, resolve_type_reference_directive,
+};
+
+/// The algorithm that looks up non-relative module specifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleResolutionKind {
+    Classic,
+    Node10,
+    Node16,
+    NodeNext,
+    Bundler,
+}
+
+impl ModuleResolutionKind {
+    /// The name of the kind as traces show it
+    pub fn name(self) -> &'static str {
+        match self {
+            ModuleResolutionKind::Classic => "Classic",
+            ModuleResolutionKind::Node10 => "Node10",
+            ModuleResolutionKind::Node16 => "Node16",
+            ModuleResolutionKind::NodeNext => "NodeNext",
+            ModuleResolutionKind::Bundler => "Bundler",
+        }
+    }
+
+    /// Whether the kind resolves packages through the `exports` and `imports` maps of their
+    /// `package.json` files
+    fn uses_package_json_exports(self) -> bool {
+        matches!(
+            self,
+            ModuleResolutionKind::Node16
+                | ModuleResolutionKind::NodeNext
+                | ModuleResolutionKind::Bundler
+        )
+    }
+}
+
+/// Whether an import loads an ECMAScript module or a CommonJS one, which decides the
+/// conditions of `exports` and `imports` maps that apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionMode {
+    CommonJs,
+    Esm,
+}
+
+/// The file system operations that module resolution needs
+pub trait ModuleResolutionHost {
+    fn file_exists(&self, path: &str) -> bool;
+    fn directory_exists(&self, path: &str) -> bool;
+    fn read_file(&self, path: &str) -> Option<String>;
+    /// Lists the names of the folders in a folder
+    fn get_directories(&self, path: &str) -> Vec<String>;
+    /// Writes a step of a resolution, when tracing is on
+    fn trace(&self, message: &str);
+}
+
+pub struct ModuleResolutionOptions {
+    pub kind: ModuleResolutionKind,
+    /// Whether the kind was configured, rather than implied by the module kind
+    pub kind_is_explicit: bool,
+    pub trace_resolution: bool,
+    /// The absolute paths of the folders that type reference directives are looked up in
+    pub type_roots: Vec<String>,
+    /// Whether the type roots were configured, rather than found in `node_modules` folders
+    pub type_roots_are_explicit: bool,
+    /// Whether specifiers ending in `.json` can resolve to JSON files
+    pub resolve_json_module: bool,
+}
+
+/// The file a module specifier resolved to
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedModule {
+    pub resolved_file_name: String,
+    /// The extension of the file, like `.ts` or `.d.ts`
+    pub extension: &'static str,
+    /// Whether the file was found in a `node_modules` folder, as part of an installed package
+    pub is_external_library_import: bool,
+}
+
+impl ResolvedModule {
+    /// Whether the module is a JavaScript file, which only takes part in the program with
+    /// `allowJs`
+    pub fn is_javascript(&self) -> bool {
+        matches!(self.extension, ".js" | ".jsx" | ".mjs" | ".cjs")
+    }
+}
+
+bitflags! {
+    /// The kinds of files a lookup accepts
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Extensions: u8 {
+        const TYPESCRIPT = 1 << 0;
+        const JAVASCRIPT = 1 << 1;
+        const DECLARATION = 1 << 2;
+        const JSON = 1 << 3;
+    }
+}
+
+impl Extensions {
+    /// Describes the kinds as traces do, like `TypeScript, Declaration`
+    fn describe(self) -> String {
+        let mut kinds = Vec::new();
+        if self.contains(Extensions::TYPESCRIPT) {
+            kinds.push("TypeScript");
+        }
+        if self.contains(Extensions::JAVASCRIPT) {
+            kinds.push("JavaScript");
+        }
+        if self.contains(Extensions::DECLARATION) {
+            kinds.push("Declaration");
+        }
+        if self.contains(Extensions::JSON) {
+            kinds.push("JSON");
+        }
+        kinds.join(", ")
+    }
+}
+
+/// Whether a module specifier is looked up relative to the importing file, rather than by
+/// the resolution kind's search for packages
+pub fn is_external_module_name_relative(module_name: &str) -> bool {
+    path_is_relative(module_name) || is_rooted_disk_path(module_name)
+}
+
+/// Resolves the module specifier `module_name` of an import in `containing_file`, an absolute
+/// path, to the file it refers to. `resolution_mode` is whether the import is an ECMAScript
+/// import or a `require`, which only the kinds that use `exports` maps tell apart.
+pub fn resolve_module_name(
+    module_name: &str,
+    containing_file: &str,
+    resolution_mode: ResolutionMode,
+    options: &ModuleResolutionOptions,
+    host: &impl ModuleResolutionHost,
+) -> Option<ResolvedModule> {
+    let resolver = Resolver::new(options, host, resolution_mode);
+    resolver.trace(
+        diagnostics::RESOLVING_MODULE_0_FROM_1_6086,
+        &[module_name, containing_file],
+    );
+    if options.kind_is_explicit {
+        resolver.trace(
+            diagnostics::EXPLICITLY_SPECIFIED_MODULE_RESOLUTION_KIND_COLON_0_6087,
+            &[options.kind.name()],
+        );
+    } else {
+        resolver.trace(
+            diagnostics::MODULE_RESOLUTION_KIND_IS_NOT_SPECIFIED_USING_0_6088,
+            &[options.kind.name()],
+        );
+    }
+    if options.kind.uses_package_json_exports() {
+        let conditions: Vec<String> = resolver
+            .conditions
+            .iter()
+            .map(|condition| format!("'{condition}'"))
+            .collect();
+        resolver.trace(
+            diagnostics::RESOLVING_IN_0_MODE_WITH_CONDITIONS_1_6402,
+            &[
+                match resolution_mode {
+                    ResolutionMode::CommonJs => "CJS",
+                    ResolutionMode::Esm => "ESM",
+                },
+                &conditions.join(", "),
+            ],
+        );
+    }
+
+    let containing_directory = get_directory_path(containing_file);
+    let resolve = |extensions| match options.kind {
+        ModuleResolutionKind::Classic => {
+            resolver.classic_resolve(extensions, module_name, &containing_directory)
+        }
+        _ => resolver.node_resolve(extensions, module_name, &containing_directory),
+    };
+    let fallback = if options.resolve_json_module {
+        Extensions::JAVASCRIPT | Extensions::JSON
+    } else {
+        Extensions::JAVASCRIPT
+    };
+    let resolved =
+        resolve(Extensions::TYPESCRIPT | Extensions::DECLARATION).or_else(|| resolve(fallback));
+
+    match &resolved {
+        Some(resolved) => resolver.trace(
+            diagnostics::MODULE_NAME_0_WAS_SUCCESSFULLY_RESOLVED_TO_1_6089,
+            &[module_name, &resolved.resolved_file_name],
+        ),
+        None => resolver.trace(
+            diagnostics::MODULE_NAME_0_WAS_NOT_RESOLVED_6090,
+            &[module_name],
+        ),
+    }
+    resolved
+}
+
+struct Resolver<'a, H: ModuleResolutionHost> {
+    options: &'a ModuleResolutionOptions,
+    host: &'a H,
+    /// The conditions of `exports` and `imports` maps that apply, besides `default`
+    conditions: Vec<&'static str>,
+    /// Whether relative paths must name files exactly, as in Node's ECMAScript imports
+    esm_mode: bool,
+}
+
+impl<'a, H: ModuleResolutionHost> Resolver<'a, H> {
+    fn new(
+        options: &'a ModuleResolutionOptions,
+        host: &'a H,
+        resolution_mode: ResolutionMode,
+    ) -> Self {
+        let mut conditions = vec![match resolution_mode {
+            ResolutionMode::CommonJs => "require",
+            ResolutionMode::Esm => "import",
+        }];
+        conditions.push("types");
+        if options.kind != ModuleResolutionKind::Bundler {
+            conditions.push("node");
+        }
+        Resolver {
+            options,
+            host,
+            conditions,
+            esm_mode: resolution_mode == ResolutionMode::Esm
+                && matches!(
+                    options.kind,
+                    ModuleResolutionKind::Node16 | ModuleResolutionKind::NodeNext
+                ),
+        }
+    }
+
+    fn trace(&self, message: &Message, args: &[&str]) {
+        if self.options.trace_resolution {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            self.host
+                .trace(&diagnostics::format_message(message, &args));
+        }
+    }
+
+    fn classic_resolve(
+        &self,
+        extensions: Extensions,
+        module_name: &str,
+        containing_directory: &str,
+    ) -> Option<ResolvedModule> {
+        if is_external_module_name_relative(module_name) {
+            let candidate = combine_paths(containing_directory, module_name);
+            return self.load_module_from_file(extensions, &candidate, false);
+        }
+        // Look for the module beside the importing file, and then in each folder above it
+        let resolved = get_ancestor_directories(containing_directory).find_map(|directory| {
+            let candidate = combine_paths(&directory, module_name);
+            self.load_module_from_file(extensions, &candidate, false)
+        });
+        if resolved.is_some() || !extensions.contains(Extensions::DECLARATION) {
+            return resolved;
+        }
+        get_ancestor_directories(containing_directory)
+            .find_map(|directory| self.load_module_from_types_directory(module_name, &directory))
+    }
+
+    fn node_resolve(
+        &self,
+        extensions: Extensions,
+        module_name: &str,
+        containing_directory: &str,
+    ) -> Option<ResolvedModule> {
+        if is_external_module_name_relative(module_name) {
+            let candidate = combine_paths(containing_directory, module_name);
+            self.trace(
+                diagnostics::LOADING_MODULE_AS_FILE_SLASH_FOLDER_CANDIDATE_MODULE_LOCATION_0_TARGET_FILE_TYPES_COLON_1_6095,
+                &[&candidate, &extensions.describe()],
+            );
+            return self.load_module_by_relative_name(extensions, &candidate, false);
+        }
+        if self.options.kind.uses_package_json_exports() {
+            if module_name.starts_with('#') {
+                return self.load_module_from_imports(
+                    extensions,
+                    module_name,
+                    containing_directory,
+                );
+            }
+            // A package can import itself by its name, through its own `exports` map
+            if let Some(resolved) =
+                self.load_module_from_self_name(extensions, module_name, containing_directory)
+            {
+                return Some(resolved);
+            }
+        }
+        self.trace(
+            diagnostics::LOADING_MODULE_0_FROM_NODE_MODULES_FOLDER_TARGET_FILE_TYPES_COLON_1_6098,
+            &[module_name, &extensions.describe()],
+        );
+        get_ancestor_directories(containing_directory)
+            .filter(|directory| get_base_file_name(directory) != "node_modules")
+            .find_map(|directory| {
+                self.load_module_from_node_modules_directory(extensions, module_name, &directory)
+            })
+            .map(|resolved| ResolvedModule {
+                is_external_library_import: true,
+                ..resolved
+            })
+    }
+
+    /// Looks up a package in the `node_modules` folder of `directory`, and for declarations,
+    /// in its `node_modules/@types` folder
+    fn load_module_from_node_modules_directory(
+        &self,
+        extensions: Extensions,
+        module_name: &str,
+        directory: &str,
+    ) -> Option<ResolvedModule> {
+        let node_modules = combine_paths(directory, "node_modules");
+        let node_modules_exists = self.host.directory_exists(&node_modules);
+        if !node_modules_exists {
+            self.trace(
+                diagnostics::DIRECTORY_0_DOES_NOT_EXIST_SKIPPING_ALL_LOOKUPS_IN_IT_6148,
+                &[&node_modules],
+            );
+        }
+        let candidate = combine_paths(&node_modules, module_name);
+        if self.options.kind.uses_package_json_exports() && node_modules_exists {
+            // A package with an `exports` map can only be imported through it
+            let (package_name, rest) = parse_package_name(module_name);
+            let package_directory = combine_paths(&node_modules, package_name);
+            if let Some(package_json) = self.read_package_json(&package_directory, false)
+                && let Some(exports) = package_json.get("exports")
+            {
+                let subpath = format!(".{rest}");
+                return self.load_module_from_exports(
+                    extensions,
+                    &package_directory,
+                    &exports.initializer,
+                    &subpath,
+                );
+            }
+        }
+        let resolved = self
+            .load_module_from_file(extensions, &candidate, !node_modules_exists)
+            .or_else(|| {
+                self.load_node_module_from_directory(extensions, &candidate, !node_modules_exists)
+            });
+        if resolved.is_some() || !extensions.contains(Extensions::DECLARATION) {
+            return resolved;
+        }
+        self.load_module_from_types_directory(module_name, directory)
+    }
+
+    /// Looks up the declarations of a package in the `node_modules/@types` folder of
+    /// `directory`, where `@scope/name` is installed as `@types/scope__name`
+    fn load_module_from_types_directory(
+        &self,
+        module_name: &str,
+        directory: &str,
+    ) -> Option<ResolvedModule> {
+        let types_directory = combine_paths(directory, "node_modules/@types");
+        let types_directory_exists = self.host.directory_exists(&types_directory);
+        if !types_directory_exists {
+            // The folder above is reported missing already
+            if self
+                .host
+                .directory_exists(&combine_paths(directory, "node_modules"))
+            {
+                self.trace(
+                    diagnostics::DIRECTORY_0_DOES_NOT_EXIST_SKIPPING_ALL_LOOKUPS_IN_IT_6148,
+                    &[&types_directory],
+                );
+            }
+            return None;
+        }
+        let candidate = combine_paths(&types_directory, &mangle_scoped_package_name(module_name));
+        self.load_module_from_file(Extensions::DECLARATION, &candidate, false)
+            .or_else(|| {
+                self.load_node_module_from_directory(Extensions::DECLARATION, &candidate, false)
+            })
+            .map(|resolved| ResolvedModule {
+                is_external_library_import: true,
+                ..resolved
+            })
+    }
+
+    /// Resolves a path as a file, or failing that, as a folder
+    fn load_module_by_relative_name(
+        &self,
+        extensions: Extensions,
+        candidate: &str,
+        mut only_record_failures: bool,
+    ) -> Option<ResolvedModule> {
+        if !candidate.ends_with('/') {
+            let directory = get_directory_path(candidate);
+            if !only_record_failures && !self.host.directory_exists(&directory) {
+                self.trace(
+                    diagnostics::DIRECTORY_0_DOES_NOT_EXIST_SKIPPING_ALL_LOOKUPS_IN_IT_6148,
+                    &[&directory],
+                );
+                only_record_failures = true;
+            }
+            if let Some(resolved) =
+                self.load_module_from_file(extensions, candidate, only_record_failures)
+            {
+                return Some(resolved);
+            }
+        }
+        // ECMAScript imports cannot name folders
+        if self.esm_mode {
+            return None;
+        }
+        if !only_record_failures && !self.host.directory_exists(candidate) {
+            self.trace(
+                diagnostics::DIRECTORY_0_DOES_NOT_EXIST_SKIPPING_ALL_LOOKUPS_IN_IT_6148,
+                &[candidate],
+            );
+            only_record_failures = true;
+        }
+        self.load_node_module_from_directory(extensions, candidate, only_record_failures)
+    }
+
+    /// Resolves a path as a file, by replacing the extension it has with the extensions of the
+    /// files it may have been emitted from, and then, unless it must name the file exactly, by
+    /// adding extensions to it
+    fn load_module_from_file(
+        &self,
+        extensions: Extensions,
+        candidate: &str,
+        only_record_failures: bool,
+    ) -> Option<ResolvedModule> {
+        self.load_module_from_file_without_adding_extensions(
+            extensions,
+            candidate,
+            only_record_failures,
+        )
+        .or_else(|| {
+            (!self.esm_mode).then(|| {
+                self.try_adding_extensions(candidate, extensions, "", only_record_failures)
+            })?
+        })
+    }
+
+    /// Resolves a path as a file by replacing the extension it has with the extensions of the
+    /// files it may have been emitted from
+    fn load_module_from_file_without_adding_extensions(
+        &self,
+        extensions: Extensions,
+        candidate: &str,
+        only_record_failures: bool,
+    ) -> Option<ResolvedModule> {
+        let file_name = get_base_file_name(candidate);
+        let dot = file_name.rfind('.')?;
+        let extension = file_name[dot..].to_string();
+        let extensionless = &candidate[..candidate.len() - extension.len()];
+        self.trace(
+            diagnostics::FILE_NAME_0_HAS_A_1_EXTENSION_STRIPPING_IT_6132,
+            &[candidate, &extension],
+        );
+        self.try_adding_extensions(extensionless, extensions, &extension, only_record_failures)
+    }
+
+    /// Tries the files `candidate` names with each extension that can stand in for
+    /// `original_extension`
+    fn try_adding_extensions(
+        &self,
+        candidate: &str,
+        extensions: Extensions,
+        original_extension: &str,
+        only_record_failures: bool,
+    ) -> Option<ResolvedModule> {
+        type Candidates = &'static [&'static str];
+        let (typescript, declaration, javascript, json): (
+            Candidates,
+            Candidates,
+            Candidates,
+            Candidates,
+        ) = match original_extension {
+            ".mjs" | ".mts" => (&[".mts"], &[".d.mts"], &[".mjs"], &[]),
+            ".cjs" | ".cts" => (&[".cts"], &[".d.cts"], &[".cjs"], &[]),
+            ".tsx" | ".jsx" => (&[".tsx", ".ts"], &[".d.ts"], &[".jsx", ".js"], &[]),
+            "" | ".ts" | ".js" => (&[".ts", ".tsx"], &[".d.ts"], &[".js", ".jsx"], &[]),
+            // A JSON file is only found by its full name, or through declarations of it
+            ".json" => (&[], &[".d.json.ts"], &[], &[".json"]),
+            _ => return None,
+        };
+        let mut candidates: Vec<&'static str> = Vec::new();
+        if extensions.contains(Extensions::TYPESCRIPT) {
+            candidates.extend(typescript);
+        }
+        if extensions.contains(Extensions::DECLARATION) {
+            candidates.extend(declaration);
+        }
+        if extensions.contains(Extensions::JAVASCRIPT) {
+            candidates.extend(javascript);
+        }
+        if extensions.contains(Extensions::JSON) {
+            candidates.extend(json);
+        }
+        candidates.into_iter().find_map(|extension| {
+            self.try_file(&format!("{candidate}{extension}"), only_record_failures)
+                .then(|| ResolvedModule {
+                    resolved_file_name: format!("{candidate}{extension}"),
+                    extension,
+                    is_external_library_import: false,
+                })
+        })
+    }
+
+    fn try_file(&self, file_name: &str, only_record_failures: bool) -> bool {
+        if only_record_failures {
+            return false;
+        }
+        if self.host.file_exists(file_name) {
+            self.trace(
+                diagnostics::FILE_0_EXISTS_USE_IT_AS_A_NAME_RESOLUTION_RESULT_6097,
+                &[file_name],
+            );
+            true
+        } else {
+            self.trace(diagnostics::FILE_0_DOES_NOT_EXIST_6096, &[file_name]);
+            false
+        }
+    }
+
+    /// Resolves a folder through the fields of its `package.json`, or to its `index` file
+    fn load_node_module_from_directory(
+        &self,
+        extensions: Extensions,
+        candidate: &str,
+        only_record_failures: bool,
+    ) -> Option<ResolvedModule> {
+        let package_json = self.read_package_json(candidate, only_record_failures);
+        if let Some(package_json) = &package_json {
+            let fields: &[&str] = if extensions.contains(Extensions::DECLARATION) {
+                &["typings", "types", "main"]
+            } else {
+                &["main"]
+            };
+            let entry = fields.iter().find_map(|field| {
+                self.read_package_json_path_field(package_json, field, candidate)
+            });
+            if let Some(entry) = entry {
+                // The entry may name the file exactly, or leave out its extension
+                let exact = extension_of(&entry, extensions)
+                    .filter(|_| self.try_file(&entry, only_record_failures));
+                let resolved = match exact {
+                    Some(extension) => Some(ResolvedModule {
+                        resolved_file_name: entry,
+                        extension,
+                        is_external_library_import: false,
+                    }),
+                    None => {
+                        self.load_module_by_relative_name(extensions, &entry, only_record_failures)
+                    }
+                };
+                if resolved.is_some() {
+                    return resolved;
+                }
+            }
+        }
+        let index = combine_paths(candidate, "index");
+        self.load_module_from_file(extensions, &index, only_record_failures)
+    }
+
+    fn read_package_json(&self, directory: &str, only_record_failures: bool) -> Option<JsonNode> {
+        let path = combine_paths(directory, "package.json");
+        if only_record_failures {
+            return None;
+        }
+        if !self.host.file_exists(&path) {
+            self.trace(diagnostics::FILE_0_DOES_NOT_EXIST_6096, &[&path]);
+            return None;
+        }
+        self.trace(diagnostics::FOUND_PACKAGE_JSON_AT_0_6099, &[&path]);
+        parse_json_text(&path, &self.host.read_file(&path)?).0
+    }
+
+    /// Gets the path that a field of a `package.json` refers to, relative to its folder
+    fn read_package_json_path_field(
+        &self,
+        package_json: &JsonNode,
+        field: &str,
+        directory: &str,
+    ) -> Option<String> {
+        let Some(property) = package_json.get(field) else {
+            self.trace(
+                diagnostics::PACKAGE_JSON_DOES_NOT_HAVE_A_0_FIELD_6100,
+                &[field],
+            );
+            return None;
+        };
+        let Some(value) = property.initializer.as_str() else {
+            let actual = match property.initializer.value {
+                JsonValue::Null => "null",
+                JsonValue::Boolean(_) => "boolean",
+                JsonValue::Number(_) => "number",
+                JsonValue::String(_) => "string",
+                JsonValue::Array(_) | JsonValue::Object(_) => "object",
+            };
+            self.trace(
+                diagnostics::EXPECTED_TYPE_OF_0_FIELD_IN_PACKAGE_JSON_TO_BE_1_GOT_2_6105,
+                &[field, "string", actual],
+            );
+            return None;
+        };
+        let path = combine_paths(directory, value);
+        self.trace(
+            diagnostics::PACKAGE_JSON_HAS_0_FIELD_1_THAT_REFERENCES_2_6101,
+            &[field, value, &path],
+        );
+        Some(path)
+    }
+}
+
+/// Gets the extension of a file that a lookup accepts, if it has one
+fn extension_of(file_name: &str, extensions: Extensions) -> Option<&'static str> {
+    // Declaration extensions come first, as `.d.ts` files also end with `.ts`
+    let kinds: [(Extensions, &[&'static str]); 4] = [
+        (Extensions::DECLARATION, &[".d.ts", ".d.mts", ".d.cts"]),
+        (Extensions::TYPESCRIPT, &[".ts", ".tsx", ".mts", ".cts"]),
+        (Extensions::JAVASCRIPT, &[".js", ".jsx", ".mjs", ".cjs"]),
+        (Extensions::JSON, &[".json"]),
+    ];
+    let (kind, extension) = kinds.iter().find_map(|(kind, kind_extensions)| {
+        kind_extensions
+            .iter()
+            .find(|extension| file_name.ends_with(*extension))
+            .map(|extension| (*kind, *extension))
+    })?;
+    extensions.contains(kind).then_some(extension)
+}
+
+/// Gets the name of the `@types` package for a package, which is `scope__name` for a
+/// package `@scope/name`
+fn mangle_scoped_package_name(module_name: &str) -> String {
+    match module_name
+        .strip_prefix('@')
+        .and_then(|scoped| scoped.split_once('/'))
+    {
+        Some((scope, name)) => format!("{scope}__{name}"),
+        None => module_name.to_string(),
+    }
+}
+
+/// Splits a package specifier into the name of the package and the path within it, which
+/// starts with `/` if there is one
+fn parse_package_name(module_name: &str) -> (&str, &str) {
+    let components = if module_name.starts_with('@') { 2 } else { 1 };
+    match module_name.match_indices('/').nth(components - 1) {
+        Some((index, _)) => module_name.split_at(index),
+        None => (module_name, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// A host over files in memory, at absolute paths, which records traces
+    struct TestHost {
+        files: HashMap<String, String>,
+        traces: RefCell<Vec<String>>,
+    }
+
+    impl TestHost {
+        fn new(files: &[(&str, &str)]) -> Self {
+            TestHost {
+                files: files
+                    .iter()
+                    .map(|(path, text)| (path.to_string(), text.to_string()))
+                    .collect(),
+                traces: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl ModuleResolutionHost for TestHost {
+        fn file_exists(&self, path: &str) -> bool {
+            self.files.contains_key(path)
+        }
+
+        fn directory_exists(&self, path: &str) -> bool {
+            let prefix = format!("{}/", path.trim_end_matches('/'));
+            self.files.keys().any(|file| file.starts_with(&prefix))
+        }
+
+        fn read_file(&self, path: &str) -> Option<String> {
+            self.files.get(path).cloned()
+        }
+
+        fn get_directories(&self, path: &str) -> Vec<String> {
+            let prefix = format!("{}/", path.trim_end_matches('/'));
+            let mut directories: Vec<String> = self
+                .files
+                .keys()
+                .filter_map(|file| file.strip_prefix(&prefix)?.split_once('/'))
+                .map(|(directory, _)| directory.to_string())
+                .collect();
+            directories.sort();
+            directories.dedup();
+            directories
+        }
+
+        fn trace(&self, message: &str) {
+            self.traces.borrow_mut().push(message.to_string());
+        }
+    }
+
+    fn resolve(
+        host: &TestHost,
+        kind: ModuleResolutionKind,
+        module_name: &str,
+        containing_file: &str,
+    ) -> Option<(String, bool)> {
+        resolve_in_mode(
+            host,
+            kind,
+            ResolutionMode::CommonJs,
+            module_name,
+            containing_file,
+        )
+    }
+
+    fn resolve_in_mode(
+        host: &TestHost,
+        kind: ModuleResolutionKind,
+        resolution_mode: ResolutionMode,
+        module_name: &str,
+        containing_file: &str,
+    ) -> Option<(String, bool)> {
+        let options = ModuleResolutionOptions {
+            kind,
+            kind_is_explicit: true,
+            trace_resolution: true,
+            type_roots: Vec::new(),
+            type_roots_are_explicit: false,
+            resolve_json_module: false,
+        };
+        resolve_module_name(
+            module_name,
+            containing_file,
+            resolution_mode,
+            &options,
+            host,
+        )
+        .map(|resolved| {
+            (
+                resolved.resolved_file_name,
+                resolved.is_external_library_import,
+            )
+        })
+    }
+
+    #[test]
+    fn resolves_relative_specifiers_with_node10() {
+        let host = TestHost::new(&[
+            ("/src/a.ts", ""),
+            ("/src/b.ts", ""),
+            ("/src/c.d.ts", ""),
+            ("/src/d/index.tsx", ""),
+            ("/src/e.js", ""),
+            ("/src/lib/package.json", r#"{ "types": "out/main.d.ts" }"#),
+            ("/src/lib/out/main.d.ts", ""),
+        ]);
+        let node10 = ModuleResolutionKind::Node10;
+        let resolved = |name| resolve(&host, node10, name, "/src/a.ts").map(|(path, _)| path);
+        assert_eq!(resolved("./b"), Some("/src/b.ts".into()));
+        // An import of the JavaScript a file is emitted to refers to the file
+        assert_eq!(resolved("./b.js"), Some("/src/b.ts".into()));
+        assert_eq!(resolved("./c"), Some("/src/c.d.ts".into()));
+        assert_eq!(resolved("./d"), Some("/src/d/index.tsx".into()));
+        assert_eq!(resolved("../src/e"), Some("/src/e.js".into()));
+        assert_eq!(resolved("./lib"), Some("/src/lib/out/main.d.ts".into()));
+        assert_eq!(resolved("./f"), None);
+    }
+
+    #[test]
+    fn resolves_packages_from_node_modules() {
+        let host = TestHost::new(&[
+            ("/app/src/a.ts", ""),
+            (
+                "/app/node_modules/typed/package.json",
+                r#"{ "typings": "./lib/index.d.ts" }"#,
+            ),
+            ("/app/node_modules/typed/lib/index.d.ts", ""),
+            (
+                "/app/node_modules/untyped/package.json",
+                r#"{ "main": "main" }"#,
+            ),
+            ("/app/node_modules/untyped/main.js", ""),
+            ("/app/node_modules/untyped/util.js", ""),
+            ("/app/node_modules/@types/untyped/index.d.ts", ""),
+            ("/node_modules/@types/scope__pkg/index.d.ts", ""),
+            ("/node_modules/plain/index.d.ts", ""),
+        ]);
+        let node10 = ModuleResolutionKind::Node10;
+        let resolved = |name| resolve(&host, node10, name, "/app/src/a.ts");
+        assert_eq!(
+            resolved("typed"),
+            Some(("/app/node_modules/typed/lib/index.d.ts".into(), true))
+        );
+        // Declarations in `@types` win over the JavaScript of the package
+        assert_eq!(
+            resolved("untyped"),
+            Some(("/app/node_modules/@types/untyped/index.d.ts".into(), true))
+        );
+        assert_eq!(
+            resolved("untyped/util"),
+            Some(("/app/node_modules/untyped/util.js".into(), true))
+        );
+        assert_eq!(
+            resolved("@scope/pkg"),
+            Some(("/node_modules/@types/scope__pkg/index.d.ts".into(), true))
+        );
+        assert_eq!(
+            resolved("plain"),
+            Some(("/node_modules/plain/index.d.ts".into(), true))
+        );
+        assert_eq!(resolved("missing"), None);
+    }
+
+    #[test]
+    fn resolves_non_relative_specifiers_in_ancestor_folders_with_classic() {
+        let host = TestHost::new(&[
+            ("/src/app/a.ts", ""),
+            ("/src/shared.ts", ""),
+            ("/src/node_modules/pkg/index.d.ts", ""),
+            ("/src/node_modules/@types/typed/index.d.ts", ""),
+        ]);
+        let classic = ModuleResolutionKind::Classic;
+        let resolved = |name| resolve(&host, classic, name, "/src/app/a.ts");
+        assert_eq!(resolved("shared"), Some(("/src/shared.ts".into(), false)));
+        // Classic resolution does not look into packages, apart from `@types`
+        assert_eq!(resolved("pkg"), None);
+        assert_eq!(
+            resolved("typed"),
+            Some(("/src/node_modules/@types/typed/index.d.ts".into(), true))
+        );
+    }
+
+    #[test]
+    fn traces_each_step() {
+        let host = TestHost::new(&[
+            ("/src/a.ts", ""),
+            (
+                "/node_modules/pkg/package.json",
+                r#"{ "types": "index.d.ts" }"#,
+            ),
+            ("/node_modules/pkg/index.d.ts", ""),
+        ]);
+        resolve(&host, ModuleResolutionKind::Node10, "pkg", "/src/a.ts");
+        assert_eq!(
+            host.traces.take(),
+            [
+                "======== Resolving module 'pkg' from '/src/a.ts'. ========",
+                "Explicitly specified module resolution kind: 'Node10'.",
+                "Loading module 'pkg' from 'node_modules' folder, target file types: TypeScript, Declaration.",
+                "Directory '/src/node_modules' does not exist, skipping all lookups in it.",
+                "File '/node_modules/pkg.ts' does not exist.",
+                "File '/node_modules/pkg.tsx' does not exist.",
+                "File '/node_modules/pkg.d.ts' does not exist.",
+                "Found 'package.json' at '/node_modules/pkg/package.json'.",
+                "'package.json' does not have a 'typings' field.",
+                "'package.json' has 'types' field 'index.d.ts' that references '/node_modules/pkg/index.d.ts'.",
+                "File '/node_modules/pkg/index.d.ts' exists - use it as a name resolution result.",
+                "======== Module name 'pkg' was successfully resolved to '/node_modules/pkg/index.d.ts'. ========",
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_conditional_exports_by_resolution_mode() {
+        let host = TestHost::new(&[
+            ("/app/a.ts", ""),
+            (
+                "/app/node_modules/dual/package.json",
+                r#"{
+                    "main": "./legacy.js",
+                    "exports": {
+                        ".": {
+                            "import": "./esm/index.js",
+                            "require": "./cjs/index.js"
+                        },
+                        "./feature": { "types": "./types/feature.d.ts", "default": "./feature.js" },
+                        "./internal": null
+                    }
+                }"#,
+            ),
+            ("/app/node_modules/dual/legacy.d.ts", ""),
+            ("/app/node_modules/dual/esm/index.d.ts", ""),
+            ("/app/node_modules/dual/cjs/index.d.ts", ""),
+            ("/app/node_modules/dual/types/feature.d.ts", ""),
+            ("/app/node_modules/dual/internal.d.ts", ""),
+        ]);
+        let resolved = |kind, mode, name| {
+            resolve_in_mode(&host, kind, mode, name, "/app/a.ts").map(|(path, _)| path)
+        };
+        let node16 = ModuleResolutionKind::Node16;
+        assert_eq!(
+            resolved(node16, ResolutionMode::Esm, "dual"),
+            Some("/app/node_modules/dual/esm/index.d.ts".into())
+        );
+        assert_eq!(
+            resolved(node16, ResolutionMode::CommonJs, "dual"),
+            Some("/app/node_modules/dual/cjs/index.d.ts".into())
+        );
+        assert_eq!(
+            resolved(node16, ResolutionMode::Esm, "dual/feature"),
+            Some("/app/node_modules/dual/types/feature.d.ts".into())
+        );
+        // Subpaths missing from the map, or mapped to `null`, cannot be imported
+        assert_eq!(resolved(node16, ResolutionMode::Esm, "dual/legacy"), None);
+        assert_eq!(resolved(node16, ResolutionMode::Esm, "dual/internal"), None);
+        // Node10 resolution does not know of `exports`
+        assert_eq!(
+            resolved(ModuleResolutionKind::Node10, ResolutionMode::Esm, "dual"),
+            Some("/app/node_modules/dual/legacy.d.ts".into())
+        );
+    }
+
+    #[test]
+    fn resolves_subpath_patterns_of_exports() {
+        let host = TestHost::new(&[
+            ("/app/a.ts", ""),
+            (
+                "/app/node_modules/pkg/package.json",
+                r#"{
+                    "exports": {
+                        "./*": "./dist/*.js",
+                        "./utils/*": "./dist/utils/*/index.js",
+                        "./utils/private/*": null
+                    }
+                }"#,
+            ),
+            ("/app/node_modules/pkg/dist/one.d.ts", ""),
+            ("/app/node_modules/pkg/dist/utils/two/index.d.ts", ""),
+            ("/app/node_modules/pkg/dist/utils/private/index.d.ts", ""),
+        ]);
+        let resolved = |name| {
+            resolve_in_mode(
+                &host,
+                ModuleResolutionKind::NodeNext,
+                ResolutionMode::Esm,
+                name,
+                "/app/a.ts",
+            )
+        };
+        assert_eq!(
+            resolved("pkg/one"),
+            Some(("/app/node_modules/pkg/dist/one.d.ts".into(), true))
+        );
+        // The key with the longest prefix wins
+        assert_eq!(
+            resolved("pkg/utils/two"),
+            Some((
+                "/app/node_modules/pkg/dist/utils/two/index.d.ts".into(),
+                true
+            ))
+        );
+        assert_eq!(resolved("pkg/utils/private/x"), None);
+    }
+
+    #[test]
+    fn resolves_imports_and_self_references() {
+        let host = TestHost::new(&[
+            (
+                "/app/package.json",
+                r##"{
+                    "name": "@me/app",
+                    "exports": { "./tools": "./src/tools.js" },
+                    "imports": {
+                        "#config": { "node": "./src/config.js" },
+                        "#lib/*": "./src/lib/*.js",
+                        "#dep": "dep"
+                    }
+                }"##,
+            ),
+            ("/app/src/a.ts", ""),
+            ("/app/src/config.ts", ""),
+            ("/app/src/tools.ts", ""),
+            ("/app/src/lib/strings.ts", ""),
+            (
+                "/app/node_modules/dep/package.json",
+                r#"{ "types": "./index.d.ts" }"#,
+            ),
+            ("/app/node_modules/dep/index.d.ts", ""),
+        ]);
+        let resolved =
+            |kind, name| resolve_in_mode(&host, kind, ResolutionMode::Esm, name, "/app/src/a.ts");
+        let node16 = ModuleResolutionKind::Node16;
+        assert_eq!(
+            resolved(node16, "#config"),
+            Some(("/app/src/config.ts".into(), false))
+        );
+        assert_eq!(
+            resolved(node16, "#lib/strings"),
+            Some(("/app/src/lib/strings.ts".into(), false))
+        );
+        assert_eq!(
+            resolved(node16, "#dep"),
+            Some(("/app/node_modules/dep/index.d.ts".into(), true))
+        );
+        assert_eq!(resolved(node16, "#missing"), None);
+        assert_eq!(
+            resolved(node16, "@me/app/tools"),
+            Some(("/app/src/tools.ts".into(), false))
+        );
+        // The bundler does not set the `node` condition
+        assert_eq!(resolved(ModuleResolutionKind::Bundler, "#config"), None);
+    }
+
+    #[test]
+    fn requires_extensions_of_relative_esm_imports() {
+        let host = TestHost::new(&[
+            ("/src/a.ts", ""),
+            ("/src/b.ts", ""),
+            ("/src/c.mts", ""),
+            ("/src/d/index.ts", ""),
+        ]);
+        let resolved = |kind, mode, name| {
+            resolve_in_mode(&host, kind, mode, name, "/src/a.ts").map(|(path, _)| path)
+        };
+        let (node16, bundler) = (ModuleResolutionKind::Node16, ModuleResolutionKind::Bundler);
+        let (esm, cjs) = (ResolutionMode::Esm, ResolutionMode::CommonJs);
+        assert_eq!(resolved(node16, esm, "./b"), None);
+        assert_eq!(resolved(node16, esm, "./d"), None);
+        assert_eq!(resolved(node16, esm, "./b.js"), Some("/src/b.ts".into()));
+        assert_eq!(resolved(node16, esm, "./c.mjs"), Some("/src/c.mts".into()));
+        assert_eq!(resolved(node16, cjs, "./b"), Some("/src/b.ts".into()));
+        assert_eq!(resolved(bundler, esm, "./b"), Some("/src/b.ts".into()));
+        assert_eq!(
+            resolved(bundler, esm, "./d"),
+            Some("/src/d/index.ts".into())
+        );
+    }
+
+    #[test]
+    fn detects_the_implied_format_of_files() {
+        let host = TestHost::new(&[
+            ("/esm/package.json", r#"{ "type": "module" }"#),
+            ("/esm/a.ts", ""),
+            ("/esm/cjs/package.json", "{}"),
+            ("/esm/cjs/b.ts", ""),
+        ]);
+        assert_eq!(
+            get_implied_node_format("/esm/a.ts", &host),
+            ResolutionMode::Esm
+        );
+        assert_eq!(
+            get_implied_node_format("/esm/cjs/b.ts", &host),
+            ResolutionMode::CommonJs
+        );
+        assert_eq!(
+            get_implied_node_format("/esm/cjs/c.mts", &host),
+            ResolutionMode::Esm
+        );
+        assert_eq!(
+            get_implied_node_format("/esm/d.cts", &host),
+            ResolutionMode::CommonJs
+        );
+        assert_eq!(
+            get_implied_node_format("/other/e.ts", &host),
+            ResolutionMode::CommonJs
+        );
+    }
+
+    #[test]
+    fn traces_conditional_exports() {
+        let host = TestHost::new(&[
+            ("/src/a.ts", ""),
+            (
+                "/node_modules/pkg/package.json",
+                r#"{ "exports": { "browser": "./browser.js", "import": "./index.js" } }"#,
+            ),
+            ("/node_modules/pkg/index.d.ts", ""),
+        ]);
+        resolve_in_mode(
+            &host,
+            ModuleResolutionKind::NodeNext,
+            ResolutionMode::Esm,
+            "pkg",
+            "/src/a.ts",
+        );
+        assert_eq!(
+            host.traces.take(),
+            [
+                "======== Resolving module 'pkg' from '/src/a.ts'. ========",
+                "Explicitly specified module resolution kind: 'NodeNext'.",
+                "Resolving in ESM mode with conditions 'import', 'types', 'node'.",
+                "File '/src/package.json' does not exist.",
+                "File '/package.json' does not exist.",
+                "Loading module 'pkg' from 'node_modules' folder, target file types: TypeScript, Declaration.",
+                "Directory '/src/node_modules' does not exist, skipping all lookups in it.",
+                "Found 'package.json' at '/node_modules/pkg/package.json'.",
+                "Entering conditional exports.",
+                "Saw non-matching condition 'browser'.",
+                "Matched 'exports' condition 'import'.",
+                "Using 'exports' subpath '.' with target './index.js'.",
+                "File name '/node_modules/pkg/index.js' has a '.js' extension - stripping it.",
+                "File '/node_modules/pkg/index.ts' does not exist.",
+                "File '/node_modules/pkg/index.tsx' does not exist.",
+                "File '/node_modules/pkg/index.d.ts' exists - use it as a name resolution result.",
+                "Resolved under condition 'import'.",
+                "Exiting conditional exports.",
+                "======== Module name 'pkg' was successfully resolved to '/node_modules/pkg/index.d.ts'. ========",
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_type_reference_directives() {
+        let host = TestHost::new(&[
+            ("/app/src/a.ts", ""),
+            ("/app/node_modules/@types/node/index.d.ts", ""),
+            (
+                "/app/node_modules/@types/jest/package.json",
+                r#"{ "types": "jest.d.ts" }"#,
+            ),
+            ("/app/node_modules/@types/jest/jest.d.ts", ""),
+            (
+                "/app/node_modules/@types/stub/package.json",
+                r#"{ "typings": null }"#,
+            ),
+            ("/app/node_modules/@types/stub/readme.md", ""),
+            ("/app/node_modules/extra/index.d.ts", ""),
+            ("/app/typings/custom.d.ts", ""),
+        ]);
+        let type_roots = get_effective_type_roots(None, "/app/src", &host);
+        assert_eq!(type_roots, ["/app/node_modules/@types"]);
+        // Packages whose `typings` are `null` have no declarations
+        assert_eq!(
+            get_automatic_type_directive_names(&type_roots, &host),
+            ["jest", "node"]
+        );
+
+        let resolved = |type_roots: Vec<String>, explicit, name| {
+            let options = ModuleResolutionOptions {
+                kind: ModuleResolutionKind::Node10,
+                kind_is_explicit: false,
+                trace_resolution: true,
+                type_roots,
+                type_roots_are_explicit: explicit,
+                resolve_json_module: false,
+            };
+            resolve_type_reference_directive(name, Some("/app/src/a.ts"), &options, &host)
+                .map(|resolved| resolved.resolved_file_name)
+        };
+        assert_eq!(
+            resolved(type_roots.clone(), false, "jest"),
+            Some("/app/node_modules/@types/jest/jest.d.ts".into())
+        );
+        // Packages outside the type roots are found in `node_modules` folders
+        assert_eq!(
+            resolved(type_roots.clone(), false, "extra"),
+            Some("/app/node_modules/extra/index.d.ts".into())
+        );
+        // Configured type roots may hold declaration files
+        let custom = get_effective_type_roots(Some(&["typings".into()]), "/app", &host);
+        assert_eq!(
+            resolved(custom, true, "custom"),
+            Some("/app/typings/custom.d.ts".into())
+        );
+        assert_eq!(resolved(type_roots, false, "missing"), None);
+    }
+}
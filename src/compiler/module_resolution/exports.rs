@@ -0,0 +1,396 @@
+//! Resolution through the `exports` and `imports` maps of `package.json` files
+//!
+//! Both maps take subpaths to targets: `exports` maps `.` and `./name` subpaths of a package,
+//! and `imports` maps the `#name` specifiers that files of the package may import. A key may
+//! hold one `*`, which matches any text that the target then substitutes for its own `*`. A
+//! target is a path within the package, an object of conditions to targets, of which the first
+//! whose condition applies is used, an array of targets to try in order, or `null`, which hides
+//! the subpath.
+
+use super::{
+    Extensions, ModuleResolutionHost, ResolutionMode, ResolvedModule, Resolver, extension_of,
+    get_ancestor_directories, get_directory_path, is_external_module_name_relative,
+};
+use crate::compiler::diagnostics;
+use crate::compiler::paths::combine_paths;
+use crate::config::{JsonNode, JsonProperty, JsonValue, parse_json_text};
+
+/// Gets whether a file is an ECMAScript module or a CommonJS one, which its extension decides,
+/// or for `.ts` and `.js` files, the `type` field of the nearest `package.json`
+pub fn get_implied_node_format(
+    file_name: &str,
+    host: &impl ModuleResolutionHost,
+) -> ResolutionMode {
+    if [".mts", ".mjs"]
+        .iter()
+        .any(|extension| file_name.ends_with(extension))
+    {
+        return ResolutionMode::Esm;
+    }
+    if [".cts", ".cjs"]
+        .iter()
+        .any(|extension| file_name.ends_with(extension))
+    {
+        return ResolutionMode::CommonJs;
+    }
+    let package_json = get_ancestor_directories(&get_directory_path(file_name))
+        .map(|directory| combine_paths(&directory, "package.json"))
+        .find(|path| host.file_exists(path))
+        .and_then(|path| parse_json_text(&path, &host.read_file(&path)?).0);
+    let is_module = package_json.is_some_and(|package_json| {
+        package_json
+            .get("type")
+            .and_then(|property| property.initializer.as_str())
+            == Some("module")
+    });
+    if is_module {
+        ResolutionMode::Esm
+    } else {
+        ResolutionMode::CommonJs
+    }
+}
+
+impl<H: ModuleResolutionHost> Resolver<'_, H> {
+    /// Resolves a `#name` specifier through the `imports` map of the package containing
+    /// `directory`
+    pub(super) fn load_module_from_imports(
+        &self,
+        extensions: Extensions,
+        module_name: &str,
+        directory: &str,
+    ) -> Option<ResolvedModule> {
+        if module_name == "#" || module_name.starts_with("#/") {
+            self.trace(
+                diagnostics::INVALID_IMPORT_SPECIFIER_0_HAS_NO_POSSIBLE_RESOLUTIONS_6272,
+                &[module_name],
+            );
+            return None;
+        }
+        let Some((scope_directory, package_json)) = self.get_package_scope(directory) else {
+            self.trace(
+                diagnostics::DIRECTORY_0_HAS_NO_CONTAINING_PACKAGE_JSON_SCOPE_IMPORTS_WILL_NOT_RESOLVE_6270,
+                &[directory],
+            );
+            return None;
+        };
+        let Some(imports) = package_json
+            .get("imports")
+            .and_then(|property| property.initializer.as_object())
+        else {
+            self.trace(
+                diagnostics::PACKAGE_JSON_SCOPE_0_HAS_NO_IMPORTS_DEFINED_6273,
+                &[&scope_directory],
+            );
+            return None;
+        };
+        let resolved = self.load_module_from_lookup_table(
+            extensions,
+            imports,
+            module_name,
+            &scope_directory,
+            true,
+        );
+        if resolved.is_none() {
+            self.trace(
+                diagnostics::IMPORT_SPECIFIER_0_DOES_NOT_EXIST_IN_PACKAGE_JSON_SCOPE_AT_PATH_1_6271,
+                &[module_name, &scope_directory],
+            );
+        }
+        resolved
+    }
+
+    /// Resolves a specifier that names the package containing `directory` through the package's
+    /// own `exports` map
+    pub(super) fn load_module_from_self_name(
+        &self,
+        extensions: Extensions,
+        module_name: &str,
+        directory: &str,
+    ) -> Option<ResolvedModule> {
+        let (scope_directory, package_json) = self.get_package_scope(directory)?;
+        let name = package_json.get("name")?.initializer.as_str()?;
+        let rest = module_name
+            .strip_prefix(name)
+            .filter(|rest| rest.is_empty() || rest.starts_with('/'))?;
+        let exports = package_json.get("exports")?;
+        self.load_module_from_exports(
+            extensions,
+            &scope_directory,
+            &exports.initializer,
+            &format!(".{rest}"),
+        )
+    }
+
+    /// Resolves the subpath `.` or `./name` of the package in `package_directory` through its
+    /// `exports` map
+    pub(super) fn load_module_from_exports(
+        &self,
+        extensions: Extensions,
+        package_directory: &str,
+        exports: &JsonNode,
+        subpath: &str,
+    ) -> Option<ResolvedModule> {
+        // Unless the map is an object of subpaths, it is the target of the package itself
+        let subpaths = exports.as_object().filter(|properties| {
+            properties
+                .iter()
+                .all(|property| property.name.starts_with('.'))
+        });
+        let resolved = match subpaths {
+            Some(subpaths) => self.load_module_from_lookup_table(
+                extensions,
+                subpaths,
+                subpath,
+                package_directory,
+                false,
+            ),
+            None if subpath == "." => self.load_module_from_target(
+                extensions,
+                exports,
+                &Subpath {
+                    key: ".",
+                    matched: "",
+                    pattern: false,
+                },
+                package_directory,
+                false,
+            ),
+            None => None,
+        };
+        if resolved.is_none() {
+            self.trace(
+                diagnostics::EXPORT_SPECIFIER_0_DOES_NOT_EXIST_IN_PACKAGE_JSON_SCOPE_AT_PATH_1_6276,
+                &[subpath, package_directory],
+            );
+        }
+        resolved
+    }
+
+    /// Finds the key of an `exports` or `imports` map that matches `module_name`, and resolves
+    /// its target. A key without `*` must match exactly, and wins over keys with one, of which
+    /// the key with the longest text before the `*` wins.
+    fn load_module_from_lookup_table(
+        &self,
+        extensions: Extensions,
+        table: &[JsonProperty],
+        module_name: &str,
+        scope_directory: &str,
+        is_imports: bool,
+    ) -> Option<ResolvedModule> {
+        if !module_name.ends_with('/')
+            && !module_name.contains('*')
+            && let Some(property) = table
+                .iter()
+                .rev()
+                .find(|property| property.name == module_name)
+        {
+            let subpath = Subpath {
+                key: &property.name,
+                matched: "",
+                pattern: false,
+            };
+            return self.load_module_from_target(
+                extensions,
+                &property.initializer,
+                &subpath,
+                scope_directory,
+                is_imports,
+            );
+        }
+
+        // Keys with a `*`, and keys ending with `/`, which map a whole folder
+        let mut expanding_keys: Vec<&JsonProperty> = table
+            .iter()
+            .filter(|property| {
+                property.name.matches('*').count() == 1 || property.name.ends_with('/')
+            })
+            .collect();
+        expanding_keys.sort_by_key(|property| {
+            let prefix = property.name.find('*').unwrap_or(property.name.len());
+            std::cmp::Reverse((prefix, property.name.len()))
+        });
+        for property in expanding_keys {
+            let key = property.name.as_str();
+            let subpath = match key.split_once('*') {
+                Some((prefix, suffix)) => module_name
+                    .strip_prefix(prefix)
+                    .and_then(|rest| rest.strip_suffix(suffix))
+                    .filter(|_| module_name.len() >= key.len() - 1)
+                    .map(|matched| Subpath {
+                        key,
+                        matched,
+                        pattern: true,
+                    }),
+                None => module_name.strip_prefix(key).map(|matched| Subpath {
+                    key,
+                    matched,
+                    pattern: false,
+                }),
+            };
+            if let Some(subpath) = subpath {
+                return self.load_module_from_target(
+                    extensions,
+                    &property.initializer,
+                    &subpath,
+                    scope_directory,
+                    is_imports,
+                );
+            }
+        }
+        None
+    }
+
+    /// Resolves a target of an `exports` or `imports` map
+    fn load_module_from_target(
+        &self,
+        extensions: Extensions,
+        target: &JsonNode,
+        subpath: &Subpath,
+        scope_directory: &str,
+        is_imports: bool,
+    ) -> Option<ResolvedModule> {
+        let map = if is_imports { "imports" } else { "exports" };
+        match &target.value {
+            JsonValue::String(target) => {
+                // Only keys ending with `/` map the rest of the specifier onto a folder
+                if !subpath.pattern && !subpath.matched.is_empty() && !target.ends_with('/') {
+                    self.trace_invalid_target(scope_directory, subpath.key);
+                    return None;
+                }
+                let final_target = if subpath.pattern {
+                    target.replace('*', subpath.matched)
+                } else {
+                    format!("{target}{}", subpath.matched)
+                };
+                if !target.starts_with("./") {
+                    // `imports` may map to another package
+                    if is_imports && !is_external_module_name_relative(target) {
+                        self.trace(
+                            diagnostics::USING_0_SUBPATH_1_WITH_TARGET_2_6404,
+                            &[map, subpath.key, &final_target],
+                        );
+                        return self.node_resolve(extensions, &final_target, scope_directory);
+                    }
+                    self.trace_invalid_target(scope_directory, subpath.key);
+                    return None;
+                }
+                // Targets cannot leave their package, or reach into its installed packages
+                if final_target
+                    .split('/')
+                    .skip(1)
+                    .any(|component| matches!(component, "" | "." | ".." | "node_modules"))
+                {
+                    self.trace_invalid_target(scope_directory, subpath.key);
+                    return None;
+                }
+                self.trace(
+                    diagnostics::USING_0_SUBPATH_1_WITH_TARGET_2_6404,
+                    &[map, subpath.key, &final_target],
+                );
+                let candidate = combine_paths(scope_directory, &final_target);
+                self.load_file_name_from_package_json_field(extensions, &candidate)
+            }
+            JsonValue::Object(conditions) => {
+                self.trace(diagnostics::ENTERING_CONDITIONAL_EXPORTS_6413, &[]);
+                for condition in conditions {
+                    let name = condition.name.as_str();
+                    if !self.condition_matches(extensions, name) {
+                        self.trace(diagnostics::SAW_NON_MATCHING_CONDITION_0_6405, &[name]);
+                        continue;
+                    }
+                    self.trace(diagnostics::MATCHED_0_CONDITION_1_6403, &[map, name]);
+                    let resolved = self.load_module_from_target(
+                        extensions,
+                        &condition.initializer,
+                        subpath,
+                        scope_directory,
+                        is_imports,
+                    );
+                    if resolved.is_some() {
+                        self.trace(diagnostics::RESOLVED_UNDER_CONDITION_0_6414, &[name]);
+                        self.trace(diagnostics::EXITING_CONDITIONAL_EXPORTS_6416, &[]);
+                        return resolved;
+                    }
+                    self.trace(
+                        diagnostics::FAILED_TO_RESOLVE_UNDER_CONDITION_0_6415,
+                        &[name],
+                    );
+                }
+                self.trace(diagnostics::EXITING_CONDITIONAL_EXPORTS_6416, &[]);
+                None
+            }
+            JsonValue::Array(targets) => targets.iter().find_map(|target| {
+                self.load_module_from_target(
+                    extensions,
+                    target,
+                    subpath,
+                    scope_directory,
+                    is_imports,
+                )
+            }),
+            JsonValue::Null => {
+                self.trace(
+                    diagnostics::PACKAGE_JSON_SCOPE_0_EXPLICITLY_MAPS_SPECIFIER_1_TO_NULL_6274,
+                    &[scope_directory, subpath.key],
+                );
+                None
+            }
+            JsonValue::Boolean(_) | JsonValue::Number(_) => {
+                self.trace_invalid_target(scope_directory, subpath.key);
+                None
+            }
+        }
+    }
+
+    /// Whether a condition of a map applies. `types` only applies when looking for
+    /// declarations, and `default` always does.
+    fn condition_matches(&self, extensions: Extensions, condition: &str) -> bool {
+        match condition {
+            "default" => true,
+            "types" => extensions.contains(Extensions::DECLARATION),
+            condition => self.conditions.contains(&condition),
+        }
+    }
+
+    /// Resolves a path that a map names, which must name the file exactly, although a
+    /// JavaScript path resolves to the TypeScript file it is emitted from
+    fn load_file_name_from_package_json_field(
+        &self,
+        extensions: Extensions,
+        candidate: &str,
+    ) -> Option<ResolvedModule> {
+        if let Some(extension) = extension_of(candidate, extensions)
+            && self.try_file(candidate, false)
+        {
+            return Some(ResolvedModule {
+                resolved_file_name: candidate.to_string(),
+                extension,
+                is_external_library_import: false,
+            });
+        }
+        self.load_module_from_file_without_adding_extensions(extensions, candidate, false)
+    }
+
+    /// Finds the nearest `package.json` to `directory`, returning its folder and contents
+    fn get_package_scope(&self, directory: &str) -> Option<(String, JsonNode)> {
+        get_ancestor_directories(directory).find_map(|directory| {
+            let package_json = self.read_package_json(&directory, false)?;
+            Some((directory, package_json))
+        })
+    }
+
+    fn trace_invalid_target(&self, scope_directory: &str, key: &str) {
+        self.trace(
+            diagnostics::PACKAGE_JSON_SCOPE_0_HAS_INVALID_TYPE_FOR_TARGET_OF_SPECIFIER_1_6275,
+            &[scope_directory, key],
+        );
+    }
+}
+
+/// The key of a map that matched a specifier, and the part of the specifier that its `*`, or
+/// for a key ending with `/`, its end matched
+struct Subpath<'a> {
+    key: &'a str,
+    matched: &'a str,
+    pattern: bool,
+}
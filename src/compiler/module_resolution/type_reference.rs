@@ -0,0 +1,201 @@
+//! Resolution of type reference directives to the declarations of type packages
+//!
+//! A directive names a package of declarations, like `node` for `@types/node`. It is looked
+//! up first in each type root, which are the `typeRoots` option or else the
+//! `node_modules/@types` folders from the working directory up, and then in the
+//! `node_modules` folders above the file containing the directive. Unless the `types` option
+//! lists the packages to include, every package in the type roots is part of the program.
+
+use super::{
+    Extensions, ModuleResolutionHost, ModuleResolutionOptions, ResolutionMode, ResolvedModule,
+    Resolver, get_ancestor_directories, get_base_file_name, get_directory_path,
+    is_external_module_name_relative,
+};
+use crate::compiler::diagnostics;
+use crate::compiler::paths::combine_paths;
+use crate::config::{JsonValue, parse_json_text};
+
+/// Gets the folders that type reference directives are looked up in: the `typeRoots` option,
+/// or else each `node_modules/@types` folder from `current_directory` up to the root
+pub fn get_effective_type_roots(
+    type_roots: Option<&[String]>,
+    current_directory: &str,
+    host: &impl ModuleResolutionHost,
+) -> Vec<String> {
+    match type_roots {
+        Some(type_roots) => type_roots
+            .iter()
+            .map(|type_root| combine_paths(current_directory, type_root))
+            .collect(),
+        None => get_ancestor_directories(current_directory)
+            .map(|directory| combine_paths(&directory, "node_modules/@types"))
+            .filter(|type_root| host.directory_exists(type_root))
+            .collect(),
+    }
+}
+
+/// Gets the names of the packages in the type roots, which are part of the program unless the
+/// `types` option lists the packages to include. A package whose `package.json` sets
+/// `typings` to `null` has no declarations, and is left out.
+pub fn get_automatic_type_directive_names(
+    type_roots: &[String],
+    host: &impl ModuleResolutionHost,
+) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for type_root in type_roots {
+        if !host.directory_exists(type_root) {
+            continue;
+        }
+        let mut directories = host.get_directories(type_root);
+        directories.sort();
+        for name in directories {
+            if name.starts_with('.') || names.contains(&name) {
+                continue;
+            }
+            let package_json = combine_paths(&combine_paths(type_root, &name), "package.json");
+            let has_no_declarations = host
+                .read_file(&package_json)
+                .and_then(|text| parse_json_text(&package_json, &text).0)
+                .is_some_and(|package_json| {
+                    package_json
+                        .get("typings")
+                        .is_some_and(|typings| typings.initializer.value == JsonValue::Null)
+                });
+            if !has_no_declarations {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Resolves a type reference directive for `type_reference_directive_name` in
+/// `containing_file`, an absolute path, to the declaration file of the package it names. The
+/// types of the program are resolved without a containing file.
+pub fn resolve_type_reference_directive(
+    type_reference_directive_name: &str,
+    containing_file: Option<&str>,
+    options: &ModuleResolutionOptions,
+    host: &impl ModuleResolutionHost,
+) -> Option<ResolvedModule> {
+    let resolver = Resolver::new(options, host, ResolutionMode::CommonJs);
+    let name = type_reference_directive_name;
+    let type_roots = options.type_roots.join(",");
+    match (containing_file, options.type_roots.is_empty()) {
+        (Some(containing_file), false) => resolver.trace(
+            diagnostics::RESOLVING_TYPE_REFERENCE_DIRECTIVE_0_CONTAINING_FILE_1_ROOT_DIRECTORY_2_6116,
+            &[name, containing_file, &type_roots],
+        ),
+        (Some(containing_file), true) => resolver.trace(
+            diagnostics::RESOLVING_TYPE_REFERENCE_DIRECTIVE_0_CONTAINING_FILE_1_ROOT_DIRECTORY_NOT_SET_6123,
+            &[name, containing_file],
+        ),
+        (None, false) => resolver.trace(
+            diagnostics::RESOLVING_TYPE_REFERENCE_DIRECTIVE_0_CONTAINING_FILE_NOT_SET_ROOT_DIRECTORY_1_6127,
+            &[name, &type_roots],
+        ),
+        (None, true) => resolver.trace(
+            diagnostics::RESOLVING_TYPE_REFERENCE_DIRECTIVE_0_CONTAINING_FILE_NOT_SET_ROOT_DIRECTORY_NOT_SET_6128,
+            &[name],
+        ),
+    }
+
+    let (resolved, primary) = match resolver.primary_type_lookup(name) {
+        Some(resolved) => (Some(resolved), true),
+        None => (resolver.secondary_type_lookup(name, containing_file), false),
+    };
+    match &resolved {
+        Some(resolved) => resolver.trace(
+            diagnostics::TYPE_REFERENCE_DIRECTIVE_0_WAS_SUCCESSFULLY_RESOLVED_TO_1_PRIMARY_COLON_2_6119,
+            &[name, &resolved.resolved_file_name, &primary.to_string()],
+        ),
+        None => resolver.trace(
+            diagnostics::TYPE_REFERENCE_DIRECTIVE_0_WAS_NOT_RESOLVED_6120,
+            &[name],
+        ),
+    }
+    resolved
+}
+
+impl<H: ModuleResolutionHost> Resolver<'_, H> {
+    /// Looks up a type package in each type root
+    fn primary_type_lookup(&self, name: &str) -> Option<ResolvedModule> {
+        let type_roots = &self.options.type_roots;
+        if type_roots.is_empty() {
+            self.trace(
+                diagnostics::ROOT_DIRECTORY_CANNOT_BE_DETERMINED_SKIPPING_PRIMARY_SEARCH_PATHS_6122,
+                &[],
+            );
+            return None;
+        }
+        self.trace(
+            diagnostics::RESOLVING_WITH_PRIMARY_SEARCH_PATH_0_6121,
+            &[&type_roots.join(", ")],
+        );
+        type_roots.iter().find_map(|type_root| {
+            let candidate = combine_paths(type_root, name);
+            let only_record_failures = !self.host.directory_exists(type_root);
+            if only_record_failures {
+                self.trace(
+                    diagnostics::DIRECTORY_0_DOES_NOT_EXIST_SKIPPING_ALL_LOOKUPS_IN_IT_6148,
+                    &[type_root],
+                );
+            }
+            // Configured type roots may hold declaration files as well as packages
+            let from_file = if self.options.type_roots_are_explicit {
+                self.load_module_from_file(
+                    Extensions::DECLARATION,
+                    &candidate,
+                    only_record_failures,
+                )
+            } else {
+                None
+            };
+            from_file.or_else(|| {
+                self.load_node_module_from_directory(
+                    Extensions::DECLARATION,
+                    &candidate,
+                    only_record_failures,
+                )
+            })
+        })
+    }
+
+    /// Looks up a type package in the `node_modules` folders above the file containing the
+    /// directive
+    fn secondary_type_lookup(
+        &self,
+        name: &str,
+        containing_file: Option<&str>,
+    ) -> Option<ResolvedModule> {
+        let Some(containing_file) = containing_file else {
+            self.trace(
+                diagnostics::CONTAINING_FILE_IS_NOT_SPECIFIED_AND_ROOT_DIRECTORY_CANNOT_BE_DETERMINED_SKIPPING_LOOKUP_IN_NODE_MODULES_FOLDER_6126,
+                &[],
+            );
+            return None;
+        };
+        let initial_location = get_directory_path(containing_file);
+        self.trace(
+            diagnostics::LOOKING_UP_IN_NODE_MODULES_FOLDER_INITIAL_LOCATION_0_6125,
+            &[&initial_location],
+        );
+        if is_external_module_name_relative(name) {
+            let candidate = combine_paths(&initial_location, name);
+            return self.load_module_by_relative_name(Extensions::DECLARATION, &candidate, false);
+        }
+        get_ancestor_directories(&initial_location)
+            .filter(|directory| get_base_file_name(directory) != "node_modules")
+            .find_map(|directory| {
+                self.load_module_from_node_modules_directory(
+                    Extensions::DECLARATION,
+                    name,
+                    &directory,
+                )
+            })
+            .map(|resolved| ResolvedModule {
+                is_external_library_import: true,
+                ..resolved
+            })
+    }
+}
@@ -0,0 +1,8633 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bitflags::bitflags;
+
+use crate::compiler::ast::nodes::*;
+use crate::compiler::ast::{Node, NodeArena, NodeData, NodeFlags, NodeList, SyntaxKind};
+use crate::compiler::diagnostics::{self, Diagnostic, Message};
+use crate::compiler::scanner::{
+    self, JSDocParsingMode, LanguageVariant, Scanner, ScannerState, ScriptKind, ScriptTarget,
+    skip_trivia,
+};
+use crate::compiler::text::TextRange;
+
+mod incremental;
+mod jsdoc;
+
+/// The kinds of lists the parser can be in the middle of parsing
+///
+/// The parser tracks every list it is currently inside so that, when it hits a token it
+/// does not understand, it can tell whether an enclosing list would accept that token and
+/// stop the inner list instead of skipping ahead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParsingContext {
+    SourceElements,
+    BlockStatements,
+    SwitchClauses,
+    SwitchClauseStatements,
+    TypeMembers,
+    ClassMembers,
+    EnumMembers,
+    HeritageClauseElement,
+    VariableDeclarations,
+    ObjectBindingElements,
+    ArrayBindingElements,
+    ArgumentExpressions,
+    ObjectLiteralMembers,
+    JsxAttributes,
+    JsxChildren,
+    ArrayLiteralMembers,
+    Parameters,
+    RestProperties,
+    TypeParameters,
+    TypeArguments,
+    TupleElementTypes,
+    HeritageClauses,
+    ImportOrExportSpecifiers,
+    ImportAttributes,
+}
+
+impl ParsingContext {
+    const ALL: [ParsingContext; 24] = [
+        ParsingContext::SourceElements,
+        ParsingContext::BlockStatements,
+        ParsingContext::SwitchClauses,
+        ParsingContext::SwitchClauseStatements,
+        ParsingContext::TypeMembers,
+        ParsingContext::ClassMembers,
+        ParsingContext::EnumMembers,
+        ParsingContext::HeritageClauseElement,
+        ParsingContext::VariableDeclarations,
+        ParsingContext::ObjectBindingElements,
+        ParsingContext::ArrayBindingElements,
+        ParsingContext::ArgumentExpressions,
+        ParsingContext::ObjectLiteralMembers,
+        ParsingContext::JsxAttributes,
+        ParsingContext::JsxChildren,
+        ParsingContext::ArrayLiteralMembers,
+        ParsingContext::Parameters,
+        ParsingContext::RestProperties,
+        ParsingContext::TypeParameters,
+        ParsingContext::TypeArguments,
+        ParsingContext::TupleElementTypes,
+        ParsingContext::HeritageClauses,
+        ParsingContext::ImportOrExportSpecifiers,
+        ParsingContext::ImportAttributes,
+    ];
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+bitflags! {
+    /// Describes the kind of signature being parsed
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct SignatureFlags: u32 {
+        const NONE = 0;
+        const YIELD = 1 << 0;
+        const AWAIT = 1 << 1;
+        const TYPE = 1 << 2;
+        const IGNORE_MISSING_OPEN_BRACE = 1 << 4;
+    }
+}
+
+/// Result of a lookahead that may not be able to decide on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tristate {
+    False,
+    True,
+    Unknown,
+}
+
+/// Binary operator precedence, from loosest to tightest binding
+mod precedence {
+    pub const INVALID: i32 = -1;
+    pub const LOWEST: i32 = 0;
+    pub const COALESCE: i32 = 4;
+    pub const LOGICAL_OR: i32 = 5;
+    pub const LOGICAL_AND: i32 = 6;
+    pub const BITWISE_OR: i32 = 7;
+    pub const BITWISE_XOR: i32 = 8;
+    pub const BITWISE_AND: i32 = 9;
+    pub const EQUALITY: i32 = 10;
+    pub const RELATIONAL: i32 = 11;
+    pub const SHIFT: i32 = 12;
+    pub const ADDITIVE: i32 = 13;
+    pub const MULTIPLICATIVE: i32 = 14;
+    pub const EXPONENTIATION: i32 = 15;
+}
+
+/// Gets the precedence of a binary operator token, or `precedence::INVALID` if the token is
+/// not a binary operator
+fn get_binary_operator_precedence(kind: SyntaxKind) -> i32 {
+    match kind {
+        SyntaxKind::QuestionQuestionToken => precedence::COALESCE,
+        SyntaxKind::BarBarToken => precedence::LOGICAL_OR,
+        SyntaxKind::AmpersandAmpersandToken => precedence::LOGICAL_AND,
+        SyntaxKind::BarToken => precedence::BITWISE_OR,
+        SyntaxKind::CaretToken => precedence::BITWISE_XOR,
+        SyntaxKind::AmpersandToken => precedence::BITWISE_AND,
+        SyntaxKind::EqualsEqualsToken
+        | SyntaxKind::ExclamationEqualsToken
+        | SyntaxKind::EqualsEqualsEqualsToken
+        | SyntaxKind::ExclamationEqualsEqualsToken => precedence::EQUALITY,
+        SyntaxKind::LessThanToken
+        | SyntaxKind::GreaterThanToken
+        | SyntaxKind::LessThanEqualsToken
+        | SyntaxKind::GreaterThanEqualsToken
+        | SyntaxKind::InstanceOfKeyword
+        | SyntaxKind::InKeyword
+        | SyntaxKind::AsKeyword
+        | SyntaxKind::SatisfiesKeyword => precedence::RELATIONAL,
+        SyntaxKind::LessThanLessThanToken
+        | SyntaxKind::GreaterThanGreaterThanToken
+        | SyntaxKind::GreaterThanGreaterThanGreaterThanToken => precedence::SHIFT,
+        SyntaxKind::PlusToken | SyntaxKind::MinusToken => precedence::ADDITIVE,
+        SyntaxKind::AsteriskToken | SyntaxKind::SlashToken | SyntaxKind::PercentToken => {
+            precedence::MULTIPLICATIVE
+        }
+        SyntaxKind::AsteriskAsteriskToken => precedence::EXPONENTIATION,
+        _ => precedence::INVALID,
+    }
+}
+
+/// Determines the script kind of a file from its extension
+pub fn get_script_kind_from_file_name(file_name: &str) -> ScriptKind {
+    let lower = file_name.to_ascii_lowercase();
+    if lower.ends_with(".js") || lower.ends_with(".cjs") || lower.ends_with(".mjs") {
+        ScriptKind::JS
+    } else if lower.ends_with(".jsx") {
+        ScriptKind::JSX
+    } else if lower.ends_with(".tsx") {
+        ScriptKind::TSX
+    } else if lower.ends_with(".json") {
+        ScriptKind::JSON
+    } else {
+        ScriptKind::TS
+    }
+}
+
+/// Gets the language variant used to scan files of the given script kind
+pub fn get_language_variant(script_kind: ScriptKind) -> LanguageVariant {
+    match script_kind {
+        ScriptKind::TSX | ScriptKind::JSX | ScriptKind::JS | ScriptKind::JSON => {
+            LanguageVariant::JSX
+        }
+        _ => LanguageVariant::Standard,
+    }
+}
+
+/// Determines if a file name refers to a declaration file (`.d.ts`, `.d.mts`, `.d.cts`, or
+/// an arbitrary-extension declaration file like `.d.css.ts`)
+pub fn is_declaration_file_name(file_name: &str) -> bool {
+    let lower = file_name.to_ascii_lowercase();
+    let base = lower.rsplit(['/', '\\']).next().unwrap_or(&lower);
+    base.ends_with(".d.ts")
+        || base.ends_with(".d.mts")
+        || base.ends_with(".d.cts")
+        || (base.ends_with(".ts") && base.contains(".d."))
+}
+
+/// Parses a source file into an AST
+///
+/// Syntax errors do not stop parsing; they are recorded on the returned `SourceFile` node
+/// and the parser recovers by synthesizing missing nodes or skipping tokens.
+pub fn parse_source_file(
+    file_name: &str,
+    source_text: &str,
+    language_version: ScriptTarget,
+    script_kind: ScriptKind,
+) -> Arc<Node> {
+    parse_source_file_with_jsdoc_parsing_mode(
+        file_name,
+        source_text,
+        language_version,
+        script_kind,
+        JSDocParsingMode::ParseAll,
+    )
+}
+
+/// Parses a source file into an AST, parsing only the JSDoc comments that `jsdoc_parsing_mode`
+/// asks for
+pub fn parse_source_file_with_jsdoc_parsing_mode(
+    file_name: &str,
+    source_text: &str,
+    language_version: ScriptTarget,
+    script_kind: ScriptKind,
+    jsdoc_parsing_mode: JSDocParsingMode,
+) -> Arc<Node> {
+    let mut parser = Parser::new(file_name, source_text, language_version, script_kind);
+    parser.set_jsdoc_parsing_mode(jsdoc_parsing_mode);
+    parser.parse_source_file_worker()
+}
+
+/// Everything needed to rewind the parser to an earlier token
+struct ParserState {
+    scanner: ScannerState,
+    token: SyntaxKind,
+    context_flags: NodeFlags,
+    diagnostics_len: usize,
+    jsdoc_diagnostics_len: usize,
+    parse_error_before_next_finished_node: bool,
+    arena_len: usize,
+}
+
+/// A recursive descent parser that produces the AST for a single source file
+pub struct Parser {
+    scanner: Scanner,
+    file_name: String,
+    language_version: ScriptTarget,
+    language_variant: LanguageVariant,
+    script_kind: ScriptKind,
+    token: SyntaxKind,
+    context_flags: NodeFlags,
+    parsing_context: u32,
+    diagnostics: Rc<RefCell<Vec<Diagnostic>>>,
+    jsdoc_diagnostics: Vec<Diagnostic>,
+    parse_error_before_next_finished_node: bool,
+    not_parenthesized_arrow: HashSet<usize>,
+    node_count: usize,
+    identifier_count: usize,
+    arena: NodeArena,
+    statement_starts: Vec<StatementStart>,
+}
+
+impl Parser {
+    /// Creates a parser for the given source text
+    pub fn new(
+        file_name: &str,
+        source_text: &str,
+        language_version: ScriptTarget,
+        script_kind: ScriptKind,
+    ) -> Self {
+        let script_kind = match script_kind {
+            ScriptKind::Unknown => get_script_kind_from_file_name(file_name),
+            kind => kind,
+        };
+        let language_variant = get_language_variant(script_kind);
+
+        let diagnostics = Rc::new(RefCell::new(Vec::new()));
+        let mut scanner = Scanner::new();
+        scanner.set_text(source_text.to_string());
+        scanner.set_script_target(language_version);
+        scanner.set_script_kind(script_kind);
+        scanner.set_language_variant(language_variant);
+        let scanner_diagnostics = Rc::clone(&diagnostics);
+        let scanner_file_name = file_name.to_string();
+        scanner.set_on_error(Box::new(move |message, pos, length, args| {
+            let mut diagnostics = scanner_diagnostics.borrow_mut();
+            push_diagnostic(
+                &mut diagnostics,
+                Diagnostic::new(
+                    Some(&scanner_file_name),
+                    TextRange::new(pos, pos + length),
+                    message,
+                    args,
+                ),
+            );
+        }));
+
+        let mut context_flags = match script_kind {
+            ScriptKind::JS | ScriptKind::JSX => NodeFlags::JAVASCRIPT_FILE,
+            ScriptKind::JSON => NodeFlags::JSON_FILE,
+            _ => NodeFlags::NONE,
+        };
+        if is_declaration_file_name(file_name) {
+            context_flags |= NodeFlags::AMBIENT;
+        }
+
+        Parser {
+            scanner,
+            file_name: file_name.to_string(),
+            language_version,
+            language_variant,
+            script_kind,
+            token: SyntaxKind::Unknown,
+            context_flags,
+            parsing_context: 0,
+            diagnostics,
+            jsdoc_diagnostics: Vec::new(),
+            parse_error_before_next_finished_node: false,
+            not_parenthesized_arrow: HashSet::new(),
+            node_count: 0,
+            identifier_count: 0,
+            arena: NodeArena::new(),
+            statement_starts: Vec::new(),
+        }
+    }
+
+    /// Sets which JSDoc comments are parsed and attached to declarations
+    pub fn set_jsdoc_parsing_mode(&mut self, mode: JSDocParsingMode) {
+        self.scanner.set_jsdoc_parsing_mode(mode);
+    }
+
+    fn parse_source_file_worker(&mut self) -> Arc<Node> {
+        if self.script_kind == ScriptKind::JSON {
+            return self.parse_json_text();
+        }
+        let pos = self.node_pos();
+        self.next_token();
+        let statements = self.parse_list(
+            ParsingContext::SourceElements,
+            Self::parse_source_file_statement,
+        );
+        debug_assert_eq!(self.token, SyntaxKind::EndOfFile);
+        let end_of_file_token = self.parse_token_node();
+        self.finish_source_file(pos, statements, end_of_file_token)
+    }
+
+    /// Parses a top-level statement, recording the state the parser starts it in
+    fn parse_source_file_statement(&mut self) -> Arc<Node> {
+        self.statement_starts.push(self.statement_start());
+        self.parse_statement()
+    }
+
+    fn statement_start(&self) -> StatementStart {
+        StatementStart {
+            diagnostics_len: self.diagnostics_len(),
+            jsdoc_diagnostics_len: self.jsdoc_diagnostics.len(),
+            error_pending: self.parse_error_before_next_finished_node,
+        }
+    }
+
+    /// Parses a JSON file, whose value becomes the expression of its only statement
+    fn parse_json_text(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.next_token();
+        let mut statements = Vec::new();
+        if self.token != SyntaxKind::EndOfFile {
+            self.statement_starts.push(self.statement_start());
+            let statement_pos = self.node_pos();
+            let expression = self.parse_assignment_expression_or_higher(false);
+            statements.push(self.finish_node(
+                SyntaxKind::ExpressionStatement,
+                statement_pos,
+                ExpressionStatement { expression },
+            ));
+            // A JSON file holds a single value
+            if self.token != SyntaxKind::EndOfFile {
+                self.parse_error_at_current_token(diagnostics::UNEXPECTED_TOKEN_1012, &[]);
+                while self.token != SyntaxKind::EndOfFile {
+                    self.next_token();
+                }
+            }
+        }
+        let statements = self.create_node_list(pos, statements);
+        let end_of_file_token = self.parse_token_node();
+        self.finish_source_file(pos, statements, end_of_file_token)
+    }
+
+    fn finish_source_file(
+        &mut self,
+        pos: usize,
+        statements: NodeList,
+        end_of_file_token: Arc<Node>,
+    ) -> Arc<Node> {
+        let parse_diagnostics = self.diagnostics.borrow().clone();
+        let arena =
+            std::mem::take(&mut self.arena).finish(statements.iter().chain([&end_of_file_token]));
+        let data = SourceFile {
+            statements,
+            end_of_file_token,
+            file_name: self.file_name.clone(),
+            text: self.scanner.text().to_string(),
+            language_version: self.language_version,
+            language_variant: self.language_variant,
+            script_kind: self.script_kind,
+            is_declaration_file: is_declaration_file_name(&self.file_name),
+            parse_diagnostics,
+            jsdoc_diagnostics: std::mem::take(&mut self.jsdoc_diagnostics),
+            jsdoc_parsing_mode: self.scanner.jsdoc_parsing_mode(),
+            node_count: self.node_count,
+            identifier_count: self.identifier_count,
+            arena: Some(Arc::new(arena)),
+            statement_starts: std::mem::take(&mut self.statement_starts),
+        };
+        let flags = self.context_flags
+            & (NodeFlags::JAVASCRIPT_FILE | NodeFlags::JSON_FILE | NodeFlags::AMBIENT);
+        Node::new(
+            SyntaxKind::SourceFile,
+            flags,
+            TextRange::new(pos, self.scanner.text().len()),
+            data,
+        )
+    }
+
+    // Scanning
+
+    fn next_token(&mut self) -> SyntaxKind {
+        // A keyword written with an escape, like `\u0069f`, is only allowed as an identifier,
+        // which is consumed without this check
+        if scanner::is_keyword(self.token)
+            && (self.scanner.has_unicode_escape() || self.scanner.has_extended_unicode_escape())
+        {
+            self.parse_error_at_current_token(
+                diagnostics::KEYWORDS_CANNOT_CONTAIN_ESCAPE_CHARACTERS_1260,
+                &[],
+            );
+        }
+        self.next_token_without_check()
+    }
+
+    fn next_token_without_check(&mut self) -> SyntaxKind {
+        self.token = self.scanner.scan();
+        self.token
+    }
+
+    fn node_pos(&self) -> usize {
+        self.scanner.token_full_start()
+    }
+
+    fn has_preceding_line_break(&self) -> bool {
+        self.scanner.has_preceding_line_break()
+    }
+
+    fn has_preceding_jsdoc_comment(&self) -> bool {
+        self.scanner.has_preceding_jsdoc_comment()
+    }
+
+    fn re_scan_greater_token(&mut self) -> SyntaxKind {
+        self.token = self.scanner.re_scan_greater_token();
+        self.token
+    }
+
+    fn re_scan_less_than_token(&mut self) -> SyntaxKind {
+        self.token = self.scanner.re_scan_less_than_token();
+        self.token
+    }
+
+    fn re_scan_slash_token(&mut self) -> SyntaxKind {
+        self.token = self.scanner.re_scan_slash_token();
+        self.token
+    }
+
+    fn re_scan_template_token(&mut self, is_tagged_template: bool) -> SyntaxKind {
+        self.token = self.scanner.re_scan_template_token(is_tagged_template);
+        self.token
+    }
+
+    fn scan_jsx_text(&mut self) -> SyntaxKind {
+        self.token = self.scanner.scan_jsx_token(true);
+        self.token
+    }
+
+    fn re_scan_jsx_token(&mut self) -> SyntaxKind {
+        self.token = self.scanner.re_scan_jsx_token(true);
+        self.token
+    }
+
+    fn scan_jsx_identifier(&mut self) -> SyntaxKind {
+        self.token = self.scanner.scan_jsx_identifier();
+        self.token
+    }
+
+    fn scan_jsx_attribute_value(&mut self) -> SyntaxKind {
+        self.token = self.scanner.scan_jsx_attribute_value();
+        self.token
+    }
+
+    // Speculation
+
+    fn mark(&self) -> ParserState {
+        ParserState {
+            scanner: self.scanner.mark(),
+            token: self.token,
+            context_flags: self.context_flags,
+            diagnostics_len: self.diagnostics.borrow().len(),
+            jsdoc_diagnostics_len: self.jsdoc_diagnostics.len(),
+            parse_error_before_next_finished_node: self.parse_error_before_next_finished_node,
+            arena_len: self.arena.len(),
+        }
+    }
+
+    fn rewind(&mut self, state: ParserState) {
+        self.scanner.rewind(state.scanner);
+        self.token = state.token;
+        self.context_flags = state.context_flags;
+        self.diagnostics
+            .borrow_mut()
+            .truncate(state.diagnostics_len);
+        self.jsdoc_diagnostics.truncate(state.jsdoc_diagnostics_len);
+        self.parse_error_before_next_finished_node = state.parse_error_before_next_finished_node;
+        self.arena.truncate(state.arena_len);
+    }
+
+    /// Runs `callback` and always restores the parser to the state it was in beforehand
+    fn look_ahead<T>(&mut self, callback: impl FnOnce(&mut Self) -> T) -> T {
+        let state = self.mark();
+        let result = callback(self);
+        self.rewind(state);
+        result
+    }
+
+    /// Runs `callback` and restores the parser state only if it returns `None`
+    fn try_parse<T>(&mut self, callback: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let state = self.mark();
+        let result = callback(self);
+        if result.is_none() {
+            self.rewind(state);
+        }
+        result
+    }
+
+    /// Runs `callback` and restores the parser state only if it returns `false`
+    fn try_parse_bool(&mut self, callback: impl FnOnce(&mut Self) -> bool) -> bool {
+        self.try_parse(|p| callback(p).then_some(())).is_some()
+    }
+
+    // Context
+
+    fn set_context_flag(&mut self, value: bool, flag: NodeFlags) {
+        self.context_flags.set(flag, value);
+    }
+
+    fn in_context(&self, flags: NodeFlags) -> bool {
+        self.context_flags.intersects(flags)
+    }
+
+    fn in_yield_context(&self) -> bool {
+        self.in_context(NodeFlags::YIELD_CONTEXT)
+    }
+
+    fn in_await_context(&self) -> bool {
+        self.in_context(NodeFlags::AWAIT_CONTEXT)
+    }
+
+    fn in_disallow_in_context(&self) -> bool {
+        self.in_context(NodeFlags::DISALLOW_IN_CONTEXT)
+    }
+
+    fn in_decorator_context(&self) -> bool {
+        self.in_context(NodeFlags::DECORATOR_CONTEXT)
+    }
+
+    fn in_disallow_conditional_types_context(&self) -> bool {
+        self.in_context(NodeFlags::DISALLOW_CONDITIONAL_TYPES_CONTEXT)
+    }
+
+    fn do_inside_of_context<T>(&mut self, flags: NodeFlags, f: impl FnOnce(&mut Self) -> T) -> T {
+        let saved = self.context_flags;
+        self.context_flags |= flags;
+        let result = f(self);
+        self.context_flags = saved;
+        result
+    }
+
+    fn do_outside_of_context<T>(&mut self, flags: NodeFlags, f: impl FnOnce(&mut Self) -> T) -> T {
+        let saved = self.context_flags;
+        self.context_flags &= !flags;
+        let result = f(self);
+        self.context_flags = saved;
+        result
+    }
+
+    fn allow_in_and<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.do_outside_of_context(NodeFlags::DISALLOW_IN_CONTEXT, f)
+    }
+
+    fn disallow_in_and<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.do_inside_of_context(NodeFlags::DISALLOW_IN_CONTEXT, f)
+    }
+
+    fn allow_conditional_types_and<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.do_outside_of_context(NodeFlags::DISALLOW_CONDITIONAL_TYPES_CONTEXT, f)
+    }
+
+    fn disallow_conditional_types_and<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.do_inside_of_context(NodeFlags::DISALLOW_CONDITIONAL_TYPES_CONTEXT, f)
+    }
+
+    fn do_in_yield_and_await_context<T>(
+        &mut self,
+        flags: SignatureFlags,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let saved = self.context_flags;
+        self.set_context_flag(
+            flags.contains(SignatureFlags::YIELD),
+            NodeFlags::YIELD_CONTEXT,
+        );
+        self.set_context_flag(
+            flags.contains(SignatureFlags::AWAIT),
+            NodeFlags::AWAIT_CONTEXT,
+        );
+        let result = f(self);
+        self.context_flags = saved;
+        result
+    }
+
+    // Diagnostics
+
+    fn parse_error_at_range(&mut self, pos: usize, end: usize, message: &Message, args: &[String]) {
+        let diagnostic = Diagnostic::new(
+            Some(&self.file_name),
+            TextRange::new(pos, end),
+            message,
+            args,
+        );
+        push_diagnostic(&mut self.diagnostics.borrow_mut(), diagnostic);
+        // Mark that we've encountered an error. We'll set an appropriate bit on the next node we finish so that it can't be reused incrementally.
+        self.parse_error_before_next_finished_node = true;
+    }
+
+    fn parse_error_at_current_token(&mut self, message: &Message, args: &[String]) {
+        let (pos, end) = (self.scanner.token_start(), self.scanner.token_end());
+        self.parse_error_at_range(pos, end, message, args);
+    }
+
+    fn parse_error_at_node(&mut self, node: &Node, message: &Message, args: &[String]) {
+        let pos = skip_trivia(self.scanner.text(), node.pos());
+        self.parse_error_at_range(pos, node.end().max(pos), message, args);
+    }
+
+    fn diagnostics_len(&self) -> usize {
+        self.diagnostics.borrow().len()
+    }
+
+    // Node construction
+
+    fn finish_node(&mut self, kind: SyntaxKind, pos: usize, data: impl NodeData) -> Arc<Node> {
+        self.finish_node_with_flags(kind, pos, NodeFlags::NONE, data)
+    }
+
+    fn finish_node_with_flags(
+        &mut self,
+        kind: SyntaxKind,
+        pos: usize,
+        flags: NodeFlags,
+        data: impl NodeData,
+    ) -> Arc<Node> {
+        let end = self.node_pos();
+        self.finish_node_with_end(kind, pos, end, flags, data)
+    }
+
+    fn finish_node_with_end(
+        &mut self,
+        kind: SyntaxKind,
+        pos: usize,
+        end: usize,
+        flags: NodeFlags,
+        data: impl NodeData,
+    ) -> Arc<Node> {
+        let mut flags = flags | (self.context_flags & NodeFlags::CONTEXT_FLAGS);
+        if self.parse_error_before_next_finished_node {
+            self.parse_error_before_next_finished_node = false;
+            flags |= NodeFlags::THIS_NODE_HAS_ERROR;
+        }
+        self.node_count += 1;
+        let node = Node::new(kind, flags, TextRange::new(pos, end.max(pos)), data);
+        self.arena.alloc(&node);
+        node
+    }
+
+    fn create_node_list(&self, pos: usize, nodes: Vec<Arc<Node>>) -> NodeList {
+        NodeList::new(TextRange::new(pos, self.node_pos()), nodes)
+    }
+
+    fn create_missing_list(&self) -> NodeList {
+        let pos = self.node_pos();
+        NodeList::new(TextRange::new(pos, pos), Vec::new())
+    }
+
+    fn create_missing_identifier(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.identifier_count += 1;
+        self.finish_node_with_end(
+            SyntaxKind::Identifier,
+            pos,
+            pos,
+            NodeFlags::NONE,
+            Identifier {
+                text: String::new(),
+            },
+        )
+    }
+
+    fn create_missing_identifier_with_error(
+        &mut self,
+        report_at_current_position: bool,
+        message: &Message,
+        args: &[String],
+    ) -> Arc<Node> {
+        if report_at_current_position {
+            let pos = self.scanner.token_full_start();
+            self.parse_error_at_range(pos, pos, message, args);
+        } else {
+            self.parse_error_at_current_token(message, args);
+        }
+        self.create_missing_identifier()
+    }
+
+    fn create_missing_token(&mut self, kind: SyntaxKind) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.finish_node_with_end(kind, pos, pos, NodeFlags::NONE, Token {})
+    }
+
+    // Tokens
+
+    fn parse_expected(&mut self, kind: SyntaxKind) -> bool {
+        self.parse_expected_with_diagnostic(kind, None, true)
+    }
+
+    fn parse_expected_with_diagnostic(
+        &mut self,
+        kind: SyntaxKind,
+        message: Option<&Message>,
+        should_advance: bool,
+    ) -> bool {
+        if self.token == kind {
+            if should_advance {
+                self.next_token();
+            }
+            return true;
+        }
+        // Report specific message if provided with one. Otherwise, report generic fallback message.
+        match message {
+            Some(message) => self.parse_error_at_current_token(message, &[]),
+            None => self
+                .parse_error_at_current_token(diagnostics::D_0_EXPECTED_1005, &[token_text(kind)]),
+        }
+        false
+    }
+
+    fn parse_expected_matching_brackets(
+        &mut self,
+        open_kind: SyntaxKind,
+        close_kind: SyntaxKind,
+        open_parsed: bool,
+        open_position: usize,
+    ) {
+        if self.token == close_kind {
+            self.next_token();
+            return;
+        }
+        let before = self.diagnostics_len();
+        self.parse_error_at_current_token(
+            diagnostics::D_0_EXPECTED_1005,
+            &[token_text(close_kind)],
+        );
+        if !open_parsed || self.diagnostics_len() == before {
+            return;
+        }
+        let related = Diagnostic::new(
+            Some(&self.file_name),
+            TextRange::new(open_position, open_position + 1),
+            diagnostics::THE_PARSER_EXPECTED_TO_FIND_A_1_TO_MATCH_THE_0_TOKEN_HERE_1007,
+            &[token_text(open_kind), token_text(close_kind)],
+        );
+        let mut diagnostics = self.diagnostics.borrow_mut();
+        if let Some(last) = diagnostics.pop() {
+            diagnostics.push(last.with_related_information(related));
+        }
+    }
+
+    fn parse_optional(&mut self, kind: SyntaxKind) -> bool {
+        if self.token == kind {
+            self.next_token();
+            return true;
+        }
+        false
+    }
+
+    fn parse_optional_token(&mut self, kind: SyntaxKind) -> Option<Arc<Node>> {
+        if self.token == kind {
+            return Some(self.parse_token_node());
+        }
+        None
+    }
+
+    fn parse_expected_token(&mut self, kind: SyntaxKind) -> Arc<Node> {
+        self.parse_optional_token(kind).unwrap_or_else(|| {
+            self.parse_error_at_current_token(diagnostics::D_0_EXPECTED_1005, &[token_text(kind)]);
+            self.create_missing_token(kind)
+        })
+    }
+
+    fn parse_token_node(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let kind = self.token;
+        self.next_token();
+        self.finish_node(kind, pos, Token {})
+    }
+
+    fn can_parse_semicolon(&self) -> bool {
+        // If there's a real semicolon, then we can always parse it out.
+        // We can parse out an optional semicolon in ASI cases in the following cases.
+        self.token == SyntaxKind::SemicolonToken
+            || self.token == SyntaxKind::CloseBraceToken
+            || self.token == SyntaxKind::EndOfFile
+            || self.has_preceding_line_break()
+    }
+
+    fn try_parse_semicolon(&mut self) -> bool {
+        if !self.can_parse_semicolon() {
+            return false;
+        }
+        if self.token == SyntaxKind::SemicolonToken {
+            // consume the semicolon if it was explicitly provided.
+            self.next_token();
+        }
+        true
+    }
+
+    fn parse_semicolon(&mut self) -> bool {
+        self.try_parse_semicolon() || self.parse_expected(SyntaxKind::SemicolonToken)
+    }
+
+    // Identifiers
+
+    fn is_identifier(&self) -> bool {
+        if self.token == SyntaxKind::Identifier {
+            return true;
+        }
+        // If we have a 'yield' keyword, and we're in the [yield] context, then 'yield' is
+        // considered a keyword and is not an identifier.
+        if self.token == SyntaxKind::YieldKeyword && self.in_yield_context() {
+            return false;
+        }
+        // If we have a 'await' keyword, and we're in the [Await] context, then 'await' is
+        // considered a keyword and is not an identifier.
+        if self.token == SyntaxKind::AwaitKeyword && self.in_await_context() {
+            return false;
+        }
+        self.token > SyntaxKind::LAST_RESERVED_WORD && self.token <= SyntaxKind::LAST_KEYWORD
+    }
+
+    fn is_binding_identifier(&self) -> bool {
+        if self.token == SyntaxKind::Identifier {
+            return true;
+        }
+        // `let await`/`let yield` in [Yield] or [Await] are allowed here and disallowed in the binder.
+        self.token > SyntaxKind::LAST_RESERVED_WORD && self.token <= SyntaxKind::LAST_KEYWORD
+    }
+
+    fn create_identifier(&mut self, is_identifier: bool) -> Arc<Node> {
+        self.create_identifier_with_diagnostic(is_identifier, None, None)
+    }
+
+    fn create_identifier_with_diagnostic(
+        &mut self,
+        is_identifier: bool,
+        message: Option<&Message>,
+        private_identifier_message: Option<&Message>,
+    ) -> Arc<Node> {
+        if is_identifier {
+            self.identifier_count += 1;
+            let pos = self.node_pos();
+            let text = self.scanner.token_value().to_string();
+            let flags = if self.scanner.has_extended_unicode_escape() {
+                NodeFlags::IDENTIFIER_HAS_EXTENDED_UNICODE_ESCAPE
+            } else {
+                NodeFlags::NONE
+            };
+            self.next_token_without_check();
+            return self.finish_node_with_flags(
+                SyntaxKind::Identifier,
+                pos,
+                flags,
+                Identifier { text },
+            );
+        }
+
+        if self.token == SyntaxKind::PrivateIdentifier {
+            self.parse_error_at_current_token(
+                private_identifier_message.unwrap_or(
+                    diagnostics::PRIVATE_IDENTIFIERS_ARE_NOT_ALLOWED_OUTSIDE_CLASS_BODIES_18016,
+                ),
+                &[],
+            );
+            return self.create_identifier(true);
+        }
+
+        if let Some(message) = message {
+            self.parse_error_at_current_token(message, &[]);
+            return self.create_missing_identifier();
+        }
+
+        // Only for end of file because the error gets reported incorrectly on embedded script tags.
+        let report_at_current_position = self.token == SyntaxKind::EndOfFile;
+        let is_reserved_word = self.token >= SyntaxKind::FIRST_RESERVED_WORD
+            && self.token <= SyntaxKind::LAST_RESERVED_WORD;
+        let text = self.scanner.token_text().to_string();
+        if is_reserved_word {
+            self.create_missing_identifier_with_error(
+                report_at_current_position,
+                diagnostics::IDENTIFIER_EXPECTED_0_IS_A_RESERVED_WORD_THAT_CANNOT_BE_USED_HERE_1359,
+                &[text],
+            )
+        } else {
+            self.create_missing_identifier_with_error(
+                report_at_current_position,
+                diagnostics::IDENTIFIER_EXPECTED_1003,
+                &[],
+            )
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Arc<Node> {
+        let is_identifier = self.is_identifier();
+        self.create_identifier(is_identifier)
+    }
+
+    fn parse_identifier_with_diagnostic(&mut self, message: &Message) -> Arc<Node> {
+        let is_identifier = self.is_identifier();
+        self.create_identifier_with_diagnostic(is_identifier, Some(message), None)
+    }
+
+    fn parse_identifier_name(&mut self) -> Arc<Node> {
+        let is_identifier = scanner::is_identifier_or_keyword(self.token);
+        self.create_identifier(is_identifier)
+    }
+
+    fn parse_binding_identifier(&mut self) -> Arc<Node> {
+        let is_identifier = self.is_binding_identifier();
+        self.create_identifier(is_identifier)
+    }
+
+    fn parse_binding_identifier_with_diagnostic(
+        &mut self,
+        private_identifier_message: &Message,
+    ) -> Arc<Node> {
+        let is_identifier = self.is_binding_identifier();
+        self.create_identifier_with_diagnostic(
+            is_identifier,
+            None,
+            Some(private_identifier_message),
+        )
+    }
+
+    fn parse_optional_binding_identifier(&mut self) -> Option<Arc<Node>> {
+        if self.is_binding_identifier() {
+            Some(self.parse_binding_identifier())
+        } else {
+            None
+        }
+    }
+
+    fn parse_private_identifier(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let text = self.scanner.token_value().to_string();
+        self.next_token();
+        self.finish_node(
+            SyntaxKind::PrivateIdentifier,
+            pos,
+            PrivateIdentifier { text },
+        )
+    }
+
+    fn is_literal_property_name(&self) -> bool {
+        // Private identifiers sort after identifiers in tsc's token ordering, so its
+        // identifier-or-keyword check accepts them here as well
+        scanner::is_identifier_or_keyword(self.token)
+            || self.token == SyntaxKind::PrivateIdentifier
+            || self.token == SyntaxKind::StringLiteral
+            || self.token == SyntaxKind::NumericLiteral
+            || self.token == SyntaxKind::BigIntLiteral
+    }
+
+    fn parse_property_name(&mut self) -> Arc<Node> {
+        match self.token {
+            SyntaxKind::StringLiteral | SyntaxKind::NumericLiteral | SyntaxKind::BigIntLiteral => {
+                self.parse_literal_node()
+            }
+            SyntaxKind::OpenBracketToken => self.parse_computed_property_name(),
+            SyntaxKind::PrivateIdentifier => self.parse_private_identifier(),
+            _ => self.parse_identifier_name(),
+        }
+    }
+
+    fn parse_computed_property_name(&mut self) -> Arc<Node> {
+        // PropertyName [Yield]:
+        //      LiteralPropertyName
+        //      ComputedPropertyName[?Yield]
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::OpenBracketToken);
+        // We parse any expression (including a comma expression). But the grammar
+        // says that only an assignment expression is allowed, so the grammar checker
+        // will error if it sees a comma expression.
+        let expression = self.allow_in_and(Self::parse_expression);
+        self.parse_expected(SyntaxKind::CloseBracketToken);
+        self.finish_node(
+            SyntaxKind::ComputedPropertyName,
+            pos,
+            ComputedPropertyName { expression },
+        )
+    }
+
+    fn parse_literal_node(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let kind = self.token;
+        let text = self.scanner.token_value().to_string();
+        let token_flags = self.scanner.token_flags();
+        match kind {
+            SyntaxKind::NumericLiteral => {
+                self.next_token();
+                self.finish_node(kind, pos, NumericLiteral { text, token_flags })
+            }
+            SyntaxKind::BigIntLiteral => {
+                self.next_token();
+                self.finish_node(kind, pos, BigIntLiteral { text })
+            }
+            SyntaxKind::StringLiteral => {
+                self.next_token();
+                self.finish_node(kind, pos, StringLiteral { text, token_flags })
+            }
+            SyntaxKind::RegularExpressionLiteral => {
+                self.next_token();
+                self.finish_node(kind, pos, RegularExpressionLiteral { text })
+            }
+            _ => {
+                let raw_text = template_raw_text(kind, self.scanner.token_text());
+                self.next_token();
+                self.finish_node(
+                    kind,
+                    pos,
+                    TemplateLiteralLikeNode {
+                        text,
+                        raw_text,
+                        token_flags,
+                    },
+                )
+            }
+        }
+    }
+
+    fn parse_entity_name(
+        &mut self,
+        allow_reserved_words: bool,
+        message: Option<&Message>,
+    ) -> Arc<Node> {
+        let pos = self.node_pos();
+        let mut entity = if allow_reserved_words && scanner::is_identifier_or_keyword(self.token) {
+            self.parse_identifier_name()
+        } else {
+            match message {
+                Some(message) => self.parse_identifier_with_diagnostic(message),
+                None => self.parse_identifier(),
+            }
+        };
+        while self.parse_optional(SyntaxKind::DotToken) {
+            if self.token == SyntaxKind::LessThanToken {
+                // The entity is part of a JSDoc-style generic. We will use the gap between `typeName` and
+                // `typeArguments` to report it as a grammar error in the checker.
+                break;
+            }
+            let right = self.parse_right_side_of_dot(allow_reserved_words, false);
+            entity = self.finish_node(
+                SyntaxKind::QualifiedName,
+                pos,
+                QualifiedName {
+                    left: entity,
+                    right,
+                },
+            );
+        }
+        entity
+    }
+
+    fn parse_right_side_of_dot(
+        &mut self,
+        allow_identifier_names: bool,
+        allow_private_identifiers: bool,
+    ) -> Arc<Node> {
+        // Technically a keyword is valid here as all identifiers and keywords are identifier names.
+        // However, often we'll encounter this in error situations when the identifier or keyword
+        // is actually starting another valid construct.
+        //
+        // So, we check for the following specific case:
+        //
+        //      name.
+        //      identifierOrKeyword identifierNameOrKeyword
+        //
+        // Note: the newlines are important here.  For example, if that above code
+        // were rewritten into:
+        //
+        //      name.identifierOrKeyword
+        //      identifierNameOrKeyword
+        //
+        // Then we would consider it valid.  That's because ASI would take effect and
+        // the code would be implicitly: "name.identifierOrKeyword; identifierNameOrKeyword".
+        // In the first case though, ASI will not take effect because there is not a
+        // line terminator after the identifier or keyword.
+        if self.has_preceding_line_break()
+            && scanner::is_identifier_or_keyword(self.token)
+            && self.look_ahead(Self::next_token_is_identifier_or_keyword_on_same_line)
+        {
+            // Report that we need an identifier.  However, report it right after the dot,
+            // and not on the next token.  This is because the next token might actually
+            // be an identifier and the error would be quite confusing.
+            return self.create_missing_identifier_with_error(
+                true,
+                diagnostics::IDENTIFIER_EXPECTED_1003,
+                &[],
+            );
+        }
+
+        if self.token == SyntaxKind::PrivateIdentifier {
+            let node = self.parse_private_identifier();
+            if !allow_private_identifiers {
+                self.parse_error_at_node(&node, diagnostics::IDENTIFIER_EXPECTED_1003, &[]);
+            }
+            return node;
+        }
+
+        if allow_identifier_names {
+            self.parse_identifier_name()
+        } else {
+            self.parse_identifier()
+        }
+    }
+
+    // Lookahead helpers
+
+    fn next_token_is_identifier(&mut self) -> bool {
+        self.next_token();
+        self.is_identifier()
+    }
+
+    fn next_token_is_identifier_or_keyword(&mut self) -> bool {
+        self.next_token();
+        scanner::is_identifier_or_keyword(self.token)
+    }
+
+    fn next_token_is_identifier_or_keyword_on_same_line(&mut self) -> bool {
+        self.next_token();
+        scanner::is_identifier_or_keyword(self.token) && !self.has_preceding_line_break()
+    }
+
+    fn next_token_is_identifier_on_same_line(&mut self) -> bool {
+        self.next_token();
+        !self.has_preceding_line_break() && self.is_identifier()
+    }
+
+    fn next_token_is_identifier_or_string_literal_on_same_line(&mut self) -> bool {
+        self.next_token();
+        !self.has_preceding_line_break()
+            && (self.is_identifier() || self.token == SyntaxKind::StringLiteral)
+    }
+
+    fn next_token_is_identifier_or_keyword_or_literal_on_same_line(&mut self) -> bool {
+        self.next_token();
+        (scanner::is_identifier_or_keyword(self.token)
+            || self.token == SyntaxKind::NumericLiteral
+            || self.token == SyntaxKind::BigIntLiteral
+            || self.token == SyntaxKind::StringLiteral)
+            && !self.has_preceding_line_break()
+    }
+
+    fn next_token_is_identifier_or_this_on_same_line(&mut self) -> bool {
+        self.next_token();
+        !self.has_preceding_line_break()
+            && (self.is_identifier() || self.token == SyntaxKind::ThisKeyword)
+    }
+
+    fn next_token_is_identifier_or_keyword_or_greater_than(&mut self) -> bool {
+        self.next_token();
+        scanner::is_identifier_or_keyword(self.token) || self.token == SyntaxKind::GreaterThanToken
+    }
+
+    fn next_token_is_identifier_or_keyword_or_open_bracket_or_template(&mut self) -> bool {
+        self.next_token();
+        scanner::is_identifier_or_keyword(self.token)
+            || self.token == SyntaxKind::OpenBracketToken
+            || self.is_template_start_of_tagged_template()
+    }
+
+    fn next_token_is_open_paren(&mut self) -> bool {
+        self.next_token() == SyntaxKind::OpenParenToken
+    }
+
+    fn next_token_is_open_brace(&mut self) -> bool {
+        self.next_token() == SyntaxKind::OpenBraceToken
+    }
+
+    fn next_token_is_open_paren_or_less_than(&mut self) -> bool {
+        self.next_token();
+        self.token == SyntaxKind::OpenParenToken || self.token == SyntaxKind::LessThanToken
+    }
+
+    fn next_token_is_open_paren_or_less_than_or_dot(&mut self) -> bool {
+        self.next_token();
+        matches!(
+            self.token,
+            SyntaxKind::OpenParenToken | SyntaxKind::LessThanToken | SyntaxKind::DotToken
+        )
+    }
+
+    fn next_token_is_dot(&mut self) -> bool {
+        self.next_token() == SyntaxKind::DotToken
+    }
+
+    fn next_token_is_slash(&mut self) -> bool {
+        self.next_token() == SyntaxKind::SlashToken
+    }
+
+    fn next_token_is_string_literal(&mut self) -> bool {
+        self.next_token() == SyntaxKind::StringLiteral
+    }
+
+    fn next_token_is_numeric_or_big_int_literal(&mut self) -> bool {
+        self.next_token();
+        self.token == SyntaxKind::NumericLiteral || self.token == SyntaxKind::BigIntLiteral
+    }
+
+    fn next_token_is_new_keyword(&mut self) -> bool {
+        self.next_token() == SyntaxKind::NewKeyword
+    }
+
+    fn next_token_is_class_keyword_on_same_line(&mut self) -> bool {
+        self.next_token();
+        self.token == SyntaxKind::ClassKeyword && !self.has_preceding_line_break()
+    }
+
+    fn next_token_is_function_keyword_on_same_line(&mut self) -> bool {
+        self.next_token();
+        self.token == SyntaxKind::FunctionKeyword && !self.has_preceding_line_break()
+    }
+
+    fn next_token_is_from_keyword_or_equals_token(&mut self) -> bool {
+        self.next_token();
+        self.token == SyntaxKind::FromKeyword || self.token == SyntaxKind::EqualsToken
+    }
+
+    fn next_token_is_colon_or_question_colon(&mut self) -> bool {
+        self.next_token() == SyntaxKind::ColonToken
+            || (self.token == SyntaxKind::QuestionToken
+                && self.next_token() == SyntaxKind::ColonToken)
+    }
+
+    // Lists
+
+    fn is_list_element(&mut self, context: ParsingContext, in_error_recovery: bool) -> bool {
+        match context {
+            ParsingContext::SourceElements
+            | ParsingContext::BlockStatements
+            | ParsingContext::SwitchClauseStatements => {
+                // If we're in error recovery, then we don't want to treat ';' as an empty statement.
+                // The problem is that ';' can show up in far too many contexts, and if we see one
+                // and assume it's a statement, then we may bail out inappropriately from whatever
+                // we're parsing.  For example, if we have a semicolon in the middle of a class, then
+                // we really don't want to assume the class is over and we're on a statement in the
+                // outer module.  We just want to consume and move on.
+                !(self.token == SyntaxKind::SemicolonToken && in_error_recovery)
+                    && self.is_start_of_statement()
+            }
+            ParsingContext::SwitchClauses => {
+                self.token == SyntaxKind::CaseKeyword || self.token == SyntaxKind::DefaultKeyword
+            }
+            ParsingContext::TypeMembers => self.look_ahead(Self::is_type_member_start),
+            ParsingContext::ClassMembers => {
+                // We allow semicolons as class elements (as specified by ES6) as long as we're
+                // not in error recovery.  If we're in error recovery, we don't want an errant
+                // semicolon to be treated as a class member (since they're almost always used
+                // for statements.
+                self.look_ahead(Self::is_class_member_start)
+                    || (self.token == SyntaxKind::SemicolonToken && !in_error_recovery)
+            }
+            ParsingContext::EnumMembers => {
+                // Include open bracket computed properties. This technically also lets in indexers,
+                // which would be a candidate for improved error reporting.
+                self.token == SyntaxKind::OpenBracketToken || self.is_literal_property_name()
+            }
+            ParsingContext::ObjectLiteralMembers => match self.token {
+                SyntaxKind::OpenBracketToken
+                | SyntaxKind::AsteriskToken
+                | SyntaxKind::DotDotDotToken
+                | SyntaxKind::DotToken => true,
+                _ => self.is_literal_property_name(),
+            },
+            ParsingContext::RestProperties => self.is_literal_property_name(),
+            ParsingContext::ObjectBindingElements => {
+                self.token == SyntaxKind::OpenBracketToken
+                    || self.token == SyntaxKind::DotDotDotToken
+                    || self.is_literal_property_name()
+            }
+            ParsingContext::ImportAttributes => self.is_import_attribute_name(),
+            ParsingContext::HeritageClauseElement => {
+                // If we see `{ ... }` then only consume it as an expression if it is followed by `,` or `{`
+                // That way we won't consume the body of a class in its heritage clause.
+                if self.token == SyntaxKind::OpenBraceToken {
+                    return self.look_ahead(Self::is_valid_heritage_clause_object_literal);
+                }
+                if !in_error_recovery {
+                    self.is_start_of_left_hand_side_expression()
+                        && !self.is_heritage_clause_extends_or_implements_keyword()
+                } else {
+                    // If we're in error recovery we tighten up what we're willing to match.
+                    // That way we don't treat something like "this" as a valid heritage clause
+                    // element during recovery.
+                    self.is_identifier() && !self.is_heritage_clause_extends_or_implements_keyword()
+                }
+            }
+            ParsingContext::VariableDeclarations => {
+                self.is_binding_identifier_or_private_identifier_or_pattern()
+            }
+            ParsingContext::ArrayBindingElements => {
+                self.token == SyntaxKind::CommaToken
+                    || self.token == SyntaxKind::DotDotDotToken
+                    || self.is_binding_identifier_or_private_identifier_or_pattern()
+            }
+            ParsingContext::TypeParameters => {
+                self.token == SyntaxKind::InKeyword
+                    || self.token == SyntaxKind::ConstKeyword
+                    || self.is_identifier()
+            }
+            ParsingContext::ArrayLiteralMembers => match self.token {
+                // Not an ArrayLiteralElement, but we want to treat it as one for error recovery.
+                SyntaxKind::CommaToken | SyntaxKind::DotToken => true,
+                _ => self.token == SyntaxKind::DotDotDotToken || self.is_start_of_expression(),
+            },
+            ParsingContext::ArgumentExpressions => {
+                self.token == SyntaxKind::DotDotDotToken || self.is_start_of_expression()
+            }
+            ParsingContext::Parameters => self.is_start_of_parameter(),
+            ParsingContext::TypeArguments | ParsingContext::TupleElementTypes => {
+                self.token == SyntaxKind::CommaToken || self.is_start_of_type(false)
+            }
+            ParsingContext::HeritageClauses => self.is_heritage_clause(),
+            ParsingContext::ImportOrExportSpecifiers => {
+                // bail out if the next token is [FromKeyword StringLiteral].
+                // That means we're in something like `import { from "mod"`. Stop here can give better error message.
+                if self.token == SyntaxKind::FromKeyword
+                    && self.look_ahead(Self::next_token_is_string_literal)
+                {
+                    return false;
+                }
+                self.token == SyntaxKind::StringLiteral
+                    || scanner::is_identifier_or_keyword(self.token)
+            }
+            ParsingContext::JsxAttributes => {
+                scanner::is_identifier_or_keyword(self.token)
+                    || self.token == SyntaxKind::OpenBraceToken
+            }
+            ParsingContext::JsxChildren => true,
+        }
+    }
+
+    fn is_list_terminator(&mut self, context: ParsingContext) -> bool {
+        if self.token == SyntaxKind::EndOfFile {
+            // Being at the end of the file ends all lists.
+            return true;
+        }
+        match context {
+            ParsingContext::BlockStatements
+            | ParsingContext::SwitchClauses
+            | ParsingContext::TypeMembers
+            | ParsingContext::ClassMembers
+            | ParsingContext::EnumMembers
+            | ParsingContext::ObjectLiteralMembers
+            | ParsingContext::ObjectBindingElements
+            | ParsingContext::ImportOrExportSpecifiers
+            | ParsingContext::ImportAttributes => self.token == SyntaxKind::CloseBraceToken,
+            ParsingContext::SwitchClauseStatements => matches!(
+                self.token,
+                SyntaxKind::CloseBraceToken | SyntaxKind::CaseKeyword | SyntaxKind::DefaultKeyword
+            ),
+            ParsingContext::HeritageClauseElement => matches!(
+                self.token,
+                SyntaxKind::OpenBraceToken
+                    | SyntaxKind::ExtendsKeyword
+                    | SyntaxKind::ImplementsKeyword
+            ),
+            ParsingContext::VariableDeclarations => self.is_variable_declarator_list_terminator(),
+            ParsingContext::TypeParameters => {
+                // Tokens other than '>' are here for better error recovery
+                matches!(
+                    self.token,
+                    SyntaxKind::GreaterThanToken
+                        | SyntaxKind::OpenParenToken
+                        | SyntaxKind::OpenBraceToken
+                        | SyntaxKind::ExtendsKeyword
+                        | SyntaxKind::ImplementsKeyword
+                )
+            }
+            ParsingContext::ArgumentExpressions => {
+                // Tokens other than ')' are here for better error recovery
+                self.token == SyntaxKind::CloseParenToken
+                    || self.token == SyntaxKind::SemicolonToken
+            }
+            ParsingContext::ArrayLiteralMembers
+            | ParsingContext::TupleElementTypes
+            | ParsingContext::ArrayBindingElements => self.token == SyntaxKind::CloseBracketToken,
+            ParsingContext::Parameters | ParsingContext::RestProperties => {
+                // Tokens other than ')' and ']' (the latter for index signatures) are here for better error recovery
+                self.token == SyntaxKind::CloseParenToken
+                    || self.token == SyntaxKind::CloseBracketToken
+            }
+            ParsingContext::TypeArguments => {
+                // All other tokens should cause the type-argument to terminate except comma token
+                self.token != SyntaxKind::CommaToken
+            }
+            ParsingContext::HeritageClauses => {
+                self.token == SyntaxKind::OpenBraceToken
+                    || self.token == SyntaxKind::CloseBraceToken
+            }
+            ParsingContext::JsxAttributes => {
+                self.token == SyntaxKind::GreaterThanToken || self.token == SyntaxKind::SlashToken
+            }
+            ParsingContext::JsxChildren => {
+                self.token == SyntaxKind::LessThanToken
+                    && self.look_ahead(Self::next_token_is_slash)
+            }
+            ParsingContext::SourceElements => false,
+        }
+    }
+
+    fn is_variable_declarator_list_terminator(&mut self) -> bool {
+        // If we can consume a semicolon (either explicitly, or with ASI), then consider us done
+        // with parsing the list of variable declarators.
+        if self.can_parse_semicolon() {
+            return true;
+        }
+        // in the case where we're parsing the variable declarator of a 'for-in' statement, we
+        // are done if we see an 'in' keyword in front of us. Same with for-of
+        if is_in_or_of_keyword(self.token) {
+            return true;
+        }
+        // ERROR RECOVERY TWEAK:
+        // For better error recovery, if we see an '=>' then we just stop immediately.  We've got an
+        // arrow function here and it's going to be very unlikely that we'll resynchronize and get
+        // another variable declaration.
+        self.token == SyntaxKind::EqualsGreaterThanToken
+    }
+
+    /// True if positioned at element or terminator of the current list or any enclosing list
+    fn is_in_some_parsing_context(&mut self) -> bool {
+        // We should be in at least one parsing context, be it SourceElements while parsing
+        // a SourceFile, or JSDocComment when lazily parsing JSDoc.
+        debug_assert!(self.parsing_context != 0, "Missing parsing context");
+        for context in ParsingContext::ALL {
+            if self.parsing_context & context.bit() != 0
+                && (self.is_list_element(context, true) || self.is_list_terminator(context))
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn abort_parsing_list_or_move_to_next_token(&mut self, context: ParsingContext) -> bool {
+        self.parsing_context_error(context);
+        if self.is_in_some_parsing_context() {
+            return true;
+        }
+        self.next_token();
+        false
+    }
+
+    fn parsing_context_error(&mut self, context: ParsingContext) {
+        let token_is_keyword = self.token.is_keyword();
+        let text = self.scanner.token_text().to_string();
+        match context {
+            ParsingContext::SourceElements | ParsingContext::BlockStatements => {
+                if self.token == SyntaxKind::DefaultKeyword {
+                    self.parse_error_at_current_token(
+                        diagnostics::D_0_EXPECTED_1005,
+                        &[token_text(SyntaxKind::ExportKeyword)],
+                    )
+                } else {
+                    self.parse_error_at_current_token(diagnostics::DECLARATION_OR_STATEMENT_EXPECTED_1128, &[])
+                }
+            }
+            ParsingContext::SwitchClauses => {
+                self.parse_error_at_current_token(diagnostics::CASE_OR_DEFAULT_EXPECTED_1130, &[])
+            }
+            ParsingContext::SwitchClauseStatements => {
+                self.parse_error_at_current_token(diagnostics::STATEMENT_EXPECTED_1129, &[])
+            }
+            ParsingContext::RestProperties | ParsingContext::TypeMembers => {
+                self.parse_error_at_current_token(diagnostics::PROPERTY_OR_SIGNATURE_EXPECTED_1131, &[])
+            }
+            ParsingContext::ClassMembers => self.parse_error_at_current_token(
+                diagnostics::UNEXPECTED_TOKEN_A_CONSTRUCTOR_METHOD_ACCESSOR_OR_PROPERTY_WAS_EXPECTED_1068,
+                &[],
+            ),
+            ParsingContext::EnumMembers => {
+                self.parse_error_at_current_token(diagnostics::ENUM_MEMBER_EXPECTED_1132, &[])
+            }
+            ParsingContext::HeritageClauseElement => {
+                self.parse_error_at_current_token(diagnostics::EXPRESSION_EXPECTED_1109, &[])
+            }
+            ParsingContext::VariableDeclarations => {
+                if token_is_keyword {
+                    self.parse_error_at_current_token(
+                        diagnostics::D_0_IS_NOT_ALLOWED_AS_A_VARIABLE_DECLARATION_NAME_1389,
+                        &[text],
+                    )
+                } else {
+                    self.parse_error_at_current_token(diagnostics::VARIABLE_DECLARATION_EXPECTED_1134, &[])
+                }
+            }
+            ParsingContext::ObjectBindingElements => self.parse_error_at_current_token(
+                diagnostics::PROPERTY_DESTRUCTURING_PATTERN_EXPECTED_1180,
+                &[],
+            ),
+            ParsingContext::ArrayBindingElements => self.parse_error_at_current_token(
+                diagnostics::ARRAY_ELEMENT_DESTRUCTURING_PATTERN_EXPECTED_1181,
+                &[],
+            ),
+            ParsingContext::ArgumentExpressions => {
+                self.parse_error_at_current_token(diagnostics::ARGUMENT_EXPRESSION_EXPECTED_1135, &[])
+            }
+            ParsingContext::ObjectLiteralMembers => {
+                self.parse_error_at_current_token(diagnostics::PROPERTY_ASSIGNMENT_EXPECTED_1136, &[])
+            }
+            ParsingContext::ArrayLiteralMembers => {
+                self.parse_error_at_current_token(diagnostics::EXPRESSION_OR_COMMA_EXPECTED_1137, &[])
+            }
+            ParsingContext::Parameters => {
+                if token_is_keyword {
+                    self.parse_error_at_current_token(
+                        diagnostics::D_0_IS_NOT_ALLOWED_AS_A_PARAMETER_NAME_1390,
+                        &[text],
+                    )
+                } else {
+                    self.parse_error_at_current_token(diagnostics::PARAMETER_DECLARATION_EXPECTED_1138, &[])
+                }
+            }
+            ParsingContext::TypeParameters => self.parse_error_at_current_token(
+                diagnostics::TYPE_PARAMETER_DECLARATION_EXPECTED_1139,
+                &[],
+            ),
+            ParsingContext::TypeArguments => {
+                self.parse_error_at_current_token(diagnostics::TYPE_ARGUMENT_EXPECTED_1140, &[])
+            }
+            ParsingContext::TupleElementTypes => {
+                self.parse_error_at_current_token(diagnostics::TYPE_EXPECTED_1110, &[])
+            }
+            ParsingContext::HeritageClauses => {
+                self.parse_error_at_current_token(diagnostics::UNEXPECTED_TOKEN_EXPECTED_1179, &[])
+            }
+            ParsingContext::ImportOrExportSpecifiers => {
+                if self.token == SyntaxKind::FromKeyword {
+                    self.parse_error_at_current_token(
+                        diagnostics::D_0_EXPECTED_1005,
+                        &[token_text(SyntaxKind::CloseBraceToken)],
+                    )
+                } else {
+                    self.parse_error_at_current_token(diagnostics::IDENTIFIER_EXPECTED_1003, &[])
+                }
+            }
+            ParsingContext::JsxAttributes | ParsingContext::JsxChildren => {
+                self.parse_error_at_current_token(diagnostics::IDENTIFIER_EXPECTED_1003, &[])
+            }
+            ParsingContext::ImportAttributes => self.parse_error_at_current_token(
+                diagnostics::IDENTIFIER_OR_STRING_LITERAL_EXPECTED_1478,
+                &[],
+            ),
+        }
+    }
+
+    /// Parses a list of elements, such as statements or class members, until the list terminator
+    fn parse_list(
+        &mut self,
+        context: ParsingContext,
+        mut parse_element: impl FnMut(&mut Self) -> Arc<Node>,
+    ) -> NodeList {
+        let saved_parsing_context = self.parsing_context;
+        self.parsing_context |= context.bit();
+        let pos = self.node_pos();
+        let mut nodes = Vec::new();
+        while !self.is_list_terminator(context) {
+            if self.is_list_element(context, false) {
+                nodes.push(parse_element(self));
+                continue;
+            }
+            if self.abort_parsing_list_or_move_to_next_token(context) {
+                break;
+            }
+        }
+        self.parsing_context = saved_parsing_context;
+        self.create_node_list(pos, nodes)
+    }
+
+    /// Parses a comma-separated list of elements
+    fn parse_delimited_list(
+        &mut self,
+        context: ParsingContext,
+        parse_element: impl FnMut(&mut Self) -> Arc<Node>,
+    ) -> NodeList {
+        self.parse_delimited_list_worker(context, parse_element, false)
+    }
+
+    fn parse_delimited_list_worker(
+        &mut self,
+        context: ParsingContext,
+        mut parse_element: impl FnMut(&mut Self) -> Arc<Node>,
+        consider_semicolon_as_delimiter: bool,
+    ) -> NodeList {
+        let saved_parsing_context = self.parsing_context;
+        self.parsing_context |= context.bit();
+        let pos = self.node_pos();
+        let mut nodes = Vec::new();
+        let mut comma_start: Option<usize> = None;
+        loop {
+            if self.is_list_element(context, false) {
+                let start_pos = self.node_pos();
+                nodes.push(parse_element(self));
+                comma_start = Some(self.scanner.token_start());
+                if self.parse_optional(SyntaxKind::CommaToken) {
+                    // No need to check for a zero length node since we know we parsed a comma
+                    continue;
+                }
+                comma_start = None;
+                if self.is_list_terminator(context) {
+                    break;
+                }
+                // We didn't get a comma, and the list wasn't terminated, explicitly parse
+                // out a comma so we give a good error message.
+                self.parse_expected(SyntaxKind::CommaToken);
+                // If the token was a semicolon, and the caller allows that, then skip it and
+                // continue.  This ensures we get back on track and don't result in tons of
+                // parse errors.  For example, this can happen when people do things like use
+                // a semicolon to delimit object literal members.   Note: we'll have already
+                // reported an error when we called parseExpected above.
+                if consider_semicolon_as_delimiter
+                    && self.token == SyntaxKind::SemicolonToken
+                    && !self.has_preceding_line_break()
+                {
+                    self.next_token();
+                }
+                if start_pos == self.node_pos() {
+                    // What we're parsing isn't actually remotely recognizable as a element and we've consumed no tokens.
+                    // Consume a token to advance the parser in some way and avoid an infinite loop
+                    // This can happen when we're speculatively parsing parenthesized expressions which we think may be arrow functions,
+                    // or when a modifier keyword which is disallowed as a parameter name (ex: 'private') is used as a parameter name.
+                    self.next_token();
+                }
+                continue;
+            }
+            if self.is_list_terminator(context) {
+                break;
+            }
+            if self.abort_parsing_list_or_move_to_next_token(context) {
+                break;
+            }
+        }
+        self.parsing_context = saved_parsing_context;
+        let mut list = self.create_node_list(pos, nodes);
+        // Recording the trailing comma is deliberately done after the previous
+        // loop, and not just if we see a list terminator. This is because the list
+        // may have ended incorrectly, but it is still important to know if there
+        // was a trailing comma.
+        list.has_trailing_comma = comma_start.is_some();
+        list
+    }
+
+    fn parse_bracketed_list(
+        &mut self,
+        context: ParsingContext,
+        parse_element: impl FnMut(&mut Self) -> Arc<Node>,
+        open: SyntaxKind,
+        close: SyntaxKind,
+    ) -> NodeList {
+        if self.parse_expected(open) {
+            let list = self.parse_delimited_list(context, parse_element);
+            self.parse_expected(close);
+            return list;
+        }
+        self.create_missing_list()
+    }
+}
+
+/// Adds a diagnostic unless one was already reported at the same position
+fn push_diagnostic(diagnostics: &mut Vec<Diagnostic>, diagnostic: Diagnostic) {
+    // Don't report another error at the same location
+    if diagnostics
+        .last()
+        .is_none_or(|last| last.pos() != diagnostic.pos())
+    {
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// Gets the source text of a punctuation or keyword token for use in diagnostics
+fn token_text(kind: SyntaxKind) -> String {
+    scanner::token_to_string(kind)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Gets the raw text of a template literal token, without its delimiters
+fn template_raw_text(kind: SyntaxKind, token_text: &str) -> String {
+    let text = token_text
+        .strip_prefix('`')
+        .or_else(|| token_text.strip_prefix('}'))
+        .unwrap_or(token_text);
+    let text = match kind {
+        SyntaxKind::TemplateHead | SyntaxKind::TemplateMiddle => {
+            text.strip_suffix("${").unwrap_or(text)
+        }
+        _ => text.strip_suffix('`').unwrap_or(text),
+    };
+    text.to_string()
+}
+
+/// Whether a token is `in` or `of`, which end the declarations in a `for-in` or `for-of` head
+fn is_in_or_of_keyword(kind: SyntaxKind) -> bool {
+    kind == SyntaxKind::InKeyword || kind == SyntaxKind::OfKeyword
+}
+
+/// Determines if a parsed expression may appear on the left-hand side of an assignment or
+/// as the operand of a postfix operator
+fn is_left_hand_side_expression(node: &Node) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::PropertyAccessExpression
+            | SyntaxKind::ElementAccessExpression
+            | SyntaxKind::NewExpression
+            | SyntaxKind::CallExpression
+            | SyntaxKind::JsxElement
+            | SyntaxKind::JsxSelfClosingElement
+            | SyntaxKind::JsxFragment
+            | SyntaxKind::TaggedTemplateExpression
+            | SyntaxKind::ArrayLiteralExpression
+            | SyntaxKind::ParenthesizedExpression
+            | SyntaxKind::ObjectLiteralExpression
+            | SyntaxKind::ClassExpression
+            | SyntaxKind::FunctionExpression
+            | SyntaxKind::Identifier
+            | SyntaxKind::PrivateIdentifier
+            | SyntaxKind::RegularExpressionLiteral
+            | SyntaxKind::NumericLiteral
+            | SyntaxKind::BigIntLiteral
+            | SyntaxKind::StringLiteral
+            | SyntaxKind::NoSubstitutionTemplateLiteral
+            | SyntaxKind::TemplateExpression
+            | SyntaxKind::FalseKeyword
+            | SyntaxKind::NullKeyword
+            | SyntaxKind::ThisKeyword
+            | SyntaxKind::TrueKeyword
+            | SyntaxKind::SuperKeyword
+            | SyntaxKind::NonNullExpression
+            | SyntaxKind::ExpressionWithTypeArguments
+            | SyntaxKind::MetaProperty
+            | SyntaxKind::ImportKeyword
+            | SyntaxKind::MissingDeclaration
+    )
+}
+
+/// Determines if a node is a zero-width node synthesized by error recovery
+fn node_is_missing(node: &Node) -> bool {
+    node.pos() == node.end() && node.kind != SyntaxKind::EndOfFile
+}
+
+// Statements and declarations
+impl Parser {
+    fn is_start_of_statement(&mut self) -> bool {
+        match self.token {
+            SyntaxKind::AtToken
+            | SyntaxKind::SemicolonToken
+            | SyntaxKind::OpenBraceToken
+            | SyntaxKind::VarKeyword
+            | SyntaxKind::LetKeyword
+            | SyntaxKind::UsingKeyword
+            | SyntaxKind::FunctionKeyword
+            | SyntaxKind::ClassKeyword
+            | SyntaxKind::EnumKeyword
+            | SyntaxKind::IfKeyword
+            | SyntaxKind::DoKeyword
+            | SyntaxKind::WhileKeyword
+            | SyntaxKind::ForKeyword
+            | SyntaxKind::ContinueKeyword
+            | SyntaxKind::BreakKeyword
+            | SyntaxKind::ReturnKeyword
+            | SyntaxKind::WithKeyword
+            | SyntaxKind::SwitchKeyword
+            | SyntaxKind::ThrowKeyword
+            | SyntaxKind::TryKeyword
+            | SyntaxKind::DebuggerKeyword
+            // 'catch' and 'finally' do not actually indicate that the code is part of a statement,
+            // however, we say they are here so that we may gracefully parse them and error later.
+            | SyntaxKind::CatchKeyword
+            | SyntaxKind::FinallyKeyword => true,
+            SyntaxKind::ImportKeyword => {
+                self.is_start_of_declaration()
+                    || self.look_ahead(Self::next_token_is_open_paren_or_less_than_or_dot)
+            }
+            SyntaxKind::ConstKeyword | SyntaxKind::ExportKeyword => self.is_start_of_declaration(),
+            SyntaxKind::AsyncKeyword
+            | SyntaxKind::DeclareKeyword
+            | SyntaxKind::InterfaceKeyword
+            | SyntaxKind::ModuleKeyword
+            | SyntaxKind::NamespaceKeyword
+            | SyntaxKind::TypeKeyword
+            | SyntaxKind::GlobalKeyword => {
+                // When these don't start a declaration, they're an identifier in an expression statement
+                true
+            }
+            SyntaxKind::AccessorKeyword
+            | SyntaxKind::PublicKeyword
+            | SyntaxKind::PrivateKeyword
+            | SyntaxKind::ProtectedKeyword
+            | SyntaxKind::StaticKeyword
+            | SyntaxKind::ReadonlyKeyword => {
+                // When these don't start a declaration, they may be the target of an ASI
+                self.is_start_of_declaration()
+                    || !self.look_ahead(Self::next_token_is_identifier_or_keyword_on_same_line)
+            }
+            _ => self.is_start_of_expression(),
+        }
+    }
+
+    fn is_start_of_declaration(&mut self) -> bool {
+        self.look_ahead(Self::is_declaration)
+    }
+
+    fn is_declaration(&mut self) -> bool {
+        loop {
+            match self.token {
+                SyntaxKind::VarKeyword
+                | SyntaxKind::LetKeyword
+                | SyntaxKind::ConstKeyword
+                | SyntaxKind::FunctionKeyword
+                | SyntaxKind::ClassKeyword
+                | SyntaxKind::EnumKeyword => return true,
+                SyntaxKind::UsingKeyword => return self.is_using_declaration(),
+                SyntaxKind::AwaitKeyword => return self.is_await_using_declaration(),
+                // 'declare', 'module', 'namespace', 'interface'* and 'type' are all legal JavaScript identifiers;
+                // however, an identifier cannot be followed by another identifier on the same line. This is what we
+                // count on to parse out the respective declarations. For instance, we exploit this to say that
+                //
+                //    namespace n
+                //
+                // can be none other than the beginning of a namespace declaration, but need to respect that JavaScript sees
+                //
+                //    namespace
+                //    n
+                //
+                // as the identifier 'namespace' on one line followed by the identifier 'n' on another.
+                // We need to look one token ahead to see if it permissible to try parsing a declaration.
+                //
+                // *Note*: 'interface' is actually a strict mode reserved word. So while
+                //
+                //   "use strict"
+                //   interface
+                //   I {}
+                //
+                // could be legal, it would add complexity for very little gain.
+                SyntaxKind::InterfaceKeyword | SyntaxKind::TypeKeyword => {
+                    return self.next_token_is_identifier_on_same_line();
+                }
+                SyntaxKind::ModuleKeyword | SyntaxKind::NamespaceKeyword => {
+                    return self.next_token_is_identifier_or_string_literal_on_same_line();
+                }
+                SyntaxKind::AbstractKeyword
+                | SyntaxKind::AccessorKeyword
+                | SyntaxKind::AsyncKeyword
+                | SyntaxKind::DeclareKeyword
+                | SyntaxKind::PrivateKeyword
+                | SyntaxKind::ProtectedKeyword
+                | SyntaxKind::PublicKeyword
+                | SyntaxKind::ReadonlyKeyword => {
+                    let previous_token = self.token;
+                    self.next_token();
+                    // ASI takes effect for this modifier.
+                    if self.has_preceding_line_break() {
+                        return false;
+                    }
+                    if previous_token == SyntaxKind::DeclareKeyword
+                        && self.token == SyntaxKind::TypeKeyword
+                    {
+                        // If we see 'declare type', then commit to parsing a type alias. parseTypeAliasDeclaration will
+                        // report Line_break_not_permitted_here if needed.
+                        return true;
+                    }
+                    continue;
+                }
+                SyntaxKind::GlobalKeyword => {
+                    self.next_token();
+                    return matches!(
+                        self.token,
+                        SyntaxKind::OpenBraceToken
+                            | SyntaxKind::Identifier
+                            | SyntaxKind::ExportKeyword
+                    );
+                }
+                SyntaxKind::ImportKeyword => {
+                    self.next_token();
+                    return self.token == SyntaxKind::StringLiteral
+                        || self.token == SyntaxKind::AsteriskToken
+                        || self.token == SyntaxKind::OpenBraceToken
+                        || scanner::is_identifier_or_keyword(self.token);
+                }
+                SyntaxKind::ExportKeyword => {
+                    let mut current_token = self.next_token();
+                    if current_token == SyntaxKind::TypeKeyword {
+                        current_token = self.look_ahead(|p| p.next_token());
+                    }
+                    if matches!(
+                        current_token,
+                        SyntaxKind::EqualsToken
+                            | SyntaxKind::AsteriskToken
+                            | SyntaxKind::OpenBraceToken
+                            | SyntaxKind::DefaultKeyword
+                            | SyntaxKind::AsKeyword
+                            | SyntaxKind::AtToken
+                    ) {
+                        return true;
+                    }
+                    continue;
+                }
+                SyntaxKind::StaticKeyword => {
+                    self.next_token();
+                    continue;
+                }
+                _ => return false,
+            }
+        }
+    }
+
+    fn next_token_is_binding_identifier_or_start_of_destructuring(&mut self) -> bool {
+        self.next_token();
+        self.is_binding_identifier()
+            || self.token == SyntaxKind::OpenBraceToken
+            || self.token == SyntaxKind::OpenBracketToken
+    }
+
+    fn next_token_is_binding_identifier_or_start_of_destructuring_on_same_line(
+        &mut self,
+        disallow_of: bool,
+    ) -> bool {
+        self.next_token();
+        if disallow_of && self.token == SyntaxKind::OfKeyword {
+            return false;
+        }
+        (self.is_binding_identifier() || self.token == SyntaxKind::OpenBraceToken)
+            && !self.has_preceding_line_break()
+    }
+
+    fn next_token_is_using_keyword_then_binding_identifier_or_start_of_destructuring_on_same_line(
+        &mut self,
+        disallow_of: bool,
+    ) -> bool {
+        if self.next_token() == SyntaxKind::UsingKeyword {
+            return self.next_token_is_binding_identifier_or_start_of_destructuring_on_same_line(
+                disallow_of,
+            );
+        }
+        false
+    }
+
+    fn is_let_declaration(&mut self) -> bool {
+        // In ES6 'let' always starts a lexical declaration if followed by an identifier or {
+        // or [.
+        self.look_ahead(Self::next_token_is_binding_identifier_or_start_of_destructuring)
+    }
+
+    fn is_using_declaration(&mut self) -> bool {
+        // 'using' always starts a lexical declaration if followed by an identifier. We also eagerly parse
+        // |ObjectBindingPattern| so that we can report a grammar error during check. We don't parse out
+        // |ArrayBindingPattern| since it potentially conflicts with element access (i.e., `using[x]`).
+        self.look_ahead(|p| {
+            p.next_token_is_binding_identifier_or_start_of_destructuring_on_same_line(false)
+        })
+    }
+
+    fn is_await_using_declaration(&mut self) -> bool {
+        self.look_ahead(|p| {
+            p.next_token_is_using_keyword_then_binding_identifier_or_start_of_destructuring_on_same_line(false)
+        })
+    }
+
+    fn parse_statement(&mut self) -> Arc<Node> {
+        self.parse_with_jsdoc(Self::parse_statement_worker)
+    }
+
+    fn parse_statement_worker(&mut self) -> Arc<Node> {
+        let token = self.token;
+        match token {
+            SyntaxKind::SemicolonToken => return self.parse_empty_statement(),
+            SyntaxKind::OpenBraceToken => return self.parse_block(false, None),
+            SyntaxKind::VarKeyword => {
+                let pos = self.node_pos();
+                return self.parse_variable_statement(pos, None);
+            }
+            SyntaxKind::LetKeyword if self.is_let_declaration() => {
+                let pos = self.node_pos();
+                return self.parse_variable_statement(pos, None);
+            }
+            SyntaxKind::AwaitKeyword if self.is_await_using_declaration() => {
+                let pos = self.node_pos();
+                return self.parse_variable_statement(pos, None);
+            }
+            SyntaxKind::UsingKeyword if self.is_using_declaration() => {
+                let pos = self.node_pos();
+                return self.parse_variable_statement(pos, None);
+            }
+            SyntaxKind::FunctionKeyword => {
+                let pos = self.node_pos();
+                return self.parse_function_declaration(pos, None);
+            }
+            SyntaxKind::ClassKeyword => {
+                let pos = self.node_pos();
+                return self.parse_class_declaration_or_expression(
+                    pos,
+                    None,
+                    SyntaxKind::ClassDeclaration,
+                );
+            }
+            SyntaxKind::IfKeyword => return self.parse_if_statement(),
+            SyntaxKind::DoKeyword => return self.parse_do_statement(),
+            SyntaxKind::WhileKeyword => return self.parse_while_statement(),
+            SyntaxKind::ForKeyword => return self.parse_for_or_for_in_or_for_of_statement(),
+            SyntaxKind::ContinueKeyword => {
+                return self.parse_break_or_continue_statement(SyntaxKind::ContinueStatement);
+            }
+            SyntaxKind::BreakKeyword => {
+                return self.parse_break_or_continue_statement(SyntaxKind::BreakStatement);
+            }
+            SyntaxKind::ReturnKeyword => return self.parse_return_statement(),
+            SyntaxKind::WithKeyword => return self.parse_with_statement(),
+            SyntaxKind::SwitchKeyword => return self.parse_switch_statement(),
+            SyntaxKind::ThrowKeyword => return self.parse_throw_statement(),
+            // Include 'catch' and 'finally' for error recovery.
+            SyntaxKind::TryKeyword | SyntaxKind::CatchKeyword | SyntaxKind::FinallyKeyword => {
+                return self.parse_try_statement();
+            }
+            SyntaxKind::DebuggerKeyword => return self.parse_debugger_statement(),
+            SyntaxKind::AtToken => return self.parse_declaration(),
+            SyntaxKind::AsyncKeyword
+            | SyntaxKind::InterfaceKeyword
+            | SyntaxKind::TypeKeyword
+            | SyntaxKind::ModuleKeyword
+            | SyntaxKind::NamespaceKeyword
+            | SyntaxKind::DeclareKeyword
+            | SyntaxKind::ConstKeyword
+            | SyntaxKind::EnumKeyword
+            | SyntaxKind::ExportKeyword
+            | SyntaxKind::ImportKeyword
+            | SyntaxKind::PrivateKeyword
+            | SyntaxKind::ProtectedKeyword
+            | SyntaxKind::PublicKeyword
+            | SyntaxKind::AbstractKeyword
+            | SyntaxKind::AccessorKeyword
+            | SyntaxKind::StaticKeyword
+            | SyntaxKind::ReadonlyKeyword
+            | SyntaxKind::GlobalKeyword
+                if self.is_start_of_declaration() =>
+            {
+                return self.parse_declaration();
+            }
+            _ => {}
+        }
+        self.parse_expression_or_labeled_statement()
+    }
+
+    fn parse_declaration(&mut self) -> Arc<Node> {
+        // `parseListElement` attempted to get the reused node at this position,
+        // but the ambient context flag was not yet set, so the node appeared
+        // not reusable in that context.
+        let pos = self.node_pos();
+        let modifiers = self.parse_modifiers(true, false, false);
+        let is_ambient = modifiers
+            .as_ref()
+            .is_some_and(|list| list.iter().any(|m| m.kind == SyntaxKind::DeclareKeyword));
+        if is_ambient {
+            self.do_inside_of_context(NodeFlags::AMBIENT, |p| {
+                p.parse_declaration_worker(pos, modifiers)
+            })
+        } else {
+            self.parse_declaration_worker(pos, modifiers)
+        }
+    }
+
+    fn parse_declaration_worker(&mut self, pos: usize, modifiers: Option<NodeList>) -> Arc<Node> {
+        match self.token {
+            SyntaxKind::VarKeyword
+            | SyntaxKind::LetKeyword
+            | SyntaxKind::ConstKeyword
+            | SyntaxKind::UsingKeyword
+            | SyntaxKind::AwaitKeyword => self.parse_variable_statement(pos, modifiers),
+            SyntaxKind::FunctionKeyword => self.parse_function_declaration(pos, modifiers),
+            SyntaxKind::ClassKeyword => self.parse_class_declaration_or_expression(
+                pos,
+                modifiers,
+                SyntaxKind::ClassDeclaration,
+            ),
+            SyntaxKind::InterfaceKeyword => self.parse_interface_declaration(pos, modifiers),
+            SyntaxKind::TypeKeyword => self.parse_type_alias_declaration(pos, modifiers),
+            SyntaxKind::EnumKeyword => self.parse_enum_declaration(pos, modifiers),
+            SyntaxKind::GlobalKeyword
+            | SyntaxKind::ModuleKeyword
+            | SyntaxKind::NamespaceKeyword => self.parse_module_declaration(pos, modifiers),
+            SyntaxKind::ImportKeyword => {
+                self.parse_import_declaration_or_import_equals_declaration(pos, modifiers)
+            }
+            SyntaxKind::ExportKeyword => {
+                self.next_token();
+                match self.token {
+                    SyntaxKind::DefaultKeyword | SyntaxKind::EqualsToken => {
+                        self.parse_export_assignment(pos, modifiers)
+                    }
+                    SyntaxKind::AsKeyword => {
+                        self.parse_namespace_export_declaration(pos, modifiers)
+                    }
+                    _ => self.parse_export_declaration(pos, modifiers),
+                }
+            }
+            _ => {
+                // We reached this point because we encountered decorators and/or modifiers and assumed a declaration
+                // would follow. For recovery and error reporting purposes, return an incomplete declaration.
+                let start = self.scanner.token_start();
+                self.parse_error_at_range(
+                    start,
+                    start,
+                    diagnostics::DECLARATION_EXPECTED_1146,
+                    &[],
+                );
+                self.finish_node(
+                    SyntaxKind::MissingDeclaration,
+                    pos,
+                    MissingDeclaration { modifiers },
+                )
+            }
+        }
+    }
+
+    fn parse_empty_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::SemicolonToken);
+        self.finish_node(SyntaxKind::EmptyStatement, pos, EmptyStatement {})
+    }
+
+    fn parse_block(
+        &mut self,
+        ignore_missing_open_brace: bool,
+        message: Option<&Message>,
+    ) -> Arc<Node> {
+        let pos = self.node_pos();
+        let open_brace_position = self.scanner.token_start();
+        let open_brace_parsed =
+            self.parse_expected_with_diagnostic(SyntaxKind::OpenBraceToken, message, true);
+        if open_brace_parsed || ignore_missing_open_brace {
+            let multi_line = self.has_preceding_line_break();
+            let statements =
+                self.parse_list(ParsingContext::BlockStatements, Self::parse_statement);
+            self.parse_expected_matching_brackets(
+                SyntaxKind::OpenBraceToken,
+                SyntaxKind::CloseBraceToken,
+                open_brace_parsed,
+                open_brace_position,
+            );
+            return self.finish_node(
+                SyntaxKind::Block,
+                pos,
+                Block {
+                    statements,
+                    multi_line,
+                },
+            );
+        }
+        let statements = self.create_missing_list();
+        self.finish_node(
+            SyntaxKind::Block,
+            pos,
+            Block {
+                statements,
+                multi_line: false,
+            },
+        )
+    }
+
+    fn parse_function_block(
+        &mut self,
+        flags: SignatureFlags,
+        message: Option<&Message>,
+    ) -> Arc<Node> {
+        self.do_in_yield_and_await_context(flags, |p| {
+            // We may be in a [Decorator] context when parsing a function expression or
+            // arrow function. The body of the function is not in [Decorator] context.
+            p.do_outside_of_context(NodeFlags::DECORATOR_CONTEXT, |p| {
+                p.parse_block(
+                    flags.contains(SignatureFlags::IGNORE_MISSING_OPEN_BRACE),
+                    message,
+                )
+            })
+        })
+    }
+
+    fn parse_function_block_or_semicolon(
+        &mut self,
+        flags: SignatureFlags,
+        message: Option<&Message>,
+    ) -> Option<Arc<Node>> {
+        if self.token != SyntaxKind::OpenBraceToken {
+            if flags.contains(SignatureFlags::TYPE) {
+                self.parse_type_member_semicolon();
+                return None;
+            }
+            if self.can_parse_semicolon() {
+                self.parse_semicolon();
+                return None;
+            }
+        }
+        Some(self.parse_function_block(flags, message))
+    }
+
+    fn parse_variable_statement(&mut self, pos: usize, modifiers: Option<NodeList>) -> Arc<Node> {
+        let declaration_list = self.parse_variable_declaration_list(false);
+        self.parse_semicolon();
+        self.finish_node(
+            SyntaxKind::VariableStatement,
+            pos,
+            VariableStatement {
+                modifiers,
+                declaration_list,
+            },
+        )
+    }
+
+    fn parse_variable_declaration_list(&mut self, in_for_statement_initializer: bool) -> Arc<Node> {
+        let pos = self.node_pos();
+        let flags = match self.token {
+            SyntaxKind::LetKeyword => NodeFlags::LET,
+            SyntaxKind::ConstKeyword => NodeFlags::CONST,
+            SyntaxKind::UsingKeyword => NodeFlags::USING,
+            SyntaxKind::AwaitKeyword => {
+                debug_assert!(self.is_await_using_declaration());
+                self.next_token();
+                NodeFlags::AWAIT_USING
+            }
+            _ => NodeFlags::NONE,
+        };
+        self.next_token();
+
+        // The user may have written the following:
+        //
+        //    for (let of X) { }
+        //
+        // In this case, we want to parse an empty declaration list, and then parse 'of'
+        // as a keyword. The reason this is not automatic is that 'of' is a valid identifier.
+        // So we need to look ahead to determine if 'of' should be treated as a keyword in
+        // this context.
+        // The checker will then give an error that there is an empty declaration list.
+        let declarations = if self.token == SyntaxKind::OfKeyword
+            && self.look_ahead(Self::can_follow_contextual_of_keyword)
+        {
+            self.create_missing_list()
+        } else {
+            let saved = self.context_flags;
+            self.set_context_flag(in_for_statement_initializer, NodeFlags::DISALLOW_IN_CONTEXT);
+            let list = self.parse_delimited_list(ParsingContext::VariableDeclarations, |p| {
+                p.parse_variable_declaration(!in_for_statement_initializer)
+            });
+            self.context_flags = saved;
+            list
+        };
+        self.finish_node_with_flags(
+            SyntaxKind::VariableDeclarationList,
+            pos,
+            flags,
+            VariableDeclarationList { declarations },
+        )
+    }
+
+    fn can_follow_contextual_of_keyword(&mut self) -> bool {
+        self.next_token_is_identifier() && self.next_token() == SyntaxKind::CloseParenToken
+    }
+
+    fn parse_variable_declaration(&mut self, allow_exclamation: bool) -> Arc<Node> {
+        let pos = self.node_pos();
+        let name = self.parse_identifier_or_pattern(Some(
+            diagnostics::PRIVATE_IDENTIFIERS_ARE_NOT_ALLOWED_IN_VARIABLE_DECLARATIONS_18029,
+        ));
+        let exclamation_token = if allow_exclamation
+            && name.kind == SyntaxKind::Identifier
+            && self.token == SyntaxKind::ExclamationToken
+            && !self.has_preceding_line_break()
+        {
+            Some(self.parse_token_node())
+        } else {
+            None
+        };
+        let type_node = self.parse_type_annotation();
+        let initializer = if is_in_or_of_keyword(self.token) {
+            None
+        } else {
+            self.parse_initializer()
+        };
+        self.finish_node(
+            SyntaxKind::VariableDeclaration,
+            pos,
+            VariableDeclaration {
+                name,
+                exclamation_token,
+                type_node,
+                initializer,
+            },
+        )
+    }
+
+    fn is_binding_identifier_or_private_identifier_or_pattern(&self) -> bool {
+        self.token == SyntaxKind::OpenBraceToken
+            || self.token == SyntaxKind::OpenBracketToken
+            || self.token == SyntaxKind::PrivateIdentifier
+            || self.is_binding_identifier()
+    }
+
+    fn parse_identifier_or_pattern(
+        &mut self,
+        private_identifier_message: Option<&Message>,
+    ) -> Arc<Node> {
+        match self.token {
+            SyntaxKind::OpenBracketToken => self.parse_array_binding_pattern(),
+            SyntaxKind::OpenBraceToken => self.parse_object_binding_pattern(),
+            _ => match private_identifier_message {
+                Some(message) => self.parse_binding_identifier_with_diagnostic(message),
+                None => self.parse_binding_identifier(),
+            },
+        }
+    }
+
+    fn parse_array_binding_pattern(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::OpenBracketToken);
+        let elements = self.allow_in_and(|p| {
+            p.parse_delimited_list(
+                ParsingContext::ArrayBindingElements,
+                Self::parse_array_binding_element,
+            )
+        });
+        self.parse_expected(SyntaxKind::CloseBracketToken);
+        self.finish_node(
+            SyntaxKind::ArrayBindingPattern,
+            pos,
+            ArrayBindingPattern { elements },
+        )
+    }
+
+    fn parse_array_binding_element(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        if self.token == SyntaxKind::CommaToken {
+            return self.finish_node(SyntaxKind::OmittedExpression, pos, OmittedExpression {});
+        }
+        let dot_dot_dot_token = self.parse_optional_token(SyntaxKind::DotDotDotToken);
+        let name = self.parse_identifier_or_pattern(None);
+        let initializer = self.parse_initializer();
+        self.finish_node(
+            SyntaxKind::BindingElement,
+            pos,
+            BindingElement {
+                dot_dot_dot_token,
+                property_name: None,
+                name,
+                initializer,
+            },
+        )
+    }
+
+    fn parse_object_binding_pattern(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::OpenBraceToken);
+        let elements = self.allow_in_and(|p| {
+            p.parse_delimited_list(
+                ParsingContext::ObjectBindingElements,
+                Self::parse_object_binding_element,
+            )
+        });
+        self.parse_expected(SyntaxKind::CloseBraceToken);
+        self.finish_node(
+            SyntaxKind::ObjectBindingPattern,
+            pos,
+            ObjectBindingPattern { elements },
+        )
+    }
+
+    fn parse_object_binding_element(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let dot_dot_dot_token = self.parse_optional_token(SyntaxKind::DotDotDotToken);
+        let token_is_identifier = self.is_binding_identifier();
+        let mut property_name = Some(self.parse_property_name());
+        let name = if token_is_identifier && self.token != SyntaxKind::ColonToken {
+            property_name.take().unwrap()
+        } else {
+            self.parse_expected(SyntaxKind::ColonToken);
+            self.parse_identifier_or_pattern(None)
+        };
+        let initializer = self.parse_initializer();
+        self.finish_node(
+            SyntaxKind::BindingElement,
+            pos,
+            BindingElement {
+                dot_dot_dot_token,
+                property_name,
+                name,
+                initializer,
+            },
+        )
+    }
+
+    fn parse_initializer(&mut self) -> Option<Arc<Node>> {
+        if self.parse_optional(SyntaxKind::EqualsToken) {
+            Some(self.parse_assignment_expression_or_higher(true))
+        } else {
+            None
+        }
+    }
+
+    fn parse_function_declaration(&mut self, pos: usize, modifiers: Option<NodeList>) -> Arc<Node> {
+        let modifier_flags = modifier_signature_flags(modifiers.as_ref());
+        self.parse_expected(SyntaxKind::FunctionKeyword);
+        let asterisk_token = self.parse_optional_token(SyntaxKind::AsteriskToken);
+        // We don't parse the name here in await context, instead we will report a grammar error in the checker.
+        let has_default = modifiers
+            .as_ref()
+            .is_some_and(|list| list.iter().any(|m| m.kind == SyntaxKind::DefaultKeyword));
+        let name = if has_default && !self.is_binding_identifier() {
+            None
+        } else {
+            Some(self.parse_binding_identifier())
+        };
+        let signature_flags = modifier_flags
+            | if asterisk_token.is_some() {
+                SignatureFlags::YIELD
+            } else {
+                SignatureFlags::NONE
+            };
+        let type_parameters = self.parse_type_parameters();
+        let parameters = self.parse_parameters(signature_flags);
+        let type_node = self.parse_return_type(SyntaxKind::ColonToken, false);
+        let body = self.parse_function_block_or_semicolon(
+            signature_flags,
+            Some(diagnostics::OR_EXPECTED_1144),
+        );
+        self.finish_node(
+            SyntaxKind::FunctionDeclaration,
+            pos,
+            FunctionDeclaration {
+                modifiers,
+                asterisk_token,
+                name,
+                type_parameters,
+                parameters,
+                type_node,
+                body,
+            },
+        )
+    }
+
+    fn parse_class_declaration_or_expression(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+        kind: SyntaxKind,
+    ) -> Arc<Node> {
+        let saved_await_context = self.in_await_context();
+        self.parse_expected(SyntaxKind::ClassKeyword);
+        // We don't parse the name here in await context, instead we will report a grammar error in the checker.
+        let name = if self.is_binding_identifier() && !self.is_implements_clause() {
+            Some(self.parse_binding_identifier())
+        } else {
+            None
+        };
+        let type_parameters = self.parse_type_parameters();
+        if modifiers
+            .as_ref()
+            .is_some_and(|list| list.iter().any(|m| m.kind == SyntaxKind::ExportKeyword))
+        {
+            self.set_context_flag(true, NodeFlags::AWAIT_CONTEXT);
+        }
+        let heritage_clauses = self.parse_heritage_clauses();
+        let members = if self.parse_expected(SyntaxKind::OpenBraceToken) {
+            // ClassTail[Yield,Await] : (Modified) See 14.5
+            //      ClassHeritage[?Yield,?Await]opt { ClassBody[?Yield,?Await]opt }
+            let members = self.parse_list(ParsingContext::ClassMembers, Self::parse_class_element);
+            self.parse_expected(SyntaxKind::CloseBraceToken);
+            members
+        } else {
+            self.create_missing_list()
+        };
+        self.set_context_flag(saved_await_context, NodeFlags::AWAIT_CONTEXT);
+        if kind == SyntaxKind::ClassDeclaration {
+            self.finish_node(
+                kind,
+                pos,
+                ClassDeclaration {
+                    modifiers,
+                    name,
+                    type_parameters,
+                    heritage_clauses,
+                    members,
+                },
+            )
+        } else {
+            self.finish_node(
+                kind,
+                pos,
+                ClassExpression {
+                    modifiers,
+                    name,
+                    type_parameters,
+                    heritage_clauses,
+                    members,
+                },
+            )
+        }
+    }
+
+    fn is_implements_clause(&mut self) -> bool {
+        self.token == SyntaxKind::ImplementsKeyword
+            && self.look_ahead(Self::next_token_is_identifier_or_keyword)
+    }
+
+    fn is_heritage_clause(&self) -> bool {
+        self.token == SyntaxKind::ExtendsKeyword || self.token == SyntaxKind::ImplementsKeyword
+    }
+
+    fn is_heritage_clause_extends_or_implements_keyword(&mut self) -> bool {
+        if self.is_heritage_clause() {
+            return self.look_ahead(|p| {
+                p.next_token();
+                p.is_start_of_expression()
+            });
+        }
+        false
+    }
+
+    fn is_valid_heritage_clause_object_literal(&mut self) -> bool {
+        if self.next_token() == SyntaxKind::CloseBraceToken {
+            // if we see "extends {}" then only treat the {} as what we're extending (and not
+            // the class body) if we have:
+            //
+            //      extends {} {
+            //      extends {},
+            //      extends {} extends
+            //      extends {} implements
+            let next = self.next_token();
+            return matches!(
+                next,
+                SyntaxKind::CommaToken
+                    | SyntaxKind::OpenBraceToken
+                    | SyntaxKind::ExtendsKeyword
+                    | SyntaxKind::ImplementsKeyword
+            );
+        }
+        true
+    }
+
+    fn parse_heritage_clauses(&mut self) -> Option<NodeList> {
+        // ClassTail[Yield,Await] : (Modified) See 14.5
+        //      ClassHeritage[?Yield,?Await]opt { ClassBody[?Yield,?Await]opt }
+        if self.is_heritage_clause() {
+            return Some(
+                self.parse_list(ParsingContext::HeritageClauses, Self::parse_heritage_clause),
+            );
+        }
+        None
+    }
+
+    fn parse_heritage_clause(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let token = self.token;
+        debug_assert!(
+            token == SyntaxKind::ExtendsKeyword || token == SyntaxKind::ImplementsKeyword
+        );
+        self.next_token();
+        let types = self.parse_delimited_list(
+            ParsingContext::HeritageClauseElement,
+            Self::parse_expression_with_type_arguments,
+        );
+        self.finish_node(
+            SyntaxKind::HeritageClause,
+            pos,
+            HeritageClause { token, types },
+        )
+    }
+
+    fn parse_expression_with_type_arguments(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let expression = self.parse_left_hand_side_expression_or_higher();
+        if expression.kind == SyntaxKind::ExpressionWithTypeArguments {
+            return expression;
+        }
+        let type_arguments = self.try_parse_type_arguments();
+        self.finish_node(
+            SyntaxKind::ExpressionWithTypeArguments,
+            pos,
+            ExpressionWithTypeArguments {
+                expression,
+                type_arguments,
+            },
+        )
+    }
+
+    fn is_class_member_start(&mut self) -> bool {
+        let mut id_token = None;
+        if self.token == SyntaxKind::AtToken {
+            return true;
+        }
+        // Eat up all modifiers, but hold on to the last one in case it is actually an identifier.
+        while self.token.is_modifier() {
+            id_token = Some(self.token);
+            // If the idToken is a class modifier (protected, private, public, and static), it is
+            // certain that we are starting to parse class member. This allows better error recovery
+            // Example:
+            //      public foo() ...     // true
+            //      public @dec blah ... // true; we will then report an error later
+            //      export public ...    // true; we will then report an error later
+            if is_class_member_modifier(self.token) {
+                return true;
+            }
+            self.next_token();
+        }
+        if self.token == SyntaxKind::AsteriskToken {
+            return true;
+        }
+        // Try to get the first property-like token following all modifiers.
+        // This can either be an identifier or the 'get' or 'set' keywords.
+        if self.is_literal_property_name() {
+            id_token = Some(self.token);
+            self.next_token();
+        }
+        // Index signatures and computed properties are class members; we can parse.
+        if self.token == SyntaxKind::OpenBracketToken {
+            return true;
+        }
+        // If we were able to get any potential identifier...
+        if let Some(id_token) = id_token {
+            // If we have a non-keyword identifier, or if we have an accessor, then it's safe to parse.
+            if !id_token.is_keyword()
+                || id_token == SyntaxKind::SetKeyword
+                || id_token == SyntaxKind::GetKeyword
+            {
+                return true;
+            }
+            // If it *is* a keyword, but not an accessor, check a little farther along
+            // to see if it should actually be parsed as a class member.
+            return match self.token {
+                // Method declaration
+                SyntaxKind::OpenParenToken
+                // Generic Method declaration
+                | SyntaxKind::LessThanToken
+                // Definite assignment assertion
+                | SyntaxKind::ExclamationToken
+                // Type Annotation for declaration
+                | SyntaxKind::ColonToken
+                // Initializer for declaration
+                | SyntaxKind::EqualsToken
+                // Not valid, but permitted so that it gets caught later on.
+                | SyntaxKind::QuestionToken => true,
+                // Covers
+                //  - Semicolons     (declaration termination)
+                //  - Closing braces (end-of-class, must be declaration)
+                //  - End-of-files   (not valid, but permitted so that it gets caught later on)
+                //  - Line-breaks    (enabling *automatic semicolon insertion*)
+                _ => self.can_parse_semicolon(),
+            };
+        }
+        false
+    }
+
+    fn parse_class_element(&mut self) -> Arc<Node> {
+        self.parse_with_jsdoc(Self::parse_class_element_worker)
+    }
+
+    fn parse_class_element_worker(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        if self.token == SyntaxKind::SemicolonToken {
+            self.next_token();
+            return self.finish_node(
+                SyntaxKind::SemicolonClassElement,
+                pos,
+                SemicolonClassElement {},
+            );
+        }
+        let modifiers = self.parse_modifiers(true, true, true);
+        if self.token == SyntaxKind::StaticKeyword
+            && self.look_ahead(Self::next_token_is_open_brace)
+        {
+            return self.parse_class_static_block_declaration(pos, modifiers);
+        }
+        if self.parse_contextual_modifier(SyntaxKind::GetKeyword) {
+            return self.parse_accessor_declaration(
+                pos,
+                modifiers,
+                SyntaxKind::GetAccessor,
+                SignatureFlags::NONE,
+            );
+        }
+        if self.parse_contextual_modifier(SyntaxKind::SetKeyword) {
+            return self.parse_accessor_declaration(
+                pos,
+                modifiers,
+                SyntaxKind::SetAccessor,
+                SignatureFlags::NONE,
+            );
+        }
+        if self.token == SyntaxKind::ConstructorKeyword || self.token == SyntaxKind::StringLiteral {
+            let modifiers_for_constructor = modifiers.clone();
+            if let Some(constructor) =
+                self.try_parse_constructor_declaration(pos, modifiers_for_constructor)
+            {
+                return constructor;
+            }
+        }
+        if self.is_index_signature() {
+            return self.parse_index_signature_declaration(pos, modifiers);
+        }
+        // It is very important that we check this *after* checking indexers because
+        // the [ token can start an index signature or a computed property name
+        if scanner::is_identifier_or_keyword(self.token)
+            || matches!(
+                self.token,
+                SyntaxKind::StringLiteral
+                    | SyntaxKind::NumericLiteral
+                    | SyntaxKind::BigIntLiteral
+                    | SyntaxKind::AsteriskToken
+                    | SyntaxKind::OpenBracketToken
+                    | SyntaxKind::PrivateIdentifier
+            )
+        {
+            let is_ambient = modifiers
+                .as_ref()
+                .is_some_and(|list| list.iter().any(|m| m.kind == SyntaxKind::DeclareKeyword));
+            if is_ambient {
+                return self.do_inside_of_context(NodeFlags::AMBIENT, |p| {
+                    p.parse_property_or_method_declaration(pos, modifiers)
+                });
+            }
+            return self.parse_property_or_method_declaration(pos, modifiers);
+        }
+        // 'isClassMemberStart' should have hinted not to attempt parsing.
+        let name = self.create_missing_identifier_with_error(
+            true,
+            diagnostics::DECLARATION_EXPECTED_1146,
+            &[],
+        );
+        self.parse_property_declaration(pos, modifiers, name, None)
+    }
+
+    fn parse_class_static_block_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Arc<Node> {
+        self.parse_expected(SyntaxKind::StaticKeyword);
+        let saved = self.context_flags;
+        self.set_context_flag(false, NodeFlags::YIELD_CONTEXT);
+        self.set_context_flag(true, NodeFlags::AWAIT_CONTEXT);
+        let body = self.parse_block(false, None);
+        self.context_flags = saved;
+        self.finish_node(
+            SyntaxKind::ClassStaticBlockDeclaration,
+            pos,
+            ClassStaticBlockDeclaration { modifiers, body },
+        )
+    }
+
+    fn parse_constructor_name(&mut self) -> bool {
+        if self.token == SyntaxKind::ConstructorKeyword {
+            return self.parse_expected(SyntaxKind::ConstructorKeyword);
+        }
+        if self.token == SyntaxKind::StringLiteral
+            && self.look_ahead(|p| p.next_token() == SyntaxKind::OpenParenToken)
+        {
+            return self.try_parse_bool(|p| {
+                let literal = p.parse_literal_node();
+                literal.text() == "constructor"
+            });
+        }
+        false
+    }
+
+    fn try_parse_constructor_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Option<Arc<Node>> {
+        self.try_parse(|p| {
+            if !p.parse_constructor_name() {
+                return None;
+            }
+            let type_parameters = p.parse_type_parameters();
+            let parameters = p.parse_parameters(SignatureFlags::NONE);
+            let type_node = p.parse_return_type(SyntaxKind::ColonToken, false);
+            let body = p.parse_function_block_or_semicolon(
+                SignatureFlags::NONE,
+                Some(diagnostics::OR_EXPECTED_1144),
+            );
+            Some(p.finish_node(
+                SyntaxKind::Constructor,
+                pos,
+                ConstructorDeclaration {
+                    modifiers,
+                    type_parameters,
+                    parameters,
+                    type_node,
+                    body,
+                },
+            ))
+        })
+    }
+
+    fn parse_accessor_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+        kind: SyntaxKind,
+        flags: SignatureFlags,
+    ) -> Arc<Node> {
+        let name = self.parse_property_name();
+        let type_parameters = self.parse_type_parameters();
+        let parameters = self.parse_parameters(SignatureFlags::NONE);
+        let type_node = self.parse_return_type(SyntaxKind::ColonToken, false);
+        let body = self.parse_function_block_or_semicolon(flags, None);
+        if kind == SyntaxKind::GetAccessor {
+            self.finish_node(
+                kind,
+                pos,
+                GetAccessorDeclaration {
+                    modifiers,
+                    name,
+                    type_parameters,
+                    parameters,
+                    type_node,
+                    body,
+                },
+            )
+        } else {
+            self.finish_node(
+                kind,
+                pos,
+                SetAccessorDeclaration {
+                    modifiers,
+                    name,
+                    type_parameters,
+                    parameters,
+                    type_node,
+                    body,
+                },
+            )
+        }
+    }
+
+    fn is_index_signature(&mut self) -> bool {
+        self.token == SyntaxKind::OpenBracketToken
+            && self.look_ahead(Self::is_unambiguously_index_signature)
+    }
+
+    fn is_unambiguously_index_signature(&mut self) -> bool {
+        // The only allowed sequence is:
+        //
+        //   [id:
+        //
+        // However, for error recovery, we also check the following cases:
+        //
+        //   [...
+        //   [id,
+        //   [id?,
+        //   [id?:
+        //   [id?]
+        //   [public id
+        //   [private id
+        //   [protected id
+        //   []
+        //
+        self.next_token();
+        if self.token == SyntaxKind::DotDotDotToken || self.token == SyntaxKind::CloseBracketToken {
+            return true;
+        }
+        if self.token.is_modifier() {
+            self.next_token();
+            if self.is_identifier() {
+                return true;
+            }
+        } else if !self.is_identifier() {
+            return false;
+        } else {
+            // Skip the identifier
+            self.next_token();
+        }
+        // A colon signifies a well formed indexer
+        // A comma should be a badly formed indexer because comma expressions are not allowed
+        // in computed properties.
+        if self.token == SyntaxKind::ColonToken || self.token == SyntaxKind::CommaToken {
+            return true;
+        }
+        // Question mark could be an indexer with an optional property,
+        // or it could be a conditional expression in a computed property.
+        if self.token != SyntaxKind::QuestionToken {
+            return false;
+        }
+        // If any of the following tokens are after the question mark, it cannot
+        // be a conditional expression, so treat it as an indexer.
+        self.next_token();
+        matches!(
+            self.token,
+            SyntaxKind::ColonToken | SyntaxKind::CommaToken | SyntaxKind::CloseBracketToken
+        )
+    }
+
+    fn parse_index_signature_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Arc<Node> {
+        let parameters = self.parse_bracketed_list(
+            ParsingContext::Parameters,
+            |p| {
+                let in_await_context = p.in_await_context();
+                p.parse_parameter(in_await_context)
+            },
+            SyntaxKind::OpenBracketToken,
+            SyntaxKind::CloseBracketToken,
+        );
+        let type_node = self.parse_type_annotation();
+        self.parse_type_member_semicolon();
+        self.finish_node(
+            SyntaxKind::IndexSignature,
+            pos,
+            IndexSignatureDeclaration {
+                modifiers,
+                parameters,
+                type_node,
+            },
+        )
+    }
+
+    fn parse_property_or_method_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Arc<Node> {
+        let asterisk_token = self.parse_optional_token(SyntaxKind::AsteriskToken);
+        let name = self.parse_property_name();
+        // Note: this is not legal as per the grammar.  But we allow it in the parser and
+        // report an error in the grammar checker.
+        let question_token = self.parse_optional_token(SyntaxKind::QuestionToken);
+        if asterisk_token.is_some()
+            || self.token == SyntaxKind::OpenParenToken
+            || self.token == SyntaxKind::LessThanToken
+        {
+            return self.parse_method_declaration(
+                pos,
+                modifiers,
+                asterisk_token,
+                name,
+                question_token,
+                Some(diagnostics::OR_EXPECTED_1144),
+            );
+        }
+        self.parse_property_declaration(pos, modifiers, name, question_token)
+    }
+
+    fn parse_method_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+        asterisk_token: Option<Arc<Node>>,
+        name: Arc<Node>,
+        postfix_token: Option<Arc<Node>>,
+        message: Option<&Message>,
+    ) -> Arc<Node> {
+        let signature_flags = modifier_signature_flags(modifiers.as_ref())
+            | if asterisk_token.is_some() {
+                SignatureFlags::YIELD
+            } else {
+                SignatureFlags::NONE
+            };
+        let type_parameters = self.parse_type_parameters();
+        let parameters = self.parse_parameters(signature_flags);
+        let type_node = self.parse_return_type(SyntaxKind::ColonToken, false);
+        let body = self.parse_function_block_or_semicolon(signature_flags, message);
+        self.finish_node(
+            SyntaxKind::MethodDeclaration,
+            pos,
+            MethodDeclaration {
+                modifiers,
+                asterisk_token,
+                name,
+                postfix_token,
+                type_parameters,
+                parameters,
+                type_node,
+                body,
+            },
+        )
+    }
+
+    fn parse_property_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+        name: Arc<Node>,
+        question_token: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        let postfix_token = match question_token {
+            Some(token) => Some(token),
+            None if !self.has_preceding_line_break() => {
+                self.parse_optional_token(SyntaxKind::ExclamationToken)
+            }
+            None => None,
+        };
+        let type_node = self.parse_type_annotation();
+        let initializer = self.do_outside_of_context(
+            NodeFlags::YIELD_CONTEXT | NodeFlags::AWAIT_CONTEXT | NodeFlags::DISALLOW_IN_CONTEXT,
+            Self::parse_initializer,
+        );
+        self.parse_semicolon();
+        self.finish_node(
+            SyntaxKind::PropertyDeclaration,
+            pos,
+            PropertyDeclaration {
+                modifiers,
+                name,
+                postfix_token,
+                type_node,
+                initializer,
+            },
+        )
+    }
+
+    fn parse_interface_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Arc<Node> {
+        self.parse_expected(SyntaxKind::InterfaceKeyword);
+        let name = self.parse_identifier();
+        let type_parameters = self.parse_type_parameters();
+        let heritage_clauses = self.parse_heritage_clauses();
+        let members = self.parse_object_type_members();
+        self.finish_node(
+            SyntaxKind::InterfaceDeclaration,
+            pos,
+            InterfaceDeclaration {
+                modifiers,
+                name,
+                type_parameters,
+                heritage_clauses,
+                members,
+            },
+        )
+    }
+
+    fn parse_type_alias_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Arc<Node> {
+        self.parse_expected(SyntaxKind::TypeKeyword);
+        if self.has_preceding_line_break() {
+            self.parse_error_at_current_token(diagnostics::LINE_BREAK_NOT_PERMITTED_HERE_1142, &[]);
+        }
+        let name = self.parse_identifier();
+        let type_parameters = self.parse_type_parameters();
+        self.parse_expected(SyntaxKind::EqualsToken);
+        let type_node = if self.token == SyntaxKind::IntrinsicKeyword
+            && self.look_ahead(|p| p.next_token() != SyntaxKind::DotToken)
+        {
+            self.parse_token_node()
+        } else {
+            self.parse_type()
+        };
+        self.parse_semicolon();
+        self.finish_node(
+            SyntaxKind::TypeAliasDeclaration,
+            pos,
+            TypeAliasDeclaration {
+                modifiers,
+                name,
+                type_parameters,
+                type_node,
+            },
+        )
+    }
+
+    fn parse_enum_declaration(&mut self, pos: usize, modifiers: Option<NodeList>) -> Arc<Node> {
+        self.parse_expected(SyntaxKind::EnumKeyword);
+        let name = self.parse_identifier();
+        let members = if self.parse_expected(SyntaxKind::OpenBraceToken) {
+            let members = self
+                .do_outside_of_context(NodeFlags::YIELD_CONTEXT | NodeFlags::AWAIT_CONTEXT, |p| {
+                    p.parse_delimited_list(ParsingContext::EnumMembers, Self::parse_enum_member)
+                });
+            self.parse_expected(SyntaxKind::CloseBraceToken);
+            members
+        } else {
+            self.create_missing_list()
+        };
+        self.finish_node(
+            SyntaxKind::EnumDeclaration,
+            pos,
+            EnumDeclaration {
+                modifiers,
+                name,
+                members,
+            },
+        )
+    }
+
+    fn parse_enum_member(&mut self) -> Arc<Node> {
+        self.parse_with_jsdoc(Self::parse_enum_member_worker)
+    }
+
+    fn parse_enum_member_worker(&mut self) -> Arc<Node> {
+        // In an ambient declaration, the grammar only allows integer literals as initializers.
+        // In a non-ambient declaration, the grammar allows uninitialized members only in a
+        // ConstantEnumMemberSection, which starts at the beginning of an enum declaration
+        // or any time an integer literal initializer is encountered.
+        let pos = self.node_pos();
+        let name = self.parse_property_name();
+        let initializer = self.allow_in_and(Self::parse_initializer);
+        self.finish_node(
+            SyntaxKind::EnumMember,
+            pos,
+            EnumMember { name, initializer },
+        )
+    }
+
+    fn parse_module_declaration(&mut self, pos: usize, modifiers: Option<NodeList>) -> Arc<Node> {
+        let keyword = self.token;
+        if self.token == SyntaxKind::GlobalKeyword {
+            // global augmentation
+            return self.parse_ambient_external_module_declaration(pos, modifiers);
+        }
+        if self.parse_optional(SyntaxKind::NamespaceKeyword) {
+            return self.parse_module_or_namespace_declaration(pos, modifiers, keyword);
+        }
+        self.parse_expected(SyntaxKind::ModuleKeyword);
+        if self.token == SyntaxKind::StringLiteral {
+            return self.parse_ambient_external_module_declaration(pos, modifiers);
+        }
+        self.parse_module_or_namespace_declaration(pos, modifiers, keyword)
+    }
+
+    fn parse_module_or_namespace_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+        keyword: SyntaxKind,
+    ) -> Arc<Node> {
+        let name = self.parse_identifier();
+        let body = if self.parse_optional(SyntaxKind::DotToken) {
+            let nested_pos = self.node_pos();
+            self.parse_module_or_namespace_declaration(nested_pos, None, keyword)
+        } else {
+            self.parse_module_block()
+        };
+        self.finish_node(
+            SyntaxKind::ModuleDeclaration,
+            pos,
+            ModuleDeclaration {
+                modifiers,
+                keyword,
+                name,
+                body: Some(body),
+            },
+        )
+    }
+
+    fn parse_ambient_external_module_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Arc<Node> {
+        let keyword;
+        let name = if self.token == SyntaxKind::GlobalKeyword {
+            keyword = SyntaxKind::GlobalKeyword;
+            // parse 'global' as name of global scope augmentation
+            self.parse_identifier()
+        } else {
+            keyword = SyntaxKind::ModuleKeyword;
+            self.parse_literal_node()
+        };
+        let body = if self.token == SyntaxKind::OpenBraceToken {
+            Some(self.parse_module_block())
+        } else {
+            self.parse_semicolon();
+            None
+        };
+        self.finish_node(
+            SyntaxKind::ModuleDeclaration,
+            pos,
+            ModuleDeclaration {
+                modifiers,
+                keyword,
+                name,
+                body,
+            },
+        )
+    }
+
+    fn parse_module_block(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let statements = if self.parse_expected(SyntaxKind::OpenBraceToken) {
+            let statements =
+                self.parse_list(ParsingContext::BlockStatements, Self::parse_statement);
+            self.parse_expected(SyntaxKind::CloseBraceToken);
+            statements
+        } else {
+            self.create_missing_list()
+        };
+        self.finish_node(SyntaxKind::ModuleBlock, pos, ModuleBlock { statements })
+    }
+
+    fn parse_import_declaration_or_import_equals_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Arc<Node> {
+        self.parse_expected(SyntaxKind::ImportKeyword);
+        let after_import_pos = self.node_pos();
+        // We don't parse the identifier here in await context, instead we will report a grammar error in the checker.
+        let mut identifier = if self.is_identifier() {
+            Some(self.parse_identifier())
+        } else {
+            None
+        };
+        let mut is_type_only = false;
+        if identifier.as_ref().is_some_and(|id| id.text() == "type")
+            && (self.token != SyntaxKind::FromKeyword
+                || (self.is_identifier()
+                    && self.look_ahead(Self::next_token_is_from_keyword_or_equals_token)))
+            && (self.is_identifier()
+                || self.token_after_import_definitely_produces_import_declaration())
+        {
+            is_type_only = true;
+            identifier = if self.is_identifier() {
+                Some(self.parse_identifier())
+            } else {
+                None
+            };
+        }
+        if let Some(identifier) = &identifier
+            && !self.token_after_import_definitely_produces_import_declaration()
+        {
+            return self.parse_import_equals_declaration(
+                pos,
+                modifiers,
+                identifier.clone(),
+                is_type_only,
+            );
+        }
+        let import_clause = if identifier.is_some()
+            || self.token == SyntaxKind::AsteriskToken
+            || self.token == SyntaxKind::OpenBraceToken
+        {
+            let clause = self.parse_import_clause(identifier, after_import_pos, is_type_only);
+            self.parse_expected(SyntaxKind::FromKeyword);
+            Some(clause)
+        } else {
+            None
+        };
+        let module_specifier = self.parse_module_specifier();
+        let attributes = self.try_parse_import_attributes();
+        self.parse_semicolon();
+        self.finish_node(
+            SyntaxKind::ImportDeclaration,
+            pos,
+            ImportDeclaration {
+                modifiers,
+                import_clause,
+                module_specifier,
+                attributes,
+            },
+        )
+    }
+
+    fn token_after_import_definitely_produces_import_declaration(&self) -> bool {
+        self.token == SyntaxKind::AsteriskToken || self.token == SyntaxKind::OpenBraceToken
+    }
+
+    fn token_after_import_definitely_produces_import_equals_declaration(&self) -> bool {
+        self.token == SyntaxKind::CommaToken || self.token == SyntaxKind::FromKeyword
+    }
+
+    fn parse_import_equals_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+        name: Arc<Node>,
+        is_type_only: bool,
+    ) -> Arc<Node> {
+        if self.token_after_import_definitely_produces_import_equals_declaration() {
+            // `import x, ...` or `import x from`: an import declaration with a default binding
+            let import_clause = self.parse_import_clause(Some(name), pos, is_type_only);
+            self.parse_expected(SyntaxKind::FromKeyword);
+            let module_specifier = self.parse_module_specifier();
+            let attributes = self.try_parse_import_attributes();
+            self.parse_semicolon();
+            return self.finish_node(
+                SyntaxKind::ImportDeclaration,
+                pos,
+                ImportDeclaration {
+                    modifiers,
+                    import_clause: Some(import_clause),
+                    module_specifier,
+                    attributes,
+                },
+            );
+        }
+        self.parse_expected(SyntaxKind::EqualsToken);
+        let module_reference = self.parse_module_reference();
+        self.parse_semicolon();
+        self.finish_node(
+            SyntaxKind::ImportEqualsDeclaration,
+            pos,
+            ImportEqualsDeclaration {
+                modifiers,
+                is_type_only,
+                name,
+                module_reference,
+            },
+        )
+    }
+
+    fn parse_module_reference(&mut self) -> Arc<Node> {
+        if self.token == SyntaxKind::RequireKeyword
+            && self.look_ahead(Self::next_token_is_open_paren)
+        {
+            return self.parse_external_module_reference();
+        }
+        self.parse_entity_name(false, None)
+    }
+
+    fn parse_external_module_reference(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::RequireKeyword);
+        self.parse_expected(SyntaxKind::OpenParenToken);
+        let expression = self.parse_module_specifier();
+        self.parse_expected(SyntaxKind::CloseParenToken);
+        self.finish_node(
+            SyntaxKind::ExternalModuleReference,
+            pos,
+            ExternalModuleReference { expression },
+        )
+    }
+
+    fn parse_module_specifier(&mut self) -> Arc<Node> {
+        if self.token == SyntaxKind::StringLiteral {
+            return self.parse_literal_node();
+        }
+        // We allow arbitrary expressions here, even though the grammar only allows string
+        // literals.  We check to ensure that it is only a string literal later in the grammar
+        // check pass.
+        self.parse_expression()
+    }
+
+    fn parse_import_clause(
+        &mut self,
+        name: Option<Arc<Node>>,
+        pos: usize,
+        is_type_only: bool,
+    ) -> Arc<Node> {
+        // ImportClause:
+        //  ImportedDefaultBinding
+        //  NameSpaceImport
+        //  NamedImports
+        //  ImportedDefaultBinding, NameSpaceImport
+        //  ImportedDefaultBinding, NamedImports
+
+        // If there was no default import or if there is comma token after default import
+        // parse namespace or named imports
+        let named_bindings = if name.is_none() || self.parse_optional(SyntaxKind::CommaToken) {
+            if self.token == SyntaxKind::AsteriskToken {
+                Some(self.parse_namespace_import())
+            } else {
+                Some(self.parse_named_imports_or_exports(SyntaxKind::NamedImports))
+            }
+        } else {
+            None
+        };
+        self.finish_node(
+            SyntaxKind::ImportClause,
+            pos,
+            ImportClause {
+                is_type_only,
+                name,
+                named_bindings,
+            },
+        )
+    }
+
+    fn parse_namespace_import(&mut self) -> Arc<Node> {
+        // NameSpaceImport:
+        //  * as ImportedBinding
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::AsteriskToken);
+        self.parse_expected(SyntaxKind::AsKeyword);
+        let name = self.parse_identifier();
+        self.finish_node(SyntaxKind::NamespaceImport, pos, NamespaceImport { name })
+    }
+
+    fn parse_named_imports_or_exports(&mut self, kind: SyntaxKind) -> Arc<Node> {
+        let pos = self.node_pos();
+        // NamedImports:
+        //  { }
+        //  { ImportsList }
+        //  { ImportsList, }
+
+        // ImportsList:
+        //  ImportSpecifier
+        //  ImportsList, ImportSpecifier
+        let specifier_kind = if kind == SyntaxKind::NamedImports {
+            SyntaxKind::ImportSpecifier
+        } else {
+            SyntaxKind::ExportSpecifier
+        };
+        let elements = self.parse_bracketed_list(
+            ParsingContext::ImportOrExportSpecifiers,
+            |p| p.parse_import_or_export_specifier(specifier_kind),
+            SyntaxKind::OpenBraceToken,
+            SyntaxKind::CloseBraceToken,
+        );
+        if kind == SyntaxKind::NamedImports {
+            self.finish_node(kind, pos, NamedImports { elements })
+        } else {
+            self.finish_node(kind, pos, NamedExports { elements })
+        }
+    }
+
+    fn parse_module_export_name(&mut self) -> Arc<Node> {
+        if self.token == SyntaxKind::StringLiteral {
+            return self.parse_literal_node();
+        }
+        self.parse_identifier_name()
+    }
+
+    fn parse_import_or_export_specifier(&mut self, kind: SyntaxKind) -> Arc<Node> {
+        let pos = self.node_pos();
+        // ImportSpecifier:
+        //   BindingIdentifier
+        //   ModuleExportName as BindingIdentifier
+        // ExportSpecifier:
+        //   ModuleExportName
+        //   ModuleExportName as ModuleExportName
+        let mut check_identifier_is_keyword = self.token.is_keyword() && !self.is_identifier();
+        let mut check_identifier_start = self.scanner.token_start();
+        let mut check_identifier_end = self.scanner.token_end();
+        let mut is_type_only = false;
+        let mut property_name = None;
+        let mut can_parse_as_keyword = true;
+        let mut name = self.parse_module_export_name();
+
+        macro_rules! parse_name_with_keyword_check {
+            () => {{
+                check_identifier_is_keyword = self.token.is_keyword() && !self.is_identifier();
+                check_identifier_start = self.scanner.token_start();
+                check_identifier_end = self.scanner.token_end();
+                self.parse_module_export_name()
+            }};
+        }
+
+        if name.kind == SyntaxKind::Identifier && name.text() == "type" {
+            // If the first token of an import specifier is 'type', there are a lot of possibilities,
+            // especially if we see 'as' afterwards:
+            //
+            // import { type } from "mod";          - isTypeOnly: false,   name: type
+            // import { type as } from "mod";       - isTypeOnly: true,    name: as
+            // import { type as as } from "mod";    - isTypeOnly: false,   name: as,    propertyName: type
+            // import { type as as as } from "mod"; - isTypeOnly: true,    name: as,    propertyName: as
+            if self.token == SyntaxKind::AsKeyword {
+                // { type as ...? }
+                let first_as = self.parse_identifier_name();
+                if self.token == SyntaxKind::AsKeyword {
+                    // { type as as ...? }
+                    let second_as = self.parse_identifier_name();
+                    if scanner::is_identifier_or_keyword(self.token)
+                        || self.token == SyntaxKind::StringLiteral
+                    {
+                        // { type as as something }
+                        // { type as as "something" }
+                        is_type_only = true;
+                        property_name = Some(first_as);
+                        name = parse_name_with_keyword_check!();
+                        can_parse_as_keyword = false;
+                    } else {
+                        // { type as as }
+                        property_name = Some(name);
+                        name = second_as;
+                        can_parse_as_keyword = false;
+                    }
+                } else if scanner::is_identifier_or_keyword(self.token)
+                    || self.token == SyntaxKind::StringLiteral
+                {
+                    // { type as something }
+                    // { type as "something" }
+                    property_name = Some(name);
+                    can_parse_as_keyword = false;
+                    name = parse_name_with_keyword_check!();
+                } else {
+                    // { type as }
+                    is_type_only = true;
+                    name = first_as;
+                }
+            } else if scanner::is_identifier_or_keyword(self.token)
+                || self.token == SyntaxKind::StringLiteral
+            {
+                // { type something ...? }
+                // { type "something" ...? }
+                is_type_only = true;
+                name = parse_name_with_keyword_check!();
+            }
+        }
+
+        if can_parse_as_keyword && self.token == SyntaxKind::AsKeyword {
+            property_name = Some(name);
+            self.parse_expected(SyntaxKind::AsKeyword);
+            name = parse_name_with_keyword_check!();
+        }
+        if kind == SyntaxKind::ImportSpecifier && check_identifier_is_keyword {
+            self.parse_error_at_range(
+                check_identifier_start,
+                check_identifier_end,
+                diagnostics::IDENTIFIER_EXPECTED_1003,
+                &[],
+            );
+        }
+        if kind == SyntaxKind::ImportSpecifier {
+            self.finish_node(
+                kind,
+                pos,
+                ImportSpecifier {
+                    is_type_only,
+                    property_name,
+                    name,
+                },
+            )
+        } else {
+            self.finish_node(
+                kind,
+                pos,
+                ExportSpecifier {
+                    is_type_only,
+                    property_name,
+                    name,
+                },
+            )
+        }
+    }
+
+    fn is_import_attribute_name(&self) -> bool {
+        scanner::is_identifier_or_keyword(self.token) || self.token == SyntaxKind::StringLiteral
+    }
+
+    fn try_parse_import_attributes(&mut self) -> Option<Arc<Node>> {
+        if self.token == SyntaxKind::WithKeyword
+            || (self.token == SyntaxKind::AssertKeyword && !self.has_preceding_line_break())
+        {
+            let token = self.token;
+            return Some(self.parse_import_attributes(token));
+        }
+        None
+    }
+
+    fn parse_import_attributes(&mut self, token: SyntaxKind) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(token);
+        let open_brace_position = self.scanner.token_start();
+        let mut multi_line = false;
+        let elements = if self.parse_expected(SyntaxKind::OpenBraceToken) {
+            multi_line = self.has_preceding_line_break();
+            let elements = self.parse_delimited_list_worker(
+                ParsingContext::ImportAttributes,
+                Self::parse_import_attribute,
+                true,
+            );
+            self.parse_expected_matching_brackets(
+                SyntaxKind::OpenBraceToken,
+                SyntaxKind::CloseBraceToken,
+                true,
+                open_brace_position,
+            );
+            elements
+        } else {
+            self.create_missing_list()
+        };
+        self.finish_node(
+            SyntaxKind::ImportAttributes,
+            pos,
+            ImportAttributes {
+                token,
+                elements,
+                multi_line,
+            },
+        )
+    }
+
+    fn parse_import_attribute(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let name = if scanner::is_identifier_or_keyword(self.token) {
+            self.parse_identifier_name()
+        } else {
+            self.parse_literal_node()
+        };
+        self.parse_expected(SyntaxKind::ColonToken);
+        let value = self.parse_assignment_expression_or_higher(true);
+        self.finish_node(
+            SyntaxKind::ImportAttribute,
+            pos,
+            ImportAttribute { name, value },
+        )
+    }
+
+    fn parse_export_assignment(&mut self, pos: usize, modifiers: Option<NodeList>) -> Arc<Node> {
+        let saved_await_context = self.in_await_context();
+        self.set_context_flag(true, NodeFlags::AWAIT_CONTEXT);
+        let is_export_equals = if self.parse_optional(SyntaxKind::EqualsToken) {
+            true
+        } else {
+            self.parse_expected(SyntaxKind::DefaultKeyword);
+            false
+        };
+        let expression = self.parse_assignment_expression_or_higher(true);
+        self.parse_semicolon();
+        self.set_context_flag(saved_await_context, NodeFlags::AWAIT_CONTEXT);
+        self.finish_node(
+            SyntaxKind::ExportAssignment,
+            pos,
+            ExportAssignment {
+                modifiers,
+                is_export_equals,
+                expression,
+            },
+        )
+    }
+
+    fn parse_namespace_export_declaration(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Arc<Node> {
+        self.parse_expected(SyntaxKind::AsKeyword);
+        self.parse_expected(SyntaxKind::NamespaceKeyword);
+        let name = self.parse_identifier();
+        self.parse_semicolon();
+        self.finish_node(
+            SyntaxKind::NamespaceExportDeclaration,
+            pos,
+            NamespaceExportDeclaration { modifiers, name },
+        )
+    }
+
+    fn parse_export_declaration(&mut self, pos: usize, modifiers: Option<NodeList>) -> Arc<Node> {
+        let saved_await_context = self.in_await_context();
+        self.set_context_flag(true, NodeFlags::AWAIT_CONTEXT);
+        let mut export_clause = None;
+        let mut module_specifier = None;
+        let mut attributes = None;
+        let is_type_only = self.parse_optional(SyntaxKind::TypeKeyword);
+        let namespace_export_pos = self.node_pos();
+        if self.parse_optional(SyntaxKind::AsteriskToken) {
+            if self.parse_optional(SyntaxKind::AsKeyword) {
+                let name = self.parse_module_export_name();
+                export_clause = Some(self.finish_node(
+                    SyntaxKind::NamespaceExport,
+                    namespace_export_pos,
+                    NamespaceExport { name },
+                ));
+            }
+            self.parse_expected(SyntaxKind::FromKeyword);
+            module_specifier = Some(self.parse_module_specifier());
+        } else {
+            export_clause = Some(self.parse_named_imports_or_exports(SyntaxKind::NamedExports));
+            // It is not uncommon to accidentally omit the 'from' keyword. Additionally, in editing scenarios,
+            // the 'from' keyword can be parsed as a named export when the export clause is unterminated (i.e. `export { from "moduleName";`)
+            // If we don't have a 'from' keyword, see if we have a string literal such that ASI won't take effect.
+            if self.token == SyntaxKind::FromKeyword
+                || (self.token == SyntaxKind::StringLiteral && !self.has_preceding_line_break())
+            {
+                self.parse_expected(SyntaxKind::FromKeyword);
+                module_specifier = Some(self.parse_module_specifier());
+            }
+        }
+        if module_specifier.is_some() {
+            attributes = self.try_parse_import_attributes();
+        }
+        self.parse_semicolon();
+        self.set_context_flag(saved_await_context, NodeFlags::AWAIT_CONTEXT);
+        self.finish_node(
+            SyntaxKind::ExportDeclaration,
+            pos,
+            ExportDeclaration {
+                modifiers,
+                is_type_only,
+                export_clause,
+                module_specifier,
+                attributes,
+            },
+        )
+    }
+
+    fn parse_if_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::IfKeyword);
+        let expression = self.parse_parenthesized_condition();
+        let then_statement = self.parse_statement();
+        let else_statement = if self.parse_optional(SyntaxKind::ElseKeyword) {
+            Some(self.parse_statement())
+        } else {
+            None
+        };
+        self.finish_node(
+            SyntaxKind::IfStatement,
+            pos,
+            IfStatement {
+                expression,
+                then_statement,
+                else_statement,
+            },
+        )
+    }
+
+    fn parse_parenthesized_condition(&mut self) -> Arc<Node> {
+        let open_paren_position = self.scanner.token_start();
+        let open_paren_parsed = self.parse_expected(SyntaxKind::OpenParenToken);
+        let expression = self.allow_in_and(Self::parse_expression);
+        self.parse_expected_matching_brackets(
+            SyntaxKind::OpenParenToken,
+            SyntaxKind::CloseParenToken,
+            open_paren_parsed,
+            open_paren_position,
+        );
+        expression
+    }
+
+    fn parse_do_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::DoKeyword);
+        let statement = self.parse_statement();
+        self.parse_expected(SyntaxKind::WhileKeyword);
+        let expression = self.parse_parenthesized_condition();
+        // From: https://mail.mozilla.org/pipermail/es-discuss/2011-August/016188.html
+        // 157 min --- All allen at wirfs-brock.com CONF --- "do{;}while(false)false" prohibited in
+        // spec but allowed in consensus reality. Approved -- this is the de-facto standard whereby
+        //  do;while(0)x will have a semicolon inserted before x.
+        self.parse_optional(SyntaxKind::SemicolonToken);
+        self.finish_node(
+            SyntaxKind::DoStatement,
+            pos,
+            DoStatement {
+                statement,
+                expression,
+            },
+        )
+    }
+
+    fn parse_while_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::WhileKeyword);
+        let expression = self.parse_parenthesized_condition();
+        let statement = self.parse_statement();
+        self.finish_node(
+            SyntaxKind::WhileStatement,
+            pos,
+            WhileStatement {
+                expression,
+                statement,
+            },
+        )
+    }
+
+    fn parse_for_or_for_in_or_for_of_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::ForKeyword);
+        let await_modifier = self.parse_optional_token(SyntaxKind::AwaitKeyword);
+        self.parse_expected(SyntaxKind::OpenParenToken);
+        let mut initializer = None;
+        if self.token != SyntaxKind::SemicolonToken {
+            if self.token == SyntaxKind::VarKeyword
+                || self.token == SyntaxKind::LetKeyword
+                || self.token == SyntaxKind::ConstKeyword
+                || (self.token == SyntaxKind::UsingKeyword
+                    && self.look_ahead(|p| {
+                        p.next_token_is_binding_identifier_or_start_of_destructuring_on_same_line(true)
+                    }))
+                // this one is meant to allow of
+                || (self.token == SyntaxKind::AwaitKeyword
+                    && self.look_ahead(|p| {
+                        p.next_token_is_using_keyword_then_binding_identifier_or_start_of_destructuring_on_same_line(true)
+                    }))
+            {
+                initializer = Some(self.parse_variable_declaration_list(true));
+            } else {
+                initializer = Some(self.disallow_in_and(Self::parse_expression));
+            }
+        }
+
+        let is_of = if await_modifier.is_some() {
+            self.parse_expected(SyntaxKind::OfKeyword)
+        } else {
+            self.parse_optional(SyntaxKind::OfKeyword)
+        };
+        if is_of {
+            let expression = self.allow_in_and(|p| p.parse_assignment_expression_or_higher(true));
+            self.parse_expected(SyntaxKind::CloseParenToken);
+            let statement = self.parse_statement();
+            let initializer = initializer.unwrap_or_else(|| self.create_missing_identifier());
+            return self.finish_node(
+                SyntaxKind::ForOfStatement,
+                pos,
+                ForOfStatement {
+                    await_modifier,
+                    initializer,
+                    expression,
+                    statement,
+                },
+            );
+        }
+        if self.parse_optional(SyntaxKind::InKeyword) {
+            let expression = self.allow_in_and(Self::parse_expression);
+            self.parse_expected(SyntaxKind::CloseParenToken);
+            let statement = self.parse_statement();
+            let initializer = initializer.unwrap_or_else(|| self.create_missing_identifier());
+            return self.finish_node(
+                SyntaxKind::ForInStatement,
+                pos,
+                ForInStatement {
+                    initializer,
+                    expression,
+                    statement,
+                },
+            );
+        }
+        self.parse_expected(SyntaxKind::SemicolonToken);
+        let condition = if self.token != SyntaxKind::SemicolonToken
+            && self.token != SyntaxKind::CloseParenToken
+        {
+            Some(self.allow_in_and(Self::parse_expression))
+        } else {
+            None
+        };
+        self.parse_expected(SyntaxKind::SemicolonToken);
+        let incrementor = if self.token != SyntaxKind::CloseParenToken {
+            Some(self.allow_in_and(Self::parse_expression))
+        } else {
+            None
+        };
+        self.parse_expected(SyntaxKind::CloseParenToken);
+        let statement = self.parse_statement();
+        self.finish_node(
+            SyntaxKind::ForStatement,
+            pos,
+            ForStatement {
+                initializer,
+                condition,
+                incrementor,
+                statement,
+            },
+        )
+    }
+
+    fn parse_break_or_continue_statement(&mut self, kind: SyntaxKind) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(if kind == SyntaxKind::BreakStatement {
+            SyntaxKind::BreakKeyword
+        } else {
+            SyntaxKind::ContinueKeyword
+        });
+        let label = if self.can_parse_semicolon() {
+            None
+        } else {
+            Some(self.parse_identifier())
+        };
+        self.parse_semicolon();
+        if kind == SyntaxKind::BreakStatement {
+            self.finish_node(kind, pos, BreakStatement { label })
+        } else {
+            self.finish_node(kind, pos, ContinueStatement { label })
+        }
+    }
+
+    fn parse_return_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::ReturnKeyword);
+        let expression = if self.can_parse_semicolon() {
+            None
+        } else {
+            Some(self.allow_in_and(Self::parse_expression))
+        };
+        self.parse_semicolon();
+        self.finish_node(
+            SyntaxKind::ReturnStatement,
+            pos,
+            ReturnStatement { expression },
+        )
+    }
+
+    fn parse_with_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::WithKeyword);
+        let expression = self.parse_parenthesized_condition();
+        let statement =
+            self.do_inside_of_context(NodeFlags::IN_WITH_STATEMENT, Self::parse_statement);
+        self.finish_node(
+            SyntaxKind::WithStatement,
+            pos,
+            WithStatement {
+                expression,
+                statement,
+            },
+        )
+    }
+
+    fn parse_switch_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::SwitchKeyword);
+        self.parse_expected(SyntaxKind::OpenParenToken);
+        let expression = self.allow_in_and(Self::parse_expression);
+        self.parse_expected(SyntaxKind::CloseParenToken);
+        let case_block = self.parse_case_block();
+        self.finish_node(
+            SyntaxKind::SwitchStatement,
+            pos,
+            SwitchStatement {
+                expression,
+                case_block,
+            },
+        )
+    }
+
+    fn parse_case_block(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::OpenBraceToken);
+        let clauses = self.parse_list(
+            ParsingContext::SwitchClauses,
+            Self::parse_case_or_default_clause,
+        );
+        self.parse_expected(SyntaxKind::CloseBraceToken);
+        self.finish_node(SyntaxKind::CaseBlock, pos, CaseBlock { clauses })
+    }
+
+    fn parse_case_or_default_clause(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        if self.parse_optional(SyntaxKind::CaseKeyword) {
+            let expression = self.allow_in_and(Self::parse_expression);
+            self.parse_expected(SyntaxKind::ColonToken);
+            let statements = self.parse_list(
+                ParsingContext::SwitchClauseStatements,
+                Self::parse_statement,
+            );
+            return self.finish_node(
+                SyntaxKind::CaseClause,
+                pos,
+                CaseClause {
+                    expression,
+                    statements,
+                },
+            );
+        }
+        self.parse_expected(SyntaxKind::DefaultKeyword);
+        self.parse_expected(SyntaxKind::ColonToken);
+        let statements = self.parse_list(
+            ParsingContext::SwitchClauseStatements,
+            Self::parse_statement,
+        );
+        self.finish_node(SyntaxKind::DefaultClause, pos, DefaultClause { statements })
+    }
+
+    fn parse_throw_statement(&mut self) -> Arc<Node> {
+        // ThrowStatement[Yield] :
+        //      throw [no LineTerminator here]Expression[In, ?Yield];
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::ThrowKeyword);
+        // Because of automatic semicolon insertion, we need to report error if this
+        // throw could be terminated with a semicolon.  Note: we can't call 'parseExpression'
+        // directly as that might consume an expression on the following line.
+        // Instead, we create a "missing" identifier, but don't report an error. The actual error
+        // will be reported in the grammar walker.
+        let expression = if self.has_preceding_line_break() {
+            self.create_missing_identifier_with_error(
+                false,
+                diagnostics::LINE_BREAK_NOT_PERMITTED_HERE_1142,
+                &[],
+            )
+        } else {
+            self.allow_in_and(Self::parse_expression)
+        };
+        self.parse_semicolon();
+        self.finish_node(
+            SyntaxKind::ThrowStatement,
+            pos,
+            ThrowStatement { expression },
+        )
+    }
+
+    fn parse_try_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::TryKeyword);
+        let try_block = self.parse_block(false, None);
+        let catch_clause = if self.token == SyntaxKind::CatchKeyword {
+            Some(self.parse_catch_clause())
+        } else {
+            None
+        };
+        // If we don't have a catch clause, then we must have a finally clause.  Try to parse
+        // one out no matter what.
+        let finally_block = if catch_clause.is_none() || self.token == SyntaxKind::FinallyKeyword {
+            self.parse_expected(SyntaxKind::FinallyKeyword);
+            Some(self.parse_block(false, None))
+        } else {
+            None
+        };
+        self.finish_node(
+            SyntaxKind::TryStatement,
+            pos,
+            TryStatement {
+                try_block,
+                catch_clause,
+                finally_block,
+            },
+        )
+    }
+
+    fn parse_catch_clause(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::CatchKeyword);
+        let variable_declaration = if self.parse_optional(SyntaxKind::OpenParenToken) {
+            let declaration = self.parse_variable_declaration(false);
+            self.parse_expected(SyntaxKind::CloseParenToken);
+            Some(declaration)
+        } else {
+            // Keep shape of node to avoid degrading performance.
+            None
+        };
+        let block = self.parse_block(false, None);
+        self.finish_node(
+            SyntaxKind::CatchClause,
+            pos,
+            CatchClause {
+                variable_declaration,
+                block,
+            },
+        )
+    }
+
+    fn parse_debugger_statement(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::DebuggerKeyword);
+        self.parse_semicolon();
+        self.finish_node(SyntaxKind::DebuggerStatement, pos, DebuggerStatement {})
+    }
+
+    fn parse_expression_or_labeled_statement(&mut self) -> Arc<Node> {
+        // Avoiding having to do the lookahead for a labeled statement by just trying to parse
+        // out an expression, seeing if it is identifier and then seeing if it is followed by
+        // a colon.
+        let pos = self.node_pos();
+        let expression = self.allow_in_and(Self::parse_expression);
+        if expression.kind == SyntaxKind::Identifier && self.parse_optional(SyntaxKind::ColonToken)
+        {
+            let statement = self.parse_statement();
+            return self.finish_node(
+                SyntaxKind::LabeledStatement,
+                pos,
+                LabeledStatement {
+                    label: expression,
+                    statement,
+                },
+            );
+        }
+        self.parse_semicolon();
+        self.finish_node(
+            SyntaxKind::ExpressionStatement,
+            pos,
+            ExpressionStatement { expression },
+        )
+    }
+
+    // Modifiers
+
+    fn parse_modifiers(
+        &mut self,
+        allow_decorators: bool,
+        permit_const_as_modifier: bool,
+        stop_on_start_of_class_static_block: bool,
+    ) -> Option<NodeList> {
+        let pos = self.node_pos();
+        let mut list = Vec::new();
+        let mut has_seen_static_modifier = false;
+        loop {
+            if allow_decorators && self.token == SyntaxKind::AtToken {
+                list.push(self.parse_decorator());
+                continue;
+            }
+            match self.try_parse_modifier(
+                has_seen_static_modifier,
+                permit_const_as_modifier,
+                stop_on_start_of_class_static_block,
+            ) {
+                Some(modifier) => {
+                    if modifier.kind == SyntaxKind::StaticKeyword {
+                        has_seen_static_modifier = true;
+                    }
+                    list.push(modifier);
+                }
+                None => break,
+            }
+        }
+        if list.is_empty() {
+            None
+        } else {
+            Some(self.create_node_list(pos, list))
+        }
+    }
+
+    fn try_parse_modifier(
+        &mut self,
+        has_seen_static_modifier: bool,
+        permit_const_as_modifier: bool,
+        stop_on_start_of_class_static_block: bool,
+    ) -> Option<Arc<Node>> {
+        let pos = self.node_pos();
+        let kind = self.token;
+        if self.token == SyntaxKind::ConstKeyword && permit_const_as_modifier {
+            // We need to ensure that any subsequent modifiers appear on the same line
+            // so that when 'const' is a standalone declaration, we don't issue an error.
+            if !self.try_parse_bool(Self::next_token_is_on_same_line_and_can_follow_modifier) {
+                return None;
+            }
+        } else if (stop_on_start_of_class_static_block
+            && self.token == SyntaxKind::StaticKeyword
+            && self.look_ahead(Self::next_token_is_open_brace))
+            || (has_seen_static_modifier && self.token == SyntaxKind::StaticKeyword)
+            || !self.parse_any_contextual_modifier()
+        {
+            return None;
+        }
+        Some(self.finish_node(kind, pos, Token {}))
+    }
+
+    fn parse_any_contextual_modifier(&mut self) -> bool {
+        self.token.is_modifier() && self.try_parse_bool(Self::next_token_can_follow_modifier)
+    }
+
+    fn parse_contextual_modifier(&mut self, kind: SyntaxKind) -> bool {
+        self.token == kind && self.try_parse_bool(Self::next_token_can_follow_modifier)
+    }
+
+    fn next_token_can_follow_modifier(&mut self) -> bool {
+        match self.token {
+            SyntaxKind::ConstKeyword => {
+                // 'const' is only a modifier if followed by 'enum'.
+                self.next_token() == SyntaxKind::EnumKeyword
+            }
+            SyntaxKind::ExportKeyword => {
+                self.next_token();
+                if self.token == SyntaxKind::DefaultKeyword {
+                    return self.look_ahead(Self::next_token_can_follow_default_keyword);
+                }
+                if self.token == SyntaxKind::TypeKeyword {
+                    return self.look_ahead(Self::next_token_can_follow_export_modifier);
+                }
+                self.can_follow_export_modifier()
+            }
+            SyntaxKind::DefaultKeyword => self.next_token_can_follow_default_keyword(),
+            SyntaxKind::StaticKeyword | SyntaxKind::GetKeyword | SyntaxKind::SetKeyword => {
+                self.next_token();
+                self.can_follow_modifier()
+            }
+            _ => self.next_token_is_on_same_line_and_can_follow_modifier(),
+        }
+    }
+
+    fn can_follow_export_modifier(&mut self) -> bool {
+        self.token == SyntaxKind::AtToken
+            || (self.token != SyntaxKind::AsteriskToken
+                && self.token != SyntaxKind::AsKeyword
+                && self.token != SyntaxKind::OpenBraceToken
+                && self.can_follow_modifier())
+    }
+
+    fn next_token_can_follow_export_modifier(&mut self) -> bool {
+        self.next_token();
+        self.can_follow_export_modifier()
+    }
+
+    fn next_token_is_on_same_line_and_can_follow_modifier(&mut self) -> bool {
+        self.next_token();
+        if self.has_preceding_line_break() {
+            return false;
+        }
+        self.can_follow_modifier()
+    }
+
+    fn can_follow_modifier(&mut self) -> bool {
+        self.token == SyntaxKind::OpenBracketToken
+            || self.token == SyntaxKind::OpenBraceToken
+            || self.token == SyntaxKind::AsteriskToken
+            || self.token == SyntaxKind::DotDotDotToken
+            || self.is_literal_property_name()
+    }
+
+    fn next_token_can_follow_default_keyword(&mut self) -> bool {
+        self.next_token();
+        self.token == SyntaxKind::ClassKeyword
+            || self.token == SyntaxKind::FunctionKeyword
+            || self.token == SyntaxKind::InterfaceKeyword
+            || self.token == SyntaxKind::AtToken
+            || (self.token == SyntaxKind::AbstractKeyword
+                && self.look_ahead(Self::next_token_is_class_keyword_on_same_line))
+            || (self.token == SyntaxKind::AsyncKeyword
+                && self.look_ahead(Self::next_token_is_function_keyword_on_same_line))
+    }
+
+    fn parse_decorator(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::AtToken);
+        let expression = self.do_inside_of_context(
+            NodeFlags::DECORATOR_CONTEXT,
+            Self::parse_decorator_expression,
+        );
+        self.finish_node(SyntaxKind::Decorator, pos, Decorator { expression })
+    }
+
+    fn parse_decorator_expression(&mut self) -> Arc<Node> {
+        if self.in_await_context() && self.token == SyntaxKind::AwaitKeyword {
+            // `@await` is is disallowed in an [Await] context, but can cause parsing to go off the rails
+            // This simply parses the missing identifier and moves on.
+            let pos = self.node_pos();
+            let await_expression =
+                self.parse_identifier_with_diagnostic(diagnostics::EXPRESSION_EXPECTED_1109);
+            self.next_token();
+            let member_expression = self.parse_member_expression_rest(pos, await_expression, true);
+            return self.parse_call_expression_rest(pos, member_expression);
+        }
+        self.parse_left_hand_side_expression_or_higher()
+    }
+}
+
+fn is_class_member_modifier(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::PublicKeyword
+            | SyntaxKind::PrivateKeyword
+            | SyntaxKind::ProtectedKeyword
+            | SyntaxKind::ReadonlyKeyword
+            | SyntaxKind::OverrideKeyword
+            | SyntaxKind::StaticKeyword
+            | SyntaxKind::AccessorKeyword
+    )
+}
+
+/// Gets the signature flags implied by a declaration's modifiers
+fn modifier_signature_flags(modifiers: Option<&NodeList>) -> SignatureFlags {
+    if modifiers.is_some_and(|list| list.iter().any(|m| m.kind == SyntaxKind::AsyncKeyword)) {
+        SignatureFlags::AWAIT
+    } else {
+        SignatureFlags::NONE
+    }
+}
+
+// Types
+impl Parser {
+    fn is_start_of_type(&mut self, in_start_of_parameter: bool) -> bool {
+        match self.token {
+            SyntaxKind::AnyKeyword
+            | SyntaxKind::UnknownKeyword
+            | SyntaxKind::StringKeyword
+            | SyntaxKind::NumberKeyword
+            | SyntaxKind::BigIntKeyword
+            | SyntaxKind::BooleanKeyword
+            | SyntaxKind::ReadonlyKeyword
+            | SyntaxKind::SymbolKeyword
+            | SyntaxKind::UniqueKeyword
+            | SyntaxKind::VoidKeyword
+            | SyntaxKind::UndefinedKeyword
+            | SyntaxKind::NullKeyword
+            | SyntaxKind::ThisKeyword
+            | SyntaxKind::TypeOfKeyword
+            | SyntaxKind::NeverKeyword
+            | SyntaxKind::OpenBraceToken
+            | SyntaxKind::OpenBracketToken
+            | SyntaxKind::LessThanToken
+            | SyntaxKind::BarToken
+            | SyntaxKind::AmpersandToken
+            | SyntaxKind::NewKeyword
+            | SyntaxKind::StringLiteral
+            | SyntaxKind::NumericLiteral
+            | SyntaxKind::BigIntLiteral
+            | SyntaxKind::TrueKeyword
+            | SyntaxKind::FalseKeyword
+            | SyntaxKind::ObjectKeyword
+            | SyntaxKind::AsteriskToken
+            | SyntaxKind::QuestionToken
+            | SyntaxKind::ExclamationToken
+            | SyntaxKind::DotDotDotToken
+            | SyntaxKind::InferKeyword
+            | SyntaxKind::ImportKeyword
+            | SyntaxKind::AssertsKeyword
+            | SyntaxKind::NoSubstitutionTemplateLiteral
+            | SyntaxKind::TemplateHead => true,
+            SyntaxKind::FunctionKeyword => !in_start_of_parameter,
+            SyntaxKind::MinusToken => {
+                !in_start_of_parameter
+                    && self.look_ahead(Self::next_token_is_numeric_or_big_int_literal)
+            }
+            SyntaxKind::OpenParenToken => {
+                // Only consider '(' the start of a type if followed by ')', '...', an identifier, a modifier,
+                // or something that starts a type. We don't want to consider things like '(1)' a type.
+                !in_start_of_parameter
+                    && self.look_ahead(Self::is_start_of_parenthesized_or_function_type)
+            }
+            _ => self.is_identifier(),
+        }
+    }
+
+    fn is_start_of_parenthesized_or_function_type(&mut self) -> bool {
+        self.next_token();
+        self.token == SyntaxKind::CloseParenToken
+            || self.is_start_of_parameter_with_types(false)
+            || self.is_start_of_type(false)
+    }
+
+    fn parse_type(&mut self) -> Arc<Node> {
+        if self.in_context(NodeFlags::TYPE_EXCLUDES_FLAGS) {
+            return self.do_outside_of_context(NodeFlags::TYPE_EXCLUDES_FLAGS, Self::parse_type);
+        }
+        if self.is_start_of_function_type_or_constructor_type() {
+            return self.parse_function_or_constructor_type();
+        }
+        let pos = self.node_pos();
+        let check_type = self.parse_union_type_or_higher();
+        if !self.in_disallow_conditional_types_context()
+            && !self.has_preceding_line_break()
+            && self.parse_optional(SyntaxKind::ExtendsKeyword)
+        {
+            // The type following 'extends' is not permitted to be another conditional type
+            let extends_type = self.disallow_conditional_types_and(Self::parse_type);
+            self.parse_expected(SyntaxKind::QuestionToken);
+            let true_type = self.allow_conditional_types_and(Self::parse_type);
+            self.parse_expected(SyntaxKind::ColonToken);
+            let false_type = self.allow_conditional_types_and(Self::parse_type);
+            return self.finish_node(
+                SyntaxKind::ConditionalType,
+                pos,
+                ConditionalTypeNode {
+                    check_type,
+                    extends_type,
+                    true_type,
+                    false_type,
+                },
+            );
+        }
+        check_type
+    }
+
+    fn parse_type_annotation(&mut self) -> Option<Arc<Node>> {
+        if self.parse_optional(SyntaxKind::ColonToken) {
+            Some(self.parse_type())
+        } else {
+            None
+        }
+    }
+
+    fn parse_return_type(&mut self, return_token: SyntaxKind, is_type: bool) -> Option<Arc<Node>> {
+        if self.should_parse_return_type(return_token, is_type) {
+            return Some(self.parse_type_or_type_predicate());
+        }
+        None
+    }
+
+    fn should_parse_return_type(&mut self, return_token: SyntaxKind, is_type: bool) -> bool {
+        if return_token == SyntaxKind::EqualsGreaterThanToken {
+            self.parse_expected(return_token);
+            return true;
+        }
+        if self.parse_optional(SyntaxKind::ColonToken) {
+            return true;
+        }
+        if is_type && self.token == SyntaxKind::EqualsGreaterThanToken {
+            // This is easy to get backward, especially in type contexts, so parse the type anyway
+            self.parse_error_at_current_token(
+                diagnostics::D_0_EXPECTED_1005,
+                &[token_text(SyntaxKind::ColonToken)],
+            );
+            self.next_token();
+            return true;
+        }
+        false
+    }
+
+    fn parse_type_or_type_predicate(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let type_predicate_variable = if self.is_identifier() {
+            self.try_parse(Self::parse_type_predicate_prefix)
+        } else {
+            None
+        };
+        let type_node = self.parse_type();
+        match type_predicate_variable {
+            Some(parameter_name) => self.finish_node(
+                SyntaxKind::TypePredicate,
+                pos,
+                TypePredicateNode {
+                    asserts_modifier: None,
+                    parameter_name,
+                    type_node: Some(type_node),
+                },
+            ),
+            None => type_node,
+        }
+    }
+
+    fn parse_type_predicate_prefix(&mut self) -> Option<Arc<Node>> {
+        let id = self.parse_identifier();
+        if self.token == SyntaxKind::IsKeyword && !self.has_preceding_line_break() {
+            self.next_token();
+            return Some(id);
+        }
+        None
+    }
+
+    fn parse_asserts_type_predicate(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let asserts_modifier = Some(self.parse_expected_token(SyntaxKind::AssertsKeyword));
+        let parameter_name = if self.token == SyntaxKind::ThisKeyword {
+            self.parse_this_type_node()
+        } else {
+            self.parse_identifier()
+        };
+        let type_node = if self.parse_optional(SyntaxKind::IsKeyword) {
+            Some(self.parse_type())
+        } else {
+            None
+        };
+        self.finish_node(
+            SyntaxKind::TypePredicate,
+            pos,
+            TypePredicateNode {
+                asserts_modifier,
+                parameter_name,
+                type_node,
+            },
+        )
+    }
+
+    fn is_start_of_function_type_or_constructor_type(&mut self) -> bool {
+        if self.token == SyntaxKind::LessThanToken {
+            return true;
+        }
+        if self.token == SyntaxKind::OpenParenToken
+            && self.look_ahead(Self::is_unambiguously_start_of_function_type)
+        {
+            return true;
+        }
+        self.token == SyntaxKind::NewKeyword
+            || (self.token == SyntaxKind::AbstractKeyword
+                && self.look_ahead(Self::next_token_is_new_keyword))
+    }
+
+    fn skip_parameter_start(&mut self) -> bool {
+        if self.token.is_modifier() {
+            // Skip modifiers
+            self.parse_modifiers(false, false, false);
+        }
+        if self.is_identifier() || self.token == SyntaxKind::ThisKeyword {
+            self.next_token();
+            return true;
+        }
+        if self.token == SyntaxKind::OpenBracketToken || self.token == SyntaxKind::OpenBraceToken {
+            // Return true if we can parse an array or object binding pattern with no errors
+            let previous_error_count = self.diagnostics_len();
+            self.parse_identifier_or_pattern(None);
+            return previous_error_count == self.diagnostics_len();
+        }
+        false
+    }
+
+    fn is_unambiguously_start_of_function_type(&mut self) -> bool {
+        self.next_token();
+        if self.token == SyntaxKind::CloseParenToken || self.token == SyntaxKind::DotDotDotToken {
+            // ( )
+            // ( ...
+            return true;
+        }
+        if self.skip_parameter_start() {
+            // We successfully skipped modifiers (if any) and an identifier or binding pattern,
+            // now see if we have something that indicates a parameter declaration
+            if matches!(
+                self.token,
+                SyntaxKind::ColonToken
+                    | SyntaxKind::CommaToken
+                    | SyntaxKind::QuestionToken
+                    | SyntaxKind::EqualsToken
+            ) {
+                // ( xxx :
+                // ( xxx ,
+                // ( xxx ?
+                // ( xxx =
+                return true;
+            }
+            if self.token == SyntaxKind::CloseParenToken {
+                self.next_token();
+                if self.token == SyntaxKind::EqualsGreaterThanToken {
+                    // ( xxx ) =>
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn parse_function_or_constructor_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let modifiers = self.parse_modifiers_for_constructor_type();
+        let is_constructor_type = self.parse_optional(SyntaxKind::NewKeyword);
+        debug_assert!(
+            modifiers.is_none() || is_constructor_type,
+            "Per isStartOfFunctionOrConstructorType, a function type cannot have modifiers."
+        );
+        let type_parameters = self.parse_type_parameters();
+        let parameters = self.parse_parameters(SignatureFlags::TYPE);
+        let type_node = self
+            .parse_return_type(SyntaxKind::EqualsGreaterThanToken, false)
+            .unwrap_or_else(|| self.create_missing_identifier());
+        if is_constructor_type {
+            self.finish_node(
+                SyntaxKind::ConstructorType,
+                pos,
+                ConstructorTypeNode {
+                    modifiers,
+                    type_parameters,
+                    parameters,
+                    type_node,
+                },
+            )
+        } else {
+            self.finish_node(
+                SyntaxKind::FunctionType,
+                pos,
+                FunctionTypeNode {
+                    modifiers,
+                    type_parameters,
+                    parameters,
+                    type_node,
+                },
+            )
+        }
+    }
+
+    fn parse_modifiers_for_constructor_type(&mut self) -> Option<NodeList> {
+        if self.token == SyntaxKind::AbstractKeyword {
+            let pos = self.node_pos();
+            let modifier = self.parse_token_node();
+            return Some(self.create_node_list(pos, vec![modifier]));
+        }
+        None
+    }
+
+    fn parse_union_type_or_higher(&mut self) -> Arc<Node> {
+        self.parse_union_or_intersection_type(
+            SyntaxKind::BarToken,
+            Self::parse_intersection_type_or_higher,
+        )
+    }
+
+    fn parse_intersection_type_or_higher(&mut self) -> Arc<Node> {
+        self.parse_union_or_intersection_type(
+            SyntaxKind::AmpersandToken,
+            Self::parse_type_operator_or_higher,
+        )
+    }
+
+    fn parse_union_or_intersection_type(
+        &mut self,
+        operator: SyntaxKind,
+        parse_constituent_type: fn(&mut Self) -> Arc<Node>,
+    ) -> Arc<Node> {
+        let pos = self.node_pos();
+        let is_union_type = operator == SyntaxKind::BarToken;
+        let has_leading_operator = self.parse_optional(operator);
+        let mut type_node = if has_leading_operator {
+            self.parse_function_or_constructor_type_to_error(is_union_type)
+                .unwrap_or_else(|| parse_constituent_type(self))
+        } else {
+            parse_constituent_type(self)
+        };
+        if self.token == operator || has_leading_operator {
+            let mut types = vec![type_node];
+            while self.parse_optional(operator) {
+                let constituent = self
+                    .parse_function_or_constructor_type_to_error(is_union_type)
+                    .unwrap_or_else(|| parse_constituent_type(self));
+                types.push(constituent);
+            }
+            let types = self.create_node_list(pos, types);
+            type_node = if is_union_type {
+                self.finish_node(SyntaxKind::UnionType, pos, UnionTypeNode { types })
+            } else {
+                self.finish_node(
+                    SyntaxKind::IntersectionType,
+                    pos,
+                    IntersectionTypeNode { types },
+                )
+            };
+        }
+        type_node
+    }
+
+    fn parse_function_or_constructor_type_to_error(
+        &mut self,
+        is_in_union_type: bool,
+    ) -> Option<Arc<Node>> {
+        // the function type and constructor type shorthand notation
+        // are not allowed directly in unions and intersections, but we'll
+        // try to parse them gracefully and issue a helpful message.
+        if self.is_start_of_function_type_or_constructor_type() {
+            let type_node = self.parse_function_or_constructor_type();
+            let message = match (type_node.kind == SyntaxKind::FunctionType, is_in_union_type) {
+                (true, true) => diagnostics::FUNCTION_TYPE_NOTATION_MUST_BE_PARENTHESIZED_WHEN_USED_IN_A_UNION_TYPE_1385,
+                (true, false) => {
+                    diagnostics::FUNCTION_TYPE_NOTATION_MUST_BE_PARENTHESIZED_WHEN_USED_IN_AN_INTERSECTION_TYPE_1387
+                }
+                (false, true) => {
+                    diagnostics::CONSTRUCTOR_TYPE_NOTATION_MUST_BE_PARENTHESIZED_WHEN_USED_IN_A_UNION_TYPE_1386
+                }
+                (false, false) => {
+                    diagnostics::CONSTRUCTOR_TYPE_NOTATION_MUST_BE_PARENTHESIZED_WHEN_USED_IN_AN_INTERSECTION_TYPE_1388
+                }
+            };
+            self.parse_error_at_node(&type_node, message, &[]);
+            return Some(type_node);
+        }
+        None
+    }
+
+    fn parse_type_operator_or_higher(&mut self) -> Arc<Node> {
+        let operator = self.token;
+        match operator {
+            SyntaxKind::KeyOfKeyword | SyntaxKind::UniqueKeyword | SyntaxKind::ReadonlyKeyword => {
+                self.parse_type_operator(operator)
+            }
+            SyntaxKind::InferKeyword => self.parse_infer_type(),
+            _ => self.allow_conditional_types_and(Self::parse_postfix_type_or_higher),
+        }
+    }
+
+    fn parse_type_operator(&mut self, operator: SyntaxKind) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(operator);
+        let type_node = self.parse_type_operator_or_higher();
+        self.finish_node(
+            SyntaxKind::TypeOperator,
+            pos,
+            TypeOperatorNode {
+                operator,
+                type_node,
+            },
+        )
+    }
+
+    fn parse_infer_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::InferKeyword);
+        let type_parameter = self.parse_type_parameter_of_infer_type();
+        self.finish_node(SyntaxKind::InferType, pos, InferTypeNode { type_parameter })
+    }
+
+    fn parse_type_parameter_of_infer_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let name = self.parse_identifier();
+        let constraint = self.try_parse(Self::try_parse_constraint_of_infer_type);
+        self.finish_node(
+            SyntaxKind::TypeParameter,
+            pos,
+            TypeParameterDeclaration {
+                modifiers: None,
+                name,
+                constraint,
+                default_type: None,
+            },
+        )
+    }
+
+    fn try_parse_constraint_of_infer_type(&mut self) -> Option<Arc<Node>> {
+        if self.parse_optional(SyntaxKind::ExtendsKeyword) {
+            let constraint = self.disallow_conditional_types_and(Self::parse_type);
+            if self.in_disallow_conditional_types_context()
+                || self.token != SyntaxKind::QuestionToken
+            {
+                return Some(constraint);
+            }
+        }
+        None
+    }
+
+    fn parse_postfix_type_or_higher(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let mut type_node = self.parse_non_array_type();
+        while !self.has_preceding_line_break() {
+            match self.token {
+                SyntaxKind::ExclamationToken => {
+                    self.next_token();
+                    type_node = self.finish_node(
+                        SyntaxKind::JSDocNonNullableType,
+                        pos,
+                        JSDocNonNullableType {
+                            type_node,
+                            postfix: true,
+                        },
+                    );
+                }
+                SyntaxKind::QuestionToken => {
+                    // If next token is start of a type we have a conditional type
+                    if self.look_ahead(Self::next_token_is_start_of_type) {
+                        return type_node;
+                    }
+                    self.next_token();
+                    type_node = self.finish_node(
+                        SyntaxKind::JSDocNullableType,
+                        pos,
+                        JSDocNullableType {
+                            type_node,
+                            postfix: true,
+                        },
+                    );
+                }
+                SyntaxKind::OpenBracketToken => {
+                    self.parse_expected(SyntaxKind::OpenBracketToken);
+                    if self.is_start_of_type(false) {
+                        let index_type = self.parse_type();
+                        self.parse_expected(SyntaxKind::CloseBracketToken);
+                        type_node = self.finish_node(
+                            SyntaxKind::IndexedAccessType,
+                            pos,
+                            IndexedAccessTypeNode {
+                                object_type: type_node,
+                                index_type,
+                            },
+                        );
+                    } else {
+                        self.parse_expected(SyntaxKind::CloseBracketToken);
+                        type_node = self.finish_node(
+                            SyntaxKind::ArrayType,
+                            pos,
+                            ArrayTypeNode {
+                                element_type: type_node,
+                            },
+                        );
+                    }
+                }
+                _ => return type_node,
+            }
+        }
+        type_node
+    }
+
+    fn next_token_is_start_of_type(&mut self) -> bool {
+        self.next_token();
+        self.is_start_of_type(false)
+    }
+
+    fn parse_non_array_type(&mut self) -> Arc<Node> {
+        match self.token {
+            SyntaxKind::AnyKeyword
+            | SyntaxKind::UnknownKeyword
+            | SyntaxKind::StringKeyword
+            | SyntaxKind::NumberKeyword
+            | SyntaxKind::BigIntKeyword
+            | SyntaxKind::SymbolKeyword
+            | SyntaxKind::BooleanKeyword
+            | SyntaxKind::UndefinedKeyword
+            | SyntaxKind::NeverKeyword
+            | SyntaxKind::ObjectKeyword => {
+                // If these are followed by a dot, then parse these out as a dotted type reference instead.
+                if let Some(keyword) = self.try_parse(Self::parse_keyword_and_no_dot) {
+                    return keyword;
+                }
+                self.parse_type_reference()
+            }
+            SyntaxKind::StringLiteral
+            | SyntaxKind::NumericLiteral
+            | SyntaxKind::BigIntLiteral
+            | SyntaxKind::TrueKeyword
+            | SyntaxKind::FalseKeyword
+            | SyntaxKind::NullKeyword => self.parse_literal_type_node(false),
+            SyntaxKind::NoSubstitutionTemplateLiteral => self.parse_literal_type_node(false),
+            SyntaxKind::MinusToken => {
+                if self.look_ahead(Self::next_token_is_numeric_or_big_int_literal) {
+                    self.parse_literal_type_node(true)
+                } else {
+                    self.parse_type_reference()
+                }
+            }
+            SyntaxKind::VoidKeyword => self.parse_token_node(),
+            SyntaxKind::ThisKeyword => {
+                let this_keyword = self.parse_this_type_node();
+                if self.token == SyntaxKind::IsKeyword && !self.has_preceding_line_break() {
+                    self.parse_this_type_predicate(this_keyword)
+                } else {
+                    this_keyword
+                }
+            }
+            SyntaxKind::TypeOfKeyword => {
+                if self.look_ahead(Self::is_start_of_type_of_import_type) {
+                    self.parse_import_type()
+                } else {
+                    self.parse_type_query()
+                }
+            }
+            SyntaxKind::OpenBraceToken => {
+                if self.look_ahead(Self::is_start_of_mapped_type) {
+                    self.parse_mapped_type()
+                } else {
+                    self.parse_type_literal()
+                }
+            }
+            SyntaxKind::OpenBracketToken => self.parse_tuple_type(),
+            SyntaxKind::OpenParenToken => self.parse_parenthesized_type(),
+            SyntaxKind::ImportKeyword => self.parse_import_type(),
+            SyntaxKind::AssertsKeyword => {
+                if self.look_ahead(Self::next_token_is_identifier_or_this_on_same_line) {
+                    self.parse_asserts_type_predicate()
+                } else {
+                    self.parse_type_reference()
+                }
+            }
+            SyntaxKind::TemplateHead => self.parse_template_type(),
+            SyntaxKind::AsteriskToken => self.parse_jsdoc_all_type(),
+            SyntaxKind::AsteriskEqualsToken => {
+                // `*=` is scanned as a single token; split it into an optional all type
+                let pos = self.node_pos();
+                let all_type_end = self.scanner.token_start() + 1;
+                self.next_token();
+                let type_node = self.finish_node_with_end(
+                    SyntaxKind::JSDocAllType,
+                    pos,
+                    all_type_end,
+                    NodeFlags::NONE,
+                    JSDocAllType {},
+                );
+                self.finish_node(
+                    SyntaxKind::JSDocOptionalType,
+                    pos,
+                    JSDocOptionalType { type_node },
+                )
+            }
+            SyntaxKind::QuestionToken | SyntaxKind::QuestionQuestionToken => {
+                self.parse_jsdoc_unknown_or_nullable_type()
+            }
+            SyntaxKind::ExclamationToken => {
+                let pos = self.node_pos();
+                self.next_token();
+                let type_node = self.parse_non_array_type();
+                self.finish_node(
+                    SyntaxKind::JSDocNonNullableType,
+                    pos,
+                    JSDocNonNullableType {
+                        type_node,
+                        postfix: false,
+                    },
+                )
+            }
+            _ => self.parse_type_reference(),
+        }
+    }
+
+    fn parse_keyword_and_no_dot(&mut self) -> Option<Arc<Node>> {
+        let node = self.parse_token_node();
+        if self.token == SyntaxKind::DotToken {
+            None
+        } else {
+            Some(node)
+        }
+    }
+
+    fn parse_literal_type_node(&mut self, negative: bool) -> Arc<Node> {
+        let pos = self.node_pos();
+        let literal = if negative {
+            let operand_pos = self.node_pos();
+            self.next_token();
+            let operand = self.parse_literal_node();
+            self.finish_node(
+                SyntaxKind::PrefixUnaryExpression,
+                operand_pos,
+                PrefixUnaryExpression {
+                    operator: SyntaxKind::MinusToken,
+                    operand,
+                },
+            )
+        } else if matches!(
+            self.token,
+            SyntaxKind::TrueKeyword | SyntaxKind::FalseKeyword | SyntaxKind::NullKeyword
+        ) {
+            self.parse_token_node()
+        } else {
+            self.parse_literal_node()
+        };
+        self.finish_node(SyntaxKind::LiteralType, pos, LiteralTypeNode { literal })
+    }
+
+    fn parse_type_reference(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let type_name = self.parse_entity_name(true, Some(diagnostics::TYPE_EXPECTED_1110));
+        let type_arguments = self.parse_type_arguments_of_type_reference();
+        self.finish_node(
+            SyntaxKind::TypeReference,
+            pos,
+            TypeReferenceNode {
+                type_name,
+                type_arguments,
+            },
+        )
+    }
+
+    fn parse_type_arguments_of_type_reference(&mut self) -> Option<NodeList> {
+        if !self.has_preceding_line_break()
+            && self.re_scan_less_than_token() == SyntaxKind::LessThanToken
+        {
+            return Some(self.parse_bracketed_list(
+                ParsingContext::TypeArguments,
+                Self::parse_type,
+                SyntaxKind::LessThanToken,
+                SyntaxKind::GreaterThanToken,
+            ));
+        }
+        None
+    }
+
+    /// Parses type arguments in a type position, where `<` is never ambiguous
+    fn try_parse_type_arguments(&mut self) -> Option<NodeList> {
+        if self.token == SyntaxKind::LessThanToken {
+            return Some(self.parse_bracketed_list(
+                ParsingContext::TypeArguments,
+                Self::parse_type,
+                SyntaxKind::LessThanToken,
+                SyntaxKind::GreaterThanToken,
+            ));
+        }
+        None
+    }
+
+    fn parse_this_type_node(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.next_token();
+        self.finish_node(SyntaxKind::ThisType, pos, ThisTypeNode {})
+    }
+
+    fn parse_this_type_predicate(&mut self, parameter_name: Arc<Node>) -> Arc<Node> {
+        let pos = parameter_name.pos();
+        self.next_token();
+        let type_node = Some(self.parse_type());
+        self.finish_node(
+            SyntaxKind::TypePredicate,
+            pos,
+            TypePredicateNode {
+                asserts_modifier: None,
+                parameter_name,
+                type_node,
+            },
+        )
+    }
+
+    fn parse_type_query(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::TypeOfKeyword);
+        let expr_name = self.parse_entity_name(true, None);
+        // Make sure we perform ASI to prevent parsing the next line's type arguments as part of an instantiation expression.
+        let type_arguments = if !self.has_preceding_line_break() {
+            self.try_parse_type_arguments()
+        } else {
+            None
+        };
+        self.finish_node(
+            SyntaxKind::TypeQuery,
+            pos,
+            TypeQueryNode {
+                expr_name,
+                type_arguments,
+            },
+        )
+    }
+
+    fn is_start_of_type_of_import_type(&mut self) -> bool {
+        self.next_token();
+        self.token == SyntaxKind::ImportKeyword
+    }
+
+    fn parse_import_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let is_type_of = self.parse_optional(SyntaxKind::TypeOfKeyword);
+        self.parse_expected(SyntaxKind::ImportKeyword);
+        self.parse_expected(SyntaxKind::OpenParenToken);
+        let argument = self.parse_type();
+        let mut attributes = None;
+        if self.parse_optional(SyntaxKind::CommaToken) {
+            let open_brace_position = self.scanner.token_start();
+            self.parse_expected(SyntaxKind::OpenBraceToken);
+            let current_token = self.token;
+            if current_token == SyntaxKind::WithKeyword
+                || current_token == SyntaxKind::AssertKeyword
+            {
+                self.next_token();
+            } else {
+                self.parse_error_at_current_token(
+                    diagnostics::D_0_EXPECTED_1005,
+                    &[token_text(SyntaxKind::WithKeyword)],
+                );
+            }
+            self.parse_expected(SyntaxKind::ColonToken);
+            attributes = Some(self.parse_import_attributes(current_token));
+            self.parse_optional(SyntaxKind::CommaToken);
+            self.parse_expected_matching_brackets(
+                SyntaxKind::OpenBraceToken,
+                SyntaxKind::CloseBraceToken,
+                true,
+                open_brace_position,
+            );
+        }
+        self.parse_expected(SyntaxKind::CloseParenToken);
+        let qualifier = if self.parse_optional(SyntaxKind::DotToken) {
+            Some(self.parse_entity_name_of_type_reference())
+        } else {
+            None
+        };
+        let type_arguments = self.parse_type_arguments_of_type_reference();
+        self.finish_node(
+            SyntaxKind::ImportType,
+            pos,
+            ImportTypeNode {
+                is_type_of,
+                argument,
+                attributes,
+                qualifier,
+                type_arguments,
+            },
+        )
+    }
+
+    fn parse_entity_name_of_type_reference(&mut self) -> Arc<Node> {
+        self.parse_entity_name(true, Some(diagnostics::TYPE_EXPECTED_1110))
+    }
+
+    fn is_start_of_mapped_type(&mut self) -> bool {
+        self.next_token();
+        if self.token == SyntaxKind::PlusToken || self.token == SyntaxKind::MinusToken {
+            return self.next_token() == SyntaxKind::ReadonlyKeyword;
+        }
+        if self.token == SyntaxKind::ReadonlyKeyword {
+            self.next_token();
+        }
+        self.token == SyntaxKind::OpenBracketToken
+            && self.next_token_is_identifier()
+            && self.next_token() == SyntaxKind::InKeyword
+    }
+
+    fn parse_mapped_type_parameter(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let name = self.parse_identifier_name();
+        self.parse_expected(SyntaxKind::InKeyword);
+        let constraint = Some(self.parse_type());
+        self.finish_node(
+            SyntaxKind::TypeParameter,
+            pos,
+            TypeParameterDeclaration {
+                modifiers: None,
+                name,
+                constraint,
+                default_type: None,
+            },
+        )
+    }
+
+    fn parse_mapped_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::OpenBraceToken);
+        let readonly_token = if matches!(
+            self.token,
+            SyntaxKind::ReadonlyKeyword | SyntaxKind::PlusToken | SyntaxKind::MinusToken
+        ) {
+            let token = self.parse_token_node();
+            if token.kind != SyntaxKind::ReadonlyKeyword {
+                self.parse_expected(SyntaxKind::ReadonlyKeyword);
+            }
+            Some(token)
+        } else {
+            None
+        };
+        self.parse_expected(SyntaxKind::OpenBracketToken);
+        let type_parameter = self.parse_mapped_type_parameter();
+        let name_type = if self.parse_optional(SyntaxKind::AsKeyword) {
+            Some(self.parse_type())
+        } else {
+            None
+        };
+        self.parse_expected(SyntaxKind::CloseBracketToken);
+        let question_token = if matches!(
+            self.token,
+            SyntaxKind::QuestionToken | SyntaxKind::PlusToken | SyntaxKind::MinusToken
+        ) {
+            let token = self.parse_token_node();
+            if token.kind != SyntaxKind::QuestionToken {
+                self.parse_expected(SyntaxKind::QuestionToken);
+            }
+            Some(token)
+        } else {
+            None
+        };
+        let type_node = self.parse_type_annotation();
+        self.parse_semicolon();
+        let members = self.parse_list(ParsingContext::TypeMembers, Self::parse_type_member);
+        self.parse_expected(SyntaxKind::CloseBraceToken);
+        let members = if members.is_empty() {
+            None
+        } else {
+            Some(members)
+        };
+        self.finish_node(
+            SyntaxKind::MappedType,
+            pos,
+            MappedTypeNode {
+                readonly_token,
+                type_parameter,
+                name_type,
+                question_token,
+                type_node,
+                members,
+            },
+        )
+    }
+
+    fn parse_type_literal(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let members = self.parse_object_type_members();
+        self.finish_node(SyntaxKind::TypeLiteral, pos, TypeLiteralNode { members })
+    }
+
+    fn parse_object_type_members(&mut self) -> NodeList {
+        if self.parse_expected(SyntaxKind::OpenBraceToken) {
+            let members = self.parse_list(ParsingContext::TypeMembers, Self::parse_type_member);
+            self.parse_expected(SyntaxKind::CloseBraceToken);
+            return members;
+        }
+        self.create_missing_list()
+    }
+
+    fn is_type_member_start(&mut self) -> bool {
+        // Return true if we have the start of a signature member
+        if self.token == SyntaxKind::OpenParenToken
+            || self.token == SyntaxKind::LessThanToken
+            || self.token == SyntaxKind::GetKeyword
+            || self.token == SyntaxKind::SetKeyword
+        {
+            return true;
+        }
+        let mut id_token = false;
+        // Eat up all modifiers, but hold on to the last one in case it is actually an identifier
+        while self.token.is_modifier() {
+            id_token = true;
+            self.next_token();
+        }
+        // Index signatures and computed property names are type members
+        if self.token == SyntaxKind::OpenBracketToken {
+            return true;
+        }
+        // Try to get the first property-like token following all modifiers
+        if self.is_literal_property_name() {
+            id_token = true;
+            self.next_token();
+        }
+        // If we were able to get any potential identifier, check that it is
+        // the start of a member declaration
+        if id_token {
+            return matches!(
+                self.token,
+                SyntaxKind::OpenParenToken
+                    | SyntaxKind::LessThanToken
+                    | SyntaxKind::QuestionToken
+                    | SyntaxKind::ColonToken
+                    | SyntaxKind::CommaToken
+            ) || self.can_parse_semicolon();
+        }
+        false
+    }
+
+    fn parse_type_member(&mut self) -> Arc<Node> {
+        self.parse_with_jsdoc(Self::parse_type_member_worker)
+    }
+
+    fn parse_type_member_worker(&mut self) -> Arc<Node> {
+        if self.token == SyntaxKind::OpenParenToken || self.token == SyntaxKind::LessThanToken {
+            return self.parse_signature_member(SyntaxKind::CallSignature);
+        }
+        if self.token == SyntaxKind::NewKeyword
+            && self.look_ahead(Self::next_token_is_open_paren_or_less_than)
+        {
+            return self.parse_signature_member(SyntaxKind::ConstructSignature);
+        }
+        let pos = self.node_pos();
+        let modifiers = self.parse_modifiers(false, false, false);
+        if self.parse_contextual_modifier(SyntaxKind::GetKeyword) {
+            return self.parse_accessor_declaration(
+                pos,
+                modifiers,
+                SyntaxKind::GetAccessor,
+                SignatureFlags::TYPE,
+            );
+        }
+        if self.parse_contextual_modifier(SyntaxKind::SetKeyword) {
+            return self.parse_accessor_declaration(
+                pos,
+                modifiers,
+                SyntaxKind::SetAccessor,
+                SignatureFlags::TYPE,
+            );
+        }
+        if self.is_index_signature() {
+            return self.parse_index_signature_declaration(pos, modifiers);
+        }
+        self.parse_property_or_method_signature(pos, modifiers)
+    }
+
+    fn parse_signature_member(&mut self, kind: SyntaxKind) -> Arc<Node> {
+        let pos = self.node_pos();
+        if kind == SyntaxKind::ConstructSignature {
+            self.parse_expected(SyntaxKind::NewKeyword);
+        }
+        let type_parameters = self.parse_type_parameters();
+        let parameters = self.parse_parameters(SignatureFlags::TYPE);
+        let type_node = self.parse_return_type(SyntaxKind::ColonToken, true);
+        self.parse_type_member_semicolon();
+        if kind == SyntaxKind::CallSignature {
+            self.finish_node(
+                kind,
+                pos,
+                CallSignatureDeclaration {
+                    type_parameters,
+                    parameters,
+                    type_node,
+                },
+            )
+        } else {
+            self.finish_node(
+                kind,
+                pos,
+                ConstructSignatureDeclaration {
+                    type_parameters,
+                    parameters,
+                    type_node,
+                },
+            )
+        }
+    }
+
+    fn parse_property_or_method_signature(
+        &mut self,
+        pos: usize,
+        modifiers: Option<NodeList>,
+    ) -> Arc<Node> {
+        let name = self.parse_property_name();
+        let postfix_token = self.parse_optional_token(SyntaxKind::QuestionToken);
+        if self.token == SyntaxKind::OpenParenToken || self.token == SyntaxKind::LessThanToken {
+            // Method signatures don't exist in expression contexts.  So they have neither
+            // [Yield] nor [Await]
+            let type_parameters = self.parse_type_parameters();
+            let parameters = self.parse_parameters(SignatureFlags::TYPE);
+            let type_node = self.parse_return_type(SyntaxKind::ColonToken, true);
+            self.parse_type_member_semicolon();
+            return self.finish_node(
+                SyntaxKind::MethodSignature,
+                pos,
+                MethodSignature {
+                    modifiers,
+                    name,
+                    postfix_token,
+                    type_parameters,
+                    parameters,
+                    type_node,
+                },
+            );
+        }
+        let type_node = self.parse_type_annotation();
+        // Although type literal properties cannot not have initializers, we attempt
+        // to parse an initializer so we can report in the checker that an interface
+        // property or type literal property cannot have an initializer.
+        let initializer = if self.token == SyntaxKind::EqualsToken {
+            self.parse_initializer()
+        } else {
+            None
+        };
+        self.parse_type_member_semicolon();
+        self.finish_node(
+            SyntaxKind::PropertySignature,
+            pos,
+            PropertySignature {
+                modifiers,
+                name,
+                postfix_token,
+                type_node,
+                initializer,
+            },
+        )
+    }
+
+    fn parse_type_member_semicolon(&mut self) {
+        // We allow type members to be separated by commas or (possibly ASI) semicolons.
+        // First check if it was a comma.  If so, we're done with the member.
+        if self.parse_optional(SyntaxKind::CommaToken) {
+            return;
+        }
+        // Didn't have a comma.  We must have a (possible ASI) semicolon.
+        self.parse_semicolon();
+    }
+
+    fn parse_tuple_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let elements = self.parse_bracketed_list(
+            ParsingContext::TupleElementTypes,
+            Self::parse_tuple_element_name_or_tuple_element_type,
+            SyntaxKind::OpenBracketToken,
+            SyntaxKind::CloseBracketToken,
+        );
+        self.finish_node(SyntaxKind::TupleType, pos, TupleTypeNode { elements })
+    }
+
+    fn is_tuple_element_name(&mut self) -> bool {
+        if self.token == SyntaxKind::DotDotDotToken {
+            return scanner::is_identifier_or_keyword(self.next_token())
+                && self.next_token_is_colon_or_question_colon();
+        }
+        scanner::is_identifier_or_keyword(self.token)
+            && self.next_token_is_colon_or_question_colon()
+    }
+
+    fn parse_tuple_element_name_or_tuple_element_type(&mut self) -> Arc<Node> {
+        if self.look_ahead(Self::is_tuple_element_name) {
+            let pos = self.node_pos();
+            let dot_dot_dot_token = self.parse_optional_token(SyntaxKind::DotDotDotToken);
+            let name = self.parse_identifier_name();
+            let question_token = self.parse_optional_token(SyntaxKind::QuestionToken);
+            self.parse_expected(SyntaxKind::ColonToken);
+            let type_node = self.parse_tuple_element_type();
+            return self.finish_node(
+                SyntaxKind::NamedTupleMember,
+                pos,
+                NamedTupleMember {
+                    dot_dot_dot_token,
+                    name,
+                    question_token,
+                    type_node,
+                },
+            );
+        }
+        self.parse_tuple_element_type()
+    }
+
+    fn parse_tuple_element_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        if self.parse_optional(SyntaxKind::DotDotDotToken) {
+            let type_node = self.parse_type();
+            return self.finish_node(SyntaxKind::RestType, pos, RestTypeNode { type_node });
+        }
+        let type_node = self.parse_type();
+        // `T?` in a tuple is parsed as a postfix JSDoc nullable type; reinterpret it as an optional element
+        if type_node.kind == SyntaxKind::JSDocNullableType {
+            let nullable = type_node.as_jsdoc_nullable_type();
+            if nullable.postfix && type_node.pos() == nullable.type_node.pos() {
+                return self.finish_node_with_end(
+                    SyntaxKind::OptionalType,
+                    type_node.pos(),
+                    type_node.end(),
+                    type_node.flags,
+                    OptionalTypeNode {
+                        type_node: nullable.type_node.clone(),
+                    },
+                );
+            }
+        }
+        type_node
+    }
+
+    fn parse_parenthesized_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::OpenParenToken);
+        let type_node = self.parse_type();
+        self.parse_expected(SyntaxKind::CloseParenToken);
+        self.finish_node(
+            SyntaxKind::ParenthesizedType,
+            pos,
+            ParenthesizedTypeNode { type_node },
+        )
+    }
+
+    fn parse_template_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let head = self.parse_template_head(false);
+        let span_pos = self.node_pos();
+        let mut spans = Vec::new();
+        loop {
+            let span = self.parse_template_type_span();
+            let is_tail = span
+                .data_as::<TemplateLiteralTypeSpan>()
+                .is_some_and(|s| s.literal.kind == SyntaxKind::TemplateTail);
+            spans.push(span);
+            if is_tail {
+                break;
+            }
+        }
+        let template_spans = self.create_node_list(span_pos, spans);
+        self.finish_node(
+            SyntaxKind::TemplateLiteralType,
+            pos,
+            TemplateLiteralTypeNode {
+                head,
+                template_spans,
+            },
+        )
+    }
+
+    fn parse_template_type_span(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let type_node = self.parse_type();
+        let literal = self.parse_literal_of_template_span(false);
+        self.finish_node(
+            SyntaxKind::TemplateLiteralTypeSpan,
+            pos,
+            TemplateLiteralTypeSpan { type_node, literal },
+        )
+    }
+
+    // Type parameters and parameters
+
+    fn parse_type_parameters(&mut self) -> Option<NodeList> {
+        if self.token == SyntaxKind::LessThanToken {
+            return Some(self.parse_bracketed_list(
+                ParsingContext::TypeParameters,
+                Self::parse_type_parameter,
+                SyntaxKind::LessThanToken,
+                SyntaxKind::GreaterThanToken,
+            ));
+        }
+        None
+    }
+
+    fn parse_type_parameter(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let modifiers = self.parse_modifiers(false, true, false);
+        let name = self.parse_identifier();
+        let mut constraint = None;
+        let mut expression = None;
+        if self.parse_optional(SyntaxKind::ExtendsKeyword) {
+            // It's not uncommon for people to write improper constraints to a generic.  If the
+            // user writes a constraint that is an expression and not an actual type, then parse
+            // it out as an expression (so we can recover well), but report that a type is needed
+            // instead.
+            if self.is_start_of_type(false) || !self.is_start_of_expression() {
+                constraint = Some(self.parse_type());
+            } else {
+                // It was not a type, and it looked like an expression.  Parse out an expression
+                // here so we recover well.  Note: it is important that we call parseUnaryExpression
+                // and not parseExpression here.  If the user has:
+                //
+                //      <T extends "">
+                //
+                // We do *not* want to consume the `>` as we're consuming the expression for "".
+                expression = Some(self.parse_unary_expression_or_higher());
+            }
+        }
+        let default_type = if self.parse_optional(SyntaxKind::EqualsToken) {
+            Some(self.parse_type())
+        } else {
+            None
+        };
+        if let Some(expression) = expression {
+            self.parse_error_at_node(&expression, diagnostics::TYPE_EXPECTED_1110, &[]);
+            constraint = Some(expression);
+        }
+        self.finish_node(
+            SyntaxKind::TypeParameter,
+            pos,
+            TypeParameterDeclaration {
+                modifiers,
+                name,
+                constraint,
+                default_type,
+            },
+        )
+    }
+
+    fn is_start_of_parameter(&mut self) -> bool {
+        self.is_start_of_parameter_with_types(false)
+    }
+
+    fn is_start_of_parameter_with_types(&mut self, is_js_doc_parameter: bool) -> bool {
+        self.token == SyntaxKind::DotDotDotToken
+            || self.is_binding_identifier_or_private_identifier_or_pattern()
+            || self.token.is_modifier()
+            || self.token == SyntaxKind::AtToken
+            || self.is_start_of_type(!is_js_doc_parameter)
+    }
+
+    fn is_parameter_name_start(&mut self) -> bool {
+        // Be permissive about await and yield by calling isBindingIdentifier instead of isIdentifier; disallowing
+        // them during a speculative parse leads to many more follow-on errors than allowing the function to parse then later
+        // complaining about the use of the keywords.
+        self.is_binding_identifier_or_private_identifier_or_pattern()
+    }
+
+    fn parse_parameters(&mut self, flags: SignatureFlags) -> NodeList {
+        // FormalParameters [Yield,Await]: (modified)
+        //      [empty]
+        //      FormalParameterList[?Yield,Await]
+        //
+        // FormalParameter[Yield,Await]: (modified)
+        //      BindingElement[?Yield,Await]
+        //
+        // BindingElement [Yield,Await]: (modified)
+        //      SingleNameBinding[?Yield,?Await]
+        //      BindingPattern[?Yield,?Await]Initializer [In, ?Yield,?Await] opt
+        //
+        // SingleNameBinding [Yield,Await]:
+        //      BindingIdentifier[?Yield,?Await]Initializer [In, ?Yield,?Await] opt
+        if !self.parse_expected(SyntaxKind::OpenParenToken) {
+            return self.create_missing_list();
+        }
+        let parameters = self
+            .parse_parameters_worker(flags, true)
+            .unwrap_or_default();
+        self.parse_expected(SyntaxKind::CloseParenToken);
+        parameters
+    }
+
+    fn parse_parameters_worker(
+        &mut self,
+        flags: SignatureFlags,
+        allow_ambiguity: bool,
+    ) -> Option<NodeList> {
+        // FormalParameters [Yield,Await]: (modified)
+        //      [empty]
+        //      FormalParameterList[?Yield,Await]
+        let saved = self.context_flags;
+        let in_outer_await_context = self.in_await_context();
+        self.set_context_flag(
+            flags.contains(SignatureFlags::YIELD),
+            NodeFlags::YIELD_CONTEXT,
+        );
+        self.set_context_flag(
+            flags.contains(SignatureFlags::AWAIT),
+            NodeFlags::AWAIT_CONTEXT,
+        );
+        let mut has_error = false;
+        let parameters = self.parse_delimited_list(ParsingContext::Parameters, |p| {
+            match p.parse_parameter_worker(in_outer_await_context, allow_ambiguity) {
+                Some(parameter) => parameter,
+                None => {
+                    has_error = true;
+                    p.create_missing_identifier()
+                }
+            }
+        });
+        self.context_flags = saved;
+        if has_error { None } else { Some(parameters) }
+    }
+
+    fn parse_parameter(&mut self, in_outer_await_context: bool) -> Arc<Node> {
+        self.parse_parameter_worker(in_outer_await_context, true)
+            .expect("parameters are always produced when ambiguity is allowed")
+    }
+
+    fn parse_parameter_worker(
+        &mut self,
+        in_outer_await_context: bool,
+        allow_ambiguity: bool,
+    ) -> Option<Arc<Node>> {
+        let pos = self.node_pos();
+        let has_jsdoc = self.has_preceding_jsdoc_comment();
+        // FormalParameter [Yield,Await]:
+        //      BindingElement[?Yield,?Await]
+
+        // Decorators are parsed in the outer [Await] context, the rest of the parameter is parsed in the function's [Await] context.
+        let saved = self.context_flags;
+        self.set_context_flag(in_outer_await_context, NodeFlags::AWAIT_CONTEXT);
+        let modifiers = self.parse_modifiers(true, false, false);
+        self.context_flags = saved;
+
+        if self.token == SyntaxKind::ThisKeyword {
+            let name = self.create_identifier(true);
+            let type_node = self.parse_type_annotation();
+            let parameter = self.finish_node(
+                SyntaxKind::Parameter,
+                pos,
+                ParameterDeclaration {
+                    modifiers,
+                    dot_dot_dot_token: None,
+                    name,
+                    question_token: None,
+                    type_node,
+                    initializer: None,
+                },
+            );
+            return Some(self.with_jsdoc(parameter, has_jsdoc));
+        }
+        let dot_dot_dot_token = self.parse_optional_token(SyntaxKind::DotDotDotToken);
+        if !allow_ambiguity && !self.is_parameter_name_start() {
+            return None;
+        }
+        let name = self.parse_name_of_parameter(modifiers.as_ref());
+        let question_token = self.parse_optional_token(SyntaxKind::QuestionToken);
+        let type_node = self.parse_type_annotation();
+        let initializer = self.parse_initializer();
+        let parameter = self.finish_node(
+            SyntaxKind::Parameter,
+            pos,
+            ParameterDeclaration {
+                modifiers,
+                dot_dot_dot_token,
+                name,
+                question_token,
+                type_node,
+                initializer,
+            },
+        );
+        Some(self.with_jsdoc(parameter, has_jsdoc))
+    }
+
+    fn parse_name_of_parameter(&mut self, modifiers: Option<&NodeList>) -> Arc<Node> {
+        // FormalParameter [Yield,Await]:
+        //      BindingElement[?Yield,?Await]
+        let name = self.parse_identifier_or_pattern(Some(
+            diagnostics::PRIVATE_IDENTIFIERS_CANNOT_BE_USED_AS_PARAMETERS_18009,
+        ));
+        if name.end() == name.pos()
+            && modifiers.is_none_or(|list| list.is_empty())
+            && self.token.is_modifier()
+        {
+            // in cases like
+            // 'use strict'
+            // function foo(static)
+            // isParameter('static') === true, because of isModifier('static')
+            // however 'static' is not a legal identifier in a strict mode.
+            // so result of this function will be ParameterDeclaration (flags = 0, name = missing, type = undefined, initializer = undefined)
+            // and current token will not change => parsing of the enclosing parameter list will last till the end of time (or OOM)
+            // to avoid this we'll advance cursor to the next token.
+            self.next_token();
+        }
+        name
+    }
+}
+
+// Expressions
+impl Parser {
+    fn is_start_of_left_hand_side_expression(&mut self) -> bool {
+        match self.token {
+            SyntaxKind::ThisKeyword
+            | SyntaxKind::SuperKeyword
+            | SyntaxKind::NullKeyword
+            | SyntaxKind::TrueKeyword
+            | SyntaxKind::FalseKeyword
+            | SyntaxKind::NumericLiteral
+            | SyntaxKind::BigIntLiteral
+            | SyntaxKind::StringLiteral
+            | SyntaxKind::NoSubstitutionTemplateLiteral
+            | SyntaxKind::TemplateHead
+            | SyntaxKind::OpenParenToken
+            | SyntaxKind::OpenBracketToken
+            | SyntaxKind::OpenBraceToken
+            | SyntaxKind::FunctionKeyword
+            | SyntaxKind::ClassKeyword
+            | SyntaxKind::NewKeyword
+            | SyntaxKind::SlashToken
+            | SyntaxKind::SlashEqualsToken
+            | SyntaxKind::Identifier => true,
+            SyntaxKind::ImportKeyword => {
+                self.look_ahead(Self::next_token_is_open_paren_or_less_than_or_dot)
+            }
+            _ => self.is_identifier(),
+        }
+    }
+
+    fn is_start_of_expression(&mut self) -> bool {
+        if self.is_start_of_left_hand_side_expression() {
+            return true;
+        }
+        match self.token {
+            SyntaxKind::PlusToken
+            | SyntaxKind::MinusToken
+            | SyntaxKind::TildeToken
+            | SyntaxKind::ExclamationToken
+            | SyntaxKind::DeleteKeyword
+            | SyntaxKind::TypeOfKeyword
+            | SyntaxKind::VoidKeyword
+            | SyntaxKind::PlusPlusToken
+            | SyntaxKind::MinusMinusToken
+            | SyntaxKind::LessThanToken
+            | SyntaxKind::AwaitKeyword
+            | SyntaxKind::YieldKeyword
+            | SyntaxKind::PrivateIdentifier
+            | SyntaxKind::AtToken => true,
+            // Error tolerance.  If we see the start of some binary operator, we consider
+            // that the start of an expression.  That way we'll parse out a missing identifier,
+            // give a good message about an identifier being missing, and then consume the
+            // rest of the binary expression.
+            _ => self.is_binary_operator() || self.is_identifier(),
+        }
+    }
+
+    fn is_start_of_expression_statement(&mut self) -> bool {
+        // As per the grammar, none of '{' or 'function' or 'class' can start an expression statement.
+        self.token != SyntaxKind::OpenBraceToken
+            && self.token != SyntaxKind::FunctionKeyword
+            && self.token != SyntaxKind::ClassKeyword
+            && self.token != SyntaxKind::AtToken
+            && self.is_start_of_expression()
+    }
+
+    fn is_binary_operator(&self) -> bool {
+        if self.in_disallow_in_context() && self.token == SyntaxKind::InKeyword {
+            return false;
+        }
+        get_binary_operator_precedence(self.token) > precedence::LOWEST
+    }
+
+    fn parse_expression(&mut self) -> Arc<Node> {
+        // Expression[in]:
+        //      AssignmentExpression[in]
+        //      Expression[in] , AssignmentExpression[in]
+
+        // clear the decorator context when parsing Expression, as it should be unambiguous when parsing a decorator
+        let saved_decorator_context = self.in_decorator_context();
+        self.set_context_flag(false, NodeFlags::DECORATOR_CONTEXT);
+        let pos = self.node_pos();
+        let mut expression = self.parse_assignment_expression_or_higher(true);
+        while let Some(operator_token) = self.parse_optional_token(SyntaxKind::CommaToken) {
+            let right = self.parse_assignment_expression_or_higher(true);
+            expression = self.make_binary_expression(expression, operator_token, right, pos);
+        }
+        self.set_context_flag(saved_decorator_context, NodeFlags::DECORATOR_CONTEXT);
+        expression
+    }
+
+    fn parse_assignment_expression_or_higher(
+        &mut self,
+        allow_return_type_in_arrow_function: bool,
+    ) -> Arc<Node> {
+        //  AssignmentExpression[in,yield]:
+        //      1) ConditionalExpression[?in,?yield]
+        //      2) LeftHandSideExpression = AssignmentExpression[?in,?yield]
+        //      3) LeftHandSideExpression AssignmentOperator AssignmentExpression[?in,?yield]
+        //      4) ArrowFunctionExpression[?in,?yield]
+        //      5) AsyncArrowFunctionExpression[in,yield,await]
+        //      6) [+Yield] YieldExpression[?In]
+        //
+        // Note: for ease of implementation we treat productions '2' and '3' as the same thing.
+        // (i.e. they're both BinaryExpressions with an assignment operator in it).
+
+        // First, do the simple check if we have a YieldExpression (production '6').
+        if self.is_yield_expression() {
+            return self.parse_yield_expression();
+        }
+
+        // Then, check if we have an arrow function (production '4' and '5') that starts with a parenthesized
+        // parameter list or is an async arrow function.
+        // AsyncArrowFunctionExpression:
+        //      1) async[no LineTerminator here]AsyncArrowBindingIdentifier[?Yield][no LineTerminator here]=>AsyncConciseBody[?In]
+        //      2) CoverCallExpressionAndAsyncArrowHead[?Yield, ?Await][no LineTerminator here]=>AsyncConciseBody[?In]
+        // Production (1) of AsyncArrowFunctionExpression is parsed in "tryParseAsyncSimpleArrowFunctionExpression".
+        // And production (2) is parsed in "tryParseParenthesizedArrowFunctionExpression".
+        //
+        // If we do successfully parse arrow-function, we must *not* recurse for productions 1, 2 or 3. An ArrowFunction is
+        // not a LeftHandSideExpression, nor does it start a ConditionalExpression.  So we are done
+        // with AssignmentExpression if we see one.
+        if let Some(arrow_expression) = self
+            .try_parse_parenthesized_arrow_function_expression(allow_return_type_in_arrow_function)
+        {
+            return arrow_expression;
+        }
+        if let Some(arrow_expression) = self
+            .try_parse_async_simple_arrow_function_expression(allow_return_type_in_arrow_function)
+        {
+            return arrow_expression;
+        }
+
+        // Now try to see if we're in production '1', '2' or '3'.  A conditional expression can
+        // start with a LogicalOrExpression, while the assignment productions can only start with
+        // LeftHandSideExpressions.
+        //
+        // So, first, we try to just parse out a BinaryExpression.  If we get something that is a
+        // LeftHandSide or higher, then we can try to parse out the assignment expression part.
+        // Otherwise, we try to parse out the conditional expression bit.  We want to allow any
+        // binary expression here, so we pass in the 'lowest' precedence here so that it matches
+        // and consumes anything.
+        let pos = self.node_pos();
+        let expression = self.parse_binary_expression_or_higher(precedence::LOWEST);
+
+        // To avoid a look-ahead, we did not handle the case of an arrow function with a single un-parenthesized
+        // parameter ('x => ...') above. We handle it here by checking if the parsed expression was a single
+        // identifier and the current token is an arrow.
+        if expression.kind == SyntaxKind::Identifier
+            && self.token == SyntaxKind::EqualsGreaterThanToken
+        {
+            return self.parse_simple_arrow_function_expression(
+                pos,
+                expression,
+                allow_return_type_in_arrow_function,
+                None,
+            );
+        }
+
+        // Now see if we might be in cases '2' or '3'.
+        // If the expression was a LHS expression, and we have an assignment operator after it,
+        // then we're in '2' or '3'. Consume the assignment and return.
+        //
+        // Note: we call reScanGreaterToken so that we get an appropriately merged token
+        // for cases like `> > =` becoming `>>=`
+        if is_left_hand_side_expression(&expression)
+            && self.re_scan_greater_token().is_assignment_operator()
+        {
+            let operator_token = self.parse_token_node();
+            let right =
+                self.parse_assignment_expression_or_higher(allow_return_type_in_arrow_function);
+            return self.make_binary_expression(expression, operator_token, right, pos);
+        }
+
+        // It wasn't an assignment or a lambda.  This is a conditional expression:
+        self.parse_conditional_expression_rest(expression, pos, allow_return_type_in_arrow_function)
+    }
+
+    fn is_yield_expression(&mut self) -> bool {
+        if self.token == SyntaxKind::YieldKeyword {
+            // If we have a 'yield' keyword, and this is a context where yield expressions are
+            // allowed, then definitely parse out a yield expression.
+            if self.in_yield_context() {
+                return true;
+            }
+
+            // We're in a context where 'yield expr' is not allowed.  However, if we can
+            // definitely tell that the user was trying to parse a 'yield expr' and not
+            // just a normal expr that start with a 'yield' identifier, then parse out
+            // a 'yield expr'.  We can then report an error later that they are only
+            // allowed in generator expressions.
+            //
+            // for example, if we see 'yield(foo)', then we'll have to treat that as an
+            // invocation expression of something called 'yield'.  However, if we have
+            // 'yield foo' then that is not legal as a normal expression, so we can
+            // definitely recognize this as a yield expression.
+            //
+            // for now we just check if the next token is an identifier.  More heuristics
+            // can be added here later as necessary.  We just need to make sure that we
+            // don't accidentally consume something legal.
+            return self
+                .look_ahead(Self::next_token_is_identifier_or_keyword_or_literal_on_same_line);
+        }
+        false
+    }
+
+    fn parse_yield_expression(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        // YieldExpression[In] :
+        //      yield
+        //      yield [no LineTerminator here] [Lexical goal InputElementRegExp]AssignmentExpression[?In, Yield]
+        //      yield [no LineTerminator here] * [Lexical goal InputElementRegExp]AssignmentExpression[?In, Yield]
+        self.next_token();
+        if !self.has_preceding_line_break()
+            && (self.token == SyntaxKind::AsteriskToken || self.is_start_of_expression())
+        {
+            let asterisk_token = self.parse_optional_token(SyntaxKind::AsteriskToken);
+            let expression = Some(self.parse_assignment_expression_or_higher(true));
+            return self.finish_node(
+                SyntaxKind::YieldExpression,
+                pos,
+                YieldExpression {
+                    asterisk_token,
+                    expression,
+                },
+            );
+        }
+        // if the next token is not on the same line as yield.  or we don't have an '*' or
+        // the start of an expression, then this is just a simple "yield" expression.
+        self.finish_node(
+            SyntaxKind::YieldExpression,
+            pos,
+            YieldExpression {
+                asterisk_token: None,
+                expression: None,
+            },
+        )
+    }
+
+    fn parse_simple_arrow_function_expression(
+        &mut self,
+        pos: usize,
+        identifier: Arc<Node>,
+        allow_return_type_in_arrow_function: bool,
+        async_modifier: Option<NodeList>,
+    ) -> Arc<Node> {
+        debug_assert_eq!(
+            self.token,
+            SyntaxKind::EqualsGreaterThanToken,
+            "parseSimpleArrowFunctionExpression should only have been called if we had a =>"
+        );
+        let parameter_loc = identifier.loc;
+        let parameter = self.finish_node_with_end(
+            SyntaxKind::Parameter,
+            parameter_loc.pos(),
+            parameter_loc.end(),
+            NodeFlags::NONE,
+            ParameterDeclaration {
+                modifiers: None,
+                dot_dot_dot_token: None,
+                name: identifier,
+                question_token: None,
+                type_node: None,
+                initializer: None,
+            },
+        );
+        let parameters = NodeList::new(parameter_loc, vec![parameter]);
+        let equals_greater_than_token =
+            self.parse_expected_token(SyntaxKind::EqualsGreaterThanToken);
+        let body = self.parse_arrow_function_expression_body(
+            async_modifier.is_some(),
+            allow_return_type_in_arrow_function,
+        );
+        self.finish_node(
+            SyntaxKind::ArrowFunction,
+            pos,
+            ArrowFunction {
+                modifiers: async_modifier,
+                type_parameters: None,
+                parameters,
+                type_node: None,
+                equals_greater_than_token,
+                body,
+            },
+        )
+    }
+
+    fn try_parse_parenthesized_arrow_function_expression(
+        &mut self,
+        allow_return_type_in_arrow_function: bool,
+    ) -> Option<Arc<Node>> {
+        match self.is_parenthesized_arrow_function_expression() {
+            // It's definitely not a parenthesized arrow function expression.
+            Tristate::False => None,
+            // If we definitely have an arrow function, then we can just parse one, not requiring a
+            // following => or { token. Otherwise, we *might* have an arrow function.  Try to parse
+            // it out, but don't allow any ambiguity, and return 'undefined' if this could be an
+            // expression instead.
+            Tristate::True => self.parse_parenthesized_arrow_function_expression(true, true),
+            Tristate::Unknown => self.parse_possible_parenthesized_arrow_function_expression(
+                allow_return_type_in_arrow_function,
+            ),
+        }
+    }
+
+    //  True        -> We definitely expect a parenthesized arrow function here.
+    //  False       -> There *cannot* be a parenthesized arrow function here.
+    //  Unknown     -> There *might* be a parenthesized arrow function here.
+    //                 Speculatively look ahead to be sure, and rollback if not.
+    fn is_parenthesized_arrow_function_expression(&mut self) -> Tristate {
+        if matches!(
+            self.token,
+            SyntaxKind::OpenParenToken | SyntaxKind::LessThanToken | SyntaxKind::AsyncKeyword
+        ) {
+            return self.look_ahead(Self::is_parenthesized_arrow_function_expression_worker);
+        }
+        if self.token == SyntaxKind::EqualsGreaterThanToken {
+            // ERROR RECOVERY TWEAK:
+            // If we see a standalone => try to parse it as an arrow function expression as that's
+            // likely what the user intended to write.
+            return Tristate::True;
+        }
+        // Definitely not a parenthesized arrow function.
+        Tristate::False
+    }
+
+    fn is_parenthesized_arrow_function_expression_worker(&mut self) -> Tristate {
+        if self.token == SyntaxKind::AsyncKeyword {
+            self.next_token();
+            if self.has_preceding_line_break() {
+                return Tristate::False;
+            }
+            if self.token != SyntaxKind::OpenParenToken && self.token != SyntaxKind::LessThanToken {
+                return Tristate::False;
+            }
+        }
+        let first = self.token;
+        let second = self.next_token();
+        if first == SyntaxKind::OpenParenToken {
+            if second == SyntaxKind::CloseParenToken {
+                // Simple cases: "() =>", "(): ", and "() {".
+                // This is an arrow function with no parameters.
+                // The last one is not actually an arrow function,
+                // but this is probably what the user intended.
+                let third = self.next_token();
+                return match third {
+                    SyntaxKind::EqualsGreaterThanToken
+                    | SyntaxKind::ColonToken
+                    | SyntaxKind::OpenBraceToken => Tristate::True,
+                    _ => Tristate::False,
+                };
+            }
+            // If encounter "([" or "({", this could be the start of a binding pattern.
+            // Examples:
+            //      ([ x ]) => { }
+            //      ({ x }) => { }
+            //      ([ x ])
+            //      ({ x })
+            if second == SyntaxKind::OpenBracketToken || second == SyntaxKind::OpenBraceToken {
+                return Tristate::Unknown;
+            }
+            // Simple case: "(..."
+            // This is an arrow function with a rest parameter.
+            if second == SyntaxKind::DotDotDotToken {
+                return Tristate::True;
+            }
+            // Check for "(xxx yyy", where xxx is a modifier and yyy is an identifier. This
+            // isn't actually allowed, but we want to treat it as a lambda so we can provide
+            // a good error message.
+            if second.is_modifier()
+                && second != SyntaxKind::AsyncKeyword
+                && self.look_ahead(Self::next_token_is_identifier)
+            {
+                if self.next_token() == SyntaxKind::AsKeyword {
+                    // https://github.com/microsoft/TypeScript/issues/44466
+                    return Tristate::False;
+                }
+                return Tristate::True;
+            }
+            // If we had "(" followed by something that's not an identifier,
+            // then this definitely doesn't look like a lambda.  "this" is not
+            // valid, but we want to parse it and then give a semantic error.
+            if !self.is_identifier() && second != SyntaxKind::ThisKeyword {
+                return Tristate::False;
+            }
+            return match self.next_token() {
+                // If we have something like "(a:", then we must have a
+                // type-annotated parameter in an arrow function expression.
+                SyntaxKind::ColonToken => Tristate::True,
+                SyntaxKind::QuestionToken => {
+                    self.next_token();
+                    // If we have "(a?:" or "(a?," or "(a?=" or "(a?)" then it is definitely a lambda.
+                    if matches!(
+                        self.token,
+                        SyntaxKind::ColonToken
+                            | SyntaxKind::CommaToken
+                            | SyntaxKind::EqualsToken
+                            | SyntaxKind::CloseParenToken
+                    ) {
+                        Tristate::True
+                    } else {
+                        // Otherwise it is definitely not a lambda.
+                        Tristate::False
+                    }
+                }
+                // If we have "(a," or "(a=" or "(a)" this *could* be an arrow function
+                SyntaxKind::CommaToken | SyntaxKind::EqualsToken | SyntaxKind::CloseParenToken => {
+                    Tristate::Unknown
+                }
+                // It is definitely not an arrow function
+                _ => Tristate::False,
+            };
+        }
+
+        debug_assert_eq!(first, SyntaxKind::LessThanToken);
+        // If we have "<" not followed by an identifier,
+        // then this definitely is not an arrow function.
+        if !self.is_identifier() && self.token != SyntaxKind::ConstKeyword {
+            return Tristate::False;
+        }
+        // JSX overrides
+        if self.language_variant == LanguageVariant::JSX {
+            let is_arrow_function_in_jsx = self.look_ahead(|p| {
+                p.parse_optional(SyntaxKind::ConstKeyword);
+                let third = p.next_token();
+                if third == SyntaxKind::ExtendsKeyword {
+                    let fourth = p.next_token();
+                    return !matches!(
+                        fourth,
+                        SyntaxKind::EqualsToken
+                            | SyntaxKind::GreaterThanToken
+                            | SyntaxKind::SlashToken
+                    );
+                }
+                third == SyntaxKind::CommaToken || third == SyntaxKind::EqualsToken
+            });
+            if is_arrow_function_in_jsx {
+                return Tristate::True;
+            }
+            return Tristate::False;
+        }
+        // This *could* be a parenthesized arrow function.
+        Tristate::Unknown
+    }
+
+    fn parse_possible_parenthesized_arrow_function_expression(
+        &mut self,
+        allow_return_type_in_arrow_function: bool,
+    ) -> Option<Arc<Node>> {
+        let token_pos = self.scanner.token_start();
+        if self.not_parenthesized_arrow.contains(&token_pos) {
+            return None;
+        }
+        let result = self.try_parse(|p| {
+            p.parse_parenthesized_arrow_function_expression(
+                false,
+                allow_return_type_in_arrow_function,
+            )
+        });
+        if result.is_none() {
+            self.not_parenthesized_arrow.insert(token_pos);
+        }
+        result
+    }
+
+    fn try_parse_async_simple_arrow_function_expression(
+        &mut self,
+        allow_return_type_in_arrow_function: bool,
+    ) -> Option<Arc<Node>> {
+        // We do a check here so that we won't be doing unnecessarily call to "lookAhead"
+        if self.token == SyntaxKind::AsyncKeyword
+            && self.look_ahead(Self::is_un_parenthesized_async_arrow_function_worker)
+        {
+            let pos = self.node_pos();
+            let async_modifier = self.parse_modifiers_for_arrow_function();
+            let expression = self.parse_binary_expression_or_higher(precedence::LOWEST);
+            return Some(self.parse_simple_arrow_function_expression(
+                pos,
+                expression,
+                allow_return_type_in_arrow_function,
+                async_modifier,
+            ));
+        }
+        None
+    }
+
+    fn is_un_parenthesized_async_arrow_function_worker(&mut self) -> bool {
+        // AsyncArrowFunctionExpression:
+        //      1) async[no LineTerminator here]AsyncArrowBindingIdentifier[?Yield][no LineTerminator here]=>AsyncConciseBody[?In]
+        //      2) CoverCallExpressionAndAsyncArrowHead[?Yield, ?Await][no LineTerminator here]=>AsyncConciseBody[?In]
+        if self.token == SyntaxKind::AsyncKeyword {
+            self.next_token();
+            // If the "async" is followed by "=>" token then it is not a beginning of an async arrow-function
+            // but instead a simple arrow-function which will be parsed inside "parseAssignmentExpressionOrHigher"
+            if self.has_preceding_line_break() || self.token == SyntaxKind::EqualsGreaterThanToken {
+                return false;
+            }
+            // Check for un-parenthesized AsyncArrowFunction
+            let expression = self.parse_binary_expression_or_higher(precedence::LOWEST);
+            if !self.has_preceding_line_break()
+                && expression.kind == SyntaxKind::Identifier
+                && self.token == SyntaxKind::EqualsGreaterThanToken
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn parse_modifiers_for_arrow_function(&mut self) -> Option<NodeList> {
+        if self.token == SyntaxKind::AsyncKeyword {
+            let pos = self.node_pos();
+            let modifier = self.parse_token_node();
+            return Some(self.create_node_list(pos, vec![modifier]));
+        }
+        None
+    }
+
+    fn parse_parenthesized_arrow_function_expression(
+        &mut self,
+        allow_ambiguity: bool,
+        allow_return_type_in_arrow_function: bool,
+    ) -> Option<Arc<Node>> {
+        let pos = self.node_pos();
+        let modifiers = self.parse_modifiers_for_arrow_function();
+        let is_async = modifiers.is_some();
+        let signature_flags = if is_async {
+            SignatureFlags::AWAIT
+        } else {
+            SignatureFlags::NONE
+        };
+        // Arrow functions are never generators.
+        //
+        // If we're speculatively parsing a signature for a parenthesized arrow function, then
+        // we have to have a complete parameter list.  Otherwise we might see something like
+        // a => (b => c)
+        // And think that "(b =>" was actually a parenthesized arrow function with a missing
+        // close paren.
+        let type_parameters = self.parse_type_parameters();
+
+        let parameters = if !self.parse_expected(SyntaxKind::OpenParenToken) {
+            if !allow_ambiguity {
+                return None;
+            }
+            self.create_missing_list()
+        } else {
+            let parameters = if !allow_ambiguity {
+                self.parse_parameters_worker(signature_flags, allow_ambiguity)?
+            } else {
+                self.parse_parameters_worker(signature_flags, allow_ambiguity)
+                    .unwrap_or_default()
+            };
+            if !self.parse_expected(SyntaxKind::CloseParenToken) && !allow_ambiguity {
+                return None;
+            }
+            parameters
+        };
+
+        let has_return_colon = self.token == SyntaxKind::ColonToken;
+        let type_node = self.parse_return_type(SyntaxKind::ColonToken, false);
+        if type_node.as_ref().is_some_and(|type_node| {
+            !allow_ambiguity && type_has_arrow_function_blocking_parse_error(type_node)
+        }) {
+            return None;
+        }
+
+        // Parsing a signature isn't enough.
+        // Parenthesized arrow signatures often look like other valid expressions.
+        // For instance:
+        //  - "(x = 10)" is an assignment expression parsed as a signature with a default parameter value.
+        //  - "(x,y)" is a comma expression parsed as a signature with two parameters.
+        //  - "a ? (b): c" will have "(b):" parsed as a signature with a return type annotation.
+        //
+        // So we need just a bit of lookahead to ensure that it can only be a signature.
+        if !allow_ambiguity
+            && self.token != SyntaxKind::EqualsGreaterThanToken
+            && self.token != SyntaxKind::OpenBraceToken
+        {
+            // Returning None here will cause our caller to rewind to where we started from.
+            return None;
+        }
+
+        // If we have an arrow, then try to parse the body. Even if not, try to parse if we have an opening brace, just in case we're in an error state.
+        let last_token = self.token;
+        let equals_greater_than_token =
+            self.parse_expected_token(SyntaxKind::EqualsGreaterThanToken);
+        let body = if last_token == SyntaxKind::EqualsGreaterThanToken
+            || last_token == SyntaxKind::OpenBraceToken
+        {
+            self.parse_arrow_function_expression_body(is_async, allow_return_type_in_arrow_function)
+        } else {
+            self.parse_identifier()
+        };
+
+        // Given:
+        //     x ? (a): b => c
+        // We might have misinterpreted "(a): b" as a return type annotation, so only accept
+        // the arrow function if it is followed by another colon, as in:
+        //     a ? (x): string => x : y
+        if !allow_return_type_in_arrow_function
+            && has_return_colon
+            && self.token != SyntaxKind::ColonToken
+        {
+            return None;
+        }
+
+        Some(self.finish_node(
+            SyntaxKind::ArrowFunction,
+            pos,
+            ArrowFunction {
+                modifiers,
+                type_parameters,
+                parameters,
+                type_node,
+                equals_greater_than_token,
+                body,
+            },
+        ))
+    }
+
+    fn parse_arrow_function_expression_body(
+        &mut self,
+        is_async: bool,
+        allow_return_type_in_arrow_function: bool,
+    ) -> Arc<Node> {
+        let async_flags = if is_async {
+            SignatureFlags::AWAIT
+        } else {
+            SignatureFlags::NONE
+        };
+        if self.token == SyntaxKind::OpenBraceToken {
+            return self.parse_function_block(async_flags, None);
+        }
+        if self.token != SyntaxKind::SemicolonToken
+            && self.token != SyntaxKind::FunctionKeyword
+            && self.token != SyntaxKind::ClassKeyword
+            && self.is_start_of_statement()
+            && !self.is_start_of_expression_statement()
+        {
+            // Check if we got a plain statement (i.e. no expression-statements, no function/class expressions/declarations)
+            //
+            // Here we try to recover from a potential error situation in the case where the
+            // user meant to supply a block. For example, if the user wrote:
+            //
+            //  a =>
+            //      let v = 0;
+            //  }
+            //
+            // they may be missing an open brace.  Check to see if that's the case so we can
+            // try to recover better.  If we don't do this, then the next close curly we see may end
+            // up preemptively closing the containing construct.
+            //
+            // Note: even when 'IgnoreMissingOpenBrace' is passed, parseBody will still error.
+            return self.parse_function_block(
+                SignatureFlags::IGNORE_MISSING_OPEN_BRACE | async_flags,
+                None,
+            );
+        }
+        let saved = self.context_flags;
+        self.set_context_flag(is_async, NodeFlags::AWAIT_CONTEXT);
+        let body = self.parse_assignment_expression_or_higher(allow_return_type_in_arrow_function);
+        self.context_flags = saved;
+        body
+    }
+
+    fn parse_conditional_expression_rest(
+        &mut self,
+        left_operand: Arc<Node>,
+        pos: usize,
+        allow_return_type_in_arrow_function: bool,
+    ) -> Arc<Node> {
+        // Note: we are passed in an expression which was produced from parseBinaryExpressionOrHigher.
+        let Some(question_token) = self.parse_optional_token(SyntaxKind::QuestionToken) else {
+            return left_operand;
+        };
+        let when_true = self.do_outside_of_context(
+            NodeFlags::DISALLOW_IN_CONTEXT | NodeFlags::DECORATOR_CONTEXT,
+            |p| p.parse_assignment_expression_or_higher(false),
+        );
+        let colon_token = self.parse_expected_token(SyntaxKind::ColonToken);
+        let when_false = if !node_is_missing(&colon_token) {
+            self.parse_assignment_expression_or_higher(allow_return_type_in_arrow_function)
+        } else {
+            self.create_missing_identifier_with_error(
+                false,
+                diagnostics::D_0_EXPECTED_1005,
+                &[token_text(SyntaxKind::ColonToken)],
+            )
+        };
+        self.finish_node(
+            SyntaxKind::ConditionalExpression,
+            pos,
+            ConditionalExpression {
+                condition: left_operand,
+                question_token,
+                when_true,
+                colon_token,
+                when_false,
+            },
+        )
+    }
+
+    fn parse_binary_expression_or_higher(&mut self, precedence: i32) -> Arc<Node> {
+        let pos = self.node_pos();
+        let left_operand = self.parse_unary_expression_or_higher();
+        self.parse_binary_expression_rest(precedence, left_operand, pos)
+    }
+
+    fn parse_binary_expression_rest(
+        &mut self,
+        precedence: i32,
+        mut left_operand: Arc<Node>,
+        pos: usize,
+    ) -> Arc<Node> {
+        loop {
+            // We either have a binary operator here, or we're finished.  We call
+            // reScanGreaterToken so that we merge token sequences like > and = into >=
+            self.re_scan_greater_token();
+            let new_precedence = get_binary_operator_precedence(self.token);
+
+            // Check the precedence to see if we should "take" this operator
+            // - For left associative operator (all operator but **), consume the operator,
+            //   recursively call the function below, and parse binaryExpression as a rightOperand
+            //   of the caller if the new precedence of the operator is greater then or equal to the current precedence.
+            //   For example:
+            //      a - b - c;
+            //            ^token; leftOperand = b. Return b to the caller as a rightOperand
+            //      a * b - c
+            //            ^token; leftOperand = b. Return b to the caller as a rightOperand
+            //      a - b * c;
+            //            ^token; leftOperand = b. Return b * c to the caller as a rightOperand
+            // - For right associative operator (**), consume the operator, recursively call the function
+            //   and parse binaryExpression as a rightOperand of the caller if the new precedence of
+            //   the operator is strictly grater than the current precedence
+            //   For example:
+            //      a ** b ** c;
+            //             ^^token; leftOperand = b. Return b ** c to the caller as a rightOperand
+            //      a - b ** c;
+            //            ^^token; leftOperand = b. Return b ** c to the caller as a rightOperand
+            //      a ** b - c
+            //             ^token; leftOperand = b. Return b to the caller as a rightOperand
+            let consume_current_operator = if self.token == SyntaxKind::AsteriskAsteriskToken {
+                new_precedence >= precedence
+            } else {
+                new_precedence > precedence
+            };
+            if !consume_current_operator {
+                break;
+            }
+            if self.token == SyntaxKind::InKeyword && self.in_disallow_in_context() {
+                break;
+            }
+            if self.token == SyntaxKind::AsKeyword || self.token == SyntaxKind::SatisfiesKeyword {
+                // Make sure we *do* perform ASI for constructs like this:
+                //    var x = foo
+                //    as (Bar)
+                // This should be parsed as an initialized variable, followed
+                // by a function call to 'as' with the argument 'Bar'
+                if self.has_preceding_line_break() {
+                    break;
+                }
+                let keyword_kind = self.token;
+                self.next_token();
+                let type_node = self.parse_type();
+                left_operand = if keyword_kind == SyntaxKind::SatisfiesKeyword {
+                    self.finish_node(
+                        SyntaxKind::SatisfiesExpression,
+                        left_operand.pos(),
+                        SatisfiesExpression {
+                            expression: left_operand,
+                            type_node,
+                        },
+                    )
+                } else {
+                    self.finish_node(
+                        SyntaxKind::AsExpression,
+                        left_operand.pos(),
+                        AsExpression {
+                            expression: left_operand,
+                            type_node,
+                        },
+                    )
+                };
+            } else {
+                let operator_token = self.parse_token_node();
+                let right = self.parse_binary_expression_or_higher(new_precedence);
+                left_operand =
+                    self.make_binary_expression(left_operand, operator_token, right, pos);
+            }
+        }
+        left_operand
+    }
+
+    fn make_binary_expression(
+        &mut self,
+        left: Arc<Node>,
+        operator_token: Arc<Node>,
+        right: Arc<Node>,
+        pos: usize,
+    ) -> Arc<Node> {
+        self.finish_node(
+            SyntaxKind::BinaryExpression,
+            pos,
+            BinaryExpression {
+                left,
+                operator_token,
+                right,
+            },
+        )
+    }
+
+    fn parse_unary_expression_or_higher(&mut self) -> Arc<Node> {
+        // ES7 UpdateExpression:
+        //      1) LeftHandSideExpression[?Yield]
+        //      2) LeftHandSideExpression[?Yield][no LineTerminator here]++
+        //      3) LeftHandSideExpression[?Yield][no LineTerminator here]--
+        //      4) ++UnaryExpression[?Yield]
+        //      5) --UnaryExpression[?Yield]
+        if self.is_update_expression() {
+            let pos = self.node_pos();
+            let update_expression = self.parse_update_expression();
+            if self.token == SyntaxKind::AsteriskAsteriskToken {
+                let precedence = get_binary_operator_precedence(self.token);
+                return self.parse_binary_expression_rest(precedence, update_expression, pos);
+            }
+            return update_expression;
+        }
+
+        // ES7 UnaryExpression:
+        //      1) UpdateExpression[?yield]
+        //      2) delete UpdateExpression[?yield]
+        //      3) void UpdateExpression[?yield]
+        //      4) typeof UpdateExpression[?yield]
+        //      5) + UpdateExpression[?yield]
+        //      6) - UpdateExpression[?yield]
+        //      7) ~ UpdateExpression[?yield]
+        //      8) ! UpdateExpression[?yield]
+        let unary_operator = self.token;
+        let simple_unary_expression = self.parse_simple_unary_expression();
+        if self.token == SyntaxKind::AsteriskAsteriskToken {
+            let pos = skip_trivia(self.scanner.text(), simple_unary_expression.pos());
+            let end = simple_unary_expression.end();
+            if simple_unary_expression.kind == SyntaxKind::TypeAssertionExpression {
+                self.parse_error_at_range(
+                    pos,
+                    end,
+                    diagnostics::A_TYPE_ASSERTION_EXPRESSION_IS_NOT_ALLOWED_IN_THE_LEFT_HAND_SIDE_OF_AN_EXPONENTIATION_EXPRESSION_CONSIDER_ENCLOSING_THE_EXPRESSION_IN_PARENTHESES_17007,
+                    &[],
+                );
+            } else {
+                self.parse_error_at_range(
+                    pos,
+                    end,
+                    diagnostics::AN_UNARY_EXPRESSION_WITH_THE_0_OPERATOR_IS_NOT_ALLOWED_IN_THE_LEFT_HAND_SIDE_OF_AN_EXPONENTIATION_EXPRESSION_CONSIDER_ENCLOSING_THE_EXPRESSION_IN_PARENTHESES_17006,
+                    &[token_text(unary_operator)],
+                );
+            }
+        }
+        simple_unary_expression
+    }
+
+    /// Check if the current token can possibly be an ES7 increment expression.
+    ///
+    /// ES7 UpdateExpression:
+    ///      LeftHandSideExpression[?Yield]
+    ///      LeftHandSideExpression[?Yield][no LineTerminator here]++
+    ///      LeftHandSideExpression[?Yield][no LineTerminator here]--
+    ///      ++LeftHandSideExpression[?Yield]
+    ///      --LeftHandSideExpression[?Yield]
+    fn is_update_expression(&self) -> bool {
+        // This function is called inside parseUnaryExpression to decide
+        // whether to call parseSimpleUnaryExpression or call parseUpdateExpression directly
+        match self.token {
+            SyntaxKind::PlusToken
+            | SyntaxKind::MinusToken
+            | SyntaxKind::TildeToken
+            | SyntaxKind::ExclamationToken
+            | SyntaxKind::DeleteKeyword
+            | SyntaxKind::TypeOfKeyword
+            | SyntaxKind::VoidKeyword
+            | SyntaxKind::AwaitKeyword => false,
+            // If we are not in JSX context, we are parsing TypeAssertion which is an UnaryExpression.
+            // Otherwise the token is part of a JSXElement.
+            SyntaxKind::LessThanToken => self.language_variant == LanguageVariant::JSX,
+            _ => true,
+        }
+    }
+
+    fn parse_simple_unary_expression(&mut self) -> Arc<Node> {
+        match self.token {
+            SyntaxKind::PlusToken
+            | SyntaxKind::MinusToken
+            | SyntaxKind::TildeToken
+            | SyntaxKind::ExclamationToken => self.parse_prefix_unary_expression(),
+            SyntaxKind::DeleteKeyword => {
+                let pos = self.node_pos();
+                self.next_token();
+                let expression = self.parse_simple_unary_expression();
+                self.finish_node(
+                    SyntaxKind::DeleteExpression,
+                    pos,
+                    DeleteExpression { expression },
+                )
+            }
+            SyntaxKind::TypeOfKeyword => {
+                let pos = self.node_pos();
+                self.next_token();
+                let expression = self.parse_simple_unary_expression();
+                self.finish_node(
+                    SyntaxKind::TypeOfExpression,
+                    pos,
+                    TypeOfExpression { expression },
+                )
+            }
+            SyntaxKind::VoidKeyword => {
+                let pos = self.node_pos();
+                self.next_token();
+                let expression = self.parse_simple_unary_expression();
+                self.finish_node(
+                    SyntaxKind::VoidExpression,
+                    pos,
+                    VoidExpression { expression },
+                )
+            }
+            SyntaxKind::LessThanToken => {
+                // Just like in parseUpdateExpression, we need to avoid parsing type assertions when
+                // in JSX and we see an expression like "+ <foo> bar".
+                if self.language_variant == LanguageVariant::JSX {
+                    return self.parse_jsx_element_or_self_closing_element_or_fragment(
+                        true, None, None, true,
+                    );
+                }
+                // This is modified UnaryExpression grammar in TypeScript
+                //  UnaryExpression (modified):
+                //      < type > UnaryExpression
+                self.parse_type_assertion()
+            }
+            SyntaxKind::AwaitKeyword => {
+                if self.is_await_expression() {
+                    let pos = self.node_pos();
+                    self.next_token();
+                    let expression = self.parse_simple_unary_expression();
+                    return self.finish_node(
+                        SyntaxKind::AwaitExpression,
+                        pos,
+                        AwaitExpression { expression },
+                    );
+                }
+                self.parse_update_expression()
+            }
+            _ => self.parse_update_expression(),
+        }
+    }
+
+    fn is_await_expression(&mut self) -> bool {
+        if self.token == SyntaxKind::AwaitKeyword {
+            if self.in_await_context() {
+                return true;
+            }
+            // here we are using similar heuristics as 'isYieldExpression'
+            return self
+                .look_ahead(Self::next_token_is_identifier_or_keyword_or_literal_on_same_line);
+        }
+        false
+    }
+
+    fn parse_prefix_unary_expression(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let operator = self.token;
+        self.next_token();
+        let operand = self.parse_simple_unary_expression();
+        self.finish_node(
+            SyntaxKind::PrefixUnaryExpression,
+            pos,
+            PrefixUnaryExpression { operator, operand },
+        )
+    }
+
+    fn parse_update_expression(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        if self.token == SyntaxKind::PlusPlusToken || self.token == SyntaxKind::MinusMinusToken {
+            let operator = self.token;
+            self.next_token();
+            let operand = self.parse_left_hand_side_expression_or_higher();
+            return self.finish_node(
+                SyntaxKind::PrefixUnaryExpression,
+                pos,
+                PrefixUnaryExpression { operator, operand },
+            );
+        }
+        if self.language_variant == LanguageVariant::JSX
+            && self.token == SyntaxKind::LessThanToken
+            && self.look_ahead(Self::next_token_is_identifier_or_keyword_or_greater_than)
+        {
+            // JSXElement is part of primaryExpression
+            return self
+                .parse_jsx_element_or_self_closing_element_or_fragment(true, None, None, false);
+        }
+        let expression = self.parse_left_hand_side_expression_or_higher();
+        if (self.token == SyntaxKind::PlusPlusToken || self.token == SyntaxKind::MinusMinusToken)
+            && !self.has_preceding_line_break()
+        {
+            let operator = self.token;
+            self.next_token();
+            return self.finish_node(
+                SyntaxKind::PostfixUnaryExpression,
+                pos,
+                PostfixUnaryExpression {
+                    operand: expression,
+                    operator,
+                },
+            );
+        }
+        expression
+    }
+
+    fn parse_type_assertion(&mut self) -> Arc<Node> {
+        debug_assert_ne!(
+            self.language_variant,
+            LanguageVariant::JSX,
+            "Type assertions should never be parsed in JSX; they should be parsed as comparisons or JSX elements/fragments."
+        );
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::LessThanToken);
+        let type_node = self.parse_type();
+        self.parse_expected(SyntaxKind::GreaterThanToken);
+        let expression = self.parse_simple_unary_expression();
+        self.finish_node(
+            SyntaxKind::TypeAssertionExpression,
+            pos,
+            TypeAssertion {
+                type_node,
+                expression,
+            },
+        )
+    }
+
+    fn parse_left_hand_side_expression_or_higher(&mut self) -> Arc<Node> {
+        // Original Ecma:
+        // LeftHandSideExpression: See 11.2
+        //      NewExpression
+        //      CallExpression
+        //
+        // Our simplification:
+        //
+        // LeftHandSideExpression: See 11.2
+        //      MemberExpression
+        //      CallExpression
+        //
+        // See comment in parseMemberExpressionOrHigher on how we replaced NewExpression with
+        // MemberExpression to make our lives easier.
+        //
+        // to best understand the below code, it's important to see how CallExpression expands
+        // out into its own productions:
+        //
+        // CallExpression:
+        //      MemberExpression Arguments
+        //      CallExpression Arguments
+        //      CallExpression[Expression]
+        //      CallExpression.IdentifierName
+        //      import (AssignmentExpression)
+        //      super Arguments
+        //      super.IdentifierName
+        //
+        // Because of the recursion in these calls, we need to bottom out first. There are three
+        // bottom out states we can run into: 1) We see 'super' which must start either of
+        // the last two CallExpression productions. 2) We see 'import' which must start import call.
+        // 3)we have a MemberExpression which either completes the LeftHandSideExpression,
+        // or starts the beginning of the first four CallExpression productions.
+        let pos = self.node_pos();
+        let expression = if self.token == SyntaxKind::ImportKeyword {
+            if self.look_ahead(Self::next_token_is_open_paren_or_less_than) {
+                // We don't want to eagerly consume all import keyword as import call expression so we look ahead to find "("
+                // For example:
+                //      var foo3 = require("subfolder
+                //      import * as foo1 from "module-foo"; // Error: Unterminated string literal.
+                //      import * as foo2 from "module-foo"; // Error: Unterminated string literal.
+                self.parse_token_node()
+            } else if self.look_ahead(Self::next_token_is_dot) {
+                // This is an 'import.*' metaproperty (i.e. 'import.meta')
+                self.next_token(); // advance past the 'import'
+                self.next_token(); // advance past the dot
+                let name = self.parse_identifier_name();
+                self.finish_node(
+                    SyntaxKind::MetaProperty,
+                    pos,
+                    MetaProperty {
+                        keyword_token: SyntaxKind::ImportKeyword,
+                        name,
+                    },
+                )
+            } else {
+                self.parse_member_expression_or_higher()
+            }
+        } else if self.token == SyntaxKind::SuperKeyword {
+            self.parse_super_expression()
+        } else {
+            self.parse_member_expression_or_higher()
+        };
+
+        // Now, we *may* be complete.  However, we might have consumed the start of a
+        // CallExpression or OptionalExpression.  As such, we need to consume the rest
+        // of it here to be complete.
+        self.parse_call_expression_rest(pos, expression)
+    }
+
+    fn parse_member_expression_or_higher(&mut self) -> Arc<Node> {
+        // Note: to make our lives simpler, we decompose the NewExpression productions and
+        // place ObjectCreationExpression and FunctionExpression into PrimaryExpression.
+        // like so:
+        //
+        //   PrimaryExpression : See 11.1
+        //      this
+        //      Identifier
+        //      Literal
+        //      ArrayLiteral
+        //      ObjectLiteral
+        //      (Expression)
+        //      FunctionExpression
+        //      new MemberExpression Arguments?
+        //
+        //   MemberExpression : See 11.2
+        //      PrimaryExpression
+        //      MemberExpression[Expression]
+        //      MemberExpression.IdentifierName
+        //
+        //   CallExpression : See 11.2
+        //      MemberExpression
+        //      CallExpression Arguments
+        //      CallExpression[Expression]
+        //      CallExpression.IdentifierName
+        //
+        // Technically this is ambiguous.  i.e. CallExpression defines:
+        //
+        //   CallExpression:
+        //      CallExpression Arguments
+        //
+        // If you see: "new Foo()"
+        //
+        // Then that could be treated as a single ObjectCreationExpression, or it could be
+        // treated as the invocation of "new Foo".  We disambiguate that in code (to match
+        // the original grammar) by making sure that if we see an ObjectCreationExpression
+        // we always consume arguments if they are there. So we treat "new Foo()" as an
+        // object creation only, and not at all as an invocation.  Another way to think
+        // about this is that for every "new" that we see, we will consume an argument list if
+        // it is there as part of the *associated* object creation node.  Any additional
+        // argument lists we see, will become invocation expressions.
+        //
+        // Because there are no other places in the grammar now that refer to FunctionExpression
+        // or ObjectCreationExpression, it is safe to push down into the PrimaryExpression
+        // production.
+        //
+        // Because CallExpression and MemberExpression are left recursive, we need to bottom out
+        // of the recursion immediately.  So we parse out a primary expression to start with.
+        let pos = self.node_pos();
+        let expression = self.parse_primary_expression();
+        self.parse_member_expression_rest(pos, expression, true)
+    }
+
+    fn parse_super_expression(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let mut expression = self.parse_token_node();
+        if self.token == SyntaxKind::LessThanToken {
+            let start_pos = self.node_pos();
+            if let Some(type_arguments) = self.try_parse(Self::parse_type_arguments_in_expression) {
+                let end = self.node_pos();
+                self.parse_error_at_range(
+                    start_pos,
+                    end,
+                    diagnostics::SUPER_MAY_NOT_USE_TYPE_ARGUMENTS_2754,
+                    &[],
+                );
+                if !self.is_template_start_of_tagged_template() {
+                    expression = self.finish_node(
+                        SyntaxKind::ExpressionWithTypeArguments,
+                        pos,
+                        ExpressionWithTypeArguments {
+                            expression,
+                            type_arguments: Some(type_arguments),
+                        },
+                    );
+                }
+            }
+        }
+        if matches!(
+            self.token,
+            SyntaxKind::OpenParenToken | SyntaxKind::DotToken | SyntaxKind::OpenBracketToken
+        ) {
+            return expression;
+        }
+        // If we have seen "super" it must be followed by '(' or '.'.
+        // If it wasn't then just try to parse out a '.' and report an error.
+        self.parse_expected_with_diagnostic(
+            SyntaxKind::DotToken,
+            Some(diagnostics::SUPER_MUST_BE_FOLLOWED_BY_AN_ARGUMENT_LIST_OR_MEMBER_ACCESS_1034),
+            true,
+        );
+        // private names will never work with `super` (`super.#foo`), but that's a semantic error, not syntactic
+        let name = self.parse_right_side_of_dot(true, true);
+        self.finish_node(
+            SyntaxKind::PropertyAccessExpression,
+            pos,
+            PropertyAccessExpression {
+                expression,
+                question_dot_token: None,
+                name,
+            },
+        )
+    }
+
+    fn is_start_of_optional_property_or_element_access_chain(&mut self) -> bool {
+        self.token == SyntaxKind::QuestionDotToken
+            && self
+                .look_ahead(Self::next_token_is_identifier_or_keyword_or_open_bracket_or_template)
+    }
+
+    fn is_template_start_of_tagged_template(&self) -> bool {
+        self.token == SyntaxKind::NoSubstitutionTemplateLiteral
+            || self.token == SyntaxKind::TemplateHead
+    }
+
+    fn parse_member_expression_rest(
+        &mut self,
+        pos: usize,
+        mut expression: Arc<Node>,
+        allow_optional_chain: bool,
+    ) -> Arc<Node> {
+        loop {
+            let mut question_dot_token = None;
+            let is_property_access;
+            if allow_optional_chain && self.is_start_of_optional_property_or_element_access_chain()
+            {
+                question_dot_token = Some(self.parse_expected_token(SyntaxKind::QuestionDotToken));
+                is_property_access = scanner::is_identifier_or_keyword(self.token);
+            } else {
+                is_property_access = self.parse_optional(SyntaxKind::DotToken);
+            }
+            if is_property_access {
+                expression =
+                    self.parse_property_access_expression_rest(pos, expression, question_dot_token);
+                continue;
+            }
+
+            // when in the [Decorator] context, we do not parse ElementAccess as it could be part of a ComputedPropertyName
+            if (question_dot_token.is_some() || !self.in_decorator_context())
+                && self.parse_optional(SyntaxKind::OpenBracketToken)
+            {
+                expression =
+                    self.parse_element_access_expression_rest(pos, expression, question_dot_token);
+                continue;
+            }
+
+            if self.is_template_start_of_tagged_template() {
+                // Absorb type arguments into TemplateExpression when preceding expression is ExpressionWithTypeArguments
+                expression = match split_expression_with_type_arguments(
+                    &expression,
+                    question_dot_token.is_none(),
+                ) {
+                    Some((tag, type_arguments)) => self.parse_tagged_template_rest(
+                        pos,
+                        tag,
+                        question_dot_token,
+                        type_arguments,
+                    ),
+                    None => {
+                        self.parse_tagged_template_rest(pos, expression, question_dot_token, None)
+                    }
+                };
+                continue;
+            }
+
+            if question_dot_token.is_none() {
+                if self.token == SyntaxKind::ExclamationToken && !self.has_preceding_line_break() {
+                    self.next_token();
+                    expression = self.finish_node(
+                        SyntaxKind::NonNullExpression,
+                        pos,
+                        NonNullExpression { expression },
+                    );
+                    continue;
+                }
+                if let Some(type_arguments) =
+                    self.try_parse(Self::parse_type_arguments_in_expression)
+                {
+                    expression = self.finish_node(
+                        SyntaxKind::ExpressionWithTypeArguments,
+                        pos,
+                        ExpressionWithTypeArguments {
+                            expression,
+                            type_arguments: Some(type_arguments),
+                        },
+                    );
+                    continue;
+                }
+            }
+            return expression;
+        }
+    }
+
+    fn parse_property_access_expression_rest(
+        &mut self,
+        pos: usize,
+        expression: Arc<Node>,
+        question_dot_token: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        let name = self.parse_right_side_of_dot(true, true);
+        let is_optional_chain =
+            question_dot_token.is_some() || is_optional_chain_continuation(&expression);
+        if is_optional_chain && name.kind == SyntaxKind::PrivateIdentifier {
+            self.parse_error_at_node(
+                &name,
+                diagnostics::AN_OPTIONAL_CHAIN_CANNOT_CONTAIN_PRIVATE_IDENTIFIERS_18030,
+                &[],
+            );
+        }
+        if expression.kind == SyntaxKind::ExpressionWithTypeArguments
+            && let Some(type_arguments) = expression
+                .as_expression_with_type_arguments()
+                .type_arguments
+                .as_ref()
+        {
+            let error_pos = type_arguments.pos() - 1;
+            let error_end = skip_trivia(self.scanner.text(), type_arguments.end()) + 1;
+            self.parse_error_at_range(
+                error_pos,
+                error_end,
+                diagnostics::AN_INSTANTIATION_EXPRESSION_CANNOT_BE_FOLLOWED_BY_A_PROPERTY_ACCESS_1477,
+                &[],
+            );
+        }
+        let flags = if is_optional_chain {
+            NodeFlags::OPTIONAL_CHAIN
+        } else {
+            NodeFlags::NONE
+        };
+        self.finish_node_with_flags(
+            SyntaxKind::PropertyAccessExpression,
+            pos,
+            flags,
+            PropertyAccessExpression {
+                expression,
+                question_dot_token,
+                name,
+            },
+        )
+    }
+
+    fn parse_element_access_expression_rest(
+        &mut self,
+        pos: usize,
+        expression: Arc<Node>,
+        question_dot_token: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        let argument_expression = if self.token == SyntaxKind::CloseBracketToken {
+            self.create_missing_identifier_with_error(
+                true,
+                diagnostics::AN_ELEMENT_ACCESS_EXPRESSION_SHOULD_TAKE_AN_ARGUMENT_1011,
+                &[],
+            )
+        } else {
+            self.allow_in_and(Self::parse_expression)
+        };
+        self.parse_expected(SyntaxKind::CloseBracketToken);
+        let flags = if question_dot_token.is_some() || is_optional_chain_continuation(&expression) {
+            NodeFlags::OPTIONAL_CHAIN
+        } else {
+            NodeFlags::NONE
+        };
+        self.finish_node_with_flags(
+            SyntaxKind::ElementAccessExpression,
+            pos,
+            flags,
+            ElementAccessExpression {
+                expression,
+                question_dot_token,
+                argument_expression,
+            },
+        )
+    }
+
+    fn parse_tagged_template_rest(
+        &mut self,
+        pos: usize,
+        tag: Arc<Node>,
+        question_dot_token: Option<Arc<Node>>,
+        type_arguments: Option<NodeList>,
+    ) -> Arc<Node> {
+        let template = if self.token == SyntaxKind::NoSubstitutionTemplateLiteral {
+            self.re_scan_template_token(true);
+            self.parse_literal_node()
+        } else {
+            self.parse_template_expression(true)
+        };
+        let flags = if question_dot_token.is_some() || tag.flags.contains(NodeFlags::OPTIONAL_CHAIN)
+        {
+            NodeFlags::OPTIONAL_CHAIN
+        } else {
+            NodeFlags::NONE
+        };
+        self.finish_node_with_flags(
+            SyntaxKind::TaggedTemplateExpression,
+            pos,
+            flags,
+            TaggedTemplateExpression {
+                tag,
+                question_dot_token,
+                type_arguments,
+                template,
+            },
+        )
+    }
+
+    fn parse_call_expression_rest(&mut self, pos: usize, mut expression: Arc<Node>) -> Arc<Node> {
+        loop {
+            expression = self.parse_member_expression_rest(pos, expression, true);
+            let mut type_arguments = None;
+            let question_dot_token = self.parse_optional_token(SyntaxKind::QuestionDotToken);
+            if question_dot_token.is_some() {
+                type_arguments = self.try_parse(Self::parse_type_arguments_in_expression);
+                if self.is_template_start_of_tagged_template() {
+                    expression = self.parse_tagged_template_rest(
+                        pos,
+                        expression,
+                        question_dot_token,
+                        type_arguments,
+                    );
+                    continue;
+                }
+            }
+            if type_arguments.is_some() || self.token == SyntaxKind::OpenParenToken {
+                // Absorb type arguments into CallExpression when preceding expression is ExpressionWithTypeArguments
+                if let Some((callee, absorbed)) =
+                    split_expression_with_type_arguments(&expression, question_dot_token.is_none())
+                {
+                    expression = callee;
+                    type_arguments = absorbed;
+                }
+                let arguments = self.parse_argument_list();
+                let flags = if question_dot_token.is_some()
+                    || is_optional_chain_continuation(&expression)
+                {
+                    NodeFlags::OPTIONAL_CHAIN
+                } else {
+                    NodeFlags::NONE
+                };
+                expression = self.finish_node_with_flags(
+                    SyntaxKind::CallExpression,
+                    pos,
+                    flags,
+                    CallExpression {
+                        expression,
+                        question_dot_token,
+                        type_arguments,
+                        arguments,
+                    },
+                );
+                continue;
+            }
+            if question_dot_token.is_some() {
+                // We parsed `?.` but then failed to parse anything, so report a missing identifier here.
+                let name = self.create_missing_identifier_with_error(
+                    false,
+                    diagnostics::IDENTIFIER_EXPECTED_1003,
+                    &[],
+                );
+                expression = self.finish_node_with_flags(
+                    SyntaxKind::PropertyAccessExpression,
+                    pos,
+                    NodeFlags::OPTIONAL_CHAIN,
+                    PropertyAccessExpression {
+                        expression,
+                        question_dot_token,
+                        name,
+                    },
+                );
+            }
+            break;
+        }
+        expression
+    }
+
+    fn parse_argument_list(&mut self) -> NodeList {
+        self.parse_expected(SyntaxKind::OpenParenToken);
+        let arguments = self.parse_delimited_list(
+            ParsingContext::ArgumentExpressions,
+            Self::parse_argument_expression,
+        );
+        self.parse_expected(SyntaxKind::CloseParenToken);
+        arguments
+    }
+
+    /// Speculatively parses `<...>` after an expression as type arguments
+    ///
+    /// This must be called from within `try_parse`: it consumes tokens even when it returns
+    /// `None`. The list is only accepted if it is properly closed by a `>` and the token that
+    /// follows could not continue a relational expression, which is how tsc tells `f<T>(x)`
+    /// apart from `a < b > c`.
+    fn parse_type_arguments_in_expression(&mut self) -> Option<NodeList> {
+        if self.context_flags.contains(NodeFlags::JAVASCRIPT_FILE) {
+            return None;
+        }
+        if self.re_scan_less_than_token() != SyntaxKind::LessThanToken {
+            return None;
+        }
+        self.next_token();
+        let type_arguments =
+            self.parse_delimited_list(ParsingContext::TypeArguments, Self::parse_type);
+        if self.re_scan_greater_token() != SyntaxKind::GreaterThanToken {
+            // If it doesn't have the closing `>` then it's definitely not an type argument list.
+            return None;
+        }
+        self.next_token();
+
+        // We successfully parsed a type argument list. The next token determines whether we want to
+        // treat it as such. If the type argument list is followed by `(` or a template literal, as in
+        // `f<number>(42)`, we favor the type argument interpretation even though JavaScript would view
+        // it as a relational expression.
+        if self.can_follow_type_arguments_in_expression() {
+            Some(type_arguments)
+        } else {
+            None
+        }
+    }
+
+    fn can_follow_type_arguments_in_expression(&mut self) -> bool {
+        match self.token {
+            // These tokens can follow a type argument list in a call expression.
+            SyntaxKind::OpenParenToken
+            | SyntaxKind::NoSubstitutionTemplateLiteral
+            | SyntaxKind::TemplateHead => true,
+            // A type argument list followed by `<` never makes sense, and a type argument list followed
+            // by `>` is ambiguous with a (re-scanned) `>>` operator, so we disqualify both. Also, in
+            // this context, `+` and `-` are unary operators, not binary operators.
+            SyntaxKind::LessThanToken
+            | SyntaxKind::GreaterThanToken
+            | SyntaxKind::PlusToken
+            | SyntaxKind::MinusToken => false,
+            // We favor the type argument list interpretation when it is immediately followed by
+            // a line break, a binary operator, or something that can't start an expression.
+            _ => {
+                self.has_preceding_line_break()
+                    || self.is_binary_operator()
+                    || !self.is_start_of_expression()
+            }
+        }
+    }
+
+    fn parse_primary_expression(&mut self) -> Arc<Node> {
+        let token = self.token;
+        match token {
+            SyntaxKind::NoSubstitutionTemplateLiteral
+            | SyntaxKind::NumericLiteral
+            | SyntaxKind::BigIntLiteral
+            | SyntaxKind::StringLiteral => return self.parse_literal_node(),
+            SyntaxKind::ThisKeyword
+            | SyntaxKind::SuperKeyword
+            | SyntaxKind::NullKeyword
+            | SyntaxKind::TrueKeyword
+            | SyntaxKind::FalseKeyword => return self.parse_token_node(),
+            SyntaxKind::OpenParenToken => return self.parse_parenthesized_expression(),
+            SyntaxKind::OpenBracketToken => return self.parse_array_literal_expression(),
+            SyntaxKind::OpenBraceToken => return self.parse_object_literal_expression(),
+            // Async arrow functions are parsed earlier in parseAssignmentExpressionOrHigher.
+            // If we encounter `async [no LineTerminator here] function` then this is an async
+            // function; otherwise, its an identifier.
+            SyntaxKind::AsyncKeyword
+                if self.look_ahead(Self::next_token_is_function_keyword_on_same_line) =>
+            {
+                return self.parse_function_expression();
+            }
+            SyntaxKind::AtToken => return self.parse_decorated_expression(),
+            SyntaxKind::ClassKeyword => {
+                let pos = self.node_pos();
+                return self.parse_class_declaration_or_expression(
+                    pos,
+                    None,
+                    SyntaxKind::ClassExpression,
+                );
+            }
+            SyntaxKind::FunctionKeyword => return self.parse_function_expression(),
+            SyntaxKind::NewKeyword => return self.parse_new_expression_or_new_dot_target(),
+            SyntaxKind::SlashToken | SyntaxKind::SlashEqualsToken
+                if self.re_scan_slash_token() == SyntaxKind::RegularExpressionLiteral =>
+            {
+                return self.parse_literal_node();
+            }
+            SyntaxKind::TemplateHead => return self.parse_template_expression(false),
+            SyntaxKind::PrivateIdentifier => return self.parse_private_identifier(),
+            _ => {}
+        }
+        self.parse_identifier_with_diagnostic(diagnostics::EXPRESSION_EXPECTED_1109)
+    }
+
+    fn parse_parenthesized_expression(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::OpenParenToken);
+        let expression = self.allow_in_and(Self::parse_expression);
+        self.parse_expected(SyntaxKind::CloseParenToken);
+        self.finish_node(
+            SyntaxKind::ParenthesizedExpression,
+            pos,
+            ParenthesizedExpression { expression },
+        )
+    }
+
+    fn parse_spread_element(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::DotDotDotToken);
+        let expression = self.parse_assignment_expression_or_higher(true);
+        self.finish_node(SyntaxKind::SpreadElement, pos, SpreadElement { expression })
+    }
+
+    fn parse_argument_or_array_literal_element(&mut self) -> Arc<Node> {
+        match self.token {
+            SyntaxKind::DotDotDotToken => self.parse_spread_element(),
+            SyntaxKind::CommaToken => {
+                let pos = self.node_pos();
+                self.finish_node(SyntaxKind::OmittedExpression, pos, OmittedExpression {})
+            }
+            _ => self.parse_assignment_expression_or_higher(true),
+        }
+    }
+
+    fn parse_argument_expression(&mut self) -> Arc<Node> {
+        self.do_outside_of_context(
+            NodeFlags::DISALLOW_IN_CONTEXT | NodeFlags::DECORATOR_CONTEXT,
+            Self::parse_argument_or_array_literal_element,
+        )
+    }
+
+    fn parse_array_literal_expression(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let open_bracket_position = self.scanner.token_start();
+        let open_bracket_parsed = self.parse_expected(SyntaxKind::OpenBracketToken);
+        let multi_line = self.has_preceding_line_break();
+        let elements = self.parse_delimited_list(
+            ParsingContext::ArrayLiteralMembers,
+            Self::parse_argument_or_array_literal_element,
+        );
+        self.parse_expected_matching_brackets(
+            SyntaxKind::OpenBracketToken,
+            SyntaxKind::CloseBracketToken,
+            open_bracket_parsed,
+            open_bracket_position,
+        );
+        self.finish_node(
+            SyntaxKind::ArrayLiteralExpression,
+            pos,
+            ArrayLiteralExpression {
+                elements,
+                multi_line,
+            },
+        )
+    }
+
+    fn parse_object_literal_element(&mut self) -> Arc<Node> {
+        self.parse_with_jsdoc(Self::parse_object_literal_element_worker)
+    }
+
+    fn parse_object_literal_element_worker(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        if self.parse_optional(SyntaxKind::DotDotDotToken) {
+            let expression = self.parse_assignment_expression_or_higher(true);
+            return self.finish_node(
+                SyntaxKind::SpreadAssignment,
+                pos,
+                SpreadAssignment { expression },
+            );
+        }
+        let modifiers = self.parse_modifiers(true, false, false);
+        if self.parse_contextual_modifier(SyntaxKind::GetKeyword) {
+            return self.parse_accessor_declaration(
+                pos,
+                modifiers,
+                SyntaxKind::GetAccessor,
+                SignatureFlags::NONE,
+            );
+        }
+        if self.parse_contextual_modifier(SyntaxKind::SetKeyword) {
+            return self.parse_accessor_declaration(
+                pos,
+                modifiers,
+                SyntaxKind::SetAccessor,
+                SignatureFlags::NONE,
+            );
+        }
+        let asterisk_token = self.parse_optional_token(SyntaxKind::AsteriskToken);
+        let token_is_identifier = self.is_identifier();
+        let name = self.parse_property_name();
+        // Disallowing of optional property assignments and definite assignment assertion happens in the grammar checker.
+        let postfix_token = self
+            .parse_optional_token(SyntaxKind::QuestionToken)
+            .or_else(|| self.parse_optional_token(SyntaxKind::ExclamationToken));
+        if asterisk_token.is_some()
+            || self.token == SyntaxKind::OpenParenToken
+            || self.token == SyntaxKind::LessThanToken
+        {
+            return self.parse_method_declaration(
+                pos,
+                modifiers,
+                asterisk_token,
+                name,
+                postfix_token,
+                None,
+            );
+        }
+
+        // check if it is short-hand property assignment or normal property assignment
+        // NOTE: if token is EqualsToken it is interpreted as CoverInitializedName production
+        // CoverInitializedName[Yield] :
+        //     IdentifierReference[?Yield] Initializer[In, ?Yield]
+        // this is necessary because ObjectLiteral productions are also used to cover grammar for ObjectAssignmentPattern
+        let is_shorthand_property_assignment =
+            token_is_identifier && self.token != SyntaxKind::ColonToken;
+        if is_shorthand_property_assignment {
+            let equals_token = self.parse_optional_token(SyntaxKind::EqualsToken);
+            let object_assignment_initializer = if equals_token.is_some() {
+                Some(self.allow_in_and(|p| p.parse_assignment_expression_or_higher(true)))
+            } else {
+                None
+            };
+            return self.finish_node(
+                SyntaxKind::ShorthandPropertyAssignment,
+                pos,
+                ShorthandPropertyAssignment {
+                    modifiers,
+                    name,
+                    postfix_token,
+                    equals_token,
+                    object_assignment_initializer,
+                },
+            );
+        }
+        self.parse_expected(SyntaxKind::ColonToken);
+        let initializer = self.allow_in_and(|p| p.parse_assignment_expression_or_higher(true));
+        self.finish_node(
+            SyntaxKind::PropertyAssignment,
+            pos,
+            PropertyAssignment {
+                modifiers,
+                name,
+                postfix_token,
+                initializer,
+            },
+        )
+    }
+
+    fn parse_object_literal_expression(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let open_brace_position = self.scanner.token_start();
+        let open_brace_parsed = self.parse_expected(SyntaxKind::OpenBraceToken);
+        let multi_line = self.has_preceding_line_break();
+        let properties = self.parse_delimited_list_worker(
+            ParsingContext::ObjectLiteralMembers,
+            Self::parse_object_literal_element,
+            true,
+        );
+        self.parse_expected_matching_brackets(
+            SyntaxKind::OpenBraceToken,
+            SyntaxKind::CloseBraceToken,
+            open_brace_parsed,
+            open_brace_position,
+        );
+        self.finish_node(
+            SyntaxKind::ObjectLiteralExpression,
+            pos,
+            ObjectLiteralExpression {
+                properties,
+                multi_line,
+            },
+        )
+    }
+
+    fn parse_function_expression(&mut self) -> Arc<Node> {
+        // GeneratorExpression:
+        //      function* BindingIdentifier [Yield][opt](FormalParameters[Yield]){ GeneratorBody }
+        //
+        // FunctionExpression:
+        //      function BindingIdentifier[opt](FormalParameters){ FunctionBody }
+        let saved_decorator_context = self.in_decorator_context();
+        self.set_context_flag(false, NodeFlags::DECORATOR_CONTEXT);
+
+        let pos = self.node_pos();
+        let modifiers = self.parse_modifiers(false, false, false);
+        self.parse_expected(SyntaxKind::FunctionKeyword);
+        let asterisk_token = self.parse_optional_token(SyntaxKind::AsteriskToken);
+        let signature_flags = modifier_signature_flags(modifiers.as_ref())
+            | if asterisk_token.is_some() {
+                SignatureFlags::YIELD
+            } else {
+                SignatureFlags::NONE
+            };
+        let name = self.do_in_yield_and_await_context(
+            signature_flags,
+            Self::parse_optional_binding_identifier,
+        );
+        let type_parameters = self.parse_type_parameters();
+        let parameters = self.parse_parameters(signature_flags);
+        let type_node = self.parse_return_type(SyntaxKind::ColonToken, false);
+        let body = self.parse_function_block(signature_flags, None);
+
+        self.set_context_flag(saved_decorator_context, NodeFlags::DECORATOR_CONTEXT);
+        self.finish_node(
+            SyntaxKind::FunctionExpression,
+            pos,
+            FunctionExpression {
+                modifiers,
+                asterisk_token,
+                name,
+                type_parameters,
+                parameters,
+                type_node,
+                body,
+            },
+        )
+    }
+
+    fn parse_decorated_expression(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let modifiers = self.parse_modifiers(true, false, false);
+        if self.token == SyntaxKind::ClassKeyword {
+            return self.parse_class_declaration_or_expression(
+                pos,
+                modifiers,
+                SyntaxKind::ClassExpression,
+            );
+        }
+        self.parse_error_at_current_token(diagnostics::EXPRESSION_EXPECTED_1109, &[]);
+        self.finish_node(
+            SyntaxKind::MissingDeclaration,
+            pos,
+            MissingDeclaration { modifiers },
+        )
+    }
+
+    fn parse_new_expression_or_new_dot_target(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::NewKeyword);
+        if self.parse_optional(SyntaxKind::DotToken) {
+            let name = self.parse_identifier_name();
+            return self.finish_node(
+                SyntaxKind::MetaProperty,
+                pos,
+                MetaProperty {
+                    keyword_token: SyntaxKind::NewKeyword,
+                    name,
+                },
+            );
+        }
+        let expression_pos = self.node_pos();
+        let primary_expression = self.parse_primary_expression();
+        let mut expression =
+            self.parse_member_expression_rest(expression_pos, primary_expression, false);
+        let mut type_arguments = None;
+        // Absorb type arguments into NewExpression when preceding expression is ExpressionWithTypeArguments
+        if let Some((callee, absorbed)) = split_expression_with_type_arguments(&expression, true) {
+            expression = callee;
+            type_arguments = absorbed;
+        }
+        if self.token == SyntaxKind::QuestionDotToken {
+            let text = self.node_text(&expression);
+            self.parse_error_at_current_token(
+                diagnostics::INVALID_OPTIONAL_CHAIN_FROM_NEW_EXPRESSION_DID_YOU_MEAN_TO_CALL_0_1209,
+                &[text],
+            );
+        }
+        let arguments = if self.token == SyntaxKind::OpenParenToken {
+            Some(self.parse_argument_list())
+        } else {
+            None
+        };
+        self.finish_node(
+            SyntaxKind::NewExpression,
+            pos,
+            NewExpression {
+                expression,
+                type_arguments,
+                arguments,
+            },
+        )
+    }
+
+    // Templates
+
+    fn parse_template_expression(&mut self, is_tagged_template: bool) -> Arc<Node> {
+        let pos = self.node_pos();
+        let head = self.parse_template_head(is_tagged_template);
+        let template_spans = self.parse_template_spans(is_tagged_template);
+        self.finish_node(
+            SyntaxKind::TemplateExpression,
+            pos,
+            TemplateExpression {
+                head,
+                template_spans,
+            },
+        )
+    }
+
+    fn parse_template_spans(&mut self, is_tagged_template: bool) -> NodeList {
+        let pos = self.node_pos();
+        let mut spans = Vec::new();
+        loop {
+            let span = self.parse_template_span(is_tagged_template);
+            let is_middle = span.as_template_span().literal.kind == SyntaxKind::TemplateMiddle;
+            spans.push(span);
+            if !is_middle {
+                break;
+            }
+        }
+        self.create_node_list(pos, spans)
+    }
+
+    fn parse_template_span(&mut self, is_tagged_template: bool) -> Arc<Node> {
+        let pos = self.node_pos();
+        let expression = self.allow_in_and(Self::parse_expression);
+        let literal = self.parse_literal_of_template_span(is_tagged_template);
+        self.finish_node(
+            SyntaxKind::TemplateSpan,
+            pos,
+            TemplateSpan {
+                expression,
+                literal,
+            },
+        )
+    }
+
+    fn parse_template_head(&mut self, is_tagged_template: bool) -> Arc<Node> {
+        if is_tagged_template {
+            self.re_scan_template_token(true);
+        }
+        debug_assert_eq!(
+            self.token,
+            SyntaxKind::TemplateHead,
+            "Template head has wrong token kind"
+        );
+        self.parse_literal_node()
+    }
+
+    fn parse_literal_of_template_span(&mut self, is_tagged_template: bool) -> Arc<Node> {
+        if self.token == SyntaxKind::CloseBraceToken {
+            self.re_scan_template_token(is_tagged_template);
+            return self.parse_literal_node();
+        }
+        self.parse_error_at_current_token(
+            diagnostics::D_0_EXPECTED_1005,
+            &[token_text(SyntaxKind::CloseBraceToken)],
+        );
+        let pos = self.node_pos();
+        self.finish_node_with_end(
+            SyntaxKind::TemplateTail,
+            pos,
+            pos,
+            NodeFlags::NONE,
+            TemplateLiteralLikeNode {
+                text: String::new(),
+                raw_text: String::new(),
+                token_flags: scanner::TokenFlags::NONE,
+            },
+        )
+    }
+
+    /// Gets the source text of a node, without its leading trivia
+    fn node_text(&self, node: &Node) -> String {
+        let text = self.scanner.text();
+        let start = skip_trivia(text, node.pos()).min(node.end());
+        text[start..node.end()].to_string()
+    }
+}
+
+/// Splits an `ExpressionWithTypeArguments` produced by speculative type argument parsing
+/// into its expression and type arguments, so that a following call, `new` or tagged
+/// template can take ownership of the type arguments
+fn split_expression_with_type_arguments(
+    expression: &Arc<Node>,
+    can_absorb: bool,
+) -> Option<(Arc<Node>, Option<NodeList>)> {
+    if !can_absorb || expression.kind != SyntaxKind::ExpressionWithTypeArguments {
+        return None;
+    }
+    let data = expression.as_expression_with_type_arguments();
+    Some((data.expression.clone(), data.type_arguments.clone()))
+}
+
+/// Determines if an expression continues an optional chain, looking through non-null assertions
+fn is_optional_chain_continuation(expression: &Node) -> bool {
+    let mut expression = expression;
+    while expression.kind == SyntaxKind::NonNullExpression {
+        if expression.flags.contains(NodeFlags::OPTIONAL_CHAIN) {
+            return true;
+        }
+        expression = expression.as_non_null_expression().expression.as_ref();
+    }
+    expression.flags.contains(NodeFlags::OPTIONAL_CHAIN)
+}
+
+fn type_has_arrow_function_blocking_parse_error(node: &Node) -> bool {
+    match node.kind {
+        SyntaxKind::TypeReference => node_is_missing(&node.as_type_reference_node().type_name),
+        SyntaxKind::FunctionType | SyntaxKind::ConstructorType => {
+            let parameters = node.parameters().expect("function types have parameters");
+            let is_missing_list = parameters.is_empty() && parameters.pos() == parameters.end();
+            is_missing_list
+                || node
+                    .type_node()
+                    .is_some_and(|t| type_has_arrow_function_blocking_parse_error(t))
+        }
+        SyntaxKind::ParenthesizedType => type_has_arrow_function_blocking_parse_error(
+            &node.as_parenthesized_type_node().type_node,
+        ),
+        _ => false,
+    }
+}
+
+// JSX
+impl Parser {
+    fn parse_jsx_element_or_self_closing_element_or_fragment(
+        &mut self,
+        in_expression_context: bool,
+        top_invalid_node_position: Option<usize>,
+        opening_tag: Option<&Arc<Node>>,
+        must_be_unary: bool,
+    ) -> Arc<Node> {
+        let pos = self.node_pos();
+        let opening = self
+            .parse_jsx_opening_or_self_closing_element_or_opening_fragment(in_expression_context);
+        let mut result = match opening.kind {
+            SyntaxKind::JsxOpeningElement => {
+                let mut children = self.parse_jsx_children(&opening);
+                let mut closing_element = None;
+                let opening_tag_name = opening.as_jsx_opening_element().tag_name.clone();
+                if let Some(last_child) = children.nodes.last().cloned()
+                    && last_child.kind == SyntaxKind::JsxElement
+                {
+                    let last_element = last_child.as_jsx_element();
+                    let last_opening_tag_name = &last_element
+                        .opening_element
+                        .as_jsx_opening_element()
+                        .tag_name;
+                    let last_closing_tag_name = &last_element
+                        .closing_element
+                        .as_jsx_closing_element()
+                        .tag_name;
+                    if !tag_names_are_equivalent(last_opening_tag_name, last_closing_tag_name)
+                        && tag_names_are_equivalent(&opening_tag_name, last_closing_tag_name)
+                    {
+                        // when an unclosed JsxOpeningElement incorrectly parses its parent's JsxClosingElement,
+                        // restructure (<div>(...<span>...</div>)) --> (<div>(...<span>...</>)</div>)
+                        // (no need to error; the parent will error)
+                        let end = last_element.children.end();
+                        let missing_tag_name = self.finish_node_with_end(
+                            SyntaxKind::Identifier,
+                            end,
+                            end,
+                            NodeFlags::NONE,
+                            Identifier {
+                                text: String::new(),
+                            },
+                        );
+                        let missing_closing_element = self.finish_node_with_end(
+                            SyntaxKind::JsxClosingElement,
+                            end,
+                            end,
+                            NodeFlags::NONE,
+                            JsxClosingElement {
+                                tag_name: missing_tag_name,
+                            },
+                        );
+                        let new_last = self.finish_node_with_end(
+                            SyntaxKind::JsxElement,
+                            last_child.pos(),
+                            end,
+                            NodeFlags::NONE,
+                            JsxElement {
+                                opening_element: last_element.opening_element.clone(),
+                                children: last_element.children.clone(),
+                                closing_element: missing_closing_element,
+                            },
+                        );
+                        closing_element = Some(last_element.closing_element.clone());
+                        children.nodes.pop();
+                        children.nodes.push(new_last);
+                        children.loc = TextRange::new(children.pos(), end);
+                    }
+                }
+                let closing_element = match closing_element {
+                    Some(closing_element) => closing_element,
+                    None => self.parse_jsx_closing_element(&opening, in_expression_context),
+                };
+                let closing_tag_name = closing_element.as_jsx_closing_element().tag_name.clone();
+                if !tag_names_are_equivalent(&opening_tag_name, &closing_tag_name) {
+                    let parent_tag_name = opening_tag
+                        .filter(|tag| tag.kind == SyntaxKind::JsxOpeningElement)
+                        .map(|tag| tag.as_jsx_opening_element().tag_name.clone());
+                    let opening_text = self.node_text(&opening_tag_name);
+                    if parent_tag_name
+                        .is_some_and(|parent| tag_names_are_equivalent(&closing_tag_name, &parent))
+                    {
+                        // opening incorrectly matched with its parent's closing -- put error on opening
+                        self.parse_error_at_node(
+                            &opening_tag_name,
+                            diagnostics::JSX_ELEMENT_0_HAS_NO_CORRESPONDING_CLOSING_TAG_17008,
+                            &[opening_text],
+                        );
+                    } else {
+                        // other opening/closing mismatches -- put error on closing
+                        self.parse_error_at_node(
+                            &closing_tag_name,
+                            diagnostics::EXPECTED_CORRESPONDING_JSX_CLOSING_TAG_FOR_0_17002,
+                            &[opening_text],
+                        );
+                    }
+                }
+                self.finish_node(
+                    SyntaxKind::JsxElement,
+                    pos,
+                    JsxElement {
+                        opening_element: opening,
+                        children,
+                        closing_element,
+                    },
+                )
+            }
+            SyntaxKind::JsxOpeningFragment => {
+                let children = self.parse_jsx_children(&opening);
+                let closing_fragment = self.parse_jsx_closing_fragment(in_expression_context);
+                self.finish_node(
+                    SyntaxKind::JsxFragment,
+                    pos,
+                    JsxFragment {
+                        opening_fragment: opening,
+                        children,
+                        closing_fragment,
+                    },
+                )
+            }
+            _ => {
+                debug_assert_eq!(opening.kind, SyntaxKind::JsxSelfClosingElement);
+                // Nothing else to do for self-closing elements
+                opening
+            }
+        };
+
+        // If the user writes the invalid code '<div></div><div></div>' in an expression context (i.e. not wrapped in
+        // an enclosing tag), we'll naively try to parse   ^ this as a 'less than' operator and the remainder of the tag
+        // as garbage, which will cause the formatter to badly mangle the JSX. Perform a speculative parse of a JSX
+        // element if we see a < token so that we can wrap it in a synthetic binary expression so the formatter
+        // does less damage and we can report a better error.
+        // Since JSX elements are invalid < operands anyway, this lookahead parse will only occur in error scenarios
+        // of one sort or another.
+        // If we are in a unary context, we can't do this recovery; the binary expression we return here is not
+        // a valid UnaryExpression and will cause problems later.
+        if !must_be_unary && in_expression_context && self.token == SyntaxKind::LessThanToken {
+            let top_bad_pos = top_invalid_node_position.unwrap_or(result.pos());
+            let invalid_element = self.try_parse(|p| {
+                Some(p.parse_jsx_element_or_self_closing_element_or_fragment(
+                    true,
+                    Some(top_bad_pos),
+                    None,
+                    false,
+                ))
+            });
+            if let Some(invalid_element) = invalid_element {
+                let operator_token = self.finish_node_with_end(
+                    SyntaxKind::CommaToken,
+                    invalid_element.pos(),
+                    invalid_element.pos(),
+                    NodeFlags::NONE,
+                    Token {},
+                );
+                let error_pos = skip_trivia(self.scanner.text(), top_bad_pos);
+                self.parse_error_at_range(
+                    error_pos,
+                    invalid_element.end(),
+                    diagnostics::JSX_EXPRESSIONS_MUST_HAVE_ONE_PARENT_ELEMENT_2657,
+                    &[],
+                );
+                result = self.make_binary_expression(result, operator_token, invalid_element, pos);
+            }
+        }
+        result
+    }
+
+    fn parse_jsx_text(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let text = self.scanner.token_value().to_string();
+        let contains_only_trivia_white_spaces = self.token == SyntaxKind::JsxTextAllWhiteSpaces;
+        self.scan_jsx_text();
+        self.finish_node(
+            SyntaxKind::JsxText,
+            pos,
+            JsxText {
+                text,
+                contains_only_trivia_white_spaces,
+            },
+        )
+    }
+
+    fn parse_jsx_child(&mut self, opening_tag: &Arc<Node>, token: SyntaxKind) -> Option<Arc<Node>> {
+        match token {
+            SyntaxKind::EndOfFile => {
+                // If we hit EOF, issue the error at the tag that lacks the closing element
+                // rather than at the end of the file (which is useless)
+                if opening_tag.kind == SyntaxKind::JsxOpeningFragment {
+                    self.parse_error_at_node(
+                        opening_tag,
+                        diagnostics::JSX_FRAGMENT_HAS_NO_CORRESPONDING_CLOSING_TAG_17014,
+                        &[],
+                    );
+                } else {
+                    // We want the error span to cover only 'Foo.Bar' in < Foo.Bar >
+                    // or to cover only 'Foo' in < Foo >
+                    let tag_name = opening_tag.as_jsx_opening_element().tag_name.clone();
+                    let text = self.node_text(&tag_name);
+                    self.parse_error_at_node(
+                        &tag_name,
+                        diagnostics::JSX_ELEMENT_0_HAS_NO_CORRESPONDING_CLOSING_TAG_17008,
+                        &[text],
+                    );
+                }
+                None
+            }
+            SyntaxKind::LessThanSlashToken | SyntaxKind::ConflictMarkerTrivia => None,
+            SyntaxKind::JsxText | SyntaxKind::JsxTextAllWhiteSpaces => Some(self.parse_jsx_text()),
+            SyntaxKind::OpenBraceToken => self.parse_jsx_expression(false),
+            SyntaxKind::LessThanToken => {
+                Some(self.parse_jsx_element_or_self_closing_element_or_fragment(
+                    false,
+                    None,
+                    Some(opening_tag),
+                    false,
+                ))
+            }
+            _ => unreachable!("Unknown JSX child kind {token:?}"),
+        }
+    }
+
+    fn parse_jsx_children(&mut self, opening_tag: &Arc<Node>) -> NodeList {
+        let pos = self.node_pos();
+        let saved_parsing_context = self.parsing_context;
+        self.parsing_context |= ParsingContext::JsxChildren.bit();
+        let mut children = Vec::new();
+        loop {
+            let current_token = self.re_scan_jsx_token();
+            let Some(child) = self.parse_jsx_child(opening_tag, current_token) else {
+                break;
+            };
+            let stop = opening_tag.kind == SyntaxKind::JsxOpeningElement
+                && child.kind == SyntaxKind::JsxElement
+                && {
+                    let element = child.as_jsx_element();
+                    let child_opening = &element.opening_element.as_jsx_opening_element().tag_name;
+                    let child_closing = &element.closing_element.as_jsx_closing_element().tag_name;
+                    !tag_names_are_equivalent(child_opening, child_closing)
+                        && tag_names_are_equivalent(
+                            &opening_tag.as_jsx_opening_element().tag_name,
+                            child_closing,
+                        )
+                };
+            children.push(child);
+            if stop {
+                // stop after parsing a mismatched child like <div>...(<span></div>) in order to reattach the </div> higher
+                break;
+            }
+        }
+        self.parsing_context = saved_parsing_context;
+        self.create_node_list(pos, children)
+    }
+
+    fn parse_jsx_attributes(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let properties = self.parse_list(ParsingContext::JsxAttributes, Self::parse_jsx_attribute);
+        self.finish_node(SyntaxKind::JsxAttributes, pos, JsxAttributes { properties })
+    }
+
+    fn parse_jsx_opening_or_self_closing_element_or_opening_fragment(
+        &mut self,
+        in_expression_context: bool,
+    ) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::LessThanToken);
+        if self.token == SyntaxKind::GreaterThanToken {
+            // See below for explanation of scan_jsx_text
+            self.scan_jsx_text();
+            return self.finish_node(SyntaxKind::JsxOpeningFragment, pos, JsxOpeningFragment {});
+        }
+        let tag_name = self.parse_jsx_element_name();
+        let type_arguments = if !self.context_flags.contains(NodeFlags::JAVASCRIPT_FILE) {
+            self.try_parse_type_arguments()
+        } else {
+            None
+        };
+        let attributes = self.parse_jsx_attributes();
+
+        // Closing tag, so scan the immediately-following text with the JSX scanning instead
+        // of regular scanning to avoid treating illegal characters (e.g. '#') as immediate
+        // scanning errors
+        if self.token == SyntaxKind::GreaterThanToken {
+            self.scan_jsx_text();
+            return self.finish_node(
+                SyntaxKind::JsxOpeningElement,
+                pos,
+                JsxOpeningElement {
+                    tag_name,
+                    type_arguments,
+                    attributes,
+                },
+            );
+        }
+        self.parse_expected(SyntaxKind::SlashToken);
+        if self.parse_expected_with_diagnostic(SyntaxKind::GreaterThanToken, None, false) {
+            // manually advance the scanner in order to look for jsx text inside jsx
+            if in_expression_context {
+                self.next_token();
+            } else {
+                self.scan_jsx_text();
+            }
+        }
+        self.finish_node(
+            SyntaxKind::JsxSelfClosingElement,
+            pos,
+            JsxSelfClosingElement {
+                tag_name,
+                type_arguments,
+                attributes,
+            },
+        )
+    }
+
+    fn parse_jsx_element_name(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        // JsxElement can have name in the form of
+        //      propertyAccessExpression
+        //      primaryExpression in the form of an identifier and "this" keyword
+        // We can't just simply use parseLeftHandSideExpressionOrHigher because then we will start consider class,function etc as a keyword
+        // We only want to consider "this" as a primaryExpression
+        let mut expression = self.parse_jsx_tag_name();
+        if expression.kind == SyntaxKind::JsxNamespacedName {
+            return expression;
+        }
+        while self.parse_optional(SyntaxKind::DotToken) {
+            let name = self.parse_right_side_of_dot(true, false);
+            expression = self.finish_node(
+                SyntaxKind::PropertyAccessExpression,
+                pos,
+                PropertyAccessExpression {
+                    expression,
+                    question_dot_token: None,
+                    name,
+                },
+            );
+        }
+        expression
+    }
+
+    fn parse_jsx_tag_name(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.scan_jsx_identifier();
+        let is_this = self.token == SyntaxKind::ThisKeyword;
+        let tag_name = self.parse_identifier_name();
+        if self.parse_optional(SyntaxKind::ColonToken) {
+            self.scan_jsx_identifier();
+            let name = self.parse_identifier_name();
+            return self.finish_node(
+                SyntaxKind::JsxNamespacedName,
+                pos,
+                JsxNamespacedName {
+                    namespace: tag_name,
+                    name,
+                },
+            );
+        }
+        if is_this {
+            return self.finish_node_with_end(
+                SyntaxKind::ThisKeyword,
+                tag_name.pos(),
+                tag_name.end(),
+                NodeFlags::NONE,
+                Token {},
+            );
+        }
+        tag_name
+    }
+
+    fn parse_jsx_expression(&mut self, in_expression_context: bool) -> Option<Arc<Node>> {
+        let pos = self.node_pos();
+        if !self.parse_expected(SyntaxKind::OpenBraceToken) {
+            return None;
+        }
+        let mut dot_dot_dot_token = None;
+        let mut expression = None;
+        if self.token != SyntaxKind::CloseBraceToken {
+            if !in_expression_context {
+                dot_dot_dot_token = self.parse_optional_token(SyntaxKind::DotDotDotToken);
+            }
+            // Only an AssignmentExpression is valid here per the JSX spec,
+            // but we can unambiguously parse a comma sequence and provide
+            // a better error message in grammar checking.
+            expression = Some(self.parse_expression());
+        }
+        if in_expression_context {
+            self.parse_expected(SyntaxKind::CloseBraceToken);
+        } else if self.parse_expected_with_diagnostic(SyntaxKind::CloseBraceToken, None, false) {
+            self.scan_jsx_text();
+        }
+        Some(self.finish_node(
+            SyntaxKind::JsxExpression,
+            pos,
+            JsxExpression {
+                dot_dot_dot_token,
+                expression,
+            },
+        ))
+    }
+
+    fn parse_jsx_attribute(&mut self) -> Arc<Node> {
+        if self.token == SyntaxKind::OpenBraceToken {
+            return self.parse_jsx_spread_attribute();
+        }
+        let pos = self.node_pos();
+        let name = self.parse_jsx_attribute_name();
+        let initializer = self.parse_jsx_attribute_value();
+        self.finish_node(
+            SyntaxKind::JsxAttribute,
+            pos,
+            JsxAttribute { name, initializer },
+        )
+    }
+
+    fn parse_jsx_attribute_value(&mut self) -> Option<Arc<Node>> {
+        if self.token == SyntaxKind::EqualsToken {
+            if self.scan_jsx_attribute_value() == SyntaxKind::StringLiteral {
+                return Some(self.parse_literal_node());
+            }
+            if self.token == SyntaxKind::OpenBraceToken {
+                return self.parse_jsx_expression(true);
+            }
+            if self.token == SyntaxKind::LessThanToken {
+                return Some(self.parse_jsx_element_or_self_closing_element_or_fragment(
+                    true, None, None, false,
+                ));
+            }
+            self.parse_error_at_current_token(diagnostics::OR_JSX_ELEMENT_EXPECTED_1145, &[]);
+        }
+        None
+    }
+
+    fn parse_jsx_attribute_name(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.scan_jsx_identifier();
+        let attribute_name = self.parse_identifier_name();
+        if self.parse_optional(SyntaxKind::ColonToken) {
+            self.scan_jsx_identifier();
+            let name = self.parse_identifier_name();
+            return self.finish_node(
+                SyntaxKind::JsxNamespacedName,
+                pos,
+                JsxNamespacedName {
+                    namespace: attribute_name,
+                    name,
+                },
+            );
+        }
+        attribute_name
+    }
+
+    fn parse_jsx_spread_attribute(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::OpenBraceToken);
+        self.parse_expected(SyntaxKind::DotDotDotToken);
+        let expression = self.parse_expression();
+        self.parse_expected(SyntaxKind::CloseBraceToken);
+        self.finish_node(
+            SyntaxKind::JsxSpreadAttribute,
+            pos,
+            JsxSpreadAttribute { expression },
+        )
+    }
+
+    fn parse_jsx_closing_element(
+        &mut self,
+        open: &Arc<Node>,
+        in_expression_context: bool,
+    ) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::LessThanSlashToken);
+        let tag_name = self.parse_jsx_element_name();
+        if self.parse_expected_with_diagnostic(SyntaxKind::GreaterThanToken, None, false) {
+            // manually advance the scanner in order to look for jsx text inside jsx
+            if in_expression_context
+                || !tag_names_are_equivalent(&open.as_jsx_opening_element().tag_name, &tag_name)
+            {
+                self.next_token();
+            } else {
+                self.scan_jsx_text();
+            }
+        }
+        self.finish_node(
+            SyntaxKind::JsxClosingElement,
+            pos,
+            JsxClosingElement { tag_name },
+        )
+    }
+
+    fn parse_jsx_closing_fragment(&mut self, in_expression_context: bool) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.parse_expected(SyntaxKind::LessThanSlashToken);
+        if self.parse_expected_with_diagnostic(
+            SyntaxKind::GreaterThanToken,
+            Some(diagnostics::EXPECTED_CORRESPONDING_CLOSING_TAG_FOR_JSX_FRAGMENT_17015),
+            false,
+        ) {
+            // manually advance the scanner in order to look for jsx text inside jsx
+            if in_expression_context {
+                self.next_token();
+            } else {
+                self.scan_jsx_text();
+            }
+        }
+        self.finish_node(SyntaxKind::JsxClosingFragment, pos, JsxClosingFragment {})
+    }
+}
+
+/// Determines if two JSX tag names refer to the same element
+fn tag_names_are_equivalent(lhs: &Node, rhs: &Node) -> bool {
+    if lhs.kind != rhs.kind {
+        return false;
+    }
+    match lhs.kind {
+        SyntaxKind::Identifier => lhs.text() == rhs.text(),
+        SyntaxKind::ThisKeyword => true,
+        SyntaxKind::JsxNamespacedName => {
+            let (lhs, rhs) = (lhs.as_jsx_namespaced_name(), rhs.as_jsx_namespaced_name());
+            lhs.namespace.text() == rhs.namespace.text() && lhs.name.text() == rhs.name.text()
+        }
+        SyntaxKind::PropertyAccessExpression => {
+            let (lhs, rhs) = (
+                lhs.as_property_access_expression(),
+                rhs.as_property_access_expression(),
+            );
+            lhs.name.text() == rhs.name.text()
+                && tag_names_are_equivalent(&lhs.expression, &rhs.expression)
+        }
+        _ => unreachable!("Unhandled JSX tag name kind {:?}", lhs.kind),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::text::TextChangeRange;
+
+    fn parse(file_name: &str, text: &str) -> Arc<Node> {
+        let file = parse_source_file(file_name, text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let diagnostics = &file.as_source_file().parse_diagnostics;
+        assert!(
+            diagnostics.is_empty(),
+            "unexpected diagnostics for {text:?}: {diagnostics:?}"
+        );
+        file
+    }
+
+    fn first_expression(file: &Node) -> Arc<Node> {
+        let statement = &file.as_source_file().statements.nodes[0];
+        assert_eq!(statement.kind, SyntaxKind::ExpressionStatement);
+        statement.as_expression_statement().expression.clone()
+    }
+
+    #[test]
+    fn parses_a_json_file_as_one_expression() {
+        let file = parse(
+            "data.json",
+            "{ \"name\": \"a\", \"values\": [1, -2, true, null] }\n",
+        );
+        assert!(file.flags.contains(NodeFlags::JSON_FILE));
+        assert_eq!(file.as_source_file().statements.len(), 1);
+        assert_eq!(
+            first_expression(&file).kind,
+            SyntaxKind::ObjectLiteralExpression
+        );
+
+        let file = parse_source_file(
+            "data.json",
+            "1 2",
+            ScriptTarget::ESNext,
+            ScriptKind::Unknown,
+        );
+        assert_eq!(file.as_source_file().parse_diagnostics[0].code, 1012);
+    }
+
+    #[test]
+    fn test_call_with_type_arguments() {
+        let file = parse("test.ts", "f<T>(x);");
+        let call = first_expression(&file);
+        assert_eq!(call.kind, SyntaxKind::CallExpression);
+        let call = call.as_call_expression();
+        assert_eq!(call.expression.kind, SyntaxKind::Identifier);
+        assert_eq!(call.type_arguments.as_ref().map(NodeList::len), Some(1));
+        assert_eq!(call.arguments.len(), 1);
+    }
+
+    #[test]
+    fn test_relational_expression_is_not_type_arguments() {
+        let file = parse("test.ts", "a < b > c;");
+        let expression = first_expression(&file);
+        assert_eq!(expression.kind, SyntaxKind::BinaryExpression);
+        let binary = expression.as_binary_expression();
+        assert_eq!(binary.operator_token.kind, SyntaxKind::GreaterThanToken);
+        assert_eq!(binary.left.kind, SyntaxKind::BinaryExpression);
+    }
+
+    #[test]
+    fn test_comma_separated_comparisons_before_paren() {
+        // `(` after the closing `>` favors the type argument interpretation
+        let file = parse("test.ts", "f < a, b > (c);");
+        let call = first_expression(&file);
+        assert_eq!(call.kind, SyntaxKind::CallExpression);
+        assert_eq!(
+            call.as_call_expression()
+                .type_arguments
+                .as_ref()
+                .map(NodeList::len),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_new_with_type_arguments() {
+        let file = parse("test.ts", "new Foo<T>();");
+        let new_expression = first_expression(&file);
+        assert_eq!(new_expression.kind, SyntaxKind::NewExpression);
+        let new_expression = new_expression.as_new_expression();
+        assert_eq!(new_expression.expression.kind, SyntaxKind::Identifier);
+        assert_eq!(
+            new_expression.type_arguments.as_ref().map(NodeList::len),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_generic_arrow_function() {
+        let file = parse("test.ts", "<T>(x: T): T => x;");
+        let arrow = first_expression(&file);
+        assert_eq!(arrow.kind, SyntaxKind::ArrowFunction);
+        assert_eq!(arrow.type_parameters().map(NodeList::len), Some(1));
+        assert!(arrow.type_node().is_some());
+    }
+
+    #[test]
+    fn test_generic_arrow_function_in_tsx() {
+        let file = parse("test.tsx", "<T,>(x: T) => x;");
+        let arrow = first_expression(&file);
+        assert_eq!(arrow.kind, SyntaxKind::ArrowFunction);
+        assert_eq!(arrow.type_parameters().map(NodeList::len), Some(1));
+    }
+
+    #[test]
+    fn test_type_assertion() {
+        let file = parse("test.ts", "<T>x;");
+        assert_eq!(
+            first_expression(&file).kind,
+            SyntaxKind::TypeAssertionExpression
+        );
+    }
+
+    #[test]
+    fn test_parenthesized_expression_is_not_arrow_function() {
+        let file = parse("test.ts", "(a, b);");
+        let expression = first_expression(&file);
+        assert_eq!(expression.kind, SyntaxKind::ParenthesizedExpression);
+    }
+
+    #[test]
+    fn test_instantiation_expression() {
+        let file = parse("test.ts", "const g = f<string>;");
+        let statement = &file.as_source_file().statements.nodes[0];
+        let declaration_list = &statement.as_variable_statement().declaration_list;
+        let declaration = &declaration_list
+            .as_variable_declaration_list()
+            .declarations
+            .nodes[0];
+        let initializer = declaration
+            .initializer()
+            .expect("declaration has an initializer");
+        assert_eq!(initializer.kind, SyntaxKind::ExpressionWithTypeArguments);
+    }
+
+    #[test]
+    fn test_generic_declarations() {
+        parse(
+            "test.ts",
+            "function id<T extends object = {}>(x: T): T { return x; }\n\
+             class Box<in out T> { map<U>(f: (x: T) => U): Box<U> { return null!; } }\n\
+             interface Pair<K, V> { key: K; value: V }\n\
+             type Keys<T> = { [P in keyof T]: T[P] extends infer U extends string ? U : never };",
+        );
+    }
+
+    fn jsdoc_tags(node: &Node) -> Vec<Arc<Node>> {
+        let jsdoc = node.jsdoc();
+        assert_eq!(
+            jsdoc.len(),
+            1,
+            "expected one JSDoc comment on {:?}",
+            node.kind
+        );
+        jsdoc[0].as_jsdoc().tags.nodes.clone()
+    }
+
+    #[test]
+    fn test_jsdoc_param_and_returns_tags() {
+        let file = parse(
+            "test.js",
+            "/**\n * Adds one.\n * @param {number} x - the input\n * @param {string=} [label=\"a\"]\n * @returns {number}\n */\nfunction f(x, label) { return x + 1; }",
+        );
+        let function = &file.as_source_file().statements.nodes[0];
+        assert_eq!(
+            function.jsdoc()[0].as_jsdoc().comment.as_deref(),
+            Some("Adds one.")
+        );
+        let tags = jsdoc_tags(function);
+        assert_eq!(tags.len(), 3);
+
+        let x = tags[0].as_jsdoc_parameter_tag();
+        assert_eq!(x.name.text(), "x");
+        assert!(!x.is_bracketed);
+        assert_eq!(x.comment.as_deref(), Some("- the input"));
+        let type_expression = x.type_expression.as_ref().expect("@param has a type");
+        assert_eq!(
+            type_expression.as_jsdoc_type_expression().type_node.kind,
+            SyntaxKind::NumberKeyword
+        );
+
+        let label = tags[1].as_jsdoc_parameter_tag();
+        assert_eq!(label.name.text(), "label");
+        assert!(label.is_bracketed);
+        let type_expression = label.type_expression.as_ref().expect("@param has a type");
+        assert_eq!(
+            type_expression.as_jsdoc_type_expression().type_node.kind,
+            SyntaxKind::JSDocOptionalType
+        );
+
+        assert_eq!(tags[2].kind, SyntaxKind::JSDocReturnTag);
+    }
+
+    #[test]
+    fn test_jsdoc_type_template_and_deprecated_tags() {
+        let file = parse(
+            "test.ts",
+            "/** @type {?string} */\nlet s;\n\
+             class C {\n  /**\n   * @template {object} T, U\n   * @deprecated use g\n   */\n  m() {}\n}",
+        );
+        let statements = &file.as_source_file().statements.nodes;
+        let tags = jsdoc_tags(&statements[0]);
+        let type_tag = tags[0].as_jsdoc_type_tag();
+        assert_eq!(
+            type_tag
+                .type_expression
+                .as_jsdoc_type_expression()
+                .type_node
+                .kind,
+            SyntaxKind::JSDocNullableType
+        );
+
+        let method = &statements[1].members().expect("class has members").nodes[0];
+        let tags = jsdoc_tags(method);
+        let template = tags[0].as_jsdoc_template_tag();
+        assert!(template.constraint.is_some());
+        assert_eq!(template.type_parameters.len(), 2);
+        assert_eq!(
+            tags[1].as_jsdoc_deprecated_tag().comment.as_deref(),
+            Some("use g")
+        );
+        assert!(method.is_deprecated());
+        assert!(!statements[1].is_deprecated());
+    }
+
+    #[test]
+    fn test_jsdoc_parsing_mode_none() {
+        let file = parse_source_file_with_jsdoc_parsing_mode(
+            "test.js",
+            "/** @deprecated */\nfunction f() {}",
+            ScriptTarget::ESNext,
+            ScriptKind::Unknown,
+            JSDocParsingMode::ParseNone,
+        );
+        assert!(file.as_source_file().statements.nodes[0].jsdoc().is_empty());
+    }
+
+    #[test]
+    fn test_optional_tuple_element() {
+        let file = parse("test.ts", "type T = [string?];");
+        let alias = &file.as_source_file().statements.nodes[0];
+        let tuple = alias.type_node().expect("alias has a type");
+        assert_eq!(
+            tuple.as_tuple_type_node().elements.nodes[0].kind,
+            SyntaxKind::OptionalType
+        );
+    }
+
+    /// Asserts that two trees have the same shape, kinds, ranges, and error marks
+    fn assert_same_tree(actual: &Node, expected: &Node) {
+        let has_error = |node: &Node| node.flags.contains(NodeFlags::THIS_NODE_HAS_ERROR);
+        assert_eq!(
+            (actual.kind, actual.loc, has_error(actual)),
+            (expected.kind, expected.loc, has_error(expected))
+        );
+        let mut actual_children = Vec::new();
+        actual.for_each_child(&mut |child| {
+            actual_children.push(child.clone());
+            false
+        });
+        let mut expected_children = Vec::new();
+        expected.for_each_child(&mut |child| {
+            expected_children.push(child.clone());
+            false
+        });
+        assert_eq!(
+            actual_children.len(),
+            expected_children.len(),
+            "children of {:?}",
+            actual.kind
+        );
+        for (actual, expected) in actual_children.iter().zip(&expected_children) {
+            assert_same_tree(actual, expected);
+        }
+    }
+
+    fn update(old: &Node, pos: usize, end: usize, replacement: &str) -> Arc<Node> {
+        let old_text = &old.as_source_file().text;
+        let new_text = format!("{}{replacement}{}", &old_text[..pos], &old_text[end..]);
+        let change = TextChangeRange::new(TextRange::new(pos, end), replacement.len());
+        let updated = old.as_source_file().update(&new_text, change);
+        let expected = parse_source_file(
+            "test.ts",
+            &new_text,
+            ScriptTarget::ESNext,
+            ScriptKind::Unknown,
+        );
+        assert_same_tree(&updated, &expected);
+        assert_eq!(
+            updated.as_source_file().parse_diagnostics,
+            expected.as_source_file().parse_diagnostics,
+            "diagnostics after replacing {pos}..{end} with {replacement:?} in {old_text:?}"
+        );
+        let arena_len = |file: &Node| file.as_source_file().arena().unwrap().len();
+        assert_eq!(arena_len(&updated), arena_len(&expected));
+        updated
+    }
+
+    #[test]
+    fn test_identifiers_with_unicode_escapes() {
+        let text = "const \\u0061b\\u{63} = 1;\nabc.\\u0069f;\nvar \\u0061sync;\n\
+                    class C { #\\u{78} = 1 }\n";
+        let file = parse("test.ts", text);
+        let statements = &file.as_source_file().statements.nodes;
+        let list = &statements[0].as_variable_statement().declaration_list;
+        let name = list.as_variable_declaration_list().declarations.nodes[0]
+            .name()
+            .unwrap();
+        assert_eq!(name.text(), "abc");
+        assert!(
+            name.flags
+                .contains(NodeFlags::IDENTIFIER_HAS_EXTENDED_UNICODE_ESCAPE)
+        );
+        let access = &statements[1].as_expression_statement().expression;
+        let name = &access.as_property_access_expression().name;
+        assert_eq!(name.text(), "if");
+        assert!(
+            !name
+                .flags
+                .contains(NodeFlags::IDENTIFIER_HAS_EXTENDED_UNICODE_ESCAPE)
+        );
+        let member = &statements[3].as_class_declaration().members.nodes[0];
+        assert_eq!(member.name().unwrap().text(), "#x");
+
+        // A keyword cannot be written with escapes, and an escape of a character that cannot
+        // start an identifier is not one
+        let codes = |text| {
+            let file =
+                parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+            let diagnostics = &file.as_source_file().parse_diagnostics;
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(codes("\\u0069f (x) {}"), [1260]);
+        assert_eq!(codes("let \\u0030;"), [1127]);
+    }
+
+    #[test]
+    fn test_incremental_update_reuses_unaffected_statements() {
+        let text = "let a = 1;\nlet b = 2;\nlet c = 3;\nlet d = 4;\n";
+        let old = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let old_statements = old.as_source_file().statements.nodes.clone();
+        let pos = text.find("3").unwrap();
+        let updated = update(&old, pos, pos + 1, "33");
+        let statements = &updated.as_source_file().statements.nodes;
+        assert!(Arc::ptr_eq(&statements[0], &old_statements[0]));
+        assert_eq!(statements[3].pos(), old_statements[3].pos() + 1);
+        assert_eq!(
+            updated.as_source_file().node_count,
+            old.as_source_file().node_count
+        );
+    }
+
+    #[test]
+    fn test_incremental_update_reparses_previous_statement() {
+        // The edit turns `a` and `(b)` into a call through the lack of automatic semicolon insertion
+        let text = "x;\na\nb;\nc;\n";
+        let old = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let pos = text.find("b").unwrap();
+        let updated = update(&old, pos, pos + 1, "(b)");
+        let statements = &updated.as_source_file().statements.nodes;
+        assert_eq!(statements.len(), 3);
+        assert_eq!(
+            statements[1].expression().unwrap().kind,
+            SyntaxKind::CallExpression
+        );
+    }
+
+    #[test]
+    fn test_incremental_update_with_errors() {
+        let text = "function f() {\n  return 1;\n}\nlet y = 2;\n";
+        let old = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let pos = text.find("}").unwrap();
+        let broken = update(&old, pos, pos + 1, "");
+        assert!(!broken.as_source_file().parse_diagnostics.is_empty());
+
+        let fixed = update(&broken, pos, pos, "}");
+        assert!(fixed.as_source_file().parse_diagnostics.is_empty());
+
+        let deleted = update(&fixed, 0, text.find("let").unwrap(), "");
+        assert_eq!(deleted.as_source_file().statements.len(), 1);
+    }
+
+    #[test]
+    fn test_incremental_update_matches_full_parse_at_every_position() {
+        let text = "/** @deprecated */\nfunction f(a, b) { return a\n+ b; }\nclass C { m() {} }\nif (x) y();\nlet z = `t${1}`;\n";
+        let old = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        for pos in 0..text.len() {
+            for replacement in ["", "(", "}", "x", "\n", "/*"] {
+                update(&old, pos, pos, replacement);
+                update(&old, pos, pos + 1, replacement);
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_update_keeps_errors_reported_in_reparsed_text() {
+        // The missing `)` is reported at the second `let`, which the first statement reached
+        // while looking for it
+        let text = "let a = (1\nlet b = 2;\nlet c = 3;\n";
+        let old = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let pos = text.rfind("let").unwrap();
+        let updated = update(&old, pos, pos, "");
+        let diagnostics = &updated.as_source_file().parse_diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].code, diagnostics[0].pos()), (1005, 11));
+
+        for pos in 0..text.len() {
+            for replacement in ["", ")", ";", "\n", "("] {
+                update(&old, pos, pos, replacement);
+                update(&old, pos, pos + 1, replacement);
+            }
+        }
+    }
+}
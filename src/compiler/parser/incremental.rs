@@ -0,0 +1,272 @@
+//! Incremental reparsing of edited source files
+//!
+//! Instead of parsing an edited file from scratch, [`SourceFile::update`] keeps the top-level
+//! statements of the old tree that the edit cannot have affected:
+//!
+//! - Statements before the edit are shared with the old tree. The statement right before the
+//!   first one the edit touches is reparsed anyway, since it may now continue into the edited
+//!   text (through automatic semicolon insertion or a new `else` clause, for example).
+//! - Parsing restarts there and stops once the parser reaches the start of an old statement
+//!   that lies entirely after the edit. That statement and everything after it only depend on
+//!   text the edit did not change, so they are copied with their positions shifted by the
+//!   change in length.
+
+use std::sync::Arc;
+
+use super::{Parser, ParsingContext, push_diagnostic};
+use crate::compiler::ast::nodes::{SourceFile, StatementStart};
+use crate::compiler::ast::{Node, NodeArena, NodeFlags, NodeList, NodeMapper, SyntaxKind};
+use crate::compiler::diagnostics::Diagnostic;
+use crate::compiler::text::{TextChangeRange, TextRange};
+
+impl SourceFile {
+    /// Parses `new_text`, the result of applying `text_change_range` to this file's text,
+    /// reusing the statements the change did not affect
+    ///
+    /// Statements before the change are shared between the two trees, and their parent
+    /// pointers move to the new source file. Panics if the length of `new_text` does not
+    /// match the change.
+    pub fn update(&self, new_text: &str, text_change_range: TextChangeRange) -> Arc<Node> {
+        let span = text_change_range.span();
+        assert!(
+            span.end() <= self.text.len()
+                && self.text.len() - span.len() + text_change_range.new_length() == new_text.len(),
+            "text change {text_change_range:?} does not match the new text"
+        );
+
+        let mut parser = Parser::new(
+            &self.file_name,
+            new_text,
+            self.language_version,
+            self.script_kind,
+        );
+        parser.set_jsdoc_parsing_mode(self.jsdoc_parsing_mode);
+        parser.reparse_source_file(self, text_change_range)
+    }
+}
+
+impl Parser {
+    fn reparse_source_file(&mut self, old: &SourceFile, change: TextChangeRange) -> Arc<Node> {
+        let span = change.span();
+        let delta = change.new_length() as isize - span.len() as isize;
+        let old_statements = &old.statements.nodes;
+        let old_arena = old.arena().expect("a parsed file has an arena");
+
+        // A statement that ends exactly where the change starts is affected: its last token may
+        // merge with the inserted text.
+        let first_affected = old_statements
+            .iter()
+            .position(|statement| statement.end() >= span.pos())
+            .unwrap_or(old_statements.len());
+        let restart = first_affected.saturating_sub(1);
+        let restart_pos = old_statements
+            .get(restart)
+            .map_or(0, |statement| statement.pos());
+
+        let mut statements = old_statements[..restart].to_vec();
+        for statement in &statements {
+            self.arena.adopt(old_arena, statement);
+        }
+        // Parsing picks up in the state the old parse started the restart statement in, so the
+        // errors the statements before it reported are kept even when they lie in the text that
+        // is reparsed
+        let restart_state = old
+            .statement_starts
+            .get(restart)
+            .copied()
+            .unwrap_or_default();
+        *self.diagnostics.borrow_mut() =
+            old.parse_diagnostics[..restart_state.diagnostics_len].to_vec();
+        self.jsdoc_diagnostics =
+            old.jsdoc_diagnostics[..restart_state.jsdoc_diagnostics_len].to_vec();
+        self.parse_error_before_next_finished_node = restart_state.error_pending;
+        self.statement_starts = old.statement_starts[..restart].to_vec();
+        let (mut node_count, mut identifier_count) = (old.node_count, old.identifier_count);
+
+        self.scanner.reset_pos(restart_pos);
+        self.next_token();
+        let saved_parsing_context = self.parsing_context;
+        self.parsing_context |= ParsingContext::SourceElements.bit();
+        let mut next_old = first_affected;
+        let mut reuse_from = None;
+        while !self.is_list_terminator(ParsingContext::SourceElements) {
+            let pos = self.node_pos();
+            while let Some(candidate) = old_statements.get(next_old)
+                && (candidate.pos() < span.end() || shift(candidate.pos(), delta) < pos)
+            {
+                next_old += 1;
+            }
+            // A statement is only reused if the parser would start it in the same state, so
+            // that its nodes carry the same error flags as if it had been parsed again
+            if let Some(candidate) = old_statements.get(next_old)
+                && shift(candidate.pos(), delta) == pos
+                && (next_old == 0
+                    || !contains_parse_error(old_arena, &old_statements[next_old - 1]))
+                && old.statement_starts.get(next_old).is_some_and(|start| {
+                    start.error_pending == self.parse_error_before_next_finished_node
+                })
+            {
+                reuse_from = Some(next_old);
+                break;
+            }
+            if self.is_list_element(ParsingContext::SourceElements, false) {
+                statements.push(self.parse_source_file_statement());
+                continue;
+            }
+            if self.abort_parsing_list_or_move_to_next_token(ParsingContext::SourceElements) {
+                break;
+            }
+        }
+        self.parsing_context = saved_parsing_context;
+
+        let replaced_end = reuse_from.unwrap_or(old_statements.len());
+        for statement in &old_statements[restart..replaced_end] {
+            let (nodes, identifiers) = count_nodes(statement);
+            node_count -= nodes;
+            identifier_count -= identifiers;
+        }
+
+        let end_of_file_token = match reuse_from {
+            Some(index) => {
+                // Each reused statement brings the diagnostics the old parse reported from its
+                // start to the start of the next one
+                let mut diagnostic_ends: Vec<StatementStart> =
+                    old.statement_starts[index + 1..].to_vec();
+                diagnostic_ends.push(StatementStart {
+                    diagnostics_len: old.parse_diagnostics.len(),
+                    jsdoc_diagnostics_len: old.jsdoc_diagnostics.len(),
+                    error_pending: false,
+                });
+                for (start, end) in old.statement_starts[index..].iter().zip(diagnostic_ends) {
+                    self.statement_starts.push(StatementStart {
+                        error_pending: start.error_pending,
+                        ..self.statement_start()
+                    });
+                    let range = start.diagnostics_len..end.diagnostics_len;
+                    for diagnostic in shift_diagnostics(&old.parse_diagnostics[range], delta) {
+                        push_diagnostic(&mut self.diagnostics.borrow_mut(), diagnostic);
+                    }
+                    let range = start.jsdoc_diagnostics_len..end.jsdoc_diagnostics_len;
+                    self.jsdoc_diagnostics
+                        .extend(shift_diagnostics(&old.jsdoc_diagnostics[range], delta));
+                }
+                let mut shifter = PositionShifter {
+                    delta,
+                    arena: &mut self.arena,
+                    old_arena,
+                };
+                statements.extend(
+                    old_statements[index..]
+                        .iter()
+                        .map(|statement| shifter.map_node(statement)),
+                );
+                shifter.map_node(&old.end_of_file_token)
+            }
+            None => {
+                node_count -= 1;
+                self.parse_token_node()
+            }
+        };
+        self.node_count += node_count;
+        self.identifier_count += identifier_count;
+
+        let statements = NodeList::new(
+            TextRange::new(old.statements.pos(), end_of_file_token.pos()),
+            statements,
+        );
+        self.finish_source_file(0, statements, end_of_file_token)
+    }
+}
+
+/// Copies nodes with their positions moved by `delta`, allocating the copies into the arena of
+/// the new tree
+struct PositionShifter<'a> {
+    delta: isize,
+    arena: &'a mut NodeArena,
+    old_arena: &'a NodeArena,
+}
+
+impl NodeMapper for PositionShifter<'_> {
+    fn map_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        if self.delta == 0 {
+            self.arena.adopt(self.old_arena, node);
+            return Arc::clone(node);
+        }
+        let data = node.data().map_children(self);
+        let shifted = Node::new_boxed(node.kind, node.flags, self.shift_range(node.loc), data);
+        self.arena.alloc(&shifted);
+        let jsdoc = node.jsdoc();
+        if !jsdoc.is_empty() {
+            let arena_len = self.arena.len();
+            let comments = jsdoc.iter().map(|comment| self.map_node(comment)).collect();
+            self.arena.truncate(arena_len);
+            shifted.set_jsdoc(comments);
+        }
+        shifted
+    }
+
+    fn map_list(&mut self, list: &NodeList) -> NodeList {
+        NodeList {
+            loc: self.shift_range(list.loc),
+            nodes: list.nodes.iter().map(|node| self.map_node(node)).collect(),
+            has_trailing_comma: list.has_trailing_comma,
+        }
+    }
+}
+
+impl PositionShifter<'_> {
+    fn shift_range(&self, range: TextRange) -> TextRange {
+        TextRange::new(
+            shift(range.pos(), self.delta),
+            shift(range.end(), self.delta),
+        )
+    }
+}
+
+fn shift(pos: usize, delta: isize) -> usize {
+    pos.checked_add_signed(delta)
+        .expect("shifted position is before the start of the file")
+}
+
+/// Copies diagnostics with their positions moved by `delta`
+fn shift_diagnostics(diagnostics: &[Diagnostic], delta: isize) -> Vec<Diagnostic> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut diagnostic = diagnostic.clone();
+            diagnostic.loc = TextRange::new(
+                shift(diagnostic.loc.pos(), delta),
+                shift(diagnostic.loc.end(), delta),
+            );
+            diagnostic
+        })
+        .collect()
+}
+
+/// Determines if the parser reported an error while building `node` or any node inside it
+fn contains_parse_error(arena: &NodeArena, node: &Node) -> bool {
+    arena
+        .subtree(node)
+        .expect("an old statement is in the arena of its file")
+        .iter()
+        .any(|node| node.flags.contains(NodeFlags::THIS_NODE_HAS_ERROR))
+}
+
+/// Counts the nodes and identifiers in `node`'s subtree, including attached JSDoc, as the
+/// parser counted them when it created the subtree
+fn count_nodes(node: &Node) -> (usize, usize) {
+    let mut nodes = 1;
+    let mut identifiers =
+        usize::from(node.kind == SyntaxKind::Identifier && !node.flags.contains(NodeFlags::JS_DOC));
+    let mut add = |child: &Arc<Node>| {
+        let (child_nodes, child_identifiers) = count_nodes(child);
+        nodes += child_nodes;
+        identifiers += child_identifiers;
+        false
+    };
+    node.for_each_child(&mut add);
+    for comment in node.jsdoc() {
+        add(comment);
+    }
+    (nodes, identifiers)
+}
@@ -0,0 +1,676 @@
+//! JSDoc comment parsing
+//!
+//! Declarations remember whether the scanner saw a `/** ... */` comment in their leading
+//! trivia. Once such a declaration has been parsed, its comments are parsed into `JSDoc`
+//! nodes and attached to it. Comment text and tag headers are parsed directly from the
+//! source text; type expressions reuse the type parser, with the scanner positioned inside
+//! the comment and skipping the leading `*` of each line.
+
+use std::sync::Arc;
+
+use super::Parser;
+use crate::compiler::ast::nodes::*;
+use crate::compiler::ast::{Node, NodeData, NodeFlags, NodeList, SyntaxKind};
+use crate::compiler::diagnostics::{self, Diagnostic, Message};
+use crate::compiler::scanner::{
+    ScriptTarget, is_identifier_part, is_identifier_start, is_line_break,
+    is_white_space_single_line,
+};
+use crate::compiler::text::TextRange;
+
+impl Parser {
+    /// Runs `parse` and attaches any JSDoc comments that precede the node it returns
+    pub(super) fn parse_with_jsdoc(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Arc<Node>,
+    ) -> Arc<Node> {
+        let has_jsdoc = self.has_preceding_jsdoc_comment();
+        let node = parse(self);
+        self.with_jsdoc(node, has_jsdoc)
+    }
+
+    /// Attaches the JSDoc comments in the leading trivia of `node` when `has_jsdoc` is set
+    pub(super) fn with_jsdoc(&mut self, node: Arc<Node>, has_jsdoc: bool) -> Arc<Node> {
+        if !has_jsdoc {
+            return node;
+        }
+        let ranges = jsdoc_comment_ranges(self.scanner.text(), node.pos());
+        if ranges.is_empty() {
+            return node;
+        }
+        // Comments are attached to the node rather than being part of the tree
+        let arena_len = self.arena.len();
+        let comments = ranges
+            .into_iter()
+            .map(|(pos, end)| self.parse_jsdoc_comment(pos, end))
+            .collect();
+        self.arena.truncate(arena_len);
+        node.set_jsdoc(comments);
+        node
+    }
+
+    /// `*` as a JSDoc type
+    pub(super) fn parse_jsdoc_all_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        self.next_token();
+        self.finish_node(SyntaxKind::JSDocAllType, pos, JSDocAllType {})
+    }
+
+    /// `?` on its own, which means any type, or `?T`
+    pub(super) fn parse_jsdoc_unknown_or_nullable_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        // skip the ?
+        self.next_token();
+        // Need to lookahead to decide if this is a nullable or unknown type.
+        //
+        // Here are cases where we'll pick the unknown type:
+        //
+        //      Foo(?,
+        //      { a: ? }
+        //      Foo(?)
+        //      Foo<?>
+        //      Foo(?=
+        //      (?|
+        if matches!(
+            self.token,
+            SyntaxKind::CommaToken
+                | SyntaxKind::CloseBraceToken
+                | SyntaxKind::CloseParenToken
+                | SyntaxKind::GreaterThanToken
+                | SyntaxKind::EqualsToken
+                | SyntaxKind::BarToken
+        ) {
+            return self.finish_node(SyntaxKind::JSDocAllType, pos, JSDocAllType {});
+        }
+        let type_node = self.parse_type();
+        self.finish_node(
+            SyntaxKind::JSDocNullableType,
+            pos,
+            JSDocNullableType {
+                type_node,
+                postfix: false,
+            },
+        )
+    }
+
+    /// Parses the `/** ... */` comment spanning `pos..end`
+    fn parse_jsdoc_comment(&mut self, pos: usize, end: usize) -> Arc<Node> {
+        let content = JSDocContent {
+            text: self.scanner.text()[pos..end].to_string(),
+            base: pos,
+            language_version: self.language_version,
+        };
+        let content_end = content.text.len() - "*/".len();
+        let tag_starts = content.tag_starts("/**".len(), content_end);
+        let comment_end = tag_starts.first().copied().unwrap_or(content_end);
+        let comment = clean_comment_text(&content.text["/**".len()..comment_end]);
+
+        let mut tags = Vec::with_capacity(tag_starts.len());
+        for (i, &tag_start) in tag_starts.iter().enumerate() {
+            let tag_end = tag_starts.get(i + 1).copied().unwrap_or(content_end);
+            tags.push(self.parse_jsdoc_tag(&content, tag_start, tag_end));
+        }
+        let tags = NodeList::new(content.range(comment_end, content_end), tags);
+
+        self.finish_node_with_end(
+            SyntaxKind::JSDoc,
+            pos,
+            end,
+            NodeFlags::JS_DOC,
+            JSDoc { comment, tags },
+        )
+    }
+
+    /// Parses the tag starting at the `@` at `start` and running up to `end`, both relative to the comment
+    fn parse_jsdoc_tag(&mut self, content: &JSDocContent, start: usize, end: usize) -> Arc<Node> {
+        let name_end = content.identifier_end(start + 1, end);
+        let tag_name_text = &content.text[start + 1..name_end];
+        let tag_name = self.create_jsdoc_identifier(content, start + 1, name_end);
+        let pos = content.base + start;
+
+        match tag_name_text {
+            "param" | "arg" | "argument" => {
+                let (type_expression, p) =
+                    self.parse_jsdoc_optional_type_expression(content, name_end, end);
+                let (name, is_bracketed, p) = self.parse_jsdoc_parameter_name(content, p, end);
+                let comment = clean_comment_text(&content.text[p..end]);
+                self.finish_jsdoc_tag(
+                    SyntaxKind::JSDocParameterTag,
+                    pos,
+                    content.base + end,
+                    JSDocParameterTag {
+                        tag_name,
+                        type_expression,
+                        name,
+                        is_bracketed,
+                        comment,
+                    },
+                )
+            }
+            "return" | "returns" => {
+                let (type_expression, p) =
+                    self.parse_jsdoc_optional_type_expression(content, name_end, end);
+                let comment = clean_comment_text(&content.text[p..end]);
+                self.finish_jsdoc_tag(
+                    SyntaxKind::JSDocReturnTag,
+                    pos,
+                    content.base + end,
+                    JSDocReturnTag {
+                        tag_name,
+                        type_expression,
+                        comment,
+                    },
+                )
+            }
+            "type" => {
+                let p = content.skip_whitespace(name_end, end);
+                match self.parse_jsdoc_type_expression(content, p, end, true) {
+                    Some((type_expression, p)) => {
+                        let comment = clean_comment_text(&content.text[p..end]);
+                        self.finish_jsdoc_tag(
+                            SyntaxKind::JSDocTypeTag,
+                            pos,
+                            content.base + end,
+                            JSDocTypeTag {
+                                tag_name,
+                                type_expression,
+                                comment,
+                            },
+                        )
+                    }
+                    None => self.finish_unknown_jsdoc_tag(content, tag_name, start, name_end, end),
+                }
+            }
+            "template" => {
+                let (constraint, p) =
+                    self.parse_jsdoc_optional_type_expression(content, name_end, end);
+                let (type_parameters, p) =
+                    self.parse_jsdoc_template_type_parameters(content, p, end);
+                let comment = clean_comment_text(&content.text[p..end]);
+                self.finish_jsdoc_tag(
+                    SyntaxKind::JSDocTemplateTag,
+                    pos,
+                    content.base + end,
+                    JSDocTemplateTag {
+                        tag_name,
+                        constraint,
+                        type_parameters,
+                        comment,
+                    },
+                )
+            }
+            "typedef" => {
+                let (type_expression, p) =
+                    self.parse_jsdoc_optional_type_expression(content, name_end, end);
+                let p = content.skip_whitespace(p, end);
+                let (full_name, p) = match self.parse_jsdoc_entity_name(content, p, end) {
+                    Some((name, p)) => (Some(name), p),
+                    None => (None, p),
+                };
+                let comment = clean_comment_text(&content.text[p..end]);
+                self.finish_jsdoc_tag(
+                    SyntaxKind::JSDocTypedefTag,
+                    pos,
+                    content.base + end,
+                    JSDocTypedefTag {
+                        tag_name,
+                        type_expression,
+                        full_name,
+                        comment,
+                    },
+                )
+            }
+            "deprecated" => {
+                let comment = clean_comment_text(&content.text[name_end..end]);
+                self.finish_jsdoc_tag(
+                    SyntaxKind::JSDocDeprecatedTag,
+                    pos,
+                    content.base + end,
+                    JSDocDeprecatedTag { tag_name, comment },
+                )
+            }
+            _ => self.finish_unknown_jsdoc_tag(content, tag_name, start, name_end, end),
+        }
+    }
+
+    fn finish_unknown_jsdoc_tag(
+        &mut self,
+        content: &JSDocContent,
+        tag_name: Arc<Node>,
+        start: usize,
+        name_end: usize,
+        end: usize,
+    ) -> Arc<Node> {
+        let comment = clean_comment_text(&content.text[name_end..end]);
+        self.finish_jsdoc_tag(
+            SyntaxKind::JSDocTag,
+            content.base + start,
+            content.base + end,
+            JSDocTag { tag_name, comment },
+        )
+    }
+
+    fn finish_jsdoc_tag(
+        &mut self,
+        kind: SyntaxKind,
+        pos: usize,
+        end: usize,
+        data: impl NodeData,
+    ) -> Arc<Node> {
+        self.finish_node_with_end(kind, pos, end, NodeFlags::JS_DOC, data)
+    }
+
+    /// Parses a `{type}` if one follows the whitespace at `pos`, returning where parsing stopped
+    fn parse_jsdoc_optional_type_expression(
+        &mut self,
+        content: &JSDocContent,
+        pos: usize,
+        end: usize,
+    ) -> (Option<Arc<Node>>, usize) {
+        let p = content.skip_whitespace(pos, end);
+        if content.char_at(p, end) != Some('{') {
+            return (None, pos);
+        }
+        match self.parse_jsdoc_type_expression(content, p, end, false) {
+            Some((type_expression, p)) => (Some(type_expression), p),
+            None => (None, end),
+        }
+    }
+
+    /// Parses a JSDoc type expression at `pos` using the type parser
+    ///
+    /// Braces around the type are required unless `may_omit_braces` is set. Returns `None`
+    /// when the type runs past the end of the tag.
+    fn parse_jsdoc_type_expression(
+        &mut self,
+        content: &JSDocContent,
+        pos: usize,
+        end: usize,
+        may_omit_braces: bool,
+    ) -> Option<(Arc<Node>, usize)> {
+        if pos >= end {
+            return None;
+        }
+        let state = self.mark();
+        let diagnostics_len = self.diagnostics.borrow().len();
+        self.scanner.reset_pos(content.base + pos);
+        self.scanner.set_skip_jsdoc_leading_asterisks(true);
+        self.context_flags |= NodeFlags::JS_DOC;
+        self.next_token();
+
+        let type_pos = self.node_pos();
+        let has_brace = if may_omit_braces {
+            self.parse_optional(SyntaxKind::OpenBraceToken)
+        } else {
+            self.parse_expected(SyntaxKind::OpenBraceToken)
+        };
+        let type_node = self.parse_jsdoc_type();
+        if has_brace {
+            self.parse_expected(SyntaxKind::CloseBraceToken);
+        }
+        let type_end = if has_brace {
+            self.node_pos()
+        } else {
+            type_node.end()
+        };
+        let type_expression = self.finish_node_with_end(
+            SyntaxKind::JSDocTypeExpression,
+            type_pos,
+            type_end,
+            NodeFlags::JS_DOC,
+            JSDocTypeExpression { type_node },
+        );
+
+        let new_diagnostics = self.diagnostics.borrow_mut().split_off(diagnostics_len);
+        self.rewind(state);
+
+        if type_end > content.base + end {
+            self.jsdoc_error(
+                content.base + pos,
+                content.base + end,
+                diagnostics::D_0_EXPECTED_1005,
+                &["}".to_string()],
+            );
+            return None;
+        }
+        self.jsdoc_diagnostics.extend(new_diagnostics);
+        Some((type_expression, type_end - content.base))
+    }
+
+    /// `...T`, `T`, or `T=`
+    fn parse_jsdoc_type(&mut self) -> Arc<Node> {
+        let pos = self.node_pos();
+        let has_dot_dot_dot = self.parse_optional(SyntaxKind::DotDotDotToken);
+        let mut type_node = self.parse_type_or_type_predicate();
+        if has_dot_dot_dot {
+            type_node = self.finish_node(
+                SyntaxKind::JSDocVariadicType,
+                pos,
+                JSDocVariadicType { type_node },
+            );
+        }
+        if self.token == SyntaxKind::EqualsToken {
+            self.next_token();
+            type_node = self.finish_node(
+                SyntaxKind::JSDocOptionalType,
+                pos,
+                JSDocOptionalType { type_node },
+            );
+        }
+        type_node
+    }
+
+    /// Parses `name`, `a.b`, or `[name=default]`, returning the name, whether it was bracketed, and where parsing stopped
+    fn parse_jsdoc_parameter_name(
+        &mut self,
+        content: &JSDocContent,
+        pos: usize,
+        end: usize,
+    ) -> (Arc<Node>, bool, usize) {
+        let mut p = content.skip_whitespace(pos, end);
+        let is_bracketed = content.char_at(p, end) == Some('[');
+        if is_bracketed {
+            p = content.skip_whitespace(p + 1, end);
+        }
+        let (name, mut p) = match self.parse_jsdoc_entity_name(content, p, end) {
+            Some(result) => result,
+            None => {
+                self.jsdoc_error(
+                    content.base + p,
+                    content.base + p,
+                    diagnostics::IDENTIFIER_EXPECTED_1003,
+                    &[],
+                );
+                (self.create_jsdoc_identifier(content, p, p), p)
+            }
+        };
+        if is_bracketed {
+            p = content.skip_whitespace(p, end);
+            if content.char_at(p, end) == Some('=') {
+                p = content.skip_default_value(p + 1, end);
+            }
+            if content.char_at(p, end) == Some(']') {
+                p += 1;
+            } else {
+                self.jsdoc_error(
+                    content.base + p,
+                    content.base + p,
+                    diagnostics::D_0_EXPECTED_1005,
+                    &["]".to_string()],
+                );
+            }
+        }
+        (name, is_bracketed, p)
+    }
+
+    /// Parses `T, U` after `@template`, returning the type parameters and where parsing stopped
+    fn parse_jsdoc_template_type_parameters(
+        &mut self,
+        content: &JSDocContent,
+        pos: usize,
+        end: usize,
+    ) -> (NodeList, usize) {
+        let mut type_parameters = Vec::new();
+        let list_pos = content.skip_whitespace(pos, end);
+        let mut p = list_pos;
+        loop {
+            p = content.skip_whitespace(p, end);
+            let name_end = content.identifier_end(p, end);
+            if name_end == p {
+                if type_parameters.is_empty() {
+                    self.jsdoc_error(
+                        content.base + p,
+                        content.base + p,
+                        diagnostics::IDENTIFIER_EXPECTED_1003,
+                        &[],
+                    );
+                }
+                break;
+            }
+            let name = self.create_jsdoc_identifier(content, p, name_end);
+            type_parameters.push(self.finish_node_with_end(
+                SyntaxKind::TypeParameter,
+                content.base + p,
+                content.base + name_end,
+                NodeFlags::JS_DOC,
+                TypeParameterDeclaration {
+                    modifiers: None,
+                    name,
+                    constraint: None,
+                    default_type: None,
+                },
+            ));
+            p = name_end;
+            let after = content.skip_whitespace(p, end);
+            if content.char_at(after, end) != Some(',') {
+                break;
+            }
+            p = after + 1;
+        }
+        (
+            NodeList::new(content.range(list_pos, p), type_parameters),
+            p,
+        )
+    }
+
+    /// Parses a dotted name such as `a.b.c` at `pos`
+    fn parse_jsdoc_entity_name(
+        &mut self,
+        content: &JSDocContent,
+        pos: usize,
+        end: usize,
+    ) -> Option<(Arc<Node>, usize)> {
+        let mut p = content.identifier_end(pos, end);
+        if p == pos {
+            return None;
+        }
+        let mut name = self.create_jsdoc_identifier(content, pos, p);
+        while content.char_at(p, end) == Some('.') {
+            let right_end = content.identifier_end(p + 1, end);
+            if right_end == p + 1 {
+                break;
+            }
+            let right = self.create_jsdoc_identifier(content, p + 1, right_end);
+            name = self.finish_node_with_end(
+                SyntaxKind::QualifiedName,
+                content.base + pos,
+                content.base + right_end,
+                NodeFlags::JS_DOC,
+                QualifiedName { left: name, right },
+            );
+            p = right_end;
+        }
+        Some((name, p))
+    }
+
+    fn create_jsdoc_identifier(
+        &mut self,
+        content: &JSDocContent,
+        pos: usize,
+        end: usize,
+    ) -> Arc<Node> {
+        self.finish_node_with_end(
+            SyntaxKind::Identifier,
+            content.base + pos,
+            content.base + end,
+            NodeFlags::JS_DOC,
+            Identifier {
+                text: content.text[pos..end].to_string(),
+            },
+        )
+    }
+
+    fn jsdoc_error(&mut self, pos: usize, end: usize, message: &Message, args: &[String]) {
+        let diagnostic = Diagnostic::new(
+            Some(&self.file_name),
+            TextRange::new(pos, end),
+            message,
+            args,
+        );
+        self.jsdoc_diagnostics.push(diagnostic);
+    }
+}
+
+/// The text of a single JSDoc comment, including its `/**` and `*/` delimiters
+///
+/// Positions handed to the methods are relative to the start of the comment; `base` maps
+/// them back to positions in the source file.
+struct JSDocContent {
+    text: String,
+    base: usize,
+    language_version: ScriptTarget,
+}
+
+impl JSDocContent {
+    fn char_at(&self, pos: usize, end: usize) -> Option<char> {
+        if pos < end {
+            self.text[pos..].chars().next()
+        } else {
+            None
+        }
+    }
+
+    fn range(&self, pos: usize, end: usize) -> TextRange {
+        TextRange::new(self.base + pos, self.base + end)
+    }
+
+    /// Skips whitespace, line breaks, and the leading `*` of each continuation line
+    fn skip_whitespace(&self, pos: usize, end: usize) -> usize {
+        let mut p = pos;
+        let mut at_line_start = false;
+        while let Some(ch) = self.char_at(p, end) {
+            if is_line_break(ch) {
+                at_line_start = true;
+            } else if ch == '*' && at_line_start {
+                at_line_start = false;
+            } else if !is_white_space_single_line(ch) {
+                break;
+            }
+            p += ch.len_utf8();
+        }
+        p
+    }
+
+    /// Returns the end of the identifier starting at `pos`, or `pos` if there is none
+    fn identifier_end(&self, pos: usize, end: usize) -> usize {
+        let mut p = pos;
+        if let Some(ch) = self.char_at(p, end)
+            && is_identifier_start(ch, self.language_version)
+        {
+            p += ch.len_utf8();
+            while let Some(ch) = self.char_at(p, end)
+                && is_identifier_part(ch, self.language_version)
+            {
+                p += ch.len_utf8();
+            }
+        }
+        p
+    }
+
+    /// Skips the default value in `[name=default]`, stopping at the closing `]`
+    fn skip_default_value(&self, pos: usize, end: usize) -> usize {
+        let mut p = pos;
+        let mut depth = 0usize;
+        while let Some(ch) = self.char_at(p, end) {
+            match ch {
+                '[' | '{' | '(' => depth += 1,
+                ']' if depth == 0 => break,
+                ']' | '}' | ')' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            p += ch.len_utf8();
+        }
+        p
+    }
+
+    /// Finds the `@` of each tag between `pos` and `end`
+    ///
+    /// A tag must be the first thing on its line, apart from whitespace and a leading `*`.
+    fn tag_starts(&self, pos: usize, end: usize) -> Vec<usize> {
+        let mut starts = Vec::new();
+        let mut p = pos;
+        let mut at_line_start = true;
+        let mut saw_asterisk = false;
+        while let Some(ch) = self.char_at(p, end) {
+            if is_line_break(ch) {
+                at_line_start = true;
+                saw_asterisk = false;
+            } else if ch == '*' && at_line_start && !saw_asterisk {
+                saw_asterisk = true;
+            } else if ch == '@'
+                && at_line_start
+                && self
+                    .char_at(p + 1, end)
+                    .is_some_and(|next| is_identifier_start(next, self.language_version))
+            {
+                starts.push(p);
+                at_line_start = false;
+            } else if !is_white_space_single_line(ch) {
+                at_line_start = false;
+            }
+            p += ch.len_utf8();
+        }
+        starts
+    }
+}
+
+/// Finds the JSDoc comments in the trivia starting at `pos`, as `(pos, end)` pairs
+///
+/// Only terminated `/** ... */` comments count; `/**/` is an ordinary empty comment.
+fn jsdoc_comment_ranges(text: &str, pos: usize) -> Vec<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut ranges = Vec::new();
+    let mut p = pos;
+    while p < bytes.len() {
+        match bytes[p] {
+            b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c => p += 1,
+            b'/' if bytes.get(p + 1) == Some(&b'/') => {
+                p = text[p..].find(['\n', '\r']).map_or(text.len(), |i| p + i);
+            }
+            b'/' if bytes.get(p + 1) == Some(&b'*') => {
+                let Some(close) = text[p + 2..].find("*/") else {
+                    break;
+                };
+                let end = p + 2 + close + 2;
+                if bytes.get(p + 2) == Some(&b'*') && bytes.get(p + 3) != Some(&b'/') {
+                    ranges.push((p, end));
+                }
+                p = end;
+            }
+            _ => {
+                let ch = text[p..].chars().next().unwrap();
+                if is_white_space_single_line(ch) || is_line_break(ch) {
+                    p += ch.len_utf8();
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+    ranges
+}
+
+/// Removes the leading `*` and indentation from each line of comment text
+fn clean_comment_text(text: &str) -> Option<String> {
+    let lines: Vec<&str> = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = line.trim_start();
+            if i == 0 {
+                return line.trim_end();
+            }
+            match line.strip_prefix('*') {
+                Some(rest) => rest.strip_prefix(' ').unwrap_or(rest).trim_end(),
+                None => line.trim_end(),
+            }
+        })
+        .collect();
+    let comment = lines.join("\n");
+    let comment = comment.trim();
+    if comment.is_empty() {
+        None
+    } else {
+        Some(comment.to_string())
+    }
+}
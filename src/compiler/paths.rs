@@ -0,0 +1,277 @@
+//! Paths of files and folders, as strings with `/` separators
+//!
+//! Paths are handled as text rather than with `std::path`, so that they behave the same on
+//! every platform: `\` separators are read as `/`, and a path is rooted if it starts with `/`,
+//! with a drive letter like `c:/`, or with the server and share of a UNC path like
+//! `//server/share/`. Everything after the root is a list of names, where `.` and `..` are
+//! resolved against the names before them, and `..` never goes above the root.
+
+/// Replaces the `\` separators of a path with `/`
+pub fn normalize_slashes(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Gets the length of the root of a path with `/` separators: `/`, a drive like `c:/` or
+/// `c:`, or the `//server/share/` of a UNC path, or 0 for a relative path
+pub fn get_root_length(path: &str) -> usize {
+    let bytes = path.as_bytes();
+    match bytes {
+        [b'/', b'/', ..] => {
+            // The server and share of a UNC path
+            let Some(server_end) = path[2..].find('/').map(|index| index + 2) else {
+                return path.len();
+            };
+            match path[server_end + 1..].find('/') {
+                Some(index) => server_end + 1 + index + 1,
+                None => path.len(),
+            }
+        }
+        [b'/', ..] => 1,
+        [drive, b':', b'/', ..] if drive.is_ascii_alphabetic() => 3,
+        [drive, b':'] if drive.is_ascii_alphabetic() => 2,
+        _ => 0,
+    }
+}
+
+/// Determines if a path starts from a root, rather than from the current folder
+pub fn is_rooted_disk_path(path: &str) -> bool {
+    get_root_length(&normalize_slashes(path)) > 0
+}
+
+/// Determines if a path starts with a `.` or `..` name, which makes it relative to the
+/// current folder, like `./a`, `..\\a`, or `.` itself
+pub fn path_is_relative(path: &str) -> bool {
+    let rest = path
+        .strip_prefix("..")
+        .or_else(|| path.strip_prefix('.'))
+        .unwrap_or(path);
+    rest.len() < path.len() && (rest.is_empty() || rest.starts_with(['/', '\\']))
+}
+
+/// Splits a path into its root, which is empty for a relative path, and the names after it,
+/// with `.` and `..` resolved. Leading `..` names of a relative path are kept, as there is
+/// nothing to resolve them against.
+pub fn get_path_components(path: &str) -> Vec<String> {
+    let path = normalize_slashes(path);
+    let root_length = get_root_length(&path);
+    let mut root = path[..root_length].to_string();
+    if root.starts_with('/') && !root.ends_with('/') {
+        root.push('/');
+    }
+    let rooted = !root.is_empty();
+    let mut components = vec![root];
+    for component in path[root_length..].split('/') {
+        match component {
+            "" | "." => {}
+            ".." if components.len() > 1 && components.last().is_some_and(|last| last != "..") => {
+                components.pop();
+            }
+            // Nothing is above the root
+            ".." if rooted => {}
+            component => components.push(component.to_string()),
+        }
+    }
+    components
+}
+
+/// Joins the root and names of a path that `get_path_components` split
+pub fn get_path_from_path_components(components: &[String]) -> String {
+    match components.split_first() {
+        Some((root, names)) => format!("{root}{}", names.join("/")),
+        None => String::new(),
+    }
+}
+
+/// Resolves the `.` and `..` names of a path and gives it `/` separators, without a trailing
+/// one unless it is a root. The current folder is `.`.
+pub fn normalize_path(path: &str) -> String {
+    let normalized = get_path_from_path_components(&get_path_components(path));
+    if normalized.is_empty() {
+        ".".to_string()
+    } else {
+        normalized
+    }
+}
+
+/// Joins a relative path onto a folder, and normalizes the result. A rooted path is returned
+/// as it is, apart from being normalized.
+pub fn combine_paths(directory: &str, path: &str) -> String {
+    if is_rooted_disk_path(path) || directory.is_empty() || directory == "." {
+        return normalize_path(path);
+    }
+    normalize_path(&format!(
+        "{}/{path}",
+        directory.trim_end_matches(['/', '\\'])
+    ))
+}
+
+/// Adds a trailing `/` to a folder, so that it is a prefix of the paths in it
+pub fn ensure_trailing_directory_separator(path: &str) -> String {
+    if path.ends_with('/') {
+        path.to_string()
+    } else {
+        format!("{path}/")
+    }
+}
+
+/// Gets the folder containing a file or folder, which is the root for the root itself, and
+/// `.` for a relative path of one name
+pub fn get_directory_path(path: &str) -> String {
+    let components = get_path_components(path);
+    if components.len() < 2 {
+        return normalize_path(path);
+    }
+    normalize_path(&get_path_from_path_components(
+        &components[..components.len() - 1],
+    ))
+}
+
+/// Gets a folder and each folder above it, up to the root
+pub fn get_ancestor_directories(directory: &str) -> impl Iterator<Item = String> {
+    std::iter::successors(Some(normalize_path(directory)), |directory| {
+        let parent = get_directory_path(directory);
+        (parent != *directory).then_some(parent)
+    })
+}
+
+/// Gets the name of a file or folder, the last name of its path, which a root has none of
+pub fn get_base_file_name(path: &str) -> &str {
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    let root_length = get_root_length(&normalize_slashes(trimmed));
+    let name_start = trimmed
+        .rfind(['/', '\\'])
+        .map_or(0, |index| index + 1)
+        .max(root_length);
+    &trimmed[name_start.min(trimmed.len())..]
+}
+
+/// Gets the path of `path` relative to the folder `directory`, with `/` separators, comparing
+/// names case-insensitively with `ignore_case`. A path with a different root than the folder,
+/// like one on another drive, cannot be made relative, and is only normalized.
+pub fn get_relative_path_from_directory(directory: &str, path: &str, ignore_case: bool) -> String {
+    let directory = get_path_components(directory);
+    let path = get_path_components(path);
+    let same = |a: &String, b: &String| {
+        if ignore_case {
+            a.to_lowercase() == b.to_lowercase()
+        } else {
+            a == b
+        }
+    };
+    if !same(&directory[0], &path[0]) {
+        return get_path_from_path_components(&path);
+    }
+    let shared = directory
+        .iter()
+        .zip(&path)
+        .take_while(|(a, b)| same(a, b))
+        .count();
+    let mut names = vec![".."; directory.len() - shared];
+    names.extend(path[shared..].iter().map(String::as_str));
+    names.join("/")
+}
+
+/// Gets the deepest folder that contains all of `directories`, or None if there are none,
+/// or if they do not share a root. Names are compared case-insensitively with `ignore_case`,
+/// and keep the casing of the first folder.
+pub fn get_common_directory<'a>(
+    directories: impl IntoIterator<Item = &'a str>,
+    ignore_case: bool,
+) -> Option<String> {
+    let same = |a: &String, b: &String| {
+        if ignore_case {
+            a.to_lowercase() == b.to_lowercase()
+        } else {
+            a == b
+        }
+    };
+    let mut common: Option<Vec<String>> = None;
+    for directory in directories {
+        let components = get_path_components(directory);
+        common = Some(match common {
+            None => components,
+            Some(common) => {
+                let shared = common
+                    .iter()
+                    .zip(&components)
+                    .take_while(|(a, b)| same(a, b))
+                    .count();
+                if shared == 0 {
+                    return None;
+                }
+                common[..shared].to_vec()
+            }
+        });
+    }
+    common.map(|common| get_path_from_path_components(&common))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_roots_of_paths() {
+        assert_eq!(get_root_length("/a/b"), 1);
+        assert_eq!(get_root_length("c:/a"), 3);
+        assert_eq!(get_root_length("c:"), 2);
+        assert_eq!(get_root_length("//server/share/a"), 15);
+        assert_eq!(get_root_length("//server"), 8);
+        assert_eq!(get_root_length("a/b"), 0);
+        assert!(is_rooted_disk_path("C:\\a"));
+        assert!(!is_rooted_disk_path("./a"));
+        assert!(path_is_relative(".\\a"));
+        assert!(path_is_relative(".."));
+        assert!(!path_is_relative(".a"));
+        assert!(!path_is_relative("...\\a"));
+    }
+
+    #[test]
+    fn normalizes_and_combines_paths() {
+        assert_eq!(normalize_path("a/./b/../c/"), "a/c");
+        assert_eq!(normalize_path("../a/.."), "..");
+        assert_eq!(normalize_path("/.."), "/");
+        assert_eq!(normalize_path("C:\\a\\..\\b"), "C:/b");
+        assert_eq!(normalize_path("//server/share/a/../.."), "//server/share/");
+        assert_eq!(normalize_path("a/.."), ".");
+        assert_eq!(combine_paths("/a/b", "../c"), "/a/c");
+        assert_eq!(combine_paths("/a", "d:/c"), "d:/c");
+        assert_eq!(combine_paths(".", "c"), "c");
+        assert_eq!(get_directory_path("/a/b.ts"), "/a");
+        assert_eq!(get_directory_path("b.ts"), ".");
+        assert_eq!(get_directory_path("c:/"), "c:/");
+        assert_eq!(get_base_file_name("/a/b.ts"), "b.ts");
+        assert_eq!(get_base_file_name("/a/b/"), "b");
+        assert_eq!(get_base_file_name("/"), "");
+        assert_eq!(
+            get_ancestor_directories("C:\\a\\b").collect::<Vec<_>>(),
+            ["C:/a/b", "C:/a", "C:/"]
+        );
+    }
+
+    #[test]
+    fn computes_relative_paths_and_common_directories() {
+        assert_eq!(
+            get_relative_path_from_directory("/a/b", "/a/c/d.ts", false),
+            "../c/d.ts"
+        );
+        assert_eq!(get_relative_path_from_directory("/a", "/a", false), "");
+        assert_eq!(
+            get_relative_path_from_directory("C:/A", "c:/a/b", true),
+            "b"
+        );
+        assert_eq!(
+            get_relative_path_from_directory("c:/a", "d:/a/b", false),
+            "d:/a/b"
+        );
+        assert_eq!(
+            get_common_directory(["/a/b/c", "/a/b/d", "/a/b"], false).as_deref(),
+            Some("/a/b")
+        );
+        assert_eq!(
+            get_common_directory(["C:/Src/a", "c:/src/b"], true).as_deref(),
+            Some("C:/Src")
+        );
+        assert_eq!(get_common_directory(["c:/a", "d:/a"], false), None);
+    }
+}
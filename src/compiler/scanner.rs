@@ -1,4 +1,5 @@
 use crate::compiler::diagnostics::{self, Message};
+use crate::compiler::text::TextRange;
 
 use super::ast::SyntaxKind;
 // use crate::compiler::types::SyntaxKind;
@@ -116,6 +117,8 @@ pub enum ScriptTarget {
     ES2020,
     ES2021,
     ES2022,
+    ES2023,
+    ES2024,
     ESNext,
     Latest,
 }
@@ -146,19 +149,6 @@ pub struct SkipTriviaOptions {
     pub in_jsdoc: bool,
 }
 
-/// Represents a text range
-#[derive(Debug, Clone, Copy)]
-pub struct TextRange {
-    pub start: usize,
-    pub end: usize,
-}
-
-impl TextRange {
-    pub fn new(start: usize, end: usize) -> Self {
-        TextRange { start, end }
-    }
-}
-
 /// Comment directive structure
 #[derive(Debug, Clone)]
 pub struct CommentDirective {
@@ -256,6 +246,11 @@ impl Scanner {
         self.jsdoc_parsing_mode = mode;
     }
 
+    /// Gets the JSDoc parsing mode
+    pub fn jsdoc_parsing_mode(&self) -> JSDocParsingMode {
+        self.jsdoc_parsing_mode
+    }
+
     /// Sets the language variant
     pub fn set_language_variant(&mut self, variant: LanguageVariant) {
         self.language_variant = variant;
@@ -308,10 +303,7 @@ impl Scanner {
 
     /// Gets the token's range
     pub fn token_range(&self) -> TextRange {
-        TextRange {
-            start: self.state.token_start,
-            end: self.state.pos,
-        }
+        TextRange::new(self.state.token_start, self.state.pos)
     }
 
     /// Marks the current scanner state
@@ -381,12 +373,12 @@ impl Scanner {
 
     /// Gets the character at the current position
     fn char(&self) -> Option<char> {
-        self.text.chars().nth(self.state.pos)
+        self.text.get(self.state.pos..)?.chars().next()
     }
 
-    /// Gets the character at a specific offset from the current position
+    /// Gets the character `offset` characters after the current position
     fn char_at(&self, offset: usize) -> Option<char> {
-        self.text.chars().nth(self.state.pos + offset)
+        self.text.get(self.state.pos..)?.chars().nth(offset)
     }
 
     /// Gets the current character and its size
@@ -401,29 +393,31 @@ impl Scanner {
         (Some(ch), size)
     }
 
-    /// Checks if JSDoc should be parsed
-    fn should_parse_jsdoc(&self) -> bool {
+    /// Checks if the given `/** ... */` comment should be parsed as JSDoc
+    fn should_parse_jsdoc(&self, comment: &str) -> bool {
         match self.jsdoc_parsing_mode {
-            JSDocParsingMode::ParseAll => true,
-            JSDocParsingMode::ParseForTypeErrors | JSDocParsingMode::ParseForTypeInfo => {
-                // Parse JSDoc in declaration files and in JS files with checkJs
-                // TODO: Implement checkJs logic
-                false
-            }
-            JSDocParsingMode::ParseNone => false,
+            JSDocParsingMode::ParseAll => return true,
+            JSDocParsingMode::ParseNone => return false,
+            _ => {}
         }
+        if self.script_kind != ScriptKind::TS && self.script_kind != ScriptKind::TSX {
+            // If outside of TS, we need JSDoc to get any type info.
+            return true;
+        }
+        if self.jsdoc_parsing_mode == JSDocParsingMode::ParseForTypeInfo {
+            // If we're in TS, but we don't need to produce reliable errors,
+            // we don't need to parse to find @see or @link.
+            return false;
+        }
+        // Otherwise, only parse if there's @see or @link (they affect unused imports)
+        contains_jsdoc_see_or_link(comment)
     }
 
     /// Scans the next token
     pub fn scan(&mut self) -> SyntaxKind {
         self.state.full_start_pos = self.state.pos;
         self.state.token_flags = TokenFlags::NONE;
-
-        // Check for the end of the file
-        if self.state.pos >= self.text.len() {
-            self.state.token = SyntaxKind::EndOfFile;
-            return self.state.token;
-        }
+        self.state.token_value = String::new();
 
         // Skip trivia
         if self.skip_trivia {
@@ -432,7 +426,7 @@ impl Scanner {
 
         self.state.token_start = self.state.pos;
 
-        // Re-scan after skipping trivia
+        // Check for the end of the file
         if self.state.pos >= self.text.len() {
             self.state.token = SyntaxKind::EndOfFile;
             return self.state.token;
@@ -443,103 +437,527 @@ impl Scanner {
         let ch = ch_opt.unwrap();
 
         // Process different characters
-        match ch {
-            // Process various characters
+        self.state.token = match ch {
             '!' => {
                 if self.char_at(1) == Some('=') {
                     if self.char_at(2) == Some('=') {
                         self.state.pos += 3;
-                        self.state.token = SyntaxKind::ExclamationEqualsEqualsToken;
+                        SyntaxKind::ExclamationEqualsEqualsToken
                     } else {
                         self.state.pos += 2;
-                        self.state.token = SyntaxKind::ExclamationEqualsToken;
+                        SyntaxKind::ExclamationEqualsToken
                     }
                 } else {
                     self.state.pos += 1;
-                    self.state.token = SyntaxKind::ExclamationToken;
+                    SyntaxKind::ExclamationToken
                 }
             }
             '"' | '\'' => {
                 self.state.token_value = self.scan_string(false);
-                self.state.token = SyntaxKind::StringLiteral;
+                SyntaxKind::StringLiteral
             }
-            '`' => {
-                self.state.token = self.scan_template_and_set_token_value(false);
+            '`' => self.scan_template_and_set_token_value(false),
+            '%' => self.scan_operator(SyntaxKind::PercentToken, SyntaxKind::PercentEqualsToken),
+            '&' => {
+                if self.char_at(1) == Some('&') {
+                    if self.char_at(2) == Some('=') {
+                        self.state.pos += 3;
+                        SyntaxKind::AmpersandAmpersandEqualsToken
+                    } else {
+                        self.state.pos += 2;
+                        SyntaxKind::AmpersandAmpersandToken
+                    }
+                } else {
+                    self.scan_operator(SyntaxKind::AmpersandToken, SyntaxKind::AmpersandEqualsToken)
+                }
+            }
+            '(' => self.scan_single(SyntaxKind::OpenParenToken),
+            ')' => self.scan_single(SyntaxKind::CloseParenToken),
+            '*' => {
+                if self.char_at(1) == Some('*') {
+                    if self.char_at(2) == Some('=') {
+                        self.state.pos += 3;
+                        SyntaxKind::AsteriskAsteriskEqualsToken
+                    } else {
+                        self.state.pos += 2;
+                        SyntaxKind::AsteriskAsteriskToken
+                    }
+                } else {
+                    self.scan_operator(SyntaxKind::AsteriskToken, SyntaxKind::AsteriskEqualsToken)
+                }
+            }
+            '+' => {
+                if self.char_at(1) == Some('+') {
+                    self.state.pos += 2;
+                    SyntaxKind::PlusPlusToken
+                } else {
+                    self.scan_operator(SyntaxKind::PlusToken, SyntaxKind::PlusEqualsToken)
+                }
+            }
+            ',' => self.scan_single(SyntaxKind::CommaToken),
+            '-' => {
+                if self.char_at(1) == Some('-') {
+                    self.state.pos += 2;
+                    SyntaxKind::MinusMinusToken
+                } else {
+                    self.scan_operator(SyntaxKind::MinusToken, SyntaxKind::MinusEqualsToken)
+                }
+            }
+            '.' => {
+                if self.char_at(1).is_some_and(is_digit) {
+                    self.scan_number()
+                } else if self.char_at(1) == Some('.') && self.char_at(2) == Some('.') {
+                    self.state.pos += 3;
+                    SyntaxKind::DotDotDotToken
+                } else {
+                    self.scan_single(SyntaxKind::DotToken)
+                }
             }
             '/' => {
-                // Check for comments or divide token
+                // Comments are only returned as tokens when trivia is not being skipped
                 if self.char_at(1) == Some('/') {
                     self.state.pos += 2;
-                    while self.state.pos < self.text.len() {
-                        let ch = self.char().unwrap();
+                    while let Some(ch) = self.char() {
                         if is_line_break(ch) {
                             break;
                         }
                         self.state.pos += ch.len_utf8();
                     }
-
-                    // Process comment directives if needed
-                    // self.process_comment_directive(token_start, self.state.pos);
-
-                    if self.skip_trivia {
-                        return self.scan();
-                    }
-
-                    self.state.token = SyntaxKind::SingleLineCommentTrivia;
+                    SyntaxKind::SingleLineCommentTrivia
                 } else if self.char_at(1) == Some('*') {
                     self.state.pos += 2;
-                    let is_jsdoc = self.char_at(0) == Some('*');
-                    let comment_start = self.state.pos - 2;
+                    let is_jsdoc = self.char() == Some('*') && self.char_at(1) != Some('/');
+                    let mut terminated = false;
 
-                    while self.state.pos < self.text.len() {
-                        if self.char() == Some('*') && self.char_at(1) == Some('/') {
+                    while let Some(ch) = self.char() {
+                        if ch == '*' && self.char_at(1) == Some('/') {
                             self.state.pos += 2;
+                            terminated = true;
                             break;
                         }
-                        self.state.pos += self.char().unwrap().len_utf8();
+                        if is_line_break(ch) {
+                            self.state.token_flags.add(TokenFlags::PRECEDING_LINE_BREAK);
+                        }
+                        self.state.pos += ch.len_utf8();
                     }
 
-                    if is_jsdoc && self.should_parse_jsdoc() {
+                    if !terminated {
+                        self.error(diagnostics::ASTERISK_SLASH_EXPECTED_1010);
+                    }
+
+                    if is_jsdoc
+                        && self
+                            .should_parse_jsdoc(&self.text[self.state.token_start..self.state.pos])
+                    {
                         self.state
                             .token_flags
                             .add(TokenFlags::PRECEDING_JSDOC_COMMENT);
-                        // Handle JSDoc leading asterisks if needed
                     }
 
-                    if self.skip_trivia {
-                        return self.scan();
+                    SyntaxKind::MultiLineCommentTrivia
+                } else {
+                    self.scan_operator(SyntaxKind::SlashToken, SyntaxKind::SlashEqualsToken)
+                }
+            }
+            '0'..='9' => self.scan_number(),
+            ':' => self.scan_single(SyntaxKind::ColonToken),
+            ';' => self.scan_single(SyntaxKind::SemicolonToken),
+            '<' => {
+                if self.char_at(1) == Some('<') {
+                    if self.char_at(2) == Some('=') {
+                        self.state.pos += 3;
+                        SyntaxKind::LessThanLessThanEqualsToken
+                    } else {
+                        self.state.pos += 2;
+                        SyntaxKind::LessThanLessThanToken
                     }
-
-                    self.state.token = SyntaxKind::MultiLineCommentTrivia;
                 } else if self.char_at(1) == Some('=') {
                     self.state.pos += 2;
-                    self.state.token = SyntaxKind::SlashEqualsToken;
+                    SyntaxKind::LessThanEqualsToken
+                } else if self.language_variant == LanguageVariant::JSX
+                    && self.char_at(1) == Some('/')
+                    && self.char_at(2) != Some('*')
+                {
+                    self.state.pos += 2;
+                    SyntaxKind::LessThanSlashToken
                 } else {
-                    self.state.pos += 1;
-                    self.state.token = SyntaxKind::SlashToken;
+                    self.scan_single(SyntaxKind::LessThanToken)
+                }
+            }
+            '=' => {
+                if self.char_at(1) == Some('=') {
+                    if self.char_at(2) == Some('=') {
+                        self.state.pos += 3;
+                        SyntaxKind::EqualsEqualsEqualsToken
+                    } else {
+                        self.state.pos += 2;
+                        SyntaxKind::EqualsEqualsToken
+                    }
+                } else if self.char_at(1) == Some('>') {
+                    self.state.pos += 2;
+                    SyntaxKind::EqualsGreaterThanToken
+                } else {
+                    self.scan_single(SyntaxKind::EqualsToken)
                 }
             }
-            // Additional character cases
-            '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => {
-                self.state.token = self.scan_number();
+            // `>` is always scanned as a single token; the parser rescans it in
+            // expression contexts so that `>>` is never produced inside type arguments.
+            '>' => self.scan_single(SyntaxKind::GreaterThanToken),
+            '?' => {
+                if self.char_at(1) == Some('.') && !self.char_at(2).is_some_and(is_digit) {
+                    self.state.pos += 2;
+                    SyntaxKind::QuestionDotToken
+                } else if self.char_at(1) == Some('?') {
+                    if self.char_at(2) == Some('=') {
+                        self.state.pos += 3;
+                        SyntaxKind::QuestionQuestionEqualsToken
+                    } else {
+                        self.state.pos += 2;
+                        SyntaxKind::QuestionQuestionToken
+                    }
+                } else {
+                    self.scan_single(SyntaxKind::QuestionToken)
+                }
+            }
+            '[' => self.scan_single(SyntaxKind::OpenBracketToken),
+            ']' => self.scan_single(SyntaxKind::CloseBracketToken),
+            '^' => self.scan_operator(SyntaxKind::CaretToken, SyntaxKind::CaretEqualsToken),
+            '{' => self.scan_single(SyntaxKind::OpenBraceToken),
+            '|' => {
+                if self.char_at(1) == Some('|') {
+                    if self.char_at(2) == Some('=') {
+                        self.state.pos += 3;
+                        SyntaxKind::BarBarEqualsToken
+                    } else {
+                        self.state.pos += 2;
+                        SyntaxKind::BarBarToken
+                    }
+                } else {
+                    self.scan_operator(SyntaxKind::BarToken, SyntaxKind::BarEqualsToken)
+                }
+            }
+            '}' => self.scan_single(SyntaxKind::CloseBraceToken),
+            '~' => self.scan_single(SyntaxKind::TildeToken),
+            '@' => self.scan_single(SyntaxKind::AtToken),
+            '#' => {
+                self.state.pos += 1;
+                if self.char().is_some_and(|ch| {
+                    is_identifier_start(ch, self.language_version)
+                        || ch == '\\' && self.char_at(1) == Some('u')
+                }) && self.scan_identifier(0)
+                {
+                    self.state.token_value = format!("#{}", self.state.token_value);
+                    SyntaxKind::PrivateIdentifier
+                } else {
+                    self.error(diagnostics::INVALID_CHARACTER_1127);
+                    self.state.token_value = "#".to_string();
+                    SyntaxKind::Unknown
+                }
+            }
+            '\\' => {
+                // An identifier that starts with a Unicode escape
+                if self.scan_identifier(0) {
+                    Self::get_identifier_token(&self.state.token_value)
+                } else {
+                    self.error(diagnostics::INVALID_CHARACTER_1127);
+                    self.state.pos += 1;
+                    SyntaxKind::Unknown
+                }
             }
-            // Other character cases would be implemented here
-            // ...
             _ => {
                 // Check for identifiers and other tokens
                 if is_identifier_start(ch, self.language_version) {
                     if self.scan_identifier(0) {
-                        self.state.token = Self::get_identifier_token(&self.state.token_value);
+                        Self::get_identifier_token(&self.state.token_value)
                     } else {
                         self.scan_invalid_character();
+                        SyntaxKind::Unknown
                     }
+                } else if is_line_break(ch) {
+                    // Only reachable when trivia is not being skipped
+                    self.state.pos += ch.len_utf8();
+                    if ch == '\r' && self.char() == Some('\n') {
+                        self.state.pos += 1;
+                    }
+                    self.state.token_flags.add(TokenFlags::PRECEDING_LINE_BREAK);
+                    SyntaxKind::NewLineTrivia
+                } else if is_white_space_single_line(ch) {
+                    while let Some(ch) = self.char() {
+                        if !is_white_space_single_line(ch) {
+                            break;
+                        }
+                        self.state.pos += ch.len_utf8();
+                    }
+                    SyntaxKind::WhitespaceTrivia
                 } else {
                     self.scan_invalid_character();
+                    SyntaxKind::Unknown
+                }
+            }
+        };
+
+        self.state.token
+    }
+
+    /// Advances past a single-character token
+    fn scan_single(&mut self, kind: SyntaxKind) -> SyntaxKind {
+        self.state.pos += 1;
+        kind
+    }
+
+    /// Scans a single-character operator that may be followed by `=` to form a compound assignment
+    fn scan_operator(&mut self, kind: SyntaxKind, equals_kind: SyntaxKind) -> SyntaxKind {
+        if self.char_at(1) == Some('=') {
+            self.state.pos += 2;
+            equals_kind
+        } else {
+            self.state.pos += 1;
+            kind
+        }
+    }
+
+    /// Rescans a `>` token as one of the longer operators that start with `>`
+    ///
+    /// The scanner never produces `>>`, `>=`, etc. on its own, because in type argument
+    /// lists those characters close nested lists one at a time (`Array<Array<number>>`).
+    pub fn re_scan_greater_token(&mut self) -> SyntaxKind {
+        if self.state.token == SyntaxKind::GreaterThanToken {
+            self.state.token = match (self.char(), self.char_at(1), self.char_at(2)) {
+                (Some('>'), Some('>'), Some('=')) => {
+                    self.state.pos += 3;
+                    SyntaxKind::GreaterThanGreaterThanGreaterThanEqualsToken
+                }
+                (Some('>'), Some('>'), _) => {
+                    self.state.pos += 2;
+                    SyntaxKind::GreaterThanGreaterThanGreaterThanToken
+                }
+                (Some('>'), Some('='), _) => {
+                    self.state.pos += 2;
+                    SyntaxKind::GreaterThanGreaterThanEqualsToken
+                }
+                (Some('>'), _, _) => {
+                    self.state.pos += 1;
+                    SyntaxKind::GreaterThanGreaterThanToken
+                }
+                (Some('='), _, _) => {
+                    self.state.pos += 1;
+                    SyntaxKind::GreaterThanEqualsToken
+                }
+                _ => SyntaxKind::GreaterThanToken,
+            };
+        }
+        self.state.token
+    }
+
+    /// Rescans a `<<` token as a single `<`, used when `<<` starts a type argument list
+    /// (`f<<T>(x: T) => T>(g)`)
+    pub fn re_scan_less_than_token(&mut self) -> SyntaxKind {
+        if self.state.token == SyntaxKind::LessThanLessThanToken {
+            self.state.pos = self.state.token_start + 1;
+            self.state.token = SyntaxKind::LessThanToken;
+        }
+        self.state.token
+    }
+
+    /// Rescans a `/` or `/=` token as a regular expression literal
+    pub fn re_scan_slash_token(&mut self) -> SyntaxKind {
+        if self.state.token != SyntaxKind::SlashToken
+            && self.state.token != SyntaxKind::SlashEqualsToken
+        {
+            return self.state.token;
+        }
+
+        let mut pos = self.state.token_start + 1;
+        let mut in_escape = false;
+        let mut in_character_class = false;
+
+        loop {
+            let Some(ch) = self.text[pos..].chars().next() else {
+                self.state.token_flags.add(TokenFlags::UNTERMINATED);
+                self.error_at(
+                    diagnostics::UNTERMINATED_REGULAR_EXPRESSION_LITERAL_1161,
+                    self.state.token_start,
+                    pos - self.state.token_start,
+                    &[],
+                );
+                break;
+            };
+
+            if is_line_break(ch) {
+                self.state.token_flags.add(TokenFlags::UNTERMINATED);
+                self.error_at(
+                    diagnostics::UNTERMINATED_REGULAR_EXPRESSION_LITERAL_1161,
+                    self.state.token_start,
+                    pos - self.state.token_start,
+                    &[],
+                );
+                break;
+            }
+
+            pos += ch.len_utf8();
+
+            if in_escape {
+                in_escape = false;
+            } else if ch == '/' && !in_character_class {
+                break;
+            } else if ch == '[' {
+                in_character_class = true;
+            } else if ch == '\\' {
+                in_escape = true;
+            } else if ch == ']' {
+                in_character_class = false;
+            }
+        }
+
+        // Consume the regular expression flags
+        while let Some(ch) = self.text[pos..].chars().next() {
+            if !is_identifier_part(ch, self.language_version) {
+                break;
+            }
+            pos += ch.len_utf8();
+        }
+
+        self.state.pos = pos;
+        self.state.token_value = self.text[self.state.token_start..pos].to_string();
+        self.state.token = SyntaxKind::RegularExpressionLiteral;
+        self.state.token
+    }
+
+    /// Rescans a `}` token as the continuation of a template literal
+    pub fn re_scan_template_token(&mut self, is_tagged_template: bool) -> SyntaxKind {
+        self.state.pos = self.state.token_start;
+        self.state.token = self.scan_template_and_set_token_value(!is_tagged_template);
+        self.state.token
+    }
+
+    /// Rescans the current token as the start of a JSX token
+    pub fn re_scan_jsx_token(&mut self, allow_multiline_jsx_text: bool) -> SyntaxKind {
+        self.state.pos = self.state.full_start_pos;
+        self.state.token_start = self.state.full_start_pos;
+        self.scan_jsx_token(allow_multiline_jsx_text)
+    }
+
+    /// Scans a token in JSX child position: text, `{`, `<` or `</`
+    pub fn scan_jsx_token(&mut self, allow_multiline_jsx_text: bool) -> SyntaxKind {
+        self.state.full_start_pos = self.state.pos;
+        self.state.token_start = self.state.pos;
+        self.state.token_flags = TokenFlags::NONE;
+
+        let Some(ch) = self.char() else {
+            self.state.token = SyntaxKind::EndOfFile;
+            return self.state.token;
+        };
+
+        if ch == '<' {
+            if self.char_at(1) == Some('/') {
+                self.state.pos += 2;
+                self.state.token = SyntaxKind::LessThanSlashToken;
+            } else {
+                self.state.pos += 1;
+                self.state.token = SyntaxKind::LessThanToken;
+            }
+            return self.state.token;
+        }
+
+        if ch == '{' {
+            self.state.pos += 1;
+            self.state.token = SyntaxKind::OpenBraceToken;
+            return self.state.token;
+        }
+
+        // First non-whitespace character on this line.
+        let mut first_non_whitespace: Option<usize> = None;
+        let mut last_non_whitespace: Option<usize> = None;
+
+        while let Some(ch) = self.char() {
+            if ch == '{' || ch == '<' {
+                break;
+            }
+            if ch == '>' || ch == '}' {
+                self.error_at(diagnostics::UNEXPECTED_TOKEN_1012, self.state.pos, 1, &[]);
+            }
+
+            if is_line_break(ch) && first_non_whitespace.is_none() {
+                self.state.token_flags.add(TokenFlags::PRECEDING_LINE_BREAK);
+            } else if !allow_multiline_jsx_text
+                && is_line_break(ch)
+                && first_non_whitespace.is_some()
+            {
+                break;
+            } else if !ch.is_whitespace() {
+                last_non_whitespace = Some(self.state.pos);
+                if first_non_whitespace.is_none() {
+                    first_non_whitespace = Some(self.state.pos);
                 }
             }
+
+            self.state.pos += ch.len_utf8();
         }
 
-        return self.state.token;
+        self.state.token_value = self.text[self.state.full_start_pos..self.state.pos].to_string();
+        self.state.token = if last_non_whitespace.is_none() {
+            SyntaxKind::JsxTextAllWhiteSpaces
+        } else {
+            SyntaxKind::JsxText
+        };
+        self.state.token
+    }
+
+    /// Extends an identifier or keyword token to include the `-` characters allowed in
+    /// JSX tag and attribute names
+    pub fn scan_jsx_identifier(&mut self) -> SyntaxKind {
+        if is_identifier_or_keyword(self.state.token) {
+            while let Some(ch) = self.char() {
+                if ch == '-' || is_identifier_part(ch, self.language_version) {
+                    self.state.pos += ch.len_utf8();
+                } else {
+                    break;
+                }
+            }
+            self.state.token_value = self.text[self.state.token_start..self.state.pos].to_string();
+            self.state.token = SyntaxKind::Identifier;
+        }
+        self.state.token
+    }
+
+    /// Scans a JSX attribute value, where string literals do not support escapes
+    pub fn scan_jsx_attribute_value(&mut self) -> SyntaxKind {
+        self.state.full_start_pos = self.state.pos;
+        self.state.token_flags = TokenFlags::NONE;
+        if self.skip_trivia {
+            self.state.token_flags = self.skip_trivia();
+        }
+        self.state.token_start = self.state.pos;
+
+        match self.char() {
+            Some('"') | Some('\'') => {
+                self.state.token_value = self.scan_string(true);
+                self.state.token = SyntaxKind::StringLiteral;
+                self.state.token
+            }
+            _ => {
+                self.state.pos = self.state.full_start_pos;
+                self.scan()
+            }
+        }
+    }
+
+    /// Runs `callback` and always restores the scanner to the state it was in beforehand
+    pub fn look_ahead<T>(&mut self, callback: impl FnOnce(&mut Self) -> T) -> T {
+        let state = self.mark();
+        let result = callback(self);
+        self.rewind(state);
+        result
+    }
+
+    /// Runs `callback` and restores the scanner state only if it returns a falsy result
+    pub fn try_scan<T: Default + PartialEq>(&mut self, callback: impl FnOnce(&mut Self) -> T) -> T {
+        let state = self.mark();
+        let result = callback(self);
+        if result == T::default() {
+            self.rewind(state);
+        }
+        result
     }
 
     /// Skip trivia such as whitespace and comments
@@ -554,17 +972,40 @@ impl Scanner {
 
             let ch = self.text[pos..].chars().next().unwrap();
 
-            if ch == ' ' || ch == '\t' || ch == '\x0B' || ch == '\x0C' {
+            // A shebang line is only valid at the very start of the file
+            if pos == 0 && ch == '#' && self.text[pos..].starts_with("#!") {
+                while pos < self.text.len() {
+                    let comment_ch = self.text[pos..].chars().next().unwrap();
+                    if is_line_break(comment_ch) {
+                        break;
+                    }
+                    pos += comment_ch.len_utf8();
+                }
+                continue;
+            }
+
+            if is_white_space_single_line(ch) {
                 pos += ch.len_utf8();
                 continue;
             }
 
-            if ch == '\n' || ch == '\r' {
+            if is_line_break(ch) {
                 token_flags.add(TokenFlags::PRECEDING_LINE_BREAK);
                 pos += ch.len_utf8();
                 continue;
             }
 
+            // Inside a JSDoc comment, the `*` that starts each continuation line is trivia
+            if ch == '*'
+                && self.state.skip_jsdoc_leading_asterisks != 0
+                && token_flags.contains(TokenFlags::PRECEDING_LINE_BREAK)
+                && !token_flags.contains(TokenFlags::PRECEDING_JSDOC_LEADING_ASTERISKS)
+            {
+                token_flags.add(TokenFlags::PRECEDING_JSDOC_LEADING_ASTERISKS);
+                pos += 1;
+                continue;
+            }
+
             // Check for comments
             if ch == '/' {
                 let next_pos = pos + 1;
@@ -586,6 +1027,7 @@ impl Scanner {
 
                     if next_ch == '*' {
                         // Multi-line comment
+                        let comment_start = pos;
                         let is_jsdoc = pos + 2 < self.text.len()
                             && self.text[pos + 2..].chars().next().unwrap() == '*';
 
@@ -609,7 +1051,7 @@ impl Scanner {
                             pos += comment_ch.len_utf8();
                         }
 
-                        if is_jsdoc && self.should_parse_jsdoc() {
+                        if is_jsdoc && self.should_parse_jsdoc(&self.text[comment_start..pos]) {
                             token_flags.add(TokenFlags::PRECEDING_JSDOC_COMMENT);
                         }
 
@@ -626,54 +1068,89 @@ impl Scanner {
         token_flags
     }
 
-    /// Scans an identifier
+    /// Scans an identifier, whose characters may be written as Unicode escapes like `\u0061`
+    /// and `\u{61}`. The token value is the identifier with its escapes replaced by the
+    /// characters they stand for.
     fn scan_identifier(&mut self, prefix_length: usize) -> bool {
         let start = self.state.pos;
         self.state.pos += prefix_length;
 
-        if self.state.pos >= self.text.len() {
+        let Some(first_ch) = self.char() else {
             return false;
-        }
-
-        let first_ch = self.char().unwrap();
+        };
+        let mut value = self.text[start..self.state.pos].to_string();
 
         // Fast path for ASCII identifiers
-        if (first_ch >= 'a' && first_ch <= 'z')
-            || (first_ch >= 'A' && first_ch <= 'Z')
-            || first_ch == '_'
-            || first_ch == '$'
-        {
-            self.state.pos += first_ch.len_utf8();
-
-            while self.state.pos < self.text.len() {
-                let ch = self.char().unwrap();
-                if !is_identifier_part(ch, self.language_version) {
-                    break;
+        let first = if first_ch.is_ascii_alphabetic() || first_ch == '_' || first_ch == '$' {
+            self.state.pos += 1;
+            first_ch
+        } else if first_ch == '\\' {
+            match self.peek_unicode_escape() {
+                Some(ch) if is_identifier_start(ch, self.language_version) => {
+                    self.scan_identifier_unicode_escape()
                 }
-                self.state.pos += ch.len_utf8();
+                _ => return false,
             }
-
-            self.state.token_value = self.text[start..self.state.pos].to_string();
-            return true;
-        }
-
-        // Handle non-ASCII identifiers
-        if is_identifier_start(first_ch, self.language_version) {
+        } else if is_identifier_start(first_ch, self.language_version) {
             self.state.pos += first_ch.len_utf8();
+            first_ch
+        } else {
+            return false;
+        };
 
-            while self.state.pos < self.text.len() {
-                let ch = self.char().unwrap();
-                if !is_identifier_part(ch, self.language_version) {
-                    break;
-                }
+        value.push(first);
+        self.state.token_value = self.scan_identifier_parts(value);
+        true
+    }
+
+    /// Scans the rest of an identifier, appending its characters to `value`
+    fn scan_identifier_parts(&mut self, mut value: String) -> String {
+        let mut start = self.state.pos;
+        while let Some(ch) = self.char() {
+            if is_identifier_part(ch, self.language_version) {
                 self.state.pos += ch.len_utf8();
+            } else if ch == '\\'
+                && self
+                    .peek_unicode_escape()
+                    .is_some_and(|ch| is_identifier_part(ch, self.language_version))
+            {
+                value.push_str(&self.text[start..self.state.pos]);
+                value.push(self.scan_identifier_unicode_escape());
+                start = self.state.pos;
+            } else {
+                break;
             }
+        }
+        value.push_str(&self.text[start..self.state.pos]);
+        value
+    }
 
-            self.state.token_value = self.text[start..self.state.pos].to_string();
-            return true;
+    /// Gets the character of the Unicode escape at the current position, if there is a valid
+    /// one, without moving past it
+    fn peek_unicode_escape(&mut self) -> Option<char> {
+        if !self.text[self.state.pos..].starts_with("\\u") {
+            return None;
         }
+        let pos = self.state.pos;
+        let code_point = self.scan_unicode_escape(false);
+        self.state.pos = pos;
+        u32::try_from(code_point).ok().and_then(char::from_u32)
+    }
 
-        return false;
+    /// Scans a Unicode escape in an identifier that `peek_unicode_escape` found valid, and
+    /// marks the token as having one
+    fn scan_identifier_unicode_escape(&mut self) -> char {
+        let extended = self.char_at(2) == Some('{');
+        let code_point = self.scan_unicode_escape(false);
+        self.state.token_flags.add(if extended {
+            TokenFlags::EXTENDED_UNICODE_ESCAPE
+        } else {
+            TokenFlags::UNICODE_ESCAPE
+        });
+        u32::try_from(code_point)
+            .ok()
+            .and_then(char::from_u32)
+            .expect("the escape was peeked")
     }
 
     /// Scans an invalid character
@@ -775,6 +1252,24 @@ impl Scanner {
         // Store token value
         self.state.token_value = self.text[start..self.state.pos].to_string();
 
+        if self.char() == Some('n') {
+            self.state.pos += 1;
+            self.state.token_value.push('n');
+            return SyntaxKind::BigIntLiteral;
+        }
+
+        if self
+            .char()
+            .is_some_and(|ch| is_identifier_start(ch, self.language_version))
+        {
+            self.error_at(
+                diagnostics::AN_IDENTIFIER_OR_KEYWORD_CANNOT_IMMEDIATELY_FOLLOW_A_NUMERIC_LITERAL_1351,
+                self.state.pos,
+                1,
+                &[],
+            );
+        }
+
         SyntaxKind::NumericLiteral
     }
 
@@ -784,7 +1279,7 @@ impl Scanner {
         self.state.pos += 1;
 
         let mut result = String::new();
-        let start = self.state.pos;
+        let mut start = self.state.pos;
 
         while self.state.pos < self.text.len() {
             let ch = self.char().unwrap();
@@ -798,42 +1293,11 @@ impl Scanner {
             if ch == '\\' && !jsx_attribute_string {
                 result.push_str(&self.text[start..self.state.pos]);
                 self.state.pos += 1;
-
-                // Handle escape sequence
-                if self.state.pos < self.text.len() {
-                    let escape_ch = self.char().unwrap();
-                    match escape_ch {
-                        '0'..='9' | 'a'..='z' | 'A'..='Z' => {
-                            // Handle specific escape sequences
-                            // This would be expanded based on the Go implementation
-                            self.state.pos += 1;
-                            result.push(match escape_ch {
-                                'n' => '\n',
-                                'r' => '\r',
-                                't' => '\t',
-                                'b' => '\u{0008}',
-                                'f' => '\u{000C}',
-                                'v' => '\u{000B}',
-                                _ => escape_ch,
-                            });
-                        }
-                        _ => {
-                            self.state.pos += 1;
-                            result.push(escape_ch);
-                        }
-                    }
-                }
-
-                // Update start position for next chunk
-                if self.state.pos < self.text.len() {
-                    let new_start = self.state.pos;
-                    if new_start < self.text.len() {
-                        let new_start = self.state.pos;
-                        if new_start < self.text.len() {
-                            let new_start = self.state.pos;
-                        }
-                    }
-                }
+                result.push_str(&self.scan_escape_sequence(EscapeSequenceScanningFlags(
+                    EscapeSequenceScanningFlags::STRING.0
+                        | EscapeSequenceScanningFlags::REPORT_ERRORS.0,
+                )));
+                start = self.state.pos;
             } else if is_line_break(ch) && !jsx_attribute_string {
                 result.push_str(&self.text[start..self.state.pos]);
                 self.state.token_flags.add(TokenFlags::UNTERMINATED);
@@ -854,63 +1318,9 @@ impl Scanner {
 
     /// Maps identifier text to the appropriate token kind (keyword or identifier)
     fn get_identifier_token(text: &str) -> SyntaxKind {
-        match text {
-            // JavaScript keywords
-            "break" => SyntaxKind::BreakKeyword,
-            "case" => SyntaxKind::CaseKeyword,
-            "catch" => SyntaxKind::CatchKeyword,
-            "class" => SyntaxKind::ClassKeyword,
-            "const" => SyntaxKind::ConstKeyword,
-            "continue" => SyntaxKind::ContinueKeyword,
-            "debugger" => SyntaxKind::DebuggerKeyword,
-            "default" => SyntaxKind::DefaultKeyword,
-            "delete" => SyntaxKind::DeleteKeyword,
-            "do" => SyntaxKind::DoKeyword,
-            "else" => SyntaxKind::ElseKeyword,
-            "enum" => SyntaxKind::EnumKeyword,
-            "export" => SyntaxKind::ExportKeyword,
-            "extends" => SyntaxKind::ExtendsKeyword,
-            "false" => SyntaxKind::FalseKeyword,
-            "finally" => SyntaxKind::FinallyKeyword,
-            "for" => SyntaxKind::ForKeyword,
-            "function" => SyntaxKind::FunctionKeyword,
-            "if" => SyntaxKind::IfKeyword,
-            "import" => SyntaxKind::ImportKeyword,
-            "in" => SyntaxKind::InKeyword,
-            "instanceof" => SyntaxKind::InstanceOfKeyword,
-            "new" => SyntaxKind::NewKeyword,
-            "null" => SyntaxKind::NullKeyword,
-            "return" => SyntaxKind::ReturnKeyword,
-            "super" => SyntaxKind::SuperKeyword,
-            "switch" => SyntaxKind::SwitchKeyword,
-            "this" => SyntaxKind::ThisKeyword,
-            "throw" => SyntaxKind::ThrowKeyword,
-            "true" => SyntaxKind::TrueKeyword,
-            "try" => SyntaxKind::TryKeyword,
-            "typeof" => SyntaxKind::TypeOfKeyword,
-            "var" => SyntaxKind::VarKeyword,
-            "void" => SyntaxKind::VoidKeyword,
-            "while" => SyntaxKind::WhileKeyword,
-            "with" => SyntaxKind::WithKeyword,
-
-            // TypeScript-specific keywords
-            "as" => SyntaxKind::AsKeyword,
-            "async" => SyntaxKind::AsyncKeyword,
-            "await" => SyntaxKind::AwaitKeyword,
-            "let" => SyntaxKind::LetKeyword,
-            "of" => SyntaxKind::OfKeyword,
-            "type" => SyntaxKind::TypeKeyword,
-            "interface" => SyntaxKind::InterfaceKeyword,
-            "namespace" => SyntaxKind::NamespaceKeyword,
-            "static" => SyntaxKind::StaticKeyword,
-            "public" => SyntaxKind::PublicKeyword,
-            "private" => SyntaxKind::PrivateKeyword,
-            "protected" => SyntaxKind::ProtectedKeyword,
-            "yield" => SyntaxKind::YieldKeyword,
-
-            // Default case - not a keyword
-            _ => SyntaxKind::Identifier,
-        }
+        string_to_token(text)
+            .filter(|kind| is_keyword(*kind))
+            .unwrap_or(SyntaxKind::Identifier)
     }
 
     /// Scan a template literal
@@ -1329,121 +1739,7 @@ impl Scanner {
     }
 }
 
-/// Unicode character ranges for JavaScript/TypeScript identifiers
-pub mod unicode {
-    /// ES5 identifier start characters (first character of an identifier)
-    pub const ES5_IDENTIFIER_START: &[char] = &[
-        '\u{00AA}', '\u{00AA}', // FEMININE ORDINAL INDICATOR
-        '\u{00B5}', '\u{00B5}', // MICRO SIGN
-        '\u{00BA}', '\u{00BA}', // MASCULINE ORDINAL INDICATOR
-        '\u{00C0}',
-        '\u{00D6}', // LATIN CAPITAL LETTER A WITH GRAVE..LATIN CAPITAL LETTER O WITH DIAERESIS
-        '\u{00D8}',
-        '\u{00F6}', // LATIN CAPITAL LETTER O WITH STROKE..LATIN SMALL LETTER O WITH DIAERESIS
-        '\u{00F8}',
-        '\u{02C1}', // LATIN SMALL LETTER O WITH STROKE..MODIFIER LETTER REVERSED GLOTTAL STOP
-        '\u{02C6}',
-        '\u{02D1}', // MODIFIER LETTER CIRCUMFLEX ACCENT..MODIFIER LETTER HALF TRIANGULAR COLON
-        '\u{02E0}',
-        '\u{02E4}', // MODIFIER LETTER SMALL GAMMA..MODIFIER LETTER SMALL REVERSED GLOTTAL STOP
-        '\u{02EC}', '\u{02EC}', // MODIFIER LETTER VOICING
-        '\u{02EE}', '\u{02EE}', // MODIFIER LETTER DOUBLE APOSTROPHE
-        '\u{0370}',
-        '\u{0374}', // GREEK CAPITAL LETTER HETA..GREEK NUMERAL SIGN
-                    // ... more ranges follow the same pattern
-    ];
-
-    /// ES5 identifier part characters (non-first characters in an identifier)
-    pub const ES5_IDENTIFIER_PART: &[char] = &[
-        '\u{00AA}', '\u{00AA}', // FEMININE ORDINAL INDICATOR
-        '\u{00B5}', '\u{00B5}', // MICRO SIGN
-        '\u{00BA}', '\u{00BA}', // MASCULINE ORDINAL INDICATOR
-        '\u{00C0}',
-        '\u{00D6}', // LATIN CAPITAL LETTER A WITH GRAVE..LATIN CAPITAL LETTER O WITH DIAERESIS
-        '\u{00D8}',
-        '\u{00F6}', // LATIN CAPITAL LETTER O WITH STROKE..LATIN SMALL LETTER O WITH DIAERESIS
-        '\u{00F8}',
-        '\u{02C1}', // LATIN SMALL LETTER O WITH STROKE..MODIFIER LETTER REVERSED GLOTTAL STOP
-        '\u{02C6}',
-        '\u{02D1}', // MODIFIER LETTER CIRCUMFLEX ACCENT..MODIFIER LETTER HALF TRIANGULAR COLON
-        '\u{02E0}',
-        '\u{02E4}', // MODIFIER LETTER SMALL GAMMA..MODIFIER LETTER SMALL REVERSED GLOTTAL STOP
-        '\u{02EC}', '\u{02EC}', // MODIFIER LETTER VOICING
-        '\u{02EE}', '\u{02EE}', // MODIFIER LETTER DOUBLE APOSTROPHE
-        '\u{0300}',
-        '\u{0374}', // COMBINING GRAVE ACCENT..GREEK NUMERAL SIGN
-                    // ... more ranges follow the same pattern
-    ];
-
-    /// ES2015+ identifier start characters
-    pub const ES_NEXT_IDENTIFIER_START: &[char] = &[
-        '\u{0041}', '\u{005A}', // A-Z
-        '\u{0061}', '\u{007A}', // a-z
-        '\u{00AA}', '\u{00AA}', // FEMININE ORDINAL INDICATOR
-        '\u{00B5}', '\u{00B5}', // MICRO SIGN
-        '\u{00BA}', '\u{00BA}', // MASCULINE ORDINAL INDICATOR
-        '\u{00C0}',
-        '\u{00D6}', // LATIN CAPITAL LETTER A WITH GRAVE..LATIN CAPITAL LETTER O WITH DIAERESIS
-        '\u{00D8}',
-        '\u{00F6}', // LATIN CAPITAL LETTER O WITH STROKE..LATIN SMALL LETTER O WITH DIAERESIS
-        '\u{00F8}',
-        '\u{02C1}', // LATIN SMALL LETTER O WITH STROKE..MODIFIER LETTER REVERSED GLOTTAL STOP
-        '\u{02C6}',
-        '\u{02D1}', // MODIFIER LETTER CIRCUMFLEX ACCENT..MODIFIER LETTER HALF TRIANGULAR COLON
-        '\u{02E0}',
-        '\u{02E4}', // MODIFIER LETTER SMALL GAMMA..MODIFIER LETTER SMALL REVERSED GLOTTAL STOP
-                    // ... more ranges follow the same pattern
-    ];
-
-    /// ES2015+ identifier part characters
-    pub const ES_NEXT_IDENTIFIER_PART: &[char] = &[
-        '\u{0030}', '\u{0039}', // 0-9
-        '\u{0041}', '\u{005A}', // A-Z
-        '\u{005F}', '\u{005F}', // _
-        '\u{0061}', '\u{007A}', // a-z
-        '\u{00AA}', '\u{00AA}', // FEMININE ORDINAL INDICATOR
-        '\u{00B5}', '\u{00B5}', // MICRO SIGN
-        '\u{00B7}', '\u{00B7}', // MIDDLE DOT
-        '\u{00BA}', '\u{00BA}', // MASCULINE ORDINAL INDICATOR
-        '\u{00C0}',
-        '\u{00D6}', // LATIN CAPITAL LETTER A WITH GRAVE..LATIN CAPITAL LETTER O WITH DIAERESIS
-        '\u{00D8}',
-        '\u{00F6}', // LATIN CAPITAL LETTER O WITH STROKE..LATIN SMALL LETTER O WITH DIAERESIS
-        '\u{00F8}',
-        '\u{02C1}', // LATIN SMALL LETTER O WITH STROKE..MODIFIER LETTER REVERSED GLOTTAL STOP
-                    // ... more ranges follow the same pattern
-    ];
-
-    /// Helper function to check if a code point is within a range of Unicode characters
-    pub fn is_in_unicode_ranges(cp: char, ranges: &[char]) -> bool {
-        // Bail out quickly if it couldn't possibly be in the map
-        if cp < ranges[0] {
-            return false;
-        }
-
-        // Perform binary search in one of the Unicode range maps
-        let mut lo = 0;
-        let mut hi = ranges.len();
-
-        while lo + 1 < hi {
-            let mut mid = lo + (hi - lo) / 2;
-            // mid has to be even to catch beginning of a range
-            mid -= mid % 2;
-
-            if ranges[mid] <= cp && cp <= ranges[mid + 1] {
-                return true;
-            }
-
-            if cp < ranges[mid] {
-                hi = mid;
-            } else {
-                lo = mid + 2;
-            }
-        }
-
-        false
-    }
-}
+pub mod unicode;
 
 // Section 6.1.4: Word characters and identifier validation
 // Based on ECMAScript Language Specification
@@ -1468,9 +1764,24 @@ pub fn is_identifier_part(ch: char, language_version: ScriptTarget) -> bool {
         || (ch > '\u{7F}' && is_unicode_identifier_part(ch, language_version))
 }
 
+/// Checks if a character is in one of a sorted list of ranges of characters
+fn is_in_unicode_ranges(ch: char, ranges: &[(char, char)]) -> bool {
+    ranges
+        .binary_search_by(|&(start, end)| {
+            if end < ch {
+                std::cmp::Ordering::Less
+            } else if start > ch {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
 /// Checks if a Unicode character can start an identifier
 pub fn is_unicode_identifier_start(ch: char, language_version: ScriptTarget) -> bool {
-    unicode::is_in_unicode_ranges(
+    is_in_unicode_ranges(
         ch,
         if language_version >= ScriptTarget::ES2015 {
             unicode::ES_NEXT_IDENTIFIER_START
@@ -1482,7 +1793,7 @@ pub fn is_unicode_identifier_start(ch: char, language_version: ScriptTarget) ->
 
 /// Checks if a Unicode character can be part of an identifier
 pub fn is_unicode_identifier_part(ch: char, language_version: ScriptTarget) -> bool {
-    unicode::is_in_unicode_ranges(
+    is_in_unicode_ranges(
         ch,
         if language_version >= ScriptTarget::ES2015 {
             unicode::ES_NEXT_IDENTIFIER_PART
@@ -1523,7 +1834,340 @@ fn is_hex_digit(ch: char) -> bool {
     (ch >= '0' && ch <= '9') || (ch >= 'a' && ch <= 'f') || (ch >= 'A' && ch <= 'F')
 }
 
+/// Gets the position of the first token at or after `pos`, skipping whitespace, comments,
+/// and a shebang line at the start of the text
+pub fn skip_trivia(text: &str, pos: usize) -> usize {
+    let mut pos = pos;
+    if pos == 0 && text.starts_with("#!") {
+        pos = text.find(is_line_break).unwrap_or(text.len());
+    }
+    loop {
+        let rest = &text[pos..];
+        let Some(ch) = rest.chars().next() else {
+            return pos;
+        };
+        if is_white_space_single_line(ch) || is_line_break(ch) {
+            pos += ch.len_utf8();
+        } else if rest.starts_with("//") {
+            pos += rest.find(is_line_break).unwrap_or(rest.len());
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            pos += comment.find("*/").map_or(rest.len(), |end| end + 4);
+        } else {
+            return pos;
+        }
+    }
+}
+
+/// A comment in source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentRange {
+    /// The range of the comment, including its delimiters
+    pub loc: TextRange,
+    /// `SingleLineCommentTrivia` or `MultiLineCommentTrivia`
+    pub kind: SyntaxKind,
+    /// Whether a line break follows the comment before the next token or comment
+    pub has_trailing_new_line: bool,
+}
+
+/// Gets the comments between `pos` and the next token, skipping a shebang line at the start
+/// of the text
+pub fn get_leading_comment_ranges(text: &str, pos: usize) -> Vec<CommentRange> {
+    scan_comment_ranges(text, pos, false)
+}
+
+/// Gets the comments between `pos` and the end of its line
+pub fn get_trailing_comment_ranges(text: &str, pos: usize) -> Vec<CommentRange> {
+    scan_comment_ranges(text, pos, true)
+}
+
+fn scan_comment_ranges(text: &str, pos: usize, trailing: bool) -> Vec<CommentRange> {
+    let mut ranges: Vec<CommentRange> = Vec::new();
+    let mut pos = pos;
+    if pos == 0 && text.starts_with("#!") {
+        if trailing {
+            return ranges;
+        }
+        pos = text.find(is_line_break).unwrap_or(text.len());
+    }
+    while let Some(ch) = text.get(pos..).and_then(|rest| rest.chars().next()) {
+        let rest = &text[pos..];
+        if is_line_break(ch) {
+            if let Some(last) = ranges.last_mut() {
+                last.has_trailing_new_line = true;
+            }
+            if trailing {
+                break;
+            }
+            pos += ch.len_utf8();
+        } else if is_white_space_single_line(ch) {
+            pos += ch.len_utf8();
+        } else if rest.starts_with("//") {
+            let end = pos + rest.find(is_line_break).unwrap_or(rest.len());
+            ranges.push(CommentRange {
+                loc: TextRange::new(pos, end),
+                kind: SyntaxKind::SingleLineCommentTrivia,
+                has_trailing_new_line: false,
+            });
+            pos = end;
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let end = pos + comment.find("*/").map_or(rest.len(), |end| end + 4);
+            ranges.push(CommentRange {
+                loc: TextRange::new(pos, end),
+                kind: SyntaxKind::MultiLineCommentTrivia,
+                has_trailing_new_line: false,
+            });
+            pos = end;
+        } else {
+            break;
+        }
+    }
+    ranges
+}
+
 /// Checks if a character is a line break
-fn is_line_break(ch: char) -> bool {
+pub fn is_line_break(ch: char) -> bool {
     ch == '\n' || ch == '\r' || ch == '\u{2028}' || ch == '\u{2029}'
 }
+
+/// Checks if a character is whitespace that does not terminate a line
+pub fn is_white_space_single_line(ch: char) -> bool {
+    matches!(
+        ch,
+        ' ' | '\t' | '\x0B' | '\x0C' | '\u{0085}' | '\u{00A0}' | '\u{1680}' | '\u{2000}'
+            ..='\u{200B}' | '\u{202F}' | '\u{205F}' | '\u{3000}' | '\u{FEFF}'
+    )
+}
+
+/// Textual representations of every keyword and punctuation token
+const TEXT_TO_TOKEN: &[(&str, SyntaxKind)] = &[
+    ("abstract", SyntaxKind::AbstractKeyword),
+    ("accessor", SyntaxKind::AccessorKeyword),
+    ("any", SyntaxKind::AnyKeyword),
+    ("as", SyntaxKind::AsKeyword),
+    ("asserts", SyntaxKind::AssertsKeyword),
+    ("assert", SyntaxKind::AssertKeyword),
+    ("bigint", SyntaxKind::BigIntKeyword),
+    ("boolean", SyntaxKind::BooleanKeyword),
+    ("break", SyntaxKind::BreakKeyword),
+    ("case", SyntaxKind::CaseKeyword),
+    ("catch", SyntaxKind::CatchKeyword),
+    ("class", SyntaxKind::ClassKeyword),
+    ("continue", SyntaxKind::ContinueKeyword),
+    ("const", SyntaxKind::ConstKeyword),
+    ("constructor", SyntaxKind::ConstructorKeyword),
+    ("debugger", SyntaxKind::DebuggerKeyword),
+    ("declare", SyntaxKind::DeclareKeyword),
+    ("default", SyntaxKind::DefaultKeyword),
+    ("delete", SyntaxKind::DeleteKeyword),
+    ("do", SyntaxKind::DoKeyword),
+    ("else", SyntaxKind::ElseKeyword),
+    ("enum", SyntaxKind::EnumKeyword),
+    ("export", SyntaxKind::ExportKeyword),
+    ("extends", SyntaxKind::ExtendsKeyword),
+    ("false", SyntaxKind::FalseKeyword),
+    ("finally", SyntaxKind::FinallyKeyword),
+    ("for", SyntaxKind::ForKeyword),
+    ("from", SyntaxKind::FromKeyword),
+    ("function", SyntaxKind::FunctionKeyword),
+    ("get", SyntaxKind::GetKeyword),
+    ("if", SyntaxKind::IfKeyword),
+    ("immediate", SyntaxKind::ImmediateKeyword),
+    ("implements", SyntaxKind::ImplementsKeyword),
+    ("import", SyntaxKind::ImportKeyword),
+    ("in", SyntaxKind::InKeyword),
+    ("infer", SyntaxKind::InferKeyword),
+    ("instanceof", SyntaxKind::InstanceOfKeyword),
+    ("interface", SyntaxKind::InterfaceKeyword),
+    ("intrinsic", SyntaxKind::IntrinsicKeyword),
+    ("is", SyntaxKind::IsKeyword),
+    ("keyof", SyntaxKind::KeyOfKeyword),
+    ("let", SyntaxKind::LetKeyword),
+    ("module", SyntaxKind::ModuleKeyword),
+    ("namespace", SyntaxKind::NamespaceKeyword),
+    ("never", SyntaxKind::NeverKeyword),
+    ("new", SyntaxKind::NewKeyword),
+    ("null", SyntaxKind::NullKeyword),
+    ("number", SyntaxKind::NumberKeyword),
+    ("object", SyntaxKind::ObjectKeyword),
+    ("package", SyntaxKind::PackageKeyword),
+    ("private", SyntaxKind::PrivateKeyword),
+    ("protected", SyntaxKind::ProtectedKeyword),
+    ("public", SyntaxKind::PublicKeyword),
+    ("override", SyntaxKind::OverrideKeyword),
+    ("out", SyntaxKind::OutKeyword),
+    ("readonly", SyntaxKind::ReadonlyKeyword),
+    ("require", SyntaxKind::RequireKeyword),
+    ("global", SyntaxKind::GlobalKeyword),
+    ("return", SyntaxKind::ReturnKeyword),
+    ("satisfies", SyntaxKind::SatisfiesKeyword),
+    ("set", SyntaxKind::SetKeyword),
+    ("static", SyntaxKind::StaticKeyword),
+    ("string", SyntaxKind::StringKeyword),
+    ("super", SyntaxKind::SuperKeyword),
+    ("switch", SyntaxKind::SwitchKeyword),
+    ("symbol", SyntaxKind::SymbolKeyword),
+    ("this", SyntaxKind::ThisKeyword),
+    ("throw", SyntaxKind::ThrowKeyword),
+    ("true", SyntaxKind::TrueKeyword),
+    ("try", SyntaxKind::TryKeyword),
+    ("type", SyntaxKind::TypeKeyword),
+    ("typeof", SyntaxKind::TypeOfKeyword),
+    ("undefined", SyntaxKind::UndefinedKeyword),
+    ("unique", SyntaxKind::UniqueKeyword),
+    ("unknown", SyntaxKind::UnknownKeyword),
+    ("using", SyntaxKind::UsingKeyword),
+    ("var", SyntaxKind::VarKeyword),
+    ("void", SyntaxKind::VoidKeyword),
+    ("while", SyntaxKind::WhileKeyword),
+    ("with", SyntaxKind::WithKeyword),
+    ("yield", SyntaxKind::YieldKeyword),
+    ("async", SyntaxKind::AsyncKeyword),
+    ("await", SyntaxKind::AwaitKeyword),
+    ("of", SyntaxKind::OfKeyword),
+    ("{", SyntaxKind::OpenBraceToken),
+    ("}", SyntaxKind::CloseBraceToken),
+    ("(", SyntaxKind::OpenParenToken),
+    (")", SyntaxKind::CloseParenToken),
+    ("[", SyntaxKind::OpenBracketToken),
+    ("]", SyntaxKind::CloseBracketToken),
+    (".", SyntaxKind::DotToken),
+    ("...", SyntaxKind::DotDotDotToken),
+    (";", SyntaxKind::SemicolonToken),
+    (",", SyntaxKind::CommaToken),
+    ("<", SyntaxKind::LessThanToken),
+    (">", SyntaxKind::GreaterThanToken),
+    ("<=", SyntaxKind::LessThanEqualsToken),
+    (">=", SyntaxKind::GreaterThanEqualsToken),
+    ("==", SyntaxKind::EqualsEqualsToken),
+    ("!=", SyntaxKind::ExclamationEqualsToken),
+    ("===", SyntaxKind::EqualsEqualsEqualsToken),
+    ("!==", SyntaxKind::ExclamationEqualsEqualsToken),
+    ("=>", SyntaxKind::EqualsGreaterThanToken),
+    ("+", SyntaxKind::PlusToken),
+    ("-", SyntaxKind::MinusToken),
+    ("**", SyntaxKind::AsteriskAsteriskToken),
+    ("*", SyntaxKind::AsteriskToken),
+    ("/", SyntaxKind::SlashToken),
+    ("%", SyntaxKind::PercentToken),
+    ("++", SyntaxKind::PlusPlusToken),
+    ("--", SyntaxKind::MinusMinusToken),
+    ("<<", SyntaxKind::LessThanLessThanToken),
+    ("</", SyntaxKind::LessThanSlashToken),
+    (">>", SyntaxKind::GreaterThanGreaterThanToken),
+    (">>>", SyntaxKind::GreaterThanGreaterThanGreaterThanToken),
+    ("&", SyntaxKind::AmpersandToken),
+    ("|", SyntaxKind::BarToken),
+    ("^", SyntaxKind::CaretToken),
+    ("!", SyntaxKind::ExclamationToken),
+    ("~", SyntaxKind::TildeToken),
+    ("&&", SyntaxKind::AmpersandAmpersandToken),
+    ("||", SyntaxKind::BarBarToken),
+    ("?", SyntaxKind::QuestionToken),
+    ("??", SyntaxKind::QuestionQuestionToken),
+    ("?.", SyntaxKind::QuestionDotToken),
+    (":", SyntaxKind::ColonToken),
+    ("=", SyntaxKind::EqualsToken),
+    ("+=", SyntaxKind::PlusEqualsToken),
+    ("-=", SyntaxKind::MinusEqualsToken),
+    ("*=", SyntaxKind::AsteriskEqualsToken),
+    ("**=", SyntaxKind::AsteriskAsteriskEqualsToken),
+    ("/=", SyntaxKind::SlashEqualsToken),
+    ("%=", SyntaxKind::PercentEqualsToken),
+    ("<<=", SyntaxKind::LessThanLessThanEqualsToken),
+    (">>=", SyntaxKind::GreaterThanGreaterThanEqualsToken),
+    (
+        ">>>=",
+        SyntaxKind::GreaterThanGreaterThanGreaterThanEqualsToken,
+    ),
+    ("&=", SyntaxKind::AmpersandEqualsToken),
+    ("|=", SyntaxKind::BarEqualsToken),
+    ("^=", SyntaxKind::CaretEqualsToken),
+    ("||=", SyntaxKind::BarBarEqualsToken),
+    ("&&=", SyntaxKind::AmpersandAmpersandEqualsToken),
+    ("??=", SyntaxKind::QuestionQuestionEqualsToken),
+    ("@", SyntaxKind::AtToken),
+    ("#", SyntaxKind::HashToken),
+    ("`", SyntaxKind::BacktickToken),
+];
+
+/// Looks up the keyword or punctuation token spelled by `text`
+pub fn string_to_token(text: &str) -> Option<SyntaxKind> {
+    TEXT_TO_TOKEN
+        .iter()
+        .find(|(token_text, _)| *token_text == text)
+        .map(|(_, kind)| *kind)
+}
+
+/// Gets the source text of a keyword or punctuation token
+pub fn token_to_string(kind: SyntaxKind) -> Option<&'static str> {
+    TEXT_TO_TOKEN
+        .iter()
+        .find(|(_, token_kind)| *token_kind == kind)
+        .map(|(text, _)| *text)
+}
+
+/// Checks if a token kind is a reserved word or contextual keyword
+pub fn is_keyword(kind: SyntaxKind) -> bool {
+    kind >= SyntaxKind::FIRST_KEYWORD && kind <= SyntaxKind::LAST_KEYWORD
+}
+
+/// Checks if a token kind is an identifier or any keyword
+/// Determines if a JSDoc comment contains an `@see` or `@link` tag
+fn contains_jsdoc_see_or_link(comment: &str) -> bool {
+    ["@see", "@link", "@linkcode", "@linkplain"]
+        .iter()
+        .any(|tag| {
+            comment.match_indices(tag).any(|(index, _)| {
+                let rest = &comment[index + tag.len()..];
+                // The tag name must end here; `@seealso` is not `@see`
+                !rest.starts_with(|ch: char| ch.is_ascii_alphanumeric())
+            })
+        })
+}
+
+pub fn is_identifier_or_keyword(kind: SyntaxKind) -> bool {
+    kind == SyntaxKind::Identifier || is_keyword(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_identifiers_in_any_script() {
+        let mut scanner = Scanner::new();
+        scanner.set_text("変数 ǅx_٣ Ωmega 𝑥ᾩ".to_string());
+        let mut names = Vec::new();
+        while scanner.scan() != SyntaxKind::EndOfFile {
+            assert_eq!(scanner.token(), SyntaxKind::Identifier);
+            names.push(scanner.token_value().to_string());
+        }
+        assert_eq!(names, ["変数", "ǅx_٣", "Ωmega", "𝑥ᾩ"]);
+
+        assert!(is_valid_identifier("𝑥ᾩ", ScriptTarget::ES2015));
+        // ES5 reads identifiers by UTF-16 code unit, so a character outside the Basic
+        // Multilingual Plane cannot be part of one
+        assert!(!is_valid_identifier("𝑥ᾩ", ScriptTarget::ES5));
+        assert!(!is_valid_identifier("٣x", ScriptTarget::ESNext));
+    }
+
+    #[test]
+    fn reads_es5_identifiers_by_general_category() {
+        // U+2E2F VERTICAL TILDE is a modifier letter, but a pattern character that ID_Start
+        // leaves out
+        assert!(is_valid_identifier("aⸯ", ScriptTarget::ES5));
+        assert!(!is_valid_identifier("aⸯ", ScriptTarget::ESNext));
+        // U+00B7 MIDDLE DOT is punctuation that ID_Continue adds, and U+2118 SCRIPT CAPITAL P
+        // a symbol that ID_Start adds
+        assert!(is_valid_identifier("a·", ScriptTarget::ESNext));
+        assert!(!is_valid_identifier("a·", ScriptTarget::ES5));
+        assert!(is_valid_identifier("℘", ScriptTarget::ESNext));
+        assert!(!is_valid_identifier("℘", ScriptTarget::ES5));
+        // Combining marks, digits, and connector punctuation continue identifiers but do not
+        // start them
+        for part in ["\u{0301}", "٣", "\u{203F}"] {
+            assert!(is_valid_identifier(&format!("a{part}"), ScriptTarget::ES5));
+            assert!(!is_valid_identifier(part, ScriptTarget::ES5));
+        }
+    }
+}
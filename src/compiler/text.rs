@@ -0,0 +1,478 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::compiler::scanner::is_line_break;
+
+/// Represents a range of text in a source file
+///
+/// Positions are byte offsets into the source text. `pos` is the full start of a node
+/// (including leading trivia) and `end` is the offset just past its last character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TextRange {
+    pos: usize,
+    end: usize,
+}
+
+impl TextRange {
+    /// Creates a new text range
+    pub fn new(pos: usize, end: usize) -> Self {
+        TextRange { pos, end }
+    }
+
+    /// Gets the start position of the range
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Gets the end position of the range
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Gets the length of the range
+    pub fn len(&self) -> usize {
+        self.end - self.pos
+    }
+
+    /// Checks if the range is empty
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.end
+    }
+
+    /// Checks if `pos` falls within this range (inclusive of the end position)
+    pub fn contains_inclusive(&self, pos: usize) -> bool {
+        self.pos <= pos && pos <= self.end
+    }
+
+    /// Returns a copy of this range with a different start position
+    pub fn with_pos(&self, pos: usize) -> Self {
+        TextRange::new(pos, self.end)
+    }
+
+    /// Returns a copy of this range with a different end position
+    pub fn with_end(&self, end: usize) -> Self {
+        TextRange::new(self.pos, end)
+    }
+}
+
+/// Describes an edit to a source text: the span of the old text that was replaced and the
+/// length of the text that replaced it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TextChangeRange {
+    span: TextRange,
+    new_length: usize,
+}
+
+impl TextChangeRange {
+    /// Creates a change that replaces `span` with `new_length` bytes of text
+    pub fn new(span: TextRange, new_length: usize) -> Self {
+        TextChangeRange { span, new_length }
+    }
+
+    /// Gets the span of the old text that was replaced
+    pub fn span(&self) -> TextRange {
+        self.span
+    }
+
+    /// Gets the length of the replacement text
+    pub fn new_length(&self) -> usize {
+        self.new_length
+    }
+
+    /// Gets the range the replacement text occupies in the new text
+    pub fn new_span(&self) -> TextRange {
+        TextRange::new(self.span.pos(), self.span.pos() + self.new_length)
+    }
+
+    /// Checks if the change leaves the text as it was
+    pub fn is_unchanged(&self) -> bool {
+        self.span.is_empty() && self.new_length == 0
+    }
+}
+
+/// Gets the one change that has the effect of a series of changes made one after another,
+/// each to the text the one before it made. The span of the result covers every span of the
+/// original text that any of the changes replaced.
+pub fn collapse_text_change_ranges_across_multiple_versions(
+    changes: &[TextChangeRange],
+) -> TextChangeRange {
+    let Some((first, rest)) = changes.split_first() else {
+        return TextChangeRange::default();
+    };
+    let mut old_start = first.span.pos() as isize;
+    let mut old_end = first.span.end() as isize;
+    let mut new_end = old_start + first.new_length as isize;
+    for change in rest {
+        let next_old_start = change.span.pos() as isize;
+        let next_old_end = change.span.end() as isize;
+        let next_new_end = next_old_start + change.new_length as isize;
+        // A change after the previous ones maps back to the original text by undoing how
+        // much they grew or shrank it, and one before them moves their new end along
+        old_start = old_start.min(next_old_start);
+        old_end = old_end.max(old_end + (next_old_end - new_end));
+        new_end = next_new_end.max(next_new_end + (new_end - next_old_end));
+    }
+    TextChangeRange::new(
+        TextRange::new(old_start as usize, old_end as usize),
+        (new_end - old_start) as usize,
+    )
+}
+
+/// Finds the span of the old text that was replaced to make the new text, as the text
+/// between their common prefix and common suffix
+pub fn compute_text_change_range(old_text: &str, new_text: &str) -> TextChangeRange {
+    let prefix = old_text
+        .char_indices()
+        .zip(new_text.chars())
+        .find(|((_, old), new)| old != new)
+        .map_or(old_text.len().min(new_text.len()), |((index, _), _)| index);
+    let suffix = old_text[prefix..]
+        .chars()
+        .rev()
+        .zip(new_text[prefix..].chars().rev())
+        .take_while(|(old, new)| old == new)
+        .map(|(old, _)| old.len_utf8())
+        .sum::<usize>();
+    TextChangeRange::new(
+        TextRange::new(prefix, old_text.len() - suffix),
+        new_text.len() - suffix - prefix,
+    )
+}
+
+/// Numbers the documents snapshots are made of, so that snapshots of different documents are
+/// never taken for versions of each other
+static NEXT_DOCUMENT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A version of the text of a document that an editor changes
+///
+/// Each edit makes a new snapshot, with the next version, that links to the edit that made
+/// it. Snapshots of a document share the edits they have in common, so an edit costs the
+/// same however long the history is. The change between any two versions of a document is
+/// then known without comparing their texts, which is what the incremental parser needs to
+/// reuse the tree of the older version.
+#[derive(Debug, Clone)]
+pub struct ScriptSnapshot {
+    document: usize,
+    text: Arc<str>,
+    version: usize,
+    /// The edit that made this version, or `None` for the first version
+    last_edit: Option<Arc<Edit>>,
+}
+
+/// A change that made a version of a document from the one before it
+#[derive(Debug)]
+struct Edit {
+    change: TextChangeRange,
+    previous: Option<Arc<Edit>>,
+}
+
+impl Drop for Edit {
+    // Dropping the edits of a long history one inside another would overflow the stack, so
+    // the ones nothing else shares are taken off the list here instead
+    fn drop(&mut self) {
+        let mut previous = self.previous.take();
+        while let Some(edit) = previous {
+            match Arc::try_unwrap(edit) {
+                Ok(mut edit) => previous = edit.previous.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl ScriptSnapshot {
+    /// Creates the first version of a document
+    pub fn from_text(text: &str) -> Self {
+        ScriptSnapshot {
+            document: NEXT_DOCUMENT_ID.fetch_add(1, Ordering::Relaxed),
+            text: Arc::from(text),
+            version: 0,
+            last_edit: None,
+        }
+    }
+
+    /// Gets the text of this version
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Gets the version number, which is the number of edits since the first version
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// Gets the text of a range of this version
+    pub fn get_text(&self, range: TextRange) -> &str {
+        &self.text[range.pos()..range.end()]
+    }
+
+    /// Creates the next version of the document, with the text of `span` replaced by
+    /// `new_text`
+    pub fn edit(&self, span: TextRange, new_text: &str) -> ScriptSnapshot {
+        let mut text = String::with_capacity(self.text.len() - span.len() + new_text.len());
+        text.push_str(&self.text[..span.pos()]);
+        text.push_str(new_text);
+        text.push_str(&self.text[span.end()..]);
+        ScriptSnapshot {
+            document: self.document,
+            text: Arc::from(text),
+            version: self.version + 1,
+            last_edit: Some(Arc::new(Edit {
+                change: TextChangeRange::new(span, new_text.len()),
+                previous: self.last_edit.clone(),
+            })),
+        }
+    }
+
+    /// Gets the change that makes this version from `old`, an earlier version of the same
+    /// document, from the edits between them. The texts are compared when `old` is not one.
+    pub fn get_change_range(&self, old: &ScriptSnapshot) -> TextChangeRange {
+        if old.document == self.document && old.version <= self.version {
+            let mut changes = Vec::with_capacity(self.version - old.version);
+            let mut edit = self.last_edit.as_ref();
+            for _ in old.version..self.version {
+                let Some(current) = edit else { break };
+                changes.push(current.change);
+                edit = current.previous.as_ref();
+            }
+            // Versions branched from an earlier one share its edits but not each other's
+            let is_earlier_version = match (edit, &old.last_edit) {
+                (Some(edit), Some(old_edit)) => Arc::ptr_eq(edit, old_edit),
+                (None, None) => true,
+                _ => false,
+            };
+            if is_earlier_version {
+                changes.reverse();
+                return collapse_text_change_ranges_across_multiple_versions(&changes);
+            }
+        }
+        compute_text_change_range(&old.text, &self.text)
+    }
+}
+
+/// A zero-based line and character of a position in a text
+///
+/// Whether `character` counts UTF-8 bytes or UTF-16 code units from the start of the line
+/// depends on the function that made it: the compiler works in bytes, while editors speaking
+/// the language server protocol, and source maps, count UTF-16 code units as JavaScript does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct LineAndCharacter {
+    pub line: usize,
+    pub character: usize,
+}
+
+/// Gets the position where each line of a text starts. Lines end at `\n`, `\r\n`, `\r`,
+/// `\u{2028}`, and `\u{2029}`, as they do in JavaScript.
+pub fn compute_line_starts(text: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        if is_line_break(ch) {
+            if ch == '\r' && chars.next_if(|&(_, next)| next == '\n').is_some() {
+                starts.push(index + 2);
+            } else {
+                starts.push(index + ch.len_utf8());
+            }
+        }
+    }
+    starts
+}
+
+/// Gets the line of a position, given the starts of the lines of its text
+pub fn get_line_of_position(line_starts: &[usize], pos: usize) -> usize {
+    line_starts
+        .partition_point(|&start| start <= pos)
+        .saturating_sub(1)
+}
+
+/// Gets the line and character of a position, with the character in UTF-8 bytes
+pub fn position_to_line_and_character(line_starts: &[usize], pos: usize) -> LineAndCharacter {
+    let line = get_line_of_position(line_starts, pos);
+    LineAndCharacter {
+        line,
+        character: pos - line_starts.get(line).copied().unwrap_or(0),
+    }
+}
+
+/// Gets the position of a line and character, with the character in UTF-8 bytes. A line past
+/// the last is the end of the text, and a character past the end of its line is the end of
+/// the line, before its line break, as the language server protocol has it.
+pub fn line_and_character_to_position(
+    text: &str,
+    line_starts: &[usize],
+    line_and_character: LineAndCharacter,
+) -> usize {
+    let Some(&start) = line_starts.get(line_and_character.line) else {
+        return text.len();
+    };
+    let end = line_content_end(text, line_starts, line_and_character.line);
+    let mut pos = (start + line_and_character.character).min(end);
+    while !text.is_char_boundary(pos) {
+        pos -= 1;
+    }
+    pos
+}
+
+/// Gets the line and character of a position, with the character in UTF-16 code units
+pub fn position_to_utf16_line_and_character(
+    text: &str,
+    line_starts: &[usize],
+    pos: usize,
+) -> LineAndCharacter {
+    let LineAndCharacter { line, character } = position_to_line_and_character(line_starts, pos);
+    let start = pos - character;
+    LineAndCharacter {
+        line,
+        character: utf16_length(&text[start..pos]),
+    }
+}
+
+/// Gets the position of a line and character, with the character in UTF-16 code units. A
+/// character in the middle of a surrogate pair is the start of the pair, and lines and
+/// characters past the end are clamped as `line_and_character_to_position` clamps them.
+pub fn utf16_line_and_character_to_position(
+    text: &str,
+    line_starts: &[usize],
+    line_and_character: LineAndCharacter,
+) -> usize {
+    let Some(&start) = line_starts.get(line_and_character.line) else {
+        return text.len();
+    };
+    let end = line_content_end(text, line_starts, line_and_character.line);
+    let mut units = 0;
+    for (index, ch) in text[start..end].char_indices() {
+        units += ch.len_utf16();
+        if units > line_and_character.character {
+            return start + index;
+        }
+    }
+    end
+}
+
+/// Gets the number of UTF-16 code units a text is encoded in
+pub fn utf16_length(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// Gets the end of the text of a line, before its line break
+fn line_content_end(text: &str, line_starts: &[usize], line: usize) -> usize {
+    let next = line_starts.get(line + 1).copied().unwrap_or(text.len());
+    let content = &text[line_starts[line]..next];
+    line_starts[line] + content.trim_end_matches(is_line_break).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapses_the_edits_between_versions_of_a_document() {
+        let first = ScriptSnapshot::from_text("abcdef");
+        let second = first.edit(TextRange::new(1, 2), "XY");
+        let third = second.edit(TextRange::new(5, 6), "");
+        assert_eq!(third.text(), "aXYcdf");
+        assert_eq!(third.version(), 2);
+        let change = third.get_change_range(&first);
+        assert_eq!(change, TextChangeRange::new(TextRange::new(1, 5), 4));
+        assert_eq!(third.get_text(change.new_span()), "XYcd");
+        assert!(third.get_change_range(&third).is_unchanged());
+
+        // An edit before the earlier ones moves them along
+        let fourth = third.edit(TextRange::new(0, 0), "__");
+        assert_eq!(
+            fourth.get_change_range(&second),
+            TextChangeRange::new(TextRange::new(0, 6), 7)
+        );
+
+        // Snapshots of another document are compared by their texts
+        let other = ScriptSnapshot::from_text("abcdef");
+        assert_eq!(
+            third.get_change_range(&other),
+            TextChangeRange::new(TextRange::new(1, 5), 4)
+        );
+        assert_eq!(
+            compute_text_change_range("abcdef", "aXYcdf"),
+            TextChangeRange::new(TextRange::new(1, 5), 4)
+        );
+
+        // A version branched from an earlier one is not a version after the other branch
+        let branch = second.edit(TextRange::new(0, 1), "_");
+        assert_eq!(branch.version(), third.version());
+        assert_eq!(
+            branch.get_change_range(&third),
+            compute_text_change_range(third.text(), branch.text())
+        );
+    }
+
+    #[test]
+    fn keeps_long_histories_of_edits() {
+        let first = ScriptSnapshot::from_text("");
+        let mut last = first.clone();
+        for _ in 0..200_000 {
+            last = last.edit(TextRange::new(0, 0), "");
+        }
+        assert_eq!(last.version(), 200_000);
+        assert!(last.get_change_range(&first).is_unchanged());
+    }
+
+    #[test]
+    fn maps_positions_to_lines_and_characters() {
+        let text = "a\r\nb\rc\u{2028}d\n\nlast";
+        let line_starts = compute_line_starts(text);
+        let line_and_character = |line, character| LineAndCharacter { line, character };
+        assert_eq!(line_starts, [0, 3, 5, 9, 11, 12]);
+        assert_eq!(
+            position_to_line_and_character(&line_starts, 4),
+            line_and_character(1, 1)
+        );
+        assert_eq!(
+            position_to_line_and_character(&line_starts, 14),
+            line_and_character(5, 2)
+        );
+        assert_eq!(
+            line_and_character_to_position(text, &line_starts, line_and_character(5, 2)),
+            14
+        );
+        // Characters past the end of a line stop before its line break
+        assert_eq!(
+            line_and_character_to_position(text, &line_starts, line_and_character(0, 5)),
+            1
+        );
+        assert_eq!(
+            line_and_character_to_position(text, &line_starts, line_and_character(2, 5)),
+            6
+        );
+        assert_eq!(
+            line_and_character_to_position(text, &line_starts, line_and_character(9, 0)),
+            text.len()
+        );
+    }
+
+    #[test]
+    fn counts_characters_in_utf16_code_units() {
+        let text = "const é = \"😀\";\nx";
+        let line_starts = compute_line_starts(text);
+        let line_and_character = |line, character| LineAndCharacter { line, character };
+        let quote = text.rfind('"').unwrap();
+        assert_eq!(
+            position_to_utf16_line_and_character(text, &line_starts, quote),
+            line_and_character(0, 13)
+        );
+        assert_eq!(
+            position_to_line_and_character(&line_starts, quote),
+            line_and_character(0, 16)
+        );
+        let position = |character| {
+            utf16_line_and_character_to_position(
+                text,
+                &line_starts,
+                line_and_character(0, character),
+            )
+        };
+        assert_eq!(position(13), quote);
+        // The middle of the surrogate pair of the emoji is its start
+        assert_eq!(position(12), quote - 4);
+        assert_eq!(position(11), quote - 4);
+        assert_eq!(position(100), text.find('\n').unwrap());
+    }
+}
@@ -0,0 +1,1400 @@
+//! Transformations of syntax trees before they are printed
+//!
+//! Each transformer takes a source file and returns a new tree, sharing the nodes it leaves
+//! unchanged with the original. Nodes that a transformer rewrites keep the source range of the
+//! node they replace, so the printer can still find their comments.
+
+mod destructuring;
+mod es2015;
+mod es2017;
+mod es2018;
+mod generators;
+mod helpers;
+mod jsx;
+mod module;
+mod ts;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    ShorthandPropertyAssignment, SourceFile, SpreadAssignment, SpreadElement,
+};
+use crate::compiler::ast::utilities::{is_class_like, is_external_module, is_function_like_kind};
+use crate::compiler::ast::visitor::{
+    NodeVisitor, ensure_sufficient_stack, for_each_descendant, visit_each_child,
+};
+use crate::compiler::ast::{Node, NodeFlags, NodeId, NodeList, NodeMapper, SyntaxKind};
+use crate::compiler::checker::LiteralValue;
+use crate::compiler::scanner::ScriptTarget;
+
+use helpers::EmitHelper;
+
+pub use jsx::JsxEmit;
+pub use module::ModuleKind;
+pub use ts::transform_type_script;
+
+/// Options that decide which transformers run over a file
+#[derive(Debug, Clone)]
+pub struct TransformOptions {
+    /// The language version the output must run on; syntax newer than it is lowered
+    pub target: ScriptTarget,
+    /// The module system the output uses
+    pub module: ModuleKind,
+    /// Whether imports of CommonJS modules go through helpers that give them a default export
+    pub es_module_interop: bool,
+    /// How JSX is emitted
+    pub jsx: JsxEmit,
+    /// Whether imports are kept as written, except for those marked as type-only
+    pub verbatim_module_syntax: bool,
+    /// Whether an import whose bindings are all removed is kept for its side effects
+    pub preserve_unused_imports: bool,
+    /// Whether `for...of` loops run iterators when they are lowered to ES5, rather than going
+    /// over array indexes
+    pub downlevel_iteration: bool,
+    /// Whether const enums are emitted like other enums, besides being inlined where they are
+    /// used
+    pub preserve_const_enums: bool,
+    /// The values the checker computed for the file's enum members, and for the accesses to
+    /// const enum members that are inlined, by node
+    pub constant_values: HashMap<NodeId, LiteralValue>,
+    /// Whether modules import the helpers they call from `tslib` rather than declaring them
+    pub import_helpers: bool,
+    /// Whether the helpers files call are left for the environment to define
+    pub no_emit_helpers: bool,
+    /// The name the module is defined under when it is bundled into one file with others,
+    /// which AMD and System modules pass to `define` and `System.register`
+    pub module_name: Option<String>,
+    /// The names the modules bundled into the same file are defined under, by the specifiers
+    /// the file imports them with
+    pub bundled_module_names: HashMap<String, String>,
+}
+
+impl Default for TransformOptions {
+    fn default() -> Self {
+        TransformOptions {
+            target: ScriptTarget::ESNext,
+            module: ModuleKind::ESNext,
+            es_module_interop: false,
+            jsx: JsxEmit::None,
+            verbatim_module_syntax: false,
+            preserve_unused_imports: false,
+            downlevel_iteration: false,
+            preserve_const_enums: false,
+            constant_values: HashMap::new(),
+            import_helpers: false,
+            no_emit_helpers: false,
+            module_name: None,
+            bundled_module_names: HashMap::new(),
+        }
+    }
+}
+
+/// Transforms a source file into JavaScript for the configured target
+///
+/// TypeScript syntax is erased first, and JSX becomes function calls if it is configured to.
+/// Then each feature newer than the target is lowered, from the newest language version down.
+/// Imports and exports are rewritten for the module system last, and the runtime helpers the
+/// lowered code calls are written at the top of the file, unless they are imported from
+/// `tslib` or left out.
+pub fn transform_file(source_file: &Arc<Node>, options: &TransformOptions) -> Arc<Node> {
+    let (file, helpers) = transform_file_without_helpers(source_file, options);
+    if options.no_emit_helpers {
+        return file;
+    }
+    helpers::add_emit_helpers(&file, &helpers)
+}
+
+/// Transforms the files bundled into one output file, in the order they are written to it
+///
+/// Each file is transformed as by [`transform_file`], except that the helpers the files call
+/// are written once, at the top of the first file.
+pub fn transform_bundle(files: &[(Arc<Node>, TransformOptions)]) -> Vec<Arc<Node>> {
+    let mut bundle_helpers: Vec<&'static EmitHelper> = Vec::new();
+    let mut transformed: Vec<Arc<Node>> = files
+        .iter()
+        .map(|(source_file, options)| {
+            let (file, helpers) = transform_file_without_helpers(source_file, options);
+            for helper in helpers {
+                if !bundle_helpers.iter().any(|added| added.name == helper.name) {
+                    bundle_helpers.push(helper);
+                }
+            }
+            file
+        })
+        .collect();
+    let no_emit_helpers = files
+        .first()
+        .is_some_and(|(_, options)| options.no_emit_helpers);
+    if let Some(first) = transformed.first_mut()
+        && !no_emit_helpers
+    {
+        *first = helpers::add_emit_helpers(first, &bundle_helpers);
+    }
+    transformed
+}
+
+/// Transforms a source file, returning the helpers it calls rather than writing them into it
+///
+/// A module that imports its helpers from `tslib` returns none.
+fn transform_file_without_helpers(
+    source_file: &Arc<Node>,
+    options: &TransformOptions,
+) -> (Arc<Node>, Vec<&'static EmitHelper>) {
+    let mut context = TransformContext::new(source_file, options);
+    let mut file = transform_type_script(source_file, options);
+    if options.jsx.transforms_jsx() {
+        file = jsx::transform_jsx(&file, &mut context);
+        context.add_used_names(&file);
+    }
+    let passes: [(ScriptTarget, Pass); 4] = [
+        (ScriptTarget::ES2018, es2018::transform_es2018),
+        (ScriptTarget::ES2017, es2017::transform_es2017),
+        (ScriptTarget::ES2015, es2015::transform_es2015),
+        (ScriptTarget::ES2015, generators::transform_generators),
+    ];
+    for (version, pass) in passes {
+        if options.target < version {
+            file = pass(&file, &mut context);
+            context.add_used_names(&file);
+        }
+    }
+    let file = module::transform_module(&file, &mut context);
+    if context.import_helpers && is_external_module(source_file) {
+        return (file, Vec::new());
+    }
+    (file, context.helpers)
+}
+
+/// A transformer that lowers the syntax of one language version
+type Pass = fn(&Arc<Node>, &mut TransformContext) -> Arc<Node>;
+
+/// State shared by the transformers that run over one file
+struct TransformContext {
+    /// The language version the output must run on
+    target: ScriptTarget,
+    /// The module system the output uses
+    module: ModuleKind,
+    /// Whether imports of CommonJS modules go through the interop helpers
+    es_module_interop: bool,
+    /// How JSX is emitted
+    jsx: JsxEmit,
+    /// Whether lowered `for...of` loops run iterators
+    downlevel_iteration: bool,
+    /// The name the module is defined under in a bundle
+    module_name: Option<String>,
+    /// The names the modules bundled with this one are defined under, by their specifiers
+    bundled_module_names: HashMap<String, String>,
+    /// Whether a module imports the helpers it calls from `tslib`
+    import_helpers: bool,
+    /// The helpers requested so far, each after the helpers it depends on
+    helpers: Vec<&'static EmitHelper>,
+    /// The identifiers of the file and the names generated so far, which a new name must not
+    /// be one of
+    used_names: HashSet<String>,
+}
+
+impl TransformContext {
+    fn new(source_file: &Arc<Node>, options: &TransformOptions) -> Self {
+        let mut used_names = HashSet::new();
+        collect_identifiers(source_file, &mut used_names);
+        TransformContext {
+            target: options.target,
+            module: options.module,
+            es_module_interop: options.es_module_interop,
+            jsx: options.jsx,
+            downlevel_iteration: options.downlevel_iteration,
+            module_name: options.module_name.clone(),
+            bundled_module_names: options.bundled_module_names.clone(),
+            import_helpers: options.import_helpers,
+            helpers: Vec::new(),
+            used_names,
+        }
+    }
+
+    /// Records that the transformed file calls `helper`
+    fn request_helper(&mut self, helper: &'static EmitHelper) {
+        if self
+            .helpers
+            .iter()
+            .any(|requested| requested.name == helper.name)
+        {
+            return;
+        }
+        for dependency in helper.dependencies {
+            self.request_helper(dependency);
+        }
+        self.helpers.push(helper);
+    }
+
+    /// Whether a generated name would be free to use anywhere in the file
+    fn is_unused_name(&self, name: &str) -> bool {
+        !self.used_names.contains(name)
+    }
+
+    /// Generates `base_1`, or the first of `base_2`, `base_3`, and so on not used in the file
+    fn create_unique_name(&mut self, base: &str) -> String {
+        let name = (1..)
+            .map(|index| format!("{base}_{index}"))
+            .find(|name| self.is_unused_name(name))
+            .expect("some suffix is free");
+        self.used_names.insert(name.clone());
+        name
+    }
+
+    /// Generates a name for a variable local to one function: `_a`, `_b`, and so on, leaving
+    /// out `_i` and `_n`, which are kept for loop counters
+    ///
+    /// `function_names` holds the names generated within the function so far. Other functions
+    /// may reuse the same names.
+    fn create_temp_name(&self, function_names: &mut HashSet<String>) -> String {
+        let letters = ('a'..='z')
+            .filter(|letter| !matches!(letter, 'i' | 'n'))
+            .map(|letter| format!("_{letter}"));
+        let numbers = (0..).map(|index| format!("_{index}"));
+        let name = letters
+            .chain(numbers)
+            .find(|name| self.is_unused_name(name) && !function_names.contains(name))
+            .expect("some name is free");
+        function_names.insert(name.clone());
+        name
+    }
+
+    /// Generates a name for a loop counter local to one function: `_i` if it is free, or else
+    /// a name from [`TransformContext::create_temp_name`]
+    fn create_loop_variable_name(&self, function_names: &mut HashSet<String>) -> String {
+        if self.is_unused_name("_i") && function_names.insert("_i".to_string()) {
+            return "_i".to_string();
+        }
+        self.create_temp_name(function_names)
+    }
+
+    /// Records the identifiers of a transformed file, so that the names a transformer
+    /// generated are not generated again by the transformers after it
+    fn add_used_names(&mut self, file: &Arc<Node>) {
+        collect_identifiers(file, &mut self.used_names);
+    }
+
+    /// Reserves `name` for a generated variable such as `_this`, or a unique name based on it
+    /// if the file already uses it
+    fn reserve_name(&mut self, name: &str) -> String {
+        if self.is_unused_name(name) {
+            self.used_names.insert(name.to_string());
+            name.to_string()
+        } else {
+            self.create_unique_name(name)
+        }
+    }
+}
+
+fn collect_identifiers(node: &Arc<Node>, names: &mut HashSet<String>) {
+    if let Some(arena) = node.data_as::<SourceFile>().and_then(SourceFile::arena) {
+        let identifiers = arena
+            .nodes()
+            .iter()
+            .filter(|node| node.kind == SyntaxKind::Identifier);
+        names.extend(identifiers.map(|identifier| identifier.text().to_string()));
+        return;
+    }
+    if node.kind == SyntaxKind::Identifier {
+        names.insert(node.text().to_string());
+    }
+    for_each_descendant(node, &mut |descendant| {
+        if descendant.kind == SyntaxKind::Identifier {
+            names.insert(descendant.text().to_string());
+        }
+        false
+    });
+}
+
+/// Replaces the statements of a source file
+fn update_statements(
+    factory: &NodeFactory,
+    source_file: &Arc<Node>,
+    statements: Vec<Arc<Node>>,
+) -> Arc<Node> {
+    let data = source_file.as_source_file();
+    factory.update_source_file(
+        source_file,
+        factory.update_node_list(&data.statements, statements),
+    )
+}
+
+/// Whether a statement is a directive such as `"use strict"` at the start of a body
+fn is_prologue_directive(statement: &Arc<Node>) -> bool {
+    statement.kind == SyntaxKind::ExpressionStatement
+        && statement.as_expression_statement().expression.kind == SyntaxKind::StringLiteral
+}
+
+/// Inserts statements at the start of a body, after its prologue directives
+fn insert_after_prologue(statements: &mut Vec<Arc<Node>>, inserted: Vec<Arc<Node>>) {
+    let index = statements
+        .iter()
+        .take_while(|statement| is_prologue_directive(statement))
+        .count();
+    statements.splice(index..index, inserted);
+}
+
+/// Declares `names` as variables without initializers at the start of a block body
+fn add_variable_declarations(
+    factory: &NodeFactory,
+    body: &Arc<Node>,
+    names: &[String],
+) -> Arc<Node> {
+    if names.is_empty() {
+        return Arc::clone(body);
+    }
+    let block = body.as_block();
+    let mut statements = block.statements.nodes.clone();
+    let declarations = names
+        .iter()
+        .map(|name| {
+            factory.create_variable_declaration(factory.create_identifier(name.as_str()), None)
+        })
+        .collect();
+    insert_after_prologue(
+        &mut statements,
+        vec![factory.create_variable_statement(declarations)],
+    );
+    factory.update_block(
+        body,
+        factory.update_node_list(&block.statements, statements),
+        true,
+    )
+}
+
+/// Replaces the body of a function-like node, and the parameters in `parameters`, each given
+/// with the parameter it replaces
+fn update_function_body(
+    factory: &NodeFactory,
+    node: &Arc<Node>,
+    parameters: Vec<(Arc<Node>, Arc<Node>)>,
+    body: Arc<Node>,
+) -> Arc<Node> {
+    struct ReplaceChildren {
+        replacements: Vec<(Arc<Node>, Arc<Node>)>,
+    }
+
+    impl NodeMapper for ReplaceChildren {
+        fn map_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+            self.replacements
+                .iter()
+                .find(|(old, _)| Arc::ptr_eq(node, old))
+                .map_or_else(|| Arc::clone(node), |(_, new)| Arc::clone(new))
+        }
+    }
+
+    let old = node.body().expect("the function has a body");
+    let mut replacements = parameters;
+    replacements.push((Arc::clone(old), body));
+    factory.update_children(node, &mut ReplaceChildren { replacements })
+}
+
+/// Inserts statements at the start of a function body, after its prologue directives, turning
+/// the expression body of an arrow function into a block that returns the expression
+fn prepend_to_function_body(
+    factory: &NodeFactory,
+    body: &Arc<Node>,
+    statements: Vec<Arc<Node>>,
+) -> Arc<Node> {
+    if statements.is_empty() {
+        return Arc::clone(body);
+    }
+    if body.kind != SyntaxKind::Block {
+        let mut statements = statements;
+        statements.push(factory.create_return(Some(Arc::clone(body))));
+        return factory.create_block(statements, true);
+    }
+    let block = body.as_block();
+    let mut body_statements = block.statements.nodes.clone();
+    insert_after_prologue(&mut body_statements, statements);
+    factory.update_block(
+        body,
+        factory.update_node_list(&block.statements, body_statements),
+        true,
+    )
+}
+
+/// Collects the identifiers a variable or parameter name declares, including those nested in
+/// destructuring patterns
+fn collect_binding_names(name: &Arc<Node>, names: &mut Vec<String>) {
+    if name.kind == SyntaxKind::Identifier {
+        names.push(name.text().to_string());
+        return;
+    }
+    name.for_each_child(&mut |child| {
+        if child.kind == SyntaxKind::BindingElement {
+            collect_binding_names(&child.as_binding_element().name, names);
+        }
+        false
+    });
+}
+
+/// Collects the names declared in the scope of a function body or source file: its
+/// parameters aside, the variables declared with `var` anywhere in it, its function
+/// declarations, and the declarations in its own statements
+fn collect_function_scoped_names(statements: &NodeList, names: &mut Vec<String>) {
+    collect_block_scoped_names(statements, names);
+    for statement in statements.iter() {
+        collect_hoisted_names(statement, names);
+    }
+}
+
+/// Collects the names of the `let`, `const`, and class declarations among `statements`
+fn collect_block_scoped_names(statements: &NodeList, names: &mut Vec<String>) {
+    for statement in statements.iter() {
+        match statement.kind {
+            SyntaxKind::VariableStatement => {
+                names.extend(block_scoped_binding_names(
+                    &statement.as_variable_statement().declaration_list,
+                ));
+            }
+            SyntaxKind::ClassDeclaration => {
+                if let Some(name) = statement.name() {
+                    names.push(name.text().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Gets the names a `let` or `const` declaration list declares
+fn block_scoped_binding_names(list: &Arc<Node>) -> Vec<String> {
+    let mut names = Vec::new();
+    if list.kind == SyntaxKind::VariableDeclarationList && is_let_or_const(list) {
+        for declaration in list.as_variable_declaration_list().declarations.iter() {
+            collect_binding_names(&declaration.as_variable_declaration().name, &mut names);
+        }
+    }
+    names
+}
+
+/// Collects the names that `var` declarations and function declarations within `node` add to
+/// the function around it
+fn collect_hoisted_names(node: &Arc<Node>, names: &mut Vec<String>) {
+    match node.kind {
+        SyntaxKind::VariableDeclarationList if !is_let_or_const(node) => {
+            for declaration in node.as_variable_declaration_list().declarations.iter() {
+                collect_binding_names(&declaration.as_variable_declaration().name, names);
+            }
+            return;
+        }
+        SyntaxKind::FunctionDeclaration => {
+            if let Some(name) = node.name() {
+                names.push(name.text().to_string());
+            }
+            return;
+        }
+        kind if is_function_like_kind(kind) || is_class_like(node) => return,
+        _ => {}
+    }
+    node.for_each_child(&mut |child| {
+        ensure_sufficient_stack(|| collect_hoisted_names(child, names));
+        false
+    });
+}
+
+/// Gets the names that a node declares for its own scope, which hide the same names outside
+/// of it, if the node is a scope: a block, a function, a class, or a loop or `catch` clause
+/// with its own variables
+fn scope_names(node: &Arc<Node>) -> Option<Vec<String>> {
+    use SyntaxKind as K;
+    let mut names = Vec::new();
+    match node.kind {
+        K::Block | K::ModuleBlock => {
+            collect_block_scoped_names(
+                node.statements().expect("blocks have statements"),
+                &mut names,
+            );
+        }
+        K::CaseBlock => {
+            for clause in node.as_case_block().clauses.iter() {
+                let statements = clause.statements().expect("clauses have statements");
+                collect_block_scoped_names(statements, &mut names);
+            }
+        }
+        K::CatchClause => {
+            if let Some(declaration) = &node.as_catch_clause().variable_declaration {
+                collect_binding_names(&declaration.as_variable_declaration().name, &mut names);
+            }
+        }
+        K::ForStatement | K::ForInStatement | K::ForOfStatement => {
+            let initializer = match node.kind {
+                K::ForStatement => node.as_for_statement().initializer.as_ref(),
+                K::ForInStatement => Some(&node.as_for_in_statement().initializer),
+                _ => Some(&node.as_for_of_statement().initializer),
+            };
+            names = initializer
+                .map(block_scoped_binding_names)
+                .unwrap_or_default();
+        }
+        K::ClassDeclaration | K::ClassExpression => {
+            names.extend(node.name().map(|name| name.text().to_string()));
+        }
+        kind if is_function_like_kind(kind) => {
+            if kind == K::FunctionExpression
+                && let Some(name) = node.name()
+            {
+                names.push(name.text().to_string());
+            }
+            for parameter in node.parameters().into_iter().flat_map(|list| list.iter()) {
+                collect_binding_names(&parameter.as_parameter().name, &mut names);
+            }
+            if let Some(body) = node.body()
+                && body.kind == K::Block
+            {
+                collect_function_scoped_names(&body.as_block().statements, &mut names);
+            }
+        }
+        _ => return None,
+    }
+    Some(names)
+}
+
+/// Removes the `export` and `default` modifiers of a declaration
+fn strip_export_modifiers(factory: &NodeFactory, node: &Arc<Node>) -> Arc<Node> {
+    struct ExportModifiers;
+
+    impl NodeVisitor for ExportModifiers {
+        fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+            Arc::clone(node)
+        }
+
+        fn visit_list_element(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+            match node.kind {
+                SyntaxKind::ExportKeyword | SyntaxKind::DefaultKeyword => Vec::new(),
+                _ => vec![Arc::clone(node)],
+            }
+        }
+    }
+
+    visit_each_child(node, &mut ExportModifiers, factory)
+}
+
+/// Gets the child of a node that is an identifier but not a reference to a variable
+fn non_reference_name(node: &Node) -> Option<&Arc<Node>> {
+    use SyntaxKind as K;
+    match node.kind {
+        K::PropertyAccessExpression => Some(&node.as_property_access_expression().name),
+        K::QualifiedName => Some(&node.as_qualified_name().right),
+        K::PropertyAssignment
+        | K::PropertyDeclaration
+        | K::MethodDeclaration
+        | K::GetAccessor
+        | K::SetAccessor
+        | K::EnumMember
+        | K::JsxAttribute
+        | K::MetaProperty => node.name(),
+        K::BindingElement => node.as_binding_element().property_name.as_ref(),
+        K::LabeledStatement => Some(&node.as_labeled_statement().label),
+        K::BreakStatement => node.as_break_statement().label.as_ref(),
+        K::ContinueStatement => node.as_continue_statement().label.as_ref(),
+        _ => None,
+    }
+}
+
+/// Inserts a statement at the start of a block, or puts it in a block with a statement that
+/// is not one
+fn prepend_to_block(factory: &NodeFactory, statement: &Arc<Node>, first: Arc<Node>) -> Arc<Node> {
+    if statement.kind != SyntaxKind::Block {
+        return factory.create_block(vec![first, Arc::clone(statement)], true);
+    }
+    let block = statement.as_block();
+    let mut statements = vec![first];
+    statements.extend(block.statements.iter().cloned());
+    factory.update_block(
+        statement,
+        factory.update_node_list(&block.statements, statements),
+        true,
+    )
+}
+
+fn is_let_or_const(list: &Node) -> bool {
+    list.flags.intersects(NodeFlags::LET | NodeFlags::CONST)
+        && !list.flags.contains(NodeFlags::USING)
+}
+
+/// Turns the name of a variable declaration into the target of an assignment, converting a
+/// destructuring pattern into the matching object or array literal
+fn to_assignment_target(factory: &NodeFactory, name: &Arc<Node>) -> Arc<Node> {
+    match name.kind {
+        SyntaxKind::ObjectBindingPattern => {
+            let properties = name
+                .as_object_binding_pattern()
+                .elements
+                .iter()
+                .map(|element| {
+                    let data = element.as_binding_element();
+                    if data.dot_dot_dot_token.is_some() {
+                        return factory.create(
+                            SyntaxKind::SpreadAssignment,
+                            SpreadAssignment {
+                                expression: to_assignment_target(factory, &data.name),
+                            },
+                        );
+                    }
+                    match &data.property_name {
+                        Some(property_name) => {
+                            let target = with_default(
+                                factory,
+                                to_assignment_target(factory, &data.name),
+                                &data.initializer,
+                            );
+                            factory.create_property_assignment(Arc::clone(property_name), target)
+                        }
+                        None => factory.create(
+                            SyntaxKind::ShorthandPropertyAssignment,
+                            ShorthandPropertyAssignment {
+                                modifiers: None,
+                                name: Arc::clone(&data.name),
+                                postfix_token: None,
+                                equals_token: data
+                                    .initializer
+                                    .as_ref()
+                                    .map(|_| factory.create_token(SyntaxKind::EqualsToken)),
+                                object_assignment_initializer: data.initializer.clone(),
+                            },
+                        ),
+                    }
+                })
+                .collect();
+            factory.create_object_literal(properties, false)
+        }
+        SyntaxKind::ArrayBindingPattern => {
+            let elements = name
+                .as_array_binding_pattern()
+                .elements
+                .iter()
+                .map(|element| {
+                    if element.kind == SyntaxKind::OmittedExpression {
+                        return Arc::clone(element);
+                    }
+                    let data = element.as_binding_element();
+                    let target = to_assignment_target(factory, &data.name);
+                    if data.dot_dot_dot_token.is_some() {
+                        factory.create(
+                            SyntaxKind::SpreadElement,
+                            SpreadElement { expression: target },
+                        )
+                    } else {
+                        with_default(factory, target, &data.initializer)
+                    }
+                })
+                .collect();
+            factory.create_array_literal(elements, false)
+        }
+        _ => factory.create_identifier(name.text()),
+    }
+}
+
+/// Creates `target = initializer` for a destructuring target with a default value
+fn with_default(
+    factory: &NodeFactory,
+    target: Arc<Node>,
+    initializer: &Option<Arc<Node>>,
+) -> Arc<Node> {
+    match initializer {
+        Some(initializer) => factory.create_assignment(target, Arc::clone(initializer)),
+        None => target,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::emitter::{PrinterOptions, print_file};
+    use crate::compiler::parser::parse_source_file;
+    use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+
+    fn erase(text: &str) -> String {
+        let file = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let options = TransformOptions::default();
+        print_file(
+            &transform_type_script(&file, &options),
+            &PrinterOptions::default(),
+        )
+    }
+
+    fn lower(text: &str) -> String {
+        lower_to(text, ScriptTarget::ES5)
+    }
+
+    fn lower_to(text: &str, target: ScriptTarget) -> String {
+        let file = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let options = TransformOptions {
+            target,
+            ..TransformOptions::default()
+        };
+        print_file(&transform_file(&file, &options), &PrinterOptions::default())
+    }
+
+    fn emit_module(file_name: &str, text: &str, options: &TransformOptions) -> String {
+        let file = parse_source_file(file_name, text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        print_file(&transform_file(&file, options), &PrinterOptions::default())
+    }
+
+    fn emit_jsx(text: &str, jsx: JsxEmit, target: ScriptTarget) -> String {
+        let file = parse_source_file(
+            "/src/test.tsx",
+            text,
+            ScriptTarget::ESNext,
+            ScriptKind::Unknown,
+        );
+        let options = TransformOptions {
+            target,
+            jsx,
+            ..TransformOptions::default()
+        };
+        print_file(&transform_file(&file, &options), &PrinterOptions::default())
+    }
+
+    fn module_options(module: ModuleKind) -> TransformOptions {
+        TransformOptions {
+            target: ScriptTarget::ES2015,
+            module,
+            ..TransformOptions::default()
+        }
+    }
+
+    #[test]
+    fn erases_type_annotations_and_assertions() {
+        assert_eq!(
+            erase(
+                "let x: number = 1, y!: string;\nfunction f<T>(this: Window, a?: T, ...b: T[]): T { return a as T; }\n"
+            ),
+            "let x = 1, y;\nfunction f(a, ...b) { return a; }\n"
+        );
+        assert_eq!(
+            erase(
+                "const n = (<any>value).length + (a as any)!.b + c!;\nconst v = (a?.b as C).d;\nconst o = ({} satisfies I);\n"
+            ),
+            "const n = value.length + a.b + c;\nconst v = (a?.b).d;\nconst o = {};\n"
+        );
+        assert_eq!(
+            erase("const g = <T,>(x: T): T => x;\nnew Map<string, number>();\nf<string>(1);\n"),
+            "const g = (x) => x;\nnew Map();\nf(1);\n"
+        );
+    }
+
+    #[test]
+    fn erases_type_level_declarations() {
+        assert_eq!(
+            erase(
+                "interface I { a: number }\ntype T = string;\ndeclare const d: number;\ndeclare module \"m\" {}\nnamespace Types { export interface J {} }\nfunction o(a: string): void;\nfunction o(a: any) {}\nlet kept = 1;\n"
+            ),
+            "function o(a) { }\nlet kept = 1;\n"
+        );
+    }
+
+    #[test]
+    fn erases_class_members_and_modifiers() {
+        assert_eq!(
+            erase(
+                "abstract class A<T> extends B<T> implements I {\n    private readonly x?: number = 1;\n    declare y: string;\n    [key: string]: any;\n    abstract m(): void;\n    constructor(public a: number, protected b = 2, c: string) {\n        super();\n        c;\n    }\n    override n(): T { return this.x!; }\n}\n"
+            ),
+            "class A extends B {\n    x = 1;\n    constructor(a, b = 2, c) {\n        super();\n        this.a = a;\n        this.b = b;\n        c;\n    }\n    n() { return this.x; }\n}\n"
+        );
+    }
+
+    #[test]
+    fn elides_imports_and_exports_of_types() {
+        assert_eq!(
+            erase(
+                "import type { T } from \"./t\";\nimport { type U, V, W } from \"./v\";\nimport D, * as N from \"./d\";\nimport \"./side-effect\";\ninterface L {}\nexport { L, W };\nexport type { T };\nnew V();\n"
+            ),
+            "import { V, W } from \"./v\";\nimport \"./side-effect\";\nexport { W };\nnew V();\n"
+        );
+        assert_eq!(
+            erase("import type { T } from \"./t\";\nexport type U = T;\n"),
+            "export {};\n"
+        );
+    }
+
+    #[test]
+    fn elides_imports_by_how_their_names_are_used() {
+        // Property and member names are not uses of the imports they share names with
+        assert_eq!(
+            erase(
+                "import { A, B, C } from \"./a\";\ninterface I {}\nlet o = { A: 1, m() {} } as B;\no.C;\nexport default I;\n"
+            ),
+            "let o = { A: 1, m() { } };\no.C;\nexport {};\n"
+        );
+
+        let text = "import type { T } from \"./t\";\nimport { type U, V } from \"./v\";\nimport { type W } from \"./w\";\nlet x: V;\n";
+        let file = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        let emit = |options: TransformOptions| {
+            print_file(
+                &transform_type_script(&file, &options),
+                &PrinterOptions::default(),
+            )
+        };
+        assert_eq!(
+            emit(TransformOptions {
+                verbatim_module_syntax: true,
+                ..TransformOptions::default()
+            }),
+            "import { V } from \"./v\";\nimport {} from \"./w\";\nlet x;\n"
+        );
+        assert_eq!(
+            emit(TransformOptions {
+                preserve_unused_imports: true,
+                ..TransformOptions::default()
+            }),
+            "import \"./v\";\nimport \"./w\";\nlet x;\n"
+        );
+    }
+
+    #[test]
+    fn emits_enums_as_objects() {
+        assert_eq!(
+            erase("export enum E { A, B = \"b\", C = -2, D }\nconst enum F { X }\n"),
+            "export var E;\n(function (E) {\n    E[E[\"A\"] = 0] = \"A\";\n    E[\"B\"] = \"b\";\n    E[E[\"C\"] = -2] = \"C\";\n    E[E[\"D\"] = -1] = \"D\";\n})(E || (E = {}));\n"
+        );
+
+        let text = "const enum F { X = 1 }\nF.X.toString();\n";
+        let file = parse_source_file("test.ts", text, ScriptTarget::ESNext, ScriptKind::Unknown);
+        // The checker gives the values of the member and of the access to it
+        let expression = &file.as_source_file().statements.nodes[1]
+            .as_expression_statement()
+            .expression;
+        let callee = &expression.as_call_expression().expression;
+        let access = &callee.as_property_access_expression().expression;
+        let member = &file.as_source_file().statements.nodes[0]
+            .as_enum_declaration()
+            .members
+            .nodes[0];
+        let one = LiteralValue::Number(crate::compiler::checker::Number(1.0));
+        let options = TransformOptions {
+            preserve_const_enums: true,
+            constant_values: HashMap::from([
+                (member.node_id(), one.clone()),
+                (access.node_id(), one),
+            ]),
+            ..TransformOptions::default()
+        };
+        assert_eq!(
+            print_file(
+                &transform_type_script(&file, &options),
+                &PrinterOptions::default()
+            ),
+            "var F;\n(function (F) {\n    F[F[\"X\"] = 1] = \"X\";\n})(F || (F = {}));\n1 /* F.X */.toString();\n"
+        );
+    }
+
+    #[test]
+    fn emits_namespaces_as_functions() {
+        assert_eq!(
+            erase(
+                "namespace A.B {\n    export const x = 1;\n    export function f() { return x; }\n}\nnamespace A.B {\n    export import g = B.f;\n    let y = { x };\n}\n"
+            ),
+            "var A;\n(function (A) {\n    let B;\n    (function (B) {\n        B.x = 1;\n        function f() { return B.x; }\n        B.f = f;\n    })(B = A.B || (A.B = {}));\n})(A || (A = {}));\n(function (A) {\n    let B;\n    (function (B) {\n        B.g = B.f;\n        let y = { x: B.x };\n    })(B = A.B || (A.B = {}));\n})(A || (A = {}));\n"
+        );
+        // Functions merge with namespaces, and a namespace's name can be hidden inside it
+        assert_eq!(
+            erase("function F() { }\nnamespace F { const F = 1; export let v = F; }\n"),
+            "function F() { }\n(function (F_1) {\n    const F = 1;\n    F_1.v = F;\n})(F || (F = {}));\n"
+        );
+    }
+
+    #[test]
+    fn keeps_comments_of_what_remains() {
+        // The comments of a removed declaration go with it
+        assert_eq!(
+            erase(
+                "// a\ninterface I {}\n/** b */\nfunction f(x: number /* c */): void {\n    // d\n    return;\n}\n"
+            ),
+            "/** b */\nfunction f(x /* c */) {\n    // d\n    return;\n}\n"
+        );
+    }
+
+    #[test]
+    fn lowers_classes_to_constructor_functions() {
+        assert_eq!(
+            lower(
+                "class A {\n    x = 1;\n    constructor(public y: number) {}\n    m() { return this.x; }\n    get v() { return 1; }\n    set v(a) {}\n    static s = 2;\n}\n"
+            ),
+            "var A = (function () {\n    function A(y) {\n        this.y = y;\n        this.x = 1;\n    }\n    A.prototype.m = function () { return this.x; };\n    Object.defineProperty(A.prototype, \"v\", {\n        get: function () { return 1; },\n        set: function (a) { },\n        enumerable: false,\n        configurable: true\n    });\n    A.s = 2;\n    return A;\n}());\n"
+        );
+        let derived = lower(
+            "export class B extends A {\n    z = 1;\n    constructor() { super(1); }\n    m() { return super.m(); }\n}\n",
+        );
+        assert!(derived.starts_with("var __extends = (this && this.__extends) || "));
+        assert!(derived.ends_with(
+            "export var B = (function (_super) {\n    __extends(B, _super);\n    function B() {\n        var _this = _super.call(this, 1) || this;\n        _this.z = 1;\n        return _this;\n    }\n    B.prototype.m = function () { return _super.prototype.m.call(this); };\n    return B;\n}(A));\n"
+        ));
+        assert!(lower("class C extends A {}\n").contains(
+            "    function C() {\n        return _super !== null && _super.apply(this, arguments) || this;\n    }\n"
+        ));
+    }
+
+    #[test]
+    fn lowers_arrow_functions_and_templates() {
+        assert_eq!(
+            lower(
+                "function f() {\n    const g = () => this.x + arguments[0];\n    return `a${1}b${g()}`;\n}\nconst s = `plain`;\n"
+            ),
+            "function f() {\n    var _this = this;\n    var _arguments = arguments;\n    var g = function () { return _this.x + _arguments[0]; };\n    return \"a\".concat(1, \"b\").concat(g());\n}\nvar s = \"plain\";\n"
+        );
+        let tagged = lower("tag`a${x}\\n`;\n");
+        assert!(tagged.starts_with("var __makeTemplateObject = "));
+        assert!(
+            tagged
+                .ends_with("tag(__makeTemplateObject([\"a\", \"\\n\"], [\"a\", \"\\\\n\"]), x);\n")
+        );
+    }
+
+    #[test]
+    fn lowers_block_scoped_declarations_to_var() {
+        assert_eq!(
+            lower(
+                "let q = 1;\n{\n    let q = 2;\n    q++;\n}\nfor (const v of list) {\n    let w;\n    use(v, w);\n}\n"
+            ),
+            "var q = 1;\n{\n    var q_1 = 2;\n    q_1++;\n}\nfor (var _i = 0, list_1 = list; _i < list_1.length; _i++) {\n    var v = list_1[_i];\n    var w = void 0;\n    use(v, w);\n}\n"
+        );
+    }
+
+    #[test]
+    fn lowers_parameters_and_spreads() {
+        let lowered = lower(
+            "function f(a = 1, ...rest) {}\nf(...a, 1);\nx.y.z(...a);\nconst o = { a, m() {}, ...b };\n",
+        );
+        assert!(lowered.starts_with("var __assign = "));
+        assert!(lowered.contains("var __spreadArray = "));
+        assert!(lowered.ends_with(
+            "var _a;\nfunction f(a) {\n    if (a === void 0) {\n        a = 1;\n    }\n    var rest = [];\n    for (var _i = 1; _i < arguments.length; _i++) {\n        rest[_i - 1] = arguments[_i];\n    }\n}\nf.apply(void 0, __spreadArray(__spreadArray([], a, false), [1], false));\n(_a = x.y).z.apply(_a, a);\nvar o = __assign({ a: a, m: function () { } }, b);\n"
+        ));
+    }
+
+    #[test]
+    fn lowers_destructuring() {
+        assert_eq!(
+            lower(
+                "var { a, b: [c, , d = 2] } = f();\n[a, b] = [b, a];\nconst v = ({ a } = o);\nfor (const [k, val] of entries) {\n    use(k, val);\n}\ntry {\n    g();\n} catch ({ message }) {\n    log(message);\n}\n"
+            ),
+            "var _d;\nvar _a = f(), a = _a.a, _b = _a.b, c = _b[0], _c = _b[2], d = _c === void 0 ? 2 : _c;\n_d = [b, a], a = _d[0], b = _d[1];\nvar v = (a = o.a, o);\nfor (var _i = 0, entries_1 = entries; _i < entries_1.length; _i++) {\n    var _e = entries_1[_i], k = _e[0], val = _e[1];\n    use(k, val);\n}\ntry {\n    g();\n}\ncatch (_f) {\n    var message = _f.message;\n    log(message);\n}\n"
+        );
+        assert_eq!(
+            lower("function p({ q }, [r] = [], ...[s, t]) {\n    return q + r;\n}\n"),
+            "function p(_a, _b) {\n    var q = _a.q;\n    var r = (_b === void 0 ? [] : _b)[0];\n    var _c = [];\n    for (var _i = 2; _i < arguments.length; _i++) {\n        _c[_i - 2] = arguments[_i];\n    }\n    var s = _c[0], t = _c[1];\n    return q + r;\n}\n"
+        );
+    }
+
+    #[test]
+    fn lowers_object_rest_with_the_rest_helper() {
+        let text = "const { a, ...rest } = o;\nfunction f({ x, ...y }) {\n    return y;\n}\n";
+        let lowered = lower(text);
+        assert!(lowered.starts_with("var __rest = (this && this.__rest) || "));
+        assert!(lowered.ends_with(
+            "var a = o.a, rest = __rest(o, [\"a\"]);\nfunction f(_a) {\n    var x = _a.x, y = __rest(_a, [\"x\"]);\n    return y;\n}\n"
+        ));
+        let lowered = lower_to(text, ScriptTarget::ES2017);
+        assert!(lowered.starts_with("var __rest = (this && this.__rest) || "));
+        assert!(lowered.ends_with(
+            "const { a } = o, rest = __rest(o, [\"a\"]);\nfunction f(_a) {\n    var { x } = _a, y = __rest(_a, [\"x\"]);\n    return y;\n}\n"
+        ));
+        assert!(!lower_to(text, ScriptTarget::ES2018).contains("__rest"));
+    }
+
+    #[test]
+    fn lowers_computed_property_names() {
+        assert_eq!(
+            lower("const o = { a: 1, [k]: 2, b, m() { }, get g() { return 3; } };\n"),
+            "var _a;\nvar o = (_a = { a: 1 }, _a[k] = 2, _a.b = b, _a.m = function () { }, Object.defineProperty(_a, \"g\", { get: function () { return 3; }, enumerable: true, configurable: true }), _a);\n"
+        );
+    }
+
+    #[test]
+    fn gives_each_loop_iteration_its_own_captured_bindings() {
+        assert_eq!(
+            lower(
+                "function f(fns, xs) {\n    for (let i = 0; i < xs.length; i++) {\n        fns.push(() => xs[i]);\n        if (xs[i] === 0)\n            break;\n        if (xs[i] < 0)\n            return i;\n        i++;\n    }\n}\n"
+            ),
+            "function f(fns, xs) {\n    var _loop_1 = function (i) {\n        fns.push(function () { return xs[i]; });\n        if (xs[i] === 0) {\n            out_i_1 = i;\n            return \"break\";\n        }\n        if (xs[i] < 0) {\n            out_i_1 = i;\n            return { value: i };\n        }\n        i++;\n        out_i_1 = i;\n    };\n    var out_i_1;\n    for (var i = 0; i < xs.length; i++) {\n        var state_1 = _loop_1(i);\n        i = out_i_1;\n        if (typeof state_1 === \"object\")\n            return state_1.value;\n        if (state_1 === \"break\")\n            break;\n    }\n}\n"
+        );
+        assert_eq!(
+            lower("for (const x of xs) {\n    var last = x;\n    fns.push(() => this.x + x);\n}\n"),
+            "var _this = this;\nvar last;\nvar _loop_1 = function (x) {\n    last = x;\n    fns.push(function () { return _this.x + x; });\n};\nfor (var _i = 0, xs_1 = xs; _i < xs_1.length; _i++) {\n    var x = xs_1[_i];\n    _loop_1(x);\n}\n"
+        );
+        // A loop whose closures capture nothing it declares is left alone
+        assert_eq!(
+            lower("for (let i = 0; i < 3; i++) {\n    fns.push(() => j);\n}\n"),
+            "for (var i = 0; i < 3; i++) {\n    fns.push(function () { return j; });\n}\n"
+        );
+    }
+
+    #[test]
+    fn lowers_async_functions_to_awaiter() {
+        let lowered = lower_to(
+            "class A extends B {\n    async m() { return super.m(await this.n); }\n}\nconst f = async (x) => await x;\n",
+            ScriptTarget::ES2015,
+        );
+        assert!(lowered.starts_with("var __awaiter = (this && this.__awaiter) || "));
+        assert!(lowered.ends_with("class A extends B {\n    m() {\n        const _super = Object.create(null, {\n            m: { get: () => super.m }\n        });\n        return __awaiter(this, void 0, void 0, function* () {\n            return _super.m.call(this, yield this.n);\n        });\n    }\n}\nconst f = (x) => __awaiter(this, void 0, void 0, function* () {\n    return yield x;\n});\n"));
+        let lowered = lower("async function f() { await g(); }\n");
+        assert!(lowered.contains("var __generator = "));
+        assert!(lowered.ends_with(
+            "function f() {\n    return __awaiter(this, void 0, void 0, function () {\n        return __generator(this, function (_a) {\n            switch (_a.label) {\n                case 0: return [4, g()];\n                case 1:\n                    _a.sent();\n                    return [2];\n            }\n        });\n    });\n}\n"
+        ));
+    }
+
+    #[test]
+    fn lowers_generators_to_state_machines() {
+        let lowered = lower(
+            "function* g(n) {\n    for (let i = 0; i < n; i++) {\n        try {\n            yield i;\n        } catch (e) {\n            log(e);\n        }\n    }\n}\n",
+        );
+        assert!(lowered.starts_with("var __generator = (this && this.__generator) || "));
+        assert!(lowered.ends_with("function g(n) {\n    var i, e_1;\n    return __generator(this, function (_a) {\n        switch (_a.label) {\n            case 0:\n                i = 0;\n                _a.label = 1;\n            case 1:\n                if (!(i < n))\n                    return [3, 6];\n                _a.label = 2;\n            case 2:\n                _a.trys.push([2, 4, , 5]);\n                return [4, i];\n            case 3:\n                _a.sent();\n                return [3, 5];\n            case 4:\n                e_1 = _a.sent();\n                log(e_1);\n                return [3, 5];\n            case 5:\n                i++;\n                return [3, 1];\n            case 6: return [2];\n        }\n    });\n}\n"));
+        assert!(
+            lower("function* d() { yield* [1]; }\n").contains("case 0: return [5, __values([1])];")
+        );
+    }
+
+    #[test]
+    fn lowers_for_of_over_iterators() {
+        let file = parse_source_file(
+            "test.ts",
+            "function* g() { yield 1; }\nouter: for (const x of g()) {\n    for (let [k, v] of entries) {\n        if (k) continue outer;\n    }\n}\n",
+            ScriptTarget::ESNext,
+            ScriptKind::Unknown,
+        );
+        let options = TransformOptions {
+            target: ScriptTarget::ES5,
+            downlevel_iteration: true,
+            ..TransformOptions::default()
+        };
+        let lowered = print_file(&transform_file(&file, &options), &PrinterOptions::default());
+        assert!(lowered.contains("var __values = "));
+        // The temporaries of both loops are declared at the top, and the label stays on the
+        // loop so that `continue` can refer to it
+        assert!(lowered.contains("var e_1, _a, e_2, _d;\nfunction g() {"));
+        let loop_start = lowered.find("try {\n    outer:").unwrap();
+        assert_eq!(
+            &lowered[loop_start..],
+            "try {\n    outer: for (var _b = __values(g()), _c = _b.next(); !_c.done; _c = _b.next()) {\n        var x = _c.value;\n        try {\n            for (var entries_1 = __values(entries), entries_1_1 = entries_1.next(); !entries_1_1.done; entries_1_1 = entries_1.next()) {\n                var _e = entries_1_1.value, k = _e[0], v = _e[1];\n                if (k)\n                    continue outer;\n            }\n        }\n        catch (e_2_1) {\n            e_2 = { error: e_2_1 };\n        }\n        finally {\n            try {\n                if (entries_1_1 && !entries_1_1.done && (_d = entries_1.return))\n                    _d.call(entries_1);\n            }\n            finally {\n                if (e_2)\n                    throw e_2.error;\n            }\n        }\n    }\n}\ncatch (e_1_1) {\n    e_1 = { error: e_1_1 };\n}\nfinally {\n    try {\n        if (_c && !_c.done && (_a = _b.return))\n            _a.call(_b);\n    }\n    finally {\n        if (e_1)\n            throw e_1.error;\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn lowers_async_generators_and_for_await() {
+        let lowered = lower_to(
+            "async function* g(xs) {\n    for await (const x of xs) yield x;\n}\n",
+            ScriptTarget::ES2017,
+        );
+        assert!(lowered.contains("var __asyncValues = "));
+        assert!(lowered.ends_with("function g(xs) {\n    return __asyncGenerator(this, arguments, function* g_1() {\n        var _a, e_1, _b, _c;\n        try {\n            for (var _d = true, xs_1 = __asyncValues(xs), xs_1_1; xs_1_1 = yield __await(xs_1.next()), _a = xs_1_1.done, !_a; _d = true) {\n                _c = xs_1_1.value;\n                _d = false;\n                const x = _c;\n                yield yield __await(x);\n            }\n        }\n        catch (e_1_1) {\n            e_1 = { error: e_1_1 };\n        }\n        finally {\n            try {\n                if (!_d && !_a && (_b = xs_1.return))\n                    yield __await(_b.call(xs_1));\n            }\n            finally {\n                if (e_1)\n                    throw e_1.error;\n            }\n        }\n    });\n}\n"));
+    }
+
+    #[test]
+    fn emits_commonjs_modules() {
+        let options = module_options(ModuleKind::CommonJS);
+        assert_eq!(
+            emit_module(
+                "test.ts",
+                "import d, { a, b as c } from \"./lib/m\";\nimport * as ns from \"ns\";\nimport \"side\";\nexport let x = 1, y;\nlet z = 0;\nexport { z as w };\nexport function f() { return a(c, d, ns.k, { a }); }\nexport default class { }\nexport * from \"./star\";\nexport { e } from \"./re\";\nx = 2;\nz++;\n",
+                &options,
+            ),
+            "\"use strict\";\n".to_string()
+                + "var __createBinding = (this && this.__createBinding) || (Object.create ? (function (o, m, k, k2) {\n    if (k2 === undefined)\n        k2 = k;\n    var desc = Object.getOwnPropertyDescriptor(m, k);\n    if (!desc || (\"get\" in desc ? !m.__esModule : desc.writable || desc.configurable)) {\n        desc = { enumerable: true, get: function () { return m[k]; } };\n    }\n    Object.defineProperty(o, k2, desc);\n}) : (function (o, m, k, k2) {\n    if (k2 === undefined)\n        k2 = k;\n    o[k2] = m[k];\n}));\n"
+                + "var __exportStar = (this && this.__exportStar) || function (m, exports) {\n    for (var p in m)\n        if (p !== \"default\" && !Object.prototype.hasOwnProperty.call(exports, p))\n            __createBinding(exports, m, p);\n};\n"
+                + "Object.defineProperty(exports, \"__esModule\", { value: true });\n"
+                + "exports.e = exports.w = exports.y = exports.x = void 0;\n"
+                + "exports.f = f;\n"
+                + "const m_1 = require(\"./lib/m\");\n"
+                + "const ns = require(\"ns\");\n"
+                + "require(\"side\");\n"
+                + "exports.x = 1;\n"
+                + "let z = 0;\n"
+                + "exports.w = z;\n"
+                + "function f() { return (0, m_1.a)(m_1.b, m_1.default, ns.k, { a: m_1.a }); }\n"
+                + "class default_1 {\n}\n"
+                + "exports.default = default_1;\n"
+                + "__exportStar(require(\"./star\"), exports);\n"
+                + "const re_1 = require(\"./re\");\n"
+                + "Object.defineProperty(exports, \"e\", { enumerable: true, get: function () { return re_1.e; } });\n"
+                + "exports.x = 2;\n"
+                + "exports.w = ++z;\n"
+        );
+        assert_eq!(
+            emit_module(
+                "test.ts",
+                "import x = require(\"./y\");\nfunction h(x) { return x; }\nexport = h(x);\n",
+                &options,
+            ),
+            "\"use strict\";\nconst x = require(\"./y\");\nfunction h(x) { return x; }\nmodule.exports = h(x);\n"
+        );
+        // Scripts have no imports or exports to rewrite
+        assert_eq!(
+            emit_module("test.ts", "let a = 1;\n", &options),
+            "let a = 1;\n"
+        );
+        // except for dynamic imports
+        assert_eq!(
+            emit_module("test.ts", "const p = import(\"./a\");\n", &options),
+            "const p = Promise.resolve().then(() => require(\"./a\"));\n"
+        );
+    }
+
+    #[test]
+    fn emits_interop_helpers_for_commonjs_imports() {
+        let options = TransformOptions {
+            target: ScriptTarget::ES5,
+            module: ModuleKind::CommonJS,
+            es_module_interop: true,
+            ..TransformOptions::default()
+        };
+        let emitted = emit_module(
+            "test.ts",
+            "import d from \"./d\";\nimport * as ns from \"./ns\";\nimport m, { n } from \"./m\";\nimport { o } from \"./o\";\nconst lazy = import(\"./lazy\");\nexport { default as e } from \"./e\";\nd(ns, m, n, o);\n",
+            &options,
+        );
+        for helper in [
+            "__createBinding",
+            "__setModuleDefault",
+            "__importStar",
+            "__importDefault",
+        ] {
+            assert!(emitted.contains(&format!("var {helper} = ")), "{helper}");
+        }
+        assert!(emitted.ends_with(
+            &("Object.defineProperty(exports, \"__esModule\", { value: true });\n".to_string()
+                + "exports.e = void 0;\n"
+                + "var d_1 = __importDefault(require(\"./d\"));\n"
+                + "var ns = __importStar(require(\"./ns\"));\n"
+                + "var m_1 = __importStar(require(\"./m\"));\n"
+                + "var o_1 = require(\"./o\");\n"
+                + "var lazy = Promise.resolve().then(function () { return __importStar(require(\"./lazy\")); });\n"
+                + "var e_1 = require(\"./e\");\n"
+                + "Object.defineProperty(exports, \"e\", { enumerable: true, get: function () { return __importDefault(e_1).default; } });\n"
+                + "(0, d_1.default)(ns, m_1.default, m_1.n, o_1.o);\n")
+        ));
+    }
+
+    #[test]
+    fn imports_helpers_from_tslib() {
+        let text = "import d from \"./d\";\nexport class A extends d {}\n";
+        let options = TransformOptions {
+            target: ScriptTarget::ES5,
+            module: ModuleKind::CommonJS,
+            es_module_interop: true,
+            import_helpers: true,
+            ..TransformOptions::default()
+        };
+        let emitted = emit_module("test.ts", text, &options);
+        assert!(!emitted.contains("var __extends"));
+        assert!(emitted.contains(
+            "var tslib_1 = require(\"tslib\");\nvar d_1 = tslib_1.__importDefault(require(\"./d\"));\n"
+        ));
+        assert!(emitted.contains("    tslib_1.__extends(A, _super);\n"));
+
+        let options = TransformOptions {
+            module: ModuleKind::ESNext,
+            ..options
+        };
+        assert!(
+            emit_module("test.ts", text, &options)
+                .starts_with("import { __extends } from \"tslib\";\nimport d from \"./d\";\n")
+        );
+        // Scripts cannot import, so they still declare their helpers
+        let script = emit_module("test.ts", "class B extends A {}\n", &options);
+        assert!(script.starts_with("var __extends = "));
+
+        let options = TransformOptions {
+            no_emit_helpers: true,
+            ..options
+        };
+        let script = emit_module("test.ts", "class B extends A {}\n", &options);
+        assert!(script.starts_with("var B = "));
+    }
+
+    #[test]
+    fn emits_amd_and_umd_modules() {
+        let text = "import { a } from \"./a\";\nimport \"./side\";\nexport const b = a + 1;\n";
+        assert_eq!(
+            emit_module("test.ts", text, &module_options(ModuleKind::AMD)),
+            "define([\"require\", \"exports\", \"./a\", \"./side\"], function (require, exports, a_1) {\n    \"use strict\";\n    Object.defineProperty(exports, \"__esModule\", { value: true });\n    exports.b = void 0;\n    exports.b = a_1.a + 1;\n});\n"
+        );
+        assert_eq!(
+            emit_module("test.ts", text, &module_options(ModuleKind::UMD)),
+            "(function (factory) {\n    if (typeof module === \"object\" && typeof module.exports === \"object\") {\n        var v = factory(require, exports);\n        if (v !== undefined)\n            module.exports = v;\n    }\n    else if (typeof define === \"function\" && define.amd) {\n        define([\"require\", \"exports\", \"./a\", \"./side\"], factory);\n    }\n})(function (require, exports) {\n    \"use strict\";\n    Object.defineProperty(exports, \"__esModule\", { value: true });\n    exports.b = void 0;\n    const a_1 = require(\"./a\");\n    require(\"./side\");\n    exports.b = a_1.a + 1;\n});\n"
+        );
+        assert_eq!(
+            emit_module(
+                "test.ts",
+                "import x = require(\"./x\");\nexport = x;\n",
+                &module_options(ModuleKind::AMD)
+            ),
+            "define([\"require\", \"exports\", \"./x\"], function (require, exports, x) {\n    \"use strict\";\n    return x;\n});\n"
+        );
+    }
+
+    #[test]
+    fn emits_system_modules() {
+        assert_eq!(
+            emit_module(
+                "test.ts",
+                "import { a } from \"./a\";\nexport { c } from \"./c\";\nexport let x = a;\nexport function f() { x++; }\nexport class K { }\nconsole.log(import.meta.url, import(\"./lazy\"));\n",
+                &module_options(ModuleKind::System),
+            ),
+            "System.register([\"./a\", \"./c\"], function (exports_1, context_1) {\n    \"use strict\";\n    var a_1, x, K;\n    var __moduleName = context_1 && context_1.id;\n    function f() { exports_1(\"x\", ++x); }\n    exports_1(\"f\", f);\n    return {\n        setters: [\n            function (a_1_1) {\n                a_1 = a_1_1;\n            },\n            function (_1) {\n                exports_1({\n                    \"c\": _1[\"c\"]\n                });\n            }\n        ],\n        execute: function () {\n            exports_1(\"x\", x = a_1.a);\n            K = class K {\n            };\n            exports_1(\"K\", K);\n            console.log(context_1.meta.url, context_1.import(\"./lazy\"));\n        }\n    };\n});\n"
+        );
+        let text = emit_module(
+            "test.ts",
+            "export * from \"./m\";\nexport const a = 1;\nexport default a;\n",
+            &module_options(ModuleKind::System),
+        );
+        assert!(text.contains(
+            "    var exportedNames_1 = {\n        \"default\": true,\n        \"a\": true\n    };\n"
+        ));
+    }
+
+    #[test]
+    fn bundles_named_modules_with_one_copy_of_each_helper() {
+        let files: Vec<_> = [
+            ("base", "export class A { }\nexport class B extends A { }\n"),
+            (
+                "main",
+                "import { A } from \"./base\";\nexport class C extends A { }\n",
+            ),
+        ]
+        .into_iter()
+        .map(|(name, text)| {
+            let file = parse_source_file(
+                &format!("{name}.ts"),
+                text,
+                ScriptTarget::ESNext,
+                ScriptKind::Unknown,
+            );
+            let options = TransformOptions {
+                target: ScriptTarget::ES5,
+                module_name: Some(name.to_string()),
+                bundled_module_names: HashMap::from([("./base".to_string(), "base".to_string())]),
+                ..module_options(ModuleKind::AMD)
+            };
+            (file, options)
+        })
+        .collect();
+        let printed: Vec<String> = transform_bundle(&files)
+            .iter()
+            .map(|file| print_file(file, &PrinterOptions::default()))
+            .collect();
+        assert_eq!(printed[0].matches("var __extends").count(), 1);
+        assert!(printed[0].contains(
+            "define(\"base\", [\"require\", \"exports\"], function (require, exports) {"
+        ));
+        assert!(!printed[1].contains("var __extends"));
+        assert!(printed[1].contains("define(\"main\", [\"require\", \"exports\", \"base\"], function (require, exports, base_1) {"));
+    }
+
+    #[test]
+    fn keeps_es_modules_for_es_module_kinds() {
+        let text = "import { a } from \"./a\";\nexport const b = import.meta.url + a;\n";
+        assert_eq!(
+            emit_module("test.ts", text, &module_options(ModuleKind::ESNext)),
+            text
+        );
+        assert_eq!(
+            emit_module("test.mts", text, &module_options(ModuleKind::NodeNext)),
+            text
+        );
+        assert!(
+            emit_module("test.cts", text, &module_options(ModuleKind::NodeNext))
+                .ends_with("const a_1 = require(\"./a\");\nexports.b = import.meta.url + a_1.a;\n")
+        );
+        assert_eq!(
+            emit_module(
+                "test.ts",
+                "import x = require(\"./x\");\nexport = x;\n",
+                &module_options(ModuleKind::Preserve)
+            ),
+            "const x = require(\"./x\");\nmodule.exports = x;\n"
+        );
+    }
+
+    #[test]
+    fn emits_classic_jsx() {
+        assert_eq!(
+            emit_jsx(
+                "const a = <div className=\"x\" data-id='1' hidden>a &amp; b&#33;</div>;\nconst b = <Foo {...props} bar={1}>\n    line one\n    line two\n    <span>{value}</span>\n    {/* comment */}\n</Foo>;\nconst c = <><a.b x:y=\"1\" /></>;\n",
+                JsxEmit::React,
+                ScriptTarget::ES2020
+            ),
+            "const a = React.createElement(\"div\", { className: \"x\", \"data-id\": \"1\", hidden: true }, \"a & b!\");\nconst b = React.createElement(Foo, { ...props, bar: 1 }, \"line one line two\", React.createElement(\"span\", null, value));\nconst c = React.createElement(React.Fragment, null, React.createElement(a.b, { \"x:y\": \"1\" }));\n"
+        );
+        assert_eq!(
+            emit_jsx(
+                "const a = <A {...p} b=\"1\" {...{ c: 2 }} />;\nconst b = <div {...p} />;\n",
+                JsxEmit::React,
+                ScriptTarget::ES5
+            ),
+            concat!(
+                "var __assign = (this && this.__assign) || function () {\n",
+                "    __assign = Object.assign || function (t) {\n",
+                "        for (var s, i = 1, n = arguments.length; i < n; i++) {\n",
+                "            s = arguments[i];\n",
+                "            for (var p in s)\n",
+                "                if (Object.prototype.hasOwnProperty.call(s, p))\n",
+                "                    t[p] = s[p];\n",
+                "        }\n",
+                "        return t;\n",
+                "    };\n",
+                "    return __assign.apply(this, arguments);\n",
+                "};\n",
+                "var a = React.createElement(A, __assign({}, p, { b: \"1\", c: 2 }));\n",
+                "var b = React.createElement(\"div\", __assign({}, p));\n"
+            )
+        );
+    }
+
+    #[test]
+    fn emits_jsx_for_the_automatic_runtime() {
+        assert_eq!(
+            emit_jsx(
+                "\"use client\";\nconst a = <div key=\"k\" id=\"a\">{x}</div>;\nconst b = <><b />text{...rest}</>;\nconst c = <C {...p} key={k} />;\n",
+                JsxEmit::ReactJsx,
+                ScriptTarget::ES2020
+            ),
+            "\"use client\";\nimport { createElement as _createElement } from \"react\";\nimport { jsx as _jsx, Fragment as _Fragment, jsxs as _jsxs } from \"react/jsx-runtime\";\nconst a = _jsx(\"div\", { id: \"a\", children: x }, \"k\");\nconst b = _jsxs(_Fragment, { children: [_jsx(\"b\", {}), \"text\", ...rest] });\nconst c = _createElement(C, { ...p, key: k });\n"
+        );
+        assert_eq!(
+            emit_module(
+                "test.tsx",
+                "export const _jsx = 1;\nexport const a = <div>{_jsx}</div>;\n",
+                &TransformOptions {
+                    target: ScriptTarget::ES2015,
+                    module: ModuleKind::CommonJS,
+                    jsx: JsxEmit::ReactJsx,
+                    ..TransformOptions::default()
+                }
+            ),
+            "\"use strict\";\nObject.defineProperty(exports, \"__esModule\", { value: true });\nexports.a = exports._jsx = void 0;\nconst jsx_runtime_1 = require(\"react/jsx-runtime\");\nexports._jsx = 1;\nexports.a = (0, jsx_runtime_1.jsx)(\"div\", { children: exports._jsx });\n"
+        );
+    }
+
+    #[test]
+    fn emits_jsx_dev_source_locations() {
+        assert_eq!(
+            emit_jsx(
+                "const a = <div>\n    <A key=\"1\" />\n</div>;\n",
+                JsxEmit::ReactJsxDev,
+                ScriptTarget::ES2020
+            ),
+            "import { jsxDEV as _jsxDEV } from \"react/jsx-dev-runtime\";\nconst _jsxFileName = \"/src/test.tsx\";\nconst a = _jsxDEV(\"div\", { children: _jsxDEV(A, {}, \"1\", false, { fileName: _jsxFileName, lineNumber: 2, columnNumber: 5 }, this) }, void 0, false, { fileName: _jsxFileName, lineNumber: 1, columnNumber: 10 }, this);\n"
+        );
+    }
+
+    #[test]
+    fn preserves_jsx_unless_it_is_transformed() {
+        let text = "const a = <div id=\"a\">{x}</div>;\n";
+        for jsx in [JsxEmit::None, JsxEmit::Preserve, JsxEmit::ReactNative] {
+            assert_eq!(emit_jsx(text, jsx, ScriptTarget::ES2020), text);
+        }
+        // The whitespace around text, and what looks like comments in it, are kept
+        let text = "const a = <a> x </a>;\nconst b = <b>\n  // y\n  {z} /* w */\n</b>;\n";
+        assert_eq!(
+            emit_jsx(text, JsxEmit::Preserve, ScriptTarget::ES2020),
+            text
+        );
+    }
+}
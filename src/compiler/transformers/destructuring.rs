@@ -0,0 +1,592 @@
+//! Flattening destructuring patterns into plain declarations and assignments
+//!
+//! ES5 has no destructuring, and before ES2018 an object pattern cannot have a rest element.
+//! A pattern is flattened into one declaration or assignment for each name it binds, reading
+//! the value of the name from the value being destructured: `var { a, b: [c] } = o` becomes
+//! `var a = o.a, _a = o.b, c = _a[0]`. A value that is read more than once and is not a plain
+//! name is stored in a temporary variable first. Default values are applied with
+//! `value === void 0 ? default : value`, an array rest element takes a `slice` of the array,
+//! and an object rest element copies the object without the properties before it through the
+//! `__rest` helper.
+//!
+//! When only object rest elements have to be lowered, the parts of a pattern without one are
+//! kept as they are: `const { a, ...r } = o` becomes `const { a } = o, r = __rest(o, ["a"])`.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    ArrayBindingPattern, BindingElement, ConditionalExpression, ObjectBindingPattern,
+    SpreadElement, TypeOfExpression,
+};
+use crate::compiler::ast::visitor::NodeVisitor;
+use crate::compiler::ast::{Node, SyntaxKind};
+
+use super::TransformContext;
+use super::helpers::REST_HELPER;
+
+/// Which destructuring a transformer lowers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FlattenLevel {
+    /// Every pattern, for ES5
+    All,
+    /// Only the object patterns that have a rest element, for ES2015 to ES2017
+    ObjectRest,
+}
+
+/// A transformer that flattens destructuring, which visits the parts of a pattern that it
+/// keeps, and declares the temporary variables that flattening needs
+pub(super) trait DestructuringHost: NodeVisitor {
+    fn context(&mut self) -> &mut TransformContext;
+
+    /// Generates a name for a variable local to the current function
+    fn create_temp_name(&mut self) -> String;
+
+    /// Declares a temporary variable at the top of the current function
+    fn hoist_variable(&mut self, name: &str);
+}
+
+/// Flattens a variable declaration or parameter whose name is a pattern, returning the
+/// variable declarations of the names it binds
+///
+/// `value` is the visited value being destructured, and `initializer` the default value of a
+/// parameter, which is used when `value` is `undefined`.
+pub(super) fn flatten_destructuring_binding(
+    host: &mut dyn DestructuringHost,
+    name: &Arc<Node>,
+    value: Arc<Node>,
+    initializer: Option<&Arc<Node>>,
+    level: FlattenLevel,
+) -> Vec<Arc<Node>> {
+    let mut flattener = Flattener::new(host, level, true);
+    flattener.flatten_element(name, value, initializer);
+    let factory = flattener.factory;
+    flattener
+        .output
+        .into_iter()
+        .map(|(target, value)| factory.create_variable_declaration(target, Some(value)))
+        .collect()
+}
+
+/// Flattens the assignment of the visited `value` to the pattern `target`, returning an
+/// expression that evaluates to `value` if `needs_value` is set
+pub(super) fn flatten_destructuring_assignment(
+    host: &mut dyn DestructuringHost,
+    target: &Arc<Node>,
+    value: Arc<Node>,
+    needs_value: bool,
+    level: FlattenLevel,
+) -> Arc<Node> {
+    let mut flattener = Flattener::new(host, level, false);
+    let value = if needs_value {
+        flattener.ensure_identifier(value, true)
+    } else {
+        value
+    };
+    flattener.flatten_element(target, flattener.copy(&value), None);
+    let factory = flattener.factory;
+    let mut expressions: Vec<Arc<Node>> = flattener
+        .output
+        .into_iter()
+        .map(|(target, value)| factory.create_assignment(target, value))
+        .collect();
+    if needs_value || expressions.is_empty() {
+        expressions.push(value);
+    }
+    let mut expressions = expressions.into_iter();
+    let first = expressions.next().expect("there is at least the value");
+    expressions.fold(first, |left, right| {
+        factory.create_binary(left, SyntaxKind::CommaToken, right)
+    })
+}
+
+/// Determines if a binding or assignment pattern has an object rest element anywhere in it
+pub(super) fn contains_object_rest(target: &Arc<Node>) -> bool {
+    if !is_object_pattern(target) && !is_array_pattern(target) {
+        return false;
+    }
+    let is_object = is_object_pattern(target);
+    pattern_elements(target)
+        .iter()
+        .flatten()
+        .any(|element| is_object && element.is_rest || contains_object_rest(&element.target))
+}
+
+/// Determines if a node is the target of a destructuring assignment, an object or array
+/// literal on the left of `=`
+pub(super) fn is_destructuring_assignment(node: &Node) -> bool {
+    node.kind == SyntaxKind::BinaryExpression && {
+        let data = node.as_binary_expression();
+        data.operator_token.kind == SyntaxKind::EqualsToken
+            && matches!(
+                data.left.kind,
+                SyntaxKind::ObjectLiteralExpression | SyntaxKind::ArrayLiteralExpression
+            )
+    }
+}
+
+/// An element of a binding or assignment pattern
+struct PatternElement {
+    /// The property the element reads, in an object pattern
+    property_name: Option<Arc<Node>>,
+    /// The name or nested pattern the element binds, or the expression it assigns to
+    target: Arc<Node>,
+    /// The default value of the element
+    initializer: Option<Arc<Node>>,
+    is_rest: bool,
+    /// The element itself, which is kept as it is when a pattern is only partly flattened
+    node: Arc<Node>,
+}
+
+/// Gets the elements of a pattern, with None for the holes of an array pattern
+fn pattern_elements(pattern: &Arc<Node>) -> Vec<Option<PatternElement>> {
+    use SyntaxKind as K;
+    let elements = match pattern.kind {
+        K::ObjectBindingPattern => &pattern.as_object_binding_pattern().elements,
+        K::ArrayBindingPattern => &pattern.as_array_binding_pattern().elements,
+        K::ObjectLiteralExpression => &pattern.as_object_literal_expression().properties,
+        _ => &pattern.as_array_literal_expression().elements,
+    };
+    elements
+        .iter()
+        .filter_map(|node| {
+            let element =
+                |property_name, target: &Arc<Node>, initializer, is_rest| PatternElement {
+                    property_name,
+                    target: Arc::clone(target),
+                    initializer,
+                    is_rest,
+                    node: Arc::clone(node),
+                };
+            let element = match node.kind {
+                K::OmittedExpression => return Some(None),
+                K::BindingElement => {
+                    let data = node.as_binding_element();
+                    let property_name = match pattern.kind {
+                        K::ObjectBindingPattern => data
+                            .property_name
+                            .clone()
+                            .or_else(|| Some(Arc::clone(&data.name))),
+                        _ => None,
+                    };
+                    element(
+                        property_name,
+                        &data.name,
+                        data.initializer.clone(),
+                        data.dot_dot_dot_token.is_some(),
+                    )
+                }
+                K::PropertyAssignment => {
+                    let data = node.as_property_assignment();
+                    let (target, initializer) = split_default(&data.initializer);
+                    element(Some(Arc::clone(&data.name)), target, initializer, false)
+                }
+                K::ShorthandPropertyAssignment => {
+                    let data = node.as_shorthand_property_assignment();
+                    element(
+                        Some(Arc::clone(&data.name)),
+                        &data.name,
+                        data.object_assignment_initializer.clone(),
+                        false,
+                    )
+                }
+                K::SpreadAssignment => {
+                    element(None, &node.as_spread_assignment().expression, None, true)
+                }
+                K::SpreadElement => element(None, &node.as_spread_element().expression, None, true),
+                _ if pattern.kind == K::ArrayLiteralExpression => {
+                    let (target, initializer) = split_default(node);
+                    element(None, target, initializer, false)
+                }
+                // Methods and accessors cannot be assigned to
+                _ => return None,
+            };
+            Some(Some(element))
+        })
+        .collect()
+}
+
+/// Splits `target = default` in an assignment pattern into its target and default value
+fn split_default(node: &Arc<Node>) -> (&Arc<Node>, Option<Arc<Node>>) {
+    if node.kind == SyntaxKind::BinaryExpression {
+        let data = node.as_binary_expression();
+        if data.operator_token.kind == SyntaxKind::EqualsToken {
+            return (&data.left, Some(Arc::clone(&data.right)));
+        }
+    }
+    (node, None)
+}
+
+fn is_object_pattern(node: &Node) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::ObjectBindingPattern | SyntaxKind::ObjectLiteralExpression
+    )
+}
+
+fn is_array_pattern(node: &Node) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::ArrayBindingPattern | SyntaxKind::ArrayLiteralExpression
+    )
+}
+
+struct Flattener<'a> {
+    host: &'a mut dyn DestructuringHost,
+    factory: NodeFactory,
+    level: FlattenLevel,
+    /// Whether the pattern declares variables, rather than being the target of an assignment
+    is_binding: bool,
+    /// The targets and values of the declarations or assignments made so far
+    output: Vec<(Arc<Node>, Arc<Node>)>,
+}
+
+impl<'a> Flattener<'a> {
+    fn new(host: &'a mut dyn DestructuringHost, level: FlattenLevel, is_binding: bool) -> Self {
+        Flattener {
+            host,
+            factory: NodeFactory::new(),
+            level,
+            is_binding,
+            output: Vec::new(),
+        }
+    }
+
+    /// Gets another reference to a value, which is a plain name if it is read more than once
+    fn copy(&self, value: &Arc<Node>) -> Arc<Node> {
+        if value.kind == SyntaxKind::Identifier {
+            self.factory.create_identifier(value.text())
+        } else {
+            Arc::clone(value)
+        }
+    }
+
+    /// Stores a value in a temporary variable, unless it is a name and `reuse_identifier` is
+    /// set, returning the name that holds it
+    fn ensure_identifier(&mut self, value: Arc<Node>, reuse_identifier: bool) -> Arc<Node> {
+        if reuse_identifier && value.kind == SyntaxKind::Identifier {
+            return value;
+        }
+        let name = self.create_temp();
+        self.output
+            .push((self.factory.create_identifier(name.as_str()), value));
+        self.factory.create_identifier(name)
+    }
+
+    /// Creates a temporary variable, which an assignment declares at the top of the function
+    /// and a binding declares along with the names it binds
+    fn create_temp(&mut self) -> String {
+        let name = self.host.create_temp_name();
+        if !self.is_binding {
+            self.host.hoist_variable(&name);
+        }
+        name
+    }
+
+    fn flatten_element(
+        &mut self,
+        target: &Arc<Node>,
+        value: Arc<Node>,
+        initializer: Option<&Arc<Node>>,
+    ) {
+        let value = match initializer {
+            Some(initializer) => self.create_default_value_check(value, initializer),
+            None => value,
+        };
+        if is_object_pattern(target) {
+            self.flatten_object_pattern(target, value);
+        } else if is_array_pattern(target) {
+            self.flatten_array_pattern(target, value);
+        } else {
+            let target = self.host.visit_node(target);
+            self.output.push((target, value));
+        }
+    }
+
+    /// Creates `value === void 0 ? initializer : value`
+    fn create_default_value_check(
+        &mut self,
+        value: Arc<Node>,
+        initializer: &Arc<Node>,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        let value = self.ensure_identifier(value, true);
+        let initializer = self.host.visit_node(initializer);
+        factory.create(
+            SyntaxKind::ConditionalExpression,
+            ConditionalExpression {
+                condition: factory.create_binary(
+                    self.copy(&value),
+                    SyntaxKind::EqualsEqualsEqualsToken,
+                    factory.create_void_zero(),
+                ),
+                question_token: factory.create_token(SyntaxKind::QuestionToken),
+                when_true: initializer,
+                colon_token: factory.create_token(SyntaxKind::ColonToken),
+                when_false: value,
+            },
+        )
+    }
+
+    fn flatten_object_pattern(&mut self, pattern: &Arc<Node>, value: Arc<Node>) {
+        let elements: Vec<PatternElement> =
+            pattern_elements(pattern).into_iter().flatten().collect();
+        let mut value = value;
+        if elements.len() != 1 {
+            value = self.ensure_identifier(value, !elements.is_empty());
+        }
+        let has_rest = elements.last().is_some_and(|element| element.is_rest);
+        // The elements kept as a pattern, when only object rest elements are lowered
+        let mut kept = Vec::new();
+        // The properties read before a rest element, which it leaves out
+        let mut keys = Vec::new();
+        for element in &elements {
+            if element.is_rest {
+                self.flush_object_pattern(pattern, &mut kept, &value);
+                self.host.context().request_helper(&REST_HELPER);
+                let rest = self.factory.create_call(
+                    self.factory.create_identifier("__rest"),
+                    vec![
+                        self.copy(&value),
+                        self.factory
+                            .create_array_literal(std::mem::take(&mut keys), false),
+                    ],
+                );
+                self.flatten_element(&element.target, rest, None);
+                continue;
+            }
+            let property_name = element
+                .property_name
+                .as_ref()
+                .expect("the elements of object patterns read properties");
+            if self.level == FlattenLevel::ObjectRest
+                && !contains_object_rest(&element.target)
+                && property_name.kind != SyntaxKind::ComputedPropertyName
+            {
+                if has_rest {
+                    keys.push(self.create_property_key(property_name));
+                }
+                let visited = self.host.visit_node(&element.node);
+                kept.push(visited);
+                continue;
+            }
+            self.flush_object_pattern(pattern, &mut kept, &value);
+            let access = self.create_property_access(&value, property_name, has_rest, &mut keys);
+            self.flatten_element(&element.target, access, element.initializer.as_ref());
+        }
+        self.flush_object_pattern(pattern, &mut kept, &value);
+    }
+
+    /// Destructures `value` with the elements kept as a pattern so far
+    fn flush_object_pattern(
+        &mut self,
+        pattern: &Arc<Node>,
+        kept: &mut Vec<Arc<Node>>,
+        value: &Arc<Node>,
+    ) {
+        if kept.is_empty() {
+            return;
+        }
+        let elements = self.factory.create_node_list(std::mem::take(kept));
+        let pattern = if pattern.kind == SyntaxKind::ObjectBindingPattern {
+            self.factory.create(
+                SyntaxKind::ObjectBindingPattern,
+                ObjectBindingPattern { elements },
+            )
+        } else {
+            self.factory.create_object_literal(elements.nodes, false)
+        };
+        self.output.push((pattern, self.copy(value)));
+    }
+
+    /// Reads the property `name` of `value`, adding the key it reads to `keys` if a rest
+    /// element follows, which needs a computed key to be evaluated only once
+    fn create_property_access(
+        &mut self,
+        value: &Arc<Node>,
+        name: &Arc<Node>,
+        has_rest: bool,
+        keys: &mut Vec<Arc<Node>>,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        let object = self.copy(value);
+        match name.kind {
+            SyntaxKind::ComputedPropertyName => {
+                let mut key = self
+                    .host
+                    .visit_node(&name.as_computed_property_name().expression);
+                if has_rest {
+                    if !matches!(
+                        key.kind,
+                        SyntaxKind::Identifier
+                            | SyntaxKind::StringLiteral
+                            | SyntaxKind::NumericLiteral
+                    ) {
+                        key = self.ensure_identifier(key, false);
+                    }
+                    keys.push(self.create_computed_key(&key));
+                }
+                let argument = self.copy(&key);
+                factory.create_element_access(object, argument)
+            }
+            SyntaxKind::Identifier | SyntaxKind::PrivateIdentifier => {
+                if has_rest {
+                    keys.push(self.create_property_key(name));
+                }
+                factory.create_property_access(object, name.text())
+            }
+            _ => {
+                if has_rest {
+                    keys.push(self.create_property_key(name));
+                }
+                let argument = if name.kind == SyntaxKind::NumericLiteral {
+                    factory.create_numeric_literal(name.text())
+                } else {
+                    factory.create_string_literal(name.text())
+                };
+                factory.create_element_access(object, argument)
+            }
+        }
+    }
+
+    /// Creates the string that `__rest` leaves out for a property that is not computed
+    fn create_property_key(&self, name: &Arc<Node>) -> Arc<Node> {
+        self.factory.create_string_literal(name.text())
+    }
+
+    /// Creates `typeof key === "symbol" ? key : key + ""`, which `__rest` leaves out for a
+    /// computed property
+    fn create_computed_key(&self, key: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        if key.kind != SyntaxKind::Identifier {
+            return self.copy(key);
+        }
+        factory.create(
+            SyntaxKind::ConditionalExpression,
+            ConditionalExpression {
+                condition: factory.create_binary(
+                    factory.create(
+                        SyntaxKind::TypeOfExpression,
+                        TypeOfExpression {
+                            expression: self.copy(key),
+                        },
+                    ),
+                    SyntaxKind::EqualsEqualsEqualsToken,
+                    factory.create_string_literal("symbol"),
+                ),
+                question_token: factory.create_token(SyntaxKind::QuestionToken),
+                when_true: self.copy(key),
+                colon_token: factory.create_token(SyntaxKind::ColonToken),
+                when_false: factory.create_binary(
+                    self.copy(key),
+                    SyntaxKind::PlusToken,
+                    factory.create_string_literal(""),
+                ),
+            },
+        )
+    }
+
+    fn flatten_array_pattern(&mut self, pattern: &Arc<Node>, value: Arc<Node>) {
+        let elements = pattern_elements(pattern);
+        if self.level == FlattenLevel::ObjectRest {
+            self.flatten_array_pattern_with_object_rest(pattern, elements, value);
+            return;
+        }
+        let factory = self.factory;
+        let mut value = value;
+        if elements.len() != 1 {
+            value = self.ensure_identifier(value, !elements.is_empty());
+        }
+        for (index, element) in elements.iter().enumerate() {
+            let Some(element) = element else {
+                continue;
+            };
+            let index = factory.create_numeric_literal(index.to_string());
+            let access = if element.is_rest {
+                factory.create_call(
+                    factory.create_property_access(self.copy(&value), "slice"),
+                    vec![index],
+                )
+            } else {
+                factory.create_element_access(self.copy(&value), index)
+            };
+            self.flatten_element(&element.target, access, element.initializer.as_ref());
+        }
+    }
+
+    /// Keeps an array pattern, binding each of its elements that has an object rest element
+    /// to a temporary variable, and then flattens those elements from their variables
+    fn flatten_array_pattern_with_object_rest(
+        &mut self,
+        pattern: &Arc<Node>,
+        elements: Vec<Option<PatternElement>>,
+        value: Arc<Node>,
+    ) {
+        let factory = self.factory;
+        let mut kept = Vec::new();
+        let mut deferred = Vec::new();
+        for (node, element) in pattern_nodes(pattern).iter().zip(elements) {
+            let Some(element) = element else {
+                kept.push(Arc::clone(node));
+                continue;
+            };
+            if !contains_object_rest(&element.target) {
+                kept.push(self.host.visit_node(node));
+                continue;
+            }
+            let temp = self.create_temp();
+            let name = factory.create_identifier(temp.as_str());
+            let initializer = element
+                .initializer
+                .as_ref()
+                .map(|initializer| self.host.visit_node(initializer));
+            kept.push(if self.is_binding {
+                factory.create(
+                    SyntaxKind::BindingElement,
+                    BindingElement {
+                        dot_dot_dot_token: element
+                            .is_rest
+                            .then(|| factory.create_token(SyntaxKind::DotDotDotToken)),
+                        property_name: None,
+                        name,
+                        initializer,
+                    },
+                )
+            } else if element.is_rest {
+                factory.create(
+                    SyntaxKind::SpreadElement,
+                    SpreadElement { expression: name },
+                )
+            } else {
+                match initializer {
+                    Some(initializer) => factory.create_assignment(name, initializer),
+                    None => name,
+                }
+            });
+            deferred.push((element.target, temp));
+        }
+        let pattern = if self.is_binding {
+            factory.create(
+                SyntaxKind::ArrayBindingPattern,
+                ArrayBindingPattern {
+                    elements: factory.create_node_list(kept),
+                },
+            )
+        } else {
+            factory.create_array_literal(kept, false)
+        };
+        self.output.push((pattern, value));
+        for (target, temp) in deferred {
+            self.flatten_element(&target, factory.create_identifier(temp), None);
+        }
+    }
+}
+
+/// Gets the nodes of the elements of an array pattern, holes included
+fn pattern_nodes(pattern: &Arc<Node>) -> &[Arc<Node>] {
+    if pattern.kind == SyntaxKind::ArrayBindingPattern {
+        &pattern.as_array_binding_pattern().elements.nodes
+    } else {
+        &pattern.as_array_literal_expression().elements.nodes
+    }
+}
@@ -0,0 +1,1978 @@
+//! Lowering ES2015 syntax to ES5
+//!
+//! Classes become constructor functions with their members assigned to the prototype, and
+//! arrow functions become function expressions that reach the `this` and `arguments` of the
+//! function around them through variables. `let` and `const` become `var`, renaming a
+//! declaration whose name would clash with another once it is hoisted to the function.
+//! Template literals become calls to `concat`, and tagged templates pass their strings through
+//! the `__makeTemplateObject` helper. Spread elements and arguments go through the
+//! `__spreadArray` helper and `Function.prototype.apply`, rest parameters are copied from
+//! `arguments`, default parameter values are assigned in the body, shorthand properties and
+//! methods of object literals are written out in full, and `for...of` loops become loops over
+//! array indexes, or with `downlevelIteration` loops that call `next` on the iterator the
+//! `__values` helper gets.
+//!
+//! Destructuring patterns in declarations, parameters, and assignments are flattened into one
+//! declaration or assignment per name, and an object literal with a computed property name
+//! assigns the properties from that one on to the object in turn. A loop whose closures
+//! capture its block-scoped variables has its body turned into a function called on each
+//! iteration. Generators are left for the generators transformer, which runs after this one.
+
+mod classes;
+mod loops;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    CatchClause, ElementAccessExpression, ForStatement, FunctionExpression, Identifier,
+    IfStatement, PostfixUnaryExpression, PrefixUnaryExpression, PropertyAccessExpression,
+    PropertyAssignment, ThrowStatement, TryStatement, VariableDeclarationList,
+};
+use crate::compiler::ast::utilities::{is_binding_pattern, skip_parentheses};
+use crate::compiler::ast::visitor::{NodeVisitor, visit_each_child, visit_nodes};
+use crate::compiler::ast::{Node, NodeFlags, NodeList, SyntaxKind};
+
+use self::loops::{LoopBody, LoopConversion, to_single_statement};
+use super::destructuring::{
+    DestructuringHost, FlattenLevel, flatten_destructuring_assignment,
+    flatten_destructuring_binding, is_destructuring_assignment,
+};
+use super::helpers::{MAKE_TEMPLATE_OBJECT_HELPER, SPREAD_ARRAY_HELPER, VALUES_HELPER};
+use super::{
+    TransformContext, block_scoped_binding_names, collect_binding_names,
+    collect_block_scoped_names, collect_function_scoped_names, is_let_or_const,
+    is_prologue_directive, prepend_to_block, update_statements,
+};
+
+pub(super) fn transform_es2015(
+    source_file: &Arc<Node>,
+    context: &mut TransformContext,
+) -> Arc<Node> {
+    let this_name = context.reserve_name("_this");
+    let arguments_name = context.reserve_name("_arguments");
+    let super_name = context.reserve_name("_super");
+    let mut transformer = Es2015Transformer {
+        factory: NodeFactory::new(),
+        context,
+        scopes: Vec::new(),
+        functions: Vec::new(),
+        this_name,
+        arguments_name,
+        super_name,
+        loop_conversions: Vec::new(),
+        loop_declarations: Vec::new(),
+    };
+
+    let statements = &source_file.as_source_file().statements;
+    let mut names = Vec::new();
+    collect_function_scoped_names(statements, &mut names);
+    transformer.enter_function(FunctionKind::Plain, names);
+    let visited = transformer.visit_function_statements(statements);
+    let state = transformer.exit_function();
+    let statements = transformer.add_function_prologue(visited, &state, Vec::new());
+    update_statements(&transformer.factory, source_file, statements)
+}
+
+struct Es2015Transformer<'a> {
+    factory: NodeFactory,
+    context: &'a mut TransformContext,
+    /// The scopes around the node being visited, innermost last, each mapping the names
+    /// declared in it to the names they are emitted as
+    scopes: Vec<HashMap<String, String>>,
+    /// The functions around the node being visited, innermost last, starting with the source
+    /// file
+    functions: Vec<FunctionState>,
+    /// The variable that holds `this` for the arrow functions within a function, and the
+    /// object being constructed in the constructor of a derived class
+    this_name: String,
+    /// The variable that holds `arguments` for the arrow functions within a function
+    arguments_name: String,
+    /// The parameter that holds the base class within a lowered class
+    super_name: String,
+    /// The loops whose bodies become functions once the visitor reaches them
+    loop_conversions: Vec<LoopConversion>,
+    /// The declarations of the functions that converted loop bodies became, which go before
+    /// the loop
+    loop_declarations: Vec<Arc<Node>>,
+}
+
+/// What kind of function a [`FunctionState`] is for, which decides what `this` and `super`
+/// refer to within it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FunctionKind {
+    /// A source file, or a function that is none of the kinds below
+    Plain,
+    /// An arrow function, which shares `this` and `arguments` with the function around it
+    Arrow,
+    /// A method, accessor, or static block of a class, or the constructor of a class without a
+    /// base class, where `super` refers to the base class's prototype, or for static members
+    /// to the base class itself
+    Method { is_static: bool },
+    /// The constructor of a derived class, where `this` is the object the base class's
+    /// constructor returned
+    DerivedConstructor,
+}
+
+struct FunctionState {
+    kind: FunctionKind,
+    /// Whether an arrow function within this function refers to `this`
+    captures_this: bool,
+    /// Whether an arrow function within this function refers to `arguments`
+    captures_arguments: bool,
+    /// Temporary variables to declare at the top of the function
+    temps: Vec<String>,
+    /// The generated names used within the function, which other generated names in it must
+    /// not repeat
+    generated_names: HashSet<String>,
+    /// How many loops the node being visited is nested in, within this function
+    loop_depth: usize,
+    /// Whether a derived class's constructor has declared the variable for `this` with its
+    /// `super` call
+    this_declared: bool,
+    /// The name that `this` becomes within the function, for the initializers of static
+    /// properties, where `this` is the class
+    this_replacement: Option<String>,
+    /// The state of the loop body the function was made from, for the body of a loop whose
+    /// closures capture its block-scoped variables
+    loop_body: Option<LoopBody>,
+}
+
+impl NodeVisitor for Es2015Transformer<'_> {
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        if let Some(body) = self.visit_loop_body(node) {
+            return body;
+        }
+        if let Some(statements) = self.visit_converted_loop(node) {
+            return factory.create_block(statements, true);
+        }
+        match node.kind {
+            K::Identifier => self.visit_identifier(node),
+            K::ThisKeyword => self.visit_this(node),
+            K::FunctionDeclaration | K::FunctionExpression => self.visit_function(node),
+            K::ArrowFunction => self.visit_arrow_function(node),
+            K::GetAccessor | K::SetAccessor => self.visit_object_literal_accessor(node),
+            K::ClassExpression => self.visit_class_expression(node),
+            K::Block => {
+                let mut names = Vec::new();
+                collect_block_scoped_names(&node.as_block().statements, &mut names);
+                self.visit_in_block_scope(node, names)
+            }
+            K::CaseBlock => {
+                let mut names = Vec::new();
+                for clause in node.as_case_block().clauses.iter() {
+                    if let Some(statements) = clause.statements() {
+                        collect_block_scoped_names(statements, &mut names);
+                    }
+                }
+                if let Some(loop_body) = &mut self.current_function().loop_body {
+                    loop_body.switch_depth += 1;
+                }
+                let visited = self.visit_in_block_scope(node, names);
+                if let Some(loop_body) = &mut self.current_function().loop_body {
+                    loop_body.switch_depth -= 1;
+                }
+                visited
+            }
+            K::CatchClause => {
+                let mut names = Vec::new();
+                if let Some(declaration) = &node.as_catch_clause().variable_declaration {
+                    collect_binding_names(&declaration.as_variable_declaration().name, &mut names);
+                }
+                self.scopes
+                    .push(names.into_iter().map(|name| (name.clone(), name)).collect());
+                let visited = self.visit_catch_clause(node);
+                self.scopes.pop();
+                visited
+            }
+            K::ExpressionStatement => {
+                let expression = &node.as_expression_statement().expression;
+                factory
+                    .update_expression_statement(node, self.visit_discarded_expression(expression))
+            }
+            K::BinaryExpression if is_destructuring_assignment(node) => {
+                let data = node.as_binary_expression();
+                let value = self.visit_node(&data.right);
+                let flattened = flatten_destructuring_assignment(
+                    self,
+                    &data.left,
+                    value,
+                    true,
+                    FlattenLevel::All,
+                );
+                factory.with_text_range(&flattened, node.loc)
+            }
+            K::VariableStatement => self.visit_variable_statement(node),
+            K::VariableDeclarationList if self.is_loop_body_variable_list(node) => {
+                // The head of a loop within a converted loop body assigns the variables
+                let visited = self.visit_variable_declaration_list(node);
+                self.hoist_loop_body_variables(&visited).unwrap_or_else(|| {
+                    let declaration = &visited.as_variable_declaration_list().declarations.nodes[0];
+                    factory.create_identifier(declaration.as_variable_declaration().name.text())
+                })
+            }
+            K::VariableDeclarationList => self.visit_variable_declaration_list(node),
+            K::ForStatement | K::ForInStatement => {
+                let initializer = match node.kind {
+                    K::ForStatement => node.as_for_statement().initializer.as_ref(),
+                    _ => Some(&node.as_for_in_statement().initializer),
+                };
+                let names = initializer
+                    .map(block_scoped_binding_names)
+                    .unwrap_or_default();
+                self.push_block_scope(names);
+                let visited = if node.kind == K::ForInStatement
+                    && is_destructuring_loop_initializer(&node.as_for_in_statement().initializer)
+                {
+                    self.visit_for_in_statement_with_pattern(node)
+                } else {
+                    self.visit_loop(node)
+                };
+                self.scopes.pop();
+                visited
+            }
+            K::ForOfStatement if node.as_for_of_statement().await_modifier.is_none() => {
+                self.visit_for_of_statement(node)
+            }
+            K::ForOfStatement | K::WhileStatement | K::DoStatement => self.visit_loop(node),
+            K::ReturnStatement => self.visit_return_statement(node),
+            K::NoSubstitutionTemplateLiteral => {
+                let text = &node.as_template_literal_like_node().text;
+                factory.with_text_range(&factory.create_string_literal(text.as_str()), node.loc)
+            }
+            K::TemplateExpression => self.visit_template_expression(node),
+            K::TaggedTemplateExpression => self.visit_tagged_template_expression(node),
+            K::ArrayLiteralExpression
+                if has_spread_element(&node.as_array_literal_expression().elements) =>
+            {
+                let data = node.as_array_literal_expression();
+                let array = self.transform_spread_elements(&data.elements, false, data.multi_line);
+                factory.with_text_range(&array, node.loc)
+            }
+            K::CallExpression => self.visit_call_expression(node),
+            K::NewExpression
+                if node
+                    .as_new_expression()
+                    .arguments
+                    .as_ref()
+                    .is_some_and(has_spread_element) =>
+            {
+                self.visit_new_expression_with_spread(node)
+            }
+            K::PropertyAccessExpression => {
+                let data = node.as_property_access_expression();
+                if data.expression.kind == K::SuperKeyword {
+                    return self.visit_super_property(node);
+                }
+                factory.update_property_access(
+                    node,
+                    self.visit_node(&data.expression),
+                    data.question_dot_token.clone(),
+                    Arc::clone(&data.name),
+                )
+            }
+            K::ElementAccessExpression
+                if node.as_element_access_expression().expression.kind == K::SuperKeyword =>
+            {
+                self.visit_super_property(node)
+            }
+            K::ObjectLiteralExpression => self.visit_object_literal(node),
+            K::PropertyAssignment => {
+                let data = node.as_property_assignment();
+                factory.update_property_assignment(
+                    node,
+                    data.modifiers.clone(),
+                    self.visit_property_name(&data.name),
+                    data.postfix_token.clone(),
+                    self.visit_node(&data.initializer),
+                )
+            }
+            K::BindingElement => {
+                let data = node.as_binding_element();
+                factory.update_binding_element(
+                    node,
+                    data.dot_dot_dot_token.clone(),
+                    data.property_name
+                        .as_ref()
+                        .map(|name| self.visit_property_name(name)),
+                    self.visit_node(&data.name),
+                    data.initializer
+                        .as_ref()
+                        .map(|initializer| self.visit_node(initializer)),
+                )
+            }
+            K::LabeledStatement => {
+                let data = node.as_labeled_statement();
+                let statement =
+                    self.visit_labeled_statement_in_loop_body(data.label.text(), &data.statement);
+                let label = |statement| {
+                    factory.update_labeled_statement(node, Arc::clone(&data.label), statement)
+                };
+                // A loop over an iterator is wrapped in a `try` statement, and its labels go on
+                // the loop within it so that `continue` can still refer to them
+                let mut labeled = &data.statement;
+                while labeled.kind == K::LabeledStatement {
+                    labeled = &labeled.as_labeled_statement().statement;
+                }
+                if self.context.downlevel_iteration && labeled.kind == K::ForOfStatement {
+                    let data = statement.as_try_statement();
+                    let try_block = data.try_block.as_block();
+                    let for_statement = label(Arc::clone(&try_block.statements.nodes[0]));
+                    return factory.update_try_statement(
+                        &statement,
+                        factory.update_block(
+                            &data.try_block,
+                            factory.update_node_list(&try_block.statements, vec![for_statement]),
+                            true,
+                        ),
+                        data.catch_clause.clone(),
+                        data.finally_block.clone(),
+                    );
+                }
+                label(statement)
+            }
+            K::JsxAttribute => {
+                let data = node.as_jsx_attribute();
+                factory.update_jsx_attribute(
+                    node,
+                    Arc::clone(&data.name),
+                    data.initializer
+                        .as_ref()
+                        .map(|initializer| self.visit_node(initializer)),
+                )
+            }
+            K::EnumDeclaration => {
+                let data = node.as_enum_declaration();
+                factory.update_enum_declaration(
+                    node,
+                    data.modifiers.clone(),
+                    Arc::clone(&data.name),
+                    visit_nodes(&data.members, self, &factory),
+                )
+            }
+            K::EnumMember => {
+                let data = node.as_enum_member();
+                factory.update_enum_member(
+                    node,
+                    Arc::clone(&data.name),
+                    data.initializer
+                        .as_ref()
+                        .map(|initializer| self.visit_node(initializer)),
+                )
+            }
+            K::ModuleDeclaration => {
+                let data = node.as_module_declaration();
+                factory.update_module_declaration(
+                    node,
+                    data.modifiers.clone(),
+                    Arc::clone(&data.name),
+                    data.body.as_ref().map(|body| self.visit_node(body)),
+                )
+            }
+            K::BreakStatement | K::ContinueStatement => match self.visit_loop_exit(node) {
+                Some(statements) => to_single_statement(&factory, statements),
+                None => Arc::clone(node),
+            },
+            // Meta properties and the names in imports and exports never refer to local
+            // variables
+            K::MetaProperty
+            | K::ImportDeclaration
+            | K::ImportEqualsDeclaration
+            | K::ExportDeclaration => Arc::clone(node),
+            _ => visit_each_child(node, self, &factory),
+        }
+    }
+
+    fn visit_list_element(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        match node.kind {
+            SyntaxKind::ClassDeclaration => return self.visit_class_declaration(node),
+            SyntaxKind::BreakStatement
+            | SyntaxKind::ContinueStatement
+            | SyntaxKind::ReturnStatement => {
+                if let Some(statements) = self.visit_loop_exit(node) {
+                    return statements;
+                }
+            }
+            SyntaxKind::VariableStatement => {
+                let visited = self.visit_node(node);
+                // A `var` statement of a converted loop body without initializers goes away
+                if visited.kind == SyntaxKind::EmptyStatement {
+                    return Vec::new();
+                }
+                return vec![visited];
+            }
+            _ => {
+                if let Some(statements) = self.visit_converted_loop(node) {
+                    return statements;
+                }
+            }
+        }
+        vec![self.visit_node(node)]
+    }
+}
+
+impl Es2015Transformer<'_> {
+    fn enter_function(&mut self, kind: FunctionKind, names: Vec<String>) {
+        self.scopes
+            .push(names.into_iter().map(|name| (name.clone(), name)).collect());
+        self.functions.push(FunctionState {
+            kind,
+            captures_this: false,
+            captures_arguments: false,
+            temps: Vec::new(),
+            generated_names: HashSet::new(),
+            loop_depth: 0,
+            this_declared: false,
+            this_replacement: None,
+            loop_body: None,
+        });
+    }
+
+    fn exit_function(&mut self) -> FunctionState {
+        self.scopes.pop();
+        self.functions
+            .pop()
+            .expect("functions are entered before they are exited")
+    }
+
+    fn current_function(&mut self) -> &mut FunctionState {
+        self.functions
+            .last_mut()
+            .expect("the source file is a function scope")
+    }
+
+    /// Gets the index of the innermost function that is not an arrow function, whose `this`,
+    /// `arguments`, and `super` the arrow functions within it share
+    fn this_function_index(&self) -> usize {
+        self.functions
+            .iter()
+            .rposition(|function| function.kind != FunctionKind::Arrow)
+            .expect("the source file is not an arrow function")
+    }
+
+    /// Pushes the scope of a block, renaming each declaration whose name is already declared
+    /// in a scope around it, since all of them become variables of the same function
+    fn push_block_scope(&mut self, names: Vec<String>) {
+        let mut scope = HashMap::new();
+        for name in names {
+            let emitted_name = if self.resolve(&name).is_some() {
+                self.context.create_unique_name(&name)
+            } else {
+                name.clone()
+            };
+            scope.insert(name, emitted_name);
+        }
+        self.scopes.push(scope);
+    }
+
+    fn visit_in_block_scope(&mut self, node: &Arc<Node>, names: Vec<String>) -> Arc<Node> {
+        self.push_block_scope(names);
+        let factory = self.factory;
+        let visited = visit_each_child(node, self, &factory);
+        self.scopes.pop();
+        visited
+    }
+
+    /// Gets the name a declaration visible at the current position is emitted as
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+    }
+
+    /// Generates a name for a variable local to the current function
+    fn create_generated_name(&mut self) -> String {
+        let state = self
+            .functions
+            .last_mut()
+            .expect("the source file is a function scope");
+        self.context.create_temp_name(&mut state.generated_names)
+    }
+
+    /// Creates a temporary variable that is declared at the top of the current function
+    fn create_temp_variable(&mut self) -> Arc<Node> {
+        let name = self.create_generated_name();
+        self.current_function().temps.push(name.clone());
+        self.factory.create_identifier(name)
+    }
+
+    /// Gets a name for a loop counter, which the loop declares itself
+    fn create_loop_variable_name(&mut self) -> String {
+        let state = self
+            .functions
+            .last_mut()
+            .expect("the source file is a function scope");
+        self.context
+            .create_loop_variable_name(&mut state.generated_names)
+    }
+
+    fn visit_identifier(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let text = node.text();
+        if let Some(name) = self.resolve(text) {
+            if name == text {
+                return Arc::clone(node);
+            }
+            return self.factory.update_identifier(node, name);
+        }
+        // An arrow function shares the `arguments` of the function around it
+        if text == "arguments" && self.current_function().kind == FunctionKind::Arrow {
+            let index = self.this_function_index();
+            self.functions[index].captures_arguments = true;
+            let name = self.arguments_name.clone();
+            return self.factory.update_identifier(node, name);
+        }
+        Arc::clone(node)
+    }
+
+    fn visit_this(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let index = self.this_function_index();
+        let is_current = index + 1 == self.functions.len();
+        let function = &mut self.functions[index];
+        if let Some(name) = &function.this_replacement {
+            let text = name.clone();
+            return self
+                .factory
+                .create_at(SyntaxKind::Identifier, node.loc, Identifier { text });
+        }
+        if function.kind != FunctionKind::DerivedConstructor {
+            if is_current {
+                return Arc::clone(node);
+            }
+            function.captures_this = true;
+        }
+        self.factory.create_at(
+            SyntaxKind::Identifier,
+            node.loc,
+            Identifier {
+                text: self.this_name.clone(),
+            },
+        )
+    }
+
+    /// Creates a reference to `this` at the current position
+    fn create_this(&mut self) -> Arc<Node> {
+        let this = self.factory.create_token(SyntaxKind::ThisKeyword);
+        self.visit_this(&this)
+    }
+
+    /// Visits the name of a property, which only refers to variables when it is computed
+    fn visit_property_name(&mut self, name: &Arc<Node>) -> Arc<Node> {
+        if name.kind == SyntaxKind::ComputedPropertyName {
+            let factory = self.factory;
+            return visit_each_child(name, self, &factory);
+        }
+        Arc::clone(name)
+    }
+
+    fn visit_function(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let Some(body) = node.body() else {
+            return Arc::clone(node);
+        };
+        let parameters = node.parameters().expect("functions have parameters");
+        if node.kind == SyntaxKind::FunctionDeclaration {
+            let data = node.as_function_declaration();
+            let (parameters, body) =
+                self.transform_function(parameters, body, FunctionKind::Plain, Vec::new());
+            return factory.update_function_declaration(
+                node,
+                data.modifiers.clone(),
+                data.asterisk_token.clone(),
+                data.name.clone(),
+                None,
+                parameters,
+                None,
+                Some(body),
+            );
+        }
+        let data = node.as_function_expression();
+        // A function expression's own name is visible only within it
+        let own_name = data
+            .name
+            .iter()
+            .map(|name| name.text().to_string())
+            .collect();
+        let (parameters, body) =
+            self.transform_function(parameters, body, FunctionKind::Plain, own_name);
+        factory.update_function_expression(
+            node,
+            data.modifiers.clone(),
+            data.asterisk_token.clone(),
+            data.name.clone(),
+            None,
+            parameters,
+            None,
+            body,
+        )
+    }
+
+    /// `(a) => a.b(this)` becomes `function (a) { return a.b(_this); }`, with `_this` declared
+    /// in the function around it
+    fn visit_arrow_function(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let data = node.as_arrow_function();
+        let (parameters, body) = self.transform_function(
+            &data.parameters,
+            &data.body,
+            FunctionKind::Arrow,
+            Vec::new(),
+        );
+        self.factory.create_at(
+            SyntaxKind::FunctionExpression,
+            node.loc,
+            FunctionExpression {
+                modifiers: data.modifiers.clone(),
+                asterisk_token: None,
+                name: None,
+                type_parameters: None,
+                parameters,
+                type_node: None,
+                body,
+            },
+        )
+    }
+
+    /// Visits an accessor of an object literal; the accessors of classes are lowered with
+    /// their class
+    fn visit_object_literal_accessor(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let Some(body) = node.body() else {
+            return Arc::clone(node);
+        };
+        let parameters = node.parameters().expect("accessors have parameters");
+        let name = self.visit_property_name(node.name().expect("accessors have names"));
+        let (parameters, body) =
+            self.transform_function(parameters, body, FunctionKind::Plain, Vec::new());
+        if node.kind == SyntaxKind::GetAccessor {
+            factory.update_get_accessor_declaration(
+                node,
+                node.as_get_accessor_declaration().modifiers.clone(),
+                name,
+                None,
+                parameters,
+                None,
+                Some(body),
+            )
+        } else {
+            factory.update_set_accessor_declaration(
+                node,
+                node.as_set_accessor_declaration().modifiers.clone(),
+                name,
+                None,
+                parameters,
+                None,
+                Some(body),
+            )
+        }
+    }
+
+    /// Visits the parameters and body of a function in a scope of its own, returning the
+    /// parameters that remain and a block body
+    ///
+    /// Default values and rest parameters become statements at the start of the body, along
+    /// with the variables that hold `this` and `arguments` for arrow functions within it and the
+    /// function's temporary variables.
+    fn transform_function(
+        &mut self,
+        parameters: &NodeList,
+        body: &Arc<Node>,
+        kind: FunctionKind,
+        mut names: Vec<String>,
+    ) -> (NodeList, Arc<Node>) {
+        let factory = self.factory;
+        for parameter in parameters.iter() {
+            collect_binding_names(&parameter.as_parameter().name, &mut names);
+        }
+        if body.kind == SyntaxKind::Block {
+            collect_function_scoped_names(&body.as_block().statements, &mut names);
+        }
+        self.enter_function(kind, names);
+
+        let mut parameter_statements = Vec::new();
+        let mut kept = Vec::new();
+        for (index, parameter) in parameters.iter().enumerate() {
+            let data = parameter.as_parameter();
+            if data.name.kind != SyntaxKind::Identifier {
+                // The argument is passed in a temporary parameter and destructured in the body
+                let temp = factory.create_identifier(self.create_generated_name());
+                if data.dot_dot_dot_token.is_some() {
+                    parameter_statements
+                        .extend(self.create_rest_parameter_statements(&temp, index));
+                } else {
+                    kept.push(factory.update_parameter(
+                        parameter,
+                        data.modifiers.clone(),
+                        None,
+                        Arc::clone(&temp),
+                        None,
+                        None,
+                        None,
+                    ));
+                }
+                let declarations = flatten_destructuring_binding(
+                    self,
+                    &data.name,
+                    factory.create_identifier(temp.text()),
+                    data.initializer.as_ref(),
+                    FlattenLevel::All,
+                );
+                parameter_statements.push(factory.create_variable_statement(declarations));
+            } else if data.dot_dot_dot_token.is_some() {
+                parameter_statements
+                    .extend(self.create_rest_parameter_statements(&data.name, index));
+            } else if let Some(initializer) = &data.initializer {
+                let initializer = self.visit_node(initializer);
+                parameter_statements
+                    .push(self.create_default_value_assignment(&data.name, initializer));
+                kept.push(factory.update_parameter(
+                    parameter,
+                    data.modifiers.clone(),
+                    None,
+                    Arc::clone(&data.name),
+                    None,
+                    None,
+                    None,
+                ));
+            } else {
+                kept.push(Arc::clone(parameter));
+            }
+        }
+
+        let mut statements = if body.kind == SyntaxKind::Block {
+            self.visit_function_statements(&body.as_block().statements)
+        } else {
+            let expression = self.visit_node(body);
+            vec![factory.create_return(Some(expression))]
+        };
+        if kind == FunctionKind::DerivedConstructor
+            && statements
+                .last()
+                .is_none_or(|statement| statement.kind != SyntaxKind::ReturnStatement)
+        {
+            statements.push(
+                factory.create_return(Some(factory.create_identifier(self.this_name.as_str()))),
+            );
+        }
+        let state = self.exit_function();
+        let statements = self.add_function_prologue(statements, &state, parameter_statements);
+
+        let parameters = factory.update_node_list(parameters, kept);
+        let body = if body.kind == SyntaxKind::Block {
+            let block = body.as_block();
+            factory.update_block(
+                body,
+                factory.update_node_list(&block.statements, statements),
+                block.multi_line,
+            )
+        } else {
+            factory.create_block(statements, false)
+        };
+        (parameters, body)
+    }
+
+    /// Visits the statements of a function body or source file. In a derived class's
+    /// constructor, a `super` call that is a statement of its own declares the variable for
+    /// `this`.
+    fn visit_function_statements(&mut self, statements: &NodeList) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let mut visited = Vec::new();
+        for statement in statements.iter() {
+            let state = self.current_function();
+            if state.kind == FunctionKind::DerivedConstructor
+                && !state.this_declared
+                && is_super_call_statement(statement)
+            {
+                state.this_declared = true;
+                // The visited call is `_this = _super.call(this) || this`
+                let expression = self.visit_node(&statement.as_expression_statement().expression);
+                let value = Arc::clone(&expression.as_binary_expression().right);
+                let declaration =
+                    factory.create_variable_statement(vec![factory.create_variable_declaration(
+                        factory.create_identifier(self.this_name.as_str()),
+                        Some(value),
+                    )]);
+                visited.push(factory.with_text_range(&declaration, statement.loc));
+                continue;
+            }
+            visited.extend(self.visit_list_element(statement));
+        }
+        visited
+    }
+
+    /// Inserts the statements a lowered function starts with after its prologue directives:
+    /// the variables for `this`, `arguments`, and temporary values, and then the statements
+    /// for its parameters
+    fn add_function_prologue(
+        &self,
+        mut statements: Vec<Arc<Node>>,
+        state: &FunctionState,
+        parameter_statements: Vec<Arc<Node>>,
+    ) -> Vec<Arc<Node>> {
+        let factory = &self.factory;
+        let mut prologue = Vec::new();
+        if state.kind == FunctionKind::DerivedConstructor && !state.this_declared {
+            prologue.push(factory.create_variable_statement(vec![
+                factory.create_variable_declaration(
+                    factory.create_identifier(self.this_name.as_str()),
+                    None,
+                ),
+            ]));
+        }
+        if state.captures_this {
+            prologue.push(factory.create_variable_statement(vec![
+                factory.create_variable_declaration(
+                    factory.create_identifier(self.this_name.as_str()),
+                    Some(factory.create_token(SyntaxKind::ThisKeyword)),
+                ),
+            ]));
+        }
+        if state.captures_arguments {
+            prologue.push(factory.create_variable_statement(vec![
+                factory.create_variable_declaration(
+                    factory.create_identifier(self.arguments_name.as_str()),
+                    Some(factory.create_identifier("arguments")),
+                ),
+            ]));
+        }
+        if !state.temps.is_empty() {
+            prologue.push(
+                factory.create_variable_statement(
+                    state
+                        .temps
+                        .iter()
+                        .map(|name| {
+                            factory.create_variable_declaration(
+                                factory.create_identifier(name.as_str()),
+                                None,
+                            )
+                        })
+                        .collect(),
+                ),
+            );
+        }
+        prologue.extend(parameter_statements);
+        let insert_at = statements
+            .iter()
+            .take_while(|statement| is_prologue_directive(statement))
+            .count();
+        statements.splice(insert_at..insert_at, prologue);
+        statements
+    }
+
+    /// Creates `if (name === void 0) { name = initializer; }`
+    fn create_default_value_assignment(
+        &self,
+        name: &Arc<Node>,
+        initializer: Arc<Node>,
+    ) -> Arc<Node> {
+        let factory = &self.factory;
+        let parameter = || factory.create_identifier(name.text());
+        factory.create(
+            SyntaxKind::IfStatement,
+            IfStatement {
+                expression: factory.create_binary(
+                    parameter(),
+                    SyntaxKind::EqualsEqualsEqualsToken,
+                    factory.create_void_zero(),
+                ),
+                then_statement: factory.create_block(
+                    vec![factory.create_expression_statement(
+                        factory.create_assignment(parameter(), initializer),
+                    )],
+                    false,
+                ),
+                else_statement: None,
+            },
+        )
+    }
+
+    /// Creates the statements that copy the arguments from `index` on into a rest parameter:
+    ///
+    /// ```js
+    /// var rest = [];
+    /// for (var _i = index; _i < arguments.length; _i++) {
+    ///     rest[_i - index] = arguments[_i];
+    /// }
+    /// ```
+    fn create_rest_parameter_statements(
+        &mut self,
+        name: &Arc<Node>,
+        index: usize,
+    ) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let counter = self.create_loop_variable_name();
+        let counter = || factory.create_identifier(counter.as_str());
+        let arguments = || factory.create_identifier("arguments");
+        let start = || factory.create_numeric_literal(index.to_string());
+        let declaration =
+            factory.create_variable_statement(vec![factory.create_variable_declaration(
+                factory.create_identifier(name.text()),
+                Some(factory.create_array_literal(Vec::new(), false)),
+            )]);
+        let target_index = if index == 0 {
+            counter()
+        } else {
+            factory.create_binary(counter(), SyntaxKind::MinusToken, start())
+        };
+        let copy = factory.create_expression_statement(factory.create_assignment(
+            factory.create_element_access(factory.create_identifier(name.text()), target_index),
+            factory.create_element_access(arguments(), counter()),
+        ));
+        let loop_statement = factory.create(
+            SyntaxKind::ForStatement,
+            ForStatement {
+                initializer: Some(create_variable_declaration_list(
+                    &factory,
+                    vec![factory.create_variable_declaration(counter(), Some(start()))],
+                )),
+                condition: Some(factory.create_binary(
+                    counter(),
+                    SyntaxKind::LessThanToken,
+                    factory.create_property_access(arguments(), "length"),
+                )),
+                incrementor: Some(create_increment(&factory, counter())),
+                statement: factory.create_block(vec![copy], true),
+            },
+        );
+        vec![declaration, loop_statement]
+    }
+
+    fn visit_return_statement(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        if let Some(statements) = self.visit_loop_exit(node) {
+            return to_single_statement(&self.factory, statements);
+        }
+        let data = node.as_return_statement();
+        // A derived class's constructor returns the object its base class constructed
+        if data.expression.is_none()
+            && self.current_function().kind == FunctionKind::DerivedConstructor
+        {
+            let this = self.factory.create_identifier(self.this_name.as_str());
+            return self.factory.update_return(node, Some(this));
+        }
+        let factory = self.factory;
+        visit_each_child(node, self, &factory)
+    }
+
+    /// `let` and `const` statements become `var` statements; in a loop, a `let` without an
+    /// initializer is given `void 0`, so each iteration starts with the variable unset
+    fn visit_variable_statement(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_variable_statement();
+        let list = &data.declaration_list;
+        if self.is_loop_body_variable_list(list) {
+            return self.visit_loop_body_variable_statement(node);
+        }
+        let mut declaration_list = self.visit_variable_declaration_list(list);
+        if list.flags.contains(NodeFlags::LET) && self.current_function().loop_depth > 0 {
+            let declarations = &declaration_list.as_variable_declaration_list().declarations;
+            let initialized = declarations
+                .iter()
+                .map(|declaration| {
+                    let declaration_data = declaration.as_variable_declaration();
+                    if declaration_data.initializer.is_some()
+                        || declaration_data.name.kind != SyntaxKind::Identifier
+                    {
+                        return Arc::clone(declaration);
+                    }
+                    factory.update_variable_declaration(
+                        declaration,
+                        Arc::clone(&declaration_data.name),
+                        None,
+                        None,
+                        Some(factory.create_void_zero()),
+                    )
+                })
+                .collect();
+            declaration_list = factory.update_variable_declaration_list(
+                &declaration_list,
+                factory.update_node_list(declarations, initialized),
+            );
+        }
+        factory.update_variable_statement(node, data.modifiers.clone(), declaration_list)
+    }
+
+    /// Visits a declaration list, flattening the declarations whose names are patterns:
+    /// `var { a, b: [c] } = o` becomes `var a = o.a, _a = o.b, c = _a[0]`
+    fn visit_variable_declaration_list(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_variable_declaration_list();
+        let mut declarations = Vec::new();
+        for declaration in data.declarations.iter() {
+            let declaration_data = declaration.as_variable_declaration();
+            match &declaration_data.initializer {
+                Some(initializer) if is_binding_pattern(&declaration_data.name) => {
+                    let value = self.visit_node(initializer);
+                    declarations.extend(flatten_destructuring_binding(
+                        self,
+                        &declaration_data.name,
+                        value,
+                        None,
+                        FlattenLevel::All,
+                    ));
+                }
+                _ => declarations.push(self.visit_node(declaration)),
+            }
+        }
+        let visited = factory.update_variable_declaration_list(
+            node,
+            factory.update_node_list(&data.declarations, declarations),
+        );
+        if !is_let_or_const(node) {
+            return visited;
+        }
+        factory.with_node_flags(&visited, visited.flags - NodeFlags::LET - NodeFlags::CONST)
+    }
+
+    /// Visits an expression whose value is not used, where a destructuring assignment need
+    /// not evaluate to the value it destructures
+    fn visit_discarded_expression(&mut self, expression: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let inner = skip_parentheses(expression);
+        if !is_destructuring_assignment(&inner) {
+            return self.visit_node(expression);
+        }
+        let data = inner.as_binary_expression();
+        let value = self.visit_node(&data.right);
+        let flattened =
+            flatten_destructuring_assignment(self, &data.left, value, false, FlattenLevel::All);
+        let flattened = if expression.kind == SyntaxKind::ParenthesizedExpression {
+            factory.create_parenthesized(flattened)
+        } else {
+            flattened
+        };
+        factory.with_text_range(&flattened, expression.loc)
+    }
+
+    /// `catch ({ message }) {}` becomes `catch (_a) { var message = _a.message; }`
+    fn visit_catch_clause(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_catch_clause();
+        let Some(declaration) = data
+            .variable_declaration
+            .as_ref()
+            .filter(|declaration| is_binding_pattern(&declaration.as_variable_declaration().name))
+        else {
+            return visit_each_child(node, self, &factory);
+        };
+        let temp = self.create_generated_name();
+        let declarations = flatten_destructuring_binding(
+            self,
+            &declaration.as_variable_declaration().name,
+            factory.create_identifier(temp.as_str()),
+            None,
+            FlattenLevel::All,
+        );
+        let block = self.visit_node(&data.block);
+        let block = prepend_to_block(
+            &factory,
+            &block,
+            factory.create_variable_statement(declarations),
+        );
+        factory.update_catch_clause(
+            node,
+            Some(factory.create_variable_declaration(factory.create_identifier(temp), None)),
+            block,
+        )
+    }
+
+    /// `for (var [k, v] in o) {}` becomes `for (var _a in o) { var k = _a[0], v = _a[1]; }`
+    fn visit_for_in_statement_with_pattern(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_for_in_statement();
+        let temp = self.create_generated_name();
+        let value = factory.create_identifier(temp.as_str());
+        let (initializer, binding) = if data.initializer.kind == SyntaxKind::VariableDeclarationList
+        {
+            let declaration = &data
+                .initializer
+                .as_variable_declaration_list()
+                .declarations
+                .nodes[0];
+            let declarations = flatten_destructuring_binding(
+                self,
+                &declaration.as_variable_declaration().name,
+                value,
+                None,
+                FlattenLevel::All,
+            );
+            (
+                create_variable_declaration_list(
+                    &factory,
+                    vec![factory.create_variable_declaration(
+                        factory.create_identifier(temp.as_str()),
+                        None,
+                    )],
+                ),
+                factory.create_variable_statement(declarations),
+            )
+        } else {
+            self.current_function().temps.push(temp.clone());
+            let assignment = flatten_destructuring_assignment(
+                self,
+                &data.initializer,
+                value,
+                false,
+                FlattenLevel::All,
+            );
+            (
+                factory.create_identifier(temp.as_str()),
+                factory.create_expression_statement(assignment),
+            )
+        };
+        let expression = self.visit_node(&data.expression);
+        self.current_function().loop_depth += 1;
+        let statement = self.visit_node(&data.statement);
+        self.current_function().loop_depth -= 1;
+        factory.update_for_in_statement(
+            node,
+            initializer,
+            expression,
+            prepend_to_block(&factory, &statement, binding),
+        )
+    }
+
+    /// Visits a loop, counting it while its body is visited
+    fn visit_loop(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        self.current_function().loop_depth += 1;
+        let visited = visit_each_child(node, self, &factory);
+        self.current_function().loop_depth -= 1;
+        visited
+    }
+
+    /// `for (const x of xs) {}` becomes a loop over the indexes of the array:
+    ///
+    /// ```js
+    /// for (var _i = 0, xs_1 = xs; _i < xs_1.length; _i++) {
+    ///     var x = xs_1[_i];
+    /// }
+    /// ```
+    ///
+    /// With `downlevelIteration`, it runs the iterator of the value instead, closing it when
+    /// the loop is left early:
+    ///
+    /// ```js
+    /// var e_1, _a;
+    /// try {
+    ///     for (var xs_1 = __values(xs), xs_1_1 = xs_1.next(); !xs_1_1.done; xs_1_1 = xs_1.next()) {
+    ///         var x = xs_1_1.value;
+    ///     }
+    /// }
+    /// catch (e_1_1) { e_1 = { error: e_1_1 }; }
+    /// finally {
+    ///     try {
+    ///         if (xs_1_1 && !xs_1_1.done && (_a = xs_1.return)) _a.call(xs_1);
+    ///     }
+    ///     finally { if (e_1) throw e_1.error; }
+    /// }
+    /// ```
+    fn visit_for_of_statement(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_for_of_statement();
+        let expression = self.visit_node(&data.expression);
+        self.push_block_scope(block_scoped_binding_names(&data.initializer));
+        if self.context.downlevel_iteration {
+            return self.visit_for_of_statement_with_iterator(node, expression);
+        }
+
+        let counter = self.create_loop_variable_name();
+        let counter = || factory.create_identifier(counter.as_str());
+        let array = if expression.kind == SyntaxKind::Identifier {
+            self.context.create_unique_name(expression.text())
+        } else {
+            self.create_generated_name()
+        };
+        let array = || factory.create_identifier(array.as_str());
+        let body = self.visit_for_of_body(node, factory.create_element_access(array(), counter()));
+        factory.create_at(
+            SyntaxKind::ForStatement,
+            node.loc,
+            ForStatement {
+                initializer: Some(create_variable_declaration_list(
+                    &factory,
+                    vec![
+                        factory.create_variable_declaration(
+                            counter(),
+                            Some(factory.create_numeric_literal("0")),
+                        ),
+                        factory.create_variable_declaration(array(), Some(expression)),
+                    ],
+                )),
+                condition: Some(factory.create_binary(
+                    counter(),
+                    SyntaxKind::LessThanToken,
+                    factory.create_property_access(array(), "length"),
+                )),
+                incrementor: Some(create_increment(&factory, counter())),
+                statement: body,
+            },
+        )
+    }
+
+    /// Lowers a `for...of` loop to a loop that calls `next` on the iterator of its visited
+    /// `expression`, within a `try` statement that closes the iterator
+    fn visit_for_of_statement_with_iterator(
+        &mut self,
+        node: &Arc<Node>,
+        expression: Arc<Node>,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        let error_record = self.context.create_unique_name("e");
+        self.current_function().temps.push(error_record.clone());
+        let return_method = self.create_temp_variable();
+        let return_method = return_method.text().to_string();
+        let (iterator, result) = if expression.kind == SyntaxKind::Identifier {
+            let iterator = self.context.create_unique_name(expression.text());
+            let result = self.context.create_unique_name(&iterator);
+            (iterator, result)
+        } else {
+            (self.create_generated_name(), self.create_generated_name())
+        };
+        let caught = self.context.create_unique_name(&error_record);
+
+        let name = |name: &str| factory.create_identifier(name);
+        let not = |operand: Arc<Node>| {
+            factory.create(
+                SyntaxKind::PrefixUnaryExpression,
+                PrefixUnaryExpression {
+                    operator: SyntaxKind::ExclamationToken,
+                    operand,
+                },
+            )
+        };
+        let and =
+            |left, right| factory.create_binary(left, SyntaxKind::AmpersandAmpersandToken, right);
+        let assign = |target: &str, value| factory.create_assignment(name(target), value);
+        let statement = |expression| factory.create_expression_statement(expression);
+        let if_then = |expression, then_statement| {
+            factory.create(
+                SyntaxKind::IfStatement,
+                IfStatement {
+                    expression,
+                    then_statement,
+                    else_statement: None,
+                },
+            )
+        };
+        let next = || {
+            factory.create_call(
+                factory.create_property_access(name(&iterator), "next"),
+                Vec::new(),
+            )
+        };
+
+        let body =
+            self.visit_for_of_body(node, factory.create_property_access(name(&result), "value"));
+        self.context.request_helper(&VALUES_HELPER);
+        let values = factory.create_call(name("__values"), vec![expression]);
+        let for_statement = factory.create_at(
+            SyntaxKind::ForStatement,
+            node.loc,
+            ForStatement {
+                initializer: Some(create_variable_declaration_list(
+                    &factory,
+                    vec![
+                        factory.create_variable_declaration(name(&iterator), Some(values)),
+                        factory.create_variable_declaration(name(&result), Some(next())),
+                    ],
+                )),
+                condition: Some(not(factory.create_property_access(name(&result), "done"))),
+                incrementor: Some(assign(&result, next())),
+                statement: body,
+            },
+        );
+
+        let catch_clause = factory.create(
+            SyntaxKind::CatchClause,
+            CatchClause {
+                variable_declaration: Some(
+                    factory.create_variable_declaration(name(&caught), None),
+                ),
+                block: factory.create_block(
+                    vec![statement(assign(
+                        &error_record,
+                        factory.create_object_literal(
+                            vec![factory.create_property_assignment(name("error"), name(&caught))],
+                            false,
+                        ),
+                    ))],
+                    false,
+                ),
+            },
+        );
+        let close_iterator = if_then(
+            and(
+                and(
+                    name(&result),
+                    not(factory.create_property_access(name(&result), "done")),
+                ),
+                factory.create_parenthesized(assign(
+                    &return_method,
+                    factory.create_property_access(name(&iterator), "return"),
+                )),
+            ),
+            statement(factory.create_call(
+                factory.create_property_access(name(&return_method), "call"),
+                vec![name(&iterator)],
+            )),
+        );
+        let rethrow = if_then(
+            name(&error_record),
+            factory.create(
+                SyntaxKind::ThrowStatement,
+                ThrowStatement {
+                    expression: factory.create_property_access(name(&error_record), "error"),
+                },
+            ),
+        );
+        let finally_block = factory.create_block(
+            vec![factory.create(
+                SyntaxKind::TryStatement,
+                TryStatement {
+                    try_block: factory.create_block(vec![close_iterator], true),
+                    catch_clause: None,
+                    finally_block: Some(factory.create_block(vec![rethrow], false)),
+                },
+            )],
+            true,
+        );
+        factory.create(
+            SyntaxKind::TryStatement,
+            TryStatement {
+                try_block: factory.create_block(vec![for_statement], true),
+                catch_clause: Some(catch_clause),
+                finally_block: Some(finally_block),
+            },
+        )
+    }
+
+    /// Visits the body of a `for...of` loop, starting it with the binding of the loop's
+    /// variable to `element`, and leaves the block scope of the loop
+    fn visit_for_of_body(&mut self, node: &Arc<Node>, element: Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_for_of_statement();
+        let binding = if data.initializer.kind == SyntaxKind::VariableDeclarationList {
+            let declaration = &data
+                .initializer
+                .as_variable_declaration_list()
+                .declarations
+                .nodes[0];
+            let name = &declaration.as_variable_declaration().name;
+            let declarations = if is_binding_pattern(name) {
+                flatten_destructuring_binding(self, name, element, None, FlattenLevel::All)
+            } else {
+                vec![factory.create_variable_declaration(self.visit_node(name), Some(element))]
+            };
+            if self.is_loop_body_variable_list(&data.initializer) {
+                let list = create_variable_declaration_list(&factory, declarations);
+                let assignments = self
+                    .hoist_loop_body_variables(&list)
+                    .expect("the element is assigned");
+                factory.create_expression_statement(assignments)
+            } else {
+                factory.create_variable_statement(declarations)
+            }
+        } else if is_destructuring_loop_initializer(&data.initializer) {
+            factory.create_expression_statement(flatten_destructuring_assignment(
+                self,
+                &data.initializer,
+                element,
+                false,
+                FlattenLevel::All,
+            ))
+        } else {
+            let target = self.visit_node(&data.initializer);
+            factory.create_expression_statement(factory.create_assignment(target, element))
+        };
+
+        self.current_function().loop_depth += 1;
+        let statement = self.visit_node(&data.statement);
+        self.current_function().loop_depth -= 1;
+        self.scopes.pop();
+
+        if statement.kind == SyntaxKind::Block {
+            let block = statement.as_block();
+            let mut statements = vec![binding];
+            statements.extend(block.statements.iter().cloned());
+            factory.update_block(
+                &statement,
+                factory.update_node_list(&block.statements, statements),
+                true,
+            )
+        } else {
+            factory.create_block(vec![binding, statement], true)
+        }
+    }
+
+    /// `` `a${b}c${d}` `` becomes `"a".concat(b, "c").concat(d)`
+    fn visit_template_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_template_expression();
+        let head = &data.head.as_template_literal_like_node().text;
+        let mut expression = factory.create_string_literal(head.as_str());
+        for span in data.template_spans.iter() {
+            let span = span.as_template_span();
+            let mut arguments = vec![self.visit_node(&span.expression)];
+            let text = &span.literal.as_template_literal_like_node().text;
+            if !text.is_empty() {
+                arguments.push(factory.create_string_literal(text.as_str()));
+            }
+            expression = factory.create_call(
+                factory.create_property_access(expression, "concat"),
+                arguments,
+            );
+        }
+        factory.with_text_range(&expression, node.loc)
+    }
+
+    /// `` tag`a${b}` `` becomes `tag(__makeTemplateObject(["a", ""], ["a", ""]), b)`, passing
+    /// the cooked and raw strings of the template
+    fn visit_tagged_template_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_tagged_template_expression();
+        let tag = self.visit_node(&data.tag);
+        let mut literals = Vec::new();
+        let mut expressions = Vec::new();
+        if data.template.kind == SyntaxKind::TemplateExpression {
+            let template = data.template.as_template_expression();
+            literals.push(&template.head);
+            for span in template.template_spans.iter() {
+                let span = span.as_template_span();
+                literals.push(&span.literal);
+                expressions.push(self.visit_node(&span.expression));
+            }
+        } else {
+            literals.push(&data.template);
+        }
+        let cooked = literals
+            .iter()
+            .map(|literal| {
+                factory.create_string_literal(literal.as_template_literal_like_node().text.as_str())
+            })
+            .collect();
+        let raw = literals
+            .iter()
+            .map(|literal| {
+                let literal = literal.as_template_literal_like_node();
+                let raw_text = if literal.raw_text.is_empty() {
+                    &literal.text
+                } else {
+                    &literal.raw_text
+                };
+                factory.create_string_literal(raw_text.replace("\r\n", "\n"))
+            })
+            .collect();
+        self.context.request_helper(&MAKE_TEMPLATE_OBJECT_HELPER);
+        let mut arguments = vec![factory.create_call(
+            factory.create_identifier("__makeTemplateObject"),
+            vec![
+                factory.create_array_literal(cooked, false),
+                factory.create_array_literal(raw, false),
+            ],
+        )];
+        arguments.extend(expressions);
+        factory.with_text_range(&factory.create_call(tag, arguments), node.loc)
+    }
+
+    fn visit_call_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_call_expression();
+        let callee = &data.expression;
+        if callee.kind == SyntaxKind::SuperKeyword {
+            return self.visit_super_call(node);
+        }
+        let has_spread = has_spread_element(&data.arguments);
+        let is_super_property = is_super_property(callee);
+        if !has_spread && !is_super_property {
+            return visit_each_child(node, self, &factory);
+        }
+        // The function is called through `call` or `apply` with an explicit `this`
+        let (target, this_argument) = if is_super_property {
+            (self.visit_super_property(callee), self.create_this())
+        } else {
+            self.create_call_binding(callee)
+        };
+        let call = if has_spread {
+            let arguments = self.transform_spread_elements(&data.arguments, true, false);
+            factory.create_call(
+                factory.create_property_access(target, "apply"),
+                vec![this_argument, arguments],
+            )
+        } else {
+            let mut arguments = vec![this_argument];
+            arguments.extend(
+                data.arguments
+                    .iter()
+                    .map(|argument| self.visit_node(argument)),
+            );
+            factory.create_call(factory.create_property_access(target, "call"), arguments)
+        };
+        factory.with_text_range(&call, node.loc)
+    }
+
+    /// `super(a)` becomes `_this = _super.call(this, a) || this`, since the base class's
+    /// constructor may return a different object than the one it was called with
+    fn visit_super_call(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_call_expression();
+        let super_name = self.super_name.clone();
+        let base = || factory.create_identifier(super_name.as_str());
+        let this = || factory.create_token(SyntaxKind::ThisKeyword);
+        let call = if has_spread_element(&data.arguments) {
+            let arguments = self.transform_spread_elements(&data.arguments, true, false);
+            factory.create_call(
+                factory.create_property_access(base(), "apply"),
+                vec![this(), arguments],
+            )
+        } else {
+            let mut arguments = vec![this()];
+            arguments.extend(
+                data.arguments
+                    .iter()
+                    .map(|argument| self.visit_node(argument)),
+            );
+            factory.create_call(factory.create_property_access(base(), "call"), arguments)
+        };
+        let assignment = factory.create_assignment(
+            factory.create_identifier(self.this_name.as_str()),
+            factory.create_binary(call, SyntaxKind::BarBarToken, this()),
+        );
+        factory.with_text_range(&assignment, node.loc)
+    }
+
+    /// `super.m` becomes `_super.prototype.m`, or `_super.m` in a static member
+    fn visit_super_property(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let index = self.this_function_index();
+        let is_static = self.functions[index].kind == FunctionKind::Method { is_static: true };
+        let base = factory.create_identifier(self.super_name.as_str());
+        let object = if is_static {
+            base
+        } else {
+            factory.create_property_access(base, "prototype")
+        };
+        let access = if node.kind == SyntaxKind::PropertyAccessExpression {
+            factory.create(
+                SyntaxKind::PropertyAccessExpression,
+                PropertyAccessExpression {
+                    expression: object,
+                    question_dot_token: None,
+                    name: Arc::clone(&node.as_property_access_expression().name),
+                },
+            )
+        } else {
+            let argument =
+                self.visit_node(&node.as_element_access_expression().argument_expression);
+            factory.create(
+                SyntaxKind::ElementAccessExpression,
+                ElementAccessExpression {
+                    expression: object,
+                    question_dot_token: None,
+                    argument_expression: argument,
+                },
+            )
+        };
+        factory.with_text_range(&access, node.loc)
+    }
+
+    /// Splits a callee into the function to call and the value of `this` for the call,
+    /// storing an object that is not a plain name in a temporary variable so that it is
+    /// evaluated once: `a.b.c` becomes `(_a = a.b).c` with `_a` as `this`
+    fn create_call_binding(&mut self, callee: &Arc<Node>) -> (Arc<Node>, Arc<Node>) {
+        let factory = self.factory;
+        let object = match callee.kind {
+            SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression => {
+                callee.expression().expect("member accesses have objects")
+            }
+            _ => return (self.visit_node(callee), factory.create_void_zero()),
+        };
+        let object = self.visit_node(object);
+        let (object, this_argument) = self.create_reusable_reference(object);
+        let target = if callee.kind == SyntaxKind::PropertyAccessExpression {
+            let data = callee.as_property_access_expression();
+            factory.update_property_access(
+                callee,
+                object,
+                data.question_dot_token.clone(),
+                Arc::clone(&data.name),
+            )
+        } else {
+            let data = callee.as_element_access_expression();
+            factory.update_element_access(
+                callee,
+                object,
+                data.question_dot_token.clone(),
+                self.visit_node(&data.argument_expression),
+            )
+        };
+        (target, this_argument)
+    }
+
+    /// Returns an expression to evaluate in place of `expression` and a second reference to
+    /// its value: a copy of a plain name or `this`, or a temporary variable assigned in place
+    fn create_reusable_reference(&mut self, expression: Arc<Node>) -> (Arc<Node>, Arc<Node>) {
+        let factory = self.factory;
+        match expression.kind {
+            SyntaxKind::Identifier => {
+                let copy = factory.create_identifier(expression.text());
+                (expression, copy)
+            }
+            SyntaxKind::ThisKeyword => (expression, factory.create_token(SyntaxKind::ThisKeyword)),
+            _ => {
+                let temp = self.create_temp_variable();
+                let assignment = factory.create_assignment(Arc::clone(&temp), expression);
+                (assignment, factory.create_identifier(temp.text()))
+            }
+        }
+    }
+
+    /// `new C(...a)` becomes `new (C.bind.apply(C, __spreadArray([void 0], a, false)))()`
+    fn visit_new_expression_with_spread(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_new_expression();
+        let arguments = data
+            .arguments
+            .as_ref()
+            .expect("the arguments have a spread");
+        let callee = self.visit_node(&data.expression);
+        let (callee, this_argument) = self.create_reusable_reference(callee);
+        let mut bound_arguments = vec![factory.create_void_zero()];
+        bound_arguments.extend(arguments.iter().cloned());
+        let bound_arguments =
+            self.transform_spread_elements(&factory.create_node_list(bound_arguments), true, false);
+        let bind = factory.create_call(
+            factory.create_property_access(factory.create_property_access(callee, "bind"), "apply"),
+            vec![this_argument, bound_arguments],
+        );
+        factory.update_new(
+            node,
+            factory.create_parenthesized(bind),
+            None,
+            Some(factory.create_node_list(Vec::new())),
+        )
+    }
+
+    /// Turns a list of elements with spreads into a single array: `[a, ...b, c]` becomes
+    /// `__spreadArray(__spreadArray([a], b, true), [c], false)`
+    ///
+    /// An argument list that is a single spread passes the spread value itself to `apply`,
+    /// and arguments do not need holes in spread arrays preserved.
+    fn transform_spread_elements(
+        &mut self,
+        elements: &NodeList,
+        is_argument_list: bool,
+        multi_line: bool,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        // Each segment is a run of plain elements as an array literal, or a spread value
+        let mut segments: Vec<(Arc<Node>, bool)> = Vec::new();
+        let mut run = Vec::new();
+        for element in elements.iter() {
+            if element.kind == SyntaxKind::SpreadElement {
+                if !run.is_empty() {
+                    segments.push((
+                        factory.create_array_literal(std::mem::take(&mut run), multi_line),
+                        false,
+                    ));
+                }
+                segments.push((
+                    self.visit_node(&element.as_spread_element().expression),
+                    true,
+                ));
+            } else {
+                run.push(self.visit_node(element));
+            }
+        }
+        if !run.is_empty() {
+            segments.push((factory.create_array_literal(run, multi_line), false));
+        }
+        if let [(expression, true)] = segments.as_slice()
+            && (is_argument_list || is_packed_array_literal(expression))
+        {
+            return Arc::clone(expression);
+        }
+
+        self.context.request_helper(&SPREAD_ARRAY_HELPER);
+        let mut segments = segments.into_iter().peekable();
+        let mut array = match segments.next_if(|(_, is_spread)| !is_spread) {
+            Some((literal, _)) => literal,
+            None => factory.create_array_literal(Vec::new(), false),
+        };
+        for (expression, is_spread) in segments {
+            let pack = is_spread && !is_argument_list && !is_packed_array_literal(&expression);
+            array = factory.create_call(
+                factory.create_identifier("__spreadArray"),
+                vec![
+                    array,
+                    expression,
+                    factory.create_token(if pack {
+                        SyntaxKind::TrueKeyword
+                    } else {
+                        SyntaxKind::FalseKeyword
+                    }),
+                ],
+            );
+        }
+        array
+    }
+
+    /// Writes out shorthand properties and methods: `{ a, m() {} }` becomes
+    /// `{ a: a, m: function () {} }`
+    ///
+    /// From the first property with a computed name on, the properties are assigned to the
+    /// object one at a time, in order: `{ a: 1, [k]: 2, b }` becomes
+    /// `(_a = { a: 1 }, _a[k] = 2, _a.b = b, _a)`.
+    fn visit_object_literal(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_object_literal_expression();
+        let first_computed = data.properties.iter().position(|property| {
+            property
+                .name()
+                .is_some_and(|name| name.kind == SyntaxKind::ComputedPropertyName)
+        });
+        let Some(first_computed) = first_computed else {
+            let properties = data
+                .properties
+                .iter()
+                .map(|property| self.visit_object_literal_element(property))
+                .collect();
+            return factory.update_object_literal(
+                node,
+                factory.update_node_list(&data.properties, properties),
+            );
+        };
+        let temp = self.create_temp_variable();
+        let properties = data.properties.nodes[..first_computed]
+            .iter()
+            .map(|property| self.visit_object_literal_element(property))
+            .collect();
+        let mut expressions = vec![factory.create_assignment(
+            factory.create_identifier(temp.text()),
+            factory.create_object_literal(properties, data.multi_line),
+        )];
+        for property in &data.properties.nodes[first_computed..] {
+            let visited = self.visit_object_literal_element(property);
+            expressions.push(create_property_definition(&factory, &temp, &visited));
+        }
+        expressions.push(temp);
+        let mut expressions = expressions.into_iter();
+        let first = expressions.next().expect("the object is assigned first");
+        let expression = expressions.fold(first, |left, right| {
+            factory.create_binary(left, SyntaxKind::CommaToken, right)
+        });
+        factory.with_text_range(&expression, node.loc)
+    }
+
+    /// Visits a property of an object literal, writing out shorthand properties and methods
+    fn visit_object_literal_element(&mut self, property: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        match property.kind {
+            SyntaxKind::ShorthandPropertyAssignment => {
+                let name = &property.as_shorthand_property_assignment().name;
+                factory.create_at(
+                    SyntaxKind::PropertyAssignment,
+                    property.loc,
+                    PropertyAssignment {
+                        modifiers: None,
+                        name: factory.create_identifier(name.text()),
+                        postfix_token: None,
+                        initializer: self.visit_identifier(name),
+                    },
+                )
+            }
+            SyntaxKind::MethodDeclaration if property.body().is_some() => {
+                let method = property.as_method_declaration();
+                let body = method.body.as_ref().expect("the method has a body");
+                let name = self.visit_property_name(&method.name);
+                let (parameters, body) = self.transform_function(
+                    &method.parameters,
+                    body,
+                    FunctionKind::Plain,
+                    Vec::new(),
+                );
+                let function = factory.create(
+                    SyntaxKind::FunctionExpression,
+                    FunctionExpression {
+                        modifiers: method.modifiers.clone(),
+                        asterisk_token: method.asterisk_token.clone(),
+                        name: None,
+                        type_parameters: None,
+                        parameters,
+                        type_node: None,
+                        body,
+                    },
+                );
+                factory.create_at(
+                    SyntaxKind::PropertyAssignment,
+                    property.loc,
+                    PropertyAssignment {
+                        modifiers: None,
+                        name,
+                        postfix_token: None,
+                        initializer: function,
+                    },
+                )
+            }
+            _ => self.visit_node(property),
+        }
+    }
+}
+
+impl DestructuringHost for Es2015Transformer<'_> {
+    fn context(&mut self) -> &mut TransformContext {
+        self.context
+    }
+
+    fn create_temp_name(&mut self) -> String {
+        self.create_generated_name()
+    }
+
+    fn hoist_variable(&mut self, name: &str) {
+        self.current_function().temps.push(name.to_string());
+    }
+}
+
+/// Creates the assignment of a visited object literal property to `object`, or for an
+/// accessor, `Object.defineProperty(object, "name", { get: ..., enumerable: true,
+/// configurable: true })`
+fn create_property_definition(
+    factory: &NodeFactory,
+    object: &Arc<Node>,
+    property: &Arc<Node>,
+) -> Arc<Node> {
+    let object = || factory.create_identifier(object.text());
+    let name = property
+        .name()
+        .expect("object literal properties are named");
+    if matches!(
+        property.kind,
+        SyntaxKind::GetAccessor | SyntaxKind::SetAccessor
+    ) {
+        let key = match name.kind {
+            SyntaxKind::ComputedPropertyName => {
+                Arc::clone(&name.as_computed_property_name().expression)
+            }
+            SyntaxKind::NumericLiteral => factory.create_numeric_literal(name.text()),
+            _ => factory.create_string_literal(name.text()),
+        };
+        let function = factory.create_function_expression(
+            None,
+            property
+                .parameters()
+                .expect("accessors have parameters")
+                .nodes
+                .clone(),
+            Arc::clone(property.body().expect("the accessor has a body")),
+        );
+        let accessor = if property.kind == SyntaxKind::GetAccessor {
+            "get"
+        } else {
+            "set"
+        };
+        let descriptor = factory.create_object_literal(
+            vec![
+                factory.create_property_assignment(factory.create_identifier(accessor), function),
+                factory.create_property_assignment(
+                    factory.create_identifier("enumerable"),
+                    factory.create_token(SyntaxKind::TrueKeyword),
+                ),
+                factory.create_property_assignment(
+                    factory.create_identifier("configurable"),
+                    factory.create_token(SyntaxKind::TrueKeyword),
+                ),
+            ],
+            false,
+        );
+        return factory.create_call(
+            factory.create_property_access(factory.create_identifier("Object"), "defineProperty"),
+            vec![object(), key, descriptor],
+        );
+    }
+    let target = match name.kind {
+        SyntaxKind::ComputedPropertyName => factory.create_element_access(
+            object(),
+            Arc::clone(&name.as_computed_property_name().expression),
+        ),
+        SyntaxKind::Identifier => factory.create_property_access(object(), name.text()),
+        SyntaxKind::NumericLiteral => {
+            factory.create_element_access(object(), factory.create_numeric_literal(name.text()))
+        }
+        _ => factory.create_element_access(object(), factory.create_string_literal(name.text())),
+    };
+    let value = Arc::clone(&property.as_property_assignment().initializer);
+    factory.create_assignment(target, value)
+}
+
+/// Determines if the declaration list or assignment target of a `for...in` or `for...of` loop
+/// destructures each value
+fn is_destructuring_loop_initializer(initializer: &Node) -> bool {
+    match initializer.kind {
+        SyntaxKind::VariableDeclarationList => initializer
+            .as_variable_declaration_list()
+            .declarations
+            .iter()
+            .any(|declaration| is_binding_pattern(&declaration.as_variable_declaration().name)),
+        SyntaxKind::ObjectLiteralExpression | SyntaxKind::ArrayLiteralExpression => true,
+        _ => false,
+    }
+}
+
+fn has_spread_element(list: &NodeList) -> bool {
+    list.iter()
+        .any(|element| element.kind == SyntaxKind::SpreadElement)
+}
+
+/// Whether an expression is an array literal without holes, which needs no copying to be spread
+fn is_packed_array_literal(expression: &Node) -> bool {
+    expression.kind == SyntaxKind::ArrayLiteralExpression
+        && expression
+            .as_array_literal_expression()
+            .elements
+            .iter()
+            .all(|element| element.kind != SyntaxKind::OmittedExpression)
+}
+
+fn is_super_property(node: &Node) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression
+    ) && node
+        .expression()
+        .is_some_and(|expression| expression.kind == SyntaxKind::SuperKeyword)
+}
+
+fn is_super_call_statement(statement: &Node) -> bool {
+    statement.kind == SyntaxKind::ExpressionStatement && {
+        let expression = &statement.as_expression_statement().expression;
+        expression.kind == SyntaxKind::CallExpression
+            && expression.as_call_expression().expression.kind == SyntaxKind::SuperKeyword
+    }
+}
+
+/// Creates a `var` declaration list for the head of a `for` loop
+fn create_variable_declaration_list(
+    factory: &NodeFactory,
+    declarations: Vec<Arc<Node>>,
+) -> Arc<Node> {
+    factory.create(
+        SyntaxKind::VariableDeclarationList,
+        VariableDeclarationList {
+            declarations: factory.create_node_list(declarations),
+        },
+    )
+}
+
+/// Creates `operand++`
+fn create_increment(factory: &NodeFactory, operand: Arc<Node>) -> Arc<Node> {
+    factory.create(
+        SyntaxKind::PostfixUnaryExpression,
+        PostfixUnaryExpression {
+            operand,
+            operator: SyntaxKind::PlusPlusToken,
+        },
+    )
+}
@@ -0,0 +1,597 @@
+//! Lowering classes to constructor functions
+//!
+//! A class becomes a function that creates its constructor within a closure, which for a
+//! derived class takes the base class as `_super`:
+//!
+//! ```js
+//! var C = (function (_super) {
+//!     __extends(C, _super);
+//!     function C() {
+//!         var _this = _super.call(this) || this;
+//!         _this.x = 1;
+//!         return _this;
+//!     }
+//!     C.prototype.m = function () { };
+//!     C.s = 2;
+//!     return C;
+//! }(B));
+//! ```
+
+use std::sync::Arc;
+
+use crate::compiler::ast::nodes::{
+    ElementAccessExpression, ExportAssignment, FunctionDeclaration, FunctionExpression,
+    PropertyAccessExpression, VariableStatement,
+};
+use crate::compiler::ast::visitor::NodeVisitor;
+use crate::compiler::ast::{Node, NodeList, SyntaxKind};
+
+use super::super::helpers::EXTENDS_HELPER;
+use super::super::is_prologue_directive;
+use super::{Es2015Transformer, FunctionKind, is_super_call_statement};
+
+/// The parts of a class declaration or expression
+struct ClassParts<'a> {
+    modifiers: Option<&'a NodeList>,
+    name: Option<&'a Arc<Node>>,
+    heritage_clauses: Option<&'a NodeList>,
+    members: &'a NodeList,
+}
+
+impl<'a> ClassParts<'a> {
+    fn of(node: &'a Node) -> Self {
+        if node.kind == SyntaxKind::ClassDeclaration {
+            let data = node.as_class_declaration();
+            ClassParts {
+                modifiers: data.modifiers.as_ref(),
+                name: data.name.as_ref(),
+                heritage_clauses: data.heritage_clauses.as_ref(),
+                members: &data.members,
+            }
+        } else {
+            let data = node.as_class_expression();
+            ClassParts {
+                modifiers: data.modifiers.as_ref(),
+                name: data.name.as_ref(),
+                heritage_clauses: data.heritage_clauses.as_ref(),
+                members: &data.members,
+            }
+        }
+    }
+
+    /// Gets the expression of the `extends` clause
+    fn base_class(&self) -> Option<&'a Arc<Node>> {
+        self.heritage_clauses?
+            .iter()
+            .find(|clause| clause.as_heritage_clause().token == SyntaxKind::ExtendsKeyword)
+            .and_then(|clause| clause.as_heritage_clause().types.nodes.first())
+            .map(|base| &base.as_expression_with_type_arguments().expression)
+    }
+}
+
+impl Es2015Transformer<'_> {
+    /// `class C {}` becomes `var C = (function () { ... }());`, and `export default class {}`
+    /// becomes the declaration of `default_1` followed by `export default default_1;`
+    pub(super) fn visit_class_declaration(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let parts = ClassParts::of(node);
+        let has_modifier = |kind| {
+            parts
+                .modifiers
+                .is_some_and(|modifiers| modifiers.iter().any(|modifier| modifier.kind == kind))
+        };
+        let is_export = has_modifier(SyntaxKind::ExportKeyword);
+        let is_default = has_modifier(SyntaxKind::DefaultKeyword);
+        let name = match parts.name {
+            Some(name) => self
+                .resolve(name.text())
+                .unwrap_or_else(|| name.text().to_string()),
+            None => self.context.create_unique_name("default"),
+        };
+
+        let class = self.transform_class(&parts, &name);
+        let declaration =
+            factory.create_variable_statement(vec![factory.create_variable_declaration(
+                factory.create_identifier(name.as_str()),
+                Some(class),
+            )]);
+        let modifiers = parts
+            .modifiers
+            .filter(|_| is_export && !is_default)
+            .map(|modifiers| {
+                let export = modifiers
+                    .iter()
+                    .filter(|modifier| modifier.kind == SyntaxKind::ExportKeyword)
+                    .cloned()
+                    .collect();
+                factory.update_node_list(modifiers, export)
+            });
+        let statement = factory.create_at(
+            SyntaxKind::VariableStatement,
+            node.loc,
+            VariableStatement {
+                modifiers,
+                declaration_list: Arc::clone(&declaration.as_variable_statement().declaration_list),
+            },
+        );
+        let mut statements = vec![statement];
+        if is_export && is_default {
+            statements.push(factory.create(
+                SyntaxKind::ExportAssignment,
+                ExportAssignment {
+                    modifiers: None,
+                    is_export_equals: false,
+                    expression: factory.create_identifier(name),
+                },
+            ));
+        }
+        statements
+    }
+
+    /// A class expression becomes the closure that creates its constructor
+    pub(super) fn visit_class_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let parts = ClassParts::of(node);
+        let name = match parts.name {
+            Some(name) => name.text().to_string(),
+            None => self.context.create_unique_name("class"),
+        };
+        let class = self.transform_class(&parts, &name);
+        self.factory.with_text_range(&class, node.loc)
+    }
+
+    /// Creates `(function (_super) { ...; return name; }(base))`
+    fn transform_class(&mut self, parts: &ClassParts, name: &str) -> Arc<Node> {
+        let factory = self.factory;
+        let base_class = parts.base_class().map(|base| self.visit_node(base));
+        let is_derived = base_class.is_some();
+        let class_name = || factory.create_identifier(name);
+
+        self.enter_function(FunctionKind::Plain, vec![name.to_string()]);
+        let mut statements = Vec::new();
+        if is_derived {
+            self.context.request_helper(&EXTENDS_HELPER);
+            statements.push(factory.create_expression_statement(factory.create_call(
+                factory.create_identifier("__extends"),
+                vec![
+                    class_name(),
+                    factory.create_identifier(self.super_name.as_str()),
+                ],
+            )));
+        }
+        statements.push(self.transform_constructor(parts, name, is_derived));
+
+        // Accessors of the same name share one property descriptor, defined where the first of
+        // them appears
+        let mut accessors: Vec<(String, bool, Vec<Arc<Node>>)> = Vec::new();
+        let mut members = Vec::new();
+        for member in parts.members.iter() {
+            let is_static = member.has_modifier(SyntaxKind::StaticKeyword);
+            match member.kind {
+                SyntaxKind::GetAccessor | SyntaxKind::SetAccessor if member.body().is_some() => {
+                    let key = property_name_key(member.name().expect("accessors have names"));
+                    let existing = key.as_ref().and_then(|key| {
+                        accessors.iter_mut().find(|(other, other_static, _)| {
+                            other == key && *other_static == is_static
+                        })
+                    });
+                    match existing {
+                        Some((_, _, group)) => group.push(Arc::clone(member)),
+                        None => {
+                            accessors.push((
+                                key.unwrap_or_default(),
+                                is_static,
+                                vec![Arc::clone(member)],
+                            ));
+                            members.push(ClassMember::Accessors(accessors.len() - 1));
+                        }
+                    }
+                }
+                SyntaxKind::MethodDeclaration if member.body().is_some() => {
+                    members.push(ClassMember::Method(Arc::clone(member)));
+                }
+                _ => {}
+            }
+        }
+        for member in members {
+            let statement = match member {
+                ClassMember::Method(method) => self.transform_method(&method, name),
+                ClassMember::Accessors(index) => {
+                    let (_, is_static, group) = &accessors[index];
+                    let group = group.clone();
+                    self.transform_accessors(&group, name, *is_static)
+                }
+            };
+            statements.push(statement);
+        }
+
+        // Static properties and blocks run once the prototype is complete, in the order they
+        // appear
+        for member in parts.members.iter() {
+            match member.kind {
+                SyntaxKind::PropertyDeclaration
+                    if member.has_modifier(SyntaxKind::StaticKeyword) =>
+                {
+                    let data = member.as_property_declaration();
+                    let Some(initializer) = &data.initializer else {
+                        continue;
+                    };
+                    self.enter_function(FunctionKind::Method { is_static: true }, Vec::new());
+                    self.current_function().this_replacement = Some(name.to_string());
+                    let target = self.create_member_target(class_name(), &data.name);
+                    let value = self.visit_node(initializer);
+                    let state = self.exit_function();
+                    self.current_function().temps.extend(state.temps);
+                    let assignment = factory
+                        .create_expression_statement(factory.create_assignment(target, value));
+                    statements.push(factory.with_text_range(&assignment, member.loc));
+                }
+                SyntaxKind::ClassStaticBlockDeclaration => {
+                    let body = &member.as_class_static_block_declaration().body;
+                    let (_, body) = self.transform_function(
+                        &factory.create_node_list(Vec::new()),
+                        body,
+                        FunctionKind::Method { is_static: true },
+                        Vec::new(),
+                    );
+                    let function = factory.create_function_expression(None, Vec::new(), body);
+                    let call = factory.create_call(
+                        factory
+                            .create_property_access(factory.create_parenthesized(function), "call"),
+                        vec![class_name()],
+                    );
+                    let statement = factory.create_expression_statement(call);
+                    statements.push(factory.with_text_range(&statement, member.loc));
+                }
+                _ => {}
+            }
+        }
+        statements.push(factory.create_return(Some(class_name())));
+        let state = self.exit_function();
+        let statements = self.add_function_prologue(statements, &state, Vec::new());
+
+        let parameters = if is_derived {
+            vec![factory.create_parameter(factory.create_identifier(self.super_name.as_str()))]
+        } else {
+            Vec::new()
+        };
+        let closure = factory.create_function_expression(
+            None,
+            parameters,
+            factory.create_block(statements, true),
+        );
+        factory.create_parenthesized(factory.create_call(closure, base_class.into_iter().collect()))
+    }
+
+    /// Creates the constructor function, with the initializers of instance properties
+    /// assigned at its start, after any `super` call and parameter properties
+    fn transform_constructor(
+        &mut self,
+        parts: &ClassParts,
+        name: &str,
+        is_derived: bool,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        let initializers: Vec<Arc<Node>> = parts
+            .members
+            .iter()
+            .filter(|member| {
+                member.kind == SyntaxKind::PropertyDeclaration
+                    && !member.has_modifier(SyntaxKind::StaticKeyword)
+            })
+            .filter_map(|member| {
+                let data = member.as_property_declaration();
+                let initializer = data.initializer.as_ref()?;
+                let target = self.create_member_target(
+                    factory.create_token(SyntaxKind::ThisKeyword),
+                    &data.name,
+                );
+                let assignment = factory.create_expression_statement(
+                    factory.create_assignment(target, Arc::clone(initializer)),
+                );
+                Some(factory.with_text_range(&assignment, member.loc))
+            })
+            .collect();
+        let kind = if is_derived {
+            FunctionKind::DerivedConstructor
+        } else {
+            FunctionKind::Method { is_static: false }
+        };
+
+        let constructor = parts
+            .members
+            .iter()
+            .find(|member| member.kind == SyntaxKind::Constructor && member.body().is_some());
+        let Some(constructor) = constructor else {
+            return self.create_default_constructor(name, kind, initializers);
+        };
+        let data = constructor.as_constructor_declaration();
+        let body = data.body.as_ref().expect("the constructor has a body");
+        let block = body.as_block();
+        let mut statements = block.statements.nodes.clone();
+        if !initializers.is_empty() {
+            let insert_at = match statements
+                .iter()
+                .position(|statement| is_super_call_statement(statement))
+            {
+                Some(index) => index + 1,
+                None => statements
+                    .iter()
+                    .take_while(|statement| is_prologue_directive(statement))
+                    .count(),
+            };
+            // The assignments of parameter properties are the synthesized statements there
+            let insert_at = insert_at
+                + statements[insert_at..]
+                    .iter()
+                    .take_while(|statement| statement.loc == Default::default())
+                    .count();
+            statements.splice(insert_at..insert_at, initializers);
+        }
+        let body = factory.update_block(
+            body,
+            factory.update_node_list(&block.statements, statements),
+            true,
+        );
+        let (parameters, body) = self.transform_function(&data.parameters, &body, kind, Vec::new());
+        factory.create_at(
+            SyntaxKind::FunctionDeclaration,
+            constructor.loc,
+            FunctionDeclaration {
+                modifiers: None,
+                asterisk_token: None,
+                name: Some(factory.create_identifier(name)),
+                type_parameters: None,
+                parameters,
+                type_node: None,
+                body: Some(body),
+            },
+        )
+    }
+
+    /// Creates the constructor of a class that declares none, which for a derived class calls
+    /// the base class with the same arguments:
+    ///
+    /// ```js
+    /// function C() {
+    ///     return _super !== null && _super.apply(this, arguments) || this;
+    /// }
+    /// ```
+    fn create_default_constructor(
+        &mut self,
+        name: &str,
+        kind: FunctionKind,
+        initializers: Vec<Arc<Node>>,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        self.enter_function(kind, Vec::new());
+        let mut statements = Vec::new();
+        if kind == FunctionKind::DerivedConstructor {
+            self.current_function().this_declared = true;
+            let base = || factory.create_identifier(self.super_name.as_str());
+            let this = || factory.create_token(SyntaxKind::ThisKeyword);
+            let construct = factory.create_binary(
+                factory.create_binary(
+                    factory.create_binary(
+                        base(),
+                        SyntaxKind::ExclamationEqualsEqualsToken,
+                        factory.create_token(SyntaxKind::NullKeyword),
+                    ),
+                    SyntaxKind::AmpersandAmpersandToken,
+                    factory.create_call(
+                        factory.create_property_access(base(), "apply"),
+                        vec![this(), factory.create_identifier("arguments")],
+                    ),
+                ),
+                SyntaxKind::BarBarToken,
+                this(),
+            );
+            if initializers.is_empty() {
+                statements.push(factory.create_return(Some(construct)));
+            } else {
+                statements.push(factory.create_variable_statement(vec![
+                    factory.create_variable_declaration(
+                        factory.create_identifier(self.this_name.as_str()),
+                        Some(construct),
+                    ),
+                ]));
+            }
+        }
+        let has_initializers = !initializers.is_empty();
+        for initializer in initializers {
+            statements.push(self.visit_node(&initializer));
+        }
+        if kind == FunctionKind::DerivedConstructor && has_initializers {
+            statements.push(
+                factory.create_return(Some(factory.create_identifier(self.this_name.as_str()))),
+            );
+        }
+        let state = self.exit_function();
+        let statements = self.add_function_prologue(statements, &state, Vec::new());
+        factory.create(
+            SyntaxKind::FunctionDeclaration,
+            FunctionDeclaration {
+                modifiers: None,
+                asterisk_token: None,
+                name: Some(factory.create_identifier(name)),
+                type_parameters: None,
+                parameters: factory.create_node_list(Vec::new()),
+                type_node: None,
+                body: Some(factory.create_block(statements, true)),
+            },
+        )
+    }
+
+    /// `m() {}` becomes `C.prototype.m = function () {};`, or `C.m = ...` when it is static
+    fn transform_method(&mut self, method: &Arc<Node>, class_name: &str) -> Arc<Node> {
+        let factory = self.factory;
+        let data = method.as_method_declaration();
+        let is_static = method.has_modifier(SyntaxKind::StaticKeyword);
+        let target =
+            self.create_member_target(self.create_member_owner(class_name, is_static), &data.name);
+        let body = data.body.as_ref().expect("the method has a body");
+        let (parameters, body) = self.transform_function(
+            &data.parameters,
+            body,
+            FunctionKind::Method { is_static },
+            Vec::new(),
+        );
+        let modifiers = data.modifiers.as_ref().and_then(|modifiers| {
+            let kept: Vec<Arc<Node>> = modifiers
+                .iter()
+                .filter(|modifier| modifier.kind == SyntaxKind::AsyncKeyword)
+                .cloned()
+                .collect();
+            (!kept.is_empty()).then(|| factory.update_node_list(modifiers, kept))
+        });
+        let function = factory.create(
+            SyntaxKind::FunctionExpression,
+            FunctionExpression {
+                modifiers,
+                asterisk_token: data.asterisk_token.clone(),
+                name: None,
+                type_parameters: None,
+                parameters,
+                type_node: None,
+                body,
+            },
+        );
+        let statement =
+            factory.create_expression_statement(factory.create_assignment(target, function));
+        factory.with_text_range(&statement, method.loc)
+    }
+
+    /// Defines the accessors of one property:
+    ///
+    /// ```js
+    /// Object.defineProperty(C.prototype, "x", {
+    ///     get: function () { },
+    ///     set: function (v) { },
+    ///     enumerable: false,
+    ///     configurable: true
+    /// });
+    /// ```
+    fn transform_accessors(
+        &mut self,
+        accessors: &[Arc<Node>],
+        class_name: &str,
+        is_static: bool,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        let name = accessors[0].name().expect("accessors have names");
+        let key = match name.kind {
+            SyntaxKind::Identifier | SyntaxKind::PrivateIdentifier => {
+                factory.create_string_literal(name.text())
+            }
+            SyntaxKind::ComputedPropertyName => {
+                self.visit_node(name.expression().expect("computed names have expressions"))
+            }
+            _ => factory.clone_node(name),
+        };
+        let mut properties = Vec::new();
+        for accessor in accessors {
+            let parameters = accessor.parameters().expect("accessors have parameters");
+            let body = accessor.body().expect("the accessor has a body");
+            let (parameters, body) = self.transform_function(
+                parameters,
+                body,
+                FunctionKind::Method { is_static },
+                Vec::new(),
+            );
+            let function = factory.create(
+                SyntaxKind::FunctionExpression,
+                FunctionExpression {
+                    modifiers: None,
+                    asterisk_token: None,
+                    name: None,
+                    type_parameters: None,
+                    parameters,
+                    type_node: None,
+                    body,
+                },
+            );
+            let kind = if accessor.kind == SyntaxKind::GetAccessor {
+                "get"
+            } else {
+                "set"
+            };
+            properties.push(
+                factory.create_property_assignment(factory.create_identifier(kind), function),
+            );
+        }
+        properties.push(factory.create_property_assignment(
+            factory.create_identifier("enumerable"),
+            factory.create_token(SyntaxKind::FalseKeyword),
+        ));
+        properties.push(factory.create_property_assignment(
+            factory.create_identifier("configurable"),
+            factory.create_token(SyntaxKind::TrueKeyword),
+        ));
+        let define = factory.create_call(
+            factory.create_property_access(factory.create_identifier("Object"), "defineProperty"),
+            vec![
+                self.create_member_owner(class_name, is_static),
+                key,
+                factory.create_object_literal(properties, true),
+            ],
+        );
+        let statement = factory.create_expression_statement(define);
+        factory.with_text_range(&statement, accessors[0].loc)
+    }
+
+    /// Gets the object a member is defined on: the class itself or its prototype
+    fn create_member_owner(&self, class_name: &str, is_static: bool) -> Arc<Node> {
+        let class = self.factory.create_identifier(class_name);
+        if is_static {
+            class
+        } else {
+            self.factory.create_property_access(class, "prototype")
+        }
+    }
+
+    /// Creates the access of a member named `name` on `owner`: `owner.name`, or
+    /// `owner[name]` for names that are not identifiers
+    fn create_member_target(&mut self, owner: Arc<Node>, name: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        match name.kind {
+            SyntaxKind::Identifier | SyntaxKind::PrivateIdentifier => factory.create(
+                SyntaxKind::PropertyAccessExpression,
+                PropertyAccessExpression {
+                    expression: owner,
+                    question_dot_token: None,
+                    name: factory.clone_node(name),
+                },
+            ),
+            _ => {
+                let argument = match name.kind {
+                    SyntaxKind::ComputedPropertyName => {
+                        self.visit_node(name.expression().expect("computed names have expressions"))
+                    }
+                    _ => factory.clone_node(name),
+                };
+                factory.create(
+                    SyntaxKind::ElementAccessExpression,
+                    ElementAccessExpression {
+                        expression: owner,
+                        question_dot_token: None,
+                        argument_expression: argument,
+                    },
+                )
+            }
+        }
+    }
+}
+
+/// A member that is assigned in the closure of a lowered class
+enum ClassMember {
+    Method(Arc<Node>),
+    /// The index of a group of accessors of the same property
+    Accessors(usize),
+}
+
+/// Gets a key that identifies the property a name declares, if it is not computed
+fn property_name_key(name: &Node) -> Option<String> {
+    match name.kind {
+        SyntaxKind::ComputedPropertyName => None,
+        _ => Some(name.text().to_string()),
+    }
+}
@@ -0,0 +1,616 @@
+//! Giving each iteration of a loop its own copy of the variables its closures capture
+//!
+//! `let` and `const` declarations become `var` declarations, which every closure created in
+//! a loop would share. When a closure in a loop refers to a block-scoped variable declared
+//! by the loop, the body of the loop becomes a function called on each iteration, with the
+//! variables of the loop's head as its parameters:
+//!
+//! ```js
+//! var _loop_1 = function (i) {
+//!     fns.push(function () { return i; });
+//! };
+//! for (var i = 0; i < 3; i++) {
+//!     _loop_1(i);
+//! }
+//! ```
+//!
+//! A `break` or `continue` that leaves the body returns a string naming the jump, and a
+//! `return` returns its value wrapped in an object, which the loop acts on after the call.
+//! A variable of the loop's head that the body assigns to is copied out before the function
+//! returns, so that the next iteration sees the new value, and the `var` declarations of the
+//! body are declared in the function around the loop instead.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    BreakStatement, ContinueStatement, EmptyStatement, FunctionExpression, IfStatement,
+    TypeOfExpression, YieldExpression,
+};
+use crate::compiler::ast::utilities::is_function_like_declaration_kind;
+use crate::compiler::ast::visitor::{NodeVisitor, for_each_descendant};
+use crate::compiler::ast::{Node, SyntaxKind};
+
+use super::super::{block_scoped_binding_names, collect_block_scoped_names, is_let_or_const};
+use super::{Es2015Transformer, FunctionKind};
+
+/// A loop whose body is turned into a function once the visitor reaches it
+pub(super) struct LoopConversion {
+    /// The loop statement, without its labels
+    statement: Arc<Node>,
+    body: Arc<Node>,
+    /// The labels of the loop statement
+    labels: Vec<String>,
+}
+
+/// The state of a loop body that is being visited as a function of its own
+#[derive(Default)]
+pub(super) struct LoopBody {
+    /// The labels of the converted loop, which jumps to refer to the loop itself
+    labels: Vec<String>,
+    /// The labels declared within the body around the node being visited
+    inner_labels: Vec<String>,
+    /// How many `switch` statements within the body the node being visited is in
+    pub(super) switch_depth: usize,
+    /// The variables of the loop's head that are copied out, and the names they are copied to
+    copy_out: Vec<(String, String)>,
+    has_break: bool,
+    has_return: bool,
+    /// The labeled jumps to statements around the loop, by kind and label
+    labeled_jumps: Vec<(SyntaxKind, String)>,
+}
+
+impl Es2015Transformer<'_> {
+    /// Visits a loop statement, possibly labeled, whose body must be converted to a function,
+    /// returning the declaration of the function followed by the visited loop
+    pub(super) fn visit_converted_loop(&mut self, node: &Arc<Node>) -> Option<Vec<Arc<Node>>> {
+        let mut labels = Vec::new();
+        let mut statement = node;
+        while statement.kind == SyntaxKind::LabeledStatement {
+            let data = statement.as_labeled_statement();
+            labels.push(data.label.text().to_string());
+            statement = &data.statement;
+        }
+        let body = loop_body(statement)?;
+        let is_pending = self
+            .loop_conversions
+            .iter()
+            .any(|conversion| Arc::ptr_eq(&conversion.statement, statement));
+        if is_pending || !should_convert_loop(statement) {
+            return None;
+        }
+        self.loop_conversions.push(LoopConversion {
+            statement: Arc::clone(statement),
+            body: Arc::clone(body),
+            labels,
+        });
+        let visited = self.visit_node(node);
+        let mut statements = std::mem::take(&mut self.loop_declarations);
+        statements.push(visited);
+        Some(statements)
+    }
+
+    /// Converts the body of a loop being converted when the visitor reaches it
+    pub(super) fn visit_loop_body(&mut self, node: &Arc<Node>) -> Option<Arc<Node>> {
+        let index = self
+            .loop_conversions
+            .iter()
+            .position(|conversion| Arc::ptr_eq(&conversion.body, node))?;
+        let conversion = self.loop_conversions.remove(index);
+        Some(self.convert_loop_body(node, conversion))
+    }
+
+    /// Turns the body of a loop into a function declared before the loop, returning the body
+    /// that calls it:
+    ///
+    /// ```js
+    /// var state_1 = _loop_1(i);
+    /// if (typeof state_1 === "object")
+    ///     return state_1.value;
+    /// if (state_1 === "break")
+    ///     break;
+    /// ```
+    fn convert_loop_body(&mut self, body: &Arc<Node>, conversion: LoopConversion) -> Arc<Node> {
+        let factory = self.factory;
+        let statement = &conversion.statement;
+        let head_names = loop_head_names(statement);
+        let parameters: Vec<String> = head_names
+            .iter()
+            .map(|name| self.resolve(name).unwrap_or_else(|| name.clone()))
+            .collect();
+        let copy_out: Vec<(String, String)> = if statement.kind == SyntaxKind::ForStatement {
+            head_names
+                .iter()
+                .zip(&parameters)
+                .filter(|(name, _)| is_assigned_in(body, name))
+                .map(|(_, parameter)| {
+                    let out_name = self.context.create_unique_name(&format!("out_{parameter}"));
+                    (parameter.clone(), out_name)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let is_generator = contains_yield(body);
+
+        self.enter_function(FunctionKind::Arrow, Vec::new());
+        self.current_function().loop_body = Some(LoopBody {
+            labels: conversion.labels,
+            copy_out: copy_out.clone(),
+            ..LoopBody::default()
+        });
+        let visited = self.visit_node(body);
+        let mut statements = if visited.kind == SyntaxKind::Block {
+            visited.as_block().statements.nodes.clone()
+        } else {
+            vec![visited]
+        };
+        statements.extend(create_copy_out_statements(self, &copy_out));
+        let mut state = self.exit_function();
+        let loop_body = state.loop_body.take().expect("the loop body was entered");
+        let statements = self.add_function_prologue(statements, &state, Vec::new());
+
+        let function = factory.create(
+            SyntaxKind::FunctionExpression,
+            FunctionExpression {
+                modifiers: None,
+                asterisk_token: is_generator
+                    .then(|| factory.create_token(SyntaxKind::AsteriskToken)),
+                name: None,
+                type_parameters: None,
+                parameters: factory.create_node_list(
+                    parameters
+                        .iter()
+                        .map(|name| factory.create_parameter(factory.create_identifier(name)))
+                        .collect(),
+                ),
+                type_node: None,
+                body: factory.create_block(statements, true),
+            },
+        );
+        let function_name = self.context.create_unique_name("_loop");
+        self.loop_declarations
+            .push(
+                factory.create_variable_statement(vec![factory.create_variable_declaration(
+                    factory.create_identifier(function_name.as_str()),
+                    Some(function),
+                )]),
+            );
+        if !copy_out.is_empty() {
+            self.loop_declarations.push(
+                factory.create_variable_statement(
+                    copy_out
+                        .iter()
+                        .map(|(_, out_name)| {
+                            factory.create_variable_declaration(
+                                factory.create_identifier(out_name.as_str()),
+                                None,
+                            )
+                        })
+                        .collect(),
+                ),
+            );
+        }
+
+        let mut call = factory.create_call(
+            factory.create_identifier(function_name),
+            parameters
+                .iter()
+                .map(|name| factory.create_identifier(name.as_str()))
+                .collect(),
+        );
+        if is_generator {
+            call = factory.create(
+                SyntaxKind::YieldExpression,
+                YieldExpression {
+                    asterisk_token: Some(factory.create_token(SyntaxKind::AsteriskToken)),
+                    expression: Some(call),
+                },
+            );
+        }
+        let has_exits =
+            loop_body.has_break || loop_body.has_return || !loop_body.labeled_jumps.is_empty();
+        let mut body_statements = Vec::new();
+        let state_name = if has_exits {
+            let state_name = self.context.create_unique_name("state");
+            body_statements.push(factory.create_variable_statement(vec![
+                factory.create_variable_declaration(
+                    factory.create_identifier(state_name.as_str()),
+                    Some(call),
+                ),
+            ]));
+            state_name
+        } else {
+            body_statements.push(factory.create_expression_statement(call));
+            String::new()
+        };
+        for (name, out_name) in &copy_out {
+            body_statements.push(
+                factory.create_expression_statement(factory.create_assignment(
+                    factory.create_identifier(name.as_str()),
+                    factory.create_identifier(out_name.as_str()),
+                )),
+            );
+        }
+        let state = || factory.create_identifier(state_name.as_str());
+        if loop_body.has_return {
+            let condition = factory.create_binary(
+                factory.create(
+                    SyntaxKind::TypeOfExpression,
+                    TypeOfExpression {
+                        expression: state(),
+                    },
+                ),
+                SyntaxKind::EqualsEqualsEqualsToken,
+                factory.create_string_literal("object"),
+            );
+            // A loop within another converted loop passes the returned object on
+            let exit = match self.create_loop_exit(state()) {
+                Some(statements) => statements,
+                None => vec![
+                    factory.create_return(Some(factory.create_property_access(state(), "value"))),
+                ],
+            };
+            body_statements.push(create_if(self, condition, exit));
+        }
+        if loop_body.has_break {
+            let condition = factory.create_binary(
+                state(),
+                SyntaxKind::EqualsEqualsEqualsToken,
+                factory.create_string_literal("break"),
+            );
+            let jump = factory.create(SyntaxKind::BreakStatement, BreakStatement { label: None });
+            body_statements.push(create_if(self, condition, vec![jump]));
+        }
+        for (kind, label) in &loop_body.labeled_jumps {
+            let condition = factory.create_binary(
+                state(),
+                SyntaxKind::EqualsEqualsEqualsToken,
+                factory.create_string_literal(jump_name(*kind, Some(label))),
+            );
+            let label = Some(factory.create_identifier(label.as_str()));
+            let jump = if *kind == SyntaxKind::BreakStatement {
+                factory.create(SyntaxKind::BreakStatement, BreakStatement { label })
+            } else {
+                factory.create(SyntaxKind::ContinueStatement, ContinueStatement { label })
+            };
+            let exit = self.visit_loop_exit(&jump).unwrap_or(vec![jump]);
+            body_statements.push(create_if(self, condition, exit));
+        }
+        factory.with_text_range(&factory.create_block(body_statements, true), body.loc)
+    }
+
+    /// Turns a `break`, `continue`, or `return` that leaves the loop body being visited into
+    /// a `return` from the function the body became, after copying out the loop's variables
+    pub(super) fn visit_loop_exit(&mut self, node: &Arc<Node>) -> Option<Vec<Arc<Node>>> {
+        let factory = self.factory;
+        let function = self.functions.last()?;
+        let loop_body = function.loop_body.as_ref()?;
+        let value = match node.kind {
+            SyntaxKind::BreakStatement | SyntaxKind::ContinueStatement => {
+                let is_break = node.kind == SyntaxKind::BreakStatement;
+                let label = match node.kind {
+                    SyntaxKind::BreakStatement => node.as_break_statement().label.as_ref(),
+                    _ => node.as_continue_statement().label.as_ref(),
+                }
+                .map(|label| label.text().to_string());
+                let targets_loop = match &label {
+                    // A jump without a label targets the innermost loop, or for `break`, the
+                    // innermost `switch`
+                    None => {
+                        if function.loop_depth > 0 || is_break && loop_body.switch_depth > 0 {
+                            return None;
+                        }
+                        true
+                    }
+                    Some(label) if loop_body.inner_labels.contains(label) => return None,
+                    Some(label) => loop_body.labels.contains(label),
+                };
+                let label = if targets_loop { None } else { label };
+                let loop_body = self.current_loop_body();
+                match &label {
+                    Some(label) => {
+                        let jump = (node.kind, label.clone());
+                        if !loop_body.labeled_jumps.contains(&jump) {
+                            loop_body.labeled_jumps.push(jump);
+                        }
+                    }
+                    None if is_break => loop_body.has_break = true,
+                    None => {}
+                }
+                factory.create_string_literal(jump_name(node.kind, label.as_deref()))
+            }
+            _ => {
+                let expression = node.as_return_statement().expression.as_ref();
+                let value = match expression {
+                    Some(expression) => self.visit_node(expression),
+                    None => factory.create_void_zero(),
+                };
+                self.current_loop_body().has_return = true;
+                factory.create_object_literal(
+                    vec![
+                        factory
+                            .create_property_assignment(factory.create_identifier("value"), value),
+                    ],
+                    false,
+                )
+            }
+        };
+        let copy_out = self.current_loop_body().copy_out.clone();
+        let mut statements = create_copy_out_statements(self, &copy_out);
+        statements.push(factory.with_text_range(&factory.create_return(Some(value)), node.loc));
+        Some(statements)
+    }
+
+    /// Creates the statements that return the state of an inner converted loop from the
+    /// loop body being visited, if there is one
+    fn create_loop_exit(&mut self, state: Arc<Node>) -> Option<Vec<Arc<Node>>> {
+        self.functions.last()?.loop_body.as_ref()?;
+        let loop_body = self.current_loop_body();
+        loop_body.has_return = true;
+        let copy_out = loop_body.copy_out.clone();
+        let mut statements = create_copy_out_statements(self, &copy_out);
+        statements.push(self.factory.create_return(Some(state)));
+        Some(statements)
+    }
+
+    fn current_loop_body(&mut self) -> &mut LoopBody {
+        self.current_function()
+            .loop_body
+            .as_mut()
+            .expect("a loop body is being visited")
+    }
+
+    /// Visits a labeled statement within a loop body, where jumps to the label stay within
+    /// the body
+    pub(super) fn visit_labeled_statement_in_loop_body(
+        &mut self,
+        label: &str,
+        statement: &Arc<Node>,
+    ) -> Arc<Node> {
+        let in_loop_body = self.current_function().loop_body.is_some();
+        if in_loop_body {
+            self.current_loop_body()
+                .inner_labels
+                .push(label.to_string());
+        }
+        let visited = self.visit_node(statement);
+        if in_loop_body {
+            self.current_loop_body().inner_labels.pop();
+        }
+        visited
+    }
+
+    /// Declares the variables of a visited `var` declaration list of a converted loop body in
+    /// the function around the loop, returning the assignments of their initial values
+    pub(super) fn hoist_loop_body_variables(&mut self, list: &Arc<Node>) -> Option<Arc<Node>> {
+        let factory = self.factory;
+        let index = self
+            .functions
+            .iter()
+            .rposition(|function| function.loop_body.is_none())
+            .expect("the source file is not a loop body");
+        let mut assignments = Vec::new();
+        for declaration in list.as_variable_declaration_list().declarations.iter() {
+            let data = declaration.as_variable_declaration();
+            let name = data.name.text().to_string();
+            if !self.functions[index].temps.contains(&name) {
+                self.functions[index].temps.push(name.clone());
+            }
+            if let Some(initializer) = &data.initializer {
+                assignments.push(
+                    factory.create_assignment(
+                        factory.create_identifier(name),
+                        Arc::clone(initializer),
+                    ),
+                );
+            }
+        }
+        let mut assignments = assignments.into_iter();
+        let first = assignments.next()?;
+        Some(assignments.fold(first, |left, right| {
+            factory.create_binary(left, SyntaxKind::CommaToken, right)
+        }))
+    }
+
+    /// Visits a `var` statement of a converted loop body, which assigns the variables that are
+    /// declared around the loop
+    pub(super) fn visit_loop_body_variable_statement(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let list =
+            self.visit_variable_declaration_list(&node.as_variable_statement().declaration_list);
+        match self.hoist_loop_body_variables(&list) {
+            Some(assignments) => {
+                factory.with_text_range(&factory.create_expression_statement(assignments), node.loc)
+            }
+            None => factory.create_at(SyntaxKind::EmptyStatement, node.loc, EmptyStatement {}),
+        }
+    }
+
+    /// Determines if a `var` declaration list is in a converted loop body
+    pub(super) fn is_loop_body_variable_list(&mut self, list: &Node) -> bool {
+        !is_let_or_const(list) && self.current_function().loop_body.is_some()
+    }
+}
+
+/// Turns the statements a node is replaced with into one statement, putting more than one in
+/// a block
+pub(super) fn to_single_statement(
+    factory: &NodeFactory,
+    mut statements: Vec<Arc<Node>>,
+) -> Arc<Node> {
+    if statements.len() == 1 {
+        statements.remove(0)
+    } else {
+        factory.create_block(statements, true)
+    }
+}
+
+/// Creates `if (condition) statement`, with a block for more than one statement
+fn create_if(
+    transformer: &Es2015Transformer<'_>,
+    condition: Arc<Node>,
+    statements: Vec<Arc<Node>>,
+) -> Arc<Node> {
+    let factory = transformer.factory;
+    let then_statement = to_single_statement(&factory, statements);
+    factory.create(
+        SyntaxKind::IfStatement,
+        IfStatement {
+            expression: condition,
+            then_statement,
+            else_statement: None,
+        },
+    )
+}
+
+/// Creates `out_i_1 = i;` for each variable copied out of a loop body
+fn create_copy_out_statements(
+    transformer: &Es2015Transformer<'_>,
+    copy_out: &[(String, String)],
+) -> Vec<Arc<Node>> {
+    let factory = transformer.factory;
+    copy_out
+        .iter()
+        .map(|(name, out_name)| {
+            factory.create_expression_statement(factory.create_assignment(
+                factory.create_identifier(out_name.as_str()),
+                factory.create_identifier(name.as_str()),
+            ))
+        })
+        .collect()
+}
+
+/// Gets the string a loop body function returns for a jump: `"break"`, `"continue"`, or
+/// with a label, `"break-label"` or `"continue-label"`
+fn jump_name(kind: SyntaxKind, label: Option<&str>) -> String {
+    let jump = if kind == SyntaxKind::BreakStatement {
+        "break"
+    } else {
+        "continue"
+    };
+    match label {
+        Some(label) => format!("{jump}-{label}"),
+        None => jump.to_string(),
+    }
+}
+
+/// Gets the body of an iteration statement
+fn loop_body(statement: &Node) -> Option<&Arc<Node>> {
+    match statement.kind {
+        SyntaxKind::ForStatement => Some(&statement.as_for_statement().statement),
+        SyntaxKind::ForInStatement => Some(&statement.as_for_in_statement().statement),
+        SyntaxKind::ForOfStatement => Some(&statement.as_for_of_statement().statement),
+        SyntaxKind::WhileStatement => Some(&statement.as_while_statement().statement),
+        SyntaxKind::DoStatement => Some(&statement.as_do_statement().statement),
+        _ => None,
+    }
+}
+
+/// Gets the names the `let` or `const` declarations in the head of a loop declare
+fn loop_head_names(statement: &Node) -> Vec<String> {
+    let initializer = match statement.kind {
+        SyntaxKind::ForStatement => statement.as_for_statement().initializer.as_ref(),
+        SyntaxKind::ForInStatement => Some(&statement.as_for_in_statement().initializer),
+        SyntaxKind::ForOfStatement => Some(&statement.as_for_of_statement().initializer),
+        _ => None,
+    };
+    initializer
+        .map(block_scoped_binding_names)
+        .unwrap_or_default()
+}
+
+/// Determines if a closure within a loop refers to a block-scoped variable that the loop
+/// declares, in its head or its body, which each iteration must have its own copy of
+fn should_convert_loop(statement: &Arc<Node>) -> bool {
+    if statement.kind == SyntaxKind::ForOfStatement
+        && statement.as_for_of_statement().await_modifier.is_some()
+    {
+        return false;
+    }
+    let mut names = loop_head_names(statement);
+    if let Some(body) = loop_body(statement) {
+        collect_loop_body_names(body, &mut names);
+    }
+    if names.is_empty() {
+        return false;
+    }
+    let names: HashSet<String> = names.into_iter().collect();
+    for_each_descendant(statement, &mut |node| {
+        (is_function_like_declaration_kind(node.kind)
+            || node.kind == SyntaxKind::ClassExpression
+            || node.kind == SyntaxKind::ClassDeclaration)
+            && for_each_descendant(node, &mut |child| {
+                child.kind == SyntaxKind::Identifier && names.contains(child.text())
+            })
+    })
+}
+
+/// Collects the names of the block-scoped declarations in a loop body, outside of the
+/// functions and loops within it
+fn collect_loop_body_names(node: &Arc<Node>, names: &mut Vec<String>) {
+    match node.kind {
+        SyntaxKind::Block => {
+            let statements = &node.as_block().statements;
+            collect_block_scoped_names(statements, names);
+            for statement in statements.iter() {
+                collect_loop_body_names(statement, names);
+            }
+        }
+        SyntaxKind::CaseClause | SyntaxKind::DefaultClause => {
+            if let Some(statements) = node.statements() {
+                collect_block_scoped_names(statements, names);
+                for statement in statements.iter() {
+                    collect_loop_body_names(statement, names);
+                }
+            }
+        }
+        SyntaxKind::IfStatement
+        | SyntaxKind::LabeledStatement
+        | SyntaxKind::TryStatement
+        | SyntaxKind::CatchClause
+        | SyntaxKind::SwitchStatement
+        | SyntaxKind::CaseBlock => {
+            node.for_each_child(&mut |child| {
+                collect_loop_body_names(child, names);
+                false
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Determines if a variable is assigned to within a loop body
+fn is_assigned_in(body: &Node, name: &str) -> bool {
+    let is_name = |node: &Arc<Node>| {
+        let mut node = node;
+        while node.kind == SyntaxKind::ParenthesizedExpression {
+            node = &node.as_parenthesized_expression().expression;
+        }
+        node.kind == SyntaxKind::Identifier && node.text() == name
+    };
+    for_each_descendant(body, &mut |node| match node.kind {
+        SyntaxKind::BinaryExpression => {
+            let data = node.as_binary_expression();
+            data.operator_token.kind.is_assignment_operator() && is_name(&data.left)
+        }
+        SyntaxKind::PrefixUnaryExpression => {
+            let data = node.as_prefix_unary_expression();
+            matches!(
+                data.operator,
+                SyntaxKind::PlusPlusToken | SyntaxKind::MinusMinusToken
+            ) && is_name(&data.operand)
+        }
+        SyntaxKind::PostfixUnaryExpression => is_name(&node.as_postfix_unary_expression().operand),
+        _ => false,
+    })
+}
+
+/// Determines if a loop body yields, outside of the functions within it
+fn contains_yield(node: &Node) -> bool {
+    node.for_each_child(&mut |child| {
+        child.kind == SyntaxKind::YieldExpression
+            || !is_function_like_declaration_kind(child.kind) && contains_yield(child)
+    })
+}
@@ -0,0 +1,414 @@
+//! Lowering ES2017 syntax
+//!
+//! An async function becomes a function that hands a generator to the `__awaiter` helper,
+//! which runs the generator and resumes it with the result of each promise it yields. The
+//! body of the function moves into the generator, where `await` becomes `yield`.
+//!
+//! A generator cannot refer to `super`, so an async method that does declares an object
+//! with a getter for each property of `super` it reads, and the generator reads them
+//! through that object instead.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    ArrowFunction, ElementAccessExpression, FunctionExpression, PropertyAccessExpression,
+    VariableDeclarationList, VariableStatement,
+};
+use crate::compiler::ast::utilities::is_function_like_declaration_kind;
+use crate::compiler::ast::visitor::{NodeVisitor, visit_each_child, visit_nodes};
+use crate::compiler::ast::{Node, NodeFlags, SyntaxKind};
+use crate::compiler::scanner::ScriptTarget;
+
+use super::es2018::{create_yield, without_async_modifier};
+use super::helpers::AWAITER_HELPER;
+use super::{TransformContext, insert_after_prologue};
+
+pub(super) fn transform_es2017(
+    source_file: &Arc<Node>,
+    context: &mut TransformContext,
+) -> Arc<Node> {
+    let mut transformer = Es2017Transformer {
+        factory: NodeFactory::new(),
+        context,
+        in_async_body: false,
+        super_access: None,
+        super_name: None,
+        super_index_name: None,
+    };
+    let factory = transformer.factory;
+    visit_each_child(source_file, &mut transformer, &factory)
+}
+
+struct Es2017Transformer<'a> {
+    factory: NodeFactory,
+    context: &'a mut TransformContext,
+    /// Whether the node being visited is in the body of an async function
+    in_async_body: bool,
+    /// The accesses to `super` in the async method being visited, if the output must make
+    /// them through a `_super` object
+    super_access: Option<SuperAccess>,
+    /// The name of the object with a getter for each property of `super`, once reserved
+    super_name: Option<String>,
+    /// The name of the function that reads a computed property of `super`, once reserved
+    super_index_name: Option<String>,
+}
+
+/// The properties of `super` that the body of an async method reads
+#[derive(Default)]
+struct SuperAccess {
+    /// The names of the properties read with `super.name`, in order of first use
+    property_names: Vec<String>,
+    /// Whether the body reads `super[expression]`
+    has_element_access: bool,
+}
+
+impl NodeVisitor for Es2017Transformer<'_> {
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        match node.kind {
+            K::FunctionDeclaration
+            | K::FunctionExpression
+            | K::MethodDeclaration
+            | K::ArrowFunction
+                if node.has_modifier(K::AsyncKeyword) && node.body().is_some() =>
+            {
+                self.visit_async_function(node)
+            }
+            // Arrow functions share `super` with the function around them
+            K::ArrowFunction => visit_each_child(node, self, &factory),
+            kind if is_function_like_declaration_kind(kind) => {
+                let in_async_body = std::mem::replace(&mut self.in_async_body, false);
+                let super_access = self.super_access.take();
+                let visited = visit_each_child(node, self, &factory);
+                self.in_async_body = in_async_body;
+                self.super_access = super_access;
+                visited
+            }
+            K::AwaitExpression if self.in_async_body => {
+                let expression = self.visit_node(&node.as_await_expression().expression);
+                let lowered = create_yield(&factory, false, expression);
+                factory.with_text_range(&lowered, node.loc)
+            }
+            K::PropertyAccessExpression | K::ElementAccessExpression
+                if self.super_access.is_some() && is_super_property(node) =>
+            {
+                self.visit_super_property(node)
+            }
+            K::CallExpression
+                if self.super_access.is_some()
+                    && is_super_property(&node.as_call_expression().expression) =>
+            {
+                self.visit_super_property_call(node)
+            }
+            _ => visit_each_child(node, self, &factory),
+        }
+    }
+}
+
+impl Es2017Transformer<'_> {
+    /// Turns `async function f(a) { body }` into
+    /// `function f(a) { return __awaiter(this, void 0, void 0, function* () { body }); }`
+    ///
+    /// The second argument is `arguments` when the body uses it, so that it refers to the
+    /// arguments of the original function from within the generator.
+    fn visit_async_function(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let is_arrow = node.kind == SyntaxKind::ArrowFunction;
+        let parameters = visit_nodes(
+            node.parameters().expect("functions have parameters"),
+            self,
+            &factory,
+        );
+
+        let in_async_body = std::mem::replace(&mut self.in_async_body, true);
+        // The generator of an arrow function is nested in the function around the arrow, so
+        // its reads of `super` go through the same object
+        let outer_super_access = match node.kind {
+            SyntaxKind::ArrowFunction => None,
+            SyntaxKind::MethodDeclaration if self.context.target >= ScriptTarget::ES2015 => {
+                Some(self.super_access.replace(SuperAccess::default()))
+            }
+            _ => Some(self.super_access.take()),
+        };
+        let body = node.body().expect("the async function has a body");
+        let statements = if body.kind == SyntaxKind::Block {
+            visit_nodes(&body.as_block().statements, self, &factory).nodes
+        } else {
+            vec![factory.create_return(Some(self.visit_node(body)))]
+        };
+        self.in_async_body = in_async_body;
+        let super_access =
+            outer_super_access.and_then(|outer| std::mem::replace(&mut self.super_access, outer));
+
+        let generator = factory.create(
+            SyntaxKind::FunctionExpression,
+            FunctionExpression {
+                modifiers: None,
+                asterisk_token: Some(factory.create_token(SyntaxKind::AsteriskToken)),
+                name: None,
+                type_parameters: None,
+                parameters: factory.create_node_list(Vec::new()),
+                type_node: None,
+                body: factory.create_block(statements, true),
+            },
+        );
+        let arguments = if !is_arrow && uses_arguments(body) {
+            factory.create_identifier("arguments")
+        } else {
+            factory.create_void_zero()
+        };
+        self.context.request_helper(&AWAITER_HELPER);
+        let awaiter = factory.create_call(
+            factory.create_identifier("__awaiter"),
+            vec![
+                factory.create_token(SyntaxKind::ThisKeyword),
+                arguments,
+                factory.create_void_zero(),
+                generator,
+            ],
+        );
+
+        let modifiers = without_async_modifier(&factory, node.modifiers());
+        if is_arrow {
+            let data = node.as_arrow_function();
+            return factory.update_arrow_function(
+                node,
+                modifiers,
+                None,
+                parameters,
+                None,
+                Arc::clone(&data.equals_greater_than_token),
+                awaiter,
+            );
+        }
+
+        let mut statements = vec![factory.create_return(Some(awaiter))];
+        if let Some(access) = super_access {
+            insert_after_prologue(&mut statements, self.create_super_declarations(access));
+        }
+        let body = factory.update_block(body, factory.create_node_list(statements), true);
+        match node.kind {
+            SyntaxKind::FunctionDeclaration => factory.update_function_declaration(
+                node,
+                modifiers,
+                None,
+                node.name().cloned(),
+                None,
+                parameters,
+                None,
+                Some(body),
+            ),
+            SyntaxKind::FunctionExpression => factory.update_function_expression(
+                node,
+                modifiers,
+                None,
+                node.name().cloned(),
+                None,
+                parameters,
+                None,
+                body,
+            ),
+            _ => {
+                let data = node.as_method_declaration();
+                factory.update_method_declaration(
+                    node,
+                    modifiers,
+                    None,
+                    self.visit_node(&data.name),
+                    data.postfix_token.clone(),
+                    None,
+                    parameters,
+                    None,
+                    Some(body),
+                )
+            }
+        }
+    }
+
+    fn super_name(&mut self) -> String {
+        if self.super_name.is_none() {
+            self.super_name = Some(self.context.reserve_name("_super"));
+        }
+        self.super_name.clone().expect("the name was just reserved")
+    }
+
+    fn super_index_name(&mut self) -> String {
+        if self.super_index_name.is_none() {
+            self.super_index_name = Some(self.context.reserve_name("_superIndex"));
+        }
+        self.super_index_name
+            .clone()
+            .expect("the name was just reserved")
+    }
+
+    /// Turns `super.name` into `_super.name`, and `super[expression]` into
+    /// `_superIndex(expression)`
+    fn visit_super_property(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let access = self
+            .super_access
+            .as_mut()
+            .expect("super properties are replaced within async methods");
+        let replacement = if node.kind == SyntaxKind::PropertyAccessExpression {
+            let name = &node.as_property_access_expression().name;
+            if !access.property_names.iter().any(|read| read == name.text()) {
+                access.property_names.push(name.text().to_string());
+            }
+            factory.create(
+                SyntaxKind::PropertyAccessExpression,
+                PropertyAccessExpression {
+                    expression: factory.create_identifier(self.super_name()),
+                    question_dot_token: None,
+                    name: Arc::clone(name),
+                },
+            )
+        } else {
+            access.has_element_access = true;
+            let argument =
+                self.visit_node(&node.as_element_access_expression().argument_expression);
+            factory.create_call(
+                factory.create_identifier(self.super_index_name()),
+                vec![argument],
+            )
+        };
+        factory.with_text_range(&replacement, node.loc)
+    }
+
+    /// Turns `super.name(a)` into `_super.name.call(this, a)`
+    fn visit_super_property_call(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_call_expression();
+        let callee = self.visit_super_property(&data.expression);
+        let mut arguments = vec![factory.create_token(SyntaxKind::ThisKeyword)];
+        arguments.extend(visit_nodes(&data.arguments, self, &factory).nodes);
+        factory.update_call(
+            node,
+            factory.create_property_access(callee, "call"),
+            None,
+            None,
+            factory.create_node_list(arguments),
+        )
+    }
+
+    /// Declares what an async method needs to read `super` from its generator:
+    ///
+    /// ```js
+    /// const _superIndex = name => super[name];
+    /// const _super = Object.create(null, {
+    ///     a: { get: () => super.a }
+    /// });
+    /// ```
+    fn create_super_declarations(&mut self, access: SuperAccess) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let arrow = |parameters: Vec<Arc<Node>>, body: Arc<Node>| {
+            factory.create(
+                SyntaxKind::ArrowFunction,
+                ArrowFunction {
+                    modifiers: None,
+                    type_parameters: None,
+                    parameters: factory.create_node_list(parameters),
+                    type_node: None,
+                    equals_greater_than_token: factory
+                        .create_token(SyntaxKind::EqualsGreaterThanToken),
+                    body,
+                },
+            )
+        };
+        let const_statement = |name: String, initializer: Arc<Node>| {
+            let declaration_list = factory.create_with_flags(
+                SyntaxKind::VariableDeclarationList,
+                NodeFlags::CONST,
+                VariableDeclarationList {
+                    declarations: factory.create_node_list(vec![
+                        factory.create_variable_declaration(
+                            factory.create_identifier(name),
+                            Some(initializer),
+                        ),
+                    ]),
+                },
+            );
+            factory.create(
+                SyntaxKind::VariableStatement,
+                VariableStatement {
+                    modifiers: None,
+                    declaration_list,
+                },
+            )
+        };
+
+        let mut statements = Vec::new();
+        if access.has_element_access {
+            let index = factory.create(
+                SyntaxKind::ElementAccessExpression,
+                ElementAccessExpression {
+                    expression: factory.create_token(SyntaxKind::SuperKeyword),
+                    question_dot_token: None,
+                    argument_expression: factory.create_identifier("name"),
+                },
+            );
+            let reader = arrow(
+                vec![factory.create_parameter(factory.create_identifier("name"))],
+                index,
+            );
+            statements.push(const_statement(self.super_index_name(), reader));
+        }
+        if !access.property_names.is_empty() {
+            let getters = access
+                .property_names
+                .iter()
+                .map(|name| {
+                    let read = factory.create_property_access(
+                        factory.create_token(SyntaxKind::SuperKeyword),
+                        name,
+                    );
+                    let getter = factory.create_object_literal(
+                        vec![factory.create_property_assignment(
+                            factory.create_identifier("get"),
+                            arrow(Vec::new(), read),
+                        )],
+                        false,
+                    );
+                    factory.create_property_assignment(factory.create_identifier(name), getter)
+                })
+                .collect();
+            let object = factory.create_call(
+                factory.create_property_access(factory.create_identifier("Object"), "create"),
+                vec![
+                    factory.create_token(SyntaxKind::NullKeyword),
+                    factory.create_object_literal(getters, true),
+                ],
+            );
+            statements.push(const_statement(self.super_name(), object));
+        }
+        statements
+    }
+}
+
+fn is_super_property(node: &Node) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression
+    ) && node
+        .expression()
+        .is_some_and(|expression| expression.kind == SyntaxKind::SuperKeyword)
+}
+
+/// Whether a function body refers to `arguments`, looking into the arrow functions within it
+/// but not the other functions, which have arguments of their own
+fn uses_arguments(body: &Node) -> bool {
+    fn visit(node: &Arc<Node>) -> bool {
+        match node.kind {
+            SyntaxKind::Identifier => node.text() == "arguments",
+            SyntaxKind::PropertyAccessExpression => {
+                visit(&node.as_property_access_expression().expression)
+            }
+            SyntaxKind::ArrowFunction => node.for_each_child(&mut visit),
+            kind if is_function_like_declaration_kind(kind) => false,
+            SyntaxKind::ClassDeclaration | SyntaxKind::ClassExpression => false,
+            _ => node.for_each_child(&mut visit),
+        }
+    }
+    body.for_each_child(&mut visit)
+}
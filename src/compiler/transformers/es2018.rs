@@ -0,0 +1,916 @@
+//! Lowering ES2018 syntax
+//!
+//! Spread properties in object literals become calls to the `__assign` helper, which copies
+//! the own enumerable properties of each operand in turn. Object patterns with a rest element
+//! are flattened, in declarations, parameters, loops, `catch` clauses, and assignments, up to
+//! the rest element, which becomes a call to the `__rest` helper.
+//!
+//! An async generator becomes a function that hands a plain generator to the
+//! `__asyncGenerator` helper. The generator yields each value it waits for wrapped with
+//! `__await`, so `await a` becomes `yield __await(a)`, and `yield a` becomes
+//! `yield yield __await(a)`, since an async generator also waits for the values it yields.
+//!
+//! A `for await` loop steps through the iterator that `__asyncValues` gets for the iterated
+//! value, closing it when the loop exits early.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    AwaitExpression, CatchClause, ForStatement, FunctionExpression, IfStatement, LabeledStatement,
+    PrefixUnaryExpression, ThrowStatement, TryStatement, VariableDeclarationList,
+    VariableStatement, YieldExpression,
+};
+use crate::compiler::ast::utilities::{is_function_like_declaration_kind, skip_parentheses};
+use crate::compiler::ast::visitor::{NodeVisitor, visit_each_child, visit_nodes};
+use crate::compiler::ast::{ModifierList, Node, SyntaxKind};
+
+use super::destructuring::{
+    DestructuringHost, FlattenLevel, contains_object_rest, flatten_destructuring_assignment,
+    flatten_destructuring_binding, is_destructuring_assignment,
+};
+use super::helpers::{
+    ASSIGN_HELPER, ASYNC_DELEGATOR_HELPER, ASYNC_GENERATOR_HELPER, ASYNC_VALUES_HELPER,
+    AWAIT_HELPER,
+};
+use super::{
+    TransformContext, add_variable_declarations, insert_after_prologue, prepend_to_block,
+    prepend_to_function_body, update_function_body, update_statements,
+};
+
+pub(super) fn transform_es2018(
+    source_file: &Arc<Node>,
+    context: &mut TransformContext,
+) -> Arc<Node> {
+    let mut transformer = Es2018Transformer {
+        factory: NodeFactory::new(),
+        context,
+        functions: vec![FunctionScope::default()],
+    };
+    let factory = transformer.factory;
+    let data = source_file.as_source_file();
+    let mut statements = visit_nodes(&data.statements, &mut transformer, &factory).nodes;
+    let scope = transformer
+        .functions
+        .pop()
+        .expect("the file is the outermost scope");
+    if !scope.hoisted.is_empty() {
+        let declarations = scope
+            .hoisted
+            .iter()
+            .map(|name| factory.create_variable_declaration(factory.create_identifier(name), None))
+            .collect();
+        insert_after_prologue(
+            &mut statements,
+            vec![factory.create_variable_statement(declarations)],
+        );
+    }
+    update_statements(&factory, source_file, statements)
+}
+
+struct Es2018Transformer<'a> {
+    factory: NodeFactory,
+    context: &'a mut TransformContext,
+    /// The functions around the node being visited, innermost last, after the file itself
+    functions: Vec<FunctionScope>,
+}
+
+/// A function, or the file, that temporary variables are declared in
+#[derive(Default)]
+struct FunctionScope {
+    /// Whether the function is the generator that replaces the body of an async generator
+    is_async_generator: bool,
+    /// The temporary variables to declare at the top of the function
+    hoisted: Vec<String>,
+    /// The names generated within the function so far
+    generated_names: HashSet<String>,
+}
+
+impl NodeVisitor for Es2018Transformer<'_> {
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        match node.kind {
+            K::ExpressionStatement => {
+                let expression = &node.as_expression_statement().expression;
+                factory
+                    .update_expression_statement(node, self.visit_discarded_expression(expression))
+            }
+            K::BinaryExpression if has_object_rest_assignment(node) => {
+                let data = node.as_binary_expression();
+                let value = self.visit_node(&data.right);
+                let flattened = flatten_destructuring_assignment(
+                    self,
+                    &data.left,
+                    value,
+                    true,
+                    FlattenLevel::ObjectRest,
+                );
+                factory.with_text_range(&flattened, node.loc)
+            }
+            K::VariableDeclarationList => self.visit_variable_declaration_list(node),
+            K::ForOfStatement | K::ForInStatement
+                if contains_object_rest_binding(loop_initializer(node)) =>
+            {
+                self.visit_loop_with_object_rest(node)
+            }
+            K::CatchClause
+                if node
+                    .as_catch_clause()
+                    .variable_declaration
+                    .as_ref()
+                    .is_some_and(|declaration| {
+                        contains_object_rest(&declaration.as_variable_declaration().name)
+                    }) =>
+            {
+                self.visit_catch_clause_with_object_rest(node)
+            }
+            K::ObjectLiteralExpression
+                if node
+                    .as_object_literal_expression()
+                    .properties
+                    .iter()
+                    .any(|property| property.kind == K::SpreadAssignment) =>
+            {
+                self.visit_object_literal_with_spread(node)
+            }
+            _ if is_async_generator(node) => self.visit_async_generator(node),
+            kind if is_function_like_declaration_kind(kind) && node.body().is_some() => {
+                self.visit_function(node)
+            }
+            K::AwaitExpression if self.in_async_generator() => {
+                let expression = self.visit_node(&node.as_await_expression().expression);
+                let awaited = self.create_await(expression);
+                factory.with_text_range(&awaited, node.loc)
+            }
+            K::YieldExpression if self.in_async_generator() => self.visit_yield_expression(node),
+            K::ReturnStatement if self.in_async_generator() => {
+                let expression = match &node.as_return_statement().expression {
+                    Some(expression) => self.visit_node(expression),
+                    None => factory.create_void_zero(),
+                };
+                factory.update_return(node, Some(self.create_await(expression)))
+            }
+            K::ForOfStatement if node.as_for_of_statement().await_modifier.is_some() => {
+                self.visit_for_await_statement(node, None)
+            }
+            K::LabeledStatement
+                if is_for_await_statement(&node.as_labeled_statement().statement) =>
+            {
+                let data = node.as_labeled_statement();
+                self.visit_for_await_statement(&data.statement, Some(&data.label))
+            }
+            _ => visit_each_child(node, self, &factory),
+        }
+    }
+}
+
+impl Es2018Transformer<'_> {
+    fn current_function(&mut self) -> &mut FunctionScope {
+        self.functions
+            .last_mut()
+            .expect("the file is the outermost scope")
+    }
+
+    fn in_async_generator(&self) -> bool {
+        self.functions
+            .last()
+            .is_some_and(|scope| scope.is_async_generator)
+    }
+
+    fn create_temp_name(&mut self) -> String {
+        let scope = self
+            .functions
+            .last_mut()
+            .expect("the file is the outermost scope");
+        self.context.create_temp_name(&mut scope.generated_names)
+    }
+
+    /// Creates a temporary variable declared at the top of the current function
+    fn create_hoisted_temp(&mut self) -> String {
+        let name = self.create_temp_name();
+        self.current_function().hoisted.push(name.clone());
+        name
+    }
+
+    /// Visits a function other than an async generator, declaring the temporary variables
+    /// created for its body at the top of it
+    ///
+    /// A parameter whose pattern has an object rest element becomes a temporary variable,
+    /// which the start of the body destructures: `function f({ a, ...r }) {}` becomes
+    /// `function f(_a) { var { a } = _a, r = __rest(_a, ["a"]); }`.
+    fn visit_function(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        self.functions.push(FunctionScope::default());
+        let parameters = node.parameters().expect("functions have parameters");
+        let temps: Vec<Option<String>> = parameters
+            .iter()
+            .map(|parameter| {
+                contains_object_rest(&parameter.as_parameter().name)
+                    .then(|| self.create_temp_name())
+            })
+            .collect();
+        let visited = visit_each_child(node, self, &factory);
+        let mut replacements = Vec::new();
+        let mut statements = Vec::new();
+        let visited_parameters = visited.parameters().expect("functions have parameters");
+        for ((parameter, visited_parameter), temp) in
+            parameters.iter().zip(visited_parameters.iter()).zip(temps)
+        {
+            let Some(temp) = temp else {
+                continue;
+            };
+            let data = parameter.as_parameter();
+            let declarations = flatten_destructuring_binding(
+                self,
+                &data.name,
+                factory.create_identifier(temp.as_str()),
+                data.initializer.as_ref(),
+                FlattenLevel::ObjectRest,
+            );
+            statements.push(factory.create_variable_statement(declarations));
+            let replacement = factory.update_parameter(
+                visited_parameter,
+                data.modifiers.clone(),
+                data.dot_dot_dot_token.clone(),
+                factory.create_identifier(temp),
+                None,
+                None,
+                None,
+            );
+            replacements.push((Arc::clone(visited_parameter), replacement));
+        }
+        let scope = self
+            .functions
+            .pop()
+            .expect("the function's scope was pushed");
+        if scope.hoisted.is_empty() && statements.is_empty() {
+            return visited;
+        }
+        if !scope.hoisted.is_empty() {
+            let declarations = scope
+                .hoisted
+                .iter()
+                .map(|name| {
+                    factory.create_variable_declaration(factory.create_identifier(name), None)
+                })
+                .collect();
+            statements.insert(0, factory.create_variable_statement(declarations));
+        }
+        let body = visited.body().expect("the function has a body");
+        let body = prepend_to_function_body(&factory, body, statements);
+        update_function_body(&factory, &visited, replacements, body)
+    }
+
+    /// Visits an expression whose value is not used, where a destructuring assignment need
+    /// not evaluate to the value it destructures
+    fn visit_discarded_expression(&mut self, expression: &Arc<Node>) -> Arc<Node> {
+        let inner = skip_parentheses(expression);
+        if !has_object_rest_assignment(&inner) {
+            return self.visit_node(expression);
+        }
+        let data = inner.as_binary_expression();
+        let value = self.visit_node(&data.right);
+        let flattened = flatten_destructuring_assignment(
+            self,
+            &data.left,
+            value,
+            false,
+            FlattenLevel::ObjectRest,
+        );
+        self.factory.with_text_range(&flattened, expression.loc)
+    }
+
+    /// Flattens the declarations whose patterns have object rest elements:
+    /// `const { a, ...r } = o` becomes `const { a } = o, r = __rest(o, ["a"])`
+    fn visit_variable_declaration_list(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_variable_declaration_list();
+        let mut declarations = Vec::new();
+        for declaration in data.declarations.iter() {
+            let declaration_data = declaration.as_variable_declaration();
+            match &declaration_data.initializer {
+                Some(initializer) if contains_object_rest(&declaration_data.name) => {
+                    let value = self.visit_node(initializer);
+                    declarations.extend(flatten_destructuring_binding(
+                        self,
+                        &declaration_data.name,
+                        value,
+                        None,
+                        FlattenLevel::ObjectRest,
+                    ));
+                }
+                _ => declarations.push(self.visit_node(declaration)),
+            }
+        }
+        factory.update_variable_declaration_list(
+            node,
+            factory.update_node_list(&data.declarations, declarations),
+        )
+    }
+
+    /// Binds each value of a `for...of` or `for...in` loop whose pattern has an object rest
+    /// element to a temporary variable, and destructures it at the start of the body:
+    /// `for (const { a, ...r } of xs) {}` becomes
+    /// `for (const _a of xs) { const { a } = _a, r = __rest(_a, ["a"]); }`
+    fn visit_loop_with_object_rest(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let initializer = loop_initializer(node);
+        let (initializer, binding) = self.bind_loop_value_to_temp(initializer);
+        let (expression, statement) = match node.kind {
+            SyntaxKind::ForOfStatement => {
+                let data = node.as_for_of_statement();
+                (&data.expression, &data.statement)
+            }
+            _ => {
+                let data = node.as_for_in_statement();
+                (&data.expression, &data.statement)
+            }
+        };
+        let expression = self.visit_node(expression);
+        let statement = self.visit_node(statement);
+        let statement = prepend_to_block(&factory, &statement, binding);
+        if node.kind == SyntaxKind::ForOfStatement {
+            factory.update_for_of_statement(node, None, initializer, expression, statement)
+        } else {
+            factory.update_for_in_statement(node, initializer, expression, statement)
+        }
+    }
+
+    /// Replaces the pattern a loop binds each value to with a temporary variable, returning
+    /// the new initializer of the loop and the statement that destructures the variable
+    fn bind_loop_value_to_temp(&mut self, initializer: &Arc<Node>) -> (Arc<Node>, Arc<Node>) {
+        let factory = self.factory;
+        if initializer.kind != SyntaxKind::VariableDeclarationList {
+            let temp = self.create_hoisted_temp();
+            let assignment = flatten_destructuring_assignment(
+                self,
+                initializer,
+                factory.create_identifier(temp.as_str()),
+                false,
+                FlattenLevel::ObjectRest,
+            );
+            return (
+                factory.create_identifier(temp),
+                factory.create_expression_statement(assignment),
+            );
+        }
+        let temp = self.create_temp_name();
+        let declaration = &initializer
+            .as_variable_declaration_list()
+            .declarations
+            .nodes[0];
+        let declarations = flatten_destructuring_binding(
+            self,
+            &declaration.as_variable_declaration().name,
+            factory.create_identifier(temp.as_str()),
+            None,
+            FlattenLevel::ObjectRest,
+        );
+        let list = |declarations| {
+            factory.update_variable_declaration_list(
+                initializer,
+                factory.create_node_list(declarations),
+            )
+        };
+        let binding = factory.create(
+            SyntaxKind::VariableStatement,
+            VariableStatement {
+                modifiers: None,
+                declaration_list: list(declarations),
+            },
+        );
+        let temp = factory.create_variable_declaration(factory.create_identifier(temp), None);
+        (list(vec![temp]), binding)
+    }
+
+    /// `catch ({ a, ...r }) {}` becomes `catch (_a) { var { a } = _a, r = __rest(_a, ["a"]); }`
+    fn visit_catch_clause_with_object_rest(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_catch_clause();
+        let declaration = data
+            .variable_declaration
+            .as_ref()
+            .expect("the catch clause has a pattern");
+        let temp = self.create_temp_name();
+        let declarations = flatten_destructuring_binding(
+            self,
+            &declaration.as_variable_declaration().name,
+            factory.create_identifier(temp.as_str()),
+            None,
+            FlattenLevel::ObjectRest,
+        );
+        let block = self.visit_node(&data.block);
+        let block = prepend_to_block(
+            &factory,
+            &block,
+            factory.create_variable_statement(declarations),
+        );
+        factory.update_catch_clause(
+            node,
+            Some(factory.create_variable_declaration(factory.create_identifier(temp), None)),
+            block,
+        )
+    }
+
+    /// Turns `async function* f(a) { body }` into
+    /// `function f(a) { return __asyncGenerator(this, arguments, function* f_1() { body }); }`
+    fn visit_async_generator(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let body = node.body().expect("the async generator has a body");
+        let parameters = visit_nodes(
+            node.parameters().expect("functions have parameters"),
+            self,
+            &factory,
+        );
+
+        self.functions.push(FunctionScope {
+            is_async_generator: true,
+            ..FunctionScope::default()
+        });
+        let statements = visit_nodes(&body.as_block().statements, self, &factory).nodes;
+        let scope = self
+            .functions
+            .pop()
+            .expect("the generator's scope was pushed");
+        let generator_body = factory.create_block(statements, true);
+        let generator_body = add_variable_declarations(&factory, &generator_body, &scope.hoisted);
+
+        let name = node
+            .name()
+            .filter(|name| name.kind == SyntaxKind::Identifier)
+            .map(|name| factory.create_identifier(self.context.create_unique_name(name.text())));
+        let generator = factory.create(
+            SyntaxKind::FunctionExpression,
+            FunctionExpression {
+                modifiers: None,
+                asterisk_token: Some(factory.create_token(SyntaxKind::AsteriskToken)),
+                name,
+                type_parameters: None,
+                parameters: factory.create_node_list(Vec::new()),
+                type_node: None,
+                body: generator_body,
+            },
+        );
+        self.context.request_helper(&ASYNC_GENERATOR_HELPER);
+        let call = factory.create_call(
+            factory.create_identifier("__asyncGenerator"),
+            vec![
+                factory.create_token(SyntaxKind::ThisKeyword),
+                factory.create_identifier("arguments"),
+                generator,
+            ],
+        );
+        let body = factory.update_block(
+            body,
+            factory.create_node_list(vec![factory.create_return(Some(call))]),
+            true,
+        );
+
+        let modifiers = without_async_modifier(&factory, node.modifiers());
+        match node.kind {
+            SyntaxKind::FunctionDeclaration => factory.update_function_declaration(
+                node,
+                modifiers,
+                None,
+                node.name().cloned(),
+                None,
+                parameters,
+                None,
+                Some(body),
+            ),
+            SyntaxKind::FunctionExpression => factory.update_function_expression(
+                node,
+                modifiers,
+                None,
+                node.name().cloned(),
+                None,
+                parameters,
+                None,
+                body,
+            ),
+            _ => {
+                let data = node.as_method_declaration();
+                factory.update_method_declaration(
+                    node,
+                    modifiers,
+                    None,
+                    self.visit_node(&data.name),
+                    data.postfix_token.clone(),
+                    None,
+                    parameters,
+                    None,
+                    Some(body),
+                )
+            }
+        }
+    }
+
+    /// Waits for a value: `yield __await(a)` in an async generator, and `await a` elsewhere
+    fn create_await(&mut self, expression: Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        if !self.in_async_generator() {
+            return factory.create(SyntaxKind::AwaitExpression, AwaitExpression { expression });
+        }
+        self.context.request_helper(&AWAIT_HELPER);
+        let wrapped = factory.create_call(factory.create_identifier("__await"), vec![expression]);
+        create_yield(&factory, false, wrapped)
+    }
+
+    /// Turns `yield a` into `yield yield __await(a)`, and `yield* a` into
+    /// `yield __await(yield* __asyncDelegator(__asyncValues(a)))`
+    fn visit_yield_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_yield_expression();
+        let expression = match &data.expression {
+            Some(expression) => self.visit_node(expression),
+            None => factory.create_void_zero(),
+        };
+        let lowered = if data.asterisk_token.is_some() {
+            self.context.request_helper(&ASYNC_DELEGATOR_HELPER);
+            self.context.request_helper(&ASYNC_VALUES_HELPER);
+            let values =
+                factory.create_call(factory.create_identifier("__asyncValues"), vec![expression]);
+            let delegator =
+                factory.create_call(factory.create_identifier("__asyncDelegator"), vec![values]);
+            self.create_await(create_yield(&factory, true, delegator))
+        } else {
+            let awaited = self.create_await(expression);
+            create_yield(&factory, false, awaited)
+        };
+        factory.with_text_range(&lowered, node.loc)
+    }
+
+    /// Turns `{ a, ...b, c }` into `__assign(__assign({ a }, b), { c })`, starting from an
+    /// empty object when the literal starts with a spread
+    fn visit_object_literal_with_spread(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_object_literal_expression();
+        let mut segments = Vec::new();
+        let mut properties = Vec::new();
+        for property in data.properties.iter() {
+            if property.kind == SyntaxKind::SpreadAssignment {
+                if !properties.is_empty() {
+                    segments.push(
+                        factory.create_object_literal(
+                            std::mem::take(&mut properties),
+                            data.multi_line,
+                        ),
+                    );
+                }
+                segments.push(self.visit_node(&property.as_spread_assignment().expression));
+            } else {
+                properties.push(self.visit_node(property));
+            }
+        }
+        if !properties.is_empty() {
+            segments.push(factory.create_object_literal(properties, data.multi_line));
+        }
+        if segments[0].kind != SyntaxKind::ObjectLiteralExpression {
+            segments.insert(0, factory.create_object_literal(Vec::new(), false));
+        }
+
+        self.context.request_helper(&ASSIGN_HELPER);
+        let mut segments = segments.into_iter();
+        let first = segments.next().expect("a spread makes a segment");
+        segments.fold(first, |target, source| {
+            factory.create_call(factory.create_identifier("__assign"), vec![target, source])
+        })
+    }
+
+    /// Lowers a `for await` loop, labeled with `label` if it has one
+    ///
+    /// `for await (const x of xs) body` becomes:
+    ///
+    /// ```js
+    /// var _a, e_1, _b, _c;
+    /// try {
+    ///     for (var _d = true, xs_1 = __asyncValues(xs), xs_1_1; xs_1_1 = await xs_1.next(), _a = xs_1_1.done, !_a; _d = true) {
+    ///         _c = xs_1_1.value;
+    ///         _d = false;
+    ///         const x = _c;
+    ///         body
+    ///     }
+    /// }
+    /// catch (e_1_1) { e_1 = { error: e_1_1 }; }
+    /// finally {
+    ///     try {
+    ///         if (!_d && !_a && (_b = xs_1.return)) await _b.call(xs_1);
+    ///     }
+    ///     finally { if (e_1) throw e_1.error; }
+    /// }
+    /// ```
+    ///
+    /// `_d` is set while the loop runs user code, so an exception thrown by the iterator
+    /// itself does not close it.
+    fn visit_for_await_statement(
+        &mut self,
+        node: &Arc<Node>,
+        label: Option<&Arc<Node>>,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_for_of_statement();
+        let expression = self.visit_node(&data.expression);
+
+        let done = self.create_hoisted_temp();
+        let error_record = self.context.create_unique_name("e");
+        self.current_function().hoisted.push(error_record.clone());
+        let return_method = self.create_hoisted_temp();
+        let value = self.create_hoisted_temp();
+        let in_user_code = self.create_temp_name();
+        let (iterator, result) = if expression.kind == SyntaxKind::Identifier {
+            let iterator = self.context.create_unique_name(expression.text());
+            let result = self.context.create_unique_name(&iterator);
+            (iterator, result)
+        } else {
+            (self.create_temp_name(), self.create_temp_name())
+        };
+        let caught = self.context.create_unique_name(&error_record);
+
+        let name = |name: &str| factory.create_identifier(name);
+        let not = |operand: Arc<Node>| {
+            factory.create(
+                SyntaxKind::PrefixUnaryExpression,
+                PrefixUnaryExpression {
+                    operator: SyntaxKind::ExclamationToken,
+                    operand,
+                },
+            )
+        };
+        let and =
+            |left, right| factory.create_binary(left, SyntaxKind::AmpersandAmpersandToken, right);
+        let comma = |left, right| factory.create_binary(left, SyntaxKind::CommaToken, right);
+        let assign = |target: &str, value| factory.create_assignment(name(target), value);
+        let statement = |expression| factory.create_expression_statement(expression);
+        let if_then = |expression, then_statement| {
+            factory.create(
+                SyntaxKind::IfStatement,
+                IfStatement {
+                    expression,
+                    then_statement,
+                    else_statement: None,
+                },
+            )
+        };
+
+        // Each iteration starts by taking the value, and binding it the way the loop did
+        let mut statements = vec![
+            statement(assign(
+                &value,
+                factory.create_property_access(name(&result), "value"),
+            )),
+            statement(assign(
+                &in_user_code,
+                factory.create_token(SyntaxKind::FalseKeyword),
+            )),
+        ];
+        if data.initializer.kind == SyntaxKind::VariableDeclarationList {
+            let list = &data.initializer;
+            let declaration = &list.as_variable_declaration_list().declarations.nodes[0];
+            let binding_name = &declaration.as_variable_declaration().name;
+            let declarations = if contains_object_rest(binding_name) {
+                flatten_destructuring_binding(
+                    self,
+                    binding_name,
+                    name(&value),
+                    None,
+                    FlattenLevel::ObjectRest,
+                )
+            } else {
+                vec![factory.update_variable_declaration(
+                    declaration,
+                    self.visit_node(binding_name),
+                    None,
+                    None,
+                    Some(name(&value)),
+                )]
+            };
+            let list = factory
+                .update_variable_declaration_list(list, factory.create_node_list(declarations));
+            statements.push(factory.create(
+                SyntaxKind::VariableStatement,
+                VariableStatement {
+                    modifiers: None,
+                    declaration_list: list,
+                },
+            ));
+        } else if contains_object_rest(&data.initializer) {
+            let assignment = flatten_destructuring_assignment(
+                self,
+                &data.initializer,
+                name(&value),
+                false,
+                FlattenLevel::ObjectRest,
+            );
+            statements.push(statement(assignment));
+        } else {
+            let target = self.visit_node(&data.initializer);
+            statements.push(statement(factory.create_assignment(target, name(&value))));
+        }
+        let body = self.visit_node(&data.statement);
+        if body.kind == SyntaxKind::Block {
+            statements.extend(body.as_block().statements.iter().cloned());
+        } else {
+            statements.push(body);
+        }
+
+        self.context.request_helper(&ASYNC_VALUES_HELPER);
+        let values =
+            factory.create_call(factory.create_identifier("__asyncValues"), vec![expression]);
+        let next = factory.create_call(
+            factory.create_property_access(name(&iterator), "next"),
+            Vec::new(),
+        );
+        let next = self.create_await(next);
+        let initializer = factory.create(
+            SyntaxKind::VariableDeclarationList,
+            VariableDeclarationList {
+                declarations: factory.create_node_list(vec![
+                    factory.create_variable_declaration(
+                        name(&in_user_code),
+                        Some(factory.create_token(SyntaxKind::TrueKeyword)),
+                    ),
+                    factory.create_variable_declaration(name(&iterator), Some(values)),
+                    factory.create_variable_declaration(name(&result), None),
+                ]),
+            },
+        );
+        let condition = comma(
+            comma(
+                assign(&result, next),
+                assign(&done, factory.create_property_access(name(&result), "done")),
+            ),
+            not(name(&done)),
+        );
+        let mut for_statement = factory.create_at(
+            SyntaxKind::ForStatement,
+            node.loc,
+            ForStatement {
+                initializer: Some(initializer),
+                condition: Some(condition),
+                incrementor: Some(assign(
+                    &in_user_code,
+                    factory.create_token(SyntaxKind::TrueKeyword),
+                )),
+                statement: factory.create_block(statements, true),
+            },
+        );
+        if let Some(label) = label {
+            for_statement = factory.create(
+                SyntaxKind::LabeledStatement,
+                LabeledStatement {
+                    label: Arc::clone(label),
+                    statement: for_statement,
+                },
+            );
+        }
+
+        let catch_clause = factory.create(
+            SyntaxKind::CatchClause,
+            CatchClause {
+                variable_declaration: Some(
+                    factory.create_variable_declaration(name(&caught), None),
+                ),
+                block: factory.create_block(
+                    vec![statement(assign(
+                        &error_record,
+                        factory.create_object_literal(
+                            vec![factory.create_property_assignment(name("error"), name(&caught))],
+                            false,
+                        ),
+                    ))],
+                    false,
+                ),
+            },
+        );
+        let close = factory.create_call(
+            factory.create_property_access(name(&return_method), "call"),
+            vec![name(&iterator)],
+        );
+        let close = self.create_await(close);
+        let close_iterator = if_then(
+            and(
+                and(not(name(&in_user_code)), not(name(&done))),
+                factory.create_parenthesized(assign(
+                    &return_method,
+                    factory.create_property_access(name(&iterator), "return"),
+                )),
+            ),
+            statement(close),
+        );
+        let rethrow = if_then(
+            name(&error_record),
+            factory.create(
+                SyntaxKind::ThrowStatement,
+                ThrowStatement {
+                    expression: factory.create_property_access(name(&error_record), "error"),
+                },
+            ),
+        );
+        let finally_block = factory.create_block(
+            vec![factory.create(
+                SyntaxKind::TryStatement,
+                TryStatement {
+                    try_block: factory.create_block(vec![close_iterator], true),
+                    catch_clause: None,
+                    finally_block: Some(factory.create_block(vec![rethrow], false)),
+                },
+            )],
+            true,
+        );
+        factory.create(
+            SyntaxKind::TryStatement,
+            TryStatement {
+                try_block: factory.create_block(vec![for_statement], true),
+                catch_clause: Some(catch_clause),
+                finally_block: Some(finally_block),
+            },
+        )
+    }
+}
+
+impl DestructuringHost for Es2018Transformer<'_> {
+    fn context(&mut self) -> &mut TransformContext {
+        self.context
+    }
+
+    fn create_temp_name(&mut self) -> String {
+        Es2018Transformer::create_temp_name(self)
+    }
+
+    fn hoist_variable(&mut self, name: &str) {
+        self.current_function().hoisted.push(name.to_string());
+    }
+}
+
+/// Determines if a node is an assignment to a pattern with an object rest element
+fn has_object_rest_assignment(node: &Node) -> bool {
+    is_destructuring_assignment(node) && contains_object_rest(&node.as_binary_expression().left)
+}
+
+/// Gets the declaration list or assignment target of a `for...in` or `for...of` loop
+fn loop_initializer(node: &Node) -> &Arc<Node> {
+    if node.kind == SyntaxKind::ForOfStatement {
+        &node.as_for_of_statement().initializer
+    } else {
+        &node.as_for_in_statement().initializer
+    }
+}
+
+/// Determines if the declaration list or assignment target of a loop has a pattern with an
+/// object rest element
+fn contains_object_rest_binding(initializer: &Arc<Node>) -> bool {
+    if initializer.kind == SyntaxKind::VariableDeclarationList {
+        initializer
+            .as_variable_declaration_list()
+            .declarations
+            .iter()
+            .any(|declaration| contains_object_rest(&declaration.as_variable_declaration().name))
+    } else {
+        contains_object_rest(initializer)
+    }
+}
+
+fn is_async_generator(node: &Node) -> bool {
+    let asterisk_token = match node.kind {
+        SyntaxKind::FunctionDeclaration => &node.as_function_declaration().asterisk_token,
+        SyntaxKind::FunctionExpression => &node.as_function_expression().asterisk_token,
+        SyntaxKind::MethodDeclaration => &node.as_method_declaration().asterisk_token,
+        _ => return false,
+    };
+    asterisk_token.is_some() && node.body().is_some() && node.has_modifier(SyntaxKind::AsyncKeyword)
+}
+
+fn is_for_await_statement(node: &Node) -> bool {
+    node.kind == SyntaxKind::ForOfStatement && node.as_for_of_statement().await_modifier.is_some()
+}
+
+/// Removes the `async` modifier, leaving no list if it was the only modifier
+pub(super) fn without_async_modifier(
+    factory: &NodeFactory,
+    modifiers: Option<&ModifierList>,
+) -> Option<ModifierList> {
+    let modifiers = modifiers?;
+    let kept = modifiers
+        .iter()
+        .filter(|modifier| modifier.kind != SyntaxKind::AsyncKeyword)
+        .cloned()
+        .collect::<Vec<_>>();
+    (!kept.is_empty()).then(|| factory.update_node_list(modifiers, kept))
+}
+
+/// Creates `yield expression`, or `yield* expression` if `delegate` is set
+pub(super) fn create_yield(
+    factory: &NodeFactory,
+    delegate: bool,
+    expression: Arc<Node>,
+) -> Arc<Node> {
+    factory.create(
+        SyntaxKind::YieldExpression,
+        YieldExpression {
+            asterisk_token: delegate.then(|| factory.create_token(SyntaxKind::AsteriskToken)),
+            expression: Some(expression),
+        },
+    )
+}
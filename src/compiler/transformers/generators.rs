@@ -0,0 +1,2403 @@
+//! Lowering generator functions for ES5
+//!
+//! A generator function becomes a function that hands a state machine to the `__generator`
+//! helper. The body of the function is split at each `yield` into numbered cases of a
+//! `switch` on the state's label, and the state machine resumes at the case after the
+//! `yield`:
+//!
+//! ```js
+//! function g() {
+//!     var x;
+//!     return __generator(this, function (_a) {
+//!         switch (_a.label) {
+//!             case 0: return [4, 1];
+//!             case 1:
+//!                 x = _a.sent();
+//!                 return [2, x];
+//!         }
+//!     });
+//! }
+//! ```
+//!
+//! Each case returns an instruction for the helper: `[2, value]` returns, `[3, label]` jumps
+//! to another case, `[4, value]` yields, `[5, iterator]` delegates to another iterator, and
+//! `[7]` ends a `finally` block. The helper keeps the ranges of the `try` statements that are
+//! running in `_a.trys`, so that it can jump to a `catch` or `finally` case when a case throws
+//! or returns.
+//!
+//! The transformation first emits a list of operations, with labels marking where the
+//! control flow may jump to, and then builds the cases from them. The local variables of the
+//! function are declared outside the state machine, so that they keep their values between
+//! cases.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    ArrayLiteralExpression, CaseBlock, CaseClause, EmptyStatement, ForInStatement,
+    FunctionExpression, IfStatement, NewExpression, ObjectLiteralExpression, OmittedExpression,
+    PostfixUnaryExpression, PrefixUnaryExpression, SwitchStatement, ThrowStatement,
+};
+use crate::compiler::ast::utilities::is_function_like_declaration_kind;
+use crate::compiler::ast::visitor::{NodeVisitor, visit_each_child, visit_nodes};
+use crate::compiler::ast::{Node, NodeFlags, NodeList, SyntaxKind};
+
+use super::helpers::{GENERATOR_HELPER, VALUES_HELPER};
+use super::{TransformContext, collect_binding_names, is_prologue_directive, to_assignment_target};
+
+pub(super) fn transform_generators(
+    source_file: &Arc<Node>,
+    context: &mut TransformContext,
+) -> Arc<Node> {
+    let mut transformer = GeneratorTransformer {
+        factory: NodeFactory::new(),
+        context,
+        function: None,
+        renamed_catch_variables: Vec::new(),
+        member_name: None,
+    };
+    let factory = transformer.factory;
+    visit_each_child(source_file, &mut transformer, &factory)
+}
+
+/// A position in the operations of a generator that control flow can jump to
+type Label = usize;
+
+/// A step of the state machine, in the order the body of the generator runs them
+#[derive(Clone)]
+enum Operation {
+    /// Does nothing, but keeps a label or block at this position
+    Nop,
+    Statement(Arc<Node>),
+    Assign(Arc<Node>, Arc<Node>),
+    Break(Label),
+    BreakWhenTrue(Label, Arc<Node>),
+    BreakWhenFalse(Label, Arc<Node>),
+    Yield(Option<Arc<Node>>),
+    YieldStar(Arc<Node>),
+    Return(Option<Arc<Node>>),
+    Throw(Arc<Node>),
+    Endfinally,
+}
+
+/// An instruction that a case of the state machine returns to the `__generator` helper
+#[derive(Clone, Copy)]
+enum Instruction {
+    Return = 2,
+    Break = 3,
+    Yield = 4,
+    YieldStar = 5,
+    Endfinally = 7,
+}
+
+/// A statement whose control flow the operations must follow
+///
+/// A block is a script block when it contains no `yield` of its own but is nested in a
+/// statement that does. It stays a JavaScript statement, so a `break` or `continue` that
+/// targets it is left as it is.
+#[derive(Clone)]
+enum CodeBlock {
+    Exception(ExceptionBlock),
+    Loop {
+        break_label: Option<Label>,
+        continue_label: Option<Label>,
+    },
+    Switch {
+        break_label: Option<Label>,
+    },
+    Labeled {
+        label_text: String,
+        break_label: Option<Label>,
+    },
+}
+
+#[derive(Clone, Copy)]
+struct ExceptionBlock {
+    state: ExceptionBlockState,
+    start_label: Label,
+    catch_label: Option<Label>,
+    finally_label: Option<Label>,
+    end_label: Label,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ExceptionBlockState {
+    Try,
+    Catch,
+    Finally,
+    Done,
+}
+
+/// Where a block opens or closes among the operations
+#[derive(Clone, Copy)]
+struct BlockAction {
+    is_open: bool,
+    offset: usize,
+    block: usize,
+}
+
+/// The state of the generator function whose body is being transformed
+struct GeneratorFunction {
+    /// The name of the parameter of the state machine, such as `_a`
+    state: String,
+    /// The names generated within the function so far
+    generated_names: HashSet<String>,
+    /// The variables to declare outside the state machine
+    hoisted_variables: Vec<String>,
+    /// The function declarations to move outside the state machine
+    hoisted_functions: Vec<Arc<Node>>,
+    /// The variable that holds the `arguments` of the generator, once it is used
+    arguments_name: Option<String>,
+    /// Whether the statement being transformed contains a `yield`, so that the statements
+    /// nested in it have to follow its blocks
+    in_statement_containing_yield: bool,
+    next_label: Label,
+    /// The index of the operation each label marks, by label
+    label_offsets: Vec<Option<usize>>,
+    /// The labels that some instruction jumps to
+    referenced_labels: HashSet<Label>,
+    operations: Vec<Operation>,
+    blocks: Vec<CodeBlock>,
+    block_actions: Vec<BlockAction>,
+    /// The indices of the open blocks, innermost last
+    block_stack: Vec<usize>,
+}
+
+impl GeneratorFunction {
+    fn new(state: String, generated_names: HashSet<String>) -> Self {
+        GeneratorFunction {
+            state,
+            generated_names,
+            hoisted_variables: Vec::new(),
+            hoisted_functions: Vec::new(),
+            arguments_name: None,
+            in_statement_containing_yield: false,
+            next_label: 1,
+            label_offsets: vec![None],
+            referenced_labels: HashSet::new(),
+            operations: Vec::new(),
+            blocks: Vec::new(),
+            block_actions: Vec::new(),
+            block_stack: Vec::new(),
+        }
+    }
+
+    fn hoist_variable(&mut self, name: &str) {
+        if !self.hoisted_variables.iter().any(|hoisted| hoisted == name) {
+            self.hoisted_variables.push(name.to_string());
+        }
+    }
+
+    fn define_label(&mut self) -> Label {
+        let label = self.next_label;
+        self.next_label += 1;
+        self.label_offsets.push(None);
+        label
+    }
+
+    fn mark_label(&mut self, label: Label) {
+        self.label_offsets[label] = Some(self.operations.len());
+    }
+
+    fn emit(&mut self, operation: Operation) {
+        // The entry point of the body gets a label if nothing has defined one before it
+        if self.next_label == 1 {
+            let label = self.define_label();
+            self.mark_label(label);
+        }
+        self.operations.push(operation);
+    }
+
+    fn begin_block(&mut self, block: CodeBlock) -> usize {
+        let index = self.blocks.len();
+        self.blocks.push(block);
+        self.block_actions.push(BlockAction {
+            is_open: true,
+            offset: self.operations.len(),
+            block: index,
+        });
+        self.block_stack.push(index);
+        index
+    }
+
+    fn end_block(&mut self) -> CodeBlock {
+        let index = self.block_stack.pop().expect("a block is open");
+        self.block_actions.push(BlockAction {
+            is_open: false,
+            offset: self.operations.len(),
+            block: index,
+        });
+        self.blocks[index].clone()
+    }
+
+    fn current_exception_block(&mut self) -> &mut ExceptionBlock {
+        let index = *self.block_stack.last().expect("a block is open");
+        match &mut self.blocks[index] {
+            CodeBlock::Exception(block) => block,
+            _ => panic!("the innermost block is not a try statement"),
+        }
+    }
+
+    /// Opens the block of a `try` statement, returning the label after it
+    fn begin_exception_block(&mut self) -> Label {
+        let start_label = self.define_label();
+        let end_label = self.define_label();
+        self.mark_label(start_label);
+        self.begin_block(CodeBlock::Exception(ExceptionBlock {
+            state: ExceptionBlockState::Try,
+            start_label,
+            catch_label: None,
+            finally_label: None,
+            end_label,
+        }));
+        self.emit(Operation::Nop);
+        end_label
+    }
+
+    /// Starts the `catch` block of the current `try` statement, whose error is assigned to
+    /// `variable`
+    fn begin_catch_block(&mut self, factory: &NodeFactory, variable: &str) {
+        let end_label = self.current_exception_block().end_label;
+        self.emit(Operation::Break(end_label));
+        let catch_label = self.define_label();
+        self.mark_label(catch_label);
+        let block = self.current_exception_block();
+        block.state = ExceptionBlockState::Catch;
+        block.catch_label = Some(catch_label);
+        let error = self.create_generator_resume(factory);
+        self.emit(Operation::Assign(
+            factory.create_identifier(variable),
+            error,
+        ));
+        self.emit(Operation::Nop);
+    }
+
+    fn begin_finally_block(&mut self) {
+        let end_label = self.current_exception_block().end_label;
+        self.emit(Operation::Break(end_label));
+        let finally_label = self.define_label();
+        self.mark_label(finally_label);
+        let block = self.current_exception_block();
+        block.state = ExceptionBlockState::Finally;
+        block.finally_label = Some(finally_label);
+    }
+
+    fn end_exception_block(&mut self) {
+        let CodeBlock::Exception(block) = self.end_block() else {
+            panic!("the innermost block is not a try statement");
+        };
+        if block.state < ExceptionBlockState::Finally {
+            self.emit(Operation::Break(block.end_label));
+        } else {
+            self.emit(Operation::Endfinally);
+        }
+        self.mark_label(block.end_label);
+        self.emit(Operation::Nop);
+        let index = *self
+            .block_actions
+            .last()
+            .map(|action| &action.block)
+            .expect("the block was closed");
+        if let CodeBlock::Exception(block) = &mut self.blocks[index] {
+            block.state = ExceptionBlockState::Done;
+        }
+    }
+
+    /// Opens a loop that the operations implement, returning the label after it
+    fn begin_loop_block(&mut self, continue_label: Label) -> Label {
+        let break_label = self.define_label();
+        self.begin_block(CodeBlock::Loop {
+            break_label: Some(break_label),
+            continue_label: Some(continue_label),
+        });
+        break_label
+    }
+
+    fn begin_script_loop_block(&mut self) {
+        self.begin_block(CodeBlock::Loop {
+            break_label: None,
+            continue_label: None,
+        });
+    }
+
+    /// Opens a `switch` statement that the operations implement, returning the label after it
+    fn begin_switch_block(&mut self) -> Label {
+        let break_label = self.define_label();
+        self.begin_block(CodeBlock::Switch {
+            break_label: Some(break_label),
+        });
+        break_label
+    }
+
+    fn begin_script_switch_block(&mut self) {
+        self.begin_block(CodeBlock::Switch { break_label: None });
+    }
+
+    fn begin_labeled_block(&mut self, label_text: &str) {
+        let break_label = self.define_label();
+        self.begin_block(CodeBlock::Labeled {
+            label_text: label_text.to_string(),
+            break_label: Some(break_label),
+        });
+    }
+
+    fn begin_script_labeled_block(&mut self, label_text: &str) {
+        self.begin_block(CodeBlock::Labeled {
+            label_text: label_text.to_string(),
+            break_label: None,
+        });
+    }
+
+    /// Closes a loop, `switch`, or labeled block, marking the label after it
+    fn end_breakable_block(&mut self) {
+        let (CodeBlock::Loop { break_label, .. }
+        | CodeBlock::Switch { break_label }
+        | CodeBlock::Labeled { break_label, .. }) = self.end_block()
+        else {
+            panic!("the innermost block is a try statement");
+        };
+        if let Some(break_label) = break_label {
+            self.mark_label(break_label);
+        }
+    }
+
+    /// Whether the blocks around the one at `index` start with a labeled block named
+    /// `label_text`, which makes `label_text` a label of that block
+    fn has_immediate_containing_labeled_block(&self, label_text: &str, index: usize) -> bool {
+        for &block in self.block_stack[..index].iter().rev() {
+            match &self.blocks[block] {
+                CodeBlock::Labeled {
+                    label_text: text, ..
+                } => {
+                    if text == label_text {
+                        return true;
+                    }
+                }
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    /// Finds where a `break` with the given label jumps to, or nothing if the statement it
+    /// breaks out of is a script block or not in the generator
+    fn find_break_target(&self, label_text: Option<&str>) -> Option<Label> {
+        for (index, &block) in self.block_stack.iter().enumerate().rev() {
+            match (&self.blocks[block], label_text) {
+                (
+                    CodeBlock::Labeled {
+                        label_text: text,
+                        break_label,
+                    },
+                    Some(label_text),
+                ) if text == label_text => return *break_label,
+                (
+                    CodeBlock::Loop { break_label, .. } | CodeBlock::Switch { break_label },
+                    Some(label_text),
+                ) if self.has_immediate_containing_labeled_block(label_text, index) => {
+                    return *break_label;
+                }
+                (CodeBlock::Loop { break_label, .. } | CodeBlock::Switch { break_label }, None) => {
+                    return *break_label;
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Finds where a `continue` with the given label jumps to, or nothing if the loop it
+    /// continues is a script block or not in the generator
+    fn find_continue_target(&self, label_text: Option<&str>) -> Option<Label> {
+        for (index, &block) in self.block_stack.iter().enumerate().rev() {
+            if let CodeBlock::Loop { continue_label, .. } = &self.blocks[block] {
+                match label_text {
+                    Some(label_text)
+                        if !self.has_immediate_containing_labeled_block(label_text, index) => {}
+                    _ => return *continue_label,
+                }
+            }
+        }
+        None
+    }
+
+    /// Creates a reference to a label, which the build replaces with the number of the case
+    /// the label starts
+    fn create_label(&mut self, factory: &NodeFactory, label: Option<Label>) -> Arc<Node> {
+        match label {
+            Some(label) => {
+                self.referenced_labels.insert(label);
+                factory.create_numeric_literal(format!("{LABEL_PREFIX}{label}"))
+            }
+            None => factory.create(SyntaxKind::OmittedExpression, OmittedExpression {}),
+        }
+    }
+
+    /// Creates `return [3, label];`, which jumps to `label`
+    fn create_inline_break(&mut self, factory: &NodeFactory, label: Label) -> Arc<Node> {
+        let label = self.create_label(factory, Some(label));
+        create_instruction_return(factory, Instruction::Break, Some(label))
+    }
+
+    /// Creates `_a.sent()`, the value the generator was resumed with
+    fn create_generator_resume(&self, factory: &NodeFactory) -> Arc<Node> {
+        factory.create_call(
+            factory.create_property_access(factory.create_identifier(self.state.as_str()), "sent"),
+            Vec::new(),
+        )
+    }
+}
+
+/// Starts the text of a numeric literal that refers to a label until the build resolves it
+const LABEL_PREFIX: char = '#';
+
+/// Creates `return [instruction, operand];`
+fn create_instruction_return(
+    factory: &NodeFactory,
+    instruction: Instruction,
+    operand: Option<Arc<Node>>,
+) -> Arc<Node> {
+    let mut elements = vec![factory.create_numeric_literal((instruction as u8).to_string())];
+    elements.extend(operand);
+    factory.create_return(Some(factory.create_array_literal(elements, false)))
+}
+
+struct GeneratorTransformer<'a> {
+    factory: NodeFactory,
+    context: &'a mut TransformContext,
+    /// The generator function whose body is being transformed, if the node being visited is
+    /// in one and not in a function nested in it
+    function: Option<GeneratorFunction>,
+    /// The catch variables renamed to variables of their generator, innermost last
+    renamed_catch_variables: Vec<(String, String)>,
+    /// The name of the member being visited, which is a property name rather than a
+    /// reference to a variable
+    member_name: Option<Arc<Node>>,
+}
+
+impl NodeVisitor for GeneratorTransformer<'_> {
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        if matches!(
+            node.kind,
+            K::PropertyAssignment
+                | K::PropertyDeclaration
+                | K::MethodDeclaration
+                | K::GetAccessor
+                | K::SetAccessor
+        ) {
+            let outer = std::mem::replace(&mut self.member_name, node.name().cloned());
+            let visited = self.visit_node_worker(node);
+            self.member_name = outer;
+            return visited;
+        }
+        self.visit_node_worker(node)
+    }
+
+    fn visit_list_element(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        if self.function.is_some()
+            && matches!(
+                node.kind,
+                SyntaxKind::FunctionDeclaration | SyntaxKind::VariableStatement
+            )
+        {
+            return self.visit_hoisted_statement(node).into_iter().collect();
+        }
+        vec![self.visit_node(node)]
+    }
+}
+
+impl GeneratorTransformer<'_> {
+    fn visit_node_worker(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        let Some(function) = &self.function else {
+            return match node.kind {
+                K::FunctionDeclaration | K::FunctionExpression => self.visit_function(node),
+                K::Identifier => self.visit_identifier(node),
+                K::PropertyAccessExpression => self.visit_property_access(node),
+                K::ShorthandPropertyAssignment => self.visit_shorthand_property(node),
+                _ => visit_each_child(node, self, &factory),
+            };
+        };
+        if function.in_statement_containing_yield {
+            match node.kind {
+                K::DoStatement | K::WhileStatement => {
+                    self.function().begin_script_loop_block();
+                    let visited = visit_each_child(node, self, &factory);
+                    self.function().end_breakable_block();
+                    return visited;
+                }
+                K::SwitchStatement => {
+                    self.function().begin_script_switch_block();
+                    let visited = visit_each_child(node, self, &factory);
+                    self.function().end_breakable_block();
+                    return visited;
+                }
+                K::LabeledStatement => {
+                    let label = node.as_labeled_statement().label.text().to_string();
+                    self.function().begin_script_labeled_block(&label);
+                    let visited = visit_each_child(node, self, &factory);
+                    self.function().end_breakable_block();
+                    return visited;
+                }
+                _ => {}
+            }
+        }
+        match node.kind {
+            K::FunctionDeclaration | K::VariableStatement => self
+                .visit_hoisted_statement(node)
+                .unwrap_or_else(|| factory.create(K::EmptyStatement, EmptyStatement {})),
+            K::FunctionExpression => self.visit_function(node),
+            kind if is_function_like_declaration_kind(kind) => self.visit_nested_function(node),
+            K::ForStatement => self.visit_for_statement(node),
+            K::ForInStatement => self.visit_for_in_statement(node),
+            K::BreakStatement | K::ContinueStatement => self.visit_break_or_continue(node),
+            K::ReturnStatement => {
+                let expression = node
+                    .as_return_statement()
+                    .expression
+                    .as_ref()
+                    .map(|expression| self.visit_node(expression));
+                let statement =
+                    create_instruction_return(&factory, Instruction::Return, expression);
+                factory.with_text_range(&statement, node.loc)
+            }
+            K::Identifier => self.visit_identifier(node),
+            K::PropertyAccessExpression => self.visit_property_access(node),
+            K::ShorthandPropertyAssignment => self.visit_shorthand_property(node),
+            _ if contains_yield(node) => self.visit_containing_yield(node),
+            _ => visit_each_child(node, self, &factory),
+        }
+    }
+
+    fn function(&mut self) -> &mut GeneratorFunction {
+        self.function
+            .as_mut()
+            .expect("the visitor is in the body of a generator")
+    }
+
+    /// Declares a variable of the generator outside its state machine: a unique name based
+    /// on `base`, or a temporary variable
+    fn declare_local(&mut self, base: Option<&str>) -> Arc<Node> {
+        let name = match base {
+            Some(base) => self.context.create_unique_name(base),
+            None => {
+                let function = self
+                    .function
+                    .as_mut()
+                    .expect("the visitor is in a generator");
+                self.context.create_temp_name(&mut function.generated_names)
+            }
+        };
+        let function = self.function();
+        function.generated_names.insert(name.clone());
+        function.hoist_variable(&name);
+        self.factory.create_identifier(name)
+    }
+
+    /// Stores the value of an expression in a temporary variable, unless it is already one or
+    /// names a helper, neither of which can change
+    fn cache_expression(&mut self, expression: Arc<Node>) -> Arc<Node> {
+        if expression.kind == SyntaxKind::Identifier {
+            let name = expression.text();
+            let is_helper = self
+                .context
+                .helpers
+                .iter()
+                .any(|helper| helper.name == name);
+            if is_helper || self.function().generated_names.contains(name) {
+                return expression;
+            }
+        }
+        let temp = self.declare_local(None);
+        self.function()
+            .emit(Operation::Assign(Arc::clone(&temp), expression));
+        temp
+    }
+
+    fn emit_statement(&mut self, statement: Option<Arc<Node>>) {
+        let operation = match statement {
+            Some(statement) if statement.kind != SyntaxKind::EmptyStatement => {
+                Operation::Statement(statement)
+            }
+            _ => Operation::Nop,
+        };
+        self.function().emit(operation);
+    }
+
+    /// Visits a function declaration or expression, transforming it if it is a generator
+    ///
+    /// Within the body of a generator, function declarations move outside the state machine.
+    fn visit_function(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let asterisk_token = match node.kind {
+            SyntaxKind::FunctionDeclaration => &node.as_function_declaration().asterisk_token,
+            _ => &node.as_function_expression().asterisk_token,
+        };
+        if asterisk_token.is_some() && node.body().is_some() {
+            self.visit_generator(node)
+        } else {
+            self.visit_nested_function(node)
+        }
+    }
+
+    /// Visits a function that is not a generator, whose body is not part of the generator
+    /// around it
+    fn visit_nested_function(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let function = self.function.take();
+        let visited = visit_each_child(node, self, &factory);
+        self.function = function;
+        visited
+    }
+
+    /// Turns `function* g() { body }` into
+    /// `function g() { return __generator(this, function (_a) { ... }); }`
+    fn visit_generator(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let parameters = visit_nodes(
+            node.parameters().expect("functions have parameters"),
+            self,
+            &factory,
+        );
+        let outer = self.function.take();
+        let body = self.transform_generator_body(node.body().expect("the generator has a body"));
+        self.function = outer;
+        if node.kind == SyntaxKind::FunctionDeclaration {
+            let data = node.as_function_declaration();
+            factory.update_function_declaration(
+                node,
+                data.modifiers.clone(),
+                None,
+                data.name.clone(),
+                None,
+                parameters,
+                None,
+                Some(body),
+            )
+        } else {
+            let data = node.as_function_expression();
+            factory.update_function_expression(
+                node,
+                data.modifiers.clone(),
+                None,
+                data.name.clone(),
+                None,
+                parameters,
+                None,
+                body,
+            )
+        }
+    }
+
+    fn transform_generator_body(&mut self, body: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let mut generated_names = HashSet::new();
+        let state = self.context.create_temp_name(&mut generated_names);
+        self.function = Some(GeneratorFunction::new(state, generated_names));
+
+        let statements = &body.as_block().statements;
+        let prologue = statements
+            .iter()
+            .take_while(|statement| is_prologue_directive(statement))
+            .count();
+        for statement in &statements.nodes[prologue..] {
+            self.transform_and_emit_statement(statement);
+        }
+        let state_machine = self.build();
+        let function = self
+            .function
+            .take()
+            .expect("the generator was set up above");
+
+        let mut result = statements.nodes[..prologue].to_vec();
+        if let Some(arguments_name) = &function.arguments_name {
+            result.push(factory.create_variable_statement(vec![
+                factory.create_variable_declaration(
+                    factory.create_identifier(arguments_name.as_str()),
+                    Some(factory.create_identifier("arguments")),
+                ),
+            ]));
+        }
+        result.extend(function.hoisted_functions);
+        if !function.hoisted_variables.is_empty() {
+            let declarations = function
+                .hoisted_variables
+                .iter()
+                .map(|name| {
+                    factory
+                        .create_variable_declaration(factory.create_identifier(name.as_str()), None)
+                })
+                .collect();
+            result.push(factory.create_variable_statement(declarations));
+        }
+        result.push(factory.create_return(Some(state_machine)));
+        factory.update_block(body, factory.update_node_list(statements, result), true)
+    }
+
+    /// Renames a catch variable that moved outside the state machine, and `arguments`, which
+    /// would otherwise refer to the arguments of the state machine
+    fn visit_identifier(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        if self
+            .member_name
+            .as_ref()
+            .is_some_and(|name| Arc::ptr_eq(name, node))
+        {
+            return Arc::clone(node);
+        }
+        let text = node.text();
+        if let Some((_, renamed)) = self
+            .renamed_catch_variables
+            .iter()
+            .rev()
+            .find(|(name, _)| name == text)
+        {
+            let renamed = self.factory.create_identifier(renamed.as_str());
+            return self.factory.with_text_range(&renamed, node.loc);
+        }
+        if text == "arguments" && self.function.is_some() {
+            let function = self.function();
+            let name = match &function.arguments_name {
+                Some(name) => name.clone(),
+                None => {
+                    let name = self.context.create_unique_name("arguments");
+                    self.function().arguments_name = Some(name.clone());
+                    name
+                }
+            };
+            let renamed = self.factory.create_identifier(name);
+            return self.factory.with_text_range(&renamed, node.loc);
+        }
+        Arc::clone(node)
+    }
+
+    /// Visits the object of a property access but not the property name
+    fn visit_property_access(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let data = node.as_property_access_expression();
+        let expression = self.visit_node(&data.expression);
+        self.factory.update_property_access(
+            node,
+            expression,
+            data.question_dot_token.clone(),
+            Arc::clone(&data.name),
+        )
+    }
+
+    /// Turns `{ e }` into `{ e: e_1 }` if the variable was renamed
+    fn visit_shorthand_property(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_shorthand_property_assignment();
+        let name = self.visit_identifier(&data.name);
+        if Arc::ptr_eq(&name, &data.name) {
+            return factory.update_shorthand_property_assignment(
+                node,
+                data.modifiers.clone(),
+                name,
+                data.postfix_token.clone(),
+                data.equals_token.clone(),
+                data.object_assignment_initializer
+                    .as_ref()
+                    .map(|initializer| self.visit_node(initializer)),
+            );
+        }
+        let property = factory.create_property_assignment(Arc::clone(&data.name), name);
+        factory.with_text_range(&property, node.loc)
+    }
+
+    /// Moves a function declaration or the declarations of a variable statement outside the
+    /// state machine, leaving the assignments of the variables' initializers
+    fn visit_hoisted_statement(&mut self, node: &Arc<Node>) -> Option<Arc<Node>> {
+        let factory = self.factory;
+        if node.kind == SyntaxKind::FunctionDeclaration {
+            let function = self.visit_function(node);
+            self.function().hoisted_functions.push(function);
+            return None;
+        }
+        let list = &node.as_variable_statement().declaration_list;
+        if contains_yield(node) {
+            self.transform_and_emit_variable_declaration_list(list);
+            return None;
+        }
+        let assignments = self.hoist_variable_declaration_list(list);
+        if assignments.is_empty() {
+            return None;
+        }
+        let statement =
+            factory.create_expression_statement(inline_expressions(&factory, assignments));
+        Some(factory.with_text_range(&statement, node.loc))
+    }
+
+    /// Hoists the variables of a declaration list, returning the assignments of their
+    /// initializers
+    fn hoist_variable_declaration_list(&mut self, list: &Arc<Node>) -> Vec<Arc<Node>> {
+        let declarations = &list.as_variable_declaration_list().declarations;
+        self.hoist_declared_names(declarations);
+        declarations
+            .iter()
+            .filter(|declaration| declaration.initializer().is_some())
+            .map(|declaration| self.transform_initialized_variable(declaration))
+            .collect()
+    }
+
+    fn hoist_declared_names(&mut self, declarations: &NodeList) {
+        let mut names = Vec::new();
+        for declaration in declarations.iter() {
+            collect_binding_names(&declaration.as_variable_declaration().name, &mut names);
+        }
+        for name in names {
+            self.function().hoist_variable(&name);
+        }
+    }
+
+    /// Turns `x = a` in a declaration list into the assignment `x = a`
+    fn transform_initialized_variable(&mut self, declaration: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = declaration.as_variable_declaration();
+        let target = to_assignment_target(&factory, &data.name);
+        let initializer = self.visit_node(
+            data.initializer
+                .as_ref()
+                .expect("the variable is initialized"),
+        );
+        let assignment = factory.create_assignment(target, initializer);
+        factory.with_text_range(&assignment, declaration.loc)
+    }
+
+    /// Emits the assignments of a declaration list with a `yield` in it, splitting them
+    /// before each initializer that contains a `yield`
+    fn transform_and_emit_variable_declaration_list(&mut self, list: &Arc<Node>) {
+        let factory = self.factory;
+        let declarations = &list.as_variable_declaration_list().declarations;
+        self.hoist_declared_names(declarations);
+        let mut pending = Vec::new();
+        for declaration in declarations.iter() {
+            let Some(initializer) = declaration.initializer() else {
+                continue;
+            };
+            if contains_yield(initializer) && !pending.is_empty() {
+                let expression = inline_expressions(&factory, std::mem::take(&mut pending));
+                self.emit_statement(Some(factory.create_expression_statement(expression)));
+            }
+            pending.push(self.transform_initialized_variable(declaration));
+        }
+        if !pending.is_empty() {
+            let expression = inline_expressions(&factory, pending);
+            self.emit_statement(Some(factory.create_expression_statement(expression)));
+        }
+    }
+
+    fn visit_for_statement(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let in_statement_containing_yield = self.function().in_statement_containing_yield;
+        if in_statement_containing_yield {
+            self.function().begin_script_loop_block();
+        }
+        let data = node.as_for_statement();
+        let visited = match &data.initializer {
+            Some(list) if list.kind == SyntaxKind::VariableDeclarationList => {
+                let assignments = self.hoist_variable_declaration_list(list);
+                factory.update_for_statement(
+                    node,
+                    (!assignments.is_empty()).then(|| inline_expressions(&factory, assignments)),
+                    data.condition
+                        .as_ref()
+                        .map(|condition| self.visit_node(condition)),
+                    data.incrementor
+                        .as_ref()
+                        .map(|incrementor| self.visit_node(incrementor)),
+                    self.visit_node(&data.statement),
+                )
+            }
+            _ => visit_each_child(node, self, &factory),
+        };
+        if in_statement_containing_yield {
+            self.function().end_breakable_block();
+        }
+        visited
+    }
+
+    fn visit_for_in_statement(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let in_statement_containing_yield = self.function().in_statement_containing_yield;
+        if in_statement_containing_yield {
+            self.function().begin_script_loop_block();
+        }
+        let data = node.as_for_in_statement();
+        let visited = if data.initializer.kind == SyntaxKind::VariableDeclarationList {
+            let declarations = &data.initializer.as_variable_declaration_list().declarations;
+            self.hoist_declared_names(declarations);
+            let name = &declarations.nodes[0].as_variable_declaration().name;
+            factory.update_for_in_statement(
+                node,
+                to_assignment_target(&factory, name),
+                self.visit_node(&data.expression),
+                self.visit_node(&data.statement),
+            )
+        } else {
+            visit_each_child(node, self, &factory)
+        };
+        if in_statement_containing_yield {
+            self.function().end_breakable_block();
+        }
+        visited
+    }
+
+    /// Turns a `break` or `continue` whose target the operations implement into a jump
+    fn visit_break_or_continue(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let function = self.function();
+        if function.in_statement_containing_yield {
+            let target = break_or_continue_target(function, node);
+            if let Some(label) = target {
+                let jump = function.create_inline_break(&factory, label);
+                return factory.with_text_range(&jump, node.loc);
+            }
+        }
+        visit_each_child(node, self, &factory)
+    }
+
+    fn transform_and_emit_statements(&mut self, statements: &NodeList) {
+        for statement in statements.iter() {
+            self.transform_and_emit_statement(statement);
+        }
+    }
+
+    fn transform_and_emit_statement(&mut self, node: &Arc<Node>) {
+        let function = self.function();
+        let in_statement_containing_yield = function.in_statement_containing_yield;
+        if !in_statement_containing_yield {
+            function.in_statement_containing_yield = contains_yield(node);
+        }
+        self.transform_and_emit_statement_worker(node);
+        self.function().in_statement_containing_yield = in_statement_containing_yield;
+    }
+
+    fn transform_and_emit_statement_worker(&mut self, node: &Arc<Node>) {
+        use SyntaxKind as K;
+        match node.kind {
+            K::Block if contains_yield(node) => {
+                self.transform_and_emit_statements(&node.as_block().statements)
+            }
+            K::IfStatement => self.transform_and_emit_if_statement(node),
+            K::DoStatement if contains_yield(node) => self.transform_and_emit_do_statement(node),
+            K::WhileStatement if contains_yield(node) => {
+                self.transform_and_emit_while_statement(node)
+            }
+            K::ForStatement if contains_yield(node) => self.transform_and_emit_for_statement(node),
+            K::ForInStatement if contains_yield(node) => {
+                self.transform_and_emit_for_in_statement(node)
+            }
+            K::BreakStatement | K::ContinueStatement => {
+                let function = self.function();
+                match break_or_continue_target(function, node) {
+                    Some(label) => function.emit(Operation::Break(label)),
+                    None => function.emit(Operation::Statement(Arc::clone(node))),
+                }
+            }
+            K::ReturnStatement => {
+                let expression = node
+                    .as_return_statement()
+                    .expression
+                    .as_ref()
+                    .map(|expression| self.visit_node(expression));
+                self.function().emit(Operation::Return(expression));
+            }
+            K::SwitchStatement if contains_yield(&node.as_switch_statement().case_block) => {
+                self.transform_and_emit_switch_statement(node)
+            }
+            K::LabeledStatement if contains_yield(node) => {
+                let data = node.as_labeled_statement();
+                self.function().begin_labeled_block(data.label.text());
+                self.transform_and_emit_embedded_statement(&data.statement);
+                self.function().end_breakable_block();
+            }
+            K::ThrowStatement => {
+                let expression = self.visit_node(&node.as_throw_statement().expression);
+                self.function().emit(Operation::Throw(expression));
+            }
+            K::TryStatement if contains_yield(node) => self.transform_and_emit_try_statement(node),
+            _ => {
+                let visited = self.visit_list_element(node).into_iter().next();
+                self.emit_statement(visited);
+            }
+        }
+    }
+
+    fn transform_and_emit_embedded_statement(&mut self, node: &Arc<Node>) {
+        if node.kind == SyntaxKind::Block {
+            self.transform_and_emit_statements(&node.as_block().statements);
+        } else {
+            self.transform_and_emit_statement(node);
+        }
+    }
+
+    /// ```text
+    /// if (x) then else otherwise
+    ///
+    ///     .brfalse elseLabel, (x)
+    ///     then
+    ///     .br endLabel
+    ///     .mark elseLabel
+    ///     otherwise
+    ///     .mark endLabel
+    /// ```
+    fn transform_and_emit_if_statement(&mut self, node: &Arc<Node>) {
+        let data = node.as_if_statement();
+        let branches_contain_yield = contains_yield(&data.then_statement)
+            || data
+                .else_statement
+                .as_ref()
+                .is_some_and(|statement| contains_yield(statement));
+        if !branches_contain_yield {
+            let visited = self.visit_node(node);
+            self.emit_statement(Some(visited));
+            return;
+        }
+        let end_label = self.function().define_label();
+        let else_label = data
+            .else_statement
+            .as_ref()
+            .map(|_| self.function().define_label());
+        let condition = self.visit_node(&data.expression);
+        self.function().emit(Operation::BreakWhenFalse(
+            else_label.unwrap_or(end_label),
+            condition,
+        ));
+        self.transform_and_emit_embedded_statement(&data.then_statement);
+        if let (Some(else_statement), Some(else_label)) = (&data.else_statement, else_label) {
+            let function = self.function();
+            function.emit(Operation::Break(end_label));
+            function.mark_label(else_label);
+            self.transform_and_emit_embedded_statement(else_statement);
+        }
+        self.function().mark_label(end_label);
+    }
+
+    /// ```text
+    /// do body while (x);
+    ///
+    ///     .loop conditionLabel, endLabel
+    ///     .mark loopLabel
+    ///     body
+    ///     .mark conditionLabel
+    ///     .brtrue loopLabel, (x)
+    ///     .endloop
+    ///     .mark endLabel
+    /// ```
+    fn transform_and_emit_do_statement(&mut self, node: &Arc<Node>) {
+        let data = node.as_do_statement();
+        let function = self.function();
+        let condition_label = function.define_label();
+        let loop_label = function.define_label();
+        function.begin_loop_block(condition_label);
+        function.mark_label(loop_label);
+        self.transform_and_emit_embedded_statement(&data.statement);
+        self.function().mark_label(condition_label);
+        let condition = self.visit_node(&data.expression);
+        let function = self.function();
+        function.emit(Operation::BreakWhenTrue(loop_label, condition));
+        function.end_breakable_block();
+    }
+
+    /// ```text
+    /// while (x) body
+    ///
+    ///     .loop loopLabel, endLabel
+    ///     .mark loopLabel
+    ///     .brfalse endLabel, (x)
+    ///     body
+    ///     .br loopLabel
+    ///     .endloop
+    ///     .mark endLabel
+    /// ```
+    fn transform_and_emit_while_statement(&mut self, node: &Arc<Node>) {
+        let data = node.as_while_statement();
+        let function = self.function();
+        let loop_label = function.define_label();
+        let end_label = function.begin_loop_block(loop_label);
+        function.mark_label(loop_label);
+        let condition = self.visit_node(&data.expression);
+        self.function()
+            .emit(Operation::BreakWhenFalse(end_label, condition));
+        self.transform_and_emit_embedded_statement(&data.statement);
+        let function = self.function();
+        function.emit(Operation::Break(loop_label));
+        function.end_breakable_block();
+    }
+
+    /// ```text
+    /// for (initializer; condition; incrementor) body
+    ///
+    ///     initializer
+    ///     .loop incrementLabel, endLabel
+    ///     .mark conditionLabel
+    ///     .brfalse endLabel, (condition)
+    ///     body
+    ///     .mark incrementLabel
+    ///     incrementor
+    ///     .br conditionLabel
+    ///     .endloop
+    ///     .mark endLabel
+    /// ```
+    fn transform_and_emit_for_statement(&mut self, node: &Arc<Node>) {
+        let factory = self.factory;
+        let data = node.as_for_statement();
+        let function = self.function();
+        let condition_label = function.define_label();
+        let increment_label = function.define_label();
+        let end_label = function.begin_loop_block(increment_label);
+        if let Some(initializer) = &data.initializer {
+            if initializer.kind == SyntaxKind::VariableDeclarationList {
+                self.transform_and_emit_variable_declaration_list(initializer);
+            } else {
+                let expression = self.visit_node(initializer);
+                let statement = factory.create_expression_statement(expression);
+                self.emit_statement(Some(factory.with_text_range(&statement, initializer.loc)));
+            }
+        }
+        self.function().mark_label(condition_label);
+        if let Some(condition) = &data.condition {
+            let condition = self.visit_node(condition);
+            self.function()
+                .emit(Operation::BreakWhenFalse(end_label, condition));
+        }
+        self.transform_and_emit_embedded_statement(&data.statement);
+        self.function().mark_label(increment_label);
+        if let Some(incrementor) = &data.incrementor {
+            let expression = self.visit_node(incrementor);
+            let statement = factory.create_expression_statement(expression);
+            self.emit_statement(Some(factory.with_text_range(&statement, incrementor.loc)));
+        }
+        let function = self.function();
+        function.emit(Operation::Break(condition_label));
+        function.end_breakable_block();
+    }
+
+    /// Steps through a snapshot of the keys, since the loop itself cannot be resumed:
+    ///
+    /// ```text
+    /// for (var p in o) body
+    ///
+    ///     _a = o;
+    ///     _b = [];
+    ///     for (_c in _a) _b.push(_c);
+    ///     _i = 0;
+    ///     .loop incrementLabel, endLabel
+    ///     .mark conditionLabel
+    ///     .brfalse endLabel, (_i < _b.length)
+    ///     _c = _b[_i];
+    ///     .brfalse incrementLabel, (_c in _a)
+    ///     p = _c;
+    ///     body
+    ///     .mark incrementLabel
+    ///     _i++;
+    ///     .br conditionLabel
+    ///     .endloop
+    ///     .mark endLabel
+    /// ```
+    fn transform_and_emit_for_in_statement(&mut self, node: &Arc<Node>) {
+        let factory = self.factory;
+        let data = node.as_for_in_statement();
+        let object = self.declare_local(None);
+        let keys = self.declare_local(None);
+        let key = self.declare_local(None);
+        let function = self
+            .function
+            .as_mut()
+            .expect("the visitor is in a generator");
+        let index_name = self
+            .context
+            .create_loop_variable_name(&mut function.generated_names);
+        function.hoist_variable(&index_name);
+        let index = || factory.create_identifier(index_name.as_str());
+        let copy = |node: &Arc<Node>| factory.create_identifier(node.text());
+
+        let expression = self.visit_node(&data.expression);
+        self.function()
+            .emit(Operation::Assign(copy(&object), expression));
+        let collect_keys = factory.create(
+            SyntaxKind::ForInStatement,
+            ForInStatement {
+                initializer: copy(&key),
+                expression: copy(&object),
+                statement: factory.create_expression_statement(factory.create_call(
+                    factory.create_property_access(copy(&keys), "push"),
+                    vec![copy(&key)],
+                )),
+            },
+        );
+        let function = self.function();
+        function.emit(Operation::Assign(
+            copy(&keys),
+            factory.create_array_literal(Vec::new(), false),
+        ));
+        function.emit(Operation::Statement(collect_keys));
+        function.emit(Operation::Assign(
+            index(),
+            factory.create_numeric_literal("0"),
+        ));
+
+        let condition_label = function.define_label();
+        let increment_label = function.define_label();
+        let end_label = function.begin_loop_block(increment_label);
+        function.mark_label(condition_label);
+        function.emit(Operation::BreakWhenFalse(
+            end_label,
+            factory.create_binary(
+                index(),
+                SyntaxKind::LessThanToken,
+                factory.create_property_access(copy(&keys), "length"),
+            ),
+        ));
+        function.emit(Operation::Assign(
+            copy(&key),
+            factory.create_element_access(copy(&keys), index()),
+        ));
+        function.emit(Operation::BreakWhenFalse(
+            increment_label,
+            factory.create_binary(copy(&key), SyntaxKind::InKeyword, copy(&object)),
+        ));
+        let variable = if data.initializer.kind == SyntaxKind::VariableDeclarationList {
+            let declarations = &data.initializer.as_variable_declaration_list().declarations;
+            self.hoist_declared_names(declarations);
+            to_assignment_target(
+                &factory,
+                &declarations.nodes[0].as_variable_declaration().name,
+            )
+        } else {
+            self.visit_node(&data.initializer)
+        };
+        self.function()
+            .emit(Operation::Assign(variable, copy(&key)));
+        self.transform_and_emit_embedded_statement(&data.statement);
+
+        let function = self.function();
+        function.mark_label(increment_label);
+        let increment = factory.create(
+            SyntaxKind::PostfixUnaryExpression,
+            PostfixUnaryExpression {
+                operand: index(),
+                operator: SyntaxKind::PlusPlusToken,
+            },
+        );
+        function.emit(Operation::Statement(
+            factory.create_expression_statement(increment),
+        ));
+        function.emit(Operation::Break(condition_label));
+        function.end_breakable_block();
+    }
+
+    /// Dispatches each run of `case` clauses to the clauses' labels with a `switch` of its
+    /// own, starting a new run at each clause whose expression contains a `yield`:
+    ///
+    /// ```text
+    /// switch (x) { case a: A; case b: B; default: C; }
+    ///
+    ///     .switch endLabel
+    ///     _a = x;
+    ///     switch (_a) { case a: .br clauseLabels[0]; case b: .br clauseLabels[1]; }
+    ///     .br clauseLabels[2]
+    ///     .mark clauseLabels[0]
+    ///     A
+    ///     .mark clauseLabels[1]
+    ///     B
+    ///     .mark clauseLabels[2]
+    ///     C
+    ///     .endswitch
+    ///     .mark endLabel
+    /// ```
+    fn transform_and_emit_switch_statement(&mut self, node: &Arc<Node>) {
+        let factory = self.factory;
+        let data = node.as_switch_statement();
+        let clauses = &data.case_block.as_case_block().clauses;
+        let end_label = self.function().begin_switch_block();
+        let expression = self.visit_node(&data.expression);
+        let expression = self.cache_expression(expression);
+
+        let clause_labels: Vec<Label> = clauses
+            .iter()
+            .map(|_| self.function().define_label())
+            .collect();
+        let default_clause = clauses
+            .iter()
+            .position(|clause| clause.kind == SyntaxKind::DefaultClause);
+
+        let mut pending = Vec::new();
+        for (index, clause) in clauses.iter().enumerate() {
+            if clause.kind != SyntaxKind::CaseClause {
+                continue;
+            }
+            let case_expression = &clause.as_case_clause().expression;
+            if contains_yield(case_expression) && !pending.is_empty() {
+                self.emit_dispatch(&expression, std::mem::take(&mut pending));
+            }
+            let case_expression = self.visit_node(case_expression);
+            let jump = self
+                .function()
+                .create_inline_break(&factory, clause_labels[index]);
+            pending.push(factory.create(
+                SyntaxKind::CaseClause,
+                CaseClause {
+                    expression: case_expression,
+                    statements: factory.create_node_list(vec![jump]),
+                },
+            ));
+        }
+        if !pending.is_empty() {
+            self.emit_dispatch(&expression, pending);
+        }
+        let fallback = default_clause.map_or(end_label, |index| clause_labels[index]);
+        self.function().emit(Operation::Break(fallback));
+
+        for (clause, label) in clauses.iter().zip(clause_labels) {
+            self.function().mark_label(label);
+            let statements = match clause.kind {
+                SyntaxKind::CaseClause => &clause.as_case_clause().statements,
+                _ => &clause.as_default_clause().statements,
+            };
+            self.transform_and_emit_statements(statements);
+        }
+        self.function().end_breakable_block();
+    }
+
+    /// Emits `switch (expression) { clauses }`
+    fn emit_dispatch(&mut self, expression: &Arc<Node>, clauses: Vec<Arc<Node>>) {
+        let factory = self.factory;
+        let case_block = factory.create(
+            SyntaxKind::CaseBlock,
+            CaseBlock {
+                clauses: factory.create_node_list(clauses),
+            },
+        );
+        let statement = factory.create(
+            SyntaxKind::SwitchStatement,
+            SwitchStatement {
+                expression: factory.create_identifier(expression.text()),
+                case_block,
+            },
+        );
+        self.function().emit(Operation::Statement(statement));
+    }
+
+    /// ```text
+    /// try { A } catch (e) { B } finally { C }
+    ///
+    ///     .try tryLabel, catchLabel, finallyLabel, endLabel
+    ///     .mark tryLabel
+    ///     A
+    ///     .br endLabel
+    ///     .mark catchLabel
+    ///     e_1 = %error%;
+    ///     B
+    ///     .br endLabel
+    ///     .mark finallyLabel
+    ///     C
+    ///     .endfinally
+    ///     .endtry
+    ///     .mark endLabel
+    /// ```
+    ///
+    /// The catch variable becomes a variable of the generator, renamed so that it does not
+    /// clash with the other variables that share the generator's scope.
+    fn transform_and_emit_try_statement(&mut self, node: &Arc<Node>) {
+        let factory = self.factory;
+        let data = node.as_try_statement();
+        self.function().begin_exception_block();
+        self.transform_and_emit_embedded_statement(&data.try_block);
+        if let Some(catch_clause) = &data.catch_clause {
+            let catch_data = catch_clause.as_catch_clause();
+            let name = catch_data
+                .variable_declaration
+                .as_ref()
+                .map(|declaration| &declaration.as_variable_declaration().name);
+            let renamed = match name {
+                // A name an earlier transformer generated is already unique
+                Some(name) if name.flags.contains(NodeFlags::SYNTHESIZED) => {
+                    self.function().hoist_variable(name.text());
+                    None
+                }
+                Some(name) if name.kind == SyntaxKind::Identifier => {
+                    let renamed = self.declare_local(Some(name.text()));
+                    self.renamed_catch_variables
+                        .push((name.text().to_string(), renamed.text().to_string()));
+                    Some(renamed)
+                }
+                _ => None,
+            };
+            let variable = match (&renamed, name) {
+                (Some(renamed), _) => renamed.text().to_string(),
+                (None, Some(name)) if name.flags.contains(NodeFlags::SYNTHESIZED) => {
+                    name.text().to_string()
+                }
+                _ => self.declare_local(None).text().to_string(),
+            };
+            self.function().begin_catch_block(&factory, &variable);
+            self.transform_and_emit_embedded_statement(&catch_data.block);
+            if renamed.is_some() {
+                self.renamed_catch_variables.pop();
+            }
+        }
+        if let Some(finally_block) = &data.finally_block {
+            self.function().begin_finally_block();
+            self.transform_and_emit_embedded_statement(finally_block);
+        }
+        self.function().end_exception_block();
+    }
+
+    fn visit_containing_yield(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        match node.kind {
+            K::BinaryExpression => self.visit_binary_expression(node),
+            K::ConditionalExpression => self.visit_conditional_expression(node),
+            K::YieldExpression => self.visit_yield_expression(node),
+            K::ArrayLiteralExpression => {
+                let data = node.as_array_literal_expression();
+                self.visit_elements(&data.elements, None, data.multi_line)
+            }
+            K::ObjectLiteralExpression => self.visit_object_literal_expression(node),
+            K::ElementAccessExpression
+                if contains_yield(&node.as_element_access_expression().argument_expression) =>
+            {
+                // `x[yield]` becomes `_a = x; ...; _a[_b.sent()]`
+                let data = node.as_element_access_expression();
+                let expression = self.visit_node(&data.expression);
+                let expression = self.cache_expression(expression);
+                factory.update_element_access(
+                    node,
+                    expression,
+                    data.question_dot_token.clone(),
+                    self.visit_node(&data.argument_expression),
+                )
+            }
+            K::CallExpression
+                if node
+                    .as_call_expression()
+                    .arguments
+                    .iter()
+                    .any(|argument| contains_yield(argument)) =>
+            {
+                self.visit_call_expression(node)
+            }
+            K::NewExpression
+                if node
+                    .as_new_expression()
+                    .arguments
+                    .as_ref()
+                    .is_some_and(|arguments| {
+                        arguments.iter().any(|argument| contains_yield(argument))
+                    }) =>
+            {
+                self.visit_new_expression(node)
+            }
+            _ => visit_each_child(node, self, &factory),
+        }
+    }
+
+    fn visit_binary_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_binary_expression();
+        let operator = data.operator_token.kind;
+        if !contains_yield(&data.right) {
+            return visit_each_child(node, self, &factory);
+        }
+        if operator.is_assignment_operator() {
+            return self.visit_assignment_expression(node);
+        }
+        match operator {
+            SyntaxKind::AmpersandAmpersandToken | SyntaxKind::BarBarToken => {
+                self.visit_logical_binary_expression(node)
+            }
+            SyntaxKind::CommaToken => self.visit_comma_expression(node),
+            // `a() + (yield)` becomes `_a = a(); ...; _a + _b.sent()`
+            _ => {
+                let left = self.visit_node(&data.left);
+                let left = self.cache_expression(left);
+                factory.update_binary(
+                    node,
+                    left,
+                    Arc::clone(&data.operator_token),
+                    self.visit_node(&data.right),
+                )
+            }
+        }
+    }
+
+    /// Evaluates the target of an assignment before the `yield` in its value, so that
+    /// `a.b = yield` becomes `_a = a; ...; _a.b = _b.sent()`
+    fn visit_assignment_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_binary_expression();
+        let left = &data.left;
+        let target = match left.kind {
+            SyntaxKind::PropertyAccessExpression => {
+                let access = left.as_property_access_expression();
+                let expression = self.visit_node(&access.expression);
+                factory.update_property_access(
+                    left,
+                    self.cache_expression(expression),
+                    None,
+                    Arc::clone(&access.name),
+                )
+            }
+            SyntaxKind::ElementAccessExpression => {
+                let access = left.as_element_access_expression();
+                let expression = self.visit_node(&access.expression);
+                let expression = self.cache_expression(expression);
+                let argument = self.visit_node(&access.argument_expression);
+                factory.update_element_access(
+                    left,
+                    expression,
+                    None,
+                    self.cache_expression(argument),
+                )
+            }
+            _ => self.visit_node(left),
+        };
+        let operator = data.operator_token.kind;
+        match non_compound_operator(operator) {
+            Some(operator) => {
+                let current = self.cache_expression(Arc::clone(&target));
+                let right = self.visit_node(&data.right);
+                let value = factory.create_binary(current, operator, right);
+                let assignment =
+                    factory.create_assignment(target, factory.with_text_range(&value, node.loc));
+                factory.with_text_range(&assignment, node.loc)
+            }
+            None => factory.update_binary(
+                node,
+                target,
+                Arc::clone(&data.operator_token),
+                self.visit_node(&data.right),
+            ),
+        }
+    }
+
+    /// ```text
+    /// a() && (yield) && b()
+    ///
+    ///     _a = a();
+    ///     .brfalse resultLabel, (_a)
+    ///     .yield resumeLabel
+    ///     .mark resumeLabel
+    ///     _a = %sent%;
+    ///     .brfalse resultLabel, (_a)
+    ///     _a = b();
+    ///     .mark resultLabel
+    ///     _a
+    /// ```
+    fn visit_logical_binary_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let data = node.as_binary_expression();
+        let result_label = self.function().define_label();
+        let result = self.declare_local(None);
+        let left = self.visit_node(&data.left);
+        let function = self.function();
+        function.emit(Operation::Assign(Arc::clone(&result), left));
+        let condition = Arc::clone(&result);
+        function.emit(
+            if data.operator_token.kind == SyntaxKind::AmpersandAmpersandToken {
+                Operation::BreakWhenFalse(result_label, condition)
+            } else {
+                Operation::BreakWhenTrue(result_label, condition)
+            },
+        );
+        let right = self.visit_node(&data.right);
+        let function = self.function();
+        function.emit(Operation::Assign(Arc::clone(&result), right));
+        function.mark_label(result_label);
+        result
+    }
+
+    /// Emits the operands of a comma expression before the one with a `yield` as a statement,
+    /// so that `a(), yield, b()` becomes `a(); ...; _a.sent(), b()`
+    fn visit_comma_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let mut operands = Vec::new();
+        flatten_comma_expression(node, &mut operands);
+        let mut pending = Vec::new();
+        for operand in operands {
+            if contains_yield(&operand) && !pending.is_empty() {
+                let expression = inline_expressions(&factory, std::mem::take(&mut pending));
+                self.function().emit(Operation::Statement(
+                    factory.create_expression_statement(expression),
+                ));
+            }
+            pending.push(self.visit_node(&operand));
+        }
+        inline_expressions(&factory, pending)
+    }
+
+    /// ```text
+    /// a() ? yield : b()
+    ///
+    ///     .brfalse whenFalseLabel, (a())
+    ///     .yield resumeLabel
+    ///     .mark resumeLabel
+    ///     _a = %sent%;
+    ///     .br resultLabel
+    ///     .mark whenFalseLabel
+    ///     _a = b();
+    ///     .mark resultLabel
+    ///     _a
+    /// ```
+    fn visit_conditional_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_conditional_expression();
+        if !contains_yield(&data.when_true) && !contains_yield(&data.when_false) {
+            return visit_each_child(node, self, &factory);
+        }
+        let function = self.function();
+        let when_false_label = function.define_label();
+        let result_label = function.define_label();
+        let result = self.declare_local(None);
+        let condition = self.visit_node(&data.condition);
+        self.function()
+            .emit(Operation::BreakWhenFalse(when_false_label, condition));
+        let when_true = self.visit_node(&data.when_true);
+        let function = self.function();
+        function.emit(Operation::Assign(Arc::clone(&result), when_true));
+        function.emit(Operation::Break(result_label));
+        function.mark_label(when_false_label);
+        let when_false = self.visit_node(&data.when_false);
+        let function = self.function();
+        function.emit(Operation::Assign(Arc::clone(&result), when_false));
+        function.mark_label(result_label);
+        result
+    }
+
+    /// Yields to the caller and resumes with the value it sends: `x = yield a` becomes
+    /// `return [4, a];` in one case and `x = _a.sent();` at the start of the next
+    fn visit_yield_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_yield_expression();
+        let resume_label = self.function().define_label();
+        let expression = data
+            .expression
+            .as_ref()
+            .map(|expression| self.visit_node(expression));
+        let operation = if data.asterisk_token.is_some() {
+            let expression = expression.unwrap_or_else(|| factory.create_void_zero());
+            // An async generator delegates to an iterator that `__asyncDelegator` already made
+            let iterator = if is_helper_call(&expression, "__asyncDelegator") {
+                expression
+            } else {
+                self.context.request_helper(&VALUES_HELPER);
+                factory.create_call(factory.create_identifier("__values"), vec![expression])
+            };
+            Operation::YieldStar(iterator)
+        } else {
+            Operation::Yield(expression)
+        };
+        let function = self.function();
+        function.emit(operation);
+        function.mark_label(resume_label);
+        let resume = function.create_generator_resume(&factory);
+        factory.with_text_range(&resume, node.loc)
+    }
+
+    /// Builds an array in steps before each element with a `yield`, so that `[1, yield, 2]`
+    /// becomes `_a = [1]; ...; _a.concat([_b.sent(), 2])`
+    ///
+    /// `leading_element` goes before the elements, in the first array built.
+    fn visit_elements(
+        &mut self,
+        elements: &NodeList,
+        mut leading_element: Option<Arc<Node>>,
+        multi_line: bool,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        let initial_count = elements
+            .iter()
+            .take_while(|element| !contains_yield(element))
+            .count();
+        let mut temp = None;
+        if initial_count > 0 {
+            let local = self.declare_local(None);
+            let mut initial: Vec<Arc<Node>> = leading_element.take().into_iter().collect();
+            for element in &elements.nodes[..initial_count] {
+                initial.push(self.visit_node(element));
+            }
+            self.function().emit(Operation::Assign(
+                Arc::clone(&local),
+                factory.create_array_literal(initial, false),
+            ));
+            temp = Some(local);
+        }
+
+        let mut expressions: Vec<Arc<Node>> = Vec::new();
+        for element in &elements.nodes[initial_count..] {
+            if contains_yield(element) && !expressions.is_empty() {
+                let value = match &temp {
+                    Some(temp) => create_array_concat(
+                        &factory,
+                        temp,
+                        factory.create_array_literal(std::mem::take(&mut expressions), multi_line),
+                    ),
+                    None => {
+                        let mut elements: Vec<Arc<Node>> =
+                            leading_element.take().into_iter().collect();
+                        elements.append(&mut expressions);
+                        factory.create_array_literal(elements, multi_line)
+                    }
+                };
+                let local = match temp.take() {
+                    Some(temp) => temp,
+                    None => self.declare_local(None),
+                };
+                self.function()
+                    .emit(Operation::Assign(Arc::clone(&local), value));
+                temp = Some(local);
+            }
+            expressions.push(self.visit_node(element));
+        }
+
+        match temp {
+            Some(temp) => create_array_concat(
+                &factory,
+                &temp,
+                factory.create_array_literal(expressions, multi_line),
+            ),
+            None => {
+                let mut elements: Vec<Arc<Node>> = leading_element.into_iter().collect();
+                elements.append(&mut expressions);
+                factory.create_array_literal(elements, multi_line)
+            }
+        }
+    }
+
+    /// Builds an object in steps, so that `{ a: 1, b: yield, c: 2 }` becomes
+    /// `_a = { a: 1 }; ...; (_a.b = _b.sent(), _a.c = 2, _a)`
+    fn visit_object_literal_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_object_literal_expression();
+        let properties = &data.properties;
+        let initial_count = properties
+            .iter()
+            .take_while(|property| !contains_yield(property))
+            .count();
+        let temp = self.declare_local(None);
+        let initial = properties.nodes[..initial_count]
+            .iter()
+            .map(|property| self.visit_node(property))
+            .collect();
+        self.function().emit(Operation::Assign(
+            Arc::clone(&temp),
+            factory.create(
+                SyntaxKind::ObjectLiteralExpression,
+                ObjectLiteralExpression {
+                    properties: factory.create_node_list(initial),
+                    multi_line: data.multi_line,
+                },
+            ),
+        ));
+
+        let mut expressions = Vec::new();
+        for property in &properties.nodes[initial_count..] {
+            if contains_yield(property) && !expressions.is_empty() {
+                let expression = inline_expressions(&factory, std::mem::take(&mut expressions));
+                self.function().emit(Operation::Statement(
+                    factory.create_expression_statement(expression),
+                ));
+            }
+            let assignment = create_property_assignment_expression(&factory, &temp, property);
+            expressions.push(self.visit_node(&assignment));
+        }
+        expressions.push(temp);
+        inline_expressions(&factory, expressions)
+    }
+
+    /// Calls the function with `apply`, so that `a.b(1, yield, 2)` becomes
+    /// `_b = (_a = a).b; _c = [1]; ...; _b.apply(_a, _c.concat([_d.sent(), 2]))`
+    fn visit_call_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_call_expression();
+        let (target, this_argument) = self.create_call_binding(&data.expression);
+        let target = self.visit_node(&target);
+        let target = self.cache_expression(target);
+        let arguments = self.visit_elements(&data.arguments, None, false);
+        let call = create_function_apply_call(&factory, target, this_argument, arguments);
+        factory.with_text_range(&call, node.loc)
+    }
+
+    /// Constructs through `bind`, so that `new a.b(1, yield, 2)` becomes
+    /// `_b = (_a = a.b).bind; _c = [void 0, 1]; ...;
+    /// new (_b.apply(_a, _c.concat([_d.sent(), 2])))()`
+    fn visit_new_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_new_expression();
+        let bind = factory.create_property_access(Arc::clone(&data.expression), "bind");
+        let (target, this_argument) = self.create_call_binding(&bind);
+        let target = self.visit_node(&target);
+        let target = self.cache_expression(target);
+        let arguments = data
+            .arguments
+            .as_ref()
+            .expect("the arguments contain a yield");
+        let arguments = self.visit_elements(arguments, Some(factory.create_void_zero()), false);
+        let constructor = create_function_apply_call(&factory, target, this_argument, arguments);
+        let expression = factory.create(
+            SyntaxKind::NewExpression,
+            NewExpression {
+                expression: constructor,
+                type_arguments: None,
+                arguments: Some(factory.create_node_list(Vec::new())),
+            },
+        );
+        factory.with_text_range(&expression, node.loc)
+    }
+
+    /// Splits a callee into the function to call and the value of `this` for the call,
+    /// storing an object that may change before the call in a temporary variable
+    fn create_call_binding(&mut self, callee: &Arc<Node>) -> (Arc<Node>, Arc<Node>) {
+        let factory = self.factory;
+        let object = match callee.kind {
+            SyntaxKind::PropertyAccessExpression | SyntaxKind::ElementAccessExpression => {
+                callee.expression().expect("accesses have an object")
+            }
+            _ => return (Arc::clone(callee), factory.create_void_zero()),
+        };
+        if object.kind == SyntaxKind::SuperKeyword {
+            return (
+                Arc::clone(callee),
+                factory.create_token(SyntaxKind::ThisKeyword),
+            );
+        }
+        if !should_capture_in_temp(object) {
+            return (Arc::clone(callee), Arc::clone(object));
+        }
+        let this_argument = self.declare_local(None);
+        let object = factory.create_parenthesized(
+            factory.create_assignment(Arc::clone(&this_argument), Arc::clone(object)),
+        );
+        let target = if callee.kind == SyntaxKind::PropertyAccessExpression {
+            let data = callee.as_property_access_expression();
+            factory.update_property_access(callee, object, None, Arc::clone(&data.name))
+        } else {
+            let data = callee.as_element_access_expression();
+            factory.update_element_access(
+                callee,
+                object,
+                None,
+                Arc::clone(&data.argument_expression),
+            )
+        };
+        (target, this_argument)
+    }
+
+    /// Builds the state machine from the operations of the generator:
+    /// `__generator(this, function (_a) { switch (_a.label) { ... } })`
+    fn build(&mut self) -> Arc<Node> {
+        let factory = self.factory;
+        let function = self.function();
+        let mut builder = Builder::new(factory, function);
+        let statements = builder.build_statements();
+        let label_numbers = builder.label_numbers;
+        let state = function.state.clone();
+
+        let body = factory.create_block(statements, true);
+        let body = LabelResolver {
+            factory,
+            label_numbers,
+        }
+        .visit_node(&body);
+        let state_machine = factory.create_function_expression(
+            None,
+            vec![factory.create_parameter(factory.create_identifier(state))],
+            body,
+        );
+        self.context.request_helper(&GENERATOR_HELPER);
+        factory.create_call(
+            factory.create_identifier("__generator"),
+            vec![factory.create_token(SyntaxKind::ThisKeyword), state_machine],
+        )
+    }
+}
+
+/// Writes the operations of a generator as the cases of its state machine, starting a case
+/// at each label
+struct Builder<'a> {
+    factory: NodeFactory,
+    function: &'a mut GeneratorFunction,
+    /// The number of the case being written
+    label_number: usize,
+    /// The number of the case each label starts
+    label_numbers: HashMap<Label, usize>,
+    last_operation_was_abrupt: bool,
+    last_operation_was_completion: bool,
+    clauses: Option<Vec<Arc<Node>>>,
+    /// The statements of the case being written
+    statements: Option<Vec<Arc<Node>>>,
+    /// The index of the next block action to apply
+    block_index: usize,
+    /// The blocks of the `try` statements around the case being written
+    exception_block_stack: Vec<Option<usize>>,
+    current_exception_block: Option<usize>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(factory: NodeFactory, function: &'a mut GeneratorFunction) -> Self {
+        Builder {
+            factory,
+            function,
+            label_number: 0,
+            label_numbers: HashMap::new(),
+            last_operation_was_abrupt: false,
+            last_operation_was_completion: false,
+            clauses: None,
+            statements: None,
+            block_index: 0,
+            exception_block_stack: Vec::new(),
+            current_exception_block: None,
+        }
+    }
+
+    fn build_statements(&mut self) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let operation_count = self.function.operations.len();
+        for index in 0..operation_count {
+            self.write_operation(index);
+        }
+        self.flush_final_label(operation_count);
+
+        if let Some(clauses) = self.clauses.take() {
+            let label = factory.create_property_access(
+                factory.create_identifier(self.function.state.as_str()),
+                "label",
+            );
+            let case_block = factory.create(
+                SyntaxKind::CaseBlock,
+                CaseBlock {
+                    clauses: factory.create_node_list(clauses),
+                },
+            );
+            return vec![factory.create(
+                SyntaxKind::SwitchStatement,
+                SwitchStatement {
+                    expression: label,
+                    case_block,
+                },
+            )];
+        }
+        self.statements.take().unwrap_or_default()
+    }
+
+    /// Ends the case being written and starts the next
+    fn flush_label(&mut self) {
+        if self.statements.is_none() {
+            return;
+        }
+        self.append_label(!self.last_operation_was_abrupt);
+        self.last_operation_was_abrupt = false;
+        self.last_operation_was_completion = false;
+        self.label_number += 1;
+    }
+
+    fn flush_final_label(&mut self, operation_index: usize) {
+        if self.is_final_label_reachable(operation_index) {
+            self.try_enter_label(operation_index);
+            self.write_return(None);
+        }
+        if self.statements.is_some() && self.clauses.is_some() {
+            self.append_label(false);
+        }
+    }
+
+    /// Whether control can reach the end of the body: either the last operation does not
+    /// complete the generator, or some jump targets the end
+    fn is_final_label_reachable(&self, operation_index: usize) -> bool {
+        if !self.last_operation_was_completion {
+            return true;
+        }
+        self.function
+            .label_offsets
+            .iter()
+            .enumerate()
+            .any(|(label, offset)| {
+                *offset == Some(operation_index) && self.function.referenced_labels.contains(&label)
+            })
+    }
+
+    /// Adds the case being written to the `switch`, falling through to the next case with
+    /// `_a.label = n + 1` if `mark_label_end` is set
+    fn append_label(&mut self, mark_label_end: bool) {
+        let factory = self.factory;
+        let state_name = self.function.state.clone();
+        let state = || factory.create_identifier(state_name.as_str());
+        let mut statements = self.statements.take();
+        if let Some(statements) = &mut statements {
+            if let Some(index) = self.current_exception_block.take() {
+                let CodeBlock::Exception(block) = self.function.blocks[index] else {
+                    unreachable!("exception blocks are only entered for try statements");
+                };
+                let labels = vec![
+                    self.function
+                        .create_label(&factory, Some(block.start_label)),
+                    self.function.create_label(&factory, block.catch_label),
+                    self.function.create_label(&factory, block.finally_label),
+                    self.function.create_label(&factory, Some(block.end_label)),
+                ];
+                let push = factory.create_call(
+                    factory.create_property_access(
+                        factory.create_property_access(state(), "trys"),
+                        "push",
+                    ),
+                    vec![factory.create(
+                        SyntaxKind::ArrayLiteralExpression,
+                        ArrayLiteralExpression {
+                            elements: factory.create_node_list(labels),
+                            multi_line: false,
+                        },
+                    )],
+                );
+                statements.insert(0, factory.create_expression_statement(push));
+            }
+            if mark_label_end {
+                let next = factory.create_assignment(
+                    factory.create_property_access(state(), "label"),
+                    factory.create_numeric_literal((self.label_number + 1).to_string()),
+                );
+                statements.push(factory.create_expression_statement(next));
+            }
+        }
+        let clause = factory.create(
+            SyntaxKind::CaseClause,
+            CaseClause {
+                expression: factory.create_numeric_literal(self.label_number.to_string()),
+                statements: factory.create_node_list(statements.unwrap_or_default()),
+            },
+        );
+        self.clauses.get_or_insert_with(Vec::new).push(clause);
+    }
+
+    /// Starts a new case if a label marks the operation at `operation_index`
+    fn try_enter_label(&mut self, operation_index: usize) {
+        let labels: Vec<Label> = self
+            .function
+            .label_offsets
+            .iter()
+            .enumerate()
+            .filter(|(_, offset)| **offset == Some(operation_index))
+            .map(|(label, _)| label)
+            .collect();
+        for label in labels {
+            self.flush_label();
+            self.label_numbers.insert(label, self.label_number);
+        }
+    }
+
+    /// Tracks the `try` statements that open or close at `operation_index`
+    fn try_enter_or_leave_block(&mut self, operation_index: usize) {
+        while let Some(action) = self.function.block_actions.get(self.block_index).copied() {
+            if action.offset > operation_index {
+                break;
+            }
+            self.block_index += 1;
+            if !matches!(self.function.blocks[action.block], CodeBlock::Exception(_)) {
+                continue;
+            }
+            if action.is_open {
+                self.statements.get_or_insert_with(Vec::new);
+                self.exception_block_stack
+                    .push(self.current_exception_block);
+                self.current_exception_block = Some(action.block);
+            } else {
+                self.current_exception_block = self.exception_block_stack.pop().flatten();
+            }
+        }
+    }
+
+    fn write_operation(&mut self, operation_index: usize) {
+        self.try_enter_label(operation_index);
+        self.try_enter_or_leave_block(operation_index);
+        // Nothing after a jump or return in the same case can run
+        if self.last_operation_was_abrupt {
+            return;
+        }
+        self.last_operation_was_completion = false;
+        let factory = self.factory;
+        match self.function.operations[operation_index].clone() {
+            Operation::Nop => {}
+            Operation::Statement(statement) => self.write_statement(statement),
+            Operation::Assign(left, right) => self.write_statement(
+                factory.create_expression_statement(factory.create_assignment(left, right)),
+            ),
+            Operation::Break(label) => {
+                self.last_operation_was_abrupt = true;
+                let statement = self.function.create_inline_break(&factory, label);
+                self.write_statement(statement);
+            }
+            Operation::BreakWhenTrue(label, condition) => self.write_break_when(label, condition),
+            Operation::BreakWhenFalse(label, condition) => {
+                let condition = factory.create(
+                    SyntaxKind::PrefixUnaryExpression,
+                    PrefixUnaryExpression {
+                        operator: SyntaxKind::ExclamationToken,
+                        operand: condition,
+                    },
+                );
+                self.write_break_when(label, condition)
+            }
+            Operation::Yield(expression) => {
+                self.last_operation_was_abrupt = true;
+                self.write_statement(create_instruction_return(
+                    &factory,
+                    Instruction::Yield,
+                    expression,
+                ));
+            }
+            Operation::YieldStar(expression) => {
+                self.last_operation_was_abrupt = true;
+                self.write_statement(create_instruction_return(
+                    &factory,
+                    Instruction::YieldStar,
+                    Some(expression),
+                ));
+            }
+            Operation::Return(expression) => self.write_return(expression),
+            Operation::Throw(expression) => {
+                self.last_operation_was_abrupt = true;
+                self.last_operation_was_completion = true;
+                self.write_statement(
+                    factory.create(SyntaxKind::ThrowStatement, ThrowStatement { expression }),
+                );
+            }
+            Operation::Endfinally => {
+                self.last_operation_was_abrupt = true;
+                self.write_statement(create_instruction_return(
+                    &factory,
+                    Instruction::Endfinally,
+                    None,
+                ));
+            }
+        }
+    }
+
+    fn write_statement(&mut self, statement: Arc<Node>) {
+        self.statements.get_or_insert_with(Vec::new).push(statement);
+    }
+
+    fn write_return(&mut self, expression: Option<Arc<Node>>) {
+        self.last_operation_was_abrupt = true;
+        self.last_operation_was_completion = true;
+        let statement = create_instruction_return(&self.factory, Instruction::Return, expression);
+        self.write_statement(statement);
+    }
+
+    /// Writes `if (condition) return [3, label];`
+    fn write_break_when(&mut self, label: Label, condition: Arc<Node>) {
+        let factory = self.factory;
+        let jump = self.function.create_inline_break(&factory, label);
+        self.write_statement(factory.create(
+            SyntaxKind::IfStatement,
+            IfStatement {
+                expression: condition,
+                then_statement: jump,
+                else_statement: None,
+            },
+        ));
+    }
+}
+
+/// Replaces the references to labels with the numbers of the cases they start
+struct LabelResolver {
+    factory: NodeFactory,
+    label_numbers: HashMap<Label, usize>,
+}
+
+impl NodeVisitor for LabelResolver {
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        if node.kind == SyntaxKind::NumericLiteral {
+            let label = node
+                .text()
+                .strip_prefix(LABEL_PREFIX)
+                .and_then(|label| label.parse::<Label>().ok());
+            if let Some(label) = label {
+                // A label that no case starts is never jumped to
+                let number = self
+                    .label_numbers
+                    .get(&label)
+                    .map_or_else(|| "9007199254740991".to_string(), usize::to_string);
+                return self.factory.create_numeric_literal(number);
+            }
+            return Arc::clone(node);
+        }
+        let factory = self.factory;
+        visit_each_child(node, self, &factory)
+    }
+}
+
+/// Whether a node contains a `yield` that belongs to the function around it
+fn contains_yield(node: &Node) -> bool {
+    match node.kind {
+        SyntaxKind::YieldExpression => true,
+        kind if is_function_like_declaration_kind(kind) => false,
+        _ => node.for_each_child(&mut |child| contains_yield(child)),
+    }
+}
+
+/// Finds where a `break` or `continue` statement jumps to within the operations
+fn break_or_continue_target(function: &GeneratorFunction, node: &Node) -> Option<Label> {
+    if node.kind == SyntaxKind::BreakStatement {
+        let label = node.as_break_statement().label.as_ref();
+        function.find_break_target(label.map(|label| label.text()))
+    } else {
+        let label = node.as_continue_statement().label.as_ref();
+        function.find_continue_target(label.map(|label| label.text()))
+    }
+}
+
+/// Whether `callee` in a call binding is a reference that may change before the call
+fn should_capture_in_temp(object: &Node) -> bool {
+    match object.kind {
+        SyntaxKind::ThisKeyword
+        | SyntaxKind::NumericLiteral
+        | SyntaxKind::BigIntLiteral
+        | SyntaxKind::StringLiteral => false,
+        SyntaxKind::ArrayLiteralExpression => {
+            !object.as_array_literal_expression().elements.is_empty()
+        }
+        SyntaxKind::ObjectLiteralExpression => {
+            !object.as_object_literal_expression().properties.is_empty()
+        }
+        SyntaxKind::ParenthesizedExpression => {
+            should_capture_in_temp(&object.as_parenthesized_expression().expression)
+        }
+        _ => true,
+    }
+}
+
+fn is_helper_call(expression: &Node, name: &str) -> bool {
+    expression.kind == SyntaxKind::CallExpression && {
+        let callee = &expression.as_call_expression().expression;
+        callee.kind == SyntaxKind::Identifier && callee.text() == name
+    }
+}
+
+/// Joins expressions with the comma operator
+fn inline_expressions(factory: &NodeFactory, expressions: Vec<Arc<Node>>) -> Arc<Node> {
+    let mut expressions = expressions.into_iter();
+    let first = expressions
+        .next()
+        .expect("there is an expression to inline");
+    expressions.fold(first, |left, right| {
+        factory.create_binary(left, SyntaxKind::CommaToken, right)
+    })
+}
+
+fn flatten_comma_expression(node: &Arc<Node>, operands: &mut Vec<Arc<Node>>) {
+    if node.kind == SyntaxKind::BinaryExpression {
+        let data = node.as_binary_expression();
+        if data.operator_token.kind == SyntaxKind::CommaToken {
+            flatten_comma_expression(&data.left, operands);
+            flatten_comma_expression(&data.right, operands);
+            return;
+        }
+    }
+    operands.push(Arc::clone(node));
+}
+
+/// Creates `target.apply(this_argument, arguments)`
+fn create_function_apply_call(
+    factory: &NodeFactory,
+    target: Arc<Node>,
+    this_argument: Arc<Node>,
+    arguments: Arc<Node>,
+) -> Arc<Node> {
+    factory.create_call(
+        factory.create_property_access(target, "apply"),
+        vec![this_argument, arguments],
+    )
+}
+
+/// Creates `array.concat(elements)`
+fn create_array_concat(factory: &NodeFactory, array: &Arc<Node>, elements: Arc<Node>) -> Arc<Node> {
+    factory.create_call(
+        factory.create_property_access(factory.create_identifier(array.text()), "concat"),
+        vec![elements],
+    )
+}
+
+/// Turns a property of an object literal into an assignment to the property of `target`,
+/// such as `_a.b = 1` for `b: 1`
+fn create_property_assignment_expression(
+    factory: &NodeFactory,
+    target: &Arc<Node>,
+    property: &Arc<Node>,
+) -> Arc<Node> {
+    let target = || factory.create_identifier(target.text());
+    let access = |name: &Arc<Node>| match name.kind {
+        SyntaxKind::Identifier => factory.create_property_access(target(), name.text()),
+        SyntaxKind::ComputedPropertyName => factory.create_element_access(
+            target(),
+            Arc::clone(&name.as_computed_property_name().expression),
+        ),
+        _ => factory.create_element_access(target(), Arc::clone(name)),
+    };
+    let assignment = match property.kind {
+        SyntaxKind::PropertyAssignment => {
+            let data = property.as_property_assignment();
+            factory.create_assignment(access(&data.name), Arc::clone(&data.initializer))
+        }
+        SyntaxKind::ShorthandPropertyAssignment => {
+            let data = property.as_shorthand_property_assignment();
+            factory.create_assignment(
+                access(&data.name),
+                factory.create_identifier(data.name.text()),
+            )
+        }
+        SyntaxKind::SpreadAssignment => {
+            // `Object.assign` copies the properties the way a spread does
+            let data = property.as_spread_assignment();
+            factory.create_call(
+                factory.create_property_access(factory.create_identifier("Object"), "assign"),
+                vec![target(), Arc::clone(&data.expression)],
+            )
+        }
+        _ => {
+            let data = property.as_method_declaration();
+            let function = factory.create(
+                SyntaxKind::FunctionExpression,
+                FunctionExpression {
+                    modifiers: None,
+                    asterisk_token: data.asterisk_token.clone(),
+                    name: None,
+                    type_parameters: None,
+                    parameters: data.parameters.clone(),
+                    type_node: None,
+                    body: data
+                        .body
+                        .clone()
+                        .expect("methods of object literals have bodies"),
+                },
+            );
+            factory.create_assignment(access(&data.name), function)
+        }
+    };
+    factory.with_text_range(&assignment, property.loc)
+}
+
+/// Gets the operator a compound assignment applies, such as `+` for `+=`
+fn non_compound_operator(operator: SyntaxKind) -> Option<SyntaxKind> {
+    use SyntaxKind as K;
+    Some(match operator {
+        K::PlusEqualsToken => K::PlusToken,
+        K::MinusEqualsToken => K::MinusToken,
+        K::AsteriskEqualsToken => K::AsteriskToken,
+        K::AsteriskAsteriskEqualsToken => K::AsteriskAsteriskToken,
+        K::SlashEqualsToken => K::SlashToken,
+        K::PercentEqualsToken => K::PercentToken,
+        K::LessThanLessThanEqualsToken => K::LessThanLessThanToken,
+        K::GreaterThanGreaterThanEqualsToken => K::GreaterThanGreaterThanToken,
+        K::GreaterThanGreaterThanGreaterThanEqualsToken => {
+            K::GreaterThanGreaterThanGreaterThanToken
+        }
+        K::AmpersandEqualsToken => K::AmpersandToken,
+        K::BarEqualsToken => K::BarToken,
+        K::CaretEqualsToken => K::CaretToken,
+        K::BarBarEqualsToken => K::BarBarToken,
+        K::AmpersandAmpersandEqualsToken => K::AmpersandAmpersandToken,
+        K::QuestionQuestionEqualsToken => K::QuestionQuestionToken,
+        _ => return None,
+    })
+}
@@ -0,0 +1,337 @@
+//! Runtime helpers called by lowered code
+//!
+//! Lowered syntax often relies on a small function at runtime, such as `__extends` to set up
+//! class inheritance. Transformers request the helpers they call, and each requested helper is
+//! declared once at the top of the file. The declarations reuse a helper of the same name that
+//! is already defined, which is how the helpers of the `tslib` package take their place.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::Node;
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::parser::parse_source_file;
+use crate::compiler::scanner::{ScriptKind, ScriptTarget};
+
+use super::{is_prologue_directive, update_statements};
+
+/// A function that lowered code calls at runtime
+pub(super) struct EmitHelper {
+    pub(super) name: &'static str,
+    /// Helpers are declared in increasing order of priority, before those without one
+    pub(super) priority: Option<u32>,
+    /// The helpers this one calls
+    pub(super) dependencies: &'static [&'static EmitHelper],
+    /// The JavaScript source of the declaration
+    pub(super) text: &'static str,
+}
+
+pub(super) static EXTENDS_HELPER: EmitHelper = EmitHelper {
+    name: "__extends",
+    priority: Some(0),
+    dependencies: &[],
+    text: r#"var __extends = (this && this.__extends) || (function () {
+    var extendStatics = function (d, b) {
+        extendStatics = Object.setPrototypeOf ||
+            ({ __proto__: [] } instanceof Array && function (d, b) { d.__proto__ = b; }) ||
+            function (d, b) { for (var p in b) if (Object.prototype.hasOwnProperty.call(b, p)) d[p] = b[p]; };
+        return extendStatics(d, b);
+    };
+    return function (d, b) {
+        if (typeof b !== "function" && b !== null)
+            throw new TypeError("Class extends value " + String(b) + " is not a constructor or null");
+        extendStatics(d, b);
+        function __() { this.constructor = d; }
+        d.prototype = b === null ? Object.create(b) : (__.prototype = b.prototype, new __());
+    };
+})();"#,
+};
+
+pub(super) static ASSIGN_HELPER: EmitHelper = EmitHelper {
+    name: "__assign",
+    priority: Some(1),
+    dependencies: &[],
+    text: r#"var __assign = (this && this.__assign) || function () {
+    __assign = Object.assign || function(t) {
+        for (var s, i = 1, n = arguments.length; i < n; i++) {
+            s = arguments[i];
+            for (var p in s) if (Object.prototype.hasOwnProperty.call(s, p))
+                t[p] = s[p];
+        }
+        return t;
+    };
+    return __assign.apply(this, arguments);
+};"#,
+};
+
+pub(super) static REST_HELPER: EmitHelper = EmitHelper {
+    name: "__rest",
+    priority: None,
+    dependencies: &[],
+    text: r#"var __rest = (this && this.__rest) || function (s, e) {
+    var t = {};
+    for (var p in s) if (Object.prototype.hasOwnProperty.call(s, p) && e.indexOf(p) < 0)
+        t[p] = s[p];
+    if (s != null && typeof Object.getOwnPropertySymbols === "function")
+        for (var i = 0, p = Object.getOwnPropertySymbols(s); i < p.length; i++) {
+            if (e.indexOf(p[i]) < 0 && Object.prototype.propertyIsEnumerable.call(s, p[i]))
+                t[p[i]] = s[p[i]];
+        }
+    return t;
+};"#,
+};
+
+pub(super) static MAKE_TEMPLATE_OBJECT_HELPER: EmitHelper = EmitHelper {
+    name: "__makeTemplateObject",
+    priority: Some(0),
+    dependencies: &[],
+    text: r#"var __makeTemplateObject = (this && this.__makeTemplateObject) || function (cooked, raw) {
+    if (Object.defineProperty) { Object.defineProperty(cooked, "raw", { value: raw }); } else { cooked.raw = raw; }
+    return cooked;
+};"#,
+};
+
+pub(super) static SPREAD_ARRAY_HELPER: EmitHelper = EmitHelper {
+    name: "__spreadArray",
+    priority: None,
+    dependencies: &[],
+    text: r#"var __spreadArray = (this && this.__spreadArray) || function (to, from, pack) {
+    if (pack || arguments.length === 2) for (var i = 0, l = from.length, ar; i < l; i++) {
+        if (ar || !(i in from)) {
+            if (!ar) ar = Array.prototype.slice.call(from, 0, i);
+            ar[i] = from[i];
+        }
+    }
+    return to.concat(ar || Array.prototype.slice.call(from));
+};"#,
+};
+
+pub(super) static AWAITER_HELPER: EmitHelper = EmitHelper {
+    name: "__awaiter",
+    priority: Some(5),
+    dependencies: &[],
+    text: r#"var __awaiter = (this && this.__awaiter) || function (thisArg, _arguments, P, generator) {
+    function adopt(value) { return value instanceof P ? value : new P(function (resolve) { resolve(value); }); }
+    return new (P || (P = Promise))(function (resolve, reject) {
+        function fulfilled(value) { try { step(generator.next(value)); } catch (e) { reject(e); } }
+        function rejected(value) { try { step(generator["throw"](value)); } catch (e) { reject(e); } }
+        function step(result) { result.done ? resolve(result.value) : adopt(result.value).then(fulfilled, rejected); }
+        step((generator = generator.apply(thisArg, _arguments || [])).next());
+    });
+};"#,
+};
+
+pub(super) static GENERATOR_HELPER: EmitHelper = EmitHelper {
+    name: "__generator",
+    priority: Some(6),
+    dependencies: &[],
+    text: r#"var __generator = (this && this.__generator) || function (thisArg, body) {
+    var _ = { label: 0, sent: function() { if (t[0] & 1) throw t[1]; return t[1]; }, trys: [], ops: [] }, f, y, t, g = Object.create((typeof Iterator === "function" ? Iterator : Object).prototype);
+    return g.next = verb(0), g["throw"] = verb(1), g["return"] = verb(2), typeof Symbol === "function" && (g[Symbol.iterator] = function() { return this; }), g;
+    function verb(n) { return function (v) { return step([n, v]); }; }
+    function step(op) {
+        if (f) throw new TypeError("Generator is already executing.");
+        while (g && (g = 0, op[0] && (_ = 0)), _) try {
+            if (f = 1, y && (t = op[0] & 2 ? y["return"] : op[0] ? y["throw"] || ((t = y["return"]) && t.call(y), 0) : y.next) && !(t = t.call(y, op[1])).done) return t;
+            if (y = 0, t) op = [op[0] & 2, t.value];
+            switch (op[0]) {
+                case 0: case 1: t = op; break;
+                case 4: _.label++; return { value: op[1], done: false };
+                case 5: _.label++; y = op[1]; op = [0]; continue;
+                case 7: op = _.ops.pop(); _.trys.pop(); continue;
+                default:
+                    if (!(t = _.trys, t = t.length > 0 && t[t.length - 1]) && (op[0] === 6 || op[0] === 2)) { _ = 0; continue; }
+                    if (op[0] === 3 && (!t || (op[1] > t[0] && op[1] < t[3]))) { _.label = op[1]; break; }
+                    if (op[0] === 6 && _.label < t[1]) { _.label = t[1]; t = op; break; }
+                    if (t && _.label < t[2]) { _.label = t[2]; _.ops.push(op); break; }
+                    if (t[2]) _.ops.pop();
+                    _.trys.pop(); continue;
+            }
+            op = body.call(thisArg, _);
+        } catch (e) { op = [6, e]; y = 0; } finally { f = t = 0; }
+        if (op[0] & 5) throw op[1]; return { value: op[0] ? op[1] : void 0, done: true };
+    }
+};"#,
+};
+
+pub(super) static VALUES_HELPER: EmitHelper = EmitHelper {
+    name: "__values",
+    priority: None,
+    dependencies: &[],
+    text: r#"var __values = (this && this.__values) || function(o) {
+    var s = typeof Symbol === "function" && Symbol.iterator, m = s && o[s], i = 0;
+    if (m) return m.call(o);
+    if (o && typeof o.length === "number") return {
+        next: function () {
+            if (o && i >= o.length) o = void 0;
+            return { value: o && o[i++], done: !o };
+        }
+    };
+    throw new TypeError(s ? "Object is not iterable." : "Symbol.iterator is not defined.");
+};"#,
+};
+
+pub(super) static AWAIT_HELPER: EmitHelper = EmitHelper {
+    name: "__await",
+    priority: None,
+    dependencies: &[],
+    text: r#"var __await = (this && this.__await) || function (v) { return this instanceof __await ? (this.v = v, this) : new __await(v); }"#,
+};
+
+pub(super) static ASYNC_GENERATOR_HELPER: EmitHelper = EmitHelper {
+    name: "__asyncGenerator",
+    priority: None,
+    dependencies: &[&AWAIT_HELPER],
+    text: r#"var __asyncGenerator = (this && this.__asyncGenerator) || function (thisArg, _arguments, generator) {
+    if (!Symbol.asyncIterator) throw new TypeError("Symbol.asyncIterator is not defined.");
+    var g = generator.apply(thisArg, _arguments || []), i, q = [];
+    return i = Object.create((typeof AsyncIterator === "function" ? AsyncIterator : Object).prototype), verb("next"), verb("throw"), verb("return", awaitReturn), i[Symbol.asyncIterator] = function () { return this; }, i;
+    function awaitReturn(f) { return function (v) { return Promise.resolve(v).then(f, reject); }; }
+    function verb(n, f) { if (g[n]) { i[n] = function (v) { return new Promise(function (a, b) { q.push([n, v, a, b]) > 1 || resume(n, v); }); }; if (f) i[n] = f(i[n]); } }
+    function resume(n, v) { try { step(g[n](v)); } catch (e) { settle(q[0][3], e); } }
+    function step(r) { r.value instanceof __await ? Promise.resolve(r.value.v).then(fulfill, reject) : settle(q[0][2], r); }
+    function fulfill(value) { resume("next", value); }
+    function reject(value) { resume("throw", value); }
+    function settle(f, v) { if (f(v), q.shift(), q.length) resume(q[0][0], q[0][1]); }
+};"#,
+};
+
+pub(super) static ASYNC_DELEGATOR_HELPER: EmitHelper = EmitHelper {
+    name: "__asyncDelegator",
+    priority: None,
+    dependencies: &[&AWAIT_HELPER],
+    text: r#"var __asyncDelegator = (this && this.__asyncDelegator) || function (o) {
+    var i, p;
+    return i = {}, verb("next"), verb("throw", function (e) { throw e; }), verb("return"), i[Symbol.iterator] = function () { return this; }, i;
+    function verb(n, f) { i[n] = o[n] ? function (v) { return (p = !p) ? { value: __await(o[n](v)), done: false } : f ? f(v) : v; } : f; }
+};"#,
+};
+
+pub(super) static ASYNC_VALUES_HELPER: EmitHelper = EmitHelper {
+    name: "__asyncValues",
+    priority: None,
+    dependencies: &[],
+    text: r#"var __asyncValues = (this && this.__asyncValues) || function (o) {
+    if (!Symbol.asyncIterator) throw new TypeError("Symbol.asyncIterator is not defined.");
+    var m = o[Symbol.asyncIterator], i;
+    return m ? m.call(o) : (o = typeof __values === "function" ? __values(o) : o[Symbol.iterator](), i = {}, verb("next"), verb("throw"), verb("return"), i[Symbol.asyncIterator] = function () { return this; }, i);
+    function verb(n) { i[n] = o[n] && function (v) { return new Promise(function (resolve, reject) { v = o[n](v), settle(resolve, reject, v.done, v.value); }); }; }
+    function settle(resolve, reject, d, v) { Promise.resolve(v).then(function(v) { resolve({ value: v, done: d }); }, reject); }
+};"#,
+};
+
+pub(super) static CREATE_BINDING_HELPER: EmitHelper = EmitHelper {
+    name: "__createBinding",
+    priority: Some(1),
+    dependencies: &[],
+    text: r#"var __createBinding = (this && this.__createBinding) || (Object.create ? (function(o, m, k, k2) {
+    if (k2 === undefined) k2 = k;
+    var desc = Object.getOwnPropertyDescriptor(m, k);
+    if (!desc || ("get" in desc ? !m.__esModule : desc.writable || desc.configurable)) {
+      desc = { enumerable: true, get: function() { return m[k]; } };
+    }
+    Object.defineProperty(o, k2, desc);
+}) : (function(o, m, k, k2) {
+    if (k2 === undefined) k2 = k;
+    o[k2] = m[k];
+}));"#,
+};
+
+pub(super) static SET_MODULE_DEFAULT_HELPER: EmitHelper = EmitHelper {
+    name: "__setModuleDefault",
+    priority: Some(1),
+    dependencies: &[],
+    text: r#"var __setModuleDefault = (this && this.__setModuleDefault) || (Object.create ? (function(o, v) {
+    Object.defineProperty(o, "default", { enumerable: true, value: v });
+}) : function(o, v) {
+    o["default"] = v;
+});"#,
+};
+
+pub(super) static IMPORT_STAR_HELPER: EmitHelper = EmitHelper {
+    name: "__importStar",
+    priority: Some(2),
+    dependencies: &[&CREATE_BINDING_HELPER, &SET_MODULE_DEFAULT_HELPER],
+    text: r#"var __importStar = (this && this.__importStar) || (function () {
+    var ownKeys = function(o) {
+        ownKeys = Object.getOwnPropertyNames || function (o) {
+            var ar = [];
+            for (var k in o) if (Object.prototype.hasOwnProperty.call(o, k)) ar[ar.length] = k;
+            return ar;
+        };
+        return ownKeys(o);
+    };
+    return function (mod) {
+        if (mod && mod.__esModule) return mod;
+        var result = {};
+        if (mod != null) for (var k = ownKeys(mod), i = 0; i < k.length; i++) if (k[i] !== "default") __createBinding(result, mod, k[i]);
+        __setModuleDefault(result, mod);
+        return result;
+    };
+})();"#,
+};
+
+pub(super) static IMPORT_DEFAULT_HELPER: EmitHelper = EmitHelper {
+    name: "__importDefault",
+    priority: None,
+    dependencies: &[],
+    text: r#"var __importDefault = (this && this.__importDefault) || function (mod) {
+    return (mod && mod.__esModule) ? mod : { "default": mod };
+};"#,
+};
+
+pub(super) static EXPORT_STAR_HELPER: EmitHelper = EmitHelper {
+    name: "__exportStar",
+    priority: Some(2),
+    dependencies: &[&CREATE_BINDING_HELPER],
+    text: r#"var __exportStar = (this && this.__exportStar) || function(m, exports) {
+    for (var p in m) if (p !== "default" && !Object.prototype.hasOwnProperty.call(exports, p)) __createBinding(exports, m, p);
+};"#,
+};
+
+/// Declares the requested helpers at the top of a transformed file, after its prologue
+/// directives
+pub(super) fn add_emit_helpers(
+    source_file: &Arc<Node>,
+    helpers: &[&'static EmitHelper],
+) -> Arc<Node> {
+    if helpers.is_empty() {
+        return Arc::clone(source_file);
+    }
+    let mut helpers = helpers.to_vec();
+    // The sort is stable, so helpers of equal priority stay in the order they were
+    // requested, each after the helpers it depends on
+    helpers.sort_by_key(|helper| helper.priority.unwrap_or(u32::MAX));
+
+    let factory = NodeFactory::new();
+    let mut statements = source_file.as_source_file().statements.nodes.clone();
+    let insert_at = statements
+        .iter()
+        .take_while(|statement| is_prologue_directive(statement))
+        .count();
+    let declarations = helpers
+        .iter()
+        .flat_map(|helper| parse_helper(&factory, helper))
+        .collect::<Vec<_>>();
+    statements.splice(insert_at..insert_at, declarations);
+    update_statements(&factory, source_file, statements)
+}
+
+/// Parses the declaration of a helper into synthesized statements
+fn parse_helper(factory: &NodeFactory, helper: &EmitHelper) -> Vec<Arc<Node>> {
+    parse_statements(factory, &format!("{}.js", helper.name), helper.text)
+}
+
+/// Parses JavaScript source into synthesized statements, which the printer writes from their
+/// data rather than from the text of the file they are inserted into
+pub(super) fn parse_statements(
+    factory: &NodeFactory,
+    file_name: &str,
+    text: &str,
+) -> Vec<Arc<Node>> {
+    let file = parse_source_file(file_name, text, ScriptTarget::ES5, ScriptKind::JS);
+    file.as_source_file()
+        .statements
+        .iter()
+        .map(|statement| factory.deep_clone(statement))
+        .collect()
+}
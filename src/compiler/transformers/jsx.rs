@@ -0,0 +1,777 @@
+//! Lowering JSX to function calls
+//!
+//! With `--jsx react`, each element becomes a call to `React.createElement` with the tag, an
+//! object of the attributes or `null`, and then the children. Intrinsic elements such as
+//! `<div>` pass their tag as a string, and fragments pass `React.Fragment`.
+//!
+//! With `--jsx react-jsx`, elements call `jsx`, or `jsxs` when they have several children,
+//! which are imported from `react/jsx-runtime`. The children become the `children` property
+//! of the props, and a `key` attribute becomes an argument of its own. `--jsx react-jsxdev`
+//! calls `jsxDEV` from `react/jsx-dev-runtime` instead, and also passes where the element is
+//! in the source file.
+//!
+//! Text in JSX loses the whitespace around its lines and the lines that are only whitespace,
+//! and character references such as `&amp;` in it are decoded.
+
+mod entities;
+
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    ImportClause, ImportDeclaration, ImportSpecifier, NamedImports, SpreadAssignment,
+    SpreadElement, VariableDeclarationList, VariableStatement,
+};
+use crate::compiler::ast::visitor::{NodeVisitor, visit_each_child, visit_nodes};
+use crate::compiler::ast::{Node, NodeFlags, SyntaxKind};
+use crate::compiler::scanner::{ScriptTarget, is_line_break, is_white_space_single_line};
+use crate::compiler::text::{compute_line_starts, position_to_utf16_line_and_character};
+
+use super::helpers::ASSIGN_HELPER;
+use super::{TransformContext, insert_after_prologue, update_statements};
+
+/// How JSX is emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsxEmit {
+    /// JSX is not configured, and is written out as it is
+    #[default]
+    None,
+    /// JSX is written out as it is, to a `.jsx` file
+    Preserve,
+    /// Elements become `React.createElement` calls
+    React,
+    /// JSX is written out as it is, to a `.js` file
+    ReactNative,
+    /// Elements become calls to the functions of `react/jsx-runtime`
+    ReactJsx,
+    /// Elements become calls to `jsxDEV` from `react/jsx-dev-runtime`, which are told where
+    /// each element is in the source
+    ReactJsxDev,
+}
+
+impl JsxEmit {
+    /// Whether JSX is rewritten into function calls rather than written out as it is
+    pub fn transforms_jsx(self) -> bool {
+        matches!(
+            self,
+            JsxEmit::React | JsxEmit::ReactJsx | JsxEmit::ReactJsxDev
+        )
+    }
+}
+
+/// The function the classic runtime creates elements with
+const JSX_FACTORY: &str = "React.createElement";
+/// The component the classic runtime creates fragments with
+const JSX_FRAGMENT_FACTORY: &str = "React.Fragment";
+/// The package that provides the automatic runtime
+const JSX_IMPORT_SOURCE: &str = "react";
+
+/// Rewrites the JSX of a file into calls for the JSX runtime of the context
+pub(super) fn transform_jsx(source_file: &Arc<Node>, context: &mut TransformContext) -> Arc<Node> {
+    let mut transformer = JsxTransformer {
+        factory: NodeFactory::new(),
+        context,
+        source_file: Arc::clone(source_file),
+        imports: Vec::new(),
+        file_name_variable: None,
+    };
+    let factory = transformer.factory;
+    let data = source_file.as_source_file();
+    let mut statements = visit_nodes(&data.statements, &mut transformer, &factory).nodes;
+    // The imports go first, with the package imported from first at the bottom
+    let mut inserted: Vec<Arc<Node>> = transformer
+        .imports
+        .iter()
+        .rev()
+        .map(|(source, names)| create_named_import(&factory, source, names))
+        .collect();
+    if let Some(name) = &transformer.file_name_variable {
+        inserted.push(create_const_statement(
+            &factory,
+            name,
+            factory.create_string_literal(data.file_name.as_str()),
+        ));
+    }
+    insert_after_prologue(&mut statements, inserted);
+    update_statements(&factory, source_file, statements)
+}
+
+struct JsxTransformer<'a> {
+    factory: NodeFactory,
+    context: &'a mut TransformContext,
+    /// The file being transformed, whose text gives the positions passed to `jsxDEV`
+    source_file: Arc<Node>,
+    /// The names imported from each package of the automatic runtime, in the order they were
+    /// first used, each with the local name it is imported as
+    imports: Vec<(String, Vec<(String, String)>)>,
+    /// The variable that holds the name of the file, once an element has needed it
+    file_name_variable: Option<String>,
+}
+
+impl NodeVisitor for JsxTransformer<'_> {
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        match node.kind {
+            SyntaxKind::JsxElement
+            | SyntaxKind::JsxSelfClosingElement
+            | SyntaxKind::JsxFragment => self.visit_jsx(node),
+            _ => {
+                let factory = self.factory;
+                visit_each_child(node, self, &factory)
+            }
+        }
+    }
+}
+
+impl JsxTransformer<'_> {
+    /// Rewrites an element or fragment into a call that creates it
+    fn visit_jsx(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let (tag_name, attributes, children): (_, &[Arc<Node>], &[Arc<Node>]) = match node.kind {
+            SyntaxKind::JsxElement => {
+                let data = node.as_jsx_element();
+                let opening = data.opening_element.as_jsx_opening_element();
+                (
+                    Some(&opening.tag_name),
+                    &opening.attributes.as_jsx_attributes().properties.nodes,
+                    &data.children.nodes,
+                )
+            }
+            SyntaxKind::JsxSelfClosingElement => {
+                let data = node.as_jsx_self_closing_element();
+                (
+                    Some(&data.tag_name),
+                    &data.attributes.as_jsx_attributes().properties.nodes,
+                    &[],
+                )
+            }
+            _ => (None, &[], &node.as_jsx_fragment().children.nodes),
+        };
+        let element = match tag_name {
+            Some(tag_name) => self.visit_element(tag_name, attributes, children),
+            None => self.visit_fragment(children),
+        };
+        match element {
+            // The automatic runtime is told where the element is
+            Element::Automatic {
+                callee,
+                mut arguments,
+                key,
+                is_static_children,
+            } => {
+                if self.context.jsx == JsxEmit::ReactJsxDev {
+                    arguments.push(key.unwrap_or_else(|| self.factory.create_void_zero()));
+                    arguments.push(self.factory.create_token(if is_static_children {
+                        SyntaxKind::TrueKeyword
+                    } else {
+                        SyntaxKind::FalseKeyword
+                    }));
+                    arguments.push(self.create_source_location(node));
+                    arguments.push(self.factory.create_token(SyntaxKind::ThisKeyword));
+                } else if let Some(key) = key {
+                    arguments.push(key);
+                }
+                let call = self.factory.create_call(callee, arguments);
+                self.factory.with_text_range(&call, node.loc)
+            }
+            Element::Classic { callee, arguments } => {
+                let call = self.factory.create_call(callee, arguments);
+                self.factory.with_text_range(&call, node.loc)
+            }
+        }
+    }
+
+    fn visit_element(
+        &mut self,
+        tag_name: &Arc<Node>,
+        attributes: &[Arc<Node>],
+        children: &[Arc<Node>],
+    ) -> Element {
+        if self.context.jsx == JsxEmit::React {
+            let callee = self.create_entity_expression(JSX_FACTORY);
+            return self.create_classic_element(callee, tag_name, attributes, children);
+        }
+        // `React.createElement` gives a `key` after a spread precedence over the spread's
+        // `key`, which the automatic runtime cannot
+        if has_key_after_props_spread(attributes) {
+            let callee = self.import_name(JSX_IMPORT_SOURCE, "createElement");
+            return self.create_classic_element(callee, tag_name, attributes, children);
+        }
+        let tag = self.visit_tag_name(tag_name);
+        let key = attributes
+            .iter()
+            .find(|attribute| is_key_attribute(attribute));
+        let attributes: Vec<Arc<Node>> = attributes
+            .iter()
+            .filter(|attribute| !is_key_attribute(attribute))
+            .cloned()
+            .collect();
+        let children_property = self.create_children_property(children);
+        let props = if attributes.is_empty() {
+            self.factory
+                .create_object_literal(children_property.into_iter().collect(), false)
+        } else {
+            self.visit_attributes(&attributes, children_property)
+        };
+        let key =
+            key.map(|key| self.visit_attribute_value(key.as_jsx_attribute().initializer.as_ref()));
+        self.create_automatic_element(tag, props, key, children)
+    }
+
+    fn visit_fragment(&mut self, children: &[Arc<Node>]) -> Element {
+        if self.context.jsx == JsxEmit::React {
+            let callee = self.create_entity_expression(JSX_FACTORY);
+            let mut arguments = vec![
+                self.create_entity_expression(JSX_FRAGMENT_FACTORY),
+                self.factory.create_token(SyntaxKind::NullKeyword),
+            ];
+            arguments.extend(children.iter().filter_map(|child| self.visit_child(child)));
+            return Element::Classic { callee, arguments };
+        }
+        let children_property = self.create_children_property(children);
+        let tag = self.import_runtime_name("Fragment");
+        let props = self
+            .factory
+            .create_object_literal(children_property.into_iter().collect(), false);
+        self.create_automatic_element(tag, props, None, children)
+    }
+
+    /// Creates `callee(tag, props, ...children)`
+    fn create_classic_element(
+        &mut self,
+        callee: Arc<Node>,
+        tag_name: &Arc<Node>,
+        attributes: &[Arc<Node>],
+        children: &[Arc<Node>],
+    ) -> Element {
+        let tag = self.visit_tag_name(tag_name);
+        let props = if attributes.is_empty() {
+            self.factory.create_token(SyntaxKind::NullKeyword)
+        } else {
+            self.visit_attributes(attributes, None)
+        };
+        let mut arguments = vec![tag, props];
+        arguments.extend(children.iter().filter_map(|child| self.visit_child(child)));
+        Element::Classic { callee, arguments }
+    }
+
+    fn create_automatic_element(
+        &mut self,
+        tag: Arc<Node>,
+        props: Arc<Node>,
+        key: Option<Arc<Node>>,
+        children: &[Arc<Node>],
+    ) -> Element {
+        let semantic_children = semantic_children(children);
+        let is_static_children = semantic_children.len() > 1
+            || semantic_children
+                .first()
+                .is_some_and(|child| is_spread_child(child));
+        let name = if self.context.jsx == JsxEmit::ReactJsxDev {
+            "jsxDEV"
+        } else if is_static_children {
+            "jsxs"
+        } else {
+            "jsx"
+        };
+        Element::Automatic {
+            callee: self.import_runtime_name(name),
+            arguments: vec![tag, props],
+            key,
+            is_static_children,
+        }
+    }
+
+    /// Creates the `children` property of the props passed to the automatic runtime: the one
+    /// child itself, or an array of them
+    fn create_children_property(&mut self, children: &[Arc<Node>]) -> Option<Arc<Node>> {
+        let semantic_children = semantic_children(children);
+        let value = match semantic_children.as_slice() {
+            [child] if !is_spread_child(child) => self.visit_child(child)?,
+            _ => {
+                let elements: Vec<Arc<Node>> = children
+                    .iter()
+                    .filter_map(|child| self.visit_child(child))
+                    .collect();
+                if elements.is_empty() {
+                    return None;
+                }
+                self.factory.create_array_literal(elements, false)
+            }
+        };
+        Some(
+            self.factory
+                .create_property_assignment(self.factory.create_identifier("children"), value),
+        )
+    }
+
+    /// Rewrites a child of an element into the expression passed for it, or `None` for text
+    /// that is only whitespace and for empty expressions
+    fn visit_child(&mut self, child: &Arc<Node>) -> Option<Arc<Node>> {
+        match child.kind {
+            SyntaxKind::JsxText => fixup_whitespace_and_decode_entities(&child.as_jsx_text().text)
+                .map(|text| self.factory.create_string_literal(text)),
+            SyntaxKind::JsxExpression => {
+                let data = child.as_jsx_expression();
+                let expression = self.visit_node(data.expression.as_ref()?);
+                if data.dot_dot_dot_token.is_some() {
+                    Some(
+                        self.factory
+                            .create(SyntaxKind::SpreadElement, SpreadElement { expression }),
+                    )
+                } else {
+                    Some(expression)
+                }
+            }
+            _ => Some(self.visit_node(child)),
+        }
+    }
+
+    /// Rewrites a tag name into the expression for the component: a string for intrinsic
+    /// elements such as `div`, and the name as an expression otherwise
+    fn visit_tag_name(&mut self, tag_name: &Arc<Node>) -> Arc<Node> {
+        match tag_name.kind {
+            SyntaxKind::JsxNamespacedName => self
+                .factory
+                .create_string_literal(namespaced_name_text(tag_name)),
+            SyntaxKind::Identifier if is_intrinsic_jsx_name(tag_name.text()) => {
+                self.factory.create_string_literal(tag_name.text())
+            }
+            _ => self.visit_node(tag_name),
+        }
+    }
+
+    /// Rewrites the attributes of an element into the props object, with `children` as its
+    /// last property
+    ///
+    /// Below ES2018, which has no spread properties, spread attributes become arguments of
+    /// `__assign` between objects of the other attributes.
+    fn visit_attributes(
+        &mut self,
+        attributes: &[Arc<Node>],
+        children: Option<Arc<Node>>,
+    ) -> Arc<Node> {
+        let factory = self.factory;
+        let spread_properties = self.context.target >= ScriptTarget::ES2018;
+        let mut expressions = Vec::new();
+        let mut properties = Vec::new();
+        for attribute in attributes {
+            if attribute.kind != SyntaxKind::JsxSpreadAttribute {
+                properties.push(self.visit_attribute(attribute));
+                continue;
+            }
+            let expression = &attribute.as_jsx_spread_attribute().expression;
+            // The properties of a spread object literal are written in its place
+            if expression.kind == SyntaxKind::ObjectLiteralExpression && !has_proto(expression) {
+                for property in &expression.as_object_literal_expression().properties.nodes {
+                    if property.kind == SyntaxKind::SpreadAssignment && !spread_properties {
+                        if !properties.is_empty() {
+                            expressions.push(
+                                factory
+                                    .create_object_literal(std::mem::take(&mut properties), false),
+                            );
+                        }
+                        expressions
+                            .push(self.visit_node(&property.as_spread_assignment().expression));
+                    } else {
+                        properties.push(self.visit_node(property));
+                    }
+                }
+                continue;
+            }
+            let expression = self.visit_node(expression);
+            if spread_properties {
+                properties.push(factory.create(
+                    SyntaxKind::SpreadAssignment,
+                    SpreadAssignment { expression },
+                ));
+            } else {
+                if !properties.is_empty() {
+                    expressions.push(
+                        factory.create_object_literal(std::mem::take(&mut properties), false),
+                    );
+                }
+                expressions.push(expression);
+            }
+        }
+        properties.extend(children);
+        if spread_properties {
+            return factory.create_object_literal(properties, false);
+        }
+        if !properties.is_empty() {
+            expressions.push(factory.create_object_literal(properties, false));
+        }
+        // `__assign` copies into its first argument, which must be a new object
+        if expressions[0].kind != SyntaxKind::ObjectLiteralExpression {
+            expressions.insert(0, factory.create_object_literal(Vec::new(), false));
+        }
+        if expressions.len() == 1 {
+            return expressions.pop().expect("there is one expression");
+        }
+        self.context.request_helper(&ASSIGN_HELPER);
+        factory.create_call(factory.create_identifier("__assign"), expressions)
+    }
+
+    /// Rewrites `name={value}` into the property `name: value`
+    fn visit_attribute(&mut self, attribute: &Arc<Node>) -> Arc<Node> {
+        let data = attribute.as_jsx_attribute();
+        let name = match data.name.kind {
+            SyntaxKind::JsxNamespacedName => self
+                .factory
+                .create_string_literal(namespaced_name_text(&data.name)),
+            _ if is_identifier_name(data.name.text()) => Arc::clone(&data.name),
+            _ => self.factory.create_string_literal(data.name.text()),
+        };
+        let value = self.visit_attribute_value(data.initializer.as_ref());
+        self.factory.create_property_assignment(name, value)
+    }
+
+    fn visit_attribute_value(&mut self, initializer: Option<&Arc<Node>>) -> Arc<Node> {
+        let Some(initializer) = initializer else {
+            return self.factory.create_token(SyntaxKind::TrueKeyword);
+        };
+        match initializer.kind {
+            SyntaxKind::StringLiteral => self
+                .factory
+                .create_string_literal(decode_entities(initializer.text())),
+            SyntaxKind::JsxExpression => match &initializer.as_jsx_expression().expression {
+                Some(expression) => self.visit_node(expression),
+                None => self.factory.create_token(SyntaxKind::TrueKeyword),
+            },
+            _ => self.visit_node(initializer),
+        }
+    }
+
+    /// Creates `{ fileName: _jsxFileName, lineNumber: 1, columnNumber: 1 }` for where an
+    /// element starts, counting its leading trivia
+    fn create_source_location(&mut self, node: &Node) -> Arc<Node> {
+        let file_name = match &self.file_name_variable {
+            Some(name) => name.clone(),
+            None => {
+                let name = self.context.reserve_name("_jsxFileName");
+                self.file_name_variable = Some(name.clone());
+                name
+            }
+        };
+        let (line, column) =
+            line_and_column(&self.source_file.as_source_file().text, node.loc.pos());
+        let factory = self.factory;
+        let properties = [
+            ("fileName", factory.create_identifier(file_name)),
+            (
+                "lineNumber",
+                factory.create_numeric_literal(line.to_string()),
+            ),
+            (
+                "columnNumber",
+                factory.create_numeric_literal(column.to_string()),
+            ),
+        ]
+        .into_iter()
+        .map(|(name, value)| {
+            factory.create_property_assignment(factory.create_identifier(name), value)
+        })
+        .collect();
+        factory.create_object_literal(properties, false)
+    }
+
+    /// Imports a function of the automatic runtime, returning its local name
+    fn import_runtime_name(&mut self, name: &str) -> Arc<Node> {
+        let source = if self.context.jsx == JsxEmit::ReactJsxDev {
+            format!("{JSX_IMPORT_SOURCE}/jsx-dev-runtime")
+        } else {
+            format!("{JSX_IMPORT_SOURCE}/jsx-runtime")
+        };
+        self.import_name(&source, name)
+    }
+
+    /// Imports `name` from the package `source` as `_name`, unless it already has been
+    fn import_name(&mut self, source: &str, name: &str) -> Arc<Node> {
+        let index = match self
+            .imports
+            .iter()
+            .position(|(imported, _)| imported == source)
+        {
+            Some(index) => index,
+            None => {
+                self.imports.push((source.to_string(), Vec::new()));
+                self.imports.len() - 1
+            }
+        };
+        let names = &self.imports[index].1;
+        let local = match names.iter().find(|(imported, _)| imported == name) {
+            Some((_, local)) => local.clone(),
+            None => {
+                let local = self.context.reserve_name(&format!("_{name}"));
+                self.imports[index]
+                    .1
+                    .push((name.to_string(), local.clone()));
+                local
+            }
+        };
+        self.factory.create_identifier(local)
+    }
+
+    /// Creates the expression for a dotted name such as `React.createElement`
+    fn create_entity_expression(&self, name: &str) -> Arc<Node> {
+        let mut parts = name.split('.');
+        let first = parts.next().expect("split gives at least one part");
+        parts.fold(self.factory.create_identifier(first), |expression, part| {
+            self.factory.create_property_access(expression, part)
+        })
+    }
+}
+
+/// The call an element becomes, before the arguments that depend on the runtime are added
+enum Element {
+    /// `callee(tag, props, ...children)`
+    Classic {
+        callee: Arc<Node>,
+        arguments: Vec<Arc<Node>>,
+    },
+    /// `callee(tag, props)`, which is passed the key after the props
+    Automatic {
+        callee: Arc<Node>,
+        arguments: Vec<Arc<Node>>,
+        key: Option<Arc<Node>>,
+        /// Whether the children were written as several children rather than one, so that
+        /// React need not check that they have keys
+        is_static_children: bool,
+    },
+}
+
+/// The children of an element other than whitespace between lines and empty expressions
+fn semantic_children(children: &[Arc<Node>]) -> Vec<&Arc<Node>> {
+    children
+        .iter()
+        .filter(|child| match child.kind {
+            SyntaxKind::JsxText => !child.as_jsx_text().contains_only_trivia_white_spaces,
+            SyntaxKind::JsxExpression => child.as_jsx_expression().expression.is_some(),
+            _ => true,
+        })
+        .collect()
+}
+
+/// Whether a child is `{...children}`
+fn is_spread_child(child: &Node) -> bool {
+    child.kind == SyntaxKind::JsxExpression && child.as_jsx_expression().dot_dot_dot_token.is_some()
+}
+
+fn is_key_attribute(attribute: &Node) -> bool {
+    attribute.kind == SyntaxKind::JsxAttribute && {
+        let name = &attribute.as_jsx_attribute().name;
+        name.kind == SyntaxKind::Identifier && name.text() == "key"
+    }
+}
+
+/// Whether a `key` attribute follows a spread attribute that may also have a `key`
+fn has_key_after_props_spread(attributes: &[Arc<Node>]) -> bool {
+    let mut spread = false;
+    for attribute in attributes {
+        if attribute.kind == SyntaxKind::JsxSpreadAttribute {
+            let expression = &attribute.as_jsx_spread_attribute().expression;
+            if expression.kind != SyntaxKind::ObjectLiteralExpression
+                || expression
+                    .as_object_literal_expression()
+                    .properties
+                    .nodes
+                    .iter()
+                    .any(|property| property.kind == SyntaxKind::SpreadAssignment)
+            {
+                spread = true;
+            }
+        } else if spread && is_key_attribute(attribute) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether an object literal sets its prototype with `__proto__: value`, which spreading it
+/// would not
+fn has_proto(object: &Node) -> bool {
+    object
+        .as_object_literal_expression()
+        .properties
+        .nodes
+        .iter()
+        .any(|property| {
+            property.kind == SyntaxKind::PropertyAssignment
+                && property.name().is_some_and(|name| {
+                    name.kind != SyntaxKind::ComputedPropertyName && name.text() == "__proto__"
+                })
+        })
+}
+
+/// Whether a tag name is an intrinsic element such as `div` or `my-element`, rather than a
+/// component
+fn is_intrinsic_jsx_name(name: &str) -> bool {
+    name.starts_with(|ch: char| ch.is_ascii_lowercase()) || name.contains('-')
+}
+
+/// Whether an attribute name can be written as a property name without quotes
+fn is_identifier_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+fn namespaced_name_text(node: &Node) -> String {
+    let data = node.as_jsx_namespaced_name();
+    format!("{}:{}", data.namespace.text(), data.name.text())
+}
+
+/// Gets the value of JSX text: each line without the whitespace around it, joined by spaces,
+/// leaving out lines that are only whitespace
+///
+/// The whitespace before the first line and after the last is kept, as it is not next to a
+/// line break. Returns `None` if no text is left.
+fn fixup_whitespace_and_decode_entities(text: &str) -> Option<String> {
+    let mut result: Option<String> = None;
+    let mut add_line = |line: &str| {
+        let decoded = decode_entities(line);
+        result = Some(match result.take() {
+            Some(result) => format!("{result} {decoded}"),
+            None => decoded,
+        });
+    };
+    // The start of the text on the current line, and the end of the text seen so far
+    let mut first_non_whitespace = Some(0);
+    let mut last_non_whitespace = None;
+    for (index, ch) in text.char_indices() {
+        if is_line_break(ch) {
+            if let (Some(first), Some(last)) = (first_non_whitespace, last_non_whitespace) {
+                add_line(&text[first..last]);
+            }
+            first_non_whitespace = None;
+        } else if !is_white_space_single_line(ch) {
+            last_non_whitespace = Some(index + ch.len_utf8());
+            first_non_whitespace.get_or_insert(index);
+        }
+    }
+    if let Some(first) = first_non_whitespace {
+        add_line(&text[first..]);
+    }
+    result
+}
+
+/// Decodes the character references in JSX text, such as `&amp;`, `&#38;`, and `&#x26;`
+///
+/// An `&` that does not start a reference is kept as it is.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match decode_entity(rest) {
+            Some((ch, length)) => {
+                decoded.push(ch);
+                rest = &rest[length..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Decodes the character reference at the start of `text`, returning its character and
+/// length
+fn decode_entity(text: &str) -> Option<(char, usize)> {
+    let end = text.find(';')?;
+    let body = &text[1..end];
+    let code = if let Some(hex) = body.strip_prefix("#x") {
+        if hex.is_empty() || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return None;
+        }
+        u32::from_str_radix(hex, 16).ok()?
+    } else if let Some(decimal) = body.strip_prefix('#') {
+        if decimal.is_empty() || !decimal.chars().all(|ch| ch.is_ascii_digit()) {
+            return None;
+        }
+        decimal.parse().ok()?
+    } else {
+        let index = entities::ENTITIES
+            .binary_search_by(|(name, _)| (*name).cmp(body))
+            .ok()?;
+        entities::ENTITIES[index].1
+    };
+    Some((char::from_u32(code)?, end + 1))
+}
+
+/// Gets the 1-based line and column of a position, with the column counted in UTF-16 code
+/// units as JavaScript counts them
+fn line_and_column(text: &str, pos: usize) -> (usize, usize) {
+    let pos = pos.min(text.len());
+    let position = position_to_utf16_line_and_character(text, &compute_line_starts(text), pos);
+    (position.line + 1, position.character + 1)
+}
+
+/// Creates `import { name as local } from "source";`
+fn create_named_import(
+    factory: &NodeFactory,
+    source: &str,
+    names: &[(String, String)],
+) -> Arc<Node> {
+    let elements = names
+        .iter()
+        .map(|(name, local)| {
+            factory.create(
+                SyntaxKind::ImportSpecifier,
+                ImportSpecifier {
+                    is_type_only: false,
+                    property_name: Some(factory.create_identifier(name.as_str())),
+                    name: factory.create_identifier(local.as_str()),
+                },
+            )
+        })
+        .collect();
+    let import_clause = factory.create(
+        SyntaxKind::ImportClause,
+        ImportClause {
+            is_type_only: false,
+            name: None,
+            named_bindings: Some(factory.create(
+                SyntaxKind::NamedImports,
+                NamedImports {
+                    elements: factory.create_node_list(elements),
+                },
+            )),
+        },
+    );
+    factory.create(
+        SyntaxKind::ImportDeclaration,
+        ImportDeclaration {
+            modifiers: None,
+            import_clause: Some(import_clause),
+            module_specifier: factory.create_string_literal(source),
+            attributes: None,
+        },
+    )
+}
+
+/// Creates `const name = initializer;`, which the later transformers lower for old targets
+fn create_const_statement(factory: &NodeFactory, name: &str, initializer: Arc<Node>) -> Arc<Node> {
+    let declaration_list =
+        factory.create_with_flags(
+            SyntaxKind::VariableDeclarationList,
+            NodeFlags::CONST,
+            VariableDeclarationList {
+                declarations: factory.create_node_list(vec![factory.create_variable_declaration(
+                    factory.create_identifier(name),
+                    Some(initializer),
+                )]),
+            },
+        );
+    factory.create(
+        SyntaxKind::VariableStatement,
+        VariableStatement {
+            modifiers: None,
+            declaration_list,
+        },
+    )
+}
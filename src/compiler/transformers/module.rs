@@ -0,0 +1,1179 @@
+//! Rewriting imports and exports for a module system
+//!
+//! ECMAScript modules are written out as they are. For the other module systems, import
+//! declarations load their module into a variable, such as `m_1` for `"./m"`, and each
+//! reference to an imported name becomes an access of that variable, such as `m_1.a`. Exports
+//! become properties of the module's `exports` object, which are assigned wherever the
+//! exported variable changes. CommonJS, AMD, and UMD modules share one set of rewrites, in
+//! [`commonjs`], and System modules, in [`system`], register setters that are called with
+//! each dependency.
+
+mod commonjs;
+mod system;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    ArrowFunction, ConditionalExpression, NewExpression, PrefixUnaryExpression,
+    VariableDeclarationList, VariableStatement,
+};
+use crate::compiler::ast::utilities::is_external_module;
+use crate::compiler::ast::visitor::{NodeVisitor, visit_each_child, visit_nodes};
+use crate::compiler::ast::{Node, NodeFlags, SyntaxKind};
+use crate::compiler::scanner::{ScriptTarget, is_identifier_part, is_identifier_start};
+
+use super::helpers::{EmitHelper, IMPORT_DEFAULT_HELPER, IMPORT_STAR_HELPER, parse_statements};
+use super::{
+    TransformContext, collect_binding_names, insert_after_prologue, non_reference_name,
+    scope_names, strip_export_modifiers, update_statements,
+};
+
+/// The module system of the emitted JavaScript
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModuleKind {
+    /// No module system; a file with imports or exports is emitted as CommonJS
+    None,
+    CommonJS,
+    AMD,
+    UMD,
+    System,
+    ES2015,
+    ES2020,
+    ES2022,
+    #[default]
+    ESNext,
+    /// The module system Node.js picks for each file: ECMAScript modules for `.mts` and
+    /// `.mjs` files, and CommonJS for the rest
+    Node16,
+    Node18,
+    NodeNext,
+    /// Imports and exports as they are written, including `import x = require("m")`
+    Preserve,
+}
+
+impl ModuleKind {
+    /// The module system used when none is configured, which depends on the target
+    pub fn default_for_target(target: ScriptTarget) -> Self {
+        if target >= ScriptTarget::ES2015 {
+            ModuleKind::ES2015
+        } else {
+            ModuleKind::CommonJS
+        }
+    }
+
+    /// Whether the module system turns on `esModuleInterop` when it is not configured
+    pub fn implies_es_module_interop(self) -> bool {
+        matches!(
+            self,
+            ModuleKind::Node16 | ModuleKind::Node18 | ModuleKind::NodeNext | ModuleKind::Preserve
+        )
+    }
+}
+
+/// Rewrites the imports and exports of a module for the module system of the context
+///
+/// Modules emitted as ECMAScript modules come back unchanged, and so do scripts, except that
+/// CommonJS loads the modules of their `import()` calls with `require`.
+pub(super) fn transform_module(
+    source_file: &Arc<Node>,
+    context: &mut TransformContext,
+) -> Arc<Node> {
+    if !is_external_module(source_file) {
+        if matches!(context.module, ModuleKind::None | ModuleKind::CommonJS)
+            && contains_dynamic_import(source_file)
+        {
+            return commonjs::transform_commonjs_script(source_file, context);
+        }
+        return Arc::clone(source_file);
+    }
+    let file_name = &source_file.as_source_file().file_name;
+    match context.module {
+        ModuleKind::None | ModuleKind::CommonJS => {
+            commonjs::transform_commonjs_module(source_file, context, ModuleFormat::CommonJS)
+        }
+        ModuleKind::AMD => {
+            commonjs::transform_commonjs_module(source_file, context, ModuleFormat::Amd)
+        }
+        ModuleKind::UMD => {
+            commonjs::transform_commonjs_module(source_file, context, ModuleFormat::Umd)
+        }
+        ModuleKind::System => system::transform_system_module(source_file, context),
+        ModuleKind::Node16 | ModuleKind::Node18 | ModuleKind::NodeNext
+            if !is_es_module_file(file_name) =>
+        {
+            commonjs::transform_commonjs_module(source_file, context, ModuleFormat::CommonJS)
+        }
+        ModuleKind::Preserve => {
+            let file = commonjs::transform_preserved_module(source_file, context);
+            import_helpers_from_tslib(&file, context)
+        }
+        _ => import_helpers_from_tslib(source_file, context),
+    }
+}
+
+/// Imports the helpers that a module emitted as an ECMAScript module calls from `tslib`, when
+/// helpers are imported rather than declared
+fn import_helpers_from_tslib(source_file: &Arc<Node>, context: &TransformContext) -> Arc<Node> {
+    if !context.import_helpers || context.helpers.is_empty() {
+        return Arc::clone(source_file);
+    }
+    let mut names = context
+        .helpers
+        .iter()
+        .map(|helper| helper.name)
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    let factory = NodeFactory::new();
+    let import = parse_statements(
+        &factory,
+        "tslib.js",
+        &format!("import {{ {} }} from \"tslib\";", names.join(", ")),
+    );
+    let mut statements = source_file.as_source_file().statements.nodes.clone();
+    insert_after_prologue(&mut statements, import);
+    update_statements(&factory, source_file, statements)
+}
+
+/// Whether a node contains an `import()` call
+fn contains_dynamic_import(node: &Arc<Node>) -> bool {
+    node.kind == SyntaxKind::CallExpression
+        && node.as_call_expression().expression.kind == SyntaxKind::ImportKeyword
+        || node.for_each_child(&mut |child| contains_dynamic_import(child))
+}
+
+/// Whether Node.js loads a file as an ECMAScript module whatever its `package.json` says
+fn is_es_module_file(file_name: &str) -> bool {
+    [".mts", ".mjs"]
+        .iter()
+        .any(|extension| file_name.ends_with(extension))
+}
+
+/// The shape of a module that is not emitted as an ECMAScript module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModuleFormat {
+    /// Dependencies are loaded with `require`
+    CommonJS,
+    /// Dependencies are parameters of the factory passed to `define`
+    Amd,
+    /// Dependencies are loaded with `require` in a factory that is either called directly or
+    /// passed to `define`
+    Umd,
+    /// Dependencies are passed to setters registered with `System.register`
+    System,
+}
+
+/// The imports and exports of a module
+#[derive(Default)]
+struct ModuleInfo {
+    /// The declarations that import another module, or re-export from one, in order
+    external_imports: Vec<Arc<Node>>,
+    /// The names exported from the module that are undefined until the module body sets
+    /// them, in order and without duplicates
+    ///
+    /// Exported functions are left out, since they are exported before the body runs, and
+    /// so is the default export.
+    exported_names: Vec<String>,
+    /// The local and exported names of the exported functions
+    exported_functions: Vec<(String, String)>,
+    /// The names each top-level declaration or import is exported as, other than the
+    /// variables in [`ModuleInfo::exported_variables`]
+    exported_bindings: HashMap<String, Vec<String>>,
+    /// The variables of exported variable statements that declare only plain names
+    exported_variables: Vec<String>,
+    /// The name given to an anonymous function or class exported as the default
+    default_name: Option<String>,
+    /// Whether the module has a default export other than a function
+    has_export_default: bool,
+    /// Whether the module has `export =`
+    has_export_equals: bool,
+    /// Whether the module has `export * from "m"`
+    has_export_star: bool,
+}
+
+impl ModuleInfo {
+    fn add_exported_name(&mut self, name: &str) {
+        if name == "default" {
+            self.has_export_default = true;
+        } else if !self.exported_names.iter().any(|exported| exported == name) {
+            self.exported_names.push(name.to_string());
+        }
+    }
+
+    fn add_exported_binding(&mut self, local: &str, exported: &str) {
+        self.exported_bindings
+            .entry(local.to_string())
+            .or_default()
+            .push(exported.to_string());
+    }
+
+    /// Gets the name of the anonymous default export, generating it the first time
+    fn default_name(&mut self, context: &mut TransformContext) -> String {
+        self.default_name
+            .get_or_insert_with(|| context.create_unique_name("default"))
+            .clone()
+    }
+}
+
+/// Collects the imports and exports among the top-level statements of a module
+fn collect_module_info(source_file: &Arc<Node>, context: &mut TransformContext) -> ModuleInfo {
+    use SyntaxKind as K;
+    let statements = &source_file.as_source_file().statements;
+    let functions = statements
+        .iter()
+        .filter(|statement| statement.kind == K::FunctionDeclaration)
+        .filter_map(|statement| statement.name())
+        .map(|name| name.text().to_string())
+        .collect::<HashSet<_>>();
+
+    let mut info = ModuleInfo::default();
+    for statement in statements.iter() {
+        let is_export = statement.has_modifier(K::ExportKeyword);
+        let is_default = statement.has_modifier(K::DefaultKeyword);
+        match statement.kind {
+            K::ImportDeclaration => info.external_imports.push(Arc::clone(statement)),
+            K::ImportEqualsDeclaration if is_external_import_equals(statement) => {
+                info.external_imports.push(Arc::clone(statement));
+                if is_export {
+                    let name = statement.as_import_equals_declaration().name.text();
+                    info.exported_variables.push(name.to_string());
+                    info.add_exported_name(name);
+                }
+            }
+            K::ExportDeclaration => {
+                let data = statement.as_export_declaration();
+                if data.module_specifier.is_some() {
+                    info.external_imports.push(Arc::clone(statement));
+                }
+                match &data.export_clause {
+                    None => info.has_export_star = true,
+                    Some(clause) if clause.kind == K::NamespaceExport => {
+                        info.add_exported_name(clause.as_namespace_export().name.text());
+                    }
+                    Some(clause) => {
+                        for specifier in clause.as_named_exports().elements.iter() {
+                            let (local, exported) = export_specifier_names(specifier);
+                            if data.module_specifier.is_some() {
+                                info.add_exported_name(exported);
+                            } else if functions.contains(local) {
+                                info.exported_functions
+                                    .push((local.to_string(), exported.to_string()));
+                            } else {
+                                info.add_exported_binding(local, exported);
+                                info.add_exported_name(exported);
+                            }
+                        }
+                    }
+                }
+            }
+            K::ExportAssignment if statement.as_export_assignment().is_export_equals => {
+                info.has_export_equals = true;
+            }
+            K::ExportAssignment => info.has_export_default = true,
+            K::VariableStatement if is_export => {
+                let list = &statement.as_variable_statement().declaration_list;
+                let declarations = &list.as_variable_declaration_list().declarations;
+                let is_plain = declarations.iter().all(|declaration| {
+                    declaration.as_variable_declaration().name.kind == K::Identifier
+                });
+                for declaration in declarations.iter() {
+                    let mut names = Vec::new();
+                    collect_binding_names(&declaration.as_variable_declaration().name, &mut names);
+                    for name in names {
+                        if is_plain {
+                            info.exported_variables.push(name.clone());
+                        } else {
+                            info.add_exported_binding(&name, &name);
+                        }
+                        info.add_exported_name(&name);
+                    }
+                }
+            }
+            K::FunctionDeclaration if is_export => {
+                let name = match statement.name() {
+                    Some(name) => name.text().to_string(),
+                    None => info.default_name(context),
+                };
+                let exported = if is_default {
+                    "default".to_string()
+                } else {
+                    name.clone()
+                };
+                info.exported_functions.push((name, exported));
+            }
+            K::ClassDeclaration | K::EnumDeclaration | K::ModuleDeclaration if is_export => {
+                let name = match statement.name() {
+                    Some(name) => name.text().to_string(),
+                    None => info.default_name(context),
+                };
+                let exported = if is_default { "default" } else { name.as_str() };
+                info.add_exported_binding(&name, exported);
+                info.add_exported_name(exported);
+            }
+            _ => {}
+        }
+    }
+    info
+}
+
+/// Whether a declaration is `import x = require("m")`, rather than an alias of a namespace
+fn is_external_import_equals(node: &Node) -> bool {
+    node.kind == SyntaxKind::ImportEqualsDeclaration
+        && node.as_import_equals_declaration().module_reference.kind
+            == SyntaxKind::ExternalModuleReference
+}
+
+/// Gets the local and exported names of an export specifier
+fn export_specifier_names(specifier: &Node) -> (&str, &str) {
+    let data = specifier.as_export_specifier();
+    let local = data.property_name.as_ref().unwrap_or(&data.name);
+    (local.text(), data.name.text())
+}
+
+/// Gets the name of the module that a declaration imports or re-exports from
+fn module_specifier(node: &Node) -> &Arc<Node> {
+    match node.kind {
+        SyntaxKind::ImportDeclaration => &node.as_import_declaration().module_specifier,
+        SyntaxKind::ExportDeclaration => node
+            .as_export_declaration()
+            .module_specifier
+            .as_ref()
+            .expect("the declaration re-exports from a module"),
+        _ => {
+            &node
+                .as_import_equals_declaration()
+                .module_reference
+                .as_external_module_reference()
+                .expression
+        }
+    }
+}
+
+/// Generates the name of the variable a module is loaded into, based on the last segment of
+/// its path: `foo_1` for `"./lib/foo"`
+fn create_module_alias(context: &mut TransformContext, specifier: &Node) -> String {
+    if specifier.kind != SyntaxKind::StringLiteral {
+        return context.create_unique_name("module");
+    }
+    let path = specifier.text().trim_end_matches('/');
+    let base = path.rsplit('/').next().unwrap_or(path);
+    let mut name = base
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    context.create_unique_name(&name)
+}
+
+/// Creates `object.name`, or `object["name"]` if the name is not an identifier
+fn create_member_access(factory: &NodeFactory, object: Arc<Node>, name: &str) -> Arc<Node> {
+    let mut chars = name.chars();
+    let is_identifier = chars
+        .next()
+        .is_some_and(|ch| is_identifier_start(ch, ScriptTarget::ESNext))
+        && chars.all(|ch| is_identifier_part(ch, ScriptTarget::ESNext));
+    if is_identifier {
+        factory.create_property_access(object, name)
+    } else {
+        factory.create_element_access(object, factory.create_string_literal(name))
+    }
+}
+
+/// Gives an anonymous function or class declaration a name
+fn with_declaration_name(factory: &NodeFactory, node: &Arc<Node>, name: &str) -> Arc<Node> {
+    if node.name().is_some() {
+        return Arc::clone(node);
+    }
+    let name = Some(factory.create_identifier(name));
+    if node.kind == SyntaxKind::FunctionDeclaration {
+        let data = node.as_function_declaration();
+        factory.update_function_declaration(
+            node,
+            data.modifiers.clone(),
+            data.asterisk_token.clone(),
+            name,
+            data.type_parameters.clone(),
+            data.parameters.clone(),
+            data.type_node.clone(),
+            data.body.clone(),
+        )
+    } else {
+        let data = node.as_class_declaration();
+        factory.update_class_declaration(
+            node,
+            data.modifiers.clone(),
+            name,
+            data.type_parameters.clone(),
+            data.heritage_clauses.clone(),
+            data.members.clone(),
+        )
+    }
+}
+
+/// Creates a `const` statement at targets that have `const`, or a `var` statement otherwise
+fn create_const_statement(
+    factory: &NodeFactory,
+    target: ScriptTarget,
+    name: &str,
+    initializer: Arc<Node>,
+) -> Arc<Node> {
+    let flags = if target >= ScriptTarget::ES2015 {
+        NodeFlags::CONST
+    } else {
+        NodeFlags::NONE
+    };
+    let declaration_list =
+        factory.create_with_flags(
+            SyntaxKind::VariableDeclarationList,
+            flags,
+            VariableDeclarationList {
+                declarations: factory.create_node_list(vec![factory.create_variable_declaration(
+                    factory.create_identifier(name),
+                    Some(initializer),
+                )]),
+            },
+        );
+    factory.create(
+        SyntaxKind::VariableStatement,
+        VariableStatement {
+            modifiers: None,
+            declaration_list,
+        },
+    )
+}
+
+/// Creates `"use strict";`
+fn create_use_strict(factory: &NodeFactory) -> Arc<Node> {
+    factory.create_expression_statement(factory.create_string_literal("use strict"))
+}
+
+/// Whether a list of statements starts with a `"use strict"` directive
+fn has_use_strict(statements: &[Arc<Node>]) -> bool {
+    statements
+        .iter()
+        .take_while(|statement| super::is_prologue_directive(statement))
+        .any(|statement| statement.as_expression_statement().expression.text() == "use strict")
+}
+
+/// Rewrites the statements of a module for a module system other than ECMAScript modules
+///
+/// The transformer itself replaces references to imported and exported names throughout the
+/// module; the top-level statements are rewritten by the functions of each module format.
+struct ModuleTransformer<'a> {
+    factory: NodeFactory,
+    context: &'a mut TransformContext,
+    format: ModuleFormat,
+    info: ModuleInfo,
+    /// The names of the variables that the declarations in
+    /// [`ModuleInfo::external_imports`] load their modules into
+    import_aliases: Vec<Option<String>>,
+    /// The object exports are assigned to, or the function that sets them in a System module
+    exports_name: String,
+    /// The second parameter of the factory of a System module
+    context_name: String,
+    /// What each imported or exported name refers to instead, such as `m_1.a` or `exports.x`
+    substitutions: HashMap<String, Arc<Node>>,
+    /// The names declared in the functions and blocks around the node being visited, which
+    /// hide the module's own names
+    scopes: Vec<HashSet<String>>,
+    /// The name of the node being visited, if it is not a reference to a variable, such as the
+    /// name of a property
+    property_name: Option<Arc<Node>>,
+    /// Whether a UMD module checks `__syncRequire` to choose how to load a module with
+    /// `import()`
+    uses_sync_require: bool,
+    /// The variable `tslib` is loaded into, when the module imports its helpers from it
+    helpers_alias: Option<String>,
+    /// The helpers the lowered code of the module calls, which are read from `tslib` instead
+    /// when it is loaded
+    lowered_helpers: HashSet<&'static str>,
+}
+
+impl NodeVisitor for ModuleTransformer<'_> {
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        match non_reference_name(node) {
+            Some(name) => {
+                let outer = self.property_name.replace(Arc::clone(name));
+                let visited = self.visit_worker(node);
+                self.property_name = outer;
+                visited
+            }
+            None => self.visit_worker(node),
+        }
+    }
+}
+
+impl<'a> ModuleTransformer<'a> {
+    fn new(
+        context: &'a mut TransformContext,
+        format: ModuleFormat,
+        info: ModuleInfo,
+        exports_name: String,
+    ) -> Self {
+        let helpers_alias = context
+            .import_helpers
+            .then(|| context.create_unique_name("tslib"));
+        let lowered_helpers = match helpers_alias {
+            Some(_) => context.helpers.iter().map(|helper| helper.name).collect(),
+            None => HashSet::new(),
+        };
+        ModuleTransformer {
+            factory: NodeFactory::new(),
+            context,
+            format,
+            info,
+            import_aliases: Vec::new(),
+            exports_name,
+            context_name: String::new(),
+            substitutions: HashMap::new(),
+            scopes: Vec::new(),
+            property_name: None,
+            uses_sync_require: false,
+            helpers_alias,
+            lowered_helpers,
+        }
+    }
+}
+
+impl ModuleTransformer<'_> {
+    fn visit_worker(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        match node.kind {
+            K::Identifier => match self.substitution(node) {
+                Some(substitution) => factory.with_text_range(&substitution, node.loc),
+                None if self.lowered_helpers.contains(node.text())
+                    && self.is_module_reference(node) =>
+                {
+                    let helper = self.helpers_module_member(node.text());
+                    factory.with_text_range(&helper, node.loc)
+                }
+                None => Arc::clone(node),
+            },
+            K::CallExpression => self.visit_call_expression(node),
+            K::TaggedTemplateExpression => self.visit_tagged_template(node),
+            K::ShorthandPropertyAssignment => self.visit_shorthand_property(node),
+            K::BinaryExpression
+                if node
+                    .as_binary_expression()
+                    .operator_token
+                    .kind
+                    .is_assignment_operator() =>
+            {
+                let visited = visit_each_child(node, self, &factory);
+                let target = &node.as_binary_expression().left;
+                self.export_assigned_value(target, visited)
+            }
+            K::PrefixUnaryExpression | K::PostfixUnaryExpression => self.visit_update(node),
+            K::ExpressionStatement => {
+                let expression = &node.as_expression_statement().expression;
+                if expression.kind != K::PostfixUnaryExpression {
+                    return visit_each_child(node, self, &factory);
+                }
+                // The value of `x++` goes unused here, so it can be exported like `++x`
+                let data = expression.as_postfix_unary_expression();
+                let prefix = factory.create_at(
+                    K::PrefixUnaryExpression,
+                    expression.loc,
+                    PrefixUnaryExpression {
+                        operator: data.operator,
+                        operand: Arc::clone(&data.operand),
+                    },
+                );
+                if self.exported_names_of(&data.operand).is_none() {
+                    return visit_each_child(node, self, &factory);
+                }
+                let expression = self.visit_update(&prefix);
+                factory.update_expression_statement(node, expression)
+            }
+            K::MetaProperty
+                if self.format == ModuleFormat::System
+                    && node.as_meta_property().keyword_token == K::ImportKeyword =>
+            {
+                let meta = factory.create_property_access(
+                    factory.create_identifier(self.context_name.as_str()),
+                    "meta",
+                );
+                factory.with_text_range(&meta, node.loc)
+            }
+            _ => match scope_names(node) {
+                Some(names) => self.visit_in_scope(node, names),
+                None => visit_each_child(node, self, &factory),
+            },
+        }
+    }
+
+    /// Visits the children of a node whose scope declares `names`
+    fn visit_in_scope(&mut self, node: &Arc<Node>, names: Vec<String>) -> Arc<Node> {
+        let factory = self.factory;
+        if names.is_empty() {
+            return visit_each_child(node, self, &factory);
+        }
+        self.scopes.push(names.into_iter().collect());
+        let visited = visit_each_child(node, self, &factory);
+        self.scopes.pop();
+        visited
+    }
+
+    /// Whether an identifier refers to a top-level name of the module, rather than to a
+    /// property or to a name declared in a nested scope
+    fn is_module_reference(&self, node: &Arc<Node>) -> bool {
+        node.kind == SyntaxKind::Identifier
+            && !self
+                .property_name
+                .as_ref()
+                .is_some_and(|name| Arc::ptr_eq(name, node))
+            && !self.scopes.iter().any(|scope| scope.contains(node.text()))
+    }
+
+    /// Gets what an identifier refers to instead, if it refers to an imported name or an
+    /// exported variable
+    fn substitution(&self, node: &Arc<Node>) -> Option<Arc<Node>> {
+        if !self.is_module_reference(node) {
+            return None;
+        }
+        self.substitutions.get(node.text()).cloned()
+    }
+
+    /// Gets the names a variable is exported as, if an identifier refers to an exported one
+    fn exported_names_of(&self, node: &Arc<Node>) -> Option<Vec<String>> {
+        if !self.is_module_reference(node) {
+            return None;
+        }
+        self.info.exported_bindings.get(node.text()).cloned()
+    }
+
+    /// Calls a function read from an import as `(0, m_1.f)()`, so that `this` is not the module
+    fn visit_call_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_call_expression();
+        if data.expression.kind == SyntaxKind::ImportKeyword {
+            return self.visit_dynamic_import(node);
+        }
+        let Some(callee) = self.detached_callee(&data.expression) else {
+            return visit_each_child(node, self, &factory);
+        };
+        factory.update_call(
+            node,
+            callee,
+            data.question_dot_token.clone(),
+            None,
+            visit_nodes(&data.arguments, self, &factory),
+        )
+    }
+
+    fn visit_tagged_template(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_tagged_template_expression();
+        let Some(tag) = self.detached_callee(&data.tag) else {
+            return visit_each_child(node, self, &factory);
+        };
+        factory.update_tagged_template(
+            node,
+            tag,
+            data.question_dot_token.clone(),
+            None,
+            self.visit_node(&data.template),
+        )
+    }
+
+    /// Gets `(0, m_1.f)` for a callee that refers to an imported name
+    fn detached_callee(&self, callee: &Arc<Node>) -> Option<Arc<Node>> {
+        let factory = self.factory;
+        let substitution = self.substitution(callee)?;
+        if substitution.kind == SyntaxKind::Identifier {
+            return Some(substitution);
+        }
+        let detached = factory.create_binary(
+            factory.create_numeric_literal("0"),
+            SyntaxKind::CommaToken,
+            substitution,
+        );
+        Some(factory.with_text_range(&factory.create_parenthesized(detached), callee.loc))
+    }
+
+    /// Spells out `{ a }` as `{ a: m_1.a }` when `a` is imported
+    fn visit_shorthand_property(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_shorthand_property_assignment();
+        match self.substitution(&data.name) {
+            Some(substitution) => factory.with_text_range(
+                &factory.create_property_assignment(
+                    factory.create_identifier(data.name.text()),
+                    substitution,
+                ),
+                node.loc,
+            ),
+            None => factory.update_shorthand_property_assignment(
+                node,
+                data.modifiers.clone(),
+                Arc::clone(&data.name),
+                data.postfix_token.clone(),
+                data.equals_token.clone(),
+                data.object_assignment_initializer
+                    .as_ref()
+                    .map(|initializer| self.visit_node(initializer)),
+            ),
+        }
+    }
+
+    /// Exports the new value of a variable that an assignment changes
+    fn export_assigned_value(&self, target: &Arc<Node>, visited: Arc<Node>) -> Arc<Node> {
+        match self.exported_names_of(target) {
+            Some(names) => names.iter().fold(visited, |value, name| {
+                self.create_export_expression(name, value)
+            }),
+            None => visited,
+        }
+    }
+
+    /// Exports the new value of a variable that `++` or `--` changes
+    ///
+    /// `x++` still evaluates to the old value: `(exports.x = ++x, x - 1)`.
+    fn visit_update(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        let (operator, operand) = match node.kind {
+            K::PrefixUnaryExpression => {
+                let data = node.as_prefix_unary_expression();
+                (data.operator, &data.operand)
+            }
+            _ => {
+                let data = node.as_postfix_unary_expression();
+                (data.operator, &data.operand)
+            }
+        };
+        let visited = visit_each_child(node, self, &factory);
+        if !matches!(operator, K::PlusPlusToken | K::MinusMinusToken) {
+            return visited;
+        }
+        let Some(names) = self.exported_names_of(operand) else {
+            return visited;
+        };
+        if node.kind == K::PrefixUnaryExpression {
+            return self.export_assigned_value(operand, visited);
+        }
+        let prefix = factory.create(
+            K::PrefixUnaryExpression,
+            PrefixUnaryExpression {
+                operator,
+                operand: self.visit_node(operand),
+            },
+        );
+        let exported = names.iter().fold(prefix, |value, name| {
+            self.create_export_expression(name, value)
+        });
+        let original = factory.create_binary(
+            self.visit_node(operand),
+            if operator == K::PlusPlusToken {
+                K::MinusToken
+            } else {
+                K::PlusToken
+            },
+            factory.create_numeric_literal("1"),
+        );
+        let sequence = factory.create_binary(exported, K::CommaToken, original);
+        factory.with_text_range(&factory.create_parenthesized(sequence), node.loc)
+    }
+
+    /// Loads a module with `import("m")` the way the module system loads its dependencies
+    fn visit_dynamic_import(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let argument = node
+            .as_call_expression()
+            .arguments
+            .iter()
+            .next()
+            .map(|argument| self.visit_node(argument));
+        let import = match self.format {
+            ModuleFormat::CommonJS => self.create_require_import(argument),
+            ModuleFormat::Amd => self.create_define_import(argument),
+            ModuleFormat::Umd => {
+                self.uses_sync_require = true;
+                let condition = factory.create_identifier("__syncRequire");
+                factory.create(
+                    SyntaxKind::ConditionalExpression,
+                    ConditionalExpression {
+                        condition,
+                        question_token: factory.create_token(SyntaxKind::QuestionToken),
+                        when_true: self.create_require_import(argument.clone()),
+                        colon_token: factory.create_token(SyntaxKind::ColonToken),
+                        when_false: self.create_define_import(argument),
+                    },
+                )
+            }
+            ModuleFormat::System => factory.create_call(
+                factory.create_property_access(
+                    factory.create_identifier(self.context_name.as_str()),
+                    "import",
+                ),
+                argument.into_iter().collect(),
+            ),
+        };
+        factory.with_text_range(&import, node.loc)
+    }
+
+    /// Creates `Promise.resolve().then(() => require("m"))`
+    fn create_require_import(&mut self, argument: Option<Arc<Node>>) -> Arc<Node> {
+        let factory = self.factory;
+        let require = factory.create_call(
+            factory.create_identifier("require"),
+            argument.into_iter().collect(),
+        );
+        let module = if self.context.es_module_interop {
+            self.call_helper(&IMPORT_STAR_HELPER, require)
+        } else {
+            require
+        };
+        let resolved = factory.create_call(
+            factory.create_property_access(factory.create_identifier("Promise"), "resolve"),
+            Vec::new(),
+        );
+        let callback = self.create_callback(Vec::new(), Callback::Expression(module));
+        factory.create_call(
+            factory.create_property_access(resolved, "then"),
+            vec![callback],
+        )
+    }
+
+    /// Creates
+    /// `new Promise((resolve_1, reject_1) => { require(["m"], resolve_1, reject_1); })`
+    fn create_define_import(&mut self, argument: Option<Arc<Node>>) -> Arc<Node> {
+        let factory = self.factory;
+        let resolve = self.context.create_unique_name("resolve");
+        let reject = self.context.create_unique_name("reject");
+        let require = factory.create_call(
+            factory.create_identifier("require"),
+            vec![
+                factory.create_array_literal(argument.into_iter().collect(), false),
+                factory.create_identifier(resolve.as_str()),
+                factory.create_identifier(reject.as_str()),
+            ],
+        );
+        let callback = self.create_callback(
+            vec![resolve, reject],
+            Callback::Statements(vec![factory.create_expression_statement(require)]),
+        );
+        let promise = factory.create(
+            SyntaxKind::NewExpression,
+            NewExpression {
+                expression: factory.create_identifier("Promise"),
+                type_arguments: None,
+                arguments: Some(factory.create_node_list(vec![callback])),
+            },
+        );
+        if !self.context.es_module_interop {
+            return promise;
+        }
+        let helper = self.helper_reference(&IMPORT_STAR_HELPER);
+        factory.create_call(
+            factory.create_property_access(promise, "then"),
+            vec![helper],
+        )
+    }
+
+    /// Creates an arrow function, or a function expression at targets without arrows
+    fn create_callback(&self, parameters: Vec<String>, body: Callback) -> Arc<Node> {
+        let factory = self.factory;
+        let parameters = parameters
+            .into_iter()
+            .map(|name| factory.create_parameter(factory.create_identifier(name)))
+            .collect::<Vec<_>>();
+        if self.context.target < ScriptTarget::ES2015 {
+            let statements = match body {
+                Callback::Expression(expression) => vec![factory.create_return(Some(expression))],
+                Callback::Statements(statements) => statements,
+            };
+            return factory.create_function_expression(
+                None,
+                parameters,
+                factory.create_block(statements, false),
+            );
+        }
+        let body = match body {
+            Callback::Expression(expression) => expression,
+            Callback::Statements(statements) => factory.create_block(statements, false),
+        };
+        factory.create(
+            SyntaxKind::ArrowFunction,
+            ArrowFunction {
+                modifiers: None,
+                type_parameters: None,
+                parameters: factory.create_node_list(parameters),
+                type_node: None,
+                equals_greater_than_token: factory.create_token(SyntaxKind::EqualsGreaterThanToken),
+                body,
+            },
+        )
+    }
+
+    /// Creates `helper(argument)`, requesting the helper
+    fn call_helper(&mut self, helper: &'static EmitHelper, argument: Arc<Node>) -> Arc<Node> {
+        let callee = self.helper_reference(helper);
+        self.factory.create_call(callee, vec![argument])
+    }
+
+    /// Requests a helper, getting the expression that refers to it: `__name`, or
+    /// `tslib_1.__name` when helpers are imported from `tslib`
+    fn helper_reference(&mut self, helper: &'static EmitHelper) -> Arc<Node> {
+        self.context.request_helper(helper);
+        self.helpers_module_member(helper.name)
+    }
+
+    fn helpers_module_member(&self, name: &str) -> Arc<Node> {
+        let factory = self.factory;
+        match &self.helpers_alias {
+            Some(alias) => {
+                factory.create_property_access(factory.create_identifier(alias.as_str()), name)
+            }
+            None => factory.create_identifier(name),
+        }
+    }
+
+    /// Gets the variable `tslib` is loaded into, if the module imports any helpers from it
+    fn helpers_import_alias(&self) -> Option<&str> {
+        self.helpers_alias
+            .as_deref()
+            .filter(|_| !self.context.helpers.is_empty())
+    }
+
+    /// Creates the expression that sets the export `name` to `value`: `exports.name = value`,
+    /// or `exports_1("name", value)` in a System module
+    fn create_export_expression(&self, name: &str, value: Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let exports = factory.create_identifier(self.exports_name.as_str());
+        if self.format == ModuleFormat::System {
+            factory.create_call(exports, vec![factory.create_string_literal(name), value])
+        } else {
+            factory.create_assignment(create_member_access(&factory, exports, name), value)
+        }
+    }
+
+    fn create_export_statement(&self, name: &str, value: Arc<Node>) -> Arc<Node> {
+        self.factory
+            .create_expression_statement(self.create_export_expression(name, value))
+    }
+
+    /// Creates the statements that export each of `names` that a top-level declaration
+    /// declares, reading the variable itself
+    fn create_local_exports(&self, names: &[String]) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        names
+            .iter()
+            .flat_map(|local| {
+                self.info
+                    .exported_bindings
+                    .get(local)
+                    .into_iter()
+                    .flatten()
+                    .map(move |exported| {
+                        self.create_export_statement(
+                            exported,
+                            factory.create_identifier(local.as_str()),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Removes the `export` modifiers of a declaration, naming it if it is an anonymous
+    /// default export
+    fn export_declaration(&self, node: &Arc<Node>) -> Arc<Node> {
+        let stripped = strip_export_modifiers(&self.factory, node);
+        match &self.info.default_name {
+            Some(name) if node.has_modifier(SyntaxKind::DefaultKeyword) => {
+                with_declaration_name(&self.factory, &stripped, name)
+            }
+            _ => stripped,
+        }
+    }
+
+    /// Chooses the variable each import loads its module into, and what each imported name
+    /// refers to through that variable
+    ///
+    /// `import d, { a as b } from "m"` loads `"m"` into `m_1`, so `d` refers to `m_1.default`
+    /// and `b` to `m_1.a`. A namespace import keeps its own name. Re-exports need a variable
+    /// only when the module format reads the re-exported names from one.
+    fn assign_import_aliases(&mut self) {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        for import in self.info.external_imports.clone() {
+            let alias = match import.kind {
+                K::ImportDeclaration => match &import.as_import_declaration().import_clause {
+                    None => None,
+                    Some(clause) => {
+                        let clause = clause.as_import_clause();
+                        let namespace = clause
+                            .named_bindings
+                            .as_ref()
+                            .filter(|bindings| bindings.kind == K::NamespaceImport);
+                        let alias = match namespace {
+                            Some(namespace) => {
+                                namespace.as_namespace_import().name.text().to_string()
+                            }
+                            None => create_module_alias(self.context, module_specifier(&import)),
+                        };
+                        let module = || factory.create_identifier(alias.as_str());
+                        if let Some(name) = &clause.name {
+                            self.substitutions.insert(
+                                name.text().to_string(),
+                                factory.create_property_access(module(), "default"),
+                            );
+                        }
+                        if let Some(bindings) = &clause.named_bindings
+                            && bindings.kind == K::NamedImports
+                        {
+                            for specifier in bindings.as_named_imports().elements.iter() {
+                                let data = specifier.as_import_specifier();
+                                let imported = data.property_name.as_ref().unwrap_or(&data.name);
+                                self.substitutions.insert(
+                                    data.name.text().to_string(),
+                                    create_member_access(&factory, module(), imported.text()),
+                                );
+                            }
+                        }
+                        Some(alias)
+                    }
+                },
+                K::ImportEqualsDeclaration => Some(
+                    import
+                        .as_import_equals_declaration()
+                        .name
+                        .text()
+                        .to_string(),
+                ),
+                _ => {
+                    let data = import.as_export_declaration();
+                    let needs_alias = match self.format {
+                        ModuleFormat::Amd => true,
+                        ModuleFormat::CommonJS | ModuleFormat::Umd => data
+                            .export_clause
+                            .as_ref()
+                            .is_some_and(|clause| clause.kind == K::NamedExports),
+                        ModuleFormat::System => false,
+                    };
+                    needs_alias
+                        .then(|| create_module_alias(self.context, module_specifier(&import)))
+                }
+            };
+            self.import_aliases.push(alias);
+        }
+    }
+
+    /// Gets the specifier a dependency is loaded with: the name it is defined under if it is
+    /// bundled into the same file, or the specifier as it is written otherwise
+    fn dependency_specifier(&self, specifier: &Arc<Node>) -> Arc<Node> {
+        match self.context.bundled_module_names.get(specifier.text()) {
+            Some(name) => self.factory.create_string_literal(name.as_str()),
+            None => Arc::clone(specifier),
+        }
+    }
+
+    /// Gets the variable an import or re-export loads its module into
+    fn import_alias(&self, node: &Arc<Node>) -> Option<String> {
+        self.info
+            .external_imports
+            .iter()
+            .position(|import| Arc::ptr_eq(import, node))
+            .and_then(|index| self.import_aliases[index].clone())
+    }
+
+    /// Chooses the helper that adapts a CommonJS module to what an import expects of it,
+    /// when `esModuleInterop` is on
+    ///
+    /// A namespace import, or one that mixes the default export with named ones, needs
+    /// `__importStar`. One that only reads the default export needs `__importDefault`.
+    fn import_helper(&self, node: &Node) -> Option<&'static EmitHelper> {
+        use SyntaxKind as K;
+        if !self.context.es_module_interop {
+            return None;
+        }
+        match node.kind {
+            K::ImportDeclaration => {
+                let clause = node.as_import_declaration().import_clause.as_ref()?;
+                let clause = clause.as_import_clause();
+                let (default_imports, named_imports) = match &clause.named_bindings {
+                    Some(bindings) if bindings.kind == K::NamespaceImport => {
+                        return Some(&IMPORT_STAR_HELPER);
+                    }
+                    Some(bindings) => {
+                        let elements = &bindings.as_named_imports().elements;
+                        let defaults = elements
+                            .iter()
+                            .filter(|specifier| {
+                                let data = specifier.as_import_specifier();
+                                data.property_name.as_ref().unwrap_or(&data.name).text()
+                                    == "default"
+                            })
+                            .count();
+                        (defaults, elements.len() - defaults)
+                    }
+                    None => (0, 0),
+                };
+                let has_default = clause.name.is_some() || default_imports > 0;
+                if named_imports > 0 && has_default {
+                    Some(&IMPORT_STAR_HELPER)
+                } else if has_default {
+                    Some(&IMPORT_DEFAULT_HELPER)
+                } else {
+                    None
+                }
+            }
+            K::ExportDeclaration => node
+                .as_export_declaration()
+                .export_clause
+                .as_ref()
+                .filter(|clause| clause.kind == K::NamespaceExport)
+                .map(|_| &IMPORT_STAR_HELPER),
+            _ => None,
+        }
+    }
+
+    /// Gets the local names an import declares that the module also exports
+    fn exported_import_names(&self, node: &Node) -> Vec<String> {
+        use SyntaxKind as K;
+        let mut names = Vec::new();
+        match node.kind {
+            K::ImportDeclaration => {
+                if let Some(clause) = &node.as_import_declaration().import_clause {
+                    let clause = clause.as_import_clause();
+                    names.extend(clause.name.iter().map(|name| name.text().to_string()));
+                    match &clause.named_bindings {
+                        Some(bindings) if bindings.kind == K::NamespaceImport => {
+                            names.push(bindings.as_namespace_import().name.text().to_string());
+                        }
+                        Some(bindings) => {
+                            names.extend(bindings.as_named_imports().elements.iter().map(
+                                |specifier| specifier.as_import_specifier().name.text().to_string(),
+                            ));
+                        }
+                        None => {}
+                    }
+                }
+            }
+            K::ImportEqualsDeclaration => {
+                names.push(node.as_import_equals_declaration().name.text().to_string());
+            }
+            _ => {}
+        }
+        names.retain(|name| self.info.exported_bindings.contains_key(name));
+        names
+    }
+}
+
+/// The body of a generated callback
+enum Callback {
+    /// The value the callback returns
+    Expression(Arc<Node>),
+    /// The statements the callback runs
+    Statements(Vec<Arc<Node>>),
+}
@@ -0,0 +1,571 @@
+//! CommonJS, AMD, and UMD modules
+//!
+//! A CommonJS module loads each import with `require` and assigns its exports to `exports`:
+//!
+//! ```js
+//! "use strict";
+//! Object.defineProperty(exports, "__esModule", { value: true });
+//! exports.x = void 0;
+//! const m_1 = require("./m");
+//! exports.x = (0, m_1.f)();
+//! ```
+//!
+//! An AMD module has the same body in a factory passed to `define`, which receives each
+//! dependency as a parameter instead of loading it with `require`. A UMD module wraps the
+//! CommonJS body in a factory that is called directly in Node.js and passed to `define`
+//! elsewhere.
+
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::visitor::{NodeVisitor, visit_each_child};
+use crate::compiler::ast::{Node, SyntaxKind};
+
+use super::super::helpers::{EXPORT_STAR_HELPER, IMPORT_DEFAULT_HELPER, parse_statements};
+use super::super::{
+    TransformContext, insert_after_prologue, is_prologue_directive, update_statements,
+};
+use super::{
+    ModuleFormat, ModuleInfo, ModuleTransformer, collect_module_info, create_const_statement,
+    create_member_access, create_use_strict, export_specifier_names, has_use_strict,
+    is_external_import_equals, module_specifier, strip_export_modifiers,
+};
+
+/// Rewrites the `import()` calls of a script, which is otherwise emitted as it is
+pub(super) fn transform_commonjs_script(
+    source_file: &Arc<Node>,
+    context: &mut TransformContext,
+) -> Arc<Node> {
+    let info = ModuleInfo::default();
+    let mut transformer =
+        ModuleTransformer::new(context, ModuleFormat::CommonJS, info, "exports".to_string());
+    let factory = transformer.factory;
+    visit_each_child(source_file, &mut transformer, &factory)
+}
+
+pub(super) fn transform_commonjs_module(
+    source_file: &Arc<Node>,
+    context: &mut TransformContext,
+    format: ModuleFormat,
+) -> Arc<Node> {
+    let info = collect_module_info(source_file, context);
+    let mut transformer = ModuleTransformer::new(context, format, info, "exports".to_string());
+    let factory = transformer.factory;
+    transformer.assign_import_aliases();
+    for name in transformer.info.exported_variables.clone() {
+        let access = create_member_access(&factory, factory.create_identifier("exports"), &name);
+        transformer.substitutions.insert(name, access);
+    }
+
+    let statements = &source_file.as_source_file().statements.nodes;
+    let prologue_length = statements
+        .iter()
+        .take_while(|statement| is_prologue_directive(statement))
+        .count();
+    let mut body = statements[..prologue_length].to_vec();
+    if !has_use_strict(&body) {
+        body.insert(0, create_use_strict(&factory));
+    }
+    body.extend(transformer.create_export_header());
+    let header_length = body.len();
+    let mut export_equals = None;
+    for statement in &statements[prologue_length..] {
+        if statement.kind == SyntaxKind::ExportAssignment
+            && statement.as_export_assignment().is_export_equals
+        {
+            let expression = &statement.as_export_assignment().expression;
+            export_equals = Some((transformer.visit_node(expression), statement.loc));
+            continue;
+        }
+        body.extend(transformer.visit_top_level_statement(statement));
+    }
+    if format != ModuleFormat::Amd
+        && let Some(alias) = transformer.helpers_import_alias()
+    {
+        let require = factory.create_call(
+            factory.create_identifier("require"),
+            vec![factory.create_string_literal("tslib")],
+        );
+        let declaration =
+            create_const_statement(&factory, transformer.context.target, alias, require);
+        body.insert(header_length, declaration);
+    }
+    if transformer.uses_sync_require {
+        let declaration = parse_statements(
+            &factory,
+            "umd.js",
+            r#"var __syncRequire = typeof module === "object" && typeof module.exports === "object";"#,
+        );
+        insert_after_prologue(&mut body, declaration);
+    }
+
+    if format == ModuleFormat::CommonJS {
+        if let Some((value, loc)) = export_equals {
+            let module_exports =
+                factory.create_property_access(factory.create_identifier("module"), "exports");
+            let assignment = factory
+                .create_expression_statement(factory.create_assignment(module_exports, value));
+            body.push(factory.with_text_range(&assignment, loc));
+        }
+        return update_statements(&factory, source_file, body);
+    }
+    if let Some((value, loc)) = export_equals {
+        body.push(factory.with_text_range(&factory.create_return(Some(value)), loc));
+    }
+    let module = if format == ModuleFormat::Amd {
+        transformer.create_define(body)
+    } else {
+        transformer.create_umd_wrapper(body)
+    };
+    update_statements(&factory, source_file, vec![module])
+}
+
+/// Rewrites the CommonJS-only syntax of a module whose imports and exports are otherwise
+/// preserved: `import x = require("m")` becomes `const x = require("m")`, and `export = x`
+/// becomes `module.exports = x`
+pub(super) fn transform_preserved_module(
+    source_file: &Arc<Node>,
+    context: &mut TransformContext,
+) -> Arc<Node> {
+    let factory = NodeFactory::new();
+    let statements = source_file
+        .as_source_file()
+        .statements
+        .iter()
+        .map(|statement| match statement.kind {
+            SyntaxKind::ImportEqualsDeclaration
+                if is_external_import_equals(statement)
+                    && !statement.has_modifier(SyntaxKind::ExportKeyword) =>
+            {
+                let data = statement.as_import_equals_declaration();
+                let require = factory.create_call(
+                    factory.create_identifier("require"),
+                    vec![Arc::clone(module_specifier(statement))],
+                );
+                let declaration =
+                    create_const_statement(&factory, context.target, data.name.text(), require);
+                factory.with_text_range(&declaration, statement.loc)
+            }
+            SyntaxKind::ExportAssignment if statement.as_export_assignment().is_export_equals => {
+                let module_exports =
+                    factory.create_property_access(factory.create_identifier("module"), "exports");
+                let assignment = factory.create_assignment(
+                    module_exports,
+                    Arc::clone(&statement.as_export_assignment().expression),
+                );
+                factory.with_text_range(
+                    &factory.create_expression_statement(assignment),
+                    statement.loc,
+                )
+            }
+            _ => Arc::clone(statement),
+        })
+        .collect();
+    update_statements(&factory, source_file, statements)
+}
+
+impl ModuleTransformer<'_> {
+    /// Creates the statements at the top of the module body: the `__esModule` marker, the
+    /// exports that start out undefined, and the exported functions, which are hoisted
+    fn create_export_header(&self) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let mut statements = Vec::new();
+        if !self.info.has_export_equals {
+            let value = factory.create_property_assignment(
+                factory.create_identifier("value"),
+                factory.create_token(SyntaxKind::TrueKeyword),
+            );
+            statements.push(self.create_define_property("__esModule", vec![value]));
+        }
+        // Long chains of assignments are split so the printer does not recurse too deeply
+        for names in self.info.exported_names.chunks(50) {
+            let assignments = names
+                .iter()
+                .fold(factory.create_void_zero(), |value, name| {
+                    self.create_export_expression(name, value)
+                });
+            statements.push(factory.create_expression_statement(assignments));
+        }
+        for (local, exported) in &self.info.exported_functions {
+            statements.push(
+                self.create_export_statement(exported, factory.create_identifier(local.as_str())),
+            );
+        }
+        statements
+    }
+
+    /// Rewrites a top-level statement of the module, returning the statements that replace it
+    fn visit_top_level_statement(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        let is_export = node.has_modifier(K::ExportKeyword);
+        match node.kind {
+            K::ImportDeclaration => self.visit_import_declaration(node),
+            K::ImportEqualsDeclaration if is_external_import_equals(node) => {
+                self.visit_import_equals_declaration(node)
+            }
+            K::ExportDeclaration if node.as_export_declaration().module_specifier.is_some() => {
+                self.visit_re_export(node)
+            }
+            K::ExportDeclaration => Vec::new(),
+            K::ExportAssignment => {
+                let expression = self.visit_node(&node.as_export_assignment().expression);
+                let statement = self.create_export_statement("default", expression);
+                vec![factory.with_text_range(&statement, node.loc)]
+            }
+            K::VariableStatement if is_export => self.visit_exported_variable_statement(node),
+            K::VariableStatement => {
+                let mut names = Vec::new();
+                let list = &node.as_variable_statement().declaration_list;
+                for declaration in list.as_variable_declaration_list().declarations.iter() {
+                    super::collect_binding_names(
+                        &declaration.as_variable_declaration().name,
+                        &mut names,
+                    );
+                }
+                let mut statements = vec![self.visit_node(node)];
+                statements.extend(self.create_local_exports(&names));
+                statements
+            }
+            K::FunctionDeclaration if is_export => {
+                let visited = self.visit_node(node);
+                vec![self.export_declaration(&visited)]
+            }
+            K::ClassDeclaration | K::EnumDeclaration | K::ModuleDeclaration => {
+                let mut visited = self.visit_node(node);
+                if is_export {
+                    visited = self.export_declaration(&visited);
+                }
+                let name = visited.name().map(|name| name.text().to_string());
+                let mut statements = vec![visited];
+                statements.extend(self.create_local_exports(&Vec::from_iter(name)));
+                statements
+            }
+            _ => vec![self.visit_node(node)],
+        }
+    }
+
+    /// Turns `export let a = 1, b;` into `exports.a = 1;`
+    ///
+    /// References to `a` read `exports.a` instead, so the variable is not declared at all.
+    /// Destructuring declarations are kept, and each name they declare is exported after
+    /// them.
+    fn visit_exported_variable_statement(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let list = &node.as_variable_statement().declaration_list;
+        let declarations = &list.as_variable_declaration_list().declarations;
+        let is_plain = declarations.iter().all(|declaration| {
+            declaration.as_variable_declaration().name.kind == SyntaxKind::Identifier
+        });
+        if !is_plain {
+            let mut names = Vec::new();
+            for declaration in declarations.iter() {
+                super::collect_binding_names(
+                    &declaration.as_variable_declaration().name,
+                    &mut names,
+                );
+            }
+            let visited = self.visit_node(node);
+            let mut statements = vec![strip_export_modifiers(&factory, &visited)];
+            statements.extend(self.create_local_exports(&names));
+            return statements;
+        }
+        let assignments = declarations
+            .iter()
+            .filter_map(|declaration| {
+                let data = declaration.as_variable_declaration();
+                let initializer = self.visit_node(data.initializer.as_ref()?);
+                let assignment = self.create_export_expression(data.name.text(), initializer);
+                Some(self.export_assigned_value(&data.name, assignment))
+            })
+            .reduce(|left, right| factory.create_binary(left, SyntaxKind::CommaToken, right));
+        match assignments {
+            Some(assignments) => vec![
+                factory
+                    .with_text_range(&factory.create_expression_statement(assignments), node.loc),
+            ],
+            None => Vec::new(),
+        }
+    }
+
+    /// Creates `require("m")` for the module an import or re-export refers to
+    fn create_require(&self, node: &Node) -> Arc<Node> {
+        let factory = self.factory;
+        factory.create_call(
+            factory.create_identifier("require"),
+            vec![Arc::clone(module_specifier(node))],
+        )
+    }
+
+    /// Turns `import d, { a } from "m"` into `const m_1 = require("m");`, or into nothing in
+    /// an AMD module, where `m_1` is a parameter of the factory
+    fn visit_import_declaration(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let helper = self.import_helper(node);
+        let mut statements = Vec::new();
+        match (self.import_alias(node), self.format) {
+            (None, ModuleFormat::Amd) => {}
+            (None, _) => {
+                statements.push(factory.create_expression_statement(self.create_require(node)));
+            }
+            (Some(alias), ModuleFormat::Amd) => {
+                if let Some(helper) = helper {
+                    let module = factory.create_identifier(alias.as_str());
+                    let value = self.call_helper(helper, module);
+                    statements.push(factory.create_expression_statement(
+                        factory.create_assignment(factory.create_identifier(alias), value),
+                    ));
+                }
+            }
+            (Some(alias), _) => {
+                let mut module = self.create_require(node);
+                if let Some(helper) = helper {
+                    module = self.call_helper(helper, module);
+                }
+                statements.push(create_const_statement(
+                    &factory,
+                    self.context.target,
+                    &alias,
+                    module,
+                ));
+            }
+        }
+        if let Some(first) = statements.first_mut() {
+            *first = factory.with_text_range(first, node.loc);
+        }
+        statements.extend(self.create_import_binding_exports(node));
+        statements
+    }
+
+    /// Turns `import x = require("m")` into `const x = require("m");`, or into
+    /// `exports.x = require("m");` if it is exported
+    fn visit_import_equals_declaration(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let name = node.as_import_equals_declaration().name.text();
+        let is_export = node.has_modifier(SyntaxKind::ExportKeyword);
+        let mut statements = Vec::new();
+        if self.format == ModuleFormat::Amd {
+            if is_export {
+                statements
+                    .push(self.create_export_statement(name, factory.create_identifier(name)));
+            }
+        } else if is_export {
+            statements.push(self.create_export_statement(name, self.create_require(node)));
+        } else {
+            statements.push(create_const_statement(
+                &factory,
+                self.context.target,
+                name,
+                self.create_require(node),
+            ));
+        }
+        if let Some(first) = statements.first_mut() {
+            *first = factory.with_text_range(first, node.loc);
+        }
+        statements.extend(self.create_import_binding_exports(node));
+        statements
+    }
+
+    /// Exports the names of an import that are re-exported by an export declaration
+    /// without a module, reading them through the module they were imported from
+    fn create_import_binding_exports(&self, node: &Node) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let mut statements = Vec::new();
+        for local in self.exported_import_names(node) {
+            for exported in &self.info.exported_bindings[&local] {
+                statements.push(match self.substitutions.get(&local) {
+                    Some(value) => self.create_export_getter(exported, Arc::clone(value)),
+                    None => self.create_export_statement(
+                        exported,
+                        factory.create_identifier(local.as_str()),
+                    ),
+                });
+            }
+        }
+        statements
+    }
+
+    /// Turns `export { a as b } from "m"` into a getter on `exports` that reads `a` from the
+    /// loaded module, `export * from "m"` into a call to `__exportStar`, and
+    /// `export * as ns from "m"` into an assignment of the whole module
+    fn visit_re_export(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let data = node.as_export_declaration();
+        let alias = self.import_alias(node);
+        let module = match (&alias, self.format) {
+            (Some(alias), ModuleFormat::Amd) => factory.create_identifier(alias.as_str()),
+            _ => self.create_require(node),
+        };
+        let mut statements = Vec::new();
+        match &data.export_clause {
+            None => {
+                let call = factory.create_call(
+                    self.helper_reference(&EXPORT_STAR_HELPER),
+                    vec![module, factory.create_identifier("exports")],
+                );
+                statements.push(factory.create_expression_statement(call));
+            }
+            Some(clause) if clause.kind == SyntaxKind::NamespaceExport => {
+                let name = clause.as_namespace_export().name.text();
+                let value = match self.import_helper(node) {
+                    Some(helper) => self.call_helper(helper, module),
+                    None => module,
+                };
+                statements.push(self.create_export_statement(name, value));
+            }
+            Some(clause) => {
+                let alias = alias.expect("named re-exports load their module into a variable");
+                if self.format != ModuleFormat::Amd {
+                    statements.push(create_const_statement(
+                        &factory,
+                        self.context.target,
+                        &alias,
+                        module,
+                    ));
+                }
+                for specifier in clause.as_named_exports().elements.iter() {
+                    let (imported, exported) = export_specifier_names(specifier);
+                    let mut source = factory.create_identifier(alias.as_str());
+                    if imported == "default" && self.context.es_module_interop {
+                        source = self.call_helper(&IMPORT_DEFAULT_HELPER, source);
+                    }
+                    let value = create_member_access(&factory, source, imported);
+                    statements.push(self.create_export_getter(exported, value));
+                }
+            }
+        }
+        if let Some(first) = statements.first_mut() {
+            *first = factory.with_text_range(first, node.loc);
+        }
+        statements
+    }
+
+    /// Creates `Object.defineProperty(exports, "name", { enumerable: true, get: ... });`,
+    /// which keeps a re-exported binding live
+    fn create_export_getter(&self, name: &str, value: Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let getter = factory.create_function_expression(
+            None,
+            Vec::new(),
+            factory.create_block(vec![factory.create_return(Some(value))], false),
+        );
+        let properties = vec![
+            factory.create_property_assignment(
+                factory.create_identifier("enumerable"),
+                factory.create_token(SyntaxKind::TrueKeyword),
+            ),
+            factory.create_property_assignment(factory.create_identifier("get"), getter),
+        ];
+        self.create_define_property(name, properties)
+    }
+
+    /// Creates `Object.defineProperty(exports, "name", { properties });`
+    fn create_define_property(&self, name: &str, properties: Vec<Arc<Node>>) -> Arc<Node> {
+        let factory = self.factory;
+        let define_property =
+            factory.create_property_access(factory.create_identifier("Object"), "defineProperty");
+        factory.create_expression_statement(factory.create_call(
+            define_property,
+            vec![
+                factory.create_identifier("exports"),
+                factory.create_string_literal(name),
+                factory.create_object_literal(properties, false),
+            ],
+        ))
+    }
+
+    /// Gets the module names an AMD module depends on, and the parameters of its factory
+    ///
+    /// The dependencies with a parameter come first, after `require` and `exports`, and
+    /// those only imported for their side effects come last.
+    fn amd_dependencies(&self) -> (Vec<Arc<Node>>, Vec<Arc<Node>>) {
+        let factory = self.factory;
+        let mut dependencies = vec![
+            factory.create_string_literal("require"),
+            factory.create_string_literal("exports"),
+        ];
+        let mut parameters = vec![
+            factory.create_parameter(factory.create_identifier("require")),
+            factory.create_parameter(factory.create_identifier("exports")),
+        ];
+        if let Some(alias) = self.helpers_import_alias() {
+            dependencies.push(factory.create_string_literal("tslib"));
+            if self.format == ModuleFormat::Amd {
+                parameters.push(factory.create_parameter(factory.create_identifier(alias)));
+            }
+        }
+        let mut unaliased = Vec::new();
+        for (import, alias) in self.info.external_imports.iter().zip(&self.import_aliases) {
+            let specifier = self.dependency_specifier(module_specifier(import));
+            match alias {
+                Some(alias) if self.format == ModuleFormat::Amd => {
+                    dependencies.push(specifier);
+                    parameters
+                        .push(factory.create_parameter(factory.create_identifier(alias.as_str())));
+                }
+                _ => unaliased.push(specifier),
+            }
+        }
+        dependencies.extend(unaliased);
+        (dependencies, parameters)
+    }
+
+    /// Creates `define(["require", "exports", ...], function (require, exports, ...) { body });`,
+    /// with the name of the module first when it is bundled
+    fn create_define(&self, body: Vec<Arc<Node>>) -> Arc<Node> {
+        let factory = self.factory;
+        let (dependencies, parameters) = self.amd_dependencies();
+        let module_factory =
+            factory.create_function_expression(None, parameters, factory.create_block(body, true));
+        let mut arguments: Vec<Arc<Node>> = self
+            .context
+            .module_name
+            .iter()
+            .map(|name| factory.create_string_literal(name.as_str()))
+            .collect();
+        arguments.push(factory.create_array_literal(dependencies, false));
+        arguments.push(module_factory);
+        factory.create_expression_statement(
+            factory.create_call(factory.create_identifier("define"), arguments),
+        )
+    }
+
+    /// Wraps the module body in a factory that is called with `require` and `exports` in
+    /// CommonJS, or passed to `define` where AMD is available
+    fn create_umd_wrapper(&self, body: Vec<Arc<Node>>) -> Arc<Node> {
+        let factory = self.factory;
+        let (dependencies, _) = self.amd_dependencies();
+        let dependencies = dependencies
+            .iter()
+            .map(|dependency| {
+                let text = dependency.text().replace('\\', "\\\\").replace('"', "\\\"");
+                format!("\"{text}\"")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let loader = format!(
+            r#"(function (factory) {{
+    if (typeof module === "object" && typeof module.exports === "object") {{
+        var v = factory(require, exports);
+        if (v !== undefined) module.exports = v;
+    }}
+    else if (typeof define === "function" && define.amd) {{
+        define([{dependencies}], factory);
+    }}
+}});"#
+        );
+        let loader = parse_statements(&factory, "umd.js", &loader)
+            .pop()
+            .expect("the loader is one statement");
+        let parameters = ["require", "exports"]
+            .into_iter()
+            .map(|name| factory.create_parameter(factory.create_identifier(name)))
+            .collect();
+        let module_factory =
+            factory.create_function_expression(None, parameters, factory.create_block(body, true));
+        factory.create_expression_statement(factory.create_call(
+            Arc::clone(&loader.as_expression_statement().expression),
+            vec![module_factory],
+        ))
+    }
+}
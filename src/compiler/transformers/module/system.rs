@@ -0,0 +1,450 @@
+//! System modules
+//!
+//! A System module registers a factory with `System.register`. The factory declares the
+//! module's top-level variables and functions, and returns a setter for each dependency,
+//! which the loader calls with the dependency's exports, and an `execute` function with the
+//! rest of the module body:
+//!
+//! ```js
+//! System.register(["./m"], function (exports_1, context_1) {
+//!     "use strict";
+//!     var m_1, x;
+//!     var __moduleName = context_1 && context_1.id;
+//!     return {
+//!         setters: [
+//!             function (m_1_1) {
+//!                 m_1 = m_1_1;
+//!             }
+//!         ],
+//!         execute: function () {
+//!             exports_1("x", x = m_1.a);
+//!         }
+//!     };
+//! });
+//! ```
+//!
+//! Exports are set by calling `exports_1`, so that the loader can update the modules that
+//! import them.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::compiler::ast::nodes::ClassExpression;
+use crate::compiler::ast::visitor::NodeVisitor;
+use crate::compiler::ast::{Node, SyntaxKind};
+
+use super::super::helpers::parse_statements;
+use super::super::{
+    TransformContext, collect_binding_names, is_prologue_directive, to_assignment_target,
+    update_statements,
+};
+use super::{
+    ModuleFormat, ModuleTransformer, collect_module_info, create_use_strict,
+    export_specifier_names, has_use_strict, is_external_import_equals, module_specifier,
+};
+
+pub(super) fn transform_system_module(
+    source_file: &Arc<Node>,
+    context: &mut TransformContext,
+) -> Arc<Node> {
+    let info = collect_module_info(source_file, context);
+    let exports_name = context.create_unique_name("exports");
+    let context_name = context.create_unique_name("context");
+    let mut transformer = ModuleTransformer::new(context, ModuleFormat::System, info, exports_name);
+    let factory = transformer.factory;
+    transformer.context_name = context_name;
+    transformer.assign_import_aliases();
+    // Exported variables are declared in the factory and exported wherever they change
+    for name in transformer.info.exported_variables.clone() {
+        transformer.info.add_exported_binding(&name, &name);
+    }
+
+    let statements = &source_file.as_source_file().statements.nodes;
+    let prologue_length = statements
+        .iter()
+        .take_while(|statement| is_prologue_directive(statement))
+        .count();
+    let mut hoisted_names = Vec::new();
+    let mut functions = Vec::new();
+    let mut execute = Vec::new();
+    for statement in &statements[prologue_length..] {
+        match statement.kind {
+            SyntaxKind::ImportDeclaration => {
+                hoisted_names.extend(transformer.import_alias(statement));
+            }
+            SyntaxKind::ImportEqualsDeclaration if is_external_import_equals(statement) => {
+                hoisted_names.extend(transformer.import_alias(statement));
+            }
+            SyntaxKind::FunctionDeclaration => {
+                let visited = transformer.visit_node(statement);
+                functions.push(transformer.export_declaration(&visited));
+            }
+            _ => execute.extend(transformer.visit_system_statement(statement, &mut hoisted_names)),
+        }
+    }
+
+    if let Some(alias) = transformer.helpers_import_alias() {
+        hoisted_names.insert(0, alias.to_string());
+    }
+
+    let mut body = statements[..prologue_length].to_vec();
+    if !has_use_strict(&body) {
+        body.insert(0, create_use_strict(&factory));
+    }
+    let mut seen = HashSet::new();
+    hoisted_names.retain(|name| seen.insert(name.clone()));
+    if !hoisted_names.is_empty() {
+        body.push(
+            factory.create_variable_statement(
+                hoisted_names
+                    .iter()
+                    .map(|name| {
+                        factory.create_variable_declaration(
+                            factory.create_identifier(name.as_str()),
+                            None,
+                        )
+                    })
+                    .collect(),
+            ),
+        );
+    }
+    let context_parameter = || factory.create_identifier(transformer.context_name.as_str());
+    body.push(
+        factory.create_variable_statement(vec![factory.create_variable_declaration(
+            factory.create_identifier("__moduleName"),
+            Some(factory.create_binary(
+                context_parameter(),
+                SyntaxKind::AmpersandAmpersandToken,
+                factory.create_property_access(context_parameter(), "id"),
+            )),
+        )]),
+    );
+    let export_star_name = transformer
+        .info
+        .has_export_star
+        .then(|| transformer.context.create_unique_name("exportStar"));
+    if let Some(export_star_name) = &export_star_name {
+        body.extend(transformer.create_export_star_function(export_star_name));
+    }
+    body.extend(functions);
+    for (local, exported) in &transformer.info.exported_functions {
+        body.push(
+            transformer
+                .create_export_statement(exported, factory.create_identifier(local.as_str())),
+        );
+    }
+
+    let (dependencies, setters) = transformer.create_setters(export_star_name.as_deref());
+    let module = factory.create_object_literal(
+        vec![
+            factory.create_property_assignment(
+                factory.create_identifier("setters"),
+                factory.create_array_literal(setters, true),
+            ),
+            factory.create_property_assignment(
+                factory.create_identifier("execute"),
+                factory.create_function_expression(
+                    None,
+                    Vec::new(),
+                    factory.create_block(execute, true),
+                ),
+            ),
+        ],
+        true,
+    );
+    body.push(factory.create_return(Some(module)));
+
+    let parameters = [
+        transformer.exports_name.as_str(),
+        transformer.context_name.as_str(),
+    ]
+    .into_iter()
+    .map(|name| factory.create_parameter(factory.create_identifier(name)))
+    .collect();
+    let mut arguments: Vec<Arc<Node>> = transformer
+        .context
+        .module_name
+        .iter()
+        .map(|name| factory.create_string_literal(name.as_str()))
+        .collect();
+    arguments.push(factory.create_array_literal(dependencies, false));
+    arguments.push(factory.create_function_expression(
+        None,
+        parameters,
+        factory.create_block(body, true),
+    ));
+    let register = factory.create_call(
+        factory.create_property_access(factory.create_identifier("System"), "register"),
+        arguments,
+    );
+    update_statements(
+        &factory,
+        source_file,
+        vec![factory.create_expression_statement(register)],
+    )
+}
+
+impl ModuleTransformer<'_> {
+    /// Rewrites a top-level statement for the `execute` function of a System module,
+    /// collecting the variables it declares, which are declared in the factory instead
+    fn visit_system_statement(
+        &mut self,
+        node: &Arc<Node>,
+        hoisted_names: &mut Vec<String>,
+    ) -> Vec<Arc<Node>> {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        match node.kind {
+            K::ExportDeclaration => Vec::new(),
+            K::ExportAssignment if !node.as_export_assignment().is_export_equals => {
+                let expression = self.visit_node(&node.as_export_assignment().expression);
+                let statement = self.create_export_statement("default", expression);
+                vec![factory.with_text_range(&statement, node.loc)]
+            }
+            K::ExportAssignment => Vec::new(),
+            K::VariableStatement => self.visit_hoisted_variable_statement(node, hoisted_names),
+            K::ClassDeclaration => {
+                let visited = self.visit_node(node);
+                let data = visited.as_class_declaration();
+                let name = match &data.name {
+                    Some(name) => name.text().to_string(),
+                    None => self
+                        .info
+                        .default_name
+                        .clone()
+                        .expect("anonymous classes are named when collecting exports"),
+                };
+                hoisted_names.push(name.clone());
+                let class = factory.create(
+                    K::ClassExpression,
+                    ClassExpression {
+                        modifiers: None,
+                        name: data.name.clone(),
+                        type_parameters: None,
+                        heritage_clauses: data.heritage_clauses.clone(),
+                        members: data.members.clone(),
+                    },
+                );
+                let assignment = factory.create_expression_statement(
+                    factory.create_assignment(factory.create_identifier(name.as_str()), class),
+                );
+                let mut statements = vec![factory.with_text_range(&assignment, node.loc)];
+                statements.extend(self.create_local_exports(&[name]));
+                statements
+            }
+            K::EnumDeclaration | K::ModuleDeclaration => {
+                let visited = self.visit_node(node);
+                let visited = self.export_declaration(&visited);
+                let names = Vec::from_iter(visited.name().map(|name| name.text().to_string()));
+                let mut statements = vec![visited];
+                statements.extend(self.create_local_exports(&names));
+                statements
+            }
+            _ => vec![self.visit_node(node)],
+        }
+    }
+
+    /// Turns `let a = 1, { b } = c;` into `a = 1, ({ b } = c);`, exporting the names that are
+    /// exported
+    fn visit_hoisted_variable_statement(
+        &mut self,
+        node: &Arc<Node>,
+        hoisted_names: &mut Vec<String>,
+    ) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let visited = self.visit_node(node);
+        let list = &visited.as_variable_statement().declaration_list;
+        let mut destructured_names = Vec::new();
+        let assignments = list
+            .as_variable_declaration_list()
+            .declarations
+            .iter()
+            .filter_map(|declaration| {
+                let data = declaration.as_variable_declaration();
+                let mut names = Vec::new();
+                collect_binding_names(&data.name, &mut names);
+                hoisted_names.extend(names.iter().cloned());
+                let initializer = Arc::clone(data.initializer.as_ref()?);
+                if data.name.kind == SyntaxKind::Identifier {
+                    let assignment = factory.create_assignment(
+                        factory.create_identifier(data.name.text()),
+                        initializer,
+                    );
+                    return Some(self.export_assigned_value(&data.name, assignment));
+                }
+                destructured_names.extend(names);
+                let assignment = factory
+                    .create_assignment(to_assignment_target(&factory, &data.name), initializer);
+                Some(factory.create_parenthesized(assignment))
+            })
+            .reduce(|left, right| factory.create_binary(left, SyntaxKind::CommaToken, right));
+        let mut statements = Vec::from_iter(assignments.map(|assignments| {
+            factory.with_text_range(&factory.create_expression_statement(assignments), node.loc)
+        }));
+        statements.extend(self.create_local_exports(&destructured_names));
+        statements
+    }
+
+    /// Declares the function that `export * from "m"` calls with the exports of `"m"`, which
+    /// exports each of them that the module does not export itself
+    fn create_export_star_function(&mut self, name: &str) -> Vec<Arc<Node>> {
+        // The default export is never taken from `"m"`, but is listed like the other exports
+        let local_names = self
+            .info
+            .has_export_default
+            .then_some("default")
+            .into_iter()
+            .chain(self.info.exported_names.iter().map(String::as_str))
+            .chain(
+                self.info
+                    .exported_functions
+                    .iter()
+                    .map(|(_, exported)| exported.as_str()),
+            )
+            .map(|name| format!("        \"{name}\": true"))
+            .collect::<Vec<_>>();
+        let exports = &self.exports_name;
+        let text = if local_names.is_empty() {
+            format!(
+                r#"function {name}(m) {{
+    var exports = {{}};
+    for (var n in m) {{
+        if (n !== "default") exports[n] = m[n];
+    }}
+    {exports}(exports);
+}}"#
+            )
+        } else {
+            let names_name = self.context.create_unique_name("exportedNames");
+            format!(
+                r#"var {names_name} = {{
+{}
+}};
+function {name}(m) {{
+    var exports = {{}};
+    for (var n in m) {{
+        if (n !== "default" && !{names_name}.hasOwnProperty(n)) exports[n] = m[n];
+    }}
+    {exports}(exports);
+}}"#,
+                local_names.join(",\n")
+            )
+        };
+        parse_statements(&self.factory, "exportStar.js", &text)
+    }
+
+    /// Creates the setters of a System module and the names of the modules they receive
+    ///
+    /// The imports of the same module share a setter, whose parameter is named after the
+    /// variable of the first of them: `m_1_1` for `m_1`.
+    fn create_setters(
+        &mut self,
+        export_star_name: Option<&str>,
+    ) -> (Vec<Arc<Node>>, Vec<Arc<Node>>) {
+        use SyntaxKind as K;
+        let factory = self.factory;
+        let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+        for (index, import) in self.info.external_imports.iter().enumerate() {
+            let text = module_specifier(import).text().to_string();
+            match groups.iter_mut().find(|(specifier, _)| *specifier == text) {
+                Some((_, indexes)) => indexes.push(index),
+                None => groups.push((text, vec![index])),
+            }
+        }
+
+        let mut dependencies = Vec::new();
+        let mut setters = Vec::new();
+        if let Some(alias) = self.helpers_import_alias().map(str::to_string) {
+            dependencies.push(factory.create_string_literal("tslib"));
+            let parameter = self.context.create_unique_name(&alias);
+            let assignment = factory.create_assignment(
+                factory.create_identifier(alias.as_str()),
+                factory.create_identifier(parameter.as_str()),
+            );
+            setters.push(factory.create_function_expression(
+                None,
+                vec![factory.create_parameter(factory.create_identifier(parameter.as_str()))],
+                factory.create_block(vec![factory.create_expression_statement(assignment)], true),
+            ));
+        }
+        for (_, indexes) in groups {
+            let first = &self.info.external_imports[indexes[0]];
+            dependencies.push(self.dependency_specifier(module_specifier(first)));
+            let local_name = indexes
+                .iter()
+                .find_map(|&index| self.import_aliases[index].clone())
+                .unwrap_or_default();
+            let parameter = self.context.create_unique_name(&local_name);
+            let module = || factory.create_identifier(parameter.as_str());
+
+            let mut statements = Vec::new();
+            for index in indexes {
+                let import = Arc::clone(&self.info.external_imports[index]);
+                if import.kind != K::ExportDeclaration {
+                    if let Some(alias) = &self.import_aliases[index] {
+                        statements.push(factory.create_expression_statement(
+                            factory.create_assignment(
+                                factory.create_identifier(alias.as_str()),
+                                module(),
+                            ),
+                        ));
+                    }
+                    for local in self.exported_import_names(&import) {
+                        let value = self
+                            .substitutions
+                            .get(&local)
+                            .cloned()
+                            .unwrap_or_else(|| factory.create_identifier(local.as_str()));
+                        for exported in &self.info.exported_bindings[&local] {
+                            statements
+                                .push(self.create_export_statement(exported, Arc::clone(&value)));
+                        }
+                    }
+                    continue;
+                }
+                match &import.as_export_declaration().export_clause {
+                    None => {
+                        let export_star = export_star_name.expect("the module declares exportStar");
+                        statements.push(factory.create_expression_statement(
+                            factory.create_call(
+                                factory.create_identifier(export_star),
+                                vec![module()],
+                            ),
+                        ));
+                    }
+                    Some(clause) if clause.kind == K::NamespaceExport => {
+                        let name = clause.as_namespace_export().name.text();
+                        statements.push(self.create_export_statement(name, module()));
+                    }
+                    Some(clause) => {
+                        let properties = clause
+                            .as_named_exports()
+                            .elements
+                            .iter()
+                            .map(|specifier| {
+                                let (imported, exported) = export_specifier_names(specifier);
+                                factory.create_property_assignment(
+                                    factory.create_string_literal(exported),
+                                    factory.create_element_access(
+                                        module(),
+                                        factory.create_string_literal(imported),
+                                    ),
+                                )
+                            })
+                            .collect();
+                        statements.push(factory.create_expression_statement(factory.create_call(
+                            factory.create_identifier(self.exports_name.as_str()),
+                            vec![factory.create_object_literal(properties, true)],
+                        )));
+                    }
+                }
+            }
+            setters.push(factory.create_function_expression(
+                None,
+                vec![factory.create_parameter(module())],
+                factory.create_block(statements, true),
+            ));
+        }
+        (dependencies, setters)
+    }
+}
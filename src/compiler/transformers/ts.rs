@@ -0,0 +1,1402 @@
+//! Erasing TypeScript syntax
+//!
+//! Type annotations, type parameters and arguments, assertions, and TypeScript-only modifiers
+//! are removed, as are declarations that only exist at the type level: interfaces, type
+//! aliases, `declare` statements, overload signatures, and namespaces that contain only types.
+//! Imports that are type-only, or whose bindings are never used as values, are removed, and
+//! so are exports of local types. With `verbatimModuleSyntax`, only what is marked as
+//! type-only is removed. Parameter properties become assignments in the constructor.
+//!
+//! Enums become objects that map the names of their members to their values, and numeric
+//! values back to names. Accesses to const enum members are replaced by the members' values,
+//! and const enums are removed unless `preserveConstEnums` keeps them or they are exported.
+//! Namespaces that declare values become objects too, which hold what the namespace exports,
+//! and `import a = N.b` aliases become variables.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::compiler::ast::factory::NodeFactory;
+use crate::compiler::ast::nodes::{
+    BinaryExpression, ExportDeclaration, ExpressionStatement, NamedExports,
+    PartiallyEmittedExpression, PrefixUnaryExpression, PropertyAccessExpression,
+    VariableDeclarationList, VariableStatement,
+};
+use crate::compiler::ast::utilities::{
+    is_external_module, number_to_string, parse_numeric_literal,
+};
+use crate::compiler::ast::visitor::{
+    NodeVisitor, ensure_sufficient_stack, visit_each_child, visit_nodes,
+};
+use crate::compiler::ast::{Node, NodeFlags, NodeId, NodeList, SyntaxKind};
+use crate::compiler::checker::{LiteralValue, Number};
+
+use super::{
+    TransformOptions, collect_binding_names, collect_function_scoped_names, is_prologue_directive,
+    non_reference_name, scope_names, strip_export_modifiers, to_assignment_target,
+    update_statements,
+};
+
+/// Removes TypeScript syntax from a source file, leaving JavaScript
+///
+/// Nodes keep the source ranges of the nodes they replace, so comments and source positions
+/// can still be found for them.
+pub fn transform_type_script(source_file: &Arc<Node>, options: &TransformOptions) -> Arc<Node> {
+    let mut transformer = TypeScriptTransformer {
+        factory: NodeFactory::new(),
+        referenced_names: HashSet::new(),
+        type_only_names: HashSet::new(),
+        verbatim_module_syntax: options.verbatim_module_syntax,
+        preserve_unused_imports: options.preserve_unused_imports,
+        preserve_const_enums: options.preserve_const_enums,
+        constant_values: options.constant_values.clone(),
+        namespace: None,
+        exported_variables: HashMap::new(),
+    };
+    collect_value_references(
+        source_file,
+        &transformer.constant_values,
+        &mut transformer.referenced_names,
+    );
+    transformer.type_only_names =
+        collect_type_only_names(source_file, options.preserve_const_enums);
+
+    let factory = transformer.factory;
+    let result = visit_each_child(source_file, &mut transformer, &factory);
+    if !is_external_module(source_file) || is_external_module(&result) {
+        return result;
+    }
+    // A file that only imported or exported types must still be a module
+    let export = factory.create(
+        SyntaxKind::ExportDeclaration,
+        ExportDeclaration {
+            modifiers: None,
+            is_type_only: false,
+            export_clause: Some(factory.create(
+                SyntaxKind::NamedExports,
+                NamedExports {
+                    elements: factory.create_node_list(Vec::new()),
+                },
+            )),
+            module_specifier: None,
+            attributes: None,
+        },
+    );
+    let mut statements = result.as_source_file().statements.nodes.clone();
+    statements.push(export);
+    update_statements(&factory, &result, statements)
+}
+
+struct TypeScriptTransformer {
+    factory: NodeFactory,
+    /// The names used in value positions anywhere in the file; imports of other names only
+    /// bring in types
+    referenced_names: HashSet<String>,
+    /// The names of the file's top-level declarations that only declare types
+    type_only_names: HashSet<String>,
+    /// Whether imports not marked as type-only are kept, whether or not they are used
+    verbatim_module_syntax: bool,
+    /// Whether an import left without bindings is kept for its side effects
+    preserve_unused_imports: bool,
+    /// Whether const enums are emitted, rather than only inlined
+    preserve_const_enums: bool,
+    /// The values of enum members and of inlined const enum accesses, by node
+    constant_values: HashMap<NodeId, LiteralValue>,
+    /// The parameter that the function of the namespace being visited receives the
+    /// namespace's object as
+    namespace: Option<String>,
+    /// The variables that namespaces export, by the node whose statements declare the
+    /// namespace and the name of the namespace
+    exported_variables: HashMap<(Option<NodeId>, String), HashSet<String>>,
+}
+
+impl NodeVisitor for TypeScriptTransformer {
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        if node.kind.is_type_node() {
+            return Arc::clone(node);
+        }
+        match node.kind {
+            K::AsExpression
+            | K::SatisfiesExpression
+            | K::NonNullExpression
+            | K::TypeAssertionExpression => {
+                self.visit_node(node.expression().expect("assertions have expressions"))
+            }
+            K::ParenthesizedExpression => self.visit_parenthesized_expression(node),
+            K::Constructor => self.visit_constructor(node),
+            K::PropertyAccessExpression | K::ElementAccessExpression
+                if constant_value(&self.constant_values, node).is_some() =>
+            {
+                self.inline_constant_value(node)
+            }
+            _ => {
+                let factory = self.factory;
+                let node = visit_each_child(node, self, &factory);
+                self.strip_types(&node)
+            }
+        }
+    }
+
+    fn visit_list_element(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        use SyntaxKind as K;
+        let erased = match node.kind {
+            kind if is_type_script_modifier(kind) => true,
+            K::InterfaceDeclaration
+            | K::TypeAliasDeclaration
+            | K::NamespaceExportDeclaration
+            | K::IndexSignature => true,
+            _ if node.has_modifier(K::DeclareKeyword) => true,
+            _ if node.has_modifier(K::AbstractKeyword) => node.kind != K::ClassDeclaration,
+            K::ModuleDeclaration => {
+                if is_instantiated_module(node, self.preserve_const_enums) {
+                    return self.visit_module_declaration(node);
+                }
+                true
+            }
+            K::FunctionDeclaration | K::MethodDeclaration | K::Constructor => node.body().is_none(),
+            K::Parameter => is_this_parameter(node),
+            K::ImportDeclaration => {
+                return self.visit_import_declaration(node).into_iter().collect();
+            }
+            K::ImportEqualsDeclaration => {
+                let data = node.as_import_equals_declaration();
+                let erased = data.is_type_only
+                    || (!node.has_modifier(K::ExportKeyword) && !self.is_import_kept(&data.name));
+                if !erased && data.module_reference.kind != K::ExternalModuleReference {
+                    return vec![self.visit_import_alias(node)];
+                }
+                erased
+            }
+            // `export default T` and `export = T` of a local type
+            K::ExportAssignment => {
+                let expression = &node.as_export_assignment().expression;
+                expression.kind == K::Identifier && self.type_only_names.contains(expression.text())
+            }
+            K::ExportDeclaration => {
+                return self.visit_export_declaration(node).into_iter().collect();
+            }
+            K::EnumDeclaration => {
+                if is_erased_const_enum(node, self.preserve_const_enums) {
+                    true
+                } else {
+                    return self.visit_enum_declaration(node);
+                }
+            }
+            _ => false,
+        };
+        if erased {
+            Vec::new()
+        } else {
+            vec![self.visit_node(node)]
+        }
+    }
+}
+
+impl TypeScriptTransformer {
+    fn is_referenced(&self, name: &Node) -> bool {
+        self.referenced_names.contains(name.text())
+    }
+
+    /// Whether an imported binding is emitted: when it is used as a value, or whenever it is
+    /// not marked as type-only under `verbatimModuleSyntax`
+    fn is_import_kept(&self, name: &Node) -> bool {
+        self.verbatim_module_syntax || self.is_referenced(name)
+    }
+
+    /// Removes the parentheses around an assertion, which the printer adds back where the
+    /// expression needs them, except around an optional chain, which they would cut short
+    fn visit_parenthesized_expression(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let expression = &node.as_parenthesized_expression().expression;
+        let visited = self.visit_node(expression);
+        if is_assertion(expression) && !visited.flags.contains(NodeFlags::OPTIONAL_CHAIN) {
+            return visited;
+        }
+        self.factory.update_parenthesized(node, visited)
+    }
+
+    /// Drops the type annotations, type parameters, type arguments, and `?` and `!` markers of
+    /// a node whose children have already been visited
+    fn strip_types(&self, node: &Arc<Node>) -> Arc<Node> {
+        use SyntaxKind as K;
+        let factory = &self.factory;
+        match node.kind {
+            K::VariableDeclaration => {
+                let data = node.as_variable_declaration();
+                factory.update_variable_declaration(
+                    node,
+                    Arc::clone(&data.name),
+                    None,
+                    None,
+                    data.initializer.clone(),
+                )
+            }
+            K::Parameter => {
+                let data = node.as_parameter();
+                factory.update_parameter(
+                    node,
+                    non_empty(&data.modifiers),
+                    data.dot_dot_dot_token.clone(),
+                    Arc::clone(&data.name),
+                    None,
+                    None,
+                    data.initializer.clone(),
+                )
+            }
+            K::PropertyDeclaration => {
+                let data = node.as_property_declaration();
+                factory.update_property_declaration(
+                    node,
+                    non_empty(&data.modifiers),
+                    Arc::clone(&data.name),
+                    None,
+                    None,
+                    data.initializer.clone(),
+                )
+            }
+            K::MethodDeclaration => {
+                let data = node.as_method_declaration();
+                factory.update_method_declaration(
+                    node,
+                    non_empty(&data.modifiers),
+                    data.asterisk_token.clone(),
+                    Arc::clone(&data.name),
+                    None,
+                    None,
+                    data.parameters.clone(),
+                    None,
+                    data.body.clone(),
+                )
+            }
+            K::GetAccessor => {
+                let data = node.as_get_accessor_declaration();
+                factory.update_get_accessor_declaration(
+                    node,
+                    non_empty(&data.modifiers),
+                    Arc::clone(&data.name),
+                    None,
+                    data.parameters.clone(),
+                    None,
+                    data.body.clone(),
+                )
+            }
+            K::SetAccessor => {
+                let data = node.as_set_accessor_declaration();
+                factory.update_set_accessor_declaration(
+                    node,
+                    non_empty(&data.modifiers),
+                    Arc::clone(&data.name),
+                    None,
+                    data.parameters.clone(),
+                    None,
+                    data.body.clone(),
+                )
+            }
+            K::FunctionDeclaration => {
+                let data = node.as_function_declaration();
+                factory.update_function_declaration(
+                    node,
+                    non_empty(&data.modifiers),
+                    data.asterisk_token.clone(),
+                    data.name.clone(),
+                    None,
+                    data.parameters.clone(),
+                    None,
+                    data.body.clone(),
+                )
+            }
+            K::FunctionExpression => {
+                let data = node.as_function_expression();
+                factory.update_function_expression(
+                    node,
+                    non_empty(&data.modifiers),
+                    data.asterisk_token.clone(),
+                    data.name.clone(),
+                    None,
+                    data.parameters.clone(),
+                    None,
+                    Arc::clone(&data.body),
+                )
+            }
+            K::ArrowFunction => {
+                let data = node.as_arrow_function();
+                factory.update_arrow_function(
+                    node,
+                    non_empty(&data.modifiers),
+                    None,
+                    data.parameters.clone(),
+                    None,
+                    Arc::clone(&data.equals_greater_than_token),
+                    Arc::clone(&data.body),
+                )
+            }
+            K::ClassDeclaration => {
+                let data = node.as_class_declaration();
+                factory.update_class_declaration(
+                    node,
+                    non_empty(&data.modifiers),
+                    data.name.clone(),
+                    None,
+                    self.strip_implements(data.heritage_clauses.as_ref()),
+                    data.members.clone(),
+                )
+            }
+            K::ClassExpression => {
+                let data = node.as_class_expression();
+                factory.update_class_expression(
+                    node,
+                    non_empty(&data.modifiers),
+                    data.name.clone(),
+                    None,
+                    self.strip_implements(data.heritage_clauses.as_ref()),
+                    data.members.clone(),
+                )
+            }
+            K::CallExpression => {
+                let data = node.as_call_expression();
+                factory.update_call(
+                    node,
+                    Arc::clone(&data.expression),
+                    data.question_dot_token.clone(),
+                    None,
+                    data.arguments.clone(),
+                )
+            }
+            K::NewExpression => {
+                let data = node.as_new_expression();
+                factory.update_new(
+                    node,
+                    Arc::clone(&data.expression),
+                    None,
+                    data.arguments.clone(),
+                )
+            }
+            K::TaggedTemplateExpression => {
+                let data = node.as_tagged_template_expression();
+                factory.update_tagged_template(
+                    node,
+                    Arc::clone(&data.tag),
+                    data.question_dot_token.clone(),
+                    None,
+                    Arc::clone(&data.template),
+                )
+            }
+            K::ExpressionWithTypeArguments => {
+                let data = node.as_expression_with_type_arguments();
+                factory.update_expression_with_type_arguments(
+                    node,
+                    Arc::clone(&data.expression),
+                    None,
+                )
+            }
+            K::JsxOpeningElement => {
+                let data = node.as_jsx_opening_element();
+                factory.update_jsx_opening_element(
+                    node,
+                    Arc::clone(&data.tag_name),
+                    None,
+                    Arc::clone(&data.attributes),
+                )
+            }
+            K::JsxSelfClosingElement => {
+                let data = node.as_jsx_self_closing_element();
+                factory.update_jsx_self_closing_element(
+                    node,
+                    Arc::clone(&data.tag_name),
+                    None,
+                    Arc::clone(&data.attributes),
+                )
+            }
+            _ => Arc::clone(node),
+        }
+    }
+
+    fn strip_implements(&self, heritage_clauses: Option<&NodeList>) -> Option<NodeList> {
+        let heritage_clauses = heritage_clauses?;
+        let clauses: Vec<Arc<Node>> = heritage_clauses
+            .iter()
+            .filter(|clause| clause.as_heritage_clause().token != SyntaxKind::ImplementsKeyword)
+            .cloned()
+            .collect();
+        (!clauses.is_empty()).then(|| self.factory.update_node_list(heritage_clauses, clauses))
+    }
+
+    /// Strips a constructor's parameter properties to plain parameters, assigning each to the
+    /// property of the same name at the start of the body, after any `super` call
+    fn visit_constructor(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_constructor_declaration();
+        let properties: Vec<Arc<Node>> = data
+            .parameters
+            .iter()
+            .filter(|parameter| is_parameter_property(parameter))
+            .map(|parameter| Arc::clone(&parameter.as_parameter().name))
+            .collect();
+        let parameters = visit_nodes(&data.parameters, self, &factory);
+        let body = data.body.as_ref().map(|body| {
+            let body = self.visit_node(body);
+            if properties.is_empty() {
+                return body;
+            }
+            let block = body.as_block();
+            let mut statements = block.statements.nodes.clone();
+            let insert_at = statements.iter().position(is_super_call).map_or_else(
+                || {
+                    statements
+                        .iter()
+                        .take_while(|statement| is_prologue_directive(statement))
+                        .count()
+                },
+                |index| index + 1,
+            );
+            let assignments = properties
+                .iter()
+                .map(|name| self.create_property_assignment(name));
+            statements.splice(insert_at..insert_at, assignments);
+            self.factory.update_block(
+                &body,
+                self.factory.update_node_list(&block.statements, statements),
+                true,
+            )
+        });
+        let modifiers = data
+            .modifiers
+            .as_ref()
+            .map(|modifiers| visit_nodes(modifiers, self, &factory))
+            .filter(|modifiers| !modifiers.is_empty());
+        factory.update_constructor_declaration(node, modifiers, None, parameters, None, body)
+    }
+
+    /// Creates `this.name = name;`
+    fn create_property_assignment(&self, name: &Arc<Node>) -> Arc<Node> {
+        let factory = &self.factory;
+        let property = factory.create(
+            SyntaxKind::PropertyAccessExpression,
+            PropertyAccessExpression {
+                expression: factory.create_token(SyntaxKind::ThisKeyword),
+                question_dot_token: None,
+                name: factory.create_identifier(name.text()),
+            },
+        );
+        factory.create(
+            SyntaxKind::ExpressionStatement,
+            ExpressionStatement {
+                expression: factory.create(
+                    SyntaxKind::BinaryExpression,
+                    BinaryExpression {
+                        left: property,
+                        operator_token: factory.create_token(SyntaxKind::EqualsToken),
+                        right: factory.create_identifier(name.text()),
+                    },
+                ),
+            },
+        )
+    }
+
+    /// Replaces an access to a const enum member with the member's value, followed by a
+    /// comment naming the member, as in `0 /* E.A */`
+    fn inline_constant_value(&self, node: &Arc<Node>) -> Arc<Node> {
+        let value = constant_value(&self.constant_values, node).expect("the access is inlined");
+        let name = match node.kind {
+            SyntaxKind::PropertyAccessExpression => entity_name_text(node),
+            _ => {
+                let argument = &node.as_element_access_expression().argument_expression;
+                match argument.kind {
+                    SyntaxKind::StringLiteral => format!("\"{}\"", argument.text()),
+                    _ => argument.text().to_string(),
+                }
+            }
+        };
+        self.factory.create_at(
+            SyntaxKind::PartiallyEmittedExpression,
+            node.loc,
+            PartiallyEmittedExpression {
+                expression: create_constant(&self.factory, value),
+                trailing_comment: Some(format!(" {} ", name.replace("*/", "*_/"))),
+            },
+        )
+    }
+
+    /// Turns an enum into a variable holding an object that a function fills in:
+    ///
+    /// ```js
+    /// var E;
+    /// (function (E) {
+    ///     E[E["A"] = 0] = "A";
+    ///     E["B"] = "b";
+    /// })(E || (E = {}));
+    /// ```
+    ///
+    /// Numeric members are also mapped back from their values to their names. Only the first
+    /// declaration of an enum in a scope declares the variable.
+    fn visit_enum_declaration(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let data = node.as_enum_declaration();
+        let name = data.name.text();
+        let is_member_name = data
+            .members
+            .iter()
+            .any(|member| member.as_enum_member().name.text() == name);
+        let parameter = if is_member_name {
+            format!("{name}_1")
+        } else {
+            name.to_string()
+        };
+
+        let mut auto_value = Some(0.0);
+        let mut members = Vec::new();
+        for member in &data.members {
+            let initializer = &member.as_enum_member().initializer;
+            let value = constant_value(&self.constant_values, member)
+                .cloned()
+                .or_else(|| match initializer {
+                    Some(initializer) => literal_value(initializer),
+                    None => auto_value.map(|value| LiteralValue::Number(Number(value))),
+                });
+            auto_value = match &value {
+                Some(LiteralValue::Number(Number(value))) => Some(value + 1.0),
+                _ => None,
+            };
+            let value = match (&value, initializer) {
+                (Some(value), _) => create_constant(&factory, value),
+                (None, Some(initializer)) => self.visit_node(initializer),
+                (None, None) => factory.create_void_zero(),
+            };
+            let member_name = member.as_enum_member().name.text();
+            let is_string = value.kind == SyntaxKind::StringLiteral;
+            let mut assignment = factory.create_assignment(
+                factory.create_element_access(
+                    factory.create_identifier(&parameter),
+                    factory.create_string_literal(member_name),
+                ),
+                value,
+            );
+            // Numbers are also mapped back to the names of their members
+            if !is_string {
+                assignment = factory.create_assignment(
+                    factory
+                        .create_element_access(factory.create_identifier(&parameter), assignment),
+                    factory.create_string_literal(member_name),
+                );
+            }
+            members.push(factory.create_at(
+                SyntaxKind::ExpressionStatement,
+                member.loc,
+                ExpressionStatement {
+                    expression: assignment,
+                },
+            ));
+        }
+
+        self.create_declaration_function(node, &parameter, members)
+    }
+
+    /// Turns a namespace into a variable holding an object that a function fills in with the
+    /// namespace's exports, as for an enum:
+    ///
+    /// ```js
+    /// var N;
+    /// (function (N) {
+    ///     N.x = 1;
+    ///     function f() { }
+    ///     N.f = f;
+    /// })(N || (N = {}));
+    /// ```
+    ///
+    /// Exported variables become properties of the object, which references to them use
+    /// instead, while exported functions and classes are declared as before and then assigned
+    /// to properties. Each name of `A.B` gets a function, nested in the one of the name before.
+    fn visit_module_declaration(&mut self, node: &Arc<Node>) -> Vec<Arc<Node>> {
+        let data = node.as_module_declaration();
+        let name = data.name.text();
+        let body = data
+            .body
+            .as_ref()
+            .expect("instantiated namespaces have bodies");
+        let mut local_names = Vec::new();
+        match body.kind {
+            SyntaxKind::ModuleBlock => {
+                collect_function_scoped_names(&body.as_module_block().statements, &mut local_names)
+            }
+            _ => local_names.extend(body.name().map(|name| name.text().to_string())),
+        }
+        let parameter = if local_names.iter().any(|local| local == name) {
+            format!("{name}_1")
+        } else {
+            name.to_string()
+        };
+
+        let outer = self.namespace.replace(parameter.clone());
+        let statements = match body.kind {
+            SyntaxKind::ModuleBlock => self.visit_namespace_body(node, body, &parameter),
+            _ => self.visit_module_declaration(body),
+        };
+        self.namespace = outer;
+        self.create_declaration_function(node, &parameter, statements)
+    }
+
+    /// Visits the statements of a namespace, assigning what they export to the properties of
+    /// the namespace's object, which is passed in as `parameter`
+    fn visit_namespace_body(
+        &mut self,
+        node: &Arc<Node>,
+        body: &Arc<Node>,
+        parameter: &str,
+    ) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let statements = &body.as_module_block().statements;
+        // The declarations merged into a namespace also see its exported variables. Those of
+        // `A.B` are keyed by the full name, as each declaration has its own `B`.
+        let mut name = node.as_module_declaration().name.text().to_string();
+        let mut outermost = Arc::clone(node);
+        while let Some(parent) = outermost.parent()
+            && parent.kind == SyntaxKind::ModuleDeclaration
+        {
+            name = format!("{}.{name}", parent.as_module_declaration().name.text());
+            outermost = parent;
+        }
+        let key = (outermost.parent().map(|parent| parent.node_id()), name);
+        let exported = self.exported_variables.entry(key).or_default();
+        exported.extend(exported_variable_names(statements));
+        let exported = exported.clone();
+
+        let mut result = Vec::new();
+        for statement in visit_nodes(statements, self, &factory).iter() {
+            if !statement.has_modifier(SyntaxKind::ExportKeyword) {
+                result.push(Arc::clone(statement));
+                continue;
+            }
+            if statement.kind == SyntaxKind::VariableStatement {
+                // `export const x = 1, y = 2;` becomes `x = 1, y = 2;`, whose names are
+                // replaced by the properties below
+                let list = &statement.as_variable_statement().declaration_list;
+                let assignments = list
+                    .as_variable_declaration_list()
+                    .declarations
+                    .iter()
+                    .filter_map(|declaration| {
+                        let data = declaration.as_variable_declaration();
+                        let initializer = Arc::clone(data.initializer.as_ref()?);
+                        let target = to_assignment_target(&factory, &data.name);
+                        Some(factory.create_assignment(target, initializer))
+                    })
+                    .reduce(|left, right| {
+                        factory.create_binary(left, SyntaxKind::CommaToken, right)
+                    });
+                if let Some(expression) = assignments {
+                    result.push(factory.create_at(
+                        SyntaxKind::ExpressionStatement,
+                        statement.loc,
+                        ExpressionStatement { expression },
+                    ));
+                }
+                continue;
+            }
+            result.push(strip_export_modifiers(&factory, statement));
+            if let Some(name) = statement.name() {
+                let property = factory
+                    .create_property_access(factory.create_identifier(parameter), name.text());
+                result.push(factory.create_expression_statement(
+                    factory.create_assignment(property, factory.create_identifier(name.text())),
+                ));
+            }
+        }
+        if exported.is_empty() {
+            return result;
+        }
+        let mut exports = NamespaceExports {
+            factory,
+            namespace: parameter,
+            names: &exported,
+            scopes: Vec::new(),
+            property_name: None,
+        };
+        result
+            .iter()
+            .map(|statement| exports.visit_node(statement))
+            .collect()
+    }
+
+    /// Declares the variable of an enum or namespace, unless a declaration merged with it
+    /// already has, and calls a function that fills in its object with `statements`
+    fn create_declaration_function(
+        &self,
+        node: &Arc<Node>,
+        parameter: &str,
+        statements: Vec<Arc<Node>>,
+    ) -> Vec<Arc<Node>> {
+        let factory = self.factory;
+        let name = node.name().expect("enums and namespaces have names").text();
+        let parent = node.parent();
+        let mut result = Vec::new();
+        if self.is_first_emitted_declaration(node) {
+            // Only the top level of a file uses `var`, as tsc does
+            let is_top_level = parent
+                .as_ref()
+                .is_none_or(|parent| parent.kind == SyntaxKind::SourceFile);
+            let flags = if is_top_level {
+                NodeFlags::NONE
+            } else {
+                NodeFlags::LET
+            };
+            let modifiers =
+                (is_top_level && node.has_modifier(SyntaxKind::ExportKeyword)).then(|| {
+                    factory.create_node_list(vec![factory.create_token(SyntaxKind::ExportKeyword)])
+                });
+            let declaration_list = factory.create_with_flags(
+                SyntaxKind::VariableDeclarationList,
+                flags,
+                VariableDeclarationList {
+                    declarations: factory.create_node_list(vec![
+                        factory.create_variable_declaration(factory.create_identifier(name), None),
+                    ]),
+                },
+            );
+            result.push(factory.create_at(
+                SyntaxKind::VariableStatement,
+                node.loc,
+                VariableStatement {
+                    modifiers,
+                    declaration_list,
+                },
+            ));
+        }
+
+        let function = factory.create_function_expression(
+            None,
+            vec![factory.create_parameter(factory.create_identifier(parameter))],
+            factory.create_block(statements, true),
+        );
+        // `N || (N = {})` reuses the object of an earlier declaration. In a namespace that
+        // exports it, the object is also a property of the namespace's: `N = A.N || (A.N = {})`.
+        let is_exported_from_namespace = parent.as_ref().is_some_and(|parent| {
+            parent.kind == SyntaxKind::ModuleDeclaration
+                || (parent.kind == SyntaxKind::ModuleBlock
+                    && node.has_modifier(SyntaxKind::ExportKeyword))
+        });
+        let object = |factory: &NodeFactory| match &self.namespace {
+            Some(namespace) if is_exported_from_namespace => {
+                factory.create_property_access(factory.create_identifier(namespace.as_str()), name)
+            }
+            _ => factory.create_identifier(name),
+        };
+        let mut argument = factory.create_binary(
+            object(&factory),
+            SyntaxKind::BarBarToken,
+            factory.create_parenthesized(factory.create_assignment(
+                object(&factory),
+                factory.create_object_literal(Vec::new(), false),
+            )),
+        );
+        if is_exported_from_namespace {
+            argument = factory.create_assignment(factory.create_identifier(name), argument);
+        }
+        let call = factory.create_call(factory.create_parenthesized(function), vec![argument]);
+        let statement = factory.create_expression_statement(call);
+        result.push(if result.is_empty() {
+            factory.with_text_range(&statement, node.loc)
+        } else {
+            statement
+        });
+        result
+    }
+
+    /// Whether no declaration that is emitted comes before an enum or namespace in its scope
+    /// with the same name, which the enum or namespace would merge with
+    fn is_first_emitted_declaration(&self, node: &Arc<Node>) -> bool {
+        let name = node.name().map_or("", |name| name.text());
+        let Some(parent) = node.parent() else {
+            return true;
+        };
+        let Some(statements) = parent.statements() else {
+            return true;
+        };
+        statements
+            .iter()
+            .take_while(|statement| !Arc::ptr_eq(statement, node))
+            .all(|statement| {
+                statement.name().is_none_or(|other| other.text() != name)
+                    || !self.is_emitted_declaration(statement)
+            })
+    }
+
+    /// Whether a class, function, enum, or namespace declaration is emitted
+    fn is_emitted_declaration(&self, statement: &Node) -> bool {
+        use SyntaxKind as K;
+        match statement.kind {
+            _ if statement.has_modifier(K::DeclareKeyword) => false,
+            K::ClassDeclaration => true,
+            K::FunctionDeclaration => statement.body().is_some(),
+            K::EnumDeclaration => !is_erased_const_enum(statement, self.preserve_const_enums),
+            K::ModuleDeclaration => is_instantiated_module(statement, self.preserve_const_enums),
+            _ => false,
+        }
+    }
+
+    /// Turns `import a = N.b` into `var a = N.b`, which is exported as other variables are
+    fn visit_import_alias(&self, node: &Arc<Node>) -> Arc<Node> {
+        let factory = self.factory;
+        let data = node.as_import_equals_declaration();
+        let modifiers = node.has_modifier(SyntaxKind::ExportKeyword).then(|| {
+            factory.create_node_list(vec![factory.create_token(SyntaxKind::ExportKeyword)])
+        });
+        let declaration = factory.create_variable_declaration(
+            factory.create_identifier(data.name.text()),
+            Some(entity_name_to_expression(&factory, &data.module_reference)),
+        );
+        let declaration_list = factory.create(
+            SyntaxKind::VariableDeclarationList,
+            VariableDeclarationList {
+                declarations: factory.create_node_list(vec![declaration]),
+            },
+        );
+        factory.create_at(
+            SyntaxKind::VariableStatement,
+            node.loc,
+            VariableStatement {
+                modifiers,
+                declaration_list,
+            },
+        )
+    }
+
+    /// Removes a type-only import, and the bindings of an import that are never used as
+    /// values, removing the import entirely if none are left, unless it is to be kept for its
+    /// side effects. Imports for side effects only are kept.
+    fn visit_import_declaration(&mut self, node: &Arc<Node>) -> Option<Arc<Node>> {
+        let data = node.as_import_declaration();
+        let Some(import_clause) = &data.import_clause else {
+            return Some(Arc::clone(node));
+        };
+        let clause = import_clause.as_import_clause();
+        if clause.is_type_only {
+            return None;
+        }
+        let name = clause
+            .name
+            .as_ref()
+            .filter(|name| self.is_import_kept(name))
+            .cloned();
+        let named_bindings = clause
+            .named_bindings
+            .as_ref()
+            .and_then(|bindings| self.visit_named_import_bindings(bindings));
+        if name.is_none() && named_bindings.is_none() {
+            return self.preserve_unused_imports.then(|| {
+                self.factory.update_import_declaration(
+                    node,
+                    data.modifiers.clone(),
+                    None,
+                    Arc::clone(&data.module_specifier),
+                    data.attributes.clone(),
+                )
+            });
+        }
+        let import_clause =
+            self.factory
+                .update_import_clause(import_clause, false, name, named_bindings);
+        Some(self.factory.update_import_declaration(
+            node,
+            data.modifiers.clone(),
+            Some(import_clause),
+            Arc::clone(&data.module_specifier),
+            data.attributes.clone(),
+        ))
+    }
+
+    fn visit_named_import_bindings(&self, bindings: &Arc<Node>) -> Option<Arc<Node>> {
+        if bindings.kind == SyntaxKind::NamespaceImport {
+            return self
+                .is_import_kept(&bindings.as_namespace_import().name)
+                .then(|| Arc::clone(bindings));
+        }
+        let elements = &bindings.as_named_imports().elements;
+        let kept: Vec<Arc<Node>> = elements
+            .iter()
+            .filter(|element| {
+                let specifier = element.as_import_specifier();
+                !specifier.is_type_only && self.is_import_kept(&specifier.name)
+            })
+            .cloned()
+            .collect();
+        // Under `verbatimModuleSyntax`, `import { type A } from "a"` becomes
+        // `import {} from "a"`
+        if kept.is_empty() && !self.verbatim_module_syntax {
+            return None;
+        }
+        Some(
+            self.factory
+                .update_named_imports(bindings, self.factory.update_node_list(elements, kept)),
+        )
+    }
+
+    /// Removes a type-only export, and the exports of type-only specifiers and local types,
+    /// removing the export entirely if none are left
+    fn visit_export_declaration(&mut self, node: &Arc<Node>) -> Option<Arc<Node>> {
+        let data = node.as_export_declaration();
+        if data.is_type_only {
+            return None;
+        }
+        let Some(export_clause) = data
+            .export_clause
+            .as_ref()
+            .filter(|clause| clause.kind == SyntaxKind::NamedExports)
+        else {
+            return Some(Arc::clone(node));
+        };
+        let elements = &export_clause.as_named_exports().elements;
+        let kept: Vec<Arc<Node>> = elements
+            .iter()
+            .filter(|element| {
+                let specifier = element.as_export_specifier();
+                let local_name = specifier.property_name.as_ref().unwrap_or(&specifier.name);
+                !specifier.is_type_only
+                    && (data.module_specifier.is_some()
+                        || !self.type_only_names.contains(local_name.text()))
+            })
+            .cloned()
+            .collect();
+        if kept.is_empty() && !elements.is_empty() {
+            return None;
+        }
+        let export_clause = self
+            .factory
+            .update_named_exports(export_clause, self.factory.update_node_list(elements, kept));
+        Some(self.factory.update_export_declaration(
+            node,
+            data.modifiers.clone(),
+            false,
+            Some(export_clause),
+            data.module_specifier.clone(),
+            data.attributes.clone(),
+        ))
+    }
+}
+
+fn non_empty(list: &Option<NodeList>) -> Option<NodeList> {
+    list.as_ref().filter(|list| !list.is_empty()).cloned()
+}
+
+/// Whether a modifier only has meaning to the type system
+fn is_type_script_modifier(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::PublicKeyword
+            | SyntaxKind::PrivateKeyword
+            | SyntaxKind::ProtectedKeyword
+            | SyntaxKind::ReadonlyKeyword
+            | SyntaxKind::AbstractKeyword
+            | SyntaxKind::OverrideKeyword
+            | SyntaxKind::InKeyword
+            | SyntaxKind::OutKeyword
+    )
+}
+
+fn is_assertion(node: &Node) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::AsExpression
+            | SyntaxKind::SatisfiesExpression
+            | SyntaxKind::NonNullExpression
+            | SyntaxKind::TypeAssertionExpression
+    )
+}
+
+fn is_this_parameter(node: &Node) -> bool {
+    let name = &node.as_parameter().name;
+    name.kind == SyntaxKind::Identifier && name.text() == "this"
+}
+
+fn is_parameter_property(node: &Node) -> bool {
+    node.modifiers().is_some_and(|modifiers| {
+        modifiers.iter().any(|modifier| {
+            matches!(
+                modifier.kind,
+                SyntaxKind::PublicKeyword
+                    | SyntaxKind::PrivateKeyword
+                    | SyntaxKind::ProtectedKeyword
+                    | SyntaxKind::ReadonlyKeyword
+                    | SyntaxKind::OverrideKeyword
+            )
+        })
+    })
+}
+
+fn is_super_call(statement: &Arc<Node>) -> bool {
+    statement.kind == SyntaxKind::ExpressionStatement && {
+        let expression = &statement.as_expression_statement().expression;
+        expression.kind == SyntaxKind::CallExpression
+            && expression.as_call_expression().expression.kind == SyntaxKind::SuperKeyword
+    }
+}
+
+/// Whether a namespace declares any values, and so is emitted as an object at runtime
+fn is_instantiated_module(node: &Node, preserve_const_enums: bool) -> bool {
+    let Some(body) = &node.as_module_declaration().body else {
+        return false;
+    };
+    if body.kind == SyntaxKind::ModuleDeclaration {
+        return is_instantiated_module(body, preserve_const_enums);
+    }
+    body.as_module_block()
+        .statements
+        .iter()
+        .any(|statement| match statement.kind {
+            SyntaxKind::InterfaceDeclaration | SyntaxKind::TypeAliasDeclaration => false,
+            _ if statement.has_modifier(SyntaxKind::DeclareKeyword) => false,
+            SyntaxKind::ModuleDeclaration => {
+                is_instantiated_module(statement, preserve_const_enums)
+            }
+            SyntaxKind::EnumDeclaration => !is_erased_const_enum(statement, preserve_const_enums),
+            SyntaxKind::ImportEqualsDeclaration => {
+                !statement.as_import_equals_declaration().is_type_only
+            }
+            _ => true,
+        })
+}
+
+/// Whether a const enum is removed, being inlined wherever it is used, including in the modules
+/// that import it
+fn is_erased_const_enum(node: &Node, preserve_const_enums: bool) -> bool {
+    node.has_modifier(SyntaxKind::ConstKeyword) && !preserve_const_enums
+}
+
+/// Gets the names of the variables and aliases among the statements of a namespace that it
+/// exports, which are read and written as properties of its object
+fn exported_variable_names(statements: &NodeList) -> Vec<String> {
+    let mut names = Vec::new();
+    for statement in statements.iter() {
+        if !statement.has_modifier(SyntaxKind::ExportKeyword) {
+            continue;
+        }
+        match statement.kind {
+            SyntaxKind::VariableStatement => {
+                let list = &statement.as_variable_statement().declaration_list;
+                for declaration in list.as_variable_declaration_list().declarations.iter() {
+                    collect_binding_names(&declaration.as_variable_declaration().name, &mut names);
+                }
+            }
+            SyntaxKind::ImportEqualsDeclaration => {
+                names.push(
+                    statement
+                        .as_import_equals_declaration()
+                        .name
+                        .text()
+                        .to_string(),
+                );
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Turns an entity name such as `A.B.c` into the property accesses it reads
+fn entity_name_to_expression(factory: &NodeFactory, name: &Node) -> Arc<Node> {
+    match name.kind {
+        SyntaxKind::QualifiedName => {
+            let data = name.as_qualified_name();
+            let left = entity_name_to_expression(factory, &data.left);
+            factory.with_text_range(
+                &factory.create_property_access(left, data.right.text()),
+                name.loc,
+            )
+        }
+        _ => factory.with_text_range(&factory.create_identifier(name.text()), name.loc),
+    }
+}
+
+/// Replaces the references to a namespace's exported variables within the namespace with the
+/// properties of its object, as `N.x`
+struct NamespaceExports<'a> {
+    factory: NodeFactory,
+    /// The parameter the namespace's object is passed in as
+    namespace: &'a str,
+    names: &'a HashSet<String>,
+    /// The names declared in the functions and blocks around the node being visited, which
+    /// hide the namespace's
+    scopes: Vec<HashSet<String>>,
+    /// The name of the node being visited, if it is not a reference to a variable
+    property_name: Option<Arc<Node>>,
+}
+
+impl NodeVisitor for NamespaceExports<'_> {
+    fn visit_node(&mut self, node: &Arc<Node>) -> Arc<Node> {
+        let outer = match non_reference_name(node) {
+            Some(name) => self.property_name.replace(Arc::clone(name)),
+            None => self.property_name.clone(),
+        };
+        let factory = self.factory;
+        let visited = match node.kind {
+            SyntaxKind::Identifier => match self.substitution(node) {
+                Some(substitution) => factory.with_text_range(&substitution, node.loc),
+                None => Arc::clone(node),
+            },
+            SyntaxKind::ShorthandPropertyAssignment => {
+                let data = node.as_shorthand_property_assignment();
+                match self.substitution(&data.name) {
+                    // `{ x = 1 } = o` in a destructuring assignment becomes `{ x: N.x = 1 }`
+                    Some(substitution) => {
+                        let target = match &data.object_assignment_initializer {
+                            Some(initializer) => factory
+                                .create_assignment(substitution, self.visit_node(initializer)),
+                            None => substitution,
+                        };
+                        let name = factory.create_identifier(data.name.text());
+                        factory.with_text_range(
+                            &factory.create_property_assignment(name, target),
+                            node.loc,
+                        )
+                    }
+                    None => visit_each_child(node, self, &factory),
+                }
+            }
+            _ => match scope_names(node) {
+                Some(names) => {
+                    self.scopes.push(names.into_iter().collect());
+                    let visited = visit_each_child(node, self, &factory);
+                    self.scopes.pop();
+                    visited
+                }
+                None => visit_each_child(node, self, &factory),
+            },
+        };
+        self.property_name = outer;
+        visited
+    }
+}
+
+impl NamespaceExports<'_> {
+    /// Gets the property an identifier refers to instead, if it refers to an exported variable
+    /// of the namespace that no nearer declaration hides
+    fn substitution(&self, node: &Arc<Node>) -> Option<Arc<Node>> {
+        let is_reference = node.kind == SyntaxKind::Identifier
+            && !self
+                .property_name
+                .as_ref()
+                .is_some_and(|name| Arc::ptr_eq(name, node))
+            && self.names.contains(node.text())
+            && !self.scopes.iter().any(|scope| scope.contains(node.text()));
+        is_reference.then(|| {
+            self.factory
+                .create_property_access(self.factory.create_identifier(self.namespace), node.text())
+        })
+    }
+}
+
+/// Gets the value a node was computed to have, if the checker computed one
+fn constant_value<'a>(
+    constant_values: &'a HashMap<NodeId, LiteralValue>,
+    node: &Node,
+) -> Option<&'a LiteralValue> {
+    // A node without an id was never looked at by the checker
+    if node.id() == 0 {
+        return None;
+    }
+    constant_values.get(&node.node_id())
+}
+
+/// Gets the value of an enum member's initializer from its syntax alone, for when the checker
+/// has not computed it
+fn literal_value(initializer: &Node) -> Option<LiteralValue> {
+    match initializer.kind {
+        SyntaxKind::StringLiteral | SyntaxKind::NoSubstitutionTemplateLiteral => {
+            Some(LiteralValue::String(initializer.text().to_string()))
+        }
+        SyntaxKind::NumericLiteral => Some(LiteralValue::Number(Number(parse_numeric_literal(
+            initializer.text(),
+        )))),
+        SyntaxKind::PrefixUnaryExpression => {
+            let data = initializer.as_prefix_unary_expression();
+            match (data.operator, literal_value(&data.operand)) {
+                (SyntaxKind::MinusToken, Some(LiteralValue::Number(Number(value)))) => {
+                    Some(LiteralValue::Number(Number(-value)))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Creates the literal for a constant value, with negative numbers written as negations
+fn create_constant(factory: &NodeFactory, value: &LiteralValue) -> Arc<Node> {
+    match value {
+        LiteralValue::String(text) => factory.create_string_literal(text),
+        LiteralValue::Number(Number(value)) if *value < 0.0 => factory.create(
+            SyntaxKind::PrefixUnaryExpression,
+            PrefixUnaryExpression {
+                operator: SyntaxKind::MinusToken,
+                operand: factory.create_numeric_literal(number_to_string(-value)),
+            },
+        ),
+        LiteralValue::Number(Number(value)) => {
+            factory.create_numeric_literal(number_to_string(*value))
+        }
+        LiteralValue::BigInt(digits) => factory.create_big_int_literal(format!("{digits}n")),
+    }
+}
+
+/// Gets the dotted text of a property access of identifiers, such as `ns.E.A`, or only the
+/// name of the property when it is accessed on some other expression
+fn entity_name_text(node: &Node) -> String {
+    let data = node.as_property_access_expression();
+    let name = data.name.text();
+    match data.expression.kind {
+        SyntaxKind::Identifier => format!("{}.{name}", data.expression.text()),
+        SyntaxKind::PropertyAccessExpression => {
+            format!("{}.{name}", entity_name_text(&data.expression))
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Collects the identifiers that appear outside of types and type-level declarations, which
+/// are the names an import may be needed for. Names in export specifiers count, since they
+/// may re-export an imported value, but property names, member names, and labels do not, and
+/// neither do the const enum accesses that are replaced by values.
+fn collect_value_references(
+    node: &Arc<Node>,
+    constant_values: &HashMap<NodeId, LiteralValue>,
+    names: &mut HashSet<String>,
+) {
+    use SyntaxKind as K;
+    match node.kind {
+        kind if kind.is_type_node() => return,
+        K::PropertyAccessExpression | K::ElementAccessExpression
+            if constant_value(constant_values, node).is_some() =>
+        {
+            return;
+        }
+        K::InterfaceDeclaration
+        | K::TypeAliasDeclaration
+        | K::TypeParameter
+        | K::IndexSignature
+        | K::ImportDeclaration => return,
+        K::HeritageClause if node.as_heritage_clause().token == K::ImplementsKeyword => return,
+        K::ImportEqualsDeclaration => {
+            let module_reference = &node.as_import_equals_declaration().module_reference;
+            collect_value_references(module_reference, constant_values, names);
+            return;
+        }
+        K::PropertyAccessExpression => {
+            let expression = &node.as_property_access_expression().expression;
+            collect_value_references(expression, constant_values, names);
+            return;
+        }
+        // Re-exports from other modules refer to nothing in the file, and type-only exports
+        // only to types
+        K::ExportDeclaration => {
+            let data = node.as_export_declaration();
+            if data.is_type_only || data.module_specifier.is_some() {
+                return;
+            }
+        }
+        K::ExportSpecifier => {
+            let specifier = node.as_export_specifier();
+            if specifier.is_type_only {
+                return;
+            }
+            let local_name = specifier.property_name.as_ref().unwrap_or(&specifier.name);
+            names.insert(local_name.text().to_string());
+            return;
+        }
+        K::BreakStatement | K::ContinueStatement | K::MetaProperty => return,
+        K::Identifier => {
+            names.insert(node.text().to_string());
+        }
+        // The classic JSX transform refers to `React`
+        K::JsxElement | K::JsxSelfClosingElement | K::JsxFragment => {
+            names.insert("React".to_string());
+        }
+        _ if node.has_modifier(K::DeclareKeyword) => return,
+        _ => {}
+    }
+    // Names that are not computed only name a member, a property, or a label
+    let name = match node.kind {
+        K::PropertyAssignment
+        | K::PropertyDeclaration
+        | K::MethodDeclaration
+        | K::GetAccessor
+        | K::SetAccessor
+        | K::EnumMember
+        | K::JsxAttribute => node.name(),
+        K::BindingElement => node.as_binding_element().property_name.as_ref(),
+        K::LabeledStatement => Some(&node.as_labeled_statement().label),
+        _ => None,
+    }
+    .filter(|name| name.kind == K::Identifier);
+    node.for_each_child(&mut |child| {
+        if !name.is_some_and(|name| Arc::ptr_eq(name, child)) {
+            ensure_sufficient_stack(|| collect_value_references(child, constant_values, names));
+        }
+        false
+    });
+}
+
+/// Collects the names of top-level declarations that only declare types, including type-only
+/// imports, leaving out names also declared as values
+fn collect_type_only_names(source_file: &Node, preserve_const_enums: bool) -> HashSet<String> {
+    use SyntaxKind as K;
+    let mut types = HashSet::new();
+    let mut values = HashSet::new();
+    for statement in source_file.as_source_file().statements.iter() {
+        match statement.kind {
+            K::InterfaceDeclaration | K::TypeAliasDeclaration => {
+                types.insert(statement.name().map_or("", |name| name.text()).to_string());
+            }
+            K::ModuleDeclaration => {
+                let name = statement.as_module_declaration().name.text().to_string();
+                if is_instantiated_module(statement, preserve_const_enums) {
+                    values.insert(name);
+                } else {
+                    types.insert(name);
+                }
+            }
+            K::ImportDeclaration => {
+                let Some(clause) = &statement.as_import_declaration().import_clause else {
+                    continue;
+                };
+                let clause = clause.as_import_clause();
+                let mut add = |name: &Node, is_type_only: bool| {
+                    let set = if clause.is_type_only || is_type_only {
+                        &mut types
+                    } else {
+                        &mut values
+                    };
+                    set.insert(name.text().to_string());
+                };
+                if let Some(name) = &clause.name {
+                    add(name, false);
+                }
+                match &clause.named_bindings {
+                    Some(bindings) if bindings.kind == K::NamespaceImport => {
+                        add(&bindings.as_namespace_import().name, false);
+                    }
+                    Some(bindings) => {
+                        for element in bindings.as_named_imports().elements.iter() {
+                            let specifier = element.as_import_specifier();
+                            add(&specifier.name, specifier.is_type_only);
+                        }
+                    }
+                    None => {}
+                }
+            }
+            K::VariableStatement => {
+                let list = &statement.as_variable_statement().declaration_list;
+                let mut names = Vec::new();
+                for declaration in list.as_variable_declaration_list().declarations.iter() {
+                    collect_binding_names(&declaration.as_variable_declaration().name, &mut names);
+                }
+                values.extend(names);
+            }
+            _ => {
+                if let Some(name) = statement.name() {
+                    values.insert(name.text().to_string());
+                }
+            }
+        }
+    }
+    types.retain(|name| !values.contains(name));
+    types
+}
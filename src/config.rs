@@ -0,0 +1,1749 @@
+//! Reading of configuration files (`tsrsonfig.json`, or `tsconfig.json` as tsc calls it)
+//!
+//! A configuration file lists the root files of a project, directly in `files` or by the
+//! `include` and `exclude` patterns, and sets compiler options in `compilerOptions`. Options
+//! given on the command line take precedence over those in the file.
+
+mod init;
+mod json;
+mod matcher;
+
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::cli::{Cli, ImportsNotUsedAsValues, JsxMode, Module, ModuleResolution, NewLine, Target};
+use crate::compile::{
+    CompilerHost, Diagnostic, SourceFile, compute_line_map, convert_diagnostic, option_diagnostic,
+    relative_path,
+};
+use crate::compiler::checker::get_spelling_suggestion;
+use crate::compiler::diagnostics::{self, Message};
+use crate::compiler::paths::{
+    combine_paths, get_ancestor_directories, get_base_file_name, get_directory_path,
+    is_rooted_disk_path, normalize_path, normalize_slashes,
+};
+use crate::compiler::text::TextRange;
+
+pub use init::{generate_config_file, write_config_file};
+pub use json::{JsonNode, JsonProperty, JsonValue, parse_json_text, write_json_string};
+pub use matcher::{match_files, read_directory};
+
+/// The names a configuration file can have, in order of preference
+pub const CONFIG_FILE_NAMES: [&str; 2] = ["tsrsonfig.json", "tsconfig.json"];
+
+/// Folders excluded from a project that does not set `exclude`, along with its `outDir`
+const DEFAULT_EXCLUDES: [&str; 3] = ["node_modules", "bower_components", "jspm_packages"];
+
+/// The root files of a project and the problems found reading its configuration
+#[derive(Default)]
+pub struct ParsedConfig {
+    pub file_names: Vec<String>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// The configuration file and the files it extends, which watch mode watches for changes
+    pub config_file_names: Vec<String>,
+    /// The compiler options the configuration files set, including those the compiler does
+    /// not act on yet, with paths resolved against the file that set them
+    pub options: Vec<(&'static str, JsonNode)>,
+    /// The `include` and `exclude` patterns, resolved against the file that lists them
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+}
+
+/// The type of the value of a compiler option
+#[derive(Clone, Copy)]
+enum OptionType {
+    Boolean,
+    String,
+    Number,
+    /// A list of strings
+    List,
+    /// A list of objects, like `plugins`
+    ObjectList,
+    Object,
+    /// One of a set of strings, compared without regard to case
+    Enum(fn() -> Vec<String>),
+}
+
+impl OptionType {
+    /// The name of the type in diagnostics
+    fn name(self) -> &'static str {
+        match self {
+            OptionType::Boolean => "boolean",
+            OptionType::String | OptionType::Enum(_) => "string",
+            OptionType::Number => "number",
+            OptionType::List | OptionType::ObjectList => "Array",
+            OptionType::Object => "object",
+        }
+    }
+}
+
+struct OptionDeclaration {
+    name: &'static str,
+    kind: OptionType,
+    /// Whether the option is a path, which is relative to the configuration file
+    is_file_path: bool,
+}
+
+const fn option(name: &'static str, kind: OptionType) -> OptionDeclaration {
+    OptionDeclaration {
+        name,
+        kind,
+        is_file_path: false,
+    }
+}
+
+const fn path_option(name: &'static str) -> OptionDeclaration {
+    OptionDeclaration {
+        name,
+        kind: OptionType::String,
+        is_file_path: true,
+    }
+}
+
+const fn path_list_option(name: &'static str) -> OptionDeclaration {
+    OptionDeclaration {
+        name,
+        kind: OptionType::List,
+        is_file_path: true,
+    }
+}
+
+/// The names and aliases of the values of an option that the command line also takes
+fn enum_values<T: ValueEnum>() -> Vec<String> {
+    T::value_variants()
+        .iter()
+        .filter_map(ValueEnum::to_possible_value)
+        .flat_map(|value| {
+            value
+                .get_name_and_aliases()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn module_detection_values() -> Vec<String> {
+    ["auto", "legacy", "force"].map(str::to_string).to_vec()
+}
+
+/// The compiler options a configuration file can set. Options that the compiler does not act
+/// on yet are still checked, so that they are not reported as unknown.
+static OPTION_DECLARATIONS: &[OptionDeclaration] = &[
+    option("allowArbitraryExtensions", OptionType::Boolean),
+    option("allowImportingTsExtensions", OptionType::Boolean),
+    option("allowJs", OptionType::Boolean),
+    option("allowSyntheticDefaultImports", OptionType::Boolean),
+    option("allowUmdGlobalAccess", OptionType::Boolean),
+    option("allowUnreachableCode", OptionType::Boolean),
+    option("allowUnusedLabels", OptionType::Boolean),
+    option("alwaysStrict", OptionType::Boolean),
+    path_option("baseUrl"),
+    option("charset", OptionType::String),
+    option("checkJs", OptionType::Boolean),
+    option("composite", OptionType::Boolean),
+    option("customConditions", OptionType::List),
+    option("declaration", OptionType::Boolean),
+    path_option("declarationDir"),
+    option("declarationMap", OptionType::Boolean),
+    option("diagnostics", OptionType::Boolean),
+    option("disableReferencedProjectLoad", OptionType::Boolean),
+    option("disableSizeLimit", OptionType::Boolean),
+    option("disableSolutionSearching", OptionType::Boolean),
+    option(
+        "disableSourceOfProjectReferenceRedirect",
+        OptionType::Boolean,
+    ),
+    option("downlevelIteration", OptionType::Boolean),
+    option("emitBOM", OptionType::Boolean),
+    option("emitDeclarationOnly", OptionType::Boolean),
+    option("emitDecoratorMetadata", OptionType::Boolean),
+    option("erasableSyntaxOnly", OptionType::Boolean),
+    option("esModuleInterop", OptionType::Boolean),
+    option("exactOptionalPropertyTypes", OptionType::Boolean),
+    option("experimentalDecorators", OptionType::Boolean),
+    option("explainFiles", OptionType::Boolean),
+    option("extendedDiagnostics", OptionType::Boolean),
+    option("forceConsistentCasingInFileNames", OptionType::Boolean),
+    path_option("generateCpuProfile"),
+    path_option("generateTrace"),
+    option("importHelpers", OptionType::Boolean),
+    option(
+        "importsNotUsedAsValues",
+        OptionType::Enum(enum_values::<ImportsNotUsedAsValues>),
+    ),
+    option("incremental", OptionType::Boolean),
+    option("inlineSourceMap", OptionType::Boolean),
+    option("inlineSources", OptionType::Boolean),
+    option("isolatedDeclarations", OptionType::Boolean),
+    option("isolatedModules", OptionType::Boolean),
+    option("jsx", OptionType::Enum(enum_values::<JsxMode>)),
+    option("jsxFactory", OptionType::String),
+    option("jsxFragmentFactory", OptionType::String),
+    option("jsxImportSource", OptionType::String),
+    option("lib", OptionType::List),
+    option("libReplacement", OptionType::Boolean),
+    option("listEmittedFiles", OptionType::Boolean),
+    option("listFiles", OptionType::Boolean),
+    option("mapRoot", OptionType::String),
+    option("maxNodeModuleJsDepth", OptionType::Number),
+    option("module", OptionType::Enum(enum_values::<Module>)),
+    option("moduleDetection", OptionType::Enum(module_detection_values)),
+    option(
+        "moduleResolution",
+        OptionType::Enum(enum_values::<ModuleResolution>),
+    ),
+    option("moduleSuffixes", OptionType::List),
+    option("newLine", OptionType::Enum(enum_values::<NewLine>)),
+    option("noCheck", OptionType::Boolean),
+    option("noEmit", OptionType::Boolean),
+    option("noEmitHelpers", OptionType::Boolean),
+    option("noEmitOnError", OptionType::Boolean),
+    option("noErrorTruncation", OptionType::Boolean),
+    option("noFallthroughCasesInSwitch", OptionType::Boolean),
+    option("noImplicitAny", OptionType::Boolean),
+    option("noImplicitOverride", OptionType::Boolean),
+    option("noImplicitReturns", OptionType::Boolean),
+    option("noImplicitThis", OptionType::Boolean),
+    option("noLib", OptionType::Boolean),
+    option("noPropertyAccessFromIndexSignature", OptionType::Boolean),
+    option("noResolve", OptionType::Boolean),
+    option("noUncheckedIndexedAccess", OptionType::Boolean),
+    option("noUncheckedSideEffectImports", OptionType::Boolean),
+    option("noUnusedLocals", OptionType::Boolean),
+    option("noUnusedParameters", OptionType::Boolean),
+    path_option("outDir"),
+    path_option("outFile"),
+    option("paths", OptionType::Object),
+    option("plugins", OptionType::ObjectList),
+    option("preserveConstEnums", OptionType::Boolean),
+    option("preserveSymlinks", OptionType::Boolean),
+    option("preserveWatchOutput", OptionType::Boolean),
+    option("pretty", OptionType::Boolean),
+    option("reactNamespace", OptionType::String),
+    option("removeComments", OptionType::Boolean),
+    option("resolveJsonModule", OptionType::Boolean),
+    option("resolvePackageJsonExports", OptionType::Boolean),
+    option("resolvePackageJsonImports", OptionType::Boolean),
+    option("rewriteRelativeImportExtensions", OptionType::Boolean),
+    path_option("rootDir"),
+    path_list_option("rootDirs"),
+    option("skipDefaultLibCheck", OptionType::Boolean),
+    option("skipLibCheck", OptionType::Boolean),
+    option("sourceMap", OptionType::Boolean),
+    option("sourceRoot", OptionType::String),
+    option("strict", OptionType::Boolean),
+    option("strictBindCallApply", OptionType::Boolean),
+    option("strictBuiltinIteratorReturn", OptionType::Boolean),
+    option("strictFunctionTypes", OptionType::Boolean),
+    option("strictNullChecks", OptionType::Boolean),
+    option("strictPropertyInitialization", OptionType::Boolean),
+    option("stripInternal", OptionType::Boolean),
+    option("target", OptionType::Enum(enum_values::<Target>)),
+    option("traceResolution", OptionType::Boolean),
+    path_option("tsBuildInfoFile"),
+    path_list_option("typeRoots"),
+    option("types", OptionType::List),
+    option("useDefineForClassFields", OptionType::Boolean),
+    option("useUnknownInCatchVariables", OptionType::Boolean),
+    option("verbatimModuleSyntax", OptionType::Boolean),
+];
+
+/// Finds the configuration file of the project in `directory`, or in the nearest folder above
+/// it that has one. A file in `directory` itself is named relative to it.
+pub fn find_config_file(directory: &str, host: &impl CompilerHost) -> Option<String> {
+    for (depth, ancestor) in get_ancestor_directories(directory).enumerate() {
+        for name in CONFIG_FILE_NAMES {
+            let path = combine_paths(&ancestor, name);
+            if host.file_exists(&path) {
+                return Some(if depth == 0 { name.to_string() } else { path });
+            }
+        }
+    }
+    None
+}
+
+/// Finds the configuration file that `--project` names, as a path to the file itself or to
+/// the folder that has it
+pub fn get_project_config_file(
+    project: &str,
+    host: &impl CompilerHost,
+) -> Result<String, Diagnostic> {
+    let project = normalize_path(project);
+    if host.directory_exists(&project) {
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| combine_paths(&project, name))
+            .find(|path| host.file_exists(path))
+            .ok_or_else(|| {
+                option_diagnostic(
+                    diagnostics::CANNOT_FIND_A_TSCONFIG_JSON_FILE_AT_THE_SPECIFIED_DIRECTORY_COLON_0_5057,
+                    std::slice::from_ref(&project),
+                )
+            })
+    } else if host.file_exists(&project) {
+        Ok(project)
+    } else {
+        Err(option_diagnostic(
+            diagnostics::THE_SPECIFIED_PATH_DOES_NOT_EXIST_COLON_0_5058,
+            &[project],
+        ))
+    }
+}
+
+/// Reads a configuration file and the files it extends, setting the compiler options of `cli`
+/// that the command line left unset, and finding the root files of the project
+pub fn parse_config_file(
+    config_file_name: &str,
+    cli: &mut Cli,
+    host: &impl CompilerHost,
+) -> ParsedConfig {
+    let mut parser = ConfigParser {
+        sources: Vec::new(),
+        diagnostics: Vec::new(),
+        global_diagnostics: Vec::new(),
+    };
+    let config_file_name = normalize_path(config_file_name);
+    let mut options = Vec::new();
+    let mut include = None;
+    let mut exclude = None;
+    let file_names = match parser.read_config_file(&config_file_name, &mut Vec::new(), host) {
+        Some(settings) => {
+            for (declaration, value) in &settings.options {
+                apply_compiler_option(declaration, value, cli);
+                options.push((declaration.name, value.clone()));
+            }
+            include = settings.include.as_ref().map(FileSpecs::resolved);
+            exclude = settings.exclude.as_ref().map(FileSpecs::resolved);
+            parser.get_file_names(&config_file_name, settings, cli, host)
+        }
+        None => {
+            parser.global_diagnostics.push(option_diagnostic(
+                diagnostics::CANNOT_READ_FILE_0_5083,
+                &[config_file_name],
+            ));
+            Vec::new()
+        }
+    };
+
+    let mut diagnostics: Vec<Diagnostic> = parser
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let source_file = parser
+                .sources
+                .iter()
+                .find(|source_file| diagnostic.file_name.as_ref() == Some(&source_file.file_name))
+                .expect("configuration diagnostics are in configuration files");
+            convert_diagnostic(source_file, diagnostic)
+        })
+        .collect();
+    diagnostics.extend(parser.global_diagnostics);
+    ParsedConfig {
+        file_names,
+        diagnostics,
+        config_file_names: parser
+            .sources
+            .into_iter()
+            .map(|source_file| source_file.file_name)
+            .collect(),
+        options,
+        include,
+        exclude,
+    }
+}
+
+/// The options that `strict` turns on, unless they are set themselves
+const STRICT_OPTIONS: [&str; 9] = [
+    "alwaysStrict",
+    "noImplicitAny",
+    "noImplicitThis",
+    "strictBindCallApply",
+    "strictBuiltinIteratorReturn",
+    "strictFunctionTypes",
+    "strictNullChecks",
+    "strictPropertyInitialization",
+    "useUnknownInCatchVariables",
+];
+
+/// The configuration a project resolves to, as `--showConfig` prints it: the compiler options
+/// of its configuration files and of `cli`, with those that `strict` implies, the files that
+/// `files` and `include` come to, and the `include` and `exclude` patterns. Paths are written
+/// relative to `config_file_name`, which does not have to exist when the project is given on
+/// the command line.
+pub fn convert_to_config(
+    cli: &Cli,
+    config_file_name: &str,
+    config: &ParsedConfig,
+    host: &impl CompilerHost,
+) -> String {
+    let current_directory = host.get_current_directory();
+    let config_path = combine_paths(&current_directory, config_file_name);
+    let config_directory = get_directory_path(&config_path);
+    let relative = |path: &str| {
+        let path = combine_paths(&current_directory, path);
+        relative_path(
+            Path::new(&config_directory),
+            Path::new(&path),
+            !host.use_case_sensitive_file_names(),
+        )
+    };
+    // A path in the project folder starts with `./`, so that it does not read as a module name
+    let relative_file = |path: &str| {
+        let path = relative(path);
+        if path.starts_with("..") {
+            path
+        } else {
+            format!("./{path}")
+        }
+    };
+    let strings = |paths: &[String], relative: &dyn Fn(&str) -> String| {
+        JsonValue::Array(
+            paths
+                .iter()
+                .map(|path| JsonValue::String(relative(path)).into())
+                .collect(),
+        )
+    };
+
+    // Options on the command line take precedence over those of the configuration files
+    let mut options: Vec<(&str, JsonValue)> = config
+        .options
+        .iter()
+        .map(|(name, value)| (*name, value.value.clone()))
+        .collect();
+    for (name, value) in command_line_options(cli) {
+        match options.iter_mut().find(|(option, _)| *option == name) {
+            Some(option) => option.1 = value,
+            None => options.push((name, value)),
+        }
+    }
+    if cli.strict == Some(true) {
+        for name in STRICT_OPTIONS {
+            if !options.iter().any(|(option, _)| *option == name) {
+                options.push((name, JsonValue::Boolean(true)));
+            }
+        }
+    }
+    for (name, value) in &mut options {
+        let is_file_path = OPTION_DECLARATIONS
+            .iter()
+            .any(|declaration| declaration.name == *name && declaration.is_file_path);
+        match value {
+            JsonValue::String(path) if is_file_path => *path = relative_file(path),
+            JsonValue::Array(paths) if is_file_path => {
+                for path in paths {
+                    if let JsonValue::String(path) = &mut path.value {
+                        *path = relative_file(path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let property = |name: &str, value: JsonValue| JsonProperty {
+        name: name.to_string(),
+        name_loc: TextRange::default(),
+        initializer: value.into(),
+    };
+    let compiler_options = options
+        .into_iter()
+        .map(|(name, value)| property(name, value))
+        .collect();
+    let mut properties = vec![property(
+        "compilerOptions",
+        JsonValue::Object(compiler_options),
+    )];
+    if !config.file_names.is_empty() {
+        properties.push(property(
+            "files",
+            strings(&config.file_names, &relative_file),
+        ));
+    }
+    // Including everything in the project folder is the default, which is left out
+    if let Some(include) = &config.include {
+        let include = strings(include, &relative);
+        if include.stringify("") != "[\"**/*\"]" {
+            properties.push(property("include", include));
+        }
+    }
+    if let Some(exclude) = &config.exclude {
+        properties.push(property("exclude", strings(exclude, &relative)));
+    }
+    JsonValue::Object(properties).stringify("    ")
+}
+
+/// The settings of a configuration file, merged with those of the files it extends
+#[derive(Default)]
+struct ConfigSettings {
+    /// The compiler options that are set, with paths resolved against the file that set them
+    options: Vec<(&'static OptionDeclaration, JsonNode)>,
+    files: Option<FileSpecs>,
+    include: Option<FileSpecs>,
+    exclude: Option<FileSpecs>,
+}
+
+/// The `files`, `include`, or `exclude` list of a configuration file
+#[derive(Clone)]
+struct FileSpecs {
+    /// Each path or pattern as it was written, and where
+    specs: Vec<(String, TextRange)>,
+    /// The configuration file that lists them
+    config_file_name: String,
+    /// The folder of that file, which they are relative to
+    base_path: String,
+}
+
+impl FileSpecs {
+    /// Gets each path or pattern relative to the working directory
+    fn resolved(&self) -> Vec<String> {
+        self.specs
+            .iter()
+            .map(|(spec, _)| combine_paths(&self.base_path, spec))
+            .collect()
+    }
+}
+
+impl ConfigSettings {
+    /// Takes the settings these lack from `base`, the settings of a file they extend
+    fn inherit_from(&mut self, base: ConfigSettings) {
+        let own = std::mem::replace(self, base);
+        for (declaration, value) in own.options {
+            self.options
+                .retain(|(existing, _)| existing.name != declaration.name);
+            self.options.push((declaration, value));
+        }
+        self.files = own.files.or(self.files.take());
+        self.include = own.include.or(self.include.take());
+        self.exclude = own.exclude.or(self.exclude.take());
+    }
+}
+
+struct ConfigParser {
+    /// The configuration files read so far
+    sources: Vec<SourceFile>,
+    /// Problems at positions in the configuration files
+    diagnostics: Vec<diagnostics::Diagnostic>,
+    /// Problems with the configuration as a whole
+    global_diagnostics: Vec<Diagnostic>,
+}
+
+impl ConfigParser {
+    fn error(&mut self, config_file_name: &str, loc: TextRange, message: &Message, args: &[&str]) {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        self.diagnostics.push(diagnostics::Diagnostic::new(
+            Some(config_file_name),
+            loc,
+            message,
+            &args,
+        ));
+    }
+
+    /// Checks that `node` has the type of the option `name`, reporting it if it does not
+    fn check_type(
+        &mut self,
+        config_file_name: &str,
+        name: &str,
+        kind: OptionType,
+        node: &JsonNode,
+    ) -> bool {
+        let matches = match (kind, &node.value) {
+            (OptionType::Boolean, JsonValue::Boolean(_))
+            | (OptionType::String, JsonValue::String(_))
+            | (OptionType::Number, JsonValue::Number(_))
+            | (OptionType::Object, JsonValue::Object(_)) => true,
+            (OptionType::List, JsonValue::Array(elements)) => {
+                for element in elements {
+                    self.check_type(config_file_name, name, OptionType::String, element);
+                }
+                true
+            }
+            (OptionType::ObjectList, JsonValue::Array(elements)) => {
+                for element in elements {
+                    self.check_type(config_file_name, name, OptionType::Object, element);
+                }
+                true
+            }
+            (OptionType::Enum(values), JsonValue::String(value)) => {
+                let values = values();
+                let known = values.iter().any(|known| known.eq_ignore_ascii_case(value));
+                if !known {
+                    let names: Vec<String> =
+                        values.iter().map(|value| format!("'{value}'")).collect();
+                    self.error(
+                        config_file_name,
+                        node.loc,
+                        diagnostics::ARGUMENT_FOR_0_OPTION_MUST_BE_COLON_1_6046,
+                        &[&format!("--{name}"), &names.join(", ")],
+                    );
+                }
+                return known;
+            }
+            _ => false,
+        };
+        if !matches {
+            self.error(
+                config_file_name,
+                node.loc,
+                diagnostics::COMPILER_OPTION_0_REQUIRES_A_VALUE_OF_TYPE_1_5024,
+                &[name, kind.name()],
+            );
+        }
+        matches
+    }
+
+    /// Reads a configuration file and the files it extends, or returns `None` if it cannot be
+    /// read. `stack` holds the files that extend this one, to detect circular extends.
+    fn read_config_file(
+        &mut self,
+        config_file_name: &str,
+        stack: &mut Vec<String>,
+        host: &impl CompilerHost,
+    ) -> Option<ConfigSettings> {
+        let text = host.read_file(config_file_name)?;
+        let (json, syntax_errors) = parse_json_text(config_file_name, &text);
+        self.diagnostics.extend(syntax_errors);
+        self.sources.push(SourceFile {
+            file_name: config_file_name.to_string(),
+            line_map: compute_line_map(&text),
+            text,
+        });
+
+        let Some(json) = json.as_ref().filter(|json| json.as_object().is_some()) else {
+            let file_name = get_base_file_name(config_file_name);
+            let loc = json.as_ref().map_or(TextRange::new(0, 0), |json| json.loc);
+            self.error(
+                config_file_name,
+                loc,
+                diagnostics::THE_ROOT_VALUE_OF_A_0_FILE_MUST_BE_AN_OBJECT_5092,
+                &[file_name],
+            );
+            return Some(ConfigSettings::default());
+        };
+        let base_path = get_directory_path(config_file_name);
+
+        if let Some(excludes) = json.get("excludes") {
+            self.error(
+                config_file_name,
+                excludes.name_loc,
+                diagnostics::UNKNOWN_OPTION_EXCLUDES_DID_YOU_MEAN_EXCLUDE_6114,
+                &[],
+            );
+        }
+        let mut settings = ConfigSettings::default();
+        if let Some(extends) = json.get("extends") {
+            stack.push(config_file_name.to_string());
+            settings = self.read_extended_config_files(
+                config_file_name,
+                &base_path,
+                &extends.initializer,
+                stack,
+                host,
+            );
+            stack.pop();
+        }
+
+        let mut own = ConfigSettings::default();
+        if let Some(compiler_options) = json.get("compilerOptions") {
+            own.options = self.parse_compiler_options(
+                config_file_name,
+                &base_path,
+                &compiler_options.initializer,
+            );
+        }
+        let mut file_specs = |name| {
+            self.parse_string_list(config_file_name, json, name)
+                .map(|specs| FileSpecs {
+                    specs,
+                    config_file_name: config_file_name.to_string(),
+                    base_path: base_path.clone(),
+                })
+        };
+        own.files = file_specs("files");
+        own.include = file_specs("include");
+        own.exclude = file_specs("exclude");
+        own.inherit_from(settings);
+        Some(own)
+    }
+
+    /// Reads the files named by `extends`, one or a list of them, merging their settings so
+    /// that later files override earlier ones
+    fn read_extended_config_files(
+        &mut self,
+        config_file_name: &str,
+        base_path: &str,
+        extends: &JsonNode,
+        stack: &mut Vec<String>,
+        host: &impl CompilerHost,
+    ) -> ConfigSettings {
+        let names: Vec<&JsonNode> = match &extends.value {
+            JsonValue::String(_) => vec![extends],
+            JsonValue::Array(elements) => elements.iter().collect(),
+            _ => {
+                self.error(
+                    config_file_name,
+                    extends.loc,
+                    diagnostics::COMPILER_OPTION_0_REQUIRES_A_VALUE_OF_TYPE_1_5024,
+                    &["extends", "string or Array"],
+                );
+                Vec::new()
+            }
+        };
+        let mut settings = ConfigSettings::default();
+        for name in names {
+            let Some(specifier) = name.as_str() else {
+                self.error(
+                    config_file_name,
+                    name.loc,
+                    diagnostics::COMPILER_OPTION_0_REQUIRES_A_VALUE_OF_TYPE_1_5024,
+                    &["extends", "string"],
+                );
+                continue;
+            };
+            let Some(path) = resolve_extended_config_file(specifier, base_path, host) else {
+                self.error(
+                    config_file_name,
+                    name.loc,
+                    diagnostics::FILE_0_NOT_FOUND_6053,
+                    &[specifier],
+                );
+                continue;
+            };
+            if stack.contains(&path) {
+                let chain: Vec<&str> = stack
+                    .iter()
+                    .map(String::as_str)
+                    .chain([path.as_str()])
+                    .collect();
+                self.global_diagnostics.push(option_diagnostic(
+                    diagnostics::CIRCULARITY_DETECTED_WHILE_RESOLVING_CONFIGURATION_COLON_0_18000,
+                    &[chain.join(" -> ")],
+                ));
+                continue;
+            }
+            match self.read_config_file(&path, stack, host) {
+                Some(mut base) => {
+                    base.inherit_from(settings);
+                    settings = base;
+                }
+                None => self.error(
+                    config_file_name,
+                    name.loc,
+                    diagnostics::CANNOT_READ_FILE_0_5083,
+                    &[&path],
+                ),
+            }
+        }
+        settings
+    }
+
+    /// Gets the strings in the list property `name` of the configuration, along with where each
+    /// was written, or `None` if the list is not set
+    fn parse_string_list(
+        &mut self,
+        config_file_name: &str,
+        json: &JsonNode,
+        name: &str,
+    ) -> Option<Vec<(String, TextRange)>> {
+        let property = json.get(name)?;
+        let node = &property.initializer;
+        if node.value == JsonValue::Null
+            || !self.check_type(config_file_name, name, OptionType::List, node)
+        {
+            return None;
+        }
+        Some(
+            node.as_array()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|element| Some((element.as_str()?.to_string(), element.loc)))
+                .collect(),
+        )
+    }
+
+    /// Gets the valid compiler options of a configuration file, reporting the others
+    fn parse_compiler_options(
+        &mut self,
+        config_file_name: &str,
+        base_path: &str,
+        node: &JsonNode,
+    ) -> Vec<(&'static OptionDeclaration, JsonNode)> {
+        let mut options = Vec::new();
+        if node.value == JsonValue::Null
+            || !self.check_type(
+                config_file_name,
+                "compilerOptions",
+                OptionType::Object,
+                node,
+            )
+        {
+            return options;
+        }
+        for property in node.as_object().unwrap_or_default() {
+            let Some(declaration) = OPTION_DECLARATIONS
+                .iter()
+                .find(|declaration| declaration.name == property.name)
+            else {
+                let suggestion =
+                    get_spelling_suggestion(&property.name, OPTION_DECLARATIONS, |declaration| {
+                        declaration.name
+                    });
+                match suggestion {
+                    Some(suggestion) => self.error(
+                        config_file_name,
+                        property.name_loc,
+                        diagnostics::UNKNOWN_COMPILER_OPTION_0_DID_YOU_MEAN_1_5025,
+                        &[&property.name, suggestion.name],
+                    ),
+                    None => self.error(
+                        config_file_name,
+                        property.name_loc,
+                        diagnostics::UNKNOWN_COMPILER_OPTION_0_5023,
+                        &[&property.name],
+                    ),
+                }
+                continue;
+            };
+            // `null` leaves an option unset
+            let mut value = property.initializer.clone();
+            if value.value == JsonValue::Null
+                || !self.check_type(config_file_name, declaration.name, declaration.kind, &value)
+            {
+                continue;
+            }
+            if declaration.is_file_path {
+                match &mut value.value {
+                    JsonValue::String(path) => *path = combine_paths(base_path, path),
+                    JsonValue::Array(elements) => {
+                        for element in elements {
+                            if let JsonValue::String(path) = &mut element.value {
+                                *path = combine_paths(base_path, path);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            options.retain(|(existing, _): &(&OptionDeclaration, _)| {
+                existing.name != declaration.name
+            });
+            options.push((declaration, value));
+        }
+        options
+    }
+
+    /// Finds the root files of the project: those listed in `files`, then those that
+    /// `include` matches and `exclude` does not
+    fn get_file_names(
+        &mut self,
+        config_file_name: &str,
+        settings: ConfigSettings,
+        cli: &Cli,
+        host: &impl CompilerHost,
+    ) -> Vec<String> {
+        let ConfigSettings {
+            files,
+            include,
+            exclude,
+            ..
+        } = settings;
+        if files.as_ref().is_some_and(|files| files.specs.is_empty()) && include.is_none() {
+            self.global_diagnostics.push(option_diagnostic(
+                diagnostics::THE_FILES_LIST_IN_CONFIG_FILE_0_IS_EMPTY_18002,
+                &[config_file_name.to_string()],
+            ));
+            return Vec::new();
+        }
+
+        let mut file_names = Vec::new();
+        if let Some(files) = &files {
+            for ((_, loc), path) in files.specs.iter().zip(files.resolved()) {
+                if host.file_exists(&path) {
+                    if !file_names.contains(&path) {
+                        file_names.push(path);
+                    }
+                } else {
+                    self.error(
+                        &files.config_file_name,
+                        *loc,
+                        diagnostics::FILE_0_NOT_FOUND_6053,
+                        &[&path],
+                    );
+                }
+            }
+        }
+
+        // Without `files`, everything in the project folder is included
+        let base_path = get_directory_path(config_file_name);
+        let include = match (include, &files) {
+            (Some(include), _) => include,
+            (None, files) => FileSpecs {
+                specs: match files {
+                    Some(_) => Vec::new(),
+                    None => vec![("**/*".to_string(), TextRange::new(0, 0))],
+                },
+                config_file_name: config_file_name.to_string(),
+                base_path: base_path.clone(),
+            },
+        };
+        let (exclude_specs, excludes) = match &exclude {
+            Some(exclude) => (
+                exclude.specs.iter().map(|(spec, _)| spec.clone()).collect(),
+                exclude.resolved(),
+            ),
+            None => {
+                let specs: Vec<String> = DEFAULT_EXCLUDES
+                    .iter()
+                    .map(|exclude| exclude.to_string())
+                    .collect();
+                let mut excludes: Vec<String> = specs
+                    .iter()
+                    .map(|spec| combine_paths(&base_path, spec))
+                    .collect();
+                // `outDir` is already relative to the working directory
+                excludes.extend(
+                    cli.out_dir
+                        .as_ref()
+                        .map(|out_dir| normalize_path(&out_dir.to_string_lossy())),
+                );
+                (specs, excludes)
+            }
+        };
+        let includes = include.resolved();
+        for path in match_files(host, ".", &includes, &excludes, cli.allow_js) {
+            if !file_names.contains(&path) {
+                file_names.push(path);
+            }
+        }
+
+        if file_names.is_empty() && !includes.is_empty() {
+            let quote = |patterns: &[String]| {
+                let quoted: Vec<String> = patterns
+                    .iter()
+                    .map(|pattern| format!("\"{pattern}\""))
+                    .collect();
+                format!("[{}]", quoted.join(","))
+            };
+            let include_specs: Vec<String> =
+                include.specs.iter().map(|(spec, _)| spec.clone()).collect();
+            self.global_diagnostics.push(option_diagnostic(
+                diagnostics::NO_INPUTS_WERE_FOUND_IN_CONFIG_FILE_0_SPECIFIED_INCLUDE_PATHS_WERE_1_AND_EXCLUDE_PATHS_WERE_2_18003,
+                &[config_file_name.to_string(), quote(&include_specs), quote(&exclude_specs)],
+            ));
+        }
+        file_names
+    }
+}
+
+/// Finds the configuration file that `extends` names from a configuration file in
+/// `base_path`: a path relative to it, or a file in a package in a `node_modules` folder of
+/// it or a folder above it. A package names its configuration file in the `tsconfig` field
+/// of its `package.json`, or has a `tsconfig.json`.
+fn resolve_extended_config_file(
+    specifier: &str,
+    base_path: &str,
+    host: &impl CompilerHost,
+) -> Option<String> {
+    // Like tsc, the `.json` extension can be left out
+    let with_json_extension = |path: String| {
+        if host.file_exists(&path) {
+            Some(path)
+        } else if !path.ends_with(".json") && host.file_exists(&format!("{path}.json")) {
+            Some(format!("{path}.json"))
+        } else {
+            None
+        }
+    };
+    let normalized = normalize_slashes(specifier);
+    if normalized.starts_with("./")
+        || normalized.starts_with("../")
+        || is_rooted_disk_path(specifier)
+    {
+        return with_json_extension(combine_paths(base_path, specifier));
+    }
+
+    // Search `node_modules` from the folder of the configuration file to the root
+    let directory = combine_paths(&host.get_current_directory(), base_path);
+    for ancestor in get_ancestor_directories(&directory) {
+        let package_path = combine_paths(&combine_paths(&ancestor, "node_modules"), specifier);
+        if host.directory_exists(&package_path) {
+            let package_json = host
+                .read_file(&combine_paths(&package_path, "package.json"))
+                .and_then(|text| parse_json_text("package.json", &text).0);
+            let tsconfig = package_json
+                .as_ref()
+                .and_then(|package_json| package_json.get("tsconfig"))
+                .and_then(|tsconfig| tsconfig.initializer.as_str())
+                .unwrap_or("tsconfig.json");
+            if let Some(path) = with_json_extension(combine_paths(&package_path, tsconfig)) {
+                return Some(path);
+            }
+        } else if let Some(path) = with_json_extension(package_path) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Sets the option of `cli` that a compiler option of the configuration file maps to,
+/// unless the command line set it
+fn apply_compiler_option(declaration: &OptionDeclaration, value: &JsonNode, cli: &mut Cli) {
+    let flag = value.as_bool().unwrap_or(false);
+    let text = value.as_str().unwrap_or_default();
+    let list = || -> Vec<String> {
+        value
+            .as_array()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|element| element.as_str().map(str::to_string))
+            .collect()
+    };
+    match declaration.name {
+        "allowArbitraryExtensions" => cli.allow_arbitrary_extensions |= flag,
+        "allowImportingTsExtensions" => cli.allow_importing_ts_extensions |= flag,
+        "allowJs" => cli.allow_js |= flag,
+        "allowUmdGlobalAccess" => cli.allow_umd_global_access |= flag,
+        "checkJs" => cli.check_js |= flag,
+        "composite" => cli.composite |= flag,
+        "declaration" => cli.declaration |= flag,
+        "declarationMap" => cli.declaration_map |= flag,
+        "diagnostics" => cli.diagnostics |= flag,
+        "downlevelIteration" => cli.downlevel_iteration |= flag,
+        "emitDeclarationOnly" => cli.emit_declaration_only |= flag,
+        "emitBOM" => cli.emit_bom |= flag,
+        "emitDecoratorMetadata" => cli.emit_decorator_metadata |= flag,
+        "erasableSyntaxOnly" => cli.erasable_syntax_only |= flag,
+        "esModuleInterop" => cli.es_module_interop |= flag,
+        "experimentalDecorators" => cli.experimental_decorators |= flag,
+        "explainFiles" => cli.explain_files |= flag,
+        "extendedDiagnostics" => cli.extended_diagnostics |= flag,
+        "importHelpers" => cli.import_helpers |= flag,
+        "incremental" => cli.incremental |= flag,
+        "inlineSourceMap" => cli.inline_source_map |= flag,
+        "inlineSources" => cli.inline_sources |= flag,
+        "isolatedDeclarations" => cli.isolated_declarations |= flag,
+        "isolatedModules" => cli.isolated_modules |= flag,
+        "listFiles" => cli.list_files |= flag,
+        "noCheck" => cli.no_check |= flag,
+        "noEmit" => cli.no_emit |= flag,
+        "noEmitHelpers" => cli.no_emit_helpers |= flag,
+        "noEmitOnError" => cli.no_emit_on_error |= flag,
+        "noLib" => cli.no_lib |= flag,
+        "noResolve" => cli.no_resolve |= flag,
+        "noUncheckedSideEffectImports" => cli.no_unchecked_side_effect_imports |= flag,
+        "preserveConstEnums" => cli.preserve_const_enums |= flag,
+        "preserveSymlinks" => cli.preserve_symlinks |= flag,
+        "removeComments" => cli.remove_comments |= flag,
+        "skipDefaultLibCheck" => cli.skip_default_lib_check |= flag,
+        "skipLibCheck" => cli.skip_lib_check |= flag,
+        "sourceMap" => cli.source_map |= flag,
+        "stripInternal" => cli.strip_internal |= flag,
+        "traceResolution" => cli.trace_resolution |= flag,
+        "verbatimModuleSyntax" => cli.verbatim_module_syntax |= flag,
+        "pretty" => cli.pretty &= flag,
+        "preserveWatchOutput" => cli.preserve_watch_output |= flag,
+        "allowSyntheticDefaultImports" => {
+            cli.allow_synthetic_default_imports.get_or_insert(flag);
+        }
+        "allowUnreachableCode" => {
+            cli.allow_unreachable_code.get_or_insert(flag);
+        }
+        "allowUnusedLabels" => {
+            cli.allow_unused_labels.get_or_insert(flag);
+        }
+        "alwaysStrict" => {
+            cli.always_strict.get_or_insert(flag);
+        }
+        "exactOptionalPropertyTypes" => {
+            cli.exact_optional_property_types.get_or_insert(flag);
+        }
+        "forceConsistentCasingInFileNames" => {
+            cli.force_consistent_casing_in_file_names
+                .get_or_insert(flag);
+        }
+        "noFallthroughCasesInSwitch" => {
+            cli.no_fallthrough_cases_in_switch.get_or_insert(flag);
+        }
+        "noImplicitAny" => {
+            cli.no_implicit_any.get_or_insert(flag);
+        }
+        "noImplicitOverride" => {
+            cli.no_implicit_override.get_or_insert(flag);
+        }
+        "noImplicitReturns" => {
+            cli.no_implicit_returns.get_or_insert(flag);
+        }
+        "noImplicitThis" => {
+            cli.no_implicit_this.get_or_insert(flag);
+        }
+        "noPropertyAccessFromIndexSignature" => {
+            cli.no_property_access_from_index_signature
+                .get_or_insert(flag);
+        }
+        "noUncheckedIndexedAccess" => {
+            cli.no_unchecked_indexed_access.get_or_insert(flag);
+        }
+        "noUnusedLocals" => {
+            cli.no_unused_locals.get_or_insert(flag);
+        }
+        "noUnusedParameters" => {
+            cli.no_unused_parameters.get_or_insert(flag);
+        }
+        "resolveJsonModule" => {
+            cli.resolve_json_module.get_or_insert(flag);
+        }
+        "strict" => {
+            cli.strict.get_or_insert(flag);
+        }
+        "strictBindCallApply" => {
+            cli.strict_bind_call_apply.get_or_insert(flag);
+        }
+        "strictBuiltinIteratorReturn" => {
+            cli.strict_builtin_iterator_return.get_or_insert(flag);
+        }
+        "strictFunctionTypes" => {
+            cli.strict_function_types.get_or_insert(flag);
+        }
+        "strictNullChecks" => {
+            cli.strict_null_checks.get_or_insert(flag);
+        }
+        "strictPropertyInitialization" => {
+            cli.strict_property_initialization.get_or_insert(flag);
+        }
+        "useDefineForClassFields" => {
+            cli.use_define_for_class_fields.get_or_insert(flag);
+        }
+        "useUnknownInCatchVariables" => {
+            cli.use_unknown_in_catch_variables.get_or_insert(flag);
+        }
+        "importsNotUsedAsValues" if cli.imports_not_used_as_values.is_none() => {
+            cli.imports_not_used_as_values = ImportsNotUsedAsValues::from_str(text, true).ok();
+        }
+        "jsx" if cli.jsx.is_none() => cli.jsx = JsxMode::from_str(text, true).ok(),
+        "module" if cli.module.is_none() => cli.module = Module::from_str(text, true).ok(),
+        "moduleResolution" if cli.module_resolution.is_none() => {
+            cli.module_resolution = ModuleResolution::from_str(text, true).ok();
+        }
+        "newLine" if cli.new_line.is_none() => cli.new_line = NewLine::from_str(text, true).ok(),
+        "target" if cli.target.is_none() => cli.target = Target::from_str(text, true).ok(),
+        "outDir" if cli.out_dir.is_none() => cli.out_dir = Some(text.into()),
+        "outFile" if cli.out_file.is_none() => cli.out_file = Some(text.into()),
+        "rootDir" if cli.root_dir.is_none() => cli.root_dir = Some(text.into()),
+        "baseUrl" if cli.base_url.is_none() => cli.base_url = Some(text.into()),
+        "declarationDir" if cli.declaration_dir.is_none() => {
+            cli.declaration_dir = Some(text.into());
+        }
+        "tsBuildInfoFile" if cli.ts_build_info_file.is_none() => {
+            cli.ts_build_info_file = Some(text.into());
+        }
+        "jsxFactory" if cli.jsx_factory.is_none() => cli.jsx_factory = Some(text.into()),
+        "jsxFragmentFactory" if cli.jsx_fragment_factory.is_none() => {
+            cli.jsx_fragment_factory = Some(text.into());
+        }
+        "jsxImportSource" if cli.jsx_import_source.is_none() => {
+            cli.jsx_import_source = Some(text.into());
+        }
+        "mapRoot" if cli.map_root.is_none() => cli.map_root = Some(text.into()),
+        "charset" if cli.charset.is_none() => cli.charset = Some(text.into()),
+        "sourceRoot" if cli.source_root.is_none() => cli.source_root = Some(text.into()),
+        "lib" if cli.lib.is_empty() => cli.lib = list(),
+        "customConditions" if cli.custom_conditions.is_none() => {
+            cli.custom_conditions = Some(list());
+        }
+        "moduleSuffixes" if cli.module_suffixes.is_none() => cli.module_suffixes = Some(list()),
+        "rootDirs" if cli.root_dirs.is_none() => cli.root_dirs = Some(list()),
+        "typeRoots" if cli.type_roots.is_none() => cli.type_roots = Some(list()),
+        "types" if cli.types.is_none() => cli.types = Some(list()),
+        _ => {}
+    }
+}
+
+/// The compiler options of `cli` that are not left at their defaults, as they are written in
+/// a configuration file. This is the reverse of [`apply_compiler_option`].
+fn command_line_options(cli: &Cli) -> Vec<(&'static str, JsonValue)> {
+    let string = |text: &str| JsonValue::String(text.to_string());
+    let list =
+        |items: &[String]| JsonValue::Array(items.iter().map(|item| string(item).into()).collect());
+    fn value<T: ValueEnum>(value: &T) -> JsonValue {
+        let value = value
+            .to_possible_value()
+            .expect("option values are not skipped");
+        JsonValue::String(value.get_name().to_string())
+    }
+
+    let flags = [
+        ("allowArbitraryExtensions", cli.allow_arbitrary_extensions),
+        (
+            "allowImportingTsExtensions",
+            cli.allow_importing_ts_extensions,
+        ),
+        ("allowJs", cli.allow_js),
+        ("allowUmdGlobalAccess", cli.allow_umd_global_access),
+        ("checkJs", cli.check_js),
+        ("composite", cli.composite),
+        ("declaration", cli.declaration),
+        ("declarationMap", cli.declaration_map),
+        ("diagnostics", cli.diagnostics),
+        ("downlevelIteration", cli.downlevel_iteration),
+        ("emitBOM", cli.emit_bom),
+        ("emitDeclarationOnly", cli.emit_declaration_only),
+        ("emitDecoratorMetadata", cli.emit_decorator_metadata),
+        ("erasableSyntaxOnly", cli.erasable_syntax_only),
+        ("esModuleInterop", cli.es_module_interop),
+        ("experimentalDecorators", cli.experimental_decorators),
+        ("explainFiles", cli.explain_files),
+        ("extendedDiagnostics", cli.extended_diagnostics),
+        ("importHelpers", cli.import_helpers),
+        ("incremental", cli.incremental),
+        ("inlineSourceMap", cli.inline_source_map),
+        ("inlineSources", cli.inline_sources),
+        ("isolatedDeclarations", cli.isolated_declarations),
+        ("isolatedModules", cli.isolated_modules),
+        ("listFiles", cli.list_files),
+        ("noCheck", cli.no_check),
+        ("noEmit", cli.no_emit),
+        ("noEmitHelpers", cli.no_emit_helpers),
+        ("noEmitOnError", cli.no_emit_on_error),
+        ("noLib", cli.no_lib),
+        ("noResolve", cli.no_resolve),
+        (
+            "noUncheckedSideEffectImports",
+            cli.no_unchecked_side_effect_imports,
+        ),
+        ("preserveConstEnums", cli.preserve_const_enums),
+        ("preserveSymlinks", cli.preserve_symlinks),
+        ("preserveWatchOutput", cli.preserve_watch_output),
+        ("removeComments", cli.remove_comments),
+        ("skipDefaultLibCheck", cli.skip_default_lib_check),
+        ("skipLibCheck", cli.skip_lib_check),
+        ("sourceMap", cli.source_map),
+        ("stripInternal", cli.strip_internal),
+        ("traceResolution", cli.trace_resolution),
+        ("verbatimModuleSyntax", cli.verbatim_module_syntax),
+    ];
+    let mut options: Vec<(&'static str, JsonValue)> = flags
+        .into_iter()
+        .filter(|(_, flag)| *flag)
+        .map(|(name, _)| (name, JsonValue::Boolean(true)))
+        .collect();
+    if !cli.pretty {
+        options.push(("pretty", JsonValue::Boolean(false)));
+    }
+    let optional_flags = [
+        (
+            "allowSyntheticDefaultImports",
+            cli.allow_synthetic_default_imports,
+        ),
+        ("allowUnreachableCode", cli.allow_unreachable_code),
+        ("allowUnusedLabels", cli.allow_unused_labels),
+        ("alwaysStrict", cli.always_strict),
+        (
+            "exactOptionalPropertyTypes",
+            cli.exact_optional_property_types,
+        ),
+        (
+            "forceConsistentCasingInFileNames",
+            cli.force_consistent_casing_in_file_names,
+        ),
+        (
+            "noFallthroughCasesInSwitch",
+            cli.no_fallthrough_cases_in_switch,
+        ),
+        ("noImplicitAny", cli.no_implicit_any),
+        ("noImplicitOverride", cli.no_implicit_override),
+        ("noImplicitReturns", cli.no_implicit_returns),
+        ("noImplicitThis", cli.no_implicit_this),
+        (
+            "noPropertyAccessFromIndexSignature",
+            cli.no_property_access_from_index_signature,
+        ),
+        ("noUncheckedIndexedAccess", cli.no_unchecked_indexed_access),
+        ("noUnusedLocals", cli.no_unused_locals),
+        ("noUnusedParameters", cli.no_unused_parameters),
+        ("resolveJsonModule", cli.resolve_json_module),
+        ("strict", cli.strict),
+        ("strictBindCallApply", cli.strict_bind_call_apply),
+        (
+            "strictBuiltinIteratorReturn",
+            cli.strict_builtin_iterator_return,
+        ),
+        ("strictFunctionTypes", cli.strict_function_types),
+        ("strictNullChecks", cli.strict_null_checks),
+        (
+            "strictPropertyInitialization",
+            cli.strict_property_initialization,
+        ),
+        ("useDefineForClassFields", cli.use_define_for_class_fields),
+        (
+            "useUnknownInCatchVariables",
+            cli.use_unknown_in_catch_variables,
+        ),
+    ];
+    options.extend(
+        optional_flags
+            .into_iter()
+            .filter_map(|(name, flag)| Some((name, JsonValue::Boolean(flag?)))),
+    );
+    options.extend(cli.target.as_ref().map(|target| ("target", value(target))));
+    options.extend(cli.module.as_ref().map(|module| ("module", value(module))));
+    options.extend(
+        cli.module_resolution
+            .as_ref()
+            .map(|resolution| ("moduleResolution", value(resolution))),
+    );
+    options.extend(cli.jsx.as_ref().map(|jsx| ("jsx", value(jsx))));
+    options.extend(
+        cli.imports_not_used_as_values
+            .as_ref()
+            .map(|imports| ("importsNotUsedAsValues", value(imports))),
+    );
+    options.extend(
+        cli.new_line
+            .as_ref()
+            .map(|new_line| ("newLine", value(new_line))),
+    );
+    let paths = [
+        ("baseUrl", &cli.base_url),
+        ("declarationDir", &cli.declaration_dir),
+        ("outDir", &cli.out_dir),
+        ("outFile", &cli.out_file),
+        ("rootDir", &cli.root_dir),
+        ("tsBuildInfoFile", &cli.ts_build_info_file),
+    ];
+    options.extend(
+        paths
+            .into_iter()
+            .filter_map(|(name, path)| Some((name, string(&path.as_ref()?.to_string_lossy())))),
+    );
+    let strings = [
+        ("charset", &cli.charset),
+        ("jsxFactory", &cli.jsx_factory),
+        ("jsxFragmentFactory", &cli.jsx_fragment_factory),
+        ("jsxImportSource", &cli.jsx_import_source),
+        ("mapRoot", &cli.map_root),
+        ("sourceRoot", &cli.source_root),
+    ];
+    options.extend(
+        strings
+            .into_iter()
+            .filter_map(|(name, text)| Some((name, string(text.as_ref()?)))),
+    );
+    if !cli.lib.is_empty() {
+        options.push(("lib", list(&cli.lib)));
+    }
+    options.extend(
+        cli.type_roots
+            .as_ref()
+            .map(|roots| ("typeRoots", list(roots))),
+    );
+    options.extend(cli.types.as_ref().map(|types| ("types", list(types))));
+    let lists = [
+        ("customConditions", &cli.custom_conditions),
+        ("moduleSuffixes", &cli.module_suffixes),
+        ("rootDirs", &cli.root_dirs),
+    ];
+    options.extend(
+        lists
+            .into_iter()
+            .filter_map(|(name, items)| Some((name, list(items.as_ref()?)))),
+    );
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::cli::create_compiler_options;
+    use crate::compile::{VirtualCompilerHost, compile};
+
+    fn parse(files: &[(&str, &str)], config_file_name: &str) -> (Cli, ParsedConfig) {
+        let mut cli = Cli::parse_from(["tsrs"]);
+        let config = parse_config_file(
+            config_file_name,
+            &mut cli,
+            &VirtualCompilerHost::with_files(files),
+        );
+        (cli, config)
+    }
+
+    fn messages(config: &ParsedConfig) -> Vec<String> {
+        config
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                format!(
+                    "({},{}) TS{}: {}",
+                    diagnostic.line, diagnostic.character, diagnostic.code, diagnostic.message
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_json_with_comments_and_trailing_commas() {
+        let (json, errors) = parse_json_text(
+            "tsconfig.json",
+            "// options\n{ \"a\": [1, -2.5e1, \"x\\n\\u0041\",], /* b */ \"b\": { \"c\": null, }, }",
+        );
+        assert!(errors.is_empty());
+        let json = json.unwrap();
+        let a = json.get("a").unwrap();
+        assert_eq!(a.name_loc, TextRange::new(13, 16));
+        assert_eq!(
+            a.initializer.value,
+            JsonValue::Array(vec![
+                JsonNode {
+                    value: JsonValue::Number(1.0),
+                    loc: TextRange::new(19, 20)
+                },
+                JsonNode {
+                    value: JsonValue::Number(-25.0),
+                    loc: TextRange::new(22, 28)
+                },
+                JsonNode {
+                    value: JsonValue::String("x\nA".to_string()),
+                    loc: TextRange::new(30, 41)
+                },
+            ])
+        );
+        assert_eq!(
+            json.get("b")
+                .unwrap()
+                .initializer
+                .get("c")
+                .unwrap()
+                .initializer
+                .value,
+            JsonValue::Null
+        );
+
+        let (json, errors) = parse_json_text("tsconfig.json", "{ \"a\": 1 \"b\": tru, 'c': 3 ");
+        let codes: Vec<i32> = errors.iter().map(|error| error.code).collect();
+        assert_eq!(codes, [1005, 1109, 1327, 1005]);
+        assert_eq!(json.unwrap().as_object().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn sets_options_the_command_line_leaves_unset() {
+        let (cli, config) = parse(
+            &[
+                (
+                    "proj/tsconfig.json",
+                    r#"{
+                        "compilerOptions": {
+                            "target": "ES2015",
+                            "module": "commonjs",
+                            "strict": true,
+                            "noImplicitAny": false,
+                            "outDir": "./dist",
+                            "lib": ["es2015", "dom"],
+                            "typeRoots": ["./types", "../shared/types"],
+                            "types": [],
+                            "skipLibCheck": true,
+                        },
+                    }"#,
+                ),
+                ("proj/a.ts", ""),
+            ],
+            "proj/tsconfig.json",
+        );
+        assert!(config.diagnostics.is_empty());
+        assert!(cli.target == Some(Target::Es2015));
+        assert!(cli.module == Some(Module::CommonJs));
+        assert_eq!(cli.strict, Some(true));
+        assert_eq!(cli.no_implicit_any, Some(false));
+        assert_eq!(cli.out_dir, Some("proj/dist".into()));
+        assert_eq!(cli.lib, ["es2015", "dom"]);
+        assert!(cli.skip_lib_check);
+        assert_eq!(
+            cli.type_roots,
+            Some(vec!["proj/types".into(), "shared/types".into()])
+        );
+        // An empty list of types includes no type packages, unlike leaving it out
+        assert_eq!(cli.types, Some(vec![]));
+
+        let mut cli = Cli::parse_from(["tsrs", "--target", "es5", "--outDir", "out"]);
+        parse_config_file(
+            "tsconfig.json",
+            &mut cli,
+            &VirtualCompilerHost::with_files(&[(
+                "tsconfig.json",
+                r#"{ "compilerOptions": { "target": "esnext", "outDir": "dist" } }"#,
+            )]),
+        );
+        assert!(cli.target == Some(Target::Es5));
+        assert_eq!(cli.out_dir, Some("out".into()));
+    }
+
+    #[test]
+    fn reports_unknown_and_mistyped_options_where_they_are_written() {
+        let (_, config) = parse(
+            &[
+                (
+                    "tsconfig.json",
+                    "{\n  \"compilerOptions\": {\n    \"strictNullCheck\": true,\n    \"frobnicate\": 1,\n    \"declaration\": \"yes\",\n    \"target\": \"es1\",\n    \"types\": [\"node\", 2]\n  },\n  \"excludes\": []\n}",
+                ),
+                ("a.ts", ""),
+            ],
+            "tsconfig.json",
+        );
+        assert_eq!(
+            messages(&config),
+            [
+                "(9,3) TS6114: Unknown option 'excludes'. Did you mean 'exclude'?",
+                "(3,5) TS5025: Unknown compiler option 'strictNullCheck'. Did you mean 'strictNullChecks'?",
+                "(4,5) TS5023: Unknown compiler option 'frobnicate'.",
+                "(5,20) TS5024: Compiler option 'declaration' requires a value of type boolean.",
+                "(6,15) TS6046: Argument for '--target' option must be: 'es5', 'es6', 'es2015', 'es2016', 'es2017', 'es2018', 'es2019', 'es2020', 'es2021', 'es2022', 'es2023', 'es2024', 'esnext'.",
+                "(7,23) TS5024: Compiler option 'types' requires a value of type string.",
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_include_and_exclude_patterns() {
+        let files = [
+            ("tsconfig.json", ""),
+            ("src/a.ts", ""),
+            ("src/a.d.ts", ""),
+            ("src/b.tsx", ""),
+            ("src/c.js", ""),
+            ("src/lib/d.ts", ""),
+            ("src/lib/d.test.ts", ""),
+            ("src/.hidden/e.ts", ""),
+            ("node_modules/f/index.ts", ""),
+            ("dist/a.d.ts", ""),
+            ("extra.ts", ""),
+        ];
+        let (_, config) = parse(
+            &[
+                files.as_slice(),
+                &[(
+                    "tsconfig.json",
+                    r#"{ "compilerOptions": { "outDir": "dist" } }"#,
+                )],
+            ]
+            .concat(),
+            "tsconfig.json",
+        );
+        assert_eq!(
+            config.file_names,
+            [
+                "extra.ts",
+                "src/a.ts",
+                "src/b.tsx",
+                "src/lib/d.test.ts",
+                "src/lib/d.ts"
+            ]
+        );
+
+        let (_, config) = parse(
+            &[
+                files.as_slice(),
+                &[(
+                    "tsconfig.json",
+                    r#"{
+                        "compilerOptions": { "allowJs": true },
+                        "files": ["extra.ts"],
+                        "include": ["src/**/*"],
+                        "exclude": ["**/*.test.ts"]
+                    }"#,
+                )],
+            ]
+            .concat(),
+            "tsconfig.json",
+        );
+        assert_eq!(
+            config.file_names,
+            [
+                "extra.ts",
+                "src/a.ts",
+                "src/b.tsx",
+                "src/c.js",
+                "src/lib/d.ts"
+            ]
+        );
+
+        let (_, config) = parse(
+            &[
+                (
+                    "tsconfig.json",
+                    r#"{ "files": ["missing.ts"], "include": ["lib"] }"#,
+                ),
+                ("a.ts", ""),
+            ],
+            "tsconfig.json",
+        );
+        assert_eq!(
+            messages(&config),
+            [
+                "(1,13) TS6053: File 'missing.ts' not found.",
+                "(0,0) TS18003: No inputs were found in config file 'tsconfig.json'. Specified 'include' paths were '[\"lib\"]' and 'exclude' paths were '[\"node_modules\",\"bower_components\",\"jspm_packages\"]'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn inherits_settings_from_extended_files() {
+        let (cli, config) = parse(
+            &[
+                (
+                    "/repo/app/tsconfig.json",
+                    r#"{
+                        "extends": ["../configs/base", "@tsconfig/node18", "shared"],
+                        "compilerOptions": { "strict": false }
+                    }"#,
+                ),
+                (
+                    "/repo/configs/base.json",
+                    r#"{
+                        "compilerOptions": { "strict": true, "outDir": "../dist", "target": "es5" },
+                        "include": ["../app/src"]
+                    }"#,
+                ),
+                (
+                    "/repo/node_modules/@tsconfig/node18/tsconfig.json",
+                    r#"{ "compilerOptions": { "target": "es2022", "module": "node16" } }"#,
+                ),
+                (
+                    "/repo/node_modules/shared/package.json",
+                    r#"{ "name": "shared", "tsconfig": "./strict.json" }"#,
+                ),
+                (
+                    "/repo/node_modules/shared/strict.json",
+                    r#"{ "compilerOptions": { "noImplicitAny": true } }"#,
+                ),
+                ("/repo/app/src/a.ts", ""),
+                ("/repo/app/b.ts", ""),
+            ],
+            "/repo/app/tsconfig.json",
+        );
+        assert!(config.diagnostics.is_empty());
+        assert_eq!(cli.strict, Some(false));
+        assert!(cli.target == Some(Target::Es2022));
+        assert!(cli.module == Some(Module::Node16));
+        assert_eq!(cli.no_implicit_any, Some(true));
+        assert_eq!(cli.out_dir, Some("/repo/dist".into()));
+        assert_eq!(config.file_names, ["/repo/app/src/a.ts"]);
+    }
+
+    #[test]
+    fn reports_missing_and_circular_extended_files() {
+        let (_, config) = parse(
+            &[
+                ("tsconfig.json", r#"{ "extends": ["./base", "missing"] }"#),
+                ("base.json", r#"{ "extends": "./tsconfig.json" }"#),
+                ("a.ts", ""),
+            ],
+            "tsconfig.json",
+        );
+        assert_eq!(
+            messages(&config),
+            [
+                "(1,25) TS6053: File 'missing' not found.",
+                "(0,0) TS18000: Circularity detected while resolving configuration: tsconfig.json -> base.json -> tsconfig.json",
+            ]
+        );
+        assert_eq!(config.file_names, ["a.ts"]);
+    }
+
+    #[test]
+    fn finds_the_config_file_of_a_project() {
+        let host = VirtualCompilerHost::with_files(&[
+            ("/repo/tsconfig.json", "{}"),
+            ("/repo/app/tsrsonfig.json", "{}"),
+            ("/repo/app/tsconfig.json", "{}"),
+        ]);
+        assert_eq!(
+            find_config_file("/repo/lib/src", &host).as_deref(),
+            Some("/repo/tsconfig.json")
+        );
+        assert_eq!(
+            find_config_file("/repo/app", &host).as_deref(),
+            Some("tsrsonfig.json")
+        );
+        assert_eq!(
+            get_project_config_file("/repo/", &host).ok().as_deref(),
+            Some("/repo/tsconfig.json")
+        );
+        assert_eq!(
+            get_project_config_file("/repo/lib", &host)
+                .unwrap_err()
+                .code,
+            5058
+        );
+    }
+
+    #[test]
+    fn shows_the_resolved_configuration_relative_to_its_file() {
+        let files = [
+            (
+                "proj/base.json",
+                r#"{ "compilerOptions": { "strict": true, "noImplicitAny": false } }"#,
+            ),
+            (
+                "proj/tsconfig.json",
+                r#"{
+                    "extends": "./base.json",
+                    "compilerOptions": { "outDir": "dist", "baseUrl": "." },
+                    "include": ["src"],
+                }"#,
+            ),
+            ("proj/src/a.ts", ""),
+            ("proj/src/b.ts", ""),
+        ];
+        let (mut cli, config) = parse(&files, "proj/tsconfig.json");
+        cli.target = Some(Target::Es2020);
+        let text = convert_to_config(
+            &cli,
+            "proj/tsconfig.json",
+            &config,
+            &VirtualCompilerHost::with_files(&files),
+        );
+        let (json, errors) = parse_json_text("tsconfig.json", &text);
+        assert!(errors.is_empty());
+        let json = json.unwrap();
+        let value = |property: Option<&JsonProperty>| property.unwrap().initializer.value.clone();
+        let compiler_options = &json.get("compilerOptions").unwrap().initializer;
+        let option = |name| value(compiler_options.get(name));
+        assert_eq!(option("outDir"), JsonValue::String("./dist".to_string()));
+        assert_eq!(option("baseUrl"), JsonValue::String("./".to_string()));
+        assert_eq!(option("target"), JsonValue::String("es2020".to_string()));
+        // `strict` implies the options it turns on, but not those set otherwise
+        assert_eq!(option("noImplicitAny"), JsonValue::Boolean(false));
+        assert_eq!(option("strictNullChecks"), JsonValue::Boolean(true));
+        assert_eq!(
+            value(json.get("files")).stringify(""),
+            r#"["./src/a.ts","./src/b.ts"]"#
+        );
+        assert_eq!(value(json.get("include")).stringify(""), r#"["src"]"#);
+        assert!(json.get("exclude").is_none());
+        assert!(text.starts_with("{\n    \"compilerOptions\": {\n        \""));
+    }
+
+    #[test]
+    fn lets_the_command_line_turn_strict_off() {
+        let files = [
+            (
+                "tsconfig.json",
+                r#"{ "compilerOptions": { "strict": true } }"#,
+            ),
+            ("a.ts", "function f(x) { return x; }\n"),
+        ];
+        let host = VirtualCompilerHost::with_files(&files);
+        let mut cli = Cli::parse_from(["tsrs", "--strict", "false"]);
+        let config = parse_config_file("tsconfig.json", &mut cli, &host);
+        assert_eq!(cli.strict, Some(false));
+        let options = create_compiler_options(&cli);
+        assert!(!options.no_implicit_any && !options.strict_null_checks);
+        let result = compile(&config.file_names, &options, &host);
+        assert!(result.diagnostics().is_empty());
+
+        let text = convert_to_config(&cli, "tsconfig.json", &config, &host);
+        assert!(text.contains("\"strict\": false"));
+        assert!(!text.contains("noImplicitAny"));
+    }
+}
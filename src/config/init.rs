@@ -0,0 +1,754 @@
+//! Writing a new configuration file for `--init`
+//!
+//! As with tsc, the file sets the recommended options and lists every other option commented
+//! out, grouped by category and described, so that a project can turn them on as it needs.
+//! Options given on the command line are set in the file, in place of the recommended ones.
+
+use super::{CONFIG_FILE_NAMES, command_line_options, write_json_string};
+use crate::cli::{Cli, NewLine};
+use crate::compile::{CompilerHost, Diagnostic, option_diagnostic};
+use crate::compiler::diagnostics::{self, Message};
+use crate::compiler::paths::combine_paths;
+
+/// An option that a new configuration file lists
+struct InitOption {
+    name: &'static str,
+    /// The value the option is shown with when it is commented out, as JSON
+    example: &'static str,
+    description: &'static str,
+}
+
+const fn init_option(
+    name: &'static str,
+    example: &'static str,
+    description: &'static str,
+) -> InitOption {
+    InitOption {
+        name,
+        example,
+        description,
+    }
+}
+
+/// The categories of options in the order they are listed, and whether a category is listed
+/// when none of its options are set. Options about the output of the compiler itself, rather
+/// than of the project, are only listed when they are set.
+static INIT_CATEGORIES: &[(&Message, bool, &[InitOption])] = &[
+    (
+        diagnostics::PROJECTS_6255,
+        true,
+        &[
+            init_option(
+                "incremental",
+                "true",
+                "Save .tsbuildinfo files to allow for incremental compilation of projects.",
+            ),
+            init_option(
+                "composite",
+                "true",
+                "Enable constraints that allow a TypeScript project to be used with project references.",
+            ),
+            init_option(
+                "tsBuildInfoFile",
+                "\"./.tsbuildinfo\"",
+                "Specify the path to .tsbuildinfo incremental compilation file.",
+            ),
+            init_option(
+                "disableSourceOfProjectReferenceRedirect",
+                "true",
+                "Disable preferring source files instead of declaration files when referencing composite projects.",
+            ),
+            init_option(
+                "disableSolutionSearching",
+                "true",
+                "Opt a project out of multi-project reference checking when editing.",
+            ),
+            init_option(
+                "disableReferencedProjectLoad",
+                "true",
+                "Reduce the number of projects loaded automatically by TypeScript.",
+            ),
+        ],
+    ),
+    (
+        diagnostics::LANGUAGE_AND_ENVIRONMENT_6254,
+        true,
+        &[
+            init_option(
+                "target",
+                "\"es2016\"",
+                "Set the JavaScript language version for emitted JavaScript and include compatible library declarations.",
+            ),
+            init_option(
+                "lib",
+                "[]",
+                "Specify a set of bundled library declaration files that describe the target runtime environment.",
+            ),
+            init_option("jsx", "\"preserve\"", "Specify what JSX code is generated."),
+            init_option(
+                "experimentalDecorators",
+                "true",
+                "Enable experimental support for legacy experimental decorators.",
+            ),
+            init_option(
+                "emitDecoratorMetadata",
+                "true",
+                "Emit design-type metadata for decorated declarations in source files.",
+            ),
+            init_option(
+                "jsxFactory",
+                "\"\"",
+                "Specify the JSX factory function used when targeting React JSX emit, e.g. 'React.createElement' or 'h'.",
+            ),
+            init_option(
+                "jsxFragmentFactory",
+                "\"\"",
+                "Specify the JSX Fragment reference used for fragments when targeting React JSX emit e.g. 'React.Fragment' or 'Fragment'.",
+            ),
+            init_option(
+                "jsxImportSource",
+                "\"\"",
+                "Specify module specifier used to import the JSX factory functions when using 'jsx: react-jsx*'.",
+            ),
+            init_option(
+                "reactNamespace",
+                "\"\"",
+                "Specify the object invoked for 'createElement'. This only applies when targeting 'react' JSX emit.",
+            ),
+            init_option(
+                "noLib",
+                "true",
+                "Disable including any library files, including the default lib.d.ts.",
+            ),
+            init_option(
+                "useDefineForClassFields",
+                "true",
+                "Emit ECMAScript-standard-compliant class fields.",
+            ),
+            init_option(
+                "moduleDetection",
+                "\"auto\"",
+                "Control what method is used to detect module-format JS files.",
+            ),
+        ],
+    ),
+    (
+        diagnostics::MODULES_6244,
+        true,
+        &[
+            init_option(
+                "module",
+                "\"commonjs\"",
+                "Specify what module code is generated.",
+            ),
+            init_option(
+                "rootDir",
+                "\"./\"",
+                "Specify the root folder within your source files.",
+            ),
+            init_option(
+                "moduleResolution",
+                "\"node10\"",
+                "Specify how TypeScript looks up a file from a given module specifier.",
+            ),
+            init_option(
+                "baseUrl",
+                "\"./\"",
+                "Specify the base directory to resolve non-relative module names.",
+            ),
+            init_option(
+                "paths",
+                "{}",
+                "Specify a set of entries that re-map imports to additional lookup locations.",
+            ),
+            init_option(
+                "rootDirs",
+                "[]",
+                "Allow multiple folders to be treated as one when resolving modules.",
+            ),
+            init_option(
+                "typeRoots",
+                "[]",
+                "Specify multiple folders that act like './node_modules/@types'.",
+            ),
+            init_option(
+                "types",
+                "[]",
+                "Specify type package names to be included without being referenced in a source file.",
+            ),
+            init_option(
+                "allowUmdGlobalAccess",
+                "true",
+                "Allow accessing UMD globals from modules.",
+            ),
+            init_option(
+                "moduleSuffixes",
+                "[]",
+                "List of file name suffixes to search when resolving a module.",
+            ),
+            init_option(
+                "allowImportingTsExtensions",
+                "true",
+                "Allow imports to include TypeScript file extensions. Requires '--moduleResolution bundler' and either '--noEmit' or '--emitDeclarationOnly' to be set.",
+            ),
+            init_option(
+                "rewriteRelativeImportExtensions",
+                "true",
+                "Rewrite '.ts', '.tsx', '.mts', and '.cts' file extensions in relative import paths to their JavaScript equivalent in output files.",
+            ),
+            init_option(
+                "resolvePackageJsonExports",
+                "true",
+                "Use the package.json 'exports' field when resolving package imports.",
+            ),
+            init_option(
+                "resolvePackageJsonImports",
+                "true",
+                "Use the package.json 'imports' field when resolving imports.",
+            ),
+            init_option(
+                "customConditions",
+                "[]",
+                "Conditions to set in addition to the resolver-specific defaults when resolving imports.",
+            ),
+            init_option(
+                "noUncheckedSideEffectImports",
+                "true",
+                "Check side effect imports.",
+            ),
+            init_option("resolveJsonModule", "true", "Enable importing .json files."),
+            init_option(
+                "allowArbitraryExtensions",
+                "true",
+                "Enable importing files with any extension, provided a declaration file is present.",
+            ),
+            init_option(
+                "noResolve",
+                "true",
+                "Disallow 'import's, 'require's or '<reference>'s from expanding the number of files TypeScript should add to a project.",
+            ),
+        ],
+    ),
+    (
+        diagnostics::JAVA_SCRIPT_SUPPORT_6247,
+        true,
+        &[
+            init_option(
+                "allowJs",
+                "true",
+                "Allow JavaScript files to be a part of your program. Use the 'checkJS' option to get errors from these files.",
+            ),
+            init_option(
+                "checkJs",
+                "true",
+                "Enable error reporting in type-checked JavaScript files.",
+            ),
+            init_option(
+                "maxNodeModuleJsDepth",
+                "1",
+                "Specify the maximum folder depth used for checking JavaScript files from 'node_modules'. Only applicable with 'allowJs'.",
+            ),
+        ],
+    ),
+    (
+        diagnostics::EMIT_6246,
+        true,
+        &[
+            init_option(
+                "declaration",
+                "true",
+                "Generate .d.ts files from TypeScript and JavaScript files in your project.",
+            ),
+            init_option(
+                "declarationMap",
+                "true",
+                "Create sourcemaps for d.ts files.",
+            ),
+            init_option(
+                "emitDeclarationOnly",
+                "true",
+                "Only output d.ts files and not JavaScript files.",
+            ),
+            init_option(
+                "sourceMap",
+                "true",
+                "Create source map files for emitted JavaScript files.",
+            ),
+            init_option(
+                "inlineSourceMap",
+                "true",
+                "Include sourcemap files inside the emitted JavaScript.",
+            ),
+            init_option(
+                "noEmit",
+                "true",
+                "Disable emitting files from a compilation.",
+            ),
+            init_option(
+                "outFile",
+                "\"./\"",
+                "Specify a file that bundles all outputs into one JavaScript file. If 'declaration' is true, also designates a file that bundles all .d.ts output.",
+            ),
+            init_option(
+                "outDir",
+                "\"./\"",
+                "Specify an output folder for all emitted files.",
+            ),
+            init_option("removeComments", "true", "Disable emitting comments."),
+            init_option(
+                "importHelpers",
+                "true",
+                "Allow importing helper functions from tslib once per project, instead of including them per-file.",
+            ),
+            init_option(
+                "downlevelIteration",
+                "true",
+                "Emit more compliant, but verbose and less performant JavaScript for iteration.",
+            ),
+            init_option(
+                "sourceRoot",
+                "\"\"",
+                "Specify the root path for debuggers to find the reference source code.",
+            ),
+            init_option(
+                "mapRoot",
+                "\"\"",
+                "Specify the location where debugger should locate map files instead of generated locations.",
+            ),
+            init_option(
+                "inlineSources",
+                "true",
+                "Include source code in the sourcemaps inside the emitted JavaScript.",
+            ),
+            init_option(
+                "emitBOM",
+                "true",
+                "Emit a UTF-8 Byte Order Mark (BOM) in the beginning of output files.",
+            ),
+            init_option(
+                "newLine",
+                "\"crlf\"",
+                "Set the newline character for emitting files.",
+            ),
+            init_option(
+                "stripInternal",
+                "true",
+                "Disable emitting declarations that have '@internal' in their JSDoc comments.",
+            ),
+            init_option(
+                "noEmitHelpers",
+                "true",
+                "Disable generating custom helper functions like '__extends' in compiled output.",
+            ),
+            init_option(
+                "noEmitOnError",
+                "true",
+                "Disable emitting files if any type checking errors are reported.",
+            ),
+            init_option(
+                "preserveConstEnums",
+                "true",
+                "Disable erasing 'const enum' declarations in generated code.",
+            ),
+            init_option(
+                "declarationDir",
+                "\"./\"",
+                "Specify the output directory for generated declaration files.",
+            ),
+        ],
+    ),
+    (
+        diagnostics::INTEROP_CONSTRAINTS_6252,
+        true,
+        &[
+            init_option(
+                "isolatedModules",
+                "true",
+                "Ensure that each file can be safely transpiled without relying on other imports.",
+            ),
+            init_option(
+                "verbatimModuleSyntax",
+                "true",
+                "Do not transform or elide any imports or exports not marked as type-only, ensuring they are written in the output file's format based on the 'module' setting.",
+            ),
+            init_option(
+                "isolatedDeclarations",
+                "true",
+                "Require sufficient annotation on exports so other tools can trivially generate declaration files.",
+            ),
+            init_option(
+                "erasableSyntaxOnly",
+                "true",
+                "Do not allow runtime constructs that are not part of ECMAScript.",
+            ),
+            init_option(
+                "allowSyntheticDefaultImports",
+                "true",
+                "Allow 'import x from y' when a module doesn't have a default export.",
+            ),
+            init_option(
+                "esModuleInterop",
+                "true",
+                "Emit additional JavaScript to ease support for importing CommonJS modules. This enables 'allowSyntheticDefaultImports' for type compatibility.",
+            ),
+            init_option(
+                "preserveSymlinks",
+                "true",
+                "Disable resolving symlinks to their realpath. This correlates to the same flag in node.",
+            ),
+            init_option(
+                "forceConsistentCasingInFileNames",
+                "true",
+                "Ensure that casing is correct in imports.",
+            ),
+        ],
+    ),
+    (
+        diagnostics::TYPE_CHECKING_6248,
+        true,
+        &[
+            init_option("strict", "true", "Enable all strict type-checking options."),
+            init_option(
+                "noImplicitAny",
+                "true",
+                "Enable error reporting for expressions and declarations with an implied 'any' type.",
+            ),
+            init_option(
+                "strictNullChecks",
+                "true",
+                "When type checking, take into account 'null' and 'undefined'.",
+            ),
+            init_option(
+                "strictFunctionTypes",
+                "true",
+                "When assigning functions, check to ensure parameters and the return values are subtype-compatible.",
+            ),
+            init_option(
+                "strictBindCallApply",
+                "true",
+                "Check that the arguments for 'bind', 'call', and 'apply' methods match the original function.",
+            ),
+            init_option(
+                "strictPropertyInitialization",
+                "true",
+                "Check for class properties that are declared but not set in the constructor.",
+            ),
+            init_option(
+                "strictBuiltinIteratorReturn",
+                "true",
+                "Built-in iterators are instantiated with a 'TReturn' type of 'undefined' instead of 'any'.",
+            ),
+            init_option(
+                "noImplicitThis",
+                "true",
+                "Enable error reporting when 'this' is given the type 'any'.",
+            ),
+            init_option(
+                "useUnknownInCatchVariables",
+                "true",
+                "Default catch clause variables as 'unknown' instead of 'any'.",
+            ),
+            init_option(
+                "alwaysStrict",
+                "true",
+                "Ensure 'use strict' is always emitted.",
+            ),
+            init_option(
+                "noUnusedLocals",
+                "true",
+                "Enable error reporting when local variables aren't read.",
+            ),
+            init_option(
+                "noUnusedParameters",
+                "true",
+                "Raise an error when a function parameter isn't read.",
+            ),
+            init_option(
+                "exactOptionalPropertyTypes",
+                "true",
+                "Interpret optional property types as written, rather than adding 'undefined'.",
+            ),
+            init_option(
+                "noImplicitReturns",
+                "true",
+                "Enable error reporting for codepaths that do not explicitly return in a function.",
+            ),
+            init_option(
+                "noFallthroughCasesInSwitch",
+                "true",
+                "Enable error reporting for fallthrough cases in switch statements.",
+            ),
+            init_option(
+                "noUncheckedIndexedAccess",
+                "true",
+                "Add 'undefined' to a type when accessed using an index.",
+            ),
+            init_option(
+                "noImplicitOverride",
+                "true",
+                "Ensure overriding members in derived classes are marked with an override modifier.",
+            ),
+            init_option(
+                "noPropertyAccessFromIndexSignature",
+                "true",
+                "Enforces using indexed accessors for keys declared using an indexed type.",
+            ),
+            init_option(
+                "allowUnusedLabels",
+                "true",
+                "Disable error reporting for unused labels.",
+            ),
+            init_option(
+                "allowUnreachableCode",
+                "true",
+                "Disable error reporting for unreachable code.",
+            ),
+        ],
+    ),
+    (
+        diagnostics::COMPLETENESS_6257,
+        true,
+        &[
+            init_option(
+                "skipDefaultLibCheck",
+                "true",
+                "Skip type checking .d.ts files that are included with TypeScript.",
+            ),
+            init_option(
+                "skipLibCheck",
+                "true",
+                "Skip type checking all .d.ts files.",
+            ),
+        ],
+    ),
+    (
+        diagnostics::COMPILER_DIAGNOSTICS_6251,
+        false,
+        &[
+            init_option(
+                "diagnostics",
+                "true",
+                "Output compiler performance information after building.",
+            ),
+            init_option(
+                "extendedDiagnostics",
+                "true",
+                "Output more detailed compiler performance information after building.",
+            ),
+            init_option(
+                "explainFiles",
+                "true",
+                "Print files read during the compilation including why it was included.",
+            ),
+            init_option(
+                "listFiles",
+                "true",
+                "Print all of the files read during the compilation.",
+            ),
+            init_option(
+                "traceResolution",
+                "true",
+                "Log paths used during the 'moduleResolution' process.",
+            ),
+        ],
+    ),
+    (
+        diagnostics::OUTPUT_FORMATTING_6256,
+        false,
+        &[
+            init_option(
+                "pretty",
+                "true",
+                "Enable color and formatting in TypeScript's output to make compiler errors easier to read.",
+            ),
+            init_option(
+                "preserveWatchOutput",
+                "true",
+                "Disable wiping the console in watch mode.",
+            ),
+        ],
+    ),
+];
+
+/// The options a new configuration file sets when the command line does not set them
+const RECOMMENDED_OPTIONS: [(&str, &str); 6] = [
+    ("target", "\"es2016\""),
+    ("module", "\"commonjs\""),
+    ("esModuleInterop", "true"),
+    ("forceConsistentCasingInFileNames", "true"),
+    ("strict", "true"),
+    ("skipLibCheck", "true"),
+];
+
+/// Writes a new configuration file for the options of `cli` in `directory`, returning its
+/// path, unless the directory has one already
+pub fn write_config_file(
+    directory: &str,
+    cli: &Cli,
+    host: &impl CompilerHost,
+) -> Result<String, Diagnostic> {
+    let path = combine_paths(directory, CONFIG_FILE_NAMES[0]);
+    if host.file_exists(&path) {
+        return Err(option_diagnostic(
+            diagnostics::A_TSCONFIG_JSON_FILE_IS_ALREADY_DEFINED_AT_COLON_0_5054,
+            &[path],
+        ));
+    }
+    if let Err(error) = host.write_file(&path, &generate_config_file(cli)) {
+        return Err(option_diagnostic(
+            diagnostics::COULD_NOT_WRITE_FILE_0_COLON_1_5033,
+            &[path, error.to_string()],
+        ));
+    }
+    Ok(path)
+}
+
+/// The text of a new configuration file: the recommended options and those of `cli` set, the
+/// others commented out, and the files given on the command line as the files of the project
+pub fn generate_config_file(cli: &Cli) -> String {
+    let mut options: Vec<(&str, String)> = RECOMMENDED_OPTIONS
+        .iter()
+        .map(|(name, value)| (*name, value.to_string()))
+        .collect();
+    for (name, value) in command_line_options(cli) {
+        let value = value.stringify("");
+        match options.iter_mut().find(|(option, _)| *option == name) {
+            Some(option) => option.1 = value,
+            None => options.push((name, value)),
+        }
+    }
+
+    // The entries of each category, with the options that are set keeping their commas but
+    // the last of them, and the width of the widest entry, to line up the descriptions
+    let mut entries: Vec<(String, &str)> = Vec::new();
+    let mut set_options = 0;
+    for (category, listed_when_unset, category_options) in INIT_CATEGORIES {
+        let is_set = |option: &InitOption| options.iter().any(|(name, _)| *name == option.name);
+        if !listed_when_unset && !category_options.iter().any(is_set) {
+            continue;
+        }
+        if !entries.is_empty() {
+            entries.push((String::new(), ""));
+        }
+        entries.push((format!("/* {} */", category.message()), ""));
+        for option in category_options.iter() {
+            if !listed_when_unset && !is_set(option) {
+                continue;
+            }
+            let entry = match options.iter().find(|(name, _)| *name == option.name) {
+                Some((name, value)) => {
+                    set_options += 1;
+                    let comma = if set_options == options.len() {
+                        ""
+                    } else {
+                        ","
+                    };
+                    format!("\"{name}\": {value}{comma}")
+                }
+                None => format!("// \"{}\": {},", option.name, option.example),
+            };
+            entries.push((entry, option.description));
+        }
+    }
+    let margin = entries
+        .iter()
+        .map(|(entry, _)| entry.len())
+        .max()
+        .unwrap_or(0)
+        + 2;
+
+    let mut lines = vec![
+        "{".to_string(),
+        "  \"compilerOptions\": {".to_string(),
+        format!(
+            "    /* {} */",
+            diagnostics::VISIT_HTTPS_COLON_SLASH_SLASHAKA_MS_SLASHTSCONFIG_TO_READ_MORE_ABOUT_THIS_FILE_95110
+                .message()
+        ),
+        String::new(),
+    ];
+    for (entry, description) in entries {
+        lines.push(if entry.is_empty() {
+            entry
+        } else if description.is_empty() {
+            format!("    {entry}")
+        } else {
+            format!("    {entry:<margin$}/* {description} */")
+        });
+    }
+    if cli.files.is_empty() {
+        lines.push("  }".to_string());
+    } else {
+        lines.push("  },".to_string());
+        lines.push("  \"files\": [".to_string());
+        for (index, file_name) in cli.files.iter().enumerate() {
+            let mut line = "    ".to_string();
+            write_json_string(file_name, &mut line);
+            if index + 1 < cli.files.len() {
+                line.push(',');
+            }
+            lines.push(line);
+        }
+        lines.push("  ]".to_string());
+    }
+    lines.push("}".to_string());
+
+    let new_line = match cli.new_line {
+        Some(NewLine::Crlf) => "\r\n",
+        _ => "\n",
+    };
+    lines.join(new_line) + new_line
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::compile::VirtualCompilerHost;
+    use crate::config::parse_json_text;
+
+    #[test]
+    fn generates_recommended_options_with_those_of_the_command_line() {
+        let cli = Cli::parse_from(["tsrs", "--init", "--target", "es2020", "--outDir", "dist"]);
+        let text = generate_config_file(&cli);
+        let (config, diagnostics) = parse_json_text("tsrsonfig.json", &text);
+        assert!(diagnostics.is_empty());
+        let options = config
+            .unwrap()
+            .get("compilerOptions")
+            .unwrap()
+            .initializer
+            .clone();
+        let option = |name: &str| options.get(name).map(|option| option.initializer.clone());
+        assert_eq!(option("target").unwrap().as_str(), Some("es2020"));
+        assert_eq!(option("outDir").unwrap().as_str(), Some("dist"));
+        assert_eq!(option("strict").unwrap().as_bool(), Some(true));
+        assert_eq!(option("noEmit"), None);
+
+        assert!(text.contains("\n    /* Type Checking */\n"));
+        assert!(text.contains("\n    // \"noEmit\": true,  "));
+        assert!(text.contains("/* Disable emitting files from a compilation. */\n"));
+        // The last option that is set has no comma, and options about the output of the
+        // compiler are left out unless they are set
+        assert!(text.contains("\n    \"skipLibCheck\": true  "));
+        assert!(!text.contains("extendedDiagnostics"));
+
+        // Options on the command line replace the recommended ones, even to turn them off
+        let cli = Cli::parse_from(["tsrs", "--init", "--strict", "false"]);
+        assert!(generate_config_file(&cli).contains("\n    \"strict\": false,"));
+    }
+
+    #[test]
+    fn does_not_overwrite_an_existing_file() {
+        let host = VirtualCompilerHost::with_files(&[("/project/tsconfig.json", "{}")]);
+        let cli = Cli::parse_from(["tsrs", "--init", "a.ts"]);
+        let path = write_config_file("/project", &cli, &host).unwrap();
+        assert_eq!(path, "/project/tsrsonfig.json");
+        let text = host.read_file(&path).unwrap();
+        assert!(text.ends_with("  },\n  \"files\": [\n    \"a.ts\"\n  ]\n}\n"));
+
+        let diagnostic = write_config_file("/project", &cli, &host).unwrap_err();
+        assert_eq!(diagnostic.code, 5054);
+    }
+}
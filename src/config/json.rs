@@ -0,0 +1,487 @@
+//! A parser for JSON with comments and trailing commas, the format of configuration files
+//!
+//! Values keep their positions so that problems with them can be reported where they were
+//! written. Like tsc, the parser reports syntax errors and carries on, so that a typo in one
+//! option does not hide the rest of the file.
+
+use crate::compiler::diagnostics::{self, Diagnostic, Message};
+use crate::compiler::text::TextRange;
+
+/// A JSON value and where it was written
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonNode {
+    pub value: JsonValue,
+    pub loc: TextRange,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonNode>),
+    Object(Vec<JsonProperty>),
+}
+
+/// A property of a JSON object
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonProperty {
+    pub name: String,
+    pub name_loc: TextRange,
+    pub initializer: JsonNode,
+}
+
+impl JsonNode {
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            JsonValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.value {
+            JsonValue::Boolean(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonNode]> {
+        match &self.value {
+            JsonValue::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[JsonProperty]> {
+        match &self.value {
+            JsonValue::Object(properties) => Some(properties),
+            _ => None,
+        }
+    }
+
+    /// Gets the value of the last property named `name` of an object, as later properties
+    /// win in JSON
+    pub fn get(&self, name: &str) -> Option<&JsonProperty> {
+        self.as_object()?
+            .iter()
+            .rev()
+            .find(|property| property.name == name)
+    }
+}
+
+/// A value that was not read from a file
+impl From<JsonValue> for JsonNode {
+    fn from(value: JsonValue) -> Self {
+        JsonNode {
+            value,
+            loc: TextRange::default(),
+        }
+    }
+}
+
+impl JsonValue {
+    /// Writes the value as JSON text, like `JSON.stringify`: on one line when `indent` is
+    /// empty, or else with each element and property on a line of its own, indented by
+    /// `indent` for each level
+    pub fn stringify(&self, indent: &str) -> String {
+        let mut out = String::new();
+        self.write(&mut out, indent, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: &str, depth: usize) {
+        let line_break = |out: &mut String, depth: usize| {
+            if !indent.is_empty() {
+                out.push('\n');
+                out.push_str(&indent.repeat(depth));
+            }
+        };
+        match self {
+            JsonValue::Null => out.push_str("null"),
+            JsonValue::Boolean(value) => out.push_str(&value.to_string()),
+            JsonValue::Number(value) => out.push_str(&value.to_string()),
+            JsonValue::String(value) => write_json_string(value, out),
+            JsonValue::Array(elements) if elements.is_empty() => out.push_str("[]"),
+            JsonValue::Array(elements) => {
+                out.push('[');
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    line_break(out, depth + 1);
+                    element.value.write(out, indent, depth + 1);
+                }
+                line_break(out, depth);
+                out.push(']');
+            }
+            JsonValue::Object(properties) if properties.is_empty() => out.push_str("{}"),
+            JsonValue::Object(properties) => {
+                out.push('{');
+                for (index, property) in properties.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    line_break(out, depth + 1);
+                    write_json_string(&property.name, out);
+                    out.push(':');
+                    if !indent.is_empty() {
+                        out.push(' ');
+                    }
+                    property.initializer.value.write(out, indent, depth + 1);
+                }
+                line_break(out, depth);
+                out.push('}');
+            }
+        }
+    }
+}
+
+/// Parses a JSON document, returning its value, if it has one, and its syntax errors
+pub fn parse_json_text(file_name: &str, text: &str) -> (Option<JsonNode>, Vec<Diagnostic>) {
+    let mut parser = JsonParser {
+        file_name,
+        text,
+        pos: 0,
+        diagnostics: Vec::new(),
+    };
+    parser.skip_trivia();
+    let value = (parser.pos < text.len()).then(|| parser.parse_value());
+    parser.skip_trivia();
+    if parser.pos < text.len() {
+        parser.error_at(parser.pos, 1, diagnostics::UNEXPECTED_TOKEN_1012, &[]);
+    }
+    (value, parser.diagnostics)
+}
+
+/// Writes `text` as a JSON string, quoted and escaped
+pub fn write_json_string(text: &str, out: &mut String) {
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+struct JsonParser<'a> {
+    file_name: &'a str,
+    text: &'a str,
+    pos: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl JsonParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn error_at(&mut self, pos: usize, len: usize, message: &Message, args: &[&str]) {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let end = (pos + len).min(self.text.len());
+        self.diagnostics.push(Diagnostic::new(
+            Some(self.file_name),
+            TextRange::new(pos, end),
+            message,
+            &args,
+        ));
+    }
+
+    /// Skips whitespace and comments
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = &self.text[self.pos..];
+            if let Some(ch) = rest.chars().next().filter(|ch| ch.is_whitespace()) {
+                self.pos += ch.len_utf8();
+            } else if rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                match comment.find("*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => {
+                        self.pos = self.text.len();
+                        self.error_at(self.pos, 0, diagnostics::ASTERISK_SLASH_EXPECTED_1010, &[]);
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Consumes `token` if it is next, or reports that it was expected
+    fn expect(&mut self, token: char) -> bool {
+        self.skip_trivia();
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            self.error_at(
+                self.pos,
+                1,
+                diagnostics::D_0_EXPECTED_1005,
+                &[&token.to_string()],
+            );
+            false
+        }
+    }
+
+    fn parse_value(&mut self) -> JsonNode {
+        self.skip_trivia();
+        let start = self.pos;
+        let value = match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => JsonValue::String(self.parse_string('"')),
+            Some('\'') => {
+                self.error_at(
+                    start,
+                    1,
+                    diagnostics::STRING_LITERAL_WITH_DOUBLE_QUOTES_EXPECTED_1327,
+                    &[],
+                );
+                JsonValue::String(self.parse_string('\''))
+            }
+            Some(ch) if ch == '-' || ch.is_ascii_digit() => self.parse_number(),
+            Some(ch) if ch.is_alphabetic() || ch == '_' || ch == '$' => {
+                match self.scan_identifier() {
+                    "true" => JsonValue::Boolean(true),
+                    "false" => JsonValue::Boolean(false),
+                    "null" => JsonValue::Null,
+                    _ => {
+                        self.error_at(
+                            start,
+                            self.pos - start,
+                            diagnostics::EXPRESSION_EXPECTED_1109,
+                            &[],
+                        );
+                        JsonValue::Null
+                    }
+                }
+            }
+            _ => {
+                self.error_at(start, 1, diagnostics::EXPRESSION_EXPECTED_1109, &[]);
+                // Skip the token, unless it ends a list so the list can end
+                if let Some(ch) = self.peek().filter(|ch| !matches!(ch, '}' | ']' | ',')) {
+                    self.pos += ch.len_utf8();
+                }
+                JsonValue::Null
+            }
+        };
+        JsonNode {
+            value,
+            loc: TextRange::new(start, self.pos),
+        }
+    }
+
+    fn parse_object(&mut self) -> JsonValue {
+        self.pos += 1;
+        let mut properties = Vec::new();
+        loop {
+            self.skip_trivia();
+            let name_start = self.pos;
+            let name = match self.peek() {
+                None => {
+                    self.expect('}');
+                    break;
+                }
+                Some('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(quote @ ('"' | '\'')) => {
+                    if quote == '\'' {
+                        self.error_at(
+                            name_start,
+                            1,
+                            diagnostics::STRING_LITERAL_WITH_DOUBLE_QUOTES_EXPECTED_1327,
+                            &[],
+                        );
+                    }
+                    self.parse_string(quote)
+                }
+                Some(ch) if ch.is_alphabetic() || ch == '_' || ch == '$' => {
+                    let name = self.scan_identifier().to_string();
+                    self.error_at(
+                        name_start,
+                        name.len(),
+                        diagnostics::STRING_LITERAL_WITH_DOUBLE_QUOTES_EXPECTED_1327,
+                        &[],
+                    );
+                    name
+                }
+                Some(ch) => {
+                    self.error_at(
+                        name_start,
+                        1,
+                        diagnostics::PROPERTY_ASSIGNMENT_EXPECTED_1136,
+                        &[],
+                    );
+                    self.pos += ch.len_utf8();
+                    continue;
+                }
+            };
+            let name_loc = TextRange::new(name_start, self.pos);
+            self.expect(':');
+            let initializer = self.parse_value();
+            properties.push(JsonProperty {
+                name,
+                name_loc,
+                initializer,
+            });
+            if !self.parse_list_separator('}') {
+                break;
+            }
+        }
+        JsonValue::Object(properties)
+    }
+
+    fn parse_array(&mut self) -> JsonValue {
+        self.pos += 1;
+        let mut elements = Vec::new();
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                None => {
+                    self.expect(']');
+                    break;
+                }
+                Some(']') => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => {}
+            }
+            elements.push(self.parse_value());
+            if !self.parse_list_separator(']') {
+                break;
+            }
+        }
+        JsonValue::Array(elements)
+    }
+
+    /// Consumes the `,` after an element of a list, or its closing token, returning whether
+    /// the list goes on. A missing comma is reported, and the list goes on as if it were there.
+    fn parse_list_separator(&mut self, close: char) -> bool {
+        self.skip_trivia();
+        match self.peek() {
+            Some(',') => {
+                self.pos += 1;
+                true
+            }
+            Some(ch) if ch == close => {
+                self.pos += 1;
+                false
+            }
+            // The list is missing its closing token, and the token closes the list around it
+            None | Some('}' | ']') => {
+                self.expect(close);
+                false
+            }
+            Some(_) => {
+                self.expect(',');
+                true
+            }
+        }
+    }
+
+    fn scan_identifier(&mut self) -> &str {
+        let start = self.pos;
+        while let Some(ch) = self
+            .peek()
+            .filter(|&ch| ch.is_alphanumeric() || ch == '_' || ch == '$')
+        {
+            self.pos += ch.len_utf8();
+        }
+        &self.text[start..self.pos]
+    }
+
+    fn parse_number(&mut self) -> JsonValue {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        let mut end = start;
+        if bytes[end] == b'-' {
+            end += 1;
+        }
+        while end < bytes.len()
+            && (bytes[end].is_ascii_alphanumeric()
+                || bytes[end] == b'.'
+                || (matches!(bytes[end], b'+' | b'-') && matches!(bytes[end - 1], b'e' | b'E')))
+        {
+            end += 1;
+        }
+        self.pos = end;
+        match self.text[start..end].parse() {
+            Ok(value) => JsonValue::Number(value),
+            Err(_) => {
+                self.error_at(
+                    start,
+                    end - start,
+                    diagnostics::EXPRESSION_EXPECTED_1109,
+                    &[],
+                );
+                JsonValue::Null
+            }
+        }
+    }
+
+    /// Parses a string literal at the current position, decoding its escapes
+    fn parse_string(&mut self, quote: char) -> String {
+        let start = self.pos;
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            let Some(ch) = self.peek().filter(|&ch| ch != '\n' && ch != '\r') else {
+                self.error_at(
+                    start,
+                    self.pos - start,
+                    diagnostics::UNTERMINATED_STRING_LITERAL_1002,
+                    &[],
+                );
+                break;
+            };
+            self.pos += ch.len_utf8();
+            if ch == quote {
+                break;
+            }
+            if ch != '\\' {
+                value.push(ch);
+                continue;
+            }
+            let Some(escaped) = self.peek() else {
+                continue;
+            };
+            self.pos += escaped.len_utf8();
+            match escaped {
+                'b' => value.push('\u{8}'),
+                'f' => value.push('\u{c}'),
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                'u' => {
+                    let digits = self.text.get(self.pos..self.pos + 4).unwrap_or("");
+                    match u32::from_str_radix(digits, 16) {
+                        Ok(code) if digits.len() == 4 => {
+                            self.pos += 4;
+                            value.extend(
+                                char::decode_utf16([code as u16])
+                                    .map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER)),
+                            );
+                        }
+                        _ => value.push('u'),
+                    }
+                }
+                other => value.push(other),
+            }
+        }
+        value
+    }
+}
@@ -0,0 +1,240 @@
+//! Expansion of the `include` and `exclude` patterns of configuration files
+//!
+//! Patterns are paths whose components may use `*` and `?` wildcards, and `**` for any number
+//! of directories. As in tsc, wildcards never match names starting with `.`, and `**` does not
+//! descend into package folders like `node_modules`. A pattern whose last component has no
+//! wildcard or extension names a directory, and includes everything in it.
+
+use crate::compile::CompilerHost;
+use crate::compiler::paths::{combine_paths, get_path_components, get_path_from_path_components};
+
+/// Folders of installed packages, which `**` does not descend into
+const COMMON_PACKAGE_FOLDERS: [&str; 3] = ["node_modules", "bower_components", "jspm_packages"];
+
+/// Extensions of TypeScript files, in order of priority: when a folder has both `a.ts` and
+/// `a.d.ts`, only `a.ts` is part of the program
+const SUPPORTED_TS_EXTENSIONS: [&[&str]; 3] = [
+    &[".ts", ".tsx", ".d.ts"],
+    &[".cts", ".d.cts"],
+    &[".mts", ".d.mts"],
+];
+
+/// Extensions of JavaScript files, which only take part in a program with `allowJs`
+const SUPPORTED_JS_EXTENSIONS: [&[&str]; 3] = [&[".js", ".jsx"], &[".cjs"], &[".mjs"]];
+
+/// Finds the files under `base_path` matched by any of `includes` and none of `excludes`,
+/// whose patterns are relative to `base_path`. Files are listed in the order of the pattern
+/// that first matches them, and within it in the order of a walk through sorted directories.
+pub fn match_files(
+    host: &impl CompilerHost,
+    base_path: &str,
+    includes: &[String],
+    excludes: &[String],
+    allow_js: bool,
+) -> Vec<String> {
+    if includes.is_empty() {
+        return Vec::new();
+    }
+    let extensions: Vec<Vec<&str>> = SUPPORTED_TS_EXTENSIONS
+        .iter()
+        .zip(SUPPORTED_JS_EXTENSIONS)
+        .map(|(ts, js)| {
+            if allow_js {
+                [*ts, js].concat()
+            } else {
+                ts.to_vec()
+            }
+        })
+        .collect();
+    let files = host.read_directory(base_path, &extensions.concat(), excludes, includes, None);
+
+    // Drop files that a file of a higher priority extension in the same folder supersedes
+    let superseded: Vec<bool> = files
+        .iter()
+        .map(|file| {
+            let Some((family, priority)) = extension_of(file, &extensions) else {
+                return false;
+            };
+            let stem = &file[..file.len() - extensions[family][priority].len()];
+            extensions[family][..priority]
+                .iter()
+                .any(|extension| files.contains(&format!("{stem}{extension}")))
+        })
+        .collect();
+    files
+        .into_iter()
+        .zip(superseded)
+        .filter(|(_, superseded)| !superseded)
+        .map(|(file, _)| file)
+        .collect()
+}
+
+/// Finds the files under `path` with one of `extensions`, or with any extension if none are
+/// given, that are matched by any of `includes` and none of `excludes`, whose patterns are
+/// relative to `path`. Without `includes`, every file is matched. With `depth`, only files at
+/// most that many folders down are, so that 1 lists the files directly in `path`.
+///
+/// Files are listed in the order of the pattern that first matches them, and within it in the
+/// order of a walk through sorted directories.
+pub fn read_directory(
+    host: &(impl CompilerHost + ?Sized),
+    path: &str,
+    extensions: &[&str],
+    excludes: &[String],
+    includes: &[String],
+    depth: Option<usize>,
+) -> Vec<String> {
+    let root_depth = get_path_components(path).len();
+    let excludes: Vec<Vec<String>> = excludes
+        .iter()
+        .map(|exclude| get_path_components(&combine_paths(path, exclude)))
+        .collect();
+    let everything = ["**/*".to_string()];
+    let includes = if includes.is_empty() {
+        &everything[..]
+    } else {
+        includes
+    };
+
+    let mut files = Vec::new();
+    for include in includes {
+        let mut pattern = get_path_components(&combine_paths(path, include));
+        if pattern
+            .last()
+            .is_some_and(|last| !last.contains(['.', '*', '?']))
+        {
+            pattern.extend(["**".to_string(), "*".to_string()]);
+        }
+        // Walk from the deepest folder that the pattern names without wildcards
+        let literal_prefix = pattern
+            .iter()
+            .take_while(|component| !component.contains(['*', '?']))
+            .count()
+            .min(pattern.len() - 1);
+        let directory = match get_path_from_path_components(&pattern[..literal_prefix]) {
+            directory if directory.is_empty() => ".".to_string(),
+            directory => directory,
+        };
+        // The depth counts from `path`, which the walk may start below
+        let start_depth = literal_prefix.saturating_sub(root_depth);
+        let levels = depth.map(|depth| depth.saturating_sub(start_depth));
+        let mut matched = Vec::new();
+        visit_directory(host, &directory, levels, &mut |file| {
+            let components = get_path_components(file);
+            let lower = file.to_ascii_lowercase();
+            if matches_pattern(&pattern, &components, false)
+                && !excludes
+                    .iter()
+                    .any(|exclude| matches_pattern(exclude, &components, true))
+                && (extensions.is_empty()
+                    || extensions
+                        .iter()
+                        .any(|extension| lower.ends_with(extension)))
+            {
+                matched.push(file.to_string());
+            }
+        });
+        for file in matched {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    files
+}
+
+/// Finds which of `extensions` a file has, as the index of its family and its priority in it
+fn extension_of(path: &str, extensions: &[Vec<&str>]) -> Option<(usize, usize)> {
+    let lower = path.to_ascii_lowercase();
+    let mut best: Option<(usize, usize, usize)> = None;
+    for (family, family_extensions) in extensions.iter().enumerate() {
+        for (priority, extension) in family_extensions.iter().enumerate() {
+            // `.d.ts` is more specific than `.ts`
+            if lower.ends_with(extension)
+                && best.is_none_or(|(.., length)| extension.len() > length)
+            {
+                best = Some((family, priority, extension.len()));
+            }
+        }
+    }
+    best.map(|(family, priority, _)| (family, priority))
+}
+
+/// Calls `callback` with every file under `directory`, or with `levels`, in at most that many
+/// levels of folders from it, listing files before subfolders, and each in sorted order
+fn visit_directory(
+    host: &(impl CompilerHost + ?Sized),
+    directory: &str,
+    levels: Option<usize>,
+    callback: &mut impl FnMut(&str),
+) {
+    if levels == Some(0) {
+        return;
+    }
+    let mut entries = host.get_accessible_entries(directory);
+    entries.files.sort();
+    entries.directories.sort();
+    for file in &entries.files {
+        callback(&combine_paths(directory, file));
+    }
+    for subdirectory in &entries.directories {
+        let directory = combine_paths(directory, subdirectory);
+        visit_directory(host, &directory, levels.map(|levels| levels - 1), callback);
+    }
+}
+
+/// Whether the path `components` match the `pattern` components, or with `prefix`, whether
+/// some folder containing the path does
+fn matches_pattern(pattern: &[String], components: &[String], prefix: bool) -> bool {
+    match pattern.split_first() {
+        None => prefix || components.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            // `**` matches any number of folders, but not hidden or package folders
+            (0..=components.len()).any(|skipped| {
+                components[..skipped].iter().all(|component| {
+                    !component.starts_with('.')
+                        && !COMMON_PACKAGE_FOLDERS.contains(&component.as_str())
+                }) && matches_pattern(rest, &components[skipped..], prefix)
+            })
+        }
+        Some((first, rest)) => components.split_first().is_some_and(|(component, others)| {
+            matches_component(first, component) && matches_pattern(rest, others, prefix)
+        }),
+    }
+}
+
+/// Whether a path component matches a pattern component with `*` and `?` wildcards
+fn matches_component(pattern: &str, component: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return pattern == component;
+    }
+    if component.starts_with('.') && !pattern.starts_with('.') {
+        return false;
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let component: Vec<char> = component.chars().collect();
+    // Match characters one by one, and on a mismatch, let the last `*` take one more character
+    let (mut p, mut c) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while c < component.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, c));
+                p += 1;
+            }
+            Some(&ch) if ch == '?' || ch == component[c] => {
+                p += 1;
+                c += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    c = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
@@ -0,0 +1,93 @@
+//! Editor features over a program, like finding the references to a declaration
+//!
+//! A language service answers questions about positions in the files of a program, using a
+//! checker over the whole program to find what the names there refer to. The checker
+//! resolves aliases only within a file, so imports of other files of the program are followed
+//! here, through the modules the program resolved them to.
+
+use std::sync::Arc;
+
+use crate::compile::{Diagnostic, Program, convert_diagnostic};
+use crate::compiler::ast::utilities::get_error_range;
+use crate::compiler::ast::{Node, SyntaxKind};
+use crate::compiler::checker::Checker;
+
+mod classification;
+mod code_fixes;
+mod completions;
+mod formatting;
+mod navigation_tree;
+mod organize_imports;
+mod quick_info;
+mod references;
+
+pub use classification::{
+    SemanticClassification, TOKEN_MODIFIERS, TOKEN_TYPES, TokenModifiers, TokenType,
+    encode_semantic_tokens,
+};
+pub use code_fixes::{CodeFixAction, apply_code_fixes, apply_text_edits, get_supported_code_fixes};
+pub use completions::{CompletionEntry, CompletionInfo};
+pub use formatting::{FormatOptions, TrailingCommas, format_files, get_formatting_edits};
+pub use navigation_tree::{NavigationTree, ScriptElementKind};
+pub use organize_imports::OrganizeImportsOptions;
+pub use quick_info::{JSDocTagInfo, QuickInfo, SymbolDisplayPart, SymbolDisplayPartKind};
+pub use references::{ReferenceEntry, TextEdit};
+
+/// Answers questions about positions in the files of a program
+pub struct LanguageService<'a> {
+    program: &'a Program,
+    checker: Checker,
+}
+
+impl<'a> LanguageService<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        LanguageService {
+            program,
+            checker: program.get_type_checker(),
+        }
+    }
+
+    /// Gets the semantic diagnostics of the statement at a position of a file, checking only
+    /// that statement and the declarations it depends on, which other files are bound but
+    /// not checked for, so that an editor can show them quickly in a large program
+    pub fn get_semantic_diagnostics_at_position(
+        &mut self,
+        file_name: &str,
+        pos: usize,
+    ) -> Vec<Diagnostic> {
+        let (Some(source_file), Some(tree)) = (
+            self.program.get_source_file(file_name),
+            self.program.get_syntax_tree(file_name),
+        ) else {
+            return Vec::new();
+        };
+        self.checker
+            .get_diagnostics_at_position(tree, pos)
+            .iter()
+            .map(|diagnostic| convert_diagnostic(source_file, diagnostic))
+            .collect()
+    }
+
+    /// Finds the identifier that a position of a file is in or just after
+    fn get_identifier_at_position(&self, file_name: &str, pos: usize) -> Option<Arc<Node>> {
+        let tree = self.program.get_syntax_tree(file_name)?;
+        get_touching_identifier(tree, tree, pos)
+    }
+}
+
+fn get_touching_identifier(node: &Node, tree: &Node, pos: usize) -> Option<Arc<Node>> {
+    let mut found = None;
+    node.for_each_child(&mut |child| {
+        if child.pos() > pos || child.end() < pos {
+            // The children are in source order, so none after this one can contain it
+            return child.pos() > pos;
+        }
+        found = if child.kind == SyntaxKind::Identifier {
+            (get_error_range(tree, child).pos() <= pos).then(|| Arc::clone(child))
+        } else {
+            get_touching_identifier(child, tree, pos)
+        };
+        found.is_some()
+    });
+    found
+}
@@ -0,0 +1,402 @@
+//! Semantic classification of the names in a file, for highlighting them by what they are
+//!
+//! Each identifier that resolves to a symbol is classified by the kind of that symbol, like a
+//! class or a parameter, with modifiers for how it is declared, like `readonly` or `static`.
+//! The token types and modifiers are those tsserver uses, in the same order, so that the
+//! encoding of a classification matches the one of its `2020` format, and the legend of a
+//! language server can list them as they are.
+
+use std::sync::Arc;
+
+use bitflags::bitflags;
+
+use super::LanguageService;
+use super::references::is_declaration_name;
+use crate::compile::SourceFile;
+use crate::compiler::ast::utilities::{
+    get_error_range, get_source_file_of_node, is_const_variable, is_static, root_declaration,
+};
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::text::{
+    LineAndCharacter, TextRange, position_to_utf16_line_and_character, utf16_length,
+};
+
+/// The names of the token types, by the index of each [`TokenType`]
+pub const TOKEN_TYPES: &[&str] = &[
+    "class",
+    "enum",
+    "interface",
+    "namespace",
+    "typeParameter",
+    "type",
+    "parameter",
+    "variable",
+    "enumMember",
+    "property",
+    "function",
+    "member",
+];
+
+/// The names of the token modifiers, by the bit of each of [`TokenModifiers`]
+pub const TOKEN_MODIFIERS: &[&str] = &[
+    "declaration",
+    "static",
+    "async",
+    "readonly",
+    "defaultLibrary",
+    "local",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenType {
+    Class,
+    Enum,
+    Interface,
+    Namespace,
+    TypeParameter,
+    Type,
+    Parameter,
+    Variable,
+    EnumMember,
+    Property,
+    Function,
+    /// A method
+    Member,
+}
+
+bitflags! {
+    /// How the symbol a token names is declared
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct TokenModifiers: u32 {
+        /// The token is the name of a declaration, rather than a use of it
+        const DECLARATION = 1 << 0;
+        const STATIC = 1 << 1;
+        const ASYNC = 1 << 2;
+        /// A `const` variable or `readonly` property
+        const READONLY = 1 << 3;
+        /// Declared by a standard library file
+        const DEFAULT_LIBRARY = 1 << 4;
+        /// A variable or function declared within a function, block, or namespace, rather
+        /// than at the top level of a file
+        const LOCAL = 1 << 5;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticClassification {
+    pub span: TextRange,
+    pub token_type: TokenType,
+    pub modifiers: TokenModifiers,
+}
+
+impl SemanticClassification {
+    /// Encodes the classification as tsserver does, with the token type plus one above the
+    /// lowest eight bits and the modifiers in them
+    pub fn encoded(&self) -> u32 {
+        ((self.token_type as u32 + 1) << 8) | self.modifiers.bits()
+    }
+}
+
+impl LanguageService<'_> {
+    /// Classifies the names within a span of a file by the symbols they refer to, in the
+    /// order they appear
+    pub fn get_semantic_classifications(
+        &mut self,
+        file_name: &str,
+        span: TextRange,
+    ) -> Vec<SemanticClassification> {
+        let Some(tree) = self.program.get_syntax_tree(file_name) else {
+            return Vec::new();
+        };
+        let mut identifiers = Vec::new();
+        collect_identifiers_in_span(tree, span, &mut identifiers);
+        identifiers
+            .iter()
+            .filter_map(|identifier| self.classify_identifier(tree, identifier))
+            .filter(|classification| {
+                span.pos() <= classification.span.pos() && classification.span.end() <= span.end()
+            })
+            .collect()
+    }
+
+    fn classify_identifier(
+        &mut self,
+        tree: &Node,
+        identifier: &Arc<Node>,
+    ) -> Option<SemanticClassification> {
+        let symbol = self.checker.get_symbol_at_location(identifier)?;
+        let symbol = self.resolve_alias(symbol);
+        let token_type = self.classify_symbol(symbol)?;
+        let mut modifiers = TokenModifiers::empty();
+        if is_declaration_name(identifier) {
+            modifiers |= TokenModifiers::DECLARATION;
+        }
+        let declaration = self
+            .checker
+            .symbol(symbol)
+            .value_declaration
+            .clone()
+            .or_else(|| self.checker.symbol(symbol).declarations.first().cloned());
+        if let Some(declaration) = declaration {
+            modifiers |= self.declaration_modifiers(&declaration, token_type);
+        }
+        Some(SemanticClassification {
+            span: get_error_range(tree, identifier),
+            token_type,
+            modifiers,
+        })
+    }
+
+    fn classify_symbol(&self, symbol: SymbolId) -> Option<TokenType> {
+        let symbol = self.checker.symbol(symbol);
+        let flags = symbol.flags;
+        let token_type = if flags.contains(SymbolFlags::CLASS) {
+            TokenType::Class
+        } else if flags.intersects(SymbolFlags::ENUM) {
+            TokenType::Enum
+        } else if flags.contains(SymbolFlags::TYPE_ALIAS) {
+            TokenType::Type
+        } else if flags.contains(SymbolFlags::INTERFACE) {
+            TokenType::Interface
+        } else if flags.contains(SymbolFlags::TYPE_PARAMETER) {
+            TokenType::TypeParameter
+        } else if flags.intersects(SymbolFlags::PROPERTY | SymbolFlags::ACCESSOR) {
+            TokenType::Property
+        } else if flags.contains(SymbolFlags::METHOD) {
+            TokenType::Member
+        } else if flags.contains(SymbolFlags::ENUM_MEMBER) {
+            TokenType::EnumMember
+        } else if flags.contains(SymbolFlags::FUNCTION) {
+            TokenType::Function
+        } else if flags.intersects(SymbolFlags::MODULE) {
+            TokenType::Namespace
+        } else if flags.intersects(SymbolFlags::VARIABLE) {
+            let is_parameter = symbol
+                .value_declaration
+                .as_ref()
+                .is_some_and(|declaration| {
+                    root_declaration(declaration).kind == SyntaxKind::Parameter
+                });
+            if is_parameter {
+                TokenType::Parameter
+            } else {
+                TokenType::Variable
+            }
+        } else {
+            return None;
+        };
+        Some(token_type)
+    }
+
+    fn declaration_modifiers(
+        &self,
+        declaration: &Arc<Node>,
+        token_type: TokenType,
+    ) -> TokenModifiers {
+        let mut modifiers = TokenModifiers::empty();
+        if is_static(declaration) {
+            modifiers |= TokenModifiers::STATIC;
+        }
+        if declaration.has_modifier(SyntaxKind::AsyncKeyword) {
+            modifiers |= TokenModifiers::ASYNC;
+        }
+        let is_const = matches!(
+            root_declaration(declaration).kind,
+            SyntaxKind::VariableDeclaration
+        ) && is_const_variable(declaration);
+        if is_const || declaration.has_modifier(SyntaxKind::ReadonlyKeyword) {
+            modifiers |= TokenModifiers::READONLY;
+        }
+        let file = get_source_file_of_node(declaration);
+        if self
+            .program
+            .is_default_lib_file(&file.as_source_file().file_name)
+        {
+            modifiers |= TokenModifiers::DEFAULT_LIBRARY;
+        }
+        if matches!(token_type, TokenType::Variable | TokenType::Function)
+            && is_local_declaration(declaration)
+        {
+            modifiers |= TokenModifiers::LOCAL;
+        }
+        modifiers
+    }
+}
+
+/// Encodes classifications as the data of an LSP `SemanticTokens` response: five numbers for
+/// each token, which are its line and start relative to the token before it, its length,
+/// its type, and its modifiers, with positions in UTF-16 code units
+pub fn encode_semantic_tokens(
+    source_file: &SourceFile,
+    classifications: &[SemanticClassification],
+) -> Vec<u32> {
+    let text = &source_file.text;
+    let mut data = Vec::with_capacity(classifications.len() * 5);
+    let (mut previous_line, mut previous_character) = (0, 0);
+    for classification in classifications {
+        let pos = classification.span.pos();
+        let LineAndCharacter { line, character } =
+            position_to_utf16_line_and_character(text, &source_file.line_map, pos);
+        let character = character as u32;
+        let delta_character = if line == previous_line {
+            character - previous_character
+        } else {
+            character
+        };
+        data.extend([
+            (line - previous_line) as u32,
+            delta_character,
+            utf16_length(&text[pos..classification.span.end()]) as u32,
+            classification.token_type as u32,
+            classification.modifiers.bits(),
+        ]);
+        (previous_line, previous_character) = (line, character);
+    }
+    data
+}
+
+/// Whether a variable or function is declared within a function, block, or namespace rather
+/// than at the top level of a file
+fn is_local_declaration(declaration: &Arc<Node>) -> bool {
+    let declaration = root_declaration(declaration);
+    let container = match declaration.kind {
+        SyntaxKind::VariableDeclaration => {
+            let list = declaration.parent();
+            if list
+                .as_ref()
+                .is_some_and(|list| list.kind == SyntaxKind::CatchClause)
+            {
+                return true;
+            }
+            // The declaration is in a list, which is in a statement
+            list.and_then(|list| list.parent())
+                .and_then(|statement| statement.parent())
+        }
+        _ => declaration.parent(),
+    };
+    container.is_some_and(|container| container.kind != SyntaxKind::SourceFile)
+}
+
+fn collect_identifiers_in_span(
+    node: &Arc<Node>,
+    span: TextRange,
+    identifiers: &mut Vec<Arc<Node>>,
+) {
+    node.for_each_child(&mut |child| {
+        if child.end() < span.pos() {
+            return false;
+        }
+        if child.pos() > span.end() {
+            return true;
+        }
+        if child.kind == SyntaxKind::Identifier {
+            identifiers.push(Arc::clone(child));
+        } else {
+            collect_identifiers_in_span(child, span, identifiers);
+        }
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::VirtualCompilerHost;
+    use crate::compile::tests::create;
+
+    /// Describes each classification by its text, type, and modifiers
+    fn describe(text: &str, classifications: &[SemanticClassification]) -> Vec<String> {
+        classifications
+            .iter()
+            .map(|classification| {
+                let name = &text[classification.span.pos()..classification.span.end()];
+                let mut description =
+                    format!("{name} {}", TOKEN_TYPES[classification.token_type as usize]);
+                for (bit, modifier) in TOKEN_MODIFIERS.iter().enumerate() {
+                    if classification.modifiers.bits() & (1 << bit) != 0 {
+                        description += &format!(" {modifier}");
+                    }
+                }
+                description
+            })
+            .collect()
+    }
+
+    #[test]
+    fn classifies_names_by_their_symbols() {
+        let text = "\
+import { Shape } from \"./b\";
+class Point implements Shape {
+    static readonly origin = 0;
+    async move(dx: number) { const step = dx; return step; }
+}
+enum Color { Red }
+let color = Color.Red;
+";
+        let program = create(
+            &VirtualCompilerHost::with_files(&[
+                ("a.ts", text),
+                ("b.ts", "export interface Shape {}"),
+            ]),
+            &["a.ts"],
+        );
+        let mut service = LanguageService::new(&program);
+        let classifications =
+            service.get_semantic_classifications("a.ts", TextRange::new(0, text.len()));
+        assert_eq!(
+            describe(text, &classifications),
+            [
+                "Shape interface",
+                "Point class declaration",
+                "Shape interface",
+                "origin property declaration static readonly",
+                "move member declaration async",
+                "dx parameter declaration",
+                "step variable declaration readonly local",
+                "dx parameter",
+                "step variable readonly local",
+                "Color enum declaration",
+                "Red enumMember declaration",
+                "color variable declaration",
+                "Color enum",
+                "Red enumMember",
+            ]
+        );
+        assert_eq!(classifications[1].encoded(), (1 << 8) | 1);
+
+        // Only the names within the span are classified
+        let start = text.find("enum").unwrap();
+        let classifications =
+            service.get_semantic_classifications("a.ts", TextRange::new(start, start + 18));
+        assert_eq!(
+            describe(text, &classifications),
+            ["Color enum declaration", "Red enumMember declaration"]
+        );
+    }
+
+    #[test]
+    fn encodes_tokens_relative_to_each_other() {
+        let text = "let a = 1;\nlet \u{e9}b = a, c = a;\n";
+        let program = create(
+            &VirtualCompilerHost::with_files(&[("a.ts", text)]),
+            &["a.ts"],
+        );
+        let mut service = LanguageService::new(&program);
+        let classifications =
+            service.get_semantic_classifications("a.ts", TextRange::new(0, text.len()));
+        let source_file = program.get_source_file("a.ts").unwrap();
+        let variable = TokenType::Variable as u32;
+        let declaration = TokenModifiers::DECLARATION.bits();
+        let data = encode_semantic_tokens(source_file, &classifications);
+        assert_eq!(
+            data.chunks(5).collect::<Vec<_>>(),
+            [
+                [0, 4, 1, variable, declaration],
+                [1, 4, 2, variable, declaration],
+                [0, 5, 1, variable, 0],
+                [0, 3, 1, variable, declaration],
+                [0, 4, 1, variable, 0],
+            ]
+        );
+    }
+}
@@ -0,0 +1,655 @@
+//! Fixes for errors, as edits to the files of a program
+//!
+//! Each fix handles the errors with some codes, and gives the actions that fix one of them
+//! at its span, like adding an import for a name that cannot be found. The fixes are listed
+//! in [`CODE_FIXES`], which is looked up by the code of an error. An action holds all of its
+//! edits, so that an editor can preview it or apply it at once.
+
+use std::sync::Arc;
+
+use super::references::{is_declaration_name, is_identifier_text};
+use super::{LanguageService, TextEdit, get_touching_identifier};
+use crate::compile::{CompilerHost, Program};
+use crate::compiler::ast::utilities::{
+    get_error_range, get_property_name_text, get_text_of_node, is_external_module,
+    is_parameter_property_declaration, skip_parentheses,
+};
+use crate::compiler::ast::{Node, SymbolFlags, SyntaxKind};
+use crate::compiler::diagnostics::{self, Message, format_message};
+use crate::compiler::text::TextRange;
+use crate::compiler::transformers::ModuleKind;
+
+/// A way of fixing an error
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeFixAction {
+    /// The name of the fix that gave the action, like `import`
+    pub fix_name: &'static str,
+    pub description: String,
+    pub changes: Vec<TextEdit>,
+}
+
+/// A fix for the errors with some codes
+struct CodeFix {
+    error_codes: &'static [&'static Message],
+    get_actions: fn(&mut LanguageService, &FixContext) -> Vec<CodeFixAction>,
+}
+
+/// Where an error to fix is
+struct FixContext {
+    file_name: String,
+    tree: Arc<Node>,
+    span: TextRange,
+}
+
+static CODE_FIXES: &[CodeFix] = &[
+    CodeFix {
+        error_codes: &[diagnostics::CANNOT_FIND_NAME_0_2304],
+        get_actions: fix_missing_import,
+    },
+    CodeFix {
+        error_codes: &[diagnostics::D_0_IS_DECLARED_BUT_ITS_VALUE_IS_NEVER_READ_6133],
+        get_actions: fix_prefix_unused_identifier,
+    },
+    CodeFix {
+        error_codes: &[
+            diagnostics::PROPERTY_0_IS_MISSING_IN_TYPE_1_BUT_REQUIRED_IN_TYPE_2_2741,
+            diagnostics::TYPE_0_IS_MISSING_THE_FOLLOWING_PROPERTIES_FROM_TYPE_1_COLON_2_2739,
+            diagnostics::TYPE_0_IS_MISSING_THE_FOLLOWING_PROPERTIES_FROM_TYPE_1_COLON_2_AND_3_MORE_2740,
+        ],
+        get_actions: fix_missing_properties,
+    },
+    CodeFix {
+        error_codes: &[diagnostics::REQUIRE_CALL_MAY_BE_CONVERTED_TO_AN_IMPORT_80005],
+        get_actions: fix_require,
+    },
+];
+
+/// The codes of the errors that have fixes
+pub fn get_supported_code_fixes() -> Vec<i32> {
+    CODE_FIXES
+        .iter()
+        .flat_map(|fix| fix.error_codes.iter().map(|message| message.code()))
+        .collect()
+}
+
+/// Applies edits to the text of a file, which must all be edits of that file that do not
+/// overlap
+pub fn apply_text_edits(text: &str, edits: &[TextEdit]) -> String {
+    let mut edits: Vec<&TextEdit> = edits.iter().collect();
+    // Edits at the same position go in the order they were given
+    edits.sort_by_key(|edit| edit.range.pos());
+    let mut result = String::with_capacity(text.len());
+    let mut pos = 0;
+    for edit in edits {
+        result.push_str(&text[pos..edit.range.pos()]);
+        result.push_str(&edit.new_text);
+        pos = edit.range.end();
+    }
+    result.push_str(&text[pos..]);
+    result
+}
+
+/// Fixes every error of a program that has a fix, writing the fixed files through the host,
+/// and returns the names of the files written
+pub fn apply_code_fixes(program: &Program, host: &impl CompilerHost) -> Vec<String> {
+    let edits = LanguageService::new(program).get_combined_code_fixes();
+    let mut file_names: Vec<String> = Vec::new();
+    for edit in &edits {
+        if !file_names.contains(&edit.file_name) {
+            file_names.push(edit.file_name.clone());
+        }
+    }
+    file_names.retain(|file_name| {
+        let source_file = program
+            .get_source_file(file_name)
+            .expect("fixes are in files of the program");
+        let file_edits: Vec<TextEdit> = edits
+            .iter()
+            .filter(|edit| edit.file_name == *file_name)
+            .cloned()
+            .collect();
+        host.write_file(file_name, &apply_text_edits(&source_file.text, &file_edits))
+            .is_ok()
+    });
+    file_names
+}
+
+impl LanguageService<'_> {
+    /// Gets the actions that fix the error with a code at a span of a file
+    pub fn get_code_fixes_at_position(
+        &mut self,
+        file_name: &str,
+        span: TextRange,
+        error_code: i32,
+    ) -> Vec<CodeFixAction> {
+        let Some(tree) = self.program.get_syntax_tree(file_name) else {
+            return Vec::new();
+        };
+        let context = FixContext {
+            file_name: tree.as_source_file().file_name.clone(),
+            tree: Arc::clone(tree),
+            span,
+        };
+        let mut actions = Vec::new();
+        for fix in CODE_FIXES {
+            if fix
+                .error_codes
+                .iter()
+                .any(|message| message.code() == error_code)
+            {
+                actions.extend((fix.get_actions)(self, &context));
+            }
+        }
+        actions
+    }
+
+    /// Gets the edits that fix every error of the program that has a fix, taking the first
+    /// action for each and leaving out those that overlap an edit already taken
+    pub fn get_combined_code_fixes(&mut self) -> Vec<TextEdit> {
+        let mut edits: Vec<TextEdit> = Vec::new();
+        let program = self.program;
+        for (source_file, tree) in program.source_files.iter().zip(&program.syntax_trees) {
+            if tree.as_source_file().is_declaration_file
+                || program.is_default_lib_file(&source_file.file_name)
+            {
+                continue;
+            }
+            for diagnostic in self.checker.get_diagnostics(tree) {
+                let actions = self.get_code_fixes_at_position(
+                    &source_file.file_name,
+                    diagnostic.loc,
+                    diagnostic.code,
+                );
+                let Some(action) = actions.into_iter().next() else {
+                    continue;
+                };
+                let overlaps = action.changes.iter().any(|change| {
+                    edits.iter().any(|edit| {
+                        edit.file_name == change.file_name
+                            && edit.range.pos() < change.range.end().max(change.range.pos() + 1)
+                            && change.range.pos() < edit.range.end().max(edit.range.pos() + 1)
+                    })
+                });
+                if !overlaps {
+                    edits.extend(action.changes);
+                }
+            }
+        }
+        edits
+    }
+}
+
+/// Adds an import of a name that cannot be found from each module of the program that
+/// exports it
+fn fix_missing_import(service: &mut LanguageService, context: &FixContext) -> Vec<CodeFixAction> {
+    let Some(identifier) =
+        get_touching_identifier(&context.tree, &context.tree, context.span.pos())
+    else {
+        return Vec::new();
+    };
+    let name = identifier.text();
+    let program = service.program;
+    let mut actions = Vec::new();
+    for (source_file, module_tree) in program.source_files.iter().zip(&program.syntax_trees) {
+        if source_file.file_name == context.file_name
+            || !is_external_module(module_tree)
+            || source_file
+                .file_name
+                .split('/')
+                .any(|part| part == "node_modules")
+        {
+            continue;
+        }
+        let Some(module_symbol) = service.checker.get_symbol_of_declaration(module_tree) else {
+            continue;
+        };
+        if !service
+            .checker
+            .symbol(module_symbol)
+            .exports
+            .contains_key(name)
+        {
+            continue;
+        }
+        let module_name =
+            program.get_relative_module_name(&context.file_name, &source_file.file_name);
+        let changes = vec![add_named_import(
+            &context.file_name,
+            &context.tree,
+            name,
+            &module_name,
+        )];
+        actions.push(CodeFixAction {
+            fix_name: "import",
+            description: format_message(diagnostics::ADD_IMPORT_FROM_0_90057, &[module_name]),
+            changes,
+        });
+    }
+    actions
+}
+
+/// Adds a name to the import of a module, or a new import after the others when the file
+/// does not import it by name yet
+pub(super) fn add_named_import(
+    file_name: &str,
+    tree: &Arc<Node>,
+    name: &str,
+    module_name: &str,
+) -> TextEdit {
+    let source_file = tree.as_source_file();
+    let imports: Vec<&Arc<Node>> = source_file
+        .statements
+        .iter()
+        .filter(|statement| statement.kind == SyntaxKind::ImportDeclaration)
+        .collect();
+    let edit = |pos: usize, new_text: String| TextEdit {
+        file_name: file_name.to_string(),
+        range: TextRange::new(pos, pos),
+        new_text,
+    };
+    for import in &imports {
+        let declaration = import.as_import_declaration();
+        if declaration.module_specifier.text() != module_name {
+            continue;
+        }
+        let named_imports = declaration
+            .import_clause
+            .as_ref()
+            .and_then(|clause| clause.as_import_clause().named_bindings.as_ref())
+            .filter(|bindings| bindings.kind == SyntaxKind::NamedImports);
+        if let Some(last) =
+            named_imports.and_then(|bindings| bindings.as_named_imports().elements.iter().last())
+        {
+            return edit(last.end(), format!(", {name}"));
+        }
+    }
+    // Match the quotes of the imports already there
+    let quote = imports
+        .first()
+        .and_then(|import| {
+            get_text_of_node(tree, &import.as_import_declaration().module_specifier)
+                .chars()
+                .next()
+        })
+        .unwrap_or('"');
+    let statement = format!("import {{ {name} }} from {quote}{module_name}{quote};");
+    match imports.last() {
+        Some(last) => edit(last.end(), format!("\n{statement}")),
+        None => {
+            let pos = source_file
+                .statements
+                .iter()
+                .next()
+                .map_or(0, |first| get_error_range(tree, first).pos());
+            edit(pos, format!("{statement}\n\n"))
+        }
+    }
+}
+
+/// Prefixes an unused name with `_`, where that marks it as unused on purpose: a parameter,
+/// the variable of a `for`-`in` or `for`-`of` loop, or an element of an object pattern
+fn fix_prefix_unused_identifier(
+    _service: &mut LanguageService,
+    context: &FixContext,
+) -> Vec<CodeFixAction> {
+    let Some(identifier) =
+        get_touching_identifier(&context.tree, &context.tree, context.span.pos())
+    else {
+        return Vec::new();
+    };
+    let Some(declaration) = identifier.parent() else {
+        return Vec::new();
+    };
+    let name = identifier.text();
+    if name.starts_with('_') || !is_declaration_name(&identifier) {
+        return Vec::new();
+    }
+    let start = get_error_range(&context.tree, &identifier).pos();
+    let new_text = match declaration.kind {
+        SyntaxKind::Parameter if !is_parameter_property_declaration(&declaration) => {
+            "_".to_string()
+        }
+        SyntaxKind::VariableDeclaration
+            if declaration
+                .parent()
+                .and_then(|list| list.parent())
+                .is_some_and(|parent| {
+                    matches!(
+                        parent.kind,
+                        SyntaxKind::ForInStatement | SyntaxKind::ForOfStatement
+                    )
+                }) =>
+        {
+            "_".to_string()
+        }
+        SyntaxKind::BindingElement
+            if declaration
+                .parent()
+                .is_some_and(|pattern| pattern.kind == SyntaxKind::ObjectBindingPattern) =>
+        {
+            // A shorthand element keeps the property it takes, under a new name
+            if declaration.as_binding_element().property_name.is_some() {
+                "_".to_string()
+            } else {
+                format!("{name}: _")
+            }
+        }
+        _ => return Vec::new(),
+    };
+    vec![CodeFixAction {
+        fix_name: "unusedIdentifier_prefix",
+        description: format_message(
+            diagnostics::PREFIX_0_WITH_AN_UNDERSCORE_90025,
+            &[name.to_string()],
+        ),
+        changes: vec![TextEdit {
+            file_name: context.file_name.clone(),
+            range: TextRange::new(start, start),
+            new_text,
+        }],
+    }]
+}
+
+/// Adds the required properties an object literal lacks, when it initializes a variable
+/// with a declared type
+fn fix_missing_properties(
+    service: &mut LanguageService,
+    context: &FixContext,
+) -> Vec<CodeFixAction> {
+    let Some(identifier) =
+        get_touching_identifier(&context.tree, &context.tree, context.span.pos())
+    else {
+        return Vec::new();
+    };
+    let Some(declaration) = identifier
+        .parent()
+        .filter(|parent| parent.kind == SyntaxKind::VariableDeclaration)
+    else {
+        return Vec::new();
+    };
+    let (Some(type_node), Some(initializer)) = (declaration.type_node(), declaration.initializer())
+    else {
+        return Vec::new();
+    };
+    let literal = skip_parentheses(initializer);
+    if literal.kind != SyntaxKind::ObjectLiteralExpression {
+        return Vec::new();
+    }
+    let properties = &literal.as_object_literal_expression().properties;
+    let mut present = Vec::new();
+    for property in properties.iter() {
+        // The properties that a spread adds are not known from the syntax
+        match property
+            .name()
+            .and_then(|name| get_property_name_text(name))
+        {
+            Some(name) if property.kind != SyntaxKind::SpreadAssignment => present.push(name),
+            _ => return Vec::new(),
+        }
+    }
+
+    let checker = &mut service.checker;
+    let target = checker.get_type_from_type_node(type_node);
+    let mut missing = Vec::new();
+    for property in checker.get_properties_of_type(target) {
+        let symbol = checker.symbol(property);
+        if symbol.flags.contains(SymbolFlags::OPTIONAL) || present.contains(&symbol.name) {
+            continue;
+        }
+        let name = symbol.name.clone();
+        let type_ = checker.get_type_of_symbol(property);
+        let value = default_value_for_type(&checker.type_to_string(type_));
+        let name = if is_identifier_text(&name) {
+            name
+        } else {
+            format!("{name:?}")
+        };
+        missing.push(format!("{name}: {value}"));
+    }
+    if missing.is_empty() {
+        return Vec::new();
+    }
+    let missing = missing.join(", ");
+    let (pos, new_text) = match properties.iter().last() {
+        Some(last) => (last.end(), format!(", {missing}")),
+        // Within the braces of an empty literal
+        None => (literal.end() - 1, format!(" {missing} ")),
+    };
+    vec![CodeFixAction {
+        fix_name: "fixMissingProperties",
+        description: format_message(diagnostics::ADD_MISSING_PROPERTIES_95165, &[]),
+        changes: vec![TextEdit {
+            file_name: context.file_name.clone(),
+            range: TextRange::new(pos, pos),
+            new_text,
+        }],
+    }]
+}
+
+/// Gets a value of a type, written as a type, to stand in for a property until it is set
+fn default_value_for_type(type_: &str) -> &'static str {
+    match type_ {
+        "string" => "\"\"",
+        "number" => "0",
+        "bigint" => "0n",
+        "boolean" | "false" => "false",
+        "true" => "true",
+        _ if type_.ends_with("[]") => "[]",
+        _ => "undefined",
+    }
+}
+
+/// Converts a variable that takes the result of `require`, like `const fs = require("fs")`,
+/// to an import of the module
+fn fix_require(service: &mut LanguageService, context: &FixContext) -> Vec<CodeFixAction> {
+    let tree = &context.tree;
+    let mut current = get_touching_identifier(tree, tree, context.span.pos());
+    while let Some(node) = current.take() {
+        if node.kind == SyntaxKind::VariableStatement {
+            current = Some(node);
+            break;
+        }
+        current = node.parent();
+    }
+    let Some(statement) = current.filter(|statement| statement.modifiers().is_none()) else {
+        return Vec::new();
+    };
+    let list = &statement.as_variable_statement().declaration_list;
+    let [declaration] = list
+        .as_variable_declaration_list()
+        .declarations
+        .nodes
+        .as_slice()
+    else {
+        return Vec::new();
+    };
+    let Some(call) = declaration
+        .initializer()
+        .map(skip_parentheses)
+        .filter(|call| call.kind == SyntaxKind::CallExpression)
+    else {
+        return Vec::new();
+    };
+    let call = call.as_call_expression();
+    let [argument] = call.arguments.nodes.as_slice() else {
+        return Vec::new();
+    };
+    if call.expression.kind != SyntaxKind::Identifier
+        || call.expression.text() != "require"
+        || argument.kind != SyntaxKind::StringLiteral
+    {
+        return Vec::new();
+    }
+
+    let module_name = get_text_of_node(tree, argument);
+    let name = &declaration.as_variable_declaration().name;
+    let import = match name.kind {
+        SyntaxKind::Identifier => {
+            let options = &service.program.options;
+            let name = name.text();
+            if options.es_module_interop {
+                format!("import {name} from {module_name};")
+            } else if options.emit_module_kind() == ModuleKind::CommonJS {
+                format!("import {name} = require({module_name});")
+            } else {
+                format!("import * as {name} from {module_name};")
+            }
+        }
+        SyntaxKind::ObjectBindingPattern => {
+            let mut specifiers = Vec::new();
+            for element in name.as_object_binding_pattern().elements.iter() {
+                let element = element.as_binding_element();
+                if element.dot_dot_dot_token.is_some()
+                    || element.initializer.is_some()
+                    || element.name.kind != SyntaxKind::Identifier
+                {
+                    return Vec::new();
+                }
+                specifiers.push(match &element.property_name {
+                    Some(property_name) => format!(
+                        "{} as {}",
+                        get_text_of_node(tree, property_name),
+                        element.name.text()
+                    ),
+                    None => element.name.text().to_string(),
+                });
+            }
+            format!("import {{ {} }} from {module_name};", specifiers.join(", "))
+        }
+        _ => return Vec::new(),
+    };
+    vec![CodeFixAction {
+        fix_name: "requireInTs",
+        description: format_message(diagnostics::CONVERT_REQUIRE_TO_IMPORT_95047, &[]),
+        changes: vec![TextEdit {
+            file_name: context.file_name.clone(),
+            range: get_error_range(tree, &statement),
+            new_text: import,
+        }],
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::VirtualCompilerHost;
+    use crate::compile::tests::create;
+
+    /// Applies the first fix for an error with a code at the start of `target` in `a.ts`
+    fn fix(files: &[(&str, &str)], args: &[&str], target: &str, code: i32) -> Option<String> {
+        let host = VirtualCompilerHost::with_files(files);
+        let args: Vec<&str> = ["a.ts"].iter().chain(args).copied().collect();
+        let program = create(&host, &args);
+        let text = &program.get_source_file("a.ts").unwrap().text;
+        let pos = text.find(target).unwrap();
+        let mut service = LanguageService::new(&program);
+        let actions = service.get_code_fixes_at_position(
+            "a.ts",
+            TextRange::new(pos, pos + target.len()),
+            code,
+        );
+        let action = actions.into_iter().next()?;
+        Some(apply_text_edits(text, &action.changes))
+    }
+
+    #[test]
+    fn adds_missing_imports() {
+        let b = (
+            "b.ts",
+            "export function greet() {}\nexport const other = 1;",
+        );
+        assert_eq!(
+            fix(&[("a.ts", "greet();"), b], &["b.ts"], "greet", 2304).as_deref(),
+            Some("import { greet } from \"./b\";\n\ngreet();")
+        );
+        assert_eq!(
+            fix(
+                &[("a.ts", "import { other } from './b';\ngreet();"), b],
+                &[],
+                "greet",
+                2304
+            )
+            .as_deref(),
+            Some("import { other, greet } from './b';\ngreet();")
+        );
+        assert_eq!(
+            fix(&[("a.ts", "missing();"), b], &["b.ts"], "missing", 2304),
+            None
+        );
+    }
+
+    #[test]
+    fn prefixes_unused_names_with_underscores() {
+        let text = "export function f(a: number, { b }: { b: number }) { for (const c of [1]) {} }";
+        let fixed = |target| fix(&[("a.ts", text)], &["--noUnusedParameters"], target, 6133);
+        assert_eq!(
+            fixed("a:").as_deref(),
+            Some("export function f(_a: number, { b }: { b: number }) { for (const c of [1]) {} }")
+        );
+        assert_eq!(
+            fixed("b }:").as_deref(),
+            Some(
+                "export function f(a: number, { b: _b }: { b: number }) { for (const c of [1]) {} }"
+            )
+        );
+        assert_eq!(
+            fixed("c of").as_deref(),
+            Some("export function f(a: number, { b }: { b: number }) { for (const _c of [1]) {} }")
+        );
+        assert_eq!(fixed("f("), None);
+    }
+
+    #[test]
+    fn adds_missing_properties_to_object_literals() {
+        let text = "interface P { x: number; y: string; z?: boolean; tags: string[] }\nconst p: P = { x: 1 };";
+        assert_eq!(
+            fix(&[("a.ts", text)], &[], "p:", 2739).as_deref(),
+            Some(
+                "interface P { x: number; y: string; z?: boolean; tags: string[] }\nconst p: P = { x: 1, y: \"\", tags: [] };"
+            )
+        );
+        let text = "interface P { x: number }\nconst p: P = {};";
+        assert_eq!(
+            fix(&[("a.ts", text)], &[], "p:", 2741).as_deref(),
+            Some("interface P { x: number }\nconst p: P = { x: 0 };")
+        );
+    }
+
+    #[test]
+    fn converts_require_to_import() {
+        let text = "const fs = require(\"fs\");\nconst { a, b: c } = require('x');";
+        let fixed = |target, args: &[&str]| fix(&[("a.ts", text)], args, target, 80005);
+        assert_eq!(
+            fixed("fs", &["--module", "commonjs"]).as_deref(),
+            Some("import fs = require(\"fs\");\nconst { a, b: c } = require('x');")
+        );
+        assert_eq!(
+            fixed("fs", &["--module", "esnext"]).as_deref(),
+            Some("import * as fs from \"fs\";\nconst { a, b: c } = require('x');")
+        );
+        assert_eq!(
+            fixed("require('x')", &[]).as_deref(),
+            Some("const fs = require(\"fs\");\nimport { a, b as c } from 'x';")
+        );
+    }
+
+    #[test]
+    fn fixes_every_error_of_a_program() {
+        let host = VirtualCompilerHost::with_files(&[
+            (
+                "a.ts",
+                "export function f(a: number, b: number) { return greet(); }",
+            ),
+            ("b.ts", "export function greet() {}"),
+        ]);
+        let program = create(&host, &["a.ts", "b.ts", "--noUnusedParameters"]);
+        assert_eq!(apply_code_fixes(&program, &host), ["a.ts"]);
+        assert_eq!(
+            host.read_file("a.ts").as_deref(),
+            Some(
+                "import { greet } from \"./b\";\n\nexport function f(_a: number, _b: number) { return greet(); }"
+            )
+        );
+        assert!(get_supported_code_fixes().contains(&2304));
+    }
+}
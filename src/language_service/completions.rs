@@ -0,0 +1,573 @@
+//! Completions of the name being typed at a position
+//!
+//! After a `.` or `?.`, the completions are the properties of the apparent type of the
+//! expression before it. Elsewhere they are the names in scope with a meaning that fits the
+//! position, a value or a type, along with the keywords that can go there. Names exported by
+//! other modules of the program are offered too, with the edit that imports them, once the
+//! start of the name has been typed.
+
+use std::sync::Arc;
+
+use super::code_fixes::add_named_import;
+use super::references::{is_declaration_name, is_identifier_text};
+use super::{LanguageService, ScriptElementKind, TextEdit};
+use crate::compiler::ast::utilities::{
+    get_source_file_of_node, is_block_scoped_variable, is_class_like, is_const_variable,
+    is_external_module,
+};
+use crate::compiler::ast::visitor::get_token_at_position;
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::checker::Checker;
+use crate::compiler::scanner::{
+    ScriptTarget, get_leading_comment_ranges, is_identifier_part, skip_trivia,
+};
+use crate::compiler::text::TextRange;
+
+/// The sort text of the names declared in the file of the completion, and of the members of a
+/// type
+const SORT_LOCATION: &str = "11";
+/// The sort text of the names declared elsewhere, and of keywords
+const SORT_GLOBALS_OR_KEYWORDS: &str = "15";
+/// The sort text of the names that need an import
+const SORT_AUTO_IMPORT: &str = "16";
+
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "abstract",
+    "async",
+    "await",
+    "break",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "declare",
+    "delete",
+    "do",
+    "enum",
+    "export",
+    "false",
+    "for",
+    "function",
+    "if",
+    "import",
+    "interface",
+    "let",
+    "namespace",
+    "new",
+    "null",
+    "return",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "var",
+    "void",
+    "while",
+];
+
+const EXPRESSION_KEYWORDS: &[&str] = &[
+    "async", "await", "class", "delete", "false", "function", "new", "null", "this", "true",
+    "typeof", "void",
+];
+
+const TYPE_KEYWORDS: &[&str] = &[
+    "any",
+    "bigint",
+    "boolean",
+    "false",
+    "keyof",
+    "never",
+    "null",
+    "number",
+    "object",
+    "readonly",
+    "string",
+    "symbol",
+    "true",
+    "typeof",
+    "undefined",
+    "unique",
+    "unknown",
+    "void",
+];
+
+const CLASS_MEMBER_KEYWORDS: &[&str] = &[
+    "abstract",
+    "accessor",
+    "async",
+    "constructor",
+    "declare",
+    "get",
+    "override",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "set",
+    "static",
+];
+
+/// A name that can be inserted at a position
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionEntry {
+    pub name: String,
+    pub kind: ScriptElementKind,
+    /// Orders the entry among the others, like the sort text of tsserver: names of the file
+    /// first, then the others and keywords, then those that need an import
+    pub sort_text: &'static str,
+    /// The module that a name that needs an import comes from
+    pub source: Option<String>,
+    /// The edits to make along with inserting the name, like adding its import
+    pub additional_edits: Vec<TextEdit>,
+}
+
+/// The completions at a position
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionInfo {
+    /// Whether the entries are the members of a type, after a `.`
+    pub is_member_completion: bool,
+    /// The part of the name already typed, which an entry replaces
+    pub replacement_span: TextRange,
+    pub entries: Vec<CompletionEntry>,
+}
+
+/// What can go at the position of a completion
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CompletionContext {
+    Statement,
+    Expression,
+    Type,
+    ClassMember,
+}
+
+impl LanguageService<'_> {
+    /// Gets the names that can complete the one being typed at a position of a file, or `None`
+    /// where no name goes, like in a comment or string or where a new name is being declared
+    pub fn get_completions_at_position(
+        &mut self,
+        file_name: &str,
+        pos: usize,
+    ) -> Option<CompletionInfo> {
+        let program = self.program;
+        let tree = program.get_syntax_tree(file_name)?;
+        let source_file = tree.as_source_file();
+        let text = &source_file.text;
+        // The name being typed ends at the position
+        let start = text[..pos]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, ch)| is_identifier_part(ch, ScriptTarget::ESNext))
+            .last()
+            .map_or(pos, |(index, _)| index);
+        let replacement_span = TextRange::new(start, pos);
+        let token = get_token_at_position(tree, start);
+        // A name cannot start with a digit, so the digits before the position are a number
+        if text[start..pos].starts_with(|ch: char| ch.is_ascii_digit())
+            || is_in_comment_or_literal(text, &token, start)
+        {
+            return None;
+        }
+
+        let before = text[..start].trim_end();
+        if let Some(dot) = before
+            .strip_suffix("?.")
+            .or_else(|| before.strip_suffix('.').filter(|rest| !rest.ends_with('.')))
+            .map(str::len)
+        {
+            let entries = self.get_member_completions(tree, dot)?;
+            return Some(CompletionInfo {
+                is_member_completion: true,
+                replacement_span,
+                entries,
+            });
+        }
+
+        let context = get_completion_context(&token, start, before)?;
+        let prefix = &text[start..pos];
+        let meaning = if context == CompletionContext::Type {
+            SymbolFlags::TYPE | SymbolFlags::NAMESPACE
+        } else {
+            SymbolFlags::VALUE
+        };
+        let mut entries = Vec::new();
+        if context != CompletionContext::ClassMember {
+            for symbol in self.checker.get_symbols_in_scope(&token, meaning) {
+                let data = self.checker.symbol(symbol);
+                if !is_identifier_text(&data.name)
+                    || data.declarations.iter().any(|declaration| {
+                        declaration.name().is_some_and(|name| {
+                            matches!(
+                                name.kind,
+                                SyntaxKind::ObjectBindingPattern | SyntaxKind::ArrayBindingPattern
+                            )
+                        })
+                    })
+                {
+                    continue;
+                }
+                let is_local = data
+                    .declarations
+                    .iter()
+                    .any(|declaration| Arc::ptr_eq(&get_source_file_of_node(declaration), tree));
+                entries.push(CompletionEntry {
+                    name: data.name.clone(),
+                    kind: get_symbol_kind(&self.checker, symbol),
+                    sort_text: if is_local {
+                        SORT_LOCATION
+                    } else {
+                        SORT_GLOBALS_OR_KEYWORDS
+                    },
+                    source: None,
+                    additional_edits: Vec::new(),
+                });
+            }
+        }
+        let keywords = match context {
+            CompletionContext::Statement => STATEMENT_KEYWORDS,
+            CompletionContext::Expression => EXPRESSION_KEYWORDS,
+            CompletionContext::Type => TYPE_KEYWORDS,
+            CompletionContext::ClassMember => CLASS_MEMBER_KEYWORDS,
+        };
+        entries.extend(keywords.iter().map(|keyword| CompletionEntry {
+            name: keyword.to_string(),
+            kind: ScriptElementKind::Keyword,
+            sort_text: SORT_GLOBALS_OR_KEYWORDS,
+            source: None,
+            additional_edits: Vec::new(),
+        }));
+        if context != CompletionContext::ClassMember && !prefix.is_empty() {
+            let auto_imports = self.get_auto_import_completions(tree, prefix, meaning, &entries);
+            entries.extend(auto_imports);
+        }
+        entries.sort_by(|a, b| (a.sort_text, &a.name).cmp(&(b.sort_text, &b.name)));
+        Some(CompletionInfo {
+            is_member_completion: false,
+            replacement_span,
+            entries,
+        })
+    }
+
+    /// Gets the properties of the apparent type of the expression before the dot at a position
+    fn get_member_completions(
+        &mut self,
+        tree: &Arc<Node>,
+        dot: usize,
+    ) -> Option<Vec<CompletionEntry>> {
+        let access = get_token_at_position(tree, dot).parent()?;
+        if access.kind != SyntaxKind::PropertyAccessExpression {
+            return None;
+        }
+        let expression = &access.as_property_access_expression().expression;
+        let type_ = self.checker.get_type_at_location(expression);
+        // `a?.` completes the members of `a` without `null` and `undefined`
+        let type_ = self.checker.get_non_nullable_type(type_);
+        let apparent_type = self.checker.get_apparent_type(type_);
+        let mut entries: Vec<CompletionEntry> = self
+            .checker
+            .get_properties_of_type(apparent_type)
+            .into_iter()
+            .filter(|&property| is_identifier_text(&self.checker.symbol(property).name))
+            .map(|property| CompletionEntry {
+                name: self.checker.symbol(property).name.clone(),
+                kind: get_symbol_kind(&self.checker, property),
+                sort_text: SORT_LOCATION,
+                source: None,
+                additional_edits: Vec::new(),
+            })
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Some(entries)
+    }
+
+    /// Gets the names starting with a prefix that other modules of the program export and that
+    /// are not in scope yet, each with the edit that imports it
+    fn get_auto_import_completions(
+        &mut self,
+        tree: &Arc<Node>,
+        prefix: &str,
+        meaning: SymbolFlags,
+        in_scope: &[CompletionEntry],
+    ) -> Vec<CompletionEntry> {
+        let program = self.program;
+        let file_name = &tree.as_source_file().file_name;
+        let prefix = prefix.to_lowercase();
+        let mut entries = Vec::new();
+        for (source_file, module_tree) in program.source_files.iter().zip(&program.syntax_trees) {
+            if source_file.file_name == *file_name
+                || !is_external_module(module_tree)
+                || source_file
+                    .file_name
+                    .split('/')
+                    .any(|part| part == "node_modules")
+            {
+                continue;
+            }
+            let Some(module_symbol) = self.checker.get_symbol_of_declaration(module_tree) else {
+                continue;
+            };
+            let mut exports: Vec<(String, SymbolId)> = self
+                .checker
+                .symbol(module_symbol)
+                .exports
+                .iter()
+                .map(|(name, &symbol)| (name.clone(), symbol))
+                .collect();
+            exports.sort();
+            let module_name = program.get_relative_module_name(file_name, &source_file.file_name);
+            for (name, symbol) in exports {
+                if !is_identifier_text(&name)
+                    || !name.to_lowercase().starts_with(&prefix)
+                    || in_scope.iter().any(|entry| entry.name == name)
+                {
+                    continue;
+                }
+                let target = if self
+                    .checker
+                    .symbol(symbol)
+                    .flags
+                    .contains(SymbolFlags::ALIAS)
+                {
+                    self.checker.get_aliased_symbol(symbol).unwrap_or(symbol)
+                } else {
+                    symbol
+                };
+                if !self.checker.symbol(target).flags.intersects(meaning) {
+                    continue;
+                }
+                entries.push(CompletionEntry {
+                    kind: get_symbol_kind(&self.checker, target),
+                    additional_edits: vec![add_named_import(file_name, tree, &name, &module_name)],
+                    name,
+                    sort_text: SORT_AUTO_IMPORT,
+                    source: Some(module_name.clone()),
+                });
+            }
+        }
+        entries
+    }
+}
+
+/// Determines whether a position is inside a comment or a literal, where nothing completes
+fn is_in_comment_or_literal(text: &str, token: &Node, pos: usize) -> bool {
+    let in_comment = get_leading_comment_ranges(text, token.pos())
+        .iter()
+        .any(|comment| {
+            comment.loc.pos() < pos
+                && (pos < comment.loc.end()
+                    || comment.kind == SyntaxKind::SingleLineCommentTrivia
+                        && pos == comment.loc.end())
+        });
+    let in_literal = matches!(
+        token.kind,
+        SyntaxKind::StringLiteral
+            | SyntaxKind::RegularExpressionLiteral
+            | SyntaxKind::NoSubstitutionTemplateLiteral
+            | SyntaxKind::TemplateHead
+            | SyntaxKind::TemplateMiddle
+            | SyntaxKind::TemplateTail
+    ) && skip_trivia(text, token.pos()) < pos
+        && pos < token.end();
+    in_comment || in_literal
+}
+
+/// Finds what can go at a position from the token there, the name being typed or the token
+/// after the position, or returns `None` where a new name is being declared
+fn get_completion_context(
+    token: &Arc<Node>,
+    start: usize,
+    before: &str,
+) -> Option<CompletionContext> {
+    let parent = token.parent()?;
+    let at_member_start = before.ends_with(['{', ';', '}']);
+    let is_typed_name = token.kind == SyntaxKind::Identifier && token.end() > start;
+    if is_typed_name
+        && parent.kind == SyntaxKind::PropertyDeclaration
+        && parent.parent().is_some_and(|class| is_class_like(&class))
+        && at_member_start
+    {
+        return Some(CompletionContext::ClassMember);
+    }
+    if !is_typed_name && is_class_like(&parent) && at_member_start {
+        return Some(CompletionContext::ClassMember);
+    }
+    if is_typed_name && is_declaration_name(token) {
+        return None;
+    }
+    if is_typed_name && parent.kind == SyntaxKind::TypeReference
+        || !is_typed_name
+            && before.ends_with([':', '|', '&'])
+            && is_type_annotation_position(&parent)
+    {
+        return Some(CompletionContext::Type);
+    }
+    let starts_statement = if is_typed_name {
+        parent.kind == SyntaxKind::ExpressionStatement
+    } else {
+        before.is_empty() || before.ends_with(['{', ';', '}'])
+    };
+    Some(if starts_statement {
+        CompletionContext::Statement
+    } else {
+        CompletionContext::Expression
+    })
+}
+
+/// Determines whether the token after a `:`, `|`, or `&` with nothing typed yet starts a type,
+/// from the node the token belongs to
+fn is_type_annotation_position(node: &Node) -> bool {
+    node.kind.is_type_node()
+        || matches!(
+            node.kind,
+            SyntaxKind::VariableDeclaration
+                | SyntaxKind::Parameter
+                | SyntaxKind::PropertyDeclaration
+                | SyntaxKind::PropertySignature
+                | SyntaxKind::FunctionDeclaration
+                | SyntaxKind::MethodDeclaration
+                | SyntaxKind::MethodSignature
+                | SyntaxKind::TypeAliasDeclaration
+        )
+}
+
+/// Gets the kind of element a symbol is, from what it is declared as
+pub(super) fn get_symbol_kind(checker: &Checker, symbol: SymbolId) -> ScriptElementKind {
+    let data = checker.symbol(symbol);
+    let flags = data.flags;
+    if flags.contains(SymbolFlags::CLASS) {
+        ScriptElementKind::Class
+    } else if flags.intersects(SymbolFlags::ENUM) {
+        ScriptElementKind::Enum
+    } else if flags.contains(SymbolFlags::TYPE_ALIAS) {
+        ScriptElementKind::TypeAlias
+    } else if flags.contains(SymbolFlags::INTERFACE) {
+        ScriptElementKind::Interface
+    } else if flags.contains(SymbolFlags::TYPE_PARAMETER) {
+        ScriptElementKind::TypeParameter
+    } else if flags.contains(SymbolFlags::ENUM_MEMBER) {
+        ScriptElementKind::EnumMember
+    } else if flags.contains(SymbolFlags::ALIAS) {
+        ScriptElementKind::Alias
+    } else if flags.contains(SymbolFlags::FUNCTION) {
+        ScriptElementKind::Function
+    } else if flags.contains(SymbolFlags::METHOD) {
+        ScriptElementKind::Method
+    } else if flags.contains(SymbolFlags::GET_ACCESSOR) {
+        ScriptElementKind::GetAccessor
+    } else if flags.contains(SymbolFlags::SET_ACCESSOR) {
+        ScriptElementKind::SetAccessor
+    } else if flags.contains(SymbolFlags::PROPERTY) {
+        ScriptElementKind::Property
+    } else if flags.intersects(SymbolFlags::VARIABLE) {
+        match &data.value_declaration {
+            Some(declaration) if declaration.kind == SyntaxKind::Parameter => {
+                ScriptElementKind::Parameter
+            }
+            Some(declaration) if is_const_variable(declaration) => ScriptElementKind::Const,
+            Some(declaration) if is_block_scoped_variable(declaration) => ScriptElementKind::Let,
+            _ => ScriptElementKind::Variable,
+        }
+    } else if flags.intersects(SymbolFlags::MODULE) {
+        ScriptElementKind::Module
+    } else {
+        ScriptElementKind::Variable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::VirtualCompilerHost;
+    use crate::compile::tests::create;
+    use crate::language_service::apply_text_edits;
+
+    /// Gets the completions at the `/**/` in `a.ts`, which is taken out of its text
+    fn complete(files: &[(&str, &str)]) -> Option<(String, CompletionInfo)> {
+        let (_, marked) = files[0];
+        let pos = marked.find("/**/").unwrap();
+        let text = marked.replacen("/**/", "", 1);
+        let mut files = files.to_vec();
+        files[0] = ("a.ts", &text);
+        let program = create(&VirtualCompilerHost::with_files(&files), &["a.ts"]);
+        let mut service = LanguageService::new(&program);
+        let info = service.get_completions_at_position("a.ts", pos)?;
+        Some((text.clone(), info))
+    }
+
+    fn names(info: &CompletionInfo) -> Vec<&str> {
+        info.entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn completes_members_after_a_dot() {
+        let source = "interface Point { x: number; y: number }\n\
+                      declare const p: Point | undefined;\np?./**/";
+        let (_, info) = complete(&[("a.ts", source)]).unwrap();
+        assert!(info.is_member_completion);
+        assert_eq!(names(&info), ["x", "y"]);
+        assert_eq!(info.entries[0].kind, ScriptElementKind::Property);
+
+        let (_, info) =
+            complete(&[("a.ts", "const o = { alpha: 1, beta: \"\" };\no.al/**/")]).unwrap();
+        assert_eq!(info.replacement_span, TextRange::new(36, 38));
+        assert_eq!(names(&info), ["alpha", "beta"]);
+    }
+
+    #[test]
+    fn completes_names_in_scope_and_keywords() {
+        let source =
+            "const total = 1;\nfunction add(value: number) {\n    let sum = 0;\n    /**/\n}";
+        let (_, info) = complete(&[("a.ts", source)]).unwrap();
+        let entry = |name: &str| info.entries.iter().find(|entry| entry.name == name);
+        assert_eq!(entry("sum").unwrap().kind, ScriptElementKind::Let);
+        assert_eq!(entry("value").unwrap().kind, ScriptElementKind::Parameter);
+        assert_eq!(entry("total").unwrap().kind, ScriptElementKind::Const);
+        assert_eq!(entry("add").unwrap().sort_text, SORT_LOCATION);
+        assert_eq!(entry("return").unwrap().kind, ScriptElementKind::Keyword);
+        assert!(entry("Point").is_none());
+
+        let (_, info) = complete(&[("a.ts", "interface Point {}\nlet p: Po/**/")]).unwrap();
+        assert!(names(&info).contains(&"Point"));
+        assert!(names(&info).contains(&"number"));
+        assert!(!names(&info).contains(&"return"));
+
+        assert!(complete(&[("a.ts", "// a comment /**/")]).is_none());
+        assert!(complete(&[("a.ts", "const s = \"ab/**/\";")]).is_none());
+        assert!(complete(&[("a.ts", "const na/**/")]).is_none());
+    }
+
+    #[test]
+    fn completes_exports_of_other_modules_with_an_import() {
+        let files = [
+            ("a.ts", "import { other } from \"./b\";\nother(gre/**/);"),
+            (
+                "b.ts",
+                "export function greet() {}\nexport function other() {}",
+            ),
+        ];
+        let (text, info) = complete(&files).unwrap();
+        let entry = info
+            .entries
+            .iter()
+            .find(|entry| entry.name == "greet")
+            .unwrap();
+        assert_eq!(entry.sort_text, SORT_AUTO_IMPORT);
+        assert_eq!(entry.source.as_deref(), Some("./b"));
+        assert_eq!(
+            apply_text_edits(&text, &entry.additional_edits),
+            "import { other, greet } from \"./b\";\nother(gre);"
+        );
+        assert_eq!(
+            info.entries
+                .iter()
+                .filter(|entry| entry.name == "other")
+                .count(),
+            1
+        );
+    }
+}
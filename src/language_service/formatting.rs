@@ -0,0 +1,966 @@
+//! Formatting of the whitespace between the tokens of a file
+//!
+//! The formatter never moves tokens: it scans the file and rewrites only the whitespace between
+//! adjacent tokens, so that the result has the same tokens in the same order. Each line is
+//! indented by the brackets open at its start, with one more level for the statements of case
+//! clauses and of control statements without a block, and for lines that continue an
+//! expression or declaration from the line before. Tokens on the same line are spaced by rules
+//! over the pair of them, like the ones of tsserver, using the tree for what the tokens can not
+//! tell, like which `-` is a binary operator. Comments, and the content of template literals,
+//! regular expressions, and JSX, are kept as they are.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::LanguageService;
+use super::code_fixes::apply_text_edits;
+use super::references::TextEdit;
+use crate::compile::{CompilerHost, Diagnostic, SourceFile, convert_diagnostic, option_diagnostic};
+use crate::compiler::ast::{Node, SyntaxKind};
+use crate::compiler::diagnostics;
+use crate::compiler::parser::{get_script_kind_from_file_name, parse_source_file};
+use crate::compiler::scanner::{
+    Scanner, ScriptTarget, is_identifier_part, is_keyword, is_line_break,
+    is_white_space_single_line, skip_trivia,
+};
+use crate::compiler::text::TextRange;
+
+/// What to do with the trailing comma of a list written over several lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailingCommas {
+    /// Keep trailing commas as they are
+    #[default]
+    Preserve,
+    /// Add a trailing comma after the last element of a list that ends on an earlier line than
+    /// the list
+    Insert,
+    /// Remove trailing commas
+    Remove,
+}
+
+/// How to format a file, with the defaults of tsserver
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    /// The number of columns of an indentation level
+    pub indent_size: usize,
+    /// The number of columns of a tab, when indenting with tabs
+    pub tab_size: usize,
+    /// Whether to indent with spaces rather than tabs
+    pub convert_tabs_to_spaces: bool,
+    pub insert_space_after_comma_delimiter: bool,
+    pub insert_space_after_semicolon_in_for_statements: bool,
+    pub insert_space_before_and_after_binary_operators: bool,
+    pub insert_space_after_keywords_in_control_flow_statements: bool,
+    pub insert_space_after_opening_and_before_closing_nonempty_braces: bool,
+    /// Whether the bodies of functions, classes, and other declarations open on a line of their
+    /// own
+    pub place_open_brace_on_new_line_for_functions: bool,
+    /// Whether the blocks of control statements open on a line of their own
+    pub place_open_brace_on_new_line_for_control_blocks: bool,
+    /// What to do with the trailing commas of object and array literals, enums, and named
+    /// imports and exports
+    pub trailing_commas: TrailingCommas,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent_size: 4,
+            tab_size: 4,
+            convert_tabs_to_spaces: true,
+            insert_space_after_comma_delimiter: true,
+            insert_space_after_semicolon_in_for_statements: true,
+            insert_space_before_and_after_binary_operators: true,
+            insert_space_after_keywords_in_control_flow_statements: true,
+            insert_space_after_opening_and_before_closing_nonempty_braces: true,
+            place_open_brace_on_new_line_for_functions: false,
+            place_open_brace_on_new_line_for_control_blocks: false,
+            trailing_commas: TrailingCommas::Preserve,
+        }
+    }
+}
+
+impl LanguageService<'_> {
+    /// Gets the edits that format a whole file
+    pub fn get_formatting_edits_for_document(
+        &self,
+        file_name: &str,
+        options: &FormatOptions,
+    ) -> Vec<TextEdit> {
+        match self.program.get_syntax_tree(file_name) {
+            Some(tree) => get_formatting_edits(tree, options),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Gets the edits that format the file of a syntax tree
+pub fn get_formatting_edits(tree: &Arc<Node>, options: &FormatOptions) -> Vec<TextEdit> {
+    let mut formatter = Formatter::new(tree, options);
+    formatter.collect(tree);
+    formatter.format()
+}
+
+/// Formats files in place, writing those that change through the host
+///
+/// A file with syntax errors is left as it is, and its errors are returned.
+pub fn format_files(
+    source_files: &[SourceFile],
+    options: &FormatOptions,
+    host: &impl CompilerHost,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for source_file in source_files {
+        let tree = parse_source_file(
+            &source_file.file_name,
+            &source_file.text,
+            ScriptTarget::Latest,
+            get_script_kind_from_file_name(&source_file.file_name),
+        );
+        let parse_diagnostics = &tree.as_source_file().parse_diagnostics;
+        if !parse_diagnostics.is_empty() {
+            diagnostics.extend(
+                parse_diagnostics
+                    .iter()
+                    .map(|diagnostic| convert_diagnostic(source_file, diagnostic)),
+            );
+            continue;
+        }
+        let edits = get_formatting_edits(&tree, options);
+        if !edits.is_empty()
+            && let Err(error) = host.write_file(
+                &source_file.file_name,
+                &apply_text_edits(&source_file.text, &edits),
+            )
+        {
+            diagnostics.push(option_diagnostic(
+                diagnostics::COULD_NOT_WRITE_FILE_0_COLON_1_5033,
+                &[source_file.file_name.clone(), error.to_string()],
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// A token of the file, or a construct like a template literal that is kept as a whole
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: SyntaxKind,
+    start: usize,
+    end: usize,
+}
+
+/// The opening brace of a body or block, which goes on the line of what it belongs to or on a
+/// line of its own
+struct Brace {
+    is_function: bool,
+    is_multi_line: bool,
+}
+
+/// A bracket open at some point of the file
+struct OpenBracket {
+    kind: SyntaxKind,
+    pos: usize,
+    /// The indentation of the line the bracket is on, which is the one of its closing bracket
+    line_indentation: usize,
+}
+
+struct Formatter<'a> {
+    text: &'a str,
+    file_name: &'a str,
+    options: &'a FormatOptions,
+    new_line: &'static str,
+    /// The ends of the constructs kept as they are, by their starts
+    verbatim: HashMap<usize, usize>,
+    /// The starts of binary operators, which are spaced on both sides
+    binary_operators: HashSet<usize>,
+    /// The starts of the operands that follow a binary-like `=`, `|`, `&`, `?`, or `:`, with
+    /// the kind of that operator, which the tree does not store
+    binary_operands: HashSet<(usize, SyntaxKind)>,
+    prefix_operators: HashSet<usize>,
+    postfix_operators: HashSet<usize>,
+    /// The starts of statements and of the elements of lists, which never continue a line
+    element_starts: HashSet<usize>,
+    /// The statements of case clauses and of control statements without a block, which are
+    /// indented one more level than the line they belong to
+    indented_ranges: Vec<TextRange>,
+    /// The opening braces of the bodies of declarations and the blocks of control statements
+    braces: HashMap<usize, Brace>,
+    decorator_ends: HashSet<usize>,
+    trailing_comma_insertions: HashSet<usize>,
+    trailing_comma_removals: HashSet<usize>,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(tree: &'a Node, options: &'a FormatOptions) -> Self {
+        let source_file = tree.as_source_file();
+        let text = source_file.text.as_str();
+        Formatter {
+            text,
+            file_name: &source_file.file_name,
+            options,
+            new_line: if text.contains("\r\n") { "\r\n" } else { "\n" },
+            verbatim: HashMap::new(),
+            binary_operators: HashSet::new(),
+            binary_operands: HashSet::new(),
+            prefix_operators: HashSet::new(),
+            postfix_operators: HashSet::new(),
+            element_starts: HashSet::new(),
+            indented_ranges: Vec::new(),
+            braces: HashMap::new(),
+            decorator_ends: HashSet::new(),
+            trailing_comma_insertions: HashSet::new(),
+            trailing_comma_removals: HashSet::new(),
+        }
+    }
+
+    fn start(&self, node: &Node) -> usize {
+        skip_trivia(self.text, node.pos())
+    }
+
+    /// Records what the tokens alone do not tell about the formatting of a node and its
+    /// descendants
+    fn collect(&mut self, node: &Arc<Node>) {
+        if let Some(statements) = node.statements() {
+            self.add_elements(statements.iter());
+        }
+        if let Some(members) = node.members() {
+            self.add_elements(members.iter());
+            // The list starts right after its opening brace
+            let brace = members.pos().wrapping_sub(1);
+            if node.kind != SyntaxKind::TypeLiteral
+                && self.text.as_bytes().get(brace) == Some(&b'{')
+            {
+                self.add_brace(brace, node.end(), true);
+            }
+        }
+        if let Some(parameters) = node.parameters() {
+            self.add_elements(parameters.iter());
+        }
+        if let Some(initializer) = node.initializer() {
+            self.add_binary_operand(initializer, SyntaxKind::EqualsToken);
+        }
+        match node.kind {
+            SyntaxKind::TemplateExpression
+            | SyntaxKind::RegularExpressionLiteral
+            | SyntaxKind::JsxElement
+            | SyntaxKind::JsxSelfClosingElement
+            | SyntaxKind::JsxFragment => {
+                self.verbatim.insert(self.start(node), node.end());
+                return;
+            }
+            SyntaxKind::BinaryExpression => {
+                let operator = &node.as_binary_expression().operator_token;
+                if operator.kind != SyntaxKind::CommaToken {
+                    self.binary_operators.insert(self.start(operator));
+                }
+            }
+            SyntaxKind::ConditionalExpression => {
+                let expression = node.as_conditional_expression();
+                self.binary_operators
+                    .insert(self.start(&expression.question_token));
+                self.binary_operators
+                    .insert(self.start(&expression.colon_token));
+            }
+            SyntaxKind::ArrowFunction => {
+                let arrow = &node.as_arrow_function().equals_greater_than_token;
+                self.binary_operators.insert(self.start(arrow));
+                self.add_function_brace(node);
+            }
+            SyntaxKind::ConditionalType => {
+                let conditional = node.as_conditional_type_node();
+                self.add_binary_operand(&conditional.true_type, SyntaxKind::QuestionToken);
+                self.add_binary_operand(&conditional.false_type, SyntaxKind::ColonToken);
+            }
+            SyntaxKind::TypeAliasDeclaration => {
+                let type_node = &node.as_type_alias_declaration().type_node;
+                self.add_binary_operand(type_node, SyntaxKind::EqualsToken);
+            }
+            SyntaxKind::UnionType => {
+                for type_node in node.as_union_type_node().types.iter() {
+                    self.add_binary_operand(type_node, SyntaxKind::BarToken);
+                }
+            }
+            SyntaxKind::IntersectionType => {
+                for type_node in node.as_intersection_type_node().types.iter() {
+                    self.add_binary_operand(type_node, SyntaxKind::AmpersandToken);
+                }
+            }
+            SyntaxKind::PrefixUnaryExpression => {
+                self.prefix_operators.insert(self.start(node));
+            }
+            SyntaxKind::PostfixUnaryExpression => {
+                // `++` and `--` are the only postfix operators
+                self.postfix_operators.insert(node.end() - 2);
+            }
+            SyntaxKind::NonNullExpression => {
+                self.postfix_operators.insert(node.end() - 1);
+            }
+            SyntaxKind::Decorator => {
+                self.decorator_ends.insert(node.end());
+            }
+            SyntaxKind::FunctionDeclaration
+            | SyntaxKind::FunctionExpression
+            | SyntaxKind::MethodDeclaration
+            | SyntaxKind::Constructor
+            | SyntaxKind::GetAccessor
+            | SyntaxKind::SetAccessor
+            | SyntaxKind::ModuleDeclaration => self.add_function_brace(node),
+            SyntaxKind::IfStatement => {
+                let statement = node.as_if_statement();
+                self.add_embedded_statement(&statement.then_statement);
+                if let Some(else_statement) = &statement.else_statement {
+                    // `else if` stays on the line of the `else`
+                    if else_statement.kind != SyntaxKind::IfStatement {
+                        self.add_embedded_statement(else_statement);
+                    }
+                }
+            }
+            SyntaxKind::DoStatement => {
+                self.add_embedded_statement(&node.as_do_statement().statement)
+            }
+            SyntaxKind::WhileStatement => {
+                self.add_embedded_statement(&node.as_while_statement().statement)
+            }
+            SyntaxKind::ForStatement => {
+                self.add_embedded_statement(&node.as_for_statement().statement)
+            }
+            SyntaxKind::ForInStatement => {
+                self.add_embedded_statement(&node.as_for_in_statement().statement)
+            }
+            SyntaxKind::ForOfStatement => {
+                self.add_embedded_statement(&node.as_for_of_statement().statement)
+            }
+            SyntaxKind::WithStatement => {
+                self.add_embedded_statement(&node.as_with_statement().statement)
+            }
+            SyntaxKind::TryStatement => {
+                let statement = node.as_try_statement();
+                self.add_control_brace(&statement.try_block);
+                if let Some(finally_block) = &statement.finally_block {
+                    self.add_control_brace(finally_block);
+                }
+            }
+            SyntaxKind::CatchClause => {
+                self.add_control_brace(&node.as_catch_clause().block);
+            }
+            SyntaxKind::SwitchStatement => {
+                let case_block = &node.as_switch_statement().case_block;
+                self.add_control_brace(case_block);
+                let clauses = &case_block.as_case_block().clauses;
+                self.add_elements(clauses.iter());
+            }
+            SyntaxKind::CaseClause => {
+                // The statements start after the `:` of the clause
+                let expression = &node.as_case_clause().expression;
+                self.indented_ranges
+                    .push(TextRange::new(expression.end() + 1, node.end()));
+            }
+            SyntaxKind::DefaultClause => {
+                let colon = self.start(node) + "default".len() + 1;
+                self.indented_ranges
+                    .push(TextRange::new(colon.min(node.end()), node.end()));
+            }
+            SyntaxKind::ObjectLiteralExpression => {
+                let properties = &node.as_object_literal_expression().properties;
+                self.add_elements(properties.iter());
+                self.add_trailing_comma(
+                    node,
+                    properties.nodes.last(),
+                    properties.has_trailing_comma,
+                );
+            }
+            SyntaxKind::ArrayLiteralExpression => {
+                let elements = &node.as_array_literal_expression().elements;
+                self.add_elements(elements.iter());
+                // A trailing comma after a hole is part of the hole
+                if elements
+                    .nodes
+                    .last()
+                    .is_none_or(|element| element.kind != SyntaxKind::OmittedExpression)
+                {
+                    self.add_trailing_comma(
+                        node,
+                        elements.nodes.last(),
+                        elements.has_trailing_comma,
+                    );
+                }
+            }
+            SyntaxKind::CallExpression => {
+                self.add_elements(node.as_call_expression().arguments.iter());
+            }
+            SyntaxKind::NewExpression => {
+                if let Some(arguments) = &node.as_new_expression().arguments {
+                    self.add_elements(arguments.iter());
+                }
+            }
+            SyntaxKind::NamedImports => {
+                let elements = &node.as_named_imports().elements;
+                self.add_elements(elements.iter());
+                self.add_trailing_comma(node, elements.nodes.last(), elements.has_trailing_comma);
+            }
+            SyntaxKind::NamedExports => {
+                let elements = &node.as_named_exports().elements;
+                self.add_elements(elements.iter());
+                self.add_trailing_comma(node, elements.nodes.last(), elements.has_trailing_comma);
+            }
+            SyntaxKind::EnumDeclaration => {
+                let members = &node.as_enum_declaration().members;
+                self.add_trailing_comma(node, members.nodes.last(), members.has_trailing_comma);
+            }
+            _ => {}
+        }
+        node.for_each_child(&mut |child| {
+            self.collect(child);
+            false
+        });
+    }
+
+    fn add_elements<'n>(&mut self, elements: impl Iterator<Item = &'n Arc<Node>>) {
+        for element in elements {
+            self.element_starts.insert(self.start(element));
+        }
+    }
+
+    fn add_binary_operand(&mut self, operand: &Node, operator: SyntaxKind) {
+        self.binary_operands.insert((self.start(operand), operator));
+    }
+
+    fn add_function_brace(&mut self, node: &Node) {
+        if let Some(body) = node.body()
+            && matches!(body.kind, SyntaxKind::Block | SyntaxKind::ModuleBlock)
+        {
+            self.add_brace(self.start(body), body.end(), true);
+        }
+    }
+
+    fn add_control_brace(&mut self, block: &Node) {
+        self.add_brace(self.start(block), block.end(), false);
+    }
+
+    fn add_brace(&mut self, pos: usize, end: usize, is_function: bool) {
+        let is_multi_line = self.text[pos..end].contains(is_line_break);
+        self.braces.insert(
+            pos,
+            Brace {
+                is_function,
+                is_multi_line,
+            },
+        );
+    }
+
+    /// Records the statement of a control statement, which is indented when it is not a block
+    fn add_embedded_statement(&mut self, statement: &Node) {
+        let start = self.start(statement);
+        if statement.kind == SyntaxKind::Block {
+            self.add_control_brace(statement);
+        } else {
+            self.indented_ranges
+                .push(TextRange::new(start, statement.end()));
+        }
+    }
+
+    /// Records the change to the trailing comma of a list, which ends with the closing bracket
+    /// of its node
+    fn add_trailing_comma(
+        &mut self,
+        node: &Node,
+        last_element: Option<&Arc<Node>>,
+        has_trailing_comma: bool,
+    ) {
+        let Some(last_element) = last_element else {
+            return;
+        };
+        match self.options.trailing_commas {
+            TrailingCommas::Preserve => {}
+            TrailingCommas::Insert => {
+                let between = &self.text[last_element.end()..node.end() - 1];
+                if !has_trailing_comma && between.contains(is_line_break) {
+                    self.trailing_comma_insertions.insert(last_element.end());
+                }
+            }
+            TrailingCommas::Remove => {
+                let comma = skip_trivia(self.text, last_element.end());
+                if has_trailing_comma && self.text[comma..].starts_with(',') {
+                    self.trailing_comma_removals.insert(comma);
+                }
+            }
+        }
+    }
+
+    /// Scans the tokens of the file, ending with the end-of-file token
+    fn scan(&self) -> Vec<Token> {
+        let mut scanner = Scanner::new();
+        scanner.set_text(self.text.to_string());
+        let mut tokens: Vec<Token> = Vec::new();
+        loop {
+            let mut kind = scanner.scan();
+            let start = scanner.token_start();
+            if let Some(&end) = self.verbatim.get(&start) {
+                scanner.reset_pos(end);
+                tokens.push(Token { kind, start, end });
+                continue;
+            }
+            if kind == SyntaxKind::GreaterThanToken && self.binary_operators.contains(&start) {
+                kind = scanner.re_scan_greater_token();
+            }
+            // A keyword after a dot is the name of a property, like the one of `promise.catch`
+            if is_keyword(kind)
+                && tokens.last().is_some_and(|token| {
+                    matches!(
+                        token.kind,
+                        SyntaxKind::DotToken | SyntaxKind::QuestionDotToken
+                    )
+                })
+            {
+                kind = SyntaxKind::Identifier;
+            }
+            tokens.push(Token {
+                kind,
+                start,
+                end: scanner.token_end(),
+            });
+            if kind == SyntaxKind::EndOfFile {
+                return tokens;
+            }
+        }
+    }
+
+    fn format(&mut self) -> Vec<TextEdit> {
+        let tokens = self.scan();
+        for pair in tokens.windows(2) {
+            if self
+                .binary_operands
+                .contains(&(pair[1].start, pair[0].kind))
+            {
+                self.binary_operators.insert(pair[0].start);
+            }
+        }
+
+        let mut edits = Vec::new();
+        let mut brackets: Vec<OpenBracket> = Vec::new();
+        let mut line_indentation = 0;
+        let mut previous: Option<Token> = None;
+        // A byte order mark is not whitespace to format
+        let mut gap_start = if self.text.starts_with('\u{feff}') {
+            '\u{feff}'.len_utf8()
+        } else {
+            0
+        };
+        for token in &tokens {
+            if self.trailing_comma_insertions.contains(&gap_start) {
+                edits.push(self.edit(gap_start, gap_start, ","));
+            }
+            let gap = &self.text[gap_start..token.start];
+            let new_gap = self.format_gap(
+                gap,
+                previous.as_ref(),
+                token,
+                &brackets,
+                &mut line_indentation,
+            );
+            if new_gap != gap {
+                edits.push(self.edit(gap_start, token.start, &new_gap));
+            }
+            if self.trailing_comma_removals.contains(&token.start) {
+                edits.push(self.edit(token.start, token.end, ""));
+            }
+
+            match token.kind {
+                SyntaxKind::OpenBraceToken
+                | SyntaxKind::OpenParenToken
+                | SyntaxKind::OpenBracketToken => brackets.push(OpenBracket {
+                    kind: token.kind,
+                    pos: token.start,
+                    line_indentation,
+                }),
+                SyntaxKind::CloseBraceToken
+                | SyntaxKind::CloseParenToken
+                | SyntaxKind::CloseBracketToken => {
+                    brackets.pop();
+                }
+                _ => {}
+            }
+            previous = Some(*token);
+            gap_start = token.end;
+        }
+        edits
+    }
+
+    fn edit(&self, pos: usize, end: usize, new_text: &str) -> TextEdit {
+        TextEdit {
+            file_name: self.file_name.to_string(),
+            range: TextRange::new(pos, end),
+            new_text: new_text.to_string(),
+        }
+    }
+
+    /// Formats the whitespace between two tokens, updating the indentation of the current line
+    /// when the second token starts a line
+    fn format_gap(
+        &self,
+        gap: &str,
+        previous: Option<&Token>,
+        token: &Token,
+        brackets: &[OpenBracket],
+        line_indentation: &mut usize,
+    ) -> String {
+        let has_comment = gap.contains("//") || gap.contains("/*");
+        let brace_on_new_line = self
+            .braces
+            .get(&token.start)
+            .filter(|_| token.kind == SyntaxKind::OpenBraceToken)
+            .map(|brace| {
+                // A block on a single line stays there
+                brace.is_multi_line
+                    && if brace.is_function {
+                        self.options.place_open_brace_on_new_line_for_functions
+                    } else {
+                        self.options.place_open_brace_on_new_line_for_control_blocks
+                    }
+            });
+        match brace_on_new_line {
+            // The brace goes under the start of the line of its declaration or statement
+            Some(true) if !has_comment => {
+                return format!("{}{}", self.new_line, self.indent(*line_indentation));
+            }
+            Some(false) if !has_comment => return " ".to_string(),
+            _ => {}
+        }
+
+        if !has_comment && !gap.contains(is_line_break) {
+            let Some(previous) = previous else {
+                // Whitespace at the start of the file
+                return String::new();
+            };
+            return match self.space_between(previous, token, brackets) {
+                Some(true) => " ".to_string(),
+                Some(false) if !self.would_join(previous, token) => String::new(),
+                _ if gap.is_empty() => String::new(),
+                _ => " ".to_string(),
+            };
+        }
+
+        let mut result = String::new();
+        let mut at_line_start = previous.is_none();
+        let mut whitespace = "";
+        let mut rest = gap;
+        while let Some(ch) = rest.chars().next() {
+            if ch == '\r' || is_line_break(ch) {
+                // Whitespace at the end of a line is removed
+                let len = if rest.starts_with("\r\n") {
+                    2
+                } else {
+                    ch.len_utf8()
+                };
+                result.push_str(&rest[..len]);
+                rest = &rest[len..];
+                whitespace = "";
+                at_line_start = true;
+            } else if is_white_space_single_line(ch) {
+                let len = rest
+                    .find(|ch: char| !is_white_space_single_line(ch))
+                    .unwrap_or(rest.len());
+                whitespace = &rest[..len];
+                rest = &rest[len..];
+            } else {
+                let len = if let Some(comment) = rest.strip_prefix("/*") {
+                    comment.find("*/").map_or(rest.len(), |end| end + 4)
+                } else {
+                    rest.find(is_line_break).unwrap_or(rest.len())
+                };
+                if at_line_start {
+                    let pos = token.start - rest.len();
+                    let level = self.content_indentation(pos, brackets);
+                    result.push_str(&self.indent(level));
+                } else {
+                    result.push_str(whitespace);
+                }
+                result.push_str(&rest[..len]);
+                rest = &rest[len..];
+                whitespace = "";
+                at_line_start = false;
+            }
+        }
+        if at_line_start {
+            *line_indentation = self.indentation(previous, token, brackets);
+            if token.kind != SyntaxKind::EndOfFile {
+                result.push_str(&self.indent(*line_indentation));
+            }
+        } else {
+            result.push_str(whitespace);
+        }
+        result
+    }
+
+    /// Gets the indentation level of a token that starts a line
+    fn indentation(
+        &self,
+        previous: Option<&Token>,
+        token: &Token,
+        brackets: &[OpenBracket],
+    ) -> usize {
+        if let Some(bracket) = brackets.last()
+            && is_closing_bracket(bracket.kind, token.kind)
+        {
+            return bracket.line_indentation;
+        }
+        let level = self.content_indentation(token.start, brackets);
+        if previous.is_some_and(|previous| self.is_continuation(previous, token)) {
+            level + 1
+        } else {
+            level
+        }
+    }
+
+    /// Gets the indentation level of a line that starts a statement or element at a position
+    fn content_indentation(&self, pos: usize, brackets: &[OpenBracket]) -> usize {
+        let bracket = brackets.last();
+        let indented = self
+            .indented_ranges
+            .iter()
+            .filter(|range| range.pos() <= pos && pos < range.end())
+            // The ranges inside the bracket add to its indentation, and the others are in it
+            .filter(|range| bracket.is_none_or(|bracket| range.pos() > bracket.pos))
+            .count();
+        bracket.map_or(0, |bracket| bracket.line_indentation + 1) + indented
+    }
+
+    /// Determines whether a token that starts a line continues what the line before started,
+    /// like an operand, a chained call, or the `extends` clause of a class
+    fn is_continuation(&self, previous: &Token, token: &Token) -> bool {
+        if self.element_starts.contains(&token.start)
+            || self.decorator_ends.contains(&previous.end)
+            || self
+                .indented_ranges
+                .iter()
+                .any(|range| range.pos() == token.start)
+        {
+            return false;
+        }
+        match (previous.kind, token.kind) {
+            (
+                SyntaxKind::OpenBraceToken
+                | SyntaxKind::OpenParenToken
+                | SyntaxKind::OpenBracketToken
+                | SyntaxKind::CommaToken
+                | SyntaxKind::SemicolonToken,
+                _,
+            )
+            | (
+                _,
+                SyntaxKind::CloseBraceToken
+                | SyntaxKind::CloseParenToken
+                | SyntaxKind::CloseBracketToken
+                | SyntaxKind::ElseKeyword
+                | SyntaxKind::CatchKeyword
+                | SyntaxKind::FinallyKeyword
+                | SyntaxKind::EndOfFile,
+            )
+            // The `while` of a `do` statement
+            | (SyntaxKind::CloseBraceToken, SyntaxKind::WhileKeyword) => false,
+            _ => true,
+        }
+    }
+
+    /// Decides whether there is a space between two tokens on the same line, or `None` to keep
+    /// whether there is one
+    fn space_between(
+        &self,
+        previous: &Token,
+        token: &Token,
+        brackets: &[OpenBracket],
+    ) -> Option<bool> {
+        use SyntaxKind::*;
+
+        let options = self.options;
+        for operator in [previous, token] {
+            if self.binary_operators.contains(&operator.start) {
+                return Some(
+                    options.insert_space_before_and_after_binary_operators
+                        || is_keyword(operator.kind),
+                );
+            }
+        }
+        let in_parentheses = brackets
+            .last()
+            .is_some_and(|bracket| bracket.kind == OpenParenToken);
+        match (previous.kind, token.kind) {
+            (_, EndOfFile | CommaToken | SemicolonToken) => Some(false),
+            (CommaToken, CloseParenToken | CloseBracketToken) => Some(false),
+            (CommaToken, _) => Some(options.insert_space_after_comma_delimiter),
+            (SemicolonToken, CloseParenToken) => Some(false),
+            (SemicolonToken, _) => {
+                Some(!in_parentheses || options.insert_space_after_semicolon_in_for_statements)
+            }
+            (OpenParenToken | OpenBracketToken, _) | (_, CloseParenToken | CloseBracketToken) => {
+                Some(false)
+            }
+            (OpenBraceToken, CloseBraceToken) => Some(false),
+            (OpenBraceToken, _) | (_, CloseBraceToken) => {
+                Some(options.insert_space_after_opening_and_before_closing_nonempty_braces)
+            }
+            (
+                IfKeyword | ForKeyword | WhileKeyword | SwitchKeyword | CatchKeyword | WithKeyword,
+                OpenParenToken,
+            ) => Some(options.insert_space_after_keywords_in_control_flow_statements),
+            (Identifier | ConstructorKeyword | SuperKeyword, OpenParenToken) => Some(false),
+            (CloseBraceToken, ElseKeyword | CatchKeyword | FinallyKeyword | WhileKeyword) => {
+                Some(true)
+            }
+            (LessThanToken | DotDotDotToken, OpenBraceToken) => Some(false),
+            (_, OpenBraceToken) => Some(true),
+            (DotToken | QuestionDotToken | DotDotDotToken | AtToken, _) => Some(false),
+            // `1 .toString()` needs its space
+            (NumericLiteral, DotToken) => None,
+            (_, DotToken | QuestionDotToken | QuestionToken | ColonToken) => Some(false),
+            (ColonToken, _) => Some(true),
+            _ if self.prefix_operators.contains(&previous.start) => Some(false),
+            _ if self.postfix_operators.contains(&token.start) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Determines whether two tokens would scan as one without a space between them, like the
+    /// two minus signs of `a - -b`
+    fn would_join(&self, previous: &Token, token: &Token) -> bool {
+        let (Some(last), Some(first)) = (
+            self.text[previous.start..previous.end].chars().next_back(),
+            self.text[token.start..token.end].chars().next(),
+        ) else {
+            return false;
+        };
+        (is_identifier_part(last, ScriptTarget::Latest)
+            && is_identifier_part(first, ScriptTarget::Latest))
+            || (last == first && "+-&|<>=".contains(last))
+            || (last == '/' && (first == '/' || first == '*'))
+    }
+
+    fn indent(&self, level: usize) -> String {
+        let width = level * self.options.indent_size;
+        if self.options.convert_tabs_to_spaces {
+            " ".repeat(width)
+        } else {
+            let tab_size = self.options.tab_size.max(1);
+            "\t".repeat(width / tab_size) + &" ".repeat(width % tab_size)
+        }
+    }
+}
+
+fn is_closing_bracket(open: SyntaxKind, close: SyntaxKind) -> bool {
+    matches!(
+        (open, close),
+        (SyntaxKind::OpenBraceToken, SyntaxKind::CloseBraceToken)
+            | (SyntaxKind::OpenParenToken, SyntaxKind::CloseParenToken)
+            | (SyntaxKind::OpenBracketToken, SyntaxKind::CloseBracketToken)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::scanner::ScriptKind;
+
+    fn format(text: &str, options: &FormatOptions) -> String {
+        let tree = parse_source_file("a.ts", text, ScriptTarget::Latest, ScriptKind::TS);
+        apply_text_edits(text, &get_formatting_edits(&tree, options))
+    }
+
+    #[test]
+    fn indents_and_spaces_tokens() {
+        let text = "\
+import {a,b as c} from \"./m\";
+function  foo (x:number,y ?: string) : number{
+if(x>-a){return x}
+  else
+  return 0   
+    switch (x) {
+  case 1:
+  // one
+  c ();
+  default:
+  break
+    }
+  const o = {a:1,b:[1,2],
+  c: `${ a+b }`
+  }
+  return promise
+  .then(( r ) => r!)
+  .catch(e => {throw e});
+}
+";
+        let expected = "\
+import { a, b as c } from \"./m\";
+function foo(x: number, y?: string): number {
+    if (x > -a) { return x }
+    else
+        return 0
+    switch (x) {
+        case 1:
+            // one
+            c();
+        default:
+            break
+    }
+    const o = { a: 1, b: [1, 2],
+        c: `${ a+b }`
+    }
+    return promise
+        .then((r) => r!)
+        .catch(e => { throw e });
+}
+";
+        assert_eq!(format(text, &FormatOptions::default()), expected);
+        // Formatting is stable
+        assert_eq!(format(expected, &FormatOptions::default()), expected);
+        assert_eq!(
+            format(&text.replace('\n', "\r\n"), &FormatOptions::default()),
+            expected.replace('\n', "\r\n")
+        );
+    }
+
+    #[test]
+    fn places_braces_and_trailing_commas_by_the_options() {
+        let text = "\
+class A {
+    m() { return [
+        1,
+        2
+    ] }
+}
+if (a) {
+    b({ c: 1, });
+}
+";
+        let options = FormatOptions {
+            convert_tabs_to_spaces: false,
+            place_open_brace_on_new_line_for_functions: true,
+            place_open_brace_on_new_line_for_control_blocks: true,
+            trailing_commas: TrailingCommas::Insert,
+            ..FormatOptions::default()
+        };
+        let formatted = "\
+class A
+{
+\tm()
+\t{ return [
+\t\t1,
+\t\t2,
+\t] }
+}
+if (a)
+{
+\tb({ c: 1, });
+}
+";
+        assert_eq!(format(text, &options), formatted);
+        let options = FormatOptions {
+            trailing_commas: TrailingCommas::Remove,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(formatted, &options),
+            text.replace("{ c: 1, }", "{ c: 1 }")
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! The outline of a file: its declarations and the members of them, as a tree
+//!
+//! The tree follows the syntax of the file, without the checker. Functions and methods hold
+//! the declarations in their bodies, and a variable holds the members of the function or
+//! class it is initialized with. Declarations that merge, like the two halves of a namespace
+//! declared twice, are separate items, in the order they are written.
+
+use std::sync::Arc;
+
+use super::LanguageService;
+use crate::compiler::ast::utilities::{
+    get_error_range, get_property_name_text, get_text_of_node, is_external_module,
+    is_parameter_property_declaration,
+};
+use crate::compiler::ast::{Node, NodeFlags, SyntaxKind};
+use crate::compiler::text::TextRange;
+
+/// What a declaration in an outline is, named as tsserver names them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptElementKind {
+    Module,
+    Class,
+    Interface,
+    TypeAlias,
+    Enum,
+    EnumMember,
+    Variable,
+    Let,
+    Const,
+    Function,
+    /// A function declared in the body of another function
+    LocalFunction,
+    Method,
+    Property,
+    Constructor,
+    GetAccessor,
+    SetAccessor,
+    Parameter,
+    TypeParameter,
+    /// A name imported from another module
+    Alias,
+    Keyword,
+}
+
+impl ScriptElementKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScriptElementKind::Module => "module",
+            ScriptElementKind::Class => "class",
+            ScriptElementKind::Interface => "interface",
+            ScriptElementKind::TypeAlias => "type",
+            ScriptElementKind::Enum => "enum",
+            ScriptElementKind::EnumMember => "enum member",
+            ScriptElementKind::Variable => "var",
+            ScriptElementKind::Let => "let",
+            ScriptElementKind::Const => "const",
+            ScriptElementKind::Function => "function",
+            ScriptElementKind::LocalFunction => "local function",
+            ScriptElementKind::Method => "method",
+            ScriptElementKind::Property => "property",
+            ScriptElementKind::Constructor => "constructor",
+            ScriptElementKind::GetAccessor => "getter",
+            ScriptElementKind::SetAccessor => "setter",
+            ScriptElementKind::Parameter => "parameter",
+            ScriptElementKind::TypeParameter => "type parameter",
+            ScriptElementKind::Alias => "alias",
+            ScriptElementKind::Keyword => "keyword",
+        }
+    }
+
+    /// Gets the `SymbolKind` of the Language Server Protocol for this kind, which a
+    /// `textDocument/documentSymbol` response gives each symbol
+    pub fn lsp_symbol_kind(self) -> u32 {
+        match self {
+            ScriptElementKind::Module => 2,
+            ScriptElementKind::Class => 5,
+            ScriptElementKind::Method => 6,
+            ScriptElementKind::Property
+            | ScriptElementKind::GetAccessor
+            | ScriptElementKind::SetAccessor => 7,
+            ScriptElementKind::Constructor => 9,
+            ScriptElementKind::Enum => 10,
+            ScriptElementKind::Interface | ScriptElementKind::TypeAlias => 11,
+            ScriptElementKind::Function | ScriptElementKind::LocalFunction => 12,
+            ScriptElementKind::Variable
+            | ScriptElementKind::Let
+            | ScriptElementKind::Parameter
+            | ScriptElementKind::Alias => 13,
+            ScriptElementKind::Const => 14,
+            ScriptElementKind::EnumMember => 22,
+            ScriptElementKind::TypeParameter => 26,
+            // Keywords are not symbols, so they take the closest kind
+            ScriptElementKind::Keyword => 13,
+        }
+    }
+
+    /// Gets the `CompletionItemKind` of the Language Server Protocol for this kind, which a
+    /// `textDocument/completion` response gives each item
+    pub fn lsp_completion_item_kind(self) -> u32 {
+        match self {
+            ScriptElementKind::Method => 2,
+            ScriptElementKind::Function | ScriptElementKind::LocalFunction => 3,
+            ScriptElementKind::Constructor => 4,
+            ScriptElementKind::Property
+            | ScriptElementKind::GetAccessor
+            | ScriptElementKind::SetAccessor => 10,
+            ScriptElementKind::Variable
+            | ScriptElementKind::Let
+            | ScriptElementKind::Parameter
+            | ScriptElementKind::Alias => 6,
+            ScriptElementKind::Class => 7,
+            ScriptElementKind::Interface | ScriptElementKind::TypeAlias => 8,
+            ScriptElementKind::Module => 9,
+            ScriptElementKind::Enum => 13,
+            ScriptElementKind::Keyword => 14,
+            ScriptElementKind::EnumMember => 20,
+            ScriptElementKind::Const => 21,
+            ScriptElementKind::TypeParameter => 25,
+        }
+    }
+}
+
+/// A declaration in the outline of a file, with the declarations within it
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavigationTree {
+    pub text: String,
+    pub kind: ScriptElementKind,
+    /// The modifiers of the declaration, like `export,declare`, separated by commas
+    pub kind_modifiers: String,
+    /// The whole declaration, without its leading trivia
+    pub span: TextRange,
+    /// The name of the declaration, when it has one
+    pub name_span: Option<TextRange>,
+    pub child_items: Vec<NavigationTree>,
+}
+
+impl LanguageService<'_> {
+    /// Gets the outline of a file of the program, whose root item is the file itself
+    pub fn get_navigation_tree(&self, file_name: &str) -> Option<NavigationTree> {
+        let tree = self.program.get_syntax_tree(file_name)?;
+        let source_file = tree.as_source_file();
+        let text = if is_external_module(tree) {
+            format!("\"{}\"", module_name(&source_file.file_name))
+        } else {
+            "<global>".to_string()
+        };
+        let mut root = NavigationTree {
+            text,
+            kind: ScriptElementKind::Module,
+            kind_modifiers: String::new(),
+            span: TextRange::new(0, source_file.text.len()),
+            name_span: None,
+            child_items: Vec::new(),
+        };
+        let builder = Builder { tree };
+        for statement in source_file.statements.iter() {
+            builder.add_statement(statement, false, &mut root.child_items);
+        }
+        Some(root)
+    }
+}
+
+/// Gets the name tsserver gives the root of a module's outline: its file name without the
+/// folders and extension
+fn module_name(file_name: &str) -> &str {
+    let base_name = file_name.rsplit('/').next().unwrap_or(file_name);
+    [
+        ".d.ts", ".d.mts", ".d.cts", ".ts", ".tsx", ".mts", ".cts", ".js", ".jsx",
+    ]
+    .iter()
+    .find_map(|extension| base_name.strip_suffix(extension))
+    .unwrap_or(base_name)
+}
+
+struct Builder<'a> {
+    tree: &'a Node,
+}
+
+impl Builder<'_> {
+    /// Adds the items a statement declares, where `is_local` is whether it is in the body of
+    /// a function
+    fn add_statement(
+        &self,
+        statement: &Arc<Node>,
+        is_local: bool,
+        items: &mut Vec<NavigationTree>,
+    ) {
+        match statement.kind {
+            SyntaxKind::FunctionDeclaration => {
+                let kind = if is_local {
+                    ScriptElementKind::LocalFunction
+                } else {
+                    ScriptElementKind::Function
+                };
+                let mut item = self.item(statement, kind);
+                self.add_body(statement, &mut item.child_items);
+                items.push(item);
+            }
+            SyntaxKind::ClassDeclaration => items.push(self.class(statement, statement)),
+            SyntaxKind::InterfaceDeclaration => {
+                let mut item = self.item(statement, ScriptElementKind::Interface);
+                for member in statement.as_interface_declaration().members.iter() {
+                    self.add_member(member, &mut item.child_items);
+                }
+                items.push(item);
+            }
+            SyntaxKind::TypeAliasDeclaration => {
+                items.push(self.item(statement, ScriptElementKind::TypeAlias));
+            }
+            SyntaxKind::EnumDeclaration => {
+                let mut item = self.item(statement, ScriptElementKind::Enum);
+                for member in statement.as_enum_declaration().members.iter() {
+                    item.child_items
+                        .push(self.item(member, ScriptElementKind::EnumMember));
+                }
+                items.push(item);
+            }
+            SyntaxKind::ModuleDeclaration => items.push(self.module(statement)),
+            SyntaxKind::VariableStatement => {
+                let list = &statement.as_variable_statement().declaration_list;
+                for declaration in list.as_variable_declaration_list().declarations.iter() {
+                    self.add_variable(statement, declaration, items);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds a variable that has a name, taking the members of the function or class it is
+    /// initialized with
+    fn add_variable(
+        &self,
+        statement: &Arc<Node>,
+        declaration: &Arc<Node>,
+        items: &mut Vec<NavigationTree>,
+    ) {
+        let name = &declaration.as_variable_declaration().name;
+        if name.kind != SyntaxKind::Identifier {
+            return;
+        }
+        let flags = declaration
+            .parent()
+            .map_or(NodeFlags::empty(), |list| list.flags);
+        let mut kind = if flags.intersects(NodeFlags::CONSTANT) {
+            ScriptElementKind::Const
+        } else if flags.contains(NodeFlags::LET) {
+            ScriptElementKind::Let
+        } else {
+            ScriptElementKind::Variable
+        };
+        let mut child_items = Vec::new();
+        if let Some(initializer) = declaration.initializer() {
+            match initializer.kind {
+                SyntaxKind::FunctionExpression | SyntaxKind::ArrowFunction => {
+                    kind = ScriptElementKind::Function;
+                    self.add_body(initializer, &mut child_items);
+                }
+                SyntaxKind::ClassExpression => {
+                    kind = ScriptElementKind::Class;
+                    child_items = self.class(initializer, declaration).child_items;
+                }
+                _ => {}
+            }
+        }
+        items.push(NavigationTree {
+            text: name.text().to_string(),
+            kind,
+            kind_modifiers: get_kind_modifiers(statement),
+            span: get_error_range(self.tree, declaration),
+            name_span: Some(get_error_range(self.tree, name)),
+            child_items,
+        });
+    }
+
+    /// Makes the item of a class, which takes its name from `named` when it has none of its
+    /// own, as a class expression assigned to a variable does
+    fn class(&self, class: &Arc<Node>, named: &Arc<Node>) -> NavigationTree {
+        let mut item = self.item(class, ScriptElementKind::Class);
+        if class.name().is_none() && !Arc::ptr_eq(class, named) {
+            item.text = self.name_text(named);
+            item.name_span = named.name().map(|name| get_error_range(self.tree, name));
+        }
+        for member in class
+            .members()
+            .into_iter()
+            .flat_map(|members| members.iter())
+        {
+            if member.kind == SyntaxKind::Constructor {
+                // Parameters like `private x: number` declare properties of the class
+                for parameter in member.parameters().into_iter().flat_map(|list| list.iter()) {
+                    if is_parameter_property_declaration(parameter) {
+                        item.child_items
+                            .push(self.item(parameter, ScriptElementKind::Property));
+                    }
+                }
+            }
+            self.add_member(member, &mut item.child_items);
+        }
+        item
+    }
+
+    /// Adds an item for a member of a class or interface
+    fn add_member(&self, member: &Arc<Node>, items: &mut Vec<NavigationTree>) {
+        let kind = match member.kind {
+            SyntaxKind::MethodDeclaration | SyntaxKind::MethodSignature => {
+                ScriptElementKind::Method
+            }
+            SyntaxKind::PropertyDeclaration | SyntaxKind::PropertySignature => {
+                ScriptElementKind::Property
+            }
+            SyntaxKind::Constructor => ScriptElementKind::Constructor,
+            SyntaxKind::GetAccessor => ScriptElementKind::GetAccessor,
+            SyntaxKind::SetAccessor => ScriptElementKind::SetAccessor,
+            _ => return,
+        };
+        let mut item = self.item(member, kind);
+        if member.body().is_some() {
+            self.add_body(member, &mut item.child_items);
+        }
+        items.push(item);
+    }
+
+    /// Makes the item of a namespace, whose dotted name, like `a.b`, is a single item
+    fn module(&self, declaration: &Arc<Node>) -> NavigationTree {
+        let mut item = self.item(declaration, ScriptElementKind::Module);
+        let mut body = declaration.as_module_declaration().body.clone();
+        while let Some(inner) = body.take() {
+            match inner.kind {
+                SyntaxKind::ModuleDeclaration => {
+                    let name = &inner.as_module_declaration().name;
+                    item.text = format!("{}.{}", item.text, self.name_text(&inner));
+                    item.name_span = item.name_span.map(|span| {
+                        TextRange::new(span.pos(), get_error_range(self.tree, name).end())
+                    });
+                    body = inner.as_module_declaration().body.clone();
+                }
+                SyntaxKind::ModuleBlock => {
+                    for statement in inner.as_module_block().statements.iter() {
+                        self.add_statement(statement, false, &mut item.child_items);
+                    }
+                }
+                _ => {}
+            }
+        }
+        item
+    }
+
+    /// Adds the declarations in the body of a function-like declaration
+    fn add_body(&self, function: &Arc<Node>, items: &mut Vec<NavigationTree>) {
+        let Some(body) = function.body() else {
+            return;
+        };
+        if let Some(statements) = body.statements() {
+            for statement in statements.iter() {
+                self.add_statement(statement, true, items);
+            }
+        }
+    }
+
+    fn item(&self, declaration: &Arc<Node>, kind: ScriptElementKind) -> NavigationTree {
+        NavigationTree {
+            text: self.name_text(declaration),
+            kind,
+            kind_modifiers: get_kind_modifiers(declaration),
+            span: get_error_range(self.tree, declaration),
+            name_span: declaration
+                .name()
+                .map(|name| get_error_range(self.tree, name)),
+            child_items: Vec::new(),
+        }
+    }
+
+    fn name_text(&self, declaration: &Node) -> String {
+        match declaration.name() {
+            Some(name) => get_property_name_text(name)
+                .unwrap_or_else(|| get_text_of_node(self.tree, name).to_string()),
+            None if declaration.kind == SyntaxKind::Constructor => "constructor".to_string(),
+            None if declaration.has_modifier(SyntaxKind::DefaultKeyword) => "default".to_string(),
+            None => "<anonymous>".to_string(),
+        }
+    }
+}
+
+/// Gets the modifiers of a declaration, like `export,declare`, separated by commas
+pub(super) fn get_kind_modifiers(declaration: &Node) -> String {
+    let mut modifiers = Vec::new();
+    for modifier in declaration
+        .modifiers()
+        .into_iter()
+        .flat_map(|list| list.iter())
+    {
+        let text = match modifier.kind {
+            SyntaxKind::ExportKeyword => "export",
+            SyntaxKind::DeclareKeyword => "declare",
+            SyntaxKind::AbstractKeyword => "abstract",
+            SyntaxKind::StaticKeyword => "static",
+            SyntaxKind::PublicKeyword => "public",
+            SyntaxKind::PrivateKeyword => "private",
+            SyntaxKind::ProtectedKeyword => "protected",
+            SyntaxKind::AsyncKeyword => "async",
+            _ => continue,
+        };
+        modifiers.push(text);
+    }
+    if declaration.is_deprecated() {
+        modifiers.push("deprecated");
+    }
+    modifiers.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::VirtualCompilerHost;
+    use crate::compile::tests::create;
+
+    /// Writes a line for each item, indented by its depth
+    fn outline(item: &NavigationTree, depth: usize, lines: &mut Vec<String>) {
+        let mut line = format!("{}{} {}", "  ".repeat(depth), item.kind.as_str(), item.text);
+        if !item.kind_modifiers.is_empty() {
+            line += &format!(" ({})", item.kind_modifiers);
+        }
+        lines.push(line);
+        for child in &item.child_items {
+            outline(child, depth + 1, lines);
+        }
+    }
+
+    #[test]
+    fn outlines_declarations_and_their_members() {
+        let text = "\
+export class Point {
+    constructor(private x: number) {}
+    static get origin() { return 0; }
+    move(dx: number) { function step() {} }
+}
+interface Shape { area(): number; name: string; }
+enum Color { Red, Green }
+namespace a.b { export const c = 1; }
+let make = () => { class Inner {} };
+type Id = string;
+";
+        let program = create(
+            &VirtualCompilerHost::with_files(&[("shapes.ts", text)]),
+            &["shapes.ts"],
+        );
+        let service = LanguageService::new(&program);
+        let tree = service.get_navigation_tree("shapes.ts").unwrap();
+        let mut lines = Vec::new();
+        outline(&tree, 0, &mut lines);
+        assert_eq!(
+            lines,
+            [
+                "module \"shapes\"",
+                "  class Point (export)",
+                "    property x (private)",
+                "    constructor constructor",
+                "    getter origin (static)",
+                "    method move",
+                "      local function step",
+                "  interface Shape",
+                "    method area",
+                "    property name",
+                "  enum Color",
+                "    enum member Red",
+                "    enum member Green",
+                "  module a.b",
+                "    const c (export)",
+                "  function make",
+                "    class Inner",
+                "  type Id",
+            ]
+        );
+
+        let point = &tree.child_items[0];
+        assert_eq!(
+            &text[point.span.pos()..point.span.pos() + 12],
+            "export class"
+        );
+        assert_eq!(point.span.end(), text.find("\ninterface").unwrap());
+        let name_span = point.name_span.unwrap();
+        assert_eq!(&text[name_span.pos()..name_span.end()], "Point");
+        let namespace = &tree.child_items[3].name_span.unwrap();
+        assert_eq!(&text[namespace.pos()..namespace.end()], "a.b");
+        assert_eq!(point.kind.lsp_symbol_kind(), 5);
+    }
+
+    #[test]
+    fn names_the_root_of_a_script_global() {
+        let program = create(
+            &VirtualCompilerHost::with_files(&[("shapes.ts", "function f() {}")]),
+            &["shapes.ts"],
+        );
+        let service = LanguageService::new(&program);
+        let tree = service.get_navigation_tree("shapes.ts").unwrap();
+        assert_eq!(tree.text, "<global>");
+        assert_eq!(tree.child_items[0].kind, ScriptElementKind::Function);
+        assert!(service.get_navigation_tree("missing.ts").is_none());
+    }
+}
@@ -0,0 +1,483 @@
+//! Organizing the imports of a file: removing those that are unused, merging those of the
+//! same module, and sorting them
+//!
+//! Each run of consecutive import declarations at the top level of a file is organized on its
+//! own, and replaced by the organized imports. An import whose names are all unused is
+//! removed, unless it imports the module only for its side effects. Whether a name is used
+//! is decided by name, so a name that a local declaration shadows still keeps its import.
+//! With the classic JSX runtime, JSX uses the names its factories are called through.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::{LanguageService, TextEdit};
+use crate::compiler::ast::utilities::{get_error_range, get_text_of_node};
+use crate::compiler::ast::visitor::for_each_descendant;
+use crate::compiler::ast::{Node, SyntaxKind};
+use crate::compiler::module_resolution::is_external_module_name_relative;
+use crate::compiler::text::TextRange;
+use crate::compiler::transformers::JsxEmit;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OrganizeImportsOptions {
+    /// Whether names are sorted ignoring their case, or with `None`, whichever way the
+    /// imports of the file are sorted already, ignoring case when they are sorted both ways
+    /// or neither
+    pub ignore_case: Option<bool>,
+}
+
+/// The parts of one import declaration
+#[derive(Debug, Clone)]
+struct Import {
+    module_specifier: String,
+    is_type_only: bool,
+    default_name: Option<String>,
+    namespace_name: Option<String>,
+    /// The names taken from the module, as written, like `a` or `a as b`, with the names
+    /// they bind
+    named: Vec<(String, String)>,
+    /// Whether the declaration has a `{ }` list of names, even an empty one
+    has_named_imports: bool,
+    /// The original text of a declaration with import attributes, which is kept as it is
+    attributes_text: Option<String>,
+}
+
+impl LanguageService<'_> {
+    /// Gets the edits that organize the imports of a file
+    pub fn organize_imports(
+        &self,
+        file_name: &str,
+        options: &OrganizeImportsOptions,
+    ) -> Vec<TextEdit> {
+        let Some(tree) = self.program.get_syntax_tree(file_name) else {
+            return Vec::new();
+        };
+        let source_file = tree.as_source_file();
+        let mut used_names = HashSet::new();
+        for statement in source_file.statements.iter() {
+            if statement.kind != SyntaxKind::ImportDeclaration {
+                collect_used_names(statement, &mut used_names);
+            }
+        }
+        // `<a />` becomes `React.createElement("a")`, which uses the import of `React`
+        let compiler_options = &self.program.options;
+        if compiler_options.jsx == JsxEmit::React && contains_jsx(tree) {
+            let factory = compiler_options
+                .jsx_factory
+                .as_deref()
+                .unwrap_or("React.createElement");
+            let fragment_factory = compiler_options
+                .jsx_fragment_factory
+                .as_deref()
+                .unwrap_or("React.Fragment");
+            for entity_name in [factory, fragment_factory] {
+                let root = entity_name.split('.').next().unwrap_or(entity_name);
+                used_names.insert(root.to_string());
+            }
+        }
+
+        let mut groups: Vec<Vec<&Arc<Node>>> = Vec::new();
+        let mut previous_was_import = false;
+        for statement in source_file.statements.iter() {
+            let is_import = statement.kind == SyntaxKind::ImportDeclaration;
+            if is_import {
+                match groups.last_mut() {
+                    Some(group) if previous_was_import => group.push(statement),
+                    _ => groups.push(vec![statement]),
+                }
+            }
+            previous_was_import = is_import;
+        }
+        let ignore_case = options
+            .ignore_case
+            .unwrap_or_else(|| detect_ignore_case(tree, &groups));
+
+        let mut edits = Vec::new();
+        for group in groups {
+            let imports: Vec<Import> = group
+                .iter()
+                .map(|declaration| read_import(tree, declaration))
+                .collect();
+            let organized = organize(imports, &used_names, ignore_case);
+            let first = get_error_range(tree, group[0]).pos();
+            let mut end = group[group.len() - 1].end();
+            let new_text = organized
+                .iter()
+                .map(print_import)
+                .collect::<Vec<_>>()
+                .join("\n");
+            if new_text.is_empty() {
+                // Take the line break after the removed imports with them
+                let rest = &source_file.text[end..];
+                let line_end = rest.find('\n').map_or(rest.len(), |index| index + 1);
+                if rest[..line_end].trim().is_empty() {
+                    end += line_end;
+                }
+            } else if new_text == source_file.text[first..end] {
+                continue;
+            }
+            edits.push(TextEdit {
+                file_name: source_file.file_name.clone(),
+                range: TextRange::new(first, end),
+                new_text,
+            });
+        }
+        edits
+    }
+}
+
+impl Import {
+    /// Whether the import binds any names, rather than importing the module only for its
+    /// side effects
+    fn has_bindings(&self) -> bool {
+        self.default_name.is_some() || self.namespace_name.is_some() || self.has_named_imports
+    }
+}
+
+fn read_import(tree: &Node, declaration: &Node) -> Import {
+    let import = declaration.as_import_declaration();
+    let mut result = Import {
+        module_specifier: get_text_of_node(tree, &import.module_specifier).to_string(),
+        is_type_only: false,
+        default_name: None,
+        namespace_name: None,
+        named: Vec::new(),
+        has_named_imports: false,
+        attributes_text: import
+            .attributes
+            .is_some()
+            .then(|| get_text_of_node(tree, declaration).to_string()),
+    };
+    let Some(clause) = &import.import_clause else {
+        return result;
+    };
+    let clause = clause.as_import_clause();
+    result.is_type_only = clause.is_type_only;
+    result.default_name = clause.name.as_ref().map(|name| name.text().to_string());
+    match &clause.named_bindings {
+        Some(bindings) if bindings.kind == SyntaxKind::NamespaceImport => {
+            result.namespace_name = Some(bindings.as_namespace_import().name.text().to_string());
+        }
+        Some(bindings) => {
+            result.has_named_imports = true;
+            for element in bindings.as_named_imports().elements.iter() {
+                let name = element.as_import_specifier().name.text().to_string();
+                result
+                    .named
+                    .push((get_text_of_node(tree, element).to_string(), name));
+            }
+        }
+        None => {}
+    }
+    result
+}
+
+/// Removes the unused names of imports and the imports left with none, merges the imports
+/// of the same module, and sorts them
+fn organize(imports: Vec<Import>, used_names: &HashSet<String>, ignore_case: bool) -> Vec<Import> {
+    let mut organized: Vec<Import> = Vec::new();
+    for mut import in imports {
+        if import.attributes_text.is_none() {
+            let is_side_effect_import = !import.has_bindings();
+            import.default_name = import.default_name.filter(|name| used_names.contains(name));
+            import.namespace_name = import
+                .namespace_name
+                .filter(|name| used_names.contains(name));
+            import.named.retain(|(_, name)| used_names.contains(name));
+            import.has_named_imports = !import.named.is_empty();
+            if !import.has_bindings() && !is_side_effect_import {
+                continue;
+            }
+        }
+        // A namespace import cannot share a declaration with named imports, and a
+        // declaration has at most one default import
+        let merged_into = organized.iter_mut().find(|existing| {
+            existing.attributes_text.is_none()
+                && import.attributes_text.is_none()
+                && unquote(&existing.module_specifier) == unquote(&import.module_specifier)
+                && existing.is_type_only == import.is_type_only
+                && existing.namespace_name.is_none()
+                && import.namespace_name.is_none()
+                && existing.has_bindings()
+                && import.has_bindings()
+                && !(existing.default_name.is_some() && import.default_name.is_some())
+                // A type-only import has a default import or named imports, not both
+                && !(import.is_type_only
+                    && (existing.default_name.is_some() || import.default_name.is_some()))
+        });
+        match merged_into {
+            Some(existing) => {
+                existing.default_name = existing.default_name.take().or(import.default_name);
+                for (text, name) in import.named {
+                    if !existing.named.iter().any(|(existing, _)| *existing == text) {
+                        existing.named.push((text, name));
+                    }
+                }
+                existing.has_named_imports |= import.has_named_imports;
+            }
+            None => organized.push(import),
+        }
+    }
+    // Named imports are sorted by the names they bind
+    for import in &mut organized {
+        if import.attributes_text.is_none() {
+            import
+                .named
+                .sort_by(|(_, a), (_, b)| compare_names(a, b, ignore_case));
+        }
+    }
+    // Packages go before relative paths, and the sort is stable so that the imports of a
+    // module stay in the order they were written
+    organized.sort_by(|a, b| {
+        let (a_name, b_name) = (unquote(&a.module_specifier), unquote(&b.module_specifier));
+        is_external_module_name_relative(a_name)
+            .cmp(&is_external_module_name_relative(b_name))
+            .then_with(|| compare_names(a_name, b_name, ignore_case))
+    });
+    organized
+}
+
+fn print_import(import: &Import) -> String {
+    if let Some(text) = &import.attributes_text {
+        return text.clone();
+    }
+    let mut bindings = Vec::new();
+    if let Some(name) = &import.default_name {
+        bindings.push(name.clone());
+    }
+    if let Some(name) = &import.namespace_name {
+        bindings.push(format!("* as {name}"));
+    }
+    if import.has_named_imports {
+        let names: Vec<&str> = import.named.iter().map(|(text, _)| text.as_str()).collect();
+        bindings.push(format!("{{ {} }}", names.join(", ")));
+    }
+    if bindings.is_empty() {
+        return format!("import {};", import.module_specifier);
+    }
+    let type_keyword = if import.is_type_only { "type " } else { "" };
+    format!(
+        "import {type_keyword}{} from {};",
+        bindings.join(", "),
+        import.module_specifier
+    )
+}
+
+/// Compares names as tsserver sorts imports, where ignoring case still puts names that differ
+/// only in case in a fixed order
+fn compare_names(a: &str, b: &str, ignore_case: bool) -> Ordering {
+    if ignore_case {
+        a.to_lowercase()
+            .cmp(&b.to_lowercase())
+            .then_with(|| a.cmp(b))
+    } else {
+        a.cmp(b)
+    }
+}
+
+/// Whether the imports of a file are sorted ignoring case, judging by the named imports and
+/// module specifiers that are sorted one way but not the other
+fn detect_ignore_case(tree: &Node, groups: &[Vec<&Arc<Node>>]) -> bool {
+    let mut lists: Vec<Vec<String>> = Vec::new();
+    for group in groups {
+        let imports: Vec<Import> = group
+            .iter()
+            .map(|declaration| read_import(tree, declaration))
+            .collect();
+        lists.push(
+            imports
+                .iter()
+                .map(|import| unquote(&import.module_specifier).to_string())
+                .collect(),
+        );
+        lists.extend(
+            imports
+                .into_iter()
+                .map(|import| import.named.into_iter().map(|(_, name)| name).collect()),
+        );
+    }
+    let is_sorted = |ignore_case: bool| {
+        lists.iter().all(|list| {
+            list.windows(2)
+                .all(|pair| compare_names(&pair[0], &pair[1], ignore_case) != Ordering::Greater)
+        })
+    };
+    is_sorted(true) || !is_sorted(false)
+}
+
+fn unquote(module_specifier: &str) -> &str {
+    module_specifier
+        .get(1..module_specifier.len().saturating_sub(1))
+        .unwrap_or(module_specifier)
+}
+
+/// Collects the names that identifiers refer to, leaving out the names of declarations and
+/// of properties, which do not refer to an import
+fn collect_used_names(node: &Arc<Node>, names: &mut HashSet<String>) {
+    node.for_each_child(&mut |child| {
+        if child.kind == SyntaxKind::Identifier {
+            if refers_to_a_name(child) {
+                names.insert(child.text().to_string());
+            }
+        } else {
+            collect_used_names(child, names);
+        }
+        false
+    });
+}
+
+fn contains_jsx(node: &Node) -> bool {
+    for_each_descendant(node, &mut |descendant| {
+        matches!(
+            descendant.kind,
+            SyntaxKind::JsxElement | SyntaxKind::JsxSelfClosingElement | SyntaxKind::JsxFragment
+        )
+    })
+}
+
+fn refers_to_a_name(identifier: &Arc<Node>) -> bool {
+    let Some(parent) = identifier.parent() else {
+        return true;
+    };
+    match parent.kind {
+        SyntaxKind::ShorthandPropertyAssignment => true,
+        SyntaxKind::QualifiedName => !Arc::ptr_eq(&parent.as_qualified_name().right, identifier),
+        SyntaxKind::ExportSpecifier => {
+            // A re-export names an export of another module
+            let is_reexport = parent
+                .parent()
+                .and_then(|exports| exports.parent())
+                .is_some_and(|declaration| {
+                    declaration
+                        .as_export_declaration()
+                        .module_specifier
+                        .is_some()
+                });
+            let specifier = parent.as_export_specifier();
+            if is_reexport {
+                return false;
+            }
+            specifier.property_name.is_none()
+                || specifier
+                    .property_name
+                    .as_ref()
+                    .is_some_and(|property_name| Arc::ptr_eq(property_name, identifier))
+        }
+        _ => !parent
+            .name()
+            .is_some_and(|name| Arc::ptr_eq(name, identifier)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::VirtualCompilerHost;
+    use crate::compile::tests::create;
+    use crate::language_service::apply_text_edits;
+
+    fn organize(text: &str, ignore_case: Option<bool>) -> String {
+        let program = create(
+            &VirtualCompilerHost::with_files(&[("a.ts", text)]),
+            &["a.ts"],
+        );
+        let service = LanguageService::new(&program);
+        let edits = service.organize_imports("a.ts", &OrganizeImportsOptions { ignore_case });
+        apply_text_edits(text, &edits)
+    }
+
+    #[test]
+    fn removes_merges_and_sorts_imports() {
+        let text = "\
+import { z, unused, a as b } from \"./util\";
+import \"./polyfill\";
+import * as path from \"path\";
+import Default, { y } from './util';
+import { gone } from \"./gone\";
+
+console.log(z, b, y, path, Default);
+";
+        assert_eq!(
+            organize(text, None),
+            "\
+import * as path from \"path\";
+import \"./polyfill\";
+import Default, { a as b, y, z } from \"./util\";
+
+console.log(z, b, y, path, Default);
+"
+        );
+    }
+
+    #[test]
+    fn removes_a_run_of_unused_imports() {
+        let text = "// header\nimport { a } from \"./a\";\nimport b from \"./b\";\nlet c = 1;\n";
+        assert_eq!(organize(text, None), "// header\nlet c = 1;\n");
+        // A name used only as a property or declaration name is not a use of the import
+        let text = "import { a } from \"./a\";\nlet o = { a: 1 }; o.a;\n";
+        assert_eq!(organize(text, None), "let o = { a: 1 }; o.a;\n");
+    }
+
+    #[test]
+    fn keeps_the_imports_of_jsx_factories() {
+        let organize_jsx = |text: &str, args: &[&str]| {
+            let program = create(&VirtualCompilerHost::with_files(&[("a.tsx", text)]), args);
+            let service = LanguageService::new(&program);
+            let edits = service.organize_imports("a.tsx", &OrganizeImportsOptions::default());
+            apply_text_edits(text, &edits)
+        };
+        let text = "import * as React from \"react\";\nimport { h, Fragment } from \"preact\";\nexport const a = <><div /></>;\n";
+        assert_eq!(
+            organize_jsx(text, &["a.tsx", "--jsx", "react"]),
+            "import * as React from \"react\";\nexport const a = <><div /></>;\n"
+        );
+        assert_eq!(
+            organize_jsx(
+                text,
+                &[
+                    "a.tsx",
+                    "--jsx",
+                    "react",
+                    "--jsxFactory",
+                    "h",
+                    "--jsxFragmentFactory",
+                    "Fragment",
+                ]
+            ),
+            "import { Fragment, h } from \"preact\";\nexport const a = <><div /></>;\n"
+        );
+        // The automatic runtime imports its own functions
+        assert_eq!(
+            organize_jsx(text, &["a.tsx", "--jsx", "react-jsx"]),
+            "export const a = <><div /></>;\n"
+        );
+    }
+
+    #[test]
+    fn sorts_by_the_configured_case_sensitivity() {
+        let text = "import { b, C, a } from \"./m\";\nexport { a, b, C };\n";
+        assert_eq!(
+            organize(text, Some(true)),
+            "import { a, b, C } from \"./m\";\nexport { a, b, C };\n"
+        );
+        assert_eq!(
+            organize(text, Some(false)),
+            "import { C, a, b } from \"./m\";\nexport { a, b, C };\n"
+        );
+        // Imports already sorted with uppercase first keep that order
+        let text = "import { B, a } from \"./m\";\nimport { D, c } from \"./n\";\nB; a; D; c;\n";
+        assert_eq!(organize(text, None), text);
+        // Organized imports need no edits
+        let program = create(
+            &VirtualCompilerHost::with_files(&[("a.ts", text)]),
+            &["a.ts"],
+        );
+        let service = LanguageService::new(&program);
+        assert!(
+            service
+                .organize_imports("a.ts", &OrganizeImportsOptions::default())
+                .is_empty()
+        );
+    }
+}
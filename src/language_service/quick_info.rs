@@ -0,0 +1,623 @@
+//! Quick info about the symbol at a position, as an editor shows on hover
+//!
+//! The display of a symbol is split into parts, like tsserver's, so that an editor can color
+//! the keywords, punctuation, and names in it. Types and signatures are printed by the
+//! checker and given as single text parts. The documentation comes from the JSDoc comments
+//! attached to the declarations of the symbol.
+
+use std::sync::Arc;
+
+use super::completions::get_symbol_kind;
+use super::navigation_tree::get_kind_modifiers;
+use super::{LanguageService, ScriptElementKind};
+use crate::compiler::ast::utilities::{get_error_range, number_to_string};
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::checker::{LiteralValue, SignatureId, TypeFormatFlags};
+use crate::compiler::text::TextRange;
+
+/// What a part of the display of a symbol is, which decides how an editor colors it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolDisplayPartKind {
+    Keyword,
+    Space,
+    Punctuation,
+    Operator,
+    LineBreak,
+    Text,
+    StringLiteral,
+    NumericLiteral,
+    AliasName,
+    ClassName,
+    EnumName,
+    EnumMemberName,
+    FunctionName,
+    InterfaceName,
+    LocalName,
+    MethodName,
+    ModuleName,
+    ParameterName,
+    PropertyName,
+    TypeParameterName,
+}
+
+impl SymbolDisplayPartKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SymbolDisplayPartKind::Keyword => "keyword",
+            SymbolDisplayPartKind::Space => "space",
+            SymbolDisplayPartKind::Punctuation => "punctuation",
+            SymbolDisplayPartKind::Operator => "operator",
+            SymbolDisplayPartKind::LineBreak => "lineBreak",
+            SymbolDisplayPartKind::Text => "text",
+            SymbolDisplayPartKind::StringLiteral => "stringLiteral",
+            SymbolDisplayPartKind::NumericLiteral => "numericLiteral",
+            SymbolDisplayPartKind::AliasName => "aliasName",
+            SymbolDisplayPartKind::ClassName => "className",
+            SymbolDisplayPartKind::EnumName => "enumName",
+            SymbolDisplayPartKind::EnumMemberName => "enumMemberName",
+            SymbolDisplayPartKind::FunctionName => "functionName",
+            SymbolDisplayPartKind::InterfaceName => "interfaceName",
+            SymbolDisplayPartKind::LocalName => "localName",
+            SymbolDisplayPartKind::MethodName => "methodName",
+            SymbolDisplayPartKind::ModuleName => "moduleName",
+            SymbolDisplayPartKind::ParameterName => "parameterName",
+            SymbolDisplayPartKind::PropertyName => "propertyName",
+            SymbolDisplayPartKind::TypeParameterName => "typeParameterName",
+        }
+    }
+}
+
+/// A part of the display of a symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolDisplayPart {
+    pub text: String,
+    pub kind: SymbolDisplayPartKind,
+}
+
+/// A JSDoc tag of a declaration, like `@deprecated` or `@param x the first value`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JSDocTagInfo {
+    /// The name of the tag, without the `@`
+    pub name: String,
+    /// The text after the tag name, with the name of the parameter a `@param` tag documents
+    pub text: Option<String>,
+}
+
+/// What the symbol at a position is
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickInfo {
+    pub kind: ScriptElementKind,
+    /// The modifiers of the declaration of the symbol, like `export,declare`
+    pub kind_modifiers: String,
+    /// The name at the position
+    pub text_span: TextRange,
+    pub display_parts: Vec<SymbolDisplayPart>,
+    /// The text of the JSDoc comments of the symbol
+    pub documentation: String,
+    pub tags: Vec<JSDocTagInfo>,
+}
+
+impl QuickInfo {
+    /// Gets the display of the symbol as one string, like `const x: number`
+    pub fn display_string(&self) -> String {
+        self.display_parts
+            .iter()
+            .map(|part| part.text.as_str())
+            .collect()
+    }
+}
+
+impl LanguageService<'_> {
+    /// Gets what the name at a position of a file refers to: its kind, declaration, and type,
+    /// with the documentation of its declarations
+    pub fn get_quick_info(&mut self, file_name: &str, pos: usize) -> Option<QuickInfo> {
+        let identifier = self.get_identifier_at_position(file_name, pos)?;
+        let tree = self.program.get_syntax_tree(file_name)?;
+        let symbol = self.checker.get_symbol_at_location(&identifier)?;
+        let mut parts = DisplayParts::default();
+        let target = if self
+            .checker
+            .symbol(symbol)
+            .flags
+            .contains(SymbolFlags::ALIAS)
+        {
+            let target = self.resolve_alias(symbol);
+            parts.text_in_parentheses("alias");
+            parts.space();
+            if self
+                .checker
+                .symbol(target)
+                .flags
+                .contains(SymbolFlags::ALIAS)
+            {
+                // The module of the import could not be resolved
+                parts.name(
+                    SymbolDisplayPartKind::AliasName,
+                    &self.checker.symbol(symbol).name,
+                );
+                target
+            } else {
+                self.write_symbol(&mut parts, target, None);
+                parts.push(SymbolDisplayPartKind::LineBreak, "\n");
+                parts.keyword("import");
+                parts.space();
+                parts.name(
+                    SymbolDisplayPartKind::AliasName,
+                    &self.checker.symbol(symbol).name,
+                );
+                target
+            }
+        } else {
+            self.write_symbol(&mut parts, symbol, Some(&identifier));
+            symbol
+        };
+
+        let declarations = self.checker.symbol(target).declarations.clone();
+        let mut documentation = Vec::new();
+        let mut tags = Vec::new();
+        for declaration in &declarations {
+            collect_documentation(declaration, &mut documentation, &mut tags);
+        }
+        Some(QuickInfo {
+            kind: if target == symbol {
+                get_symbol_kind(&self.checker, symbol)
+            } else {
+                ScriptElementKind::Alias
+            },
+            kind_modifiers: declarations
+                .first()
+                .map(|declaration| get_kind_modifiers(&get_modifiers_owner(declaration)))
+                .unwrap_or_default(),
+            text_span: get_error_range(tree, &identifier),
+            display_parts: parts.parts,
+            documentation: documentation.join("\n"),
+            tags,
+        })
+    }
+
+    /// Writes the display of a symbol, with the type it has at a location when given
+    fn write_symbol(
+        &mut self,
+        parts: &mut DisplayParts,
+        symbol: SymbolId,
+        location: Option<&Arc<Node>>,
+    ) {
+        let kind = get_symbol_kind(&self.checker, symbol);
+        let data = self.checker.symbol(symbol);
+        let name = data.name.clone();
+        let value_declaration = data.value_declaration.clone();
+        let parent = data.parent;
+        match kind {
+            ScriptElementKind::Class => {
+                let declared_type = self.checker.get_declared_type_of_symbol(symbol);
+                parts.keyword("class");
+                parts.space();
+                let text = self.checker.type_to_string(declared_type);
+                parts.name(SymbolDisplayPartKind::ClassName, &text);
+            }
+            ScriptElementKind::Interface => {
+                let declared_type = self.checker.get_declared_type_of_symbol(symbol);
+                parts.keyword("interface");
+                parts.space();
+                let text = self.checker.type_to_string(declared_type);
+                parts.name(SymbolDisplayPartKind::InterfaceName, &text);
+            }
+            ScriptElementKind::TypeAlias => {
+                let declared_type = self.checker.get_declared_type_of_symbol(symbol);
+                parts.keyword("type");
+                parts.space();
+                parts.name(SymbolDisplayPartKind::AliasName, &name);
+                parts.space();
+                parts.push(SymbolDisplayPartKind::Operator, "=");
+                parts.space();
+                let text = self
+                    .checker
+                    .type_to_string_with_flags(declared_type, TypeFormatFlags::IN_TYPE_ALIAS);
+                parts.push(SymbolDisplayPartKind::Text, &text);
+            }
+            ScriptElementKind::Enum => {
+                if value_declaration
+                    .as_ref()
+                    .is_some_and(|declaration| declaration.has_modifier(SyntaxKind::ConstKeyword))
+                {
+                    parts.keyword("const");
+                    parts.space();
+                }
+                parts.keyword("enum");
+                parts.space();
+                parts.name(SymbolDisplayPartKind::EnumName, &name);
+            }
+            ScriptElementKind::EnumMember => {
+                parts.text_in_parentheses("enum member");
+                parts.space();
+                self.write_qualified_name(parts, SymbolDisplayPartKind::EnumMemberName, symbol);
+                let value = value_declaration
+                    .as_ref()
+                    .and_then(|declaration| self.checker.get_constant_value(declaration));
+                if let Some(value) = value {
+                    parts.space();
+                    parts.push(SymbolDisplayPartKind::Operator, "=");
+                    parts.space();
+                    match value {
+                        LiteralValue::String(text) => {
+                            parts.push(SymbolDisplayPartKind::StringLiteral, &format!("{text:?}"))
+                        }
+                        LiteralValue::Number(number) => parts.push(
+                            SymbolDisplayPartKind::NumericLiteral,
+                            &number_to_string(number.0),
+                        ),
+                        LiteralValue::BigInt(digits) => {
+                            parts.push(SymbolDisplayPartKind::NumericLiteral, &format!("{digits}n"))
+                        }
+                    }
+                }
+            }
+            ScriptElementKind::Module => {
+                let is_ambient_module = name.starts_with('"');
+                parts.keyword(if is_ambient_module {
+                    "module"
+                } else {
+                    "namespace"
+                });
+                parts.space();
+                parts.name(SymbolDisplayPartKind::ModuleName, &name);
+            }
+            ScriptElementKind::TypeParameter => {
+                parts.text_in_parentheses("type parameter");
+                parts.space();
+                parts.name(SymbolDisplayPartKind::TypeParameterName, &name);
+            }
+            ScriptElementKind::Function
+            | ScriptElementKind::LocalFunction
+            | ScriptElementKind::Method => {
+                if kind == ScriptElementKind::Method {
+                    parts.text_in_parentheses("method");
+                    parts.space();
+                    self.write_qualified_name(parts, SymbolDisplayPartKind::MethodName, symbol);
+                } else {
+                    parts.keyword("function");
+                    parts.space();
+                    parts.name(SymbolDisplayPartKind::FunctionName, &name);
+                }
+                let type_ = self.checker.get_type_of_symbol(symbol);
+                let signatures = self.checker.get_signatures_of_type(type_, false);
+                self.write_signatures(parts, &signatures);
+            }
+            ScriptElementKind::Constructor => {
+                parts.keyword("constructor");
+                parts.space();
+                let Some(class) = parent else {
+                    return;
+                };
+                parts.name(
+                    SymbolDisplayPartKind::ClassName,
+                    &self.checker.symbol(class).name.clone(),
+                );
+                let type_ = self.checker.get_type_of_symbol(class);
+                let signatures = self.checker.get_signatures_of_type(type_, true);
+                self.write_signatures(parts, &signatures);
+            }
+            _ => {
+                let (prefix, name_kind) = match kind {
+                    ScriptElementKind::Const => ("const", SymbolDisplayPartKind::LocalName),
+                    ScriptElementKind::Let => ("let", SymbolDisplayPartKind::LocalName),
+                    ScriptElementKind::Parameter => {
+                        ("parameter", SymbolDisplayPartKind::ParameterName)
+                    }
+                    ScriptElementKind::Property => {
+                        ("property", SymbolDisplayPartKind::PropertyName)
+                    }
+                    ScriptElementKind::GetAccessor => {
+                        ("getter", SymbolDisplayPartKind::PropertyName)
+                    }
+                    ScriptElementKind::SetAccessor => {
+                        ("setter", SymbolDisplayPartKind::PropertyName)
+                    }
+                    _ => ("var", SymbolDisplayPartKind::LocalName),
+                };
+                if name_kind == SymbolDisplayPartKind::LocalName {
+                    parts.keyword(prefix);
+                } else {
+                    parts.text_in_parentheses(prefix);
+                }
+                parts.space();
+                if name_kind == SymbolDisplayPartKind::PropertyName {
+                    self.write_qualified_name(parts, name_kind, symbol);
+                } else {
+                    parts.name(name_kind, &name);
+                }
+                // The type at a reference is the one narrowed there
+                let type_ = match location {
+                    Some(location) => self.checker.get_type_at_location(location),
+                    None => self.checker.get_type_of_symbol(symbol),
+                };
+                let text = self.checker.type_to_string(type_);
+                parts.push(SymbolDisplayPartKind::Punctuation, ":");
+                parts.space();
+                parts.push(SymbolDisplayPartKind::Text, &text);
+            }
+        }
+    }
+
+    /// Writes the first of the signatures of a function, with the number of its overloads
+    fn write_signatures(&mut self, parts: &mut DisplayParts, signatures: &[SignatureId]) {
+        let Some(&first) = signatures.first() else {
+            return;
+        };
+        let text = self.checker.signature_to_string(first);
+        parts.push(SymbolDisplayPartKind::Text, &text);
+        if signatures.len() > 1 {
+            let overloads = signatures.len() - 1;
+            parts.space();
+            parts.push(SymbolDisplayPartKind::Punctuation, "(");
+            parts.push(SymbolDisplayPartKind::Operator, "+");
+            parts.push(
+                SymbolDisplayPartKind::NumericLiteral,
+                &overloads.to_string(),
+            );
+            parts.space();
+            parts.push(
+                SymbolDisplayPartKind::Text,
+                if overloads == 1 {
+                    "overload"
+                } else {
+                    "overloads"
+                },
+            );
+            parts.push(SymbolDisplayPartKind::Punctuation, ")");
+        }
+    }
+
+    /// Writes the name of a member qualified by the name of the class, interface, or enum it
+    /// belongs to, like `Point.x`
+    fn write_qualified_name(
+        &self,
+        parts: &mut DisplayParts,
+        kind: SymbolDisplayPartKind,
+        symbol: SymbolId,
+    ) {
+        let data = self.checker.symbol(symbol);
+        if let Some(parent) = data.parent {
+            let parent = self.checker.symbol(parent);
+            let parent_kind = if parent.flags.contains(SymbolFlags::CLASS) {
+                Some(SymbolDisplayPartKind::ClassName)
+            } else if parent.flags.contains(SymbolFlags::INTERFACE) {
+                Some(SymbolDisplayPartKind::InterfaceName)
+            } else if parent.flags.intersects(SymbolFlags::ENUM) {
+                Some(SymbolDisplayPartKind::EnumName)
+            } else {
+                None
+            };
+            if let Some(parent_kind) = parent_kind {
+                parts.name(parent_kind, &parent.name);
+                parts.push(SymbolDisplayPartKind::Punctuation, ".");
+            }
+        }
+        parts.name(kind, &data.name);
+    }
+}
+
+/// Collects the display parts of a symbol
+#[derive(Default)]
+struct DisplayParts {
+    parts: Vec<SymbolDisplayPart>,
+}
+
+impl DisplayParts {
+    fn push(&mut self, kind: SymbolDisplayPartKind, text: &str) {
+        self.parts.push(SymbolDisplayPart {
+            text: text.to_string(),
+            kind,
+        });
+    }
+
+    fn keyword(&mut self, keyword: &str) {
+        self.push(SymbolDisplayPartKind::Keyword, keyword);
+    }
+
+    fn space(&mut self) {
+        self.push(SymbolDisplayPartKind::Space, " ");
+    }
+
+    fn name(&mut self, kind: SymbolDisplayPartKind, name: &str) {
+        self.push(kind, name);
+    }
+
+    /// Writes a description of a symbol that has no keyword, like `(parameter)`
+    fn text_in_parentheses(&mut self, text: &str) {
+        self.push(SymbolDisplayPartKind::Punctuation, "(");
+        self.push(SymbolDisplayPartKind::Text, text);
+        self.push(SymbolDisplayPartKind::Punctuation, ")");
+    }
+}
+
+/// Gets the node that holds the modifiers and JSDoc comments of a declaration, which for a
+/// variable is its statement
+fn get_modifiers_owner(declaration: &Arc<Node>) -> Arc<Node> {
+    if declaration.kind == SyntaxKind::VariableDeclaration
+        && let Some(statement) = declaration.parent().and_then(|list| list.parent())
+        && statement.kind == SyntaxKind::VariableStatement
+    {
+        return statement;
+    }
+    Arc::clone(declaration)
+}
+
+/// Collects the text and tags of the JSDoc comments of a declaration, and for a parameter the
+/// text of the `@param` tag of its function that documents it
+fn collect_documentation(
+    declaration: &Arc<Node>,
+    documentation: &mut Vec<String>,
+    tags: &mut Vec<JSDocTagInfo>,
+) {
+    if declaration.kind == SyntaxKind::Parameter
+        && let Some(name) = declaration
+            .name()
+            .filter(|name| name.kind == SyntaxKind::Identifier)
+        && let Some(function) = declaration.parent()
+    {
+        for comment in function.jsdoc() {
+            for tag in comment.as_jsdoc().tags.iter() {
+                if tag.kind == SyntaxKind::JSDocParameterTag {
+                    let tag = tag.as_jsdoc_parameter_tag();
+                    if tag.name.text() == name.text()
+                        && let Some(text) = &tag.comment
+                    {
+                        documentation.push(text.clone());
+                    }
+                }
+            }
+        }
+    }
+    for comment in get_modifiers_owner(declaration).jsdoc() {
+        let comment = comment.as_jsdoc();
+        if let Some(text) = &comment.comment {
+            documentation.push(text.clone());
+        }
+        for tag in comment.tags.iter() {
+            tags.push(get_tag_info(tag));
+        }
+    }
+}
+
+fn get_tag_info(tag: &Node) -> JSDocTagInfo {
+    let (tag_name, comment) = match tag.kind {
+        SyntaxKind::JSDocParameterTag => {
+            let tag = tag.as_jsdoc_parameter_tag();
+            let text = match &tag.comment {
+                Some(comment) => format!("{} {comment}", tag.name.text()),
+                None => tag.name.text().to_string(),
+            };
+            return JSDocTagInfo {
+                name: tag.tag_name.text().to_string(),
+                text: Some(text),
+            };
+        }
+        SyntaxKind::JSDocReturnTag => {
+            let tag = tag.as_jsdoc_return_tag();
+            (&tag.tag_name, &tag.comment)
+        }
+        SyntaxKind::JSDocTypeTag => {
+            let tag = tag.as_jsdoc_type_tag();
+            (&tag.tag_name, &tag.comment)
+        }
+        SyntaxKind::JSDocTemplateTag => {
+            let tag = tag.as_jsdoc_template_tag();
+            (&tag.tag_name, &tag.comment)
+        }
+        SyntaxKind::JSDocTypedefTag => {
+            let tag = tag.as_jsdoc_typedef_tag();
+            (&tag.tag_name, &tag.comment)
+        }
+        SyntaxKind::JSDocDeprecatedTag => {
+            let tag = tag.as_jsdoc_deprecated_tag();
+            (&tag.tag_name, &tag.comment)
+        }
+        _ => {
+            let tag = tag.as_jsdoc_tag();
+            (&tag.tag_name, &tag.comment)
+        }
+    };
+    JSDocTagInfo {
+        name: tag_name.text().to_string(),
+        text: comment.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::tests::create;
+    use crate::compile::{Program, VirtualCompilerHost};
+
+    /// Gets the quick info of the `n`th occurrence of `target` in `a.ts`
+    fn quick_info(program: &Program, target: &str, n: usize) -> QuickInfo {
+        let text = &program.get_source_file("a.ts").unwrap().text;
+        let pos = text.match_indices(target).nth(n).unwrap().0;
+        LanguageService::new(program)
+            .get_quick_info("a.ts", pos)
+            .unwrap()
+    }
+
+    #[test]
+    fn displays_declarations_with_their_types() {
+        let program = create(
+            &VirtualCompilerHost::with_files(&[(
+                "a.ts",
+                "interface Point { x: number }\n\
+             function length(p: Point, scale?: number): number { return p.x; }\n\
+             export const origin: Point = { x: 0 };\n\
+             let value: string | number;\n\
+             if (typeof value === \"string\") { value; }\n\
+             enum Color { Red = 1 }\n\
+             type Pair = [number, number];",
+            )]),
+            &["a.ts"],
+        );
+        let display = |target: &str, n: usize| quick_info(&program, target, n).display_string();
+        assert_eq!(display("Point", 0), "interface Point");
+        assert_eq!(
+            display("length", 0),
+            "function length(p: Point, scale?: number): number"
+        );
+        assert_eq!(display("p.x", 0), "(parameter) p: Point");
+        assert_eq!(display("x;", 0), "(property) Point.x: number");
+        assert_eq!(display("origin", 0), "const origin: Point");
+        assert_eq!(display("value", 0), "let value: string | number");
+        assert_eq!(display("value", 2), "let value: string");
+        assert_eq!(display("Red", 0), "(enum member) Color.Red = 1");
+        assert_eq!(display("Pair", 0), "type Pair = [number, number]");
+
+        let info = quick_info(&program, "origin", 0);
+        assert_eq!(info.kind, ScriptElementKind::Const);
+        assert_eq!(info.kind_modifiers, "export");
+        assert_eq!(info.display_parts[0].kind, SymbolDisplayPartKind::Keyword);
+        assert_eq!(info.display_parts[2].kind, SymbolDisplayPartKind::LocalName);
+    }
+
+    #[test]
+    fn gives_the_jsdoc_of_declarations_and_imports() {
+        let program = create(
+            &VirtualCompilerHost::with_files(&[
+                (
+                    "a.ts",
+                    "import { scale as resize } from \"./b\";\nresize(2);",
+                ),
+                (
+                    "b.ts",
+                    "/**\n * Scales a size.\n * @param factor How much to scale by\n * @deprecated\n */\n\
+                 export function scale(factor: number): number { return factor; }",
+                ),
+            ]),
+            &["a.ts"],
+        );
+        let info = quick_info(&program, "resize", 1);
+        assert_eq!(info.kind, ScriptElementKind::Alias);
+        assert_eq!(
+            info.display_string(),
+            "(alias) function scale(factor: number): number\nimport resize"
+        );
+        assert_eq!(info.documentation, "Scales a size.");
+        assert_eq!(
+            info.tags,
+            [
+                JSDocTagInfo {
+                    name: "param".to_string(),
+                    text: Some("factor How much to scale by".to_string()),
+                },
+                JSDocTagInfo {
+                    name: "deprecated".to_string(),
+                    text: None,
+                },
+            ]
+        );
+
+        let text = &program.get_source_file("b.ts").unwrap().text;
+        let pos = text.find("factor:").unwrap();
+        let info = LanguageService::new(&program)
+            .get_quick_info("b.ts", pos)
+            .unwrap();
+        assert_eq!(info.display_string(), "(parameter) factor: number");
+        assert_eq!(info.documentation, "How much to scale by");
+    }
+}
@@ -0,0 +1,570 @@
+//! Finding the references to a symbol across a program, and renaming it
+//!
+//! References are found by name: every identifier with the name of the symbol is resolved,
+//! and those that resolve to the symbol are its references. Imports are followed to the
+//! exports of the files they resolve to, so the uses of an imported name in one file are
+//! references to the declaration in another.
+//!
+//! Renaming stops at local exports instead: `export { foo }` keeps the name it exports, as
+//! `export { bar as foo }`, so the files that import it are left alone.
+
+use std::sync::Arc;
+
+use super::LanguageService;
+use crate::compile::{Diagnostic, option_diagnostic};
+use crate::compiler::ast::utilities::{get_error_range, get_source_file_of_node};
+use crate::compiler::ast::{Node, SymbolFlags, SymbolId, SyntaxKind};
+use crate::compiler::diagnostics;
+use crate::compiler::scanner::{ScriptTarget, is_valid_identifier, string_to_token};
+use crate::compiler::text::TextRange;
+
+/// How many aliases a name may go through, like re-exports of re-exports, before it is taken
+/// to be circular
+const MAX_ALIAS_DEPTH: usize = 32;
+
+/// A place a symbol is referred to or declared
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceEntry {
+    pub file_name: String,
+    pub range: TextRange,
+    /// Whether this is a declaration of the symbol, rather than a use of it
+    pub is_definition: bool,
+}
+
+/// A replacement of a range of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub file_name: String,
+    pub range: TextRange,
+    pub new_text: String,
+}
+
+impl LanguageService<'_> {
+    /// Finds the declarations and uses of the symbol named at a position of a file, in every
+    /// file of the program
+    pub fn find_references(&mut self, file_name: &str, pos: usize) -> Vec<ReferenceEntry> {
+        let Some(name) = self.get_identifier_at_position(file_name, pos) else {
+            return Vec::new();
+        };
+        match self.get_referenced_symbol(&name, false) {
+            Some(symbol) => self.find_references_to_symbol(symbol, false),
+            None => Vec::new(),
+        }
+    }
+
+    /// Gets the edits that rename the symbol named at a position of a file to `new_name`,
+    /// or why it cannot be renamed
+    pub fn rename(
+        &mut self,
+        file_name: &str,
+        pos: usize,
+        new_name: &str,
+    ) -> Result<Vec<TextEdit>, Diagnostic> {
+        let cannot_rename =
+            || option_diagnostic(diagnostics::YOU_CANNOT_RENAME_THIS_ELEMENT_8000, &[]);
+        let name = self
+            .get_identifier_at_position(file_name, pos)
+            .ok_or_else(cannot_rename)?;
+        let symbol = self
+            .get_referenced_symbol(&name, true)
+            .ok_or_else(cannot_rename)?;
+        if !is_identifier_text(new_name) {
+            return Err(cannot_rename());
+        }
+        for declaration in &self.checker.symbol(symbol).declarations {
+            let declaration_file = get_source_file_of_node(declaration);
+            let declaration_file = &declaration_file.as_source_file().file_name;
+            if self.program.is_default_lib_file(declaration_file) {
+                return Err(option_diagnostic(
+                    diagnostics::YOU_CANNOT_RENAME_ELEMENTS_THAT_ARE_DEFINED_IN_THE_STANDARD_TYPE_SCRIPT_LIBRARY_8001,
+                    &[],
+                ));
+            }
+            if declaration_file
+                .split('/')
+                .any(|part| part == "node_modules")
+            {
+                return Err(option_diagnostic(
+                    diagnostics::YOU_CANNOT_RENAME_ELEMENTS_THAT_ARE_DEFINED_IN_A_NODE_MODULES_FOLDER_8035,
+                    &[],
+                ));
+            }
+        }
+
+        let references = self.find_references_to_symbol(symbol, true);
+        let mut edits = Vec::with_capacity(references.len());
+        for reference in references {
+            let tree = self
+                .program
+                .get_syntax_tree(&reference.file_name)
+                .expect("references are in files of the program");
+            let old_name =
+                &tree.as_source_file().text[reference.range.pos()..reference.range.end()];
+            let identifier = get_identifier_at_range(tree, reference.range);
+            let parent = identifier
+                .as_ref()
+                .and_then(|identifier| identifier.parent());
+            let new_text = match parent {
+                // A shorthand property keeps its name, and takes the renamed value
+                Some(parent) if is_shorthand_property(&parent) => {
+                    format!("{old_name}: {new_name}")
+                }
+                // An export specifier renames either the export or the local it exports,
+                // keeping the name of the other
+                Some(parent)
+                    if parent.kind == SyntaxKind::ExportSpecifier
+                        && parent.as_export_specifier().property_name.is_none() =>
+                {
+                    let identifier = identifier.expect("an identifier has the parent");
+                    if self.get_referenced_symbol(&identifier, true) == Some(symbol) {
+                        format!("{old_name} as {new_name}")
+                    } else {
+                        format!("{new_name} as {old_name}")
+                    }
+                }
+                _ => new_name.to_string(),
+            };
+            edits.push(TextEdit {
+                file_name: reference.file_name,
+                range: reference.range,
+                new_text,
+            });
+        }
+        Ok(edits)
+    }
+
+    /// Finds the names that refer to a symbol. For a rename, the local exports of the symbol
+    /// are references to it, but the imports of those exports are not.
+    fn find_references_to_symbol(
+        &mut self,
+        symbol: SymbolId,
+        for_rename: bool,
+    ) -> Vec<ReferenceEntry> {
+        let name = self.checker.symbol(symbol).name.clone();
+        let mut references = Vec::new();
+        for (source_file, tree) in self
+            .program
+            .source_files
+            .iter()
+            .zip(&self.program.syntax_trees)
+        {
+            let mut candidates = Vec::new();
+            collect_identifiers_named(tree, &name, &mut candidates);
+            for identifier in candidates {
+                if self.get_referenced_symbol(&identifier, for_rename) != Some(symbol)
+                    && !(for_rename && self.is_local_export_of(&identifier, symbol))
+                {
+                    continue;
+                }
+                references.push(ReferenceEntry {
+                    file_name: source_file.file_name.clone(),
+                    range: get_error_range(tree, &identifier),
+                    is_definition: is_declaration_name(&identifier),
+                });
+            }
+        }
+        references
+    }
+
+    /// Whether a name is in a local `export { name }` of a symbol
+    fn is_local_export_of(&mut self, name: &Arc<Node>, symbol: SymbolId) -> bool {
+        let Some(specifier) = name
+            .parent()
+            .filter(|parent| parent.kind == SyntaxKind::ExportSpecifier)
+        else {
+            return false;
+        };
+        if module_specifier_of(&specifier).is_some() {
+            return false;
+        }
+        let local = self
+            .checker
+            .get_symbol_of_declaration(&specifier)
+            .and_then(|export| self.checker.get_aliased_symbol(export));
+        local.is_some_and(|local| self.resolve_symbol(local, 0, true) == symbol)
+    }
+
+    /// Gets the symbol a name declares or refers to, following aliases to the symbols they
+    /// import or re-export, except for local exports when renaming
+    fn get_referenced_symbol(&mut self, name: &Arc<Node>, for_rename: bool) -> Option<SymbolId> {
+        let parent = name.parent()?;
+        // The name an import or export specifier takes from a module is an export of it
+        if matches!(
+            parent.kind,
+            SyntaxKind::ImportSpecifier | SyntaxKind::ExportSpecifier
+        ) {
+            let property_name = match parent.kind {
+                SyntaxKind::ImportSpecifier => &parent.as_import_specifier().property_name,
+                _ => &parent.as_export_specifier().property_name,
+            };
+            if property_name
+                .as_ref()
+                .is_some_and(|property_name| Arc::ptr_eq(property_name, name))
+            {
+                let export = self.get_export_of_specifier(&parent, name.text())?;
+                return Some(self.resolve_symbol(export, 0, for_rename));
+            }
+        }
+        // A shorthand property refers to the value it takes, as well as declaring a property
+        let symbol = match self.checker.get_shorthand_assignment_value_symbol(&parent) {
+            Some(symbol) => symbol,
+            None => self.checker.get_symbol_at_location(name)?,
+        };
+        Some(self.resolve_symbol(symbol, 0, for_rename))
+    }
+
+    /// Follows an alias of the same name to the symbol it refers to, and the local symbol of
+    /// an exported declaration to the exported one. A rename stops at a local `export { a }`,
+    /// which keeps its name while what it exports is renamed.
+    fn resolve_symbol(&mut self, symbol: SymbolId, depth: usize, for_rename: bool) -> SymbolId {
+        let symbol = self.checker.symbol(symbol).export_symbol.unwrap_or(symbol);
+        if depth > MAX_ALIAS_DEPTH || for_rename && self.is_local_export_specifier(symbol) {
+            return symbol;
+        }
+        match self.get_alias_target(symbol) {
+            // An alias with a name of its own, like `b` in `import { a as b }`, is renamed
+            // apart from what it refers to
+            Some(target)
+                if self.checker.symbol(target).name == self.checker.symbol(symbol).name =>
+            {
+                self.resolve_symbol(target, depth + 1, for_rename)
+            }
+            _ => symbol,
+        }
+    }
+
+    /// Follows aliases to the symbol they finally refer to, whatever their names, and the
+    /// local symbol of an exported declaration to the exported one
+    pub(super) fn resolve_alias(&mut self, mut symbol: SymbolId) -> SymbolId {
+        for _ in 0..MAX_ALIAS_DEPTH {
+            symbol = self.checker.symbol(symbol).export_symbol.unwrap_or(symbol);
+            match self.get_alias_target(symbol) {
+                Some(target) => symbol = target,
+                None => break,
+            }
+        }
+        symbol
+    }
+
+    /// Whether a symbol is declared by an export specifier without a module specifier
+    fn is_local_export_specifier(&self, symbol: SymbolId) -> bool {
+        self.checker
+            .symbol(symbol)
+            .declarations
+            .first()
+            .is_some_and(|declaration| {
+                declaration.kind == SyntaxKind::ExportSpecifier
+                    && module_specifier_of(declaration).is_none()
+            })
+    }
+
+    /// Gets the symbol an alias refers to, which may be another alias
+    fn get_alias_target(&mut self, symbol: SymbolId) -> Option<SymbolId> {
+        if !self
+            .checker
+            .symbol(symbol)
+            .flags
+            .contains(SymbolFlags::ALIAS)
+        {
+            return None;
+        }
+        let declaration = self.checker.symbol(symbol).declarations.first().cloned()?;
+        match declaration.kind {
+            SyntaxKind::ImportSpecifier => {
+                let specifier = declaration.as_import_specifier();
+                let name = specifier.property_name.as_ref().unwrap_or(&specifier.name);
+                self.get_export_of_specifier(&declaration, name.text())
+            }
+            SyntaxKind::ExportSpecifier if module_specifier_of(&declaration).is_some() => {
+                let specifier = declaration.as_export_specifier();
+                let name = specifier.property_name.as_ref().unwrap_or(&specifier.name);
+                self.get_export_of_specifier(&declaration, name.text())
+            }
+            SyntaxKind::ImportClause => self.get_export_of_specifier(&declaration, "default"),
+            _ => self.checker.get_aliased_symbol(symbol),
+        }
+    }
+
+    /// Gets an export of the module that an import or export declaration names, from a
+    /// specifier or clause within it
+    fn get_export_of_specifier(&mut self, node: &Arc<Node>, export_name: &str) -> Option<SymbolId> {
+        let module_specifier = module_specifier_of(node)?;
+        let file = get_source_file_of_node(node);
+        let module_file = self
+            .program
+            .get_resolved_module(&file.as_source_file().file_name, module_specifier.text())?;
+        let module_tree = self.program.get_syntax_tree(module_file)?;
+        let module_symbol = self.checker.get_symbol_of_declaration(module_tree)?;
+        self.checker
+            .symbol(module_symbol)
+            .exports
+            .get(export_name)
+            .copied()
+    }
+}
+
+/// Gets the module specifier of the import or export declaration containing a node
+fn module_specifier_of(node: &Arc<Node>) -> Option<Arc<Node>> {
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        match ancestor.kind {
+            SyntaxKind::ImportDeclaration => {
+                return Some(Arc::clone(
+                    &ancestor.as_import_declaration().module_specifier,
+                ));
+            }
+            SyntaxKind::ExportDeclaration => {
+                return ancestor.as_export_declaration().module_specifier.clone();
+            }
+            _ => current = ancestor.parent(),
+        }
+    }
+    None
+}
+
+/// Whether an identifier is the name of the declaration it is in, rather than a use of a name
+///
+/// The name of an import or export specifier declares something only when it differs from
+/// the name it takes, as `b` does in `import { a as b }`.
+pub(super) fn is_declaration_name(identifier: &Arc<Node>) -> bool {
+    identifier.parent().is_some_and(|parent| {
+        let is_renaming = match parent.kind {
+            SyntaxKind::PropertyAccessExpression | SyntaxKind::ShorthandPropertyAssignment => {
+                return false;
+            }
+            SyntaxKind::ImportSpecifier => parent.as_import_specifier().property_name.is_some(),
+            SyntaxKind::ExportSpecifier => parent.as_export_specifier().property_name.is_some(),
+            _ => true,
+        };
+        is_renaming
+            && parent
+                .name()
+                .is_some_and(|name| Arc::ptr_eq(name, identifier))
+    })
+}
+
+/// Whether a node is a shorthand property assignment or object binding element, whose one
+/// name is both a property and a value
+fn is_shorthand_property(node: &Node) -> bool {
+    match node.kind {
+        SyntaxKind::ShorthandPropertyAssignment => true,
+        SyntaxKind::BindingElement => {
+            let element = node.as_binding_element();
+            element.property_name.is_none()
+                && element.dot_dot_dot_token.is_none()
+                && node
+                    .parent()
+                    .is_some_and(|pattern| pattern.kind == SyntaxKind::ObjectBindingPattern)
+        }
+        _ => false,
+    }
+}
+
+fn collect_identifiers_named(node: &Arc<Node>, name: &str, identifiers: &mut Vec<Arc<Node>>) {
+    node.for_each_child(&mut |child| {
+        if child.kind == SyntaxKind::Identifier {
+            if child.text() == name {
+                identifiers.push(Arc::clone(child));
+            }
+        } else {
+            collect_identifiers_named(child, name, identifiers);
+        }
+        false
+    });
+}
+
+fn get_identifier_at_range(tree: &Arc<Node>, range: TextRange) -> Option<Arc<Node>> {
+    super::get_touching_identifier(tree, tree, range.pos())
+        .filter(|identifier| get_error_range(tree, identifier) == range)
+}
+
+/// Whether a name can be written as an identifier, which reserved words cannot
+pub(super) fn is_identifier_text(name: &str) -> bool {
+    is_valid_identifier(name, ScriptTarget::ESNext)
+        && string_to_token(name).is_none_or(|kind| kind.is_contextual_keyword())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::tests::create;
+    use crate::compile::{Program, VirtualCompilerHost};
+
+    fn texts(program: &Program, references: &[ReferenceEntry]) -> Vec<(String, String, bool)> {
+        references
+            .iter()
+            .map(|reference| {
+                let text = &program.get_source_file(&reference.file_name).unwrap().text;
+                (
+                    reference.file_name.clone(),
+                    text[reference.range.pos()..reference.range.end()].to_string(),
+                    reference.is_definition,
+                )
+            })
+            .collect()
+    }
+
+    const B: &str = "export function greet(name: string) { return name; }\nexport const other = greet(\"x\");\n";
+    const A: &str = "import { greet } from \"./b\";\nimport { greet as hello } from \"./b\";\nconst value = { greet };\ngreet(\"a\");\nhello(\"b\");\n";
+
+    #[test]
+    fn finds_references_across_imports() {
+        let program = create(
+            &VirtualCompilerHost::with_files(&[("a.ts", A), ("b.ts", B)]),
+            &["a.ts", "b.ts"],
+        );
+        let mut service = LanguageService::new(&program);
+        let references = service.find_references("b.ts", B.find("greet").unwrap() + 2);
+        assert_eq!(
+            references
+                .iter()
+                .map(|reference| (reference.file_name.as_str(), reference.range.pos()))
+                .collect::<Vec<_>>(),
+            [
+                ("b.ts", 16),
+                ("b.ts", 74),
+                ("a.ts", 9),
+                ("a.ts", 38),
+                ("a.ts", 83),
+                ("a.ts", 92),
+            ]
+        );
+        assert!(references[0].is_definition);
+        assert!(
+            references
+                .iter()
+                .filter(|reference| reference.is_definition)
+                .count()
+                == 1
+        );
+        // The same references are found from a use in the importing file
+        assert_eq!(service.find_references("a.ts", 93), references);
+
+        // A renaming import is a declaration of its own
+        let aliases = service.find_references("a.ts", A.find("hello").unwrap());
+        assert_eq!(
+            texts(&program, &aliases),
+            [
+                ("a.ts".to_string(), "hello".to_string(), true),
+                ("a.ts".to_string(), "hello".to_string(), false),
+            ]
+        );
+        assert!(
+            service
+                .find_references("a.ts", A.find("./b").unwrap())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn renames_across_files() {
+        let program = create(
+            &VirtualCompilerHost::with_files(&[("a.ts", A), ("b.ts", B)]),
+            &["a.ts", "b.ts"],
+        );
+        let mut service = LanguageService::new(&program);
+        let edits = service.rename("a.ts", 92, "welcome").unwrap();
+        assert_eq!(edits.len(), 6);
+        assert!(edits.iter().all(|edit| edit.new_text == "welcome"
+            || edit.file_name == "a.ts"
+                && edit.range.pos() == 83
+                && edit.new_text == "greet: welcome"));
+
+        let cannot_rename = |result: Result<Vec<TextEdit>, Diagnostic>| result.unwrap_err().code;
+        assert_eq!(cannot_rename(service.rename("a.ts", 92, "1st")), 8000);
+        assert_eq!(cannot_rename(service.rename("a.ts", 92, "class")), 8000);
+        assert_eq!(cannot_rename(service.rename("a.ts", 0, "name")), 8000);
+    }
+
+    fn edits(program: &Program, edits: &[TextEdit]) -> Vec<(String, String, String)> {
+        edits
+            .iter()
+            .map(|edit| {
+                let text = &program.get_source_file(&edit.file_name).unwrap().text;
+                (
+                    edit.file_name.clone(),
+                    text[edit.range.pos()..edit.range.end()].to_string(),
+                    edit.new_text.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn renames_locals_apart_from_exports_and_destructured_properties() {
+        let a = "const foo = 1;\nconst o = { foo: 2 };\nconst { foo: bar } = o;\nexport { foo };\nfoo;\n";
+        let b = "import { foo } from \"./a\";\nfoo;\n";
+        let program = create(
+            &VirtualCompilerHost::with_files(&[("a.ts", a), ("b.ts", b)]),
+            &["a.ts", "b.ts"],
+        );
+        let mut service = LanguageService::new(&program);
+        let edit = |file: &str, text: &str, new_text: &str| {
+            (file.to_string(), text.to_string(), new_text.to_string())
+        };
+
+        // The export keeps its name, so the importing file is left alone
+        let renamed = service.rename("a.ts", 6, "baz").unwrap();
+        assert_eq!(
+            edits(&program, &renamed),
+            [
+                edit("a.ts", "foo", "baz"),
+                edit("a.ts", "foo", "baz as foo"),
+                edit("a.ts", "foo", "baz"),
+            ]
+        );
+        assert_eq!(renamed[1].range.pos(), a.find("foo }").unwrap());
+
+        // Renaming the import renames the export, and not the local it exports
+        let renamed = service
+            .rename("b.ts", b.rfind("foo").unwrap(), "qux")
+            .unwrap();
+        assert_eq!(
+            edits(&program, &renamed),
+            [
+                edit("a.ts", "foo", "foo as qux"),
+                edit("b.ts", "foo", "qux"),
+                edit("b.ts", "foo", "qux"),
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_destructured_properties_with_their_property() {
+        let a = "interface O { foo: number }\ndeclare const o: O;\nconst { foo: bar } = o;\nconst { foo } = o;\no.foo + foo;\n";
+        let program = create(
+            &VirtualCompilerHost::with_files(&[("a.ts", a), ("b.ts", "")]),
+            &["a.ts", "b.ts"],
+        );
+        let mut service = LanguageService::new(&program);
+        let renamed = service
+            .rename("a.ts", a.find("foo").unwrap(), "baz")
+            .unwrap();
+        assert_eq!(
+            renamed
+                .iter()
+                .map(|edit| (edit.range.pos(), edit.new_text.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (a.find("foo").unwrap(), "baz"),
+                (a.find("foo: bar").unwrap(), "baz"),
+                (a.find("o.foo").unwrap() + 2, "baz"),
+            ]
+        );
+
+        // A shorthand binding keeps the property, and declares the renamed variable
+        let renamed = service
+            .rename("a.ts", a.rfind("foo").unwrap(), "baz")
+            .unwrap();
+        assert_eq!(
+            renamed
+                .iter()
+                .map(|edit| (edit.range.pos(), edit.new_text.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (a.find("foo }").unwrap(), "foo: baz"),
+                (a.rfind("foo").unwrap(), "baz"),
+            ]
+        );
+    }
+}
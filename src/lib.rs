@@ -1,4 +1,39 @@
+//! A TypeScript compiler, which the `tsrs` executable wraps
+//!
+//! Tools that embed the compiler create a program from root files, options, and a
+//! [`CompilerHost`] that reads and writes files, then check and emit it:
+//!
+//! ```no_run
+//! use typescript::{CompilerOptions, compile, create_compiler_host};
+//!
+//! let host = create_compiler_host();
+//! let options = CompilerOptions {
+//!     out_dir: Some("dist".to_string()),
+//!     ..CompilerOptions::default()
+//! };
+//! let result = compile(&["src/index.ts".to_string()], &options, &host);
+//! for diagnostic in result.diagnostics() {
+//!     eprintln!("error TS{}: {}", diagnostic.code, diagnostic.message);
+//! }
+//! ```
+
 pub mod cli;
 pub mod compile;
 pub mod compiler;
+pub mod config;
+pub mod language_service;
+pub mod watch;
 // pub mod parse;
+
+pub use cli::CompilerOptions;
+pub use compile::{
+    CompileResult, CompilerHost, Diagnostic, DiagnosticCategory, FileIncludeReason, FileReference,
+    FileSystemEntries, Program, SourceFile, TranspileOptions, TranspileOutput, VirtualCompilerHost,
+    compile, compile_with_cancellation, create_compiler_host, create_program, emit_files,
+    transpile_module, type_check,
+};
+pub use compiler::cancellation::{CancellationToken, OperationCanceled};
+pub use language_service::{
+    CodeFixAction, CompletionEntry, CompletionInfo, FormatOptions, LanguageService, NavigationTree,
+    QuickInfo, ReferenceEntry, ScriptElementKind, SemanticClassification, TextEdit,
+};
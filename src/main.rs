@@ -1,90 +1,298 @@
-mod cli;
-mod compile;
-
-use clap::Parser;
-
-use crate::cli::*;
-use crate::compile::*;
-
-fn main() {
+use std::process::ExitCode;
+use std::time::Instant;
+
+use typescript::cli::*;
+use typescript::compile::*;
+use typescript::compiler::diagnostics;
+use typescript::compiler::paths::{combine_paths, get_base_file_name};
+use typescript::config::{
+    ParsedConfig, convert_to_config, find_config_file, get_project_config_file, parse_config_file,
+    write_config_file,
+};
+use typescript::language_service::{apply_code_fixes, format_files};
+use typescript::watch::watch;
+
+fn main() -> ExitCode {
     // Parse the CLI args
-    let cli = Cli::parse();
+    let cli = match parse_command_line(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(diagnostics) => {
+            let host = create_compiler_host();
+            report_diagnostics(&diagnostics, DiagnosticFormatKind::Pretty, true, &host);
+            return ExitStatus::InvalidOptions.into();
+        }
+    };
 
     // Handle command dispatch based on args
-    if cli.help {
+    let status = if cli.help {
         print_help(cli.all);
+        ExitStatus::Success
     } else if cli.version {
         print_version();
+        ExitStatus::Success
+    } else if let Some(Command::Fmt(args)) = &cli.command {
+        format(args)
     } else if cli.init {
-        create_tsrsonfig();
+        create_tsrsonfig(&cli)
     } else if cli.build {
-        build_project(&cli);
+        build_project(&cli)
     } else if cli.show_config {
-        show_config(&cli);
+        show_config(&cli)
     } else if !cli.files.is_empty() {
-        compile_files(&cli);
-    } else if let Some(project) = &cli.project {
-        compile_project(&cli);
+        compile_files(&cli)
+    } else if cli.project.is_some() {
+        compile_project(&cli)
     } else {
-        compile_current_project(&cli);
-    }
+        compile_current_project(&cli)
+    };
+    status.into()
 }
 
 fn print_version() {
     println!("Version 5.8.2");
 }
 
-fn create_tsrsonfig() {
-    println!("Creating tsrsonfig.json");
-    // Implementation for creating tsrsonfig.json
+fn format(args: &FmtArgs) -> ExitStatus {
+    let host = create_compiler_host();
+    let source_files = read_source_files(&args.files, &host);
+    let diagnostics = format_files(&source_files, &create_format_options(args), &host);
+    // Files with syntax errors are left as they are
+    if diagnostics.is_empty() {
+        return ExitStatus::Success;
+    }
+    report_diagnostics(&diagnostics, DiagnosticFormatKind::Pretty, true, &host);
+    ExitStatus::DiagnosticsPresentOutputsSkipped
 }
 
-fn build_project(cli: &Cli) {
-    println!("Building project");
-    // Implementation for building project
+fn create_tsrsonfig(cli: &Cli) -> ExitStatus {
+    let host = create_compiler_host();
+    // Options given along with --init are set in the new file, and files given are its files
+    match write_config_file(&host.get_current_directory(), cli, &host) {
+        Ok(path) => {
+            // tsc's message names tsconfig.json, so the file written is named here instead
+            println!("Successfully created a {} file.", get_base_file_name(&path));
+            ExitStatus::Success
+        }
+        Err(diagnostic) => {
+            report_diagnostics(
+                &[diagnostic],
+                DiagnosticFormatKind::Pretty,
+                cli.pretty,
+                &host,
+            );
+            ExitStatus::InvalidOptions
+        }
+    }
 }
 
-fn show_config(cli: &Cli) {
-    println!("Showing configuration");
-    // Implementation for showing configuration
+fn build_project(cli: &Cli) -> ExitStatus {
+    // Build mode is not implemented, so --build is reported as an option tsrs does not know
+    // rather than doing nothing and succeeding
+    let host = create_compiler_host();
+    let diagnostic = option_diagnostic(
+        diagnostics::UNKNOWN_COMPILER_OPTION_0_5023,
+        &["--build".to_string()],
+    );
+    report_diagnostics(
+        &[diagnostic],
+        DiagnosticFormatKind::Pretty,
+        cli.pretty,
+        &host,
+    );
+    ExitStatus::InvalidOptions
 }
 
-fn compile_files(cli: &Cli) {
-    println!("Compiling files: {:?}", cli.files);
-    // 1. Set up compiler options from CLI arguments
-    let compiler_options = create_compiler_options(cli);
-
-    // 2. Create a compiler host (filesystem abstraction)
+fn show_config(cli: &Cli) -> ExitStatus {
     let host = create_compiler_host();
+    let current_directory = host.get_current_directory();
+    let config_file_name = if let Some(project) = &cli.project {
+        get_project_config_file(&project.to_string_lossy(), &host).map(Some)
+    } else if !cli.files.is_empty() {
+        Ok(None)
+    } else {
+        find_config_file(&current_directory, &host)
+            .map(Some)
+            .ok_or_else(|| {
+                option_diagnostic(
+                diagnostics::CANNOT_FIND_A_TSCONFIG_JSON_FILE_AT_THE_CURRENT_DIRECTORY_COLON_0_5081,
+                std::slice::from_ref(&current_directory),
+            )
+            })
+    };
+
+    // Files on the command line make up the project when there is no configuration file, and
+    // their paths are shown from the working directory
+    let (cli, config_file_name, config) = match config_file_name {
+        Ok(Some(config_file_name)) => {
+            let mut cli = cli.clone();
+            let config = parse_config_file(&config_file_name, &mut cli, &host);
+            (cli, config_file_name, config)
+        }
+        Ok(None) => {
+            let config = ParsedConfig {
+                file_names: cli.files.clone(),
+                ..ParsedConfig::default()
+            };
+            (
+                cli.clone(),
+                combine_paths(&current_directory, "tsrsonfig.json"),
+                config,
+            )
+        }
+        Err(diagnostic) => {
+            report_diagnostics(
+                &[diagnostic],
+                DiagnosticFormatKind::Pretty,
+                cli.pretty,
+                &host,
+            );
+            return ExitStatus::InvalidOptions;
+        }
+    };
+    if !config.diagnostics.is_empty() {
+        let diagnostics = sort_and_deduplicate_diagnostics(config.diagnostics);
+        report_diagnostics(
+            &diagnostics,
+            DiagnosticFormatKind::Pretty,
+            cli.pretty,
+            &host,
+        );
+        return ExitStatus::InvalidOptions;
+    }
+    println!(
+        "{}",
+        convert_to_config(&cli, &config_file_name, &config, &host)
+    );
+    ExitStatus::Success
+}
 
-    // 3. Read and parse the input files
-    let source_files = read_source_files(&cli.files, &host);
-
-    // 4. Initialize the compilation process
-    let mut program = create_program(&source_files, &compiler_options, &host);
-
-    // 5. Perform type checking if needed
-    if !compiler_options.skip_type_checking {
-        type_check(&mut program);
+fn compile_files(cli: &Cli) -> ExitStatus {
+    // Machine-readable diagnostics and listed files are the only output, so that they can be
+    // parsed
+    if cli.diagnostic_format == DiagnosticFormat::Pretty && !cli.list_files_only {
+        println!("Compiling files: {:?}", cli.files);
     }
+    let host = create_compiler_host();
+    if cli.watch {
+        watch(cli, None, &host);
+        ExitStatus::Success
+    } else {
+        compile_and_report(cli, &cli.files, Vec::new(), &host)
+    }
+}
 
-    // 6. Emit the output files (JS, declaration files, sourcemaps)
-    if !compiler_options.no_emit {
-        emit_files(&program, &compiler_options, &host);
+fn compile_project(cli: &Cli) -> ExitStatus {
+    let host = create_compiler_host();
+    let project = cli
+        .project
+        .as_ref()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match get_project_config_file(&project, &host) {
+        Ok(config_file_name) => compile_config_file(cli, &config_file_name, &host),
+        Err(diagnostic) => {
+            report_diagnostics(
+                &[diagnostic],
+                create_compiler_options(cli).diagnostic_format,
+                cli.pretty,
+                &host,
+            );
+            ExitStatus::InvalidOptions
+        }
     }
+}
 
-    // 7. Report any diagnostics
-    report_diagnostics(&program.diagnostics, compiler_options.pretty);
+fn compile_current_project(cli: &Cli) -> ExitStatus {
+    let host = create_compiler_host();
+    match find_config_file(&host.get_current_directory(), &host) {
+        Some(config_file_name) => compile_config_file(cli, &config_file_name, &host),
+        // Without a project to compile, show how to use the compiler, which compiles nothing
+        None => {
+            print_help(cli.all);
+            ExitStatus::InvalidOptions
+        }
+    }
 }
 
-fn compile_project(cli: &Cli) {
-    println!("Compiling project");
-    // Implementation for compiling current project
+fn compile_config_file(cli: &Cli, config_file_name: &str, host: &impl CompilerHost) -> ExitStatus {
+    if cli.watch {
+        // The configuration file is read again on every compilation, to pick up changes to it
+        watch(cli, Some(config_file_name), host);
+        return ExitStatus::Success;
+    }
+    // Options on the command line take precedence over the configuration file
+    let mut cli = cli.clone();
+    let config = parse_config_file(config_file_name, &mut cli, host);
+    compile_and_report(&cli, &config.file_names, config.diagnostics, host)
 }
 
-fn compile_current_project(cli: &Cli) {
-    println!("Compiling current project");
-    // Implementation for compiling current project
+fn compile_and_report(
+    cli: &Cli,
+    file_names: &[String],
+    config_diagnostics: Vec<Diagnostic>,
+    host: &impl CompilerHost,
+) -> ExitStatus {
+    // 1. Set up compiler options from CLI arguments and the configuration file
+    let compiler_options = create_compiler_options(cli);
+    let report_performance = compiler_options.diagnostics || compiler_options.extended_diagnostics;
+    if report_performance {
+        performance::enable();
+    }
+    let start_time = Instant::now();
+
+    // Fix what errors can be fixed first, so that only the others are reported
+    if cli.fix {
+        let source_files = read_source_files(file_names, host);
+        apply_code_fixes(
+            &create_program(&source_files, &compiler_options, host),
+            host,
+        );
+    }
+
+    // 2. Read, parse, check, and emit the input files and the files they import. Errors in the
+    // configuration file make the options invalid, whatever the files hold.
+    let invalid_config = config_diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.category == DiagnosticCategory::Error);
+    let mut result = compile(file_names, &compiler_options, host);
+    result.program.diagnostics = sort_and_deduplicate_diagnostics(
+        config_diagnostics
+            .into_iter()
+            .chain(std::mem::take(&mut result.program.diagnostics)),
+    );
+    let diagnostics = result.diagnostics();
+
+    // 3. Report any diagnostics, and the files of the program if asked to. Listing the files
+    // only is meant for scripts, so it prints nothing else when there is nothing to report.
+    if !(compiler_options.list_files_only && diagnostics.is_empty()) {
+        report_diagnostics(
+            diagnostics,
+            compiler_options.diagnostic_format,
+            compiler_options.pretty,
+            host,
+        );
+    }
+    if compiler_options.explain_files
+        || compiler_options.list_files
+        || compiler_options.list_files_only
+    {
+        print!(
+            "{}",
+            result.program.list_files(compiler_options.explain_files)
+        );
+    }
+    if report_performance {
+        report_statistics(
+            &result.program,
+            compiler_options.extended_diagnostics,
+            start_time.elapsed(),
+        );
+    }
+    if invalid_config {
+        ExitStatus::InvalidOptions
+    } else {
+        result.exit_status()
+    }
 }
 
 // use clap::{Args, Parser, Subcommand};
@@ -0,0 +1,640 @@
+//! Watch mode, which compiles a project and compiles it again whenever its files change
+//!
+//! Files are watched through the watcher the `CompilerHost` creates. By default it polls their
+//! modification times through the host, so that watching works the same on every platform and
+//! over in-memory hosts. Changes that arrive
+//! close together, like those of a save in an editor that writes several files, are gathered
+//! into one batch. Each compilation reuses the syntax trees of the last one for the files that
+//! did not change, reparses only the changed parts of those that did, and emits only the
+//! changed files.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::cli::{Cli, CompilerOptions, create_compiler_options};
+use crate::compile::{
+    CompilerHost, Diagnostic, DiagnosticCategory, Program, SourceFile, TextReporter,
+    create_incremental_program, emit_changed_files, read_source_files, type_check,
+};
+use crate::compiler::cancellation::{CancellationToken, OperationCanceled};
+use crate::compiler::diagnostics::{self, Message};
+use crate::compiler::paths::get_directory_path;
+use crate::config::parse_config_file;
+
+/// How often the polling watcher checks watched files for changes
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How a watched file or folder changed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileChangeKind {
+    Created,
+    Changed,
+    Deleted,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+}
+
+/// Watches files and folders for changes, for watch mode
+///
+/// Hosts give watch mode their watcher through [`CompilerHost::create_file_watcher`]. The
+/// default one polls modification times; a host that gets events from the file system can
+/// replace it.
+pub trait FileWatcher {
+    /// Sets the paths to watch. Changes to paths that were already watched are kept for the
+    /// next batch, so that changes made since the last one are not missed.
+    fn set_watched_paths(&mut self, paths: Vec<String>);
+
+    /// Waits until watched paths change, and then until they settle, and returns the whole
+    /// batch of changes
+    fn wait_for_changes(&mut self) -> Vec<FileChange>;
+}
+
+/// Watches files and folders for changes by polling their modification times through a host.
+/// A folder's time changes when files are added to or removed from it.
+pub struct PollingFileWatcher<'a> {
+    host: &'a dyn CompilerHost,
+    /// Each watched path, with its modification time when last polled, if it existed
+    modified_times: HashMap<String, Option<SystemTime>>,
+    poll_interval: Duration,
+}
+
+impl<'a> PollingFileWatcher<'a> {
+    pub fn new(host: &'a dyn CompilerHost, poll_interval: Duration) -> Self {
+        PollingFileWatcher {
+            host,
+            modified_times: HashMap::new(),
+            poll_interval,
+        }
+    }
+
+    /// Finds the watched paths that changed since the last poll, in the order of their paths
+    pub fn poll(&mut self) -> Vec<FileChange> {
+        let mut changes = Vec::new();
+        for (path, modified_time) in &mut self.modified_times {
+            let current = self.host.get_modified_time(path);
+            let kind = match (*modified_time, current) {
+                (None, Some(_)) => FileChangeKind::Created,
+                (Some(_), None) => FileChangeKind::Deleted,
+                (Some(old), Some(new)) if old != new => FileChangeKind::Changed,
+                _ => continue,
+            };
+            *modified_time = current;
+            changes.push(FileChange {
+                path: path.clone(),
+                kind,
+            });
+        }
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+}
+
+impl FileWatcher for PollingFileWatcher<'_> {
+    fn set_watched_paths(&mut self, paths: Vec<String>) {
+        let mut modified_times = HashMap::with_capacity(paths.len());
+        for path in paths {
+            if modified_times.contains_key(&path) {
+                continue;
+            }
+            let modified_time = match self.modified_times.get(&path) {
+                Some(modified_time) => *modified_time,
+                None => self.host.get_modified_time(&path),
+            };
+            modified_times.insert(path, modified_time);
+        }
+        self.modified_times = modified_times;
+    }
+
+    fn wait_for_changes(&mut self) -> Vec<FileChange> {
+        let mut changes: Vec<FileChange> = Vec::new();
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        loop {
+            thread::sleep(self.poll_interval);
+            let new_changes = self.poll();
+            if new_changes.is_empty() && !changes.is_empty() {
+                return changes;
+            }
+            for change in new_changes {
+                match indices.get(&change.path).map(|&index| &mut changes[index]) {
+                    // A file created in this batch is still new to the compilation
+                    Some(known) if known.kind == FileChangeKind::Created => {
+                        if change.kind == FileChangeKind::Deleted {
+                            known.kind = FileChangeKind::Deleted;
+                        }
+                    }
+                    Some(known) => known.kind = change.kind,
+                    None => {
+                        indices.insert(change.path.clone(), changes.len());
+                        changes.push(change);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A project being watched: its settings, its last compilation, and the files it depends on
+pub struct WatchProgram<'a, H: CompilerHost> {
+    cli: Cli,
+    config_file_name: Option<String>,
+    host: &'a H,
+    program: Option<Program>,
+    /// Whether the last compilation left its output unwritten, because of `noEmitOnError` or
+    /// because some of it could not be written, so that the next one writes all of it
+    emit_skipped: bool,
+    watcher: Box<dyn FileWatcher + 'a>,
+}
+
+impl<'a, H: CompilerHost> WatchProgram<'a, H> {
+    /// Watches the project of a configuration file, or without one, the files given on the
+    /// command line
+    pub fn new(cli: &Cli, config_file_name: Option<&str>, host: &'a H) -> Self {
+        WatchProgram {
+            cli: cli.clone(),
+            config_file_name: config_file_name.map(str::to_string),
+            host,
+            program: None,
+            emit_skipped: false,
+            watcher: host.create_file_watcher(),
+        }
+    }
+
+    pub fn program(&self) -> Option<&Program> {
+        self.program.as_ref()
+    }
+
+    /// Compiles the project, reusing the last compilation where the files are unchanged.
+    /// `changes` are those since the last compilation; a change to a configuration file makes
+    /// every file emit again, as the options may have changed.
+    ///
+    /// A compilation cancelled by `cancellation_token`, because the files changed again while
+    /// it ran, leaves the last one in place, and its changes are to be given to the next.
+    pub fn compile(
+        &mut self,
+        changes: &[FileChange],
+        cancellation_token: &CancellationToken,
+    ) -> Result<&Program, OperationCanceled> {
+        let mut cli = self.cli.clone();
+        let (file_names, config_diagnostics, config_file_names) = match &self.config_file_name {
+            Some(config_file_name) => {
+                let config = parse_config_file(config_file_name, &mut cli, self.host);
+                (
+                    config.file_names,
+                    config.diagnostics,
+                    config.config_file_names,
+                )
+            }
+            None => (cli.files.clone(), Vec::new(), Vec::new()),
+        };
+        let compiler_options = create_compiler_options(&cli);
+
+        let source_files = read_source_files(&file_names, self.host);
+        let old_program = self.program.take();
+        let config_changed = changes
+            .iter()
+            .any(|change| config_file_names.contains(&change.path));
+        let result = self.compile_program(
+            &source_files,
+            &compiler_options,
+            old_program.as_ref(),
+            config_changed,
+            config_diagnostics,
+            cancellation_token,
+        );
+        // The files a cancelled compilation left unwritten differ from the last compilation,
+        // so the next one writes them
+        let program = match result {
+            Ok(program) => program,
+            Err(canceled) => {
+                self.program = old_program;
+                return Err(canceled);
+            }
+        };
+
+        // Watch the folders of the project too, to see files added to them
+        let mut watched_paths: Vec<String> = program
+            .source_files
+            .iter()
+            .map(|source_file| source_file.file_name.clone())
+            .chain(config_file_names.iter().cloned())
+            .collect();
+        watched_paths.extend(
+            file_names
+                .iter()
+                .chain(&config_file_names)
+                .map(|path| get_directory_path(path)),
+        );
+        self.watcher.set_watched_paths(watched_paths);
+
+        Ok(self.program.insert(program))
+    }
+
+    fn compile_program(
+        &mut self,
+        source_files: &[SourceFile],
+        compiler_options: &CompilerOptions,
+        old_program: Option<&Program>,
+        config_changed: bool,
+        config_diagnostics: Vec<Diagnostic>,
+        cancellation_token: &CancellationToken,
+    ) -> Result<Program, OperationCanceled> {
+        let mut program = create_incremental_program(
+            source_files,
+            compiler_options,
+            self.host,
+            old_program,
+            cancellation_token,
+        )?;
+        program.diagnostics.splice(0..0, config_diagnostics);
+
+        if !compiler_options.skip_type_checking {
+            type_check(&mut program, cancellation_token)?;
+        }
+
+        let has_errors = program
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.category == DiagnosticCategory::Error);
+        let mut emit_skipped = compiler_options.no_emit_on_error && has_errors;
+        if !compiler_options.no_emit && !emit_skipped {
+            let emit_all = old_program.is_none() || config_changed || self.emit_skipped;
+            let emit = emit_changed_files(
+                &program,
+                compiler_options,
+                self.host,
+                cancellation_token,
+                |index| {
+                    let source_file = &program.source_files[index];
+                    emit_all
+                        || !old_program.is_some_and(|old_program| {
+                            old_program.source_files.iter().any(|old_file| {
+                                old_file.file_name == source_file.file_name
+                                    && old_file.text == source_file.text
+                            })
+                        })
+                },
+            )?;
+            emit_skipped = !emit.diagnostics.is_empty();
+            program.diagnostics.extend(emit.diagnostics);
+        }
+        self.emit_skipped = emit_skipped;
+        Ok(program)
+    }
+
+    /// Waits for a batch of changes to the files of the project
+    pub fn wait_for_changes(&mut self) -> Vec<FileChange> {
+        self.watcher.wait_for_changes()
+    }
+}
+
+/// Compiles a project and then compiles it again on every change to its files, reporting the
+/// status of each compilation. This never returns; watch mode ends when the process is stopped.
+pub fn watch(cli: &Cli, config_file_name: Option<&str>, host: &impl CompilerHost) {
+    let mut watch_program = WatchProgram::new(cli, config_file_name, host);
+    let mut reporter = WatchStatusReporter::new(cli, host);
+    let mut out = io::stdout();
+    let _ = reporter.report_status(
+        diagnostics::STARTING_COMPILATION_IN_WATCH_MODE_6031,
+        &[],
+        &mut out,
+    );
+    let mut changes = Vec::new();
+    loop {
+        // Changes are only looked for between compilations, so none is cancelled here
+        if let Ok(program) = watch_program.compile(&changes, &CancellationToken::new()) {
+            // The configuration file may have turned `preserveWatchOutput` on or off
+            reporter.set_options(&program.options);
+            let _ = reporter.report_diagnostics(&program.diagnostics, &mut out);
+        }
+        changes = watch_program.wait_for_changes();
+        let _ = reporter.report_status(
+            diagnostics::FILE_CHANGE_DETECTED_STARTING_INCREMENTAL_COMPILATION_6032,
+            &[],
+            &mut out,
+        );
+    }
+}
+
+/// The messages that start a compilation, before which the screen is cleared
+const SCREEN_STARTING_MESSAGES: [&Message; 2] = [
+    diagnostics::STARTING_COMPILATION_IN_WATCH_MODE_6031,
+    diagnostics::FILE_CHANGE_DETECTED_STARTING_INCREMENTAL_COMPILATION_6032,
+];
+
+/// Clears the terminal and moves the cursor to its top, as tsc does
+const CLEAR_SCREEN: &str = "\x1bc";
+
+/// Reports the progress of watch mode as tsc does: a status line stamped with the time when a
+/// compilation starts, and the diagnostics of each compilation followed by its number of
+/// errors. The screen is cleared when a compilation starts, so that only the results of the
+/// latest one show, unless `--preserveWatchOutput` is set.
+pub struct WatchStatusReporter<'a> {
+    pretty: bool,
+    preserve_watch_output: bool,
+    host: &'a dyn CompilerHost,
+}
+
+impl<'a> WatchStatusReporter<'a> {
+    pub fn new(cli: &Cli, host: &'a dyn CompilerHost) -> Self {
+        WatchStatusReporter {
+            pretty: cli.pretty,
+            preserve_watch_output: cli.preserve_watch_output,
+            host,
+        }
+    }
+
+    /// Follows the options of the latest compilation
+    pub fn set_options(&mut self, options: &CompilerOptions) {
+        self.pretty = options.pretty;
+        self.preserve_watch_output = options.preserve_watch_output;
+    }
+
+    /// Writes a status line, stamped with the time
+    pub fn report_status(
+        &self,
+        message: &Message,
+        args: &[String],
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        self.write_status(message, args, SystemTime::now(), out)
+    }
+
+    fn write_status(
+        &self,
+        message: &Message,
+        args: &[String],
+        now: SystemTime,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let starts_screen = SCREEN_STARTING_MESSAGES
+            .iter()
+            .any(|starting| starting.code() == message.code());
+        let clears_screen = starts_screen && !self.preserve_watch_output;
+        if clears_screen {
+            write!(out, "{CLEAR_SCREEN}")?;
+        }
+        let time = format_time(now);
+        let text = diagnostics::format_message(message, args);
+        if self.pretty {
+            write!(out, "[\x1b[90m{time}\x1b[0m] {text}\n\n")?;
+        } else {
+            // Without a cleared screen, a blank line separates the status from what came before
+            if !clears_screen {
+                writeln!(out)?;
+            }
+            writeln!(out, "{time} - {text}")?;
+            if starts_screen {
+                writeln!(out)?;
+            }
+        }
+        out.flush()
+    }
+
+    /// Writes the errors and warnings of a compilation, followed by the number of errors, and
+    /// returns that number
+    pub fn report_diagnostics(
+        &self,
+        diagnostics: &[Diagnostic],
+        out: &mut dyn Write,
+    ) -> io::Result<usize> {
+        let reporter = TextReporter {
+            pretty: self.pretty,
+            host: self.host,
+        };
+        for diagnostic in diagnostics {
+            if matches!(
+                diagnostic.category,
+                DiagnosticCategory::Error | DiagnosticCategory::Warning
+            ) {
+                reporter.write_diagnostic(diagnostic, out)?;
+            }
+        }
+        let error_count = diagnostics
+            .iter()
+            .filter(|diagnostic| matches!(diagnostic.category, DiagnosticCategory::Error))
+            .count();
+        if error_count == 1 {
+            self.report_status(
+                diagnostics::FOUND_1_ERROR_WATCHING_FOR_FILE_CHANGES_6193,
+                &[],
+                out,
+            )?;
+        } else {
+            self.report_status(
+                diagnostics::FOUND_0_ERRORS_WATCHING_FOR_FILE_CHANGES_6194,
+                &[error_count.to_string()],
+                out,
+            )?;
+        }
+        Ok(error_count)
+    }
+}
+
+/// Formats the time of day like `10:04:05 PM`. The time is in UTC, as the standard library
+/// does not know the local time zone.
+fn format_time(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+        % (24 * 60 * 60);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let period = if hours < 12 { "AM" } else { "PM" };
+    let hours = match hours % 12 {
+        0 => 12,
+        hours => hours,
+    };
+    format!("{hours}:{minutes:02}:{seconds:02} {period}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use clap::Parser;
+
+    use super::*;
+    use crate::compile::VirtualCompilerHost;
+
+    fn changes(changes: &[(&str, FileChangeKind)]) -> Vec<FileChange> {
+        changes
+            .iter()
+            .map(|(path, kind)| FileChange {
+                path: path.to_string(),
+                kind: *kind,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batches_changes_to_watched_files() {
+        let host = VirtualCompilerHost::with_files(&[("a.ts", ""), ("b.ts", ""), ("c.ts", "")]);
+        let mut watcher = PollingFileWatcher::new(&host, Duration::ZERO);
+        watcher.set_watched_paths(vec![
+            "a.ts".into(),
+            "b.ts".into(),
+            "c.ts".into(),
+            "d.ts".into(),
+        ]);
+        assert_eq!(watcher.poll(), []);
+
+        host.write_file("a.ts", "let a = 1;").unwrap();
+        host.write_file("a.ts", "let a = 2;").unwrap();
+        host.remove_file("b.ts");
+        host.write_file("d.ts", "").unwrap();
+        assert_eq!(
+            watcher.wait_for_changes(),
+            changes(&[
+                ("a.ts", FileChangeKind::Changed),
+                ("b.ts", FileChangeKind::Deleted),
+                ("d.ts", FileChangeKind::Created),
+            ])
+        );
+        assert_eq!(watcher.poll(), []);
+
+        // Paths that stay watched keep their times, so changes before rewatching are kept
+        host.write_file("c.ts", "let c = 1;").unwrap();
+        watcher.set_watched_paths(vec!["c.ts".into()]);
+        assert_eq!(
+            watcher.poll(),
+            changes(&[("c.ts", FileChangeKind::Changed)])
+        );
+    }
+
+    #[test]
+    fn reuses_unchanged_files_and_emits_changed_ones() {
+        let host =
+            VirtualCompilerHost::with_files(&[("a.ts", "let a = 1;\n"), ("b.ts", "let b = 1;\n")]);
+        let cli = Cli::parse_from(["tsrs", "a.ts", "b.ts"]);
+        let mut watch_program = WatchProgram::new(&cli, None, &host);
+
+        let program = watch_program
+            .compile(&[], &CancellationToken::new())
+            .unwrap();
+        assert!(program.diagnostics.is_empty());
+        let old_trees = program.syntax_trees.clone();
+        assert_eq!(host.read_file("b.js").as_deref(), Some("let b = 1;\n"));
+        host.write_file("b.js", "").unwrap();
+
+        host.write_file("a.ts", "let a = 2;\n").unwrap();
+        let program = watch_program
+            .compile(
+                &changes(&[("a.ts", FileChangeKind::Changed)]),
+                &CancellationToken::new(),
+            )
+            .unwrap();
+        assert!(!Arc::ptr_eq(&program.syntax_trees[0], &old_trees[0]));
+        assert!(Arc::ptr_eq(&program.syntax_trees[1], &old_trees[1]));
+        assert_eq!(host.read_file("a.js").as_deref(), Some("let a = 2;\n"));
+        // The unchanged file is not emitted again
+        assert_eq!(host.read_file("b.js").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn keeps_the_last_compilation_when_one_is_cancelled() {
+        let host = VirtualCompilerHost::with_files(&[("a.ts", "let a = 1;\n")]);
+        let cli = Cli::parse_from(["tsrs", "a.ts"]);
+        let mut watch_program = WatchProgram::new(&cli, None, &host);
+        watch_program
+            .compile(&[], &CancellationToken::new())
+            .unwrap();
+
+        host.write_file("a.ts", "let a = 2;\n").unwrap();
+        let a_changed = changes(&[("a.ts", FileChangeKind::Changed)]);
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+        assert_eq!(
+            watch_program.compile(&a_changed, &cancellation_token).err(),
+            Some(OperationCanceled)
+        );
+        let program = watch_program.program().unwrap();
+        assert_eq!(program.source_files[0].text, "let a = 1;\n");
+        assert_eq!(host.read_file("a.js").as_deref(), Some("let a = 1;\n"));
+
+        watch_program
+            .compile(&a_changed, &CancellationToken::new())
+            .unwrap();
+        assert_eq!(host.read_file("a.js").as_deref(), Some("let a = 2;\n"));
+    }
+
+    #[test]
+    fn reports_errors_of_incremental_edits() {
+        let host = VirtualCompilerHost::with_files(&[("a.ts", "let a: number = 1;\n")]);
+        let cli = Cli::parse_from(["tsrs", "a.ts"]);
+        let mut watch_program = WatchProgram::new(&cli, None, &host);
+        assert!(
+            watch_program
+                .compile(&[], &CancellationToken::new())
+                .unwrap()
+                .diagnostics
+                .is_empty()
+        );
+
+        host.write_file("a.ts", "let a: number = \"one\";\n")
+            .unwrap();
+        let program = watch_program
+            .compile(
+                &changes(&[("a.ts", FileChangeKind::Changed)]),
+                &CancellationToken::new(),
+            )
+            .unwrap();
+        let codes: Vec<u32> = program
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        assert_eq!(codes, [2322]);
+    }
+
+    #[test]
+    fn clears_the_screen_unless_watch_output_is_preserved() {
+        let host = VirtualCompilerHost::with_files(&[("a.ts", "let a: number = \"one\";\n")]);
+        let noon = SystemTime::UNIX_EPOCH + Duration::from_secs(12 * 3600);
+        let report = |args: &[&str]| {
+            let cli = Cli::parse_from(["tsrs"].iter().chain(args));
+            let mut reporter = WatchStatusReporter::new(&cli, &host);
+            reporter.pretty = false;
+            let mut out = Vec::new();
+            reporter
+                .write_status(
+                    diagnostics::STARTING_COMPILATION_IN_WATCH_MODE_6031,
+                    &[],
+                    noon,
+                    &mut out,
+                )
+                .unwrap();
+            let program = WatchProgram::new(&cli, None, &host)
+                .compile(&[], &CancellationToken::new())
+                .unwrap()
+                .diagnostics
+                .clone();
+            let error_count = reporter.report_diagnostics(&program, &mut out).unwrap();
+            (String::from_utf8(out).unwrap(), error_count)
+        };
+
+        let (cleared, error_count) = report(&["a.ts"]);
+        assert_eq!(error_count, 1);
+        assert!(cleared.starts_with(
+            "\x1bc12:00:00 PM - Starting compilation in watch mode...\n\n\
+             a.ts(1:5) - error TS2322: "
+        ));
+        assert!(cleared.ends_with(" - Found 1 error. Watching for file changes.\n"));
+
+        let (preserved, _) = report(&["--preserveWatchOutput", "a.ts"]);
+        assert!(preserved.starts_with("\n12:00:00 PM - Starting compilation in watch mode...\n\n"));
+    }
+
+    #[test]
+    fn formats_times_like_tsc() {
+        let time = |seconds| SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+        assert_eq!(format_time(time(0)), "12:00:00 AM");
+        assert_eq!(format_time(time(9 * 3600 + 5 * 60 + 7)), "9:05:07 AM");
+        assert_eq!(format_time(time(12 * 3600 + 30)), "12:00:30 PM");
+        assert_eq!(format_time(time(23 * 3600 + 59 * 60)), "11:59:00 PM");
+    }
+}